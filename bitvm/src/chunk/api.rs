@@ -11,10 +11,12 @@ use crate::signatures::wots_api::{wots256, wots_hash};
 use crate::treepp::*;
 use ark_bn254::Bn254;
 use ark_ec::bn::Bn;
+use sha2::{Digest, Sha256};
 
 use super::api_runtime_utils::{
     execute_script_from_assertion, get_pubkeys, get_signature_from_assertion,
 };
+use super::g16_runner_utils::{ScriptType, Segment};
 use super::wrap_hasher::BLAKE3_HASH_LENGTH;
 
 pub const NUM_PUBS: usize = 1;
@@ -24,6 +26,33 @@ const VALIDATING_TAPS: usize = 1;
 const HASHING_TAPS: usize = NUM_HASH;
 pub const NUM_TAPS: usize = HASHING_TAPS + VALIDATING_TAPS;
 
+// Bump whenever the segments, their order or their scripts change in a way the parameters hashed
+// by `segment_layout_version` do not capture
+const SEGMENT_LAYOUT_REVISION: u32 = 1;
+
+/// Identifies the segment layout: the lock scripts generated for a set of public keys and the
+/// intermediate values asserted for a proof. An operator and a verifier on different layouts
+/// cannot disprove each other's assertions. Formatted as the revision followed by the first bytes
+/// of a hash of the layout parameters.
+pub fn segment_layout_version() -> String {
+    let mut hasher = Sha256::new();
+    for parameter in [
+        NUM_PUBS,
+        NUM_U256,
+        NUM_HASH,
+        NUM_TAPS,
+        BLAKE3_HASH_LENGTH,
+        wots256::N_DIGITS as usize,
+        wots_hash::N_DIGITS as usize,
+    ] {
+        hasher.update((parameter as u64).to_be_bytes());
+    }
+    format!(
+        "{SEGMENT_LAYOUT_REVISION}-{}",
+        hex::encode(&hasher.finalize()[..4])
+    )
+}
+
 pub type PublicInputs = [ark_bn254::Fr; NUM_PUBS];
 
 pub type PublicKeys = (
@@ -276,6 +305,14 @@ pub fn generate_signatures(
     Ok(sigs)
 }
 
+/// Evaluating the asserted values in rust and executing the disprove scripts on them disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionEvaluationError {
+    /// Index of the segment the disagreement was found at
+    pub segment_index: usize,
+    pub reason: &'static str,
+}
+
 // Step 4
 // validate signed assertions
 // returns index of disprove script generated in Step 2
@@ -283,9 +320,21 @@ pub fn generate_signatures(
 pub fn validate_assertions(
     vk: &ark_groth16::VerifyingKey<Bn254>,
     signed_asserts: Signatures,
-    _inpubkeys: PublicKeys,
+    inpubkeys: PublicKeys,
     disprove_scripts: &[Script; NUM_TAPS],
 ) -> Option<(usize, Script)> {
+    try_validate_assertions(vk, signed_asserts, inpubkeys, disprove_scripts)
+        .expect("ensure script execution matches rust execution match")
+}
+
+// Same as validate_assertions, but reports a disagreement between the rust evaluation and the
+// script execution instead of panicking
+pub fn try_validate_assertions(
+    vk: &ark_groth16::VerifyingKey<Bn254>,
+    signed_asserts: Signatures,
+    _inpubkeys: PublicKeys,
+    disprove_scripts: &[Script; NUM_TAPS],
+) -> Result<Option<(usize, Script)>, AssertionEvaluationError> {
     println!("validate_assertions; get_assertions_from_signature");
     let asserts = get_assertions_from_signature(signed_asserts.clone());
     println!("validate_assertions; get_segments_from_assertion");
@@ -295,12 +344,28 @@ pub fn validate_assertions(
     }
     println!("validate_assertions; execute_script_from_signature");
     let exec_result = execute_script_from_signature(&segments, signed_asserts, disprove_scripts);
-    assert_eq!(
-        success,
-        exec_result.is_none(),
-        "ensure script execution matches rust execution match"
-    );
-    exec_result
+    match (success, &exec_result) {
+        (true, Some((tap_index, _))) => Err(AssertionEvaluationError {
+            segment_index: segment_index_of_tap(&segments, *tap_index),
+            reason: "disprove script succeeds on a segment the rust evaluation accepts",
+        }),
+        (false, None) => Err(AssertionEvaluationError {
+            segment_index: segments.len().saturating_sub(1),
+            reason: "rust evaluation rejects a segment no disprove script succeeds on",
+        }),
+        _ => Ok(exec_result),
+    }
+}
+
+// Non deterministic segments have no disprove script
+fn segment_index_of_tap(segments: &[Segment], tap_index: usize) -> usize {
+    segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| segment.scr_type != ScriptType::NonDeterministic)
+        .nth(tap_index)
+        .map(|(index, _)| index)
+        .unwrap_or(tap_index)
 }
 
 // doesn't crash even if the proof may be incorrect
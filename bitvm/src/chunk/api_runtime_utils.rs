@@ -1,4 +1,4 @@
-use std::ops::Neg;
+use std::{env, fs, ops::Neg, path::PathBuf, process, sync::Mutex, thread};
 
 use crate::bn254::ell_coeffs::AffinePairing;
 use crate::bn254::ell_coeffs::BnAffinePairing;
@@ -17,11 +17,13 @@ use ark_bn254::Bn254;
 use ark_ec::bn::Bn;
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::Field;
+use bitcoin::ScriptBuf;
 use bitcoin_script::script;
 
-use crate::{bn254::utils::Hint, execute_script};
+use crate::{bn254::utils::Hint, execute_script_buf};
 
 use super::api::{Assertions, PublicKeys, Signatures, NUM_HASH, NUM_PUBS, NUM_TAPS, NUM_U256};
+use super::config::chunker_config;
 use super::g16_runner_utils::{ScriptType, Segment};
 use super::wrap_hasher::BLAKE3_HASH_LENGTH;
 use super::{
@@ -396,46 +398,192 @@ fn utils_execute_chunked_g16(
     segments: &Vec<Segment>,
     disprove_scripts: &[Script; NUM_TAPS],
 ) -> Option<(usize, Script)> {
-    let mut tap_script_index = 0;
-    for i in 0..aux_hints.len() {
-        if segments[i].scr_type == ScriptType::NonDeterministic {
-            continue;
-        }
-        let hint_script = script! {
+    let config = chunker_config();
+    let hint_script = |i: usize| {
+        script! {
             for h in &aux_hints[i] {
                 {h.push()}
             }
             {bc_hints[i].clone()}
-        };
-        let total_script = script! {
-            {hint_script.clone()}
-            {disprove_scripts[tap_script_index].clone()}
-        };
-        let exec_result = execute_script(total_script);
-        if exec_result.final_stack.len() > 1 {
-            for i in 0..exec_result.final_stack.len() {
-                println!("{i:} {:?}", exec_result.final_stack.get(i));
-            }
         }
-        if !exec_result.success {
-            if exec_result.final_stack.len() != 1 {
-                println!("final {:?}", i);
-                println!("final {:?}", segments[i].scr_type);
-                assert!(false);
+    };
+
+    // (segment index, tap script index), non deterministic segments have no disprove script
+    let taps: Vec<(usize, usize)> = (0..aux_hints.len())
+        .filter(|i| segments[*i].scr_type != ScriptType::NonDeterministic)
+        .enumerate()
+        .map(|(tap_script_index, i)| (i, tap_script_index))
+        .collect();
+
+    // Segments are compiled and executed in windows of at most `max_in_flight_segments`, the
+    // first segment in order whose disprove script succeeds is reported whatever the parallelism
+    let spill = SegmentSpill::new(config.spill_directory.clone());
+    for window in taps.chunks(config.max_in_flight_segments.max(1)) {
+        let mut in_memory_bytes = 0;
+        let compiled_segments: Vec<CompiledSegment> = window
+            .iter()
+            .map(|&(i, tap_script_index)| {
+                let total_script = script! {
+                    {hint_script(i)}
+                    {disprove_scripts[tap_script_index].clone()}
+                }
+                .compile();
+                let size = total_script.len() as u64;
+                if config
+                    .memory_budget_bytes
+                    .is_some_and(|budget| in_memory_bytes + size > budget)
+                {
+                    spill.store(i, total_script)
+                } else {
+                    in_memory_bytes += size;
+                    CompiledSegment::InMemory(total_script)
+                }
+            })
+            .collect();
+
+        let executions = execute_compiled_segments(compiled_segments, config.parallelism);
+        for (&(i, tap_script_index), execution) in window.iter().zip(executions) {
+            for (index, element) in execution.final_stack.iter().enumerate() {
+                println!("{index:} {element}");
+            }
+            if !execution.success {
+                if execution.final_stack_len != 1 {
+                    println!("final {:?}", i);
+                    println!("final {:?}", segments[i].scr_type);
+                    panic!("disprove script failed without a single element on the final stack");
+                }
+            } else {
+                println!(
+                    "disprove script {}: tapindex {}, {:?}",
+                    i, tap_script_index, segments[i].scr_type
+                );
+                let disprove_hint = (tap_script_index, hint_script(i));
+                return Some(disprove_hint);
             }
-        } else {
-            println!(
-                "disprove script {}: tapindex {}, {:?}",
-                i, tap_script_index, segments[i].scr_type
-            );
-            let disprove_hint = (tap_script_index, hint_script);
-            return Some(disprove_hint);
         }
-        tap_script_index += 1;
     }
     None
 }
 
+// Compiled disprove script of a segment with its witness, on disk if it did not fit the memory
+// budget of the chunker
+enum CompiledSegment {
+    InMemory(ScriptBuf),
+    Spilled(PathBuf),
+}
+
+impl CompiledSegment {
+    fn load(self) -> ScriptBuf {
+        match self {
+            CompiledSegment::InMemory(script) => script,
+            CompiledSegment::Spilled(path) => {
+                let bytes = fs::read(&path).unwrap_or_else(|e| {
+                    panic!("failed to read spilled segment {}: {e}", path.display())
+                });
+                let _ = fs::remove_file(&path);
+                ScriptBuf::from_bytes(bytes)
+            }
+        }
+    }
+}
+
+struct SegmentSpill {
+    directory: PathBuf,
+}
+
+impl SegmentSpill {
+    fn new(spill_directory: Option<PathBuf>) -> Self {
+        let directory = spill_directory
+            .unwrap_or_else(env::temp_dir)
+            .join(format!("bitvm-chunker-{}", process::id()));
+        Self { directory }
+    }
+
+    // Keeps the script in memory if it cannot be written
+    fn store(&self, segment_index: usize, script: ScriptBuf) -> CompiledSegment {
+        let path = self.directory.join(format!("segment-{segment_index}.bin"));
+        match fs::create_dir_all(&self.directory).and_then(|_| fs::write(&path, script.as_bytes()))
+        {
+            Ok(()) => CompiledSegment::Spilled(path),
+            Err(e) => {
+                println!(
+                    "failed to spill segment {segment_index} to {}: {e}",
+                    path.display()
+                );
+                CompiledSegment::InMemory(script)
+            }
+        }
+    }
+}
+
+impl Drop for SegmentSpill {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
+
+// Script execution results are not `Send`, workers only hand back what is reported
+struct SegmentExecution {
+    success: bool,
+    final_stack_len: usize,
+    final_stack: Vec<String>, // only formatted if more than one element is left
+}
+
+fn execute_compiled_segment(segment: CompiledSegment) -> SegmentExecution {
+    let exec_result = execute_script_buf(segment.load());
+    let final_stack_len = exec_result.final_stack.len();
+    let final_stack = match final_stack_len > 1 {
+        true => (0..final_stack_len)
+            .map(|i| format!("{:?}", exec_result.final_stack.get(i)))
+            .collect(),
+        false => vec![],
+    };
+    SegmentExecution {
+        success: exec_result.success,
+        final_stack_len,
+        final_stack,
+    }
+}
+
+// Executions are returned in the order of the segments
+fn execute_compiled_segments(
+    segments: Vec<CompiledSegment>,
+    parallelism: usize,
+) -> Vec<SegmentExecution> {
+    if parallelism <= 1 || segments.len() <= 1 {
+        return segments.into_iter().map(execute_compiled_segment).collect();
+    }
+
+    let workers = parallelism.min(segments.len());
+    let queue = Mutex::new(segments.into_iter().enumerate());
+    let mut executions: Vec<(usize, SegmentExecution)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut executions = vec![];
+                    loop {
+                        let next = queue.lock().unwrap().next();
+                        let Some((index, segment)) = next else {
+                            break;
+                        };
+                        executions.push((index, execute_compiled_segment(segment)));
+                    }
+                    executions
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    executions.sort_by_key(|(index, _)| *index);
+    executions
+        .into_iter()
+        .map(|(_, execution)| execution)
+        .collect()
+}
+
 pub(crate) fn execute_script_from_assertion(
     segments: &Vec<Segment>,
     assts: Assertions,
@@ -0,0 +1,145 @@
+use std::{
+    path::PathBuf,
+    sync::{LazyLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+const MIB: u64 = 1024 * 1024;
+const GIB: u64 = 1024 * MIB;
+
+// Memory needed besides the segment executions, mostly the segments of a proof with their hints
+pub const BASE_MEMORY_BYTES: u64 = 4 * GIB;
+// Peak memory of executing one disprove script with its witness
+pub const SEGMENT_EXECUTION_MEMORY_BYTES: u64 = 512 * MIB;
+// Upper bound of a compiled disprove script with its witness, a tapscript fits in a block
+pub const COMPILED_SEGMENT_BYTES: u64 = 4 * MIB;
+
+static CHUNKER_CONFIG: LazyLock<RwLock<ChunkerConfig>> =
+    LazyLock::new(|| RwLock::new(ChunkerConfig::default()));
+
+/// How disprove scripts are executed against the asserted segments, see `validate_assertions`.
+/// The default executes one segment at a time and keeps everything in memory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChunkerConfig {
+    /// Threads executing segment scripts
+    #[serde(default = "default_parallelism")]
+    pub parallelism: usize,
+    /// Segment scripts compiled ahead of their execution
+    #[serde(default = "default_max_in_flight_segments")]
+    pub max_in_flight_segments: usize,
+    /// Bytes of compiled segment scripts kept in memory, the ones beyond are written to
+    /// `spill_directory` until they are executed. Unlimited if not set.
+    #[serde(default)]
+    pub memory_budget_bytes: Option<u64>,
+    /// Defaults to the temporary directory of the system
+    #[serde(default)]
+    pub spill_directory: Option<PathBuf>,
+}
+
+fn default_parallelism() -> usize {
+    1
+}
+
+fn default_max_in_flight_segments() -> usize {
+    1
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        Self {
+            parallelism: default_parallelism(),
+            max_in_flight_segments: default_max_in_flight_segments(),
+            memory_budget_bytes: None,
+            spill_directory: None,
+        }
+    }
+}
+
+impl ChunkerConfig {
+    /// Memory needed to validate assertions with this configuration
+    pub fn minimum_memory_bytes(&self) -> u64 {
+        let in_flight_bytes = self.max_in_flight_segments as u64 * COMPILED_SEGMENT_BYTES;
+        let compiled_segments = match self.memory_budget_bytes {
+            Some(budget) => budget.min(in_flight_bytes),
+            None => in_flight_bytes,
+        };
+        BASE_MEMORY_BYTES
+            + self.parallelism as u64 * SEGMENT_EXECUTION_MEMORY_BYTES
+            + compiled_segments
+    }
+
+    /// Checks the configuration against the resources of the machine it runs on
+    pub fn check_resources(&self, total_memory_bytes: u64, cpus: usize) -> Result<(), String> {
+        if self.parallelism == 0 || self.max_in_flight_segments == 0 {
+            return Err(String::from(
+                "chunker parallelism and maximum in-flight segments must be at least 1",
+            ));
+        }
+        if self.parallelism > cpus {
+            return Err(format!(
+                "chunker parallelism of {} exceeds the {cpus} available CPUs",
+                self.parallelism
+            ));
+        }
+        let minimum_memory_bytes = self.minimum_memory_bytes();
+        if total_memory_bytes < minimum_memory_bytes {
+            return Err(format!(
+                "{} MiB of memory is below the {} MiB required with a chunker parallelism of {}, lower the parallelism or set a memory budget",
+                total_memory_bytes / MIB,
+                minimum_memory_bytes / MIB,
+                self.parallelism
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets the configuration used by all later chunker operations
+pub fn set_chunker_config(config: ChunkerConfig) {
+    *CHUNKER_CONFIG.write().unwrap() = config;
+}
+
+pub fn chunker_config() -> ChunkerConfig {
+    CHUNKER_CONFIG.read().unwrap().clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        ChunkerConfig, BASE_MEMORY_BYTES, COMPILED_SEGMENT_BYTES, GIB,
+        SEGMENT_EXECUTION_MEMORY_BYTES,
+    };
+
+    #[test]
+    fn test_minimum_memory_grows_with_parallelism() {
+        let sequential = ChunkerConfig::default();
+        assert_eq!(
+            sequential.minimum_memory_bytes(),
+            BASE_MEMORY_BYTES + SEGMENT_EXECUTION_MEMORY_BYTES + COMPILED_SEGMENT_BYTES
+        );
+
+        let parallel = ChunkerConfig {
+            parallelism: 4,
+            max_in_flight_segments: 8,
+            ..ChunkerConfig::default()
+        };
+        assert!(parallel.minimum_memory_bytes() > sequential.minimum_memory_bytes());
+
+        // Compiled segments beyond the budget go to disk
+        let budgeted = ChunkerConfig {
+            memory_budget_bytes: Some(0),
+            ..parallel.clone()
+        };
+        assert_eq!(
+            budgeted.minimum_memory_bytes(),
+            BASE_MEMORY_BYTES + 4 * SEGMENT_EXECUTION_MEMORY_BYTES
+        );
+
+        assert!(sequential.check_resources(8 * GIB, 1).is_ok());
+        assert!(sequential.check_resources(2 * GIB, 1).is_err());
+        assert!(parallel.check_resources(64 * GIB, 2).is_err());
+        assert!(parallel.check_resources(64 * GIB, 4).is_ok());
+    }
+}
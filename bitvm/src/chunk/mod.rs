@@ -7,6 +7,7 @@ mod helpers;
 pub mod api;
 pub mod api_compiletime_utils;
 mod api_runtime_utils;
+pub mod config;
 
 pub mod g16_runner_core;
 pub mod g16_runner_utils;
@@ -117,6 +117,11 @@ impl fmt::Display for ExecuteInfo {
 }
 
 pub fn execute_script(script: treepp::Script) -> ExecuteInfo {
+    execute_script_buf(script.compile())
+}
+
+/// Same as `execute_script` for an already compiled script
+pub fn execute_script_buf(script: ScriptBuf) -> ExecuteInfo {
     let mut exec = Exec::new(
         ExecCtx::Tapscript,
         Options::default(),
@@ -131,7 +136,7 @@ pub fn execute_script(script: treepp::Script) -> ExecuteInfo {
             input_idx: 0,
             taproot_annex_scriptleaf: Some((TapLeafHash::all_zeros(), None)),
         },
-        script.compile(),
+        script,
         vec![],
     )
     .expect("error creating exec");
@@ -1,4 +1,4 @@
-use bitcoin::Network;
+use bitcoin::{Network, PublicKey};
 use bridge::{client::cli::query_command::QueryCommand, constants::DestinationNetwork};
 use clap::{arg, command};
 use std::error::Error;
@@ -18,7 +18,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(QueryCommand::peg_in_graphs_command())
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin and L2 network environment (mainnet, testnet, local)").required(false)
         .default_value("testnet"))
-        .arg(arg!(-p --prefix <PREFIX> "Prefix for local file cache path").required(false));
+        .arg(arg!(-p --prefix <PREFIX> "Prefix for local file cache path").required(false))
+        .arg(
+            arg!(-f --verifiers [VERIFIER_PUBKEYS] "Comma-separated list of verifier public keys")
+                .required(false)
+                .num_args(0..1000)
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(PublicKey))
+                .env("VERIFIERS"),
+        );
 
     let matches = command.clone().get_matches();
     let (source_network, destination_network) =
@@ -33,14 +41,18 @@ async fn main() -> Result<(), Box<dyn Error>> {
         };
     let prefix = matches.get_one::<String>("prefix").map(|s| s.as_str());
 
-    let mut query = QueryCommand::new(source_network, destination_network, prefix).await;
+    let verifiers = matches
+        .get_many::<PublicKey>("verifiers")
+        .map(|verifiers| verifiers.cloned().collect());
+
+    let mut query = QueryCommand::new(source_network, destination_network, prefix, verifiers).await;
     let resp = match matches.subcommand() {
         Some(("depositor", sub)) => query.handle_depositor(sub).await,
         Some(("withdrawer", sub)) => query.handle_withdrawer(sub, destination_network).await,
         Some(("history", sub)) => query.handle_history(sub, destination_network).await,
         Some(("transactions", sub)) => query.handle_transactions(sub, destination_network).await,
         Some(("signatures", sub)) => query.handle_signatures(sub, destination_network).await,
-        Some(("broadcast", sub)) => query.handle_broadcast(sub).await,
+        Some(("broadcast", sub)) => query.handle_broadcast(sub, destination_network).await,
         Some(("pegins", _)) => query.handle_peg_in_graphs().await,
         _ => unreachable!(),
     };
@@ -1,9 +1,24 @@
 use bitcoin::PublicKey;
 use bridge::client::cli::client_command::{ClientCommand, CommonArgs};
+use bridge::client::cli::committee_command::CommitteeCommand;
 use bridge::client::cli::key_command::KeysCommand;
+use bridge::client::cli::rehearse_command::RehearseCommand;
+use bridge::client::profiling::startup_profile;
 use clap::{arg, command};
 use std::error::Error;
 
+// Exit code of failures that can succeed when tried again later (EX_TEMPFAIL), see
+// `bridge::error::Error::is_retryable`. Other failures exit with 1.
+const EXIT_CODE_RETRYABLE: i32 = 75;
+
+fn exit_with_error(context: &str, err: bridge::error::Error) -> ! {
+    eprintln!("{context}: {err}");
+    std::process::exit(match err.is_retryable() {
+        true => EXIT_CODE_RETRYABLE,
+        false => 1,
+    });
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     // Load environment variables from .env file
@@ -25,8 +40,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .env("VERIFIERS"),
         )
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet, regtest)").required(false).default_value("testnet").env("ENVIRONMENT"))
+        .arg(arg!(--"profile-startup" "Report where time goes during client startup and the first graph operation").required(false))
         .arg(arg!(-p --"user-profile" <USER_PROFILE> "Name of the protocol participant (e.g. 'operator_one', 'verifier_0'). Used as a namespace separator in the local file path for storing private and public client data").required(false).default_value("default_user").env("USER_PROFILE"))
         .subcommand(KeysCommand::get_command())
+        .subcommand(CommitteeCommand::get_command())
+        .subcommand(RehearseCommand::get_command())
         .subcommand(ClientCommand::get_funding_amounts_command())
         .subcommand(ClientCommand::get_operator_address_command())
         .subcommand(ClientCommand::get_operator_utxos_command())
@@ -34,12 +52,30 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(ClientCommand::get_depositor_utxos_command())
         .subcommand(ClientCommand::get_initiate_peg_in_command())
         .subcommand(ClientCommand::get_create_peg_out_graph_command())
+        .subcommand(ClientCommand::get_wizard_command())
         .subcommand(ClientCommand::get_push_nonces_command())
         .subcommand(ClientCommand::get_push_signature_command())
+        .subcommand(ClientCommand::get_resume_ceremony_command())
+        .subcommand(ClientCommand::get_export_descriptors_command())
+        .subcommand(ClientCommand::get_export_signing_bundle_command())
+        .subcommand(ClientCommand::get_export_events_command())
+        .subcommand(ClientCommand::get_history_command())
+        .subcommand(ClientCommand::get_graph_command())
+        .subcommand(ClientCommand::get_validate_graph_command())
+        .subcommand(ClientCommand::get_cache_command())
+        .subcommand(ClientCommand::get_resign_command())
         .subcommand(ClientCommand::get_mock_l2_pegout_event_command())
         .subcommand(ClientCommand::get_status_command())
+        .subcommand(ClientCommand::get_track_command())
+        .subcommand(ClientCommand::get_list_graphs_command())
         .subcommand(ClientCommand::get_broadcast_command())
+        .subcommand(ClientCommand::get_approve_command())
+        .subcommand(ClientCommand::get_cpfp_command())
+        .subcommand(ClientCommand::get_explain_command())
+        .subcommand(ClientCommand::get_script_budget_command())
         .subcommand(ClientCommand::get_automatic_command())
+        .subcommand(ClientCommand::get_watch_command())
+        .subcommand(ClientCommand::get_jobs_command())
         .subcommand(ClientCommand::get_interactive_command());
 
     let matches = command.clone().get_matches();
@@ -51,14 +87,21 @@ async fn main() -> Result<(), Box<dyn Error>> {
             .map(|x| x.cloned().collect::<Vec<PublicKey>>()),
         environment: matches.get_one::<String>("environment").cloned(),
         path_prefix: matches.get_one::<String>("user-profile").cloned(),
+        profile_startup: matches.get_flag("profile-startup"),
     };
 
     if let Some(sub_matches) = matches.subcommand_matches("keys") {
         let keys_command = KeysCommand::new(global_args.key_dir);
         keys_command.handle_command(sub_matches)?;
-    } else if matches.subcommand_matches("get-funding-amounts").is_some() {
+    } else if let Some(sub_matches) = matches.subcommand_matches("committee") {
+        let committee_command = CommitteeCommand::new(global_args.key_dir, global_args.verifiers);
+        committee_command.handle_command(sub_matches)?;
+    } else if let Some(sub_matches) = matches.subcommand_matches("rehearse") {
+        let rehearse_command = RehearseCommand::new();
+        rehearse_command.handle_command(sub_matches).await?;
+    } else if let Some(sub_matches) = matches.subcommand_matches("get-funding-amounts") {
         let client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_get_funding_amounts().await;
+        let _ = client_command.handle_get_funding_amounts(sub_matches).await;
     } else if matches.subcommand_matches("get-operator-address").is_some() {
         let client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_get_operator_address().await;
@@ -76,14 +119,22 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let _ = client_command.handle_get_depositor_utxos().await;
     } else if let Some(sub_matches) = matches.subcommand_matches("initiate-peg-in") {
         let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command
+        if let Err(e) = client_command
             .handle_initiate_peg_in_command(sub_matches)
-            .await;
+            .await
+        {
+            exit_with_error("Failed to initiate peg-in", e);
+        }
     } else if let Some(sub_matches) = matches.subcommand_matches("create-peg-out") {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command
             .handle_create_peg_out_graph_command(sub_matches)
             .await;
+    } else if matches.subcommand_matches("wizard").is_some() {
+        let mut client_command = ClientCommand::new(global_args).await;
+        if let Err(e) = client_command.handle_wizard_command().await {
+            eprintln!("{e}");
+        }
     } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_push_nonces_command(sub_matches).await;
@@ -92,6 +143,44 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let _ = client_command
             .handle_push_signature_command(sub_matches)
             .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("resume-ceremony") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_resume_ceremony_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("export-descriptors") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_export_descriptors_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("export-signing-bundle") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_export_signing_bundle_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("export-events") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_export_events_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("history") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_history_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("graph") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_graph_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("validate-graph") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        // Issues are returned as an error so the exit code can block a broadcast
+        client_command
+            .handle_validate_graph_command(sub_matches)
+            .await?;
+    } else if let Some(sub_matches) = matches.subcommand_matches("cache") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_cache_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("resign") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_resign_command(sub_matches).await;
     } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command
@@ -100,16 +189,47 @@ async fn main() -> Result<(), Box<dyn Error>> {
     } else if matches.subcommand_matches("status").is_some() {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_status_command().await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("track") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_track_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("list-graphs") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_list_graphs_command(sub_matches).await;
     } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
         let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_broadcast_command(sub_matches).await;
-    } else if matches.subcommand_matches("automatic").is_some() {
+        if let Err(e) = client_command.handle_broadcast_command(sub_matches).await {
+            exit_with_error("Failed to broadcast transaction", e);
+        }
+    } else if let Some(sub_matches) = matches.subcommand_matches("approve") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_approve_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("cpfp") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_cpfp_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("explain") {
+        let client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_explain_command(sub_matches);
+    } else if matches.subcommand_matches("script-budget").is_some() {
+        if let Err(e) = ClientCommand::handle_script_budget_command() {
+            exit_with_error("Script size budget check failed", e);
+        }
+    } else if let Some(sub_matches) = matches.subcommand_matches("automatic") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_automatic_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("watch") {
         let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_automatic_command().await;
+        let _ = client_command.handle_watch_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("jobs") {
+        let client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_jobs_command(sub_matches);
     } else if matches.subcommand_matches("interactive").is_some() {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_interactive_command(&command).await;
     }
 
+    if let Some(profile) = startup_profile() {
+        println!("{profile}");
+    }
+
     Ok(())
 }
@@ -0,0 +1,289 @@
+use std::{collections::HashSet, process::Stdio, time::Duration};
+
+use alloy::transports::http::Client;
+use bitcoin::{Network, PublicKey, Txid};
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command, time::timeout};
+
+use crate::graphs::{
+    base::{GraphId, GraphStage},
+    peg_out::PegOutPresignedTransaction,
+};
+
+use super::graph_list::GraphKind;
+
+// Critical events automatic mode reports to the operator's monitoring: webhooks get the alert
+// POSTed as JSON, commands get it on stdin, e.g. to send an email or page someone. Delivery is best
+// effort, a failing hook is logged and never interrupts the client.
+
+// An alert fires once a take transaction is available in this many blocks or less, about an hour
+pub const DEFAULT_TAKE_WINDOW_ALERT_BLOCKS: u32 = 6;
+// Hooks taking longer are given up on, automatic mode waits for them
+const ALERT_HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Set with the `[alerts]` table in bridge.toml, or `--alert_webhook` and `--alert_command`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AlertConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<String>,
+    // Run with `sh -c`, with `BRIDGE_ALERT_KIND` and `BRIDGE_ALERT_MESSAGE` set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    #[serde(default = "default_take_window_blocks")]
+    pub take_window_blocks: u32,
+    // Also report every stage a graph moves to, e.g. deposit confirmed or kicked off, see
+    // `GraphStage`. Off by default, as it costs a status query of every graph per iteration.
+    #[serde(default)]
+    pub graph_transitions: bool,
+}
+
+fn default_take_window_blocks() -> u32 {
+    DEFAULT_TAKE_WINDOW_ALERT_BLOCKS
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            webhooks: vec![],
+            commands: vec![],
+            take_window_blocks: DEFAULT_TAKE_WINDOW_ALERT_BLOCKS,
+            graph_transitions: false,
+        }
+    }
+}
+
+impl AlertConfig {
+    pub fn has_hooks(&self) -> bool {
+        !self.webhooks.is_empty() || !self.commands.is_empty()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum AlertEvent {
+    ChallengeDetected {
+        graph_id: GraphId,
+    },
+    InvalidProofAsserted {
+        graph_id: GraphId,
+    },
+    DisproveBroadcast {
+        graph_id: GraphId,
+        txid: Txid,
+    },
+    TakeWindowApproaching {
+        graph_id: GraphId,
+        transaction: PegOutPresignedTransaction,
+        available_at: u32, // block height
+    },
+    FlushFailed {
+        error: String,
+    },
+    GraphStageChanged {
+        graph_id: GraphId,
+        graph_type: GraphKind,
+        previous: GraphStage,
+        stage: GraphStage,
+    },
+    // No member contributed to the signing ceremony of the graph for several rounds of turns
+    CeremonyDeadlocked {
+        graph_id: GraphId,
+        pending: Vec<PublicKey>,
+    },
+    // The peg-out of the graph confirmed first among the graphs initiated for its withdrawal
+    PegOutRaceWon {
+        graph_id: GraphId,
+        losers: Vec<GraphId>,
+    },
+    // Another graph's peg-out of the withdrawal confirmed first, the graph is not kicked off
+    PegOutRaceLost {
+        graph_id: GraphId,
+        winner: GraphId,
+    },
+}
+
+impl AlertEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertEvent::ChallengeDetected { .. } => "challenge-detected",
+            AlertEvent::InvalidProofAsserted { .. } => "invalid-proof-asserted",
+            AlertEvent::DisproveBroadcast { .. } => "disprove-broadcast",
+            AlertEvent::TakeWindowApproaching { .. } => "take-window-approaching",
+            AlertEvent::FlushFailed { .. } => "flush-failed",
+            AlertEvent::GraphStageChanged { .. } => "graph-stage-changed",
+            AlertEvent::CeremonyDeadlocked { .. } => "ceremony-deadlocked",
+            AlertEvent::PegOutRaceWon { .. } => "peg-out-race-won",
+            AlertEvent::PegOutRaceLost { .. } => "peg-out-race-lost",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            AlertEvent::ChallengeDetected { graph_id } => {
+                format!("Peg-out graph {graph_id} was challenged")
+            }
+            AlertEvent::InvalidProofAsserted { graph_id } => {
+                format!("The operator of peg-out graph {graph_id} asserted an invalid proof")
+            }
+            AlertEvent::DisproveBroadcast { graph_id, txid } => {
+                format!("Disprove {txid} of peg-out graph {graph_id} was broadcast")
+            }
+            AlertEvent::TakeWindowApproaching {
+                graph_id,
+                transaction,
+                available_at,
+            } => format!(
+                "{transaction} of peg-out graph {graph_id} can be broadcast from block {available_at}"
+            ),
+            AlertEvent::FlushFailed { error } => {
+                format!("Failed to write the client data to the data store: {error}")
+            }
+            AlertEvent::GraphStageChanged {
+                graph_id,
+                graph_type,
+                previous,
+                stage,
+            } => match graph_type {
+                GraphKind::PegIn => format!("Peg-in graph {graph_id} moved from {previous} to {stage}"),
+                GraphKind::PegOut => {
+                    format!("Peg-out graph {graph_id} moved from {previous} to {stage}")
+                }
+            },
+            AlertEvent::CeremonyDeadlocked { graph_id, pending } => {
+                let pending = pending.iter().map(PublicKey::to_string).collect::<Vec<_>>();
+                format!(
+                    "Signing ceremony of graph {graph_id} stopped, waiting for: {}",
+                    pending.join(", ")
+                )
+            }
+            AlertEvent::PegOutRaceWon { graph_id, losers } => format!(
+                "Peg-out graph {graph_id} paid out its withdrawal first, also initiated by {}",
+                losers.join(", ")
+            ),
+            AlertEvent::PegOutRaceLost { graph_id, winner } => format!(
+                "Peg-out graph {winner} paid out the withdrawal of peg-out graph {graph_id} first, {graph_id} is not kicked off"
+            ),
+        }
+    }
+
+    // Graph events are only reported once, a flush failure every time, see `BitVMClient::flush`
+    fn is_recurring(&self) -> bool {
+        matches!(self, AlertEvent::FlushFailed { .. })
+    }
+}
+
+#[derive(Serialize)]
+struct AlertPayload<'a> {
+    #[serde(flatten)]
+    event: &'a AlertEvent,
+    message: String,
+    network: Network,
+    timestamp: u64,
+}
+
+pub struct AlertNotifier {
+    config: AlertConfig,
+    network: Network,
+    client: Option<Client>,
+    sent: HashSet<AlertEvent>,
+}
+
+impl AlertNotifier {
+    pub fn new(config: AlertConfig, network: Network) -> Self {
+        let client = Client::builder()
+            .timeout(ALERT_HOOK_TIMEOUT)
+            .build()
+            .inspect_err(|e| eprintln!("Could not create the alert webhook client: {e}"))
+            .ok();
+
+        Self {
+            config,
+            network,
+            client,
+            sent: HashSet::new(),
+        }
+    }
+
+    pub fn config(&self) -> &AlertConfig {
+        &self.config
+    }
+
+    // Returns whether the alert was passed to the hooks, not whether they succeeded
+    pub async fn notify(&mut self, event: AlertEvent, timestamp: u64) -> bool {
+        if !event.is_recurring() && !self.sent.insert(event.clone()) {
+            return false;
+        }
+
+        let message = event.message();
+        eprintln!("Alert: {message}");
+        let payload = AlertPayload {
+            event: &event,
+            message: message.clone(),
+            network: self.network,
+            timestamp,
+        };
+        let json = serde_json::to_string(&payload).unwrap();
+
+        for url in self.config.webhooks.iter() {
+            if let Err(err) = self.post(url, &json).await {
+                eprintln!("Failed to deliver alert to webhook {url}: {err}");
+            }
+        }
+        for command in self.config.commands.iter() {
+            if let Err(err) = run_command(command, &event, &message, &json).await {
+                eprintln!("Failed to deliver alert to command `{command}`: {err}");
+            }
+        }
+
+        true
+    }
+
+    async fn post(&self, url: &str, json: &str) -> Result<(), String> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| "no webhook client available".to_string())?;
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(json.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        match response.status().is_success() {
+            true => Ok(()),
+            false => Err(format!("responded with {}", response.status())),
+        }
+    }
+}
+
+async fn run_command(
+    command: &str,
+    event: &AlertEvent,
+    message: &str,
+    json: &str,
+) -> Result<(), String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("BRIDGE_ALERT_KIND", event.kind())
+        .env("BRIDGE_ALERT_MESSAGE", message)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    // A command not reading stdin closes it early, which is not an error
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(json.as_bytes()).await;
+    }
+
+    let status = timeout(ALERT_HOOK_TIMEOUT, child.wait())
+        .await
+        .map_err(|_| format!("timed out after {} s", ALERT_HOOK_TIMEOUT.as_secs()))?
+        .map_err(|e| e.to_string())?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(format!("exited with {status}")),
+    }
+}
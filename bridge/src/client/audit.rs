@@ -0,0 +1,267 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bitcoin::{
+    consensus::encode::serialize_hex,
+    hashes::{sha256, Hash, HashEngine},
+    key::Keypair,
+    secp256k1::{schnorr::Signature, Message},
+    BlockHash, Transaction, Txid, XOnlyPublicKey,
+};
+use secp256k1::SECP256K1;
+use serde::{Deserialize, Serialize};
+
+use crate::graphs::base::GraphId;
+
+pub const BROADCAST_AUDIT_FILE_NAME: &str = "broadcast_audit.jsonl";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastOutcome {
+    Accepted(String), // status reported after the broadcast
+    Rejected(String), // error returned by the broadcast
+}
+
+// One broadcast attempt of a graph transaction. Records are appended to the audit file and never
+// rewritten. Each record commits to the hash of the previous one, so removing or reordering
+// records breaks the chain even though every record carries a valid signature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastAuditRecord {
+    pub graph_id: GraphId,
+    pub broadcaster: XOnlyPublicKey,
+    pub timestamp: u64, // seconds since the unix epoch
+    pub txid: Txid,
+    pub raw_tx_hash: sha256::Hash, // sha256 of the raw tx hex, witness included
+    pub outcome: BroadcastOutcome,
+    pub previous_record_hash: Option<sha256::Hash>,
+    pub signature: Signature,
+}
+
+impl BroadcastAuditRecord {
+    pub fn new(
+        keypair: &Keypair,
+        graph_id: &str,
+        tx: &Transaction,
+        outcome: BroadcastOutcome,
+        previous_record_hash: Option<sha256::Hash>,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before the unix epoch")
+            .as_secs();
+        let mut record = BroadcastAuditRecord {
+            graph_id: graph_id.to_string(),
+            broadcaster: keypair.x_only_public_key().0,
+            timestamp,
+            txid: tx.compute_txid(),
+            raw_tx_hash: raw_tx_hash(tx),
+            outcome,
+            previous_record_hash,
+            signature: Signature::from_slice(&[0; 64]).unwrap(),
+        };
+        record.signature = SECP256K1.sign_schnorr_no_aux_rand(&record.message(), keypair);
+
+        record
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        self.signature
+            .verify(&self.message(), &self.broadcaster)
+            .is_ok()
+    }
+
+    // Hash the next record commits to, covers the signature as well
+    pub fn record_hash(&self) -> sha256::Hash {
+        let mut engine = self.message_engine();
+        engine.input(self.signature.as_ref());
+        sha256::Hash::from_engine(engine)
+    }
+
+    fn message(&self) -> Message {
+        Message::from_digest(sha256::Hash::from_engine(self.message_engine()).to_byte_array())
+    }
+
+    fn message_engine(&self) -> sha256::HashEngine {
+        let (outcome_tag, outcome_message) = match &self.outcome {
+            BroadcastOutcome::Accepted(status) => (0u8, status),
+            BroadcastOutcome::Rejected(error) => (1u8, error),
+        };
+
+        let mut engine = sha256::Hash::engine();
+        input_with_length(&mut engine, self.graph_id.as_bytes());
+        engine.input(&self.broadcaster.serialize());
+        engine.input(&self.timestamp.to_le_bytes());
+        engine.input(self.txid.as_byte_array());
+        engine.input(self.raw_tx_hash.as_byte_array());
+        engine.input(&[outcome_tag]);
+        input_with_length(&mut engine, outcome_message.as_bytes());
+        match self.previous_record_hash {
+            Some(hash) => engine.input(hash.as_byte_array()),
+            None => engine.input(&[0; 32]),
+        }
+        engine
+    }
+}
+
+impl fmt::Display for BroadcastAuditRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match &self.outcome {
+            BroadcastOutcome::Accepted(status) => format!("accepted: {status}"),
+            BroadcastOutcome::Rejected(error) => format!("rejected: {error}"),
+        };
+        write!(
+            f,
+            "[{}] {} by {} (raw tx sha256 {}), {outcome}",
+            self.timestamp, self.txid, self.broadcaster, self.raw_tx_hash
+        )
+    }
+}
+
+// Commits to the witness as well, unlike the txid
+pub fn raw_tx_hash(tx: &Transaction) -> sha256::Hash {
+    sha256::Hash::hash(serialize_hex(tx).as_bytes())
+}
+
+fn input_with_length(engine: &mut sha256::HashEngine, data: &[u8]) {
+    engine.input(&(data.len() as u64).to_le_bytes());
+    engine.input(data);
+}
+
+// A graph transaction that confirmed with a witness other than the ones this client broadcast it
+// with, e.g. with an annex or re-encoded stack elements added by whoever relayed it. The txid is
+// the same, the raw tx hashes show the witnesses differ.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WitnessMismatch {
+    pub graph_id: GraphId,
+    pub txid: Txid,
+    pub block_hash: BlockHash,
+    pub broadcast_raw_tx_hashes: Vec<sha256::Hash>,
+    pub confirmed_raw_tx_hash: sha256::Hash,
+}
+
+impl WitnessMismatch {
+    // None if the confirmed tx matches one of the accepted broadcasts of it among the records or
+    // the client never broadcast it
+    pub fn find(
+        records: &[BroadcastAuditRecord],
+        confirmed_tx: &Transaction,
+        block_hash: BlockHash,
+    ) -> Option<Self> {
+        let txid = confirmed_tx.compute_txid();
+        let broadcasts: Vec<&BroadcastAuditRecord> = records
+            .iter()
+            .filter(|record| {
+                record.txid == txid && matches!(record.outcome, BroadcastOutcome::Accepted(_))
+            })
+            .collect();
+        let confirmed_raw_tx_hash = raw_tx_hash(confirmed_tx);
+        if broadcasts.is_empty()
+            || broadcasts
+                .iter()
+                .any(|record| record.raw_tx_hash == confirmed_raw_tx_hash)
+        {
+            return None;
+        }
+
+        let mut broadcast_raw_tx_hashes: Vec<sha256::Hash> =
+            broadcasts.iter().map(|record| record.raw_tx_hash).collect();
+        broadcast_raw_tx_hashes.sort();
+        broadcast_raw_tx_hashes.dedup();
+        Some(Self {
+            graph_id: broadcasts[0].graph_id.clone(),
+            txid,
+            block_hash,
+            broadcast_raw_tx_hashes,
+            confirmed_raw_tx_hash,
+        })
+    }
+}
+
+impl fmt::Display for WitnessMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of graph {} confirmed in block {} with a witness it was not broadcast with (raw tx sha256 {})",
+            self.txid, self.graph_id, self.block_hash, self.confirmed_raw_tx_hash
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditTrailIssue {
+    InvalidSignature(usize),         // index of the record in the audit file
+    BrokenChain(usize),              // the record does not commit to the record before it
+    UnreadableRecord(usize, String), // with the parse error
+}
+
+pub fn get_broadcast_audit_file_path(data_root_path: &Path) -> PathBuf {
+    data_root_path.join(BROADCAST_AUDIT_FILE_NAME)
+}
+
+pub fn read_broadcast_audit_records(
+    data_root_path: &Path,
+) -> std::io::Result<(Vec<BroadcastAuditRecord>, Vec<AuditTrailIssue>)> {
+    let contents = match fs::read_to_string(get_broadcast_audit_file_path(data_root_path)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((vec![], vec![])),
+        Err(e) => return Err(e),
+    };
+
+    let mut records: Vec<BroadcastAuditRecord> = vec![];
+    let mut issues = vec![];
+    let mut previous_record_hash = None;
+    for (index, line) in contents.lines().enumerate() {
+        let record = match serde_json::from_str::<BroadcastAuditRecord>(line) {
+            Ok(record) => record,
+            Err(e) => {
+                issues.push(AuditTrailIssue::UnreadableRecord(index, e.to_string()));
+                previous_record_hash = None;
+                continue;
+            }
+        };
+        if !record.verify_signature() {
+            issues.push(AuditTrailIssue::InvalidSignature(index));
+        }
+        if index > 0 && record.previous_record_hash != previous_record_hash {
+            issues.push(AuditTrailIssue::BrokenChain(index));
+        }
+        previous_record_hash = Some(record.record_hash());
+        records.push(record);
+    }
+
+    Ok((records, issues))
+}
+
+pub fn append_broadcast_audit_record(
+    data_root_path: &Path,
+    keypair: &Keypair,
+    graph_id: &str,
+    tx: &Transaction,
+    outcome: BroadcastOutcome,
+) -> std::io::Result<BroadcastAuditRecord> {
+    let (records, _) = read_broadcast_audit_records(data_root_path)?;
+    let record = BroadcastAuditRecord::new(
+        keypair,
+        graph_id,
+        tx,
+        outcome,
+        records.last().map(BroadcastAuditRecord::record_hash),
+    );
+
+    fs::create_dir_all(data_root_path)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_broadcast_audit_file_path(data_root_path))?;
+    writeln!(
+        file,
+        "{}",
+        serde_json::to_string(&record).expect("Failed to serialize broadcast audit record")
+    )?;
+
+    Ok(record)
+}
@@ -0,0 +1,515 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    process::Stdio,
+    sync::Mutex,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bitcoin::{
+    consensus::encode::serialize_hex,
+    hashes::{sha256, Hash, HashEngine},
+    key::Keypair,
+    secp256k1::{schnorr::Signature, Message},
+    Network, PublicKey, Txid,
+};
+use esplora_client::AsyncClient;
+use secp256k1::SECP256K1;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{io::AsyncWriteExt, process::Command, time::timeout};
+
+use crate::{
+    graphs::base::GraphId, serialization::try_deserialize,
+    transactions::script_diagnostics::diagnose_input_scripts,
+};
+
+use super::{audit::BroadcastOutcome, data_store::data_store::DataStore};
+
+// Deployment policies around the broadcasts of graph transactions, e.g. requiring two people to
+// approve take 1 on mainnet. The checks run before a broadcast in the order the middlewares were
+// added and the first failing one blocks it. Every middleware is told the outcome of a broadcast
+// that was attempted. Deployments add their own with `BitVMClient::add_broadcast_middleware`, or
+// configure the built-in ones with the `[broadcast_policy]` table in bridge.toml.
+
+// Appended to the remote data path, next to the shared data like the staged flushes
+pub const BROADCAST_APPROVAL_DIRECTORY_SUFFIX: &str = "-broadcast-approvals";
+pub const BROADCAST_APPROVAL_FILE_SUFFIX: &str = "-broadcast-approval.json";
+
+const BROADCAST_APPROVAL_MESSAGE_TAG: &[u8] = b"bitvm-bridge/broadcast-approval";
+// Sign-off commands may wait for a person, the broadcast waits with them
+const SIGN_OFF_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+pub struct BroadcastRequest<'a> {
+    pub graph_id: &'a str,
+    pub transaction: Option<&'static str>, // name of the graph transaction, e.g. `Take1`
+    pub tx: &'a bitcoin::Transaction,
+    pub network: Network,
+    pub timestamp: u64, // seconds since the unix epoch
+}
+
+impl BroadcastRequest<'_> {
+    // Transactions are given by name, as `take_1` or `Take1`. An empty list matches every
+    // transaction.
+    pub fn is_one_of(&self, transactions: &[String]) -> bool {
+        transactions.is_empty()
+            || self.transaction.is_some_and(|name| {
+                transactions
+                    .iter()
+                    .any(|transaction| is_same_transaction_name(transaction, name))
+            })
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{} {} of graph {}",
+            self.transaction.unwrap_or("Transaction"),
+            self.tx.compute_txid(),
+            self.graph_id
+        )
+    }
+}
+
+// What the middlewares can query besides the request
+pub struct BroadcastContext<'a> {
+    pub esplora: &'a AsyncClient,
+    pub data_store: &'a DataStore,
+    pub approval_file_path: &'a str,
+}
+
+#[async_trait]
+pub trait BroadcastMiddleware: Send + Sync {
+    fn name(&self) -> &str;
+
+    // An error blocks the broadcast, with the reason given
+    async fn before_broadcast(
+        &self,
+        _request: &BroadcastRequest<'_>,
+        _context: &BroadcastContext<'_>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn after_broadcast(&self, _request: &BroadcastRequest<'_>, _outcome: &BroadcastOutcome) {}
+}
+
+#[derive(Default)]
+pub struct BroadcastMiddlewareChain {
+    middlewares: Vec<Box<dyn BroadcastMiddleware>>,
+}
+
+impl BroadcastMiddlewareChain {
+    pub fn push(&mut self, middleware: Box<dyn BroadcastMiddleware>) {
+        self.middlewares.push(middleware);
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.middlewares
+            .iter()
+            .map(|middleware| middleware.name())
+            .collect()
+    }
+
+    // Fails with the name of the middleware that blocked the broadcast and its reason
+    pub async fn before_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        context: &BroadcastContext<'_>,
+    ) -> Result<(), (String, String)> {
+        for middleware in self.middlewares.iter() {
+            middleware
+                .before_broadcast(request, context)
+                .await
+                .map_err(|reason| (middleware.name().to_string(), reason))?;
+        }
+        Ok(())
+    }
+
+    pub async fn after_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        outcome: &BroadcastOutcome,
+    ) {
+        for middleware in self.middlewares.iter() {
+            middleware.after_broadcast(request, outcome).await;
+        }
+    }
+}
+
+// Set with the `[broadcast_policy]` table in bridge.toml
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BroadcastPolicyConfig {
+    // JSON lines file every broadcast attempt is appended to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+    // Execute the input scripts locally before broadcasting, and block if any of them fails
+    #[serde(default)]
+    pub simulate: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sign_off: Vec<SignOffConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approvals: Vec<ApprovalRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    pub max_broadcasts: usize,
+    pub window: u64, // seconds
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignOffConfig {
+    // Run with `sh -c`, the broadcast goes ahead if it exits with 0
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<String>,
+}
+
+// Broadcasts of `transactions` on `networks` need signed approvals of `required` of the
+// `approvers`, published with `approve`. Empty lists match every transaction or network.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRule {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<Network>,
+    pub required: usize,
+    pub approvers: Vec<PublicKey>,
+}
+
+impl ApprovalRule {
+    pub fn applies_to(&self, request: &BroadcastRequest<'_>) -> bool {
+        request.is_one_of(&self.transactions)
+            && (self.networks.is_empty() || self.networks.contains(&request.network))
+    }
+
+    // Approvers that signed an approval of this transaction of the graph, each counted once
+    pub fn count_approvals(
+        &self,
+        approvals: &[BroadcastApproval],
+        graph_id: &str,
+        txid: &Txid,
+    ) -> usize {
+        approvals
+            .iter()
+            .filter(|approval| {
+                approval.graph_id == graph_id
+                    && approval.txid == *txid
+                    && self.approvers.contains(&approval.public_key)
+                    && approval.verify_signature()
+            })
+            .map(|approval| approval.public_key)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+impl BroadcastPolicyConfig {
+    // Cheap checks first, the simulation fetches the outputs spent by the transaction
+    pub fn middlewares(&self) -> Vec<Box<dyn BroadcastMiddleware>> {
+        let mut middlewares: Vec<Box<dyn BroadcastMiddleware>> = vec![];
+        if let Some(rate_limit) = self.rate_limit.clone() {
+            middlewares.push(Box::new(RateLimitMiddleware::new(rate_limit)));
+        }
+        for rule in self.approvals.iter() {
+            middlewares.push(Box::new(ApprovalMiddleware { rule: rule.clone() }));
+        }
+        for sign_off in self.sign_off.iter() {
+            middlewares.push(Box::new(SignOffMiddleware {
+                config: sign_off.clone(),
+            }));
+        }
+        if self.simulate {
+            middlewares.push(Box::new(SimulationMiddleware));
+        }
+        if let Some(file_path) = self.log_file.clone() {
+            middlewares.push(Box::new(LogMiddleware { file_path }));
+        }
+        middlewares
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastApproval {
+    pub graph_id: GraphId,
+    pub txid: Txid,
+    pub public_key: PublicKey,
+    pub timestamp: u64, // seconds since the unix epoch
+    pub signature: Signature,
+}
+
+impl BroadcastApproval {
+    pub fn new(keypair: &Keypair, graph_id: &str, txid: Txid, timestamp: u64) -> Self {
+        let mut approval = BroadcastApproval {
+            graph_id: graph_id.to_string(),
+            txid,
+            public_key: PublicKey::new(keypair.public_key()),
+            timestamp,
+            signature: Signature::from_slice(&[0; 64]).unwrap(),
+        };
+        approval.signature = SECP256K1.sign_schnorr_no_aux_rand(&approval.message(), keypair);
+
+        approval
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        self.signature
+            .verify(
+                &self.message(),
+                &self.public_key.inner.x_only_public_key().0,
+            )
+            .is_ok()
+    }
+
+    fn message(&self) -> Message {
+        let mut engine = sha256::Hash::engine();
+        engine.input(BROADCAST_APPROVAL_MESSAGE_TAG);
+        engine.input(&(self.graph_id.len() as u64).to_le_bytes());
+        engine.input(self.graph_id.as_bytes());
+        engine.input(self.txid.as_byte_array());
+        engine.input(&self.public_key.to_bytes());
+        engine.input(&self.timestamp.to_le_bytes());
+        Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+    }
+}
+
+pub fn broadcast_approval_file_name(txid: &Txid, public_key: &PublicKey) -> String {
+    format!("{txid}-{public_key}{BROADCAST_APPROVAL_FILE_SUFFIX}")
+}
+
+struct ApprovalMiddleware {
+    rule: ApprovalRule,
+}
+
+#[async_trait]
+impl BroadcastMiddleware for ApprovalMiddleware {
+    fn name(&self) -> &str {
+        "approvals"
+    }
+
+    async fn before_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        context: &BroadcastContext<'_>,
+    ) -> Result<(), String> {
+        if !self.rule.applies_to(request) {
+            return Ok(());
+        }
+
+        let txid = request.tx.compute_txid();
+        let mut approvals: Vec<BroadcastApproval> = vec![];
+        for approver in self.rule.approvers.iter() {
+            let contents = context
+                .data_store
+                .fetch_optional_data_by_key(
+                    &broadcast_approval_file_name(&txid, approver),
+                    Some(context.approval_file_path),
+                )
+                .await?;
+            if let Some(approval) = contents.and_then(|contents| try_deserialize(&contents).ok()) {
+                approvals.push(approval);
+            }
+        }
+
+        let approved = self
+            .rule
+            .count_approvals(&approvals, request.graph_id, &txid);
+        match approved >= self.rule.required {
+            true => Ok(()),
+            false => Err(format!(
+                "{} has {approved} of {} required approvals, approvers publish theirs with `approve --id {} --tx {}`",
+                request.description(),
+                self.rule.required,
+                request.graph_id,
+                request.transaction.unwrap_or("<TX>"),
+            )),
+        }
+    }
+}
+
+struct SignOffMiddleware {
+    config: SignOffConfig,
+}
+
+#[async_trait]
+impl BroadcastMiddleware for SignOffMiddleware {
+    fn name(&self) -> &str {
+        "sign-off"
+    }
+
+    async fn before_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        _context: &BroadcastContext<'_>,
+    ) -> Result<(), String> {
+        if !request.is_one_of(&self.config.transactions) {
+            return Ok(());
+        }
+
+        let txid = request.tx.compute_txid();
+        let json = json!({
+            "graph_id": request.graph_id,
+            "transaction": request.transaction,
+            "txid": txid,
+            "network": request.network,
+            "tx": serialize_hex(request.tx),
+        })
+        .to_string();
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.config.command)
+            .env("BRIDGE_BROADCAST_GRAPH_ID", request.graph_id)
+            .env(
+                "BRIDGE_BROADCAST_TRANSACTION",
+                request.transaction.unwrap_or_default(),
+            )
+            .env("BRIDGE_BROADCAST_TXID", txid.to_string())
+            .stdin(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("`{}` could not be run: {e}", self.config.command))?;
+        // A command not reading stdin closes it early, which is not an error
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(json.as_bytes()).await;
+        }
+
+        let status = timeout(SIGN_OFF_TIMEOUT, child.wait())
+            .await
+            .map_err(|_| {
+                format!(
+                    "`{}` did not sign off {} within {} s",
+                    self.config.command,
+                    request.description(),
+                    SIGN_OFF_TIMEOUT.as_secs()
+                )
+            })?
+            .map_err(|e| e.to_string())?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!(
+                "`{}` refused {} and exited with {status}",
+                self.config.command,
+                request.description()
+            )),
+        }
+    }
+}
+
+struct SimulationMiddleware;
+
+#[async_trait]
+impl BroadcastMiddleware for SimulationMiddleware {
+    fn name(&self) -> &str {
+        "simulation"
+    }
+
+    async fn before_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        context: &BroadcastContext<'_>,
+    ) -> Result<(), String> {
+        let diagnostics = diagnose_input_scripts(context.esplora, request.tx).await;
+        match diagnostics.failed_inputs().next() {
+            None => Ok(()),
+            Some(_) => Err(format!(
+                "Input scripts of {} fail locally\n{diagnostics}",
+                request.description()
+            )),
+        }
+    }
+}
+
+pub struct RateLimitMiddleware {
+    config: RateLimitConfig,
+    broadcasts: Mutex<VecDeque<u64>>, // timestamps of the accepted broadcasts within the window
+}
+
+impl RateLimitMiddleware {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            broadcasts: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl BroadcastMiddleware for RateLimitMiddleware {
+    fn name(&self) -> &str {
+        "rate limit"
+    }
+
+    async fn before_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        _context: &BroadcastContext<'_>,
+    ) -> Result<(), String> {
+        let mut broadcasts = self.broadcasts.lock().unwrap();
+        let window_start = request.timestamp.saturating_sub(self.config.window);
+        while broadcasts
+            .front()
+            .is_some_and(|timestamp| *timestamp <= window_start)
+        {
+            broadcasts.pop_front();
+        }
+        match broadcasts.len() < self.config.max_broadcasts {
+            true => Ok(()),
+            false => Err(format!(
+                "{} broadcasts in the last {} s, {} is held back",
+                broadcasts.len(),
+                self.config.window,
+                request.description()
+            )),
+        }
+    }
+
+    async fn after_broadcast(&self, request: &BroadcastRequest<'_>, outcome: &BroadcastOutcome) {
+        if let BroadcastOutcome::Accepted(_) = outcome {
+            self.broadcasts.lock().unwrap().push_back(request.timestamp);
+        }
+    }
+}
+
+struct LogMiddleware {
+    file_path: PathBuf,
+}
+
+#[async_trait]
+impl BroadcastMiddleware for LogMiddleware {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn after_broadcast(&self, request: &BroadcastRequest<'_>, outcome: &BroadcastOutcome) {
+        let line = json!({
+            "timestamp": request.timestamp,
+            "network": request.network,
+            "graph_id": request.graph_id,
+            "transaction": request.transaction,
+            "txid": request.tx.compute_txid(),
+            "outcome": outcome,
+        });
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)
+            .and_then(|mut file| writeln!(file, "{line}"));
+        if let Err(e) = result {
+            eprintln!(
+                "Failed to log the broadcast to {}: {e}",
+                self.file_path.display()
+            );
+        }
+    }
+}
+
+// `take_1`, `Take1` and `take1` name the same transaction
+pub fn is_same_transaction_name(name: &str, other_name: &str) -> bool {
+    let key = |name: &str| name.to_lowercase().replace('_', "");
+    key(name) == key(other_name)
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use bitcoin::{
+    hashes::{sha256, Hash},
+    PublicKey,
+};
+
+use crate::graphs::base::GraphId;
+
+// Committee members take turns contributing nonces and signatures to a signing ceremony, so that
+// they do not all rewrite the same graph in the data store at once and nobody has to agree out of
+// band on who goes first. The order is derived from the graph id and the committee, so every
+// client computes the same schedule without a coordinator. Turns are only a courtesy: a member
+// whose predecessors all contributed goes right away, and a member that is offline holds up the
+// others for one turn per round at most.
+
+// Seconds a member has for its contribution before the turn passes to the next one
+pub const DEFAULT_CEREMONY_TURN_SECS: u64 = 2 * 60;
+// A ceremony without a new contribution for this many rounds of turns is reported as deadlocked
+pub const DEFAULT_CEREMONY_DEADLOCK_ROUNDS: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CeremonyScheduleConfig {
+    pub turn_secs: u64,
+    pub deadlock_rounds: u64,
+}
+
+impl Default for CeremonyScheduleConfig {
+    fn default() -> Self {
+        Self {
+            turn_secs: DEFAULT_CEREMONY_TURN_SECS,
+            deadlock_rounds: DEFAULT_CEREMONY_DEADLOCK_ROUNDS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum CeremonyPhase {
+    Nonces,
+    Signatures,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CeremonyTurn {
+    // Every member ahead in the order contributed, or the current turn is the member's
+    Ours,
+    // Members ahead in the order have not contributed yet. Retried on the next sync, at the
+    // latest at `turn_at` when the turn comes round to the member.
+    Waiting {
+        ahead: Vec<PublicKey>,
+        turn_at: u64, // unix seconds
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CeremonySchedule {
+    // The committee sorted and rotated by the graph id, so that graphs are not all started by the
+    // same member
+    pub order: Vec<PublicKey>,
+    // Turns pass around the order from this time on, the creation time of the graph
+    pub started_at: u64,
+    pub turn_secs: u64,
+}
+
+impl CeremonySchedule {
+    pub fn new(
+        graph_id: &GraphId,
+        committee: &[PublicKey],
+        started_at: u64,
+        config: &CeremonyScheduleConfig,
+    ) -> Self {
+        let mut order = committee.to_vec();
+        order.sort();
+        order.dedup();
+        if !order.is_empty() {
+            let hash = sha256::Hash::hash(graph_id.as_bytes());
+            let mut prefix = [0u8; 8];
+            prefix.copy_from_slice(&hash.as_byte_array()[..8]);
+            order.rotate_left((u64::from_be_bytes(prefix) % order.len() as u64) as usize);
+        }
+
+        Self {
+            order,
+            started_at,
+            turn_secs: config.turn_secs.max(1),
+        }
+    }
+
+    pub fn position(&self, member: &PublicKey) -> Option<usize> {
+        self.order.iter().position(|pubkey| pubkey == member)
+    }
+
+    pub fn round_secs(&self) -> u64 {
+        self.turn_secs * self.order.len() as u64
+    }
+
+    // Member whose turn it is at `now`
+    pub fn turn_holder(&self, now: u64) -> Option<&PublicKey> {
+        if self.order.is_empty() {
+            return None;
+        }
+
+        self.order
+            .get(self.turn_index(now) as usize % self.order.len())
+    }
+
+    // Start of the next turn of the member, `now` while it holds the turn
+    pub fn next_turn_at(&self, member: &PublicKey, now: u64) -> Option<u64> {
+        let position = self.position(member)? as u64;
+        let committee_size = self.order.len() as u64;
+        let turn_index = self.turn_index(now);
+        let turns_to_wait =
+            (position + committee_size - turn_index % committee_size) % committee_size;
+        if turns_to_wait == 0 {
+            return Some(now);
+        }
+
+        Some(self.started_at + (turn_index + turns_to_wait) * self.turn_secs)
+    }
+
+    // Whether the member contributes now, given the members that still have to contribute to
+    // the current phase. Members outside the committee are not held back, the graph checks whose
+    // contributions it accepts.
+    pub fn turn(&self, member: &PublicKey, pending: &[PublicKey], now: u64) -> CeremonyTurn {
+        let Some(position) = self.position(member) else {
+            return CeremonyTurn::Ours;
+        };
+        let ahead: Vec<PublicKey> = self.order[..position]
+            .iter()
+            .filter(|pubkey| pending.contains(pubkey))
+            .copied()
+            .collect();
+        if ahead.is_empty() || self.turn_holder(now) == Some(member) {
+            return CeremonyTurn::Ours;
+        }
+
+        CeremonyTurn::Waiting {
+            ahead,
+            turn_at: self
+                .next_turn_at(member, now)
+                .expect("The member is in the committee"),
+        }
+    }
+
+    // Whether a ceremony that made no progress for `stalled_secs` is deadlocked: every member had
+    // its turn `deadlock_rounds` times and none of them contributed
+    pub fn is_deadlocked(&self, stalled_secs: u64, config: &CeremonyScheduleConfig) -> bool {
+        !self.order.is_empty() && stalled_secs >= config.deadlock_rounds * self.round_secs()
+    }
+
+    fn turn_index(&self, now: u64) -> u64 {
+        now.saturating_sub(self.started_at) / self.turn_secs
+    }
+}
+
+// Contributions last seen per graph, to tell a slow ceremony from one that stopped. Kept in
+// memory, a restarted client gives the committee another `deadlock_rounds` rounds.
+#[derive(Debug, Default)]
+pub struct CeremonyProgressTracker {
+    graphs: HashMap<GraphId, (usize, u64)>,
+}
+
+impl CeremonyProgressTracker {
+    // Records the number of nonces and signatures contributed to the graph so far and returns the
+    // seconds since it last changed
+    pub fn observe(&mut self, graph_id: &GraphId, contributions: usize, now: u64) -> u64 {
+        let (last_contributions, since) = self
+            .graphs
+            .entry(graph_id.clone())
+            .or_insert((contributions, now));
+        if *last_contributions != contributions {
+            *last_contributions = contributions;
+            *since = now;
+        }
+
+        now.saturating_sub(*since)
+    }
+
+    // Graphs whose ceremony completed or was abandoned
+    pub fn forget(&mut self, graph_id: &GraphId) {
+        self.graphs.remove(graph_id);
+    }
+}
@@ -1,18 +1,52 @@
-use bitcoin::{Amount, OutPoint, PubkeyHash, PublicKey};
+use bitcoin::{Address, AddressType, Amount, OutPoint, PubkeyHash, PublicKey, XOnlyPublicKey};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "client")]
 use super::{chain_adaptor::ChainAdaptor, mock_adaptor::MockAdaptor};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 pub struct PegOutEvent {
     pub withdrawer_chain_address: String,
     pub withdrawer_destination_address: String,
-    pub withdrawer_public_key_hash: PubkeyHash,
+    // Events recorded before taproot destinations were supported only have the public key hash
+    #[serde(alias = "withdrawer_public_key_hash")]
+    pub withdrawer_destination: WithdrawerDestination,
     pub source_outpoint: OutPoint,
     pub amount: Amount,
     pub operator_public_key: PublicKey,
     pub timestamp: u32,
     pub tx_hash: Vec<u8>,
+    // Identifies the withdrawal on the destination chain, committed in kick-off 1 by graphs that
+    // commit to the destination chain metadata. Events recorded before it existed default to 0.
+    #[serde(default)]
+    pub withdrawal_nonce: u64,
+}
+
+// Bitcoin address the withdrawer is paid to, see `PegOutTransaction`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+#[serde(untagged)]
+pub enum WithdrawerDestination {
+    // P2PKH address, paid through a P2WSH output inscribed with the withdrawal
+    PublicKeyHash(PubkeyHash),
+    // P2TR address, paid directly to its output key
+    Taproot {
+        #[serde(with = "crate::serialization::hex_encoded")]
+        output_key: XOnlyPublicKey,
+    },
+}
+
+impl WithdrawerDestination {
+    // Other address types cannot be paid out to
+    pub fn from_address(address: &Address) -> Option<Self> {
+        match address.address_type()? {
+            AddressType::P2pkh => address.pubkey_hash().map(Self::PublicKeyHash),
+            AddressType::P2tr => address
+                .witness_program()
+                .and_then(|program| XOnlyPublicKey::from_slice(program.program().as_bytes()).ok())
+                .map(|output_key| Self::Taproot { output_key }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -32,16 +66,19 @@ pub struct PegInEvent {
     pub depositor_pubkey: PublicKey,
 }
 
+#[cfg(feature = "client")]
 pub struct Chain {
     adaptor: Box<dyn ChainAdaptor>,
 }
 
+#[cfg(feature = "client")]
 impl Default for Chain {
     fn default() -> Self {
         Self::new(Box::new(MockAdaptor::new(None)))
     }
 }
 
+#[cfg(feature = "client")]
 impl Chain {
     pub fn new(adaptor: Box<dyn ChainAdaptor>) -> Self {
         Self { adaptor }
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use async_trait::async_trait;
 
 use crate::constants::DestinationNetwork;
@@ -9,6 +11,10 @@ use super::ethereum_adaptor::EthereumAdaptor;
 use super::ethereum_adaptor::EthereumInitConfig;
 use super::mock_adaptor::MockAdaptor;
 use super::mock_adaptor::MockAdaptorConfig;
+use super::recording_adaptor::RecordingAdaptor;
+
+// Responses of the destination chain are appended to this file when set, see `RecordingAdaptor`
+const RECORD_FILE_VARIABLE: &str = "BRIDGE_CHAIN_ADAPTOR_RECORD_FILE";
 
 #[async_trait]
 pub trait ChainAdaptor {
@@ -23,8 +29,13 @@ pub fn get_chain_adaptor(
     mock_adaptor_config: Option<MockAdaptorConfig>,
 ) -> Box<dyn ChainAdaptor> {
     match network {
-        DestinationNetwork::Ethereum => Box::new(EthereumAdaptor::new(ethereum_config)),
-        DestinationNetwork::EthereumSepolia => Box::new(EthereumAdaptor::new(ethereum_config)),
+        DestinationNetwork::Ethereum | DestinationNetwork::EthereumSepolia => {
+            let adaptor = EthereumAdaptor::new(ethereum_config);
+            match dotenv::var(RECORD_FILE_VARIABLE) {
+                Ok(path) => Box::new(RecordingAdaptor::new(adaptor, Path::new(&path))),
+                Err(_) => Box::new(adaptor),
+            }
+        }
         DestinationNetwork::Local => Box::new(MockAdaptor::new(mock_adaptor_config)),
     }
 }
@@ -3,7 +3,8 @@ use std::str::FromStr;
 use alloy::rpc::types::Log;
 
 use super::{
-    chain::PegInEvent, chain::PegOutBurntEvent, chain::PegOutEvent, chain_adaptor::ChainAdaptor,
+    chain::PegInEvent, chain::PegOutBurntEvent, chain::PegOutEvent, chain::WithdrawerDestination,
+    chain_adaptor::ChainAdaptor,
 };
 use alloy::sol_types::SolEvent;
 use alloy::{
@@ -49,6 +50,56 @@ sol!(
     }
 );
 
+// The bridge contract holds txids in the byte order they are displayed in, the reverse of the
+// byte order of `Txid`
+fn decode_outpoint(outpoint: &IBridge::Outpoint) -> OutPoint {
+    let mut txid = outpoint.txId.to_vec();
+    txid.reverse();
+    OutPoint {
+        txid: Txid::from_slice(&txid).unwrap(),
+        vout: outpoint.vOut.to::<u32>(),
+    }
+}
+
+pub fn decode_peg_out_init_event(e: &Log<IBridge::PegOutInitiated>) -> Option<PegOutEvent> {
+    let withdrawer_address = Address::from_str(&e.inner.data.destination_address)
+        .ok()?
+        .assume_checked();
+    let withdrawer_destination = WithdrawerDestination::from_address(&withdrawer_address)?;
+
+    Some(PegOutEvent {
+        withdrawer_chain_address: e.inner.data.withdrawer.to_string(),
+        withdrawer_destination_address: e.inner.data.destination_address.to_string(),
+        withdrawer_destination,
+        source_outpoint: decode_outpoint(&e.inner.data.source_outpoint),
+        amount: Amount::from_str_in(
+            e.inner.data.amount.to_string().as_str(),
+            Denomination::Satoshi,
+        )
+        .unwrap(),
+        operator_public_key: PublicKey::from_slice(e.inner.data.operator_pubKey.as_ref()).unwrap(),
+        timestamp: u32::try_from(e.block_timestamp.unwrap()).unwrap(),
+        tx_hash: e.transaction_hash.unwrap().to_vec(),
+        // Block number and position of the log in the block, unique per chain
+        withdrawal_nonce: (e.block_number.unwrap() << 32) | e.log_index.unwrap(),
+    })
+}
+
+pub fn decode_peg_out_burnt_event(e: &Log<IBridge::PegOutBurnt>) -> PegOutBurntEvent {
+    PegOutBurntEvent {
+        withdrawer_chain_address: e.inner.data.withdrawer.to_string(),
+        source_outpoint: decode_outpoint(&e.inner.data.source_outpoint),
+        amount: Amount::from_str_in(
+            e.inner.data.amount.to_string().as_str(),
+            Denomination::Satoshi,
+        )
+        .unwrap(),
+        operator_public_key: PublicKey::from_slice(e.inner.data.operator_pubKey.as_ref()).unwrap(),
+        timestamp: u32::try_from(e.block_timestamp.unwrap()).unwrap(),
+        tx_hash: e.transaction_hash.unwrap().to_vec(),
+    }
+}
+
 pub struct EthereumAdaptor {
     bridge_address: EvmAddress,
     bridge_creation_block: u64,
@@ -106,41 +157,7 @@ impl ChainAdaptor for EthereumAdaptor {
         let peg_out_init_events = sol_events
             .unwrap()
             .iter()
-            .filter_map(|e| {
-                let withdrawer_address = Address::from_str(&e.inner.data.destination_address)
-                    .unwrap()
-                    .assume_checked();
-                let operator_public_key =
-                    PublicKey::from_slice(e.inner.data.operator_pubKey.as_ref()).unwrap();
-                match withdrawer_address.pubkey_hash() {
-                    Some(withdrawer_public_key_hash) => {
-                        let mut txid_vec = e.inner.data.source_outpoint.txId.to_vec();
-                        txid_vec.reverse();
-                        Some(PegOutEvent {
-                            withdrawer_chain_address: e.inner.data.withdrawer.to_string(),
-                            withdrawer_destination_address: e
-                                .inner
-                                .data
-                                .destination_address
-                                .to_string(),
-                            withdrawer_public_key_hash,
-                            source_outpoint: OutPoint {
-                                txid: Txid::from_slice(&txid_vec).unwrap(),
-                                vout: e.inner.data.source_outpoint.vOut.to::<u32>(),
-                            },
-                            amount: Amount::from_str_in(
-                                e.inner.data.amount.to_string().as_str(),
-                                Denomination::Satoshi,
-                            )
-                            .unwrap(),
-                            operator_public_key,
-                            timestamp: u32::try_from(e.block_timestamp.unwrap()).unwrap(),
-                            tx_hash: e.transaction_hash.unwrap().to_vec(),
-                        })
-                    }
-                    None => None,
-                }
-            })
+            .filter_map(decode_peg_out_init_event)
             .collect();
 
         Ok(peg_out_init_events)
@@ -155,25 +172,7 @@ impl ChainAdaptor for EthereumAdaptor {
         let peg_out_burnt_events = sol_events
             .unwrap()
             .iter()
-            .map(|e| {
-                let operator_public_key =
-                    PublicKey::from_slice(e.inner.data.operator_pubKey.as_ref()).unwrap();
-                PegOutBurntEvent {
-                    withdrawer_chain_address: e.inner.data.withdrawer.to_string(),
-                    source_outpoint: OutPoint {
-                        txid: Txid::from_slice(e.inner.data.source_outpoint.txId.as_ref()).unwrap(),
-                        vout: e.inner.data.source_outpoint.vOut.to::<u32>(),
-                    },
-                    amount: Amount::from_str_in(
-                        e.inner.data.amount.to_string().as_str(),
-                        Denomination::Satoshi,
-                    )
-                    .unwrap(),
-                    operator_public_key,
-                    timestamp: u32::try_from(e.block_timestamp.unwrap()).unwrap(),
-                    tx_hash: e.transaction_hash.unwrap().to_vec(),
-                }
-            })
+            .map(decode_peg_out_burnt_event)
             .collect();
 
         Ok(peg_out_burnt_events)
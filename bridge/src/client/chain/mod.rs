@@ -1,4 +1,9 @@
 pub mod chain;
+#[cfg(feature = "client")]
 pub mod chain_adaptor;
+#[cfg(feature = "client")]
 pub mod ethereum_adaptor;
+#[cfg(feature = "client")]
 pub mod mock_adaptor;
+#[cfg(feature = "client")]
+pub mod recording_adaptor;
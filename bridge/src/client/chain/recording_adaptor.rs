@@ -0,0 +1,196 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use bitcoin::{OutPoint, PublicKey};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    chain::{PegInEvent, PegOutBurntEvent, PegOutEvent},
+    chain_adaptor::ChainAdaptor,
+};
+
+// Responses of a chain adaptor in the order they were returned, one entry per call
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ChainRecording {
+    #[serde(default)]
+    pub peg_out_init_events: Vec<Result<Vec<PegOutEvent>, String>>,
+    #[serde(default)]
+    pub peg_out_burnt_events: Vec<Result<Vec<PegOutBurntEvent>, String>>,
+    #[serde(default)]
+    pub peg_in_minted_events: Vec<Result<Vec<PegInEvent>, String>>,
+}
+
+impl ChainRecording {
+    pub fn read_from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read chain recording {}: {e}", path.display()))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid chain recording {}: {e}", path.display()))
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to save chain recording {}: {e}", path.display()))
+    }
+
+    // Recorded events reference outpoints and operators of the recorded chain. Tests point them at
+    // the peg-in confirm outpoint and operator of the graphs they create instead.
+    pub fn rebind_source_outpoint(&mut self, recorded: OutPoint, replacement: OutPoint) {
+        for event in events_mut(&mut self.peg_out_init_events) {
+            if event.source_outpoint == recorded {
+                event.source_outpoint = replacement;
+            }
+        }
+        for event in events_mut(&mut self.peg_out_burnt_events) {
+            if event.source_outpoint == recorded {
+                event.source_outpoint = replacement;
+            }
+        }
+    }
+
+    pub fn rebind_operator_public_key(&mut self, recorded: PublicKey, replacement: PublicKey) {
+        for event in events_mut(&mut self.peg_out_init_events) {
+            if event.operator_public_key == recorded {
+                event.operator_public_key = replacement;
+            }
+        }
+        for event in events_mut(&mut self.peg_out_burnt_events) {
+            if event.operator_public_key == recorded {
+                event.operator_public_key = replacement;
+            }
+        }
+    }
+}
+
+fn events_mut<T>(responses: &mut [Result<Vec<T>, String>]) -> impl Iterator<Item = &mut T> {
+    responses
+        .iter_mut()
+        .filter_map(|response| response.as_mut().ok())
+        .flatten()
+}
+
+// Passes calls through to another adaptor and appends every response to a recording file, so a
+// run against a real RPC can be replayed with `ReplayAdaptor` in tests.
+pub struct RecordingAdaptor<A: ChainAdaptor> {
+    adaptor: A,
+    path: PathBuf,
+    recording: Mutex<ChainRecording>,
+}
+
+impl<A: ChainAdaptor> RecordingAdaptor<A> {
+    // Appends to the recording at `path` if there is one already
+    pub fn new(adaptor: A, path: &Path) -> Self {
+        Self {
+            adaptor,
+            path: path.to_path_buf(),
+            recording: Mutex::new(ChainRecording::read_from_file(path).unwrap_or_default()),
+        }
+    }
+
+    fn record(&self, update: impl FnOnce(&mut ChainRecording)) {
+        let mut recording = self.recording.lock().unwrap();
+        update(&mut recording);
+        if let Err(e) = recording.save_to_file(&self.path) {
+            eprintln!("{e}");
+        }
+    }
+}
+
+#[async_trait]
+impl<A: ChainAdaptor + Sync> ChainAdaptor for RecordingAdaptor<A> {
+    async fn get_peg_out_init_event(&self) -> Result<Vec<PegOutEvent>, String> {
+        let response = self.adaptor.get_peg_out_init_event().await;
+        self.record(|recording| recording.peg_out_init_events.push(response.clone()));
+        response
+    }
+
+    async fn get_peg_out_burnt_event(&self) -> Result<Vec<PegOutBurntEvent>, String> {
+        let response = self.adaptor.get_peg_out_burnt_event().await;
+        self.record(|recording| recording.peg_out_burnt_events.push(response.clone()));
+        response
+    }
+
+    async fn get_peg_in_minted_event(&self) -> Result<Vec<PegInEvent>, String> {
+        let response = self.adaptor.get_peg_in_minted_event().await;
+        self.record(|recording| recording.peg_in_minted_events.push(response.clone()));
+        response
+    }
+}
+
+// Returns the responses of a recording in order. Once they run out, the last response is repeated,
+// as the destination chain is assumed to have stayed where the recording stopped.
+pub struct ReplayAdaptor {
+    recording: ChainRecording,
+    calls: Mutex<ReplayCalls>,
+}
+
+#[derive(Default)]
+struct ReplayCalls {
+    peg_out_init: usize,
+    peg_out_burnt: usize,
+    peg_in_minted: usize,
+}
+
+impl ReplayAdaptor {
+    pub fn new(recording: ChainRecording) -> Self {
+        Self {
+            recording,
+            calls: Mutex::new(ReplayCalls::default()),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        Ok(Self::new(ChainRecording::read_from_file(path)?))
+    }
+}
+
+fn replay<T: Clone>(
+    responses: &[Result<Vec<T>, String>],
+    call: &mut usize,
+    name: &str,
+) -> Result<Vec<T>, String> {
+    let index = (*call).min(responses.len().saturating_sub(1));
+    *call += 1;
+    responses
+        .get(index)
+        .cloned()
+        .unwrap_or_else(|| Err(format!("No {name} responses recorded")))
+}
+
+#[async_trait]
+impl ChainAdaptor for ReplayAdaptor {
+    async fn get_peg_out_init_event(&self) -> Result<Vec<PegOutEvent>, String> {
+        let mut calls = self.calls.lock().unwrap();
+        replay(
+            &self.recording.peg_out_init_events,
+            &mut calls.peg_out_init,
+            "peg-out init event",
+        )
+    }
+
+    async fn get_peg_out_burnt_event(&self) -> Result<Vec<PegOutBurntEvent>, String> {
+        let mut calls = self.calls.lock().unwrap();
+        replay(
+            &self.recording.peg_out_burnt_events,
+            &mut calls.peg_out_burnt,
+            "peg-out burnt event",
+        )
+    }
+
+    async fn get_peg_in_minted_event(&self) -> Result<Vec<PegInEvent>, String> {
+        let mut calls = self.calls.lock().unwrap();
+        replay(
+            &self.recording.peg_in_minted_events,
+            &mut calls.peg_in_minted,
+            "peg-in minted event",
+        )
+    }
+}
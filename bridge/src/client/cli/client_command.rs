@@ -1,24 +1,66 @@
-use super::key_command::{Config, KeysCommand};
-use super::utils::get_mock_chain_service;
+use super::committee_command::CommitteeCommand;
+use super::key_command::{pubkey_of, Config, KeysCommand};
+use super::keystore::{KeyRole, PassphraseProvider};
+use super::utils::{
+    get_mock_chain_service, parse_peg_in_graph_id, parse_peg_out_graph_id, parse_txid,
+    report_graph_integrity, total_memory_bytes,
+};
+use super::wizard::{ask_funding_input, parse_amount, print_balance, print_graph_preview, Prompt};
 use crate::client::chain::chain_adaptor::get_chain_adaptor;
 use crate::client::client::BitVMClient;
-use crate::client::esplora::get_esplora_url;
-use crate::commitments::CommitmentMessageId;
+use crate::client::congestion::CongestionConfig;
+use crate::client::esplora::{get_esplora_url, DEFAULT_FEE_RATE_CONFIRMATION_TARGET};
+use crate::client::esplora_notifications::get_esplora_websocket_url;
+use crate::client::faucet::FaucetClient;
+use crate::client::graph_list::{GraphListFilter, GraphRole};
+use crate::client::heartbeat::HeartbeatConfig;
+use crate::client::jobs::{JobId, JobState};
+use crate::client::memory_cache::{cache_stats, configure_caches};
+use crate::client::peg_in_queue::PegInProcessingConfig;
+use crate::client::profiling::{enable_startup_profile, profile_phase, profile_phase_async};
+use crate::client::scheduler::{BlockScheduler, SchedulerEvent};
+use crate::client::shutdown::{
+    RunSummary, Shutdown, STAGED_FLUSH_GRACE_PERIOD, STAGED_FLUSH_POLL_INTERVAL,
+};
+use crate::client::staged_flush::FlushCoordinationConfig;
 use crate::common::ZkProofVerifyingKey;
+use crate::connectors::cache_files::{
+    cache_directory, migrate_cache_directory, spawn_cache_migration,
+};
 use crate::constants::DestinationNetwork;
-use crate::contexts::base::generate_keys_from_secret;
-use crate::graphs::base::{PEG_IN_FEE, PEG_OUT_FEE};
+use crate::destination::destination_commitment;
+use crate::error::{ClientError, Error, ValidationError};
+use crate::graphs::base::{
+    peg_in_fee, peg_out_fee, BaseGraph, GraphStage, PegInGraphId, PegOutGraphId, MIN_RELAY_FEE_RATE,
+};
+use crate::graphs::funding::{
+    peg_in_deposit_amount, peg_out_confirm_funding, required_funding, select_deposit_funding,
+};
+use crate::graphs::peg_out::{ChallengePolicy, MerkleRootVerification, PegOutPresignedTransaction};
+use crate::graphs::template::{GraphParameters, GraphTemplate};
 use crate::proof::{get_proof, invalidate_proof};
-use crate::transactions::base::{Input, MIN_RELAY_FEE_PEG_OUT};
+use crate::proof_binding::ExternalProof;
+use crate::protocol::{describe, script_budget_report};
+use crate::rewards::parse_reward_destination;
+use crate::scripts::DepositorAddressType;
+use crate::serialization::try_deserialize;
+use crate::transactions::assert_transactions::assert_final::AssertFinalOutputLayout;
+use crate::transactions::base::{
+    relay_fee_at_rate, Input, MIN_RELAY_FEE_PEG_OUT, VSIZE_KICK_OFF_1_COLLATERAL,
+};
+use crate::transactions::fee_sensitivity::{fee_sensitivity_warnings, FEE_RATE_WARNING_THRESHOLD};
+use crate::transactions::peg_in_deposit::DepositFunding;
 use ark_serialize::CanonicalDeserialize;
+use bitvm::chunk::config::{chunker_config, set_chunker_config, ChunkerConfig};
 
-use bitcoin::{Address, PublicKey};
-use bitcoin::{Network, OutPoint};
-use clap::{arg, ArgMatches, Command};
+use bitcoin::{Amount, PublicKey};
+use bitcoin::{Network, OutPoint, Txid};
+use clap::{arg, Arg, ArgGroup, ArgMatches, Command};
 use colored::Colorize;
 use std::io::{self, Write};
+use std::path::Path;
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::time::sleep;
 
@@ -27,15 +69,22 @@ pub struct CommonArgs {
     pub verifiers: Option<Vec<PublicKey>>,
     pub environment: Option<String>,
     pub path_prefix: Option<String>,
+    pub profile_startup: bool, // report where time goes during startup and the first graph operation
 }
 
 pub struct ClientCommand {
     client: BitVMClient,
     config: Config,
+    // Background jobs only finish while the process keeps running, see `jobs`
+    interactive: bool,
 }
 
 impl ClientCommand {
     pub async fn new(common_args: CommonArgs) -> Self {
+        if common_args.profile_startup {
+            enable_startup_profile();
+        }
+
         let (source_network, destination_network) = match common_args.environment.as_deref() {
             Some("mainnet") => (Network::Bitcoin, DestinationNetwork::Ethereum),
             Some("testnet") => (Network::Testnet, DestinationNetwork::EthereumSepolia),
@@ -47,11 +96,55 @@ impl ClientCommand {
         };
 
         let keys_command = KeysCommand::new(common_args.key_dir);
-        let config = keys_command
-            .read_config()
-            .expect("Failed to read config file");
+        let mut config = profile_phase("read config", || {
+            keys_command
+                .read_config()
+                .expect("Failed to read config file")
+        });
+
+        // Plaintext secret keys are kept working for regtest setups
+        let plaintext_roles = config.plaintext_roles();
+        if source_network != Network::Regtest && !plaintext_roles.is_empty() {
+            let roles = plaintext_roles
+                .iter()
+                .map(KeyRole::to_string)
+                .collect::<Vec<_>>();
+            eprintln!(
+                "warning: The {} secret keys are stored in plaintext in {}. Encrypt them with `keys --encrypt`.",
+                roles.join(", "),
+                keys_command.config_path.display()
+            );
+        }
+        if let Err(err) = config.unlock(&mut PassphraseProvider::new()) {
+            eprintln!("error: Failed to decrypt the secret keys: {err}");
+            std::process::exit(1);
+        }
 
-        let n_of_n_public_keys = common_args.verifiers.expect("Error: Verifier public keys must be specified either in command line or environment variable.");
+        let n_of_n_public_keys = common_args
+            .verifiers
+            .or_else(|| {
+                config
+                    .committee
+                    .as_ref()
+                    .map(|committee| committee.verifiers.clone())
+            })
+            .expect("Error: Verifier public keys must be specified either in command line, environment variable or config file.");
+
+        // After a key rotation, the retired key is used with the committees it is a member of
+        let verifier_secret = config
+            .verifier_secret_for(&n_of_n_public_keys)
+            .unwrap_or_else(|err| {
+                eprintln!("error: {err}");
+                std::process::exit(1);
+            });
+        if verifier_secret.is_none() {
+            if let Some(secret) = config.keys.verifier.as_deref() {
+                eprintln!(
+                    "Verifier key {} is not a member of the committee, continuing without the verifier role.",
+                    pubkey_of(secret)
+                );
+            }
+        }
 
         let mut verifying_key = None;
         if let Some(vk) = config.keys.verifying_key.clone() {
@@ -59,50 +152,164 @@ impl ClientCommand {
             verifying_key = Some(ZkProofVerifyingKey::deserialize_compressed(&*bytes).unwrap());
         }
 
-        let bitvm_client = BitVMClient::new(
-            Some(get_esplora_url(source_network)),
-            source_network,
-            destination_network,
-            Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Will be replaced with a destination network specific adaptor once Ethereum support is added.
-            &n_of_n_public_keys,
-            config.keys.depositor.as_deref(),
-            config.keys.operator.as_deref(),
-            config.keys.verifier.as_deref(),
-            config.keys.withdrawer.as_deref(),
-            common_args.path_prefix.as_deref(),
-            verifying_key,
+        if let Some(cache) = config.cache.as_ref() {
+            configure_caches(cache);
+        }
+        // Caches written by an earlier version would otherwise be converted on first use, which
+        // can be in the middle of a dispute
+        spawn_cache_migration();
+        if let Some(chunker) = config.chunker.clone() {
+            set_chunker_config(chunker);
+        }
+
+        let mut bitvm_client = profile_phase_async(
+            "client startup",
+            BitVMClient::new(
+                Some(get_esplora_url(source_network)),
+                source_network,
+                destination_network,
+                Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Will be replaced with a destination network specific adaptor once Ethereum support is added.
+                &n_of_n_public_keys,
+                config.keys.depositor.as_deref(),
+                config.keys.operator.as_deref(),
+                verifier_secret,
+                config.keys.withdrawer.as_deref(),
+                common_args.path_prefix.as_deref(),
+                verifying_key,
+            ),
         )
         .await;
+        // The contexts hold the keys from here on
+        config.keys.zeroize_secrets();
+        if let Some(header_verification) = config.header_verification.clone() {
+            bitvm_client.set_header_verification(header_verification);
+        }
+        if let Some(rewards) = config.rewards.as_ref() {
+            if let Err(err) = bitvm_client.set_reward_destinations(rewards) {
+                eprintln!("error: Invalid reward destination: {err}");
+                std::process::exit(1);
+            }
+        }
+        if let Some(broadcast_policy) = config.broadcast_policy.as_ref() {
+            bitvm_client.set_broadcast_policy(broadcast_policy);
+        }
 
         Self {
             client: bitvm_client,
             config,
+            interactive: false,
         }
     }
 
-    async fn get_funding_utxo_input(&self, utxo_arg: Option<&String>) -> io::Result<Input> {
-        let utxo = utxo_arg.expect("Missing UTXO argument, please see help.");
-        let outpoint = OutPoint::from_str(utxo)
-            .expect("Could not parse the provided UTXO, please see help for the correct format.");
+    async fn get_funding_utxo_input(&self, utxo_arg: Option<&String>) -> Result<Input, Error> {
+        let utxo = utxo_arg.ok_or(Error::Other(String::from(
+            "Missing UTXO argument, please see help.",
+        )))?;
+        let outpoint = OutPoint::from_str(utxo).map_err(|_| {
+            Error::Other(String::from(
+                "Could not parse the provided UTXO, please see help for the correct format.",
+            ))
+        })?;
         let tx = self
             .client
             .esplora
             .get_tx(&outpoint.txid)
             .await
-            .map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Esplora failed to retrieve tx: {e}"),
-                )
-            })?;
-        let tx = tx.expect(&format!("Esplora did not find a txid {}", outpoint.txid));
+            .map_err(Error::Esplora)?
+            .ok_or(Error::Client(ClientError::FundingUtxoNotFound(outpoint)))?;
+        let output = tx
+            .output
+            .get(outpoint.vout as usize)
+            .ok_or(Error::Client(ClientError::FundingUtxoNotFound(outpoint)))?;
 
         Ok(Input {
             outpoint,
-            amount: tx.output[outpoint.vout as usize].value,
+            amount: output.value,
         })
     }
 
+    async fn get_faucet_funded_input(
+        &self,
+        sub_matches: &ArgMatches,
+        fee_rate: u64,
+    ) -> Result<Input, Error> {
+        let Some(faucet_url) = sub_matches.get_one::<String>("faucet_url") else {
+            return Err(Error::Other(String::from(
+                "A faucet URL must be specified either in command line or environment variable.",
+            )));
+        };
+        let faucet = FaucetClient::new(faucet_url, self.client.source_network)?;
+        let amount =
+            Amount::from_sat(*sub_matches.get_one::<u64>("amount").unwrap() + peg_in_fee(fee_rate));
+
+        self.client
+            .fund_depositor_from_faucet(&faucet, amount)
+            .await
+    }
+
+    fn background_arg() -> Arg {
+        arg!(--background "Run as a background job in interactive mode, see the jobs command")
+            .required(false)
+    }
+
+    fn run_in_background(&self, sub_matches: &ArgMatches) -> Result<bool, Error> {
+        let background = sub_matches.get_flag("background");
+        if background && !self.interactive {
+            return Err(Error::Other(String::from(
+                "Background jobs only run in interactive mode, the process would exit before they finish",
+            )));
+        }
+        Ok(background)
+    }
+
+    fn fee_rate_arg() -> Arg {
+        arg!(--"fee-rate" <SAT_PER_VB> "Fee rate to build the graph transactions at, overriding the [fees] table of the config. Estimated by esplora if neither is set")
+            .required(false)
+            .value_parser(clap::value_parser!(u64).range(MIN_RELAY_FEE_RATE..))
+    }
+
+    // `--fee-rate`, then the `[fees]` table of the config, then the esplora estimate. Falls back to
+    // the minimum relay fee rate if esplora has no estimate, as on a fresh regtest node.
+    async fn fee_rate(&self, sub_matches: Option<&ArgMatches>) -> u64 {
+        let fees = self.config.fees.unwrap_or_default();
+        let fee_rate_arg =
+            sub_matches.and_then(|sub_matches| sub_matches.get_one::<u64>("fee-rate").copied());
+        if let Some(fee_rate) = fee_rate_arg.or(fees.fee_rate) {
+            return fee_rate;
+        }
+
+        let target_blocks = fees
+            .confirmation_target
+            .unwrap_or(DEFAULT_FEE_RATE_CONFIRMATION_TARGET);
+        match self.client.estimate_fee_rate(target_blocks).await {
+            Ok(fee_rate) => {
+                println!("Esplora estimates {fee_rate} sat/vB for confirmation within {target_blocks} blocks");
+                fee_rate
+            }
+            Err(err) => {
+                eprintln!("warning: Could not estimate the fee rate ({err}), using the minimum relay fee rate of {MIN_RELAY_FEE_RATE} sat/vB");
+                MIN_RELAY_FEE_RATE
+            }
+        }
+    }
+
+    async fn ask_fee_rate(&self, prompt: &mut Prompt) -> io::Result<u64> {
+        let default_fee_rate = self.fee_rate(None).await.to_string();
+        prompt
+            .ask_parsed(
+                "Fee rate of the graph transactions, in sat/vB",
+                Some(&default_fee_rate),
+                |answer| match answer.parse::<u64>() {
+                    Ok(fee_rate) if fee_rate >= MIN_RELAY_FEE_RATE => Ok(fee_rate),
+                    Ok(_) => Err(format!(
+                        "The fee rate must be at least {MIN_RELAY_FEE_RATE} sat/vB"
+                    )),
+                    Err(_) => Err(format!("Invalid fee rate {answer}")),
+                },
+            )
+            .await
+    }
+
     pub fn get_funding_amounts_command() -> Command {
         Command::new("get-funding-amounts")
             .short_flag('m')
@@ -110,25 +317,31 @@ impl ClientCommand {
             .after_help(
                 "This is useful when creating the funding UTXOs for the peg-in and peg-out graphs.",
             )
+            .arg(Self::fee_rate_arg())
     }
 
-    pub async fn handle_get_funding_amounts(&self) -> io::Result<()> {
+    pub async fn handle_get_funding_amounts(&self, sub_matches: &ArgMatches) -> io::Result<()> {
         const INITIAL_AMOUNT: u64 = 2 << 20; // 2097152
 
-        println!("Minimum required input amounts");
+        let fee_rate = self.fee_rate(Some(sub_matches)).await;
+        println!("Minimum required input amounts at {fee_rate} sat/vB");
         println!("------------------------------");
         println!(
             "'Peg-in deposit' tx input:  {} SAT (spendable by [DEPOSITOR])",
-            INITIAL_AMOUNT + PEG_IN_FEE
+            INITIAL_AMOUNT + peg_in_fee(fee_rate)
         );
         println!(
             "'Peg-out confirm' tx input: {} SAT (spendable by [OPERATOR])",
-            INITIAL_AMOUNT + PEG_OUT_FEE
+            INITIAL_AMOUNT + peg_out_fee(fee_rate)
         );
         println!(
             "'Peg-out' tx input:         {} SAT (spendable by [OPERATOR])",
             INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_OUT
         );
+        println!(
+            "'Kick-off 1' collateral input, if the graph posts collateral: the collateral + {} SAT (spendable by [OPERATOR])",
+            relay_fee_at_rate(VSIZE_KICK_OFF_1_COLLATERAL, fee_rate)
+        );
 
         println!();
         self.handle_get_depositor_address().await?;
@@ -184,7 +397,7 @@ impl ClientCommand {
         Command::new("get-depositor-address")
             .short_flag('d')
             .about("Get an address spendable by the configured depositor private key")
-            .after_help("Get an address spendable by the configured depositor private key")
+            .after_help("Get the addresses spendable by the configured depositor private key: a P2WSH address and a taproot key path address, the latter to be spent with `initiate-peg-in --taproot`")
     }
 
     pub async fn handle_get_depositor_address(&self) -> io::Result<()> {
@@ -192,6 +405,13 @@ impl ClientCommand {
             "[DEPOSITOR] address: {}",
             self.client.get_depositor_address().to_string().green()
         );
+        println!(
+            "[DEPOSITOR] taproot address: {}",
+            self.client
+                .get_depositor_taproot_address()
+                .to_string()
+                .green()
+        );
         Ok(())
     }
 
@@ -204,11 +424,12 @@ impl ClientCommand {
 
     pub async fn handle_get_depositor_utxos(&mut self) -> io::Result<()> {
         let utxos = self.client.get_depositor_utxos().await;
-        match utxos.len() {
+        let taproot_utxos = self.client.get_depositor_taproot_utxos().await;
+        match utxos.len() + taproot_utxos.len() {
             0 => println!("No depositor UTXOs found."),
             utxo_count => {
                 println!(
-                    "{} operator UTXO{} found (<TXID>:<VOUT> <AMOUNT> <CONFIRMED>):",
+                    "{} depositor UTXO{} found (<TXID>:<VOUT> <AMOUNT> <CONFIRMED>):",
                     utxo_count,
                     if utxo_count == 1 { "" } else { "s" }
                 );
@@ -218,6 +439,13 @@ impl ClientCommand {
                         utxo.txid, utxo.vout, utxo.value, utxo.status.confirmed
                     );
                 }
+                // Spent with `initiate-peg-in --taproot`
+                for utxo in taproot_utxos {
+                    println!(
+                        "{}:{} {} {} (taproot)",
+                        utxo.txid, utxo.vout, utxo.value, utxo.status.confirmed
+                    );
+                }
             }
         }
 
@@ -229,34 +457,107 @@ impl ClientCommand {
         .short_flag('n')
         .about("Initiate a peg-in")
         .after_help("Initiate a peg-in by creating a peg-in graph")
-        .arg(arg!(-u --utxo <UTXO> "Specify the utxo to spend from. Format: <TXID>:<VOUT>")
-        .required(true))
-        .arg(arg!(-d --destination_address <EVM_ADDRESS> "The evm-address to send the wrapped bitcoin to")
+        .arg(arg!(-u --utxo <UTXO> "Specify a utxo to spend from, repeat to spend several. Format: <TXID>:<VOUT>")
+        .required_unless_present_any(["faucet", "auto-select"])
+        .action(clap::ArgAction::Append))
+        .arg(arg!(-d --destination_address <DESTINATION_ADDRESS> "The address to send the wrapped bitcoin to: an EVM address, or <CHAIN_ID>:<ADDRESS> for any chain of the destination registry")
         .required(true))
+        .arg(arg!(--faucet "Fund the depositor address from a faucet instead of spending --utxo. Not available on mainnet")
+        .required(false)
+        .conflicts_with("utxo")
+        .requires("amount"))
+        .arg(arg!(--"auto-select" "Select the utxos to spend from the depositor UTXOs instead of spending --utxo")
+        .required(false)
+        .conflicts_with_all(["utxo", "faucet"])
+        .requires("amount"))
+        .arg(arg!(--taproot "Spend the utxos of the depositor taproot address, see get-depositor-address")
+        .required(false)
+        .conflicts_with("faucet"))
+        .arg(arg!(--amount <SATS> "Amount to peg in, the peg-in fee comes on top. The rest of the utxos is paid back to the depositor address. Everything but the fee is pegged in if not set")
+        .required(false)
+        .value_parser(clap::value_parser!(u64)))
+        .arg(arg!(--faucet_url <URL> "Faucet used with --faucet")
+        .required(false)
+        .env("FAUCET_URL"))
+        .arg(arg!(--template <TEMPLATE> "Graph template (standard-mainnet, fast-regtest, high-security)")
+        .required(false)
+        .default_value("standard-mainnet")
+        .value_parser(clap::value_parser!(GraphTemplate)))
+        .arg(Self::fee_rate_arg())
     }
 
+    // Errors are returned rather than printed, see `handle_broadcast_command`
     pub async fn handle_initiate_peg_in_command(
         &mut self,
         sub_matches: &ArgMatches,
-    ) -> io::Result<()> {
+    ) -> Result<(), Error> {
         self.client.sync().await;
 
         let evm_address = sub_matches
             .get_one::<String>("destination_address")
             .unwrap();
-        let input = self
-            .get_funding_utxo_input(sub_matches.get_one::<String>("utxo"))
+        if let Err(err) = destination_commitment(evm_address) {
+            return Err(Error::Validation(
+                ValidationError::InvalidDestinationAddress(format!("{err:?}")),
+            ));
+        }
+        let fee_rate = self.fee_rate(Some(sub_matches)).await;
+        let depositor_address_type = match sub_matches.get_flag("taproot") {
+            true => DepositorAddressType::P2tr,
+            false => DepositorAddressType::P2wsh,
+        };
+        let peg_in_amount = sub_matches
+            .get_one::<u64>("amount")
+            .map(|amount| Amount::from_sat(*amount));
+        let funding = if sub_matches.get_flag("faucet") {
+            DepositFunding::from(self.get_faucet_funded_input(sub_matches, fee_rate).await?)
+        } else if sub_matches.get_flag("auto-select") {
+            self.client
+                .select_depositor_funding(peg_in_amount.unwrap(), depositor_address_type, fee_rate)
+                .await?
+        } else {
+            let mut inputs = Vec::new();
+            for utxo in sub_matches.get_many::<String>("utxo").into_iter().flatten() {
+                inputs.push(self.get_funding_utxo_input(Some(utxo)).await?);
+            }
+            match peg_in_amount {
+                // All of the utxos are spent, even if fewer would do
+                Some(peg_in_amount) => {
+                    select_deposit_funding(&inputs, peg_in_amount, fee_rate).map_err(
+                        |required| {
+                            Error::Client(ClientError::InsufficientDepositorFunds {
+                                required,
+                                available: inputs.iter().map(|input| input.amount).sum(),
+                            })
+                        },
+                    )?;
+                    DepositFunding::new(
+                        inputs,
+                        Some(peg_in_deposit_amount(peg_in_amount, fee_rate)),
+                    )
+                }
+                None => DepositFunding::new(inputs, None),
+            }
+        };
+        let template = *sub_matches.get_one::<GraphTemplate>("template").unwrap();
+        let peg_in_id = self
+            .client
+            .create_peg_in_graph(
+                funding,
+                evm_address,
+                template
+                    .parameters()
+                    .with_fee_rate(fee_rate)
+                    .with_depositor_address_type(depositor_address_type),
+            )
             .await?;
-        let peg_in_id = self.client.create_peg_in_graph(input, evm_address).await;
 
         self.client.flush().await;
 
         println!("Created peg-in graph with ID: {peg_in_id}");
         println!("Broadcasting deposit...");
 
-        if let Err(e) = self.client.broadcast_peg_in_deposit(&peg_in_id).await {
-            eprintln!("Failed to broadcast peg-in deposit: {e}");
-        }
+        self.client.broadcast_peg_in_deposit(&peg_in_id).await?;
 
         Ok(())
     }
@@ -271,27 +572,295 @@ impl ClientCommand {
                     .required(true),
             )
             .arg(
-                arg!(-i --peg_in_id <PEG_IN_GRAPH_ID> "Specify the peg-in graph ID").required(true),
+                arg!(-i --peg_in_id <PEG_IN_GRAPH_ID> "Specify the peg-in graph ID")
+                    .required(true)
+                    .value_parser(parse_peg_in_graph_id),
+            )
+            .arg(
+                arg!(--consolidate "Consolidate the assert final outputs to reduce the number of UTXOs, regardless of the graph template")
+                    .required(false),
             )
+            .arg(
+                arg!(--collateral <SATS> "Post slashable collateral in kick-off 1, overriding the graph template")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+            .arg(
+                arg!(--collateral_utxo <UTXO> "Specify the utxo to fund the collateral from, picked from the operator UTXOs if not set. Format: <TXID>:<VOUT>")
+                    .required(false),
+            )
+            .arg(Self::fee_rate_arg())
+            .arg(Self::background_arg())
     }
 
     pub async fn handle_create_peg_out_graph_command(
         &mut self,
         sub_matches: &ArgMatches,
     ) -> io::Result<()> {
+        let background = self.run_in_background(sub_matches)?;
         self.client.sync().await;
 
-        let peg_in_id = sub_matches.get_one::<String>("peg_in_id").unwrap();
+        let peg_in_id = sub_matches.get_one::<PegInGraphId>("peg_in_id").unwrap();
         let input = self
             .get_funding_utxo_input(sub_matches.get_one::<String>("utxo"))
             .await?;
 
-        let peg_out_id = self.client.create_peg_out_graph(
+        // Peg-out graphs use the template of their peg-in graph
+        let mut parameters = self
+            .client
+            .peg_in_graph(peg_in_id)
+            .map(|peg_in_graph| peg_in_graph.template().parameters())
+            .unwrap_or_default();
+        if sub_matches.get_flag("consolidate") {
+            parameters =
+                parameters.with_assert_final_output_layout(AssertFinalOutputLayout::Consolidated);
+        }
+        if let Some(collateral) = sub_matches.get_one::<u64>("collateral") {
+            parameters = parameters.with_operator_collateral(Amount::from_sat(*collateral));
+        }
+        parameters = parameters.with_fee_rate(self.fee_rate(Some(sub_matches)).await);
+
+        let collateral_input = match parameters.operator_collateral_funding_amount() {
+            Some(funding_amount) => {
+                let collateral_input = match sub_matches.get_one::<String>("collateral_utxo") {
+                    Some(utxo) => self.get_funding_utxo_input(Some(utxo)).await?,
+                    None => self
+                        .client
+                        .select_operator_collateral_input(funding_amount, &[input.outpoint])
+                        .await
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::NotFound,
+                                format!("No operator UTXO of at least {funding_amount} to fund the collateral with"),
+                            )
+                        })?,
+                };
+                if collateral_input.amount < funding_amount {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "Collateral UTXO holds {}, at least {funding_amount} is required",
+                            collateral_input.amount
+                        ),
+                    ));
+                }
+                Some(collateral_input)
+            }
+            None => None,
+        };
+
+        if background {
+            let job_id =
+                self.client
+                    .queue_peg_out_graph(peg_in_id, input, collateral_input, parameters);
+            println!("Creating the peg-out graph in background job {job_id}");
+            return Ok(());
+        }
+
+        let peg_out_id = self.client.create_peg_out_graph_with_collateral(
             peg_in_id,
             input,
-            CommitmentMessageId::generate_commitment_secrets(),
+            collateral_input,
+            parameters,
+        );
+
+        self.client.flush().await;
+
+        println!("Created peg-out with ID: {peg_out_id}");
+        Ok(())
+    }
+
+    pub fn get_wizard_command() -> Command {
+        Command::new("wizard")
+            .about("Walk through creating a peg-in or peg-out graph")
+            .after_help("Checks the depositor or operator balance, suggests funding UTXOs of the required amounts, validates the answers and previews the transactions and fees of the graph before creating it. Press enter to take the suggested answer in brackets.")
+    }
+
+    pub async fn handle_wizard_command(&mut self) -> io::Result<()> {
+        let mut prompt = Prompt::new();
+        self.client.sync().await;
+
+        let create_peg_in = prompt
+            .ask_parsed(
+                "Create a peg-in or a peg-out graph? (peg-in, peg-out)",
+                Some("peg-in"),
+                |answer| match answer {
+                    "peg-in" => Ok(true),
+                    "peg-out" => Ok(false),
+                    _ => Err(String::from("Answer peg-in or peg-out")),
+                },
+            )
+            .await?;
+
+        match create_peg_in {
+            true => self.peg_in_wizard(&mut prompt).await,
+            false => self.peg_out_wizard(&mut prompt).await,
+        }
+    }
+
+    async fn peg_in_wizard(&mut self, prompt: &mut Prompt) -> io::Result<()> {
+        self.handle_get_depositor_address().await?;
+        let utxos = self.client.get_depositor_utxos().await;
+        print_balance("DEPOSITOR", &utxos);
+
+        let network = self.client.source_network;
+        let default_template = match network {
+            Network::Regtest => GraphTemplate::FastRegtest,
+            _ => GraphTemplate::default(),
+        }
+        .to_string();
+        let template = prompt
+            .ask_parsed(
+                "Graph template (standard-mainnet, fast-regtest, high-security)",
+                Some(&default_template),
+                |answer| match GraphTemplate::from_str(answer) {
+                    Ok(template) if template.supports_network(network) => Ok(template),
+                    Ok(template) => Err(format!("{template} is not available on {network}")),
+                    Err(_) => Err(format!("Unknown graph template {answer}")),
+                },
+            )
+            .await?;
+
+        let fee_rate = self.ask_fee_rate(prompt).await?;
+        let parameters = template.parameters().with_fee_rate(fee_rate);
+
+        let amount = prompt
+            .ask_parsed("Amount to peg in, in sats", None, parse_amount)
+            .await?;
+        let funding = required_funding(amount, &parameters);
+        println!(
+            "The peg-in deposit spends a UTXO of at least {} ({amount} plus {} of fees)",
+            funding.peg_in_deposit,
+            funding.peg_in_deposit - amount
+        );
+        let input = ask_funding_input(prompt, &utxos, funding.peg_in_deposit, &[]).await?;
+
+        let evm_address = prompt
+            .ask_parsed(
+                "Destination address (an EVM address, or <CHAIN_ID>:<ADDRESS>)",
+                None,
+                |answer| {
+                    destination_commitment(answer)
+                        .map(|_| answer.to_string())
+                        .map_err(|err| format!("Invalid destination address: {err:?}"))
+                },
+            )
+            .await?;
+
+        let peg_in_graph = self
+            .client
+            .build_peg_in_graph(input, &evm_address, parameters)?;
+        print_graph_preview(
+            &peg_in_graph.transaction_dag(),
+            &peg_in_graph.fee_sensitivity(),
+        );
+        if !prompt
+            .confirm("Create the peg-in graph and broadcast its deposit?")
+            .await?
+        {
+            println!("No graph created");
+            return Ok(());
+        }
+
+        let peg_in_id = self.client.add_peg_in_graph(peg_in_graph)?;
+        self.client.flush().await;
+
+        println!("Created peg-in graph with ID: {peg_in_id}");
+        println!("Broadcasting deposit...");
+
+        if let Err(e) = self.client.broadcast_peg_in_deposit(&peg_in_id).await {
+            eprintln!("Failed to broadcast peg-in deposit: {e}");
+        }
+
+        Ok(())
+    }
+
+    async fn peg_out_wizard(&mut self, prompt: &mut Prompt) -> io::Result<()> {
+        let peg_in_graphs: Vec<(PegInGraphId, GraphParameters, Amount)> = self
+            .client
+            .data()
+            .peg_in_graphs
+            .iter()
+            .map(|peg_in_graph| {
+                (
+                    peg_in_graph.id().clone(),
+                    peg_in_graph.template().parameters(),
+                    peg_in_graph.peg_in_deposit_transaction.tx().output[0].value,
+                )
+            })
+            .collect();
+        if peg_in_graphs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No peg-in graph to create a peg-out graph for",
+            ));
+        }
+        println!("Peg-in graphs (<ID> <TEMPLATE> <DEPOSIT>):");
+        for (id, parameters, deposit_amount) in &peg_in_graphs {
+            println!("{id} {} {deposit_amount}", parameters.template);
+        }
+
+        let default_id = match peg_in_graphs.as_slice() {
+            [(id, _, _)] => Some(id.to_string()),
+            _ => None,
+        };
+        let (peg_in_id, mut parameters, deposit_amount) = prompt
+            .ask_parsed("Peg-in graph ID", default_id.as_deref(), |answer| {
+                let id = parse_peg_in_graph_id(answer)?;
+                peg_in_graphs
+                    .iter()
+                    .find(|(peg_in_id, _, _)| *peg_in_id == id)
+                    .cloned()
+                    .ok_or(format!("Peg-in graph {id} not found"))
+            })
+            .await?;
+
+        let default_collateral = parameters.operator_collateral.to_sat().to_string();
+        let collateral = prompt
+            .ask_parsed(
+                "Collateral to post in kick-off 1, in sats (0 for none)",
+                Some(&default_collateral),
+                parse_amount,
+            )
+            .await?;
+        parameters = parameters.with_operator_collateral(collateral);
+        parameters = parameters.with_fee_rate(self.ask_fee_rate(prompt).await?);
+
+        self.handle_get_operator_address().await?;
+        let utxos = self.client.get_operator_utxos().await;
+        print_balance("OPERATOR", &utxos);
+
+        let peg_out_confirm_amount = peg_out_confirm_funding(deposit_amount, &parameters);
+        println!(
+            "The peg-out confirm spends a UTXO of at least {peg_out_confirm_amount} (the operator reward of the template plus fees)"
+        );
+        let input = ask_funding_input(prompt, &utxos, peg_out_confirm_amount, &[]).await?;
+        let collateral_input = match parameters.operator_collateral_funding_amount() {
+            Some(funding_amount) => {
+                println!(
+                    "Kick-off 1 spends a UTXO of at least {funding_amount} for the collateral"
+                );
+                Some(ask_funding_input(prompt, &utxos, funding_amount, &[input.outpoint]).await?)
+            }
+            None => None,
+        };
+
+        println!("Building the peg-out graph...");
+        let (peg_out_graph, commitment_secrets) =
+            self.client
+                .build_peg_out_graph(&peg_in_id, input, collateral_input, parameters);
+        print_graph_preview(
+            &peg_out_graph.transaction_dag(),
+            &peg_out_graph.fee_sensitivity(),
         );
+        if !prompt.confirm("Create the peg-out graph?").await? {
+            println!("No graph created");
+            return Ok(());
+        }
 
+        let peg_out_id = self
+            .client
+            .add_peg_out_graph(peg_out_graph, commitment_secrets);
         self.client.flush().await;
 
         println!("Created peg-out with ID: {peg_out_id}");
@@ -302,12 +871,23 @@ impl ClientCommand {
         Command::new("push-nonces")
             .short_flag('c')
             .about("Push nonces for peg-out or peg-in graph")
-            .after_help("")
+            .after_help("With --spot-check, the first call requests the leaf proofs from the operator, who supplies them in automatic mode. Run the command again once they are available.")
             .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+            .arg(
+                arg!(--spot_check <SAMPLE_SIZE> "Verify the connector C merkle root of a peg-out graph by checking this many random leaves supplied by the operator, instead of generating all of them")
+                    .required(false)
+                    .value_parser(clap::value_parser!(usize)),
+            )
     }
 
     pub async fn handle_push_nonces_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
         let graph_id = sub_matches.get_one::<String>("id").unwrap();
+        if let Some(sample_size) = sub_matches.get_one::<usize>("spot_check") {
+            self.client
+                .set_merkle_root_verification(MerkleRootVerification::SpotCheck {
+                    sample_size: *sample_size,
+                });
+        }
 
         self.client.sync().await;
         self.client.push_verifier_nonces(graph_id);
@@ -337,79 +917,977 @@ impl ClientCommand {
         Ok(())
     }
 
-    pub fn get_mock_l2_pegout_event_command() -> Command {
-        Command::new("mock-l2-pegout-event")
-            .short_flag('x')
-            .about("FOR TEST PURPOSES ONLY! Use mock L2 chain service with specified peg-in-confirm txid")
-            .after_help("")
-            .arg(
-                arg!(-u --utxo <UTXO> "Specify the peg-in confirm utxo. Format: <TXID>:<VOUT>")
-                    .required(true),
-            )
+    pub fn get_resume_ceremony_command() -> Command {
+        Command::new("resume-ceremony")
+            .about("Resume the signing ceremony of a peg-out or peg-in graph")
+            .after_help("Pushes the nonces not pushed yet and signs every transaction whose nonces are complete, e.g. after the client stopped between push-nonces and push-signatures. Peg-out transactions whose secret nonces were lost are re-signed with fresh nonces.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
     }
 
-    pub async fn handle_mock_l2_pegout_event_command(
+    pub async fn handle_resume_ceremony_command(
         &mut self,
         sub_matches: &ArgMatches,
     ) -> io::Result<()> {
-        let utxo = sub_matches.get_one::<String>("utxo").unwrap();
-        let outpoint = OutPoint::from_str(utxo).expect(
-            "Could not parse the provided UTXO, please see help for the correct format: {e}.",
-        );
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
-        if let Some(operator_secret) = &self.config.keys.operator {
-            let (_, operator_public_key) =
-                generate_keys_from_secret(self.client.source_network, operator_secret);
+        self.client.sync().await;
+        match self.client.resume_ceremony(graph_id).await {
+            Ok(statuses) => {
+                for status in statuses {
+                    println!("{status}");
+                }
+                self.client.flush().await;
+            }
+            Err(e) => println!("Failed to resume the signing ceremony: {e}"),
+        }
 
-            self.client.sync().await;
-            let mock_chain_service = get_mock_chain_service(outpoint, operator_public_key);
-            self.client.set_chain_service(mock_chain_service);
-            self.client.sync_l2().await;
-            self.client.flush().await;
-        } else {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Failed to set chain service, missing operator configuration",
-            ));
+        Ok(())
+    }
+
+    pub fn get_export_descriptors_command() -> Command {
+        Command::new("export-descriptors")
+            .about("Export output descriptors of the connector addresses of a graph")
+            .after_help("Descriptors marked as complete contain all spending paths. Output only descriptors (addr() and rawtr()) let wallets and indexers track the outputs without being able to spend them.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+    }
+
+    pub async fn handle_export_descriptors_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
+
+        self.client.sync().await;
+        match self.client.export_descriptors(graph_id) {
+            Ok(descriptors) => {
+                for descriptor in descriptors {
+                    println!("{descriptor}");
+                }
+            }
+            Err(err) => eprintln!("error: {err}"),
         }
 
         Ok(())
     }
 
-    pub fn get_automatic_command() -> Command {
-        Command::new("automatic")
-            .short_flag('a')
-            .about("Automatic mode: Poll for status updates and sign or broadcast transactions")
+    pub fn get_export_signing_bundle_command() -> Command {
+        Command::new("export-signing-bundle")
+            .about("Export the sighash preimages of the pre-signed transactions of a graph")
+            .after_help("Prints a JSON bundle with the unsigned transactions, their previous outputs and, for every committee input, the leaf script, sighash type, BIP 341 signature message and sighash. The sighash is the TapSighash tagged hash of the preimage, so auditors and hardware signers can recompute what the committee authorizes.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
     }
 
-    pub async fn handle_automatic_command(&mut self) -> io::Result<()> {
-        loop {
-            self.client.sync().await;
+    pub async fn handle_export_signing_bundle_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
-            let old_data = self.client.data().clone();
+        self.client.sync().await;
+        match self.client.export_signing_bundle(graph_id) {
+            Ok(bundle) => println!("{}", serde_json::to_string_pretty(&bundle)?),
+            Err(err) => eprintln!("error: {err}"),
+        }
 
-            self.client.process_peg_ins().await;
-            self.client.process_peg_outs().await;
+        Ok(())
+    }
 
-            // A bit inefficient, but fine for now: only flush if data changed
-            if self.client.data() != &old_data {
-                self.client.flush().await;
-            } else {
-                sleep(Duration::from_millis(250)).await;
-            }
+    pub fn get_export_events_command() -> Command {
+        Command::new("export-events")
+            .about("Export the block anchored event log of the bridge for destination chain relayers")
+            .after_help("Prints one JSON event per line for every confirmed graph transaction, ordered as mined: the graph it belongs to, its txid, the height and hash of its block and the merkle branch proving its inclusion. The log is kept in the local data directory and extended on every sync, events less than 6 blocks deep are dropped if a reorg undoes them.")
+            .arg(
+                arg!(--from_height <HEIGHT> "Only export events mined at or above this height")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u32))
+                    .default_value("0"),
+            )
+    }
+
+    pub async fn handle_export_events_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let from_height = sub_matches.get_one::<u32>("from_height").unwrap();
+
+        self.client.enable_event_log();
+        self.client.sync().await;
+        for event in self.client.export_event_log(*from_height) {
+            println!("{}", serde_json::to_string(&event)?);
         }
+
+        Ok(())
     }
 
-    // TODO: there are verifier's commands missing here
-    pub fn get_broadcast_command() -> Command {
-        Command::new("broadcast")
-            .short_flag('b')
-            .about("Broadcast transactions")
-            .after_help("Broadcast transactions.")
+    pub fn get_history_command() -> Command {
+        Command::new("history")
+            .about("Show the broadcast audit trail of a graph")
+            .after_help("Every broadcast attempt made by this client is appended to a local audit file and signed with the key of the client. Records that fail signature verification or break the record chain are reported.")
+            .arg(arg!(-g --graph_id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+    }
+
+    pub async fn handle_history_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("graph_id").unwrap();
+
+        let (records, issues) = self.client.broadcast_history(graph_id)?;
+        if records.is_empty() {
+            println!("No broadcasts recorded for graph {graph_id}");
+        }
+        for record in records {
+            println!("{record}");
+        }
+        for issue in issues {
+            eprintln!("{}", format!("Audit trail issue: {issue:?}").red());
+        }
+
+        Ok(())
+    }
+
+    pub fn get_graph_command() -> Command {
+        Command::new("graph")
+            .about("Inspect a graph and its past states")
             .subcommand(
-                Command::new("pegin")
+                Command::new("show")
+                    .about("Show the transactions of a peg-out graph and the fee rates they can be broadcast at")
+                    .after_help("Pre-signed transactions pay the fee fixed when the graph was created. Each one is listed with the highest fee rate it remains broadcastable at, and those below the warning threshold are flagged.")
+                    .arg(
+                        arg!(-i --id <GRAPH_ID> "Specify the peg-out graph ID")
+                            .required(true)
+                            .value_parser(parse_peg_out_graph_id),
+                    )
+                    .arg(
+                        arg!(-w --warn_below <SAT_PER_VB> "Flag transactions only broadcastable below this fee rate, defaults to the threshold warned about at graph creation")
+                            .required(false)
+                            .value_parser(clap::value_parser!(u64)),
+                    ),
+            )
+            .subcommand(
+                Command::new("replay")
+                    .about("Step through the changes of a graph recorded in the data store")
+                    .after_help("Every data store version that changed the graph is listed with the nonces and signatures it added, which helps finding out why a signing ceremony got stuck.")
+                    .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+                    .arg(
+                        arg!(-u --until <TIMESTAMP> "Stop at the state as of this time, in milliseconds since the unix epoch")
+                            .required(false)
+                            .value_parser(clap::value_parser!(u64)),
+                    ),
+            )
+            .subcommand_required(true)
+    }
+
+    pub async fn handle_graph_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        match sub_matches.subcommand() {
+            Some(("show", show_matches)) => self.handle_graph_show_command(show_matches).await,
+            Some(("replay", replay_matches)) => {
+                self.handle_graph_replay_command(replay_matches).await
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    async fn handle_graph_show_command(&mut self, show_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = show_matches.get_one::<PegOutGraphId>("id").unwrap();
+        let warn_below = show_matches
+            .get_one::<u64>("warn_below")
+            .copied()
+            .unwrap_or(FEE_RATE_WARNING_THRESHOLD);
+
+        self.client.sync().await;
+
+        let Some(peg_out_graph) = self
+            .client
+            .data()
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+        else {
+            println!("Peg-out graph {graph_id} not found");
+            return Ok(());
+        };
+
+        println!(
+            "Peg-out graph {graph_id} of peg-in graph {}",
+            peg_out_graph.peg_in_graph_id
+        );
+        for (name, txid) in peg_out_graph.txids() {
+            println!("  {name}: {txid}");
+        }
+
+        let report = peg_out_graph.fee_sensitivity();
+        println!("\nFee sensitivity:");
+        for sensitivity in &report {
+            println!("  {sensitivity}");
+        }
+        let warnings = fee_sensitivity_warnings(&report, warn_below);
+        if warnings.is_empty() {
+            println!("All transactions are broadcastable at {warn_below} sat/vB");
+        }
+        for sensitivity in warnings {
+            println!(
+                "{}",
+                format!(
+                    "Warning: {} is only broadcastable up to {} sat/vB, below {warn_below} sat/vB",
+                    sensitivity.transaction, sensitivity.max_fee_rate
+                )
+                .yellow()
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn handle_graph_replay_command(&mut self, replay_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = replay_matches.get_one::<String>("id").unwrap();
+        let until = replay_matches.get_one::<u64>("until").copied();
+
+        let transitions = match self.client.graph_history(graph_id, until).await {
+            Ok(transitions) => transitions,
+            Err(e) => {
+                eprintln!("Failed to read graph history: {e}");
+                return Ok(());
+            }
+        };
+        let Some(last_transition) = transitions.last() else {
+            println!("Graph {graph_id} not found in the data store history");
+            return Ok(());
+        };
+
+        for transition in &transitions {
+            println!("{transition}");
+        }
+        println!("\nState as of {}:", last_transition.timestamp);
+        let progress = last_transition.signing_progress();
+        if progress.is_empty() {
+            println!("  All pre-signed transactions are signed");
+        }
+        for progress in progress {
+            println!("  {progress}");
+        }
+
+        Ok(())
+    }
+
+    pub fn get_validate_graph_command() -> Command {
+        Command::new("validate-graph")
+            .about("Check the integrity of a peg-in or peg-out graph before broadcasting from it")
+            .after_help("Every transaction is compared with the one re-derived from the graph's connectors, and missing committee signatures and malformed commitment public keys are reported. The chain is not queried.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+    }
+
+    pub async fn handle_validate_graph_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
+
+        self.client.sync().await;
+
+        report_graph_integrity(graph_id, self.client.graph_integrity_issues(graph_id))
+    }
+
+    pub fn get_cache_command() -> Command {
+        Command::new("cache")
+            .about("Inspect the in-memory caches and migrate the disk caches")
+            .subcommand(
+                Command::new("stats")
+                    .about("Show the size, capacity, hits, misses and evictions of each cache")
+                    .after_help("Counters cover the current process only, so they are most useful in interactive or automatic mode. Capacities are set with the [cache] table in bridge.toml.")
+                    .arg(arg!(-s --sync "Sync the client first so the counters include loading the graphs").required(false)),
+            )
+            .subcommand(
+                Command::new("migrate")
+                    .about("Remove the connector C disk caches written by earlier versions, which are named by stale cache ids")
+                    .after_help("Every client migrates the caches in the background on startup, so running this is only needed to free the disk space ahead of time, e.g. right after an upgrade. Files written by a newer version are left as they are."),
+            )
+            .subcommand(
+                Command::new("export-lock-scripts")
+                    .about("Export the connector C lock scripts of a peg-out graph, generating them unless they are cached")
+                    .after_help("Verifiers import the exported lock scripts instead of generating them, which takes minutes per graph. With --publish they are shared through the data store, where `cache import-lock-scripts --id` fetches them.")
+                    .arg(
+                        arg!(-i --id <GRAPH_ID> "Specify the peg-out graph ID")
+                            .required(true)
+                            .value_parser(parse_peg_out_graph_id),
+                    )
+                    .arg(arg!(-o --output <FILE> "Write the lock scripts to this file").required(false))
+                    .arg(arg!(--publish "Publish the lock scripts to the data store").required(false))
+                    .group(ArgGroup::new("destination").args(["output", "publish"]).required(true).multiple(true)),
+            )
+            .subcommand(
+                Command::new("warm-up")
+                    .about("Generate the connector C lock scripts of peg-out graphs unless they are cached")
+                    .after_help("Asserting and disproving spend from connector C, generating its lock scripts then stalls for minutes unless they were generated ahead of time. Without --id, every peg-out graph is warmed up.")
+                    .arg(
+                        arg!(-i --id <GRAPH_ID> "Specify the peg-out graph ID")
+                            .required(false)
+                            .value_parser(parse_peg_out_graph_id),
+                    )
+                    .arg(Self::background_arg()),
+            )
+            .subcommand(
+                Command::new("import-lock-scripts")
+                    .about("Import connector C lock scripts exported by another member")
+                    .after_help("The lock scripts are only cached once every leaf locks one of the operator's commitment public keys and the scripts hash to the merkle root of the graph's connector C.")
+                    .arg(arg!(-f --file <FILE> "Import the lock scripts from this file").required(false))
+                    .arg(
+                        arg!(-i --id <GRAPH_ID> "Fetch the lock scripts of this peg-out graph from the data store")
+                            .required(false)
+                            .value_parser(parse_peg_out_graph_id),
+                    )
+                    .group(ArgGroup::new("source").args(["file", "id"]).required(true)),
+            )
+            .subcommand_required(true)
+    }
+
+    pub async fn handle_cache_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let stats_matches = match sub_matches.subcommand() {
+            Some(("stats", stats_matches)) => stats_matches,
+            Some(("migrate", _)) => return self.handle_cache_migrate_command(),
+            Some(("export-lock-scripts", export_matches)) => {
+                return self
+                    .handle_export_lock_scripts_command(export_matches)
+                    .await
+            }
+            Some(("import-lock-scripts", import_matches)) => {
+                return self
+                    .handle_import_lock_scripts_command(import_matches)
+                    .await
+            }
+            Some(("warm-up", warm_up_matches)) => {
+                return self.handle_warm_up_command(warm_up_matches).await
+            }
+            _ => unreachable!(),
+        };
+        if stats_matches.get_flag("sync") {
+            self.client.sync().await;
+        }
+
+        for (name, stats) in cache_stats() {
+            println!("{name}: {stats}");
+        }
+
+        Ok(())
+    }
+
+    fn handle_cache_migrate_command(&self) -> io::Result<()> {
+        let directory = cache_directory();
+        let report = migrate_cache_directory(&directory)?;
+        for file_path in report.removed.iter() {
+            println!("Removed {}", file_path.display());
+        }
+        for (file_path, e) in report.failed.iter() {
+            println!("{}", format!("Skipped {}: {e}", file_path.display()).red());
+        }
+        println!(
+            "{} stale cache files in {} removed, {} up to date, {} skipped",
+            report.removed.len(),
+            directory.display(),
+            report.up_to_date,
+            report.failed.len()
+        );
+
+        Ok(())
+    }
+
+    async fn handle_export_lock_scripts_command(
+        &mut self,
+        export_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let graph_id = export_matches.get_one::<PegOutGraphId>("id").unwrap();
+        self.client.sync().await;
+
+        if let Some(output) = export_matches.get_one::<String>("output") {
+            let merkle_root = self
+                .client
+                .export_lock_scripts(graph_id, Path::new(output))?;
+            println!("Lock scripts with merkle root {merkle_root} written to {output}");
+        }
+        if export_matches.get_flag("publish") {
+            let merkle_root = self.client.publish_lock_scripts(graph_id).await?;
+            println!("Lock scripts with merkle root {merkle_root} published to the data store");
+        }
+
+        Ok(())
+    }
+
+    async fn handle_warm_up_command(&mut self, warm_up_matches: &ArgMatches) -> io::Result<()> {
+        let background = self.run_in_background(warm_up_matches)?;
+        self.client.sync().await;
+
+        let graph_ids = match warm_up_matches.get_one::<PegOutGraphId>("id") {
+            Some(graph_id) => vec![graph_id.clone()],
+            None => self
+                .client
+                .data()
+                .peg_out_graphs
+                .iter()
+                .map(|graph| graph.id().clone())
+                .collect(),
+        };
+        for graph_id in graph_ids {
+            if background {
+                match self.client.queue_lock_scripts(&graph_id)? {
+                    Some(job_id) => println!(
+                        "Generating the lock scripts of peg-out graph {graph_id} in background job {job_id}"
+                    ),
+                    None => println!("Lock scripts of peg-out graph {graph_id} are cached"),
+                }
+            } else {
+                self.client.warm_up_peg_out_graph(&graph_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_import_lock_scripts_command(
+        &mut self,
+        import_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        self.client.sync().await;
+
+        if let Some(file) = import_matches.get_one::<String>("file") {
+            let graph_id = self.client.import_lock_scripts(Path::new(file))?;
+            println!("Lock scripts of peg-out graph {graph_id} verified and cached");
+        } else if let Some(graph_id) = import_matches.get_one::<PegOutGraphId>("id") {
+            match self.client.fetch_lock_scripts(graph_id).await? {
+                true => println!("Lock scripts of peg-out graph {graph_id} verified and cached"),
+                false => println!(
+                    "Nothing imported, the lock scripts of peg-out graph {graph_id} are cached already or were not published"
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_resign_command() -> Command {
+        Command::new("resign")
+            .short_flag('e')
+            .about("Re-sign pre-signed peg-out transactions after they changed")
+            .after_help("Invalidate pre-signed peg-out transactions, e.g. after a connector or reward script change, and repeat the musig2 ceremony for those transactions only.")
+            .arg(
+                arg!(-i --id <GRAPH_ID> "Specify the peg-out graph ID")
+                    .required(true)
+                    .value_parser(parse_peg_out_graph_id),
+            )
+            .subcommand(
+                Command::new("invalidate")
+                    .about("Invalidate pre-signed transactions and start a new signing round")
+                    .arg(
+                        arg!(-t --txs <TXS> "Comma-separated list of transactions to invalidate (assert_initial, assert_final, disprove_chain, disprove, kick_off_timeout, start_time_timeout, take_1, take_2)")
+                            .required(true)
+                            .value_delimiter(',')
+                            .value_parser(clap::value_parser!(PegOutPresignedTransaction)),
+                    ),
+            )
+            .subcommand(
+                Command::new("nonces").about("Push nonces for the invalidated transactions"),
+            )
+            .subcommand(
+                Command::new("signatures")
+                    .about("Push signatures for the invalidated transactions"),
+            )
+            .subcommand(
+                Command::new("status").about("Show which verifiers have re-signed"),
+            )
+            .subcommand_required(true)
+    }
+
+    pub async fn handle_resign_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<PegOutGraphId>("id").unwrap();
+
+        self.client.sync().await;
+
+        let result = match sub_matches.subcommand() {
+            Some(("invalidate", invalidate_matches)) => {
+                let transactions: Vec<PegOutPresignedTransaction> = invalidate_matches
+                    .get_many::<PegOutPresignedTransaction>("txs")
+                    .unwrap()
+                    .copied()
+                    .collect();
+                self.client
+                    .invalidate_presigned_transactions(graph_id, &transactions)
+                    .await
+            }
+            Some(("nonces", _)) => self.client.push_verifier_resign_nonces(graph_id),
+            Some(("signatures", _)) => self.client.push_verifier_resign_signature(graph_id),
+            Some(("status", _)) => {
+                match self.client.resign_status(graph_id) {
+                    Ok(statuses) => {
+                        for status in statuses {
+                            println!(
+                                "{} (round {}, template {}): signed by {:?}, pending {:?}",
+                                status.transaction,
+                                status.round,
+                                status.template_id,
+                                status.signed,
+                                status.pending
+                            );
+                        }
+                    }
+                    Err(e) => println!("Failed to get resign status: {e}"),
+                }
+                return Ok(());
+            }
+            _ => unreachable!(),
+        };
+
+        match result {
+            Ok(_) => self.client.flush().await,
+            Err(e) => println!("Failed to resign transactions: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_mock_l2_pegout_event_command() -> Command {
+        Command::new("mock-l2-pegout-event")
+            .short_flag('x')
+            .about("FOR TEST PURPOSES ONLY! Use mock L2 chain service with specified peg-in-confirm txid")
+            .after_help("")
+            .arg(
+                arg!(-u --utxo <UTXO> "Specify the peg-in confirm utxo. Format: <TXID>:<VOUT>")
+                    .required(true),
+            )
+    }
+
+    pub async fn handle_mock_l2_pegout_event_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let utxo = sub_matches.get_one::<String>("utxo").unwrap();
+        let outpoint = OutPoint::from_str(utxo).expect(
+            "Could not parse the provided UTXO, please see help for the correct format: {e}.",
+        );
+
+        if let Some(operator_public_key) = self.client.operator_public_key() {
+            self.client.sync().await;
+            let mock_chain_service = get_mock_chain_service(outpoint, operator_public_key);
+            self.client.set_chain_service(mock_chain_service);
+            self.client.sync_l2().await;
+            self.client.flush().await;
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Failed to set chain service, missing operator configuration",
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn get_automatic_command() -> Command {
+        Command::new("automatic")
+            .short_flag('a')
+            .about("Automatic mode: On every new block, check for status updates and sign or broadcast transactions")
+            .after_help("Peg-in graphs are processed by priority: graphs closest to their refund timeout first, then by deposit confirmation age and amount. Verifiers check the proof the operator asserted on chain and disprove it if it is invalid. Proofs known to be invalid are always challenged, the challenge policy decides about proofs that are not asserted yet.")
+            .arg(
+                arg!(--max_peg_in_actions <MAX_ACTIONS> "Maximum number of peg-in graphs to act on per sync, the rest are handled on the next one")
+                    .required(false)
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(
+                arg!(--challenge_policy <POLICY> "As a verifier, whether to challenge kicked-off peg-outs whose proof cannot be verified yet: challenge or wait")
+                    .required(false)
+                    .value_parser(["challenge", "wait"])
+                    .default_value("wait"),
+            )
+            .arg(
+                arg!(--heartbeat_interval <SECONDS> "As a verifier, publish a signed heartbeat to the data store at this interval, so other committee members see when this client was last online")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64).range(1..)),
+            )
+            .arg(
+                arg!(--heartbeat_beacon "Also publish each heartbeat as an OP_RETURN output spending from the verifier's P2WPKH address, on regtest and testnet only")
+                    .required(false)
+                    .requires("heartbeat_interval"),
+            )
+            .arg(
+                arg!(--event_log "Keep the block anchored event log for relayers up to date, see export-events")
+                    .required(false),
+            )
+            .arg(
+                arg!(--notifications_url <URL> "Websocket pushing new blocks and graph address activity, derived from the esplora url by default. The tip is polled while it cannot be reached.")
+                    .required(false),
+            )
+            .arg(
+                arg!(--poll "Only poll the chain tip, do not connect to the esplora websocket")
+                    .required(false)
+                    .conflicts_with("notifications_url"),
+            )
+            .arg(
+                arg!(--flush_acknowledgments <COUNT> "Stage every flush until this many other committee members validated and approved it, and as a verifier acknowledge the flushes others staged")
+                    .required(false)
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .args(Self::chunker_args())
+            .arg(
+                arg!(--defer_above_fee_rate <SAT_PER_VB> "As an operator, hold back the peg-out confirm and take 1 broadcasts while the estimated fee rate is above this, see --max_deferral_blocks")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+            .arg(
+                arg!(--max_deferral_blocks <BLOCKS> "Broadcast a held back transaction regardless of the fee rate after this many blocks")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u32)),
+            )
+            .args(Self::alert_args())
+            .arg(
+                arg!(--"max-runtime" <SECONDS> "Shut down gracefully after running this long, e.g. to be restarted by a supervisor")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64).range(1..)),
+            )
+    }
+
+    fn chunker_args() -> [Arg; 3] {
+        [
+            arg!(--chunker_parallelism <THREADS> "As a verifier, number of disprove scripts executed in parallel when checking an asserted proof")
+                .required(false)
+                .value_parser(clap::value_parser!(usize)),
+            arg!(--chunker_max_in_flight <SEGMENTS> "As a verifier, number of disprove scripts compiled ahead of their execution")
+                .required(false)
+                .value_parser(clap::value_parser!(usize)),
+            arg!(--chunker_memory_budget <MIB> "As a verifier, memory for compiled disprove scripts, the ones beyond are written to the temporary directory until executed")
+                .required(false)
+                .value_parser(clap::value_parser!(u64)),
+        ]
+    }
+
+    fn alert_args() -> [Arg; 4] {
+        [
+            arg!(--alert_webhook <URL> "POST a JSON alert to this URL on challenges, invalid proofs, disproves, approaching take windows and failed flushes, can be given multiple times")
+                .required(false)
+                .action(clap::ArgAction::Append),
+            arg!(--alert_command <COMMAND> "Run this shell command with the JSON alert on stdin for the same events, e.g. to send an email, can be given multiple times")
+                .required(false)
+                .action(clap::ArgAction::Append),
+            arg!(--take_window_alert_blocks <BLOCKS> "Alert once a take transaction becomes available in this many blocks")
+                .required(false)
+                .value_parser(clap::value_parser!(u32)),
+            arg!(--alert_graph_transitions "Also alert whenever a graph moves to another stage, e.g. deposit confirmed, kicked off, challenged, asserted, disproved or taken")
+                .required(false),
+        ]
+    }
+
+    pub async fn handle_automatic_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let challenge_policy = sub_matches.get_one::<String>("challenge_policy").unwrap();
+        self.client
+            .set_challenge_policy(ChallengePolicy::from_str(challenge_policy).unwrap());
+        if let Some(max_actions) = sub_matches.get_one::<usize>("max_peg_in_actions") {
+            self.client
+                .set_peg_in_processing_config(PegInProcessingConfig {
+                    max_actions_per_sync: Some(*max_actions),
+                    ..PegInProcessingConfig::default()
+                });
+        }
+        if let Some(interval) = sub_matches.get_one::<u64>("heartbeat_interval") {
+            self.client
+                .set_heartbeat_config(Some(HeartbeatConfig {
+                    interval: *interval,
+                    beacon: sub_matches.get_flag("heartbeat_beacon"),
+                }))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        }
+        if sub_matches.get_flag("event_log") {
+            self.client.enable_event_log();
+        }
+        if let Some(required_acknowledgments) =
+            sub_matches.get_one::<usize>("flush_acknowledgments")
+        {
+            self.client
+                .set_flush_coordination(Some(FlushCoordinationConfig {
+                    required_acknowledgments: *required_acknowledgments,
+                    ..FlushCoordinationConfig::default()
+                }));
+        }
+        self.configure_chunker(sub_matches)?;
+        self.configure_congestion(sub_matches);
+        self.configure_alerts(sub_matches);
+        // Every asserted proof would be inconclusive, so invalid ones could never be disproved
+        if self.client.is_verifier() && !self.client.has_zkproof_verifying_key() {
+            let err = "Verifiers need the zero-knowledge proof verifying key to check asserted proofs, set it with `keys --vk <KEY>`";
+            eprintln!("error: {err}");
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, err));
+        }
+
+        let mut scheduler = BlockScheduler::default();
+        if !sub_matches.get_flag("poll") {
+            let notifications_url = match sub_matches.get_one::<String>("notifications_url") {
+                Some(url) => url.clone(),
+                None => get_esplora_websocket_url(self.client.esplora.url()),
+            };
+            scheduler = scheduler.with_notifications(&notifications_url);
+        }
+        let max_runtime = sub_matches
+            .get_one::<u64>("max-runtime")
+            .map(|seconds| Duration::from_secs(*seconds));
+        let mut shutdown = Shutdown::listen(max_runtime)?;
+        let mut summary = RunSummary::default();
+        let reason = loop {
+            if let Some(reason) = shutdown.requested() {
+                break reason;
+            }
+            scheduler.track_addresses(self.client.tracked_addresses());
+            // Only waiting is interrupted, an iteration that started always completes
+            let event = tokio::select! {
+                event = scheduler.next_event(&self.client.esplora) => event,
+                _ = self.client.disprove_precomputed() => SchedulerEvent::ProofEvaluated,
+                reason = shutdown.wait() => break reason,
+            };
+            summary.iterations += 1;
+
+            let old_data = self.client.data().clone();
+            self.client.sync().await;
+            self.client.publish_heartbeat_if_due().await;
+
+            // Between blocks nothing can change on chain, so only re-evaluate if other
+            // participants changed the data store
+            if event == SchedulerEvent::DataStoreSyncDue && self.client.data() == &old_data {
+                continue;
+            }
+
+            self.client.process_peg_ins().await;
+            self.client.process_peg_outs().await;
+            self.client.alert_graph_transitions().await;
+
+            // A bit inefficient, but fine for now: only flush if data changed. A failed flush is
+            // retried on the next change or on shutdown.
+            if self.client.data() != &old_data || self.client.has_unsaved_changes() {
+                self.client.flush().await;
+                summary.flushes += 1;
+            }
+
+            if let SchedulerEvent::NewBlock(_) = event {
+                summary.blocks += 1;
+            }
+        };
+
+        if self.client.has_unsaved_changes() {
+            println!("Retrying the failed flush before exiting...");
+            self.client.flush().await;
+            summary.flushes += 1;
+        }
+        let pending_staged_flushes = |client: &BitVMClient| {
+            client
+                .staged_flushes()
+                .iter()
+                .filter(|staged_flush| staged_flush.is_pending())
+                .count()
+        };
+        let grace_period_end = Instant::now() + STAGED_FLUSH_GRACE_PERIOD;
+        while pending_staged_flushes(&self.client) > 0 && Instant::now() < grace_period_end {
+            println!(
+                "Waiting for the committee to approve {} staged flushes...",
+                pending_staged_flushes(&self.client)
+            );
+            sleep(STAGED_FLUSH_POLL_INTERVAL).await;
+            self.client.sync().await;
+        }
+        summary.print(
+            reason,
+            shutdown.uptime(),
+            self.client.has_unsaved_changes(),
+            pending_staged_flushes(&self.client),
+        );
+
+        Ok(())
+    }
+
+    pub fn get_watch_command() -> Command {
+        Command::new("watch")
+            .about("Watchtower mode: On every new block, disprove the chain or the proof committed in the peg-out graphs")
+            .after_help("Reacts to the kick-off 2 and assert transactions operators broadcast. Once kick-off 2 confirms, the blocks since the committed start time are scanned for a superblock heavier than the committed one, and disprove chain is broadcast with it before take 1 or assert initial can spend its input. Once the assert transactions confirm, the asserted proof is evaluated and disproved if it is invalid. The commitments are read from the chain, so operators that do not share their graph data are watched too. Signing and timeouts are left to automatic mode.")
+            .arg(
+                arg!(--notifications_url <URL> "Websocket pushing new blocks and graph address activity, derived from the esplora url by default. The tip is polled while it cannot be reached.")
+                    .required(false),
+            )
+            .arg(
+                arg!(--poll "Only poll the chain tip, do not connect to the esplora websocket")
+                    .required(false)
+                    .conflicts_with("notifications_url"),
+            )
+            .args(Self::chunker_args())
+            .args(Self::alert_args())
+            .arg(
+                arg!(--"max-runtime" <SECONDS> "Shut down gracefully after running this long, e.g. to be restarted by a supervisor")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64).range(1..)),
+            )
+    }
+
+    pub async fn handle_watch_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        // Disprove transactions pay the reward to the verifier, and proofs are checked against
+        // the verifying key
+        if !self.client.is_verifier() || !self.client.has_zkproof_verifying_key() {
+            let err = "Watch mode needs a verifier key and the zero-knowledge proof verifying key, set them with `keys --verifier <KEY> --vk <KEY>`";
+            eprintln!("error: {err}");
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, err));
+        }
+        self.configure_chunker(sub_matches)?;
+        self.configure_alerts(sub_matches);
+
+        let mut scheduler = BlockScheduler::default();
+        if !sub_matches.get_flag("poll") {
+            let notifications_url = match sub_matches.get_one::<String>("notifications_url") {
+                Some(url) => url.clone(),
+                None => get_esplora_websocket_url(self.client.esplora.url()),
+            };
+            scheduler = scheduler.with_notifications(&notifications_url);
+        }
+        let max_runtime = sub_matches
+            .get_one::<u64>("max-runtime")
+            .map(|seconds| Duration::from_secs(*seconds));
+        let mut shutdown = Shutdown::listen(max_runtime)?;
+        let mut summary = RunSummary::default();
+        let reason = loop {
+            if let Some(reason) = shutdown.requested() {
+                break reason;
+            }
+            scheduler.track_addresses(self.client.tracked_addresses());
+            let event = tokio::select! {
+                event = scheduler.next_event(&self.client.esplora) => event,
+                _ = self.client.disprove_precomputed() => SchedulerEvent::ProofEvaluated,
+                reason = shutdown.wait() => break reason,
+            };
+            summary.iterations += 1;
+            if let SchedulerEvent::NewBlock(_) = event {
+                summary.blocks += 1;
+            }
+
+            let old_data = self.client.data().clone();
+            self.client.sync().await;
+            self.client.watch_peg_outs().await;
+            self.client.alert_graph_transitions().await;
+
+            // Commitment witnesses read from the chain are shared with the other verifiers
+            if self.client.data() != &old_data || self.client.has_unsaved_changes() {
+                self.client.flush().await;
+                summary.flushes += 1;
+            }
+        };
+
+        if self.client.has_unsaved_changes() {
+            println!("Retrying the failed flush before exiting...");
+            self.client.flush().await;
+            summary.flushes += 1;
+        }
+        summary.print(
+            reason,
+            shutdown.uptime(),
+            self.client.has_unsaved_changes(),
+            0,
+        );
+
+        Ok(())
+    }
+
+    // Verifiers watching peg-outs may have to execute the disprove scripts of an asserted proof at
+    // any time, so automatic mode refuses to start on a machine below the minimum spec
+    fn configure_chunker(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let current = chunker_config();
+        let chunker = ChunkerConfig {
+            parallelism: sub_matches
+                .get_one::<usize>("chunker_parallelism")
+                .copied()
+                .unwrap_or(current.parallelism),
+            max_in_flight_segments: sub_matches
+                .get_one::<usize>("chunker_max_in_flight")
+                .copied()
+                .unwrap_or(current.max_in_flight_segments),
+            memory_budget_bytes: sub_matches
+                .get_one::<u64>("chunker_memory_budget")
+                .map(|mib| mib * 1024 * 1024)
+                .or(current.memory_budget_bytes),
+            spill_directory: current.spill_directory,
+        };
+        if !self.client.is_verifier() {
+            set_chunker_config(chunker);
+            return Ok(());
+        }
+
+        let cpus = std::thread::available_parallelism().map_or(1, |cpus| cpus.get());
+        let result = match total_memory_bytes() {
+            Some(total_memory_bytes) => chunker.check_resources(total_memory_bytes, cpus),
+            None => {
+                eprintln!("warning: Could not determine the memory of this machine, skipping the chunker resource check.");
+                chunker.check_resources(u64::MAX, cpus)
+            }
+        };
+        if let Err(err) = result {
+            eprintln!("error: {err}");
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, err));
+        }
+        set_chunker_config(chunker);
+
+        Ok(())
+    }
+
+    // Flags override the `[congestion]` table, deferring stays off unless a threshold is set
+    fn configure_congestion(&mut self, sub_matches: &ArgMatches) {
+        let mut congestion = self.config.congestion;
+        if let Some(max_fee_rate) = sub_matches.get_one::<u64>("defer_above_fee_rate") {
+            congestion = Some(CongestionConfig {
+                max_fee_rate: *max_fee_rate,
+                ..congestion.unwrap_or(CongestionConfig::new(*max_fee_rate))
+            });
+        }
+        if let Some(max_deferral_blocks) = sub_matches.get_one::<u32>("max_deferral_blocks") {
+            match congestion.as_mut() {
+                Some(congestion) => congestion.max_deferral_blocks = *max_deferral_blocks,
+                None => eprintln!("warning: --max_deferral_blocks has no effect without a fee rate threshold, set one with --defer_above_fee_rate"),
+            }
+        }
+        self.client.set_congestion_config(congestion);
+    }
+
+    // Hooks given as flags are added to the ones of the `[alerts]` table
+    fn configure_alerts(&mut self, sub_matches: &ArgMatches) {
+        let mut alerts = self.config.alerts.clone().unwrap_or_default();
+        if let Some(webhooks) = sub_matches.get_many::<String>("alert_webhook") {
+            alerts.webhooks.extend(webhooks.cloned());
+        }
+        if let Some(commands) = sub_matches.get_many::<String>("alert_command") {
+            alerts.commands.extend(commands.cloned());
+        }
+        if let Some(blocks) = sub_matches.get_one::<u32>("take_window_alert_blocks") {
+            alerts.take_window_blocks = *blocks;
+        }
+        if sub_matches.get_flag("alert_graph_transitions") {
+            alerts.graph_transitions = true;
+        }
+        if !alerts.has_hooks() && sub_matches.contains_id("take_window_alert_blocks") {
+            eprintln!("warning: --take_window_alert_blocks has no effect without an alert hook, add one with --alert_webhook or --alert_command");
+        }
+        if !alerts.has_hooks() && sub_matches.get_flag("alert_graph_transitions") {
+            eprintln!("warning: --alert_graph_transitions has no effect without an alert hook, add one with --alert_webhook or --alert_command");
+        }
+        self.client.set_alert_config(Some(alerts));
+    }
+
+    // TODO: there are verifier's commands missing here
+    pub fn get_broadcast_command() -> Command {
+        Command::new("broadcast")
+            .short_flag('b')
+            .about("Broadcast transactions")
+            .after_help("Broadcast transactions.")
+            .arg(
+                arg!(--diagnose "If a transaction is rejected because of a failing script, re-execute its input scripts locally and show the failing input, opcode and stack")
+                    .required(false)
+                    .global(true),
+            )
+            .arg(
+                arg!(--"scrub-witnesses" "Canonicalize the witnesses of the transaction before broadcasting it, so that what confirms can be checked against what was broadcast")
+                    .required(false)
+                    .global(true),
+            )
+            .subcommand(
+                Command::new("pegin")
                     .about("Broadcast peg-in transactions")
-                    .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-in graph ID").required(true))
+                    .arg(
+                        arg!(-g --graph_id <GRAPH_ID> "Peg-in graph ID")
+                            .required(true)
+                            .value_parser(parse_peg_in_graph_id),
+                    )
                     .subcommand(Command::new("deposit").about("Broadcast peg-in deposit"))
                     .subcommand(Command::new("refund").about("Broadcast peg-in refund"))
                     .subcommand(Command::new("confirm").about("Broadcast peg-in confirm"))
@@ -418,9 +1896,25 @@ impl ClientCommand {
             .subcommand(
                 Command::new("tx")
                     .about("Broadcast transactions")
-                    .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-out graph ID").required(true))
+                    .arg(
+                        arg!(-g --graph_id <GRAPH_ID> "Peg-out graph ID")
+                            .required(true)
+                            .value_parser(parse_peg_out_graph_id),
+                    )
                     .arg(arg!(-u --utxo <UTXO> "Specify the utxo to spend from. Format: <TXID>:<VOUT>").required(false))
-                    .arg(arg!(-a --address <ADDRESS> "Specify the reward address to receive BTC reward").required(false))
+                    .arg(arg!(-a --address <ADDRESS> "Specify the reward address, descriptor or script pubkey to receive BTC reward. Defaults to the verifier reward destination").required(false))
+                    .arg(
+                        arg!(--"rbf-replace" <TXID> "Replace a broadcast, unconfirmed transaction of the graph with one paying --fee-rate. Only a challenge funded by this client can be replaced")
+                            .required(false)
+                            .value_parser(parse_txid)
+                            .requires("fee-rate"),
+                    )
+                    .arg(
+                        arg!(--"fee-rate" <SAT_PER_VB> "Fee rate of the replacement transaction")
+                            .required(false)
+                            .value_parser(clap::value_parser!(u64).range(MIN_RELAY_FEE_RATE..))
+                            .requires("rbf-replace"),
+                    )
                     .subcommand(Command::new("peg_out").about("Broadcast peg-out"))
                     .subcommand(Command::new("peg_out_confirm").about("Broadcast peg-out confirm"))
                     .subcommand(Command::new("kick_off_1").about("Broadcast kick off 1"))
@@ -428,7 +1922,12 @@ impl ClientCommand {
                     .subcommand(Command::new("start_time").about("Broadcast start time"))
                     .subcommand(Command::new("assert_initial").about("Broadcast assert initial"))
                     .subcommand(
-                        Command::new("assert_commits").about("Broadcast assert commitments"),
+                        Command::new("assert_commits")
+                            .about("Broadcast assert commitments")
+                            .after_help("Without --proof, commits to a proof generated by the client. A proof file from an external prover holds the hex encoded compressed Groth16 proof and public inputs as json: {\"proof\": \"...\", \"public_inputs\": [\"...\"]}. It is only committed to if it verifies against the verifying key and its public inputs are the ones derived from the peg-in confirm, kick-off 1 and peg-out txids, the amount and the withdrawer's EVM address of the graph.")
+                            .arg(arg!(--proof <FILE> "Commit to the proof of an external prover saved in this file").required(false))
+                            .arg(arg!(--missing "Only broadcast the assert commits that are not confirmed yet, signed ones as they were").required(false))
+                            .arg(Self::background_arg().conflicts_with("missing")),
                     )
                     .subcommand(
                         Command::new("assert_commit_1").about("Broadcast assert commit 1"),
@@ -446,32 +1945,114 @@ impl ClientCommand {
                     .subcommand(Command::new("take_1").about("Broadcast take 1"))
                     .subcommand(Command::new("take_2").about("Broadcast take 2"))
                     .subcommand(Command::new("disprove").about("Broadcast disprove"))
-                    .subcommand_required(true),
+                    // either a transaction to broadcast or --rbf-replace
+                    .subcommand_required(false),
             )
             .subcommand_required(true)
     }
 
-    pub async fn handle_broadcast_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+    // Errors are returned rather than printed, so that the exit code tells automation whether to
+    // retry, see `Error::is_retryable`
+    pub async fn handle_broadcast_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> Result<(), Error> {
+        self.client
+            .set_script_diagnostics(sub_matches.get_flag("diagnose"));
+        self.client
+            .set_witness_scrubbing(sub_matches.get_flag("scrub-witnesses"));
         self.client.sync().await;
 
         let subcommand = sub_matches.subcommand();
-        let graph_id = subcommand.unwrap().1.get_one::<String>("graph_id").unwrap();
+        if let Some(("pegin", pegin_matches)) = subcommand {
+            let graph_id = pegin_matches.get_one::<PegInGraphId>("graph_id").unwrap();
+            match pegin_matches.subcommand() {
+                Some(("deposit", _)) => self.client.broadcast_peg_in_deposit(graph_id).await?,
+                Some(("refund", _)) => self.client.broadcast_peg_in_refund(graph_id).await?,
+                Some(("confirm", _)) => self.client.broadcast_peg_in_confirm(graph_id).await?,
+                _ => unreachable!(),
+            };
+            return Ok(());
+        }
+        let graph_id = subcommand
+            .unwrap()
+            .1
+            .get_one::<PegOutGraphId>("graph_id")
+            .unwrap();
+
+        if let Some(replaced_txid) = subcommand.unwrap().1.get_one::<Txid>("rbf-replace") {
+            let fee_rate = *subcommand.unwrap().1.get_one::<u64>("fee-rate").unwrap();
+            let result = self
+                .client
+                .bump_fee(graph_id, *replaced_txid, fee_rate)
+                .await;
+            self.client.flush().await;
+            let txid = result?;
+            println!("Replaced {replaced_txid} with {txid} at {fee_rate} sat/vB");
+            return Ok(());
+        }
 
         match subcommand.unwrap().1.subcommand() {
-            Some(("assert_commits", _)) => {
-                let result = self
-                    .client
-                    .broadcast_assert_commits(graph_id, &get_proof())
-                    .await;
-                if let Err(e) = result {
-                    println!("Failed to broadcast transaction: {e}");
+            Some(("assert_commits", assert_matches)) => {
+                let proof = match assert_matches.get_one::<String>("proof") {
+                    Some(path) => {
+                        let contents = std::fs::read_to_string(path).map_err(|e| {
+                            Error::Other(format!("Failed to read the proof in {path}: {e}"))
+                        })?;
+                        let external_proof =
+                            try_deserialize::<ExternalProof>(&contents).map_err(|e| {
+                                Error::Validation(ValidationError::InvalidExternalProof(e))
+                            })?;
+                        self.client.load_external_proof(graph_id, &external_proof)?
+                    }
+                    None => get_proof(),
+                };
+                if self.run_in_background(assert_matches)? {
+                    let job_id = self.client.queue_assert_commits(graph_id, proof)?;
+                    println!("Signing the assert commits in background job {job_id}, they are broadcast once signed");
+                } else if assert_matches.get_flag("missing") {
+                    for progress in self.client.assert_commit_progress(graph_id).await? {
+                        match progress.confirmed_height {
+                            Some(height) => println!(
+                                "{} {} confirmed at block {height}",
+                                progress.commit, progress.txid
+                            ),
+                            None => {
+                                println!("{} {} is not confirmed", progress.commit, progress.txid)
+                            }
+                        }
+                    }
+                    let result = self
+                        .client
+                        .broadcast_missing_assert_commits(graph_id, &proof)
+                        .await;
+                    self.client.flush().await;
+                    if result?.is_empty() {
+                        println!("Both assert commits are confirmed");
+                    }
+                } else {
+                    self.client
+                        .broadcast_assert_commits(graph_id, &proof)
+                        .await?;
                 }
             }
             Some((others, _)) => {
+                // Report everything a pre-signed tx still misses before trying to finalize it
+                let presigned_transaction = match others {
+                    "assert_initial" => Some(PegOutPresignedTransaction::AssertInitial),
+                    "assert_final" => Some(PegOutPresignedTransaction::AssertFinal),
+                    "take_1" => Some(PegOutPresignedTransaction::Take1),
+                    "take_2" => Some(PegOutPresignedTransaction::Take2),
+                    "disprove" => Some(PegOutPresignedTransaction::Disprove),
+                    _ => None,
+                };
+                if let Some(transaction) = presigned_transaction {
+                    self.client
+                        .ready_to_broadcast(graph_id, transaction)
+                        .await?;
+                }
+
                 let result = match others {
-                    "deposit" => self.client.broadcast_peg_in_deposit(graph_id).await,
-                    "refund" => self.client.broadcast_peg_in_refund(graph_id).await,
-                    "confirm" => self.client.broadcast_peg_in_confirm(graph_id).await,
                     "peg_out" => {
                         let input = self
                             .get_funding_utxo_input(subcommand.unwrap().1.get_one::<String>("utxo"))
@@ -485,6 +2066,16 @@ impl ClientCommand {
                     "kick_off_2" => self.client.broadcast_kick_off_2(graph_id).await,
                     "start_time" => self.client.broadcast_start_time(graph_id).await,
                     "assert_initial" => self.client.broadcast_assert_initial(graph_id).await,
+                    "assert_commit_1" => {
+                        self.client
+                            .broadcast_assert_commit_1(graph_id, &get_proof())
+                            .await
+                    }
+                    "assert_commit_2" => {
+                        self.client
+                            .broadcast_assert_commit_2(graph_id, &get_proof())
+                            .await
+                    }
                     "assert_commit_1_invalid" => {
                         self.client
                             .broadcast_assert_commit_1(graph_id, &invalidate_proof(&get_proof()))
@@ -499,26 +2090,163 @@ impl ClientCommand {
                     "take_1" => self.client.broadcast_take_1(graph_id).await,
                     "take_2" => self.client.broadcast_take_2(graph_id).await,
                     "disprove" => {
-                        let address = subcommand.unwrap().1.get_one::<String>("address").unwrap();
-                        let reward_address = Address::from_str(address).unwrap();
-                        let reward_script = reward_address.assume_checked().script_pubkey(); // TODO: verify checked/unchecked address
-
+                        let reward_script = match subcommand.unwrap().1.get_one::<String>("address")
+                        {
+                            Some(destination) => {
+                                parse_reward_destination(self.client.source_network, destination)
+                                    .map_err(Error::Validation)?
+                            }
+                            None => self.client.verifier_reward_script().cloned().ok_or(
+                                Error::Other(String::from(
+                                    "Specify a reward address or a verifier key to reward",
+                                )),
+                            )?,
+                        };
                         self.client
                             .broadcast_disprove(graph_id, reward_script)
                             .await
                     }
                     &_ => unreachable!(),
                 };
-                if let Err(e) = result {
-                    println!("Failed to broadcast transaction: {e}");
+                match result {
+                    Err(Error::Chunker(e)) if !e.is_actionable() => {
+                        println!("Nothing to disprove: {e:?}");
+                    }
+                    Err(e) => return Err(e),
+                    Ok(_) => {}
+                }
+            }
+            None => {
+                println!("Specify a transaction to broadcast, or one to replace with --rbf-replace")
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_approve_command() -> Command {
+        Command::new("approve")
+            .about("Approve the broadcast of a graph transaction")
+            .after_help("Publishes your signed approval of the broadcast of a transaction of the graph to the data store. Deployments whose broadcast policy requires approvals, e.g. of two people before take_1 on mainnet, refuse to broadcast the transaction until enough approvers ran this command.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+            .arg(arg!(--tx <TX_NAME> "Transaction name, e.g. take_1 or Take1").required(true))
+    }
+
+    pub async fn handle_approve_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
+        let transaction = sub_matches.get_one::<String>("tx").unwrap();
+
+        self.client.sync().await;
+        match self.client.approve_broadcast(graph_id, transaction).await {
+            Ok(txid) => println!("Approved the broadcast of {transaction} {txid}"),
+            Err(e) => println!("Failed to approve the broadcast: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_cpfp_command() -> Command {
+        Command::new("cpfp")
+            .about("Speed up a stuck graph transaction with a child paying for it")
+            .after_help("Pre-signed graph transactions cannot be replaced with a higher fee. Instead, a child transaction spends one of their outputs locked to your key and pays enough for both to reach the fee rate. If the output cannot pay the fee alone, confirmed outputs at your address are added.")
+            .arg(
+                arg!(--txid <TXID> "The unconfirmed graph transaction to speed up")
+                    .required(true)
+                    .value_parser(parse_txid),
+            )
+            .arg(
+                arg!(--vout <VOUT> "Its output to spend, locked to your key")
+                    .required(true)
+                    .value_parser(clap::value_parser!(u32)),
+            )
+            .arg(
+                arg!(--"fee-rate" <SAT_PER_VB> "Fee rate the transaction and its child pay together")
+                    .required(true)
+                    .value_parser(clap::value_parser!(u64).range(MIN_RELAY_FEE_RATE..)),
+            )
+    }
+
+    pub async fn handle_cpfp_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let txid = *sub_matches.get_one::<Txid>("txid").unwrap();
+        let vout = *sub_matches.get_one::<u32>("vout").unwrap();
+        let fee_rate = *sub_matches.get_one::<u64>("fee-rate").unwrap();
+
+        self.client.sync().await;
+        match self.client.cpfp(txid, vout, fee_rate).await {
+            Ok(child_txid) => {
+                println!("Child {child_txid} brings {txid} up to {fee_rate} sat/vB")
+            }
+            Err(e) => println!("Failed to broadcast child transaction: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_explain_command() -> Command {
+        Command::new("explain")
+            .about("Explain what a bridge transaction does")
+            .after_help("Shows what a transaction of the peg-in and peg-out graphs does, who can broadcast it, which transactions have to confirm first, its timelock and what happens once it confirms. Without a transaction name, every transaction is shown. Timelocks are those of graphs created from the template on the current network.")
+            .arg(arg!([TX_NAME] "Transaction name, e.g. kick_off_2 or KickOff2").required(false))
+            .arg(arg!(--template <TEMPLATE> "Graph template (standard-mainnet, fast-regtest, high-security)")
+                .required(false)
+                .default_value("standard-mainnet")
+                .value_parser(clap::value_parser!(GraphTemplate)))
+    }
+
+    pub fn handle_explain_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let template = sub_matches.get_one::<GraphTemplate>("template").unwrap();
+        let descriptions = describe(&template.parameters(), self.client.source_network);
+
+        match sub_matches.get_one::<String>("TX_NAME") {
+            Some(name) => match descriptions
+                .iter()
+                .find(|description| description.matches(name))
+            {
+                Some(description) => println!("{description}"),
+                None => println!(
+                    "Unknown transaction {name}, one of: {}",
+                    descriptions
+                        .iter()
+                        .map(|description| description.name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            None => {
+                for description in descriptions {
+                    println!("{description}\n");
                 }
             }
-            _ => unreachable!(),
         }
 
         Ok(())
     }
 
+    pub fn get_script_budget_command() -> Command {
+        Command::new("script-budget")
+            .about("Check the sizes of the connector C lock scripts and assert commit witnesses")
+            .after_help("Measures the connector C lock scripts and the assert commit witnesses of a graph built from fixed keys, and compares them to the budgets the relay fees of the assert and disprove transactions were estimated for. Exits with an error if any size is over its budget. Generates the lock scripts unless they are cached, which takes minutes.")
+    }
+
+    // Does not need the client, so it runs without keys, e.g. in CI
+    pub fn handle_script_budget_command() -> Result<(), Error> {
+        let report = script_budget_report();
+        println!("{report}");
+
+        let exceeded = report.exceeded();
+        match exceeded.is_empty() {
+            true => Ok(()),
+            false => Err(Error::Other(format!(
+                "Over budget: {}",
+                exceeded
+                    .iter()
+                    .map(|entry| entry.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
     pub fn get_status_command() -> Command {
         Command::new("status")
             .short_flag('s')
@@ -532,6 +2260,144 @@ impl ClientCommand {
         Ok(())
     }
 
+    pub fn get_track_command() -> Command {
+        Command::new("track")
+            .about("Track the progress of a withdrawal")
+            .after_help("Finds the peg-outs requested by a withdrawer and shows whether the operator broadcast the peg-out transaction, how many confirmations it has and how far the operator got claiming its reimbursement. With --watch, the withdrawals are checked on every new block and only changes are printed.")
+            .arg(arg!(-a --address <ADDRESS> "Specify the withdrawer destination chain address, e.g. an EVM address, or the destination address").required(true))
+            .arg(arg!(-w --watch "Keep checking on every new block").required(false))
+    }
+
+    pub async fn handle_track_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let address = sub_matches.get_one::<String>("address").unwrap();
+        let watch = sub_matches.get_flag("watch");
+
+        let mut scheduler = BlockScheduler::default();
+        loop {
+            self.client.sync().await;
+            match self.client.track_withdrawal(address).await {
+                Ok(events) => {
+                    if events.is_empty() && !watch {
+                        println!("No peg-out found for {address}");
+                    }
+                    for event in events {
+                        println!("[WITHDRAWAL]: {}\n", event.progress);
+                    }
+                }
+                Err(err) => eprintln!("error: {err}"),
+            }
+
+            if !watch {
+                return Ok(());
+            }
+            while !matches!(
+                scheduler.next_event(&self.client.esplora).await,
+                SchedulerEvent::NewBlock(_)
+            ) {}
+        }
+    }
+
+    pub fn get_list_graphs_command() -> Command {
+        Command::new("list-graphs")
+            .about("List peg-in and peg-out graphs with their progress")
+            .after_help("Lists the peg-in graphs, each followed by its peg-out graphs, with the stage they reached: pending-ceremony while the committee has not pre-signed them, then awaiting-deposit, deposit-confirmed, peg-in-confirmed or refunded for peg-in graphs and ready, paid-out, kicked-off, challenged, asserted, disproved, timed-out or taken for peg-out graphs. With --role, only the graphs of the depositor, operator or verifier key of the client are listed. With --json, the graphs are printed as a JSON array for scripts and dashboards.")
+            .arg(arg!(-r --role <ROLE> "Only list the graphs of this role (depositor, operator, verifier)")
+            .required(false)
+            .value_parser(clap::value_parser!(GraphRole)))
+            .arg(arg!(--state <STATE> "Only list the graphs in this stage, e.g. pending-ceremony or kicked-off")
+            .required(false)
+            .value_parser(clap::value_parser!(GraphStage)))
+            .arg(arg!(--json "Print the graphs as JSON").required(false))
+    }
+
+    pub async fn handle_list_graphs_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let filter = GraphListFilter {
+            role: sub_matches.get_one::<GraphRole>("role").copied(),
+            stage: sub_matches.get_one::<GraphStage>("state").copied(),
+        };
+
+        self.client.sync().await;
+        match self.client.list_graphs(&filter).await {
+            Ok(graphs) if sub_matches.get_flag("json") => {
+                println!("{}", serde_json::to_string_pretty(&graphs)?)
+            }
+            Ok(graphs) => {
+                if graphs.is_empty() {
+                    println!("No graphs found");
+                }
+                for graph in graphs {
+                    println!("{graph}\n");
+                }
+            }
+            Err(err) => eprintln!("error: {err}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_jobs_command() -> Command {
+        Command::new("jobs")
+            .about("Show the background jobs of interactive mode")
+            .after_help("Commands run with --background in interactive mode are queued as jobs and run on worker threads. A notification is printed once a job finished. Jobs are not kept once interactive mode exits.")
+            .subcommand(Command::new("list").about("List the jobs, oldest first"))
+            .subcommand(
+                Command::new("status")
+                    .about("Show the status of a job")
+                    .arg(
+                        arg!(<ID> "Job ID")
+                            .required(true)
+                            .value_parser(clap::value_parser!(JobId)),
+                    ),
+            )
+            .subcommand_required(true)
+    }
+
+    pub fn handle_jobs_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        match sub_matches.subcommand() {
+            Some(("list", _)) => {
+                let jobs = self.client.jobs().list();
+                if jobs.is_empty() {
+                    println!("No jobs");
+                }
+                for job in jobs {
+                    println!("{job}");
+                }
+            }
+            Some(("status", status_matches)) => {
+                let id = *status_matches.get_one::<JobId>("ID").unwrap();
+                match self.client.jobs().status(id) {
+                    Some(job) => println!("{job}"),
+                    None => println!("{}", format!("No job {id}").red()),
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    // Applies the finished jobs and notifies of them, on a line of their own if the prompt is shown
+    async fn report_finished_jobs(&mut self, at_prompt: bool) {
+        let finished = self.client.apply_finished_jobs().await;
+        if finished.is_empty() {
+            return;
+        }
+        self.client.flush().await;
+        if at_prompt {
+            println!();
+        }
+        for job in finished {
+            match job.state {
+                JobState::Failed(_) => println!("{}", job.to_string().red()),
+                _ => println!("{}", job.to_string().green()),
+            }
+        }
+        if at_prompt {
+            print!("{}", "bitvm >> ".bold());
+            io::stdout().flush().unwrap();
+        }
+    }
+
     pub fn get_interactive_command() -> Command {
         Command::new("interactive")
             .short_flag('i')
@@ -545,13 +2411,24 @@ impl ClientCommand {
                 .green()
         );
 
-        let mut stdin_reader = BufReader::new(tokio::io::stdin());
+        self.interactive = true;
+        let jobs = self.client.jobs().clone();
+        let mut stdin_lines = BufReader::new(tokio::io::stdin()).lines();
         loop {
+            self.report_finished_jobs(false).await;
             print!("{}", "bitvm >> ".bold());
             io::stdout().flush().unwrap(); // Ensure the prompt is printed out immediately
 
-            let mut line = String::new();
-            stdin_reader.read_line(&mut line).await.unwrap();
+            // Jobs finishing while waiting for input are reported right away
+            let line = loop {
+                tokio::select! {
+                    line = stdin_lines.next_line() => break line.unwrap(),
+                    _ = jobs.finished() => self.report_finished_jobs(true).await,
+                }
+            };
+            let Some(line) = line else {
+                break; // end of input
+            };
             let input = line.trim();
 
             if input == "exit" {
@@ -577,6 +2454,13 @@ impl ClientCommand {
                 let key_dir = matches.get_one::<String>("key-dir").cloned();
                 let keys_command = KeysCommand::new(key_dir);
                 keys_command.handle_command(sub_matches)?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("committee") {
+                let key_dir = matches.get_one::<String>("key-dir").cloned();
+                let verifiers = matches
+                    .get_many::<PublicKey>("verifiers")
+                    .map(|x| x.cloned().collect::<Vec<PublicKey>>());
+                let committee_command = CommitteeCommand::new(key_dir, verifiers);
+                committee_command.handle_command(sub_matches)?;
             } else if matches.subcommand_matches("get-operator-address").is_some() {
                 self.handle_get_operator_address().await?;
             } else if matches.subcommand_matches("get-operator-utxos").is_some() {
@@ -589,23 +2473,66 @@ impl ClientCommand {
             } else if matches.subcommand_matches("get-depositor-utxos").is_some() {
                 self.handle_get_depositor_utxos().await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("initiate-peg-in") {
-                self.handle_initiate_peg_in_command(sub_matches).await?;
+                if let Err(e) = self.handle_initiate_peg_in_command(sub_matches).await {
+                    println!("{}", format!("Failed to initiate peg-in: {e}").red());
+                }
             } else if let Some(sub_matches) = matches.subcommand_matches("create-peg-out") {
                 self.handle_create_peg_out_graph_command(sub_matches)
                     .await?;
+            } else if matches.subcommand_matches("wizard").is_some() {
+                self.handle_wizard_command().await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
                 self.handle_push_nonces_command(sub_matches).await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("push-signatures") {
                 self.handle_push_signature_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("resume-ceremony") {
+                self.handle_resume_ceremony_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("export-descriptors") {
+                self.handle_export_descriptors_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("export-signing-bundle") {
+                self.handle_export_signing_bundle_command(sub_matches)
+                    .await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("history") {
+                self.handle_history_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("graph") {
+                self.handle_graph_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("validate-graph") {
+                if let Err(e) = self.handle_validate_graph_command(sub_matches).await {
+                    println!("{}", e.to_string().red());
+                }
+            } else if let Some(sub_matches) = matches.subcommand_matches("cache") {
+                self.handle_cache_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("resign") {
+                self.handle_resign_command(sub_matches).await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
                 self.handle_mock_l2_pegout_event_command(sub_matches)
                     .await?;
             } else if matches.subcommand_matches("status").is_some() {
                 self.handle_status_command().await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("track") {
+                self.handle_track_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("list-graphs") {
+                self.handle_list_graphs_command(sub_matches).await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
-                self.handle_broadcast_command(sub_matches).await?;
-            } else if matches.subcommand_matches("automatic").is_some() {
-                self.handle_automatic_command().await?;
+                if let Err(e) = self.handle_broadcast_command(sub_matches).await {
+                    println!("{}", format!("Failed to broadcast transaction: {e}").red());
+                }
+            } else if let Some(sub_matches) = matches.subcommand_matches("approve") {
+                self.handle_approve_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("cpfp") {
+                self.handle_cpfp_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("explain") {
+                self.handle_explain_command(sub_matches)?;
+            } else if matches.subcommand_matches("script-budget").is_some() {
+                if let Err(e) = Self::handle_script_budget_command() {
+                    println!("{}", e.to_string().red());
+                }
+            } else if let Some(sub_matches) = matches.subcommand_matches("automatic") {
+                self.handle_automatic_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("watch") {
+                self.handle_watch_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("jobs") {
+                self.handle_jobs_command(sub_matches)?;
             } else if matches.subcommand_matches("interactive").is_some() {
                 println!("{}", "Already in interactive mode.".yellow());
             } else {
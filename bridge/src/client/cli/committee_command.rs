@@ -0,0 +1,166 @@
+use bitcoin::PublicKey;
+use clap::{arg, ArgMatches, Command};
+use std::io;
+
+use crate::contexts::base::generate_n_of_n_public_key;
+
+use super::key_command::{pubkey_of, Committee, Config, KeysCommand};
+use super::keystore::{KeyRole, PassphraseProvider};
+
+pub struct CommitteeCommand {
+    keys_command: KeysCommand,
+    verifiers: Option<Vec<PublicKey>>,
+}
+
+impl CommitteeCommand {
+    pub fn new(key_dir: Option<String>, verifiers: Option<Vec<PublicKey>>) -> Self {
+        CommitteeCommand {
+            keys_command: KeysCommand::new(key_dir),
+            verifiers,
+        }
+    }
+
+    pub fn get_command() -> Command {
+        Command::new("committee")
+            .about("Manage the verifier committee")
+            .after_help("Graphs are bound to the committee they were created with. After a verifier key rotation new graphs use the updated committee, while graphs of the previous committee keep being signed with the retired key. Run the client with the previous committee as --verifiers to process them.")
+            .subcommand(
+                Command::new("show")
+                    .about("Show the current and previous committees and the verifier key used with each"),
+            )
+            .subcommand(
+                Command::new("rotate-key")
+                    .about("Replace the verifier key in the committee used for new graphs")
+                    .arg(arg!(-v --verifier <SECRET_KEY> "New secret key for the verifier").required(true)),
+            )
+            .subcommand_required(true)
+    }
+
+    pub fn handle_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        match sub_matches.subcommand() {
+            Some(("show", _)) => self.handle_show_command(),
+            Some(("rotate-key", sub_matches)) => self.handle_rotate_key_command(sub_matches),
+            _ => unreachable!(),
+        }
+    }
+
+    // Verifiers given on the command line take precedence over the committee in the config file
+    fn current_committee(&self, config: &Config) -> Option<Vec<PublicKey>> {
+        self.verifiers.clone().or_else(|| {
+            config
+                .committee
+                .as_ref()
+                .map(|committee| committee.verifiers.clone())
+        })
+    }
+
+    fn handle_show_command(&self) -> io::Result<()> {
+        let config = self
+            .keys_command
+            .read_unlocked_config(&mut PassphraseProvider::new())?;
+        let Some(current) = self.current_committee(&config) else {
+            println!("No committee is configured.");
+            return Ok(());
+        };
+
+        print_committee("CURRENT COMMITTEE", &current, &config);
+        let previous = config
+            .committee
+            .as_ref()
+            .map(|committee| committee.previous.as_slice())
+            .unwrap_or_default();
+        for (index, committee) in previous.iter().enumerate().rev() {
+            print_committee(&format!("PREVIOUS COMMITTEE {index}"), committee, &config);
+        }
+
+        Ok(())
+    }
+
+    fn handle_rotate_key_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let mut passphrases = PassphraseProvider::new();
+        let mut config = self.keys_command.read_unlocked_config(&mut passphrases)?;
+
+        let new_secret = sub_matches.get_one::<String>("verifier").unwrap();
+        if !self.keys_command.validate_key(new_secret) {
+            eprintln!("error: Invalid verifier secret key.");
+            return Ok(());
+        }
+        let Some(old_secret) = config.keys.verifier.clone() else {
+            eprintln!("error: No verifier key to rotate, set one with `keys --verifier` instead.");
+            return Ok(());
+        };
+        let Some(committee) = self.current_committee(&config) else {
+            eprintln!("error: Verifier public keys must be specified either in command line, environment variable or config file.");
+            return Ok(());
+        };
+
+        let old_public_key = pubkey_of(&old_secret);
+        let new_public_key = pubkey_of(new_secret);
+        let Some(position) = committee.iter().position(|key| *key == old_public_key) else {
+            eprintln!("error: Verifier key {old_public_key} is not a member of the committee.");
+            return Ok(());
+        };
+        let previous_committees = config
+            .committee
+            .take()
+            .map(|committee| committee.previous)
+            .unwrap_or_default();
+        if committee.contains(&new_public_key)
+            || previous_committees
+                .iter()
+                .any(|previous| previous.contains(&new_public_key))
+        {
+            eprintln!("error: Verifier key {new_public_key} is already used by a committee.");
+            return Ok(());
+        }
+
+        let mut new_committee = committee.clone();
+        new_committee[position] = new_public_key;
+        let mut previous = previous_committees;
+        previous.push(committee.clone());
+        config.committee = Some(Committee {
+            verifiers: new_committee.clone(),
+            previous,
+        });
+        // The new key is encrypted with the passphrase of the retired one
+        let encrypt = config.keystore.verifier.is_some();
+        config.retire_verifier_secret();
+        config.set_secret(KeyRole::Verifier, new_secret, encrypt, &mut passphrases)?;
+        self.keys_command.write_config(&config)?;
+
+        println!("Verifier key rotated from {old_public_key} to {new_public_key}.");
+        println!(
+            "New graphs use the committee: {}",
+            join_keys(&new_committee)
+        );
+        println!("Share the new public key with the other committee members, depositors and operators. The new committee is used unless --verifiers or VERIFIERS are set.");
+        println!(
+            "Graphs created before the rotation keep using {old_public_key}. Process them with --verifiers {}",
+            join_keys(&committee)
+        );
+
+        Ok(())
+    }
+}
+
+fn print_committee(name: &str, committee: &[PublicKey], config: &Config) {
+    println!("[{name}]:");
+    println!(
+        "  Aggregated key: {}",
+        generate_n_of_n_public_key(committee).0
+    );
+    println!("       Verifiers: {}", join_keys(committee));
+    match config.verifier_secret_for(committee) {
+        Ok(Some(secret)) => println!("    Our verifier: {}", pubkey_of(secret)),
+        Ok(None) => println!("    Our verifier: not a member"),
+        Err(err) => println!("    Our verifier: {err}"),
+    }
+    println!();
+}
+
+fn join_keys(keys: &[PublicKey]) -> String {
+    keys.iter()
+        .map(PublicKey::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -1,4 +1,5 @@
 use bitcoin::{Network, PublicKey};
+use bitvm::chunk::config::ChunkerConfig;
 use clap::{arg, ArgGroup, ArgMatches, Command};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -7,21 +8,254 @@ use std::fs::{self, OpenOptions};
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use toml;
+use zeroize::{Zeroize, Zeroizing};
 
+use crate::client::{
+    alerts::AlertConfig, broadcast_middleware::BroadcastPolicyConfig, congestion::CongestionConfig,
+    esplora::FeeConfig, light_client::HeaderVerificationConfig, memory_cache::CacheConfig,
+};
 use crate::contexts::base::generate_keys_from_secret;
+use crate::rewards::RewardConfig;
 
-#[derive(Serialize, Deserialize, Default)]
+use super::keystore::{EncryptedSecret, KeyRole, Keystore, PassphraseProvider, PassphrasePurpose};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Config {
     pub keys: Keys,
+    // Encrypted secret keys, see `keys --encrypt`. Decrypted into `keys` when the config is unlocked.
+    #[serde(default, skip_serializing_if = "Keystore::is_empty")]
+    pub keystore: Keystore,
+    // Used when the verifiers are not given on the command line, see `committee rotate-key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committee: Option<Committee>,
+    // How the confirmations reported by esplora are checked before take 1 and peg-out fronting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_verification: Option<HeaderVerificationConfig>,
+    // Byte capacities of the in-memory caches, see `cache stats`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheConfig>,
+    // Where rewards are paid to instead of the role keys, e.g. a multisig or custody address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewards: Option<RewardConfig>,
+    // Parallelism and memory budget of disprove script execution, see `automatic`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunker: Option<ChunkerConfig>,
+    // Fee rate of the graphs this client creates, see `--fee-rate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fees: Option<FeeConfig>,
+    // When automatic mode defers the operator's non-urgent broadcasts, see `--defer_above_fee_rate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub congestion: Option<CongestionConfig>,
+    // Webhooks and commands automatic mode alerts on critical events, see `--alert_webhook`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<AlertConfig>,
+    // Checks every broadcast of a graph transaction has to pass, e.g. approvals, see `approve`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broadcast_policy: Option<BroadcastPolicyConfig>,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Keys {
     pub depositor: Option<String>,
     pub operator: Option<String>,
     pub verifier: Option<String>,
     pub withdrawer: Option<String>,
     pub verifying_key: Option<String>,
+    // Verifier keys replaced by a key rotation, still needed for the graphs of earlier committees
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retired_verifiers: Vec<String>,
+}
+
+impl Keys {
+    pub fn secret(&self, role: KeyRole) -> Option<&String> {
+        match role {
+            KeyRole::Depositor => self.depositor.as_ref(),
+            KeyRole::Operator => self.operator.as_ref(),
+            KeyRole::Verifier => self.verifier.as_ref(),
+            KeyRole::Withdrawer => self.withdrawer.as_ref(),
+        }
+    }
+
+    fn secret_mut(&mut self, role: KeyRole) -> &mut Option<String> {
+        match role {
+            KeyRole::Depositor => &mut self.depositor,
+            KeyRole::Operator => &mut self.operator,
+            KeyRole::Verifier => &mut self.verifier,
+            KeyRole::Withdrawer => &mut self.withdrawer,
+        }
+    }
+
+    // Overwrites the secret keys in memory and removes them, e.g. once the contexts are created
+    pub fn zeroize_secrets(&mut self) {
+        for role in KeyRole::ALL {
+            self.secret_mut(role).zeroize();
+        }
+        self.retired_verifiers.zeroize();
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        self.zeroize_secrets();
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct Committee {
+    // Verifier public keys new graphs are created with
+    pub verifiers: Vec<PublicKey>,
+    // Committees before each key rotation, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous: Vec<Vec<PublicKey>>,
+}
+
+impl Config {
+    // Picks the verifier key that is a member of the given committee. After a key rotation, the
+    // graphs of the previous committee keep being signed with the retired key, while new graphs
+    // use the current one. A committee holding more than one of the keys would mix them.
+    pub fn verifier_secret_for(&self, committee: &[PublicKey]) -> Result<Option<&str>, String> {
+        let mut members = self
+            .keys
+            .verifier
+            .iter()
+            .chain(self.keys.retired_verifiers.iter())
+            .filter(|secret| committee.contains(&pubkey_of(secret)));
+
+        match (members.next(), members.next()) {
+            (Some(first), Some(second)) => Err(format!(
+                "Verifier keys {} and {} are both members of the committee. Each verifier can only take part with a single key",
+                pubkey_of(first),
+                pubkey_of(second)
+            )),
+            (member, _) => Ok(member.map(String::as_str)),
+        }
+    }
+
+    // Roles whose secret key is stored in plaintext
+    pub fn plaintext_roles(&self) -> Vec<KeyRole> {
+        KeyRole::ALL
+            .into_iter()
+            .filter(|role| {
+                self.keys
+                    .secret(*role)
+                    .is_some_and(|secret| !self.keystore.contains_public_key(&pubkey_of(secret)))
+                    || (*role == KeyRole::Verifier
+                        && self
+                            .keys
+                            .retired_verifiers
+                            .iter()
+                            .any(|secret| !self.keystore.contains_public_key(&pubkey_of(secret))))
+            })
+            .collect()
+    }
+
+    // Decrypts the encrypted secret keys into `keys`
+    pub fn unlock(&mut self, passphrases: &mut PassphraseProvider) -> io::Result<()> {
+        for role in KeyRole::ALL {
+            if let Some(encrypted) = self.keystore.get(role).cloned() {
+                let secret = decrypt_secret(&encrypted, role, passphrases)?;
+                *self.keys.secret_mut(role) = Some(secret);
+            }
+        }
+        for encrypted in self.keystore.retired_verifiers.clone() {
+            let secret = decrypt_secret(&encrypted, KeyRole::Verifier, passphrases)?;
+            if !self.keys.retired_verifiers.contains(&secret) {
+                self.keys.retired_verifiers.push(secret);
+            }
+        }
+        Ok(())
+    }
+
+    // Sets the secret key of a role, encrypted if the role already has an encrypted key or if
+    // `encrypt` is set
+    pub fn set_secret(
+        &mut self,
+        role: KeyRole,
+        secret: &str,
+        encrypt: bool,
+        passphrases: &mut PassphraseProvider,
+    ) -> io::Result<()> {
+        let encrypted = self.keystore.get_mut(role);
+        if encrypt || encrypted.is_some() {
+            *encrypted = Some(encrypt_secret(secret, role, passphrases)?);
+        }
+        *self.keys.secret_mut(role) = Some(secret.to_string());
+        Ok(())
+    }
+
+    // Encrypts the secret keys stored in plaintext
+    pub fn encrypt_plaintext_secrets(
+        &mut self,
+        passphrases: &mut PassphraseProvider,
+    ) -> io::Result<()> {
+        for role in self.plaintext_roles() {
+            if let Some(secret) = self.keys.secret(role).cloned() {
+                if !self.keystore.contains_public_key(&pubkey_of(&secret)) {
+                    *self.keystore.get_mut(role) =
+                        Some(encrypt_secret(&secret, role, passphrases)?);
+                }
+            }
+        }
+        for secret in self.keys.retired_verifiers.clone() {
+            if !self.keystore.contains_public_key(&pubkey_of(&secret)) {
+                let encrypted = encrypt_secret(&secret, KeyRole::Verifier, passphrases)?;
+                self.keystore.retired_verifiers.push(encrypted);
+            }
+        }
+        Ok(())
+    }
+
+    // Moves the current verifier key to the retired keys, see `committee rotate-key`
+    pub fn retire_verifier_secret(&mut self) {
+        if let Some(secret) = self.keys.verifier.take() {
+            self.keys.retired_verifiers.push(secret);
+        }
+        if let Some(encrypted) = self.keystore.verifier.take() {
+            self.keystore.retired_verifiers.push(encrypted);
+        }
+    }
+
+    // The config as written to bridge.toml, without the plaintext of the encrypted secret keys
+    fn without_encrypted_plaintext(&self) -> Config {
+        let mut config = self.clone();
+        let is_encrypted = |secret: &String| self.keystore.contains_public_key(&pubkey_of(secret));
+        for role in KeyRole::ALL {
+            let secret = config.keys.secret_mut(role);
+            if secret.as_ref().is_some_and(is_encrypted) {
+                *secret = None;
+            }
+        }
+        config
+            .keys
+            .retired_verifiers
+            .retain(|secret| !is_encrypted(secret));
+        config
+    }
+}
+
+fn decrypt_secret(
+    encrypted: &EncryptedSecret,
+    role: KeyRole,
+    passphrases: &mut PassphraseProvider,
+) -> io::Result<String> {
+    let passphrase = passphrases.passphrase_for(role, PassphrasePurpose::Unlock)?;
+    let secret = encrypted
+        .decrypt(role, &passphrase)
+        .map_err(|err| io::Error::new(io::ErrorKind::PermissionDenied, err))?;
+    passphrases.remember(role, passphrase);
+    Ok(secret)
+}
+
+fn encrypt_secret(
+    secret: &str,
+    role: KeyRole,
+    passphrases: &mut PassphraseProvider,
+) -> io::Result<EncryptedSecret> {
+    let passphrase = passphrases.passphrase_for(role, PassphrasePurpose::Encrypt)?;
+    let encrypted =
+        EncryptedSecret::encrypt(role, secret, &passphrase).map_err(io::Error::other)?;
+    passphrases.remember(role, passphrase);
+    Ok(encrypted)
 }
 
 const BRIDGE_KEY_DIR_NAME: &str = ".bitvm-bridge";
@@ -53,12 +287,15 @@ impl KeysCommand {
         Command::new("keys")
             .short_flag('k')
             .about("Manage secret keys for different contexts")
-            .after_help("The depositor, operator, verifier, and withdrawer contexts are optional and can be specified using the -d, -o, -v, and -w flags respectively. If a context is not specified, the current key configuration will be displayed. The verifying key for the zero-knowledge proof is optional and must be specified when running scenarios that involve proof verification.")
+            .after_help("The depositor, operator, verifier, and withdrawer contexts are optional and can be specified using the -d, -o, -v, and -w flags respectively. If a context is not specified, the current key configuration will be displayed. The verifying key for the zero-knowledge proof is optional and must be specified when running scenarios that involve proof verification. Secret keys can be stored encrypted with --encrypt. Their passphrases are read from BRIDGE_KEYSTORE_PASSPHRASE_<ROLE>, BRIDGE_KEYSTORE_PASSPHRASE or the file descriptor in BRIDGE_KEYSTORE_PASSPHRASE_FD, or prompted for.")
             .arg(arg!(-d --depositor <SECRET_KEY> "Secret key for depositor").required(false))
             .arg(arg!(-o --operator <SECRET_KEY> "Secret key for operator").required(false))
             .arg(arg!(-v --verifier <SECRET_KEY> "Secret key for verifier").required(false))
             .arg(arg!(-w --withdrawer <SECRET_KEY> "Secret key for withdrawer").required(false))
             .arg(arg!(-k --vk <KEY> "Zero-knowledge proof verifying key").required(false))
+            .arg(arg!(--encrypt "Encrypt the secret keys stored in plaintext, or the key set along with this flag").required(false))
+            .arg(arg!(--master "Encrypt all keys with a single master passphrase instead of one per role").required(false).requires("encrypt"))
+            .arg(arg!(--decrypt "Store the encrypted secret keys in plaintext again, intended for regtest").required(false).conflicts_with_all(["encrypt", "context", "vk"]))
             .group(ArgGroup::new("context")
                 .args(["depositor", "operator", "verifier", "withdrawer"]))
     }
@@ -76,7 +313,7 @@ impl KeysCommand {
                 ("VERIFYING KEY", &config.keys.verifying_key),
             ]);
 
-            if keys.values().any(|k| k.is_some()) {
+            if keys.values().any(|k| k.is_some()) || !config.keystore.is_empty() {
                 println!("Key configuration:");
                 println!();
 
@@ -89,6 +326,16 @@ impl KeysCommand {
                     }
                 };
 
+                // Encrypted keys are shown without asking for their passphrase
+                let print_encrypted_key = |encrypted: Option<&EncryptedSecret>, name: &str| {
+                    if let Some(encrypted) = encrypted {
+                        println!("[{name}]:");
+                        println!("  Private key: encrypted");
+                        println!("   Public key: {}", encrypted.public_key);
+                        println!();
+                    }
+                };
+
                 let print_verifying_key = |verifying_key: &Option<String>, name: &str| {
                     if let Some(vk) = verifying_key {
                         println!("[{name}]:");
@@ -99,12 +346,22 @@ impl KeysCommand {
 
                 let mut name = "DEPOSITOR";
                 print_user_key(keys.get(name).unwrap(), name);
+                print_encrypted_key(config.keystore.depositor.as_ref(), name);
                 name = "OPERATOR";
                 print_user_key(keys.get(name).unwrap(), name);
+                print_encrypted_key(config.keystore.operator.as_ref(), name);
                 name = "VERIFIER";
                 print_user_key(keys.get(name).unwrap(), name);
+                print_encrypted_key(config.keystore.verifier.as_ref(), name);
+                for retired_verifier in &config.keys.retired_verifiers {
+                    print_user_key(&Some(retired_verifier.clone()), "RETIRED VERIFIER");
+                }
+                for retired_verifier in &config.keystore.retired_verifiers {
+                    print_encrypted_key(Some(retired_verifier), "RETIRED VERIFIER");
+                }
                 name = "WITHDRAWER";
                 print_user_key(keys.get(name).unwrap(), name);
+                print_encrypted_key(config.keystore.withdrawer.as_ref(), name);
                 name = "VERIFYING KEY";
                 print_verifying_key(keys.get(name).unwrap(), name);
             } else {
@@ -112,11 +369,23 @@ impl KeysCommand {
                 println!();
             }
 
+            Ok(())
+        } else if sub_matches.get_flag("decrypt") {
+            config.unlock(&mut PassphraseProvider::new())?;
+            config.keystore = Keystore::default();
+            self.write_config(&config)?;
+            println!("Secret keys are stored in plaintext.");
             Ok(())
         } else {
+            let encrypt = sub_matches.get_flag("encrypt");
+            let mut passphrases = match sub_matches.get_flag("master") {
+                true => PassphraseProvider::new().master_for_all_roles(),
+                false => PassphraseProvider::new(),
+            };
+
             if let Some(secret_key) = sub_matches.get_one::<String>("depositor") {
                 if self.validate_key(secret_key) {
-                    config.keys.depositor = Some(secret_key.clone());
+                    config.set_secret(KeyRole::Depositor, secret_key, encrypt, &mut passphrases)?;
                     println!(
                         "Secret key for depositor {} saved successfully!",
                         pubkey_of(secret_key)
@@ -126,7 +395,7 @@ impl KeysCommand {
                 }
             } else if let Some(secret_key) = sub_matches.get_one::<String>("operator") {
                 if self.validate_key(secret_key) {
-                    config.keys.operator = Some(secret_key.clone());
+                    config.set_secret(KeyRole::Operator, secret_key, encrypt, &mut passphrases)?;
                     println!(
                         "Secret key for operator {} saved successfully!",
                         pubkey_of(secret_key)
@@ -136,7 +405,7 @@ impl KeysCommand {
                 }
             } else if let Some(secret_key) = sub_matches.get_one::<String>("verifier") {
                 if self.validate_key(secret_key) {
-                    config.keys.verifier = Some(secret_key.clone());
+                    config.set_secret(KeyRole::Verifier, secret_key, encrypt, &mut passphrases)?;
                     println!(
                         "Secret key for verifier {} saved successfully!",
                         pubkey_of(secret_key)
@@ -146,7 +415,12 @@ impl KeysCommand {
                 }
             } else if let Some(secret_key) = sub_matches.get_one::<String>("withdrawer") {
                 if self.validate_key(secret_key) {
-                    config.keys.withdrawer = Some(secret_key.clone());
+                    config.set_secret(
+                        KeyRole::Withdrawer,
+                        secret_key,
+                        encrypt,
+                        &mut passphrases,
+                    )?;
                     println!(
                         "Secret key for withdrawer {} saved successfully!",
                         pubkey_of(secret_key)
@@ -161,6 +435,9 @@ impl KeysCommand {
                 } else {
                     eprintln!("error: Invalid ZK proof verifying key.");
                 }
+            } else if encrypt {
+                config.encrypt_plaintext_secrets(&mut passphrases)?;
+                println!("Secret keys are stored encrypted.");
             }
             self.write_config(&config)
         }
@@ -169,7 +446,7 @@ impl KeysCommand {
     pub fn read_config(&self) -> io::Result<Config> {
         if self.config_path.exists() {
             let mut file = OpenOptions::new().read(true).open(&self.config_path)?;
-            let mut content = String::new();
+            let mut content = Zeroizing::new(String::new());
             file.read_to_string(&mut content)?;
             Ok(toml::from_str(&content).unwrap_or_default())
         } else {
@@ -177,8 +454,19 @@ impl KeysCommand {
         }
     }
 
+    // Reads the config and decrypts the encrypted secret keys, asking for their passphrases
+    pub fn read_unlocked_config(&self, passphrases: &mut PassphraseProvider) -> io::Result<Config> {
+        let mut config = self.read_config()?;
+        config.unlock(passphrases)?;
+        Ok(config)
+    }
+
+    // Secret keys with an encrypted copy in the keystore are not written in plaintext
     pub fn write_config(&self, config: &Config) -> io::Result<()> {
-        let toml_string = toml::to_string(config).expect("Failed to serialize config");
+        let toml_string = Zeroizing::new(
+            toml::to_string(&config.without_encrypted_plaintext())
+                .expect("Failed to serialize config"),
+        );
         let mut file = OpenOptions::new()
             .create(true)
             .write(true)
@@ -187,7 +475,7 @@ impl KeysCommand {
         file.write_all(toml_string.as_bytes())
     }
 
-    fn validate_key(&self, key: &str) -> bool {
+    pub(crate) fn validate_key(&self, key: &str) -> bool {
         key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
     }
 
@@ -200,6 +488,6 @@ impl KeysCommand {
 
 // TODO: Technically this should use the source network specified by the user. However, since this
 // is only used in console output as an ID, we can leave it for now.
-fn pubkey_of(private_key: &str) -> PublicKey {
+pub(crate) fn pubkey_of(private_key: &str) -> PublicKey {
     generate_keys_from_secret(Network::Bitcoin, private_key).1
 }
@@ -0,0 +1,351 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use bitcoin::PublicKey;
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, IsTerminal},
+};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::client::data_store::encryption::env_var_suffix;
+
+use super::key_command::pubkey_of;
+
+// Passphrases for the encrypted secret keys in bridge.toml are read from, in order:
+// export BRIDGE_KEYSTORE_PASSPHRASE_<ROLE>="..." e.g. BRIDGE_KEYSTORE_PASSPHRASE_OPERATOR
+// export BRIDGE_KEYSTORE_PASSPHRASE="..."         master passphrase used for every role
+// export BRIDGE_KEYSTORE_PASSPHRASE_FD="<fd>"     master passphrase read from a file descriptor
+// and otherwise prompted for when the client runs in a terminal. Passphrases, the keys derived
+// from them and decrypted secret keys are overwritten in memory once they are no longer needed.
+const PASSPHRASE_ENV_VAR: &str = "BRIDGE_KEYSTORE_PASSPHRASE";
+const PASSPHRASE_FD_ENV_VAR: &str = "BRIDGE_KEYSTORE_PASSPHRASE_FD";
+
+const KDF_ALGORITHM: &str = "argon2id";
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_ITERATIONS: u32 = 3;
+const KDF_PARALLELISM: u32 = 1;
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 24;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyRole {
+    Depositor,
+    Operator,
+    Verifier,
+    Withdrawer,
+}
+
+impl KeyRole {
+    pub const ALL: [KeyRole; 4] = [
+        KeyRole::Depositor,
+        KeyRole::Operator,
+        KeyRole::Verifier,
+        KeyRole::Withdrawer,
+    ];
+}
+
+impl fmt::Display for KeyRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            KeyRole::Depositor => "depositor",
+            KeyRole::Operator => "operator",
+            KeyRole::Verifier => "verifier",
+            KeyRole::Withdrawer => "withdrawer",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub salt: String, // hex
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    fn generate() -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        Self {
+            algorithm: KDF_ALGORITHM.to_string(),
+            salt: hex::encode(salt),
+            memory_kib: KDF_MEMORY_KIB,
+            iterations: KDF_ITERATIONS,
+            parallelism: KDF_PARALLELISM,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, String> {
+        if self.algorithm != KDF_ALGORITHM {
+            return Err(format!("Unsupported key derivation: {}", self.algorithm));
+        }
+        let salt = hex::decode(&self.salt).map_err(|err| format!("Invalid salt: {err}"))?;
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LENGTH),
+        )
+        .map_err(|err| format!("Invalid key derivation parameters: {err}"))?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut_slice())
+            .map_err(|err| format!("Failed to derive key: {err}"))?;
+        Ok(key)
+    }
+}
+
+// A secret key encrypted with a key derived from a passphrase. The public key is kept in the
+// clear to show and match the key without the passphrase. It is authenticated together with the
+// role, so an entry copied to another role or paired with another public key fails to decrypt.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EncryptedSecret {
+    pub public_key: PublicKey,
+    pub kdf: KdfParams,
+    pub nonce: String,      // hex
+    pub ciphertext: String, // hex
+}
+
+impl EncryptedSecret {
+    pub fn encrypt(role: KeyRole, secret: &str, passphrase: &str) -> Result<Self, String> {
+        let public_key = pubkey_of(secret);
+        let kdf = KdfParams::generate();
+        let cipher =
+            XChaCha20Poly1305::new(Key::from_slice(kdf.derive_key(passphrase)?.as_slice()));
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: secret.as_bytes(),
+                    aad: associated_data(role, &public_key).as_bytes(),
+                },
+            )
+            .map_err(|_| format!("Failed to encrypt the {role} key"))?;
+
+        Ok(Self {
+            public_key,
+            kdf,
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, role: KeyRole, passphrase: &str) -> Result<String, String> {
+        let nonce = hex::decode(&self.nonce)
+            .ok()
+            .filter(|nonce| nonce.len() == NONCE_LENGTH)
+            .ok_or_else(|| format!("Invalid nonce of the {role} key"))?;
+        let ciphertext = hex::decode(&self.ciphertext)
+            .map_err(|err| format!("Invalid ciphertext of the {role} key: {err}"))?;
+
+        let cipher =
+            XChaCha20Poly1305::new(Key::from_slice(self.kdf.derive_key(passphrase)?.as_slice()));
+        let secret = cipher
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: associated_data(role, &self.public_key).as_bytes(),
+                },
+            )
+            .map_err(|_| format!("Wrong passphrase for the {role} key {}", self.public_key))?;
+        let mut secret = String::from_utf8(secret).map_err(|err| {
+            err.into_bytes().zeroize();
+            format!("Decrypted {role} key is not a valid secret key")
+        })?;
+
+        if pubkey_of(&secret) != self.public_key {
+            secret.zeroize();
+            return Err(format!(
+                "Decrypted {role} key does not match its public key {}",
+                self.public_key
+            ));
+        }
+        Ok(secret)
+    }
+}
+
+fn associated_data(role: KeyRole, public_key: &PublicKey) -> String {
+    format!("bitvm-bridge-keystore:{role}:{public_key}")
+}
+
+// Encrypted secret keys in bridge.toml. A role with an entry here has no plaintext secret in the
+// [keys] table. Retired verifier keys are encrypted with the verifier passphrase.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct Keystore {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depositor: Option<EncryptedSecret>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<EncryptedSecret>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifier: Option<EncryptedSecret>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawer: Option<EncryptedSecret>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retired_verifiers: Vec<EncryptedSecret>,
+}
+
+impl Keystore {
+    pub fn is_empty(&self) -> bool {
+        KeyRole::ALL.iter().all(|role| self.get(*role).is_none())
+            && self.retired_verifiers.is_empty()
+    }
+
+    pub fn get(&self, role: KeyRole) -> Option<&EncryptedSecret> {
+        match role {
+            KeyRole::Depositor => self.depositor.as_ref(),
+            KeyRole::Operator => self.operator.as_ref(),
+            KeyRole::Verifier => self.verifier.as_ref(),
+            KeyRole::Withdrawer => self.withdrawer.as_ref(),
+        }
+    }
+
+    pub fn get_mut(&mut self, role: KeyRole) -> &mut Option<EncryptedSecret> {
+        match role {
+            KeyRole::Depositor => &mut self.depositor,
+            KeyRole::Operator => &mut self.operator,
+            KeyRole::Verifier => &mut self.verifier,
+            KeyRole::Withdrawer => &mut self.withdrawer,
+        }
+    }
+
+    pub fn contains_public_key(&self, public_key: &PublicKey) -> bool {
+        KeyRole::ALL
+            .iter()
+            .filter_map(|role| self.get(*role))
+            .chain(self.retired_verifiers.iter())
+            .any(|encrypted| encrypted.public_key == *public_key)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PassphrasePurpose {
+    Unlock,
+    Encrypt, // a new passphrase is confirmed when prompted for
+}
+
+// Supplies the passphrases of the roles. A passphrase that unlocked or encrypted a key is reused
+// for the role, e.g. to encrypt the new verifier key after a key rotation.
+#[derive(Default)]
+pub struct PassphraseProvider {
+    master: Option<Zeroizing<String>>,
+    master_for_all_roles: bool,
+    passphrases: HashMap<KeyRole, Zeroizing<String>>,
+}
+
+impl PassphraseProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Prompts for a single master passphrase instead of one per role
+    pub fn master_for_all_roles(mut self) -> Self {
+        self.master_for_all_roles = true;
+        self
+    }
+
+    pub fn with_master(passphrase: &str) -> Self {
+        Self {
+            master: Some(Zeroizing::new(passphrase.to_string())),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_passphrase(mut self, role: KeyRole, passphrase: &str) -> Self {
+        self.passphrases
+            .insert(role, Zeroizing::new(passphrase.to_string()));
+        self
+    }
+
+    pub fn passphrase_for(
+        &mut self,
+        role: KeyRole,
+        purpose: PassphrasePurpose,
+    ) -> io::Result<Zeroizing<String>> {
+        if let Some(passphrase) = self.passphrases.get(&role) {
+            return Ok(passphrase.clone());
+        }
+        let role_env_var = format!("{PASSPHRASE_ENV_VAR}_{}", env_var_suffix(&role.to_string()));
+        if let Ok(passphrase) = dotenv::var(&role_env_var) {
+            return Ok(Zeroizing::new(passphrase));
+        }
+        if let Some(passphrase) = self.master_passphrase()? {
+            return Ok(passphrase);
+        }
+
+        if !io::stdin().is_terminal() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No passphrase for the {role} key. Set {role_env_var}, {PASSPHRASE_ENV_VAR} or {PASSPHRASE_FD_ENV_VAR}"),
+            ));
+        }
+        if self.master_for_all_roles {
+            let passphrase = prompt_passphrase("Master passphrase", purpose)?;
+            self.master = Some(passphrase.clone());
+            return Ok(passphrase);
+        }
+        prompt_passphrase(&format!("Passphrase for the {role} key"), purpose)
+    }
+
+    pub fn remember(&mut self, role: KeyRole, passphrase: Zeroizing<String>) {
+        self.passphrases.insert(role, passphrase);
+    }
+
+    // Read once, a file descriptor cannot be read again for the next role
+    fn master_passphrase(&mut self) -> io::Result<Option<Zeroizing<String>>> {
+        if self.master.is_none() {
+            self.master = match dotenv::var(PASSPHRASE_ENV_VAR) {
+                Ok(passphrase) => Some(Zeroizing::new(passphrase)),
+                Err(_) => match dotenv::var(PASSPHRASE_FD_ENV_VAR) {
+                    Ok(fd) => Some(read_passphrase_from_fd(&fd)?),
+                    Err(_) => None,
+                },
+            };
+        }
+        Ok(self.master.clone())
+    }
+}
+
+fn read_passphrase_from_fd(fd: &str) -> io::Result<Zeroizing<String>> {
+    let fd: u32 = fd.trim().parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{PASSPHRASE_FD_ENV_VAR} must be a file descriptor number"),
+        )
+    })?;
+    let content = Zeroizing::new(fs::read_to_string(format!("/dev/fd/{fd}"))?);
+    Ok(Zeroizing::new(
+        content.lines().next().unwrap_or_default().to_string(),
+    ))
+}
+
+fn prompt_passphrase(prompt: &str, purpose: PassphrasePurpose) -> io::Result<Zeroizing<String>> {
+    loop {
+        let passphrase = Zeroizing::new(rpassword::prompt_password(format!("{prompt}: "))?);
+        if purpose == PassphrasePurpose::Unlock {
+            return Ok(passphrase);
+        }
+        if passphrase.is_empty() {
+            eprintln!("The passphrase cannot be empty.");
+            continue;
+        }
+        if Zeroizing::new(rpassword::prompt_password(format!("{prompt} (again): "))?) == passphrase
+        {
+            return Ok(passphrase);
+        }
+        eprintln!("The passphrases do not match, try again.");
+    }
+}
@@ -1,6 +1,10 @@
 pub mod client_command;
+pub mod committee_command;
 pub mod key_command;
+pub mod keystore;
 pub mod query_command;
 pub mod query_response;
+pub mod rehearse_command;
 pub mod utils;
-pub mod validation;
+pub(crate) mod validation;
+pub(crate) mod wizard;
@@ -35,11 +35,18 @@ impl QueryCommand {
         source_network: Network,
         destination_network: DestinationNetwork,
         path_prefix: Option<&str>,
+        verifiers: Option<Vec<PublicKey>>,
     ) -> Self {
-        let (_, verifier_0_public_key) =
-            generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
-
-        let n_of_n_public_keys: Vec<PublicKey> = vec![verifier_0_public_key];
+        // Graphs are stored per committee, so the committee has to be known to find them. Without
+        // one, the single verifier committee of local test setups is queried.
+        let (verifier_secret, n_of_n_public_keys) = match verifiers {
+            Some(verifiers) => (None, verifiers),
+            None => {
+                let (_, verifier_0_public_key) =
+                    generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
+                (Some(VERIFIER_0_SECRET), vec![verifier_0_public_key])
+            }
+        };
 
         let bitvm_client = BitVMClient::new(
             Some(get_esplora_url(source_network)),
@@ -49,7 +56,7 @@ impl QueryCommand {
             &n_of_n_public_keys,
             Some(FAKE_SECRET),
             Some(FAKE_SECRET),
-            Some(VERIFIER_0_SECRET),
+            verifier_secret,
             Some(FAKE_SECRET),
             path_prefix.or(Some(QUERY_COMMAND_PATH_PREFIX)),
             None,
@@ -106,21 +113,27 @@ impl QueryCommand {
         let recipient_address = sub_matches.get_one::<String>("RECIPIENT_ADDRESS").unwrap();
         let depositor_taproot_key = XOnlyPublicKey::from(*depositor_public_key);
         let amount: Amount = Amount::from_sat(amount.parse::<u64>().unwrap());
-        let taproot_address = self.client.generate_connector_z_taproot_address(
+        let taproot_address = match self.client.generate_connector_z_taproot_address(
             self.network,
             recipient_address,
             &depositor_taproot_key,
-        );
+        ) {
+            Ok(address) => address,
+            Err(err) => return Response::new(ResponseStatus::NOK(err.to_string()), None),
+        };
         let outpoint = self
             .generate_stub_outpoint(&self.client, &taproot_address, amount)
             .await;
-        let result = self.client.generate_presign_pegin_refund_tx(
+        let result = match self.client.generate_presign_pegin_refund_tx(
             self.network,
             amount,
             recipient_address,
             depositor_public_key,
             outpoint,
-        );
+        ) {
+            Ok(result) => result,
+            Err(err) => return Response::new(ResponseStatus::NOK(err.to_string()), None),
+        };
         Response::new(
             ResponseStatus::OK,
             Some(serde_json::to_value(result).unwrap()),
@@ -142,13 +155,16 @@ impl QueryCommand {
                 amount,
             )
             .await;
-        let result = self.client.generate_presign_pegin_deposit_tx(
+        let result = match self.client.generate_presign_pegin_deposit_tx(
             self.network,
             amount,
             recipient_address,
             depositor_public_key,
             outpoint,
-        );
+        ) {
+            Ok(result) => result,
+            Err(err) => return Response::new(ResponseStatus::NOK(err.to_string()), None),
+        };
         Response::new(
             ResponseStatus::OK,
             Some(serde_json::to_value(result).unwrap()),
@@ -173,21 +189,27 @@ impl QueryCommand {
         let recipient_address = sub_matches.get_one::<String>("RECIPIENT_ADDRESS").unwrap();
         let depositor_taproot_key = XOnlyPublicKey::from(*depositor_public_key);
         let amount: Amount = Amount::from_sat(amount.parse::<u64>().unwrap());
-        let taproot_address = self.client.generate_connector_z_taproot_address(
+        let taproot_address = match self.client.generate_connector_z_taproot_address(
             self.network,
             recipient_address,
             &depositor_taproot_key,
-        );
+        ) {
+            Ok(address) => address,
+            Err(err) => return Response::new(ResponseStatus::NOK(err.to_string()), None),
+        };
         let outpoint = self
             .generate_stub_outpoint(&self.client, &taproot_address, amount)
             .await;
-        let result = self.client.generate_presign_pegin_confirm_tx(
+        let result = match self.client.generate_presign_pegin_confirm_tx(
             self.network,
             recipient_address,
             amount,
             &depositor_taproot_key,
             outpoint,
-        );
+        ) {
+            Ok(result) => result,
+            Err(err) => return Response::new(ResponseStatus::NOK(err.to_string()), None),
+        };
         Response::new(
             ResponseStatus::OK,
             Some(serde_json::to_value(result).unwrap()),
@@ -501,18 +523,24 @@ impl QueryCommand {
             .args([arg!(<GRAPH_ID> "peg-in graph id").required(true)])
     }
 
-    pub async fn handle_broadcast(&mut self, matches: &ArgMatches) -> Response {
-        self.sync().await;
-        let arg = "GRAPH_ID";
-        let Some(peg_in_graph_id) = matches.get_one::<String>(arg) else {
-            return Response::new(
-                ResponseStatus::NOK(format!("Missing argument: {}", arg)),
-                None,
-            );
+    pub async fn handle_broadcast(
+        &mut self,
+        matches: &ArgMatches,
+        destination_network: DestinationNetwork,
+    ) -> Response {
+        let args = vec!["GRAPH_ID".to_string()];
+        let peg_in_graph_id = match validate(matches, args, destination_network) {
+            Ok(args) => match &args[0] {
+                ArgType::PegInGraphId(graph_id) => graph_id.clone(),
+                _ => unreachable!(),
+            },
+            Err(err) => return err,
         };
+
+        self.sync().await;
         let result = self
             .client
-            .retry_broadcast_peg_in_deposit(peg_in_graph_id)
+            .retry_broadcast_peg_in_deposit(&peg_in_graph_id)
             .await;
 
         match result {
@@ -0,0 +1,52 @@
+use std::io;
+use std::path::Path;
+
+use clap::{arg, ArgMatches, Command};
+
+use crate::client::esplora::get_esplora_url;
+use crate::client::rehearsal::{Rehearsal, Scenario};
+
+use bitcoin::Network;
+
+#[derive(Default)]
+pub struct RehearseCommand {}
+
+impl RehearseCommand {
+    pub fn new() -> Self {
+        RehearseCommand {}
+    }
+
+    pub fn get_command() -> Command {
+        Command::new("rehearse")
+            .about("Rehearse a dispute scenario on regtest")
+            .after_help("Runs the timeline of a TOML scenario file against the regtest esplora (see regtest/README.md): funds the participants, runs the peg-in and peg-out signing ceremonies, then broadcasts the scripted transactions of the operator, challenger and verifiers at the given block heights and checks the expected outcomes and balance changes. The destination chain is mocked. Example scenarios are in regtest/rehearsals.")
+            .arg(arg!(<SCENARIO> "Path to the scenario file").required(true))
+            .arg(arg!(--"dry-run" "Only validate the scenario and print its timeline").required(false))
+    }
+
+    pub async fn handle_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let path = sub_matches.get_one::<String>("SCENARIO").unwrap();
+        let scenario = Scenario::from_file(Path::new(path))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        if sub_matches.get_flag("dry-run") {
+            println!("{scenario}");
+            return Ok(());
+        }
+
+        let mut rehearsal = Rehearsal::new(scenario, Some(get_esplora_url(Network::Regtest))).await;
+        let report = rehearsal
+            .run()
+            .await
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        println!("{report}");
+
+        match report.passed() {
+            true => Ok(()),
+            false => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The rehearsal did not go as scripted",
+            )),
+        }
+    }
+}
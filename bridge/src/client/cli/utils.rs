@@ -1,10 +1,18 @@
-use std::str::FromStr;
+use std::{fs, io, str::FromStr};
 
-use bitcoin::{hashes::hash160::Hash, Amount, OutPoint, PubkeyHash, PublicKey};
+use bitcoin::{hashes::hash160::Hash, Amount, OutPoint, PubkeyHash, PublicKey, Txid};
+use colored::Colorize;
 
-use crate::client::chain::{
-    chain::{Chain, PegOutEvent},
-    mock_adaptor::{MockAdaptor, MockAdaptorConfig},
+use crate::{
+    client::chain::{
+        chain::{Chain, PegOutBurntEvent, PegOutEvent, WithdrawerDestination},
+        mock_adaptor::{MockAdaptor, MockAdaptorConfig},
+    },
+    error::Error,
+    graphs::{
+        base::{PegInGraphId, PegOutGraphId},
+        integrity::GraphIntegrityIssue,
+    },
 };
 
 pub fn get_mock_chain_service(outpoint: OutPoint, operator_public_key: PublicKey) -> Chain {
@@ -16,15 +24,78 @@ pub fn get_mock_chain_service(outpoint: OutPoint, operator_public_key: PublicKey
             withdrawer_chain_address: "0x0000000000000000000000000000000000000000".to_string(),
             withdrawer_destination_address: "0x0000000000000000000000000000000000000000"
                 .to_string(),
-            withdrawer_public_key_hash: PubkeyHash::from_raw_hash(
-                Hash::from_str("0e6719ac074b0e3cac76d057643506faa1c266b3").unwrap(),
+            withdrawer_destination: WithdrawerDestination::PublicKeyHash(
+                PubkeyHash::from_raw_hash(
+                    Hash::from_str("0e6719ac074b0e3cac76d057643506faa1c266b3").unwrap(),
+                ),
             ),
             operator_public_key: operator_public_key,
             tx_hash: [0u8; 32].into(),
+            withdrawal_nonce: 0,
+        }]),
+        // The burn backing the peg-out above, required before the peg-out confirm is broadcast
+        peg_out_burnt_events: Some(vec![PegOutBurntEvent {
+            withdrawer_chain_address: "0x0000000000000000000000000000000000000000".to_string(),
+            source_outpoint: outpoint,
+            amount: Amount::from_sat(0),
+            operator_public_key,
+            timestamp: 1722328130u32,
+            tx_hash: [0u8; 32].into(),
         }]),
-        peg_out_burnt_events: None,
         peg_out_minted_events: None,
     };
     let mock_adaptor = MockAdaptor::new(Some(mock_adaptor_config));
     Chain::new(Box::new(mock_adaptor))
 }
+
+// Total memory of the machine as reported by the kernel, None where /proc/meminfo is not available
+pub fn total_memory_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let kib = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kib * 1024)
+}
+
+// Value parsers for graph ID arguments, a malformed ID is rejected before the command runs
+pub fn parse_peg_in_graph_id(graph_id: &str) -> Result<PegInGraphId, String> {
+    PegInGraphId::from_str(graph_id).map_err(|e| format!("{e:?}"))
+}
+
+pub fn parse_peg_out_graph_id(graph_id: &str) -> Result<PegOutGraphId, String> {
+    PegOutGraphId::from_str(graph_id).map_err(|e| format!("{e:?}"))
+}
+
+pub fn parse_txid(txid: &str) -> Result<Txid, String> {
+    Txid::from_str(txid).map_err(|e| e.to_string())
+}
+
+// Prints the outcome of `validate-graph`. A graph with issues, or one that could not be validated,
+// is an error so scripts can rely on the exit code before broadcasting from the graph.
+pub fn report_graph_integrity(
+    graph_id: &str,
+    issues: Result<Vec<GraphIntegrityIssue>, Error>,
+) -> io::Result<()> {
+    let issues = issues
+        .map_err(|e| io::Error::other(format!("Failed to validate graph {graph_id}: {e}")))?;
+    if issues.is_empty() {
+        println!("{}", format!("Graph {graph_id} is valid").green());
+        return Ok(());
+    }
+
+    println!("Graph {graph_id} has {} issue(s):", issues.len());
+    for issue in &issues {
+        println!("{}", format!("- {issue}").red());
+    }
+
+    Err(io::Error::other(format!(
+        "Graph {graph_id} has {} issue(s)",
+        issues.len()
+    )))
+}
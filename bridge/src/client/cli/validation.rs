@@ -3,7 +3,7 @@ use bitcoin::{ecdsa, taproot, Amount, Denomination, OutPoint, PublicKey};
 use clap::ArgMatches;
 use core::str::FromStr;
 
-use crate::constants::DestinationNetwork;
+use crate::{constants::DestinationNetwork, graphs::base::PegInGraphId};
 
 use super::query_response::{Response, ResponseStatus};
 
@@ -14,6 +14,7 @@ pub enum ArgType {
     Satoshis(Amount),
     EcdsaSignature(ecdsa::Signature),
     TaprootSignature(taproot::Signature),
+    PegInGraphId(PegInGraphId),
 }
 
 pub fn validate(
@@ -78,6 +79,14 @@ pub fn validate(
                         )))
                     }
                 },
+                "GRAPH_ID" => match PegInGraphId::from_str(value) {
+                    Ok(graph_id) => result.push(ArgType::PegInGraphId(graph_id)),
+                    Err(_) => {
+                        return Err(error_response(
+                            "Invalid graph id. Use 64 hex characters.".to_string(),
+                        ))
+                    }
+                },
                 _ => return Err(error_response(format!("Invalid argument: {}", arg))),
             },
             None => return Err(error_response(format!("Missing argument: {}", arg))),
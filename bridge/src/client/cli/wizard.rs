@@ -0,0 +1,196 @@
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
+
+use bitcoin::{Amount, OutPoint};
+use colored::Colorize;
+use esplora_client::Utxo;
+use tokio::io::{AsyncBufReadExt, BufReader, Stdin};
+
+use crate::transactions::{
+    base::Input,
+    fee_sensitivity::{fee_sensitivity_warnings, FeeSensitivity, FEE_RATE_WARNING_THRESHOLD},
+};
+
+// Questions of the graph creation wizard. Answers are read line by line from stdin, an empty
+// answer takes the suggested default.
+pub struct Prompt {
+    reader: BufReader<Stdin>,
+}
+
+impl Prompt {
+    pub fn new() -> Self {
+        Self {
+            reader: BufReader::new(tokio::io::stdin()),
+        }
+    }
+
+    pub async fn ask(&mut self, question: &str, default: Option<&str>) -> io::Result<String> {
+        match default {
+            Some(default) => print!("{question} [{}]: ", default.green()),
+            None => print!("{question}: "),
+        }
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Wizard aborted",
+            ));
+        }
+        let answer = line.trim();
+
+        Ok(match (answer.is_empty(), default) {
+            (true, Some(default)) => default.to_string(),
+            _ => answer.to_string(),
+        })
+    }
+
+    // Asks again until `parse` accepts the answer
+    pub async fn ask_parsed<T>(
+        &mut self,
+        question: &str,
+        default: Option<&str>,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> io::Result<T> {
+        loop {
+            let answer = self.ask(question, default).await?;
+            match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => println!("{}", e.red()),
+            }
+        }
+    }
+
+    pub async fn confirm(&mut self, question: &str) -> io::Result<bool> {
+        self.ask_parsed(question, Some("n"), |answer| {
+            match answer.to_lowercase().as_str() {
+                "y" | "yes" => Ok(true),
+                "n" | "no" => Ok(false),
+                _ => Err(String::from("Answer y or n")),
+            }
+        })
+        .await
+    }
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Smallest confirmed output of at least `amount`, so large outputs stay available for other graphs
+pub fn suggest_funding_utxo(
+    utxos: &[Utxo],
+    amount: Amount,
+    excluded: &[OutPoint],
+) -> Option<OutPoint> {
+    utxos
+        .iter()
+        .filter(|utxo| utxo.status.confirmed && utxo.value >= amount)
+        .map(|utxo| (OutPoint::new(utxo.txid, utxo.vout), utxo.value))
+        .filter(|(outpoint, _)| !excluded.contains(outpoint))
+        .min_by_key(|(_, value)| *value)
+        .map(|(outpoint, _)| outpoint)
+}
+
+pub fn parse_amount(answer: &str) -> Result<Amount, String> {
+    answer
+        .parse::<u64>()
+        .map(Amount::from_sat)
+        .map_err(|e| format!("Invalid amount in sats: {e}"))
+}
+
+// Asks for one of `utxos` holding at least `amount`, suggesting `suggest_funding_utxo`
+pub async fn ask_funding_input(
+    prompt: &mut Prompt,
+    utxos: &[Utxo],
+    amount: Amount,
+    excluded: &[OutPoint],
+) -> io::Result<Input> {
+    let suggestion = suggest_funding_utxo(utxos, amount, excluded).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No confirmed UTXO of at least {amount}, fund the address above first"),
+        )
+    })?;
+
+    prompt
+        .ask_parsed(
+            "Funding UTXO (<TXID>:<VOUT>)",
+            Some(&suggestion.to_string()),
+            |answer| {
+                let outpoint =
+                    OutPoint::from_str(answer).map_err(|e| format!("Invalid UTXO: {e}"))?;
+                if excluded.contains(&outpoint) {
+                    return Err(format!("{outpoint} is already spent by this graph"));
+                }
+                let utxo = utxos
+                    .iter()
+                    .find(|utxo| utxo.txid == outpoint.txid && utxo.vout == outpoint.vout)
+                    .ok_or(format!("{outpoint} is not a UTXO of the address above"))?;
+                if utxo.value < amount {
+                    return Err(format!(
+                        "{outpoint} holds {}, at least {amount} is required",
+                        utxo.value
+                    ));
+                }
+                Ok(Input {
+                    outpoint,
+                    amount: utxo.value,
+                })
+            },
+        )
+        .await
+}
+
+pub fn print_balance(role: &str, utxos: &[Utxo]) {
+    let confirmed: Amount = utxos
+        .iter()
+        .filter(|utxo| utxo.status.confirmed)
+        .map(|utxo| utxo.value)
+        .sum();
+    let unconfirmed: Amount = utxos
+        .iter()
+        .filter(|utxo| !utxo.status.confirmed)
+        .map(|utxo| utxo.value)
+        .sum();
+    println!(
+        "[{role}] balance: {} confirmed, {} unconfirmed in {} UTXO(s)",
+        confirmed,
+        unconfirmed,
+        utxos.len()
+    );
+}
+
+pub fn print_graph_preview(
+    dag: &[(&'static str, Vec<&'static str>)],
+    fee_sensitivity: &[FeeSensitivity],
+) {
+    println!("\nTransactions, with the transactions of the graph they spend:");
+    for (name, spent) in dag {
+        if spent.is_empty() {
+            println!("  {name}");
+        } else {
+            println!("  {name} <- {}", spent.join(", "));
+        }
+    }
+
+    println!("\nFees, fixed when the graph is created:");
+    for sensitivity in fee_sensitivity {
+        println!("  {sensitivity}");
+    }
+    for sensitivity in fee_sensitivity_warnings(fee_sensitivity, FEE_RATE_WARNING_THRESHOLD) {
+        println!(
+            "{}",
+            format!(
+                "Warning: {} is only broadcastable up to {} sat/vB",
+                sensitivity.transaction, sensitivity.max_fee_rate
+            )
+            .yellow()
+        );
+    }
+}
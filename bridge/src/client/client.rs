@@ -1,50 +1,96 @@
 use bitcoin::{
-    absolute::Height, consensus::encode::serialize_hex, Address, Amount, Network, OutPoint,
-    PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
+    absolute::Height, block::Header, consensus::encode::serialize_hex, key::Keypair, Address,
+    Amount, BlockHash, Network, OutPoint, PublicKey, ScriptBuf, TapNodeHash, Transaction, Txid,
+    XOnlyPublicKey,
 };
 use colored::Colorize;
-use esplora_client::{AsyncClient, Builder, TxStatus, Utxo};
-use futures::future::join_all;
+use esplora_client::{AsyncClient, Builder, Utxo};
 use human_bytes::human_bytes;
 use musig2::SecNonce;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    mem,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
+use tokio::task::spawn_blocking;
 
 use crate::{
     client::{
-        chain::ethereum_adaptor::EthereumAdaptor, esplora::get_esplora_url,
+        chain::ethereum_adaptor::EthereumAdaptor,
+        esplora::{estimate_fee_rate, get_esplora_url},
         files::DEFAULT_PATH_PREFIX,
     },
     commitments::CommitmentMessageId,
     common::ZkProofVerifyingKey,
-    connectors::{base::TaprootConnector, connector_0::Connector0, connector_z::ConnectorZ},
+    connectors::{
+        base::TaprootConnector,
+        cache_files::{
+            cache_directory, lock_scripts_transfer_path, shared_lock_scripts_file_name,
+            SHARED_LOCK_SCRIPTS_DIRECTORY_NAME,
+        },
+        connector_0::Connector0,
+        connector_z::ConnectorZ,
+        descriptor::ConnectorDescriptor,
+        lock_scripts_file::LockScriptsFile,
+    },
     constants::DestinationNetwork,
     contexts::base::generate_n_of_n_public_key,
-    error::{ClientError, Error},
+    error::{
+        ChunkerError, ClientError, Error, GraphError, HeaderChainError, L2Error, TransactionError,
+        ValidationError,
+    },
     graphs::{
         base::{
-            broadcast_and_verify, get_tx_statuses, GraphId, PEG_OUT_FEE, REWARD_MULTIPLIER,
-            REWARD_PRECISION,
+            broadcast_and_verify, verify_if_not_mined, GraphId, GraphStage, PegInGraphId,
+            PegOutGraphId, MIN_RELAY_FEE_RATE,
+        },
+        commitment_registry::{commitment_key_reuses, CommitmentKeyReuse},
+        funding::{peg_out_confirm_funding, select_deposit_funding},
+        integrity::GraphIntegrityIssue,
+        operator_commitments::{
+            check_collateral_input, OperatorCommitments, SignedOperatorCommitments,
         },
         peg_in::{PegInDepositorStatus, PegInVerifierStatus},
-        peg_out::PegOutOperatorStatus,
+        peg_out::{
+            evaluate_asserted_proof, AssertCommit, AssertCommitProgress, ChallengePolicy,
+            MerkleRootStatus, MerkleRootVerification, PegOutOperatorStatus,
+            PegOutPresignedTransaction, PegOutResignStatus, PegOutVerifierStatus,
+            ProofVerification, WithdrawalEvent, WithdrawalProgress,
+        },
+        peg_out_race::{find_peg_out_races, PegOutClaim, PegOutRace},
+        template::GraphParameters,
     },
     proof::get_proof,
-    scripts::generate_pay_to_pubkey_script_address,
-    serialization::{serialize, try_deserialize_slice},
+    proof_binding::ExternalProof,
+    rewards::{parse_reward_destination, RewardConfig},
+    scripts::{
+        generate_p2tr_key_path_address, generate_p2wpkh_address, generate_pay_to_pubkey_script,
+        generate_pay_to_pubkey_script_address, DepositorAddressType,
+    },
+    serialization::{serialize, try_deserialize, try_deserialize_slice},
     transactions::{
-        peg_in_confirm::PegInConfirmTransaction, peg_in_deposit::PegInDepositTransaction,
-        peg_in_refund::PegInRefundTransaction, pre_signed_musig2::PreSignedMusig2Transaction,
+        assert_transactions::utils::{
+            sign_assert_tx_with_groth16_proof, CommittedIntermediateValues,
+        },
+        fee_sensitivity::{fee_sensitivity_warnings, FEE_RATE_WARNING_THRESHOLD},
+        peg_in_confirm::PegInConfirmTransaction,
+        peg_in_deposit::{DepositFunding, PegInDepositTransaction},
+        peg_in_refund::PegInRefundTransaction,
+        pre_signed_musig2::{CeremonyStatus, CeremonyStep, PreSignedMusig2Transaction},
+        script_diagnostics::{diagnose_input_scripts, get_prevouts, is_script_verify_rejection},
+        signing_bundle::SigningBundle,
+        standardness::StandardnessViolation,
+        witness_scrubber::scrub_witnesses,
     },
 };
 
 use bitvm::{
     // chunker::disprove_execution::RawProof,
-    chunk::api::type_conversion_utils::RawProof,
+    chunk::api::type_conversion_utils::{RawProof, RawWitness},
     signatures::signing_winternitz::WinternitzSecret,
 };
 
@@ -57,26 +103,78 @@ use super::{
         graphs::{
             base::BaseGraph,
             peg_in::{generate_id as peg_in_generate_id, PegInGraph},
-            peg_out::{generate_id as peg_out_generate_id, PegOutGraph},
+            peg_out::{
+                generate_id as peg_out_generate_id,
+                generate_id_from_peg_in_graph_id as peg_out_generate_id_from_peg_in_graph_id,
+                PegOutGraph,
+            },
         },
         transactions::{
             base::{Input, InputWithScript},
+            cpfp::create_cpfp_transaction,
             pre_signed::PreSignedTransaction,
         },
     },
-    chain::{chain::Chain, chain_adaptor::ChainAdaptor},
+    alerts::{AlertConfig, AlertEvent, AlertNotifier},
+    audit::{
+        append_broadcast_audit_record, read_broadcast_audit_records, AuditTrailIssue,
+        BroadcastAuditRecord, BroadcastOutcome, WitnessMismatch,
+    },
+    broadcast_middleware::{
+        broadcast_approval_file_name, is_same_transaction_name, BroadcastApproval,
+        BroadcastContext, BroadcastMiddleware, BroadcastMiddlewareChain, BroadcastPolicyConfig,
+        BroadcastRequest, BROADCAST_APPROVAL_DIRECTORY_SUFFIX,
+    },
+    ceremony_schedule::{
+        CeremonyPhase, CeremonyProgressTracker, CeremonySchedule, CeremonyScheduleConfig,
+        CeremonyTurn,
+    },
+    chain::{
+        chain::{Chain, PegOutBurntEvent},
+        chain_adaptor::ChainAdaptor,
+    },
+    clock::{BlockProducer, Clock, SystemClock, WaitingBlockProducer},
+    congestion::{BroadcastDecision, CongestionConfig, CongestionScheduler, DeferrableTx},
     data_store::data_store::DataStore,
+    disprove_precomputation::DisprovePrecomputation,
+    event_log::{event_candidates, BridgeEvent, BridgeEventLog, EVENT_LOG_FILE_NAME},
+    faucet::FaucetClient,
     files::{
-        get_private_data_file_path, get_private_data_from_file, save_local_private_file,
-        save_local_public_file, BRIDGE_DATA_DIRECTORY_NAME,
+        get_private_data_from_files, save_private_graph_file, PrivateDataRole,
+        BRIDGE_DATA_DIRECTORY_NAME,
+    },
+    graph_history::{replay_graph_history, DataStoreVersion, GraphState, GraphTransition},
+    graph_list::{GraphListFilter, GraphSummary},
+    graph_store::GraphStore,
+    heartbeat::{
+        generate_heartbeat_beacon_tx, heartbeat_file_name, new_shared_committee_heartbeats,
+        CommitteeHeartbeats, HeartbeatConfig, HeartbeatRecord, MemberLiveness,
+        SharedCommitteeHeartbeats, HEARTBEAT_DIRECTORY_NAME,
     },
+    jobs::{JobId, JobKind, JobOutput, JobQueue, JobStatus},
+    light_client::{
+        HeaderChain, HeaderCheckpoint, HeaderVerificationConfig, HeaderVerificationLevel,
+    },
+    peg_in_queue::{PegInPriorityQueue, PegInProcessingConfig, PegInQueueEntry},
+    profiling::{profile_phase, profile_phase_async},
+    read_handle::{new_shared_client_data, BitVMClientReadHandle, ClientQueries, SharedClientData},
     sdk::{
         query::{ClientCliQuery, GraphCliQuery},
         query_contexts::depositor_signatures::DepositorSignatures,
     },
+    secret_nonces::EncryptedSecretNonces,
+    staged_flush::{
+        flush_acknowledgment_file_name, validate_transition, FlushAcknowledgment,
+        FlushCoordinationConfig, StagedFlush, StagedFlushStatus,
+        FLUSH_ACKNOWLEDGMENT_DIRECTORY_NAME, STAGED_FLUSH_DIRECTORY_SUFFIX,
+    },
+    watchtower::Watchtower,
 };
 
 const TEN_MINUTES: u64 = 10 * 60;
+const HEADER_CHAIN_FILE_NAME: &str = "header_chain.json";
+const FAUCET_TX_LOOKUP_RETRIES: usize = 10;
+const FAUCET_TX_LOOKUP_WAIT_SECS: u64 = 3;
 
 pub type UtxoSet = HashMap<OutPoint, Height>;
 
@@ -99,7 +197,7 @@ impl BitVMClientPublicData {
     }
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Default)]
 pub struct BitVMClientPrivateData {
     // Peg in and peg out nonces all go into the same file for now
     // Verifier public key -> Graph ID -> Tx ID -> Input index -> Secret nonce
@@ -109,11 +207,182 @@ pub struct BitVMClientPrivateData {
     // Operator public key -> Graph ID -> Message ID -> Winternitz secret
     pub commitment_secrets:
         HashMap<PublicKey, HashMap<String, HashMap<CommitmentMessageId, WinternitzSecret>>>,
+    // Groth16 intermediate values committed by the operator's assert commits, a restarted assert
+    // sequence must commit the same ones.
+    // Operator public key -> Graph ID -> Committed values
+    #[serde(default)]
+    pub committed_intermediate_values:
+        HashMap<PublicKey, HashMap<String, CommittedIntermediateValues>>,
+    // Secret nonces as saved, until they are decrypted into `secret_nonces` with the verifier key,
+    // see `secret_nonces`
+    // Verifier public key -> Graph ID -> Encrypted secret nonces
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub encrypted_secret_nonces: HashMap<PublicKey, HashMap<String, EncryptedSecretNonces>>,
+}
+
+// Private data of one graph for one role and key, what a private data file holds. Verifiers only
+// keep secret nonces, operators the rest.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PrivateGraphData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_nonces: Option<HashMap<Txid, HashMap<usize, SecNonce>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment_secrets: Option<HashMap<CommitmentMessageId, WinternitzSecret>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committed_intermediate_values: Option<CommittedIntermediateValues>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_secret_nonces: Option<EncryptedSecretNonces>,
+}
+
+impl BitVMClientPrivateData {
+    // Role, key and graph id of every graph with private data
+    pub fn graph_keys(&self) -> Vec<(PrivateDataRole, PublicKey, String)> {
+        let verifier_keys = self
+            .secret_nonces
+            .iter()
+            .flat_map(|(public_key, graphs)| {
+                graphs.keys().map(move |graph_id| (public_key, graph_id))
+            })
+            .chain(
+                self.encrypted_secret_nonces
+                    .iter()
+                    .flat_map(|(public_key, graphs)| {
+                        graphs.keys().map(move |graph_id| (public_key, graph_id))
+                    }),
+            )
+            .map(|(public_key, graph_id)| {
+                (PrivateDataRole::Verifier, *public_key, graph_id.clone())
+            })
+            .collect::<HashSet<_>>();
+        let operator_keys =
+            self.commitment_secrets
+                .iter()
+                .flat_map(|(public_key, graphs)| {
+                    graphs.keys().map(move |graph_id| (public_key, graph_id))
+                })
+                .chain(self.committed_intermediate_values.iter().flat_map(
+                    |(public_key, graphs)| {
+                        graphs.keys().map(move |graph_id| (public_key, graph_id))
+                    },
+                ))
+                .map(|(public_key, graph_id)| {
+                    (PrivateDataRole::Operator, *public_key, graph_id.clone())
+                })
+                .collect::<HashSet<_>>();
+
+        verifier_keys.into_iter().chain(operator_keys).collect()
+    }
+
+    pub fn graph_data(
+        &self,
+        role: PrivateDataRole,
+        public_key: &PublicKey,
+        graph_id: &str,
+    ) -> PrivateGraphData {
+        match role {
+            PrivateDataRole::Verifier => PrivateGraphData {
+                secret_nonces: self
+                    .secret_nonces
+                    .get(public_key)
+                    .and_then(|graphs| graphs.get(graph_id))
+                    .cloned(),
+                encrypted_secret_nonces: self
+                    .encrypted_secret_nonces
+                    .get(public_key)
+                    .and_then(|graphs| graphs.get(graph_id))
+                    .cloned(),
+                ..Default::default()
+            },
+            PrivateDataRole::Operator => PrivateGraphData {
+                commitment_secrets: self
+                    .commitment_secrets
+                    .get(public_key)
+                    .and_then(|graphs| graphs.get(graph_id))
+                    .cloned(),
+                committed_intermediate_values: self
+                    .committed_intermediate_values
+                    .get(public_key)
+                    .and_then(|graphs| graphs.get(graph_id))
+                    .cloned(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn insert_graph_data(
+        &mut self,
+        role: PrivateDataRole,
+        public_key: &PublicKey,
+        graph_id: &str,
+        graph_data: PrivateGraphData,
+    ) {
+        let graph_id = graph_id.to_string();
+        if role == PrivateDataRole::Verifier {
+            if let Some(secret_nonces) = graph_data.secret_nonces {
+                self.secret_nonces
+                    .entry(*public_key)
+                    .or_default()
+                    .insert(graph_id.clone(), secret_nonces);
+            }
+            if let Some(encrypted_secret_nonces) = graph_data.encrypted_secret_nonces {
+                self.encrypted_secret_nonces
+                    .entry(*public_key)
+                    .or_default()
+                    .insert(graph_id.clone(), encrypted_secret_nonces);
+            }
+        }
+        if role == PrivateDataRole::Operator {
+            if let Some(commitment_secrets) = graph_data.commitment_secrets {
+                self.commitment_secrets
+                    .entry(*public_key)
+                    .or_default()
+                    .insert(graph_id.clone(), commitment_secrets);
+            }
+            if let Some(committed_values) = graph_data.committed_intermediate_values {
+                self.committed_intermediate_values
+                    .entry(*public_key)
+                    .or_default()
+                    .insert(graph_id, committed_values);
+            }
+        }
+    }
+
+    // Moves the saved secret nonces of the verifier key into `secret_nonces`. Returns the graphs
+    // whose nonces could not be decrypted, they stay as saved.
+    pub fn decrypt_secret_nonces(&mut self, keypair: &Keypair) -> Vec<(String, String)> {
+        let public_key = PublicKey::new(keypair.public_key());
+        let Some(graphs) = self.encrypted_secret_nonces.remove(&public_key) else {
+            return vec![];
+        };
+
+        let mut failures = vec![];
+        let mut undecrypted = HashMap::new();
+        for (graph_id, encrypted_secret_nonces) in graphs {
+            match encrypted_secret_nonces.decrypt(keypair, &graph_id) {
+                Ok(secret_nonces) => {
+                    self.secret_nonces
+                        .entry(public_key)
+                        .or_default()
+                        .insert(graph_id, secret_nonces);
+                }
+                Err(e) => {
+                    failures.push((graph_id.clone(), e));
+                    undecrypted.insert(graph_id, encrypted_secret_nonces);
+                }
+            }
+        }
+        if !undecrypted.is_empty() {
+            self.encrypted_secret_nonces.insert(public_key, undecrypted);
+        }
+
+        failures
+    }
 }
 
 pub struct BitVMClient {
     pub esplora: AsyncClient,
     pub source_network: Network,
+    destination_network: DestinationNetwork,
 
     depositor_context: Option<DepositorContext>,
     operator_context: Option<OperatorContext>,
@@ -122,15 +391,76 @@ pub struct BitVMClient {
 
     data_store: DataStore,
     data: BitVMClientPublicData,
+    shared_data: SharedClientData,
     latest_processed_file_name: Option<String>,
     remote_file_path: String,
     local_file_path: PathBuf,
+    // Local copy of the public data, see `persist_graphs`
+    graph_store: GraphStore,
 
     private_data: BitVMClientPrivateData,
 
     chain_service: Chain,
 
     zkproof_verifying_key: Option<ZkProofVerifyingKey>,
+
+    merkle_root_verification: MerkleRootVerification,
+
+    peg_in_processing_config: PegInProcessingConfig,
+
+    challenge_policy: ChallengePolicy,
+    // Defers the operator's non-urgent broadcasts while fees are high, if configured
+    congestion_scheduler: Option<CongestionScheduler>,
+    // Conclusive results only, the assertions cannot change once confirmed
+    disprove_precomputation: DisprovePrecomputation,
+    // Long-running operations queued on worker threads, see `jobs`
+    jobs: JobQueue,
+    // Reports critical events to webhooks and commands, if configured
+    alert_notifier: Option<AlertNotifier>,
+    // Superblock searches of `watch_peg_outs`
+    watchtower: Watchtower,
+    // Deployment policies checked before every broadcast of a graph transaction
+    broadcast_middleware: BroadcastMiddlewareChain,
+
+    // Last seen progress of the withdrawals tracked with `track_withdrawal`
+    tracked_withdrawals: HashMap<GraphId, WithdrawalProgress>,
+    // Last seen stage of every graph, for alerting on transitions, see `alert_graph_transitions`
+    graph_stages: HashMap<GraphId, GraphStage>,
+    // Withdrawals several peg-out graphs were initiated for, by the deposit they are paid out of,
+    // see `resolve_peg_out_races`
+    peg_out_races: HashMap<OutPoint, PegOutRace>,
+
+    // Turns of the verifiers in the signing ceremonies of peg-in graphs, see `ceremony_schedule`
+    ceremony_schedule_config: CeremonyScheduleConfig,
+    ceremony_progress: CeremonyProgressTracker,
+
+    script_diagnostics: bool,
+    witness_scrubbing: bool,
+
+    header_verification: HeaderVerificationConfig,
+    header_chain: Option<HeaderChain>,
+
+    clock: Arc<dyn Clock>,
+    block_producer: Arc<dyn BlockProducer>,
+
+    // Heartbeats are only published once configured, the committee's are read on every sync
+    heartbeat_config: Option<HeartbeatConfig>,
+    last_heartbeat: Option<u64>,
+    committee_heartbeats: CommitteeHeartbeats,
+    shared_heartbeats: SharedCommitteeHeartbeats,
+
+    // Only kept once enabled, updated on every sync
+    event_log: Option<BridgeEventLog>,
+
+    // Flushes are staged for the committee to acknowledge once configured, see `staged_flush`
+    flush_coordination: Option<FlushCoordinationConfig>,
+    staged_flushes: Vec<StagedFlush>,
+    acknowledged_stages: HashSet<String>,
+    // Set while the last push or stage to the data store failed
+    unsaved_changes: bool,
+
+    // Found on the last sync, see `commitment_registry`
+    commitment_key_reuses: Vec<CommitmentKeyReuse>,
 }
 
 impl BitVMClient {
@@ -176,22 +506,56 @@ impl BitVMClient {
             .join(n_of_n_public_key.to_string());
         println!("Using data file path: {}", local_file_path.display());
 
-        let data = BitVMClientPublicData {
-            version: 1,
-            peg_in_graphs: vec![],
-            peg_out_graphs: vec![],
-        };
+        let mut graph_store = GraphStore::new(&local_file_path);
+        let (data, latest_processed_file_name) =
+            match profile_phase("read graph store", || graph_store.load()) {
+                Some((data, state)) => (data, state.latest_processed_file_name),
+                None => (
+                    BitVMClientPublicData {
+                        version: 1,
+                        peg_in_graphs: vec![],
+                        peg_out_graphs: vec![],
+                    },
+                    None,
+                ),
+            };
+
+        let shared_data = new_shared_client_data(&data);
 
-        let data_store = DataStore::new().await;
+        let committee_heartbeats = CommitteeHeartbeats::new(n_of_n_public_keys);
+        let shared_heartbeats = new_shared_committee_heartbeats(&committee_heartbeats);
+
+        let data_store =
+            profile_phase_async("data store setup", DataStore::new(file_path_prefix)).await;
+
+        let mut private_data = profile_phase("read private data", || {
+            get_private_data_from_files(&local_file_path)
+        })
+        .unwrap_or_else(|err| panic!("{err}"));
+        // Secret nonces saved before they were encrypted are rewritten encrypted below
+        let mut unencrypted_nonce_graphs: Vec<String> = vec![];
+        if let Some(verifier) = verifier_context.as_ref() {
+            if let Some(graphs) = private_data
+                .secret_nonces
+                .get(&verifier.verifier_public_key)
+            {
+                unencrypted_nonce_graphs = graphs.keys().cloned().collect();
+            }
+            for (graph_id, e) in private_data.decrypt_secret_nonces(&verifier.verifier_keypair) {
+                eprintln!("Failed to read the secret nonces of graph {graph_id}: {e}");
+            }
+        }
 
-        let private_data =
-            get_private_data_from_file(&get_private_data_file_path(&local_file_path));
+        let esplora = Builder::new(esplora_url.unwrap_or(get_esplora_url(source_network)))
+            .build_async()
+            .expect("Could not build esplora client");
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let block_producer = Arc::new(WaitingBlockProducer::new(esplora.clone(), clock.clone()));
 
-        Self {
-            esplora: Builder::new(esplora_url.unwrap_or(get_esplora_url(source_network)))
-                .build_async()
-                .expect("Could not build esplora client"),
+        let client = Self {
+            esplora,
             source_network,
+            destination_network,
 
             depositor_context,
             operator_context,
@@ -200,9 +564,11 @@ impl BitVMClient {
 
             data_store,
             data,
-            latest_processed_file_name: None,
+            shared_data,
+            latest_processed_file_name,
             remote_file_path,
             local_file_path,
+            graph_store,
 
             private_data,
 
@@ -211,7 +577,69 @@ impl BitVMClient {
             ),
 
             zkproof_verifying_key,
+
+            merkle_root_verification: MerkleRootVerification::default(),
+
+            peg_in_processing_config: PegInProcessingConfig::default(),
+
+            challenge_policy: ChallengePolicy::default(),
+            congestion_scheduler: None,
+            disprove_precomputation: DisprovePrecomputation::default(),
+            jobs: JobQueue::default(),
+            alert_notifier: None,
+            watchtower: Watchtower::default(),
+            broadcast_middleware: BroadcastMiddlewareChain::default(),
+
+            tracked_withdrawals: HashMap::new(),
+            graph_stages: HashMap::new(),
+            peg_out_races: HashMap::new(),
+
+            ceremony_schedule_config: CeremonyScheduleConfig::default(),
+            ceremony_progress: CeremonyProgressTracker::default(),
+
+            script_diagnostics: false,
+            witness_scrubbing: false,
+
+            header_verification: HeaderVerificationConfig::default(),
+            header_chain: None,
+
+            clock,
+            block_producer,
+
+            heartbeat_config: None,
+            last_heartbeat: None,
+            committee_heartbeats,
+            shared_heartbeats,
+
+            event_log: None,
+
+            flush_coordination: None,
+            staged_flushes: vec![],
+            acknowledged_stages: HashSet::new(),
+            unsaved_changes: false,
+
+            commitment_key_reuses: vec![],
+        };
+        if let Some(verifier) = client.verifier_context.as_ref() {
+            for graph_id in unencrypted_nonce_graphs {
+                // Left unencrypted on disk, saving is retried on the next start
+                if let Err(err) = client.save_private_graph_data(
+                    PrivateDataRole::Verifier,
+                    &verifier.verifier_public_key,
+                    &graph_id,
+                ) {
+                    eprintln!("{err}");
+                }
+            }
         }
+
+        client
+    }
+
+    pub fn operator_public_key(&self) -> Option<PublicKey> {
+        self.operator_context
+            .as_ref()
+            .map(|context| context.operator_public_key)
     }
 
     pub fn data(&self) -> &BitVMClientPublicData {
@@ -222,6 +650,34 @@ impl BitVMClient {
         &mut self.data
     }
 
+    pub fn peg_in_graph(&self, peg_in_graph_id: &str) -> Option<&PegInGraph> {
+        self.data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id() == peg_in_graph_id)
+    }
+
+    pub fn peg_in_graph_mut(&mut self, peg_in_graph_id: &str) -> Option<&mut PegInGraph> {
+        self.data
+            .peg_in_graphs
+            .iter_mut()
+            .find(|peg_in_graph| peg_in_graph.id() == peg_in_graph_id)
+    }
+
+    pub fn peg_out_graph(&self, peg_out_graph_id: &str) -> Option<&PegOutGraph> {
+        self.data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == peg_out_graph_id)
+    }
+
+    pub fn peg_out_graph_mut(&mut self, peg_out_graph_id: &str) -> Option<&mut PegOutGraph> {
+        self.data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id() == peg_out_graph_id)
+    }
+
     // TODO: This should be private. Currently used in the fees test. See if it can be refactored.
     pub fn private_data(&self) -> &BitVMClientPrivateData {
         &self.private_data
@@ -232,20 +688,285 @@ impl BitVMClient {
         self.chain_service = chain_service;
     }
 
-    fn save_private_data(&self) {
-        save_local_private_file(&self.local_file_path, &serialize(&self.private_data));
+    // TODO: This fn is only used in tests to resume from checkpoints. Consider refactoring, so it can be removed.
+    pub fn restore_private_data(
+        &mut self,
+        private_data: BitVMClientPrivateData,
+    ) -> Result<(), Error> {
+        self.private_data = private_data;
+        self.save_private_data()
+    }
+
+    pub fn set_merkle_root_verification(
+        &mut self,
+        merkle_root_verification: MerkleRootVerification,
+    ) {
+        self.merkle_root_verification = merkle_root_verification;
+    }
+
+    pub fn set_peg_in_processing_config(
+        &mut self,
+        peg_in_processing_config: PegInProcessingConfig,
+    ) {
+        self.peg_in_processing_config = peg_in_processing_config;
+    }
+
+    pub fn set_challenge_policy(&mut self, challenge_policy: ChallengePolicy) {
+        self.challenge_policy = challenge_policy;
+    }
+
+    pub fn set_ceremony_schedule_config(&mut self, config: CeremonyScheduleConfig) {
+        self.ceremony_schedule_config = config;
+    }
+
+    pub fn set_congestion_config(&mut self, congestion_config: Option<CongestionConfig>) {
+        self.congestion_scheduler = congestion_config.map(CongestionScheduler::new);
+    }
+
+    // Alerts are only raised by `process_peg_outs`, `alert_graph_transitions` and `flush`, see
+    // `AlertEvent`
+    pub fn set_alert_config(&mut self, alert_config: Option<AlertConfig>) {
+        self.alert_notifier = alert_config
+            .filter(|config| config.has_hooks())
+            .map(|config| AlertNotifier::new(config, self.source_network));
+    }
+
+    // Tests use a `MockClock` to move past pre-signing deadlines without waiting for them
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    // Tests on regtest use a `RegtestBlockProducer` to mine blocks on demand instead of waiting
+    // for the block generator
+    pub fn set_block_producer(&mut self, block_producer: Arc<dyn BlockProducer>) {
+        self.block_producer = block_producer;
+    }
+
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+
+    // Enables publishing heartbeats with `publish_heartbeat_if_due`, requires a verifier context
+    pub fn set_heartbeat_config(
+        &mut self,
+        heartbeat_config: Option<HeartbeatConfig>,
+    ) -> Result<(), Error> {
+        if heartbeat_config.is_some() && self.verifier_context.is_none() {
+            return Err(Error::Client(ClientError::VerifierContextNotDefined));
+        }
+        if heartbeat_config.is_some_and(|config| config.beacon)
+            && self.source_network == Network::Bitcoin
+        {
+            return Err(Error::Client(ClientError::HeartbeatBeaconNotAvailable(
+                self.source_network,
+            )));
+        }
+        self.heartbeat_config = heartbeat_config;
+
+        Ok(())
+    }
+
+    // Stages flushes until other committee members acknowledged them instead of writing them to
+    // the shared data right away. Stages of others are only acknowledged with a verifier context.
+    pub fn set_flush_coordination(&mut self, flush_coordination: Option<FlushCoordinationConfig>) {
+        self.flush_coordination = flush_coordination;
+    }
+
+    // Returns the new tip height once the given number of blocks were mined
+    pub async fn produce_blocks(&self, num_blocks: u32) -> Result<u32, Error> {
+        self.block_producer
+            .produce_blocks(num_blocks)
+            .await
+            .map_err(Error::Other)
+    }
+
+    // Re-execute the input scripts locally when a node rejects a tx because of them, and include
+    // the failing input, opcode and stack in the returned error
+    pub fn set_script_diagnostics(&mut self, script_diagnostics: bool) {
+        self.script_diagnostics = script_diagnostics;
+    }
+
+    // Canonicalize the witnesses of graph transactions before they are broadcast, see
+    // `witness_scrubber`. Every script path spend is re-executed locally for it.
+    pub fn set_witness_scrubbing(&mut self, witness_scrubbing: bool) {
+        self.witness_scrubbing = witness_scrubbing;
+    }
+
+    // Middlewares run in the order they were added, see `broadcast_middleware`
+    pub fn add_broadcast_middleware(&mut self, middleware: Box<dyn BroadcastMiddleware>) {
+        self.broadcast_middleware.push(middleware);
+    }
+
+    pub fn set_broadcast_policy(&mut self, config: &BroadcastPolicyConfig) {
+        for middleware in config.middlewares() {
+            self.add_broadcast_middleware(middleware);
+        }
+    }
+
+    // Pays the reimbursement of peg-out graphs created from now on and the rewards of disproves and
+    // timeouts broadcast in automatic mode to the configured destinations. Roles the client has no
+    // context for are ignored.
+    pub fn set_reward_destinations(&mut self, config: &RewardConfig) -> Result<(), Error> {
+        if let (Some(context), Some(destination)) =
+            (self.operator_context.as_mut(), &config.operator)
+        {
+            context.reward_script = parse_reward_destination(self.source_network, destination)
+                .map_err(Error::Validation)?;
+        }
+        if let (Some(context), Some(destination)) =
+            (self.verifier_context.as_mut(), &config.verifier)
+        {
+            context.reward_script = parse_reward_destination(self.source_network, destination)
+                .map_err(Error::Validation)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_verifier(&self) -> bool {
+        self.verifier_context.is_some()
+    }
+
+    // Without it asserted proofs cannot be checked, see `verify_asserted_proof`
+    pub fn has_zkproof_verifying_key(&self) -> bool {
+        self.zkproof_verifying_key.is_some()
+    }
+
+    pub fn verifier_reward_script(&self) -> Option<&ScriptBuf> {
+        self.verifier_context
+            .as_ref()
+            .map(|context| &context.reward_script)
+    }
+
+    // Cross-check the confirmations esplora reports against a locally validated header chain
+    // before broadcasting take 1 or fronting a peg-out
+    pub fn set_header_verification(&mut self, header_verification: HeaderVerificationConfig) {
+        if self.header_verification.checkpoint != header_verification.checkpoint {
+            self.header_chain = None;
+        }
+        self.header_verification = header_verification;
+    }
+
+    fn save_private_data(&self) -> Result<(), Error> {
+        for (role, public_key, graph_id) in self.private_data.graph_keys() {
+            self.save_private_graph_data(role, &public_key, &graph_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Only the file of the graph is written, see `PrivateDataRole`
+    fn save_private_graph_data(
+        &self,
+        role: PrivateDataRole,
+        public_key: &PublicKey,
+        graph_id: &str,
+    ) -> Result<(), Error> {
+        let mut graph_data = self.private_data.graph_data(role, public_key, graph_id);
+        // Secret nonces of the verifier key are only saved encrypted, see `secret_nonces`
+        if let (PrivateDataRole::Verifier, Some(verifier)) = (role, self.verifier_context.as_ref())
+        {
+            if verifier.verifier_public_key == *public_key {
+                if let Some(secret_nonces) = graph_data.secret_nonces.take() {
+                    graph_data.encrypted_secret_nonces = Some(
+                        EncryptedSecretNonces::encrypt(
+                            &verifier.verifier_keypair,
+                            graph_id,
+                            &secret_nonces,
+                        )
+                        .map_err(Error::Other)?,
+                    );
+                }
+            }
+        }
+        save_private_graph_file(
+            &self.local_file_path,
+            role,
+            public_key,
+            graph_id,
+            &serialize(&graph_data),
+        );
+
+        Ok(())
+    }
+
+    // Handle for reading the client state from other tasks, see `BitVMClientReadHandle`.
+    pub fn read_handle(&self) -> BitVMClientReadHandle {
+        BitVMClientReadHandle::new(
+            self.esplora.clone(),
+            self.source_network,
+            self.depositor_context.clone(),
+            self.operator_context.clone(),
+            self.verifier_context.clone(),
+            self.shared_data.clone(),
+            self.shared_heartbeats.clone(),
+        )
+    }
+
+    // Makes the working copy of the public data visible to read handles. Only done at the end of a
+    // sync or flush, so readers never see a half-applied operation. Most syncs change nothing, the
+    // data is only cloned into a new snapshot if it differs from the published one.
+    fn publish_data(&self) {
+        let published_data = self.shared_data.read().unwrap().clone();
+        if *published_data != self.data {
+            *self.shared_data.write().unwrap() = Arc::new(self.data.clone());
+        }
+        let published_heartbeats = self.shared_heartbeats.read().unwrap().clone();
+        if *published_heartbeats != self.committee_heartbeats {
+            *self.shared_heartbeats.write().unwrap() = Arc::new(self.committee_heartbeats.clone());
+        }
     }
 
     pub async fn sync(&mut self) {
-        self.read_from_data_store().await;
+        profile_phase_async("sync with data store", self.read_from_data_store()).await;
+        self.persist_graphs().await;
+        self.report_commitment_key_reuses();
+        self.read_committee_heartbeats().await;
+        self.acknowledge_staged_flushes().await;
+        self.settle_staged_flushes().await;
+        self.update_event_log().await;
+        self.publish_data();
+    }
+
+    // Only the graphs that changed are written, a restarted client continues from the data store
+    // file processed last. Written on a blocking thread, waiting for the lock of a graph file
+    // would otherwise stall the runtime.
+    async fn persist_graphs(&mut self) {
+        let mut graph_store = mem::replace(
+            &mut self.graph_store,
+            GraphStore::new(&self.local_file_path),
+        );
+        let data = self.data.clone();
+        let latest_processed_file_name = self.latest_processed_file_name.clone();
+        let result = spawn_blocking(move || {
+            let written_graphs = graph_store.save(&data, latest_processed_file_name.as_deref());
+            (graph_store, written_graphs)
+        })
+        .await;
+        match result {
+            Ok((graph_store, written_graphs)) => {
+                self.graph_store = graph_store;
+                if written_graphs > 0 {
+                    println!("Saved {written_graphs} changed graphs to the local graph store");
+                }
+            }
+            // The fresh graph store rewrites every graph on the next save
+            Err(e) => eprintln!("Failed to save the local graph store: {e}"),
+        }
     }
 
     pub async fn sync_l2(&mut self) {
         self.read_from_l2().await;
+        self.resolve_peg_out_races().await;
+        self.publish_data();
     }
 
     pub async fn flush(&mut self) {
-        self.save_to_data_store().await;
+        match self.flush_coordination {
+            Some(_) => self.stage_to_data_store().await,
+            None => self.save_to_data_store().await,
+        }
+        self.publish_data();
     }
 
     /*
@@ -274,11 +995,6 @@ impl BitVMClient {
                 let (latest_file, latest_file_name) =
                     self.fetch_latest_valid_file(&mut latest_file_names).await;
                 if latest_file.is_some() && latest_file_name.is_some() {
-                    save_local_public_file(
-                        &self.local_file_path,
-                        latest_file_name.as_ref().unwrap(),
-                        &serialize(&latest_file.as_ref().unwrap()),
-                    );
                     self.latest_processed_file_name = latest_file_name;
 
                     // fetch and process all the previous files if latest valid file exists
@@ -290,7 +1006,10 @@ impl BitVMClient {
                         Err(err) => println!("Error: {}", err),
                     }
 
-                    self.merge_data(latest_file.unwrap()); // merge the latest data at the end
+                    // merge the latest data at the end
+                    if let Err(err) = self.merge_data(latest_file.unwrap()) {
+                        println!("Error: {}", err);
+                    }
                 }
             }
         } else {
@@ -323,6 +1042,120 @@ impl BitVMClient {
         }
     }
 
+    // Finds the withdrawals more than one peg-out graph was initiated for and alerts both sides
+    // once a peg-out of one of them confirmed. Only the winner continues to kick-off, see
+    // `check_peg_out_race`.
+    async fn resolve_peg_out_races(&mut self) {
+        let claims = self.data.peg_out_graphs.iter().filter_map(|graph| {
+            let event = graph.peg_out_chain_event.as_ref()?;
+            Some((
+                event.source_outpoint,
+                PegOutClaim {
+                    graph_id: graph.peg_out_graph_id().clone(),
+                    operator_public_key: *graph.operator_public_key(),
+                    event_timestamp: event.timestamp,
+                    peg_out_height: None,
+                },
+            ))
+        });
+
+        let mut races = HashMap::new();
+        for race in find_peg_out_races(claims) {
+            let mut claims = race.claims;
+            for claim in claims.iter_mut() {
+                let Some(peg_out_txid) = self
+                    .data
+                    .peg_out_graphs
+                    .iter()
+                    .find(|graph| *graph.peg_out_graph_id() == claim.graph_id)
+                    .and_then(|graph| graph.peg_out_transaction.as_ref())
+                    .map(|tx| tx.tx().compute_txid())
+                else {
+                    continue;
+                };
+                // Deciding on a partial view could let a loser kick off, keep the last result
+                match self.esplora.get_tx_status(&peg_out_txid).await {
+                    Ok(status) => claim.peg_out_height = status.block_height,
+                    Err(err) => {
+                        eprintln!(
+                            "Could not check the peg-out of graph {}: {err}",
+                            claim.graph_id
+                        );
+                        return;
+                    }
+                }
+            }
+            races.insert(
+                race.source_outpoint,
+                PegOutRace::new(race.source_outpoint, claims),
+            );
+        }
+
+        for race in races.values() {
+            if !self.peg_out_races.contains_key(&race.source_outpoint) {
+                let graph_ids = race.claims.iter().map(|claim| claim.graph_id.to_string());
+                println!(
+                    "Peg-out graphs {} were initiated for the same withdrawal from {}",
+                    graph_ids.collect::<Vec<_>>().join(", "),
+                    race.source_outpoint
+                );
+            }
+        }
+        self.peg_out_races = races;
+        self.alert_peg_out_races().await;
+    }
+
+    async fn alert_peg_out_races(&mut self) {
+        let mut events = Vec::new();
+        for race in self.peg_out_races.values() {
+            let Some(winner) = race.winner() else {
+                continue;
+            };
+            let is_reported = |claim: &PegOutClaim| {
+                self.verifier_context.is_some()
+                    || self.operator_context.as_ref().is_some_and(|context| {
+                        context.operator_public_key == claim.operator_public_key
+                    })
+            };
+            if is_reported(winner) {
+                events.push(AlertEvent::PegOutRaceWon {
+                    graph_id: winner.graph_id.to_string(),
+                    losers: race
+                        .losers()
+                        .iter()
+                        .map(|claim| claim.graph_id.to_string())
+                        .collect(),
+                });
+            }
+            for loser in race.losers().iter().filter(|claim| is_reported(claim)) {
+                events.push(AlertEvent::PegOutRaceLost {
+                    graph_id: loser.graph_id.to_string(),
+                    winner: winner.graph_id.to_string(),
+                });
+            }
+        }
+        for event in events {
+            self.alert(event).await;
+        }
+    }
+
+    // Fails for a graph that lost the race for its withdrawal, fronting or continuing it would only
+    // cost the operator
+    async fn check_peg_out_race(&mut self, peg_out_graph_id: &PegOutGraphId) -> Result<(), Error> {
+        self.resolve_peg_out_races().await;
+        match self
+            .peg_out_races
+            .values()
+            .find(|race| race.is_loser(peg_out_graph_id))
+            .and_then(PegOutRace::winner)
+        {
+            Some(winner) => Err(Error::Graph(GraphError::PegOutRaceLost {
+                winner: winner.graph_id.to_string(),
+            })),
+            None => Ok(()),
+        }
+    }
+
     async fn get_latest_file_names(
         data_store: &DataStore,
         file_path: Option<&str>,
@@ -411,7 +1244,10 @@ impl BitVMClient {
                 if latest_data.is_some() {
                     // merge the file if the data is valid
                     println!("Merging {} data...", { file_name });
-                    self.merge_data(latest_data.unwrap());
+                    if let Err(err) = self.merge_data(latest_data.unwrap()) {
+                        println!("Failed to merge {}: {}", file_name, err);
+                        continue;
+                    }
                     if latest_valid_file_name.is_none() {
                         latest_valid_file_name = Some(file_name.clone());
                     }
@@ -460,20 +1296,7 @@ impl BitVMClient {
     }
 
     async fn save_to_data_store(&mut self) {
-        // read newly created data before pushing
-        let latest_file_names_result = Self::get_latest_file_names(
-            &self.data_store,
-            Some(&self.remote_file_path),
-            self.latest_processed_file_name.clone(),
-        )
-        .await;
-
-        if latest_file_names_result.is_ok() {
-            let mut latest_file_names = latest_file_names_result.unwrap();
-            latest_file_names.reverse();
-            let latest_valid_file_name = Self::process_files(self, latest_file_names).await;
-            self.latest_processed_file_name = latest_valid_file_name;
-        }
+        self.merge_unprocessed_files().await;
 
         // push data
         self.data.version += 1;
@@ -491,20 +1314,140 @@ impl BitVMClient {
                     human_bytes(contents.len() as f64),
                     human_bytes(size as f64)
                 );
-                save_local_public_file(&self.local_file_path, &file_name, &contents);
                 self.latest_processed_file_name = Some(file_name);
+                self.unsaved_changes = false;
+                self.persist_graphs().await;
+            }
+            Err(err) => {
+                println!("Failed to push: {}", err);
+                self.alert_flush_failure(&err).await;
+                self.unsaved_changes = true;
             }
-            Err(err) => println!("Failed to push: {}", err),
         }
     }
 
-    pub async fn validate_data_by_key(
-        &self,
-        file_name: &str,
-    ) -> (Option<BitVMClientPublicData>, usize, usize) {
+    // Same as `save_to_data_store`, but the data only becomes part of the shared data once the
+    // committee acknowledged it, see `settle_staged_flushes`
+    async fn stage_to_data_store(&mut self) {
+        self.merge_unprocessed_files().await;
+
+        self.data.version += 1;
+
+        let contents = serialize(&self.data);
         let result = self
             .data_store
-            .fetch_compressed_data_by_key(file_name, Some(&self.remote_file_path))
+            .write_compressed_data(
+                &contents.as_bytes().to_vec(),
+                Some(&self.staged_file_path()),
+            )
+            .await;
+        match result {
+            Ok((file_name, size)) => {
+                println!(
+                    "Staged new file: {} (size: {}, compressed: {}), waiting for acknowledgments",
+                    file_name,
+                    human_bytes(contents.len() as f64),
+                    human_bytes(size as f64)
+                );
+                self.staged_flushes
+                    .push(StagedFlush::new(file_name, self.clock.now()));
+                self.unsaved_changes = false;
+            }
+            Err(err) => {
+                println!("Failed to stage: {}", err);
+                self.alert_flush_failure(&err).await;
+                self.unsaved_changes = true;
+            }
+        }
+    }
+
+    // Only the first of consecutive failures is reported, the flush is retried until it succeeds
+    async fn alert_flush_failure(&mut self, err: &str) {
+        if !self.unsaved_changes {
+            self.alert(AlertEvent::FlushFailed {
+                error: err.to_string(),
+            })
+            .await;
+        }
+    }
+
+    async fn merge_unprocessed_files(&mut self) {
+        // read newly created data before pushing
+        let latest_file_names_result = Self::get_latest_file_names(
+            &self.data_store,
+            Some(&self.remote_file_path),
+            self.latest_processed_file_name.clone(),
+        )
+        .await;
+
+        if latest_file_names_result.is_ok() {
+            let mut latest_file_names = latest_file_names_result.unwrap();
+            latest_file_names.reverse();
+            let latest_valid_file_name = Self::process_files(self, latest_file_names).await;
+            self.latest_processed_file_name = latest_valid_file_name;
+        }
+    }
+
+    // Every change of the graph recorded in the data store. The data store keeps one file per
+    // flush, so the resolution of the history is the flushes that touched the graph.
+    pub async fn graph_history(
+        &self,
+        graph_id: &GraphId,
+        until_timestamp: Option<u64>,
+    ) -> Result<Vec<GraphTransition>, Error> {
+        let file_names = self
+            .data_store
+            .get_file_names(Some(&self.remote_file_path))
+            .await
+            .map_err(|e| Error::Client(ClientError::DataStoreUnavailable(e)))?;
+
+        let mut versions = vec![];
+        for file_name in file_names {
+            let Ok(timestamp) = self.data_store.get_file_timestamp(&file_name) else {
+                continue;
+            };
+            if until_timestamp.is_some_and(|until_timestamp| timestamp > until_timestamp) {
+                continue;
+            }
+
+            let content = self
+                .data_store
+                .fetch_compressed_data_by_key(&file_name, Some(&self.remote_file_path))
+                .await
+                .map_err(|e| Error::Client(ClientError::DataStoreUnavailable(e)))?;
+            // Versions are not validated, an invalid one may be what the ceremony got stuck on
+            match content {
+                (Some(content), _) => match try_deserialize_slice(&content) {
+                    Ok(data) => versions.push(DataStoreVersion {
+                        file_name,
+                        timestamp,
+                        data,
+                    }),
+                    Err(e) => eprintln!("Skipping unreadable file {file_name}: {e}"),
+                },
+                (None, _) => eprintln!("Skipping missing file {file_name}"),
+            }
+        }
+
+        Ok(replay_graph_history(graph_id, versions))
+    }
+
+    // State of the graph as of the given time, in milliseconds since the unix epoch
+    pub async fn graph_at(&self, graph_id: &GraphId, timestamp: u64) -> Result<GraphState, Error> {
+        self.graph_history(graph_id, Some(timestamp))
+            .await?
+            .pop()
+            .map(|transition| transition.state)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))
+    }
+
+    pub async fn validate_data_by_key(
+        &self,
+        file_name: &str,
+    ) -> (Option<BitVMClientPublicData>, usize, usize) {
+        let result = self
+            .data_store
+            .fetch_compressed_data_by_key(file_name, Some(&self.remote_file_path))
             .await;
         if result.is_ok() {
             if let (Some(content), encoded_size) = result.unwrap() {
@@ -540,6 +1483,22 @@ impl BitVMClient {
             }
         }
         for peg_out_graph in data.peg_out_graphs.iter() {
+            let committee_result = data
+                .peg_in_graphs
+                .iter()
+                .find(|peg_in_graph| peg_in_graph.id() == &peg_out_graph.peg_in_graph_id)
+                .map_or(Ok(()), |peg_in_graph| {
+                    peg_out_graph.validate_committee(peg_in_graph)
+                });
+            if let Err(err) = committee_result {
+                eprintln!(
+                    "Encountered peg-out graph signed outside of its committee (graph ID: {}), with error: {}",
+                    peg_out_graph.id(),
+                    err,
+                );
+
+                return false;
+            }
             if let Err(err) = peg_out_graph.validate(client).await {
                 eprintln!(
                     "Encountered invalid peg-out graph (graph ID: {}), with error: {}",
@@ -549,6 +1508,20 @@ impl BitVMClient {
 
                 return false;
             }
+            // Fixed fees do not make a graph invalid, they are only reported
+            let report = peg_out_graph.fee_sensitivity();
+            let warnings = fee_sensitivity_warnings(&report, FEE_RATE_WARNING_THRESHOLD);
+            if !warnings.is_empty() {
+                let transactions: Vec<&str> = warnings
+                    .iter()
+                    .map(|sensitivity| sensitivity.transaction)
+                    .collect();
+                eprintln!(
+                    "Peg-out graph (graph ID: {}) has transactions only broadcastable below the warning threshold of {FEE_RATE_WARNING_THRESHOLD} sat/vB: {}",
+                    peg_out_graph.id(),
+                    transactions.join(", "),
+                );
+            }
         }
 
         // println!("All graph data is valid");
@@ -560,7 +1533,7 @@ impl BitVMClient {
     /// # Arguments
     ///
     /// * `data` - Must be valid data verified via `BitVMClient::validate_data()` function
-    pub fn merge_data(&mut self, data: BitVMClientPublicData) {
+    pub fn merge_data(&mut self, data: BitVMClientPublicData) -> Result<(), Error> {
         // peg-in graphs
         let mut peg_in_graphs_by_id: HashMap<String, &mut PegInGraph> = HashMap::new();
         for peg_in_graph in self.data.peg_in_graphs.iter_mut() {
@@ -592,7 +1565,7 @@ impl BitVMClient {
         for peg_out_graph in data.peg_out_graphs.iter() {
             let graph = peg_out_graphs_by_id.get_mut(peg_out_graph.id());
             if let Some(graph) = graph {
-                graph.merge(peg_out_graph);
+                graph.merge(peg_out_graph)?;
             } else {
                 peg_out_graphs_to_add.push(peg_out_graph);
             }
@@ -601,6 +1574,200 @@ impl BitVMClient {
         for graph in peg_out_graphs_to_add.into_iter() {
             self.data.peg_out_graphs.push(graph.clone());
         }
+
+        self.warm_up_legacy_peg_out_graphs();
+
+        Ok(())
+    }
+
+    // Graphs serialized before connector C stored its merkle root need its lock scripts to derive
+    // the connector address. Generating them takes minutes, so it is done once here rather than
+    // inside whichever call needs the address first.
+    fn warm_up_legacy_peg_out_graphs(&mut self) {
+        for graph in self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .filter(|graph| !graph.knows_connector_c_merkle_root())
+        {
+            println!(
+                "Peg-out graph {} was stored without the connector C merkle root, deriving it...",
+                graph.id()
+            );
+            graph.warm_up();
+        }
+    }
+
+    // Loads or generates the connector C lock scripts of the peg-out graphs ahead of time, so
+    // asserting or disproving later does not stall on them
+    pub fn warm_up_peg_out_graphs(&mut self) {
+        for graph in self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .filter(|graph| !graph.is_warm())
+        {
+            println!("Warming up peg-out graph {}...", graph.id());
+            graph.warm_up();
+        }
+    }
+
+    pub fn warm_up_peg_out_graph(&mut self, peg_out_graph_id: &PegOutGraphId) -> Result<(), Error> {
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        if !graph.is_warm() {
+            println!("Warming up peg-out graph {}...", graph.id());
+            graph.warm_up();
+        }
+        Ok(())
+    }
+
+    // Generates the connector C lock scripts of a cold peg-out graph on a worker thread, see
+    // `warm_up_peg_out_graphs`. None if the graph is warm already.
+    pub fn queue_lock_scripts(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Option<JobId>, Error> {
+        let graph = self.peg_out_graph(peg_out_graph_id).ok_or_else(|| {
+            Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.to_string(),
+            ))
+        })?;
+        if graph.is_warm() {
+            return Ok(None);
+        }
+
+        let mut graph = graph.clone();
+        Ok(Some(self.jobs.submit(
+            JobKind::GenerateLockScripts,
+            peg_out_graph_id,
+            move || {
+                graph.warm_up();
+                Ok(JobOutput::LockScripts)
+            },
+        )))
+    }
+
+    // Writes the connector C lock scripts of the graph to `file_path`, for the other members to
+    // import instead of generating them. Returns their merkle root.
+    pub fn export_lock_scripts(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+        file_path: &Path,
+    ) -> Result<TapNodeHash, Error> {
+        self.peg_out_graph(peg_out_graph_id)
+            .ok_or_else(|| {
+                Error::Client(ClientError::PegOutGraphNotFound(
+                    peg_out_graph_id.to_string(),
+                ))
+            })?
+            .export_lock_scripts(file_path)
+            .map_err(|e| Error::Other(format!("Failed to export lock scripts: {e}")))
+    }
+
+    // Caches exported lock scripts for the peg-out graph whose connector C they were generated
+    // for, once they are verified against its merkle root. Returns the id of the graph.
+    pub fn import_lock_scripts(&self, file_path: &Path) -> Result<PegOutGraphId, Error> {
+        // Only used to find the graph, the scripts are checked against the root of the graph
+        let merkle_root = LockScriptsFile::open(file_path)
+            .map_err(|e| Error::Chunker(ChunkerError::InvalidLockScripts(e.to_string())))?
+            .merkle_root();
+        let graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|graph| graph.connector_c_taproot_merkle_root() == Some(merkle_root))
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "No peg-out graph has a connector C with merkle root {merkle_root}"
+                ))
+            })?;
+        graph
+            .import_lock_scripts(file_path)
+            .map_err(Error::Chunker)?;
+
+        Ok(graph.peg_out_graph_id().clone())
+    }
+
+    // Shares the connector C lock scripts of the graph through the data store, under their merkle
+    // root. Operators publish them once, verifiers then fetch them with `fetch_lock_scripts`.
+    pub async fn publish_lock_scripts(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<TapNodeHash, Error> {
+        let export_path = lock_scripts_transfer_path(peg_out_graph_id);
+        let merkle_root = self.export_lock_scripts(peg_out_graph_id, &export_path)?;
+        let contents = std::fs::read(&export_path);
+        let _ = std::fs::remove_file(&export_path);
+        let contents = contents.map_err(|e| Error::Other(e.to_string()))?;
+
+        let size = self
+            .data_store
+            .write_compressed_data_by_key(
+                &shared_lock_scripts_file_name(&merkle_root),
+                &contents,
+                Some(&self.lock_scripts_file_path()),
+            )
+            .await
+            .map_err(|e| Error::Client(ClientError::DataStoreUnavailable(e)))?;
+        println!(
+            "Published connector C lock scripts of peg-out graph {peg_out_graph_id} ({})",
+            human_bytes(size as f64)
+        );
+
+        Ok(merkle_root)
+    }
+
+    // Imports the lock scripts published for the connector C of the graph, unless they are cached
+    // already. Returns whether lock scripts were imported, false if none were published.
+    pub async fn fetch_lock_scripts(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<bool, Error> {
+        let graph = self.peg_out_graph(peg_out_graph_id).ok_or_else(|| {
+            Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.to_string(),
+            ))
+        })?;
+        if graph.is_warm() {
+            return Ok(false);
+        }
+        let Some(merkle_root) = graph.connector_c_taproot_merkle_root() else {
+            return Ok(false);
+        };
+
+        let (contents, _) = self
+            .data_store
+            .fetch_compressed_data_by_key(
+                &shared_lock_scripts_file_name(&merkle_root),
+                Some(&self.lock_scripts_file_path()),
+            )
+            .await
+            .map_err(|e| Error::Client(ClientError::DataStoreUnavailable(e)))?;
+        let Some(contents) = contents else {
+            return Ok(false);
+        };
+
+        // Lock scripts files are memory-mapped, so the download is written to disk first
+        let import_path = lock_scripts_transfer_path(peg_out_graph_id);
+        let imported = std::fs::create_dir_all(cache_directory())
+            .and_then(|_| std::fs::write(&import_path, contents))
+            .map_err(|e| Error::Other(e.to_string()))
+            .and_then(|_| {
+                graph
+                    .import_lock_scripts(&import_path)
+                    .map_err(Error::Chunker)
+            });
+        let _ = std::fs::remove_file(&import_path);
+        imported?;
+
+        Ok(true)
+    }
+
+    fn lock_scripts_file_path(&self) -> String {
+        format!(
+            "{}/{SHARED_LOCK_SCRIPTS_DIRECTORY_NAME}",
+            self.remote_file_path
+        )
     }
 
     // fn process(&self) {
@@ -629,80 +1796,482 @@ impl BitVMClient {
     // }
 
     pub async fn status(&self) {
-        if self.depositor_context.is_some() {
-            self.depositor_status().await;
+        self.queries().status().await;
+        self.committee_heartbeats.print_liveness(self.clock.now());
+        self.print_staged_flushes();
+    }
+
+    // Last seen time of every committee member, as of the latest sync
+    pub fn committee_liveness(&self) -> Vec<MemberLiveness> {
+        self.committee_heartbeats.liveness(self.clock.now())
+    }
+
+    // Publishes a heartbeat if none was published by this client within the configured interval
+    pub async fn publish_heartbeat_if_due(&mut self) {
+        let Some(config) = self.heartbeat_config else {
+            return;
+        };
+        if self
+            .last_heartbeat
+            .is_some_and(|last_heartbeat| self.clock.now() < last_heartbeat + config.interval)
+        {
+            return;
         }
-        if self.operator_context.is_some() {
-            self.operator_status().await;
+        if let Err(err) = self.publish_heartbeat().await {
+            eprintln!("Failed to publish heartbeat: {err}");
         }
-        if self.verifier_context.is_some() {
-            self.verifier_status().await;
+    }
+
+    pub async fn publish_heartbeat(&mut self) -> Result<HeartbeatRecord, Error> {
+        let config = self.heartbeat_config.unwrap_or_default();
+        let keypair = match &self.verifier_context {
+            Some(context) => context.verifier_keypair,
+            None => return Err(Error::Client(ClientError::VerifierContextNotDefined)),
+        };
+        let timestamp = self.clock.now();
+
+        // The beacon is best effort, a member without funds still publishes its heartbeat
+        let mut beacon_txid = None;
+        if config.beacon {
+            match self.broadcast_heartbeat_beacon(&keypair, timestamp).await {
+                Ok(txid) => beacon_txid = Some(txid),
+                Err(err) => eprintln!("Heartbeat beacon not published: {err}"),
+            }
         }
+
+        let record = HeartbeatRecord::new(&keypair, timestamp, config.interval, beacon_txid);
+        self.data_store
+            .write_data_by_key(
+                &heartbeat_file_name(&record.public_key),
+                &serialize(&record),
+                Some(&self.heartbeat_file_path()),
+            )
+            .await
+            .map_err(|e| Error::Client(ClientError::DataStoreUnavailable(e)))?;
+        self.last_heartbeat = Some(timestamp);
+        self.committee_heartbeats.insert(record.clone());
+        self.publish_data();
+
+        Ok(record)
     }
 
-    async fn depositor_status(&self) {
-        if self.depositor_context.is_none() {
-            panic!("Depositor context must be initialized");
+    async fn broadcast_heartbeat_beacon(
+        &self,
+        keypair: &Keypair,
+        timestamp: u64,
+    ) -> Result<Txid, String> {
+        let address =
+            generate_p2wpkh_address(self.source_network, &PublicKey::new(keypair.public_key()));
+        let utxo = self
+            .esplora
+            .get_address_utxo(address.clone())
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .max_by_key(|utxo| utxo.value)
+            .ok_or_else(|| format!("{address} holds no funds"))?;
+        let input = Input {
+            outpoint: OutPoint {
+                txid: utxo.txid,
+                vout: utxo.vout,
+            },
+            amount: utxo.value,
+        };
+        let tx = generate_heartbeat_beacon_tx(self.source_network, keypair, &input, timestamp)?;
+        self.broadcast_tx_unaudited(&tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(tx.compute_txid())
+    }
+
+    async fn read_committee_heartbeats(&mut self) {
+        let file_path = self.heartbeat_file_path();
+        for public_key in self.committee_heartbeats.committee.clone() {
+            let Ok(Some(contents)) = self
+                .data_store
+                .fetch_optional_data_by_key(&heartbeat_file_name(&public_key), Some(&file_path))
+                .await
+            else {
+                continue;
+            };
+            match try_deserialize::<HeartbeatRecord>(&contents) {
+                // Records published under another member's name or with a bad signature are ignored
+                Ok(record) if record.public_key == public_key => {
+                    self.committee_heartbeats.insert(record);
+                }
+                Ok(_) => eprintln!("Heartbeat file of {public_key} holds another member's record"),
+                Err(err) => eprintln!("Unreadable heartbeat of {public_key}: {err}"),
+            }
         }
+    }
 
-        let depositor_public_key = &self
-            .depositor_context
+    fn heartbeat_file_path(&self) -> String {
+        format!("{}/{HEARTBEAT_DIRECTORY_NAME}", self.remote_file_path)
+    }
+
+    // Whether the last flush failed to reach the data store, so its changes are only in memory
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
+
+    // Flushes of this client that were not promoted to the shared data, pending ones included
+    pub fn staged_flushes(&self) -> &[StagedFlush] {
+        &self.staged_flushes
+    }
+
+    // Rejected and expired stages stay listed, their changes are only in the local data
+    fn print_staged_flushes(&self) {
+        if self.staged_flushes.is_empty() {
+            return;
+        }
+        let staged_flushes = self
+            .staged_flushes
+            .iter()
+            .map(|staged_flush| format!("  {staged_flush}"))
+            .collect::<Vec<_>>();
+        println!(
+            "[STAGED FLUSHES]: Not part of the shared data yet\n{}\n",
+            staged_flushes.join("\n")
+        );
+    }
+
+    // Validates the flushes other committee members staged and publishes a signed acknowledgment
+    // for each, approving or rejecting it. Every stage is acknowledged once.
+    async fn acknowledge_staged_flushes(&mut self) {
+        let (Some(config), Some(context)) = (self.flush_coordination, &self.verifier_context)
+        else {
+            return;
+        };
+        let keypair = context.verifier_keypair;
+        let staged_file_path = self.staged_file_path();
+        let stages = match self
+            .data_store
+            .get_file_names(Some(&staged_file_path))
+            .await
+        {
+            Ok(stages) => stages,
+            Err(err) => {
+                eprintln!("Failed to list staged flushes: {err}");
+                return;
+            }
+        };
+
+        let now = self.clock.now();
+        for stage in stages {
+            if self.acknowledged_stages.contains(&stage)
+                || self
+                    .staged_flushes
+                    .iter()
+                    .any(|staged_flush| staged_flush.stage == stage)
+            {
+                continue;
+            }
+            // Expired stages are not promoted anymore, there is nothing to acknowledge
+            let Ok(stage_timestamp) = self.data_store.get_file_timestamp(&stage) else {
+                continue;
+            };
+            if stage_timestamp / 1000 + config.expiry <= now {
+                continue;
+            }
+
+            // Left for the next sync if the stage cannot be fetched, it is not the author's fault
+            let content = match self
+                .data_store
+                .fetch_compressed_data_by_key(&stage, Some(&staged_file_path))
+                .await
+            {
+                Ok((Some(content), _)) => content,
+                Ok((None, _)) => continue,
+                Err(err) => {
+                    eprintln!("Failed to fetch staged flush {stage}: {err}");
+                    continue;
+                }
+            };
+            let rejection = self.validate_staged_data(&content).await.err();
+
+            let acknowledgment = FlushAcknowledgment::new(&keypair, &stage, now, rejection);
+            let result = self
+                .data_store
+                .write_data_by_key(
+                    &flush_acknowledgment_file_name(stage_timestamp, &acknowledgment.public_key),
+                    &serialize(&acknowledgment),
+                    Some(&self.flush_acknowledgment_file_path()),
+                )
+                .await;
+            match (result, &acknowledgment.rejection) {
+                (Ok(()), None) => println!("Approved staged flush {stage}"),
+                (Ok(()), Some(reason)) => eprintln!("Rejected staged flush {stage}: {reason}"),
+                (Err(err), _) => {
+                    eprintln!("Failed to acknowledge staged flush {stage}: {err}");
+                    continue;
+                }
+            }
+            self.acknowledged_stages.insert(stage);
+        }
+    }
+
+    // Why the staged data must not become part of the shared data, if anything. The graphs are
+    // validated as on every sync, and must be a legal continuation of the ones held locally.
+    async fn validate_staged_data(&self, content: &[u8]) -> Result<(), String> {
+        let data = try_deserialize_slice::<BitVMClientPublicData>(content)
+            .map_err(|e| format!("Unreadable data: {e}"))?;
+        if !Self::validate_data(&self.esplora, &data).await {
+            return Err(String::from("Invalid graph data"));
+        }
+
+        validate_transition(&self.data, &data).map_err(|e| e.to_string())
+    }
+
+    // Promotes the stages of this client that enough committee members approved, and gives up
+    // the ones that were rejected or expired
+    async fn settle_staged_flushes(&mut self) {
+        let Some(config) = self.flush_coordination else {
+            return;
+        };
+        let own_public_key = self
+            .verifier_context
             .as_ref()
-            .unwrap()
-            .depositor_public_key;
-        for peg_in_graph in self.data.peg_in_graphs.iter() {
-            if peg_in_graph.depositor_public_key.eq(depositor_public_key) {
-                let status = peg_in_graph.depositor_status(&self.esplora).await;
-                println!(
-                    "[DEPOSITOR]: Peg-in graph ID: {} status: {}\n",
-                    peg_in_graph.id(),
-                    status
-                );
+            .map(|context| context.verifier_public_key);
+        let committee = self.committee_heartbeats.committee.clone();
+        let staged_file_path = self.staged_file_path();
+        let acknowledgment_file_path = self.flush_acknowledgment_file_path();
+        let now = self.clock.now();
+
+        let mut promoted_stages = vec![];
+        for staged_flush in self
+            .staged_flushes
+            .iter_mut()
+            .filter(|staged_flush| staged_flush.is_pending())
+        {
+            let stage = staged_flush.stage.clone();
+            let Ok(stage_timestamp) = self.data_store.get_file_timestamp(&stage) else {
+                continue;
+            };
+
+            let mut acknowledgments = vec![];
+            for public_key in committee
+                .iter()
+                .filter(|public_key| Some(**public_key) != own_public_key)
+            {
+                let Ok(Some(contents)) = self
+                    .data_store
+                    .fetch_optional_data_by_key(
+                        &flush_acknowledgment_file_name(stage_timestamp, public_key),
+                        Some(&acknowledgment_file_path),
+                    )
+                    .await
+                else {
+                    continue;
+                };
+                match try_deserialize::<FlushAcknowledgment>(&contents) {
+                    // Acknowledgments published under another member's name, for another stage or
+                    // with a bad signature are ignored
+                    Ok(acknowledgment)
+                        if acknowledgment.public_key == *public_key
+                            && acknowledgment.stage == stage
+                            && acknowledgment.verify_signature() =>
+                    {
+                        acknowledgments.push(acknowledgment)
+                    }
+                    Ok(_) => eprintln!("Invalid acknowledgment of {public_key} for {stage}"),
+                    Err(err) => eprintln!("Unreadable acknowledgment of {public_key}: {err}"),
+                }
+            }
+
+            if !staged_flush.record_acknowledgments(&acknowledgments, &config, now) {
+                if matches!(
+                    staged_flush.status,
+                    StagedFlushStatus::Rejected(_) | StagedFlushStatus::Unacknowledged
+                ) {
+                    eprintln!("Staged flush not promoted: {staged_flush}");
+                }
+                continue;
+            }
+            match Self::promote_staged_flush(
+                &self.data_store,
+                &stage,
+                &staged_file_path,
+                &self.remote_file_path,
+            )
+            .await
+            {
+                Ok(file_name) => {
+                    println!("Promoted staged flush {stage} to {file_name}");
+                    promoted_stages.push(stage);
+                }
+                Err(err) => eprintln!("Failed to promote staged flush {stage}: {err}"),
             }
         }
+
+        self.staged_flushes
+            .retain(|staged_flush| !promoted_stages.contains(&staged_flush.stage));
     }
 
-    async fn operator_status(&self) {
-        if self.operator_context.is_none() {
-            panic!("Operator context must be initialized");
+    // The shared data gets the stage as the committee acknowledged it, local changes made since
+    // are part of later flushes
+    async fn promote_staged_flush(
+        data_store: &DataStore,
+        stage: &str,
+        staged_file_path: &str,
+        remote_file_path: &str,
+    ) -> Result<String, String> {
+        let (Some(content), _) = data_store
+            .fetch_compressed_data_by_key(stage, Some(staged_file_path))
+            .await?
+        else {
+            return Err(String::from("Staged data not found"));
+        };
+        let (file_name, _) = data_store
+            .write_compressed_data(&content, Some(remote_file_path))
+            .await?;
+
+        Ok(file_name)
+    }
+
+    fn staged_file_path(&self) -> String {
+        format!("{}{STAGED_FLUSH_DIRECTORY_SUFFIX}", self.remote_file_path)
+    }
+
+    fn flush_acknowledgment_file_path(&self) -> String {
+        format!(
+            "{}/{FLUSH_ACKNOWLEDGMENT_DIRECTORY_NAME}",
+            self.staged_file_path()
+        )
+    }
+
+    // Keeps a block anchored log of the confirmed graph transactions for destination chain
+    // relayers, see `BridgeEventLog`. Resumes the log saved in the local data directory.
+    pub fn enable_event_log(&mut self) {
+        if self.event_log.is_some() {
+            return;
+        }
+        let file_path = self.local_file_path.join(EVENT_LOG_FILE_NAME);
+        self.event_log = Some(
+            BridgeEventLog::read_from_file(&file_path)
+                .filter(|event_log| event_log.network() == self.source_network)
+                .unwrap_or_else(|| BridgeEventLog::new(self.source_network)),
+        );
+    }
+
+    pub fn event_log(&self) -> Option<&BridgeEventLog> {
+        self.event_log.as_ref()
+    }
+
+    // Events mined at or above `from_height`, empty unless the event log is enabled
+    pub fn export_event_log(&self, from_height: u32) -> Vec<BridgeEvent> {
+        self.event_log
+            .as_ref()
+            .map(|event_log| event_log.events_from(from_height).to_vec())
+            .unwrap_or_default()
+    }
+
+    async fn update_event_log(&mut self) {
+        let Some(event_log) = self.event_log.as_mut() else {
+            return;
+        };
+        let candidates = event_candidates(&self.data.peg_in_graphs, &self.data.peg_out_graphs);
+        let new_events = match event_log.update(&self.esplora, &candidates).await {
+            Ok(new_events) => new_events,
+            Err(err) => {
+                eprintln!("Failed to update the event log: {err:?}");
+                return;
+            }
+        };
+
+        // Newly confirmed transactions this client broadcast are checked against the audit file
+        let records = match read_broadcast_audit_records(&self.local_file_path) {
+            Ok((records, _)) => records,
+            Err(err) => {
+                eprintln!("Failed to read the broadcast audit file: {err}");
+                vec![]
+            }
+        };
+        let mut witness_mismatches = Vec::new();
+        for event in new_events
+            .iter()
+            .filter(|event| records.iter().any(|record| record.txid == event.txid))
+        {
+            match self
+                .find_witness_mismatch(&records, &event.txid, event.block_hash)
+                .await
+            {
+                Ok(Some(witness_mismatch)) => {
+                    eprintln!("Witness mismatch: {witness_mismatch}");
+                    witness_mismatches.push(witness_mismatch);
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("Failed to check the witness of {}: {err:?}", event.txid),
+            }
         }
 
-        let mut peg_out_graphs_by_id: HashMap<&String, &PegOutGraph> = HashMap::new();
-        for peg_out_graph in self.data.peg_out_graphs.iter() {
-            peg_out_graphs_by_id.insert(peg_out_graph.id(), peg_out_graph);
+        let event_log = self.event_log.as_mut().unwrap();
+        for witness_mismatch in witness_mismatches {
+            event_log.record_witness_mismatch(witness_mismatch);
         }
+        event_log.save_to_file(&self.local_file_path.join(EVENT_LOG_FILE_NAME));
+    }
 
-        let operator_public_key = &self.operator_context.as_ref().unwrap().operator_public_key;
-        for peg_in_graph in self.data.peg_in_graphs.iter() {
-            let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
-            if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
-                println!(
-                    "[OPERATOR]: Peg-in graph ID: {} status: Missing peg out graph.\n",
-                    peg_in_graph.id() // TODO update this to ask the operator to create a new peg out graph
-                );
-            } else {
-                let peg_out_graph = peg_out_graphs_by_id.get(&peg_out_graph_id).unwrap();
-                let status = peg_out_graph.operator_status(&self.esplora).await;
-                println!(
-                    "[OPERATOR]: Peg-out graph ID: {} status: {}\n",
-                    peg_out_graph.id(),
-                    status
-                );
+    // Watch-only view for withdrawers: finds the peg-out graphs requested by the given destination
+    // chain address, e.g. an EVM address, or destination address and reports how far the operator
+    // got. Emits an event for every withdrawal seen for the first time or that advanced since the
+    // last call, new confirmations of the peg-out transaction alone do not emit an event.
+    pub async fn track_withdrawal(&mut self, address: &str) -> Result<Vec<WithdrawalEvent>, Error> {
+        let mut events = Vec::new();
+        for progress in self.queries().withdrawal_progress(address).await? {
+            let previous = self.tracked_withdrawals.get(&progress.graph_id).cloned();
+            if previous
+                .as_ref()
+                .is_none_or(|previous| progress.has_advanced_from(previous))
+            {
+                events.push(WithdrawalEvent {
+                    previous,
+                    progress: progress.clone(),
+                });
             }
+            self.tracked_withdrawals
+                .insert(progress.graph_id.clone(), progress);
+        }
+
+        Ok(events)
+    }
+
+    pub async fn withdrawal_progress(
+        &self,
+        address: &str,
+    ) -> Result<Vec<WithdrawalProgress>, Error> {
+        self.queries().withdrawal_progress(address).await
+    }
+
+    pub async fn list_graphs(&self, filter: &GraphListFilter) -> Result<Vec<GraphSummary>, Error> {
+        self.queries().list_graphs(filter).await
+    }
+
+    // Read-only view of the working copy of the public data, see `ClientQueries`.
+    fn queries(&self) -> ClientQueries<'_> {
+        ClientQueries {
+            esplora: &self.esplora,
+            data: &self.data,
+            depositor_context: self.depositor_context.as_ref(),
+            operator_context: self.operator_context.as_ref(),
+            verifier_context: self.verifier_context.as_ref(),
         }
     }
 
     // TODO: refactor, see note on self.process_peg_in_as_verifier
-    pub async fn process_peg_in_as_depositor(&mut self, peg_in_graph_id: &GraphId) {
+    // Returns whether an action was taken, the same goes for the other process_peg_in_as_* methods
+    pub async fn process_peg_in_as_depositor(&mut self, peg_in_graph_id: &PegInGraphId) -> bool {
         if self.depositor_context.is_some() {
             if let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) {
                 let status = peg_in_graph.depositor_status(&self.esplora).await;
                 match status {
                     PegInDepositorStatus::PegInDepositWait => {
                         let _ = self.broadcast_peg_in_deposit(peg_in_graph_id).await;
+                        return true;
                     }
                     PegInDepositorStatus::PegInConfirmWait => {
                         let _ = self.broadcast_peg_in_confirm(peg_in_graph_id).await;
+                        return true;
                     }
                     _ => {
                         println!("Peg-in graph {} is in status: {}", peg_in_graph_id, status);
@@ -710,37 +2279,53 @@ impl BitVMClient {
                 }
             }
         }
+
+        false
     }
 
     // TODO: refactor series of method e.g. process_*_as_* to return Result in order to properly handle internal errors
-    pub async fn process_peg_in_as_verifier(&mut self, peg_in_graph_id: &GraphId) {
+    pub async fn process_peg_in_as_verifier(&mut self, peg_in_graph_id: &PegInGraphId) -> bool {
         if let Some(ref context) = self.verifier_context {
             if let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) {
                 let peg_outs_for_this_peg_in = self
                     .data
                     .peg_out_graphs
                     .iter()
-                    .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
+                    .filter(|peg_out| {
+                        peg_in_graph
+                            .peg_out_graphs
+                            .contains(peg_out.peg_out_graph_id())
+                    })
                     .collect::<Vec<_>>();
                 let status = peg_in_graph
                     .verifier_status(&self.esplora, context, &peg_outs_for_this_peg_in)
                     .await;
+                self.check_ceremony_progress(peg_in_graph_id, &status).await;
                 match status {
                     PegInVerifierStatus::PendingOurNonces(graph_ids) => {
+                        if !self.is_ceremony_turn(peg_in_graph_id, CeremonyPhase::Nonces) {
+                            return false;
+                        }
                         println!("Pushing nonces for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
                             self.push_verifier_nonces(&graph_id);
                         }
+                        return true;
                     }
                     PegInVerifierStatus::PendingOurSignature(graph_ids) => {
+                        if !self.is_ceremony_turn(peg_in_graph_id, CeremonyPhase::Signatures) {
+                            return false;
+                        }
                         println!("Pushing signature for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
                             self.push_verifier_signature(&graph_id);
                         }
+                        return true;
                     }
                     PegInVerifierStatus::ReadyToSubmit => {
                         println!("Broadcasting peg-in confirm");
                         let _ = self.broadcast_peg_in_confirm(peg_in_graph_id).await;
+                        return true;
                     }
                     _ => {
                         // nothing to do
@@ -748,10 +2333,110 @@ impl BitVMClient {
                 }
             }
         }
+
+        false
+    }
+
+    // Turns of the committee in the signing ceremony of the peg-in confirm, None for graphs that
+    // are not known
+    pub fn ceremony_schedule(&self, peg_in_graph_id: &PegInGraphId) -> Option<CeremonySchedule> {
+        let peg_in_graph = self.get_peg_in_graph(peg_in_graph_id).ok()?;
+
+        // Graphs serialized before the pre-signing window was recorded count turns from the epoch
+        Some(CeremonySchedule::new(
+            peg_in_graph.id(),
+            peg_in_graph.n_of_n_public_keys(),
+            peg_in_graph.presigning_window().created_at,
+            &self.ceremony_schedule_config,
+        ))
+    }
+
+    // Whether this verifier contributes to the ceremony now or leaves it to the members ahead of
+    // it, see `CeremonySchedule::turn`. Checked again on the next sync.
+    fn is_ceremony_turn(&self, peg_in_graph_id: &PegInGraphId, phase: CeremonyPhase) -> bool {
+        let (Some(verifier), Some(schedule), Some(progress)) = (
+            self.verifier_context.as_ref(),
+            self.ceremony_schedule(peg_in_graph_id),
+            self.get_peg_in_graph(peg_in_graph_id)
+                .ok()
+                .and_then(PegInGraph::signing_progress),
+        ) else {
+            return true;
+        };
+        let pending = match phase {
+            CeremonyPhase::Nonces => &progress.pending_nonces,
+            CeremonyPhase::Signatures => &progress.pending_signatures,
+        };
+
+        let now = self.clock.now();
+        match schedule.turn(&verifier.verifier_public_key, pending, now) {
+            CeremonyTurn::Ours => true,
+            CeremonyTurn::Waiting { ahead, turn_at } => {
+                let ahead = ahead.iter().map(PublicKey::to_string).collect::<Vec<_>>();
+                println!(
+                    "Waiting for {} to contribute {phase} to peg-in graph {peg_in_graph_id} first, our turn comes in {}s",
+                    ahead.join(", "),
+                    turn_at.saturating_sub(now)
+                );
+                false
+            }
+        }
+    }
+
+    // Reports the ceremony of the peg-in graph once nobody contributed to it for
+    // `deadlock_rounds` rounds of turns. Without a coordinator to notice, the committee would
+    // otherwise wait until the pre-signing deadline.
+    async fn check_ceremony_progress(
+        &mut self,
+        peg_in_graph_id: &PegInGraphId,
+        status: &PegInVerifierStatus,
+    ) {
+        let in_ceremony = matches!(
+            status,
+            PegInVerifierStatus::PendingOurNonces(_)
+                | PegInVerifierStatus::AwaitingNonces
+                | PegInVerifierStatus::PendingOurSignature(_)
+                | PegInVerifierStatus::AwaitingSignatures
+        );
+        let progress = self
+            .get_peg_in_graph(peg_in_graph_id)
+            .ok()
+            .and_then(PegInGraph::signing_progress);
+        let (Some(progress), Some(schedule)) = (progress, self.ceremony_schedule(peg_in_graph_id))
+        else {
+            self.ceremony_progress.forget(peg_in_graph_id);
+            return;
+        };
+        if !in_ceremony {
+            self.ceremony_progress.forget(peg_in_graph_id);
+            return;
+        }
+
+        let contributions = progress.nonces.len() + progress.signatures.len();
+        let stalled_secs =
+            self.ceremony_progress
+                .observe(peg_in_graph_id, contributions, self.clock.now());
+        if !schedule.is_deadlocked(stalled_secs, &self.ceremony_schedule_config) {
+            return;
+        }
+
+        let pending = if progress.pending_nonces.is_empty() {
+            progress.pending_signatures
+        } else {
+            progress.pending_nonces
+        };
+        eprintln!(
+            "Signing ceremony of peg-in graph {peg_in_graph_id} made no progress for {stalled_secs}s"
+        );
+        self.alert(AlertEvent::CeremonyDeadlocked {
+            graph_id: peg_in_graph_id.to_string(),
+            pending,
+        })
+        .await;
     }
 
     // TODO: refactor, see note on self.process_peg_in_as_verifier
-    pub async fn process_peg_in_as_operator(&mut self, peg_in_graph_id: &GraphId) {
+    pub async fn process_peg_in_as_operator(&mut self, peg_in_graph_id: &PegInGraphId) -> bool {
         if let Some(ref context) = self.operator_context {
             if let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) {
                 let peg_out_graph_id =
@@ -760,11 +2445,14 @@ impl BitVMClient {
                     .peg_out_graphs
                     .iter()
                     .any(|x| x == &peg_out_graph_id)
+                    && !peg_in_graph
+                        .is_abandoned(peg_in_graph.n_of_n_public_keys(), self.clock.now())
                 {
+                    let parameters = peg_in_graph.template().parameters();
                     let deposit_amount =
                         peg_in_graph.peg_in_deposit_transaction.tx().output[0].value;
-                    let reward_amount = deposit_amount * REWARD_MULTIPLIER / REWARD_PRECISION;
-                    let expected_peg_out_confirm_amount = reward_amount.to_sat() + PEG_OUT_FEE;
+                    let expected_peg_out_confirm_amount =
+                        peg_out_confirm_funding(deposit_amount, &parameters).to_sat();
                     let input = {
                         // todo: don't use a random address
                         let address = generate_pay_to_pubkey_script_address(
@@ -790,122 +2478,615 @@ impl BitVMClient {
                             },
                         }
                     };
-                    self.create_peg_out_graph(
-                        peg_in_graph_id,
-                        input,
-                        CommitmentMessageId::generate_commitment_secrets(),
-                    );
+                    self.create_peg_out_graph(peg_in_graph_id, input, parameters);
+                    return true;
                 }
             }
         }
+
+        false
     }
 
+    // Processes peg-in graphs by priority, see `PegInProcessingConfig`, so that graphs close to
+    // their refund timeout are handled first and a large backlog is worked off predictably.
     pub async fn process_peg_ins(&mut self) {
-        for peg_in_graph in self.data.peg_in_graphs.clone() {
-            self.process_peg_in_as_depositor(peg_in_graph.id()).await;
-            self.process_peg_in_as_verifier(peg_in_graph.id()).await;
-            self.process_peg_in_as_operator(peg_in_graph.id()).await;
+        let queue = self.peg_in_queue().await;
+        let mut actions = 0;
+        for entry in queue {
+            if let Some(max_actions) = self.peg_in_processing_config.max_actions_per_sync {
+                if actions >= max_actions {
+                    println!(
+                        "Reached the limit of {max_actions} peg-in actions, remaining graphs are processed on the next sync"
+                    );
+                    break;
+                }
+            }
+
+            // Entries are made from the peg-in graphs
+            let graph_id = &PegInGraphId::from_hash(entry.graph_id);
+            let acted_as_depositor = self.process_peg_in_as_depositor(graph_id).await;
+            let acted_as_verifier = self.process_peg_in_as_verifier(graph_id).await;
+            let acted_as_operator = self.process_peg_in_as_operator(graph_id).await;
+            if acted_as_depositor || acted_as_verifier || acted_as_operator {
+                actions += 1;
+            }
         }
     }
 
+    async fn peg_in_queue(&self) -> PegInPriorityQueue {
+        let mut entries = vec![];
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            entries.push(PegInQueueEntry {
+                graph_id: peg_in_graph.id().clone(),
+                amount: peg_in_graph.peg_in_deposit_transaction.tx().output[0].value,
+                deposit_height: peg_in_graph.deposit_height(&self.esplora).await,
+                refund_timelock: peg_in_graph.refund_timelock(),
+            });
+        }
+        // Without the height only the amounts can be compared
+        let height = self.esplora.get_height().await.unwrap_or_default();
+
+        PegInPriorityQueue::new(
+            &self.peg_in_processing_config,
+            self.source_network,
+            height,
+            entries,
+        )
+    }
+
     // TODO: handle internal errors
     pub async fn process_peg_outs(&mut self) {
+        self.resolve_peg_out_races().await;
         let peg_out_graphs = self.data().peg_out_graphs.clone();
         for peg_out_graph in peg_out_graphs.iter() {
+            let is_own_graph = self.operator_context.as_ref().is_some_and(|context| {
+                context.operator_public_key == *peg_out_graph.operator_public_key()
+            });
+            if is_own_graph && peg_out_graph.has_pending_merkle_root_spot_check_requests() {
+                self.push_merkle_root_spot_check_proofs(peg_out_graph.peg_out_graph_id());
+            }
+            // Built by a coordinator from the commitments of this operator
+            if is_own_graph && !peg_out_graph.has_operator_signatures() {
+                if let Err(err) =
+                    self.pre_sign_peg_out_graph_as_operator(peg_out_graph.peg_out_graph_id())
+                {
+                    eprintln!(
+                        "Failed to pre-sign peg-out graph {}: {err:?}",
+                        peg_out_graph.id()
+                    );
+                }
+            }
+
             let status = peg_out_graph.operator_status(&self.esplora).await;
             match status {
                 PegOutOperatorStatus::PegOutStartTimeAvailable => {
-                    let _ = self.broadcast_start_time(peg_out_graph.id()).await;
+                    let _ = self
+                        .broadcast_start_time(peg_out_graph.peg_out_graph_id())
+                        .await;
                 }
                 PegOutOperatorStatus::PegOutPegOutConfirmAvailable => {
-                    let _ = self.broadcast_peg_out_confirm(peg_out_graph.id()).await;
+                    if !self
+                        .defer_broadcast(peg_out_graph.id(), DeferrableTx::PegOutConfirm)
+                        .await
+                    {
+                        let _ = self
+                            .broadcast_peg_out_confirm(peg_out_graph.peg_out_graph_id())
+                            .await;
+                    }
                 }
                 PegOutOperatorStatus::PegOutKickOff1Available => {
-                    let _ = self.broadcast_kick_off_1(peg_out_graph.id()).await;
+                    let _ = self
+                        .broadcast_kick_off_1(peg_out_graph.peg_out_graph_id())
+                        .await;
                 }
                 PegOutOperatorStatus::PegOutKickOff2Available => {
-                    let _ = self.broadcast_kick_off_2(peg_out_graph.id()).await;
+                    let _ = self
+                        .broadcast_kick_off_2(peg_out_graph.peg_out_graph_id())
+                        .await;
                 }
                 PegOutOperatorStatus::PegOutAssertInitialAvailable => {
-                    let _ = self.broadcast_assert_initial(peg_out_graph.id()).await;
+                    let _ = self
+                        .broadcast_assert_initial(peg_out_graph.peg_out_graph_id())
+                        .await;
                 }
                 PegOutOperatorStatus::PegOutAssertCommit1Available => {
                     let _ = self
-                        .broadcast_assert_commit_1(peg_out_graph.id(), &get_proof())
+                        .broadcast_assert_commit_1(peg_out_graph.peg_out_graph_id(), &get_proof())
                         .await;
                 }
                 PegOutOperatorStatus::PegOutAssertCommit2Available => {
                     let _ = self
-                        .broadcast_assert_commit_2(peg_out_graph.id(), &get_proof())
+                        .broadcast_assert_commit_2(peg_out_graph.peg_out_graph_id(), &get_proof())
                         .await;
                 }
                 PegOutOperatorStatus::PegOutAssertFinalAvailable => {
-                    let _ = self.broadcast_assert_final(peg_out_graph.id()).await;
+                    let _ = self
+                        .broadcast_assert_final(peg_out_graph.peg_out_graph_id())
+                        .await;
                 }
                 PegOutOperatorStatus::PegOutTake1Available => {
-                    let _ = self.broadcast_take_1(peg_out_graph.id()).await;
+                    if !self
+                        .defer_broadcast(peg_out_graph.id(), DeferrableTx::Take1)
+                        .await
+                    {
+                        let _ = self
+                            .broadcast_take_1(peg_out_graph.peg_out_graph_id())
+                            .await;
+                    }
                 }
                 PegOutOperatorStatus::PegOutTake2Available => {
-                    let _ = self.broadcast_take_2(peg_out_graph.id()).await;
+                    let _ = self
+                        .broadcast_take_2(peg_out_graph.peg_out_graph_id())
+                        .await;
                 }
                 _ => {}
             }
+
+            self.alert_dispute_status(peg_out_graph, is_own_graph).await;
+            self.process_peg_out_as_verifier(peg_out_graph).await;
         }
     }
 
-    async fn verifier_status(&self) {
-        if self.verifier_context.is_none() {
-            panic!("Verifier context must be initialized");
+    // Challenges of the graphs this client operates or verifies, and take transactions that are
+    // about to become available
+    async fn alert_dispute_status(&mut self, peg_out_graph: &PegOutGraph, is_own_graph: bool) {
+        let Some(take_window_blocks) = self
+            .alert_notifier
+            .as_ref()
+            .map(|notifier| notifier.config().take_window_blocks)
+        else {
+            return;
+        };
+        if !is_own_graph && self.verifier_context.is_none() {
+            return;
         }
 
-        for peg_in_graph in self.data.peg_in_graphs.iter() {
-            let peg_outs = peg_in_graph
-                .peg_out_graphs
-                .iter()
-                .map(|peg_out_id| {
-                    self.data
-                        .peg_out_graphs
-                        .iter()
-                        .find(|x| x.id() == peg_out_id)
-                        .unwrap()
+        let dispute_status = peg_out_graph.dispute_status(&self.esplora).await;
+        let graph_id = peg_out_graph.id();
+        if dispute_status.challenged {
+            self.alert(AlertEvent::ChallengeDetected {
+                graph_id: graph_id.clone(),
+            })
+            .await;
+        }
+        if let Some((transaction, available_at)) = dispute_status.take_window {
+            let Ok(height) = self.esplora.get_height().await else {
+                return;
+            };
+            if available_at.saturating_sub(height) <= take_window_blocks {
+                self.alert(AlertEvent::TakeWindowApproaching {
+                    graph_id: graph_id.clone(),
+                    transaction,
+                    available_at,
+                })
+                .await;
+            }
+        }
+    }
+
+    // Reports the graphs that moved to another stage since the last call, if enabled in the alert
+    // config. Graphs seen for the first time are only recorded, so that a restart does not report
+    // the stage of every graph again.
+    pub async fn alert_graph_transitions(&mut self) {
+        if !self
+            .alert_notifier
+            .as_ref()
+            .is_some_and(|notifier| notifier.config().graph_transitions)
+        {
+            return;
+        }
+
+        let graphs = match self
+            .queries()
+            .list_graphs(&GraphListFilter::default())
+            .await
+        {
+            Ok(graphs) => graphs,
+            Err(err) => {
+                eprintln!("Could not check the stages of the graphs: {err}");
+                return;
+            }
+        };
+        for graph in graphs {
+            let previous = self
+                .graph_stages
+                .insert(graph.graph_id.clone(), graph.stage);
+            if let Some(previous) = previous.filter(|previous| *previous != graph.stage) {
+                self.alert(AlertEvent::GraphStageChanged {
+                    graph_id: graph.graph_id,
+                    graph_type: graph.kind,
+                    previous,
+                    stage: graph.stage,
+                })
+                .await;
+            }
+        }
+    }
+
+    async fn alert(&mut self, event: AlertEvent) {
+        let timestamp = self.clock.now();
+        if let Some(notifier) = self.alert_notifier.as_mut() {
+            notifier.notify(event, timestamp).await;
+        }
+    }
+
+    // Whether a non-urgent broadcast is held back until fees drop, see `CongestionScheduler`
+    async fn defer_broadcast(&mut self, graph_id: &GraphId, tx: DeferrableTx) -> bool {
+        let Some(scheduler) = self.congestion_scheduler.as_ref() else {
+            return false;
+        };
+        let CongestionConfig {
+            max_fee_rate,
+            confirmation_target,
+            ..
+        } = *scheduler.config();
+        let fee_rate = self
+            .estimate_fee_rate(confirmation_target)
+            .await
+            .inspect_err(|err| {
+                eprintln!("Could not estimate the fee rate, not deferring {tx}: {err}")
+            })
+            .ok();
+        let Ok(height) = self.esplora.get_height().await else {
+            return false;
+        };
+
+        let scheduler = self.congestion_scheduler.as_mut().unwrap();
+        match scheduler.decide(graph_id, tx, height, fee_rate) {
+            BroadcastDecision::Broadcast => false,
+            BroadcastDecision::Defer {
+                fee_rate,
+                blocks_left,
+            } => {
+                println!(
+                    "Deferring {tx} of peg-out graph {graph_id}: {fee_rate} sat/vB is above {max_fee_rate} sat/vB, broadcasting regardless in {blocks_left} blocks"
+                );
+                true
+            }
+        }
+    }
+
+    // Challenges and disproves peg-outs based on local verification of the asserted proof. Whether
+    // to challenge while the proof cannot be checked yet is up to the `ChallengePolicy`.
+    async fn process_peg_out_as_verifier(&mut self, peg_out_graph: &PegOutGraph) {
+        let Some(ref context) = self.verifier_context else {
+            return;
+        };
+        let reward_script = context.reward_script.clone();
+        let graph_id = peg_out_graph.peg_out_graph_id();
+        match peg_out_graph.verifier_status(&self.esplora, context).await {
+            PegOutVerifierStatus::PegOutChallengeAvailable => {
+                let verification = self
+                    .verify_asserted_proof(graph_id)
+                    .await
+                    .unwrap_or_else(|e| ProofVerification::Inconclusive(e.to_string()));
+                if !self.challenge_policy.should_challenge(&verification) {
+                    println!("Not challenging peg-out graph {graph_id}: {verification:?}");
+                    return;
+                }
+                println!("Challenging peg-out graph {graph_id}: {verification:?}");
+                if let Err(e) = self.broadcast_challenge_with_own_funds(graph_id).await {
+                    eprintln!("Failed to challenge peg-out graph {graph_id}: {e}");
+                }
+            }
+            PegOutVerifierStatus::PegOutDisproveAvailable => {
+                self.disprove_invalid_proof(graph_id, reward_script).await;
+            }
+            // Races the operator's start time or kick-off 2, whichever confirms first wins
+            PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable => {
+                if let Err(e) = self
+                    .broadcast_start_time_timeout(graph_id, reward_script)
+                    .await
+                {
+                    eprintln!("Failed to time out start time of peg-out graph {graph_id}: {e}");
+                }
+            }
+            PegOutVerifierStatus::PegOutKickOffTimeoutAvailable => {
+                if let Err(e) = self
+                    .broadcast_kick_off_timeout(graph_id, reward_script)
+                    .await
+                {
+                    eprintln!("Failed to time out kick-off of peg-out graph {graph_id}: {e}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn disprove_invalid_proof(&mut self, graph_id: &PegOutGraphId, reward_script: ScriptBuf) {
+        // The chunker runs in the background, the loop is woken up once it finished
+        if !self.disprove_precomputation.is_started(graph_id) {
+            self.start_disprove_precomputation(graph_id).await;
+        }
+        match self
+            .verify_asserted_proof(graph_id)
+            .await
+            .unwrap_or_else(|e| ProofVerification::Inconclusive(e.to_string()))
+        {
+            ProofVerification::Invalid => {
+                self.alert(AlertEvent::InvalidProofAsserted {
+                    graph_id: graph_id.clone(),
                 })
-                .collect::<Vec<_>>();
-            let peg_in_status = peg_in_graph
-                .verifier_status(
-                    &self.esplora,
-                    self.verifier_context.as_ref().unwrap(),
-                    &peg_outs,
-                )
                 .await;
+                println!("Disproving peg-out graph {graph_id}");
+                match self.broadcast_disprove(graph_id, reward_script).await {
+                    Ok(txid) => {
+                        self.alert(AlertEvent::DisproveBroadcast {
+                            graph_id: graph_id.clone(),
+                            txid,
+                        })
+                        .await;
+                    }
+                    Err(e) => eprintln!("Failed to disprove peg-out graph {graph_id}: {e}"),
+                }
+            }
+            ProofVerification::Valid => {}
+            ProofVerification::Inconclusive(reason) => {
+                println!("Could not verify the proof of peg-out graph {graph_id}: {reason}");
+            }
+        }
+    }
 
-            if peg_in_status == PegInVerifierStatus::Complete {
-                for peg_out_graph in peg_outs {
-                    let peg_out_status = peg_out_graph
-                        .verifier_status(&self.esplora, self.verifier_context.as_ref().unwrap())
+    // Watchtower of `watch` mode: only reacts to what operators reveal on chain, the kick-off 2
+    // and assert transactions, and leaves signing and the timeouts to automatic mode
+    pub async fn watch_peg_outs(&mut self) {
+        let Some(reward_script) = self
+            .verifier_context
+            .as_ref()
+            .map(|context| context.reward_script.clone())
+        else {
+            return;
+        };
+        let peg_out_graphs = self.data().peg_out_graphs.clone();
+        for peg_out_graph in peg_out_graphs.iter() {
+            let context = self.verifier_context.as_ref().unwrap();
+            let graph_id = peg_out_graph.peg_out_graph_id();
+            match peg_out_graph.verifier_status(&self.esplora, context).await {
+                PegOutVerifierStatus::PegOutDisproveChainAvailable => {
+                    self.disprove_committed_superblock(graph_id, reward_script.clone())
                         .await;
-                    println!(
-                        "[VERIFIER]: Peg-out graph ID: {} status: {}\n",
-                        peg_out_graph.id(),
-                        peg_out_status
-                    );
                 }
+                PegOutVerifierStatus::PegOutDisproveAvailable => {
+                    self.watchtower.forget(graph_id);
+                    self.disprove_invalid_proof(graph_id, reward_script.clone())
+                        .await;
+                }
+                _ => self.watchtower.forget(graph_id),
             }
-            println!(
-                "[VERIFIER]: Peg-in graph ID: {} status: {}\n",
-                peg_in_graph.id(),
-                peg_in_status
-            );
         }
     }
 
-    pub async fn create_peg_in_graph(&mut self, input: Input, evm_address: &str) -> String {
-        if self.depositor_context.is_none() {
-            panic!("Depositor context must be initialized");
-        }
+    // Broadcasts disprove chain once a superblock heavier than the one committed in kick-off 2 is
+    // found, before take 1 or assert initial spend connector B
+    async fn disprove_committed_superblock(
+        &mut self,
+        graph_id: &PegOutGraphId,
+        reward_script: ScriptBuf,
+    ) {
+        let committed = match Self::find_peg_out_or_fail(&mut self.data, graph_id) {
+            Ok(graph) => graph
+                .committed_superblock(&self.esplora)
+                .await
+                .map(|committed| {
+                    (
+                        committed,
+                        graph.disprove_chain_deadline(committed.kick_off_2_height),
+                    )
+                }),
+            Err(e) => Err(e.to_string()),
+        };
+        let (committed, deadline) = match committed {
+            Ok(committed) => committed,
+            Err(reason) => {
+                eprintln!("Could not read the commitments of peg-out graph {graph_id}: {reason}");
+                return;
+            }
+        };
+        let Ok(height) = self.esplora.get_height().await else {
+            return;
+        };
+
+        let superblock = match self
+            .watchtower
+            .heavier_superblock(&self.esplora, graph_id, &committed, height)
+            .await
+        {
+            Ok(Some(superblock)) => superblock,
+            Ok(None) => {
+                println!(
+                    "Block {height}: superblock {} committed in peg-out graph {graph_id} is the heaviest, {} blocks left to disprove it",
+                    committed.superblock_hash,
+                    deadline.saturating_sub(height)
+                );
+                return;
+            }
+            Err(e) => {
+                eprintln!("Failed to scan for superblocks of peg-out graph {graph_id}: {e}");
+                return;
+            }
+        };
+
+        println!(
+            "Disproving the chain of peg-out graph {graph_id} with superblock {}",
+            superblock.block_hash()
+        );
+        match self
+            .broadcast_disprove_chain_with_superblock(graph_id, reward_script, &superblock)
+            .await
+        {
+            Ok(txid) => {
+                self.alert(AlertEvent::DisproveBroadcast {
+                    graph_id: graph_id.clone(),
+                    txid,
+                })
+                .await;
+            }
+            Err(e) => eprintln!("Failed to disprove the chain of peg-out graph {graph_id}: {e}"),
+        }
+    }
+
+    // Checks the proof asserted on chain for a peg-out graph against the verifying key. Inconclusive
+    // while the proof is evaluated in the background, see `start_disprove_precomputation`.
+    pub async fn verify_asserted_proof(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<ProofVerification, Error> {
+        if let Some(verification) = self.disprove_precomputation.verification(peg_out_graph_id) {
+            return Ok(verification);
+        }
+        if self.disprove_precomputation.is_running(peg_out_graph_id) {
+            return Ok(ProofVerification::Inconclusive(
+                "the asserted proof is still being evaluated".to_string(),
+            ));
+        }
+        let verifying_key = self
+            .zkproof_verifying_key
+            .as_ref()
+            .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?;
+        let peg_out_graph = self.peg_out_graph(peg_out_graph_id).ok_or(Error::Client(
+            ClientError::GraphNotFound(peg_out_graph_id.to_string()),
+        ))?;
+
+        let (verification, disprove_witness) =
+            match peg_out_graph.asserted_commitments(&self.esplora).await {
+                Ok(commitments) => {
+                    evaluate_asserted_proof(peg_out_graph.connector_c(), commitments, verifying_key)
+                }
+                Err(reason) => (ProofVerification::Inconclusive(reason), None),
+            };
+        self.disprove_precomputation.insert(
+            peg_out_graph_id,
+            verification.clone(),
+            disprove_witness,
+        );
+        Ok(verification)
+    }
+
+    // Starts evaluating the asserted proof in the background, the assert commit transactions have
+    // to be confirmed. Returns whether an evaluation is running or finished.
+    pub async fn start_disprove_precomputation(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> bool {
+        if self.disprove_precomputation.is_started(peg_out_graph_id) {
+            return true;
+        }
+        let (Some(verifying_key), Some(peg_out_graph)) = (
+            self.zkproof_verifying_key.as_ref(),
+            self.peg_out_graph(peg_out_graph_id),
+        ) else {
+            return false;
+        };
+        let Ok(commitments) = peg_out_graph.asserted_commitments(&self.esplora).await else {
+            return false;
+        };
+
+        println!(
+            "Evaluating the asserted proof of peg-out graph {peg_out_graph_id} in the background"
+        );
+        self.disprove_precomputation.start(
+            peg_out_graph_id,
+            peg_out_graph.connector_c().clone(),
+            commitments,
+            verifying_key.clone(),
+        );
+        true
+    }
+
+    // Resolves once a background evaluation of an asserted proof finished
+    pub async fn disprove_precomputed(&self) {
+        self.disprove_precomputation.finished().await;
+    }
+
+    // Funds the challenge with a confirmed output of the pay to pubkey address of the key that
+    // signs it, see `broadcast_challenge`. The change goes back to the same address.
+    async fn broadcast_challenge_with_own_funds(
+        &mut self,
+        graph_id: &GraphId,
+    ) -> Result<Txid, Error> {
+        let public_key = Self::user_keypair(
+            &self.depositor_context,
+            &self.operator_context,
+            &self.verifier_context,
+            &self.withdrawer_context,
+        )
+        .map(|keypair| PublicKey::from(keypair.public_key()))
+        .ok_or(Error::Client(ClientError::NoUserContextDefined))?;
+        let crowdfunding_amount =
+            Self::find_peg_out_or_fail(&mut self.data, graph_id)?.min_crowdfunding_amount();
+
+        let address = generate_pay_to_pubkey_script_address(self.source_network, &public_key);
+        let utxo = self
+            .esplora
+            .get_address_utxo(address.clone())
+            .await
+            .map_err(Error::Esplora)?
+            .into_iter()
+            .filter(|utxo| utxo.status.confirmed)
+            .find(|utxo| utxo.value.to_sat() >= crowdfunding_amount)
+            .ok_or(Error::Other(format!(
+                "No confirmed output of at least {crowdfunding_amount} sats at {address} to fund the challenge"
+            )))?;
+
+        let script = generate_pay_to_pubkey_script(&public_key);
+        let crowdfunding_input = InputWithScript {
+            outpoint: OutPoint {
+                txid: utxo.txid,
+                vout: utxo.vout,
+            },
+            amount: utxo.value,
+            script: &script,
+        };
+        self.broadcast_challenge(graph_id, &vec![crowdfunding_input], script.clone())
+            .await
+    }
+
+    // Addresses automatic mode is notified about, see `BlockScheduler::track_addresses`. Outputs
+    // without an address, like OP_RETURN, are left out.
+    pub fn tracked_addresses(&self) -> BTreeSet<String> {
+        self.data
+            .peg_out_graphs
+            .iter()
+            .flat_map(|peg_out_graph| peg_out_graph.template_output_scripts())
+            .filter_map(|script| Address::from_script(&script, self.source_network).ok())
+            .map(|address| address.to_string())
+            .collect()
+    }
+
+    pub async fn create_peg_in_graph(
+        &mut self,
+        input: impl Into<DepositFunding>,
+        evm_address: &str,
+        parameters: impl Into<GraphParameters>,
+    ) -> Result<PegInGraphId, Error> {
+        let peg_in_graph = self.build_peg_in_graph(input, evm_address, parameters)?;
+        self.add_peg_in_graph(peg_in_graph)
+    }
+
+    // Builds a peg-in graph without adding it to the client data, so it can be previewed first
+    pub fn build_peg_in_graph(
+        &self,
+        input: impl Into<DepositFunding>,
+        evm_address: &str,
+        parameters: impl Into<GraphParameters>,
+    ) -> Result<PegInGraph, Error> {
+        let context = self
+            .depositor_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::DepositorContextNotDefined))?;
+
+        let parameters: GraphParameters = parameters.into();
+        parameters
+            .validate(context.network, context.n_of_n_public_keys.len())
+            .map_err(|err| Error::Validation(ValidationError::InvalidGraphParameters(err)))?;
 
-        let peg_in_graph =
-            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address);
+        let peg_in_graph = PegInGraph::new(context, input, evm_address, parameters)?;
+        Self::reject_non_standard_transactions(peg_in_graph.lint_standardness())?;
 
+        Ok(peg_in_graph)
+    }
+
+    pub fn add_peg_in_graph(&mut self, peg_in_graph: PegInGraph) -> Result<PegInGraphId, Error> {
         let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
 
         let graph = self
@@ -914,60 +3095,227 @@ impl BitVMClient {
             .iter()
             .find(|&peg_out_graph| peg_out_graph.id().eq(&peg_in_graph_id));
         if graph.is_some() {
-            panic!("Peg in graph already exists");
+            return Err(Error::Client(ClientError::PegInGraphAlreadyExists(
+                peg_in_graph_id,
+            )));
         }
 
         self.data.peg_in_graphs.push(peg_in_graph);
 
-        peg_in_graph_id
+        Ok(peg_in_graph_id)
     }
 
     pub async fn broadcast_peg_in_deposit(
         &mut self,
-        peg_in_graph_id: &String,
+        peg_in_graph_id: &PegInGraphId,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
         let tx = graph.deposit(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_in_graph_id, &tx).await
     }
 
     pub async fn broadcast_peg_in_refund(
         &mut self,
-        peg_in_graph_id: &String,
+        peg_in_graph_id: &PegInGraphId,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
         let tx = graph.refund(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_in_graph_id, &tx).await
     }
 
     pub async fn broadcast_peg_in_confirm(
         &mut self,
-        peg_in_graph_id: &String,
+        peg_in_graph_id: &PegInGraphId,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
         let tx = graph.confirm(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_in_graph_id, &tx).await
     }
 
     pub fn create_peg_out_graph(
         &mut self,
-        peg_in_graph_id: &str,
+        peg_in_graph_id: &PegInGraphId,
         peg_out_confirm_input: Input,
-        commitment_secrets: HashMap<CommitmentMessageId, WinternitzSecret>,
-    ) -> String {
+        parameters: GraphParameters,
+    ) -> PegOutGraphId {
+        self.create_peg_out_graph_with_collateral(
+            peg_in_graph_id,
+            peg_out_confirm_input,
+            None,
+            parameters,
+        )
+    }
+
+    // See `PegOutGraph::new_with_collateral`. `select_operator_collateral_input` finds a suitable
+    // operator UTXO for `collateral_input`.
+    pub fn create_peg_out_graph_with_collateral(
+        &mut self,
+        peg_in_graph_id: &PegInGraphId,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        parameters: GraphParameters,
+    ) -> PegOutGraphId {
+        let (peg_out_graph, commitment_secrets) = self.build_peg_out_graph(
+            peg_in_graph_id,
+            peg_out_confirm_input,
+            collateral_input,
+            parameters,
+        );
+        self.add_peg_out_graph(peg_out_graph, commitment_secrets)
+    }
+
+    // Builds a peg-out graph and its commitment secrets without adding them to the client data, so
+    // the graph can be previewed first
+    pub fn build_peg_out_graph(
+        &self,
+        peg_in_graph_id: &PegInGraphId,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        parameters: GraphParameters,
+    ) -> (PegOutGraph, HashMap<CommitmentMessageId, WinternitzSecret>) {
+        let (context, peg_in_graph) = self.peg_out_graph_inputs(peg_in_graph_id, &parameters);
+        let (peg_out_graph, commitment_secrets) = Self::generate_peg_out_graph(
+            context,
+            peg_in_graph,
+            peg_out_confirm_input,
+            collateral_input,
+            parameters,
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+        Self::report_fee_sensitivity(&peg_out_graph);
+        if let Some(reuse) = self.commitment_key_reuse(&peg_out_graph) {
+            panic!("{reuse}");
+        }
+
+        (peg_out_graph, commitment_secrets)
+    }
+
+    // Same as `build_peg_out_graph` on a worker thread, the graph is added to the client data once
+    // the job finished, see `apply_finished_jobs`
+    pub fn queue_peg_out_graph(
+        &self,
+        peg_in_graph_id: &PegInGraphId,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        parameters: GraphParameters,
+    ) -> JobId {
+        let (context, peg_in_graph) = self.peg_out_graph_inputs(peg_in_graph_id, &parameters);
+        let peg_out_graph_id = peg_out_generate_id(peg_in_graph, &context.operator_public_key);
+        let (context, peg_in_graph) = (context.clone(), peg_in_graph.clone());
+        self.jobs
+            .submit(JobKind::CreatePegOutGraph, &peg_out_graph_id, move || {
+                let (peg_out_graph, commitment_secrets) = Self::generate_peg_out_graph(
+                    &context,
+                    &peg_in_graph,
+                    peg_out_confirm_input,
+                    collateral_input,
+                    parameters,
+                )
+                .map_err(|err| err.to_string())?;
+                Ok(JobOutput::PegOutGraph(
+                    Box::new(peg_out_graph),
+                    commitment_secrets,
+                ))
+            })
+    }
+
+    pub fn jobs(&self) -> &JobQueue {
+        &self.jobs
+    }
+
+    // Applies the outputs of the jobs finished since the last call to the client data. Returns
+    // the finished jobs, the client data needs flushing if any succeeded.
+    pub async fn apply_finished_jobs(&mut self) -> Vec<JobStatus> {
+        let mut finished = vec![];
+        for (status, output) in self.jobs.take_finished() {
+            let result = match output {
+                Ok(output) => self.apply_job_output(&status.graph_id, output).await,
+                Err(reason) => Err(reason),
+            };
+            finished.extend(self.jobs.complete(status.id, result));
+        }
+
+        finished
+    }
+
+    async fn apply_job_output(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        output: JobOutput,
+    ) -> Result<String, String> {
+        match output {
+            JobOutput::PegOutGraph(peg_out_graph, commitment_secrets) => {
+                // Another graph may have been added while the job was running
+                if self.peg_out_graph(peg_out_graph_id).is_some() {
+                    return Err(format!("peg-out graph {peg_out_graph_id} already exists"));
+                }
+                Self::report_fee_sensitivity(&peg_out_graph);
+                if let Some(reuse) = self.commitment_key_reuse(&peg_out_graph) {
+                    return Err(reuse.to_string());
+                }
+                let peg_out_graph_id = self.add_peg_out_graph(*peg_out_graph, commitment_secrets);
+                Ok(format!("created peg-out graph {peg_out_graph_id}"))
+            }
+            JobOutput::LockScripts => {
+                // Loaded from the cache the job filled
+                let graph = self
+                    .peg_out_graph_mut(peg_out_graph_id)
+                    .ok_or_else(|| format!("peg-out graph {peg_out_graph_id} not found"))?;
+                graph.warm_up();
+                Ok(match graph.connector_c_taproot_merkle_root() {
+                    Some(merkle_root) => {
+                        format!("lock scripts with merkle root {merkle_root} cached")
+                    }
+                    None => "lock scripts cached".to_string(),
+                })
+            }
+            JobOutput::AssertCommitWitnesses(witness_for_commit1, witness_for_commit2) => {
+                let (commit1_txid, commit2_txid) = self
+                    .broadcast_assert_commits_with_witnesses(
+                        peg_out_graph_id,
+                        (witness_for_commit1, witness_for_commit2),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(format!(
+                    "broadcast assert commit 1 {commit1_txid} and assert commit 2 {commit2_txid}"
+                ))
+            }
+        }
+    }
+
+    // Panics unless a peg-out graph with `parameters` can be created for the peg-in graph
+    fn peg_out_graph_inputs(
+        &self,
+        peg_in_graph_id: &PegInGraphId,
+        parameters: &GraphParameters,
+    ) -> (&OperatorContext, &PegInGraph) {
         if self.operator_context.is_none() {
             panic!("Operator context must be initialized");
         }
-        let operator_public_key = &self.operator_context.as_ref().unwrap().operator_public_key;
+        let context = self.operator_context.as_ref().unwrap();
+
+        if let Err(err) = parameters.validate(context.network, context.n_of_n_public_keys.len()) {
+            panic!("{err}");
+        }
 
         let peg_in_graph = self
             .data
             .peg_in_graphs
-            .iter_mut()
+            .iter()
             .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
             .unwrap_or_else(|| panic!("Invalid graph ID"));
+        if peg_in_graph.n_of_n_public_keys() != context.n_of_n_public_keys.as_slice() {
+            panic!("Peg out graph must use the committee of its peg in graph");
+        }
+        if parameters.template != peg_in_graph.template() {
+            panic!(
+                "Peg out graph must use the {} template of its peg in graph",
+                peg_in_graph.template()
+            );
+        }
 
-        let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
+        let peg_out_graph_id = peg_out_generate_id(peg_in_graph, &context.operator_public_key);
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -977,30 +3325,243 @@ impl BitVMClient {
             panic!("Peg out graph already exists");
         }
 
-        let peg_out_graph = PegOutGraph::new(
-            self.operator_context.as_ref().unwrap(),
-            peg_in_graph,
+        (context, peg_in_graph)
+    }
+
+    // The slow part of `build_peg_out_graph`, independent of the client data
+    fn generate_peg_out_graph(
+        context: &OperatorContext,
+        peg_in_graph: &PegInGraph,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        parameters: GraphParameters,
+    ) -> Result<(PegOutGraph, HashMap<CommitmentMessageId, WinternitzSecret>), Error> {
+        // Secrets are derived per graph, they are never reused across graphs
+        let peg_out_graph_id = peg_out_generate_id(peg_in_graph, &context.operator_public_key);
+        let commitment_secrets = context.generate_commitment_secrets(&peg_out_graph_id);
+
+        let peg_out_graph = profile_phase("create peg-out graph", || {
+            PegOutGraph::new_with_collateral(
+                context,
+                peg_in_graph,
+                peg_out_confirm_input,
+                collateral_input,
+                &commitment_secrets,
+                parameters,
+            )
+        })?;
+
+        Self::reject_exceeding_stack_usage(&peg_out_graph, &commitment_secrets);
+        Self::reject_non_standard_transactions(peg_out_graph.lint_standardness())?;
+
+        Ok((peg_out_graph, commitment_secrets))
+    }
+
+    // Secrets are derived from the graph id, a shared key means the derivation is broken
+    fn commitment_key_reuse(&self, peg_out_graph: &PegOutGraph) -> Option<CommitmentKeyReuse> {
+        commitment_key_reuses(self.data.peg_out_graphs.iter().chain([peg_out_graph]))
+            .into_iter()
+            .find(|reuse| reuse.graph_id == *peg_out_graph.id())
+    }
+
+    pub fn add_peg_out_graph(
+        &mut self,
+        peg_out_graph: PegOutGraph,
+        commitment_secrets: HashMap<CommitmentMessageId, WinternitzSecret>,
+    ) -> PegOutGraphId {
+        let operator_public_key = self
+            .operator_context
+            .as_ref()
+            .expect("Operator context must be initialized")
+            .operator_public_key;
+        let peg_out_graph_id = peg_out_graph.peg_out_graph_id().clone();
+        if self
+            .data
+            .peg_out_graphs
+            .iter()
+            .any(|graph| graph.id().eq(&peg_out_graph_id))
+        {
+            panic!("Peg out graph already exists");
+        }
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter_mut()
+            .find(|peg_in_graph| peg_in_graph.id().eq(&peg_out_graph.peg_in_graph_id))
+            .unwrap_or_else(|| panic!("Invalid graph ID"));
+
+        peg_in_graph.peg_out_graphs.push(peg_out_graph_id.clone());
+        self.data.peg_out_graphs.push(peg_out_graph);
+
+        self.private_data
+            .commitment_secrets
+            .entry(operator_public_key)
+            .or_default()
+            .insert(peg_out_graph_id.to_string(), commitment_secrets);
+        self.save_private_graph_data(
+            PrivateDataRole::Operator,
+            &operator_public_key,
+            &peg_out_graph_id,
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+
+        peg_out_graph_id
+    }
+
+    // Cold operator flow, operator side: what a coordinator needs to build the peg-out graph of
+    // `peg_in_graph_id`, signed with the operator key. Works offline, the peg-in graph is not
+    // needed and the commitment secrets are derived again when pre-signing.
+    pub fn export_operator_commitments(
+        &self,
+        peg_in_graph_id: &PegInGraphId,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        parameters: GraphParameters,
+    ) -> SignedOperatorCommitments {
+        if self.operator_context.is_none() {
+            panic!("Operator context must be initialized");
+        }
+        let context = self.operator_context.as_ref().unwrap();
+
+        if let Err(err) = parameters.validate(context.network, context.n_of_n_public_keys.len()) {
+            panic!("{err}");
+        }
+        if let Err(err) = check_collateral_input(&parameters, collateral_input.as_ref()) {
+            panic!("{err}");
+        }
+
+        let peg_out_graph_id =
+            peg_out_generate_id_from_peg_in_graph_id(peg_in_graph_id, &context.operator_public_key);
+        let commitment_secrets = context.generate_commitment_secrets(&peg_out_graph_id);
+
+        OperatorCommitments::new(
+            context,
+            peg_in_graph_id,
             peg_out_confirm_input,
+            collateral_input,
             &commitment_secrets,
-        );
+            parameters,
+        )
+        .sign(context)
+    }
+
+    // Cold operator flow, coordinator side: builds the peg-out graph from the signed commitments
+    // of an operator, no operator secret is needed. The graph is published without operator
+    // signatures, verifiers can pre-sign it before the operator comes online.
+    pub fn create_peg_out_graph_from_operator_commitments(
+        &mut self,
+        signed_commitments: &SignedOperatorCommitments,
+    ) -> Result<PegOutGraphId, Error> {
+        let peg_in_graph_id = &signed_commitments.commitments.peg_in_graph_id;
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter_mut()
+            .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+            .ok_or_else(|| {
+                Error::Client(ClientError::PegInGraphNotFound(peg_in_graph_id.to_string()))
+            })?;
+
+        let peg_out_graph = profile_phase("create peg-out graph", || {
+            PegOutGraph::new_from_operator_commitments(peg_in_graph, signed_commitments)
+        })?;
+        let peg_out_graph_id = peg_out_graph.peg_out_graph_id().clone();
+        if self
+            .data
+            .peg_out_graphs
+            .iter()
+            .any(|peg_out_graph| peg_out_graph.id().eq(&peg_out_graph_id))
+        {
+            return Err(Error::Client(ClientError::PegOutGraphAlreadyExists(
+                peg_out_graph_id.to_string(),
+            )));
+        }
+
+        let violations = peg_out_graph.lint_standardness();
+        if !violations.is_empty() {
+            let violations: Vec<String> = violations.iter().map(ToString::to_string).collect();
+            return Err(Error::Validation(
+                ValidationError::InvalidOperatorCommitments(format!(
+                    "graph transactions violate relay policy: {}",
+                    violations.join(", ")
+                )),
+            ));
+        }
+        Self::report_fee_sensitivity(&peg_out_graph);
 
         self.data.peg_out_graphs.push(peg_out_graph);
         peg_in_graph.peg_out_graphs.push(peg_out_graph_id.clone());
 
-        self.private_data.commitment_secrets = HashMap::from([(
-            *operator_public_key,
-            HashMap::from([(peg_out_graph_id.to_string(), commitment_secrets)]),
-        )]);
-        self.save_private_data();
+        Ok(peg_out_graph_id)
+    }
 
-        peg_out_graph_id
+    // Cold operator flow, operator side: adds the operator signatures to a graph built from its
+    // commitments, after checking the graph against the operator's own commitment secrets
+    pub fn pre_sign_peg_out_graph_as_operator(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<(), Error> {
+        let Some(ref context) = self.operator_context else {
+            return Err(Error::Client(ClientError::OperatorContextNotDefined));
+        };
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .ok_or_else(|| {
+                Error::Client(ClientError::PegOutGraphNotFound(
+                    peg_out_graph_id.to_string(),
+                ))
+            })?;
+        if peg_out_graph.has_operator_signatures() {
+            return Ok(());
+        }
+        peg_out_graph
+            .check_protocol_params()
+            .map_err(Error::Validation)?;
+        peg_out_graph
+            .check_segment_layout()
+            .map_err(Error::Chunker)?;
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(&peg_out_graph.peg_in_graph_id))
+            .ok_or_else(|| {
+                Error::Client(ClientError::PegInGraphNotFound(
+                    peg_out_graph.peg_in_graph_id.to_string(),
+                ))
+            })?;
+
+        let commitment_secrets = context.generate_commitment_secrets(peg_out_graph_id);
+        Self::reject_exceeding_stack_usage(peg_out_graph, &commitment_secrets);
+        peg_out_graph.pre_sign_operator_inputs(context, peg_in_graph, &commitment_secrets)?;
+
+        self.private_data
+            .commitment_secrets
+            .entry(context.operator_public_key)
+            .or_default()
+            .insert(peg_out_graph_id.to_string(), commitment_secrets);
+        self.save_private_graph_data(
+            PrivateDataRole::Operator,
+            &context.operator_public_key,
+            peg_out_graph_id,
+        )
     }
 
     pub async fn broadcast_peg_out(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         input: Input,
     ) -> Result<Txid, Error> {
+        self.check_peg_out_race(peg_out_graph_id).await?;
+        // The operator fronts the peg-out from its own funds, against the deposit of the peg-in
+        let peg_in_confirm_txid =
+            Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?.peg_in_confirm_txid();
+        self.verify_confirmations(&BTreeMap::from([(peg_in_confirm_txid, 1)]))
+            .await?;
+
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         if self.operator_context.is_some() {
             let tx = graph
@@ -1010,57 +3571,105 @@ impl BitVMClient {
                     input,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
     }
 
+    // Verifies that the burn on the destination chain matching the peg-out graph is final and
+    // agrees with the amount and operator in the graph.
+    pub async fn check_peg_out_event(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<PegOutBurntEvent, Error> {
+        let events = self
+            .chain_service
+            .get_peg_out_burnt()
+            .await
+            .map_err(|err| Error::L2(L2Error::ChainAdaptor(err)))?;
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        graph.match_peg_out_burnt_event(&events)
+    }
+
     pub async fn broadcast_peg_out_confirm(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
     ) -> Result<Txid, Error> {
+        self.check_peg_out_race(peg_out_graph_id).await?;
+        self.check_peg_out_event(peg_out_graph_id).await?;
+
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.peg_out_confirm(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
-    pub async fn broadcast_kick_off_1(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+    pub async fn broadcast_kick_off_1(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Txid, Error> {
+        self.check_peg_out_race(peg_out_graph_id).await?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
         if self.operator_context.is_some() {
+            let commitment_secrets = Self::operator_commitment_secrets(
+                &self.private_data,
+                self.operator_context.as_ref(),
+                peg_out_graph_id,
+            )?;
+            let secret = |message_id| {
+                Self::commitment_secret(commitment_secrets, peg_out_graph_id, message_id)
+            };
+            // Graphs created before the destination metadata commitments have no secrets for them
+            let destination_metadata = commitment_secrets
+                .get(&CommitmentMessageId::DestinationChainId)
+                .zip(commitment_secrets.get(&CommitmentMessageId::WithdrawalNonce))
+                .map(|(destination_chain_id_secret, withdrawal_nonce_secret)| {
+                    (
+                        self.destination_network.chain_id(),
+                        destination_chain_id_secret,
+                        withdrawal_nonce_secret,
+                    )
+                });
             let tx = graph
                 .kick_off_1(
                     &self.esplora,
                     self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdSourceNetwork],
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+                    secret(CommitmentMessageId::PegOutTxIdSourceNetwork)?,
+                    secret(CommitmentMessageId::PegOutTxIdDestinationNetwork)?,
+                    destination_metadata,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
     }
 
-    pub async fn broadcast_start_time(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+    pub async fn broadcast_start_time(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
         if self.operator_context.is_some() {
+            let commitment_secrets = Self::operator_commitment_secrets(
+                &self.private_data,
+                self.operator_context.as_ref(),
+                peg_out_graph_id,
+            )?;
             let tx = graph
                 .start_time(
                     &self.esplora,
                     self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::StartTime],
+                    Self::commitment_secret(
+                        commitment_secrets,
+                        peg_out_graph_id,
+                        CommitmentMessageId::StartTime,
+                    )?,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
@@ -1068,65 +3677,76 @@ impl BitVMClient {
 
     pub async fn broadcast_start_time_timeout(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
             .start_time_timeout(&self.esplora, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
-    pub async fn broadcast_kick_off_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+    pub async fn broadcast_kick_off_2(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let operator_context = self
+            .operator_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?;
+        let commitment_secrets = Self::operator_commitment_secrets(
+            &self.private_data,
+            Some(operator_context),
+            peg_out_graph_id,
+        )?;
         let tx = graph
             .kick_off_2(
                 &self.esplora,
-                self.operator_context.as_ref().unwrap(),
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id][&CommitmentMessageId::Superblock],
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id][&CommitmentMessageId::SuperblockHash],
+                operator_context,
+                Self::commitment_secret(
+                    commitment_secrets,
+                    peg_out_graph_id,
+                    CommitmentMessageId::Superblock,
+                )?,
+                Self::commitment_secret(
+                    commitment_secrets,
+                    peg_out_graph_id,
+                    CommitmentMessageId::SuperblockHash,
+                )?,
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_kick_off_timeout(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
             .kick_off_timeout(&self.esplora, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_challenge(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
-        let keypair = match (
-            self.depositor_context.as_ref(),
-            self.operator_context.as_ref(),
-            self.verifier_context.as_ref(),
-            self.withdrawer_context.as_ref(),
-        ) {
-            (Some(c), _, _, _) => &c.depositor_keypair,
-            (_, Some(c), _, _) => &c.operator_keypair,
-            (_, _, Some(c), _) => &c.verifier_keypair,
-            (_, _, _, Some(c)) => &c.withdrawer_keypair,
-            _ => Err(Error::Client(ClientError::NoUserContextDefined))?,
-        };
+        let keypair = Self::user_keypair(
+            &self.depositor_context,
+            &self.operator_context,
+            &self.verifier_context,
+            &self.withdrawer_context,
+        )
+        .ok_or(Error::Client(ClientError::NoUserContextDefined))?;
 
         let tx = graph
             .challenge(
@@ -1136,128 +3756,543 @@ impl BitVMClient {
                 output_script_pubkey,
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    // Replaces a broadcast, unconfirmed transaction of the graph with one paying `fee_rate`
+    // sat/vB, see `PegOutGraph::bump_fee`
+    pub async fn bump_fee(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        replaced_txid: Txid,
+        fee_rate: u64,
+    ) -> Result<Txid, Error> {
+        let replaced_tx = self
+            .esplora
+            .get_tx(&replaced_txid)
+            .await
+            .map_err(Error::Esplora)?
+            .ok_or(Error::Other(format!(
+                "Transaction {replaced_txid} not found"
+            )))?;
+        verify_if_not_mined(&self.esplora, replaced_txid).await?;
+        let prev_outs = get_prevouts(&self.esplora, &replaced_tx)
+            .await
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::Other(format!(
+                "Outputs spent by {replaced_txid} not found"
+            )))?;
+
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let keypair = Self::user_keypair(
+            &self.depositor_context,
+            &self.operator_context,
+            &self.verifier_context,
+            &self.withdrawer_context,
+        )
+        .ok_or(Error::Client(ClientError::NoUserContextDefined))?;
+
+        let tx = graph.bump_fee(&replaced_tx, &prev_outs, keypair, fee_rate)?;
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    // Child-pays-for-parent for graph transactions that cannot be replaced, see
+    // `PegOutGraph::bump_fee`. Spends output `vout` of the unconfirmed `parent_txid` so that the
+    // package pays `fee_rate` sat/vB. The output has to be locked to the user's key, confirmed
+    // outputs at the user's address are added if it cannot pay the fee alone.
+    pub async fn cpfp(
+        &mut self,
+        parent_txid: Txid,
+        vout: u32,
+        fee_rate: u64,
+    ) -> Result<Txid, Error> {
+        let parent_tx = self
+            .esplora
+            .get_tx(&parent_txid)
+            .await
+            .map_err(Error::Esplora)?
+            .ok_or(Error::Other(format!("Transaction {parent_txid} not found")))?;
+        verify_if_not_mined(&self.esplora, parent_txid).await?;
+        let graph_id = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|graph| graph.txids().iter().any(|(_, txid)| *txid == parent_txid))
+            .map(|graph| graph.id())
+            .or_else(|| {
+                self.data
+                    .peg_out_graphs
+                    .iter()
+                    .find(|graph| graph.is_graph_tx(&parent_tx))
+                    .map(|graph| graph.id())
+            })
+            .cloned()
+            .ok_or(Error::Client(ClientError::GraphOfTxNotFound(parent_txid)))?;
+
+        let parent_prev_outs = get_prevouts(&self.esplora, &parent_tx)
+            .await
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::Other(format!(
+                "Outputs spent by {parent_txid} not found"
+            )))?;
+        let parent_fee = parent_prev_outs
+            .iter()
+            .map(|prev_out| prev_out.value)
+            .sum::<Amount>()
+            - parent_tx
+                .output
+                .iter()
+                .map(|output| output.value)
+                .sum::<Amount>();
+
+        let keypair = Self::user_keypair(
+            &self.depositor_context,
+            &self.operator_context,
+            &self.verifier_context,
+            &self.withdrawer_context,
+        )
+        .ok_or(Error::Client(ClientError::NoUserContextDefined))?;
+        let address = generate_pay_to_pubkey_script_address(
+            self.source_network,
+            &PublicKey::from(keypair.public_key()),
+        );
+        let anchor = OutPoint {
+            txid: parent_txid,
+            vout,
+        };
+        let anchor_output = parent_tx
+            .output
+            .get(vout as usize)
+            .filter(|output| output.script_pubkey == address.script_pubkey())
+            .ok_or(Error::Transaction(
+                TransactionError::CpfpOutputNotSpendable(anchor),
+            ))?;
+
+        let mut inputs = vec![Input {
+            outpoint: anchor,
+            amount: anchor_output.value,
+        }];
+        let mut funding_utxos = None;
+        let child_tx = loop {
+            let e = match create_cpfp_transaction(
+                &inputs,
+                keypair,
+                parent_fee,
+                parent_tx.vsize(),
+                fee_rate,
+            ) {
+                Ok(tx) => break tx,
+                Err(e @ TransactionError::CpfpFundsInsufficient { .. }) => e,
+                Err(e) => return Err(Error::Transaction(e)),
+            };
+            if funding_utxos.is_none() {
+                let mut utxos: Vec<_> = self
+                    .esplora
+                    .get_address_utxo(address.clone())
+                    .await
+                    .map_err(Error::Esplora)?
+                    .into_iter()
+                    .filter(|utxo| utxo.status.confirmed)
+                    .collect();
+                // largest first, to add as few inputs as possible
+                utxos.sort_by_key(|utxo| std::cmp::Reverse(utxo.value));
+                funding_utxos = Some(utxos.into_iter());
+            }
+            match funding_utxos.as_mut().and_then(Iterator::next) {
+                Some(utxo) => inputs.push(Input {
+                    outpoint: OutPoint {
+                        txid: utxo.txid,
+                        vout: utxo.vout,
+                    },
+                    amount: utxo.value,
+                }),
+                None => return Err(Error::Transaction(e)),
+            }
+        };
+
+        self.broadcast_tx(&graph_id, &child_tx).await
     }
 
     pub async fn broadcast_assert_initial(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.assert_initial(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_assert_commit_1(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         proof: &RawProof,
     ) -> Result<Txid, Error> {
+        let mut committed_values = self.committed_intermediate_values(peg_out_graph_id);
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
             .assert_commit_1(
                 &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
+                Self::operator_commitment_secrets(
+                    &self.private_data,
+                    self.operator_context.as_ref(),
+                    peg_out_graph_id,
+                )?,
+                proof,
+                &mut committed_values,
+            )
+            .await?;
+        self.record_committed_intermediate_values(peg_out_graph_id, committed_values)?;
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    pub async fn broadcast_assert_commit_2(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        proof: &RawProof,
+    ) -> Result<Txid, Error> {
+        let mut committed_values = self.committed_intermediate_values(peg_out_graph_id);
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let tx = graph
+            .assert_commit_2(
+                &self.esplora,
+                Self::operator_commitment_secrets(
+                    &self.private_data,
+                    self.operator_context.as_ref(),
+                    peg_out_graph_id,
+                )?,
+                proof,
+                &mut committed_values,
+            )
+            .await?;
+        self.record_committed_intermediate_values(peg_out_graph_id, committed_values)?;
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    // use this when possible
+    // broadcast assert commits together to save groth16 verifying time
+    pub async fn broadcast_assert_commits(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        proof: &RawProof,
+    ) -> Result<(Txid, Txid), Error> {
+        let mut committed_values = self.committed_intermediate_values(peg_out_graph_id);
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let (commit1_tx, commit2_tx) = graph
+            .assert_commits(
+                &self.esplora,
+                Self::operator_commitment_secrets(
+                    &self.private_data,
+                    self.operator_context.as_ref(),
+                    peg_out_graph_id,
+                )?,
                 proof,
+                &mut committed_values,
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.record_committed_intermediate_values(peg_out_graph_id, committed_values)?;
+        Ok((
+            self.broadcast_tx(peg_out_graph_id, &commit1_tx).await?,
+            self.broadcast_tx(peg_out_graph_id, &commit2_tx).await?,
+        ))
+    }
+
+    // Signs the assert commits on a worker thread, they are broadcast like `broadcast_assert_commits`
+    // once the job finished, see `apply_finished_jobs`
+    pub fn queue_assert_commits(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+        proof: RawProof,
+    ) -> Result<JobId, Error> {
+        let commitment_secrets = Self::operator_commitment_secrets(
+            &self.private_data,
+            self.operator_context.as_ref(),
+            peg_out_graph_id,
+        )?
+        .clone();
+        Ok(self
+            .jobs
+            .submit(JobKind::SignAssertCommits, peg_out_graph_id, move || {
+                let (witness_for_commit1, witness_for_commit2) =
+                    sign_assert_tx_with_groth16_proof(&commitment_secrets, &proof);
+                Ok(JobOutput::AssertCommitWitnesses(
+                    witness_for_commit1,
+                    witness_for_commit2,
+                ))
+            }))
+    }
+
+    async fn broadcast_assert_commits_with_witnesses(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        witnesses: (Vec<RawWitness>, Vec<RawWitness>),
+    ) -> Result<(Txid, Txid), Error> {
+        let mut committed_values = self.committed_intermediate_values(peg_out_graph_id);
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let (commit1_tx, commit2_tx) = graph
+            .assert_commits_with_witnesses(&self.esplora, witnesses, &mut committed_values)
+            .await?;
+        self.record_committed_intermediate_values(peg_out_graph_id, committed_values)?;
+        Ok((
+            self.broadcast_tx(peg_out_graph_id, &commit1_tx).await?,
+            self.broadcast_tx(peg_out_graph_id, &commit2_tx).await?,
+        ))
+    }
+
+    pub async fn assert_commit_progress(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Vec<AssertCommitProgress>, Error> {
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        graph.assert_commit_progress(&self.esplora).await
+    }
+
+    // For when one assert commit confirmed and the other did not, e.g. it was evicted from the
+    // mempool. Only the unconfirmed commits are broadcast: signed ones as they were, the others
+    // signed with the values committed before, so both commits attest to the same proof.
+    pub async fn broadcast_missing_assert_commits(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        proof: &RawProof,
+    ) -> Result<Vec<Txid>, Error> {
+        let mut txids = vec![];
+        for progress in self.assert_commit_progress(peg_out_graph_id).await? {
+            if progress.confirmed_height.is_some() {
+                continue;
+            }
+            let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+            let txid = match graph.signed_assert_commit(progress.commit) {
+                Some(tx) => self.broadcast_tx(peg_out_graph_id, &tx).await?,
+                None => match progress.commit {
+                    AssertCommit::Commit1 => {
+                        self.broadcast_assert_commit_1(peg_out_graph_id, proof)
+                            .await?
+                    }
+                    AssertCommit::Commit2 => {
+                        self.broadcast_assert_commit_2(peg_out_graph_id, proof)
+                            .await?
+                    }
+                },
+            };
+            txids.push(txid);
+        }
+
+        Ok(txids)
+    }
+
+    // Load-and-verify mode for proofs generated by an external prover: the proof is only committed
+    // to if it verifies against the verifying key and its public inputs bind it to this peg-out
+    pub fn load_external_proof(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+        external_proof: &ExternalProof,
+    ) -> Result<RawProof, Error> {
+        let verifying_key = self
+            .zkproof_verifying_key
+            .as_ref()
+            .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?;
+        let peg_out_graph = self.peg_out_graph(peg_out_graph_id).ok_or(Error::Client(
+            ClientError::GraphNotFound(peg_out_graph_id.to_string()),
+        ))?;
+
+        external_proof
+            .load(&peg_out_graph.peg_out_facts()?, verifying_key)
+            .map_err(Error::Validation)
     }
 
-    pub async fn broadcast_assert_commit_2(
-        &mut self,
-        peg_out_graph_id: &String,
-        proof: &RawProof,
-    ) -> Result<Txid, Error> {
-        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph
-            .assert_commit_2(
-                &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
-                proof,
-            )
-            .await?;
-        self.broadcast_tx(&tx).await
+    // Values committed by an earlier assert attempt of the operator for the graph
+    pub fn committed_intermediate_values(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Option<CommittedIntermediateValues> {
+        let operator_public_key = self.operator_context.as_ref()?.operator_public_key;
+        self.private_data
+            .committed_intermediate_values
+            .get(&operator_public_key)?
+            .get(peg_out_graph_id.as_str())
+            .cloned()
     }
 
-    // use this when possible
-    // broadcast assert commits together to save groth16 verifying time
-    pub async fn broadcast_assert_commits(
+    // Saved before the assert commits are broadcast, the values are fixed from the first attempt
+    // on even if it never confirms
+    fn record_committed_intermediate_values(
         &mut self,
-        peg_out_graph_id: &String,
-        proof: &RawProof,
-    ) -> Result<(Txid, Txid), Error> {
-        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let (commit1_tx, commit2_tx) = graph
-            .assert_commits(
-                &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
-                proof,
-            )
-            .await?;
-        Ok((
-            self.broadcast_tx(&commit1_tx).await?,
-            self.broadcast_tx(&commit2_tx).await?,
-        ))
+        peg_out_graph_id: &str,
+        committed_values: Option<CommittedIntermediateValues>,
+    ) -> Result<(), Error> {
+        let (Some(context), Some(committed_values)) = (&self.operator_context, committed_values)
+        else {
+            return Ok(());
+        };
+        let graph_values = self
+            .private_data
+            .committed_intermediate_values
+            .entry(context.operator_public_key)
+            .or_default();
+        if graph_values.get(peg_out_graph_id) != Some(&committed_values) {
+            graph_values.insert(peg_out_graph_id.to_string(), committed_values);
+            self.save_private_graph_data(
+                PrivateDataRole::Operator,
+                &context.operator_public_key,
+                peg_out_graph_id,
+            )?;
+        }
+
+        Ok(())
     }
 
     pub async fn broadcast_assert_final(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.assert_final(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_disprove(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Txid, Error> {
+        let disprove_witness = self
+            .disprove_precomputation
+            .disprove_witness(peg_out_graph_id);
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph
-            .disprove(
-                &self.esplora,
-                output_script_pubkey,
-                self.zkproof_verifying_key
-                    .as_ref()
-                    .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?,
-            )
-            .await?;
-        self.broadcast_tx(&tx).await
+        let tx = match disprove_witness {
+            Some(disprove_witness) => {
+                graph
+                    .disprove_with_witness(&self.esplora, output_script_pubkey, disprove_witness)
+                    .await?
+            }
+            None => {
+                graph
+                    .disprove(
+                        &self.esplora,
+                        output_script_pubkey,
+                        self.zkproof_verifying_key
+                            .as_ref()
+                            .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?,
+                    )
+                    .await?
+            }
+        };
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_disprove_chain(
         &mut self,
-        peg_out_graph_id: &String,
+        peg_out_graph_id: &PegOutGraphId,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
             .disprove_chain(&self.esplora, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    pub async fn broadcast_disprove_chain_with_superblock(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        output_script_pubkey: ScriptBuf,
+        superblock: &Header,
+    ) -> Result<Txid, Error> {
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let tx = graph
+            .disprove_chain_with_superblock(&self.esplora, output_script_pubkey, superblock)
+            .await?;
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
-    pub async fn broadcast_take_1(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+    pub async fn broadcast_take_1(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Txid, Error> {
+        // Take 1 can be broadcast once the timelocks of the txs it spends have passed
+        let required_confirmations: BTreeMap<Txid, u32> =
+            Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?
+                .preceding_txs(PegOutPresignedTransaction::Take1)
+                .into_iter()
+                .map(|(txid, num_blocks)| (txid, num_blocks + 1))
+                .collect();
+        self.verify_confirmations(&required_confirmations).await?;
+
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.take_1(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    // Checks on the locally validated header chain that each tx has at least the given number of
+    // confirmations. Does nothing if esplora is trusted.
+    async fn verify_confirmations(
+        &mut self,
+        required_confirmations: &BTreeMap<Txid, u32>,
+    ) -> Result<(), Error> {
+        let level = self.header_verification.level;
+        if level == HeaderVerificationLevel::Trusted {
+            return Ok(());
+        }
+
+        let file_path = self.local_file_path.join(HEADER_CHAIN_FILE_NAME);
+        if self.header_chain.is_none() {
+            let checkpoint = self
+                .header_verification
+                .checkpoint
+                .unwrap_or_else(|| HeaderCheckpoint::genesis(self.source_network));
+            let header_chain = match HeaderChain::read_from_file(&file_path)
+                .filter(|header_chain| header_chain.checkpoint() == checkpoint)
+            {
+                Some(header_chain) => header_chain,
+                None => {
+                    let checkpoint_header = self
+                        .esplora
+                        .get_header_by_hash(&checkpoint.hash)
+                        .await
+                        .map_err(Error::Esplora)?;
+                    HeaderChain::new(self.source_network, checkpoint, checkpoint_header)
+                        .map_err(Error::HeaderChain)?
+                }
+            };
+            self.header_chain = Some(header_chain);
+        }
+
+        let header_chain = self.header_chain.as_mut().unwrap();
+        let sync_result = header_chain.sync(&self.esplora).await;
+        // Headers validated before a failure are kept
+        header_chain.save_to_file(&file_path);
+        sync_result?;
+
+        for (txid, required) in required_confirmations {
+            let verified = header_chain
+                .verify_confirmations(&self.esplora, txid, level)
+                .await?;
+            if verified < *required {
+                return Err(Error::HeaderChain(
+                    HeaderChainError::InsufficientConfirmations {
+                        txid: *txid,
+                        required: *required,
+                        verified,
+                    },
+                ));
+            }
+        }
+
+        Ok(())
     }
 
-    pub async fn broadcast_take_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+    pub async fn broadcast_take_2(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph
-            .take_2(&self.esplora, self.operator_context.as_ref().unwrap())
-            .await?;
-        self.broadcast_tx(&tx).await
+        let operator_context = self
+            .operator_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?;
+        let tx = graph.take_2(&self.esplora, operator_context).await?;
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn get_initial_utxo(&self, address: Address, amount: Amount) -> Option<Utxo> {
@@ -1302,6 +4337,36 @@ impl BitVMClient {
             .unwrap()
     }
 
+    // Operator UTXO to fund the collateral output of kick-off 1 with, see
+    // `GraphParameters::operator_collateral_funding_amount`. An output of exactly the amount is
+    // preferred, otherwise the smallest larger one, so large outputs stay available for funding
+    // peg-out confirm transactions. Outputs in `excluded` are already spent by the graph.
+    pub async fn select_operator_collateral_input(
+        &self,
+        amount: Amount,
+        excluded: &[OutPoint],
+    ) -> Option<Input> {
+        self.get_operator_utxos()
+            .await
+            .into_iter()
+            .filter(|utxo| utxo.value >= amount)
+            .map(|utxo| Input {
+                outpoint: OutPoint {
+                    txid: utxo.txid,
+                    vout: utxo.vout,
+                },
+                amount: utxo.value,
+            })
+            .filter(|input| !excluded.contains(&input.outpoint))
+            .min_by_key(|input| input.amount)
+    }
+
+    // Fee rate, in sat/vB, to build graphs at for confirmation within `target_blocks`. Pass it to
+    // the graph with `GraphParameters::with_fee_rate`.
+    pub async fn estimate_fee_rate(&self, target_blocks: u16) -> Result<u64, Error> {
+        estimate_fee_rate(&self.esplora, target_blocks).await
+    }
+
     pub fn get_depositor_address(&self) -> Address {
         if let Some(ref context) = self.depositor_context {
             generate_pay_to_pubkey_script_address(context.network, &context.depositor_public_key)
@@ -1317,15 +4382,233 @@ impl BitVMClient {
             .unwrap()
     }
 
+    // Key path address of the depositor taproot key, see `DepositorAddressType::P2tr`
+    pub fn get_depositor_taproot_address(&self) -> Address {
+        if let Some(ref context) = self.depositor_context {
+            generate_p2tr_key_path_address(context.network, &context.depositor_taproot_public_key)
+        } else {
+            panic!("Depositor private key not provided in configuration.");
+        }
+    }
+
+    pub async fn get_depositor_taproot_utxos(&self) -> Vec<Utxo> {
+        self.esplora
+            .get_address_utxo(self.get_depositor_taproot_address())
+            .await
+            .unwrap()
+    }
+
+    // Coin-selects depositor UTXOs of the address type to peg in `peg_in_amount`, confirmed ones
+    // first, then the largest ones
+    pub async fn select_depositor_funding(
+        &self,
+        peg_in_amount: Amount,
+        depositor_address_type: DepositorAddressType,
+        fee_rate: u64,
+    ) -> Result<DepositFunding, Error> {
+        let mut utxos = match depositor_address_type {
+            DepositorAddressType::P2wsh => self.get_depositor_utxos().await,
+            DepositorAddressType::P2tr => self.get_depositor_taproot_utxos().await,
+        };
+        utxos.sort_by_key(|utxo| (!utxo.status.confirmed, std::cmp::Reverse(utxo.value)));
+        let inputs: Vec<Input> = utxos
+            .iter()
+            .map(|utxo| Input {
+                outpoint: OutPoint {
+                    txid: utxo.txid,
+                    vout: utxo.vout,
+                },
+                amount: utxo.value,
+            })
+            .collect();
+
+        select_deposit_funding(&inputs, peg_in_amount, fee_rate).map_err(|required| {
+            Error::Client(ClientError::InsufficientDepositorFunds {
+                required,
+                available: inputs.iter().map(|input| input.amount).sum(),
+            })
+        })
+    }
+
+    // Funds the depositor address from a test network faucet, unless it already holds an output of
+    // the amount. Returns the output to be spent by a peg-in deposit.
+    pub async fn fund_depositor_from_faucet(
+        &self,
+        faucet: &FaucetClient,
+        amount: Amount,
+    ) -> Result<Input, Error> {
+        if faucet.network() != self.source_network {
+            return Err(Error::Client(ClientError::FaucetNotAvailable(
+                self.source_network,
+            )));
+        }
+
+        let address = self.get_depositor_address();
+        if let Some(utxos) = self.get_initial_utxos(address.clone(), amount).await {
+            println!("Depositor address already holds {amount}, not using the faucet");
+            return Ok(Input {
+                outpoint: OutPoint {
+                    txid: utxos[0].txid,
+                    vout: utxos[0].vout,
+                },
+                amount,
+            });
+        }
+
+        let txid = faucet.fund(&address, amount).await?;
+        println!("Faucet funded {address} with {amount} in tx {txid}");
+        for _ in 0..FAUCET_TX_LOOKUP_RETRIES {
+            if let Some(tx) = self.esplora.get_tx(&txid).await.map_err(Error::Esplora)? {
+                let vout = tx
+                    .output
+                    .iter()
+                    .position(|output| {
+                        output.script_pubkey == address.script_pubkey() && output.value == amount
+                    })
+                    .ok_or_else(|| {
+                        Error::Client(ClientError::FaucetRequestFailed(format!(
+                            "Faucet tx {txid} does not pay {amount} to {address}"
+                        )))
+                    })?;
+                return Ok(Input {
+                    outpoint: OutPoint {
+                        txid,
+                        vout: vout as u32,
+                    },
+                    amount,
+                });
+            }
+            self.clock
+                .sleep(Duration::from_secs(FAUCET_TX_LOOKUP_WAIT_SECS))
+                .await;
+        }
+
+        Err(Error::Client(ClientError::FaucetRequestFailed(format!(
+            "Faucet tx {txid} was not found by esplora"
+        ))))
+    }
+
     pub fn push_verifier_nonces(&mut self, graph_id: &GraphId) {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
         }
 
+        if self.is_abandoned(graph_id) {
+            eprintln!(
+                "Refusing to push nonces for graph {graph_id}: not pre-signed before its deadline"
+            );
+            return;
+        }
+
+        if let Err(err) = self.data.graph_mut(graph_id).check_protocol_params() {
+            eprintln!(
+                "Refusing to push nonces for graph {graph_id}: {}",
+                Error::Validation(err)
+            );
+            return;
+        }
+        if let Err(err) = self.data.graph_mut(graph_id).check_segment_layout() {
+            eprintln!(
+                "Refusing to push nonces for graph {graph_id}: {}",
+                Error::Chunker(err)
+            );
+            return;
+        }
+        if let Err(err) = self.check_commitment_key_uniqueness(graph_id) {
+            eprintln!(
+                "Refusing to push nonces for graph {graph_id}: {}",
+                Error::Validation(err)
+            );
+            return;
+        }
+
+        if !self.verify_merkle_root(graph_id) {
+            return;
+        }
+
+        self.check_committee(graph_id);
+
         let graph = self.data.graph_mut(graph_id);
         let secret_nonces = graph.push_verifier_nonces(self.verifier_context.as_ref().unwrap());
-        self.merge_secret_nonces(graph_id, secret_nonces);
-        self.save_private_data();
+        if let Err(err) = self.merge_secret_nonces(graph_id, secret_nonces) {
+            eprintln!("Failed to save the nonces pushed for graph {graph_id}: {err}");
+        }
+    }
+
+    // Checks the connector C merkle root of a peg-out graph before it gets pre-signed, requesting a
+    // spot check from the operator if needed. Returns whether the graph can be signed.
+    fn verify_merkle_root(&mut self, graph_id: &GraphId) -> bool {
+        let verifier = self
+            .verifier_context
+            .as_ref()
+            .expect("Can only be called by a verifier!");
+        let Ok(peg_out_graph) = Self::find_peg_out_or_fail(&mut self.data, graph_id) else {
+            return true; // peg-in graphs have no connector C
+        };
+
+        match peg_out_graph.merkle_root_status(verifier, self.merkle_root_verification) {
+            MerkleRootStatus::Verified => true,
+            MerkleRootStatus::PendingSpotCheck => {
+                if let MerkleRootVerification::SpotCheck { sample_size } =
+                    self.merkle_root_verification
+                {
+                    println!("Requesting connector C merkle root spot check for graph {graph_id}");
+                    peg_out_graph.request_merkle_root_spot_check(verifier, sample_size);
+                }
+                false
+            }
+            MerkleRootStatus::AwaitingSpotCheckProofs => {
+                println!("Awaiting connector C leaf proofs from the operator of graph {graph_id}");
+                false
+            }
+            MerkleRootStatus::Invalid(err) => {
+                eprintln!("Refusing to pre-sign graph {graph_id}: {err}");
+                false
+            }
+        }
+    }
+
+    // Answers the connector C merkle root spot checks requested by verifiers
+    pub fn push_merkle_root_spot_check_proofs(&mut self, peg_out_graph_id: &PegOutGraphId) {
+        let operator = self
+            .operator_context
+            .as_ref()
+            .expect("Can only be called by an operator!");
+        let peg_out_graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)
+            .unwrap_or_else(|err| panic!("{err}"));
+        if peg_out_graph.push_merkle_root_spot_check_proofs(operator) {
+            println!("Pushed connector C leaf proofs for graph {peg_out_graph_id}");
+        }
+    }
+
+    // Output descriptors of all connector addresses of a peg-in or peg-out graph
+    pub fn export_descriptors(
+        &self,
+        graph_id: &GraphId,
+    ) -> Result<Vec<ConnectorDescriptor>, Error> {
+        if let Ok(peg_in_graph) = self.get_peg_in_graph(graph_id) {
+            return Ok(peg_in_graph.connector_descriptors());
+        }
+        self.data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+            .map(PegOutGraph::connector_descriptors)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))
+    }
+
+    // Transactions the committee pre-signs for a peg-in or peg-out graph, with the sighash
+    // preimage of every committee input
+    pub fn export_signing_bundle(&self, graph_id: &GraphId) -> Result<SigningBundle, Error> {
+        if let Ok(peg_in_graph) = self.get_peg_in_graph(graph_id) {
+            return Ok(peg_in_graph.signing_bundle());
+        }
+        self.data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+            .map(PegOutGraph::signing_bundle)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))
     }
 
     fn get_peg_in_graph(&self, peg_in_graph_id: &String) -> Result<&PegInGraph, Error> {
@@ -1350,6 +4633,90 @@ impl BitVMClient {
     //         )))
     // }
 
+    // Graphs with a transaction the network would not relay are rejected before anything is
+    // signed, all violations are listed together
+    // Graphs with a leaf that cannot be spent within the consensus stack limit are rejected
+    // before anything is signed
+    fn reject_exceeding_stack_usage(
+        peg_out_graph: &PegOutGraph,
+        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    ) {
+        let stack_usage = profile_phase("analyze peg-out graph stack usage", || {
+            peg_out_graph.analyze_stack_usage(commitment_secrets)
+        });
+        for leaf in stack_usage.iter().filter(|leaf| !leaf.completed) {
+            eprintln!("Stack usage analysis did not execute every opcode: {leaf}");
+        }
+        let exceeding_leaves: Vec<String> = stack_usage
+            .iter()
+            .filter(|leaf| leaf.usage.exceeds_limit())
+            .map(ToString::to_string)
+            .collect();
+        if !exceeding_leaves.is_empty() {
+            panic!(
+                "Connector leaves exceed the stack limit:\n{}",
+                exceeding_leaves.join("\n")
+            );
+        }
+    }
+
+    // The graph is created anyway, the fee buffers are the committee's call
+    fn report_fee_sensitivity(peg_out_graph: &PegOutGraph) {
+        let report = peg_out_graph.fee_sensitivity();
+        println!("Fee sensitivity of peg-out graph {}:", peg_out_graph.id());
+        for sensitivity in &report {
+            println!("  {sensitivity}");
+        }
+        for sensitivity in fee_sensitivity_warnings(&report, FEE_RATE_WARNING_THRESHOLD) {
+            eprintln!(
+                "{}",
+                format!(
+                    "Warning: {} is only broadcastable up to {} sat/vB, below the threshold of {FEE_RATE_WARNING_THRESHOLD} sat/vB",
+                    sensitivity.transaction, sensitivity.max_fee_rate
+                )
+                .yellow()
+            );
+        }
+    }
+
+    fn reject_non_standard_transactions(
+        violations: Vec<StandardnessViolation>,
+    ) -> Result<(), Error> {
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(Error::Validation(ValidationError::NonStandardTransactions(
+                violations.iter().map(ToString::to_string).collect(),
+            ))),
+        }
+    }
+
+    // The operator's commitment secrets of a peg-out graph, e.g. to broadcast kick-off 1
+    fn operator_commitment_secrets<'a>(
+        private_data: &'a BitVMClientPrivateData,
+        operator_context: Option<&OperatorContext>,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<&'a HashMap<CommitmentMessageId, WinternitzSecret>, Error> {
+        let operator_context =
+            operator_context.ok_or(Error::Client(ClientError::OperatorContextNotDefined))?;
+        private_data
+            .commitment_secrets
+            .get(&operator_context.operator_public_key)
+            .and_then(|graph_secrets| graph_secrets.get(peg_out_graph_id.as_str()))
+            .ok_or(Error::Client(ClientError::CommitmentSecretsNotFound(
+                peg_out_graph_id.to_string(),
+            )))
+    }
+
+    fn commitment_secret<'a>(
+        commitment_secrets: &'a HashMap<CommitmentMessageId, WinternitzSecret>,
+        peg_out_graph_id: &PegOutGraphId,
+        message_id: CommitmentMessageId,
+    ) -> Result<&'a WinternitzSecret, Error> {
+        commitment_secrets.get(&message_id).ok_or(Error::Client(
+            ClientError::CommitmentSecretNotFound(peg_out_graph_id.to_string(), message_id),
+        ))
+    }
+
     fn find_peg_in_or_fail<'a>(
         data: &'a mut BitVMClientPublicData,
         peg_in_graph_id: &'a String,
@@ -1384,43 +4751,288 @@ impl BitVMClient {
         }
     }
 
-    async fn broadcast_tx(&self, tx: &Transaction) -> Result<Txid, Error> {
-        let status_message = broadcast_and_verify(&self.esplora, tx).await?;
+    // Key of the first user context defined, in the same order as the contexts are declared
+    fn user_keypair<'a>(
+        depositor_context: &'a Option<DepositorContext>,
+        operator_context: &'a Option<OperatorContext>,
+        verifier_context: &'a Option<VerifierContext>,
+        withdrawer_context: &'a Option<WithdrawerContext>,
+    ) -> Option<&'a Keypair> {
+        match (
+            depositor_context,
+            operator_context,
+            verifier_context,
+            withdrawer_context,
+        ) {
+            (Some(c), _, _, _) => Some(&c.depositor_keypair),
+            (_, Some(c), _, _) => Some(&c.operator_keypair),
+            (_, _, Some(c), _) => Some(&c.verifier_keypair),
+            (_, _, _, Some(c)) => Some(&c.withdrawer_keypair),
+            _ => None,
+        }
+    }
+
+    async fn broadcast_tx(&self, graph_id: &str, tx: &Transaction) -> Result<Txid, Error> {
+        let tx = &self.scrub_witnesses(tx).await;
+        let request = BroadcastRequest {
+            graph_id,
+            transaction: self.graph_tx_name(graph_id, tx),
+            tx,
+            network: self.source_network,
+            timestamp: self.clock.now(),
+        };
+        let approval_file_path = self.broadcast_approval_file_path();
+        let context = BroadcastContext {
+            esplora: &self.esplora,
+            data_store: &self.data_store,
+            approval_file_path: &approval_file_path,
+        };
+        if let Err((middleware, reason)) = self
+            .broadcast_middleware
+            .before_broadcast(&request, &context)
+            .await
+        {
+            return Err(Error::Client(ClientError::BroadcastBlocked {
+                txid: tx.compute_txid(),
+                middleware,
+                reason,
+            }));
+        }
+
+        let result = self.broadcast_tx_unaudited(tx).await;
+        let outcome = match &result {
+            Ok(status_message) => BroadcastOutcome::Accepted(status_message.to_string()),
+            Err(e) => BroadcastOutcome::Rejected(e.to_string()),
+        };
+        self.broadcast_middleware
+            .after_broadcast(&request, &outcome)
+            .await;
+        self.audit_broadcast(graph_id, tx, outcome);
+
+        result.map(|_| tx.compute_txid())
+    }
+
+    fn graph_tx_name(&self, graph_id: &str, tx: &Transaction) -> Option<&'static str> {
+        let txid = tx.compute_txid();
+        match self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|graph| graph.id() == graph_id)
+        {
+            Some(graph) => graph
+                .txids()
+                .into_iter()
+                .find_map(|(name, graph_txid)| (graph_txid == txid).then_some(name)),
+            None => self
+                .data
+                .peg_out_graphs
+                .iter()
+                .find(|graph| graph.id() == graph_id)
+                .and_then(|graph| graph.graph_tx_name(tx)),
+        }
+    }
+
+    fn broadcast_approval_file_path(&self) -> String {
+        format!(
+            "{}{BROADCAST_APPROVAL_DIRECTORY_SUFFIX}",
+            self.remote_file_path
+        )
+    }
+
+    // Publishes the user's approval of the broadcast of a transaction of the graph, required by
+    // the `approvals` rules of the broadcast policy. Transactions are named as in `txids`, e.g.
+    // `take_1`. Returns the approved txid.
+    pub async fn approve_broadcast(
+        &self,
+        graph_id: &str,
+        transaction: &str,
+    ) -> Result<Txid, Error> {
+        let txids = match self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|graph| graph.id() == graph_id)
+        {
+            Some(graph) => graph.txids(),
+            None => self
+                .data
+                .peg_out_graphs
+                .iter()
+                .find(|graph| graph.id() == graph_id)
+                .ok_or(Error::Client(ClientError::GraphNotFound(
+                    graph_id.to_string(),
+                )))?
+                .txids(),
+        };
+        let (_, txid) = txids
+            .into_iter()
+            .find(|(name, _)| is_same_transaction_name(transaction, name))
+            .ok_or(Error::Other(format!(
+                "Graph {graph_id} has no transaction {transaction}"
+            )))?;
+        let keypair = Self::user_keypair(
+            &self.depositor_context,
+            &self.operator_context,
+            &self.verifier_context,
+            &self.withdrawer_context,
+        )
+        .ok_or(Error::Client(ClientError::NoUserContextDefined))?;
+
+        let approval = BroadcastApproval::new(keypair, graph_id, txid, self.clock.now());
+        self.data_store
+            .write_data_by_key(
+                &broadcast_approval_file_name(&txid, &approval.public_key),
+                &serialize(&approval),
+                Some(&self.broadcast_approval_file_path()),
+            )
+            .await
+            .map_err(Error::Other)?;
+
+        Ok(txid)
+    }
+
+    async fn scrub_witnesses(&self, tx: &Transaction) -> Transaction {
+        let mut tx = tx.clone();
+        if !self.witness_scrubbing {
+            return tx;
+        }
+        // Signature opcodes need the outputs spent by all inputs
+        let Some(prevouts) = get_prevouts(&self.esplora, &tx)
+            .await
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+        else {
+            eprintln!(
+                "Outputs spent by {} not found, its witnesses are not scrubbed",
+                tx.compute_txid()
+            );
+            return tx;
+        };
+        for scrubbed_input in scrub_witnesses(&mut tx, &prevouts) {
+            println!(
+                "Scrubbed witness of input {} of {}: {:?}",
+                scrubbed_input.input_index,
+                tx.compute_txid(),
+                scrubbed_input.fixes
+            );
+        }
+
+        tx
+    }
+
+    async fn broadcast_tx_unaudited(&self, tx: &Transaction) -> Result<&'static str, Error> {
+        let status_message = match broadcast_and_verify(&self.esplora, tx).await {
+            Err(Error::Esplora(e))
+                if self.script_diagnostics && is_script_verify_rejection(&e.to_string()) =>
+            {
+                return Err(Error::Transaction(
+                    TransactionError::ScriptVerificationFailed {
+                        reject_reason: e.to_string(),
+                        diagnostics: diagnose_input_scripts(&self.esplora, tx).await,
+                    },
+                ));
+            }
+            result => result?,
+        };
+
+        let txid = tx.compute_txid();
+        println!("{} Txid: {}", status_message, txid.to_string().green());
+
+        Ok(status_message)
+    }
+
+    fn audit_broadcast(&self, graph_id: &str, tx: &Transaction, outcome: BroadcastOutcome) {
+        let Some(keypair) = Self::user_keypair(
+            &self.depositor_context,
+            &self.operator_context,
+            &self.verifier_context,
+            &self.withdrawer_context,
+        ) else {
+            eprintln!(
+                "No user context defined, broadcast of {} is not audited",
+                tx.compute_txid()
+            );
+            return;
+        };
+        if let Err(e) =
+            append_broadcast_audit_record(&self.local_file_path, keypair, graph_id, tx, outcome)
+        {
+            eprintln!("Failed to write broadcast audit record: {e}");
+        }
+    }
+
+    // Confirmed transactions of the graph whose witness differs from every witness this client
+    // broadcast them with
+    pub async fn check_confirmed_witnesses(
+        &self,
+        graph_id: &GraphId,
+    ) -> Result<Vec<WitnessMismatch>, Error> {
+        let (records, _) = self.broadcast_history(graph_id)?;
+        let txids: HashSet<Txid> = records.iter().map(|record| record.txid).collect();
+
+        let mut witness_mismatches = Vec::new();
+        for txid in txids {
+            let status = self
+                .esplora
+                .get_tx_status(&txid)
+                .await
+                .map_err(Error::Esplora)?;
+            let (true, Some(block_hash)) = (status.confirmed, status.block_hash) else {
+                continue;
+            };
+            if let Some(witness_mismatch) = self
+                .find_witness_mismatch(&records, &txid, block_hash)
+                .await?
+            {
+                witness_mismatches.push(witness_mismatch);
+            }
+        }
 
-        let txid = tx.compute_txid();
-        println!("{} Txid: {}", status_message, txid.to_string().green());
+        Ok(witness_mismatches)
+    }
 
-        Ok(txid)
+    async fn find_witness_mismatch(
+        &self,
+        records: &[BroadcastAuditRecord],
+        txid: &Txid,
+        block_hash: BlockHash,
+    ) -> Result<Option<WitnessMismatch>, Error> {
+        let confirmed_tx = self.esplora.get_tx(txid).await.map_err(Error::Esplora)?;
+
+        Ok(confirmed_tx.and_then(|tx| WitnessMismatch::find(records, &tx, block_hash)))
+    }
+
+    // Broadcast attempts recorded for the graph, with the issues found in the whole audit file
+    pub fn broadcast_history(
+        &self,
+        graph_id: &GraphId,
+    ) -> Result<(Vec<BroadcastAuditRecord>, Vec<AuditTrailIssue>), Error> {
+        let (records, issues) = read_broadcast_audit_records(&self.local_file_path)
+            .map_err(|e| Error::Other(format!("Could not read broadcast audit file: {e}")))?;
+        let records = records
+            .into_iter()
+            .filter(|record| record.graph_id == *graph_id)
+            .collect();
+
+        Ok((records, issues))
     }
 
+    // Saves the nonces of the graph right away
     fn merge_secret_nonces(
         &mut self,
         graph_id: &str,
         secret_nonces: HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
-        self.private_data
-            .secret_nonces
-            .entry(self.verifier_context.as_ref().unwrap().verifier_public_key)
-            .or_default();
-
-        if !self.private_data.secret_nonces
-            [&self.verifier_context.as_ref().unwrap().verifier_public_key]
-            .contains_key(graph_id)
-        {
-            self.private_data
-                .secret_nonces
-                .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
-                .unwrap()
-                .insert(graph_id.to_string(), HashMap::new());
-        }
-
+    ) -> Result<(), Error> {
+        let verifier_public_key = self.verifier_context.as_ref().unwrap().verifier_public_key;
         self.private_data
             .secret_nonces
-            .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
-            .unwrap()
-            .get_mut(graph_id)
-            .unwrap()
+            .entry(verifier_public_key)
+            .or_default()
+            .entry(graph_id.to_string())
+            .or_default()
             .extend(secret_nonces);
+        self.save_private_graph_data(PrivateDataRole::Verifier, &verifier_public_key, graph_id)
     }
 
     pub fn generate_connector_z_taproot_address(
@@ -1428,7 +5040,7 @@ impl BitVMClient {
         source_network: Network,
         recipient_address: &str,
         depositor_taproot_key: &XOnlyPublicKey,
-    ) -> Address {
+    ) -> Result<Address, Error> {
         let connector_z = ConnectorZ::new(
             source_network,
             recipient_address,
@@ -1438,8 +5050,9 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
-        );
-        connector_z.generate_taproot_address()
+        )
+        .map_err(Error::Validation)?;
+        Ok(connector_z.generate_taproot_address())
     }
 
     pub fn generate_presign_pegin_confirm_tx(
@@ -1449,7 +5062,7 @@ impl BitVMClient {
         amount: Amount,
         depositor_taproot_key: &XOnlyPublicKey,
         outpoint: OutPoint,
-    ) -> String {
+    ) -> Result<String, Error> {
         let connector_z = ConnectorZ::new(
             source_network,
             recipient_address,
@@ -1459,7 +5072,8 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
-        );
+        )
+        .map_err(Error::Validation)?;
         let connector_0 = Connector0::new(
             source_network,
             &self
@@ -1477,6 +5091,7 @@ impl BitVMClient {
                 .unwrap()
                 .n_of_n_public_keys
                 .clone(),
+            MIN_RELAY_FEE_RATE,
         );
         let secret_nonces_0 =
             peg_in_confirm_tx.push_nonces(self.verifier_context.as_ref().unwrap());
@@ -1485,8 +5100,8 @@ impl BitVMClient {
             self.verifier_context.as_ref().unwrap(),
             &connector_z,
             &secret_nonces_0,
-        );
-        serialize_hex(&(peg_in_confirm_tx.tx_mut()))
+        )?;
+        Ok(serialize_hex(&(peg_in_confirm_tx.tx_mut())))
     }
 
     pub fn generate_presign_pegin_deposit_tx(
@@ -1496,7 +5111,7 @@ impl BitVMClient {
         recipient_address: &str,
         depositor_public_key: &PublicKey,
         outpoint: OutPoint,
-    ) -> String {
+    ) -> Result<String, Error> {
         let depositor_taproot_key = XOnlyPublicKey::from(*depositor_public_key);
         let connector_z = ConnectorZ::new(
             source_network,
@@ -1507,14 +5122,17 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
-        );
+        )
+        .map_err(Error::Validation)?;
         let mut peg_in_deposit_tx = PegInDepositTransaction::new_for_validation(
             source_network,
             depositor_public_key,
             &connector_z,
             Input { outpoint, amount },
-        );
-        serialize_hex(&(peg_in_deposit_tx.tx_mut()))
+            DepositorAddressType::P2wsh,
+            MIN_RELAY_FEE_RATE,
+        )?;
+        Ok(serialize_hex(&(peg_in_deposit_tx.tx_mut())))
     }
 
     pub fn generate_presign_pegin_refund_tx(
@@ -1524,7 +5142,7 @@ impl BitVMClient {
         recipient_address: &str,
         depositor_public_key: &PublicKey,
         outpoint: OutPoint,
-    ) -> String {
+    ) -> Result<String, Error> {
         let depositor_taproot_key: XOnlyPublicKey = XOnlyPublicKey::from(*depositor_public_key);
         let connector_z = ConnectorZ::new(
             source_network,
@@ -1535,14 +5153,16 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
-        );
+        )
+        .map_err(Error::Validation)?;
         let mut peg_in_refund_tx = PegInRefundTransaction::new_for_validation(
             source_network,
             depositor_public_key,
             &connector_z,
             Input { outpoint, amount },
+            MIN_RELAY_FEE_RATE,
         );
-        serialize_hex(&(peg_in_refund_tx.tx_mut()))
+        Ok(serialize_hex(&(peg_in_refund_tx.tx_mut())))
     }
 
     pub fn push_verifier_signature(&mut self, graph_id: &GraphId) {
@@ -1550,13 +5170,420 @@ impl BitVMClient {
             .verifier_context
             .as_ref()
             .expect("Can only be called by a verifier!");
+        if self.is_abandoned(graph_id) {
+            eprintln!("Refusing to pre-sign graph {graph_id}: not pre-signed before its deadline");
+            return;
+        }
+        if let Err(err) = self.data.graph_mut(graph_id).check_protocol_params() {
+            eprintln!(
+                "Refusing to pre-sign graph {graph_id}: {}",
+                Error::Validation(err)
+            );
+            return;
+        }
+        if let Err(err) = self.data.graph_mut(graph_id).check_segment_layout() {
+            eprintln!(
+                "Refusing to pre-sign graph {graph_id}: {}",
+                Error::Chunker(err)
+            );
+            return;
+        }
+        if let Err(err) = self.check_commitment_key_uniqueness(graph_id) {
+            eprintln!(
+                "Refusing to pre-sign graph {graph_id}: {}",
+                Error::Validation(err)
+            );
+            return;
+        }
+        self.check_committee(graph_id);
 
         let graph = self.data.graph_mut(graph_id);
-        graph.verifier_sign(
+        if let Err(err) = graph.verifier_sign(
             verifier,
             &self.private_data.secret_nonces
                 [&self.verifier_context.as_ref().unwrap().verifier_public_key][graph_id],
-        );
+        ) {
+            eprintln!("Failed to pre-sign graph {graph_id}: {err}");
+        }
+    }
+
+    // A graph committing to the Winternitz public key of an earlier graph is never signed, its
+    // operator reused a one-time signature secret
+    fn check_commitment_key_uniqueness(&self, graph_id: &GraphId) -> Result<(), ValidationError> {
+        match commitment_key_reuses(&self.data.peg_out_graphs)
+            .into_iter()
+            .find(|reuse| reuse.graph_id == *graph_id)
+        {
+            Some(reuse) => Err(reuse.into()),
+            None => Ok(()),
+        }
+    }
+
+    // Flags every graph reusing a commitment public key as soon as it is synced, each one once
+    fn report_commitment_key_reuses(&mut self) {
+        let reuses = commitment_key_reuses(&self.data.peg_out_graphs);
+        for reuse in reuses
+            .iter()
+            .filter(|reuse| !self.commitment_key_reuses.contains(*reuse))
+        {
+            eprintln!(
+                "{}",
+                format!("HIGH SEVERITY: {reuse}, the operator reused a one-time signature secret. The graph will not be signed.").red()
+            );
+        }
+        self.commitment_key_reuses = reuses;
+    }
+
+    pub fn commitment_key_reuses(&self) -> &[CommitmentKeyReuse] {
+        &self.commitment_key_reuses
+    }
+
+    // See `BaseGraph::is_abandoned`
+    fn is_abandoned(&mut self, graph_id: &GraphId) -> bool {
+        let verifier_pubkeys = self
+            .verifier_context
+            .as_ref()
+            .expect("Can only be called by a verifier!")
+            .n_of_n_public_keys
+            .clone();
+        self.data
+            .graph_mut(graph_id)
+            .is_abandoned(&verifier_pubkeys, self.clock.now())
+    }
+
+    // Graphs stay with the committee they were created with. After a verifier key rotation the
+    // client has to be run with the previous committee, and so the retired key, to sign them.
+    fn check_committee(&self, graph_id: &GraphId) {
+        let verifier = self
+            .verifier_context
+            .as_ref()
+            .expect("Can only be called by a verifier!");
+        let peg_in_graph_id = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == graph_id)
+            .map_or(graph_id, |peg_out_graph| &*peg_out_graph.peg_in_graph_id);
+        let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id() == peg_in_graph_id)
+        else {
+            return;
+        };
+
+        if peg_in_graph.n_of_n_public_keys() != verifier.n_of_n_public_keys.as_slice()
+            || !verifier
+                .n_of_n_public_keys
+                .contains(&verifier.verifier_public_key)
+        {
+            panic!(
+                "Graph {graph_id} belongs to a committee verifier {} is not a member of",
+                verifier.verifier_public_key
+            );
+        }
+    }
+
+    // Invalidates pre-signed transactions that have to be re-signed, e.g. after a connector
+    // change, without recreating the whole graph. Transactions already on chain cannot be resigned.
+    pub async fn invalidate_presigned_transactions(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+        transactions: &[PegOutPresignedTransaction],
+    ) -> Result<(), Error> {
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        for transaction in transactions {
+            let txid = graph.presigned_txid(*transaction);
+            if self
+                .esplora
+                .get_tx(&txid)
+                .await
+                .is_ok_and(|tx| tx.is_some())
+            {
+                return Err(Error::Transaction(TransactionError::AlreadyMined(txid)));
+            }
+        }
+
+        graph.invalidate_presigned_transactions(transactions)
+    }
+
+    pub fn push_verifier_resign_nonces(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<(), Error> {
+        let verifier = self
+            .verifier_context
+            .as_ref()
+            .expect("Can only be called by a verifier!");
+
+        self.check_commitment_key_uniqueness(peg_out_graph_id)
+            .map_err(Error::Validation)?;
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        graph.check_protocol_params().map_err(Error::Validation)?;
+        graph.check_segment_layout().map_err(Error::Chunker)?;
+        let secret_nonces = graph.push_verifier_resign_nonces(verifier);
+        self.merge_secret_nonces(peg_out_graph_id, secret_nonces)
+    }
+
+    pub fn push_verifier_resign_signature(
+        &mut self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<(), Error> {
+        let verifier = self
+            .verifier_context
+            .as_ref()
+            .expect("Can only be called by a verifier!");
+
+        self.check_commitment_key_uniqueness(peg_out_graph_id)
+            .map_err(Error::Validation)?;
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        graph.check_protocol_params().map_err(Error::Validation)?;
+        graph.check_segment_layout().map_err(Error::Chunker)?;
+        let secret_nonces = self
+            .private_data
+            .secret_nonces
+            .get(&verifier.verifier_public_key)
+            .and_then(|graphs| graphs.get(peg_out_graph_id.as_str()))
+            .ok_or_else(|| {
+                Error::Graph(GraphError::SecretNoncesNotFound(
+                    peg_out_graph_id.to_string(),
+                ))
+            })?;
+        graph.verifier_resign(verifier, secret_nonces)
+    }
+
+    // Where the verifier stands in the signing ceremony of every pre-signed transaction of the graph
+    pub fn ceremony_status(&self, graph_id: &GraphId) -> Result<Vec<CeremonyStatus>, Error> {
+        let verifier = self
+            .verifier_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::VerifierContextNotDefined))?;
+        let secret_nonces = self
+            .private_data
+            .secret_nonces
+            .get(&verifier.verifier_public_key)
+            .and_then(|graphs| graphs.get(graph_id.as_str()));
+
+        if let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id() == graph_id)
+        {
+            return Ok(vec![peg_in_graph.ceremony_status(verifier, secret_nonces)]);
+        }
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == graph_id)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+
+        Ok(peg_out_graph
+            .ceremony_status(verifier, secret_nonces)
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect())
+    }
+
+    // Continues the verifier's part of the signing ceremony of the graph, e.g. after a crash
+    // between pushing nonces and signing. Pushes the nonces not pushed yet and signs every
+    // transaction whose nonces are complete. Peg-out transactions whose secret nonces were lost
+    // before they were saved start a re-sign round, so the committee signs them again with fresh
+    // nonces. Returns where the ceremony stands afterwards.
+    pub async fn resume_ceremony(
+        &mut self,
+        graph_id: &GraphId,
+    ) -> Result<Vec<CeremonyStatus>, Error> {
+        let statuses = self.ceremony_status(graph_id)?;
+        if self.is_abandoned(graph_id) {
+            return Err(Error::Graph(GraphError::PresigningAbandoned));
+        }
+        self.check_commitment_key_uniqueness(graph_id)
+            .map_err(Error::Validation)?;
+        let graph = self.data.graph_mut(graph_id);
+        graph.check_protocol_params().map_err(Error::Validation)?;
+        graph.check_segment_layout().map_err(Error::Chunker)?;
+
+        let verifier = self
+            .verifier_context
+            .as_ref()
+            .expect("Checked by ceremony_status");
+        let secret_nonces = self
+            .private_data
+            .secret_nonces
+            .get(&verifier.verifier_public_key)
+            .and_then(|graphs| graphs.get(graph_id.as_str()));
+        match self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == graph_id)
+        {
+            Some(peg_out_graph) => {
+                let lost_transactions: Vec<PegOutPresignedTransaction> = peg_out_graph
+                    .ceremony_status(verifier, secret_nonces)
+                    .into_iter()
+                    .filter(|(_, status)| status.step == CeremonyStep::NoncesLost)
+                    .map(|(transaction, _)| transaction)
+                    .collect();
+                if !lost_transactions.is_empty() {
+                    let peg_out_graph_id = peg_out_graph.peg_out_graph_id().clone();
+                    println!(
+                        "Secret nonces of {} of graph {graph_id} were lost, starting a re-sign round",
+                        lost_transactions
+                            .iter()
+                            .map(|transaction| transaction.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    self.invalidate_presigned_transactions(&peg_out_graph_id, &lost_transactions)
+                        .await?;
+                    self.push_verifier_resign_nonces(&peg_out_graph_id)?;
+                }
+            }
+            // Nonces contributed to the peg-in confirm are never replaced
+            None => {
+                if let Some(status) = statuses
+                    .iter()
+                    .find(|status| status.step == CeremonyStep::NoncesLost)
+                {
+                    return Err(Error::Graph(GraphError::SecretNoncesLost(
+                        status.name.clone(),
+                    )));
+                }
+            }
+        }
+
+        if self
+            .ceremony_status(graph_id)?
+            .iter()
+            .any(|status| status.step == CeremonyStep::PushNonces)
+        {
+            self.push_verifier_nonces(graph_id);
+        }
+
+        if self
+            .ceremony_status(graph_id)?
+            .iter()
+            .any(|status| status.step == CeremonyStep::Sign)
+        {
+            let verifier = self
+                .verifier_context
+                .as_ref()
+                .expect("Checked by ceremony_status");
+            let secret_nonces =
+                &self.private_data.secret_nonces[&verifier.verifier_public_key][graph_id.as_str()];
+            match self
+                .data
+                .peg_out_graphs
+                .iter_mut()
+                .find(|peg_out_graph| peg_out_graph.id() == graph_id)
+            {
+                Some(peg_out_graph) => peg_out_graph.verifier_sign_pending(verifier, secret_nonces),
+                None => self
+                    .data
+                    .graph_mut(graph_id)
+                    .verifier_sign(verifier, secret_nonces),
+            }?;
+        }
+
+        self.ceremony_status(graph_id)
+    }
+
+    pub fn resign_status(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+    ) -> Result<Vec<PegOutResignStatus>, Error> {
+        let graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.to_string(),
+            )))?;
+        let n_of_n_public_keys = self
+            .verifier_context
+            .as_ref()
+            .map(|context| context.n_of_n_public_keys.clone())
+            .or(self
+                .operator_context
+                .as_ref()
+                .map(|context| context.n_of_n_public_keys.clone()))
+            .ok_or(Error::Client(ClientError::NoUserContextDefined))?;
+
+        Ok(graph.resign_status(&n_of_n_public_keys))
+    }
+
+    // Reports what a pre-signed transaction still misses before it can be broadcast, instead of
+    // failing while finalizing it. The committee is the one of the peg-in graph.
+    pub async fn ready_to_broadcast(
+        &self,
+        peg_out_graph_id: &PegOutGraphId,
+        transaction: PegOutPresignedTransaction,
+    ) -> Result<(), Error> {
+        let graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.to_string(),
+            )))?;
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(&graph.peg_in_graph_id))
+            .ok_or(Error::Client(ClientError::PegInGraphNotFound(
+                graph.peg_in_graph_id.to_string(),
+            )))?;
+
+        graph
+            .ready_to_broadcast(
+                &self.esplora,
+                transaction,
+                peg_in_graph.n_of_n_public_keys(),
+            )
+            .await
+            .map_err(|missing_prereqs| {
+                Error::Graph(GraphError::NotReadyToBroadcast(missing_prereqs))
+            })
+    }
+
+    // Checks a peg-in or peg-out graph without touching the chain, see `integrity_issues` of the
+    // graphs. Peg-out signatures are checked against the committee of the peg-in graph.
+    pub fn graph_integrity_issues(
+        &self,
+        graph_id: &GraphId,
+    ) -> Result<Vec<GraphIntegrityIssue>, Error> {
+        if let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id() == graph_id)
+        {
+            return Ok(peg_in_graph.integrity_issues());
+        }
+
+        let graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == graph_id)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(&graph.peg_in_graph_id))
+            .ok_or(Error::Client(ClientError::PegInGraphNotFound(
+                graph.peg_in_graph_id.to_string(),
+            )))?;
+
+        Ok(graph.integrity_issues(peg_in_graph.n_of_n_public_keys()))
     }
 
     // pub async fn execute_possible_txs(
@@ -1649,163 +5676,19 @@ impl BitVMClient {
 
 impl ClientCliQuery for BitVMClient {
     async fn get_unused_peg_in_graphs(&self) -> Vec<Value> {
-        join_all(self.data.peg_in_graphs.iter().filter_map(|peg_in| {
-            Some(async move {
-                match peg_in.depositor_status(&self.esplora).await {
-                    PegInDepositorStatus::PegInConfirmComplete => match self.data.peg_out_graphs.iter().find(|peg_out| peg_out.peg_in_graph_id == *peg_in.id()) {
-                        Some(peg_out) => match peg_out.operator_status(&self.esplora).await {
-                            PegOutOperatorStatus::PegOutWait => Some(json!({
-                                "graph_id": peg_in.id(),
-                                "amount": peg_in.peg_in_confirm_transaction.prev_outs()[0].value.to_sat(),
-                                "source_outpoint": {
-                                    "txid": peg_in.peg_in_confirm_transaction.tx().compute_txid(),
-                                    "vout": 0
-                                },
-                            })),
-                            _ => None,
-                        },
-                        None => None,
-                    },
-                    _ => None,
-                }
-            })
-        }))
-        .await
-        .iter()
-        .filter_map(|v| {
-            v.clone()
-        })
-        .collect()
+        self.queries().get_unused_peg_in_graphs().await
     }
 
     async fn get_depositor_status(&self, depositor_public_key: &PublicKey) -> Vec<Value> {
-        join_all(
-            self.data
-                .peg_in_graphs
-                .iter()
-                .filter(|&graph| graph.depositor_public_key.eq(depositor_public_key))
-                .map(|graph| async {
-                    let tx_ids = vec![
-                        graph.peg_in_deposit_transaction.tx().compute_txid(),
-                        graph.peg_in_confirm_transaction.tx().compute_txid(),
-                        graph.peg_in_refund_transaction.tx().compute_txid(),
-                    ];
-                    let tx_statuses_results = get_tx_statuses(&self.esplora, &tx_ids).await;
-                    let blockchain_height = self.esplora.get_height().await;
-                    let status = graph.interpret_depositor_status(
-                        &tx_statuses_results[0],
-                        &tx_statuses_results[1],
-                        &tx_statuses_results[2],
-                        blockchain_height,
-                    );
-
-                    let tx_statuses = tx_statuses_results
-                        .iter()
-                        .map(|tx_status| {
-                            tx_status.as_ref().unwrap_or(&TxStatus {
-                                confirmed: false,
-                                block_height: None,
-                                block_hash: None,
-                                block_time: None,
-                            })
-                        })
-                        .collect::<Vec<_>>();
-                    let tx_json_values = tx_statuses
-                        .iter()
-                        .enumerate()
-                        .map(|(i, tx_status)| {
-                            json!({
-                            "type": match i {
-                                0 => "peg_in_deposit",
-                                1 => "peg_in_confirm",
-                                2 => "peg_in_refund",
-                                _ => unreachable!(),
-                            },
-                            "txid": tx_ids[i],
-                            "status": {
-                                "confirmed": tx_status.confirmed,
-                                "block_height": tx_status.block_height.unwrap_or(0),
-                                "block_hash": tx_status.block_hash.or(None),
-                                "block_time": tx_status.block_time.unwrap_or(0),
-                            }})
-                        })
-                        .collect::<Vec<_>>();
-
-                    json!({
-                        "type": "peg_in",
-                        "graph_id": graph.id(),
-                        "status": status.to_string(),
-                        "amount": graph.peg_in_deposit_transaction.prev_outs()[0].value.to_sat(),
-                        "destination_address": graph.depositor_evm_address,
-                        "txs" : tx_json_values,
-                    })
-                }),
-        )
-        .await
+        self.queries()
+            .get_depositor_status(depositor_public_key)
+            .await
     }
 
     async fn get_withdrawer_status(&self, withdrawer_chain_address: &str) -> Vec<Value> {
-        join_all(
-            self.data
-                .peg_out_graphs
-                .iter()
-                .filter(|&graph| {
-                    if graph.peg_out_chain_event.is_some() {
-                        return graph
-                            .peg_out_chain_event
-                            .as_ref()
-                            .unwrap()
-                            .withdrawer_chain_address
-                            .eq(withdrawer_chain_address);
-                    }
-                    false
-                })
-                .map(|graph| async {
-                    let (tx_json_value, tx_status_result) = match &graph.peg_out_transaction {
-                        Some(tx) => {
-                            let txid = tx.tx().compute_txid();
-                            let tx_status_result = self.esplora.get_tx_status(&txid).await;
-                            let tx_status = tx_status_result.as_ref().unwrap_or(&TxStatus {
-                                confirmed: false,
-                                block_height: None,
-                                block_hash: None,
-                                block_time: None,
-                            });
-                            let tx_json_value = json!({
-                                "type": "peg_out",
-                                "txid": txid,
-                                "status": {
-                                    "confirmed": tx_status.confirmed,
-                                    "block_height": tx_status.block_height.unwrap_or(0),
-                                    "block_hash": tx_status.block_hash.or(None),
-                                    "block_time": tx_status.block_time.unwrap_or(0),
-                                }
-                            });
-
-                            (Some(tx_json_value), Some(tx_status_result))
-                        }
-                        None => (Some(json!([])), None),
-                    };
-                    let (peg_out_amount, destination_address) = match &graph.peg_out_chain_event {
-                        Some(peg_out_chain_event) => (
-                            peg_out_chain_event.amount.to_sat(),
-                            peg_out_chain_event.withdrawer_destination_address.clone(),
-                        ),
-                        None => (0, "".to_string()),
-                    };
-
-                    let status = graph.interpret_withdrawer_status(tx_status_result.as_ref());
-                    json!({
-                        "type": "peg_out",
-                        "graph_id": graph.id(),
-                        "status": status.to_string(),
-                        "amount": peg_out_amount,
-                        "destination_address": destination_address,
-                        "txs": tx_json_value,
-                    })
-                }),
-        )
-        .await
+        self.queries()
+            .get_withdrawer_status(withdrawer_chain_address)
+            .await
     }
 
     async fn get_depositor_transactions(
@@ -1836,7 +5719,8 @@ impl ClientCliQuery for BitVMClient {
             n_of_n_taproot_public_key,
             depositor_evm_address,
             deposit_input,
-        );
+        )
+        .map_err(|err| err.to_string())?;
 
         Ok(json!({
             "deposit": serialize_hex(peg_in_graph.peg_in_deposit_transaction.tx()),
@@ -1875,7 +5759,8 @@ impl ClientCliQuery for BitVMClient {
             depositor_evm_address,
             deposit_input,
             signatures,
-        );
+        )
+        .map_err(|err| err.to_string())?;
 
         let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
 
@@ -1896,7 +5781,10 @@ impl ClientCliQuery for BitVMClient {
         }
     }
 
-    async fn retry_broadcast_peg_in_deposit(&self, peg_in_graph_id: &str) -> Result<Value, String> {
+    async fn retry_broadcast_peg_in_deposit(
+        &self,
+        peg_in_graph_id: &PegInGraphId,
+    ) -> Result<Value, String> {
         let Some(peg_in_graph) = self
             .data
             .peg_in_graphs
@@ -0,0 +1,204 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use esplora_client::AsyncClient;
+use tokio::{process::Command, time::sleep};
+
+use crate::graphs::base::current_unix_time;
+
+// How often the chain tip is checked while waiting for blocks
+pub const DEFAULT_BLOCK_WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Wall time as seen by the client, e.g. for pre-signing deadlines. Tests substitute a `MockClock`
+// to move past deadlines without waiting for them.
+#[async_trait]
+pub trait Clock: Send + Sync {
+    // Seconds since the unix epoch
+    fn now(&self) -> u64;
+    async fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        current_unix_time()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await;
+    }
+}
+
+// Only moves when told to. Sleeping advances it instantly by the slept duration.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(now: u64) -> Self {
+        Self {
+            now: AtomicU64::new(now),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.fetch_add(duration.as_secs(), Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+// Source of new blocks, for waiting out confirmations and relative timelocks
+#[async_trait]
+pub trait BlockProducer: Send + Sync {
+    // Returns the height of the tip once it is at least `num_blocks` above the current one
+    async fn produce_blocks(&self, num_blocks: u32) -> Result<u32, String>;
+}
+
+// Chain tip that only moves when blocks are produced, instantly. Tests step it through exact
+// heights, e.g. timelock boundaries.
+#[derive(Debug, Default)]
+pub struct MockBlockProducer {
+    height: AtomicU32,
+}
+
+impl MockBlockProducer {
+    pub fn new(height: u32) -> Self {
+        Self {
+            height: AtomicU32::new(height),
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl BlockProducer for MockBlockProducer {
+    async fn produce_blocks(&self, num_blocks: u32) -> Result<u32, String> {
+        Ok(self.height.fetch_add(num_blocks, Ordering::SeqCst) + num_blocks)
+    }
+}
+
+// Waits for the network, or the regtest block generator, to mine the blocks
+pub struct WaitingBlockProducer {
+    esplora: AsyncClient,
+    clock: Arc<dyn Clock>,
+    poll_interval: Duration,
+}
+
+impl WaitingBlockProducer {
+    pub fn new(esplora: AsyncClient, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            esplora,
+            clock,
+            poll_interval: DEFAULT_BLOCK_WAIT_POLL_INTERVAL,
+        }
+    }
+}
+
+#[async_trait]
+impl BlockProducer for WaitingBlockProducer {
+    async fn produce_blocks(&self, num_blocks: u32) -> Result<u32, String> {
+        let target = tip_height(&self.esplora).await? + num_blocks;
+        wait_for_height(
+            &self.esplora,
+            self.clock.as_ref(),
+            self.poll_interval,
+            target,
+        )
+        .await
+    }
+}
+
+// Mines blocks on demand on regtest by running a command with the number of blocks appended as
+// its last argument, e.g. `regtest/generate-blocks.sh`, then waits for esplora to index them
+pub struct RegtestBlockProducer {
+    esplora: AsyncClient,
+    program: String,
+    args: Vec<String>,
+    poll_interval: Duration,
+}
+
+impl RegtestBlockProducer {
+    // The command line is split on whitespace, the first word is the program
+    pub fn new(esplora: AsyncClient, command: &str) -> Result<Self, String> {
+        let mut words = command.split_whitespace().map(String::from);
+        let program = words
+            .next()
+            .ok_or(String::from("Empty block generator command"))?;
+
+        Ok(Self {
+            esplora,
+            program,
+            args: words.collect(),
+            poll_interval: Duration::from_millis(100),
+        })
+    }
+}
+
+#[async_trait]
+impl BlockProducer for RegtestBlockProducer {
+    async fn produce_blocks(&self, num_blocks: u32) -> Result<u32, String> {
+        let target = tip_height(&self.esplora).await? + num_blocks;
+        if num_blocks > 0 {
+            let output = Command::new(&self.program)
+                .args(&self.args)
+                .arg(num_blocks.to_string())
+                .output()
+                .await
+                .map_err(|e| format!("Failed to run {}: {e}", self.program))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "{} failed with {}: {}",
+                    self.program,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+        }
+
+        wait_for_height(&self.esplora, &SystemClock, self.poll_interval, target).await
+    }
+}
+
+async fn tip_height(esplora: &AsyncClient) -> Result<u32, String> {
+    esplora
+        .get_height()
+        .await
+        .map_err(|e| format!("Failed to get the current block height: {e}"))
+}
+
+async fn wait_for_height(
+    esplora: &AsyncClient,
+    clock: &dyn Clock,
+    poll_interval: Duration,
+    target: u32,
+) -> Result<u32, String> {
+    loop {
+        let height = tip_height(esplora).await?;
+        if height >= target {
+            return Ok(height);
+        }
+        clock.sleep(poll_interval).await;
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphs::base::GraphId;
+
+use super::esplora::DEFAULT_FEE_RATE_CONFIRMATION_TARGET;
+
+// Blocks a broadcast is held back for at most, about a day
+pub const DEFAULT_MAX_DEFERRAL_BLOCKS: u32 = 144;
+
+// Transactions automatic mode may hold back while the mempool is congested. Only the operator's
+// reimbursement waits on them: the peg-out confirm releases kick-off 1 and take 1 pays out after
+// its window. Challenges, disproves, timeouts and the rest of the peg-out sequence race other
+// participants or a timelock, and are never deferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DeferrableTx {
+    PegOutConfirm,
+    Take1,
+}
+
+// Set with the `[congestion]` table in bridge.toml, or `--defer_above_fee_rate`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CongestionConfig {
+    // sat/vB above which deferrable transactions are held back
+    pub max_fee_rate: u64,
+    // Blocks after which a held back transaction is broadcast at any fee rate
+    #[serde(default = "default_max_deferral_blocks")]
+    pub max_deferral_blocks: u32,
+    // Blocks the estimated fee rate aims to confirm within
+    #[serde(default = "default_confirmation_target")]
+    pub confirmation_target: u16,
+}
+
+fn default_max_deferral_blocks() -> u32 {
+    DEFAULT_MAX_DEFERRAL_BLOCKS
+}
+
+fn default_confirmation_target() -> u16 {
+    DEFAULT_FEE_RATE_CONFIRMATION_TARGET
+}
+
+impl CongestionConfig {
+    pub fn new(max_fee_rate: u64) -> Self {
+        Self {
+            max_fee_rate,
+            max_deferral_blocks: DEFAULT_MAX_DEFERRAL_BLOCKS,
+            confirmation_target: DEFAULT_FEE_RATE_CONFIRMATION_TARGET,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastDecision {
+    Broadcast,
+    Defer {
+        fee_rate: u64,
+        blocks_left: u32, // until the transaction is broadcast regardless of the fee rate
+    },
+}
+
+// Holds back deferrable broadcasts while the estimated fee rate is above the threshold, until the
+// deadline counted from the block they were first held back at
+pub struct CongestionScheduler {
+    config: CongestionConfig,
+    deferred_since: HashMap<(GraphId, DeferrableTx), u32>,
+}
+
+impl CongestionScheduler {
+    pub fn new(config: CongestionConfig) -> Self {
+        Self {
+            config,
+            deferred_since: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &CongestionConfig {
+        &self.config
+    }
+
+    // Without an estimate the transaction is broadcast, a failing fee estimator must not delay the
+    // operator indefinitely
+    pub fn decide(
+        &mut self,
+        graph_id: &GraphId,
+        tx: DeferrableTx,
+        height: u32,
+        fee_rate: Option<u64>,
+    ) -> BroadcastDecision {
+        let key = (graph_id.clone(), tx);
+        let Some(fee_rate) = fee_rate.filter(|fee_rate| *fee_rate > self.config.max_fee_rate)
+        else {
+            self.deferred_since.remove(&key);
+            return BroadcastDecision::Broadcast;
+        };
+
+        let deferred_since = *self.deferred_since.entry(key.clone()).or_insert(height);
+        let deferred_blocks = height.saturating_sub(deferred_since);
+        if deferred_blocks >= self.config.max_deferral_blocks {
+            self.deferred_since.remove(&key);
+            return BroadcastDecision::Broadcast;
+        }
+
+        BroadcastDecision::Defer {
+            fee_rate,
+            blocks_left: self.config.max_deferral_blocks - deferred_blocks,
+        }
+    }
+}
@@ -2,7 +2,13 @@ use regex::Regex;
 use std::cmp::Ordering;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::{
+    error::err_to_string,
+    utils::{compress, decompress, DEFAULT_COMPRESSION_LEVEL},
+};
+
 use super::base::DataStoreDriver;
+use super::encryption::DataStoreEncryption;
 use super::local_file::LocalFile;
 use super::{
     aws_s3::AwsS3,
@@ -23,10 +29,11 @@ pub struct DataStore {
     ftps: Option<Ftps>,
     sftp: Option<Sftp>,
     local_file: Option<LocalFile>,
+    encryption: Option<DataStoreEncryption>,
 }
 
 impl DataStore {
-    pub async fn new() -> Self {
+    pub async fn new(user_profile: Option<&str>) -> Self {
         dotenv::dotenv().ok();
         let client_data_suffix = match dotenv::var("BRIDGE_DATA_STORE_CLIENT_DATA_SUFFIX") {
             Ok(suffix) => suffix,
@@ -40,6 +47,8 @@ impl DataStore {
             ftps: Ftps::new().await,
             sftp: Sftp::new().await,
             local_file: LocalFile::new(),
+            encryption: DataStoreEncryption::from_env(user_profile)
+                .unwrap_or_else(|err| panic!("{err}")),
         }
     }
 
@@ -90,16 +99,32 @@ impl DataStore {
         &self,
         key: &String,
         file_path: Option<&str>,
+    ) -> Result<Option<String>, String> {
+        let data = self.fetch_optional_data_by_key(key, file_path).await?;
+        if data.is_none() {
+            println!("No data file {} found", key);
+        }
+
+        Ok(data)
+    }
+
+    // Same as `fetch_data_by_key`, for files that are expected to be missing at times
+    pub async fn fetch_optional_data_by_key(
+        &self,
+        key: &str,
+        file_path: Option<&str>,
     ) -> Result<Option<String>, String> {
         match self.get_driver() {
             Ok(driver) => {
                 let json = driver.fetch_object(key, file_path).await;
                 if let Ok(data) = json {
                     // println!("Fetched data file: {}", key);
-                    return Ok(Some(data));
+                    return match &self.encryption {
+                        Some(encryption) => encryption.decrypt_string(&data).map(Some),
+                        None => Ok(Some(data)),
+                    };
                 }
 
-                println!("No data file {} found", key);
                 Ok(None)
             }
             Err(err) => Err(err.to_string()),
@@ -118,7 +143,11 @@ impl DataStore {
                     .unwrap()
                     .as_millis();
                 let file_name = self.create_file_name(time);
-                let response = driver.upload_object(&file_name, contents, file_path).await;
+                let contents = match &self.encryption {
+                    Some(encryption) => encryption.encrypt_string(contents)?,
+                    None => contents.clone(),
+                };
+                let response = driver.upload_object(&file_name, &contents, file_path).await;
 
                 match response {
                     Ok(_) => Ok(file_name),
@@ -129,6 +158,31 @@ impl DataStore {
         }
     }
 
+    // Writes under the given name, replacing an earlier file of that name. For files each client
+    // owns on its own, shared client data is only ever added with `write_data`.
+    pub async fn write_data_by_key(
+        &self,
+        key: &str,
+        contents: &String,
+        file_path: Option<&str>,
+    ) -> Result<(), String> {
+        match self.get_driver() {
+            Ok(driver) => {
+                let contents = match &self.encryption {
+                    Some(encryption) => encryption.encrypt_string(contents)?,
+                    None => contents.clone(),
+                };
+                let response = driver.upload_object(key, &contents, file_path).await;
+
+                match response {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(String::from("Failed to save data file")),
+                }
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
     pub async fn fetch_compressed_data_by_key(
         &self,
         key: &str,
@@ -139,7 +193,14 @@ impl DataStore {
                 let json = driver.fetch_compressed_object(key, file_path).await;
                 if let Ok((data, size)) = json {
                     // println!("Fetched data file: {}", key);
-                    return Ok((Some(data), size));
+                    return match &self.encryption {
+                        Some(encryption) => {
+                            let data =
+                                decompress(&encryption.decrypt(&data)?).map_err(err_to_string)?;
+                            Ok((Some(data), size))
+                        }
+                        None => Ok((Some(data), size)),
+                    };
                 }
 
                 println!("No data file {} found", key);
@@ -161,8 +222,16 @@ impl DataStore {
                     .unwrap()
                     .as_millis();
                 let file_name = self.create_file_name(time);
+                // Encrypted data does not compress, so it is compressed before being encrypted.
+                // The driver compression is then left to operate on the encrypted envelope.
+                let contents = match &self.encryption {
+                    Some(encryption) => encryption.encrypt(
+                        &compress(contents, DEFAULT_COMPRESSION_LEVEL).map_err(err_to_string)?,
+                    )?,
+                    None => contents.clone(),
+                };
                 let response = driver
-                    .upload_compressed_object(&file_name, contents, file_path)
+                    .upload_compressed_object(&file_name, &contents, file_path)
                     .await;
 
                 match response {
@@ -174,6 +243,34 @@ impl DataStore {
         }
     }
 
+    // Compressed counterpart of `write_data_by_key`
+    pub async fn write_compressed_data_by_key(
+        &self,
+        key: &str,
+        contents: &Vec<u8>,
+        file_path: Option<&str>,
+    ) -> Result<usize, String> {
+        match self.get_driver() {
+            Ok(driver) => {
+                let contents = match &self.encryption {
+                    Some(encryption) => encryption.encrypt(
+                        &compress(contents, DEFAULT_COMPRESSION_LEVEL).map_err(err_to_string)?,
+                    )?,
+                    None => contents.clone(),
+                };
+                let response = driver
+                    .upload_compressed_object(key, &contents, file_path)
+                    .await;
+
+                match response {
+                    Ok(size) => Ok(size),
+                    Err(_) => Err(String::from("Failed to save data file")),
+                }
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
     pub fn get_past_max_file_name_by_timestamp(
         &self,
         latest_timestamp: u64,
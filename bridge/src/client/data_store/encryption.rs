@@ -0,0 +1,169 @@
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+// To encrypt the objects written to the data store, add the following value to the .env file:
+// export BRIDGE_DATA_STORE_ENCRYPTION_KEY="<64 hex characters>"
+// The key is shared by all committee members using the same data store. A key for a single user
+// profile can be set with BRIDGE_DATA_STORE_ENCRYPTION_KEY_<USER_PROFILE>, e.g.
+// BRIDGE_DATA_STORE_ENCRYPTION_KEY_OPERATOR_ONE, which takes precedence over the shared variable.
+const ENCRYPTION_KEY_ENV_VAR: &str = "BRIDGE_DATA_STORE_ENCRYPTION_KEY";
+
+const ENVELOPE_MAGIC: &[u8; 4] = b"BVME";
+const ENVELOPE_VERSION: u8 = 1;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const HEADER_LENGTH: usize = ENVELOPE_MAGIC.len() + 1;
+const WRAPPED_KEY_LENGTH: usize = NONCE_LENGTH + KEY_LENGTH + TAG_LENGTH;
+
+// Envelope encryption of data store objects. Every object is encrypted with a fresh data key,
+// which is in turn encrypted (wrapped) with the committee key and stored alongside the object:
+// magic | version | wrapped key nonce | wrapped data key | data nonce | encrypted object
+// The header is authenticated as associated data of both encryptions.
+pub struct DataStoreEncryption {
+    committee_cipher: ChaCha20Poly1305,
+}
+
+impl DataStoreEncryption {
+    pub fn new(key: &[u8; KEY_LENGTH]) -> Self {
+        Self {
+            committee_cipher: ChaCha20Poly1305::new(Key::from_slice(key)),
+        }
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, String> {
+        let key: [u8; KEY_LENGTH] = hex::decode(key.trim())
+            .map_err(|err| format!("Invalid data store encryption key: {}", err))?
+            .try_into()
+            .map_err(|_| format!("Data store encryption key must be {KEY_LENGTH} bytes long"))?;
+
+        Ok(Self::new(&key))
+    }
+
+    pub fn from_env(user_profile: Option<&str>) -> Result<Option<Self>, String> {
+        dotenv::dotenv().ok();
+        let profile_key = user_profile.and_then(|profile| {
+            dotenv::var(format!(
+                "{ENCRYPTION_KEY_ENV_VAR}_{}",
+                env_var_suffix(profile)
+            ))
+            .ok()
+        });
+
+        match profile_key.or_else(|| dotenv::var(ENCRYPTION_KEY_ENV_VAR).ok()) {
+            Some(key) => Self::from_hex(&key).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn encrypt(&self, contents: &[u8]) -> Result<Vec<u8>, String> {
+        let header = envelope_header();
+
+        let data_key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let key_nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let wrapped_data_key = self
+            .committee_cipher
+            .encrypt(
+                &key_nonce,
+                Payload {
+                    msg: data_key.as_slice(),
+                    aad: &header,
+                },
+            )
+            .map_err(|_| String::from("Failed to wrap data key"))?;
+
+        let data_nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&data_key)
+            .encrypt(
+                &data_nonce,
+                Payload {
+                    msg: contents,
+                    aad: &header,
+                },
+            )
+            .map_err(|_| String::from("Failed to encrypt data"))?;
+
+        let mut envelope = Vec::with_capacity(
+            HEADER_LENGTH + WRAPPED_KEY_LENGTH + NONCE_LENGTH + ciphertext.len(),
+        );
+        envelope.extend_from_slice(&header);
+        envelope.extend_from_slice(&key_nonce);
+        envelope.extend_from_slice(&wrapped_data_key);
+        envelope.extend_from_slice(&data_nonce);
+        envelope.extend_from_slice(&ciphertext);
+
+        Ok(envelope)
+    }
+
+    pub fn decrypt(&self, envelope: &[u8]) -> Result<Vec<u8>, String> {
+        if envelope.len() < HEADER_LENGTH + WRAPPED_KEY_LENGTH + NONCE_LENGTH + TAG_LENGTH {
+            return Err(String::from("Encrypted data is too short"));
+        }
+
+        let (header, rest) = envelope.split_at(HEADER_LENGTH);
+        if header[..ENVELOPE_MAGIC.len()] != ENVELOPE_MAGIC[..] {
+            return Err(String::from("Data is not encrypted"));
+        }
+        if header[ENVELOPE_MAGIC.len()] != ENVELOPE_VERSION {
+            return Err(format!(
+                "Unsupported encrypted data version: {}",
+                header[ENVELOPE_MAGIC.len()]
+            ));
+        }
+
+        let (key_nonce, rest) = rest.split_at(NONCE_LENGTH);
+        let (wrapped_data_key, rest) = rest.split_at(KEY_LENGTH + TAG_LENGTH);
+        let (data_nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+
+        let data_key = self
+            .committee_cipher
+            .decrypt(
+                Nonce::from_slice(key_nonce),
+                Payload {
+                    msg: wrapped_data_key,
+                    aad: header,
+                },
+            )
+            .map_err(|_| String::from("Failed to unwrap data key, check the encryption key"))?;
+
+        ChaCha20Poly1305::new(Key::from_slice(&data_key))
+            .decrypt(
+                Nonce::from_slice(data_nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| String::from("Failed to decrypt data"))
+    }
+
+    pub fn encrypt_string(&self, contents: &str) -> Result<String, String> {
+        self.encrypt(contents.as_bytes()).map(hex::encode)
+    }
+
+    pub fn decrypt_string(&self, contents: &str) -> Result<String, String> {
+        let envelope = hex::decode(contents.trim())
+            .map_err(|err| format!("Failed to decode encrypted data: {}", err))?;
+        String::from_utf8(self.decrypt(&envelope)?)
+            .map_err(|err| format!("Failed to parse decrypted data: {}", err))
+    }
+}
+
+fn envelope_header() -> [u8; HEADER_LENGTH] {
+    let mut header = [0u8; HEADER_LENGTH];
+    header[..ENVELOPE_MAGIC.len()].copy_from_slice(ENVELOPE_MAGIC);
+    header[ENVELOPE_MAGIC.len()] = ENVELOPE_VERSION;
+    header
+}
+
+pub(crate) fn env_var_suffix(user_profile: &str) -> String {
+    user_profile
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
@@ -1,6 +1,7 @@
-pub mod aws_s3;
+pub(crate) mod aws_s3;
 pub mod base;
 pub mod data_store;
-pub mod ftp;
-pub mod local_file;
-pub mod sftp;
+pub mod encryption;
+pub(crate) mod ftp;
+pub(crate) mod local_file;
+pub(crate) mod sftp;
@@ -0,0 +1,117 @@
+use std::{
+    collections::HashMap,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+};
+
+use tokio::{sync::Notify, task::spawn_blocking};
+
+use crate::{
+    common::ZkProofVerifyingKey,
+    connectors::connector_c::ConnectorC,
+    graphs::{
+        base::GraphId,
+        peg_out::{
+            evaluate_asserted_proof, AssertedCommitments, DisproveWitness, ProofVerification,
+        },
+    },
+};
+
+#[derive(Clone)]
+enum Evaluation {
+    Running,
+    Finished(ProofVerification, Option<DisproveWitness>),
+}
+
+// Evaluations of asserted proofs by peg-out graph. Running the chunker takes minutes, so verifiers
+// start it in the background as soon as assert final confirms and keep the disprove witness, the
+// disprove transaction is then built right away once they decide to disprove. Inconclusive
+// evaluations are dropped, so they are tried again.
+#[derive(Clone, Default)]
+pub struct DisprovePrecomputation {
+    evaluations: Arc<Mutex<HashMap<GraphId, Evaluation>>>,
+    finished: Arc<Notify>,
+}
+
+impl DisprovePrecomputation {
+    // Does nothing if the graph is already evaluated or being evaluated
+    pub fn start(
+        &self,
+        graph_id: &GraphId,
+        connector_c: ConnectorC,
+        commitments: AssertedCommitments,
+        verifying_key: ZkProofVerifyingKey,
+    ) {
+        {
+            let mut evaluations = self.evaluations.lock().unwrap();
+            if evaluations.contains_key(graph_id) {
+                return;
+            }
+            evaluations.insert(graph_id.clone(), Evaluation::Running);
+        }
+
+        let precomputation = self.clone();
+        let graph_id = graph_id.clone();
+        spawn_blocking(move || {
+            let (verification, disprove_witness) = catch_unwind(AssertUnwindSafe(|| {
+                evaluate_asserted_proof(&connector_c, commitments, &verifying_key)
+            }))
+            .unwrap_or_else(|_| {
+                (
+                    ProofVerification::Inconclusive("the chunker panicked".to_string()),
+                    None,
+                )
+            });
+            println!("Evaluated the asserted proof of peg-out graph {graph_id}: {verification:?}");
+            precomputation.insert(&graph_id, verification, disprove_witness);
+            precomputation.finished.notify_one();
+        });
+    }
+
+    pub fn insert(
+        &self,
+        graph_id: &GraphId,
+        verification: ProofVerification,
+        disprove_witness: Option<DisproveWitness>,
+    ) {
+        let mut evaluations = self.evaluations.lock().unwrap();
+        match verification {
+            ProofVerification::Inconclusive(_) => evaluations.remove(graph_id),
+            _ => evaluations.insert(
+                graph_id.clone(),
+                Evaluation::Finished(verification, disprove_witness),
+            ),
+        };
+    }
+
+    pub fn is_running(&self, graph_id: &GraphId) -> bool {
+        matches!(
+            self.evaluations.lock().unwrap().get(graph_id),
+            Some(Evaluation::Running)
+        )
+    }
+
+    pub fn is_started(&self, graph_id: &GraphId) -> bool {
+        self.evaluations.lock().unwrap().contains_key(graph_id)
+    }
+
+    // None until the evaluation finished
+    pub fn verification(&self, graph_id: &GraphId) -> Option<ProofVerification> {
+        match self.evaluations.lock().unwrap().get(graph_id) {
+            Some(Evaluation::Finished(verification, _)) => Some(verification.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn disprove_witness(&self, graph_id: &GraphId) -> Option<DisproveWitness> {
+        match self.evaluations.lock().unwrap().get(graph_id) {
+            Some(Evaluation::Finished(_, disprove_witness)) => disprove_witness.clone(),
+            _ => None,
+        }
+    }
+
+    // Resolves once a background evaluation finished, including one that finished before waiting
+    pub async fn finished(&self) {
+        self.finished.notified().await;
+    }
+}
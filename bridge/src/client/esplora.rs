@@ -1,10 +1,34 @@
+use std::collections::HashMap;
+
 use bitcoin::Network;
+use esplora_client::AsyncClient;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ClientError, Error},
+    graphs::base::MIN_RELAY_FEE_RATE,
+};
 
 const REGTEST_ESPLORA_URL: &str = "http://localhost:8094/regtest/api/";
 // This endpoint accepts non-standard transactions.
 // const ALPEN_SIGNET_ESPLORA_URL: &str = "https://esplora-large.devnet-annapurna.stratabtc.org";
 const BITVM_SIGNET_ESPLORA_URL: &str = "https://esplora.bitvmnet.org";
 
+// Confirmation target, in blocks, of the fee rate graphs are built at when none is configured
+pub const DEFAULT_FEE_RATE_CONFIRMATION_TARGET: u16 = 6;
+
+// Fee rate graphs are built at, set with the `[fees]` table in bridge.toml. `--fee-rate` overrides
+// it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeConfig {
+    // sat/vB, estimated by esplora if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<u64>,
+    // Blocks the estimated fee rate aims to confirm within
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation_target: Option<u16>,
+}
+
 // TODO: Needs to be updated for production environment.
 pub fn get_esplora_url(network: Network) -> &'static str {
     match network {
@@ -12,3 +36,22 @@ pub fn get_esplora_url(network: Network) -> &'static str {
         _ => BITVM_SIGNET_ESPLORA_URL,
     }
 }
+
+// Esplora only estimates some confirmation targets, the closest one at or below `target_blocks` is
+// used. Rounded up to whole sat/vB, and never below the minimum relay fee rate, which is all a
+// regtest node without fee history estimates.
+pub fn fee_rate_from_estimates(estimates: &HashMap<u16, f64>, target_blocks: u16) -> Option<u64> {
+    estimates
+        .iter()
+        .filter(|(target, _)| **target <= target_blocks)
+        .max_by_key(|(target, _)| **target)
+        .map(|(_, fee_rate)| (fee_rate.ceil() as u64).max(MIN_RELAY_FEE_RATE))
+}
+
+// sat/vB to pay for confirmation within `target_blocks`, see `fee_rate_from_estimates`
+pub async fn estimate_fee_rate(esplora: &AsyncClient, target_blocks: u16) -> Result<u64, Error> {
+    let estimates = esplora.get_fee_estimates().await.map_err(Error::Esplora)?;
+    fee_rate_from_estimates(&estimates, target_blocks).ok_or(Error::Client(
+        ClientError::FeeEstimateNotAvailable(target_blocks),
+    ))
+}
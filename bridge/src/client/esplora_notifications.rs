@@ -0,0 +1,124 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use bitcoin::Txid;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+// Esplora backends with the mempool.space websocket API push new blocks and the transactions of
+// tracked addresses, so a challenge or disprove is seen as soon as it is relayed instead of on the
+// next poll. Plain esplora has no websocket, the scheduler keeps polling then.
+
+// Path of the websocket relative to the esplora API url
+const WEBSOCKET_PATH: &str = "v1/ws";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainNotification {
+    NewBlock(u32), // u32: height of the block
+    // Transactions funding or spending a tracked address, in the mempool or in a block
+    AddressActivity(Vec<Txid>),
+}
+
+pub struct EsploraSubscription {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    tracked_addresses: BTreeSet<String>,
+}
+
+impl EsploraSubscription {
+    pub async fn connect(url: &str) -> Result<Self, String> {
+        let (mut stream, _) = connect_async(url)
+            .await
+            .map_err(|e| format!("Could not connect to {url}: {e}"))?;
+        stream
+            .send(Message::Text(
+                json!({ "action": "want", "data": ["blocks"] }).to_string(),
+            ))
+            .await
+            .map_err(|e| format!("Could not subscribe to blocks: {e}"))?;
+
+        Ok(Self {
+            stream,
+            tracked_addresses: BTreeSet::new(),
+        })
+    }
+
+    // Replaces the tracked addresses, nothing is sent if they did not change
+    pub async fn track_addresses(&mut self, addresses: BTreeSet<String>) -> Result<(), String> {
+        if addresses == self.tracked_addresses {
+            return Ok(());
+        }
+        self.stream
+            .send(Message::Text(
+                json!({ "track-addresses": addresses }).to_string(),
+            ))
+            .await
+            .map_err(|e| format!("Could not track addresses: {e}"))?;
+        self.tracked_addresses = addresses;
+
+        Ok(())
+    }
+
+    // Waits for the next block or address activity. Fails once the connection is lost.
+    pub async fn next_notification(&mut self) -> Result<ChainNotification, String> {
+        loop {
+            let message = match self.stream.next().await {
+                Some(Ok(message)) => message,
+                Some(Err(e)) => return Err(format!("Websocket failed: {e}")),
+                None => return Err("Websocket closed".to_string()),
+            };
+            let Message::Text(text) = message else {
+                // Pings are answered by the stream itself
+                continue;
+            };
+            if let Some(notification) = parse_notification(&text) {
+                return Ok(notification);
+            }
+        }
+    }
+}
+
+// `esplora_url` is the API url the esplora client is built with
+pub fn get_esplora_websocket_url(esplora_url: &str) -> String {
+    let url = esplora_url.trim_end_matches('/');
+    let url = match url.split_once("://") {
+        Some(("https", rest)) => format!("wss://{rest}"),
+        Some(("http", rest)) => format!("ws://{rest}"),
+        _ => url.to_string(),
+    };
+
+    format!("{url}/{WEBSOCKET_PATH}")
+}
+
+// A message may carry several updates, a new block is reported before address activity as the
+// scheduler re-evaluates every graph on it anyway
+pub fn parse_notification(text: &str) -> Option<ChainNotification> {
+    let message: Value = serde_json::from_str(text).ok()?;
+
+    if let Some(height) = message["block"]["height"].as_u64() {
+        return Some(ChainNotification::NewBlock(height as u32));
+    }
+
+    let mut transactions: Vec<&Value> = Vec::new();
+    for key in ["address-transactions", "block-transactions"] {
+        if let Some(list) = message[key].as_array() {
+            transactions.extend(list);
+        }
+    }
+    if let Some(addresses) = message["multi-address-transactions"].as_object() {
+        for activity in addresses.values() {
+            for key in ["mempool", "confirmed", "removed"] {
+                if let Some(list) = activity[key].as_array() {
+                    transactions.extend(list);
+                }
+            }
+        }
+    }
+    let txids: BTreeSet<Txid> = transactions
+        .into_iter()
+        .filter_map(|transaction| transaction["txid"].as_str())
+        .filter_map(|txid| Txid::from_str(txid).ok())
+        .collect();
+
+    (!txids.is_empty()).then(|| ChainNotification::AddressActivity(txids.into_iter().collect()))
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashSet, fmt, fs, path::Path};
+
+use bitcoin::{BlockHash, Network, TxMerkleNode, Txid};
+use esplora_client::AsyncClient;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, HeaderChainError},
+    graphs::{
+        base::{BaseGraph, GraphId},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+};
+
+use super::{audit::WitnessMismatch, light_client::merkle_root_from_branch};
+
+// Relayers reporting bridge activity to the destination chain follow the source chain
+// transactions of all graphs in this log: ordered as they were mined, each anchored to its block
+// with the merkle branch proving its inclusion. Kept next to the local client data and updated on
+// every sync once enabled, see `BitVMClient::enable_event_log`.
+
+pub const EVENT_LOG_FILE_NAME: &str = "event_log.json";
+// Events with fewer confirmations are checked again on every update and dropped if a reorg undid
+// them, relayers should wait for this depth before acting on an event
+pub const EVENT_LOG_FINALITY_DEPTH: u32 = 6;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEvent {
+    pub graph_id: GraphId,
+    pub peg_in_graph_id: GraphId, // the graph itself for peg-in graph transactions
+    pub transaction: String,      // e.g. PegInConfirm or Take1
+    pub txid: Txid,
+    pub block_height: u32,
+    pub block_hash: BlockHash,
+    // Position of the tx in the block and the sibling hashes from the tx up to the merkle root
+    pub position: usize,
+    pub merkle_branch: Vec<Txid>,
+}
+
+impl BridgeEvent {
+    // Whether the merkle branch leads from the txid to the merkle root of the block header
+    pub fn verify_inclusion(&self, merkle_root: &TxMerkleNode) -> bool {
+        merkle_root_from_branch(&self.txid, self.position, &self.merkle_branch) == *merkle_root
+    }
+
+    fn order_key(&self) -> (u32, usize) {
+        (self.block_height, self.position)
+    }
+}
+
+impl fmt::Display for BridgeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} confirmed at height {} (graph {})",
+            self.transaction, self.txid, self.block_height, self.graph_id
+        )
+    }
+}
+
+// A graph transaction the event log waits for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventCandidate {
+    pub graph_id: GraphId,
+    pub peg_in_graph_id: GraphId,
+    pub transaction: &'static str,
+    pub txid: Txid,
+}
+
+pub fn event_candidates(
+    peg_in_graphs: &[PegInGraph],
+    peg_out_graphs: &[PegOutGraph],
+) -> Vec<EventCandidate> {
+    let peg_in_candidates = peg_in_graphs.iter().flat_map(|graph| {
+        graph
+            .txids()
+            .into_iter()
+            .map(|(transaction, txid)| EventCandidate {
+                graph_id: graph.id().clone(),
+                peg_in_graph_id: graph.id().clone(),
+                transaction,
+                txid,
+            })
+    });
+    let peg_out_candidates = peg_out_graphs.iter().flat_map(|graph| {
+        graph
+            .txids()
+            .into_iter()
+            .map(|(transaction, txid)| EventCandidate {
+                graph_id: graph.id().clone(),
+                peg_in_graph_id: graph.peg_in_graph_id.to_string(),
+                transaction,
+                txid,
+            })
+    });
+
+    peg_in_candidates.chain(peg_out_candidates).collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEventLog {
+    network: Network,
+    events: Vec<BridgeEvent>, // ordered by block height and position in the block
+    synced_height: u32,       // source chain tip at the last update
+    // Of the logged events, dropped with them on a reorg
+    #[serde(default)]
+    witness_mismatches: Vec<WitnessMismatch>,
+}
+
+impl BridgeEventLog {
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            events: Vec::new(),
+            synced_height: 0,
+            witness_mismatches: Vec::new(),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn events(&self) -> &[BridgeEvent] {
+        &self.events
+    }
+
+    pub fn synced_height(&self) -> u32 {
+        self.synced_height
+    }
+
+    pub fn witness_mismatches(&self) -> &[WitnessMismatch] {
+        &self.witness_mismatches
+    }
+
+    pub fn record_witness_mismatch(&mut self, witness_mismatch: WitnessMismatch) {
+        if !self.witness_mismatches.contains(&witness_mismatch) {
+            self.witness_mismatches.push(witness_mismatch);
+        }
+    }
+
+    // Events mined at or above `height`, for relayers resuming from the last height they processed
+    pub fn events_from(&self, height: u32) -> &[BridgeEvent] {
+        let start = self
+            .events
+            .partition_point(|event| event.block_height < height);
+        &self.events[start..]
+    }
+
+    // Drops the recent events a reorg undid and records the candidates mined since the last
+    // update. Returns the new events in log order. Nothing is changed if esplora fails.
+    pub async fn update(
+        &mut self,
+        client: &AsyncClient,
+        candidates: &[EventCandidate],
+    ) -> Result<Vec<BridgeEvent>, Error> {
+        let tip_height = client.get_height().await.map_err(Error::Esplora)?;
+
+        let mut reorged = HashSet::new();
+        for event in self.events.iter().filter(|event| {
+            tip_height.saturating_sub(event.block_height) + 1 < EVENT_LOG_FINALITY_DEPTH
+        }) {
+            let status = client
+                .get_tx_status(&event.txid)
+                .await
+                .map_err(Error::Esplora)?;
+            if !status.confirmed || status.block_hash != Some(event.block_hash) {
+                reorged.insert(event.txid);
+            }
+        }
+
+        let recorded: HashSet<Txid> = self
+            .events
+            .iter()
+            .map(|event| event.txid)
+            .filter(|txid| !reorged.contains(txid))
+            .collect();
+        let mut new_events = Vec::new();
+        for candidate in candidates
+            .iter()
+            .filter(|candidate| !recorded.contains(&candidate.txid))
+        {
+            if let Some(event) = Self::find_event(client, candidate).await? {
+                new_events.push(event);
+            }
+        }
+        new_events.sort_by_key(BridgeEvent::order_key);
+
+        self.events.retain(|event| !reorged.contains(&event.txid));
+        self.witness_mismatches
+            .retain(|witness_mismatch| !reorged.contains(&witness_mismatch.txid));
+        self.events.extend(new_events.iter().cloned());
+        self.events.sort_by_key(BridgeEvent::order_key);
+        self.synced_height = tip_height;
+
+        Ok(new_events)
+    }
+
+    async fn find_event(
+        client: &AsyncClient,
+        candidate: &EventCandidate,
+    ) -> Result<Option<BridgeEvent>, Error> {
+        let status = client
+            .get_tx_status(&candidate.txid)
+            .await
+            .map_err(Error::Esplora)?;
+        let (Some(block_height), Some(block_hash)) = (status.block_height, status.block_hash)
+        else {
+            return Ok(None);
+        };
+        if !status.confirmed {
+            return Ok(None);
+        }
+
+        // A proof from another block means esplora moved on between the requests, the tx is
+        // picked up on the next update
+        let Some(proof) = client
+            .get_merkle_proof(&candidate.txid)
+            .await
+            .map_err(Error::Esplora)?
+            .filter(|proof| proof.block_height == block_height)
+        else {
+            return Ok(None);
+        };
+        let header = client
+            .get_header_by_hash(&block_hash)
+            .await
+            .map_err(Error::Esplora)?;
+
+        let event = BridgeEvent {
+            graph_id: candidate.graph_id.clone(),
+            peg_in_graph_id: candidate.peg_in_graph_id.clone(),
+            transaction: candidate.transaction.to_string(),
+            txid: candidate.txid,
+            block_height,
+            block_hash,
+            position: proof.pos,
+            merkle_branch: proof.merkle,
+        };
+        // Relayers check the branch against the header, one that does not lead there is useless
+        if header.block_hash() != block_hash || !event.verify_inclusion(&header.merkle_root) {
+            return Err(Error::HeaderChain(HeaderChainError::MerkleProofMismatch(
+                candidate.txid,
+            )));
+        }
+
+        Ok(Some(event))
+    }
+
+    pub fn read_from_file(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save_to_file(&self, path: &Path) {
+        if let Err(e) = serde_json::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                fs::write(path, content).map_err(|e| e.to_string())
+            })
+        {
+            eprintln!("Failed to save event log to {}: {e}", path.display());
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use alloy::transports::http::{
+    reqwest::{header::RETRY_AFTER, Response, StatusCode},
+    Client,
+};
+use bitcoin::{Address, Amount, Network, Txid};
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::error::{ClientError, Error};
+
+pub const FAUCET_MAX_RETRIES: usize = 5;
+pub const FAUCET_DEFAULT_RETRY_WAIT_SECS: u64 = 10;
+const FAUCET_MAX_RETRY_WAIT_SECS: u64 = 120;
+
+#[derive(Serialize)]
+struct FundRequest {
+    sats: u64,
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct FundResult {
+    txid: Txid,
+}
+
+// Client of a public faucet funding addresses on test networks. The faucet is expected to accept
+// `POST <url>api/onchain` with `{"sats": <amount>, "address": <address>}` and reply with the
+// funding txid. Rate limited requests are retried after the wait time given by the faucet.
+pub struct FaucetClient {
+    url: String,
+    network: Network,
+    client: Client,
+}
+
+impl FaucetClient {
+    pub fn new(url: &str, network: Network) -> Result<Self, Error> {
+        if network == Network::Bitcoin {
+            return Err(Error::Client(ClientError::FaucetNotAvailable(network)));
+        }
+        let url = match url.ends_with('/') {
+            true => url.to_string(),
+            false => format!("{url}/"),
+        };
+        let client = Client::builder()
+            .build()
+            .map_err(|e| Error::Client(ClientError::FaucetRequestFailed(e.to_string())))?;
+
+        Ok(Self {
+            url,
+            network,
+            client,
+        })
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub async fn fund(&self, address: &Address, amount: Amount) -> Result<Txid, Error> {
+        let mut retry = 0;
+        loop {
+            let response = self.request_funding(address, amount).await?;
+            let status = response.status();
+            if status.is_success() {
+                let result = response.json::<FundResult>().await.map_err(|e| {
+                    Error::Client(ClientError::FaucetRequestFailed(format!(
+                        "Unexpected faucet response: {e}"
+                    )))
+                })?;
+                return Ok(result.txid);
+            }
+
+            let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+                || status == StatusCode::SERVICE_UNAVAILABLE;
+            if !rate_limited || retry >= FAUCET_MAX_RETRIES {
+                return Err(Error::Client(ClientError::FaucetRequestFailed(format!(
+                    "Faucet responded with {status}: {}",
+                    response.text().await.unwrap_or_default()
+                ))));
+            }
+
+            retry += 1;
+            let wait = retry_wait(&response, retry);
+            eprintln!(
+                "Faucet is rate limited, retrying ({retry}/{FAUCET_MAX_RETRIES}) in {} s...",
+                wait.as_secs()
+            );
+            sleep(wait).await;
+        }
+    }
+
+    async fn request_funding(&self, address: &Address, amount: Amount) -> Result<Response, Error> {
+        let payload = FundRequest {
+            sats: amount.to_sat(),
+            address: address.to_string(),
+        };
+        self.client
+            .post(format!("{}api/onchain", self.url))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| Error::Client(ClientError::FaucetRequestFailed(e.to_string())))
+    }
+}
+
+// Waits as long as the faucet asks to, or backs off exponentially if it does not say
+fn retry_wait(response: &Response, retry: usize) -> Duration {
+    let secs = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(FAUCET_DEFAULT_RETRY_WAIT_SECS << (retry - 1));
+    Duration::from_secs(secs.min(FAUCET_MAX_RETRY_WAIT_SECS))
+}
@@ -1,19 +1,60 @@
+#[cfg(feature = "client")]
+use std::str::FromStr;
 use std::{
-    collections::HashMap,
-    fs,
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
     path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
 };
 
-use crate::serialization::try_deserialize;
+use bitcoin::{
+    hashes::{sha256, Hash},
+    PublicKey,
+};
+
+use crate::error::{ClientError, Error};
+#[cfg(feature = "client")]
+use crate::serialization::{serialize, try_deserialize};
 
-use super::client::BitVMClientPrivateData;
+#[cfg(feature = "client")]
+use super::client::{BitVMClientPrivateData, PrivateGraphData};
 
 pub const BRIDGE_DATA_DIRECTORY_NAME: &str = "bridge_data";
 pub const DEFAULT_PATH_PREFIX: &str = "default_user";
+// Single file all private data used to be kept in, only read to migrate it
 pub const PRIVATE_DATA_FILE_NAME: &str = "secret_data.json";
 const PRIVATE_DATA_DIRECTORY_NAME: &str = "private";
 const PUBLIC_DATA_DIRECTORY_NAME: &str = "public";
 
+const PRIVATE_GRAPH_FILE_EXTENSION: &str = "json";
+const LOCK_FILE_EXTENSION: &str = "lock";
+const TEMPORARY_FILE_EXTENSION: &str = "tmp";
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+// Writing one graph file takes well below this, a lock this old was left by a crashed process
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+// Roles keeping private data. Every role and key has its own directory and every graph its own
+// file in there, so clients running different roles from the same directory never write the same
+// file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrivateDataRole {
+    Operator,
+    Verifier,
+}
+
+impl PrivateDataRole {
+    #[cfg(feature = "client")]
+    const ALL: [PrivateDataRole; 2] = [PrivateDataRole::Operator, PrivateDataRole::Verifier];
+
+    fn directory_name(&self) -> &'static str {
+        match self {
+            PrivateDataRole::Operator => "operator",
+            PrivateDataRole::Verifier => "verifier",
+        }
+    }
+}
+
 fn get_private_data_directory_path(data_root_path: &Path) -> PathBuf {
     data_root_path.join(PRIVATE_DATA_DIRECTORY_NAME)
 }
@@ -22,7 +63,23 @@ pub fn get_private_data_file_path(data_root_path: &Path) -> PathBuf {
     get_private_data_directory_path(data_root_path).join(PRIVATE_DATA_FILE_NAME)
 }
 
-fn get_public_data_directory_path(data_root_path: &Path) -> PathBuf {
+fn get_private_role_directory_path(data_root_path: &Path, role: PrivateDataRole) -> PathBuf {
+    get_private_data_directory_path(data_root_path).join(role.directory_name())
+}
+
+pub fn get_private_graph_file_path(
+    data_root_path: &Path,
+    role: PrivateDataRole,
+    public_key: &PublicKey,
+    graph_id: &str,
+) -> PathBuf {
+    get_private_role_directory_path(data_root_path, role)
+        .join(public_key.to_string())
+        .join(graph_id)
+        .with_extension(PRIVATE_GRAPH_FILE_EXTENSION)
+}
+
+pub(crate) fn get_public_data_directory_path(data_root_path: &Path) -> PathBuf {
     data_root_path.join(PUBLIC_DATA_DIRECTORY_NAME)
 }
 
@@ -42,22 +99,211 @@ pub fn create_directories_if_non_existent(data_root_path: &Path) {
     }
 }
 
-pub fn get_private_data_from_file(path: &Path) -> BitVMClientPrivateData {
-    match read_file(path) {
-        Some(data) => try_deserialize::<BitVMClientPrivateData>(&data)
-            .expect("Could not deserialize private data"),
-        None => BitVMClientPrivateData {
-            secret_nonces: HashMap::new(),
-            commitment_secrets: HashMap::new(),
-        },
+// Reads the private graph files of all roles. The single private data file of earlier versions is
+// split into graph files first and deleted once they were read back.
+#[cfg(feature = "client")]
+pub fn get_private_data_from_files(data_root_path: &Path) -> Result<BitVMClientPrivateData, Error> {
+    migrate_private_data_file(data_root_path)?;
+
+    let mut private_data = BitVMClientPrivateData::default();
+    for role in PrivateDataRole::ALL {
+        for (public_key, graph_id, path) in list_private_graph_files(data_root_path, role) {
+            let Some(contents) = read_private_graph_file(&path)? else {
+                continue;
+            };
+            let graph_data = try_deserialize::<PrivateGraphData>(&contents)
+                .map_err(|e| private_data_invalid(&path, e))?;
+            private_data.insert_graph_data(role, &public_key, &graph_id, graph_data);
+        }
     }
+
+    Ok(private_data)
+}
+
+#[cfg(feature = "client")]
+fn migrate_private_data_file(data_root_path: &Path) -> Result<(), Error> {
+    let path = get_private_data_file_path(data_root_path);
+    let Some(contents) = read_file(&path) else {
+        return Ok(());
+    };
+    let private_data = try_deserialize::<BitVMClientPrivateData>(&contents)
+        .map_err(|e| private_data_invalid(&path, e))?;
+    println!(
+        "Splitting private data in {} by role and graph...",
+        path.display()
+    );
+    for (role, public_key, graph_id) in private_data.graph_keys() {
+        let graph_data = serialize(&private_data.graph_data(role, &public_key, &graph_id));
+        save_private_graph_file(data_root_path, role, &public_key, &graph_id, &graph_data);
+    }
+    // The secrets are not kept in plaintext any longer than needed, but only deleted once every
+    // graph file holds them
+    for (role, public_key, graph_id) in private_data.graph_keys() {
+        let graph_data = serialize(&private_data.graph_data(role, &public_key, &graph_id));
+        let graph_path = get_private_graph_file_path(data_root_path, role, &public_key, &graph_id);
+        if read_private_graph_file(&graph_path)?.as_deref() != Some(graph_data.as_str()) {
+            return Err(Error::Client(ClientError::PrivateGraphFileCorrupted(
+                graph_id,
+            )));
+        }
+    }
+    // Another client may have migrated it in the meantime
+    if let Err(e) = fs::remove_file(&path) {
+        if e.kind() != ErrorKind::NotFound {
+            panic!("Could not delete {} due to error: {e}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "client")]
+fn private_data_invalid(path: &Path, e: String) -> Error {
+    Error::Client(ClientError::PrivateDataInvalid(
+        path.display().to_string(),
+        e,
+    ))
+}
+
+// Public key, graph id and path of every graph file of the role
+#[cfg(feature = "client")]
+fn list_private_graph_files(
+    data_root_path: &Path,
+    role: PrivateDataRole,
+) -> Vec<(PublicKey, String, PathBuf)> {
+    let mut graph_files = Vec::new();
+    let Ok(key_directories) = fs::read_dir(get_private_role_directory_path(data_root_path, role))
+    else {
+        return graph_files;
+    };
+    for key_directory in key_directories.flatten() {
+        let Some(public_key) = key_directory
+            .file_name()
+            .to_str()
+            .and_then(|name| PublicKey::from_str(name).ok())
+        else {
+            continue;
+        };
+        let Ok(files) = fs::read_dir(key_directory.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|extension| extension.to_str())
+                != Some(PRIVATE_GRAPH_FILE_EXTENSION)
+            {
+                continue;
+            }
+            if let Some(graph_id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                graph_files.push((public_key, graph_id.to_string(), path.clone()));
+            }
+        }
+    }
+
+    graph_files
 }
 
-fn read_file(path: &Path) -> Option<String> {
+// Graph files start with the sha256 of their contents on a line of its own, so a file that was
+// only partially written is detected instead of loaded. Graph files are named after their graph.
+pub fn read_private_graph_file(path: &Path) -> Result<Option<String>, Error> {
+    let Some(file) = read_file(path) else {
+        return Ok(None);
+    };
+    let Some(contents) = verify_checksum(&file) else {
+        let graph_id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        return Err(Error::Client(ClientError::PrivateGraphFileCorrupted(
+            graph_id,
+        )));
+    };
+
+    Ok(Some(contents.to_string()))
+}
+
+// Contents of a checksummed file, see `write_checksummed_file`
+pub(crate) fn verify_checksum(file: &str) -> Option<&str> {
+    let (checksum, contents) = file.split_once('\n').unwrap_or(("", file));
+    (checksum == sha256::Hash::hash(contents.as_bytes()).to_string()).then_some(contents)
+}
+
+// Written to a temporary file that replaces the graph file once complete, while holding the lock
+// of the graph file
+pub fn save_private_graph_file(
+    data_root_path: &Path,
+    role: PrivateDataRole,
+    public_key: &PublicKey,
+    graph_id: &str,
+    contents: &str,
+) {
+    write_checksummed_file(
+        &get_private_graph_file_path(data_root_path, role, public_key, graph_id),
+        contents,
+    );
+}
+
+pub(crate) fn write_checksummed_file(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).expect("Failed to create directories");
+
+    let _lock = FileLock::acquire(path);
+    let temporary_path = path.with_extension(TEMPORARY_FILE_EXTENSION);
+    let checksum = sha256::Hash::hash(contents.as_bytes());
+    let mut file = File::create(&temporary_path).expect("Unable to create a file");
+    file.write_all(format!("{checksum}\n{contents}").as_bytes())
+        .expect("Unable to write a file");
+    // On disk before it replaces the file, otherwise a crash can leave an empty file behind
+    file.sync_all().expect("Unable to sync a file");
+    fs::rename(&temporary_path, path).expect("Unable to replace a file");
+    // Makes the rename itself durable
+    File::open(path.parent().unwrap())
+        .and_then(|directory| directory.sync_all())
+        .expect("Unable to sync a directory");
+}
+
+// Advisory lock held as long as the lock file next to the locked file exists
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    fn acquire(locked_path: &Path) -> Self {
+        let path = locked_path.with_extension(LOCK_FILE_EXTENSION);
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Self { path },
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if Self::is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                    } else {
+                        thread::sleep(LOCK_RETRY_INTERVAL);
+                    }
+                }
+                Err(e) => panic!("Could not lock {} due to error: {e}", locked_path.display()),
+            }
+        }
+    }
+
+    fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_LOCK_AGE)
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub(crate) fn read_file(path: &Path) -> Option<String> {
     match fs::read_to_string(path) {
         Ok(content) => Some(content),
         Err(e) => match e.kind() {
-            std::io::ErrorKind::NotFound => return None,
+            ErrorKind::NotFound => None,
             _ => {
                 // If the file exists, but we cannot read it (e.g. due to invalid permissions, etc.),
                 // we want to fail and let the user fix the issue.
@@ -66,20 +312,3 @@ fn read_file(path: &Path) -> Option<String> {
         },
     }
 }
-
-pub fn save_local_public_file(data_root_path: &Path, file_name: &String, contents: &String) {
-    create_directories_if_non_existent(data_root_path);
-    println!("Saving public data in local file: {}...", file_name);
-    fs::write(
-        get_public_data_directory_path(data_root_path).join(file_name),
-        contents,
-    )
-    .expect("Unable to write a file");
-}
-
-pub fn save_local_private_file(data_root_path: &Path, contents: &String) {
-    create_directories_if_non_existent(data_root_path);
-    println!("Saving private data in local file...");
-    fs::write(get_private_data_file_path(data_root_path), contents)
-        .expect("Unable to write a file");
-}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+use bitcoin::PublicKey;
+
+use crate::{
+    error::Error,
+    graphs::{
+        base::{BaseGraph, GraphId},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    transactions::pre_signed_musig2::Musig2SigningProgress,
+};
+
+use super::client::BitVMClientPublicData;
+
+// One version of the public data as written to the data store
+pub struct DataStoreVersion {
+    pub file_name: String,
+    pub timestamp: u64, // milliseconds since the unix epoch, taken from the file name
+    pub data: BitVMClientPublicData,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum GraphState {
+    PegIn(PegInGraph),
+    PegOut(PegOutGraph),
+}
+
+impl GraphState {
+    fn find(graph_id: &GraphId, data: &BitVMClientPublicData) -> Option<Self> {
+        if let Some(graph) = data.peg_in_graphs.iter().find(|x| x.id() == graph_id) {
+            return Some(GraphState::PegIn(graph.clone()));
+        }
+        data.peg_out_graphs
+            .iter()
+            .find(|x| x.id() == graph_id)
+            .map(|graph| GraphState::PegOut(graph.clone()))
+    }
+
+    fn merge(&mut self, source: &Self) -> Result<(), Error> {
+        match (self, source) {
+            (GraphState::PegIn(graph), GraphState::PegIn(source)) => {
+                graph.merge(source);
+                Ok(())
+            }
+            (GraphState::PegOut(graph), GraphState::PegOut(source)) => graph.merge(source),
+            _ => unreachable!("Graph ID shared by a peg-in and a peg-out graph"),
+        }
+    }
+
+    pub fn id(&self) -> &GraphId {
+        match self {
+            GraphState::PegIn(graph) => graph.id(),
+            GraphState::PegOut(graph) => graph.id(),
+        }
+    }
+
+    // Pre-signed transactions that still miss nonces or signatures. The committee of a peg-out
+    // graph is the one of its peg-in graph.
+    pub fn signing_progress(&self, committee: &[PublicKey]) -> Vec<Musig2SigningProgress> {
+        match self {
+            GraphState::PegIn(graph) => graph.signing_progress().into_iter().collect(),
+            GraphState::PegOut(graph) => graph.signing_progress(committee),
+        }
+    }
+}
+
+// State of a graph right after a data store version changed it
+pub struct GraphTransition {
+    pub file_name: String,
+    pub timestamp: u64,
+    pub state: GraphState,
+    pub committee: Vec<PublicKey>,
+    pub changes: Vec<String>,
+}
+
+impl GraphTransition {
+    pub fn signing_progress(&self) -> Vec<Musig2SigningProgress> {
+        self.state.signing_progress(&self.committee)
+    }
+}
+
+impl fmt::Display for GraphTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.timestamp, self.file_name)?;
+        for change in &self.changes {
+            write!(f, "\n  {change}")?;
+        }
+        Ok(())
+    }
+}
+
+// Replays the data store versions in timestamp order, merging the graph the same way a sync
+// does. Every version that changes the graph yields a transition, so the state at any past time
+// is the one of the last transition before it.
+pub fn replay_graph_history(
+    graph_id: &GraphId,
+    mut versions: Vec<DataStoreVersion>,
+) -> Vec<GraphTransition> {
+    versions.sort_by_key(|version| version.timestamp);
+
+    let mut transitions: Vec<GraphTransition> = vec![];
+    let mut state: Option<GraphState> = None;
+    let mut committee: Vec<PublicKey> = vec![];
+    for version in versions {
+        let Some(version_state) = GraphState::find(graph_id, &version.data) else {
+            continue;
+        };
+        if let Some(version_committee) = find_committee(&version_state, &version.data) {
+            committee = version_committee;
+        }
+
+        let previous_state = state.clone();
+        match state.as_mut() {
+            Some(current_state) => {
+                // Sync skips versions that cannot be merged, so does the replay
+                if current_state.merge(&version_state).is_err() {
+                    state = previous_state;
+                    continue;
+                }
+            }
+            None => state = Some(version_state),
+        }
+        let current_state = state.as_ref().unwrap();
+        if previous_state.as_ref() == Some(current_state) {
+            continue;
+        }
+
+        let changes = match previous_state {
+            Some(previous_state) => describe_signing_changes(
+                &previous_state.signing_progress(&committee),
+                &current_state.signing_progress(&committee),
+            ),
+            None => vec!["Graph created".to_string()],
+        };
+        transitions.push(GraphTransition {
+            file_name: version.file_name,
+            timestamp: version.timestamp,
+            state: current_state.clone(),
+            committee: committee.clone(),
+            changes,
+        });
+    }
+
+    transitions
+}
+
+fn find_committee(state: &GraphState, data: &BitVMClientPublicData) -> Option<Vec<PublicKey>> {
+    match state {
+        GraphState::PegIn(graph) => Some(graph.n_of_n_public_keys().to_vec()),
+        GraphState::PegOut(graph) => data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id() == &graph.peg_in_graph_id)
+            .map(|peg_in_graph| peg_in_graph.n_of_n_public_keys().to_vec()),
+    }
+}
+
+fn describe_signing_changes(
+    previous: &[Musig2SigningProgress],
+    current: &[Musig2SigningProgress],
+) -> Vec<String> {
+    let mut changes = vec![];
+    for progress in current {
+        match previous
+            .iter()
+            .find(|previous| previous.name == progress.name)
+        {
+            Some(previous) => {
+                describe_contributions(
+                    &mut changes,
+                    progress,
+                    "nonces",
+                    &previous.nonces,
+                    &progress.nonces,
+                );
+                describe_contributions(
+                    &mut changes,
+                    progress,
+                    "signatures",
+                    &previous.signatures,
+                    &progress.signatures,
+                );
+            }
+            // Fully signed before, a re-sign round reset the ceremony
+            None => changes.push(format!("{}: signing restarted", progress.name)),
+        }
+    }
+    for progress in previous {
+        if !current.iter().any(|current| current.name == progress.name) {
+            changes.push(format!("{}: fully signed", progress.name));
+        }
+    }
+    if changes.is_empty() {
+        changes.push("Graph data changed".to_string());
+    }
+
+    changes
+}
+
+fn describe_contributions(
+    changes: &mut Vec<String>,
+    progress: &Musig2SigningProgress,
+    contribution: &str,
+    previous: &[PublicKey],
+    current: &[PublicKey],
+) {
+    for pubkey in current.iter().filter(|pubkey| !previous.contains(pubkey)) {
+        changes.push(format!("{}: {contribution} from {pubkey}", progress.name));
+    }
+    for pubkey in previous.iter().filter(|pubkey| !current.contains(pubkey)) {
+        changes.push(format!(
+            "{}: {contribution} from {pubkey} dropped",
+            progress.name
+        ));
+    }
+}
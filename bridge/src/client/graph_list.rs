@@ -0,0 +1,76 @@
+use std::fmt;
+
+use bitcoin::Amount;
+use serde::{Deserialize, Serialize};
+
+use crate::graphs::base::{GraphId, GraphStage};
+
+// Graphs as listed by `list-graphs`, for scripts and dashboards that cannot parse the status
+// output. The serialized form is part of the CLI output and only gains fields.
+
+// Whose graphs to list, by the keys of the matching context of the client
+#[derive(
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum GraphRole {
+    Depositor, // peg-in graphs of deposits made with the depositor key
+    Operator,  // peg-out graphs of the operator key
+    Verifier,  // peg-in graphs with the verifier key in their committee and their peg-out graphs
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct GraphListFilter {
+    pub role: Option<GraphRole>,
+    pub stage: Option<GraphStage>,
+}
+
+impl GraphListFilter {
+    pub fn matches_stage(&self, stage: GraphStage) -> bool {
+        self.stage.is_none_or(|expected| expected == stage)
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug, strum::Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum GraphKind {
+    PegIn,
+    PegOut,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct GraphSummary {
+    pub graph_id: GraphId,
+    #[serde(rename = "type")]
+    pub kind: GraphKind,
+    pub stage: GraphStage,
+    pub peg_in_graph_id: Option<GraphId>, // set for peg-out graphs
+    pub amount: Amount,                   // of the deposit
+}
+
+impl fmt::Display for GraphSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            GraphKind::PegIn => write!(f, "Peg-in graph ID: {}", self.graph_id)?,
+            GraphKind::PegOut => write!(f, "Peg-out graph ID: {}", self.graph_id)?,
+        }
+        write!(f, " stage: {} amount: {}", self.stage, self.amount)?;
+        if let Some(peg_in_graph_id) = &self.peg_in_graph_id {
+            write!(f, "\n  Peg-in graph ID: {peg_in_graph_id}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use bitcoin::hashes::{sha256, Hash};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    graphs::{base::BaseGraph, peg_in::PegInGraph, peg_out::PegOutGraph},
+    serialization::{serialize, try_deserialize},
+};
+
+use super::{
+    client::BitVMClientPublicData,
+    files::{get_public_data_directory_path, read_file, verify_checksum, write_checksummed_file},
+};
+
+const GRAPH_STORE_DIRECTORY_NAME: &str = "graphs";
+const PEG_IN_GRAPH_DIRECTORY_NAME: &str = "peg_in";
+const PEG_OUT_GRAPH_DIRECTORY_NAME: &str = "peg_out";
+const GRAPH_FILE_EXTENSION: &str = "json";
+const STATE_FILE_NAME: &str = "state.json";
+
+// Written after the graph files, so the graphs on disk are never older than the data store files
+// the state claims to have processed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GraphStoreState {
+    pub version: u32,
+    // Files up to this one are merged into the stored graphs, only later ones have to be fetched
+    pub latest_processed_file_name: Option<String>,
+    pub peg_in_graph_ids: Vec<String>,
+    pub peg_out_graph_ids: Vec<String>,
+}
+
+// Local copy of the public data with a file per graph, so a restarted client only fetches the data
+// store files pushed since it stopped, and only graphs that changed are written. The data store
+// itself still receives complete snapshots, other clients rely on them.
+pub struct GraphStore {
+    directory_path: PathBuf,
+    // Checksums of the files on disk
+    checksums: HashMap<PathBuf, sha256::Hash>,
+}
+
+impl GraphStore {
+    pub fn new(data_root_path: &Path) -> Self {
+        Self {
+            directory_path: get_public_data_directory_path(data_root_path)
+                .join(GRAPH_STORE_DIRECTORY_NAME),
+            checksums: HashMap::new(),
+        }
+    }
+
+    pub fn state_file_path(&self) -> PathBuf {
+        self.directory_path.join(STATE_FILE_NAME)
+    }
+
+    pub fn peg_in_graph_file_path(&self, graph_id: &str) -> PathBuf {
+        self.graph_file_path(PEG_IN_GRAPH_DIRECTORY_NAME, graph_id)
+    }
+
+    pub fn peg_out_graph_file_path(&self, graph_id: &str) -> PathBuf {
+        self.graph_file_path(PEG_OUT_GRAPH_DIRECTORY_NAME, graph_id)
+    }
+
+    fn graph_file_path(&self, directory_name: &str, graph_id: &str) -> PathBuf {
+        self.directory_path
+            .join(directory_name)
+            .join(graph_id)
+            .with_extension(GRAPH_FILE_EXTENSION)
+    }
+
+    // None if nothing was stored yet. A store with a missing or corrupted file is discarded as a
+    // whole, the client then fetches everything from the data store again.
+    pub fn load(&mut self) -> Option<(BitVMClientPublicData, GraphStoreState)> {
+        self.checksums.clear();
+        let state_file_path = self.state_file_path();
+        let state = self.read::<GraphStoreState>(&state_file_path)?;
+
+        let peg_in_paths: Vec<PathBuf> = state
+            .peg_in_graph_ids
+            .iter()
+            .map(|graph_id| self.peg_in_graph_file_path(graph_id))
+            .collect();
+        let peg_out_paths: Vec<PathBuf> = state
+            .peg_out_graph_ids
+            .iter()
+            .map(|graph_id| self.peg_out_graph_file_path(graph_id))
+            .collect();
+        let loaded = thread::scope(|scope| {
+            let peg_in_graphs = scope.spawn(|| read_graph_files::<PegInGraph>(&peg_in_paths));
+            let peg_out_graphs = read_graph_files::<PegOutGraph>(&peg_out_paths);
+            (peg_in_graphs.join().unwrap(), peg_out_graphs)
+        });
+        let (Some(peg_in_graphs), Some(peg_out_graphs)) = loaded else {
+            println!(
+                "Local graph store in {} is incomplete, fetching all data from the data store",
+                self.directory_path.display()
+            );
+            self.checksums.clear();
+            return None;
+        };
+
+        for (path, (_, checksum)) in peg_in_paths.into_iter().zip(&peg_in_graphs) {
+            self.checksums.insert(path, *checksum);
+        }
+        for (path, (_, checksum)) in peg_out_paths.into_iter().zip(&peg_out_graphs) {
+            self.checksums.insert(path, *checksum);
+        }
+        let data = BitVMClientPublicData {
+            version: state.version,
+            peg_in_graphs: peg_in_graphs.into_iter().map(|(graph, _)| graph).collect(),
+            peg_out_graphs: peg_out_graphs.into_iter().map(|(graph, _)| graph).collect(),
+        };
+
+        Some((data, state))
+    }
+
+    // Writes the graphs that changed since they were last loaded or saved, returns how many
+    pub fn save(
+        &mut self,
+        data: &BitVMClientPublicData,
+        latest_processed_file_name: Option<&str>,
+    ) -> usize {
+        let mut written_graphs = 0;
+        for peg_in_graph in &data.peg_in_graphs {
+            let path = self.peg_in_graph_file_path(peg_in_graph.id());
+            written_graphs += self.write_if_changed(path, &serialize(peg_in_graph)) as usize;
+        }
+        for peg_out_graph in &data.peg_out_graphs {
+            let path = self.peg_out_graph_file_path(peg_out_graph.id());
+            written_graphs += self.write_if_changed(path, &serialize(peg_out_graph)) as usize;
+        }
+
+        let state = GraphStoreState {
+            version: data.version,
+            latest_processed_file_name: latest_processed_file_name.map(str::to_string),
+            peg_in_graph_ids: data
+                .peg_in_graphs
+                .iter()
+                .map(|graph| graph.id().clone())
+                .collect(),
+            peg_out_graph_ids: data
+                .peg_out_graphs
+                .iter()
+                .map(|graph| graph.id().clone())
+                .collect(),
+        };
+        self.write_if_changed(self.state_file_path(), &serialize(&state));
+
+        written_graphs
+    }
+
+    fn write_if_changed(&mut self, path: PathBuf, contents: &str) -> bool {
+        let checksum = sha256::Hash::hash(contents.as_bytes());
+        if self.checksums.get(&path) == Some(&checksum) {
+            return false;
+        }
+        write_checksummed_file(&path, contents);
+        self.checksums.insert(path, checksum);
+        true
+    }
+
+    fn read<T: DeserializeOwned>(&mut self, path: &Path) -> Option<T> {
+        let (value, checksum) = read_checksummed::<T>(path)?;
+        self.checksums.insert(path.to_path_buf(), checksum);
+        Some(value)
+    }
+}
+
+fn read_checksummed<T: DeserializeOwned>(path: &Path) -> Option<(T, sha256::Hash)> {
+    let file = read_file(path)?;
+    let contents = verify_checksum(&file)?;
+    let value = try_deserialize::<T>(contents).ok()?;
+    Some((value, sha256::Hash::hash(contents.as_bytes())))
+}
+
+// Deserialized on all available cores, peg-out graphs are large. None if any file is unusable.
+fn read_graph_files<T: DeserializeOwned + Send>(
+    paths: &[PathBuf],
+) -> Option<Vec<(T, sha256::Hash)>> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = paths.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let chunks: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| read_checksummed::<T>(path))
+                        .collect::<Option<Vec<_>>>()
+                })
+            })
+            .collect();
+
+        let mut graphs = Vec::with_capacity(paths.len());
+        for chunk in chunks {
+            graphs.extend(chunk.join().expect("Graph store reader panicked")?);
+        }
+        Some(graphs)
+    })
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use bitcoin::{
+    absolute,
+    hashes::{sha256, Hash, HashEngine},
+    key::Keypair,
+    script::PushBytesBuf,
+    secp256k1::{schnorr::Signature, Message},
+    sighash::SighashCache,
+    transaction::Version,
+    Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Txid, Witness,
+};
+use secp256k1::SECP256K1;
+use serde::{Deserialize, Serialize};
+
+use crate::{scripts::generate_p2wpkh_address, transactions::base::Input};
+
+// Committee members publish a heartbeat to the data store while their client runs, so a signing
+// ceremony that stalls can be attributed to the members that went offline. Heartbeats are not part
+// of the graph data: each member overwrites its own file and nothing is merged.
+
+pub const HEARTBEAT_DIRECTORY_NAME: &str = "heartbeats";
+pub const HEARTBEAT_FILE_SUFFIX: &str = "-heartbeat.json";
+// Seconds between two heartbeats of a member
+pub const DEFAULT_HEARTBEAT_INTERVAL: u64 = 5 * 60;
+// A member is reported offline once this many of its heartbeats are overdue
+pub const MISSED_HEARTBEATS_BEFORE_OFFLINE: u64 = 3;
+
+// Prefix of the OP_RETURN beacon, followed by the member's public key and the heartbeat timestamp
+pub const HEARTBEAT_BEACON_TAG: [u8; 4] = *b"BVHB";
+pub const HEARTBEAT_BEACON_FEE: Amount = Amount::from_sat(1_000);
+// Dust limit of the P2WPKH change output
+const HEARTBEAT_BEACON_MIN_CHANGE: Amount = Amount::from_sat(294);
+
+const HEARTBEAT_MESSAGE_TAG: &[u8] = b"bitvm-bridge/heartbeat";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatConfig {
+    pub interval: u64, // seconds
+    // Also publish an OP_RETURN beacon on chain, only on test networks. Spends from the member's
+    // P2WPKH address, the heartbeat is published without a beacon if the address holds no funds.
+    pub beacon: bool,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_HEARTBEAT_INTERVAL,
+            beacon: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HeartbeatRecord {
+    pub public_key: PublicKey,
+    pub timestamp: u64, // seconds since the unix epoch
+    pub interval: u64,  // seconds until the next heartbeat is due
+    pub beacon_txid: Option<Txid>,
+    pub signature: Signature,
+}
+
+impl HeartbeatRecord {
+    pub fn new(
+        keypair: &Keypair,
+        timestamp: u64,
+        interval: u64,
+        beacon_txid: Option<Txid>,
+    ) -> Self {
+        let mut record = HeartbeatRecord {
+            public_key: PublicKey::new(keypair.public_key()),
+            timestamp,
+            interval,
+            beacon_txid,
+            signature: Signature::from_slice(&[0; 64]).unwrap(),
+        };
+        record.signature = SECP256K1.sign_schnorr_no_aux_rand(&record.message(), keypair);
+
+        record
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        self.signature
+            .verify(
+                &self.message(),
+                &self.public_key.inner.x_only_public_key().0,
+            )
+            .is_ok()
+    }
+
+    fn message(&self) -> Message {
+        let mut engine = sha256::Hash::engine();
+        engine.input(HEARTBEAT_MESSAGE_TAG);
+        engine.input(&self.public_key.to_bytes());
+        engine.input(&self.timestamp.to_le_bytes());
+        engine.input(&self.interval.to_le_bytes());
+        match self.beacon_txid {
+            Some(txid) => engine.input(txid.as_byte_array()),
+            None => engine.input(&[0; 32]),
+        }
+        Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+    }
+}
+
+pub fn heartbeat_file_name(public_key: &PublicKey) -> String {
+    format!("{public_key}{HEARTBEAT_FILE_SUFFIX}")
+}
+
+// 4 byte tag, 33 byte compressed public key and 8 byte big endian timestamp, well below the
+// OP_RETURN relay limit
+pub fn heartbeat_beacon_payload(public_key: &PublicKey, timestamp: u64) -> Vec<u8> {
+    [
+        HEARTBEAT_BEACON_TAG.to_vec(),
+        public_key.to_bytes(),
+        timestamp.to_be_bytes().to_vec(),
+    ]
+    .concat()
+}
+
+pub fn heartbeat_beacon_script(public_key: &PublicKey, timestamp: u64) -> ScriptBuf {
+    ScriptBuf::new_op_return(
+        PushBytesBuf::try_from(heartbeat_beacon_payload(public_key, timestamp))
+            .expect("Heartbeat beacon payload fits in a single push"),
+    )
+}
+
+// Spends `input`, an output of the member's P2WPKH address, back to that address with the beacon
+// attached. Mainnet is refused, the beacon is a debugging aid for test networks.
+pub fn generate_heartbeat_beacon_tx(
+    network: Network,
+    keypair: &Keypair,
+    input: &Input,
+    timestamp: u64,
+) -> Result<Transaction, String> {
+    if network == Network::Bitcoin {
+        return Err(String::from(
+            "heartbeat beacons are only published on test networks",
+        ));
+    }
+    let change = input
+        .amount
+        .checked_sub(HEARTBEAT_BEACON_FEE)
+        .filter(|change| *change >= HEARTBEAT_BEACON_MIN_CHANGE)
+        .ok_or_else(|| {
+            format!(
+                "input of {} does not cover the beacon fee of {HEARTBEAT_BEACON_FEE}",
+                input.amount
+            )
+        })?;
+
+    let public_key = PublicKey::new(keypair.public_key());
+    let script_pubkey = generate_p2wpkh_address(network, &public_key).script_pubkey();
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: input.outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::default(),
+        }],
+        output: vec![
+            TxOut {
+                value: change,
+                script_pubkey: script_pubkey.clone(),
+            },
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: heartbeat_beacon_script(&public_key, timestamp),
+            },
+        ],
+    };
+
+    let sighash = SighashCache::new(&tx)
+        .p2wpkh_signature_hash(0, &script_pubkey, input.amount, EcdsaSighashType::All)
+        .map_err(|e| e.to_string())?;
+    let signature = bitcoin::ecdsa::Signature {
+        signature: keypair.secret_key().sign_ecdsa(Message::from(sighash)),
+        sighash_type: EcdsaSighashType::All,
+    };
+    tx.input[0].witness.push_ecdsa_signature(&signature);
+    tx.input[0].witness.push(public_key.to_bytes());
+
+    Ok(tx)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberLiveness {
+    pub public_key: PublicKey,
+    pub last_seen: Option<u64>, // timestamp of the latest heartbeat
+    pub age: u64,               // seconds since the latest heartbeat
+    pub missed_heartbeats: u64,
+}
+
+impl MemberLiveness {
+    pub fn is_offline(&self) -> bool {
+        self.last_seen.is_none() || self.missed_heartbeats >= MISSED_HEARTBEATS_BEFORE_OFFLINE
+    }
+}
+
+impl fmt::Display for MemberLiveness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.last_seen {
+            None => write!(f, "{}: never seen", self.public_key),
+            Some(_) if self.is_offline() => write!(
+                f,
+                "{}: OFFLINE, last seen {}s ago, {} heartbeats missed",
+                self.public_key, self.age, self.missed_heartbeats
+            ),
+            Some(_) => write!(f, "{}: last seen {}s ago", self.public_key, self.age),
+        }
+    }
+}
+
+// Latest heartbeat of every committee member, as last read from the data store
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitteeHeartbeats {
+    pub committee: Vec<PublicKey>,
+    pub records: HashMap<PublicKey, HeartbeatRecord>,
+}
+
+pub type SharedCommitteeHeartbeats = Arc<RwLock<Arc<CommitteeHeartbeats>>>;
+
+impl CommitteeHeartbeats {
+    pub fn new(committee: &[PublicKey]) -> Self {
+        Self {
+            committee: committee.to_vec(),
+            records: HashMap::new(),
+        }
+    }
+
+    // Keeps the record if it is a validly signed heartbeat of a committee member that is newer than
+    // the one seen so far. Returns whether the record was kept.
+    pub fn insert(&mut self, record: HeartbeatRecord) -> bool {
+        if !self.committee.contains(&record.public_key) || !record.verify_signature() {
+            return false;
+        }
+        if self
+            .records
+            .get(&record.public_key)
+            .is_some_and(|known| known.timestamp >= record.timestamp)
+        {
+            return false;
+        }
+        self.records.insert(record.public_key, record);
+
+        true
+    }
+
+    pub fn liveness(&self, now: u64) -> Vec<MemberLiveness> {
+        self.committee
+            .iter()
+            .map(|public_key| match self.records.get(public_key) {
+                Some(record) => {
+                    let age = now.saturating_sub(record.timestamp);
+                    MemberLiveness {
+                        public_key: *public_key,
+                        last_seen: Some(record.timestamp),
+                        age,
+                        missed_heartbeats: age / record.interval.max(1),
+                    }
+                }
+                None => MemberLiveness {
+                    public_key: *public_key,
+                    last_seen: None,
+                    age: 0,
+                    missed_heartbeats: 0,
+                },
+            })
+            .collect()
+    }
+
+    // Only printed once a member published a heartbeat, committees that do not use them stay quiet
+    pub fn print_liveness(&self, now: u64) {
+        if self.records.is_empty() {
+            return;
+        }
+        let liveness = self
+            .liveness(now)
+            .iter()
+            .map(|member| format!("  {member}"))
+            .collect::<Vec<_>>();
+        println!(
+            "[LIVENESS]: Committee heartbeats\n{}\n",
+            liveness.join("\n")
+        );
+    }
+}
+
+pub fn new_shared_committee_heartbeats(
+    heartbeats: &CommitteeHeartbeats,
+) -> SharedCommitteeHeartbeats {
+    Arc::new(RwLock::new(Arc::new(heartbeats.clone())))
+}
@@ -0,0 +1,238 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bitvm::{
+    chunk::api::type_conversion_utils::RawWitness, signatures::signing_winternitz::WinternitzSecret,
+};
+use tokio::{
+    sync::{Notify, Semaphore},
+    task::spawn_blocking,
+};
+
+use crate::{
+    commitments::CommitmentMessageId,
+    graphs::{base::GraphId, peg_out::PegOutGraph},
+};
+
+// Creating a peg-out graph, generating connector C lock scripts and signing the assert commits
+// take minutes. Jobs run them on worker threads so interactive mode stays responsive. A job only
+// computes: its output is applied to the client data on the thread owning the client, see
+// `BitVMClient::apply_finished_jobs`. Jobs live as long as the process, they are not persisted.
+
+// Generating lock scripts takes gigabytes of memory, so only a few jobs run at a time
+pub const DEFAULT_JOB_WORKERS: usize = 2;
+
+pub type JobId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum JobKind {
+    #[display("create peg-out graph")]
+    CreatePegOutGraph,
+    #[display("generate lock scripts")]
+    GenerateLockScripts,
+    #[display("sign assert commits")]
+    SignAssertCommits,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobState {
+    Queued,
+    // Also while the output waits to be applied
+    Running,
+    Succeeded(String), // string: what the job did
+    Failed(String),    // string: why the job failed
+}
+
+pub enum JobOutput {
+    PegOutGraph(
+        Box<PegOutGraph>,
+        HashMap<CommitmentMessageId, WinternitzSecret>,
+    ),
+    // The lock scripts are in the memory and disk caches, shared with the client
+    LockScripts,
+    AssertCommitWitnesses(Vec<RawWitness>, Vec<RawWitness>),
+}
+
+#[derive(Debug, Clone)]
+pub struct JobStatus {
+    pub id: JobId,
+    pub kind: JobKind,
+    pub graph_id: GraphId, // peg-out graph the job is for
+    pub state: JobState,
+    pub queued_at: Instant,
+    pub started_at: Option<Instant>,
+    pub finished_at: Option<Instant>,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, JobState::Succeeded(_) | JobState::Failed(_))
+    }
+
+    // Time spent queued, running so far or until the job finished
+    pub fn elapsed(&self) -> Duration {
+        let end = self.finished_at.unwrap_or_else(Instant::now);
+        end.duration_since(self.started_at.unwrap_or(self.queued_at))
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elapsed = self.elapsed().as_secs();
+        write!(f, "Job {}: {} {}, ", self.id, self.kind, self.graph_id)?;
+        match &self.state {
+            JobState::Queued => write!(f, "queued for {elapsed}s"),
+            JobState::Running => write!(f, "running for {elapsed}s"),
+            JobState::Succeeded(summary) => write!(f, "succeeded after {elapsed}s: {summary}"),
+            JobState::Failed(reason) => write!(f, "failed after {elapsed}s: {reason}"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct JobTable {
+    last_id: JobId,
+    statuses: BTreeMap<JobId, JobStatus>,
+    // Outputs of finished jobs not applied yet
+    outputs: BTreeMap<JobId, Result<JobOutput, String>>,
+}
+
+#[derive(Clone)]
+pub struct JobQueue {
+    jobs: Arc<Mutex<JobTable>>,
+    workers: Arc<Semaphore>,
+    finished: Arc<Notify>,
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_JOB_WORKERS)
+    }
+}
+
+impl JobQueue {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(JobTable::default())),
+            workers: Arc::new(Semaphore::new(workers.max(1))),
+            finished: Arc::new(Notify::new()),
+        }
+    }
+
+    // Runs `work` once a worker is free. A panic fails the job with the panic message.
+    pub fn submit(
+        &self,
+        kind: JobKind,
+        graph_id: &GraphId,
+        work: impl FnOnce() -> Result<JobOutput, String> + Send + 'static,
+    ) -> JobId {
+        let id = {
+            let mut jobs = self.jobs.lock().unwrap();
+            jobs.last_id += 1;
+            let id = jobs.last_id;
+            jobs.statuses.insert(
+                id,
+                JobStatus {
+                    id,
+                    kind,
+                    graph_id: graph_id.clone(),
+                    state: JobState::Queued,
+                    queued_at: Instant::now(),
+                    started_at: None,
+                    finished_at: None,
+                },
+            );
+            id
+        };
+
+        let queue = self.clone();
+        tokio::spawn(async move {
+            let _worker = queue
+                .workers
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("The job workers are never closed");
+            queue.update(id, |status| {
+                status.state = JobState::Running;
+                status.started_at = Some(Instant::now());
+            });
+
+            let output = spawn_blocking(move || {
+                catch_unwind(AssertUnwindSafe(work))
+                    .unwrap_or_else(|panic| Err(panic_message(panic.as_ref())))
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            queue.jobs.lock().unwrap().outputs.insert(id, output);
+            queue.finished.notify_one();
+        });
+
+        id
+    }
+
+    pub fn list(&self) -> Vec<JobStatus> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .statuses
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        self.jobs.lock().unwrap().statuses.get(&id).cloned()
+    }
+
+    // Outputs of the jobs finished since the last call, each to be passed to `complete` once
+    // applied
+    pub fn take_finished(&self) -> Vec<(JobStatus, Result<JobOutput, String>)> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let outputs = std::mem::take(&mut jobs.outputs);
+        outputs
+            .into_iter()
+            .filter_map(|(id, output)| Some((jobs.statuses.get(&id)?.clone(), output)))
+            .collect()
+    }
+
+    pub fn complete(&self, id: JobId, result: Result<String, String>) -> Option<JobStatus> {
+        self.update(id, |status| {
+            status.state = match result {
+                Ok(summary) => JobState::Succeeded(summary),
+                Err(reason) => JobState::Failed(reason),
+            };
+            status.finished_at = Some(Instant::now());
+        })
+    }
+
+    // Resolves once a job finished, including one that finished before waiting
+    pub async fn finished(&self) {
+        self.finished.notified().await;
+    }
+
+    fn update(&self, id: JobId, update: impl FnOnce(&mut JobStatus)) -> Option<JobStatus> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let status = jobs.statuses.get_mut(&id)?;
+        update(status);
+        Some(status.clone())
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| {
+            panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+        })
+        .unwrap_or_else(|| "the job panicked".to_string())
+}
@@ -0,0 +1,369 @@
+use std::{fs, path::Path};
+
+use bitcoin::{
+    block::Header,
+    blockdata::constants::genesis_block,
+    hashes::{sha256d, Hash},
+    BlockHash, CompactTarget, Network, Params, Target, TxMerkleNode, Txid,
+};
+use esplora_client::{AsyncClient, MerkleProof};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, HeaderChainError};
+
+// Number of previous blocks whose median time a new block must exceed
+const MEDIAN_TIME_SPAN: usize = 11;
+
+// How much of what esplora reports about a confirmation is checked before acting on it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderVerificationLevel {
+    // Confirmations are used as reported by esplora
+    #[default]
+    Trusted,
+    // The confirming block must be in the locally validated header chain, and confirmations are
+    // counted on that chain
+    HeaderChain,
+    // Additionally, the merkle proof of the tx must lead to the merkle root of that block
+    MerkleProof,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderCheckpoint {
+    pub height: u32,
+    pub hash: BlockHash,
+}
+
+impl HeaderCheckpoint {
+    pub fn genesis(network: Network) -> Self {
+        Self {
+            height: 0,
+            hash: genesis_block(network).block_hash(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderVerificationConfig {
+    #[serde(default)]
+    pub level: HeaderVerificationLevel,
+    // Headers are validated from here on. Without one, from the genesis block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<HeaderCheckpoint>,
+}
+
+// Headers from a trusted checkpoint to the tip, each checked for proof of work, difficulty and
+// timestamp against the ones before it. Only the chain with the most work since a fork is kept.
+// Signet block signatures are not checked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HeaderChain {
+    network: Network,
+    checkpoint: HeaderCheckpoint,
+    headers: Vec<Header>, // headers[0] is the checkpoint header
+}
+
+impl HeaderChain {
+    pub fn new(
+        network: Network,
+        checkpoint: HeaderCheckpoint,
+        checkpoint_header: Header,
+    ) -> Result<Self, HeaderChainError> {
+        if checkpoint_header.block_hash() != checkpoint.hash {
+            return Err(HeaderChainError::CheckpointMismatch(checkpoint.height));
+        }
+        // The first block of the difficulty period is needed to check the next retarget
+        let params = Params::new(network);
+        if !params.no_pow_retargeting
+            && checkpoint.height % difficulty_adjustment_interval(&params) != 0
+        {
+            return Err(HeaderChainError::CheckpointNotAtRetarget(checkpoint.height));
+        }
+
+        Ok(Self {
+            network,
+            checkpoint,
+            headers: vec![checkpoint_header],
+        })
+    }
+
+    pub fn checkpoint(&self) -> HeaderCheckpoint {
+        self.checkpoint
+    }
+
+    pub fn tip_height(&self) -> u32 {
+        self.checkpoint.height + self.headers.len() as u32 - 1
+    }
+
+    pub fn tip_hash(&self) -> BlockHash {
+        self.headers.last().unwrap().block_hash()
+    }
+
+    pub fn header_at(&self, height: u32) -> Option<&Header> {
+        height
+            .checked_sub(self.checkpoint.height)
+            .and_then(|index| self.headers.get(index as usize))
+    }
+
+    // Checks that the header can extend the tip
+    pub fn validate_next(&self, header: &Header) -> Result<(), HeaderChainError> {
+        let height = self.tip_height() + 1;
+        let tip = self.headers.last().unwrap();
+        if header.prev_blockhash != tip.block_hash() {
+            return Err(HeaderChainError::HeaderNotConnected(height));
+        }
+
+        let expected = self.expected_bits(header, height);
+        if header.bits != expected {
+            return Err(HeaderChainError::UnexpectedDifficulty {
+                height,
+                expected,
+                found: header.bits,
+            });
+        }
+        let target = Target::from_compact(header.bits);
+        if target > Params::new(self.network).max_attainable_target
+            || header.validate_pow(target).is_err()
+        {
+            return Err(HeaderChainError::InvalidProofOfWork(height));
+        }
+
+        let mut times: Vec<u32> = self
+            .headers
+            .iter()
+            .rev()
+            .take(MEDIAN_TIME_SPAN)
+            .map(|header| header.time)
+            .collect();
+        times.sort();
+        if header.time <= times[times.len() / 2] {
+            return Err(HeaderChainError::TimestampTooOld(height));
+        }
+
+        Ok(())
+    }
+
+    // Difficulty bits the header at `height` must have
+    fn expected_bits(&self, header: &Header, height: u32) -> CompactTarget {
+        let params = Params::new(self.network);
+        let tip = self.headers.last().unwrap();
+        if params.no_pow_retargeting {
+            return tip.bits;
+        }
+
+        let interval = difficulty_adjustment_interval(&params);
+        if height % interval == 0 {
+            // The checkpoint starts a difficulty period, so the first block of the ending one is known
+            let first = self
+                .header_at(height - interval)
+                .unwrap_or(&self.headers[0]);
+            let timespan = tip.time.saturating_sub(first.time) as u64;
+            return CompactTarget::from_next_work_required(tip.bits, timespan, &params);
+        }
+
+        if !params.allow_min_difficulty_blocks {
+            return tip.bits;
+        }
+        // Test networks require a minimum difficulty block after twice the target spacing. Other
+        // blocks keep the difficulty of the last regular block of the period.
+        let min_difficulty = params.max_attainable_target.to_compact_lossy();
+        if header.time as u64 > tip.time as u64 + 2 * params.pow_target_spacing {
+            return min_difficulty;
+        }
+        self.headers
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(index, previous)| {
+                previous.bits != min_difficulty
+                    || (self.checkpoint.height + *index as u32) % interval == 0
+                    || *index == 0
+            })
+            .map_or(tip.bits, |(_, previous)| previous.bits)
+    }
+
+    pub fn extend(&mut self, headers: Vec<Header>) -> Result<(), HeaderChainError> {
+        for header in headers {
+            self.validate_next(&header)?;
+            self.headers.push(header);
+        }
+        Ok(())
+    }
+
+    // Replaces the headers above `fork_height` with the given ones if they have more work
+    pub fn reorganize(
+        &mut self,
+        fork_height: u32,
+        headers: Vec<Header>,
+    ) -> Result<(), HeaderChainError> {
+        if fork_height < self.checkpoint.height {
+            return Err(HeaderChainError::CheckpointMismatch(self.checkpoint.height));
+        }
+        let mut candidate = self.clone();
+        candidate
+            .headers
+            .truncate((fork_height - self.checkpoint.height) as usize + 1);
+        candidate.extend(headers)?;
+
+        let work_above_fork = |chain: &HeaderChain| {
+            chain.headers[(fork_height - chain.checkpoint.height) as usize + 1..]
+                .iter()
+                .map(Header::work)
+                .reduce(|total, work| total + work)
+        };
+        if work_above_fork(&candidate) <= work_above_fork(self) {
+            return Err(HeaderChainError::LessWork(fork_height));
+        }
+
+        *self = candidate;
+        Ok(())
+    }
+
+    // Follows the chain reported by esplora, from the last block both chains have in common
+    pub async fn sync(&mut self, client: &AsyncClient) -> Result<u32, Error> {
+        let esplora_height = client.get_height().await.map_err(Error::Esplora)?;
+
+        let mut fork_height = self.tip_height().min(esplora_height);
+        loop {
+            let hash = client
+                .get_block_hash(fork_height)
+                .await
+                .map_err(Error::Esplora)?;
+            if self.header_at(fork_height).map(Header::block_hash) == Some(hash) {
+                break;
+            }
+            if fork_height == self.checkpoint.height {
+                return Err(Error::HeaderChain(HeaderChainError::CheckpointMismatch(
+                    self.checkpoint.height,
+                )));
+            }
+            fork_height -= 1;
+        }
+
+        let mut headers = vec![];
+        for height in fork_height + 1..=esplora_height {
+            let hash = client
+                .get_block_hash(height)
+                .await
+                .map_err(Error::Esplora)?;
+            let header = client
+                .get_header_by_hash(&hash)
+                .await
+                .map_err(Error::Esplora)?;
+            if header.block_hash() != hash {
+                return Err(Error::HeaderChain(HeaderChainError::HeaderNotConnected(
+                    height,
+                )));
+            }
+            headers.push(header);
+        }
+
+        // Esplora may lag behind the local chain, which is no reason to give up the local tip
+        if fork_height == self.tip_height() {
+            self.extend(headers).map_err(Error::HeaderChain)?;
+        } else if !headers.is_empty() {
+            self.reorganize(fork_height, headers)
+                .map_err(Error::HeaderChain)?;
+        }
+
+        Ok(self.tip_height())
+    }
+
+    pub fn verify_merkle_proof(
+        &self,
+        txid: &Txid,
+        proof: &MerkleProof,
+    ) -> Result<(), HeaderChainError> {
+        let header = self
+            .header_at(proof.block_height)
+            .ok_or(HeaderChainError::MerkleProofMismatch(*txid))?;
+
+        match merkle_root_from_branch(txid, proof.pos, &proof.merkle) == header.merkle_root {
+            true => Ok(()),
+            false => Err(HeaderChainError::MerkleProofMismatch(*txid)),
+        }
+    }
+
+    // Number of confirmations of the tx on the local chain, after checking that the block esplora
+    // reports it in is part of it
+    pub async fn verify_confirmations(
+        &self,
+        client: &AsyncClient,
+        txid: &Txid,
+        level: HeaderVerificationLevel,
+    ) -> Result<u32, Error> {
+        let status = client.get_tx_status(txid).await.map_err(Error::Esplora)?;
+        let Some(block_height) = status.block_height.filter(|_| status.confirmed) else {
+            return Err(Error::HeaderChain(HeaderChainError::NotConfirmed(*txid)));
+        };
+
+        let in_chain = self
+            .header_at(block_height)
+            .is_some_and(|header| Some(header.block_hash()) == status.block_hash);
+        if !in_chain {
+            return Err(Error::HeaderChain(HeaderChainError::BlockNotInChain {
+                txid: *txid,
+                block_height,
+                block_hash: status.block_hash,
+            }));
+        }
+
+        if level == HeaderVerificationLevel::MerkleProof {
+            let proof = client
+                .get_merkle_proof(txid)
+                .await
+                .map_err(Error::Esplora)?
+                .filter(|proof| proof.block_height == block_height)
+                .ok_or(Error::HeaderChain(HeaderChainError::MerkleProofMismatch(
+                    *txid,
+                )))?;
+            self.verify_merkle_proof(txid, &proof)
+                .map_err(Error::HeaderChain)?;
+        }
+
+        Ok(self.tip_height() - block_height + 1)
+    }
+
+    pub fn read_from_file(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save_to_file(&self, path: &Path) {
+        if let Err(e) = serde_json::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                fs::write(path, content).map_err(|e| e.to_string())
+            })
+        {
+            eprintln!("Failed to save header chain to {}: {e}", path.display());
+        }
+    }
+}
+
+// Merkle root of the block that has `txid` at `position`, `branch` holds the sibling hashes from
+// the leaf up, as reported by esplora
+pub fn merkle_root_from_branch(txid: &Txid, position: usize, branch: &[Txid]) -> TxMerkleNode {
+    let mut hash = txid.to_raw_hash();
+    let mut position = position;
+    for sibling in branch {
+        let (left, right) = match position % 2 {
+            0 => (hash, sibling.to_raw_hash()),
+            _ => (sibling.to_raw_hash(), hash),
+        };
+        let mut pair = [0u8; 64];
+        pair[..32].copy_from_slice(left.as_byte_array());
+        pair[32..].copy_from_slice(right.as_byte_array());
+        hash = sha256d::Hash::hash(&pair);
+        position /= 2;
+    }
+
+    TxMerkleNode::from_raw_hash(hash)
+}
+
+fn difficulty_adjustment_interval(params: &Params) -> u32 {
+    params.difficulty_adjustment_interval() as u32
+}
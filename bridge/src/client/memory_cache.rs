@@ -1,39 +1,172 @@
 use std::{
     borrow::Borrow,
+    fmt,
     hash::Hash,
-    num::NonZeroUsize,
+    mem::size_of,
     sync::{LazyLock, RwLock},
 };
 
 use lru::LruCache;
+use serde::{Deserialize, Serialize};
 
 use crate::connectors::base::{LockScriptCacheEntry, TaprootSpendInfoCacheEntry};
 
-const DEFAULT_CACHE_SIZE: usize = 1000;
+const MIB: usize = 1024 * 1024;
+const DEFAULT_SPEND_INFO_CAPACITY_BYTES: usize = MIB;
+const DEFAULT_LOCK_SCRIPTS_CAPACITY_BYTES: usize = 256 * MIB;
+
 pub(crate) static TAPROOT_SPEND_INFO_CACHE: LazyLock<
     RwLock<Cache<String, TaprootSpendInfoCacheEntry>>,
-> = LazyLock::new(|| RwLock::new(Cache::new(DEFAULT_CACHE_SIZE)));
+> = LazyLock::new(|| RwLock::new(Cache::new(DEFAULT_SPEND_INFO_CAPACITY_BYTES)));
 pub(crate) static TAPROOT_LOCK_SCRIPTS_CACHE: LazyLock<
     RwLock<Cache<String, LockScriptCacheEntry>>,
-> = LazyLock::new(|| RwLock::new(Cache::new(DEFAULT_CACHE_SIZE)));
+> = LazyLock::new(|| RwLock::new(Cache::new(DEFAULT_LOCK_SCRIPTS_CAPACITY_BYTES)));
+
+// Capacities of the in-memory caches, set with the `[cache]` table in bridge.toml
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    #[serde(default = "default_spend_info_capacity_bytes")]
+    pub spend_info_capacity_bytes: usize,
+    #[serde(default = "default_lock_scripts_capacity_bytes")]
+    pub lock_scripts_capacity_bytes: usize,
+}
+
+fn default_spend_info_capacity_bytes() -> usize {
+    DEFAULT_SPEND_INFO_CAPACITY_BYTES
+}
+
+fn default_lock_scripts_capacity_bytes() -> usize {
+    DEFAULT_LOCK_SCRIPTS_CAPACITY_BYTES
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            spend_info_capacity_bytes: DEFAULT_SPEND_INFO_CAPACITY_BYTES,
+            lock_scripts_capacity_bytes: DEFAULT_LOCK_SCRIPTS_CAPACITY_BYTES,
+        }
+    }
+}
+
+pub fn configure_caches(config: &CacheConfig) {
+    TAPROOT_SPEND_INFO_CACHE
+        .write()
+        .unwrap()
+        .set_capacity(config.spend_info_capacity_bytes);
+    TAPROOT_LOCK_SCRIPTS_CACHE
+        .write()
+        .unwrap()
+        .set_capacity(config.lock_scripts_capacity_bytes);
+}
+
+// Counters of the caches since the process started, by cache name
+pub fn cache_stats() -> Vec<(&'static str, CacheStats)> {
+    vec![
+        (
+            "taproot spend info",
+            TAPROOT_SPEND_INFO_CACHE.read().unwrap().stats(),
+        ),
+        (
+            "taproot lock scripts",
+            TAPROOT_LOCK_SCRIPTS_CACHE.read().unwrap().stats(),
+        ),
+    ]
+}
+
+// Approximate memory held by a cached key or value, in bytes
+pub trait CacheWeight {
+    fn weight(&self) -> usize;
+}
+
+impl CacheWeight for String {
+    fn weight(&self) -> usize {
+        size_of::<String>() + self.len()
+    }
+}
+
+impl CacheWeight for TaprootSpendInfoCacheEntry {
+    fn weight(&self) -> usize {
+        size_of::<Self>()
+    }
+}
+
+impl CacheWeight for LockScriptCacheEntry {
+    fn weight(&self) -> usize {
+        size_of::<Self>() + self.control_block.size() + self.encoded_script.len()
+    }
+}
 
-pub struct Cache<K: Eq + Hash, V>(LruCache<K, V>);
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub size_bytes: usize,
+    pub capacity_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64, // including entries larger than the whole capacity, which are not kept
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lookups = self.hits + self.misses;
+        write!(
+            f,
+            "{} entries, {:.1} of {:.1} MiB, {} hits, {} misses ({:.1}% hit rate), {} evictions",
+            self.entries,
+            self.size_bytes as f64 / MIB as f64,
+            self.capacity_bytes as f64 / MIB as f64,
+            self.hits,
+            self.misses,
+            match lookups {
+                0 => 0.0,
+                _ => self.hits as f64 * 100.0 / lookups as f64,
+            },
+            self.evictions
+        )
+    }
+}
+
+// Least recently used entries are evicted once the weight of all entries exceeds the capacity
+pub struct Cache<K: Eq + Hash, V> {
+    entries: LruCache<K, V>,
+    size_bytes: usize,
+    capacity_bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
 
 impl<K, V> Cache<K, V>
 where
-    K: Eq + Hash,
-    V: Clone,
+    K: Eq + Hash + CacheWeight,
+    V: Clone + CacheWeight,
 {
-    fn new(cap: usize) -> Self {
-        Self(LruCache::new(NonZeroUsize::new(cap).unwrap()))
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            entries: LruCache::unbounded(),
+            size_bytes: 0,
+            capacity_bytes,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
     }
 
     pub fn put(&mut self, key: K, value: V) -> Option<V> {
-        self.0.put(key, value)
-    }
+        let weight = key.weight() + value.weight();
+        let previous = self.entries.pop(&key);
+        if let Some(previous) = &previous {
+            self.size_bytes -= key.weight() + previous.weight();
+        }
+        if weight > self.capacity_bytes {
+            self.evictions += 1;
+            return previous;
+        }
 
-    pub fn push(&mut self, key: K, value: V) -> Option<(K, V)> {
-        self.0.push(key, value)
+        self.entries.put(key, value);
+        self.size_bytes += weight;
+        self.evict_to(self.capacity_bytes);
+        previous
     }
 
     pub fn get<Q: ?Sized>(&mut self, key: &Q) -> Option<&V>
@@ -41,28 +174,64 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq,
     {
-        self.0.get(key)
+        let value = self.entries.get(key);
+        match value {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+        value
     }
 
-    pub fn get_or_put<F>(&mut self, key: K, f: F) -> &V
+    // Does not count as a lookup in the stats
+    pub fn contains<Q: ?Sized>(&self, key: &Q) -> bool
     where
-        F: FnOnce() -> V,
+        K: Borrow<Q>,
+        Q: Hash + Eq,
     {
-        self.0.get_or_insert(key, f)
+        self.entries.contains(key)
     }
 
-    pub fn try_get_or_insert<F, E>(&mut self, k: K, f: F) -> Result<&V, E>
-    where
-        F: FnOnce() -> Result<V, E>,
-    {
-        self.0.try_get_or_insert(k, f)
+    pub fn set_capacity(&mut self, capacity_bytes: usize) {
+        self.capacity_bytes = capacity_bytes;
+        self.evict_to(capacity_bytes);
     }
 
-    pub fn contains<Q: ?Sized>(&self, key: &Q) -> bool
-    where
-        K: Borrow<Q>,
-        Q: Hash + Eq,
-    {
-        self.0.contains(key)
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            size_bytes: self.size_bytes,
+            capacity_bytes: self.capacity_bytes,
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+        }
+    }
+
+    fn evict_to(&mut self, capacity_bytes: usize) {
+        while self.size_bytes > capacity_bytes {
+            let Some((key, value)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.size_bytes -= key.weight() + value.weight();
+            self.evictions += 1;
+        }
     }
 }
+
+// Looks up `key` and generates the value on a miss without holding the lock, so readers running
+// concurrently with the client are not blocked while an expensive entry is generated. Two threads
+// missing the same key may both generate it, which is harmless as generation is deterministic.
+pub fn get_or_generate<K, V, F>(cache: &RwLock<Cache<K, V>>, key: K, generate: F) -> V
+where
+    K: Eq + Hash + CacheWeight,
+    V: Clone + CacheWeight,
+    F: FnOnce() -> V,
+{
+    if let Some(value) = cache.write().unwrap().get(&key) {
+        return value.clone();
+    }
+
+    let value = generate();
+    cache.write().unwrap().put(key, value.clone());
+    value
+}
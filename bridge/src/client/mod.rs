@@ -1,9 +1,66 @@
 #![allow(clippy::module_inception)]
+// Only the modules connector generation relies on are built without the `client` feature
+#[cfg(feature = "client")]
+pub mod alerts;
+#[cfg(feature = "client")]
+pub mod audit;
+#[cfg(feature = "client")]
+pub mod broadcast_middleware;
+#[cfg(feature = "client")]
+pub mod ceremony_schedule;
 pub mod chain;
+#[cfg(feature = "client")]
 pub mod cli;
+#[cfg(feature = "client")]
 pub mod client;
+#[cfg(feature = "client")]
+pub mod clock;
+#[cfg(feature = "client")]
+pub mod congestion;
+#[cfg(feature = "client")]
 pub mod data_store;
+#[cfg(feature = "client")]
+pub mod disprove_precomputation;
+#[cfg(feature = "client")]
 pub mod esplora;
+#[cfg(feature = "client")]
+pub mod esplora_notifications;
+#[cfg(feature = "client")]
+pub mod event_log;
+#[cfg(feature = "client")]
+pub mod faucet;
 pub mod files;
+#[cfg(feature = "client")]
+pub mod graph_history;
+#[cfg(feature = "client")]
+pub mod graph_list;
+#[cfg(feature = "client")]
+pub mod graph_store;
+#[cfg(feature = "client")]
+pub mod heartbeat;
+#[cfg(feature = "client")]
+pub mod jobs;
+#[cfg(feature = "client")]
+pub mod light_client;
 pub mod memory_cache;
+#[cfg(feature = "client")]
+pub mod peg_in_queue;
+pub mod profiling;
+#[cfg(feature = "client")]
+pub mod read_handle;
+#[cfg(feature = "regtest")]
+pub mod regtest;
+#[cfg(feature = "client")]
+pub mod rehearsal;
+#[cfg(feature = "client")]
+pub mod scheduler;
+#[cfg(feature = "client")]
 pub mod sdk;
+#[cfg(feature = "client")]
+pub mod secret_nonces;
+#[cfg(feature = "client")]
+pub mod shutdown;
+#[cfg(feature = "client")]
+pub mod staged_flush;
+#[cfg(feature = "client")]
+pub(crate) mod watchtower;
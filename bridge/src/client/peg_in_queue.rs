@@ -0,0 +1,142 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bitcoin::{Amount, Network};
+
+use crate::{constants::NUM_BLOCKS_PER_DAY, graphs::base::GraphId, utils::num_blocks_per_network};
+
+// Weights of the peg-in processing priority, see `PegInQueueEntry::score`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PegInPriorityWeights {
+    // Per block since the deposit was confirmed
+    pub confirmation_age: f64,
+    // Per bitcoin deposited
+    pub amount: f64,
+    // Per block the refund timelock expiry has moved into the urgency window
+    pub refund_urgency: f64,
+}
+
+impl Default for PegInPriorityWeights {
+    fn default() -> Self {
+        Self {
+            confirmation_age: 1.0,
+            amount: 10.0,
+            refund_urgency: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PegInProcessingConfig {
+    pub weights: PegInPriorityWeights,
+    // Graphs whose deposit can be refunded within this many blocks count as urgent. In mainnet
+    // blocks, scaled down on test networks like the timelocks.
+    pub refund_urgency_window: u32,
+    // Graphs acted on per sync, the rest are left for the next one. Processing all graphs if None.
+    pub max_actions_per_sync: Option<usize>,
+}
+
+impl Default for PegInProcessingConfig {
+    fn default() -> Self {
+        Self {
+            weights: PegInPriorityWeights::default(),
+            refund_urgency_window: NUM_BLOCKS_PER_DAY,
+            max_actions_per_sync: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegInQueueEntry {
+    pub graph_id: GraphId,
+    pub amount: Amount,
+    // Height the deposit was confirmed at, None while it is unconfirmed
+    pub deposit_height: Option<u32>,
+    pub refund_timelock: u32,
+}
+
+impl PegInQueueEntry {
+    pub fn score(&self, config: &PegInProcessingConfig, network: Network, height: u32) -> f64 {
+        let weights = &config.weights;
+        let amount_score = weights.amount * self.amount.to_btc();
+        let Some(deposit_height) = self.deposit_height else {
+            return amount_score;
+        };
+
+        let confirmation_age = height.saturating_sub(deposit_height);
+        let blocks_until_refund = (deposit_height + self.refund_timelock).saturating_sub(height);
+        let refund_urgency = num_blocks_per_network(network, config.refund_urgency_window)
+            .saturating_sub(blocks_until_refund);
+
+        amount_score
+            + weights.confirmation_age * f64::from(confirmation_age)
+            + weights.refund_urgency * f64::from(refund_urgency)
+    }
+}
+
+struct ScoredEntry {
+    score: f64,
+    entry: PegInQueueEntry,
+}
+
+impl Ord for ScoredEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Ties go to the lower graph ID, so the order does not depend on how graphs were stored
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.entry.graph_id.cmp(&self.entry.graph_id))
+    }
+}
+
+impl PartialOrd for ScoredEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ScoredEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredEntry {}
+
+// Peg-in graphs in the order automatic mode processes them, highest score first.
+pub struct PegInPriorityQueue {
+    heap: BinaryHeap<ScoredEntry>,
+}
+
+impl PegInPriorityQueue {
+    pub fn new(
+        config: &PegInProcessingConfig,
+        network: Network,
+        height: u32,
+        entries: impl IntoIterator<Item = PegInQueueEntry>,
+    ) -> Self {
+        Self {
+            heap: entries
+                .into_iter()
+                .map(|entry| ScoredEntry {
+                    score: entry.score(config, network, height),
+                    entry,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+impl Iterator for PegInPriorityQueue {
+    type Item = PegInQueueEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop().map(|scored| scored.entry)
+    }
+}
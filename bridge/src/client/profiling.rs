@@ -0,0 +1,118 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+// Wall clock time spent in the phases of client startup and the first graph operations, reported
+// with `--profile-startup`. Recording is a no-op until the profile is enabled, so phases can be
+// timed anywhere without passing a profiler around.
+static STARTUP_PROFILE: LazyLock<Mutex<Option<StartupProfile>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfiledPhase {
+    pub name: String,
+    pub start: Duration, // offset from enabling the profile
+    pub duration: Duration,
+}
+
+impl ProfiledPhase {
+    fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+
+    fn contains(&self, other: &ProfiledPhase) -> bool {
+        self.start <= other.start && other.end() <= self.end()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StartupProfile {
+    enabled_at: Instant,
+    phases: Vec<ProfiledPhase>,
+}
+
+impl StartupProfile {
+    pub fn phases(&self) -> &[ProfiledPhase] {
+        &self.phases
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.enabled_at.elapsed()
+    }
+}
+
+// Phases are listed in the order they started. A phase that ran within another one is indented
+// below it, so the time of the outer phase includes the time of the ones below it.
+impl fmt::Display for StartupProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut phases = self.phases.clone();
+        phases.sort_by_key(|phase| (phase.start, std::cmp::Reverse(phase.duration)));
+
+        write!(
+            f,
+            "Startup profile ({:.3} s in total):",
+            self.elapsed().as_secs_f64()
+        )?;
+        for (i, phase) in phases.iter().enumerate() {
+            let depth = phases[..i]
+                .iter()
+                .filter(|outer| outer.contains(phase))
+                .count();
+            write!(
+                f,
+                "\n{}{}: {:.3} s (started at {:.3} s)",
+                "  ".repeat(depth + 1),
+                phase.name,
+                phase.duration.as_secs_f64(),
+                phase.start.as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn enable_startup_profile() {
+    let mut profile = STARTUP_PROFILE.lock().unwrap();
+    if profile.is_none() {
+        *profile = Some(StartupProfile {
+            enabled_at: Instant::now(),
+            phases: vec![],
+        });
+    }
+}
+
+pub fn is_startup_profile_enabled() -> bool {
+    STARTUP_PROFILE.lock().unwrap().is_some()
+}
+
+pub fn startup_profile() -> Option<StartupProfile> {
+    STARTUP_PROFILE.lock().unwrap().clone()
+}
+
+fn record_phase(name: &str, started_at: Instant) {
+    let duration = started_at.elapsed();
+    if let Some(profile) = STARTUP_PROFILE.lock().unwrap().as_mut() {
+        profile.phases.push(ProfiledPhase {
+            name: name.to_string(),
+            start: started_at.saturating_duration_since(profile.enabled_at),
+            duration,
+        });
+    }
+}
+
+pub fn profile_phase<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let started_at = Instant::now();
+    let result = f();
+    record_phase(name, started_at);
+    result
+}
+
+pub async fn profile_phase_async<T>(name: &str, future: impl Future<Output = T>) -> T {
+    let started_at = Instant::now();
+    let result = future.await;
+    record_phase(name, started_at);
+    result
+}
@@ -0,0 +1,545 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use bitcoin::{Network, PublicKey};
+use esplora_client::{AsyncClient, TxStatus};
+use futures::future::join_all;
+use serde_json::{json, Value};
+
+use crate::{
+    contexts::{depositor::DepositorContext, operator::OperatorContext, verifier::VerifierContext},
+    error::{ClientError, Error},
+    graphs::{
+        base::{current_unix_time, get_tx_statuses, BaseGraph, GraphStage, PegOutGraphId},
+        peg_in::{PegInDepositorStatus, PegInVerifierStatus},
+        peg_out::{
+            generate_id as peg_out_generate_id, PegOutGraph, PegOutOperatorStatus,
+            WithdrawalProgress,
+        },
+    },
+    transactions::pre_signed::PreSignedTransaction,
+};
+
+use super::{
+    client::BitVMClientPublicData,
+    graph_list::{GraphKind, GraphListFilter, GraphRole, GraphSummary},
+    heartbeat::{CommitteeHeartbeats, SharedCommitteeHeartbeats},
+};
+
+// The public data as last published by the client, i.e. after the latest sync or flush.
+pub type SharedClientData = Arc<RwLock<Arc<BitVMClientPublicData>>>;
+
+pub fn new_shared_client_data(data: &BitVMClientPublicData) -> SharedClientData {
+    Arc::new(RwLock::new(Arc::new(data.clone())))
+}
+
+// Cheap to clone handle for reading the client state without `&mut` access to the client, e.g.
+// from a status server or another task while automatic mode is running. Queries run against the
+// snapshot of the public data published by the client on every sync and flush, and never block
+// the client for longer than it takes to swap the snapshot.
+#[derive(Clone)]
+pub struct BitVMClientReadHandle {
+    pub esplora: AsyncClient,
+    pub source_network: Network,
+
+    depositor_context: Option<DepositorContext>,
+    operator_context: Option<OperatorContext>,
+    verifier_context: Option<VerifierContext>,
+
+    data: SharedClientData,
+    heartbeats: SharedCommitteeHeartbeats,
+}
+
+impl BitVMClientReadHandle {
+    pub(crate) fn new(
+        esplora: AsyncClient,
+        source_network: Network,
+        depositor_context: Option<DepositorContext>,
+        operator_context: Option<OperatorContext>,
+        verifier_context: Option<VerifierContext>,
+        data: SharedClientData,
+        heartbeats: SharedCommitteeHeartbeats,
+    ) -> Self {
+        Self {
+            esplora,
+            source_network,
+            depositor_context,
+            operator_context,
+            verifier_context,
+            data,
+            heartbeats,
+        }
+    }
+
+    pub fn data(&self) -> Arc<BitVMClientPublicData> {
+        self.data.read().unwrap().clone()
+    }
+
+    pub fn heartbeats(&self) -> Arc<CommitteeHeartbeats> {
+        self.heartbeats.read().unwrap().clone()
+    }
+
+    pub async fn status(&self) {
+        let data = self.data();
+        self.queries(&data).status().await;
+        self.heartbeats().print_liveness(current_unix_time());
+    }
+
+    pub async fn get_unused_peg_in_graphs(&self) -> Vec<Value> {
+        let data = self.data();
+        self.queries(&data).get_unused_peg_in_graphs().await
+    }
+
+    pub async fn get_depositor_status(&self, depositor_public_key: &PublicKey) -> Vec<Value> {
+        let data = self.data();
+        self.queries(&data)
+            .get_depositor_status(depositor_public_key)
+            .await
+    }
+
+    pub async fn get_withdrawer_status(&self, withdrawer_chain_address: &str) -> Vec<Value> {
+        let data = self.data();
+        self.queries(&data)
+            .get_withdrawer_status(withdrawer_chain_address)
+            .await
+    }
+
+    pub async fn withdrawal_progress(
+        &self,
+        address: &str,
+    ) -> Result<Vec<WithdrawalProgress>, Error> {
+        let data = self.data();
+        self.queries(&data).withdrawal_progress(address).await
+    }
+
+    pub async fn list_graphs(&self, filter: &GraphListFilter) -> Result<Vec<GraphSummary>, Error> {
+        let data = self.data();
+        self.queries(&data).list_graphs(filter).await
+    }
+
+    fn queries<'a>(&'a self, data: &'a BitVMClientPublicData) -> ClientQueries<'a> {
+        ClientQueries {
+            esplora: &self.esplora,
+            data,
+            depositor_context: self.depositor_context.as_ref(),
+            operator_context: self.operator_context.as_ref(),
+            verifier_context: self.verifier_context.as_ref(),
+        }
+    }
+}
+
+// Read-only queries over the public data. Shared by the client, which runs them against its
+// working copy, and the read handle, which runs them against the published snapshot.
+pub(crate) struct ClientQueries<'a> {
+    pub esplora: &'a AsyncClient,
+    pub data: &'a BitVMClientPublicData,
+    pub depositor_context: Option<&'a DepositorContext>,
+    pub operator_context: Option<&'a OperatorContext>,
+    pub verifier_context: Option<&'a VerifierContext>,
+}
+
+impl ClientQueries<'_> {
+    pub async fn status(&self) {
+        if self.depositor_context.is_some() {
+            self.depositor_status().await;
+        }
+        if self.operator_context.is_some() {
+            self.operator_status().await;
+        }
+        if self.verifier_context.is_some() {
+            self.verifier_status().await;
+        }
+        self.signing_status();
+    }
+
+    // Which committee members still have to push nonces or signatures, per pre-signed tx
+    fn signing_status(&self) {
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            let committee = peg_in_graph.n_of_n_public_keys();
+            if let Some(progress) = peg_in_graph.signing_progress() {
+                println!(
+                    "[SIGNING]: Peg-in graph ID: {}\n  {progress}\n",
+                    peg_in_graph.id()
+                );
+            }
+
+            for peg_out_graph in self
+                .data
+                .peg_out_graphs
+                .iter()
+                .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
+            {
+                let progress = peg_out_graph.signing_progress(committee);
+                if !progress.is_empty() {
+                    let progress = progress
+                        .iter()
+                        .map(|progress| format!("  {progress}"))
+                        .collect::<Vec<_>>();
+                    println!(
+                        "[SIGNING]: Peg-out graph ID: {}\n{}\n",
+                        peg_out_graph.id(),
+                        progress.join("\n")
+                    );
+                }
+            }
+        }
+    }
+
+    async fn depositor_status(&self) {
+        if self.depositor_context.is_none() {
+            panic!("Depositor context must be initialized");
+        }
+
+        let depositor_public_key = &self.depositor_context.unwrap().depositor_public_key;
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            if peg_in_graph.depositor_public_key.eq(depositor_public_key) {
+                let status = peg_in_graph.depositor_status(self.esplora).await;
+                println!(
+                    "[DEPOSITOR]: Peg-in graph ID: {} status: {}\n",
+                    peg_in_graph.id(),
+                    status
+                );
+            }
+        }
+    }
+
+    async fn operator_status(&self) {
+        if self.operator_context.is_none() {
+            panic!("Operator context must be initialized");
+        }
+
+        let mut peg_out_graphs_by_id: HashMap<&PegOutGraphId, &PegOutGraph> = HashMap::new();
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            peg_out_graphs_by_id.insert(peg_out_graph.peg_out_graph_id(), peg_out_graph);
+        }
+
+        let operator_public_key = &self.operator_context.unwrap().operator_public_key;
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
+            if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
+                println!(
+                    "[OPERATOR]: Peg-in graph ID: {} status: Missing peg out graph.\n",
+                    peg_in_graph.id() // TODO update this to ask the operator to create a new peg out graph
+                );
+            } else {
+                let peg_out_graph = peg_out_graphs_by_id.get(&peg_out_graph_id).unwrap();
+                let status = peg_out_graph.operator_status(self.esplora).await;
+                println!(
+                    "[OPERATOR]: Peg-out graph ID: {} status: {}\n",
+                    peg_out_graph.id(),
+                    status
+                );
+            }
+        }
+    }
+
+    async fn verifier_status(&self) {
+        if self.verifier_context.is_none() {
+            panic!("Verifier context must be initialized");
+        }
+
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            let peg_outs = peg_in_graph
+                .peg_out_graphs
+                .iter()
+                .map(|peg_out_id| {
+                    self.data
+                        .peg_out_graphs
+                        .iter()
+                        .find(|x| x.id() == peg_out_id)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            let peg_in_status = peg_in_graph
+                .verifier_status(self.esplora, self.verifier_context.unwrap(), &peg_outs)
+                .await;
+
+            if peg_in_status == PegInVerifierStatus::Complete {
+                for peg_out_graph in peg_outs {
+                    let peg_out_status = peg_out_graph
+                        .verifier_status(self.esplora, self.verifier_context.unwrap())
+                        .await;
+                    println!(
+                        "[VERIFIER]: Peg-out graph ID: {} status: {}\n",
+                        peg_out_graph.id(),
+                        peg_out_status
+                    );
+                }
+            }
+            println!(
+                "[VERIFIER]: Peg-in graph ID: {} status: {}\n",
+                peg_in_graph.id(),
+                peg_in_status
+            );
+        }
+    }
+
+    pub async fn get_unused_peg_in_graphs(&self) -> Vec<Value> {
+        join_all(self.data.peg_in_graphs.iter().filter_map(|peg_in| {
+            Some(async move {
+                match peg_in.depositor_status(self.esplora).await {
+                    PegInDepositorStatus::PegInConfirmComplete => match self.data.peg_out_graphs.iter().find(|peg_out| peg_out.peg_in_graph_id == *peg_in.id()) {
+                        Some(peg_out) => match peg_out.operator_status(self.esplora).await {
+                            PegOutOperatorStatus::PegOutWait => Some(json!({
+                                "graph_id": peg_in.id(),
+                                "amount": peg_in.peg_in_confirm_transaction.prev_outs()[0].value.to_sat(),
+                                "source_outpoint": {
+                                    "txid": peg_in.peg_in_confirm_transaction.tx().compute_txid(),
+                                    "vout": 0
+                                },
+                            })),
+                            _ => None,
+                        },
+                        None => None,
+                    },
+                    _ => None,
+                }
+            })
+        }))
+        .await
+        .iter()
+        .filter_map(|v| {
+            v.clone()
+        })
+        .collect()
+    }
+
+    pub async fn get_depositor_status(&self, depositor_public_key: &PublicKey) -> Vec<Value> {
+        join_all(
+            self.data
+                .peg_in_graphs
+                .iter()
+                .filter(|&graph| graph.depositor_public_key.eq(depositor_public_key))
+                .map(|graph| async {
+                    let tx_ids = vec![
+                        graph.peg_in_deposit_transaction.tx().compute_txid(),
+                        graph.peg_in_confirm_transaction.tx().compute_txid(),
+                        graph.peg_in_refund_transaction.tx().compute_txid(),
+                    ];
+                    let tx_statuses_results = get_tx_statuses(self.esplora, &tx_ids).await;
+                    let blockchain_height = self.esplora.get_height().await;
+                    let status = graph.interpret_depositor_status(
+                        &tx_statuses_results[0],
+                        &tx_statuses_results[1],
+                        &tx_statuses_results[2],
+                        blockchain_height,
+                    );
+
+                    let tx_statuses = tx_statuses_results
+                        .iter()
+                        .map(|tx_status| {
+                            tx_status.as_ref().unwrap_or(&TxStatus {
+                                confirmed: false,
+                                block_height: None,
+                                block_hash: None,
+                                block_time: None,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    let tx_json_values = tx_statuses
+                        .iter()
+                        .enumerate()
+                        .map(|(i, tx_status)| {
+                            json!({
+                            "type": match i {
+                                0 => "peg_in_deposit",
+                                1 => "peg_in_confirm",
+                                2 => "peg_in_refund",
+                                _ => unreachable!(),
+                            },
+                            "txid": tx_ids[i],
+                            "status": {
+                                "confirmed": tx_status.confirmed,
+                                "block_height": tx_status.block_height.unwrap_or(0),
+                                "block_hash": tx_status.block_hash.or(None),
+                                "block_time": tx_status.block_time.unwrap_or(0),
+                            }})
+                        })
+                        .collect::<Vec<_>>();
+
+                    json!({
+                        "type": "peg_in",
+                        "graph_id": graph.id(),
+                        "status": status.to_string(),
+                        "amount": graph.peg_in_deposit_transaction.funding_amount().to_sat(),
+                        "destination_address": graph.depositor_evm_address,
+                        "txs" : tx_json_values,
+                    })
+                }),
+        )
+        .await
+    }
+
+    // Progress of the peg-outs requested by the withdrawer with the given destination chain or
+    // destination address
+    pub async fn withdrawal_progress(
+        &self,
+        address: &str,
+    ) -> Result<Vec<WithdrawalProgress>, Error> {
+        let mut withdrawals = Vec::new();
+        for peg_out_graph in self
+            .data
+            .peg_out_graphs
+            .iter()
+            .filter(|graph| graph.is_withdrawal_of(address))
+        {
+            if let Some(progress) = peg_out_graph.withdrawal_progress(self.esplora).await? {
+                withdrawals.push(progress);
+            }
+        }
+
+        Ok(withdrawals)
+    }
+
+    // Peg-in graphs followed by their peg-out graphs, with how far each got. Graphs the committee
+    // has not fully signed yet are pending the ceremony until something of them is confirmed.
+    pub async fn list_graphs(&self, filter: &GraphListFilter) -> Result<Vec<GraphSummary>, Error> {
+        let depositor_public_key = match filter.role {
+            Some(GraphRole::Depositor) => Some(
+                self.depositor_context
+                    .ok_or(Error::Client(ClientError::DepositorContextNotDefined))?
+                    .depositor_public_key,
+            ),
+            _ => None,
+        };
+        let operator_public_key = match filter.role {
+            Some(GraphRole::Operator) => Some(
+                self.operator_context
+                    .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?
+                    .operator_public_key,
+            ),
+            _ => None,
+        };
+        let verifier_public_key = match filter.role {
+            Some(GraphRole::Verifier) => Some(
+                self.verifier_context
+                    .ok_or(Error::Client(ClientError::VerifierContextNotDefined))?
+                    .verifier_public_key,
+            ),
+            _ => None,
+        };
+
+        let mut graphs = Vec::new();
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            let committee = peg_in_graph.n_of_n_public_keys();
+            if verifier_public_key.is_some_and(|key| !committee.contains(&key)) {
+                continue;
+            }
+            let amount = peg_in_graph.peg_in_deposit_transaction.funding_amount();
+
+            if operator_public_key.is_none()
+                && depositor_public_key.is_none_or(|key| peg_in_graph.depositor_public_key == key)
+            {
+                let mut stage = peg_in_graph.chain_stage(self.esplora).await?;
+                if matches!(
+                    stage,
+                    GraphStage::AwaitingDeposit | GraphStage::DepositConfirmed
+                ) && peg_in_graph.signing_progress().is_some()
+                {
+                    stage = GraphStage::PendingCeremony;
+                }
+                if filter.matches_stage(stage) {
+                    graphs.push(GraphSummary {
+                        graph_id: peg_in_graph.id().clone(),
+                        kind: GraphKind::PegIn,
+                        stage,
+                        peg_in_graph_id: None,
+                        amount,
+                    });
+                }
+            }
+
+            if depositor_public_key.is_some() {
+                continue;
+            }
+            for peg_out_graph in self.data.peg_out_graphs.iter().filter(|peg_out| {
+                peg_in_graph.peg_out_graphs.contains(peg_out.id())
+                    && operator_public_key.is_none_or(|key| *peg_out.operator_public_key() == key)
+            }) {
+                let mut stage = peg_out_graph.chain_stage(self.esplora).await?;
+                if stage == GraphStage::Ready
+                    && !peg_out_graph.signing_progress(committee).is_empty()
+                {
+                    stage = GraphStage::PendingCeremony;
+                }
+                if filter.matches_stage(stage) {
+                    graphs.push(GraphSummary {
+                        graph_id: peg_out_graph.id().clone(),
+                        kind: GraphKind::PegOut,
+                        stage,
+                        peg_in_graph_id: Some(peg_in_graph.id().clone()),
+                        amount,
+                    });
+                }
+            }
+        }
+
+        Ok(graphs)
+    }
+
+    pub async fn get_withdrawer_status(&self, withdrawer_chain_address: &str) -> Vec<Value> {
+        join_all(
+            self.data
+                .peg_out_graphs
+                .iter()
+                .filter(|&graph| {
+                    if graph.peg_out_chain_event.is_some() {
+                        return graph
+                            .peg_out_chain_event
+                            .as_ref()
+                            .unwrap()
+                            .withdrawer_chain_address
+                            .eq(withdrawer_chain_address);
+                    }
+                    false
+                })
+                .map(|graph| async {
+                    let (tx_json_value, tx_status_result) = match &graph.peg_out_transaction {
+                        Some(tx) => {
+                            let txid = tx.tx().compute_txid();
+                            let tx_status_result = self.esplora.get_tx_status(&txid).await;
+                            let tx_status = tx_status_result.as_ref().unwrap_or(&TxStatus {
+                                confirmed: false,
+                                block_height: None,
+                                block_hash: None,
+                                block_time: None,
+                            });
+                            let tx_json_value = json!({
+                                "type": "peg_out",
+                                "txid": txid,
+                                "status": {
+                                    "confirmed": tx_status.confirmed,
+                                    "block_height": tx_status.block_height.unwrap_or(0),
+                                    "block_hash": tx_status.block_hash.or(None),
+                                    "block_time": tx_status.block_time.unwrap_or(0),
+                                }
+                            });
+
+                            (Some(tx_json_value), Some(tx_status_result))
+                        }
+                        None => (Some(json!([])), None),
+                    };
+                    let (peg_out_amount, destination_address) = match &graph.peg_out_chain_event {
+                        Some(peg_out_chain_event) => (
+                            peg_out_chain_event.amount.to_sat(),
+                            peg_out_chain_event.withdrawer_destination_address.clone(),
+                        ),
+                        None => (0, "".to_string()),
+                    };
+
+                    let status = graph.interpret_withdrawer_status(tx_status_result.as_ref());
+                    json!({
+                        "type": "peg_out",
+                        "graph_id": graph.id(),
+                        "status": status.to_string(),
+                        "amount": peg_out_amount,
+                        "destination_address": destination_address,
+                        "txs": tx_json_value,
+                    })
+                }),
+        )
+        .await
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use alloy::transports::http::Client;
+use async_trait::async_trait;
+use bitcoin::{Address, Amount, Txid};
+use esplora_client::AsyncClient;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::time::sleep;
+
+use crate::error::{ClientError, Error};
+
+use super::clock::BlockProducer;
+
+// Drives a local regtest bitcoind over its RPC interface, for tests and local development: mines
+// blocks on demand, funds addresses from the node wallet and fast-forwards past relative
+// timelocks. Unlike a faucet or a block generator script, every call returns once esplora has
+// indexed its effect, so callers do not need to sleep for a block time.
+
+// Environment variables configuring the controller, e.g. in `.env.test`
+pub const BITCOIND_RPC_URL: &str = "BITCOIND_RPC_URL";
+pub const BITCOIND_RPC_USER: &str = "BITCOIND_RPC_USER";
+pub const BITCOIND_RPC_PASSWORD: &str = "BITCOIND_RPC_PASSWORD";
+pub const BITCOIND_RPC_WALLET: &str = "BITCOIND_RPC_WALLET"; // the node's default wallet if unset
+
+const ESPLORA_INDEX_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize)]
+struct RpcRequest<'a> {
+    jsonrpc: &'a str,
+    id: &'a str,
+    method: &'a str,
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegtestRpcConfig {
+    pub url: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub wallet: Option<String>,
+}
+
+impl RegtestRpcConfig {
+    // `None` unless `BITCOIND_RPC_URL` is set
+    pub fn from_env() -> Option<Self> {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        Some(Self {
+            url: var(BITCOIND_RPC_URL)?,
+            user: var(BITCOIND_RPC_USER),
+            password: var(BITCOIND_RPC_PASSWORD),
+            wallet: var(BITCOIND_RPC_WALLET),
+        })
+    }
+}
+
+pub struct RegtestController {
+    config: RegtestRpcConfig,
+    esplora: AsyncClient,
+    client: Client,
+}
+
+impl RegtestController {
+    pub fn new(config: RegtestRpcConfig, esplora: AsyncClient) -> Result<Self, Error> {
+        let client = Client::builder()
+            .build()
+            .map_err(|e| Error::Client(ClientError::RegtestRpcFailed(e.to_string())))?;
+
+        Ok(Self {
+            config,
+            esplora,
+            client,
+        })
+    }
+
+    // Returns the tip height once esplora has indexed the mined blocks
+    pub async fn mine_blocks(&self, num_blocks: u32) -> Result<u32, Error> {
+        let target = self.esplora.get_height().await.map_err(Error::Esplora)? + num_blocks;
+        if num_blocks > 0 {
+            let address: String = self.call("getnewaddress", json!([])).await?;
+            let _: Vec<String> = self
+                .call("generatetoaddress", json!([num_blocks, address]))
+                .await?;
+        }
+
+        self.wait_for_height(target).await
+    }
+
+    // Sends the amount from the node wallet, the funding transaction is left unconfirmed
+    pub async fn fund(&self, address: &Address, amount: Amount) -> Result<Txid, Error> {
+        let txid: String = self
+            .call(
+                "sendtoaddress",
+                json!([address.to_string(), amount.to_btc()]),
+            )
+            .await?;
+
+        txid.parse().map_err(|e| {
+            Error::Client(ClientError::RegtestRpcFailed(format!(
+                "Invalid txid {txid} returned by sendtoaddress: {e}"
+            )))
+        })
+    }
+
+    pub async fn fund_and_confirm(&self, address: &Address, amount: Amount) -> Result<Txid, Error> {
+        let txid = self.fund(address, amount).await?;
+        self.mine_blocks(1).await?;
+
+        Ok(txid)
+    }
+
+    // Mines enough blocks for outputs confirmed at the current tip to be spendable through a
+    // relative timelock of `num_blocks_timelock` blocks
+    pub async fn fast_forward(&self, num_blocks_timelock: u32) -> Result<u32, Error> {
+        self.mine_blocks(num_blocks_timelock).await
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Error> {
+        let failed = |message: String| {
+            Error::Client(ClientError::RegtestRpcFailed(format!(
+                "{method}: {message}"
+            )))
+        };
+
+        let url = match &self.config.wallet {
+            Some(wallet) => format!("{}/wallet/{wallet}", self.config.url.trim_end_matches('/')),
+            None => self.config.url.clone(),
+        };
+        let mut request = self.client.post(url).json(&RpcRequest {
+            jsonrpc: "1.0",
+            id: "bridge",
+            method,
+            params,
+        });
+        if let Some(user) = &self.config.user {
+            request = request.basic_auth(user, self.config.password.as_ref());
+        }
+
+        // bitcoind replies to failed calls with an error status and the error in the body
+        let response = request
+            .send()
+            .await
+            .map_err(|e| failed(e.to_string()))?
+            .json::<RpcResponse>()
+            .await
+            .map_err(|e| failed(format!("Unexpected response: {e}")))?;
+        if let Some(error) = response.error {
+            return Err(failed(format!("{} (code {})", error.message, error.code)));
+        }
+
+        serde_json::from_value(response.result.unwrap_or(Value::Null))
+            .map_err(|e| failed(format!("Unexpected result: {e}")))
+    }
+
+    async fn wait_for_height(&self, target: u32) -> Result<u32, Error> {
+        loop {
+            let height = self.esplora.get_height().await.map_err(Error::Esplora)?;
+            if height >= target {
+                return Ok(height);
+            }
+            sleep(ESPLORA_INDEX_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[async_trait]
+impl BlockProducer for RegtestController {
+    async fn produce_blocks(&self, num_blocks: u32) -> Result<u32, String> {
+        self.mine_blocks(num_blocks)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
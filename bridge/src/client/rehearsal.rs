@@ -0,0 +1,943 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use bitcoin::{key::Keypair, Amount, Network, OutPoint, PublicKey, ScriptBuf, Txid};
+use bitvm::chunk::api::type_conversion_utils::RawProof;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::{
+    client::{
+        chain::chain_adaptor::get_chain_adaptor, cli::utils::get_mock_chain_service,
+        client::BitVMClient, faucet::FaucetClient,
+    },
+    constants::DestinationNetwork,
+    contexts::base::generate_keys_from_secret,
+    error::Error,
+    graphs::{
+        base::{BaseGraph, GraphId, PegInGraphId, PegOutGraphId, PEG_IN_FEE, PEG_OUT_FEE},
+        template::GraphTemplate,
+    },
+    proof::{get_proof, invalidate_proof},
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
+    transactions::base::{Input, InputWithScript, MIN_RELAY_FEE_PEG_OUT},
+};
+
+const NETWORK: Network = Network::Regtest;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Longest wait for the next block before the block generator is assumed to be stopped
+const BLOCK_STALL_TIMEOUT: Duration = Duration::from_secs(300);
+const DEFAULT_FUNDING_TIMEOUT_SECS: u64 = 600;
+const DEFAULT_DEPOSITOR_EVM_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+// A participant of the rehearsal, written as `depositor`, `operator`, `verifier_<index>` or
+// `challenger` in scenario files
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum Actor {
+    Depositor,
+    Operator,
+    Verifier(usize), // index in `participants.verifiers`
+    Challenger,
+}
+
+impl fmt::Display for Actor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Actor::Depositor => write!(f, "depositor"),
+            Actor::Operator => write!(f, "operator"),
+            Actor::Verifier(index) => write!(f, "verifier_{index}"),
+            Actor::Challenger => write!(f, "challenger"),
+        }
+    }
+}
+
+impl FromStr for Actor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "depositor" => Ok(Actor::Depositor),
+            "operator" => Ok(Actor::Operator),
+            "challenger" => Ok(Actor::Challenger),
+            _ => s
+                .strip_prefix("verifier_")
+                .and_then(|index| index.parse().ok())
+                .map(Actor::Verifier)
+                .ok_or(format!(
+                    "Unknown actor '{s}', use depositor, operator, verifier_<index> or challenger"
+                )),
+        }
+    }
+}
+
+impl TryFrom<String> for Actor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Actor> for String {
+    fn from(actor: Actor) -> Self {
+        actor.to_string()
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RehearsalAction {
+    PegOut,
+    PegOutConfirm,
+    KickOff1,
+    KickOff2,
+    StartTime,
+    StartTimeTimeout,
+    KickOffTimeout,
+    Challenge,
+    AssertInitial,
+    AssertCommits,
+    AssertFinal,
+    Disprove,
+    DisproveChain,
+    Take1,
+    Take2,
+}
+
+impl RehearsalAction {
+    // Actions signed with the operator's keys
+    pub fn requires_operator(&self) -> bool {
+        !matches!(
+            self,
+            RehearsalAction::StartTimeTimeout
+                | RehearsalAction::KickOffTimeout
+                | RehearsalAction::Challenge
+                | RehearsalAction::Disprove
+                | RehearsalAction::DisproveChain
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StepExpectation {
+    #[default]
+    Accepted,
+    Rejected, // the client refuses to build the tx or the node rejects it
+}
+
+// The proof the operator commits to in the assert txs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RehearsalProof {
+    #[default]
+    Valid,
+    Invalid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Participants {
+    pub depositor: String, // secret keys
+    pub operator: String,
+    pub verifiers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenger: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Step {
+    // Blocks after the start of the timeline, which begins once the peg-out graph is signed
+    pub at: u32,
+    pub actor: Actor,
+    pub action: RehearsalAction,
+    #[serde(default)]
+    pub expect: StepExpectation,
+}
+
+// Bounds of the change of the balance of the actor's address over the timeline, in sats
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExpectedBalance {
+    pub actor: Actor,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_change: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_change: Option<i64>,
+}
+
+impl ExpectedBalance {
+    pub fn is_met(&self, change: i64) -> bool {
+        self.min_change.is_none_or(|min| change >= min)
+            && self.max_change.is_none_or(|max| change <= max)
+    }
+}
+
+impl fmt::Display for ExpectedBalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min_change, self.max_change) {
+            (Some(min), Some(max)) => write!(f, "{min:+} to {max:+} sats"),
+            (Some(min), None) => write!(f, "at least {min:+} sats"),
+            (None, Some(max)) => write!(f, "at most {max:+} sats"),
+            (None, None) => write!(f, "any change"),
+        }
+    }
+}
+
+// A dispute timeline to rehearse on regtest, read from a TOML file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default = "default_template")]
+    pub template: GraphTemplate,
+    pub amount: u64, // peg-in amount in sats
+    #[serde(default)]
+    pub proof: RehearsalProof,
+    #[serde(default = "default_depositor_evm_address")]
+    pub depositor_evm_address: String,
+    // Without a faucet, the required funding is printed and waited for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faucet_url: Option<String>,
+    #[serde(default = "default_funding_timeout_secs")]
+    pub funding_timeout_secs: u64,
+    pub participants: Participants,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+    #[serde(default)]
+    pub expected_balances: Vec<ExpectedBalance>,
+}
+
+fn default_template() -> GraphTemplate {
+    GraphTemplate::FastRegtest
+}
+
+fn default_depositor_evm_address() -> String {
+    DEFAULT_DEPOSITOR_EVM_ADDRESS.to_string()
+}
+
+fn default_funding_timeout_secs() -> u64 {
+    DEFAULT_FUNDING_TIMEOUT_SECS
+}
+
+impl Scenario {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read scenario {}: {e}", path.display()))?;
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let scenario: Scenario =
+            toml::from_str(content).map_err(|e| format!("Invalid scenario: {e}"))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.participants.verifiers.is_empty() {
+            return Err("The scenario needs at least one verifier".to_string());
+        }
+        self.template
+            .parameters()
+            .validate(NETWORK, self.participants.verifiers.len())?;
+        for actor in self.actors() {
+            let secret = self.secret(actor).unwrap();
+            if Keypair::from_seckey_str_global(secret).is_err() {
+                return Err(format!("Invalid secret key for {actor}"));
+            }
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            if self.secret(step.actor).is_none() {
+                return Err(format!(
+                    "Step {} is taken by {}, who is not a participant",
+                    i + 1,
+                    step.actor
+                ));
+            }
+            if step.action.requires_operator() && step.actor != Actor::Operator {
+                return Err(format!(
+                    "Step {}: only the operator can broadcast {}",
+                    i + 1,
+                    step.action
+                ));
+            }
+            if step.action == RehearsalAction::Challenge && step.actor == Actor::Operator {
+                return Err(format!("Step {}: the operator cannot challenge", i + 1));
+            }
+            if i > 0 && step.at < self.steps[i - 1].at {
+                return Err(format!(
+                    "Step {} is at block {}, before the previous step",
+                    i + 1,
+                    step.at
+                ));
+            }
+        }
+
+        for expected in &self.expected_balances {
+            if self.secret(expected.actor).is_none() {
+                return Err(format!(
+                    "Expected balance of {}, who is not a participant",
+                    expected.actor
+                ));
+            }
+            if let (Some(min), Some(max)) = (expected.min_change, expected.max_change) {
+                if min > max {
+                    return Err(format!(
+                        "Expected balance of {}: min_change is above max_change",
+                        expected.actor
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn actors(&self) -> Vec<Actor> {
+        let mut actors = vec![Actor::Depositor, Actor::Operator];
+        actors.extend((0..self.participants.verifiers.len()).map(Actor::Verifier));
+        if self.participants.challenger.is_some() {
+            actors.push(Actor::Challenger);
+        }
+        actors
+    }
+
+    pub fn secret(&self, actor: Actor) -> Option<&str> {
+        match actor {
+            Actor::Depositor => Some(&self.participants.depositor),
+            Actor::Operator => Some(&self.participants.operator),
+            Actor::Verifier(index) => self.participants.verifiers.get(index),
+            Actor::Challenger => self.participants.challenger.as_ref(),
+        }
+        .map(String::as_str)
+    }
+
+    pub fn public_key(&self, actor: Actor) -> Option<PublicKey> {
+        self.secret(actor)
+            .map(|secret| generate_keys_from_secret(NETWORK, secret).1)
+    }
+
+    // Outputs the depositor and operator need before the peg-in, in the order they are used:
+    // the deposit, the peg-out confirm input and, if the operator fronts the peg-out, its input
+    pub fn setup_funding(&self) -> Vec<(Actor, Amount)> {
+        let mut funding = vec![
+            (Actor::Depositor, Amount::from_sat(self.amount + PEG_IN_FEE)),
+            (Actor::Operator, Amount::from_sat(self.amount + PEG_OUT_FEE)),
+        ];
+        if self.has_action(RehearsalAction::PegOut) {
+            funding.push((
+                Actor::Operator,
+                Amount::from_sat(self.amount + MIN_RELAY_FEE_PEG_OUT),
+            ));
+        }
+        funding
+    }
+
+    // One crowdfunding output of the given amount for every challenge in the timeline
+    pub fn challenge_funding(&self, crowdfunding_amount: Amount) -> Vec<(Actor, Amount)> {
+        self.steps
+            .iter()
+            .filter(|step| step.action == RehearsalAction::Challenge)
+            .map(|step| (step.actor, crowdfunding_amount))
+            .collect()
+    }
+
+    fn has_action(&self, action: RehearsalAction) -> bool {
+        self.steps.iter().any(|step| step.action == action)
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Scenario '{}': peg-in of {} sats with the {} template, {} verifiers, {} proof",
+            self.name,
+            self.amount,
+            self.template,
+            self.participants.verifiers.len(),
+            match self.proof {
+                RehearsalProof::Valid => "valid",
+                RehearsalProof::Invalid => "invalid",
+            }
+        )?;
+        write!(f, "Timeline:")?;
+        for step in &self.steps {
+            write!(
+                f,
+                "\n  block +{:<4} {:<12} {:<20} expected {}",
+                step.at, step.actor, step.action, step.expect
+            )?;
+        }
+        if !self.expected_balances.is_empty() {
+            write!(f, "\nExpected balance changes:")?;
+            for expected in &self.expected_balances {
+                write!(f, "\n  {:<12} {expected}", expected.actor)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StepOutcome {
+    Accepted(Vec<Txid>),
+    Rejected(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct StepReport {
+    pub step: Step,
+    pub height: u32,
+    pub outcome: StepOutcome,
+}
+
+impl StepReport {
+    pub fn passed(&self) -> bool {
+        matches!(
+            (&self.outcome, self.step.expect),
+            (StepOutcome::Accepted(_), StepExpectation::Accepted)
+                | (StepOutcome::Rejected(_), StepExpectation::Rejected)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BalanceReport {
+    pub expected: ExpectedBalance,
+    pub change: i64,
+}
+
+impl BalanceReport {
+    pub fn passed(&self) -> bool {
+        self.expected.is_met(self.change)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RehearsalReport {
+    pub name: String,
+    pub steps: Vec<StepReport>,
+    pub balances: Vec<BalanceReport>,
+}
+
+impl RehearsalReport {
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(StepReport::passed) && self.balances.iter().all(BalanceReport::passed)
+    }
+}
+
+fn verdict(passed: bool) -> &'static str {
+    match passed {
+        true => "ok",
+        false => "FAILED",
+    }
+}
+
+impl fmt::Display for RehearsalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Rehearsal '{}': {}",
+            self.name,
+            match self.passed() {
+                true => "passed",
+                false => "FAILED",
+            }
+        )?;
+        for report in &self.steps {
+            let outcome = match &report.outcome {
+                StepOutcome::Accepted(txids) => format!(
+                    "accepted ({})",
+                    txids
+                        .iter()
+                        .map(Txid::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                StepOutcome::Rejected(reason) => format!("rejected: {reason}"),
+            };
+            write!(
+                f,
+                "\n  [{}] block +{:<4} {:<12} {:<20} {outcome}",
+                verdict(report.passed()),
+                report.step.at,
+                report.step.actor,
+                report.step.action,
+            )?;
+            if !report.passed() {
+                write!(f, " (expected {})", report.step.expect)?;
+            }
+        }
+        for report in &self.balances {
+            write!(
+                f,
+                "\n  [{}] {:<12} balance changed by {:+} sats, expected {}",
+                verdict(report.passed()),
+                report.expected.actor,
+                report.change,
+                report.expected
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Runs a scenario against the regtest esplora, with a client per participant sharing the data
+// store, and a mock destination chain that reports the peg-out the operator fronts
+pub struct Rehearsal {
+    scenario: Scenario,
+    clients: BTreeMap<Actor, BitVMClient>,
+    proof: RawProof,
+}
+
+impl Rehearsal {
+    pub async fn new(scenario: Scenario, esplora_url: Option<&str>) -> Self {
+        let n_of_n_public_keys: Vec<PublicKey> = (0..scenario.participants.verifiers.len())
+            .map(|index| scenario.public_key(Actor::Verifier(index)).unwrap())
+            .collect();
+        let valid_proof = get_proof();
+        let proof = match scenario.proof {
+            RehearsalProof::Valid => valid_proof.clone(),
+            RehearsalProof::Invalid => invalidate_proof(&valid_proof),
+        };
+
+        let mut clients = BTreeMap::new();
+        for actor in scenario.actors() {
+            let secret = scenario.secret(actor);
+            // The challenger only needs a keypair to sign its crowdfunding input
+            let (depositor_secret, operator_secret, verifier_secret) = match actor {
+                Actor::Depositor | Actor::Challenger => (secret, None, None),
+                Actor::Operator => (None, secret, None),
+                Actor::Verifier(_) => (None, None, secret),
+            };
+            let client = BitVMClient::new(
+                esplora_url,
+                NETWORK,
+                DestinationNetwork::Local,
+                Some(get_chain_adaptor(DestinationNetwork::Local, None, None)),
+                &n_of_n_public_keys,
+                depositor_secret,
+                operator_secret,
+                verifier_secret,
+                None,
+                Some(&format!("rehearsal_{actor}")),
+                Some(valid_proof.vk.clone()),
+            )
+            .await;
+            clients.insert(actor, client);
+        }
+
+        Self {
+            scenario,
+            clients,
+            proof,
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<RehearsalReport, String> {
+        println!("{}", self.scenario);
+
+        let setup_funding = self.scenario.setup_funding();
+        let mut setup_inputs = self.fund(&setup_funding).await?.into_iter();
+        let deposit_input = setup_inputs.next().unwrap();
+        let peg_out_confirm_input = setup_inputs.next().unwrap();
+        let peg_out_input = setup_inputs.next();
+
+        let peg_in_graph_id = self.peg_in(deposit_input).await?;
+        let peg_out_graph_id = self
+            .create_peg_out_graph(&peg_in_graph_id, peg_out_confirm_input)
+            .await?;
+
+        let crowdfunding_amount = {
+            let operator = self.client(Actor::Operator);
+            let graph = operator
+                .data()
+                .peg_out_graphs
+                .iter()
+                .find(|graph| graph.id() == &peg_out_graph_id)
+                .ok_or(format!("Peg-out graph {peg_out_graph_id} not found"))?;
+            Amount::from_sat(graph.min_crowdfunding_amount())
+        };
+        let challenge_funding = self.scenario.challenge_funding(crowdfunding_amount);
+        let mut challenge_inputs = self.fund(&challenge_funding).await?.into_iter();
+
+        let mut balances = HashMap::new();
+        for expected in &self.scenario.expected_balances {
+            balances.insert(expected.actor, self.balance(expected.actor).await?);
+        }
+
+        let start_height = self.height().await?;
+        println!("Timeline starts at block {start_height}");
+
+        let mut step_reports = vec![];
+        for step in self.scenario.steps.clone() {
+            let height = self.wait_for_height(start_height + step.at).await?;
+            let input = match step.action {
+                RehearsalAction::PegOut => peg_out_input.clone(),
+                RehearsalAction::Challenge => challenge_inputs.next(),
+                _ => None,
+            };
+            let outcome = match self.execute(&step, &peg_out_graph_id, input).await {
+                Ok(txids) => StepOutcome::Accepted(txids),
+                Err(e) => StepOutcome::Rejected(e.to_string()),
+            };
+            let report = StepReport {
+                step,
+                height,
+                outcome,
+            };
+            println!(
+                "[{}] block {} ({}): {} {}: {:?}",
+                verdict(report.passed()),
+                height,
+                report.step.at,
+                report.step.actor,
+                report.step.action,
+                report.outcome
+            );
+            step_reports.push(report);
+        }
+
+        // Balances are compared once the last txs are confirmed
+        let end_height = self.height().await?;
+        self.wait_for_height(end_height + 1).await?;
+        let mut balance_reports = vec![];
+        for expected in self.scenario.expected_balances.clone() {
+            let change = self.balance(expected.actor).await? - balances[&expected.actor];
+            balance_reports.push(BalanceReport { expected, change });
+        }
+
+        Ok(RehearsalReport {
+            name: self.scenario.name.clone(),
+            steps: step_reports,
+            balances: balance_reports,
+        })
+    }
+
+    fn client(&self, actor: Actor) -> &BitVMClient {
+        &self.clients[&actor]
+    }
+
+    fn client_mut(&mut self, actor: Actor) -> &mut BitVMClient {
+        self.clients.get_mut(&actor).unwrap()
+    }
+
+    fn verifiers(&self) -> Vec<Actor> {
+        (0..self.scenario.participants.verifiers.len())
+            .map(Actor::Verifier)
+            .collect()
+    }
+
+    fn reward_script(&self, actor: Actor) -> ScriptBuf {
+        generate_pay_to_pubkey_script_address(NETWORK, &self.scenario.public_key(actor).unwrap())
+            .script_pubkey()
+    }
+
+    async fn peg_in(&mut self, deposit_input: Input) -> Result<PegInGraphId, String> {
+        let template = self.scenario.template;
+        let evm_address = self.scenario.depositor_evm_address.clone();
+        let depositor = self.client_mut(Actor::Depositor);
+        let graph_id = depositor
+            .create_peg_in_graph(deposit_input, &evm_address, template)
+            .await
+            .map_err(|e| format!("Failed to create peg-in graph: {e}"))?;
+        let txid = depositor
+            .broadcast_peg_in_deposit(&graph_id)
+            .await
+            .map_err(|e| format!("Failed to broadcast peg-in deposit: {e}"))?;
+        depositor.flush().await;
+        self.wait_for_confirmation(txid, "peg-in deposit").await?;
+
+        self.signing_ceremony(&graph_id).await;
+
+        let depositor = self.client_mut(Actor::Depositor);
+        depositor.sync().await;
+        let txid = depositor
+            .broadcast_peg_in_confirm(&graph_id)
+            .await
+            .map_err(|e| format!("Failed to broadcast peg-in confirm: {e}"))?;
+        depositor.flush().await;
+        self.wait_for_confirmation(txid, "peg-in confirm").await?;
+
+        Ok(graph_id)
+    }
+
+    async fn create_peg_out_graph(
+        &mut self,
+        peg_in_graph_id: &PegInGraphId,
+        peg_out_confirm_input: Input,
+    ) -> Result<PegOutGraphId, String> {
+        let parameters = self.scenario.template.parameters();
+        let operator_public_key = self.scenario.public_key(Actor::Operator).unwrap();
+        let operator = self.client_mut(Actor::Operator);
+        operator.sync().await;
+        let graph_id =
+            operator.create_peg_out_graph(peg_in_graph_id, peg_out_confirm_input, parameters);
+        operator.flush().await;
+
+        self.signing_ceremony(&graph_id).await;
+
+        // The destination chain reports a peg-out request for the deposit, and its burn
+        let operator = self.client_mut(Actor::Operator);
+        operator.sync().await;
+        let peg_in_confirm_txid = operator
+            .data()
+            .peg_out_graphs
+            .iter()
+            .find(|graph| graph.id() == &graph_id)
+            .ok_or(format!("Peg-out graph {graph_id} not found"))?
+            .peg_in_confirm_txid();
+        operator.set_chain_service(get_mock_chain_service(
+            OutPoint {
+                txid: peg_in_confirm_txid,
+                vout: 0,
+            },
+            operator_public_key,
+        ));
+        operator.sync_l2().await;
+        operator.flush().await;
+
+        Ok(graph_id)
+    }
+
+    async fn signing_ceremony(&mut self, graph_id: &GraphId) {
+        for verifier in self.verifiers() {
+            let client = self.client_mut(verifier);
+            client.sync().await;
+            client.push_verifier_nonces(graph_id);
+            client.flush().await;
+        }
+        for verifier in self.verifiers() {
+            let client = self.client_mut(verifier);
+            client.sync().await;
+            client.push_verifier_signature(graph_id);
+            client.flush().await;
+        }
+    }
+
+    async fn execute(
+        &mut self,
+        step: &Step,
+        graph_id: &PegOutGraphId,
+        input: Option<Input>,
+    ) -> Result<Vec<Txid>, Error> {
+        let reward_script = self.reward_script(step.actor);
+        let public_key = self.scenario.public_key(step.actor).unwrap();
+        let proof = self.proof.clone();
+        let client = self.client_mut(step.actor);
+        client.sync().await;
+
+        let txids = match step.action {
+            RehearsalAction::PegOut => {
+                let input = input.expect("the peg-out input is funded with the setup");
+                vec![client.broadcast_peg_out(graph_id, input).await?]
+            }
+            RehearsalAction::PegOutConfirm => {
+                vec![client.broadcast_peg_out_confirm(graph_id).await?]
+            }
+            RehearsalAction::KickOff1 => vec![client.broadcast_kick_off_1(graph_id).await?],
+            RehearsalAction::KickOff2 => vec![client.broadcast_kick_off_2(graph_id).await?],
+            RehearsalAction::StartTime => vec![client.broadcast_start_time(graph_id).await?],
+            RehearsalAction::StartTimeTimeout => vec![
+                client
+                    .broadcast_start_time_timeout(graph_id, reward_script)
+                    .await?,
+            ],
+            RehearsalAction::KickOffTimeout => vec![
+                client
+                    .broadcast_kick_off_timeout(graph_id, reward_script)
+                    .await?,
+            ],
+            RehearsalAction::Challenge => {
+                let input = input.expect("challenges are funded before the timeline");
+                let script = generate_pay_to_pubkey_script(&public_key);
+                let crowdfunding_input = InputWithScript {
+                    outpoint: input.outpoint,
+                    amount: input.amount,
+                    script: &script,
+                };
+                vec![
+                    client
+                        .broadcast_challenge(graph_id, &vec![crowdfunding_input], script.clone())
+                        .await?,
+                ]
+            }
+            RehearsalAction::AssertInitial => {
+                vec![client.broadcast_assert_initial(graph_id).await?]
+            }
+            RehearsalAction::AssertCommits => {
+                let (commit_1_txid, commit_2_txid) =
+                    client.broadcast_assert_commits(graph_id, &proof).await?;
+                vec![commit_1_txid, commit_2_txid]
+            }
+            RehearsalAction::AssertFinal => vec![client.broadcast_assert_final(graph_id).await?],
+            RehearsalAction::Disprove => {
+                vec![client.broadcast_disprove(graph_id, reward_script).await?]
+            }
+            RehearsalAction::DisproveChain => vec![
+                client
+                    .broadcast_disprove_chain(graph_id, reward_script)
+                    .await?,
+            ],
+            RehearsalAction::Take1 => vec![client.broadcast_take_1(graph_id).await?],
+            RehearsalAction::Take2 => vec![client.broadcast_take_2(graph_id).await?],
+        };
+
+        // Other participants see the txs the graph recorded, e.g. the peg-out tx
+        client.flush().await;
+        Ok(txids)
+    }
+
+    // Confirmed outputs of the given amounts, requested from the faucet if one is configured.
+    // Outputs the addresses already hold are used first.
+    async fn fund(&self, funding: &[(Actor, Amount)]) -> Result<Vec<Input>, String> {
+        let mut required: BTreeMap<(Actor, Amount), usize> = BTreeMap::new();
+        for key in funding {
+            *required.entry(*key).or_default() += 1;
+        }
+
+        let faucet = match &self.scenario.faucet_url {
+            Some(url) => Some(FaucetClient::new(url, NETWORK).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        for (&(actor, amount), &count) in &required {
+            let address = generate_pay_to_pubkey_script_address(
+                NETWORK,
+                &self.scenario.public_key(actor).unwrap(),
+            );
+            let missing = count.saturating_sub(self.funding_outputs(actor, amount).await?.len());
+            for _ in 0..missing {
+                match &faucet {
+                    Some(faucet) => {
+                        faucet
+                            .fund(&address, amount)
+                            .await
+                            .map_err(|e| format!("Failed to fund {actor}: {e}"))?;
+                    }
+                    None => println!("Fund {actor} address {address} with {amount}"),
+                }
+            }
+        }
+
+        let started_at = Instant::now();
+        let mut available: BTreeMap<(Actor, Amount), Vec<OutPoint>> = BTreeMap::new();
+        for (&(actor, amount), &count) in &required {
+            loop {
+                let outpoints = self.funding_outputs(actor, amount).await?;
+                if outpoints.len() >= count {
+                    available.insert((actor, amount), outpoints);
+                    break;
+                }
+                if started_at.elapsed().as_secs() > self.scenario.funding_timeout_secs {
+                    return Err(format!(
+                        "{actor} was not funded with {count} confirmed outputs of {amount} in time"
+                    ));
+                }
+                sleep(POLL_INTERVAL).await;
+            }
+        }
+
+        Ok(funding
+            .iter()
+            .map(|&(actor, amount)| Input {
+                outpoint: available.get_mut(&(actor, amount)).unwrap().remove(0),
+                amount,
+            })
+            .collect())
+    }
+
+    async fn funding_outputs(&self, actor: Actor, amount: Amount) -> Result<Vec<OutPoint>, String> {
+        let address = generate_pay_to_pubkey_script_address(
+            NETWORK,
+            &self.scenario.public_key(actor).unwrap(),
+        );
+        let utxos = self
+            .client(actor)
+            .esplora
+            .get_address_utxo(address)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(utxos
+            .into_iter()
+            .filter(|utxo| utxo.status.confirmed && utxo.value == amount)
+            .map(|utxo| OutPoint {
+                txid: utxo.txid,
+                vout: utxo.vout,
+            })
+            .collect())
+    }
+
+    // Confirmed balance of the actor's address in sats
+    async fn balance(&self, actor: Actor) -> Result<i64, String> {
+        let address = generate_pay_to_pubkey_script_address(
+            NETWORK,
+            &self.scenario.public_key(actor).unwrap(),
+        );
+        let utxos = self
+            .client(actor)
+            .esplora
+            .get_address_utxo(address)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(utxos
+            .iter()
+            .filter(|utxo| utxo.status.confirmed)
+            .map(|utxo| utxo.value.to_sat() as i64)
+            .sum())
+    }
+
+    async fn height(&self) -> Result<u32, String> {
+        self.client(Actor::Operator)
+            .esplora
+            .get_height()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn wait_for_height(&self, target: u32) -> Result<u32, String> {
+        let mut height = self.height().await?;
+        let mut last_block_at = Instant::now();
+        while height < target {
+            sleep(POLL_INTERVAL).await;
+            let new_height = self.height().await?;
+            if new_height > height {
+                last_block_at = Instant::now();
+            } else if last_block_at.elapsed() > BLOCK_STALL_TIMEOUT {
+                return Err(format!(
+                    "No new block since block {height} for {} s, is regtest/block-generator.sh running?",
+                    BLOCK_STALL_TIMEOUT.as_secs()
+                ));
+            }
+            height = new_height;
+        }
+        Ok(height)
+    }
+
+    async fn wait_for_confirmation(&self, txid: Txid, name: &str) -> Result<(), String> {
+        let esplora = &self.client(Actor::Operator).esplora;
+        let started_at = Instant::now();
+        loop {
+            let status = esplora
+                .get_tx_status(&txid)
+                .await
+                .map_err(|e| e.to_string())?;
+            if status.confirmed {
+                return Ok(());
+            }
+            if started_at.elapsed() > BLOCK_STALL_TIMEOUT {
+                return Err(format!("{name} tx {txid} was not confirmed in time"));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
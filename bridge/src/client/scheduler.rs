@@ -0,0 +1,203 @@
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+
+use esplora_client::AsyncClient;
+use tokio::time::sleep;
+
+use super::esplora_notifications::{ChainNotification, EsploraSubscription};
+
+// How often the chain tip is checked for a new block. Plain esplora does not push new blocks, so
+// without a websocket this is the only poll left in automatic mode and it is a single cheap request.
+pub const DEFAULT_BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// How often the data store is checked for changes pushed by other participants between blocks.
+pub const DEFAULT_DATA_STORE_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+// How often a lost or refused websocket connection is tried again, the tip is polled meanwhile
+pub const NOTIFICATIONS_RECONNECT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulerEvent {
+    // The chain tip moved to the given height. Graph state machines should be re-evaluated.
+    NewBlock(u32),
+    // No new block, but the data store should be checked for changes made by other participants.
+    DataStoreSyncDue,
+    // A transaction funding or spending a tracked address was relayed or confirmed. Graph state
+    // machines should be re-evaluated without waiting for the next block.
+    AddressActivity,
+    // A proof evaluated in the background turned out invalid or valid. Graph state machines should
+    // be re-evaluated, so a disprove is broadcast right away.
+    ProofEvaluated,
+}
+
+// Drives automatic mode by block height instead of a busy loop: every graph is re-evaluated
+// once per new block, plus whenever other participants change the data store in between.
+pub struct BlockScheduler {
+    block_poll_interval: Duration,
+    data_store_sync_interval: Duration,
+    last_height: Option<u32>,
+    last_data_store_sync: Instant,
+    notifications: Option<Notifications>,
+}
+
+// Websocket of the esplora backend, see `esplora_notifications`
+struct Notifications {
+    url: String,
+    subscription: Option<EsploraSubscription>,
+    tracked_addresses: BTreeSet<String>,
+    last_connection_attempt: Option<Instant>,
+}
+
+impl BlockScheduler {
+    pub fn new(block_poll_interval: Duration, data_store_sync_interval: Duration) -> Self {
+        Self {
+            block_poll_interval,
+            data_store_sync_interval,
+            last_height: None,
+            last_data_store_sync: Instant::now(),
+            notifications: None,
+        }
+    }
+
+    // Waits for blocks and address activity pushed through the websocket at `url` where the
+    // backend supports it, falling back to polling the tip while it cannot be reached
+    pub fn with_notifications(mut self, url: &str) -> Self {
+        self.notifications = Some(Notifications {
+            url: url.to_string(),
+            subscription: None,
+            tracked_addresses: BTreeSet::new(),
+            last_connection_attempt: None,
+        });
+        self
+    }
+
+    // Addresses whose transactions are notified from now on, e.g. the graph connector addresses
+    pub fn track_addresses(&mut self, addresses: BTreeSet<String>) {
+        if let Some(notifications) = self.notifications.as_mut() {
+            notifications.tracked_addresses = addresses;
+        }
+    }
+
+    pub fn last_height(&self) -> Option<u32> {
+        self.last_height
+    }
+
+    // Waits until the next block, tracked address activity or until the data store is due for a
+    // sync, whichever comes first. The first call returns immediately with the current height.
+    pub async fn next_event(&mut self, esplora: &AsyncClient) -> SchedulerEvent {
+        // Blocks found while not connected are caught up by polling the tip first
+        if self.last_height.is_some() && self.connect_notifications().await {
+            if let Some(event) = self.next_notified_event().await {
+                return event;
+            }
+        }
+
+        loop {
+            match esplora.get_height().await {
+                Ok(height)
+                    if self
+                        .last_height
+                        .is_none_or(|last_height| height > last_height) =>
+                {
+                    self.last_height = Some(height);
+                    self.last_data_store_sync = Instant::now();
+                    return SchedulerEvent::NewBlock(height);
+                }
+                Ok(_) => {}
+                Err(err) => eprintln!("Failed to get the current block height: {err}"),
+            }
+
+            if self.last_data_store_sync.elapsed() >= self.data_store_sync_interval {
+                self.last_data_store_sync = Instant::now();
+                return SchedulerEvent::DataStoreSyncDue;
+            }
+
+            sleep(self.block_poll_interval).await;
+        }
+    }
+
+    // Whether the websocket is connected and tracks the current addresses
+    async fn connect_notifications(&mut self) -> bool {
+        let Some(notifications) = self.notifications.as_mut() else {
+            return false;
+        };
+        if notifications.subscription.is_none() {
+            if notifications
+                .last_connection_attempt
+                .is_some_and(|attempt| attempt.elapsed() < NOTIFICATIONS_RECONNECT_INTERVAL)
+            {
+                return false;
+            }
+            notifications.last_connection_attempt = Some(Instant::now());
+            match EsploraSubscription::connect(&notifications.url).await {
+                Ok(subscription) => notifications.subscription = Some(subscription),
+                Err(err) => {
+                    eprintln!("Esplora notifications unavailable, polling instead: {err}");
+                    return false;
+                }
+            }
+        }
+
+        let subscription = notifications.subscription.as_mut().unwrap();
+        if let Err(err) = subscription
+            .track_addresses(notifications.tracked_addresses.clone())
+            .await
+        {
+            eprintln!("Esplora notifications lost, polling instead: {err}");
+            notifications.subscription = None;
+            return false;
+        }
+
+        true
+    }
+
+    // None once the websocket is lost
+    async fn next_notified_event(&mut self) -> Option<SchedulerEvent> {
+        loop {
+            let sync_due_in = self
+                .data_store_sync_interval
+                .saturating_sub(self.last_data_store_sync.elapsed());
+            let subscription = self.notifications.as_mut()?.subscription.as_mut()?;
+            let notification = tokio::select! {
+                notification = subscription.next_notification() => Some(notification),
+                _ = sleep(sync_due_in) => None,
+            };
+
+            match notification {
+                None => {
+                    self.last_data_store_sync = Instant::now();
+                    return Some(SchedulerEvent::DataStoreSyncDue);
+                }
+                Some(Ok(ChainNotification::NewBlock(height))) => {
+                    if self
+                        .last_height
+                        .is_none_or(|last_height| height > last_height)
+                    {
+                        self.last_height = Some(height);
+                        self.last_data_store_sync = Instant::now();
+                        return Some(SchedulerEvent::NewBlock(height));
+                    }
+                }
+                Some(Ok(ChainNotification::AddressActivity(_))) => {
+                    return Some(SchedulerEvent::AddressActivity);
+                }
+                Some(Err(err)) => {
+                    eprintln!("Esplora notifications lost, polling instead: {err}");
+                    if let Some(notifications) = self.notifications.as_mut() {
+                        notifications.subscription = None;
+                    }
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl Default for BlockScheduler {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_BLOCK_POLL_INTERVAL,
+            DEFAULT_DATA_STORE_SYNC_INTERVAL,
+        )
+    }
+}
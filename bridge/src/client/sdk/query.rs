@@ -4,7 +4,7 @@ use bitcoin::{PublicKey, XOnlyPublicKey};
 use esplora_client::AsyncClient;
 use serde_json::Value;
 
-use crate::transactions::base::Input;
+use crate::{graphs::base::PegInGraphId, transactions::base::Input};
 
 use super::query_contexts::depositor_signatures::DepositorSignatures;
 
@@ -35,7 +35,7 @@ pub trait ClientCliQuery {
     ) -> impl Future<Output = Result<Value, String>>;
     fn retry_broadcast_peg_in_deposit(
         &self,
-        peg_in_graph_id: &str,
+        peg_in_graph_id: &PegInGraphId,
     ) -> impl Future<Output = Result<Value, String>>;
 }
 
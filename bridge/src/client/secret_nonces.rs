@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use bitcoin::{
+    hashes::{sha256, Hash, HashEngine},
+    key::Keypair,
+    PublicKey, Txid,
+};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use musig2::SecNonce;
+use serde::{Deserialize, Serialize};
+
+use crate::serialization::{serialize, try_deserialize};
+
+// Secret nonces are kept in the private data until the verifier signed with them. Anyone holding
+// a secret nonce and the partial signature made with it can compute the verifier's secret key, so
+// they are saved encrypted with a key derived from the verifier's secret key. A client run with
+// the verifier key can decrypt them after a crash without asking for a passphrase.
+
+const SECRET_NONCES_KEY_TAG: &[u8] = b"bitvm-bridge/secret-nonces";
+const NONCE_LENGTH: usize = 24;
+
+// Tx ID -> Input index -> Secret nonce
+pub type GraphSecretNonces = HashMap<Txid, HashMap<usize, SecNonce>>;
+
+// Secret nonces of one graph, authenticated together with the graph id and the verifier's public
+// key so that they cannot be moved to another graph
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EncryptedSecretNonces {
+    pub nonce: String,      // hex
+    pub ciphertext: String, // hex
+}
+
+impl EncryptedSecretNonces {
+    pub fn encrypt(
+        keypair: &Keypair,
+        graph_id: &str,
+        secret_nonces: &GraphSecretNonces,
+    ) -> Result<Self, String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher(keypair)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: serialize(secret_nonces).as_bytes(),
+                    aad: associated_data(keypair, graph_id).as_bytes(),
+                },
+            )
+            .map_err(|_| format!("Failed to encrypt the secret nonces of graph {graph_id}"))?;
+
+        Ok(Self {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, keypair: &Keypair, graph_id: &str) -> Result<GraphSecretNonces, String> {
+        let nonce = hex::decode(&self.nonce)
+            .ok()
+            .filter(|nonce| nonce.len() == NONCE_LENGTH)
+            .ok_or_else(|| format!("Invalid nonce of the secret nonces of graph {graph_id}"))?;
+        let ciphertext = hex::decode(&self.ciphertext).map_err(|err| {
+            format!("Invalid ciphertext of the secret nonces of graph {graph_id}: {err}")
+        })?;
+
+        let contents = cipher(keypair)
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: associated_data(keypair, graph_id).as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                format!("Secret nonces of graph {graph_id} were not saved with this verifier key")
+            })?;
+        let contents = String::from_utf8(contents)
+            .map_err(|_| format!("Decrypted secret nonces of graph {graph_id} are not valid"))?;
+
+        try_deserialize(&contents)
+    }
+}
+
+fn cipher(keypair: &Keypair) -> XChaCha20Poly1305 {
+    let mut engine = sha256::Hash::engine();
+    engine.input(SECRET_NONCES_KEY_TAG);
+    engine.input(&keypair.secret_bytes());
+    let key = sha256::Hash::from_engine(engine).to_byte_array();
+
+    XChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn associated_data(keypair: &Keypair, graph_id: &str) -> String {
+    format!(
+        "bitvm-bridge-secret-nonces:{}:{graph_id}",
+        PublicKey::new(keypair.public_key())
+    )
+}
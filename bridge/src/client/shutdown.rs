@@ -0,0 +1,168 @@
+use std::{
+    fmt, io,
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::watch, time::sleep};
+
+// How long a shutdown waits for the committee to approve staged flushes, only their author promotes
+// them to the shared data
+pub const STAGED_FLUSH_GRACE_PERIOD: Duration = Duration::from_secs(120);
+pub const STAGED_FLUSH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+// Exit code of a process terminated by SIGINT, used when a second signal cuts the shutdown short
+const FORCED_EXIT_CODE: i32 = 130;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+    Signal(&'static str),
+    MaxRuntime(Duration),
+}
+
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShutdownReason::Signal(name) => write!(f, "received {name}"),
+            ShutdownReason::MaxRuntime(max_runtime) => {
+                write!(
+                    f,
+                    "reached the maximum runtime of {}s",
+                    max_runtime.as_secs()
+                )
+            }
+        }
+    }
+}
+
+// Shutdown request of automatic mode. The first SIGINT or SIGTERM, or the end of the maximum
+// runtime, only marks the shutdown as requested: the loop notices it while waiting for the next
+// event, so the broadcasts and the flush of the current iteration always complete. A second signal
+// exits right away.
+pub struct Shutdown {
+    requested: watch::Receiver<Option<ShutdownReason>>,
+    started_at: Instant,
+}
+
+impl Shutdown {
+    // Must be called from within the tokio runtime. Replaces the default signal handlers for the
+    // rest of the process.
+    pub fn listen(max_runtime: Option<Duration>) -> io::Result<Self> {
+        let mut signals = Signals::new()?;
+        let (sender, requested) = watch::channel(None);
+
+        tokio::spawn(async move {
+            let reason = tokio::select! {
+                name = signals.recv() => ShutdownReason::Signal(name),
+                _ = max_runtime_elapsed(max_runtime) => {
+                    ShutdownReason::MaxRuntime(max_runtime.unwrap())
+                }
+            };
+            println!("Shutting down ({reason}), finishing the current iteration. Send the signal again to exit immediately.");
+            let _ = sender.send(Some(reason));
+
+            let name = signals.recv().await;
+            eprintln!("Received {name} again, exiting without saving the current iteration");
+            std::process::exit(FORCED_EXIT_CODE);
+        });
+
+        Ok(Self {
+            requested,
+            started_at: Instant::now(),
+        })
+    }
+
+    pub fn requested(&self) -> Option<ShutdownReason> {
+        *self.requested.borrow()
+    }
+
+    // Resolves once a shutdown is requested
+    pub async fn wait(&mut self) -> ShutdownReason {
+        match self.requested.wait_for(Option::is_some).await {
+            Ok(reason) => reason.unwrap(),
+            // The listener only stops by exiting the process
+            Err(_) => std::future::pending().await,
+        }
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+}
+
+// Printed when a long running mode exits
+#[derive(Debug, Default)]
+pub struct RunSummary {
+    pub iterations: u64,
+    pub blocks: u64,
+    pub flushes: u64,
+}
+
+impl RunSummary {
+    pub fn print(
+        &self,
+        reason: ShutdownReason,
+        uptime: Duration,
+        unsaved_changes: bool,
+        pending_staged_flushes: usize,
+    ) {
+        println!("Shut down after {}s, {reason}", uptime.as_secs());
+        println!(
+            "Processed {} blocks in {} iterations, flushed {} times",
+            self.blocks, self.iterations, self.flushes
+        );
+        if pending_staged_flushes > 0 {
+            eprintln!("warning: {pending_staged_flushes} staged flushes were not approved in time, they are not promoted to the shared data");
+        }
+        match unsaved_changes {
+            true => eprintln!(
+                "warning: The last flush failed, changes since the previous flush are lost"
+            ),
+            false => println!("All changes are saved to the data store"),
+        }
+    }
+}
+
+async fn max_runtime_elapsed(max_runtime: Option<Duration>) {
+    match max_runtime {
+        Some(max_runtime) => sleep(max_runtime).await,
+        None => std::future::pending().await,
+    }
+}
+
+// Registered up front, so a signal arriving before the listener first polls is not lost
+struct Signals {
+    #[cfg(unix)]
+    interrupt: tokio::signal::unix::Signal,
+    #[cfg(unix)]
+    terminate: tokio::signal::unix::Signal,
+}
+
+impl Signals {
+    #[cfg(unix)]
+    fn new() -> io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> io::Result<Self> {
+        Ok(Self {})
+    }
+
+    #[cfg(unix)]
+    async fn recv(&mut self) -> &'static str {
+        tokio::select! {
+            _ = self.interrupt.recv() => "SIGINT",
+            _ = self.terminate.recv() => "SIGTERM",
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn recv(&mut self) -> &'static str {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+
+use bitcoin::{
+    hashes::{sha256, Hash, HashEngine},
+    key::Keypair,
+    secp256k1::{schnorr::Signature, Message},
+    PublicKey,
+};
+use secp256k1::SECP256K1;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, graphs::base::BaseGraph};
+
+use super::client::BitVMClientPublicData;
+
+// With flush coordination enabled a flush is not written to the shared data directly. It is
+// staged next to it, the other committee members validate it on their next sync and publish a
+// signed acknowledgment, and only once enough of them approved does the author promote the stage
+// to the shared data. A client writing broken graph data then only corrupts its own stage.
+
+// Appended to the remote data path, a sibling rather than a subdirectory so that listing the
+// shared data does not list the stages too
+pub const STAGED_FLUSH_DIRECTORY_SUFFIX: &str = "-staged";
+pub const FLUSH_ACKNOWLEDGMENT_DIRECTORY_NAME: &str = "acknowledgments";
+pub const FLUSH_ACKNOWLEDGMENT_FILE_SUFFIX: &str = "-acknowledgment.json";
+// Seconds a stage waits for acknowledgments before it is given up
+pub const DEFAULT_STAGE_EXPIRY: u64 = 60 * 60;
+
+const FLUSH_ACKNOWLEDGMENT_MESSAGE_TAG: &[u8] = b"bitvm-bridge/flush-acknowledgment";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlushCoordinationConfig {
+    // Approvals of other committee members needed to promote a stage
+    pub required_acknowledgments: usize,
+    pub expiry: u64, // seconds
+}
+
+impl Default for FlushCoordinationConfig {
+    fn default() -> Self {
+        Self {
+            required_acknowledgments: 1,
+            expiry: DEFAULT_STAGE_EXPIRY,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlushAcknowledgment {
+    pub stage: String, // file name of the staged flush
+    pub public_key: PublicKey,
+    pub timestamp: u64,            // seconds since the unix epoch
+    pub rejection: Option<String>, // why the stage was rejected, none if it was approved
+    pub signature: Signature,
+}
+
+impl FlushAcknowledgment {
+    pub fn new(keypair: &Keypair, stage: &str, timestamp: u64, rejection: Option<String>) -> Self {
+        let mut acknowledgment = FlushAcknowledgment {
+            stage: stage.to_string(),
+            public_key: PublicKey::new(keypair.public_key()),
+            timestamp,
+            rejection,
+            signature: Signature::from_slice(&[0; 64]).unwrap(),
+        };
+        acknowledgment.signature =
+            SECP256K1.sign_schnorr_no_aux_rand(&acknowledgment.message(), keypair);
+
+        acknowledgment
+    }
+
+    pub fn is_approval(&self) -> bool {
+        self.rejection.is_none()
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        self.signature
+            .verify(
+                &self.message(),
+                &self.public_key.inner.x_only_public_key().0,
+            )
+            .is_ok()
+    }
+
+    fn message(&self) -> Message {
+        let mut engine = sha256::Hash::engine();
+        engine.input(FLUSH_ACKNOWLEDGMENT_MESSAGE_TAG);
+        engine.input(&(self.stage.len() as u64).to_le_bytes());
+        engine.input(self.stage.as_bytes());
+        engine.input(&self.public_key.to_bytes());
+        engine.input(&self.timestamp.to_le_bytes());
+        match &self.rejection {
+            Some(reason) => {
+                engine.input(&[1]);
+                engine.input(reason.as_bytes());
+            }
+            None => engine.input(&[0]),
+        }
+        Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+    }
+}
+
+// Named after the stage timestamp rather than the stage file name, which would make the
+// acknowledgment look like client data to the data store
+pub fn flush_acknowledgment_file_name(stage_timestamp: u64, public_key: &PublicKey) -> String {
+    format!("{stage_timestamp}-{public_key}{FLUSH_ACKNOWLEDGMENT_FILE_SUFFIX}")
+}
+
+// Whether the staged data may follow `canonical`, the data the committee member validating it
+// holds. Graphs only known to one side are not compared, merging never drops graphs.
+pub fn validate_transition(
+    canonical: &BitVMClientPublicData,
+    staged: &BitVMClientPublicData,
+) -> Result<(), Error> {
+    for graph in staged.peg_in_graphs.iter() {
+        if let Some(previous) = canonical
+            .peg_in_graphs
+            .iter()
+            .find(|previous| previous.id() == graph.id())
+        {
+            graph.validate_transition(previous)?;
+        }
+    }
+    for graph in staged.peg_out_graphs.iter() {
+        if let Some(previous) = canonical
+            .peg_out_graphs
+            .iter()
+            .find(|previous| previous.id() == graph.id())
+        {
+            graph.validate_transition(previous)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StagedFlushStatus {
+    Pending,
+    Rejected(Vec<(PublicKey, String)>),
+    Unacknowledged, // expired before enough committee members approved it
+}
+
+// A flush of this client, as staged in the data store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFlush {
+    pub stage: String,  // file name of the staged data
+    pub staged_at: u64, // seconds since the unix epoch
+    pub approvals: Vec<PublicKey>,
+    pub status: StagedFlushStatus,
+}
+
+impl StagedFlush {
+    pub fn new(stage: String, staged_at: u64) -> Self {
+        Self {
+            stage,
+            staged_at,
+            approvals: vec![],
+            status: StagedFlushStatus::Pending,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == StagedFlushStatus::Pending
+    }
+
+    // Settles a pending stage on the acknowledgments published so far, which must be validly
+    // signed ones of other committee members. A single rejection rejects the stage. Returns
+    // whether the stage has enough approvals to be promoted.
+    pub fn record_acknowledgments(
+        &mut self,
+        acknowledgments: &[FlushAcknowledgment],
+        config: &FlushCoordinationConfig,
+        now: u64,
+    ) -> bool {
+        if !self.is_pending() {
+            return false;
+        }
+
+        let rejections = acknowledgments
+            .iter()
+            .filter_map(|acknowledgment| {
+                acknowledgment
+                    .rejection
+                    .clone()
+                    .map(|reason| (acknowledgment.public_key, reason))
+            })
+            .collect::<Vec<_>>();
+        if !rejections.is_empty() {
+            self.status = StagedFlushStatus::Rejected(rejections);
+            return false;
+        }
+
+        self.approvals = acknowledgments
+            .iter()
+            .map(|acknowledgment| acknowledgment.public_key)
+            .collect();
+        if self.approvals.len() >= config.required_acknowledgments {
+            return true;
+        }
+        if now >= self.staged_at + config.expiry {
+            self.status = StagedFlushStatus::Unacknowledged;
+        }
+
+        false
+    }
+}
+
+impl fmt::Display for StagedFlush {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.status {
+            StagedFlushStatus::Pending => write!(
+                f,
+                "{}: waiting for acknowledgments, {} approved",
+                self.stage,
+                self.approvals.len()
+            ),
+            StagedFlushStatus::Rejected(rejections) => {
+                write!(f, "{}: REJECTED", self.stage)?;
+                for (public_key, reason) in rejections {
+                    write!(f, "\n    by {public_key}: {reason}")?;
+                }
+                Ok(())
+            }
+            StagedFlushStatus::Unacknowledged => write!(
+                f,
+                "{}: UNACKNOWLEDGED, expired with {} approvals",
+                self.stage,
+                self.approvals.len()
+            ),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use bitcoin::block::Header;
+use esplora_client::AsyncClient;
+
+use crate::{
+    graphs::{base::GraphId, peg_out::CommittedSuperblock},
+    superblock::is_heavier_superblock,
+};
+
+// The watchtower of `watch` mode looks for a superblock heavier than the one an operator committed
+// to in kick-off 2. The blocks from the start time confirmation on are scanned once, on every new
+// block only the blocks mined since are fetched.
+#[derive(Default)]
+pub struct Watchtower {
+    searches: HashMap<GraphId, SuperblockSearch>,
+}
+
+struct SuperblockSearch {
+    next_height: u32,
+    heaviest: Option<Header>,
+}
+
+impl Watchtower {
+    // Heaviest superblock up to the tip that disproves the committed one, if any
+    pub async fn heavier_superblock(
+        &mut self,
+        esplora: &AsyncClient,
+        graph_id: &GraphId,
+        committed: &CommittedSuperblock,
+        tip_height: u32,
+    ) -> Result<Option<Header>, esplora_client::Error> {
+        let search = self
+            .searches
+            .entry(graph_id.clone())
+            .or_insert_with(|| SuperblockSearch {
+                next_height: committed.start_time_height,
+                heaviest: None,
+            });
+
+        while search.next_height <= tip_height {
+            let block_hash = esplora.get_block_hash(search.next_height).await?;
+            let header = esplora.get_header_by_hash(&block_hash).await?;
+            let heaviest_hash = search
+                .heaviest
+                .map_or(committed.superblock_hash, |heaviest| heaviest.block_hash());
+            if is_heavier_superblock(&header, &heaviest_hash, committed.start_time) {
+                search.heaviest = Some(header);
+            }
+            search.next_height += 1;
+        }
+
+        Ok(search.heaviest)
+    }
+
+    // Once the graph's disprove chain is settled one way or the other
+    pub fn forget(&mut self, graph_id: &GraphId) {
+        self.searches.remove(graph_id);
+    }
+}
@@ -1,16 +1,23 @@
 use std::collections::HashMap;
 
+use bitcoin::Witness;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
 use bitvm::{
     chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256},
-    signatures::{signing_winternitz::WinternitzSecret, wots_api},
+    signatures::{
+        signing_winternitz::{WinternitzSecret, LOG_D},
+        winternitz::Parameters,
+        wots_api,
+    },
 };
 
 use super::{
     constants::{
-        DESTINATION_NETWORK_TXID_LENGTH, SOURCE_NETWORK_TXID_LENGTH, START_TIME_MESSAGE_LENGTH,
+        DESTINATION_CHAIN_ID_MESSAGE_LENGTH, DESTINATION_NETWORK_TXID_LENGTH,
+        SOURCE_NETWORK_TXID_LENGTH, START_TIME_MESSAGE_LENGTH, WITHDRAWAL_NONCE_MESSAGE_LENGTH,
     },
     superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
 };
@@ -25,6 +32,9 @@ pub enum CommitmentMessageId {
     StartTime,
     Superblock,
     SuperblockHash,
+    // Destination chain metadata, only committed by graphs whose parameters ask for it
+    DestinationChainId,
+    WithdrawalNonce,
     // name of intermediate value and length of message
     Groth16IntermediateValues((String, usize)),
 }
@@ -77,55 +87,124 @@ impl CommitmentMessageId {
     // btree map is a copy of chunker related commitments
     pub fn generate_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecret> {
         println!("Generating commitment secrets ...");
-        let mut commitment_map = HashMap::from([
+        Self::message_lengths()
+            .into_iter()
+            .map(|(message_id, message_length)| (message_id, WinternitzSecret::new(message_length)))
+            .collect()
+    }
+
+    // Derives the commitment secrets of a single graph from a master seed, so the same seed
+    // never yields the same one-time Winternitz secret for two graphs or two messages.
+    pub fn generate_commitment_secrets_from_seed(
+        seed: &[u8],
+        graph_id: &str,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        Self::message_lengths()
+            .into_iter()
+            .map(|(message_id, message_length)| {
+                let mut hasher = Sha256::new();
+                hasher.update(seed);
+                hasher.update(graph_id.as_bytes());
+                hasher.update(String::from(message_id.clone()).as_bytes());
+                let secret = hex::encode(&hasher.finalize()[..20]);
+
+                let parameters = Parameters::new_by_bit_length(message_length as u32 * 8, LOG_D);
+                (
+                    message_id,
+                    WinternitzSecret::from_string(&secret, &parameters),
+                )
+            })
+            .collect()
+    }
+
+    // Every message a peg-out graph commits to, with its length in bytes
+    pub fn message_lengths() -> Vec<(CommitmentMessageId, usize)> {
+        let mut message_lengths = vec![
             (
                 CommitmentMessageId::PegOutTxIdSourceNetwork,
-                WinternitzSecret::new(SOURCE_NETWORK_TXID_LENGTH),
+                SOURCE_NETWORK_TXID_LENGTH,
             ),
             (
                 CommitmentMessageId::PegOutTxIdDestinationNetwork,
-                WinternitzSecret::new(DESTINATION_NETWORK_TXID_LENGTH),
+                DESTINATION_NETWORK_TXID_LENGTH,
             ),
+            (CommitmentMessageId::StartTime, START_TIME_MESSAGE_LENGTH),
+            (CommitmentMessageId::Superblock, SUPERBLOCK_MESSAGE_LENGTH),
             (
-                CommitmentMessageId::StartTime,
-                WinternitzSecret::new(START_TIME_MESSAGE_LENGTH),
+                CommitmentMessageId::SuperblockHash,
+                SUPERBLOCK_HASH_MESSAGE_LENGTH,
             ),
             (
-                CommitmentMessageId::Superblock,
-                WinternitzSecret::new(SUPERBLOCK_MESSAGE_LENGTH),
+                CommitmentMessageId::DestinationChainId,
+                DESTINATION_CHAIN_ID_MESSAGE_LENGTH,
             ),
             (
-                CommitmentMessageId::SuperblockHash,
-                WinternitzSecret::new(SUPERBLOCK_HASH_MESSAGE_LENGTH),
+                CommitmentMessageId::WithdrawalNonce,
+                WITHDRAWAL_NONCE_MESSAGE_LENGTH,
             ),
-        ]);
+        ];
 
         for i in 0..NUM_PUBS {
-            commitment_map.insert(
+            message_lengths.push((
                 CommitmentMessageId::Groth16IntermediateValues((format!("{}", i), 32)),
-                WinternitzSecret::new(32),
-            );
+                32,
+            ));
         }
         for i in 0..NUM_U256 {
-            commitment_map.insert(
+            message_lengths.push((
                 CommitmentMessageId::Groth16IntermediateValues((format!("{}", i + NUM_PUBS), 32)),
-                WinternitzSecret::new(32),
-            );
+                32,
+            ));
         }
         for i in 0..NUM_HASH {
-            commitment_map.insert(
+            message_lengths.push((
                 CommitmentMessageId::Groth16IntermediateValues((
                     format!("{}", i + NUM_PUBS + NUM_U256),
                     wots_api::HASH_LEN as usize,
                 )),
-                WinternitzSecret::new(wots_api::HASH_LEN as usize),
-            );
+                wots_api::HASH_LEN as usize,
+            ));
         }
 
-        commitment_map
+        message_lengths
     }
 }
 
+// Commitment revealed at `offset` of a script path witness, as signed by
+// `generate_winternitz_witness`: a signature and a digit for every block, the digits of the message
+// in reverse order followed by the checksum digits. Returns the message and the witness elements
+// of the commitment.
+pub fn revealed_commitment(
+    witness: &Witness,
+    offset: usize,
+    parameters: &Parameters,
+) -> Option<(Vec<u8>, Witness)> {
+    let elements: Vec<&[u8]> = witness
+        .iter()
+        .skip(offset)
+        .take(2 * parameters.total_length() as usize)
+        .collect();
+    if elements.len() != 2 * parameters.total_length() as usize {
+        return None;
+    }
+
+    let message_length = parameters.byte_message_length() as usize;
+    let digits_per_byte = (8 / LOG_D) as usize;
+    let num_digits = message_length * digits_per_byte;
+    let mut message = vec![0u8; message_length];
+    for digit_index in 0..num_digits {
+        let digit = match elements[2 * (num_digits - 1 - digit_index) + 1] {
+            [] => 0,
+            [digit] if u32::from(*digit) < 1 << LOG_D => *digit,
+            _ => return None,
+        };
+        message[digit_index / digits_per_byte] |=
+            digit << (LOG_D as usize * (digit_index % digits_per_byte));
+    }
+
+    Some((message, Witness::from_slice(&elements)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -142,6 +221,8 @@ mod tests {
             (CommitmentMessageId::StartTime, "test"),
             (CommitmentMessageId::Superblock, "test"),
             (CommitmentMessageId::SuperblockHash, "test"),
+            (CommitmentMessageId::DestinationChainId, "test"),
+            (CommitmentMessageId::WithdrawalNonce, "test"),
             (
                 CommitmentMessageId::Groth16IntermediateValues(("F_10_mul_c_1p0c".to_string(), 31)),
                 "test",
@@ -159,4 +240,24 @@ mod tests {
         let deserialized_messages = deserialize::<HashMap<CommitmentMessageId, &str>>(&json);
         assert_eq!(messages, deserialized_messages);
     }
+
+    #[test]
+    fn test_commitment_secrets_from_seed() {
+        let seed = [7u8; 32];
+        let secrets = CommitmentMessageId::generate_commitment_secrets_from_seed(&seed, "graph_0");
+
+        assert_eq!(
+            secrets,
+            CommitmentMessageId::generate_commitment_secrets_from_seed(&seed, "graph_0")
+        );
+        assert_ne!(
+            secrets[&CommitmentMessageId::StartTime],
+            CommitmentMessageId::generate_commitment_secrets_from_seed(&seed, "graph_1")
+                [&CommitmentMessageId::StartTime]
+        );
+        assert_ne!(
+            secrets[&CommitmentMessageId::Superblock],
+            secrets[&CommitmentMessageId::SuperblockHash]
+        );
+    }
 }
@@ -0,0 +1,240 @@
+use std::{
+    fs::File,
+    io::{self, ErrorKind, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use bitcoin::{hashes::Hash, TapNodeHash};
+
+use crate::{client::files::BRIDGE_DATA_DIRECTORY_NAME, utils::decompress};
+
+use super::lock_scripts_file::{LOCK_SCRIPTS_FILE_TAG, LOCK_SCRIPTS_FILE_VERSION};
+
+// Files of the connector C disk caches start with a tag naming the cache and the version of their
+// format as two ASCII digits, e.g. `BVMLKS01`. Files written before a cache had a header are zstd
+// compressed bitcode, which never starts with a tag.
+//
+// Files are named by the cache id of their connector C, see `ConnectorC::spend_info_cache_id`.
+// Files below `FULL_CACHE_ID_FILE_VERSION` were named by an id of the first commitment public key
+// only, so graphs sharing that key read each other's lock scripts. They are never looked up again,
+// and `migrate_cache_directory` removes them in the background on startup.
+
+pub const CACHE_DIRECTORY_NAME: &str = "cache";
+pub const LOCK_SCRIPTS_FILE_PREFIX: &str = "lock_scripts_";
+pub const MERKLE_ROOT_FILE_PREFIX: &str = "merkle_root_";
+// Lock scripts shared through the data store, named after their merkle root, see
+// `BitVMClient::publish_lock_scripts`
+pub const SHARED_LOCK_SCRIPTS_DIRECTORY_NAME: &str = "lock-scripts";
+
+// Hashed into every cache id, bump it on every change of what the ids cover
+pub const CACHE_ID_VERSION: u8 = 2;
+// First version of both caches named by the cache ids of `CACHE_ID_VERSION`
+pub const FULL_CACHE_ID_FILE_VERSION: u8 = 2;
+
+pub type CacheFileTag = [u8; 6];
+pub const CACHE_HEADER_LENGTH: usize = 8;
+
+// Merkle root file: header | merkle root (32 bytes)
+pub const MERKLE_ROOT_FILE_TAG: &CacheFileTag = b"BVMMRT";
+pub const MERKLE_ROOT_FILE_VERSION: u8 = 2;
+
+// Keeps the temporary files of concurrent writers of the same cache file apart
+static TEMPORARY_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+// The startup migration and `cache migrate` never rewrite the same files at the same time
+static MIGRATION_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheFileFormat {
+    // Written before the cache had a header
+    Legacy,
+    Version(u8),
+}
+
+pub fn cache_directory() -> PathBuf {
+    Path::new(BRIDGE_DATA_DIRECTORY_NAME).join(CACHE_DIRECTORY_NAME)
+}
+
+pub fn shared_lock_scripts_file_name(merkle_root: &TapNodeHash) -> String {
+    format!("{LOCK_SCRIPTS_FILE_PREFIX}{merkle_root}.bin")
+}
+
+// Lock scripts on their way to or from the data store. Not a cache file, so neither migrated nor
+// counted against the cache size.
+pub fn lock_scripts_transfer_path(name: &str) -> PathBuf {
+    cache_directory().join(format!(
+        "transfer_{name}.{}.{}.tmp",
+        std::process::id(),
+        TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+pub fn cache_header(tag: &CacheFileTag, version: u8) -> [u8; CACHE_HEADER_LENGTH] {
+    let mut header = [0u8; CACHE_HEADER_LENGTH];
+    header[..tag.len()].copy_from_slice(tag);
+    header[tag.len()] = b'0' + version / 10;
+    header[tag.len() + 1] = b'0' + version % 10;
+    header
+}
+
+pub fn cache_file_format(tag: &CacheFileTag, data: &[u8]) -> CacheFileFormat {
+    match data.get(..CACHE_HEADER_LENGTH) {
+        Some([tag_bytes @ .., tens, ones])
+            if tag_bytes == tag && tens.is_ascii_digit() && ones.is_ascii_digit() =>
+        {
+            CacheFileFormat::Version((tens - b'0') * 10 + (ones - b'0'))
+        }
+        _ => CacheFileFormat::Legacy,
+    }
+}
+
+// Only reads the header, lock scripts files are hundreds of megabytes
+pub fn read_cache_file_format(tag: &CacheFileTag, file_path: &Path) -> io::Result<CacheFileFormat> {
+    let mut header = Vec::with_capacity(CACHE_HEADER_LENGTH);
+    File::open(file_path)?
+        .take(CACHE_HEADER_LENGTH as u64)
+        .read_to_end(&mut header)?;
+    Ok(cache_file_format(tag, &header))
+}
+
+pub fn unsupported_version(tag: &CacheFileTag, version: u8, supported_version: u8) -> io::Error {
+    io::Error::new(
+        ErrorKind::Unsupported,
+        format!(
+            "{} cache file version {version} is newer than the supported version {supported_version}",
+            String::from_utf8_lossy(tag)
+        ),
+    )
+}
+
+// Written next to the file and renamed over it, so a process that has the previous file mapped
+// keeps reading the previous contents
+pub fn write_cache_file(file_path: &Path, data: &[u8]) -> io::Result<()> {
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temporary_path = file_path.with_extension(format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&temporary_path, data)?;
+    std::fs::rename(&temporary_path, file_path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temporary_path);
+    })
+}
+
+pub fn write_merkle_root_file(file_path: &Path, merkle_root: TapNodeHash) -> io::Result<()> {
+    println!("Writing cache to {}...", file_path.display());
+    let mut data = cache_header(MERKLE_ROOT_FILE_TAG, MERKLE_ROOT_FILE_VERSION).to_vec();
+    data.extend(merkle_root.to_byte_array());
+    write_cache_file(file_path, &data)
+}
+
+pub fn read_merkle_root_file(file_path: &Path) -> io::Result<TapNodeHash> {
+    let data = std::fs::read(file_path)?;
+    let merkle_root = match cache_file_format(MERKLE_ROOT_FILE_TAG, &data) {
+        CacheFileFormat::Legacy => {
+            bitcode::decode::<[u8; 32]>(&decompress(&data)?).map_err(io::Error::other)?
+        }
+        // Version 1 has the same layout, only the file names changed
+        CacheFileFormat::Version(1..=MERKLE_ROOT_FILE_VERSION) => data[CACHE_HEADER_LENGTH..]
+            .try_into()
+            .map_err(|_| io::Error::new(ErrorKind::InvalidData, "Truncated merkle root file"))?,
+        CacheFileFormat::Version(version) => {
+            return Err(unsupported_version(
+                MERKLE_ROOT_FILE_TAG,
+                version,
+                MERKLE_ROOT_FILE_VERSION,
+            ))
+        }
+    };
+    Ok(TapNodeHash::from_byte_array(merkle_root))
+}
+
+#[derive(Debug, Default)]
+pub struct CacheMigrationReport {
+    // Files named by cache ids of a previous scheme, see `FULL_CACHE_ID_FILE_VERSION`
+    pub removed: Vec<PathBuf>,
+    pub up_to_date: usize,
+    // Files that could not be migrated, e.g. written by a newer client, left as they are
+    pub failed: Vec<(PathBuf, io::Error)>,
+}
+
+// Brings a cache file up to the current version of its cache. Returns whether it was removed.
+pub fn migrate_cache_file(file_path: &Path) -> io::Result<bool> {
+    let file_name = file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let (tag, version) = if file_name.starts_with(LOCK_SCRIPTS_FILE_PREFIX) {
+        (LOCK_SCRIPTS_FILE_TAG, LOCK_SCRIPTS_FILE_VERSION)
+    } else if file_name.starts_with(MERKLE_ROOT_FILE_PREFIX) {
+        (MERKLE_ROOT_FILE_TAG, MERKLE_ROOT_FILE_VERSION)
+    } else {
+        return Ok(false);
+    };
+
+    match read_cache_file_format(tag, file_path)? {
+        CacheFileFormat::Version(file_version) if file_version > version => {
+            Err(unsupported_version(tag, file_version, version))
+        }
+        CacheFileFormat::Version(file_version) if file_version >= FULL_CACHE_ID_FILE_VERSION => {
+            Ok(false)
+        }
+        // Converting the file would not help, no connector looks its name up anymore
+        _ => {
+            std::fs::remove_file(file_path)?;
+            Ok(true)
+        }
+    }
+}
+
+pub fn migrate_cache_directory(directory: &Path) -> io::Result<CacheMigrationReport> {
+    let _lock = MIGRATION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut report = CacheMigrationReport::default();
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(report),
+        Err(e) => return Err(e),
+    };
+
+    let mut file_paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "bin"))
+        .collect();
+    file_paths.sort();
+    for file_path in file_paths {
+        match migrate_cache_file(&file_path) {
+            Ok(true) => report.removed.push(file_path),
+            Ok(false) => report.up_to_date += 1,
+            Err(e) => report.failed.push((file_path, e)),
+        }
+    }
+
+    Ok(report)
+}
+
+// Migrates the local cache directory on a background thread, so stale cache files do not count
+// against the cache size until they are evicted
+pub fn spawn_cache_migration() -> thread::JoinHandle<()> {
+    thread::spawn(|| match migrate_cache_directory(&cache_directory()) {
+        Ok(report) => {
+            if !report.removed.is_empty() {
+                println!(
+                    "Removed {} cache files named by a previous cache id",
+                    report.removed.len()
+                );
+            }
+            for (file_path, e) in report.failed {
+                eprintln!("Failed to migrate cache file {}: {e}", file_path.display());
+            }
+        }
+        Err(e) => eprintln!("Failed to migrate the cache directory: {e}"),
+    })
+}
@@ -13,6 +13,7 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Connector0 {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
 }
 
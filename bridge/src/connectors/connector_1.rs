@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use bitcoin::{
     taproot::{TaprootBuilder, TaprootSpendInfo},
@@ -30,9 +30,11 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Connector1 {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_taproot_public_key: XOnlyPublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
-    pub commitment_public_keys: HashMap<CommitmentMessageId, WinternitzPublicKey>,
+    pub commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
     pub num_blocks_timelock_leaf_0: u32,
     pub num_blocks_timelock_leaf_1: u32,
     pub num_blocks_timelock_leaf_2: u32,
@@ -43,7 +45,7 @@ impl Connector1 {
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
-        commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
     ) -> Self {
         Connector1 {
             network,
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::{
     commitments::CommitmentMessageId,
@@ -26,9 +26,11 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Connector2 {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_taproot_public_key: XOnlyPublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
-    pub commitment_public_keys: HashMap<CommitmentMessageId, WinternitzPublicKey>,
+    pub commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
 }
 
 impl Connector2 {
@@ -36,7 +38,7 @@ impl Connector2 {
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
-        commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
     ) -> Self {
         Connector2 {
             network,
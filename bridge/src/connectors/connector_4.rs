@@ -13,6 +13,7 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Connector4 {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_public_key: PublicKey,
     pub num_blocks_timelock: u32,
 }
@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::{
     commitments::CommitmentMessageId,
-    constants::{DESTINATION_NETWORK_TXID_LENGTH, SOURCE_NETWORK_TXID_LENGTH},
+    constants::{
+        DESTINATION_CHAIN_ID_MESSAGE_LENGTH, DESTINATION_NETWORK_TXID_LENGTH,
+        SOURCE_NETWORK_TXID_LENGTH, WITHDRAWAL_NONCE_MESSAGE_LENGTH,
+    },
     transactions::base::Input,
 };
 
@@ -24,15 +27,16 @@ use super::base::{generate_default_tx_in, TaprootConnector};
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Connector6 {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_taproot_public_key: XOnlyPublicKey,
-    pub commitment_public_keys: HashMap<CommitmentMessageId, WinternitzPublicKey>,
+    pub commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
 }
 
 impl Connector6 {
     pub fn new(
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
-        commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
     ) -> Self {
         Connector6 {
             network,
@@ -41,11 +45,36 @@ impl Connector6 {
         }
     }
 
+    // Whether kick-off 1 also commits to the destination chain id and withdrawal nonce. Graphs
+    // created before these commitments existed only have the peg-out txid keys.
+    pub fn commits_destination_metadata(&self) -> bool {
+        self.commitment_public_keys
+            .contains_key(&CommitmentMessageId::DestinationChainId)
+            && self
+                .commitment_public_keys
+                .contains_key(&CommitmentMessageId::WithdrawalNonce)
+    }
+
     fn generate_taproot_leaf_0_script(&self) -> ScriptBuf {
         let destination_network_txid_public_key =
             &self.commitment_public_keys[&CommitmentMessageId::PegOutTxIdDestinationNetwork];
         let source_network_txid_public_key =
             &self.commitment_public_keys[&CommitmentMessageId::PegOutTxIdSourceNetwork];
+        if self.commits_destination_metadata() {
+            let destination_chain_id_public_key =
+                &self.commitment_public_keys[&CommitmentMessageId::DestinationChainId];
+            let withdrawal_nonce_public_key =
+                &self.commitment_public_keys[&CommitmentMessageId::WithdrawalNonce];
+            return script! {
+                { winternitz_message_checksig_verify(destination_chain_id_public_key, DESTINATION_CHAIN_ID_MESSAGE_LENGTH * 2) }
+                { winternitz_message_checksig_verify(withdrawal_nonce_public_key, WITHDRAWAL_NONCE_MESSAGE_LENGTH * 2) }
+                { winternitz_message_checksig_verify(destination_network_txid_public_key, DESTINATION_NETWORK_TXID_LENGTH * 2) }
+                { winternitz_message_checksig_verify(source_network_txid_public_key, SOURCE_NETWORK_TXID_LENGTH * 2) }
+                { self.operator_taproot_public_key }
+                OP_CHECKSIG
+            }
+            .compile();
+        }
         script! {
             { winternitz_message_checksig_verify(destination_network_txid_public_key, DESTINATION_NETWORK_TXID_LENGTH * 2) }
             { winternitz_message_checksig_verify(source_network_txid_public_key, SOURCE_NETWORK_TXID_LENGTH * 2) }
@@ -13,7 +13,9 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorA {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_taproot_public_key: XOnlyPublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
 }
 
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use bitcoin::{
     taproot::{TaprootBuilder, TaprootSpendInfo},
@@ -18,7 +18,10 @@ use serde::{Deserialize, Serialize};
 use crate::{
     commitments::CommitmentMessageId,
     constants::START_TIME_MESSAGE_LENGTH,
-    superblock::{extract_superblock_ts_from_header, SUPERBLOCK_MESSAGE_LENGTH},
+    superblock::{
+        extract_superblock_ts_from_header, SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS,
+        SUPERBLOCK_MESSAGE_LENGTH,
+    },
     utils::{sb_hash_from_bytes, sb_hash_from_nibbles, H256},
 };
 
@@ -33,8 +36,9 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorB {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
-    pub commitment_public_keys: HashMap<CommitmentMessageId, WinternitzPublicKey>,
+    pub commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
     pub num_blocks_timelock_1: u32,
 }
 
@@ -42,7 +46,7 @@ impl ConnectorB {
     pub fn new(
         network: Network,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
-        commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
     ) -> Self {
         ConnectorB {
             network,
@@ -72,7 +76,6 @@ impl ConnectorB {
     }
 
     fn generate_taproot_leaf_2_script(&self) -> ScriptBuf {
-        const TWO_WEEKS_IN_SECONDS: u32 = 60 * 60 * 24 * 14;
         let superblock_hash_public_key =
             &self.commitment_public_keys[&CommitmentMessageId::SuperblockHash];
         let start_time_public_key = &self.commitment_public_keys[&CommitmentMessageId::StartTime];
@@ -105,7 +108,7 @@ impl ConnectorB {
             OP_GREATERTHAN OP_VERIFY        // Stack: SB' SB'.time start_time | Altstack: SB.hash
 
             // SB'.time < start_time + 2 weeks
-            { TWO_WEEKS_IN_SECONDS } OP_ADD // Stack: SB' SB'.time (start_time + 2 weeks) | Altstack: SB.hash
+            { SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS } OP_ADD // Stack: SB' SB'.time (start_time + 2 weeks) | Altstack: SB.hash
             OP_LESSTHAN OP_VERIFY           // Stack: SB' | Altstack: SB.hash
 
             // Calculate SB' hash
@@ -1,28 +1,38 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
+    io::{self, ErrorKind},
     path::{Path, PathBuf},
+    sync::OnceLock,
 };
 
 use crate::{
     client::{
-        files::BRIDGE_DATA_DIRECTORY_NAME,
-        memory_cache::{TAPROOT_LOCK_SCRIPTS_CACHE, TAPROOT_SPEND_INFO_CACHE},
+        memory_cache::{get_or_generate, TAPROOT_LOCK_SCRIPTS_CACHE, TAPROOT_SPEND_INFO_CACHE},
+        profiling::profile_phase,
     },
     commitments::CommitmentMessageId,
     common::ZkProofVerifyingKey,
-    connectors::base::*,
+    connectors::{
+        base::*,
+        cache_files::{
+            cache_directory, read_merkle_root_file, write_merkle_root_file, CACHE_ID_VERSION,
+            LOCK_SCRIPTS_FILE_PREFIX, MERKLE_ROOT_FILE_PREFIX,
+        },
+        lock_scripts_file::{write_lock_scripts_file, LockScriptsFile},
+    },
     error::{ChunkerError, Error},
     transactions::base::Input,
     utils::{
         cleanup_cache_files, compress, decompress, read_disk_cache,
-        remove_script_and_control_block_from_witness, write_disk_cache,
+        remove_script_and_control_block_from_witness,
     },
 };
 use bitcoin::{
-    hashes::{hash160, Hash},
-    key::TweakedPublicKey,
+    hashes::{sha256, Hash, HashEngine},
+    key::{TapTweak, TweakedPublicKey},
+    script::Instruction,
     taproot::{ControlBlock, LeafVersion, TaprootBuilder, TaprootSpendInfo},
-    Address, Network, ScriptBuf, TapNodeHash, Transaction, TxIn, XOnlyPublicKey,
+    Address, Network, Script, ScriptBuf, TapNodeHash, Transaction, TxIn, XOnlyPublicKey,
 };
 use num_traits::ToPrimitive;
 use secp256k1::SECP256K1;
@@ -30,12 +40,13 @@ use serde::{Deserialize, Serialize};
 
 use bitvm::{
     chunk::api::{
-        api_generate_full_tapscripts, api_generate_partial_script,
+        api_generate_full_tapscripts, api_generate_partial_script, segment_layout_version,
+        try_validate_assertions,
         type_conversion_utils::{
             script_to_witness, utils_signatures_from_raw_witnesses, utils_typed_pubkey_from_raw,
             RawProof, RawWitness,
         },
-        validate_assertions, PublicKeys,
+        PublicKeys, NUM_HASH, NUM_PUBS, NUM_TAPS, NUM_U256,
     },
     // chunker::{
     //     assigner::BridgeAssigner,
@@ -43,10 +54,16 @@ use bitvm::{
     //     common::RawWitness,
     //     disprove_execution::{disprove_exec, RawProof},
     // },
-    signatures::signing_winternitz::WinternitzPublicKey,
+    signatures::{
+        signing_winternitz::WinternitzPublicKey,
+        wots_api::{wots256, wots_hash},
+    },
 };
 use zstd::DEFAULT_COMPRESSION_LEVEL;
 
+// Groth16 public inputs, proof elements and intermediate hashes committed by the assert txs
+const NUM_COMMITMENTS: usize = NUM_PUBS + NUM_U256 + NUM_HASH;
+
 // Specialized for assert leaves currently.
 pub type LockScript = fn(index: u32) -> ScriptBuf;
 pub type UnlockWitnessData = Vec<u8>;
@@ -57,25 +74,65 @@ pub struct DisproveLeaf {
     pub unlock: UnlockWitness,
 }
 
-const CACHE_DIRECTORY_NAME: &str = "cache";
-const LOCK_SCRIPTS_FILE_PREFIX: &str = "lock_scripts_";
 const MAX_CACHE_FILES: u32 = 90; //~1GB in total, based on lock scripts cache being 11MB each
 
 fn get_lock_scripts_cache_path(cache_id: &str) -> PathBuf {
     let lock_scripts_file_name = format!("{LOCK_SCRIPTS_FILE_PREFIX}{}.bin", cache_id);
-    Path::new(BRIDGE_DATA_DIRECTORY_NAME)
-        .join(CACHE_DIRECTORY_NAME)
-        .join(lock_scripts_file_name)
+    cache_directory().join(lock_scripts_file_name)
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
+fn get_merkle_root_cache_path(cache_id: &str) -> PathBuf {
+    let merkle_root_file_name = format!("{MERKLE_ROOT_FILE_PREFIX}{}.bin", cache_id);
+    cache_directory().join(merkle_root_file_name)
+}
+
+// Script and merkle proof of a single connector C leaf, supplied by the operator so that verifiers
+// can spot check the claimed merkle root without generating all the lock scripts.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct TaprootLeafProof {
+    pub leaf_index: usize,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    pub script: ScriptBuf,
+    pub control_block: ControlBlock,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ConnectorC {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_taproot_public_key: XOnlyPublicKey,
     commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    // The merkle root is all that is needed to derive the address, so the lock scripts are only
+    // generated once an assert or disprove actually spends from this connector. Graphs serialized
+    // before it was stored look it up from the cache instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    taproot_merkle_root: Option<TapNodeHash>,
+    // Segment layout of the chunker the lock scripts were generated with, see
+    // `check_segment_layout`. None for connectors serialized before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    segment_layout_version: Option<String>,
+    // Hashing every commitment public key is too slow to repeat for each leaf, see
+    // `spend_info_cache_id`
+    #[serde(skip)]
+    cache_id: OnceLock<String>,
+}
+
+// The cache id is derived from the other fields, so whether it was computed yet does not matter
+impl PartialEq for ConnectorC {
+    fn eq(&self, other: &Self) -> bool {
+        self.network == other.network
+            && self.operator_taproot_public_key == other.operator_taproot_public_key
+            && self.commitment_public_keys == other.commitment_public_keys
+            && self.taproot_merkle_root == other.taproot_merkle_root
+            && self.segment_layout_version == other.segment_layout_version
+    }
 }
 
+impl Eq for ConnectorC {}
+
 impl ConnectorC {
+    // Cold unless the lock scripts for these commitment public keys were generated on this machine
+    // before: deriving the merkle root then takes minutes. See `is_warm`.
     pub fn new(
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
@@ -85,10 +142,84 @@ impl ConnectorC {
             !commitment_public_keys.is_empty(),
             "commitment_public_keys is empty"
         );
-        ConnectorC {
+        let mut connector_c = ConnectorC {
+            network,
+            operator_taproot_public_key: *operator_taproot_public_key,
+            commitment_public_keys: commitment_public_keys.clone(),
+            taproot_merkle_root: None,
+            segment_layout_version: Some(segment_layout_version()),
+            cache_id: OnceLock::new(),
+        };
+        connector_c.taproot_merkle_root = profile_phase("connector C merkle root", || {
+            connector_c.precomputed_taproot_merkle_root()
+        });
+        connector_c
+    }
+
+    // Whether nothing is left to generate: the merkle root is known and the lock scripts are in the
+    // memory or disk cache. Spending from a cold connector generates the lock scripts first.
+    pub fn is_warm(&self) -> bool {
+        let cache_id = self.spend_info_cache_id();
+        self.taproot_merkle_root().is_some()
+            && (TAPROOT_SPEND_INFO_CACHE.read().unwrap().contains(&cache_id)
+                || get_lock_scripts_cache_path(&cache_id).exists())
+    }
+
+    // Loads the lock scripts into memory, generating them if they are not cached on disk, so later
+    // spends do not stall. Also derives the merkle root of connectors serialized without one. A
+    // claimed merkle root is kept as it is, verifying it is up to `verify_taproot_leaf_proof`.
+    pub fn warm_up(&mut self) {
+        let spend_info = profile_phase("connector C warm-up", || self.taproot_spend_info_cached());
+        if self.taproot_merkle_root.is_none() {
+            self.taproot_merkle_root = spend_info.merkle_root;
+        }
+    }
+
+    // Rebuilds the connector of a received graph. The merkle root claimed by the operator is used
+    // unless the local cache knows the actual one, so validating a graph never generates the lock
+    // scripts. The claimed root must be verified before pre-signing, see `verify_taproot_leaf_proof`.
+    pub fn new_for_validation(
+        network: Network,
+        operator_taproot_public_key: &XOnlyPublicKey,
+        commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+        claimed_taproot_merkle_root: Option<TapNodeHash>,
+    ) -> Self {
+        assert!(
+            !commitment_public_keys.is_empty(),
+            "commitment_public_keys is empty"
+        );
+        let mut connector_c = ConnectorC {
             network,
             operator_taproot_public_key: *operator_taproot_public_key,
             commitment_public_keys: commitment_public_keys.clone(),
+            taproot_merkle_root: None,
+            segment_layout_version: Some(segment_layout_version()),
+            cache_id: OnceLock::new(),
+        };
+        connector_c.taproot_merkle_root = connector_c
+            .cached_taproot_merkle_root()
+            .or(claimed_taproot_merkle_root)
+            .or_else(|| connector_c.precomputed_taproot_merkle_root());
+        connector_c
+    }
+
+    // Segment layout the lock scripts were generated with, as recorded by whoever built the connector
+    pub fn segment_layout_version(&self) -> Option<&str> {
+        self.segment_layout_version.as_deref()
+    }
+
+    // The assertions are split into segments, and the lock scripts check them, according to the
+    // segment layout of the chunker. Lock scripts generated by a chunker with another layout do not
+    // lock the segments the local chunker asserts or evaluates, so neither is attempted. Connectors
+    // serialized before the layout was recorded are assumed to match.
+    pub fn check_segment_layout(&self) -> Result<(), ChunkerError> {
+        let expected = segment_layout_version();
+        match &self.segment_layout_version {
+            Some(found) if *found != expected => Err(ChunkerError::SegmentLayoutMismatch {
+                expected,
+                found: found.clone(),
+            }),
+            _ => Ok(()),
         }
     }
 
@@ -98,6 +229,7 @@ impl ConnectorC {
         commit_2_witness: Vec<RawWitness>,
         vk: &ZkProofVerifyingKey,
     ) -> Result<(usize, RawWitness), Error> {
+        self.check_segment_layout().map_err(Error::Chunker)?;
         println!("Generating disprove witness ...");
         let mut sorted_pks: Vec<(u32, WinternitzPublicKey)> = vec![];
         self.commitment_public_keys
@@ -114,7 +246,15 @@ impl ConnectorC {
             .iter()
             .map(|f| &f.1)
             .collect::<Vec<&WinternitzPublicKey>>();
+        if sorted_pks.len() != NUM_COMMITMENTS {
+            return Err(Error::Chunker(ChunkerError::MissingCommitmentPublicKeys {
+                expected: NUM_COMMITMENTS,
+                found: sorted_pks.len(),
+            }));
+        }
 
+        validate_commitment_witnesses(&commit_1_witness, &commit_2_witness)
+            .map_err(Error::Chunker)?;
         let mut commit_witness = commit_1_witness.clone();
         commit_witness.extend_from_slice(&commit_2_witness);
 
@@ -128,48 +268,270 @@ impl ConnectorC {
                     .push_script(ScriptBuf::from_bytes(f))
             })
             .collect();
-        let locs = locs.try_into().unwrap();
-        let exec_res = validate_assertions(vk, sigs, pubs, &locs);
-        if exec_res.is_some() {
-            let exec_res = exec_res.unwrap();
-            let wit: RawWitness = script_to_witness(exec_res.1);
-            return Ok((exec_res.0, wit));
+        let locs_len = locs.len();
+        let locs: [bitcoin_script::builder::StructuredScript; NUM_TAPS] =
+            locs.try_into().map_err(|_| {
+                Error::Chunker(ChunkerError::LockScriptCountMismatch {
+                    expected: NUM_TAPS,
+                    found: locs_len,
+                })
+            })?;
+        let exec_res = try_validate_assertions(vk, sigs, pubs, &locs).map_err(|err| {
+            Error::Chunker(ChunkerError::EvaluationFailed {
+                segment_index: err.segment_index,
+                reason: err.reason.to_string(),
+            })
+        })?;
+        match exec_res {
+            Some((disprove_script_index, disprove_hint)) => {
+                let wit: RawWitness = script_to_witness(disprove_hint);
+                Ok((disprove_script_index, wit))
+            }
+            None => Err(Error::Chunker(ChunkerError::ValidProof)),
         }
-        return Err(Error::Chunker(ChunkerError::ValidProof));
     }
 
+    // Stored or cached merkle root, never generates the lock scripts. Only connectors serialized
+    // before the merkle root was stored can miss it, see `warm_up`.
     pub fn taproot_merkle_root(&self) -> Option<TapNodeHash> {
+        self.taproot_merkle_root
+            .or_else(|| self.cached_taproot_merkle_root())
+    }
+
+    // Merkle root claimed by whoever built the connector, as serialized with the graph
+    pub fn claimed_taproot_merkle_root(&self) -> Option<TapNodeHash> {
+        self.taproot_merkle_root
+    }
+
+    // Merkle root computed locally, if the lock scripts for these commitment public keys were
+    // generated before. Never generates them.
+    pub fn cached_taproot_merkle_root(&self) -> Option<TapNodeHash> {
+        let cache_id = self.spend_info_cache_id();
+        if let Some(cache) = TAPROOT_SPEND_INFO_CACHE.write().unwrap().get(&cache_id) {
+            return cache.merkle_root;
+        }
+
+        let file_path = get_merkle_root_cache_path(&cache_id);
+        read_merkle_root_file(&file_path)
+            .inspect_err(|e| {
+                if e.kind() != ErrorKind::NotFound {
+                    eprintln!(
+                        "Failed to read merkle root cache from expected location: {}",
+                        e
+                    );
+                }
+            })
+            .ok()
+    }
+
+    // Merkle root derived from the lock scripts, generating them if they are not cached
+    pub fn generated_taproot_merkle_root(&self) -> Option<TapNodeHash> {
         self.taproot_spend_info_cached().merkle_root
     }
 
+    pub fn taproot_leaf_proof(&self, leaf_index: usize) -> TaprootLeafProof {
+        let (script, control_block) = self.taproot_script_and_control_block(leaf_index);
+        TaprootLeafProof {
+            leaf_index,
+            script,
+            control_block,
+        }
+    }
+
+    // Spot check of a single leaf against the merkle root this connector was built with. Passing
+    // does not prove the root, but a root over a tree with leaves that do not lock the operator's
+    // commitments is caught with a probability that grows with the number of leaves checked.
+    pub fn verify_taproot_leaf_proof(&self, proof: &TaprootLeafProof) -> Result<(), String> {
+        let leaf_index = proof.leaf_index;
+        if leaf_index >= NUM_TAPS {
+            return Err(format!(
+                "Leaf {leaf_index} is out of range, connector C has {NUM_TAPS} leaves"
+            ));
+        }
+
+        let control_block = &proof.control_block;
+        if control_block.internal_key != self.operator_taproot_public_key {
+            return Err(format!(
+                "Leaf {leaf_index} proof uses an internal key other than the operator's"
+            ));
+        }
+        if control_block.leaf_version != LeafVersion::TapScript {
+            return Err(format!("Leaf {leaf_index} is not a tapscript leaf"));
+        }
+        let (min_depth, max_depth) = huffman_tree_depth_range(NUM_TAPS);
+        let depth = control_block.merkle_branch.len();
+        if depth < min_depth || depth > max_depth {
+            return Err(format!(
+                "Leaf {leaf_index} is at depth {depth}, expected between {min_depth} and {max_depth}"
+            ));
+        }
+        if !control_block.verify_taproot_commitment(
+            SECP256K1,
+            self.taproot_output_key().to_inner(),
+            &proof.script,
+        ) {
+            return Err(format!(
+                "Leaf {leaf_index} is not committed to by the merkle root"
+            ));
+        }
+        if !self.locks_commitment_public_key(&proof.script) {
+            return Err(format!(
+                "Leaf {leaf_index} does not lock any of the operator's commitment public keys"
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Copies the lock scripts file to `file_path`, generating the lock scripts first unless they
+    // are cached. Other members import it instead of generating them, see `import_lock_scripts`.
+    // Returns the merkle root of the exported lock scripts.
+    pub fn export_lock_scripts(&self, file_path: &Path) -> io::Result<TapNodeHash> {
+        let cache_path = get_lock_scripts_cache_path(&self.spend_info_cache_id());
+        if LockScriptsFile::open(&cache_path).is_err() {
+            // Written in the indexed layout as a side effect
+            self.lock_scripts_bytes();
+        }
+        let merkle_root = LockScriptsFile::open(&cache_path)?.merkle_root();
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&cache_path, file_path)?;
+
+        Ok(merkle_root)
+    }
+
+    // Caches lock scripts exported by another member. Only the scripts are taken from the file:
+    // every leaf has to lock one of the operator's commitment public keys and the scripts have to
+    // hash to the merkle root this connector was built with, the merkle branches are derived
+    // again. Connectors serialized without a merkle root have nothing to check the scripts against.
+    pub fn import_lock_scripts(&self, file_path: &Path) -> Result<(), ChunkerError> {
+        let expected = self.taproot_merkle_root().ok_or_else(|| {
+            ChunkerError::InvalidLockScripts(String::from("connector C merkle root is unknown"))
+        })?;
+        let lock_scripts_bytes = LockScriptsFile::open(file_path)
+            .and_then(|file| file.lock_scripts())
+            .map_err(|e| ChunkerError::InvalidLockScripts(e.to_string()))?;
+        if lock_scripts_bytes.len() != NUM_TAPS {
+            return Err(ChunkerError::LockScriptCountMismatch {
+                expected: NUM_TAPS,
+                found: lock_scripts_bytes.len(),
+            });
+        }
+        if let Some(leaf_index) = lock_scripts_bytes
+            .iter()
+            .position(|script| !self.locks_commitment_public_key(Script::from_bytes(script)))
+        {
+            return Err(ChunkerError::InvalidLockScripts(format!(
+                "leaf {leaf_index} does not lock any of the operator's commitment public keys"
+            )));
+        }
+        let spend_info =
+            generate_taproot_spend_info(self.operator_taproot_public_key, &lock_scripts_bytes);
+        if spend_info.merkle_root() != Some(expected) {
+            return Err(ChunkerError::LockScriptsMerkleRootMismatch {
+                expected,
+                found: spend_info.merkle_root(),
+            });
+        }
+
+        let cache_id = self.spend_info_cache_id();
+        let cache_path = get_lock_scripts_cache_path(&cache_id);
+        write_lock_scripts_file(&cache_path, &lock_scripts_bytes, &spend_info)
+            .map_err(|e| ChunkerError::InvalidLockScripts(e.to_string()))?;
+        cleanup_cache_files(
+            LOCK_SCRIPTS_FILE_PREFIX,
+            cache_path.parent().unwrap(),
+            MAX_CACHE_FILES,
+        );
+        write_merkle_root_cache(&cache_id, expected);
+
+        Ok(())
+    }
+
+    // Cache ids cover everything the cached data is derived from: the network, the operator's
+    // taproot public key, every commitment public key and the local segment layout, so lock
+    // scripts cached by a chunker with another layout are never picked up. Cache files named by
+    // ids of the previous scheme are removed, see `FULL_CACHE_ID_FILE_VERSION`.
+    fn spend_info_cache_id(&self) -> String {
+        self.cache_id
+            .get_or_init(|| {
+                let mut engine = sha256::Hash::engine();
+                engine.input(&[CACHE_ID_VERSION]);
+                engine.input(&self.network.magic().to_bytes());
+                engine.input(&self.operator_taproot_public_key.serialize());
+                for (message_id, public_key) in self.commitment_public_keys.iter() {
+                    let message_id = String::from(message_id.clone());
+                    let public_key = public_key.public_key.as_flattened();
+                    // Length prefixed, so no two key sets hash the same bytes
+                    engine.input(&(message_id.len() as u32).to_le_bytes());
+                    engine.input(message_id.as_bytes());
+                    engine.input(&(public_key.len() as u32).to_le_bytes());
+                    engine.input(public_key);
+                }
+                engine.input(segment_layout_version().as_bytes());
+                hex::encode(sha256::Hash::from_engine(engine))
+            })
+            .clone()
+    }
+
+    fn lock_script_cache_id(&self, leaf_index: usize) -> String {
+        format!("{}_{leaf_index}", self.spend_info_cache_id())
+    }
+
+    // Every assert leaf starts with the Winternitz signature checks of its inputs and outputs,
+    // which push the digit public keys as they are.
+    fn locks_commitment_public_key(&self, script: &Script) -> bool {
+        let pushes: HashSet<&[u8]> = script
+            .instructions()
+            .filter_map(|instruction| match instruction {
+                Ok(Instruction::PushBytes(bytes)) => Some(bytes.as_bytes()),
+                _ => None,
+            })
+            .collect();
+        self.commitment_public_keys.values().any(|public_key| {
+            public_key
+                .public_key
+                .iter()
+                .all(|digit| pushes.contains(digit.as_slice()))
+        })
+    }
+
     pub fn taproot_output_key(&self) -> TweakedPublicKey {
-        self.taproot_spend_info_cached().output_key
+        let merkle_root = self.taproot_merkle_root().expect(
+            "Connector C merkle root is unknown, call `warm_up` before deriving its address",
+        );
+        let (output_key, _) = self
+            .operator_taproot_public_key
+            .tap_tweak(SECP256K1, Some(merkle_root));
+        output_key
     }
 
+    // Every assert leaf is a lock script, so the count does not depend on the scripts themselves
     pub fn taproot_scripts_len(&self) -> usize {
-        self.taproot_spend_info_cached().scripts_length
+        NUM_TAPS
     }
 
     pub fn taproot_script_and_control_block(&self, leaf_index: usize) -> (ScriptBuf, ControlBlock) {
-        let cache_id = lock_script_cache_id(&self.commitment_public_keys, leaf_index);
-        let cache = TAPROOT_LOCK_SCRIPTS_CACHE
-            .write()
-            .unwrap()
-            .get_or_put(cache_id, || {
-                let (script, control_block) = generate_script_and_control_block(
-                    self.operator_taproot_public_key,
-                    &self.lock_scripts_bytes(),
-                    leaf_index,
-                );
-                let encoded_data = bitcode::encode(script.as_bytes());
-                let compressed_data = compress(&encoded_data, DEFAULT_COMPRESSION_LEVEL)
-                    .expect("Unable to compress script for caching");
-                LockScriptCacheEntry {
-                    control_block,
-                    encoded_script: compressed_data,
-                }
-            })
-            .clone();
+        let cache_id = self.lock_script_cache_id(leaf_index);
+        let cache = get_or_generate(&TAPROOT_LOCK_SCRIPTS_CACHE, cache_id, || {
+            let (script, control_block) = self
+                .indexed_script_and_control_block(leaf_index)
+                .unwrap_or_else(|| {
+                    generate_script_and_control_block(
+                        self.operator_taproot_public_key,
+                        &self.lock_scripts_bytes(),
+                        leaf_index,
+                    )
+                });
+            let encoded_data = bitcode::encode(script.as_bytes());
+            let compressed_data = compress(&encoded_data, DEFAULT_COMPRESSION_LEVEL)
+                .expect("Unable to compress script for caching");
+            LockScriptCacheEntry {
+                control_block,
+                encoded_script: compressed_data,
+            }
+        });
         decompress(&cache.encoded_script)
             .ok()
             .map(|data| (data, cache.control_block))
@@ -181,40 +543,102 @@ impl ConnectorC {
             .expect("Cached script data corrupted")
     }
 
+    // Sizes in bytes of every lock script and of the control block spending it, in leaf order.
+    // Generates the lock scripts unless they are cached on disk.
+    pub fn taproot_leaf_sizes(&self) -> Vec<(usize, usize)> {
+        let lock_scripts_bytes = self.lock_scripts_bytes();
+        let spend_info =
+            generate_taproot_spend_info(self.operator_taproot_public_key, &lock_scripts_bytes);
+        lock_scripts_bytes
+            .into_iter()
+            .map(|script| {
+                let prevout_leaf = (ScriptBuf::from(script), LeafVersion::TapScript);
+                let control_block = spend_info
+                    .control_block(&prevout_leaf)
+                    .expect("Unable to create Control block");
+                (prevout_leaf.0.len(), control_block.size())
+            })
+            .collect()
+    }
+
+    // Looks the merkle root up in the memory and disk caches, and only falls back to generating
+    // the lock scripts if neither has it.
+    fn precomputed_taproot_merkle_root(&self) -> Option<TapNodeHash> {
+        self.cached_taproot_merkle_root()
+            .or_else(|| self.generated_taproot_merkle_root())
+    }
+
     fn taproot_spend_info_cached(&self) -> TaprootSpendInfoCacheEntry {
-        let cache_id = spend_info_cache_id(&self.commitment_public_keys);
-        TAPROOT_SPEND_INFO_CACHE
-            .write()
-            .unwrap()
-            .get_or_put(cache_id, || {
-                let lock_scripts_bytes = &self.lock_scripts_bytes();
-                let spend_info = generate_taproot_spend_info(
-                    self.operator_taproot_public_key,
-                    lock_scripts_bytes,
-                );
-                TaprootSpendInfoCacheEntry::new(&spend_info, lock_scripts_bytes.len())
+        let cache_id = self.spend_info_cache_id();
+        get_or_generate(&TAPROOT_SPEND_INFO_CACHE, cache_id.clone(), || {
+            let lock_scripts_bytes = &self.lock_scripts_bytes();
+            let spend_info =
+                generate_taproot_spend_info(self.operator_taproot_public_key, lock_scripts_bytes);
+            if let Some(merkle_root) = spend_info.merkle_root() {
+                write_merkle_root_cache(&cache_id, merkle_root);
+            }
+            TaprootSpendInfoCacheEntry::new(&spend_info, lock_scripts_bytes.len())
+        })
+    }
+
+    // Looks a single leaf up in the memory-mapped lock scripts file, without reading the other
+    // leaves. None if the file does not exist yet or is in the previous layout.
+    fn indexed_script_and_control_block(
+        &self,
+        leaf_index: usize,
+    ) -> Option<(ScriptBuf, ControlBlock)> {
+        let file_path = get_lock_scripts_cache_path(&self.spend_info_cache_id());
+        profile_phase("read connector C lock script", || {
+            LockScriptsFile::open(&file_path).and_then(|file| {
+                file.script_and_control_block(leaf_index, self.operator_taproot_public_key)
             })
-            .clone()
+        })
+        .inspect_err(|e| {
+            if !matches!(e.kind(), ErrorKind::NotFound | ErrorKind::InvalidData) {
+                eprintln!("Failed to read lock script from expected location: {}", e);
+            }
+        })
+        .ok()
     }
 
     fn lock_scripts_bytes(&self) -> Vec<Vec<u8>> {
-        let cache_id = spend_info_cache_id(&self.commitment_public_keys);
+        let cache_id = self.spend_info_cache_id();
         let file_path = get_lock_scripts_cache_path(&cache_id);
-        let lock_scripts_bytes = read_disk_cache(&file_path)
-            .inspect_err(|e| {
-                if e.kind() != std::io::ErrorKind::NotFound {
+        let indexed = profile_phase("read connector C lock scripts cache", || {
+            LockScriptsFile::open(&file_path).and_then(|file| file.lock_scripts())
+        });
+        let lock_scripts_bytes = match indexed {
+            Ok(lock_scripts_bytes) => return lock_scripts_bytes,
+            // Files in the previous layout are read once more and rewritten in the indexed one
+            Err(e) if e.kind() == ErrorKind::InvalidData => read_disk_cache(&file_path)
+                .inspect_err(|e| {
+                    eprintln!(
+                        "Failed to read lock scripts cache from expected location: {}",
+                        e
+                    )
+                })
+                .ok(),
+            Err(e) => {
+                if e.kind() != ErrorKind::NotFound {
                     eprintln!(
                         "Failed to read lock scripts cache from expected location: {}",
                         e
                     );
                 }
-            })
-            .unwrap_or_else(|_| generate_assert_leaves(&self.commitment_public_keys));
-        if !file_path.exists() {
-            write_disk_cache(&file_path, &lock_scripts_bytes)
-                .inspect_err(|e| eprintln!("Failed to write lock scripts cache to disk: {}", e))
-                .ok();
+                None
+            }
         }
+        .unwrap_or_else(|| {
+            profile_phase("generate connector C lock scripts", || {
+                generate_assert_leaves(&self.commitment_public_keys)
+            })
+        });
+
+        let spend_info =
+            generate_taproot_spend_info(self.operator_taproot_public_key, &lock_scripts_bytes);
+        write_lock_scripts_file(&file_path, &lock_scripts_bytes, &spend_info)
+            .inspect_err(|e| eprintln!("Failed to write lock scripts cache to disk: {}", e))
+            .ok();
         cleanup_cache_files(
             LOCK_SCRIPTS_FILE_PREFIX,
             file_path.parent().unwrap(),
@@ -249,35 +673,21 @@ impl TaprootConnector for ConnectorC {
     }
 }
 
-fn first_winternitz_public_key_bytes(
-    commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
-) -> Vec<u8> {
-    let (_, first_winternitz_public_key) = commitment_public_keys
-        .iter()
-        .next()
-        .expect("commitment_public_keys should not be empty");
-    first_winternitz_public_key
-        .public_key
-        .as_flattened()
-        .to_vec()
-}
-
-fn spend_info_cache_id(
-    commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
-) -> String {
-    let bytes = first_winternitz_public_key_bytes(commitment_public_keys);
-    let hash = hash160::Hash::hash(&bytes);
-    hex::encode(hash)
-}
-
-fn lock_script_cache_id(
-    commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
-    leaf_index: usize,
-) -> String {
-    let mut bytes = first_winternitz_public_key_bytes(commitment_public_keys);
-    bytes.append(leaf_index.to_be_bytes().to_vec().as_mut());
-    let hash = hash160::Hash::hash(&bytes);
-    hex::encode(hash)
+fn write_merkle_root_cache(cache_id: &str, merkle_root: TapNodeHash) {
+    let file_path = get_merkle_root_cache_path(cache_id);
+    if file_path.exists() {
+        return;
+    }
+    let written = write_merkle_root_file(&file_path, merkle_root)
+        .inspect_err(|e| eprintln!("Failed to write merkle root cache to disk: {}", e))
+        .is_ok();
+    if written {
+        cleanup_cache_files(
+            MERKLE_ROOT_FILE_PREFIX,
+            file_path.parent().unwrap(),
+            MAX_CACHE_FILES,
+        );
+    }
 }
 
 fn generate_script_and_control_block(
@@ -294,7 +704,26 @@ fn generate_script_and_control_block(
     (prevout_leaf.0, control_block)
 }
 
-fn generate_taproot_spend_info(
+// Merkle root derivation: the leaves are the assert lock scripts in chunker order, i.e. with the
+// Groth16 intermediate value public keys sorted by index, see `generate_assert_leaves`. Every leaf
+// is a tapscript leaf of weight 1 and the tree is built with `TaprootBuilder::with_huffman_tree`,
+// so each leaf ends up at a depth of either floor or ceil of log2 of the number of leaves. The root
+// does not depend on the internal key, which is the operator's taproot public key.
+pub fn derive_taproot_merkle_root(
+    operator_taproot_public_key: XOnlyPublicKey,
+    lock_scripts_bytes: &Vec<Vec<u8>>,
+) -> Option<TapNodeHash> {
+    generate_taproot_spend_info(operator_taproot_public_key, lock_scripts_bytes).merkle_root()
+}
+
+fn huffman_tree_depth_range(leaf_count: usize) -> (usize, usize) {
+    (
+        leaf_count.ilog2() as usize,
+        leaf_count.next_power_of_two().ilog2() as usize,
+    )
+}
+
+pub(crate) fn generate_taproot_spend_info(
     operator_taproot_public_key: XOnlyPublicKey,
     lock_scripts_bytes: &Vec<Vec<u8>>,
 ) -> TaprootSpendInfo {
@@ -338,6 +767,60 @@ fn generate_assert_leaves(
     locks
 }
 
+// Checks the witnesses of both assert commits, before they are parsed into Winternitz signatures
+pub fn validate_commitment_witnesses(
+    commit_1_witness: &[RawWitness],
+    commit_2_witness: &[RawWitness],
+) -> Result<(), ChunkerError> {
+    let found = commit_1_witness.len() + commit_2_witness.len();
+    if found != NUM_COMMITMENTS {
+        return Err(ChunkerError::MissingCommitmentWitness {
+            expected: NUM_COMMITMENTS,
+            found,
+        });
+    }
+    for (commitment_index, witness) in commit_1_witness.iter().chain(commit_2_witness).enumerate() {
+        validate_commitment_witness(commitment_index, witness)?;
+    }
+    Ok(())
+}
+
+// Checks the shape of a commitment witness, which is converted to a Winternitz signature without
+// error handling. Public inputs and proof elements are 256-bit values, the rest are hashes.
+fn validate_commitment_witness(
+    commitment_index: usize,
+    witness: &RawWitness,
+) -> Result<(), ChunkerError> {
+    let digits = if commitment_index < NUM_PUBS + NUM_U256 {
+        wots256::N_DIGITS
+    } else {
+        wots_hash::N_DIGITS
+    } as usize;
+    let malformed = |reason: String| ChunkerError::MalformedCommitmentWitness {
+        commitment_index,
+        reason,
+    };
+
+    // (preimage, digit) pairs, empty elements are minimally encoded zeros
+    if witness.len() != 2 * digits {
+        return Err(malformed(format!(
+            "expected {} witness elements, found {}",
+            2 * digits,
+            witness.len()
+        )));
+    }
+    for (element_index, element) in witness.iter().enumerate() {
+        let max_len = if element_index % 2 == 0 { 20 } else { 1 };
+        if !element.is_empty() && element.len() != max_len {
+            return Err(malformed(format!(
+                "witness element {element_index} has {} bytes",
+                element.len()
+            )));
+        }
+    }
+    Ok(())
+}
+
 pub fn get_commit_from_assert_commit_tx(assert_commit_tx: &Transaction) -> Vec<RawWitness> {
     let mut assert_commit_witness = Vec::new();
     for input in assert_commit_tx.input.iter() {
@@ -15,6 +15,7 @@ use std::collections::BTreeMap;
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorE {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_public_key: PublicKey,
     pub commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
 }
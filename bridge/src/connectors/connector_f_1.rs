@@ -9,6 +9,7 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorF1 {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub operator_public_key: PublicKey,
 }
 
@@ -1,4 +1,7 @@
-use crate::{constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network};
+use crate::{
+    constants::NUM_BLOCKS_PER_2_WEEKS, destination::destination_commitment, error::ValidationError,
+    utils::num_blocks_per_network,
+};
 use bitcoin::{
     taproot::{TaprootBuilder, TaprootSpendInfo},
     Address, Network, ScriptBuf, TxIn, XOnlyPublicKey,
@@ -15,8 +18,12 @@ use super::{
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorZ {
     pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub depositor_taproot_public_key: XOnlyPublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
+    // Destination address of the peg-in, either `<chain id>:<address>` or an untagged EVM address,
+    // see `destination_commitment`
     pub evm_address: String,
     pub num_blocks_timelock_0: u32,
 }
@@ -27,14 +34,16 @@ impl ConnectorZ {
         evm_address: &str,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
-    ) -> Self {
-        ConnectorZ {
+    ) -> Result<Self, ValidationError> {
+        destination_commitment(evm_address)?;
+
+        Ok(ConnectorZ {
             network,
             depositor_taproot_public_key: *depositor_taproot_public_key,
             n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
             evm_address: evm_address.to_string(),
             num_blocks_timelock_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
-        }
+        })
     }
 
     fn generate_taproot_leaf_0_script(&self) -> ScriptBuf {
@@ -49,12 +58,14 @@ impl ConnectorZ {
     }
 
     // leaf[1] is spendable by a multisig of depositor and OPK and VPK[1…N]
-    // the transaction script contains an [evm_address] (inscription data)
+    // the transaction script contains the destination address (inscription data)
     fn generate_taproot_leaf_1_script(&self) -> ScriptBuf {
+        let destination = destination_commitment(&self.evm_address)
+            .expect("Destination is validated on construction");
         script! {
         OP_FALSE
         OP_IF
-        { self.evm_address.clone().into_bytes() }
+        { destination }
         OP_ENDIF
         { self.n_of_n_taproot_public_key }
         OP_CHECKSIGVERIFY
@@ -0,0 +1,169 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use bitcoin::{
+    key::TweakedPublicKey,
+    taproot::{LeafVersion, TaprootSpendInfo},
+    Address, ScriptBuf, TapNodeHash, XOnlyPublicKey,
+};
+use miniscript::{
+    descriptor::{checksum::Engine, TapTree},
+    Descriptor, Miniscript, Segwitv0, Tap,
+};
+
+use crate::script_policies::generate_wsh_descriptor;
+
+use super::base::{P2wshConnector, TaprootConnector};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptorCoverage {
+    // The descriptor contains every spending path, wallets can also sign for the output
+    Complete,
+    // Some script is not expressible in miniscript, only the output is described
+    OutputOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectorDescriptor {
+    pub name: String,
+    pub address: Address,
+    pub descriptor: String, // with checksum
+    pub coverage: DescriptorCoverage,
+}
+
+impl fmt::Display for ConnectorDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coverage = match self.coverage {
+            DescriptorCoverage::Complete => "complete",
+            DescriptorCoverage::OutputOnly => "output only",
+        };
+        write!(
+            f,
+            "{} ({}, {coverage}): {}",
+            self.name, self.address, self.descriptor
+        )
+    }
+}
+
+// `wsh()` descriptor if the script is valid miniscript, `addr()` otherwise
+pub fn generate_p2wsh_descriptor(
+    name: &str,
+    connector: &impl P2wshConnector,
+) -> ConnectorDescriptor {
+    let address = connector.generate_address();
+    let descriptor = Miniscript::<_, Segwitv0>::parse(&connector.generate_script())
+        .ok()
+        .map(generate_wsh_descriptor)
+        .filter(|descriptor| descriptor.script_pubkey() == address.script_pubkey());
+
+    match descriptor {
+        Some(descriptor) => ConnectorDescriptor {
+            name: name.to_string(),
+            address,
+            descriptor: descriptor.to_string(),
+            coverage: DescriptorCoverage::Complete,
+        },
+        None => ConnectorDescriptor {
+            name: name.to_string(),
+            descriptor: with_checksum(&format!("addr({address})")),
+            address,
+            coverage: DescriptorCoverage::OutputOnly,
+        },
+    }
+}
+
+// `tr()` descriptor with the full script tree if all leaves are valid miniscript, `rawtr()` of
+// the output key otherwise
+pub fn generate_taproot_descriptor(
+    name: &str,
+    connector: &impl TaprootConnector,
+) -> ConnectorDescriptor {
+    let address = connector.generate_taproot_address();
+    let spend_info = connector.generate_taproot_spend_info();
+    let descriptor = generate_tr_descriptor(&spend_info)
+        .filter(|descriptor| descriptor.script_pubkey() == address.script_pubkey());
+
+    match descriptor {
+        Some(descriptor) => ConnectorDescriptor {
+            name: name.to_string(),
+            address,
+            descriptor: descriptor.to_string(),
+            coverage: DescriptorCoverage::Complete,
+        },
+        None => generate_raw_taproot_descriptor(name, spend_info.output_key(), address),
+    }
+}
+
+// For connectors whose script tree is too large to be generated for a descriptor
+pub fn generate_raw_taproot_descriptor(
+    name: &str,
+    output_key: TweakedPublicKey,
+    address: Address,
+) -> ConnectorDescriptor {
+    ConnectorDescriptor {
+        name: name.to_string(),
+        address,
+        descriptor: with_checksum(&format!("rawtr({output_key})")),
+        coverage: DescriptorCoverage::OutputOnly,
+    }
+}
+
+pub fn generate_tr_descriptor(spend_info: &TaprootSpendInfo) -> Option<Descriptor<XOnlyPublicKey>> {
+    let tree = match spend_info.merkle_root() {
+        Some(merkle_root) => Some(generate_tap_tree(
+            &tap_tree_nodes(spend_info)?,
+            merkle_root,
+        )?),
+        None => None,
+    };
+    Descriptor::new_tr(spend_info.internal_key(), tree).ok()
+}
+
+enum TapTreeNode {
+    Leaf(ScriptBuf),
+    Branch(TapNodeHash, TapNodeHash),
+}
+
+// Recovers the tree shape from the merkle branches of the leaves. Sibling order does not matter,
+// nodes are hashed in lexicographic order.
+fn tap_tree_nodes(spend_info: &TaprootSpendInfo) -> Option<HashMap<TapNodeHash, TapTreeNode>> {
+    let mut nodes = HashMap::new();
+    for ((script, leaf_version), merkle_branches) in spend_info.script_map() {
+        if *leaf_version != LeafVersion::TapScript {
+            return None;
+        }
+        let leaf_hash = TapNodeHash::from_script(script, *leaf_version);
+        nodes.insert(leaf_hash, TapTreeNode::Leaf(script.clone()));
+        for merkle_branch in merkle_branches {
+            let mut node_hash = leaf_hash;
+            for sibling_hash in merkle_branch.iter() {
+                let parent_hash = TapNodeHash::from_node_hashes(node_hash, *sibling_hash);
+                nodes.insert(parent_hash, TapTreeNode::Branch(node_hash, *sibling_hash));
+                node_hash = parent_hash;
+            }
+        }
+    }
+    Some(nodes)
+}
+
+fn generate_tap_tree(
+    nodes: &HashMap<TapNodeHash, TapTreeNode>,
+    node_hash: TapNodeHash,
+) -> Option<TapTree<XOnlyPublicKey>> {
+    match nodes.get(&node_hash)? {
+        TapTreeNode::Leaf(script) => Miniscript::<_, Tap>::parse(script)
+            .ok()
+            .map(|miniscript| TapTree::Leaf(Arc::new(miniscript))),
+        TapTreeNode::Branch(left, right) => Some(TapTree::combine(
+            generate_tap_tree(nodes, *left)?,
+            generate_tap_tree(nodes, *right)?,
+        )),
+    }
+}
+
+fn with_checksum(descriptor: &str) -> String {
+    let mut engine = Engine::new();
+    engine
+        .input(descriptor)
+        .expect("Descriptor contains invalid characters");
+    format!("{descriptor}#{}", engine.checksum())
+}
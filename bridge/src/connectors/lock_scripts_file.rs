@@ -0,0 +1,232 @@
+use std::{
+    fs::File,
+    io::{self, ErrorKind},
+    path::Path,
+    thread,
+};
+
+use bitcoin::{
+    hashes::Hash,
+    key::TapTweak,
+    taproot::{ControlBlock, LeafVersion, TaprootSpendInfo},
+    ScriptBuf, TapNodeHash, XOnlyPublicKey,
+};
+use memmap2::Mmap;
+use secp256k1::SECP256K1;
+
+use crate::utils::{compress, decompress, DEFAULT_COMPRESSION_LEVEL};
+
+use super::cache_files::{
+    cache_file_format, cache_header, unsupported_version, write_cache_file, CacheFileFormat,
+    CacheFileTag, CACHE_HEADER_LENGTH,
+};
+
+// Indexed layout of the connector C lock scripts cache. Every leaf is compressed on its own next to
+// its merkle branch, so looking up a single leaf decompresses only that leaf and does not rebuild
+// the taproot tree. Integers are little endian:
+//
+//   header | leaf count (u32) | merkle root (32 bytes) | per leaf: offset (u64), length (u32)
+//   per leaf, at its offset: branch length (u8) | branch (32 bytes per node) | compressed script
+//
+// Files written before this layout are a single compressed bitcode blob, see `read_disk_cache`.
+// Version 2 has the layout of version 1, only the file names changed, see
+// `cache_files::FULL_CACHE_ID_FILE_VERSION`. Bump the version on every change of the layout.
+pub const LOCK_SCRIPTS_FILE_TAG: &CacheFileTag = b"BVMLKS";
+pub const LOCK_SCRIPTS_FILE_VERSION: u8 = 2;
+const HEADER_LENGTH: usize = CACHE_HEADER_LENGTH + 4 + 32;
+const INDEX_ENTRY_LENGTH: usize = 8 + 4;
+const NODE_LENGTH: usize = 32;
+
+pub fn write_lock_scripts_file(
+    file_path: &Path,
+    lock_scripts_bytes: &[Vec<u8>],
+    spend_info: &TaprootSpendInfo,
+) -> io::Result<()> {
+    println!("Writing cache to {}...", file_path.display());
+    let merkle_root = spend_info
+        .merkle_root()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "No lock scripts to write"))?;
+
+    let mut records = Vec::with_capacity(lock_scripts_bytes.len());
+    for script in lock_scripts_bytes {
+        let control_block = spend_info
+            .control_block(&(
+                ScriptBuf::from_bytes(script.clone()),
+                LeafVersion::TapScript,
+            ))
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Leaf missing from tree"))?;
+        // Serialized control blocks are the leaf version and parity byte, the internal key and
+        // then the branch, only the branch does not depend on the internal key
+        let branch = control_block.serialize()[1 + 32..].to_vec();
+        let mut record = vec![(branch.len() / NODE_LENGTH) as u8];
+        record.extend(branch);
+        record.extend(compress(script, DEFAULT_COMPRESSION_LEVEL)?);
+        records.push(record);
+    }
+
+    let mut data = Vec::with_capacity(
+        HEADER_LENGTH
+            + INDEX_ENTRY_LENGTH * records.len()
+            + records.iter().map(Vec::len).sum::<usize>(),
+    );
+    data.extend(cache_header(
+        LOCK_SCRIPTS_FILE_TAG,
+        LOCK_SCRIPTS_FILE_VERSION,
+    ));
+    data.extend((records.len() as u32).to_le_bytes());
+    data.extend(merkle_root.to_byte_array());
+    let mut offset = (HEADER_LENGTH + INDEX_ENTRY_LENGTH * records.len()) as u64;
+    for record in &records {
+        data.extend(offset.to_le_bytes());
+        data.extend((record.len() as u32).to_le_bytes());
+        offset += record.len() as u64;
+    }
+    for record in records {
+        data.extend(record);
+    }
+
+    write_cache_file(file_path, &data)
+}
+
+// Memory-mapped lock scripts cache in the indexed layout
+pub struct LockScriptsFile {
+    mmap: Mmap,
+    leaf_count: usize,
+    merkle_root: TapNodeHash,
+}
+
+impl LockScriptsFile {
+    // Fails with `ErrorKind::InvalidData` for files in the previous layout, and with
+    // `ErrorKind::Unsupported` for files written by a newer version
+    pub fn open(file_path: &Path) -> io::Result<Self> {
+        let file = File::open(file_path)?;
+        // Safety: cache files are only ever replaced by renaming, never modified in place
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        match cache_file_format(LOCK_SCRIPTS_FILE_TAG, &mmap) {
+            // Lock scripts exported by previous clients are still imported
+            CacheFileFormat::Version(1..=LOCK_SCRIPTS_FILE_VERSION) => {}
+            CacheFileFormat::Version(version) => {
+                return Err(unsupported_version(
+                    LOCK_SCRIPTS_FILE_TAG,
+                    version,
+                    LOCK_SCRIPTS_FILE_VERSION,
+                ))
+            }
+            CacheFileFormat::Legacy => {
+                return Err(invalid_data("Not an indexed lock scripts file"))
+            }
+        }
+        if mmap.len() < HEADER_LENGTH {
+            return Err(invalid_data("Truncated lock scripts header"));
+        }
+        let leaf_count = u32::from_le_bytes(
+            mmap[CACHE_HEADER_LENGTH..CACHE_HEADER_LENGTH + 4]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let merkle_root = TapNodeHash::from_byte_array(
+            mmap[CACHE_HEADER_LENGTH + 4..HEADER_LENGTH]
+                .try_into()
+                .unwrap(),
+        );
+        if mmap.len() < HEADER_LENGTH + INDEX_ENTRY_LENGTH * leaf_count {
+            return Err(invalid_data("Truncated lock scripts index"));
+        }
+
+        Ok(Self {
+            mmap,
+            leaf_count,
+            merkle_root,
+        })
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    pub fn merkle_root(&self) -> TapNodeHash {
+        self.merkle_root
+    }
+
+    // Script of the leaf and its control block for the given internal key
+    pub fn script_and_control_block(
+        &self,
+        leaf_index: usize,
+        internal_key: XOnlyPublicKey,
+    ) -> io::Result<(ScriptBuf, ControlBlock)> {
+        let (branch, compressed_script) = self.record(leaf_index)?;
+        let (_, output_key_parity) = internal_key.tap_tweak(SECP256K1, Some(self.merkle_root));
+
+        let mut control_block =
+            vec![LeafVersion::TapScript.to_consensus() | output_key_parity.to_u8()];
+        control_block.extend(internal_key.serialize());
+        control_block.extend(branch);
+        let control_block = ControlBlock::decode(&control_block)
+            .map_err(|e| invalid_data(&format!("Invalid merkle branch: {e}")))?;
+
+        Ok((
+            ScriptBuf::from_bytes(decompress(&compressed_script.to_vec())?),
+            control_block,
+        ))
+    }
+
+    // Every lock script, decompressed on all available cores
+    pub fn lock_scripts(&self) -> io::Result<Vec<Vec<u8>>> {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = self.leaf_count.div_ceil(threads).max(1);
+        let leaf_indexes: Vec<usize> = (0..self.leaf_count).collect();
+
+        thread::scope(|scope| {
+            let chunks: Vec<_> = leaf_indexes
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|leaf_index| {
+                                let (_, compressed_script) = self.record(*leaf_index)?;
+                                decompress(&compressed_script.to_vec())
+                            })
+                            .collect::<io::Result<Vec<Vec<u8>>>>()
+                    })
+                })
+                .collect();
+
+            let mut lock_scripts = Vec::with_capacity(self.leaf_count);
+            for chunk in chunks {
+                lock_scripts.extend(chunk.join().expect("Lock scripts reader panicked")?);
+            }
+            Ok(lock_scripts)
+        })
+    }
+
+    // Merkle branch and compressed script of a leaf
+    fn record(&self, leaf_index: usize) -> io::Result<(&[u8], &[u8])> {
+        if leaf_index >= self.leaf_count {
+            return Err(invalid_data(&format!(
+                "Leaf {leaf_index} out of {} lock scripts",
+                self.leaf_count
+            )));
+        }
+        let entry = HEADER_LENGTH + INDEX_ENTRY_LENGTH * leaf_index;
+        let offset = u64::from_le_bytes(self.mmap[entry..entry + 8].try_into().unwrap()) as usize;
+        let length =
+            u32::from_le_bytes(self.mmap[entry + 8..entry + 12].try_into().unwrap()) as usize;
+        let record = offset
+            .checked_add(length)
+            .and_then(|end| self.mmap.get(offset..end))
+            .filter(|record| !record.is_empty())
+            .ok_or_else(|| invalid_data(&format!("Truncated lock script {leaf_index}")))?;
+
+        let branch_length = record[0] as usize * NODE_LENGTH;
+        if record.len() < 1 + branch_length {
+            return Err(invalid_data(&format!("Truncated lock script {leaf_index}")));
+        }
+        Ok((&record[1..1 + branch_length], &record[1 + branch_length..]))
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.to_string())
+}
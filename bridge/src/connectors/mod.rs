@@ -1,4 +1,5 @@
 pub mod base;
+pub mod cache_files;
 pub mod connector_0;
 pub mod connector_1;
 pub mod connector_2;
@@ -14,3 +15,6 @@ pub mod connector_e;
 pub mod connector_f_1;
 pub mod connector_f_2;
 pub mod connector_z;
+pub mod descriptor;
+pub mod lock_scripts_file;
+pub mod stack_usage;
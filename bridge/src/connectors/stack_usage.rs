@@ -0,0 +1,193 @@
+use std::fmt;
+
+use bitcoin::{
+    absolute, taproot::LeafVersion, transaction, Amount, OutPoint, ScriptBuf, TapLeafHash,
+    Transaction, TxOut,
+};
+use bitcoin_scriptexec::{Exec, ExecCtx, Options, TxTemplate};
+use bitvm::signatures::signing_winternitz::{
+    generate_winternitz_witness, WinternitzSecret, WinternitzSigningInputs,
+};
+
+use crate::transactions::base::Input;
+
+use super::base::TaprootConnector;
+
+// Consensus limit on the number of stack and altstack items combined
+pub const MAX_STACK_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StackUsage {
+    pub max_stack_items: usize,
+    pub max_altstack_items: usize,
+    pub max_total_items: usize, // stack and altstack combined at the same step
+}
+
+impl StackUsage {
+    pub fn exceeds_limit(&self) -> bool {
+        self.max_total_items > MAX_STACK_SIZE
+    }
+}
+
+pub struct LeafStackUsage {
+    pub connector: String,
+    pub leaf_index: u32,
+    pub script_size: usize,
+    pub usage: StackUsage,
+    // Every opcode was executed. Otherwise the script failed early with the representative
+    // witness and the usage is only a lower bound.
+    pub completed: bool,
+}
+
+impl fmt::Display for LeafStackUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} leaf {}: {} bytes, max {} stack + {} altstack items, {}/{MAX_STACK_SIZE} combined",
+            self.connector,
+            self.leaf_index,
+            self.script_size,
+            self.usage.max_stack_items,
+            self.usage.max_altstack_items,
+            self.usage.max_total_items,
+        )?;
+        if self.usage.exceeds_limit() {
+            write!(f, " (exceeds the limit)")?;
+        }
+        if !self.completed {
+            write!(f, " (incomplete execution, lower bound)")?;
+        }
+        Ok(())
+    }
+}
+
+// Signature checks cannot succeed without the spending transaction. An empty signature makes
+// OP_CHECKSIG push false instead of failing, so the rest of the script still executes.
+pub fn placeholder_signature_witness() -> Vec<Vec<u8>> {
+    vec![vec![]]
+}
+
+pub fn commitment_witness(secret: &WinternitzSecret, message: &[u8]) -> Vec<Vec<u8>> {
+    generate_winternitz_witness(&WinternitzSigningInputs {
+        message,
+        signing_key: secret,
+    })
+    .to_vec()
+}
+
+// Executes connector leaf scripts with representative witnesses and records how many stack
+// items they use. The stack limit is not enforced during execution, so the report shows by how
+// much a leaf exceeds it.
+pub struct StackUsageAnalyzer {
+    // Lock time of the spending transaction, must satisfy the OP_CLTV checks of the leaves
+    lock_time: absolute::LockTime,
+    reports: Vec<LeafStackUsage>,
+}
+
+impl StackUsageAnalyzer {
+    pub fn new(lock_time: absolute::LockTime) -> Self {
+        StackUsageAnalyzer {
+            lock_time,
+            reports: vec![],
+        }
+    }
+
+    pub fn analyze_leaf(
+        &mut self,
+        connector_name: &str,
+        connector: &impl TaprootConnector,
+        leaf_index: u32,
+        witness: Vec<Vec<u8>>,
+    ) {
+        let script = connector.generate_taproot_leaf_script(leaf_index);
+        let input = Input {
+            outpoint: OutPoint::null(),
+            amount: Amount::ZERO,
+        };
+        // The leaf tx in carries the relative timelock checked by OP_CSV
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: self.lock_time,
+            input: vec![connector.generate_taproot_leaf_tx_in(leaf_index, &input)],
+            output: vec![],
+        };
+        let prevout = TxOut {
+            value: input.amount,
+            script_pubkey: connector.generate_taproot_address().script_pubkey(),
+        };
+        let (usage, completed) = execute_with_stack_usage(&tx, prevout, &script, witness);
+
+        self.reports.push(LeafStackUsage {
+            connector: connector_name.to_string(),
+            leaf_index,
+            script_size: script.len(),
+            usage,
+            completed,
+        });
+    }
+
+    // For connectors whose leaves only check signatures
+    pub fn analyze_connector(&mut self, connector_name: &str, connector: &impl TaprootConnector) {
+        let leaf_count: usize = connector
+            .generate_taproot_spend_info()
+            .script_map()
+            .values()
+            .map(|merkle_branches| merkle_branches.len())
+            .sum();
+        for leaf_index in 0..leaf_count as u32 {
+            self.analyze_leaf(
+                connector_name,
+                connector,
+                leaf_index,
+                placeholder_signature_witness(),
+            );
+        }
+    }
+
+    pub fn reports(self) -> Vec<LeafStackUsage> {
+        self.reports
+    }
+}
+
+fn execute_with_stack_usage(
+    tx: &Transaction,
+    prevout: TxOut,
+    script: &ScriptBuf,
+    witness: Vec<Vec<u8>>,
+) -> (StackUsage, bool) {
+    let options = Options {
+        enforce_stack_limit: false,
+        ..Default::default()
+    };
+    let mut exec = Exec::new(
+        ExecCtx::Tapscript,
+        options,
+        TxTemplate {
+            tx: tx.clone(),
+            prevouts: vec![prevout],
+            input_idx: 0,
+            taproot_annex_scriptleaf: Some((
+                TapLeafHash::from_script(script, LeafVersion::TapScript),
+                None,
+            )),
+        },
+        script.clone(),
+        witness,
+    )
+    .expect("error creating exec");
+
+    let mut usage = StackUsage::default();
+    loop {
+        let stack_items = exec.stack().len();
+        let altstack_items = exec.altstack().len();
+        usage.max_stack_items = usage.max_stack_items.max(stack_items);
+        usage.max_altstack_items = usage.max_altstack_items.max(altstack_items);
+        usage.max_total_items = usage.max_total_items.max(stack_items + altstack_items);
+        if exec.exec_next().is_err() {
+            break;
+        }
+    }
+
+    let completed = exec.remaining_script().is_empty();
+    (usage, completed)
+}
@@ -24,6 +24,8 @@ const ETHEREUM_TXID_LENGTH: usize = 64;
 const BITCOIN_TXID_LENGTH: usize = 64;
 pub const SOURCE_NETWORK_TXID_LENGTH: usize = BITCOIN_TXID_LENGTH;
 pub const DESTINATION_NETWORK_TXID_LENGTH: usize = ETHEREUM_TXID_LENGTH;
+pub const DESTINATION_CHAIN_ID_MESSAGE_LENGTH: usize = 8;
+pub const WITHDRAWAL_NONCE_MESSAGE_LENGTH: usize = 8;
 
 #[derive(Eq, PartialEq, Clone, Copy)]
 pub enum DestinationNetwork {
@@ -47,3 +49,14 @@ impl fmt::Display for DestinationNetwork {
         write!(f, "{}", s)
     }
 }
+
+impl DestinationNetwork {
+    // EIP-155 chain id, committed in kick-off 1 by graphs that commit to destination chain metadata
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            DestinationNetwork::Ethereum => 1,
+            DestinationNetwork::EthereumSepolia => 11155111,
+            DestinationNetwork::Local => 831337,
+        }
+    }
+}
@@ -1,7 +1,20 @@
-use bitcoin::{key::Keypair, Network, PublicKey, XOnlyPublicKey};
+use std::collections::HashMap;
+
+use bitcoin::{key::Keypair, Network, PublicKey, ScriptBuf, XOnlyPublicKey};
+use bitvm::signatures::signing_winternitz::WinternitzSecret;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    commitments::CommitmentMessageId,
+    error::ValidationError,
+    rewards::{default_reward_script, validate_reward_script},
+};
 
 use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
 
+const COMMITMENT_SEED_TAG: &[u8] = b"bitvm-bridge/commitment-seed";
+
+#[derive(Clone)]
 pub struct OperatorContext {
     pub network: Network,
 
@@ -12,6 +25,11 @@ pub struct OperatorContext {
     pub n_of_n_public_keys: Vec<PublicKey>,
     pub n_of_n_public_key: PublicKey,
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
+
+    // Pays the reimbursement of take 1 and take 2, see `rewards`
+    pub reward_script: ScriptBuf,
+
+    commitment_seed: [u8; 32],
 }
 
 impl BaseContext for OperatorContext {
@@ -35,6 +53,13 @@ impl OperatorContext {
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
+        // The master commitment seed is derived from the operator secret key, so that
+        // commitment secrets can always be regenerated from the key configuration.
+        let mut hasher = Sha256::new();
+        hasher.update(COMMITMENT_SEED_TAG);
+        hasher.update(keypair.secret_bytes());
+        let commitment_seed = hasher.finalize().into();
+
         OperatorContext {
             network,
 
@@ -45,6 +70,25 @@ impl OperatorContext {
             n_of_n_public_keys: n_of_n_public_keys.to_owned(),
             n_of_n_public_key,
             n_of_n_taproot_public_key,
+
+            reward_script: default_reward_script(network, &public_key),
+
+            commitment_seed,
         }
     }
+
+    // Pays the reimbursement of the peg-out graphs created from now on to `reward_script` instead
+    // of the pay to pubkey script of the operator key
+    pub fn with_reward_script(mut self, reward_script: ScriptBuf) -> Result<Self, ValidationError> {
+        validate_reward_script(&reward_script)?;
+        self.reward_script = reward_script;
+        Ok(self)
+    }
+
+    pub fn generate_commitment_secrets(
+        &self,
+        graph_id: &str,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        CommitmentMessageId::generate_commitment_secrets_from_seed(&self.commitment_seed, graph_id)
+    }
 }
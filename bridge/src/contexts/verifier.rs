@@ -1,7 +1,13 @@
-use bitcoin::{key::Keypair, Network, PublicKey, XOnlyPublicKey};
+use bitcoin::{key::Keypair, Network, PublicKey, ScriptBuf, XOnlyPublicKey};
+
+use crate::{
+    error::ValidationError,
+    rewards::{default_reward_script, validate_reward_script},
+};
 
 use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
 
+#[derive(Clone)]
 pub struct VerifierContext {
     pub network: Network,
 
@@ -11,6 +17,9 @@ pub struct VerifierContext {
     pub n_of_n_public_keys: Vec<PublicKey>,
     pub n_of_n_public_key: PublicKey,
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
+
+    // Pays the rewards of disprove, disprove chain and the timeouts, see `rewards`
+    pub reward_script: ScriptBuf,
 }
 
 impl BaseContext for VerifierContext {
@@ -43,6 +52,16 @@ impl VerifierContext {
             n_of_n_public_keys: n_of_n_public_keys.to_owned(),
             n_of_n_public_key,
             n_of_n_taproot_public_key,
+
+            reward_script: default_reward_script(network, &public_key),
         }
     }
+
+    // Pays the rewards of the transactions broadcast by this verifier to `reward_script` instead of
+    // the pay to pubkey script of the verifier key
+    pub fn with_reward_script(mut self, reward_script: ScriptBuf) -> Result<Self, ValidationError> {
+        validate_reward_script(&reward_script)?;
+        self.reward_script = reward_script;
+        Ok(self)
+    }
 }
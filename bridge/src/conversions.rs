@@ -0,0 +1,85 @@
+// Native reference implementations of the number conversions performed in script. The script
+// side is tested against these, so the encodings used to build witnesses and commitments in Rust
+// and the values reconstructed on the stack cannot drift apart.
+
+/// Largest value accepted as input by the arithmetic opcodes, i.e. the largest number that can be
+/// reconstructed from digits in script.
+pub const MAX_SCRIPT_NUMBER: u32 = i32::MAX as u32;
+
+pub const H256_BYTE_LENGTH: usize = 32;
+
+/// Splits `number` into `digit_count` digits of `log_d` bits each, most significant digit first,
+/// which is the order `digits_to_number` expects them to be pushed in.
+pub fn number_to_digits(number: u32, digit_count: usize, log_d: usize) -> Vec<u32> {
+    assert!(
+        log_d > 0 && log_d < 32,
+        "Digit size must be between 1 and 31 bits"
+    );
+    assert!(
+        digit_count * log_d >= 32 || number >> (digit_count * log_d) == 0,
+        "Number does not fit in {digit_count} digits of {log_d} bits"
+    );
+
+    let mask = (1u32 << log_d) - 1;
+    (0..digit_count)
+        .rev()
+        .map(|i| match i * log_d {
+            shift if shift < 32 => (number >> shift) & mask,
+            _ => 0,
+        })
+        .collect()
+}
+
+/// Reference for `digits_to_number`: concatenates digits of `log_d` bits each, the first pushed
+/// digit being the most significant one.
+pub fn digits_to_number(digits: &[u32], log_d: usize) -> u32 {
+    assert!(
+        log_d > 0 && log_d < 32,
+        "Digit size must be between 1 and 31 bits"
+    );
+
+    digits.iter().fold(0, |number, digit| {
+        assert!(*digit >> log_d == 0, "Digit {digit} exceeds {log_d} bits");
+        (number << log_d) | digit
+    })
+}
+
+/// Reference for `split_digit`: splits a digit of `window` bits into its `index` most significant
+/// bits and the remaining low bits, returned as (high, low). In script, high ends up on top.
+pub fn split_digit(digit: u32, window: u32, index: u32) -> (u32, u32) {
+    assert!(
+        index > 0 && index < window,
+        "Split index must be inside the window"
+    );
+
+    let low_bits = window - index;
+    (digit >> low_bits, digit & ((1 << low_bits) - 1))
+}
+
+/// Splits bytes into nibbles, high nibble first, as pushed for `sb_hash_from_nibbles`.
+pub fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+/// Reference for `sb_hash_from_bytes` and `sb_hash_from_nibbles`: the hash is read as a big-endian
+/// number and returned as the little-endian u32 words used by `H256::push_u32_le` and
+/// `H256::read_u32_le`.
+pub fn hash_to_u32_le(hash: &[u8; H256_BYTE_LENGTH]) -> Vec<u32> {
+    hash.rchunks(4)
+        .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+        .collect()
+}
+
+/// Inverse of `hash_to_u32_le`.
+pub fn u32_le_to_hash(words: &[u32]) -> [u8; H256_BYTE_LENGTH] {
+    assert_eq!(words.len() * 4, H256_BYTE_LENGTH, "Expected 8 words");
+
+    let mut hash = [0u8; H256_BYTE_LENGTH];
+    for (chunk, word) in hash.rchunks_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    hash
+}
@@ -0,0 +1,216 @@
+use std::{fmt, str::FromStr};
+
+use bitcoin::hex::{DisplayHex, FromHex};
+
+use crate::{constants::DestinationNetwork, error::ValidationError};
+
+// Destination chains a peg-in can mint on. The depositor's address on the destination chain is
+// committed in connector Z as a tagged payload: the chain id (8 bytes, big endian) followed by the
+// address bytes in the chain's own format. Adding a chain only takes a registry entry, the script
+// layout stays the same.
+
+pub const DESTINATION_CHAIN_ID_LENGTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestinationAddressFormat {
+    // 20 byte account address, hex encoded with a 0x prefix
+    Evm,
+    // Field element below 2^251 - 256, hex encoded with a 0x prefix, leading zeros may be omitted
+    Starknet,
+}
+
+const EVM_ADDRESS_LENGTH: usize = 20;
+const STARKNET_ADDRESS_LENGTH: usize = 32;
+// Starknet contract addresses are below 2^251 - 256
+const STARKNET_ADDRESS_BOUND: [u8; STARKNET_ADDRESS_LENGTH] = {
+    let mut bound = [0xff; STARKNET_ADDRESS_LENGTH];
+    bound[0] = 0x07;
+    bound[STARKNET_ADDRESS_LENGTH - 1] = 0x00;
+    bound
+};
+
+impl DestinationAddressFormat {
+    pub fn address_length(&self) -> usize {
+        match self {
+            DestinationAddressFormat::Evm => EVM_ADDRESS_LENGTH,
+            DestinationAddressFormat::Starknet => STARKNET_ADDRESS_LENGTH,
+        }
+    }
+
+    // Decodes an address in its display format, e.g. `0x` followed by hex digits
+    pub fn parse(&self, address: &str) -> Result<Vec<u8>, String> {
+        let digits = address
+            .strip_prefix("0x")
+            .ok_or_else(|| String::from("expected a 0x prefix"))?;
+
+        let bytes = match self {
+            DestinationAddressFormat::Evm if digits.len() != 2 * EVM_ADDRESS_LENGTH => {
+                return Err(format!("expected {} hex digits", 2 * EVM_ADDRESS_LENGTH));
+            }
+            DestinationAddressFormat::Evm => Vec::<u8>::from_hex(digits),
+            DestinationAddressFormat::Starknet
+                if digits.is_empty() || digits.len() > 2 * STARKNET_ADDRESS_LENGTH =>
+            {
+                return Err(format!(
+                    "expected 1 to {} hex digits",
+                    2 * STARKNET_ADDRESS_LENGTH
+                ));
+            }
+            DestinationAddressFormat::Starknet => Vec::<u8>::from_hex(&format!(
+                "{digits:0>width$}",
+                width = 2 * STARKNET_ADDRESS_LENGTH
+            )),
+        }
+        .map_err(|e| e.to_string())?;
+
+        self.validate(&bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn validate(&self, address: &[u8]) -> Result<(), String> {
+        if address.len() != self.address_length() {
+            return Err(format!(
+                "expected {} address bytes, found {}",
+                self.address_length(),
+                address.len()
+            ));
+        }
+        if *self == DestinationAddressFormat::Starknet && address >= &STARKNET_ADDRESS_BOUND[..] {
+            return Err(String::from("not below the Starknet address bound"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DestinationChain {
+    pub chain_id: u64,
+    pub name: &'static str,
+    pub address_format: DestinationAddressFormat,
+}
+
+// EVM chains use their EIP-155 chain id, Starknet the short string of its chain id (`SN_MAIN`)
+pub const DESTINATION_REGISTRY: &[DestinationChain] = &[
+    DestinationChain {
+        chain_id: 1,
+        name: "ethereum",
+        address_format: DestinationAddressFormat::Evm,
+    },
+    DestinationChain {
+        chain_id: 11155111,
+        name: "ethereum_sepolia",
+        address_format: DestinationAddressFormat::Evm,
+    },
+    DestinationChain {
+        chain_id: 831337,
+        name: "anvil_831337",
+        address_format: DestinationAddressFormat::Evm,
+    },
+    DestinationChain {
+        chain_id: 0x534e_5f4d_4149_4e,
+        name: "starknet",
+        address_format: DestinationAddressFormat::Starknet,
+    },
+];
+
+pub fn find_destination_chain(chain_id: u64) -> Option<&'static DestinationChain> {
+    DESTINATION_REGISTRY
+        .iter()
+        .find(|chain| chain.chain_id == chain_id)
+}
+
+impl DestinationNetwork {
+    pub fn destination_chain(&self) -> &'static DestinationChain {
+        find_destination_chain(self.chain_id())
+            .expect("Destination networks must be in the destination registry")
+    }
+}
+
+// An address on a registered destination chain, written `<chain id>:<address>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DestinationAddress {
+    pub chain_id: u64,
+    pub address: Vec<u8>,
+}
+
+impl DestinationAddress {
+    pub fn new(chain_id: u64, address: Vec<u8>) -> Result<Self, ValidationError> {
+        let chain = find_destination_chain(chain_id).ok_or_else(|| {
+            ValidationError::InvalidDestinationAddress(format!("unknown chain id {chain_id}"))
+        })?;
+        chain.address_format.validate(&address).map_err(|reason| {
+            ValidationError::InvalidDestinationAddress(format!("{}: {reason}", chain.name))
+        })?;
+
+        Ok(Self { chain_id, address })
+    }
+
+    pub fn chain(&self) -> &'static DestinationChain {
+        find_destination_chain(self.chain_id).expect("Validated on construction")
+    }
+
+    // The bytes committed in connector Z
+    pub fn payload(&self) -> Vec<u8> {
+        [self.chain_id.to_be_bytes().to_vec(), self.address.clone()].concat()
+    }
+
+    pub fn from_payload(payload: &[u8]) -> Result<Self, ValidationError> {
+        if payload.len() < DESTINATION_CHAIN_ID_LENGTH {
+            return Err(ValidationError::InvalidDestinationAddress(String::from(
+                "payload is shorter than the chain id",
+            )));
+        }
+        let (chain_id, address) = payload.split_at(DESTINATION_CHAIN_ID_LENGTH);
+        Self::new(
+            u64::from_be_bytes(chain_id.try_into().unwrap()),
+            address.to_vec(),
+        )
+    }
+}
+
+impl FromStr for DestinationAddress {
+    type Err = ValidationError;
+
+    fn from_str(destination: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| {
+            ValidationError::InvalidDestinationAddress(format!("{destination}: {reason}"))
+        };
+
+        let (chain_id, address) = destination
+            .split_once(':')
+            .ok_or_else(|| invalid(String::from("expected <chain id>:<address>")))?;
+        let chain_id = u64::from_str(chain_id).map_err(|e| invalid(e.to_string()))?;
+        let chain = find_destination_chain(chain_id)
+            .ok_or_else(|| invalid(format!("unknown chain id {chain_id}")))?;
+        let address = chain.address_format.parse(address).map_err(invalid)?;
+
+        Ok(Self { chain_id, address })
+    }
+}
+
+impl fmt::Display for DestinationAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:0x{}",
+            self.chain_id,
+            self.address.to_lower_hex_string()
+        )
+    }
+}
+
+// Bytes connector Z commits for the destination address of a peg-in. Addresses tagged with a
+// chain id are committed as their payload. Untagged addresses are EVM addresses committed as the
+// ASCII string, the layout of graphs created before destinations were tagged.
+pub fn destination_commitment(destination: &str) -> Result<Vec<u8>, ValidationError> {
+    if destination.contains(':') {
+        Ok(DestinationAddress::from_str(destination)?.payload())
+    } else {
+        DestinationAddressFormat::Evm
+            .parse(destination)
+            .map_err(|reason| {
+                ValidationError::InvalidDestinationAddress(format!("{destination}: {reason}"))
+            })?;
+        Ok(destination.as_bytes().to_vec())
+    }
+}
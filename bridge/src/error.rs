@@ -1,16 +1,53 @@
 use super::commitments::CommitmentMessageId;
 use super::graphs::base::GraphId;
-use super::transactions::{base::BaseTransaction, pre_signed::PreSignedTransaction};
-use bitcoin::{PublicKey, Txid};
+use super::protocol::ProtocolParamsId;
+use super::transactions::{
+    assert_transactions::utils::CommittedValueMismatch, base::BaseTransaction,
+    pre_signed::PreSignedTransaction, script_diagnostics::ScriptDiagnostics,
+};
+use bitcoin::{
+    Amount, BlockHash, CompactTarget, Network, OutPoint, PublicKey, ScriptBuf, TapNodeHash, Txid,
+};
 use std::fmt::{self, Display};
 
 #[derive(Debug)]
 pub enum ClientError {
     NoUserContextDefined,
+    DepositorContextNotDefined,
     OperatorContextNotDefined,
     ZkProofVerifyingKeyNotDefined,
     PegInGraphNotFound(GraphId),
+    PegInGraphAlreadyExists(GraphId),
     PegOutGraphNotFound(GraphId),
+    PegOutGraphAlreadyExists(GraphId),
+    GraphNotFound(GraphId),
+    FaucetNotAvailable(Network), // faucets are only used on test networks
+    FaucetRequestFailed(String),
+    RegtestRpcFailed(String), // bitcoind RPC of the regtest controller, see `client::regtest`
+    DataStoreUnavailable(String),
+    VerifierContextNotDefined,
+    HeartbeatBeaconNotAvailable(Network), // beacons are only published on test networks
+    FeeEstimateNotAvailable(u16), // esplora has no estimate for the confirmation target, in blocks
+    GraphOfTxNotFound(Txid),
+    // The operator's private data has no commitment secrets of the graph, or none for the message
+    CommitmentSecretsNotFound(GraphId),
+    CommitmentSecretNotFound(GraphId, CommitmentMessageId),
+    // The private data file of the graph does not match its checksum, e.g. partially written
+    PrivateGraphFileCorrupted(GraphId),
+    // A private data file matching its checksum cannot be deserialized, with its path and the error
+    PrivateDataInvalid(String, String),
+    FundingUtxoNotFound(OutPoint),
+    // The depositor UTXOs cannot fund the peg-in deposit, see `select_deposit_funding`
+    InsufficientDepositorFunds {
+        required: Amount,
+        available: Amount,
+    },
+    // Refused by a middleware of the broadcast policy, see `broadcast_middleware`
+    BroadcastBlocked {
+        txid: Txid,
+        middleware: String,
+        reason: String,
+    },
 }
 
 #[derive(Debug)]
@@ -36,16 +73,145 @@ pub enum GraphError {
     PrecedingTxNotConfirmed(Vec<NamedTx>),
     PrecedingTxTimelockNotMet(NamedTx),
     WitnessNotGenerated(CommitmentMessageId),
+    PegOutAlreadyInitiated,
+    // The graph commits to the destination chain id and withdrawal nonce in kick-off 1
+    DestinationMetadataNotProvided,
+    NotReadyToBroadcast(MissingPrereqs),
+    // An assert commit would commit other intermediate values than an earlier attempt of the graph
+    CommittedValuesMismatch(Vec<CommittedValueMismatch>),
+    // Auxiliary transactions may only spend connector outputs created by the graph
+    NotConnectorOutput(OutPoint),
+    InvalidLeafIndex { connector: String, leaf_index: u32 },
+    ConnectorOutputSpent(OutPoint),
+    // Graph transactions that can still spend the connector output
+    ActiveGraphPath(Vec<NamedTx>),
+    AuxiliaryInputMismatch(usize), // usize: tx input index
+    // Not pre-signed before the deadline of its presigning window
+    PresigningAbandoned,
+    // Nonces contributed to the tx are never replaced and its secret nonces are gone
+    SecretNoncesLost(String), // string: tx name
+    // The verifier holds no secret nonces for the graph, its nonces were never pushed
+    SecretNoncesNotFound(GraphId),
+    // Another operator's peg-out of the same withdrawal confirmed first, see `peg_out_race`
+    PegOutRaceLost { winner: GraphId },
+}
+
+// Something a pre-signed transaction still lacks before it can be broadcast
+#[derive(Debug)]
+pub enum MissingPrereq {
+    Signatures {
+        input_index: usize,
+        pending: Vec<PublicKey>, // committee members that have not signed the input yet
+    },
+    Witness(usize), // usize: tx input index, signed but its witness was not assembled
+    CommitmentWitness(CommitmentMessageId), // revealed by a preceding tx of this graph
+    Confirmation(NamedTx),
+    Timelock {
+        preceding_tx: NamedTx,
+        remaining_blocks: u32,
+    },
+    ChainStatusUnavailable(String), // the chain could not be queried, readiness is unknown
+}
+
+impl fmt::Display for MissingPrereq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MissingPrereq::Signatures {
+                input_index,
+                pending,
+            } => {
+                let pending = pending.iter().map(PublicKey::to_string).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "input {input_index}: waiting for signatures from {}",
+                    pending.join(", ")
+                )
+            }
+            MissingPrereq::Witness(input_index) => write!(
+                f,
+                "input {input_index}: witness not assembled, merge the latest graph data"
+            ),
+            MissingPrereq::CommitmentWitness(message_id) => {
+                write!(f, "{message_id} commitment witness not revealed yet")
+            }
+            MissingPrereq::Confirmation(tx) => {
+                write!(f, "{} ({}) not confirmed yet", tx.name, tx.txid)
+            }
+            MissingPrereq::Timelock {
+                preceding_tx,
+                remaining_blocks,
+            } => write!(
+                f,
+                "timelock after {} ({}) expires in {remaining_blocks} block(s)",
+                preceding_tx.name, preceding_tx.txid
+            ),
+            MissingPrereq::ChainStatusUnavailable(e) => {
+                write!(f, "chain status unavailable: {e}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingPrereqs {
+    pub tx_name: String,
+    pub txid: Txid,
+    pub missing: Vec<MissingPrereq>,
+}
+
+impl fmt::Display for MissingPrereqs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) is not ready to broadcast:",
+            self.tx_name, self.txid
+        )?;
+        for missing in &self.missing {
+            write!(f, "\n  - {missing}")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
 pub enum TransactionError {
     AlreadyMined(Txid),
+    // The node rejected the tx because of a failing input script. The diagnostics come from
+    // re-executing the input scripts locally.
+    ScriptVerificationFailed {
+        reject_reason: String,
+        diagnostics: ScriptDiagnostics,
+    },
+    // Only a challenge can be replaced, see `ChallengeTransaction::bump_fee`
+    ReplacementNotPermitted(Txid, Option<&'static str>), // str: name of the graph tx
+    ReplacementFeeNotCovered {
+        required: Amount,  // fee increase
+        available: Amount, // refund output of the replaced challenge
+    },
+    ReplacementInputNotOwned(usize), // usize: tx input index, spent by another crowdfunder
+    // A CPFP child can only spend outputs locked to the user's key, see `create_cpfp_transaction`
+    CpfpOutputNotSpendable(OutPoint),
+    CpfpFundsInsufficient {
+        required: Amount,  // fee of the child plus its dust output
+        available: Amount, // spent by the child
+    },
+    NonceMissing(Txid, usize, PublicKey), // txid: the transaction id, usize: tx input index, pubkey: the committee member that has not pushed a nonce
 }
 
 #[derive(Debug)]
 pub enum L2Error {
     PegOutNotInitiated,
+    PegOutBurnNotFound(Txid), // txid: the peg-in confirm txid the burn should reference
+    PegOutBurnNotUnique(Txid),
+    PegOutBurnAmountMismatch {
+        expected: Amount,
+        actual: Amount,
+    },
+    PegOutBurnOperatorMismatch {
+        expected: PublicKey,
+        actual: PublicKey,
+    },
+    ChainAdaptor(String),
 }
 
 #[derive(Debug)]
@@ -53,15 +219,134 @@ pub enum ValidationError {
     WitnessMismatch(&'static str, Txid, usize), // str: tx name, txid: the transaction id, usize: tx input index
     TxValidationFailed(&'static str, Txid, usize), // str: tx name, txid: the transaction id, usize: tx input index
     NoncesValidationFailed(&'static str, PublicKey, Txid, usize), // str: tx name, pubkey: the public key, txid: the transaction id, usize: tx input index
+    NotCommitteeMember(&'static str, PublicKey, Txid), // str: tx name, pubkey: the key that contributed a nonce or signature, txid: the transaction id
+    CommitteeMismatch(GraphId), // graph id: the peg-out graph created for a different committee than its peg-in graph
+    AssertCommitInputOrderMismatch {
+        tx_name: &'static str,
+        input_index: usize, // first input that commits to a different message than expected
+        expected: Option<CommitmentMessageId>,
+        found: Option<CommitmentMessageId>,
+    },
+    OperatorCollateralMismatch {
+        required: Amount,
+        posted: Amount, // zero if kick-off 1 has no collateral output
+    },
+    PresigningDeadlineMismatch {
+        expected: Option<u64>, // unix time in seconds
+        found: Option<u64>,
+    },
+    InvalidRewardDestination(String), // string: why the configured address, descriptor or script is rejected
+    NonStandardRewardScript(ScriptBuf), // script: a reward output script that would not be relayed
+    RewardOutputBelowDust {
+        amount: Amount,
+        min: Amount, // dust limit of the reward output script
+    },
+    InvalidDestinationAddress(String), // string: why the destination chain address is rejected
+    InvalidOperatorCommitments(String), // string: why the commitments handed in by an operator are rejected
+    OperatorGraphMismatch(&'static str), // str: name of the first tx that differs from the one the operator's inputs and secrets produce
+    IllegalTransition(GraphId, String), // string: why the staged graph cannot follow the one the committee already holds
+    InvalidGraphId(String),             // string: the rejected id, graph ids are 64 hex characters
+    ProtocolParamsMismatch {
+        graph_id: GraphId,
+        expected: ProtocolParamsId,
+        found: Option<ProtocolParamsId>, // none for graphs created before parameters were recorded
+    },
+    CommitmentKeyReused {
+        graph_id: GraphId,
+        message_id: CommitmentMessageId,
+        earlier_graph_id: GraphId, // graph created before that commits to the same Winternitz public key
+        earlier_message_id: CommitmentMessageId,
+    },
+    InvalidExternalProof(String), // string: why the proof supplied by an external prover is rejected
+    InvalidGraphParameters(String), // string: why the parameters cannot be used on the network or committee
+    NonStandardTransactions(Vec<String>), // strings: the relay policy violations of the graph transactions
+    // The proof is about another peg-out, see `proof_binding`. Hex of the compressed field elements.
+    ProofPublicInputsMismatch {
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+    // A peg-in served to a depositor does not match the committee parameters, see
+    // `peg_in_verification`
+    PegInAddressMismatch {
+        expected: String, // deposit address derived from the parameters
+        found: String,
+    },
+    PegInGraphMismatch(&'static str), // str: first part of the served graph that differs from the derived one
+    MalformedGraph(String),           // string: why the served graph cannot be read
 }
 
 #[derive(Debug)]
 pub enum ChunkerError {
-    ValidProof,
+    ValidProof, // every asserted value is correct, there is nothing to disprove
+    MissingCommitmentWitness {
+        expected: usize,
+        found: usize,
+    }, // the assert commit txs carry fewer commitments than connector C commits to
+    MalformedCommitmentWitness {
+        commitment_index: usize,
+        reason: String,
+    }, // a commitment witness is not a well formed Winternitz signature
+    MissingCommitmentPublicKeys {
+        expected: usize,
+        found: usize,
+    }, // connector C has fewer intermediate value public keys than the chunker needs
+    LockScriptCountMismatch {
+        expected: usize,
+        found: usize,
+    }, // connector C has a different number of disprove lock scripts than the chunker
+    EvaluationFailed {
+        segment_index: usize,
+        reason: String,
+    }, // evaluating the asserted values and executing the disprove scripts disagree
+    SegmentLayoutMismatch {
+        expected: String, // segment layout version of the local chunker
+        found: String,    // segment layout version connector C was generated with
+    },
+    InvalidLockScripts(String), // imported lock scripts cannot be read or do not lock the operator's commitments
+    LockScriptsMerkleRootMismatch {
+        expected: TapNodeHash,      // merkle root connector C was built with
+        found: Option<TapNodeHash>, // merkle root of the imported lock scripts
+    },
+}
+
+impl ChunkerError {
+    // Whether the outcome needs attention. A valid proof is the expected result for an honest
+    // operator, the other errors mean the graph or the on-chain assertions could not be checked.
+    pub fn is_actionable(&self) -> bool {
+        !matches!(self, ChunkerError::ValidProof)
+    }
+}
+
+#[derive(Debug)]
+pub enum HeaderChainError {
+    CheckpointMismatch(u32), // u32: checkpoint height, the header there has another hash
+    CheckpointNotAtRetarget(u32), // u32: checkpoint height, must start a difficulty period
+    HeaderNotConnected(u32), // u32: height, the header does not extend the local chain
+    InvalidProofOfWork(u32), // u32: height
+    UnexpectedDifficulty {
+        height: u32,
+        expected: CompactTarget,
+        found: CompactTarget,
+    },
+    TimestampTooOld(u32), // u32: height, not after the median time of the previous blocks
+    LessWork(u32),        // u32: fork height, the esplora chain has less work than the local one
+    NotConfirmed(Txid),
+    BlockNotInChain {
+        txid: Txid,
+        block_height: u32,
+        block_hash: Option<BlockHash>, // as reported by esplora
+    },
+    MerkleProofMismatch(Txid),
+    InsufficientConfirmations {
+        txid: Txid,
+        required: u32,
+        verified: u32,
+    },
 }
 
 #[derive(Debug)]
 pub enum Error {
+    #[cfg(feature = "client")]
     Esplora(esplora_client::Error),
     Client(ClientError),
     Graph(GraphError),
@@ -69,12 +354,136 @@ pub enum Error {
     L2(L2Error),
     Chunker(ChunkerError),
     Validation(ValidationError),
+    HeaderChain(HeaderChainError),
     Other(String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::Transaction(TransactionError::ScriptVerificationFailed {
+                reject_reason,
+                diagnostics,
+            }) => write!(f, "Tx rejected: {reject_reason}\n{diagnostics}"),
+            Error::Transaction(TransactionError::ReplacementNotPermitted(txid, name)) => write!(
+                f,
+                "{} {txid} cannot be replaced, only a challenge is. The other transactions are signed by the n-of-n or their txid is committed to by pre-signed transactions.",
+                name.unwrap_or("Transaction")
+            ),
+            Error::Transaction(TransactionError::NonceMissing(txid, input_index, public_key)) => {
+                write!(
+                    f,
+                    "Cannot pre-sign input {input_index} of {txid}, verifier {public_key} has not pushed a nonce"
+                )
+            }
+            Error::Chunker(ChunkerError::SegmentLayoutMismatch { expected, found }) => write!(
+                f,
+                "Connector C was generated by a chunker with segment layout {found}, this client runs segment layout {expected}. Both parties have to run a compatible chunker version."
+            ),
+            Error::Client(ClientError::BroadcastBlocked {
+                txid,
+                middleware,
+                reason,
+            }) => write!(f, "Broadcast of {txid} blocked by {middleware}: {reason}"),
+            Error::Client(ClientError::InsufficientDepositorFunds {
+                required,
+                available,
+            }) => write!(
+                f,
+                "Depositor UTXOs hold {available}, the peg-in deposit needs {required}"
+            ),
+            Error::Client(ClientError::PrivateGraphFileCorrupted(graph_id)) => write!(
+                f,
+                "Private data of graph {graph_id} does not match its checksum, the file is corrupted"
+            ),
+            Error::Client(ClientError::PrivateDataInvalid(path, e)) => {
+                write!(f, "Could not deserialize private data in {path}: {e}")
+            }
+            Error::Validation(ValidationError::NonStandardTransactions(violations)) => write!(
+                f,
+                "Graph transactions violate relay policy:\n{}",
+                violations.join("\n")
+            ),
+            Error::Validation(ValidationError::PegInAddressMismatch { expected, found }) => write!(
+                f,
+                "Peg-in address {found} was not derived from the committee parameters, expected {expected}"
+            ),
+            Error::Validation(ValidationError::PegInGraphMismatch(field)) => write!(
+                f,
+                "Peg-in graph does not match the committee parameters: {field} differs"
+            ),
+            Error::Graph(GraphError::PegOutRaceLost { winner }) => write!(
+                f,
+                "The withdrawal was paid out by peg-out graph {winner} first, this graph cannot take the deposit"
+            ),
+            Error::Graph(GraphError::NotReadyToBroadcast(missing_prereqs)) => {
+                write!(f, "{missing_prereqs}")
+            }
+            Error::Graph(GraphError::CommittedValuesMismatch(mismatches)) => {
+                writeln!(
+                    f,
+                    "Re-assert would equivocate on {} committed intermediate values:",
+                    mismatches.len()
+                )?;
+                for mismatch in mismatches {
+                    writeln!(f, "  {mismatch}")?;
+                }
+                Ok(())
+            }
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// For the CLI handlers, which report errors as io errors
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, err.to_string())
+    }
+}
+
+impl Error {
+    // Whether trying again later can succeed without changing anything, e.g. once esplora is
+    // reachable again or a preceding transaction confirmed. Automation retries these and alerts on
+    // the others, which need someone to fix the graph, the keys or the configuration.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "client")]
+            Error::Esplora(esplora_client::Error::Reqwest(_)) => true,
+            #[cfg(feature = "client")]
+            Error::Esplora(esplora_client::Error::HttpResponse { status, .. }) => {
+                *status == 429 || *status >= 500
+            }
+            #[cfg(feature = "client")]
+            Error::Esplora(_) => false,
+            Error::Client(err) => matches!(
+                err,
+                ClientError::DataStoreUnavailable(_)
+                    | ClientError::FaucetRequestFailed(_)
+                    | ClientError::RegtestRpcFailed(_)
+                    | ClientError::FeeEstimateNotAvailable(_)
+            ),
+            Error::Graph(err) => matches!(
+                err,
+                GraphError::PrecedingTxNotConfirmed(_)
+                    | GraphError::PrecedingTxTimelockNotMet(_)
+                    | GraphError::NotReadyToBroadcast(_)
+            ),
+            Error::L2(err) => matches!(
+                err,
+                L2Error::PegOutBurnNotFound(_) | L2Error::ChainAdaptor(_)
+            ),
+            Error::HeaderChain(err) => matches!(
+                err,
+                HeaderChainError::NotConfirmed(_)
+                    | HeaderChainError::InsufficientConfirmations { .. }
+            ),
+            Error::Transaction(_) | Error::Chunker(_) | Error::Validation(_) | Error::Other(_) => {
+                false
+            }
+        }
     }
 }
 
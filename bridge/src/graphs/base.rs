@@ -1,21 +1,37 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::Deref,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+#[cfg(feature = "client")]
+use bitcoin::Transaction;
 use bitcoin::{
+    hex::{Case::Upper, DisplayHex},
     policy::{DEFAULT_MIN_RELAY_TX_FEE, DUST_RELAY_TX_FEE},
-    Network, Transaction, Txid,
+    Network, PublicKey, Txid,
 };
+#[cfg(feature = "client")]
 use esplora_client::{AsyncClient, TxStatus};
+#[cfg(feature = "client")]
 use futures::future::join_all;
 use musig2::SecNonce;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+#[cfg(feature = "client")]
+use crate::error::{ChunkerError, Error, TransactionError};
 use crate::{
     contexts::verifier::VerifierContext,
-    error::{Error, TransactionError},
+    error::ValidationError,
+    protocol::ProtocolParamsId,
     transactions::base::{
-        MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2, MIN_RELAY_FEE_ASSERT_FINAL,
-        MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_KICK_OFF_1,
-        MIN_RELAY_FEE_KICK_OFF_2, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
-        MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME,
+        relay_fee_at_rate, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_PEG_OUT_CONFIRM,
+        VSIZE_ASSERT_COMMIT1, VSIZE_ASSERT_COMMIT2, VSIZE_ASSERT_FINAL, VSIZE_ASSERT_INITIAL,
+        VSIZE_KICK_OFF_1, VSIZE_KICK_OFF_2, VSIZE_PEG_IN_CONFIRM, VSIZE_PEG_IN_DEPOSIT,
+        VSIZE_PEG_IN_REFUND, VSIZE_START_TIME, VSIZE_TAKE_2,
     },
 };
 
@@ -36,24 +52,233 @@ pub const DUST_RELAY_FEE_RATE: u64 = (DUST_RELAY_TX_FEE / 1000) as u64;
 pub const REWARD_PRECISION: u64 = 1000;
 pub const REWARD_MULTIPLIER: u64 = 20;
 
-pub const MIN_RELAY_FEE_ASSERT_SET: u64 = MIN_RELAY_FEE_ASSERT_INITIAL
-    + MIN_RELAY_FEE_ASSERT_COMMIT1
-    + MIN_RELAY_FEE_ASSERT_COMMIT2
-    + MIN_RELAY_FEE_ASSERT_FINAL;
+pub const MIN_RELAY_FEE_ASSERT_SET: u64 = assert_set_fee(MIN_RELAY_FEE_RATE);
+pub const PEG_OUT_FEE: u64 = peg_out_fee(MIN_RELAY_FEE_RATE);
+pub const PEG_IN_FEE: u64 = peg_in_fee(MIN_RELAY_FEE_RATE);
+
+pub const fn assert_set_fee(fee_rate: u64) -> u64 {
+    relay_fee_at_rate(VSIZE_ASSERT_INITIAL, fee_rate)
+        + relay_fee_at_rate(VSIZE_ASSERT_COMMIT1, fee_rate)
+        + relay_fee_at_rate(VSIZE_ASSERT_COMMIT2, fee_rate)
+        + relay_fee_at_rate(VSIZE_ASSERT_FINAL, fee_rate)
+}
+
+// Fees the operator funds the peg-out confirm transaction with, for graphs built at `fee_rate`.
 // use largest fee from each depth
 // assert fee is big enough to cover disprove chain or take 1
-// disprove fee is big enough to cover take 2
-pub const PEG_OUT_FEE: u64 = MIN_RELAY_FEE_PEG_OUT_CONFIRM // depth 0
-    + MIN_RELAY_FEE_KICK_OFF_1 // depth 1
-    + MIN_RELAY_FEE_START_TIME // include START_TIME tx, spent in kickoff 1
-    + MIN_RELAY_FEE_KICK_OFF_2 // depth 2
-    + MIN_RELAY_FEE_ASSERT_SET // depth 3
-    + MIN_RELAY_FEE_DISPROVE; // depth 4
-pub const PEG_IN_FEE: u64 =
-    MIN_RELAY_FEE_PEG_IN_DEPOSIT + max(MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_REFUND);
+// peg-out confirm and disprove pay the minimum relay fee at any graph fee rate
+pub const fn peg_out_fee(fee_rate: u64) -> u64 {
+    MIN_RELAY_FEE_PEG_OUT_CONFIRM // depth 0
+        + relay_fee_at_rate(VSIZE_KICK_OFF_1, fee_rate) // depth 1
+        + relay_fee_at_rate(VSIZE_START_TIME, fee_rate) // include START_TIME tx, spent in kickoff 1
+        + relay_fee_at_rate(VSIZE_KICK_OFF_2, fee_rate) // depth 2
+        + assert_set_fee(fee_rate) // depth 3
+        + max(MIN_RELAY_FEE_DISPROVE, relay_fee_at_rate(VSIZE_TAKE_2, fee_rate))
+    // depth 4
+}
+
+// Fees the depositor funds the peg-in deposit transaction with, for graphs built at `fee_rate`
+pub const fn peg_in_fee(fee_rate: u64) -> u64 {
+    relay_fee_at_rate(VSIZE_PEG_IN_DEPOSIT, fee_rate)
+        + max(
+            relay_fee_at_rate(VSIZE_PEG_IN_CONFIRM, fee_rate),
+            relay_fee_at_rate(VSIZE_PEG_IN_REFUND, fee_rate),
+        )
+}
 
 pub type GraphId = String;
 
+// Graph ids are the upper case hex encoding of a sha256 hash
+pub const GRAPH_ID_LENGTH: usize = 64;
+
+// Ids of peg-in and peg-out graphs as distinct types, so one cannot be passed where the other is
+// expected. Both deref to the plain `GraphId` shared by code handling either kind of graph and
+// serialize as it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct PegInGraphId(GraphId);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct PegOutGraphId(GraphId);
+
+fn parse_graph_id(graph_id: &str) -> Result<GraphId, ValidationError> {
+    if graph_id.len() != GRAPH_ID_LENGTH || !graph_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ValidationError::InvalidGraphId(graph_id.to_string()));
+    }
+
+    Ok(graph_id.to_ascii_uppercase())
+}
+
+// Ids generated by the graphs themselves need no validation
+impl PegInGraphId {
+    pub(crate) fn from_hash(graph_id: GraphId) -> Self {
+        Self(graph_id)
+    }
+
+    // Peg-in graphs are identified by their deposit transaction
+    pub fn from_deposit_txid(deposit_txid: &Txid) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(deposit_txid.to_string());
+
+        Self(hasher.finalize().to_hex_string(Upper))
+    }
+}
+
+impl PegOutGraphId {
+    pub(crate) fn from_hash(graph_id: GraphId) -> Self {
+        Self(graph_id)
+    }
+}
+
+impl FromStr for PegInGraphId {
+    type Err = ValidationError;
+
+    fn from_str(graph_id: &str) -> Result<Self, Self::Err> {
+        parse_graph_id(graph_id).map(Self)
+    }
+}
+
+impl FromStr for PegOutGraphId {
+    type Err = ValidationError;
+
+    fn from_str(graph_id: &str) -> Result<Self, Self::Err> {
+        parse_graph_id(graph_id).map(Self)
+    }
+}
+
+impl Deref for PegInGraphId {
+    type Target = GraphId;
+
+    fn deref(&self) -> &GraphId {
+        &self.0
+    }
+}
+
+impl Deref for PegOutGraphId {
+    type Target = GraphId;
+
+    fn deref(&self) -> &GraphId {
+        &self.0
+    }
+}
+
+impl fmt::Display for PegInGraphId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for PegOutGraphId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<PegInGraphId> for GraphId {
+    fn from(graph_id: PegInGraphId) -> Self {
+        graph_id.0
+    }
+}
+
+impl From<PegOutGraphId> for GraphId {
+    fn from(graph_id: PegOutGraphId) -> Self {
+        graph_id.0
+    }
+}
+
+// Typed ids compare equal to the plain id of the same graph
+impl PartialEq<GraphId> for PegInGraphId {
+    fn eq(&self, other: &GraphId) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<PegInGraphId> for GraphId {
+    fn eq(&self, other: &PegInGraphId) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<GraphId> for PegOutGraphId {
+    fn eq(&self, other: &GraphId) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<PegOutGraphId> for GraphId {
+    fn eq(&self, other: &PegOutGraphId) -> bool {
+        *self == other.0
+    }
+}
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+pub fn current_unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch")
+        .as_secs()
+}
+
+/// Wall clock window in which the committee has to pre-sign a graph, in unix seconds. Graphs
+/// serialized before the window was recorded have neither a creation time nor a deadline.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct PresigningWindow {
+    pub created_at: u64,
+    pub deadline: Option<u64>,
+}
+
+impl PresigningWindow {
+    pub fn starting_now(expiry_days: Option<u32>) -> Self {
+        let created_at = current_unix_time();
+        PresigningWindow {
+            created_at,
+            deadline: expiry_days.map(|days| created_at + days as u64 * SECONDS_PER_DAY),
+        }
+    }
+
+    pub fn has_expired(&self, now: u64) -> bool {
+        self.deadline.is_some_and(|deadline| now >= deadline)
+    }
+}
+
+/// How far a graph got, from its signing ceremony to the last of its transactions confirmed on
+/// chain. Serialized and parsed in kebab case, e.g. `pending-ceremony`, for scripts listing graphs.
+#[derive(
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum GraphStage {
+    // The committee has not pre-signed all transactions of the graph yet
+    PendingCeremony,
+    // Peg-in graphs
+    AwaitingDeposit,
+    DepositConfirmed,
+    PegInConfirmed,
+    Refunded,
+    // Peg-out graphs
+    Ready,
+    PaidOut, // the operator fronted the withdrawal, reimbursement not started
+    KickedOff,
+    Challenged,
+    Asserted,
+    Disproved,
+    TimedOut,
+    Taken,
+}
+
 pub trait BaseGraph {
     fn network(&self) -> Network;
     fn id(&self) -> &String;
@@ -65,13 +290,67 @@ pub trait BaseGraph {
         &mut self,
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    );
+    ) -> Result<(), Error>;
+    // Graphs the committee did not pre-sign before their deadline. Verifiers refuse to contribute
+    // nonces or signatures to them, so a dormant half-signed graph cannot be completed months
+    // later under changed assumptions.
+    fn is_abandoned(&self, verifier_pubkeys: &[PublicKey], now: u64) -> bool;
+    // Protocol parameters the graph was created under, see `protocol`
+    fn protocol_params(&self) -> Option<&ProtocolParamsId>;
+
+    // Nonces and signatures are only contributed to graphs created under the protocol parameters
+    // of this client, a graph built by a client of another version would not match its scripts
+    fn check_protocol_params(&self) -> Result<(), ValidationError> {
+        let expected = ProtocolParamsId::current();
+        if self.protocol_params() == Some(&expected) {
+            return Ok(());
+        }
+
+        Err(ValidationError::ProtocolParamsMismatch {
+            graph_id: self.id().clone(),
+            expected,
+            found: self.protocol_params().cloned(),
+        })
+    }
+
+    // Graphs with disprove scripts are only signed or asserted if they were generated by a chunker
+    // with the local segment layout, see `ConnectorC::check_segment_layout`
+    fn check_segment_layout(&self) -> Result<(), ChunkerError> {
+        Ok(())
+    }
 }
 
 pub const fn max(a: u64, b: u64) -> u64 {
     [a, b][(a < b) as usize]
 }
 
+// Every transaction of a graph with the transactions of the same graph it spends an output of
+pub fn transaction_dag(
+    transactions: &[(&'static str, &Transaction)],
+) -> Vec<(&'static str, Vec<&'static str>)> {
+    let txids: Vec<(&'static str, Txid)> = transactions
+        .iter()
+        .map(|(name, tx)| (*name, tx.compute_txid()))
+        .collect();
+
+    transactions
+        .iter()
+        .map(|(name, tx)| {
+            let spent = txids
+                .iter()
+                .filter(|(_, txid)| {
+                    tx.input
+                        .iter()
+                        .any(|input| input.previous_output.txid == *txid)
+                })
+                .map(|(spent_name, _)| *spent_name)
+                .collect();
+            (*name, spent)
+        })
+        .collect()
+}
+
+#[cfg(feature = "client")]
 pub async fn get_block_height(client: &AsyncClient) -> Result<u32, Error> {
     match client.get_height().await {
         Ok(height) => Ok(height),
@@ -79,6 +358,7 @@ pub async fn get_block_height(client: &AsyncClient) -> Result<u32, Error> {
     }
 }
 
+#[cfg(feature = "client")]
 pub async fn verify_if_not_mined(client: &AsyncClient, txid: Txid) -> Result<(), Error> {
     match is_confirmed(client, txid).await {
         Ok(false) => Ok(()),
@@ -87,11 +367,13 @@ pub async fn verify_if_not_mined(client: &AsyncClient, txid: Txid) -> Result<(),
     }
 }
 
+#[cfg(feature = "client")]
 pub async fn is_confirmed(client: &AsyncClient, txid: Txid) -> Result<bool, esplora_client::Error> {
     let tx_status = client.get_tx_status(&txid).await;
     tx_status.map(|x| x.confirmed)
 }
 
+#[cfg(feature = "client")]
 pub async fn broadcast_and_verify(
     client: &AsyncClient,
     transaction: &Transaction,
@@ -111,6 +393,7 @@ pub async fn broadcast_and_verify(
     }
 }
 
+#[cfg(feature = "client")]
 pub async fn get_tx_statuses(
     client: &AsyncClient,
     txids: &[Txid],
@@ -118,6 +401,7 @@ pub async fn get_tx_statuses(
     join_all(txids.iter().map(|txid| client.get_tx_status(txid))).await
 }
 
+#[cfg(feature = "client")]
 pub async fn get_onchain_txs(
     client: &AsyncClient,
     txids: &[Txid],
@@ -0,0 +1,99 @@
+use std::{collections::HashMap, fmt};
+
+use bitvm::signatures::signing_winternitz::WinternitzPublicKey;
+
+use crate::{commitments::CommitmentMessageId, error::ValidationError};
+
+use super::{
+    base::{BaseGraph, GraphId},
+    peg_out::PegOutGraph,
+};
+
+// A Winternitz public key committed to by two peg-out graphs. Winternitz signatures are one-time
+// signatures: the operator reused the secret, and signing a message in each graph can reveal
+// enough of it to forge commitments to other values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitmentKeyReuse {
+    pub graph_id: GraphId, // the newer of the two graphs
+    pub message_id: CommitmentMessageId,
+    pub earlier_graph_id: GraphId,
+    pub earlier_message_id: CommitmentMessageId,
+}
+
+impl fmt::Display for CommitmentKeyReuse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The commitment public key of {} in graph {} is already used for {} in graph {}",
+            String::from(self.message_id.clone()),
+            self.graph_id,
+            String::from(self.earlier_message_id.clone()),
+            self.earlier_graph_id
+        )
+    }
+}
+
+impl From<CommitmentKeyReuse> for ValidationError {
+    fn from(reuse: CommitmentKeyReuse) -> Self {
+        ValidationError::CommitmentKeyReused {
+            graph_id: reuse.graph_id,
+            message_id: reuse.message_id,
+            earlier_graph_id: reuse.earlier_graph_id,
+            earlier_message_id: reuse.earlier_message_id,
+        }
+    }
+}
+
+// Every commitment public key committed to by more than one graph. Graphs are registered from the
+// oldest, by creation time, so a reuse is reported against the newer graph.
+pub fn commitment_key_reuses<'a>(
+    peg_out_graphs: impl IntoIterator<Item = &'a PegOutGraph>,
+) -> Vec<CommitmentKeyReuse> {
+    let mut peg_out_graphs: Vec<&PegOutGraph> = peg_out_graphs.into_iter().collect();
+    peg_out_graphs.sort_by_key(|peg_out_graph| peg_out_graph.presigning_window().created_at);
+
+    // Keyed by the first digit of the public key, hashing whole keys of every graph on every check
+    // would cost more than the comparisons it saves
+    let mut registry: HashMap<&[u8; 20], RegisteredKey> = HashMap::new();
+    let mut reuses = vec![];
+    for peg_out_graph in peg_out_graphs {
+        for (_, public_keys) in peg_out_graph.commitment_public_keys() {
+            for (message_id, public_key) in public_keys {
+                let Some(first_digit) = public_key.public_key.first() else {
+                    continue; // malformed, see `commitment_public_key_issues`
+                };
+                match registry.get(first_digit) {
+                    // Connectors of the same graph share the keys of the messages they both commit to
+                    Some(earlier) if earlier.graph_id == peg_out_graph.id() => {}
+                    Some(earlier) if earlier.public_key == public_key => {
+                        reuses.push(CommitmentKeyReuse {
+                            graph_id: peg_out_graph.id().clone(),
+                            message_id: message_id.clone(),
+                            earlier_graph_id: earlier.graph_id.clone(),
+                            earlier_message_id: earlier.message_id.clone(),
+                        })
+                    }
+                    Some(_) => {} // distinct keys starting with the same digit
+                    None => {
+                        registry.insert(
+                            first_digit,
+                            RegisteredKey {
+                                public_key,
+                                graph_id: peg_out_graph.id(),
+                                message_id,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    reuses
+}
+
+struct RegisteredKey<'a> {
+    public_key: &'a WinternitzPublicKey,
+    graph_id: &'a GraphId,
+    message_id: &'a CommitmentMessageId,
+}
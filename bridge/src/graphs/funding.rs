@@ -0,0 +1,81 @@
+use bitcoin::Amount;
+
+use crate::transactions::{
+    base::{
+        peg_in_deposit_vsize, relay_fee_at_rate, Input, MIN_RELAY_FEE_PEG_OUT, VSIZE_PEG_IN_DEPOSIT,
+    },
+    peg_in_deposit::DepositFunding,
+};
+
+use super::{
+    base::{peg_in_fee, peg_out_fee, REWARD_PRECISION},
+    template::GraphParameters,
+};
+
+// Smallest outputs the depositor and the operator fund a peg-in graph and its peg-out graph with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequiredFunding {
+    // Spent by the peg-in deposit, funded by the depositor
+    pub peg_in_deposit: Amount,
+    // Spent by the peg-out confirm, funded by the operator
+    pub peg_out_confirm: Amount,
+    // Spent by the peg-out, the operator fronts the withdrawal with it
+    pub peg_out: Amount,
+    // See `GraphParameters::operator_collateral_funding_amount`
+    pub kick_off_1_collateral: Option<Amount>,
+}
+
+// `peg_in_amount` is the amount bridged, the fees of the graph transactions at the fee rate of the
+// parameters come on top
+pub fn required_funding(peg_in_amount: Amount, parameters: &GraphParameters) -> RequiredFunding {
+    let peg_in_deposit = peg_in_amount + Amount::from_sat(peg_in_fee(parameters.fee_rate));
+    let deposit_amount = peg_in_deposit_amount(peg_in_amount, parameters.fee_rate);
+
+    RequiredFunding {
+        peg_in_deposit,
+        peg_out_confirm: peg_out_confirm_funding(deposit_amount, parameters),
+        peg_out: peg_in_amount + Amount::from_sat(MIN_RELAY_FEE_PEG_OUT),
+        kick_off_1_collateral: parameters.operator_collateral_funding_amount(),
+    }
+}
+
+// The peg-out confirm pays the operator reward of the fee schedule, a share of the output of the
+// peg-in deposit, plus the relay fees of the transactions up to disprove
+pub fn peg_out_confirm_funding(deposit_amount: Amount, parameters: &GraphParameters) -> Amount {
+    deposit_amount * parameters.reward_multiplier / REWARD_PRECISION
+        + Amount::from_sat(peg_out_fee(parameters.fee_rate))
+}
+
+// Output of connector Z that bridges `peg_in_amount`: the fees of the peg-in confirm or refund come
+// on top, the fee of the deposit is paid by its inputs
+pub fn peg_in_deposit_amount(peg_in_amount: Amount, fee_rate: u64) -> Amount {
+    peg_in_amount + Amount::from_sat(peg_in_fee(fee_rate))
+        - Amount::from_sat(relay_fee_at_rate(VSIZE_PEG_IN_DEPOSIT, fee_rate))
+}
+
+// Picks depositor outputs in the order given until they fund a deposit of `peg_in_amount`, the
+// rest is paid back as change. Returns the funding all of them would have to hold if they do not.
+pub fn select_deposit_funding(
+    utxos: &[Input],
+    peg_in_amount: Amount,
+    fee_rate: u64,
+) -> Result<DepositFunding, Amount> {
+    let deposit_amount = peg_in_deposit_amount(peg_in_amount, fee_rate);
+    let required = |input_count: usize| {
+        deposit_amount
+            + Amount::from_sat(relay_fee_at_rate(
+                peg_in_deposit_vsize(input_count, false),
+                fee_rate,
+            ))
+    };
+
+    let mut funding = DepositFunding::new(Vec::new(), Some(deposit_amount));
+    for utxo in utxos {
+        funding.inputs.push(*utxo);
+        if funding.total_amount() >= required(funding.inputs.len()) {
+            return Ok(funding);
+        }
+    }
+
+    Err(required(utxos.len().max(1)))
+}
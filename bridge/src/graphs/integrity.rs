@@ -0,0 +1,97 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use bitvm::signatures::{
+    signing_winternitz::{WinternitzPublicKey, LOG_D},
+    winternitz::Parameters,
+};
+
+use crate::{
+    commitments::CommitmentMessageId,
+    error::{Error, MissingPrereq},
+};
+
+// Problems of a graph reported by `validate-graph`. The checks do not query the chain and do not
+// stop at the first problem, so a corrupted data file is diagnosed before anything is broadcast.
+#[derive(Debug)]
+pub enum GraphIntegrityIssue {
+    // A transaction differs from the one re-derived from the connectors, or a graph wide check failed
+    Invalid(Error),
+    MissingSignatures {
+        transaction: String,
+        missing: Vec<MissingPrereq>,
+    },
+    MalformedCommitmentPublicKey {
+        connector: &'static str,
+        message_id: CommitmentMessageId,
+        reason: String,
+    },
+}
+
+impl fmt::Display for GraphIntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphIntegrityIssue::Invalid(e) => write!(f, "{e}"),
+            GraphIntegrityIssue::MissingSignatures {
+                transaction,
+                missing,
+            } => {
+                write!(f, "{transaction} is missing signatures:")?;
+                for missing in missing {
+                    write!(f, "\n  - {missing}")?;
+                }
+                Ok(())
+            }
+            GraphIntegrityIssue::MalformedCommitmentPublicKey {
+                connector,
+                message_id,
+                reason,
+            } => write!(
+                f,
+                "{connector}: commitment public key of {message_id} {reason}"
+            ),
+        }
+    }
+}
+
+// Checks every Winternitz public key of a connector against the length of the message it commits
+// to. A key of the wrong shape makes the commitment script unspendable.
+pub fn commitment_public_key_issues(
+    connector: &'static str,
+    commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+) -> Vec<GraphIntegrityIssue> {
+    let message_lengths: HashMap<CommitmentMessageId, usize> =
+        CommitmentMessageId::message_lengths().into_iter().collect();
+
+    commitment_public_keys
+        .iter()
+        .filter_map(|(message_id, public_key)| {
+            let reason = match message_lengths.get(message_id) {
+                None => Some(String::from("is for an unknown message")),
+                Some(message_length) => {
+                    let parameters =
+                        Parameters::new_by_bit_length(*message_length as u32 * 8, LOG_D);
+                    if public_key.parameters != parameters {
+                        Some(String::from("has the wrong parameters"))
+                    } else if public_key.public_key.len() != parameters.total_length() as usize {
+                        Some(format!(
+                            "has {} digits instead of {}",
+                            public_key.public_key.len(),
+                            parameters.total_length()
+                        ))
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            reason.map(|reason| GraphIntegrityIssue::MalformedCommitmentPublicKey {
+                connector,
+                message_id: message_id.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,75 @@
+// Kick-off 1 opens a race for its connector outputs: the operator continues with start time and
+// kick-off 2, the committee times the operator out with start time timeout or kick-off timeout.
+// Near the timelock boundaries transactions of both sides are valid at once. The operator and the
+// verifiers decide from the same chain state, the confirmations of kick-off 1 and which of the
+// racing transactions confirmed, so they agree on what is available at every height.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KickOffPath {
+    StartTime,
+    StartTimeTimeout,
+    KickOff2,
+    KickOffTimeout,
+}
+
+// Relative timelocks of the connector 1 leaves, in blocks since kick-off 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KickOffTimelocks {
+    pub kick_off_2: u32,
+    pub kick_off_timeout: u32,
+    pub start_time_timeout: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KickOffRace {
+    pub kick_off_1_height: u32,
+    pub tip_height: u32,
+    pub start_time_confirmed: bool,
+    // Start time timeout, kick-off 2 and kick-off timeout all spend connector 1, at most one of
+    // them confirms
+    pub winner: Option<KickOffPath>,
+}
+
+impl KickOffRace {
+    pub fn is_settled(&self) -> bool {
+        self.winner.is_some()
+    }
+
+    pub fn is_timelock_met(&self, num_blocks: u32) -> bool {
+        is_relative_timelock_met(self.kick_off_1_height, self.tip_height, num_blocks)
+    }
+
+    // Transaction the operator is to broadcast next, none while it has to wait or once the race
+    // is settled. Start time stays available after it timed out, it races the timeout.
+    pub fn operator_path(&self, timelocks: &KickOffTimelocks) -> Option<KickOffPath> {
+        if self.is_settled() {
+            None
+        } else if !self.start_time_confirmed {
+            Some(KickOffPath::StartTime)
+        } else {
+            self.is_timelock_met(timelocks.kick_off_2)
+                .then_some(KickOffPath::KickOff2)
+        }
+    }
+
+    // Timeout the verifiers are to broadcast next, none while the operator is within its time or
+    // once the race is settled. Kick-off 2 stays available after the kick-off timeout became
+    // valid, whichever confirms first wins.
+    pub fn verifier_path(&self, timelocks: &KickOffTimelocks) -> Option<KickOffPath> {
+        if self.is_settled() {
+            None
+        } else if !self.start_time_confirmed {
+            self.is_timelock_met(timelocks.start_time_timeout)
+                .then_some(KickOffPath::StartTimeTimeout)
+        } else {
+            self.is_timelock_met(timelocks.kick_off_timeout)
+                .then_some(KickOffPath::KickOffTimeout)
+        }
+    }
+}
+
+// Whether a transaction spending an output confirmed at `confirmed_height` with a relative
+// timelock of `num_blocks` can be mined in the block after `tip_height`, see BIP 68
+pub fn is_relative_timelock_met(confirmed_height: u32, tip_height: u32, num_blocks: u32) -> bool {
+    tip_height + 1 >= confirmed_height + num_blocks
+}
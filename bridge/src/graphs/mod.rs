@@ -1,3 +1,16 @@
 pub mod base;
+#[cfg(feature = "client")]
+pub mod commitment_registry;
+pub mod funding;
+#[cfg(feature = "client")]
+pub mod integrity;
+pub mod kick_off_race;
+#[cfg(feature = "client")]
+pub mod operator_commitments;
+#[cfg(feature = "client")]
 pub mod peg_in;
+pub mod peg_in_verification;
+#[cfg(feature = "client")]
 pub mod peg_out;
+pub mod peg_out_race;
+pub mod template;
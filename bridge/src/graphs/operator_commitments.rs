@@ -0,0 +1,211 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bitcoin::{
+    hashes::{sha256, Hash, HashEngine},
+    secp256k1::{schnorr::Signature, Message},
+    Network, PublicKey, ScriptBuf, XOnlyPublicKey,
+};
+use bitvm::signatures::{
+    signing_winternitz::{WinternitzPublicKey, WinternitzSecret, LOG_D},
+    winternitz::Parameters,
+};
+use secp256k1::SECP256K1;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commitments::CommitmentMessageId,
+    contexts::{base::generate_n_of_n_public_key, operator::OperatorContext},
+    error::ValidationError,
+    rewards::validate_reward_script,
+    serialization::serialize,
+    transactions::base::Input,
+};
+
+use super::{
+    base::{BaseGraph, PegInGraphId},
+    peg_in::PegInGraph,
+    template::GraphParameters,
+};
+
+const OPERATOR_COMMITMENTS_MESSAGE_TAG: &[u8] = b"bitvm-bridge/operator-commitments";
+
+// Everything of the operator a peg-out graph is built from. An operator keeping its keys offline
+// prepares these ahead of time, a coordinator without access to any operator secret builds the
+// graph from them, and the operator adds its pre-signatures whenever it comes online, see
+// `PegOutGraph::new_from_operator_commitments` and `PegOutGraph::pre_sign_operator_inputs`.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct OperatorCommitments {
+    pub network: Network,
+    pub peg_in_graph_id: PegInGraphId,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
+    pub n_of_n_public_keys: Vec<PublicKey>,
+
+    #[serde(with = "crate::serialization::hex_encoded")]
+    pub operator_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    pub operator_taproot_public_key: XOnlyPublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    pub reward_script: ScriptBuf,
+
+    // Operator UTXOs funding peg-out confirm, and with it kick-off 1, and the collateral
+    pub peg_out_confirm_input: Input,
+    pub collateral_input: Option<Input>,
+
+    pub commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    pub parameters: GraphParameters,
+}
+
+impl OperatorCommitments {
+    pub fn new(
+        context: &OperatorContext,
+        peg_in_graph_id: &PegInGraphId,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        parameters: GraphParameters,
+    ) -> Self {
+        Self {
+            network: context.network,
+            peg_in_graph_id: peg_in_graph_id.clone(),
+            n_of_n_public_keys: context.n_of_n_public_keys.clone(),
+            operator_public_key: context.operator_public_key,
+            operator_taproot_public_key: context.operator_taproot_public_key,
+            reward_script: context.reward_script.clone(),
+            peg_out_confirm_input,
+            collateral_input,
+            commitment_public_keys: commitment_secrets
+                .iter()
+                .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+                .collect(),
+            parameters,
+        }
+    }
+
+    pub fn sign(self, context: &OperatorContext) -> SignedOperatorCommitments {
+        let signature =
+            SECP256K1.sign_schnorr_no_aux_rand(&self.message(), &context.operator_keypair);
+
+        SignedOperatorCommitments {
+            commitments: self,
+            signature,
+        }
+    }
+
+    // Aggregated n-of-n public key and its taproot key
+    pub fn n_of_n_public_key(&self) -> (PublicKey, XOnlyPublicKey) {
+        generate_n_of_n_public_key(&self.n_of_n_public_keys)
+    }
+
+    // Checks what the coordinator cannot take on trust: the commitments are for this peg-in graph
+    // and its committee, and hold a well formed public key for every message a graph commits to
+    pub fn validate(&self, peg_in_graph: &PegInGraph) -> Result<(), ValidationError> {
+        let invalid = ValidationError::InvalidOperatorCommitments;
+
+        if self.peg_in_graph_id != *peg_in_graph.id() {
+            return Err(invalid(format!(
+                "made for peg-in graph {}",
+                self.peg_in_graph_id
+            )));
+        }
+        if self.network != peg_in_graph.network() {
+            return Err(invalid(format!("made for {}", self.network)));
+        }
+        if self.n_of_n_public_keys != peg_in_graph.n_of_n_public_keys() {
+            return Err(invalid(String::from(
+                "made for another committee than the peg-in graph's",
+            )));
+        }
+        if self.parameters.template != peg_in_graph.template() {
+            return Err(invalid(format!(
+                "peg-out graph must use the {} template of its peg in graph",
+                peg_in_graph.template()
+            )));
+        }
+        self.parameters
+            .validate(self.network, self.n_of_n_public_keys.len())
+            .map_err(invalid)?;
+        check_collateral_input(&self.parameters, self.collateral_input.as_ref())
+            .map_err(invalid)?;
+        validate_reward_script(&self.reward_script)?;
+
+        let includes_destination_metadata = self
+            .parameters
+            .kick_off_1_commitments
+            .includes_destination_metadata();
+        for (message_id, message_length) in CommitmentMessageId::message_lengths() {
+            if !includes_destination_metadata
+                && matches!(
+                    message_id,
+                    CommitmentMessageId::DestinationChainId | CommitmentMessageId::WithdrawalNonce
+                )
+            {
+                continue;
+            }
+            let parameters = Parameters::new_by_bit_length(message_length as u32 * 8, LOG_D);
+            match self.commitment_public_keys.get(&message_id) {
+                Some(public_key) if public_key.parameters == parameters => {}
+                Some(_) => {
+                    return Err(invalid(format!(
+                        "commitment public key of {message_id} has the wrong parameters"
+                    )))
+                }
+                None => {
+                    return Err(invalid(format!(
+                        "commitment public key of {message_id} is missing"
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn message(&self) -> Message {
+        let mut engine = sha256::Hash::engine();
+        engine.input(OPERATOR_COMMITMENTS_MESSAGE_TAG);
+        engine.input(serialize(self).as_bytes());
+        Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+    }
+}
+
+// Operator commitments as handed to the coordinator, signed with the operator key
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct SignedOperatorCommitments {
+    pub commitments: OperatorCommitments,
+    pub signature: Signature,
+}
+
+impl SignedOperatorCommitments {
+    pub fn verify_signature(&self) -> bool {
+        self.signature
+            .verify(
+                &self.commitments.message(),
+                &self.commitments.operator_taproot_public_key,
+            )
+            .is_ok()
+    }
+}
+
+// The collateral input must be given if and only if the parameters ask for operator collateral,
+// and cover the funding amount
+pub fn check_collateral_input(
+    parameters: &GraphParameters,
+    collateral_input: Option<&Input>,
+) -> Result<(), String> {
+    match (
+        parameters.operator_collateral_funding_amount(),
+        collateral_input,
+    ) {
+        (Some(funding_amount), Some(input)) if input.amount < funding_amount => Err(format!(
+            "Operator collateral input of {} is below the required {funding_amount}",
+            input.amount
+        )),
+        (Some(_), None) => Err(String::from(
+            "Graph parameters require an operator collateral input",
+        )),
+        (None, Some(_)) => Err(String::from(
+            "Graph parameters do not require an operator collateral input",
+        )),
+        _ => Ok(()),
+    }
+}
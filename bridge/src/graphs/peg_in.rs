@@ -1,40 +1,56 @@
-use bitcoin::{
-    hex::{Case::Upper, DisplayHex},
-    Network, OutPoint, PublicKey, Transaction, Txid, XOnlyPublicKey,
-};
+use bitcoin::{Network, OutPoint, PublicKey, Transaction, Txid, XOnlyPublicKey};
 use esplora_client::{AsyncClient, TxStatus};
 use itertools::Itertools;
 use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
 use crate::{
     client::sdk::{
         query::GraphCliQuery, query_contexts::depositor_signatures::DepositorSignatures,
     },
-    error::{Error, GraphError, NamedTx},
-    transactions::pre_signed_musig2::PreSignedMusig2Transaction,
+    error::{Error, GraphError, MissingPrereq, NamedTx, ValidationError},
+    protocol::ProtocolParamsId,
+    scripts::DepositorAddressType,
+    transactions::{
+        pre_signed_musig2::{
+            conflicting_contributions, CeremonyStatus, Musig2SigningProgress,
+            PreSignedMusig2Transaction,
+        },
+        signing_bundle::{SigningBundle, SigningBundleTransaction},
+    },
 };
 
 use super::{
     super::{
-        connectors::{connector_0::Connector0, connector_z::ConnectorZ},
+        connectors::{
+            connector_0::Connector0,
+            connector_z::ConnectorZ,
+            descriptor::{generate_taproot_descriptor, ConnectorDescriptor},
+        },
         contexts::{depositor::DepositorContext, verifier::VerifierContext},
         transactions::{
-            base::{validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input},
+            base::{
+                validate_transaction, verify_committee_contributions, verify_public_nonces_for_tx,
+                BaseTransaction, Input, VSIZE_PEG_IN_CONFIRM, VSIZE_PEG_IN_REFUND,
+            },
+            fee_sensitivity::{transaction_fee_sensitivity, FeeSensitivity},
             peg_in_confirm::PegInConfirmTransaction,
-            peg_in_deposit::PegInDepositTransaction,
+            peg_in_deposit::{DepositFunding, PegInDepositTransaction},
             peg_in_refund::PegInRefundTransaction,
             pre_signed::PreSignedTransaction,
+            standardness::{lint_transaction, StandardnessViolation},
         },
     },
     base::{
-        get_tx_statuses, verify_if_not_mined, BaseGraph, GraphId, GRAPH_VERSION,
-        NUM_REQUIRED_OPERATORS,
+        current_unix_time, get_tx_statuses, transaction_dag, verify_if_not_mined, BaseGraph,
+        GraphId, GraphStage, PegInGraphId, PegOutGraphId, PresigningWindow, GRAPH_VERSION,
+        MIN_RELAY_FEE_RATE, NUM_REQUIRED_OPERATORS,
     },
-    peg_out::{PegOutGraph, PegOutId},
+    integrity::GraphIntegrityIssue,
+    peg_out::PegOutGraph,
+    template::{GraphParameters, GraphTemplate},
 };
 
 #[derive(derive_more::Display)]
@@ -69,6 +85,8 @@ pub enum PegInVerifierStatus {
     ReadyToSubmit, // all signatures collected, can now submit
     #[display("Peg-in done.")]
     Complete, // peg-in complete
+    #[display("Peg-in confirm transaction not pre-signed before its deadline. Abandoned.")]
+    Abandoned, // no more nonces or signatures are contributed
 }
 
 #[derive(derive_more::Display)]
@@ -90,24 +108,47 @@ struct PegInConnectors {
 pub struct PegInGraph {
     version: String,
     network: Network,
-    id: String,
+    id: PegInGraphId,
 
     pub peg_in_deposit_transaction: PegInDepositTransaction,
     pub peg_in_refund_transaction: PegInRefundTransaction,
     pub peg_in_confirm_transaction: PegInConfirmTransaction,
 
+    #[serde(with = "crate::serialization::hex_encoded")]
     n_of_n_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     n_of_n_public_keys: Vec<PublicKey>,
+    #[serde(with = "crate::serialization::hex_encoded")]
     n_of_n_taproot_public_key: XOnlyPublicKey,
 
+    #[serde(with = "crate::serialization::hex_encoded")]
     pub depositor_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     depositor_taproot_public_key: XOnlyPublicKey,
     pub depositor_evm_address: String,
+    // Address the peg-in deposit spends from, needed to rebuild its prevout for validation
+    #[serde(default)]
+    depositor_address_type: DepositorAddressType,
 
     connector_0: Connector0,
     connector_z: ConnectorZ,
 
-    pub peg_out_graphs: Vec<PegOutId>,
+    pub peg_out_graphs: Vec<PegOutGraphId>,
+
+    // Template the peg-out graphs of this peg-in are created from
+    #[serde(default)]
+    template: GraphTemplate,
+
+    // sat/vB the peg-in transactions are built at, see `GraphParameters::fee_rate`
+    #[serde(default = "crate::graphs::template::min_relay_fee_rate")]
+    fee_rate: u64,
+
+    #[serde(default)]
+    presigning_window: PresigningWindow,
+
+    // None for graphs created before protocol parameters were recorded
+    #[serde(default)]
+    protocol_params: Option<ProtocolParamsId>,
 }
 
 impl BaseGraph for PegInGraph {
@@ -123,18 +164,25 @@ impl BaseGraph for PegInGraph {
         &mut self,
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+    ) -> Result<(), Error> {
         self.peg_in_confirm_transaction.pre_sign(
             verifier_context,
             &self.connector_z,
             &secret_nonces[&self.peg_in_confirm_transaction.tx().compute_txid()],
-        );
+        )
     }
 
+    // Nonces already pushed are never replaced, see `validate_transition`
     fn push_verifier_nonces(
         &mut self,
         verifier_context: &VerifierContext,
     ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
+        if self
+            .peg_in_confirm_transaction
+            .has_nonces_for(verifier_context.verifier_public_key)
+        {
+            return HashMap::new();
+        }
         [(
             self.peg_in_confirm_transaction.tx().compute_txid(),
             self.peg_in_confirm_transaction
@@ -142,25 +190,48 @@ impl BaseGraph for PegInGraph {
         )]
         .into()
     }
+
+    // The peg-in confirm transaction records the committee it is signed by
+    fn is_abandoned(&self, _verifier_pubkeys: &[PublicKey], now: u64) -> bool {
+        self.presigning_window.has_expired(now)
+            && !self.peg_in_confirm_transaction.has_all_signatures()
+    }
+
+    fn protocol_params(&self) -> Option<&ProtocolParamsId> {
+        self.protocol_params.as_ref()
+    }
 }
 
 impl PegInGraph {
-    pub fn new(context: &DepositorContext, deposit_input: Input, evm_address: &str) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        deposit_funding: impl Into<DepositFunding>,
+        evm_address: &str,
+        parameters: impl Into<GraphParameters>,
+    ) -> Result<Self, Error> {
+        let parameters: GraphParameters = parameters.into();
         let connectors = create_new_connectors(
             context.network,
             &context.n_of_n_taproot_public_key,
             &context.depositor_taproot_public_key,
             evm_address,
-        );
+        )
+        .map_err(Error::Validation)?;
 
-        let peg_in_deposit_transaction =
-            PegInDepositTransaction::new(context, &connectors.connector_z, deposit_input);
+        let peg_in_deposit_transaction = PegInDepositTransaction::new(
+            context,
+            &connectors.connector_z,
+            deposit_funding,
+            parameters.depositor_address_type,
+            parameters.fee_rate,
+        )?;
 
         let peg_in_refund_vout_0: usize = 0;
         let peg_in_refund_transaction = PegInRefundTransaction::new(
             context,
             &connectors.connector_z,
             generate_input(peg_in_deposit_transaction.tx(), peg_in_refund_vout_0),
+            parameters.fee_rate,
         );
 
         let peg_in_confirm_vout_0: usize = 0;
@@ -169,9 +240,10 @@ impl PegInGraph {
             &connectors.connector_0,
             &connectors.connector_z,
             generate_input(peg_in_deposit_transaction.tx(), peg_in_confirm_vout_0),
+            parameters.fee_rate,
         );
 
-        PegInGraph {
+        Ok(PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network: context.network,
             id: generate_id(&peg_in_deposit_transaction),
@@ -184,10 +256,15 @@ impl PegInGraph {
             depositor_public_key: context.depositor_public_key,
             depositor_taproot_public_key: context.depositor_taproot_public_key,
             depositor_evm_address: evm_address.to_string(),
+            depositor_address_type: parameters.depositor_address_type,
             connector_0: connectors.connector_0,
             connector_z: connectors.connector_z,
             peg_out_graphs: Vec::new(),
-        }
+            template: parameters.template,
+            fee_rate: parameters.fee_rate,
+            presigning_window: PresigningWindow::starting_now(parameters.presigning_expiry_days),
+            protocol_params: Some(ProtocolParamsId::current()),
+        })
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -200,7 +277,7 @@ impl PegInGraph {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         depositor_evm_address: &str,
         deposit_input: Input,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         create_graph_without_signing(
             network,
             depositor_public_key,
@@ -209,7 +286,9 @@ impl PegInGraph {
             n_of_n_public_keys,
             n_of_n_taproot_public_key,
             depositor_evm_address,
-            deposit_input,
+            deposit_input.into(),
+            DepositorAddressType::P2wsh,
+            MIN_RELAY_FEE_RATE,
         )
     }
 
@@ -224,13 +303,14 @@ impl PegInGraph {
         depositor_evm_address: &str,
         deposit_input: Input,
         signatures: &DepositorSignatures,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let connectors = create_new_connectors(
             network,
             n_of_n_taproot_public_key,
             depositor_taproot_public_key,
             depositor_evm_address,
-        );
+        )
+        .map_err(Error::Validation)?;
 
         let peg_in_deposit_transaction = PegInDepositTransaction::new_with_signature(
             network,
@@ -238,7 +318,8 @@ impl PegInGraph {
             &connectors.connector_z,
             deposit_input,
             signatures.deposit,
-        );
+            MIN_RELAY_FEE_RATE,
+        )?;
 
         let peg_in_refund_vout_0: usize = 0;
         let peg_in_refund_transaction = PegInRefundTransaction::new_with_signature(
@@ -247,6 +328,7 @@ impl PegInGraph {
             &connectors.connector_z,
             generate_input(peg_in_deposit_transaction.tx(), peg_in_refund_vout_0),
             signatures.refund,
+            MIN_RELAY_FEE_RATE,
         );
 
         let peg_in_confirm_vout_0: usize = 0;
@@ -256,9 +338,10 @@ impl PegInGraph {
             generate_input(peg_in_deposit_transaction.tx(), peg_in_confirm_vout_0),
             n_of_n_public_keys,
             signatures.confirm,
+            MIN_RELAY_FEE_RATE,
         );
 
-        PegInGraph {
+        Ok(PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network,
             id: generate_id(&peg_in_deposit_transaction),
@@ -271,13 +354,20 @@ impl PegInGraph {
             depositor_public_key: *depositor_public_key,
             depositor_taproot_public_key: *depositor_taproot_public_key,
             depositor_evm_address: depositor_evm_address.to_string(),
+            depositor_address_type: DepositorAddressType::P2wsh,
             connector_0: connectors.connector_0,
             connector_z: connectors.connector_z,
             peg_out_graphs: Vec::new(),
-        }
+            template: GraphTemplate::default(),
+            fee_rate: MIN_RELAY_FEE_RATE,
+            presigning_window: PresigningWindow::starting_now(
+                GraphTemplate::default().parameters().presigning_expiry_days,
+            ),
+            protocol_params: Some(ProtocolParamsId::current()),
+        })
     }
 
-    pub fn new_for_validation(&self) -> Self {
+    pub fn new_for_validation(&self) -> Result<Self, Error> {
         create_graph_without_signing(
             self.network,
             &self.depositor_public_key,
@@ -286,10 +376,9 @@ impl PegInGraph {
             &self.n_of_n_public_keys,
             &self.n_of_n_taproot_public_key,
             &self.depositor_evm_address,
-            Input {
-                outpoint: self.peg_in_deposit_transaction.tx().input[0].previous_output, // Self-referencing
-                amount: self.peg_in_deposit_transaction.prev_outs()[0].value, // Self-referencing
-            },
+            self.peg_in_deposit_transaction.funding(), // Self-referencing
+            self.depositor_address_type,
+            self.fee_rate,
         )
     }
 
@@ -297,6 +386,127 @@ impl PegInGraph {
         &self.peg_in_confirm_transaction
     }
 
+    pub fn peg_in_graph_id(&self) -> &PegInGraphId {
+        &self.id
+    }
+
+    pub fn template(&self) -> GraphTemplate {
+        self.template
+    }
+
+    pub fn fee_rate(&self) -> u64 {
+        self.fee_rate
+    }
+
+    // Name and txid of every transaction of the graph
+    pub fn txids(&self) -> Vec<(&'static str, Txid)> {
+        vec![
+            (
+                self.peg_in_deposit_transaction.name(),
+                self.peg_in_deposit_transaction.tx().compute_txid(),
+            ),
+            (
+                self.peg_in_confirm_transaction.name(),
+                self.peg_in_confirm_transaction.tx().compute_txid(),
+            ),
+            (
+                self.peg_in_refund_transaction.name(),
+                self.peg_in_refund_transaction.tx().compute_txid(),
+            ),
+        ]
+    }
+
+    pub fn n_of_n_public_keys(&self) -> &[PublicKey] {
+        &self.n_of_n_public_keys
+    }
+
+    pub fn presigning_window(&self) -> &PresigningWindow {
+        &self.presigning_window
+    }
+
+    // Nonces and signatures collected for the peg-in confirm, None once it is fully signed
+    pub fn signing_progress(&self) -> Option<Musig2SigningProgress> {
+        let progress = Musig2SigningProgress::new(
+            self.peg_in_confirm_transaction.name(),
+            &self.peg_in_confirm_transaction,
+            &self.n_of_n_public_keys,
+        );
+        (!progress.is_complete()).then_some(progress)
+    }
+
+    // Where the verifier stands in the signing ceremony of the peg-in confirm, given the secret
+    // nonces it kept for the graph
+    pub fn ceremony_status(
+        &self,
+        context: &VerifierContext,
+        secret_nonces: Option<&HashMap<Txid, HashMap<usize, SecNonce>>>,
+    ) -> CeremonyStatus {
+        CeremonyStatus::new(
+            self.peg_in_confirm_transaction.name(),
+            &self.peg_in_confirm_transaction,
+            context,
+            secret_nonces,
+        )
+    }
+
+    // Whether this graph, as staged by a committee member, may follow `previous`, the same graph
+    // as the committee holds it. Nonces and signatures already contributed are never replaced.
+    pub fn validate_transition(&self, previous: &PegInGraph) -> Result<(), Error> {
+        let conflicts = conflicting_contributions(
+            &previous.peg_in_confirm_transaction,
+            &self.peg_in_confirm_transaction,
+        );
+        if !conflicts.is_empty() {
+            let conflicts = conflicts
+                .iter()
+                .map(PublicKey::to_string)
+                .collect::<Vec<_>>();
+            return Err(Error::Validation(ValidationError::IllegalTransition(
+                self.id().clone(),
+                format!(
+                    "{} replaces the contributions of {}",
+                    self.peg_in_confirm_transaction.name(),
+                    conflicts.join(", ")
+                ),
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Sighash preimages of the peg-in confirm, the only transaction the committee pre-signs
+    pub fn signing_bundle(&self) -> SigningBundle {
+        SigningBundle {
+            graph_id: self.id().clone(),
+            transactions: vec![SigningBundleTransaction::new(
+                self.peg_in_confirm_transaction.name(),
+                &self.peg_in_confirm_transaction,
+            )],
+        }
+    }
+
+    // Output descriptors of the connector addresses, for tracking the graph in wallet software
+    pub fn connector_descriptors(&self) -> Vec<ConnectorDescriptor> {
+        vec![
+            generate_taproot_descriptor("connector_z", &self.connector_z),
+            generate_taproot_descriptor("connector_0", &self.connector_0),
+        ]
+    }
+
+    // Blocks after the deposit confirmation from which the depositor can take the deposit back
+    pub fn refund_timelock(&self) -> u32 {
+        self.connector_z.num_blocks_timelock_0
+    }
+
+    pub async fn deposit_height(&self, client: &AsyncClient) -> Option<u32> {
+        client
+            .get_tx_status(&self.peg_in_deposit_transaction.tx().compute_txid())
+            .await
+            .ok()
+            .filter(|status| status.confirmed)
+            .and_then(|status| status.block_height)
+    }
+
     pub async fn verifier_status(
         &self,
         client: &AsyncClient,
@@ -304,7 +514,11 @@ impl PegInGraph {
         peg_outs: &[&PegOutGraph],
     ) -> PegInVerifierStatus {
         // check that the supplied peg out graphs match our expectation
-        let supplied_peg_out_ids = peg_outs.iter().map(|x| x.id()).sorted().collect::<Vec<_>>();
+        let supplied_peg_out_ids = peg_outs
+            .iter()
+            .map(|x| x.peg_out_graph_id())
+            .sorted()
+            .collect::<Vec<_>>();
         let expected_peg_out_ids = self.peg_out_graphs.iter().sorted().collect::<Vec<_>>();
         if supplied_peg_out_ids != expected_peg_out_ids {
             panic!("Invalid peg outs supplied as argument");
@@ -327,11 +541,15 @@ impl PegInGraph {
             return PegInVerifierStatus::Complete;
         }
 
+        if self.is_abandoned(&verifier_context.n_of_n_public_keys, current_unix_time()) {
+            return PegInVerifierStatus::Abandoned;
+        }
+
         if !self
             .peg_in_confirm_transaction
             .has_nonce_of(verifier_context)
         {
-            return PegInVerifierStatus::PendingOurNonces(vec![self.id.clone()]);
+            return PegInVerifierStatus::PendingOurNonces(vec![self.id().clone()]);
         }
 
         let has_all_pegin_nonces = self.peg_in_confirm_transaction.has_all_nonces();
@@ -343,7 +561,7 @@ impl PegInGraph {
             .peg_in_confirm_transaction
             .has_signatures_for(verifier_context.verifier_public_key)
         {
-            return PegInVerifierStatus::PendingOurSignature(vec![self.id.clone()]);
+            return PegInVerifierStatus::PendingOurSignature(vec![self.id().clone()]);
         }
 
         let has_all_pegin_signatures = self.peg_in_confirm_transaction.has_all_signatures();
@@ -448,6 +666,30 @@ impl PegInGraph {
         )
     }
 
+    // Stage of the graph on chain, the signing ceremony is not taken into account
+    pub async fn chain_stage(&self, client: &AsyncClient) -> Result<GraphStage, Error> {
+        let tx_statuses = get_tx_statuses(
+            client,
+            &[
+                self.peg_in_deposit_transaction.tx().compute_txid(),
+                self.peg_in_confirm_transaction.tx().compute_txid(),
+                self.peg_in_refund_transaction.tx().compute_txid(),
+            ],
+        )
+        .await
+        .into_iter()
+        .map(|status| status.map(|status| status.confirmed))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::Esplora)?;
+
+        Ok(match tx_statuses[..] {
+            [_, _, true] => GraphStage::Refunded,
+            [_, true, _] => GraphStage::PegInConfirmed,
+            [true, _, _] => GraphStage::DepositConfirmed,
+            _ => GraphStage::AwaitingDeposit,
+        })
+    }
+
     pub async fn deposit(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
         let txid = self.peg_in_deposit_transaction.tx().compute_txid();
         verify_if_not_mined(client, txid).await?;
@@ -518,7 +760,7 @@ impl PegInGraph {
     }
 
     pub fn validate(&self) -> Result<(), Error> {
-        let peg_in_graph = self.new_for_validation();
+        let peg_in_graph = self.new_for_validation()?;
         validate_transaction(
             self.peg_in_deposit_transaction.tx(),
             peg_in_graph.peg_in_deposit_transaction.tx(),
@@ -536,10 +778,110 @@ impl PegInGraph {
         )?;
 
         verify_public_nonces_for_tx(&self.peg_in_confirm_transaction)?;
+        verify_committee_contributions(&self.peg_in_confirm_transaction, &self.n_of_n_public_keys)?;
 
         Ok(())
     }
 
+    // `validate` without stopping at the first problem, plus the committee signatures the peg-in
+    // confirm transaction still misses
+    pub fn integrity_issues(&self) -> Vec<GraphIntegrityIssue> {
+        let peg_in_graph = match self.new_for_validation() {
+            Ok(peg_in_graph) => peg_in_graph,
+            Err(err) => return vec![GraphIntegrityIssue::Invalid(err)],
+        };
+        let mut issues: Vec<GraphIntegrityIssue> = [
+            self.check_protocol_params().map_err(Error::Validation),
+            validate_transaction(
+                self.peg_in_deposit_transaction.tx(),
+                peg_in_graph.peg_in_deposit_transaction.tx(),
+                self.peg_in_deposit_transaction.name(),
+            ),
+            validate_transaction(
+                self.peg_in_refund_transaction.tx(),
+                peg_in_graph.peg_in_refund_transaction.tx(),
+                self.peg_in_refund_transaction.name(),
+            ),
+            validate_transaction(
+                self.peg_in_confirm_transaction.tx(),
+                peg_in_graph.peg_in_confirm_transaction.tx(),
+                self.peg_in_confirm_transaction.name(),
+            ),
+            verify_public_nonces_for_tx(&self.peg_in_confirm_transaction),
+            verify_committee_contributions(
+                &self.peg_in_confirm_transaction,
+                &self.n_of_n_public_keys,
+            ),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .map(GraphIntegrityIssue::Invalid)
+        .collect();
+
+        let input_index = 0;
+        let signatures = self
+            .peg_in_confirm_transaction
+            .musig2_signatures()
+            .get(&input_index);
+        let pending: Vec<PublicKey> = self
+            .n_of_n_public_keys
+            .iter()
+            .copied()
+            .filter(|pubkey| !signatures.is_some_and(|signatures| signatures.contains_key(pubkey)))
+            .collect();
+        if !pending.is_empty() {
+            issues.push(GraphIntegrityIssue::MissingSignatures {
+                transaction: self.peg_in_confirm_transaction.name().to_string(),
+                missing: vec![MissingPrereq::Signatures {
+                    input_index,
+                    pending,
+                }],
+            });
+        }
+
+        issues
+    }
+
+    // Relay policy violations of the transaction templates, see `lint_transaction`
+    pub fn lint_standardness(&self) -> Vec<StandardnessViolation> {
+        [
+            lint_transaction(&self.peg_in_deposit_transaction),
+            lint_transaction(&self.peg_in_refund_transaction),
+            lint_transaction(&self.peg_in_confirm_transaction),
+        ]
+        .concat()
+    }
+
+    // See `PegOutGraph::fee_sensitivity`
+    pub fn fee_sensitivity(&self) -> Vec<FeeSensitivity> {
+        vec![
+            transaction_fee_sensitivity(
+                &self.peg_in_deposit_transaction,
+                self.peg_in_deposit_transaction.estimated_vsize(),
+            ),
+            transaction_fee_sensitivity(&self.peg_in_confirm_transaction, VSIZE_PEG_IN_CONFIRM),
+            transaction_fee_sensitivity(&self.peg_in_refund_transaction, VSIZE_PEG_IN_REFUND),
+        ]
+    }
+
+    // See `transaction_dag`
+    pub fn transaction_dag(&self) -> Vec<(&'static str, Vec<&'static str>)> {
+        transaction_dag(&[
+            (
+                self.peg_in_deposit_transaction.name(),
+                self.peg_in_deposit_transaction.tx(),
+            ),
+            (
+                self.peg_in_confirm_transaction.name(),
+                self.peg_in_confirm_transaction.tx(),
+            ),
+            (
+                self.peg_in_refund_transaction.name(),
+                self.peg_in_refund_transaction.tx(),
+            ),
+        ])
+    }
+
     pub fn merge(&mut self, source_peg_in_graph: &PegInGraph) {
         self.peg_in_confirm_transaction
             .merge(&source_peg_in_graph.peg_in_confirm_transaction);
@@ -576,12 +918,8 @@ impl GraphCliQuery for PegInGraph {
     }
 }
 
-pub fn generate_id(peg_in_deposit_transaction: &PegInDepositTransaction) -> String {
-    let mut hasher = Sha256::new();
-
-    hasher.update(peg_in_deposit_transaction.tx().compute_txid().to_string());
-
-    hasher.finalize().to_hex_string(Upper)
+pub fn generate_id(peg_in_deposit_transaction: &PegInDepositTransaction) -> PegInGraphId {
+    PegInGraphId::from_deposit_txid(&peg_in_deposit_transaction.tx().compute_txid())
 }
 
 fn create_new_connectors(
@@ -589,19 +927,19 @@ fn create_new_connectors(
     n_of_n_taproot_public_key: &XOnlyPublicKey,
     depositor_taproot_public_key: &XOnlyPublicKey,
     evm_address: &str,
-) -> PegInConnectors {
+) -> Result<PegInConnectors, ValidationError> {
     let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
     let connector_z = ConnectorZ::new(
         network,
         evm_address,
         depositor_taproot_public_key,
         n_of_n_taproot_public_key,
-    );
+    )?;
 
-    PegInConnectors {
+    Ok(PegInConnectors {
         connector_0,
         connector_z,
-    }
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -613,20 +951,25 @@ fn create_graph_without_signing(
     n_of_n_public_keys: &[PublicKey],
     n_of_n_taproot_public_key: &XOnlyPublicKey,
     depositor_evm_address: &str,
-    deposit_input: Input,
-) -> PegInGraph {
+    deposit_funding: DepositFunding,
+    depositor_address_type: DepositorAddressType,
+    fee_rate: u64,
+) -> Result<PegInGraph, Error> {
     let connectors = create_new_connectors(
         network,
         n_of_n_taproot_public_key,
         depositor_taproot_public_key,
         depositor_evm_address,
-    );
+    )
+    .map_err(Error::Validation)?;
     let peg_in_deposit_transaction = PegInDepositTransaction::new_for_validation(
         network,
         depositor_public_key,
         &connectors.connector_z,
-        deposit_input,
-    );
+        deposit_funding,
+        depositor_address_type,
+        fee_rate,
+    )?;
 
     let peg_in_refund_vout_0: usize = 0;
     let peg_in_refund_transaction = PegInRefundTransaction::new_for_validation(
@@ -634,6 +977,7 @@ fn create_graph_without_signing(
         depositor_public_key,
         &connectors.connector_z,
         generate_input(peg_in_deposit_transaction.tx(), peg_in_refund_vout_0),
+        fee_rate,
     );
 
     let peg_in_confirm_vout_0: usize = 0;
@@ -642,9 +986,10 @@ fn create_graph_without_signing(
         &connectors.connector_z,
         generate_input(peg_in_deposit_transaction.tx(), peg_in_confirm_vout_0),
         n_of_n_public_keys.to_owned(),
+        fee_rate,
     );
 
-    PegInGraph {
+    Ok(PegInGraph {
         version: GRAPH_VERSION.to_string(),
         network,
         id: generate_id(&peg_in_deposit_transaction),
@@ -657,10 +1002,15 @@ fn create_graph_without_signing(
         depositor_public_key: *depositor_public_key,
         depositor_taproot_public_key: *depositor_taproot_public_key,
         depositor_evm_address: depositor_evm_address.to_string(),
+        depositor_address_type,
         connector_0: connectors.connector_0,
         connector_z: connectors.connector_z,
         peg_out_graphs: Vec::new(),
-    }
+        template: GraphTemplate::default(),
+        fee_rate,
+        presigning_window: PresigningWindow::default(),
+        protocol_params: Some(ProtocolParamsId::current()),
+    })
 }
 
 fn generate_input(tx: &Transaction, vout: usize) -> Input {
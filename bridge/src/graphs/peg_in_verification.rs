@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use bitcoin::{Address, Amount, Network, OutPoint, PublicKey, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connectors::{base::TaprootConnector, connector_0::Connector0, connector_z::ConnectorZ},
+    contexts::base::generate_n_of_n_public_key,
+    error::ValidationError,
+    transactions::{
+        peg_in_confirm::PegInConfirmTransaction, peg_in_deposit::PegInDepositTransaction,
+        peg_in_refund::PegInRefundTransaction, pre_signed::PreSignedTransaction,
+    },
+};
+
+use super::base::PegInGraphId;
+
+// Checks a depositor can run before funding a peg-in served by someone else, e.g. a bridge web
+// app: the deposit address and the peg-in graph are derived again from the committee parameters
+// the depositor trusts. Only connectors, transactions and serialization are used, so the checks
+// are built without the client feature and for wasm, see `wasm`.
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PegInCommitteeParameters {
+    pub network: Network,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    pub depositor_public_key: PublicKey,
+    // In the order the committee aggregates its key in
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
+    pub n_of_n_public_keys: Vec<PublicKey>,
+    // Destination of the peg-in, see `ConnectorZ::evm_address`
+    pub depositor_evm_address: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PegInAddresses {
+    pub deposit: String, // connector Z, funded by the peg-in deposit
+    pub confirm: String, // connector 0, funded by the peg-in confirm
+}
+
+// What a served peg-in graph was verified to be
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedPegIn {
+    pub graph_id: PegInGraphId,
+    pub addresses: PegInAddresses,
+    #[serde(with = "bitcoin::amount::serde::as_sat")]
+    pub amount: Amount, // deposited to connector Z
+}
+
+// The fields of a serialized `PegInGraph` that are checked, the others are ignored
+#[derive(Deserialize)]
+struct ServedPegInGraph {
+    network: Network,
+    id: PegInGraphId,
+    peg_in_deposit_transaction: PegInDepositTransaction,
+    peg_in_refund_transaction: PegInRefundTransaction,
+    peg_in_confirm_transaction: PegInConfirmTransaction,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    n_of_n_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
+    n_of_n_public_keys: Vec<PublicKey>,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    n_of_n_taproot_public_key: XOnlyPublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    depositor_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    depositor_taproot_public_key: XOnlyPublicKey,
+    depositor_evm_address: String,
+    connector_0: Connector0,
+    connector_z: ConnectorZ,
+}
+
+impl PegInCommitteeParameters {
+    // The connectors a peg-in graph of the depositor and committee is built with
+    pub fn connectors(&self) -> Result<(Connector0, ConnectorZ), ValidationError> {
+        if self.n_of_n_public_keys.is_empty() {
+            return Err(ValidationError::InvalidGraphParameters(String::from(
+                "the committee has no members",
+            )));
+        }
+        let (_, n_of_n_taproot_public_key) = generate_n_of_n_public_key(&self.n_of_n_public_keys);
+        Ok((
+            Connector0::new(self.network, &n_of_n_taproot_public_key),
+            ConnectorZ::new(
+                self.network,
+                &self.depositor_evm_address,
+                &XOnlyPublicKey::from(self.depositor_public_key),
+                &n_of_n_taproot_public_key,
+            )?,
+        ))
+    }
+
+    pub fn addresses(&self) -> Result<PegInAddresses, ValidationError> {
+        let (connector_0, connector_z) = self.connectors()?;
+
+        Ok(PegInAddresses {
+            deposit: connector_z.generate_taproot_address().to_string(),
+            confirm: connector_0.generate_taproot_address().to_string(),
+        })
+    }
+
+    pub fn verify_deposit_address(&self, address: &str) -> Result<(), ValidationError> {
+        let expected = self.connectors()?.1.generate_taproot_address();
+        let matches = Address::from_str(address.trim())
+            .ok()
+            .and_then(|address| address.require_network(self.network).ok())
+            .is_some_and(|address| address == expected);
+        if !matches {
+            return Err(ValidationError::PegInAddressMismatch {
+                expected: expected.to_string(),
+                found: address.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Verifies a peg-in graph as serialized by the client: its keys and connectors are the ones
+    // derived from the parameters, the deposit pays to connector Z and the confirm and refund
+    // transactions spend the deposit. Signatures are not checked, the committee checks them before
+    // the graph can be confirmed.
+    pub fn verify_peg_in_graph(&self, graph_json: &str) -> Result<VerifiedPegIn, ValidationError> {
+        let graph: ServedPegInGraph = serde_json::from_str(graph_json)
+            .map_err(|err| ValidationError::MalformedGraph(err.to_string()))?;
+        let (connector_0, connector_z) = self.connectors()?;
+        let (n_of_n_public_key, n_of_n_taproot_public_key) =
+            generate_n_of_n_public_key(&self.n_of_n_public_keys);
+
+        let mismatch = ValidationError::PegInGraphMismatch;
+        if graph.network != self.network {
+            return Err(mismatch("network"));
+        }
+        if graph.n_of_n_public_keys != self.n_of_n_public_keys {
+            return Err(mismatch("n_of_n_public_keys"));
+        }
+        if graph.n_of_n_public_key != n_of_n_public_key
+            || graph.n_of_n_taproot_public_key != n_of_n_taproot_public_key
+        {
+            return Err(mismatch("n_of_n_public_key"));
+        }
+        if graph.depositor_public_key != self.depositor_public_key
+            || graph.depositor_taproot_public_key != XOnlyPublicKey::from(self.depositor_public_key)
+        {
+            return Err(mismatch("depositor_public_key"));
+        }
+        if graph.depositor_evm_address != self.depositor_evm_address {
+            return Err(mismatch("depositor_evm_address"));
+        }
+        if graph.connector_0 != connector_0 {
+            return Err(mismatch("connector_0"));
+        }
+        if graph.connector_z != connector_z {
+            return Err(mismatch("connector_z"));
+        }
+
+        let deposit_tx = graph.peg_in_deposit_transaction.tx();
+        let connector_z_address = connector_z.generate_taproot_address();
+        let Some(deposit_output) = deposit_tx
+            .output
+            .first()
+            .filter(|output| output.script_pubkey == connector_z_address.script_pubkey())
+        else {
+            return Err(mismatch("peg_in_deposit_transaction"));
+        };
+        let deposit_outpoint = OutPoint {
+            txid: deposit_tx.compute_txid(),
+            vout: 0,
+        };
+        if PegInGraphId::from_deposit_txid(&deposit_outpoint.txid) != graph.id {
+            return Err(mismatch("id"));
+        }
+
+        let confirm_tx = graph.peg_in_confirm_transaction.tx();
+        let connector_0_address = connector_0.generate_taproot_address();
+        if confirm_tx.input.len() != 1
+            || confirm_tx.input[0].previous_output != deposit_outpoint
+            || confirm_tx
+                .output
+                .first()
+                .is_none_or(|output| output.script_pubkey != connector_0_address.script_pubkey())
+        {
+            return Err(mismatch("peg_in_confirm_transaction"));
+        }
+        let refund_tx = graph.peg_in_refund_transaction.tx();
+        if refund_tx.input.len() != 1 || refund_tx.input[0].previous_output != deposit_outpoint {
+            return Err(mismatch("peg_in_refund_transaction"));
+        }
+
+        Ok(VerifiedPegIn {
+            graph_id: graph.id,
+            addresses: PegInAddresses {
+                deposit: connector_z_address.to_string(),
+                confirm: connector_0_address.to_string(),
+            },
+            amount: deposit_output.value,
+        })
+    }
+}
@@ -1,27 +1,60 @@
 use bitcoin::{
+    absolute,
+    block::Header,
+    consensus::encode::deserialize,
     hashes::Hash,
     hex::{Case::Upper, DisplayHex},
     key::Keypair,
-    Amount, Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
+    relative, Amount, BlockHash, Network, OutPoint, PublicKey, ScriptBuf, TapNodeHash, Transaction,
+    TxOut, Txid, Witness, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, TxStatus};
 use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt, io,
+    path::Path,
+};
+use strum::IntoEnumIterator;
 
 use crate::{
-    commitments::CommitmentMessageId,
+    commitments::{revealed_commitment, CommitmentMessageId},
     common::ZkProofVerifyingKey,
     connectors::{
-        connector_c::get_commit_from_assert_commit_tx, connector_d::ConnectorD,
-        connector_e::ConnectorE, connector_f_1::ConnectorF1, connector_f_2::ConnectorF2,
+        base::TaprootConnector,
+        connector_c::{
+            get_commit_from_assert_commit_tx, validate_commitment_witnesses, TaprootLeafProof,
+        },
+        connector_d::ConnectorD,
+        connector_e::ConnectorE,
+        connector_f_1::ConnectorF1,
+        connector_f_2::ConnectorF2,
+        descriptor::{
+            generate_p2wsh_descriptor, generate_raw_taproot_descriptor,
+            generate_taproot_descriptor, ConnectorDescriptor,
+        },
+        stack_usage::{
+            commitment_witness, placeholder_signature_witness, LeafStackUsage, StackUsageAnalyzer,
+        },
     },
-    error::{Error, GraphError, L2Error, NamedTx},
+    constants::{
+        DESTINATION_CHAIN_ID_MESSAGE_LENGTH, DESTINATION_NETWORK_TXID_LENGTH,
+        SOURCE_NETWORK_TXID_LENGTH, WITHDRAWAL_NONCE_MESSAGE_LENGTH,
+    },
+    contexts::base::generate_n_of_n_public_key,
+    error::{
+        ChunkerError, Error, GraphError, L2Error, MissingPrereq, MissingPrereqs, NamedTx,
+        TransactionError, ValidationError,
+    },
+    proof_binding::PegOutFacts,
+    protocol::ProtocolParamsId,
+    rewards::{default_reward_script, validate_reward_script},
     superblock::{
         find_superblock, get_start_time_block_number, get_superblock_hash_message,
-        get_superblock_message,
+        get_superblock_message, SUPERBLOCK_HASH_MESSAGE_LENGTH,
     },
     transactions::{
         assert_transactions::{
@@ -30,19 +63,37 @@ use crate::{
             assert_final::AssertFinalTransaction,
             assert_initial::AssertInitialTransaction,
             utils::{
-                groth16_commitment_secrets_to_public_keys, merge_to_connector_c_commits_public_key,
-                sign_assert_tx_with_groth16_proof, AssertCommit1ConnectorsE,
-                AssertCommit2ConnectorsE, AssertCommitConnectorsF,
+                canonical_input_order, check_committed_values, find_input_order_mismatch,
+                merge_to_connector_c_commits_public_key, sign_assert_tx_with_committed_values,
+                split_groth16_commitment_public_keys, AssertCommit1ConnectorsE,
+                AssertCommit2ConnectorsE, AssertCommitConnectorsF, CommittedIntermediateValues,
             },
         },
-        base::validate_witness,
+        auxiliary::{AuxiliaryConnector, AuxiliaryInput},
+        base::{
+            validate_witness, VSIZE_ASSERT_COMMIT1, VSIZE_ASSERT_COMMIT2, VSIZE_ASSERT_FINAL,
+            VSIZE_ASSERT_FINAL_CONSOLIDATED, VSIZE_ASSERT_INITIAL, VSIZE_COLLATERAL_INPUT,
+            VSIZE_DISPROVE, VSIZE_DISPROVE_CHAIN, VSIZE_KICK_OFF_1, VSIZE_KICK_OFF_1_COLLATERAL,
+            VSIZE_KICK_OFF_2, VSIZE_KICK_OFF_TIMEOUT, VSIZE_PEG_OUT_CONFIRM, VSIZE_START_TIME,
+            VSIZE_START_TIME_TIMEOUT, VSIZE_TAKE_1, VSIZE_TAKE_2, VSIZE_TAKE_2_CONSOLIDATED,
+        },
+        fee_sensitivity::{transaction_fee_sensitivity, FeeSensitivity},
         peg_in_confirm::PEG_IN_CONFIRM_TX_NAME,
-        pre_signed_musig2::PreSignedMusig2Transaction,
+        pre_signed_musig2::{
+            conflicting_contributions, CeremonyStatus, Musig2SigningProgress,
+            PreSignedMusig2Transaction,
+        },
+        signing_bundle::{SigningBundle, SigningBundleTransaction},
+        standardness::{lint_transaction, StandardnessViolation},
+        template_id::TemplateId,
     },
 };
 
 use bitvm::{
-    chunk::api::type_conversion_utils::RawProof,
+    chunk::api::{
+        type_conversion_utils::{RawProof, RawWitness},
+        NUM_TAPS,
+    },
     signatures::signing_winternitz::{
         WinternitzPublicKey, WinternitzSecret, WinternitzSigningInputs,
     },
@@ -50,7 +101,7 @@ use bitvm::{
 
 use super::{
     super::{
-        client::chain::chain::PegOutEvent,
+        client::chain::chain::{PegOutBurntEvent, PegOutEvent},
         connectors::{
             connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
             connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
@@ -60,13 +111,13 @@ use super::{
         contexts::{operator::OperatorContext, verifier::VerifierContext},
         transactions::{
             base::{
-                validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input,
-                InputWithScript,
+                validate_transaction, verify_committee_contributions, verify_public_nonces_for_tx,
+                BaseTransaction, Input, InputWithScript,
             },
             challenge::ChallengeTransaction,
             disprove::DisproveTransaction,
             disprove_chain::DisproveChainTransaction,
-            kick_off_1::KickOff1Transaction,
+            kick_off_1::{DestinationMetadataSigningInputs, KickOff1Transaction},
             kick_off_2::KickOff2Transaction,
             kick_off_timeout::KickOffTimeoutTransaction,
             peg_out::PegOutTransaction,
@@ -79,14 +130,19 @@ use super::{
         },
     },
     base::{
-        get_onchain_txs, get_tx_statuses, verify_if_not_mined, BaseGraph, GraphId,
-        CROWDFUNDING_AMOUNT, GRAPH_VERSION,
+        current_unix_time, get_onchain_txs, get_tx_statuses, transaction_dag, verify_if_not_mined,
+        BaseGraph, GraphId, GraphStage, PegInGraphId, PegOutGraphId, PresigningWindow,
+        CROWDFUNDING_AMOUNT, GRAPH_VERSION, SECONDS_PER_DAY,
+    },
+    integrity::{commitment_public_key_issues, GraphIntegrityIssue},
+    kick_off_race::{is_relative_timelock_met, KickOffPath, KickOffRace, KickOffTimelocks},
+    operator_commitments::{
+        check_collateral_input, OperatorCommitments, SignedOperatorCommitments,
     },
     peg_in::PegInGraph,
+    template::{GraphParameters, GraphTimelocks},
 };
 
-pub type PegOutId = GraphId;
-
 #[derive(derive_more::Display)]
 pub enum PegOutWithdrawerStatus {
     #[display("Peg-out available. Request peg-out?")]
@@ -125,6 +181,8 @@ pub enum PegOutVerifierStatus {
     PegOutDisproveAvailable,
     #[display("Peg-out complete, reimbursement failed. Done.")]
     PegOutFailed, // timeouts or disproves executed
+    #[display("Peg-out graph not pre-signed before its deadline. Abandoned.")]
+    PegOutAbandoned, // no more nonces or signatures are contributed
 }
 
 #[derive(derive_more::Display)]
@@ -160,6 +218,234 @@ pub enum PegOutOperatorStatus {
     PegOutTake2Available,
 }
 
+// Progress of a withdrawal as seen by the withdrawer, who can only watch the operator
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, derive_more::Display)]
+pub enum WithdrawalStage {
+    #[display(
+        "Peg-out requested. Waiting for the operator to broadcast the peg-out transaction..."
+    )]
+    PegOutRequested,
+    #[display("Peg-out transaction broadcast. Waiting for confirmation...")]
+    PegOutBroadcast,
+    #[display("Peg-out transaction confirmed. Done.")]
+    PegOutConfirmed,
+}
+
+// How far the operator got claiming back the funds it fronted for the withdrawal
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, derive_more::Display)]
+pub enum OperatorReimbursementStage {
+    #[display("Not started")]
+    NotStarted,
+    #[display("Kicked off")]
+    KickedOff,
+    #[display("Challenged")]
+    Challenged,
+    #[display("Reimbursed with take 1")]
+    ReimbursedTake1,
+    #[display("Reimbursed with take 2")]
+    ReimbursedTake2,
+    #[display("Failed")]
+    Failed, // timeouts or disproves executed
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct WithdrawalProgress {
+    pub graph_id: GraphId,
+    pub amount: Amount,
+    pub destination_address: String,
+    pub peg_out_txid: Option<Txid>,
+    pub confirmations: u32,
+    pub stage: WithdrawalStage,
+    pub reimbursement: OperatorReimbursementStage,
+}
+
+impl WithdrawalProgress {
+    // Whether the withdrawal moved on since `previous`, new confirmations alone do not count
+    pub fn has_advanced_from(&self, previous: &WithdrawalProgress) -> bool {
+        self.stage != previous.stage || self.reimbursement != previous.reimbursement
+    }
+}
+
+impl fmt::Display for WithdrawalProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Peg-out graph ID: {} Amount: {} Destination: {}\n  {}",
+            self.graph_id, self.amount, self.destination_address, self.stage
+        )?;
+        if let Some(txid) = self.peg_out_txid {
+            write!(
+                f,
+                "\n  Peg-out txid: {txid} ({} confirmations)",
+                self.confirmations
+            )?;
+        }
+        write!(f, "\n  Operator reimbursement: {}", self.reimbursement)
+    }
+}
+
+// Emitted when tracking a withdrawal finds it for the first time or finds that it advanced
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct WithdrawalEvent {
+    pub previous: Option<WithdrawalProgress>,
+    pub progress: WithdrawalProgress,
+}
+
+// Peg-out transactions pre-signed by the n-of-n verifier committee, which may have to be re-signed
+// if they change before being broadcast.
+#[derive(
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum PegOutPresignedTransaction {
+    AssertInitial,
+    AssertFinal,
+    DisproveChain,
+    Disprove,
+    KickOffTimeout,
+    StartTimeTimeout,
+    Take1,
+    Take2,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegOutResignStatus {
+    pub transaction: PegOutPresignedTransaction,
+    pub template_id: TemplateId,
+    pub round: u32,
+    pub signed: Vec<PublicKey>,
+    pub pending: Vec<PublicKey>,
+}
+
+// How a verifier makes sure the connector C merkle root claimed by the operator is correct before
+// pre-signing the graph. A root already in the local cache is compared directly either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MerkleRootVerification {
+    // Generate all the lock scripts and derive the root from them
+    #[default]
+    Regenerate,
+    // Request the scripts and merkle proofs of random leaves from the operator through the data
+    // store and check those
+    SpotCheck {
+        sample_size: usize,
+    },
+}
+
+// Outcome of checking the proof the operator committed to in the assert commit transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofVerification {
+    Valid,
+    Invalid,              // a chunk fails, the graph can be disproven
+    Inconclusive(String), // the assertions are not on chain yet or could not be evaluated
+}
+
+// Whether a started peg-out was challenged, and the take transaction the operator is waiting for
+// with the height it can be broadcast from, see `PegOutGraph::dispute_status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DisputeStatus {
+    pub challenged: bool,
+    pub take_window: Option<(PegOutPresignedTransaction, u32)>,
+}
+
+// What the operator committed to in the confirmed start time and kick-off 2 transactions, which a
+// disprove chain transaction is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommittedSuperblock {
+    pub start_time: u32,
+    pub superblock: Header,
+    pub superblock_hash: BlockHash,
+    pub start_time_height: u32,
+    pub kick_off_2_height: u32,
+}
+
+// Index of the failing connector C leaf and the witness executing it
+pub type DisproveWitness = (usize, RawWitness);
+
+// Witnesses of the assert commit transactions as confirmed on chain
+#[derive(Debug, Clone)]
+pub struct AssertedCommitments {
+    pub commit_1_witness: Vec<RawWitness>,
+    pub commit_2_witness: Vec<RawWitness>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum AssertCommit {
+    #[display("Assert commit 1")]
+    Commit1,
+    #[display("Assert commit 2")]
+    Commit2,
+}
+
+// Confirmation of one assert commit transaction, see `PegOutGraph::assert_commit_progress`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertCommitProgress {
+    pub commit: AssertCommit,
+    pub txid: Txid,
+    pub confirmed_height: Option<u32>,
+    // Signed by the operator before, broadcasting it again repeats the witness of that attempt
+    pub signed: bool,
+}
+
+// Runs the chunker over the asserted proof, which takes minutes. The disprove witness is returned
+// for invalid proofs, so the disprove transaction can be built without running it again.
+pub fn evaluate_asserted_proof(
+    connector_c: &ConnectorC,
+    commitments: AssertedCommitments,
+    verifying_key: &ZkProofVerifyingKey,
+) -> (ProofVerification, Option<DisproveWitness>) {
+    match connector_c.generate_disprove_witness(
+        commitments.commit_1_witness,
+        commitments.commit_2_witness,
+        verifying_key,
+    ) {
+        Ok(disprove_witness) => (ProofVerification::Invalid, Some(disprove_witness)),
+        Err(Error::Chunker(ChunkerError::ValidProof)) => (ProofVerification::Valid, None),
+        Err(e) => (ProofVerification::Inconclusive(e.to_string()), None),
+    }
+}
+
+// What a verifier in automatic mode does about a kicked-off peg-out whose proof cannot be checked
+// yet. The operator only has to commit to the proof once challenged, so this is the usual case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ChallengePolicy {
+    // Challenge, forcing the operator to assert the proof
+    Challenge,
+    // Leave the challenge to others, only challenge proofs known to be invalid
+    #[default]
+    Wait,
+}
+
+impl ChallengePolicy {
+    pub fn should_challenge(&self, verification: &ProofVerification) -> bool {
+        match verification {
+            ProofVerification::Valid => false,
+            ProofVerification::Invalid => true,
+            ProofVerification::Inconclusive(_) => *self == ChallengePolicy::Challenge,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerkleRootStatus {
+    Verified,
+    PendingSpotCheck,        // the verifier has to request leaf proofs
+    AwaitingSpotCheckProofs, // waiting for the operator to supply the requested leaf proofs
+    Invalid(String),         // the graph must not be signed
+}
+
 struct PegOutConnectors {
     connector_0: Connector0,
     connector_1: Connector1,
@@ -181,15 +467,17 @@ struct PegOutConnectors {
 pub struct PegOutGraph {
     version: String,
     network: Network,
-    id: String,
+    id: PegOutGraphId,
 
     // state: State,
     // n_of_n_pre_signing_state: PreSigningState,
     n_of_n_presigned: bool,
+    #[serde(with = "crate::serialization::hex_encoded")]
     n_of_n_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     n_of_n_taproot_public_key: XOnlyPublicKey,
 
-    pub peg_in_graph_id: String,
+    pub peg_in_graph_id: PegInGraphId,
     peg_in_confirm_txid: Txid,
 
     // Note that only the connectors that are used with message commitments are
@@ -228,11 +516,40 @@ pub struct PegOutGraph {
     take_1_transaction: Take1Transaction,
     take_2_transaction: Take2Transaction,
 
+    #[serde(with = "crate::serialization::hex_encoded")]
     operator_public_key: PublicKey,
+    #[serde(with = "crate::serialization::hex_encoded")]
     operator_taproot_public_key: XOnlyPublicKey,
+    // Pays the reimbursement of take 1 and take 2. Graphs created before it was configurable pay
+    // to the pay to pubkey script of the operator key.
+    #[serde(default, with = "crate::serialization::hex_encoded_option")]
+    operator_reward_script: Option<ScriptBuf>,
 
     pub peg_out_chain_event: Option<PegOutEvent>,
     pub peg_out_transaction: Option<PegOutTransaction>,
+
+    // Number of times each pre-signed transaction was invalidated for re-signing. Nonces and
+    // signatures from a lower round are stale and get dropped on merge.
+    #[serde(default)]
+    resign_rounds: BTreeMap<PegOutPresignedTransaction, u32>,
+
+    // Graphs created before templates existed used the standard parameters
+    #[serde(default)]
+    parameters: GraphParameters,
+
+    // Connector C merkle root spot checks. Verifier public key -> requested leaf indexes
+    #[serde(default)]
+    merkle_root_spot_check_requests: BTreeMap<PublicKey, BTreeSet<usize>>,
+    // Leaf index -> leaf script and merkle proof supplied by the operator
+    #[serde(default)]
+    merkle_root_spot_check_proofs: BTreeMap<usize, TaprootLeafProof>,
+
+    #[serde(default)]
+    presigning_window: PresigningWindow,
+
+    // None for graphs created before protocol parameters were recorded
+    #[serde(default)]
+    protocol_params: Option<ProtocolParamsId>,
 }
 
 impl BaseGraph for PegOutGraph {
@@ -248,59 +565,39 @@ impl BaseGraph for PegOutGraph {
         &mut self,
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
-        self.assert_initial_transaction.pre_sign(
-            verifier_context,
-            &self.connector_b,
-            &secret_nonces[&self.assert_initial_transaction.tx().compute_txid()],
-        );
-        self.assert_final_transaction.pre_sign(
-            verifier_context,
-            &self.connector_d,
-            &secret_nonces[&self.assert_final_transaction.tx().compute_txid()],
-        );
-        self.disprove_chain_transaction.pre_sign(
-            verifier_context,
-            &self.connector_b,
-            &secret_nonces[&self.disprove_chain_transaction.tx().compute_txid()],
-        );
-        self.disprove_transaction.pre_sign(
-            verifier_context,
-            &self.connector_5,
-            &secret_nonces[&self.disprove_transaction.tx().compute_txid()],
-        );
-        self.kick_off_timeout_transaction.pre_sign(
-            verifier_context,
-            &self.connector_1,
-            &secret_nonces[&self.kick_off_timeout_transaction.tx().compute_txid()],
-        );
-        self.start_time_timeout_transaction.pre_sign(
-            verifier_context,
-            &self.connector_1,
-            &self.connector_2,
-            &secret_nonces[&self.start_time_timeout_transaction.tx().compute_txid()],
-        );
-        self.take_1_transaction.pre_sign(
-            verifier_context,
-            &self.connector_0,
-            &self.connector_b,
-            &secret_nonces[&self.take_1_transaction.tx().compute_txid()],
-        );
-        self.take_2_transaction.pre_sign(
-            verifier_context,
-            &self.connector_0,
-            &self.connector_5,
-            &secret_nonces[&self.take_2_transaction.tx().compute_txid()],
-        );
+    ) -> Result<(), Error> {
+        for transaction in PegOutPresignedTransaction::iter() {
+            self.pre_sign_transaction(transaction, verifier_context, secret_nonces)?;
+        }
 
         self.n_of_n_presigned = true; // TODO: set to true after collecting all n of n signatures
+
+        Ok(())
     }
 
+    // Resigning rounds are only started on graphs that were pre-signed in time
+    fn is_abandoned(&self, verifier_pubkeys: &[PublicKey], now: u64) -> bool {
+        self.presigning_window.has_expired(now)
+            && self.resign_rounds.is_empty()
+            && !self.has_all_signatures(verifier_pubkeys)
+    }
+
+    fn protocol_params(&self) -> Option<&ProtocolParamsId> {
+        self.protocol_params.as_ref()
+    }
+
+    fn check_segment_layout(&self) -> Result<(), ChunkerError> {
+        self.connector_c.check_segment_layout()
+    }
+
+    // Nonces already pushed are never replaced, the other committee members may have signed with
+    // them
     fn push_verifier_nonces(
         &mut self,
         verifier_context: &VerifierContext,
     ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
         self.all_presigned_txs_mut()
+            .filter(|tx_wrapper| !tx_wrapper.has_nonces_for(verifier_context.verifier_public_key))
             .map(|tx_wrapper| {
                 (
                     tx_wrapper.tx().compute_txid(),
@@ -317,76 +614,163 @@ impl PegOutGraph {
         peg_in_graph: &PegInGraph,
         peg_out_confirm_input: Input,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
-    ) -> Self {
+        parameters: GraphParameters,
+    ) -> Result<Self, Error> {
+        Self::new_with_collateral(
+            context,
+            peg_in_graph,
+            peg_out_confirm_input,
+            None,
+            commitment_secrets,
+            parameters,
+        )
+    }
+
+    // `collateral_input` is an operator UTXO funding the collateral output of kick-off 1. It is
+    // required if the parameters ask for operator collateral and must cover
+    // `GraphParameters::operator_collateral_funding_amount`, anything above is posted as well.
+    pub fn new_with_collateral(
+        context: &OperatorContext,
+        peg_in_graph: &PegInGraph,
+        peg_out_confirm_input: Input,
+        collateral_input: Option<Input>,
+        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        parameters: GraphParameters,
+    ) -> Result<Self, Error> {
+        let commitments = OperatorCommitments::new(
+            context,
+            peg_in_graph.peg_in_graph_id(),
+            peg_out_confirm_input,
+            collateral_input,
+            commitment_secrets,
+            parameters,
+        );
+        let mut peg_out_graph = Self::new_unsigned(peg_in_graph, &commitments)?;
+        peg_out_graph.sign_operator_inputs(context);
+
+        Ok(peg_out_graph)
+    }
+
+    // Graph of a cold operator, built by a coordinator from the commitments the operator signed
+    // offline. It has no operator signatures until the operator calls `pre_sign_operator_inputs`,
+    // the verifiers can pre-sign it in the meantime.
+    pub fn new_from_operator_commitments(
+        peg_in_graph: &PegInGraph,
+        signed_commitments: &SignedOperatorCommitments,
+    ) -> Result<Self, Error> {
+        if !signed_commitments.verify_signature() {
+            return Err(Error::Validation(
+                ValidationError::InvalidOperatorCommitments(String::from(
+                    "operator signature does not verify",
+                )),
+            ));
+        }
+        signed_commitments
+            .commitments
+            .validate(peg_in_graph)
+            .map_err(Error::Validation)?;
+
+        Self::new_unsigned(peg_in_graph, &signed_commitments.commitments)
+    }
+
+    // Builds the graph from public operator data only, no transaction is signed by the operator
+    fn new_unsigned(
+        peg_in_graph: &PegInGraph,
+        commitments: &OperatorCommitments,
+    ) -> Result<Self, Error> {
+        let network = commitments.network;
+        let parameters = commitments.parameters;
+        let (n_of_n_public_key, n_of_n_taproot_public_key) = commitments.n_of_n_public_key();
+        let operator_public_key = commitments.operator_public_key;
+        let operator_taproot_public_key = commitments.operator_taproot_public_key;
+        let commitment_public_keys = &commitments.commitment_public_keys;
+        check_collateral_input(&parameters, commitments.collateral_input.as_ref())
+            .map_err(|reason| Error::Validation(ValidationError::InvalidGraphParameters(reason)))?;
+
+        let assert_final_output_layout = parameters.assert_final_output_layout;
         let peg_in_confirm_transaction = peg_in_graph.peg_in_confirm_transaction_ref();
         let peg_in_confirm_txid = peg_in_confirm_transaction.tx().compute_txid();
 
-        let connector_1_commitment_public_keys = HashMap::from([
+        let connector_1_commitment_public_keys = BTreeMap::from([
             (
                 CommitmentMessageId::Superblock,
-                WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::Superblock]),
+                commitment_public_keys[&CommitmentMessageId::Superblock].clone(),
             ),
             (
                 CommitmentMessageId::SuperblockHash,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::SuperblockHash],
-                ),
+                commitment_public_keys[&CommitmentMessageId::SuperblockHash].clone(),
             ),
         ]);
-        let connector_2_commitment_public_keys = HashMap::from([(
+        let connector_2_commitment_public_keys = BTreeMap::from([(
             CommitmentMessageId::StartTime,
-            WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
+            commitment_public_keys[&CommitmentMessageId::StartTime].clone(),
         )]);
-        let connector_6_commitment_public_keys = HashMap::from([
+        let mut connector_6_commitment_public_keys = BTreeMap::from([
             (
                 CommitmentMessageId::PegOutTxIdSourceNetwork,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
-                ),
+                commitment_public_keys[&CommitmentMessageId::PegOutTxIdSourceNetwork].clone(),
             ),
             (
                 CommitmentMessageId::PegOutTxIdDestinationNetwork,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-                ),
+                commitment_public_keys[&CommitmentMessageId::PegOutTxIdDestinationNetwork].clone(),
             ),
         ]);
-        let connector_b_commitment_public_keys = HashMap::from([
+        if parameters
+            .kick_off_1_commitments
+            .includes_destination_metadata()
+        {
+            for message_id in [
+                CommitmentMessageId::DestinationChainId,
+                CommitmentMessageId::WithdrawalNonce,
+            ] {
+                let public_key = commitment_public_keys[&message_id].clone();
+                connector_6_commitment_public_keys.insert(message_id, public_key);
+            }
+        }
+        let connector_b_commitment_public_keys = BTreeMap::from([
             (
                 CommitmentMessageId::StartTime,
-                WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
+                commitment_public_keys[&CommitmentMessageId::StartTime].clone(),
             ),
             (
                 CommitmentMessageId::SuperblockHash,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::SuperblockHash],
-                ),
+                commitment_public_keys[&CommitmentMessageId::SuperblockHash].clone(),
             ),
         ]);
 
         let (connector_e1_commitment_public_keys, connector_e2_commitment_public_keys) =
-            groth16_commitment_secrets_to_public_keys(commitment_secrets);
+            split_groth16_commitment_public_keys(commitment_public_keys);
 
         let connectors = Self::create_new_connectors(
-            context.network,
-            &context.n_of_n_taproot_public_key,
-            &context.operator_taproot_public_key,
-            &context.operator_public_key,
+            network,
+            &parameters.timelocks,
+            &n_of_n_taproot_public_key,
+            &operator_taproot_public_key,
+            &operator_public_key,
             &connector_1_commitment_public_keys,
             &connector_2_commitment_public_keys,
             &connector_6_commitment_public_keys,
             &connector_b_commitment_public_keys,
             &connector_e1_commitment_public_keys,
             &connector_e2_commitment_public_keys,
+            None,
         );
 
-        let peg_out_confirm_transaction =
-            PegOutConfirmTransaction::new(context, &connectors.connector_6, peg_out_confirm_input);
+        let peg_out_confirm_transaction = PegOutConfirmTransaction::new_for_validation(
+            network,
+            &operator_public_key,
+            &connectors.connector_6,
+            commitments.peg_out_confirm_input,
+        );
         let peg_out_confirm_txid = peg_out_confirm_transaction.tx().compute_txid();
 
         let kick_off_1_vout_0 = 0;
-        let kick_off_1_transaction = KickOff1Transaction::new(
-            context,
+        let kick_off_1_transaction = KickOff1Transaction::new_for_validation(
+            network,
+            &operator_public_key,
+            &operator_taproot_public_key,
+            &n_of_n_taproot_public_key,
+            &connectors.connector_0,
             &connectors.connector_1,
             &connectors.connector_2,
             &connectors.connector_6,
@@ -397,12 +781,16 @@ impl PegOutGraph {
                 },
                 amount: peg_out_confirm_transaction.tx().output[kick_off_1_vout_0].value,
             },
+            commitments.collateral_input,
+            parameters.fee_rate,
         );
         let kick_off_1_txid = kick_off_1_transaction.tx().compute_txid();
+        let collateral_input = Self::collateral_input(&kick_off_1_transaction);
 
         let start_time_vout_0 = 2;
-        let start_time_transaction = StartTimeTransaction::new(
-            context,
+        let start_time_transaction = StartTimeTransaction::new_for_validation(
+            network,
+            &operator_public_key,
             &connectors.connector_2,
             Input {
                 outpoint: OutPoint {
@@ -411,12 +799,14 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[start_time_vout_0].value,
             },
+            parameters.fee_rate,
         );
 
         let start_time_timeout_vout_0 = 2;
         let start_time_timeout_vout_1 = 1;
-        let start_time_timeout_transaction = StartTimeTimeoutTransaction::new(
-            context,
+        let start_time_timeout_transaction = StartTimeTimeoutTransaction::new_for_validation(
+            network,
+            &connectors.connector_0,
             &connectors.connector_1,
             &connectors.connector_2,
             Input {
@@ -433,12 +823,13 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[start_time_timeout_vout_1].value,
             },
+            collateral_input,
         );
 
         let kick_off_2_vout_0 = 1;
         let kick_off_2_transaction = KickOff2Transaction::new(
-            context,
             &connectors.connector_1,
+            &connectors.connector_3,
             &connectors.connector_b,
             Input {
                 outpoint: OutPoint {
@@ -447,12 +838,14 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_2_vout_0].value,
             },
+            parameters.fee_rate,
         );
         let kick_off_2_txid = kick_off_2_transaction.tx().compute_txid();
 
         let kick_off_timeout_vout_0 = 1;
-        let kick_off_timeout_transaction = KickOffTimeoutTransaction::new(
-            context,
+        let kick_off_timeout_transaction = KickOffTimeoutTransaction::new_for_validation(
+            network,
+            &connectors.connector_0,
             &connectors.connector_1,
             Input {
                 outpoint: OutPoint {
@@ -461,12 +854,14 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_timeout_vout_0].value,
             },
+            collateral_input,
         );
 
         let input_amount_crowdfunding = Amount::from_btc(CROWDFUNDING_AMOUNT).unwrap();
         let challenge_vout_0 = 0;
-        let challenge_transaction = ChallengeTransaction::new(
-            context,
+        let challenge_transaction = ChallengeTransaction::new_for_validation(
+            network,
+            &operator_public_key,
             &connectors.connector_a,
             Input {
                 outpoint: OutPoint {
@@ -482,8 +877,8 @@ impl PegOutGraph {
         let take_1_vout_1 = 0;
         let take_1_vout_2 = 0;
         let take_1_vout_3 = 1;
-        let take_1_transaction = Take1Transaction::new(
-            context,
+        let take_1_transaction = Take1Transaction::new_for_validation(
+            &commitments.reward_script,
             &connectors.connector_0,
             &connectors.connector_3,
             &connectors.connector_a,
@@ -516,6 +911,8 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[take_1_vout_3].value,
             },
+            collateral_input,
+            parameters.fee_rate,
         );
 
         // assert initial
@@ -532,6 +929,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[assert_initial_vout_0].value,
             },
+            parameters.fee_rate,
         );
         let assert_initial_txid = assert_initial_transaction.tx().compute_txid();
 
@@ -549,6 +947,7 @@ impl PegOutGraph {
                     amount: assert_initial_transaction.tx().output[idx + vout_base].value,
                 })
                 .collect(),
+            parameters.fee_rate,
         );
 
         vout_base += connectors.assert_commit_connectors_e_1.connectors_num();
@@ -565,14 +964,14 @@ impl PegOutGraph {
                     amount: assert_initial_transaction.tx().output[idx + vout_base].value,
                 })
                 .collect(),
+            parameters.fee_rate,
         );
 
         // assert final
         let assert_final_vout_0 = 0;
         let assert_final_vout_1 = 0;
         let assert_final_vout_2 = 0;
-        let assert_final_transaction = AssertFinalTransaction::new(
-            context,
+        let assert_final_transaction = AssertFinalTransaction::new_for_validation(
             &connectors.connector_4,
             &connectors.connector_5,
             &connectors.connector_c,
@@ -599,15 +998,17 @@ impl PegOutGraph {
                 },
                 amount: assert_commit_2_transaction.tx().output[assert_final_vout_2].value,
             },
+            assert_final_output_layout,
+            parameters.fee_rate,
         );
         let assert_final_txid = assert_final_transaction.tx().compute_txid();
 
         let take_2_vout_0 = 0;
-        let take_2_vout_1 = 0;
-        let take_2_vout_2 = 1;
-        let take_2_vout_3 = 2;
-        let take_2_transaction = Take2Transaction::new(
-            context,
+        let take_2_vout_1 = assert_final_output_layout.connector_4_vout();
+        let take_2_vout_2 = assert_final_output_layout.connector_5_vout();
+        let take_2_vout_3 = assert_final_output_layout.connector_c_vout();
+        let take_2_transaction = Take2Transaction::new_for_validation(
+            &commitments.reward_script,
             &connectors.connector_0,
             &connectors.connector_4,
             &connectors.connector_5,
@@ -619,13 +1020,13 @@ impl PegOutGraph {
                 },
                 amount: peg_in_confirm_transaction.tx().output[take_2_vout_0].value,
             },
-            Input {
+            take_2_vout_1.map(|take_2_vout_1| Input {
                 outpoint: OutPoint {
                     txid: assert_final_txid,
                     vout: take_2_vout_1.to_u32().unwrap(),
                 },
                 amount: assert_final_transaction.tx().output[take_2_vout_1].value,
-            },
+            }),
             Input {
                 outpoint: OutPoint {
                     txid: assert_final_txid,
@@ -640,12 +1041,15 @@ impl PegOutGraph {
                 },
                 amount: assert_final_transaction.tx().output[take_2_vout_3].value,
             },
+            collateral_input,
+            parameters.fee_rate,
         );
 
-        let disprove_vout_0 = 1;
-        let disprove_vout_1 = 2;
-        let disprove_transaction = DisproveTransaction::new(
-            context,
+        let disprove_vout_0 = assert_final_output_layout.connector_5_vout();
+        let disprove_vout_1 = assert_final_output_layout.connector_c_vout();
+        let disprove_transaction = DisproveTransaction::new_for_validation(
+            network,
+            &connectors.connector_0,
             &connectors.connector_5,
             &connectors.connector_c,
             Input {
@@ -662,11 +1066,14 @@ impl PegOutGraph {
                 },
                 amount: assert_final_transaction.tx().output[disprove_vout_1].value,
             },
+            parameters.disprove_burn_percentage,
+            collateral_input,
         );
 
         let disprove_chain_vout_0 = 1;
-        let disprove_chain_transaction = DisproveChainTransaction::new(
-            context,
+        let disprove_chain_transaction = DisproveChainTransaction::new_for_validation(
+            network,
+            &connectors.connector_0,
             &connectors.connector_b,
             Input {
                 outpoint: OutPoint {
@@ -675,16 +1082,18 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[disprove_chain_vout_0].value,
             },
+            parameters.disprove_burn_percentage,
+            collateral_input,
         );
 
-        PegOutGraph {
+        Ok(PegOutGraph {
             version: GRAPH_VERSION.to_string(),
-            network: context.network,
-            id: generate_id(peg_in_graph, &context.operator_public_key),
+            network,
+            id: generate_id(peg_in_graph, &operator_public_key),
             n_of_n_presigned: false,
-            n_of_n_public_key: context.n_of_n_public_key,
-            n_of_n_taproot_public_key: context.n_of_n_taproot_public_key,
-            peg_in_graph_id: peg_in_graph.id().clone(),
+            n_of_n_public_key,
+            n_of_n_taproot_public_key,
+            peg_in_graph_id: peg_in_graph.peg_in_graph_id().clone(),
             peg_in_confirm_txid,
             connector_0: connectors.connector_0,
             connector_1: connectors.connector_1,
@@ -716,11 +1125,162 @@ impl PegOutGraph {
             start_time_timeout_transaction,
             take_1_transaction,
             take_2_transaction,
-            operator_public_key: context.operator_public_key,
-            operator_taproot_public_key: context.operator_taproot_public_key,
+            operator_public_key,
+            operator_taproot_public_key,
+            operator_reward_script: Some(commitments.reward_script.clone()),
             peg_out_chain_event: None,
             peg_out_transaction: None,
+            resign_rounds: BTreeMap::new(),
+            parameters,
+            merkle_root_spot_check_requests: BTreeMap::new(),
+            merkle_root_spot_check_proofs: BTreeMap::new(),
+            presigning_window: PresigningWindow::starting_now(parameters.presigning_expiry_days),
+            protocol_params: Some(ProtocolParamsId::current()),
+        })
+    }
+
+    fn sign_operator_inputs(&mut self, context: &OperatorContext) {
+        self.peg_out_confirm_transaction
+            .sign_operator_inputs(context);
+        self.challenge_transaction
+            .sign_operator_inputs(context, &self.connector_a);
+        self.take_1_transaction
+            .sign_operator_inputs(context, &self.connector_a);
+        self.take_2_transaction.sign_operator_inputs(context);
+        self.assert_final_transaction.sign_operator_inputs(context);
+    }
+
+    // Graphs built from operator commitments lack the operator signatures until the operator
+    // pre-signs them
+    pub fn has_operator_signatures(&self) -> bool {
+        !self.peg_out_confirm_transaction.tx().input[0]
+            .witness
+            .is_empty()
+    }
+
+    // Adds the operator signatures to a graph built from its commitments. The graph is rebuilt from
+    // the operator's own secrets first, the operator only signs if every transaction matches.
+    pub fn pre_sign_operator_inputs(
+        &mut self,
+        context: &OperatorContext,
+        peg_in_graph: &PegInGraph,
+        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    ) -> Result<(), Error> {
+        if context.operator_public_key != self.operator_public_key {
+            return Err(Error::Validation(ValidationError::OperatorGraphMismatch(
+                "operator public key",
+            )));
+        }
+
+        let peg_out_confirm_vout_0 = 0;
+        let mut commitments = OperatorCommitments::new(
+            context,
+            peg_in_graph.peg_in_graph_id(),
+            Input {
+                outpoint: self.peg_out_confirm_transaction.tx().input[peg_out_confirm_vout_0]
+                    .previous_output,
+                amount: self.peg_out_confirm_transaction.prev_outs()[peg_out_confirm_vout_0].value,
+            },
+            self.kick_off_1_transaction
+                .collateral_vout()
+                .map(|_| Input {
+                    outpoint: self.kick_off_1_transaction.tx().input[1].previous_output,
+                    amount: self.kick_off_1_transaction.prev_outs()[1].value,
+                }),
+            commitment_secrets,
+            self.parameters,
+        );
+        // The reward script the graph was created with, the operator may have changed it since
+        commitments.reward_script = self.operator_reward_script();
+        let expected = Self::new_unsigned(peg_in_graph, &commitments)?;
+
+        if let Some(name) = self
+            .template_txids()
+            .into_iter()
+            .zip(expected.template_txids())
+            .find_map(|((name, txid), (_, expected_txid))| (txid != expected_txid).then_some(name))
+        {
+            return Err(Error::Validation(ValidationError::OperatorGraphMismatch(
+                name,
+            )));
         }
+
+        self.sign_operator_inputs(context);
+
+        Ok(())
+    }
+
+    // Name and txid of every transaction of the graph, including the peg-out once it is created
+    pub fn txids(&self) -> Vec<(&'static str, Txid)> {
+        let peg_out = self.peg_out_transaction.as_ref().map(template_txid);
+
+        peg_out.into_iter().chain(self.template_txids()).collect()
+    }
+
+    pub fn is_graph_tx(&self, tx: &Transaction) -> bool {
+        self.graph_tx_name(tx).is_some()
+    }
+
+    // Transactions completed at broadcast, like a crowdfunded challenge or a disprove with its
+    // reward output, have another txid than their template and are matched by the first output
+    // they spend instead
+    pub fn graph_tx_name(&self, tx: &Transaction) -> Option<&'static str> {
+        let txid = tx.compute_txid();
+        let first_outpoint = tx.input.first().map(|input| input.previous_output);
+
+        self.txids()
+            .into_iter()
+            .find_map(|(name, graph_txid)| (graph_txid == txid).then_some(name))
+            .or_else(|| {
+                self.template_txs()
+                    .into_iter()
+                    .find_map(|(name, template_tx)| {
+                        (template_tx.input.first().map(|input| input.previous_output)
+                            == first_outpoint)
+                            .then_some(name)
+                    })
+            })
+    }
+
+    // Txids of every pre-built transaction of the graph, they do not depend on any signature
+    fn template_txids(&self) -> [(&'static str, Txid); 15] {
+        self.template_txs()
+            .map(|(name, tx)| (name, tx.compute_txid()))
+    }
+
+    fn template_txs(&self) -> [(&'static str, &Transaction); 15] {
+        [
+            template_tx(&self.peg_out_confirm_transaction),
+            template_tx(&self.kick_off_1_transaction),
+            template_tx(&self.kick_off_2_transaction),
+            template_tx(&self.kick_off_timeout_transaction),
+            template_tx(&self.start_time_transaction),
+            template_tx(&self.start_time_timeout_transaction),
+            template_tx(&self.challenge_transaction),
+            template_tx(&self.assert_initial_transaction),
+            template_tx(&self.assert_commit_1_transaction),
+            template_tx(&self.assert_commit_2_transaction),
+            template_tx(&self.assert_final_transaction),
+            template_tx(&self.disprove_chain_transaction),
+            template_tx(&self.disprove_transaction),
+            template_tx(&self.take_1_transaction),
+            template_tx(&self.take_2_transaction),
+        ]
+    }
+
+    // See `transaction_dag`, a peg-out spends no transaction of the graph and is left out
+    pub fn transaction_dag(&self) -> Vec<(&'static str, Vec<&'static str>)> {
+        transaction_dag(&self.template_txs())
+    }
+
+    // Output scripts of the pre-built transactions, every other transaction of the graph, like a
+    // challenge or a disprove, spends one of them
+    pub fn template_output_scripts(&self) -> BTreeSet<ScriptBuf> {
+        self.template_txs()
+            .iter()
+            .flat_map(|(_, tx)| tx.output.iter())
+            .map(|output| output.script_pubkey.clone())
+            .collect()
     }
 
     pub fn new_for_validation(&self) -> Self {
@@ -728,6 +1288,7 @@ impl PegOutGraph {
 
         let connectors = Self::create_new_connectors(
             self.network,
+            &self.parameters.timelocks,
             &self.n_of_n_taproot_public_key,
             &self.operator_taproot_public_key,
             &self.operator_public_key,
@@ -737,6 +1298,7 @@ impl PegOutGraph {
             &self.connector_b.commitment_public_keys,
             &self.connector_e_1.commitment_public_keys(),
             &self.connector_e_2.commitment_public_keys(),
+            self.connector_c.claimed_taproot_merkle_root(),
         );
 
         let peg_out_confirm_vout_0 = 0;
@@ -752,10 +1314,13 @@ impl PegOutGraph {
         );
 
         let kick_off_1_vout_0 = 0;
+        let kick_off_1_vout_1 = 1;
         let kick_off_1_transaction = KickOff1Transaction::new_for_validation(
             self.network,
+            &self.operator_public_key,
             &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
+            &connectors.connector_0,
             &connectors.connector_1,
             &connectors.connector_2,
             &connectors.connector_6,
@@ -763,8 +1328,17 @@ impl PegOutGraph {
                 outpoint: self.kick_off_1_transaction.tx().input[kick_off_1_vout_0].previous_output, // Self-referencing
                 amount: self.kick_off_1_transaction.prev_outs()[kick_off_1_vout_0].value, // Self-referencing
             },
+            self.kick_off_1_transaction
+                .collateral_vout()
+                .map(|_| Input {
+                    outpoint: self.kick_off_1_transaction.tx().input[kick_off_1_vout_1]
+                        .previous_output, // Self-referencing
+                    amount: self.kick_off_1_transaction.prev_outs()[kick_off_1_vout_1].value, // Self-referencing
+                }),
+            self.parameters.fee_rate,
         );
         let kick_off_1_txid = kick_off_1_transaction.tx().compute_txid();
+        let collateral_input = Self::collateral_input(&kick_off_1_transaction);
 
         let start_time_vout_0 = 2;
         let start_time_transaction = StartTimeTransaction::new_for_validation(
@@ -778,12 +1352,14 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[start_time_vout_0].value,
             },
+            self.parameters.fee_rate,
         );
 
         let start_time_timeout_vout_0 = 2;
         let start_time_timeout_vout_1 = 1;
         let start_time_timeout_transaction = StartTimeTimeoutTransaction::new_for_validation(
             self.network,
+            &connectors.connector_0,
             &connectors.connector_1,
             &connectors.connector_2,
             Input {
@@ -800,13 +1376,13 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[start_time_timeout_vout_1].value,
             },
+            collateral_input,
         );
 
         let kick_off_2_vout_0 = 1;
         let kick_off_2_transaction = KickOff2Transaction::new_for_validation(
-            self.network,
-            &self.operator_public_key,
             &connectors.connector_1,
+            &connectors.connector_3,
             &connectors.connector_b,
             Input {
                 outpoint: OutPoint {
@@ -815,12 +1391,14 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_2_vout_0].value,
             },
+            self.parameters.fee_rate,
         );
         let kick_off_2_txid = kick_off_2_transaction.tx().compute_txid();
 
         let kick_off_timeout_vout_0 = 1;
         let kick_off_timeout_transaction = KickOffTimeoutTransaction::new_for_validation(
             self.network,
+            &connectors.connector_0,
             &connectors.connector_1,
             Input {
                 outpoint: OutPoint {
@@ -829,6 +1407,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_timeout_vout_0].value,
             },
+            collateral_input,
         );
 
         let input_amount_crowdfunding = Amount::from_btc(CROWDFUNDING_AMOUNT).unwrap();
@@ -852,8 +1431,7 @@ impl PegOutGraph {
         let take_1_vout_2 = 0;
         let take_1_vout_3 = 1;
         let take_1_transaction = Take1Transaction::new_for_validation(
-            self.network,
-            &self.operator_public_key,
+            &self.operator_reward_script(),
             &connectors.connector_0,
             &connectors.connector_3,
             &connectors.connector_a,
@@ -886,6 +1464,8 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[take_1_vout_3].value,
             },
+            collateral_input,
+            self.parameters.fee_rate,
         );
 
         // assert initial
@@ -902,6 +1482,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[assert_initial_vout_0].value,
             },
+            self.parameters.fee_rate,
         );
         let assert_initial_txid = assert_initial_transaction.tx().compute_txid();
 
@@ -919,6 +1500,7 @@ impl PegOutGraph {
                     amount: assert_initial_transaction.tx().output[idx + vout_base].value,
                 })
                 .collect(),
+            self.parameters.fee_rate,
         );
 
         vout_base += connectors.assert_commit_connectors_e_1.connectors_num();
@@ -935,6 +1517,7 @@ impl PegOutGraph {
                     amount: assert_initial_transaction.tx().output[idx + vout_base].value,
                 })
                 .collect(),
+            self.parameters.fee_rate,
         );
 
         // assert final
@@ -968,16 +1551,18 @@ impl PegOutGraph {
                 },
                 amount: assert_commit_2_transaction.tx().output[assert_final_vout_2].value,
             },
+            self.assert_final_transaction.output_layout(),
+            self.parameters.fee_rate,
         );
         let assert_final_txid = assert_final_transaction.tx().compute_txid();
 
+        let assert_final_output_layout = assert_final_transaction.output_layout();
         let take_2_vout_0 = 0;
-        let take_2_vout_1 = 0;
-        let take_2_vout_2 = 1;
-        let take_2_vout_3 = 2;
+        let take_2_vout_1 = assert_final_output_layout.connector_4_vout();
+        let take_2_vout_2 = assert_final_output_layout.connector_5_vout();
+        let take_2_vout_3 = assert_final_output_layout.connector_c_vout();
         let take_2_transaction = Take2Transaction::new_for_validation(
-            self.network,
-            &self.operator_public_key,
+            &self.operator_reward_script(),
             &connectors.connector_0,
             &connectors.connector_4,
             &connectors.connector_5,
@@ -989,13 +1574,13 @@ impl PegOutGraph {
                 },
                 amount: self.take_2_transaction.prev_outs()[take_2_vout_0].value, // Self-referencing
             },
-            Input {
+            take_2_vout_1.map(|take_2_vout_1| Input {
                 outpoint: OutPoint {
                     txid: assert_final_txid,
                     vout: take_2_vout_1.to_u32().unwrap(),
                 },
                 amount: assert_final_transaction.tx().output[take_2_vout_1].value,
-            },
+            }),
             Input {
                 outpoint: OutPoint {
                     txid: assert_final_txid,
@@ -1010,12 +1595,15 @@ impl PegOutGraph {
                 },
                 amount: assert_final_transaction.tx().output[take_2_vout_3].value,
             },
+            collateral_input,
+            self.parameters.fee_rate,
         );
 
-        let disprove_vout_0 = 1;
-        let disprove_vout_1 = 2;
+        let disprove_vout_0 = assert_final_output_layout.connector_5_vout();
+        let disprove_vout_1 = assert_final_output_layout.connector_c_vout();
         let disprove_transaction = DisproveTransaction::new_for_validation(
             self.network,
+            &self.connector_0,
             &self.connector_5,
             &self.connector_c,
             Input {
@@ -1032,11 +1620,14 @@ impl PegOutGraph {
                 },
                 amount: assert_final_transaction.tx().output[disprove_vout_1].value,
             },
+            self.parameters.disprove_burn_percentage,
+            collateral_input,
         );
 
         let disprove_chain_vout_0 = 1;
         let disprove_chain_transaction = DisproveChainTransaction::new_for_validation(
             self.network,
+            &self.connector_0,
             &self.connector_b,
             Input {
                 outpoint: OutPoint {
@@ -1045,6 +1636,8 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[disprove_chain_vout_0].value,
             },
+            self.parameters.disprove_burn_percentage,
+            collateral_input,
         );
 
         PegOutGraph {
@@ -1088,16 +1681,153 @@ impl PegOutGraph {
             take_2_transaction,
             operator_public_key: self.operator_public_key,
             operator_taproot_public_key: self.operator_taproot_public_key,
+            operator_reward_script: self.operator_reward_script.clone(),
             peg_out_chain_event: None,
             peg_out_transaction: None,
+            resign_rounds: self.resign_rounds.clone(),
+            parameters: self.parameters,
+            merkle_root_spot_check_requests: self.merkle_root_spot_check_requests.clone(),
+            merkle_root_spot_check_proofs: self.merkle_root_spot_check_proofs.clone(),
+            presigning_window: self.presigning_window,
+            protocol_params: self.protocol_params.clone(),
         }
     }
 
-    pub async fn verifier_status(
+    pub fn peg_out_graph_id(&self) -> &PegOutGraphId {
+        &self.id
+    }
+
+    pub fn parameters(&self) -> &GraphParameters {
+        &self.parameters
+    }
+
+    pub fn operator_public_key(&self) -> &PublicKey {
+        &self.operator_public_key
+    }
+
+    pub fn operator_reward_script(&self) -> ScriptBuf {
+        self.operator_reward_script
+            .clone()
+            .unwrap_or_else(|| default_reward_script(self.network, &self.operator_public_key))
+    }
+
+    pub fn presigning_window(&self) -> &PresigningWindow {
+        &self.presigning_window
+    }
+
+    // Collateral posted in kick-off 1, which can be more than the parameters require
+    pub fn operator_collateral(&self) -> Option<Amount> {
+        Self::collateral_input(&self.kick_off_1_transaction).map(|input| input.amount)
+    }
+
+    // Asks the operator for the scripts and merkle proofs of `sample_size` random connector C leaves
+    pub fn request_merkle_root_spot_check(
+        &mut self,
+        verifier_context: &VerifierContext,
+        sample_size: usize,
+    ) {
+        let leaf_indexes = rand::seq::index::sample(
+            &mut rand::thread_rng(),
+            NUM_TAPS,
+            sample_size.clamp(1, NUM_TAPS),
+        )
+        .into_iter()
+        .collect();
+        self.merkle_root_spot_check_requests
+            .insert(verifier_context.verifier_public_key, leaf_indexes);
+    }
+
+    // Supplies the proofs for all requested leaves that have none yet. Returns whether any were added.
+    pub fn push_merkle_root_spot_check_proofs(&mut self, context: &OperatorContext) -> bool {
+        if context.operator_public_key != self.operator_public_key {
+            panic!("Can only be called by the operator of the graph!");
+        }
+
+        let pending_leaf_indexes: BTreeSet<usize> = self
+            .merkle_root_spot_check_requests
+            .values()
+            .flatten()
+            .filter(|leaf_index| !self.merkle_root_spot_check_proofs.contains_key(leaf_index))
+            .copied()
+            .collect();
+        for leaf_index in pending_leaf_indexes.iter() {
+            self.merkle_root_spot_check_proofs.insert(
+                *leaf_index,
+                self.connector_c.taproot_leaf_proof(*leaf_index),
+            );
+        }
+
+        !pending_leaf_indexes.is_empty()
+    }
+
+    pub fn has_pending_merkle_root_spot_check_requests(&self) -> bool {
+        self.merkle_root_spot_check_requests
+            .values()
+            .flatten()
+            .any(|leaf_index| !self.merkle_root_spot_check_proofs.contains_key(leaf_index))
+    }
+
+    // Part of the pre-signing validation. `validate` rebuilds connector C from the merkle root
+    // claimed by the operator, so the root itself has to be verified here before signing.
+    pub fn merkle_root_status(
         &self,
-        client: &AsyncClient,
+        verifier_context: &VerifierContext,
+        verification: MerkleRootVerification,
+    ) -> MerkleRootStatus {
+        // Graphs created before the root was stored had it derived from the lock scripts when
+        // they were validated
+        let Some(claimed_merkle_root) = self.connector_c.claimed_taproot_merkle_root() else {
+            return MerkleRootStatus::Verified;
+        };
+
+        let derived_merkle_root = match verification {
+            MerkleRootVerification::Regenerate => self.connector_c.generated_taproot_merkle_root(),
+            MerkleRootVerification::SpotCheck { .. } => {
+                self.connector_c.cached_taproot_merkle_root()
+            }
+        };
+        if let Some(derived_merkle_root) = derived_merkle_root {
+            return match derived_merkle_root == claimed_merkle_root {
+                true => MerkleRootStatus::Verified,
+                false => MerkleRootStatus::Invalid(format!(
+                    "Claimed connector C merkle root {claimed_merkle_root} does not match the derived root {derived_merkle_root}"
+                )),
+            };
+        }
+
+        let Some(leaf_indexes) = self
+            .merkle_root_spot_check_requests
+            .get(&verifier_context.verifier_public_key)
+        else {
+            return MerkleRootStatus::PendingSpotCheck;
+        };
+        for leaf_index in leaf_indexes {
+            let Some(proof) = self.merkle_root_spot_check_proofs.get(leaf_index) else {
+                return MerkleRootStatus::AwaitingSpotCheckProofs;
+            };
+            if proof.leaf_index != *leaf_index {
+                return MerkleRootStatus::Invalid(format!(
+                    "Proof supplied for leaf {leaf_index} is for leaf {}",
+                    proof.leaf_index
+                ));
+            }
+            if let Err(err) = self.connector_c.verify_taproot_leaf_proof(proof) {
+                return MerkleRootStatus::Invalid(err);
+            }
+        }
+
+        MerkleRootStatus::Verified
+    }
+
+    pub async fn verifier_status(
+        &self,
+        client: &AsyncClient,
         verifier_context: &VerifierContext,
     ) -> PegOutVerifierStatus {
+        if self.is_abandoned(&verifier_context.n_of_n_public_keys, current_unix_time()) {
+            return PegOutVerifierStatus::PegOutAbandoned;
+        }
+
         if self.n_of_n_presigned {
             let (
                 _,
@@ -1147,52 +1877,35 @@ impl PegOutGraph {
                 .as_ref()
                 .is_ok_and(|status| status.confirmed)
             {
-                if start_time_timeout_status
-                    .as_ref()
-                    .is_ok_and(|status| status.confirmed)
-                    || kick_off_timeout_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                {
+                // Without the full chain state the race is not decided on a partial one
+                let Some(race) = get_kick_off_race(
+                    &kick_off_1_status,
+                    &start_time_status,
+                    &start_time_timeout_status,
+                    &kick_off_2_status,
+                    &kick_off_timeout_status,
+                    &blockchain_height,
+                ) else {
+                    return PegOutVerifierStatus::PegOutWait;
+                };
+                if race.is_settled() {
                     return PegOutVerifierStatus::PegOutFailed; // TODO: can be also `PegOutVerifierStatus::PegOutComplete`
-                } else if start_time_status
-                    .as_ref()
-                    .is_ok_and(|status| !status.confirmed)
-                {
-                    if kick_off_1_status
-                        .as_ref()
-                        .unwrap()
-                        .block_height
-                        .is_some_and(|block_height| {
-                            blockchain_height.is_ok_and(|blockchain_height| {
-                                block_height + self.connector_1.num_blocks_timelock_leaf_2
-                                    <= blockchain_height
-                            })
-                        })
-                    {
+                }
+                match race.verifier_path(&self.kick_off_timelocks()) {
+                    Some(KickOffPath::StartTimeTimeout) => {
                         return PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable;
-                    } else {
-                        return PegOutVerifierStatus::PegOutWait;
                     }
-                } else if kick_off_1_status
-                    .as_ref()
-                    .unwrap()
-                    .block_height
-                    .is_some_and(|block_height| {
-                        blockchain_height.is_ok_and(|blockchain_height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_1
-                                <= blockchain_height
-                        })
-                    })
-                {
-                    return PegOutVerifierStatus::PegOutKickOffTimeoutAvailable;
-                } else if challenge_status
-                    .as_ref()
-                    .is_ok_and(|status| !status.confirmed)
-                {
-                    return PegOutVerifierStatus::PegOutChallengeAvailable;
-                } else {
-                    return PegOutVerifierStatus::PegOutWait;
+                    Some(KickOffPath::KickOffTimeout) => {
+                        return PegOutVerifierStatus::PegOutKickOffTimeoutAvailable;
+                    }
+                    _ if race.start_time_confirmed
+                        && challenge_status
+                            .as_ref()
+                            .is_ok_and(|status| !status.confirmed) =>
+                    {
+                        return PegOutVerifierStatus::PegOutChallengeAvailable;
+                    }
+                    _ => return PegOutVerifierStatus::PegOutWait,
                 }
             } else {
                 return PegOutVerifierStatus::PegOutWait;
@@ -1327,35 +2040,27 @@ impl PegOutGraph {
                     .as_ref()
                     .is_ok_and(|status| status.confirmed)
                 {
-                    if start_time_timeout_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                        || kick_off_timeout_status
-                            .as_ref()
-                            .is_ok_and(|status| status.confirmed)
-                    {
+                    let Some(race) = get_kick_off_race(
+                        &kick_off_1_status,
+                        &start_time_status,
+                        &start_time_timeout_status,
+                        &kick_off_2_status,
+                        &kick_off_timeout_status,
+                        &blockchain_height,
+                    ) else {
+                        return PegOutOperatorStatus::PegOutWait;
+                    };
+                    if race.is_settled() {
                         return PegOutOperatorStatus::PegOutFailed; // TODO: can be also `PegOutOperatorStatus::PegOutComplete`
-                    } else if start_time_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                    {
-                        if kick_off_1_status
-                            .as_ref()
-                            .unwrap()
-                            .block_height
-                            .is_some_and(|block_height| {
-                                blockchain_height.is_ok_and(|blockchain_height| {
-                                    block_height + self.connector_1.num_blocks_timelock_leaf_0
-                                        <= blockchain_height
-                                })
-                            })
-                        {
+                    }
+                    match race.operator_path(&self.kick_off_timelocks()) {
+                        Some(KickOffPath::StartTime) => {
+                            return PegOutOperatorStatus::PegOutStartTimeAvailable;
+                        }
+                        Some(KickOffPath::KickOff2) => {
                             return PegOutOperatorStatus::PegOutKickOff2Available;
-                        } else {
-                            return PegOutOperatorStatus::PegOutWait;
                         }
-                    } else {
-                        return PegOutOperatorStatus::PegOutStartTimeAvailable;
+                        _ => return PegOutOperatorStatus::PegOutWait,
                     }
                 } else if peg_out_confirm_status
                     .as_ref()
@@ -1373,6 +2078,69 @@ impl PegOutGraph {
         PegOutOperatorStatus::PegOutWait
     }
 
+    pub async fn dispute_status(&self, client: &AsyncClient) -> DisputeStatus {
+        if !self.n_of_n_presigned || !self.is_peg_out_initiated() {
+            return DisputeStatus::default();
+        }
+
+        let (
+            _,
+            _,
+            _,
+            assert_final_status,
+            challenge_status,
+            disprove_chain_status,
+            disprove_status,
+            _,
+            _,
+            kick_off_2_status,
+            kick_off_timeout_status,
+            _,
+            start_time_timeout_status,
+            _,
+            take_1_status,
+            take_2_status,
+        ) = Self::get_peg_out_statuses(self, client).await;
+        let confirmed_height = |status: &Result<TxStatus, esplora_client::Error>| {
+            status
+                .as_ref()
+                .ok()
+                .filter(|status| status.confirmed)
+                .and_then(|status| status.block_height)
+        };
+
+        let challenged = confirmed_height(&challenge_status).is_some();
+        let settled = [
+            &take_1_status,
+            &take_2_status,
+            &disprove_chain_status,
+            &disprove_status,
+            &kick_off_timeout_status,
+            &start_time_timeout_status,
+        ]
+        .into_iter()
+        .any(|status| confirmed_height(status).is_some());
+        let take_window = match confirmed_height(&kick_off_2_status) {
+            _ if settled => None,
+            Some(kick_off_2_height) if !challenged => Some((
+                PegOutPresignedTransaction::Take1,
+                kick_off_2_height + self.connector_3.num_blocks_timelock,
+            )),
+            Some(_) => confirmed_height(&assert_final_status).map(|assert_final_height| {
+                (
+                    PegOutPresignedTransaction::Take2,
+                    assert_final_height + self.connector_4.num_blocks_timelock,
+                )
+            }),
+            None => None,
+        };
+
+        DisputeStatus {
+            challenged,
+            take_window,
+        }
+    }
+
     pub fn interpret_withdrawer_status(
         &self,
         peg_out_status: Option<&Result<TxStatus, esplora_client::Error>>,
@@ -1405,6 +2173,156 @@ impl PegOutGraph {
         self.interpret_withdrawer_status(peg_out_status.as_ref())
     }
 
+    // Whether the peg-out was requested by the withdrawer with the given destination chain address,
+    // e.g. an EVM address, or the given destination address on the source network
+    pub fn is_withdrawal_of(&self, address: &str) -> bool {
+        self.peg_out_chain_event.as_ref().is_some_and(|event| {
+            event.withdrawer_chain_address.eq_ignore_ascii_case(address)
+                || event.withdrawer_destination_address == address
+        })
+    }
+
+    pub fn interpret_withdrawal_progress(
+        &self,
+        peg_out_status: Option<&TxStatus>,
+        reimbursement: OperatorReimbursementStage,
+        tip_height: u32,
+    ) -> Option<WithdrawalProgress> {
+        let peg_out_chain_event = self.peg_out_chain_event.as_ref()?;
+
+        let (stage, confirmations) = match peg_out_status {
+            None => (WithdrawalStage::PegOutRequested, 0),
+            Some(TxStatus {
+                confirmed: true,
+                block_height: Some(block_height),
+                ..
+            }) => (
+                WithdrawalStage::PegOutConfirmed,
+                tip_height.saturating_sub(*block_height) + 1,
+            ),
+            Some(_) => (WithdrawalStage::PegOutBroadcast, 0),
+        };
+
+        Some(WithdrawalProgress {
+            graph_id: self.id().clone(),
+            amount: peg_out_chain_event.amount,
+            destination_address: peg_out_chain_event.withdrawer_destination_address.clone(),
+            peg_out_txid: self
+                .peg_out_transaction
+                .as_ref()
+                .map(|peg_out| peg_out.tx().compute_txid()),
+            confirmations,
+            stage,
+            reimbursement,
+        })
+    }
+
+    // Returns `None` if no peg-out was requested for this graph
+    pub async fn withdrawal_progress(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<Option<WithdrawalProgress>, Error> {
+        if !self.is_peg_out_initiated() {
+            return Ok(None);
+        }
+
+        let tip_height = client.get_height().await.map_err(Error::Esplora)?;
+        let (
+            _,
+            _,
+            _,
+            _,
+            challenge_status,
+            disprove_chain_status,
+            disprove_status,
+            _,
+            kick_off_1_status,
+            _,
+            kick_off_timeout_status,
+            peg_out_status,
+            start_time_timeout_status,
+            _,
+            take_1_status,
+            take_2_status,
+        ) = Self::get_peg_out_statuses(self, client).await;
+        let peg_out_status = peg_out_status.transpose().map_err(Error::Esplora)?;
+        let is_confirmed = |status: Result<TxStatus, esplora_client::Error>| {
+            status
+                .map(|status| status.confirmed)
+                .map_err(Error::Esplora)
+        };
+
+        let reimbursement = if is_confirmed(take_1_status)? {
+            OperatorReimbursementStage::ReimbursedTake1
+        } else if is_confirmed(take_2_status)? {
+            OperatorReimbursementStage::ReimbursedTake2
+        } else if is_confirmed(disprove_chain_status)?
+            || is_confirmed(disprove_status)?
+            || is_confirmed(kick_off_timeout_status)?
+            || is_confirmed(start_time_timeout_status)?
+        {
+            OperatorReimbursementStage::Failed
+        } else if is_confirmed(challenge_status)? {
+            OperatorReimbursementStage::Challenged
+        } else if is_confirmed(kick_off_1_status)? {
+            OperatorReimbursementStage::KickedOff
+        } else {
+            OperatorReimbursementStage::NotStarted
+        };
+
+        Ok(self.interpret_withdrawal_progress(peg_out_status.as_ref(), reimbursement, tip_height))
+    }
+
+    // Stage of the graph on chain, the signing ceremony is not taken into account. Transactions
+    // ending the graph take precedence over the ones leading to them.
+    pub async fn chain_stage(&self, client: &AsyncClient) -> Result<GraphStage, Error> {
+        let (
+            assert_initial_status,
+            _,
+            _,
+            _,
+            challenge_status,
+            disprove_chain_status,
+            disprove_status,
+            _,
+            kick_off_1_status,
+            _,
+            kick_off_timeout_status,
+            peg_out_status,
+            start_time_timeout_status,
+            _,
+            take_1_status,
+            take_2_status,
+        ) = Self::get_peg_out_statuses(self, client).await;
+        let is_confirmed = |status: Result<TxStatus, esplora_client::Error>| {
+            status
+                .map(|status| status.confirmed)
+                .map_err(Error::Esplora)
+        };
+
+        Ok(
+            if is_confirmed(take_1_status)? || is_confirmed(take_2_status)? {
+                GraphStage::Taken
+            } else if is_confirmed(disprove_chain_status)? || is_confirmed(disprove_status)? {
+                GraphStage::Disproved
+            } else if is_confirmed(kick_off_timeout_status)?
+                || is_confirmed(start_time_timeout_status)?
+            {
+                GraphStage::TimedOut
+            } else if is_confirmed(assert_initial_status)? {
+                GraphStage::Asserted
+            } else if is_confirmed(challenge_status)? {
+                GraphStage::Challenged
+            } else if is_confirmed(kick_off_1_status)? {
+                GraphStage::KickedOff
+            } else if peg_out_status.map(is_confirmed).transpose()? == Some(true) {
+                GraphStage::PaidOut
+            } else {
+                GraphStage::Ready
+            },
+        )
+    }
+
     pub async fn peg_out(
         &mut self,
         client: &AsyncClient,
@@ -1467,8 +2385,14 @@ impl PegOutGraph {
         context: &OperatorContext,
         source_network_txid_commitment_secret: &WinternitzSecret,
         destination_network_txid_commitment_secret: &WinternitzSecret,
+        // Destination chain id, with the chain id and withdrawal nonce commitment secrets. Only
+        // used by graphs created with `KickOff1Commitments::PegOutTxIdsAndDestinationMetadata`.
+        destination_metadata: Option<(u64, &WinternitzSecret, &WinternitzSecret)>,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.kick_off_1_transaction.tx().compute_txid()).await?;
+        if self.connector_6.commits_destination_metadata() && destination_metadata.is_none() {
+            return Err(Error::Graph(GraphError::DestinationMetadataNotProvided));
+        }
 
         let peg_out_confirm_txid = self.peg_out_confirm_transaction.tx().compute_txid();
         let peg_out_confirm_status = client.get_tx_status(&peg_out_confirm_txid).await;
@@ -1488,21 +2412,44 @@ impl PegOutGraph {
                         message: &pegout_txid,
                         signing_key: source_network_txid_commitment_secret,
                     };
+                    let peg_out_chain_event = self.peg_out_chain_event.as_ref().unwrap();
                     let destination_network_txid_inputs = WinternitzSigningInputs {
-                        message: self
-                            .peg_out_chain_event
-                            .as_ref()
-                            .unwrap()
-                            .tx_hash
-                            .as_slice(),
+                        message: peg_out_chain_event.tx_hash.as_slice(),
                         signing_key: destination_network_txid_commitment_secret,
                     };
-                    self.kick_off_1_transaction.sign(
-                        context,
-                        &self.connector_6,
-                        &source_network_txid_inputs,
-                        &destination_network_txid_inputs,
-                    );
+                    match destination_metadata {
+                        Some((
+                            destination_chain_id,
+                            destination_chain_id_commitment_secret,
+                            withdrawal_nonce_commitment_secret,
+                        )) if self.connector_6.commits_destination_metadata() => {
+                            let destination_chain_id = destination_chain_id.to_be_bytes();
+                            let withdrawal_nonce =
+                                peg_out_chain_event.withdrawal_nonce.to_be_bytes();
+                            self.kick_off_1_transaction.sign_with_destination_metadata(
+                                context,
+                                &self.connector_6,
+                                &source_network_txid_inputs,
+                                &destination_network_txid_inputs,
+                                &DestinationMetadataSigningInputs {
+                                    destination_chain_id: WinternitzSigningInputs {
+                                        message: &destination_chain_id,
+                                        signing_key: destination_chain_id_commitment_secret,
+                                    },
+                                    withdrawal_nonce: WinternitzSigningInputs {
+                                        message: &withdrawal_nonce,
+                                        signing_key: withdrawal_nonce_commitment_secret,
+                                    },
+                                },
+                            );
+                        }
+                        _ => self.kick_off_1_transaction.sign(
+                            context,
+                            &self.connector_6,
+                            &source_network_txid_inputs,
+                            &destination_network_txid_inputs,
+                        ),
+                    }
                     Ok(self.kick_off_1_transaction.finalize())
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
@@ -1543,6 +2490,37 @@ impl PegOutGraph {
         }
     }
 
+    // Replaces the broadcast, unconfirmed `replaced_tx` with one paying `fee_rate`. Only a
+    // challenge can be replaced: every other transaction is signed by the n-of-n or has
+    // pre-signed descendants committing to its txid, and the peg-out pays the withdrawer its
+    // whole input less a fixed fee.
+    pub fn bump_fee(
+        &mut self,
+        replaced_tx: &Transaction,
+        prev_outs: &[TxOut],
+        keypair: &Keypair,
+        fee_rate: u64,
+    ) -> Result<Transaction, Error> {
+        let replaced_txid = replaced_tx.compute_txid();
+        match self
+            .challenge_transaction
+            .bump_fee(replaced_tx, prev_outs, keypair, fee_rate)
+        {
+            Ok(()) => Ok(self.challenge_transaction.tx().clone()),
+            Err(TransactionError::ReplacementNotPermitted(txid, _)) => {
+                let name = self
+                    .txids()
+                    .into_iter()
+                    .find(|(_, graph_txid)| *graph_txid == replaced_txid)
+                    .map(|(name, _)| name);
+                Err(Error::Transaction(
+                    TransactionError::ReplacementNotPermitted(txid, name),
+                ))
+            }
+            Err(e) => Err(Error::Transaction(e)),
+        }
+    }
+
     pub async fn start_time(
         &mut self,
         client: &AsyncClient,
@@ -1594,11 +2572,16 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_2 <= height
+                            is_relative_timelock_met(
+                                block_height,
+                                height,
+                                self.connector_1.num_blocks_timelock_leaf_2,
+                            )
                         }) =>
                     {
                         self.start_time_timeout_transaction
-                            .add_output(output_script_pubkey);
+                            .add_output(output_script_pubkey)
+                            .map_err(Error::Validation)?;
                         Ok(self.start_time_timeout_transaction.finalize())
                     }
                     _ => Err(Error::Graph(GraphError::PrecedingTxTimelockNotMet(
@@ -1632,7 +2615,11 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_0 <= height
+                            is_relative_timelock_met(
+                                block_height,
+                                height,
+                                self.connector_1.num_blocks_timelock_leaf_0,
+                            )
                         }) =>
                     {
                         let superblock_header = find_superblock();
@@ -1683,11 +2670,16 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_1 <= height
+                            is_relative_timelock_met(
+                                block_height,
+                                height,
+                                self.connector_1.num_blocks_timelock_leaf_1,
+                            )
                         }) =>
                     {
                         self.kick_off_timeout_transaction
-                            .add_output(output_script_pubkey);
+                            .add_output(output_script_pubkey)
+                            .map_err(Error::Validation)?;
                         Ok(self.kick_off_timeout_transaction.finalize())
                     }
                     _ => Err(Error::Graph(GraphError::PrecedingTxTimelockNotMet(
@@ -1732,13 +2724,16 @@ impl PegOutGraph {
         }
     }
 
+    // See `sign_assert_tx_with_committed_values` for `committed_values`
     pub async fn assert_commit_1(
         &mut self,
         client: &AsyncClient,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
         proof: &RawProof,
+        committed_values: &mut Option<CommittedIntermediateValues>,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_commit_1_transaction.tx().compute_txid()).await?;
+        self.check_segment_layout().map_err(Error::Chunker)?;
 
         let assert_initial_txid = self.assert_initial_transaction.tx().compute_txid();
         let assert_initial_status = client.get_tx_status(&assert_initial_txid).await;
@@ -1746,8 +2741,11 @@ impl PegOutGraph {
         match assert_initial_status {
             Ok(status) => match status.confirmed {
                 true => {
-                    let (witness_for_commit1, _) =
-                        sign_assert_tx_with_groth16_proof(commitment_secrets, proof);
+                    let (witness_for_commit1, _) = sign_assert_tx_with_committed_values(
+                        commitment_secrets,
+                        proof,
+                        committed_values,
+                    )?;
                     self.assert_commit_1_transaction
                         .sign(&self.connector_e_1, witness_for_commit1);
                     Ok(self.assert_commit_1_transaction.finalize())
@@ -1760,13 +2758,16 @@ impl PegOutGraph {
         }
     }
 
+    // See `sign_assert_tx_with_committed_values` for `committed_values`
     pub async fn assert_commit_2(
         &mut self,
         client: &AsyncClient,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
         proof: &RawProof,
+        committed_values: &mut Option<CommittedIntermediateValues>,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_commit_2_transaction.tx().compute_txid()).await?;
+        self.check_segment_layout().map_err(Error::Chunker)?;
 
         let assert_initial_txid = self.assert_initial_transaction.tx().compute_txid();
         let assert_initial_status = client.get_tx_status(&assert_initial_txid).await;
@@ -1774,8 +2775,11 @@ impl PegOutGraph {
         match assert_initial_status {
             Ok(status) => match status.confirmed {
                 true => {
-                    let (_, witness_for_commit2) =
-                        sign_assert_tx_with_groth16_proof(commitment_secrets, proof);
+                    let (_, witness_for_commit2) = sign_assert_tx_with_committed_values(
+                        commitment_secrets,
+                        proof,
+                        committed_values,
+                    )?;
                     self.assert_commit_2_transaction
                         .sign(&self.connector_e_2, witness_for_commit2);
                     Ok(self.assert_commit_2_transaction.finalize())
@@ -1795,9 +2799,36 @@ impl PegOutGraph {
         client: &AsyncClient,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
         proof: &RawProof,
+        committed_values: &mut Option<CommittedIntermediateValues>,
+    ) -> Result<(Transaction, Transaction), Error> {
+        self.sign_assert_commits(client, || {
+            sign_assert_tx_with_committed_values(commitment_secrets, proof, committed_values)
+        })
+        .await
+    }
+
+    // Same as `assert_commits`, with the witnesses of `sign_assert_tx_with_groth16_proof` signed
+    // ahead of time, see `JobKind::SignAssertCommits`
+    pub async fn assert_commits_with_witnesses(
+        &mut self,
+        client: &AsyncClient,
+        witnesses: (Vec<RawWitness>, Vec<RawWitness>),
+        committed_values: &mut Option<CommittedIntermediateValues>,
+    ) -> Result<(Transaction, Transaction), Error> {
+        self.sign_assert_commits(client, || {
+            check_committed_values(witnesses, committed_values)
+        })
+        .await
+    }
+
+    async fn sign_assert_commits(
+        &mut self,
+        client: &AsyncClient,
+        sign: impl FnOnce() -> Result<(Vec<RawWitness>, Vec<RawWitness>), Error>,
     ) -> Result<(Transaction, Transaction), Error> {
         verify_if_not_mined(client, self.assert_commit_1_transaction.tx().compute_txid()).await?;
         verify_if_not_mined(client, self.assert_commit_2_transaction.tx().compute_txid()).await?;
+        self.check_segment_layout().map_err(Error::Chunker)?;
 
         let assert_initial_txid = self.assert_initial_transaction.tx().compute_txid();
         let assert_initial_status = client.get_tx_status(&assert_initial_txid).await;
@@ -1805,8 +2836,7 @@ impl PegOutGraph {
         match assert_initial_status {
             Ok(status) => match status.confirmed {
                 true => {
-                    let (witness_for_commit1, witness_for_commit2) =
-                        sign_assert_tx_with_groth16_proof(commitment_secrets, proof);
+                    let (witness_for_commit1, witness_for_commit2) = sign()?;
                     self.assert_commit_1_transaction
                         .sign(&self.connector_e_1, witness_for_commit1);
                     self.assert_commit_2_transaction
@@ -1824,6 +2854,78 @@ impl PegOutGraph {
         }
     }
 
+    // Each assert commit confirms on its own, one can be stuck in the mempool while the other
+    // confirmed, see `BitVMClient::broadcast_missing_assert_commits`
+    pub async fn assert_commit_progress(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<Vec<AssertCommitProgress>, Error> {
+        let mut progress = Vec::new();
+        for commit in [AssertCommit::Commit1, AssertCommit::Commit2] {
+            let tx = self.assert_commit_tx(commit);
+            let txid = tx.compute_txid();
+            let status = client.get_tx_status(&txid).await.map_err(Error::Esplora)?;
+            progress.push(AssertCommitProgress {
+                commit,
+                txid,
+                confirmed_height: status.block_height.filter(|_| status.confirmed),
+                signed: has_witnesses(tx),
+            });
+        }
+
+        Ok(progress)
+    }
+
+    // The assert commit as signed before, none if it was never signed
+    pub fn signed_assert_commit(&self, commit: AssertCommit) -> Option<Transaction> {
+        let tx = self.assert_commit_tx(commit);
+        has_witnesses(tx).then(|| tx.clone())
+    }
+
+    fn assert_commit_tx(&self, commit: AssertCommit) -> &Transaction {
+        match commit {
+            AssertCommit::Commit1 => self.assert_commit_1_transaction.tx(),
+            AssertCommit::Commit2 => self.assert_commit_2_transaction.tx(),
+        }
+    }
+
+    // Values committed to by the confirmed assert commits. Fails unless both are confirmed and
+    // their witnesses parse back into a Winternitz signature of every commitment.
+    pub async fn asserted_intermediate_values(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<CommittedIntermediateValues, Error> {
+        let mut unconfirmed = vec![];
+        for named_tx in [
+            NamedTx::for_tx(&self.assert_commit_1_transaction, false),
+            NamedTx::for_tx(&self.assert_commit_2_transaction, false),
+        ] {
+            let status = client
+                .get_tx_status(&named_tx.txid)
+                .await
+                .map_err(Error::Esplora)?;
+            if !status.confirmed {
+                unconfirmed.push(named_tx);
+            }
+        }
+        if !unconfirmed.is_empty() {
+            return Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(
+                unconfirmed,
+            )));
+        }
+
+        let commitments = self
+            .asserted_commitments(client)
+            .await
+            .map_err(Error::Other)?;
+        validate_commitment_witnesses(&commitments.commit_1_witness, &commitments.commit_2_witness)
+            .map_err(Error::Chunker)?;
+        Ok(CommittedIntermediateValues::from_witnesses(
+            &commitments.commit_1_witness,
+            &commitments.commit_2_witness,
+        ))
+    }
+
     pub async fn assert_final(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_final_transaction.tx().compute_txid()).await?;
 
@@ -1832,7 +2934,12 @@ impl PegOutGraph {
 
         match assert_initial_status {
             Ok(status) => match status.confirmed {
-                true => Ok(self.assert_final_transaction.finalize()),
+                // Assert final closes the assertion, so it waits until what both commits
+                // committed to can be read back from the chain, as verifiers will
+                true => {
+                    self.asserted_intermediate_values(client).await?;
+                    Ok(self.assert_final_transaction.finalize())
+                }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
                     NamedTx::for_tx(&self.assert_initial_transaction, status.confirmed),
                 ]))),
@@ -1848,58 +2955,38 @@ impl PegOutGraph {
         verifying_key: &ZkProofVerifyingKey,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.disprove_transaction.tx().compute_txid()).await?;
+        self.verify_assert_final_confirmed(client).await?;
 
-        let assert_commit_1_txid = self.assert_commit_1_transaction.tx().compute_txid();
-        let assert_commit_2_txid = self.assert_commit_2_transaction.tx().compute_txid();
-        let Some(onchain_assert_commit_1_tx) = client
-            .get_tx(&assert_commit_1_txid)
-            .await
-            .map_err(Error::Esplora)?
-        else {
-            return Err(Error::Other(format!(
-                "Esplora failed to retrieve a tx {} with id: {}",
-                self.assert_commit_1_transaction.name(),
-                assert_commit_1_txid
-            )));
-        };
-        let Some(onchain_assert_commit_2_tx) = client
-            .get_tx(&assert_commit_2_txid)
+        let commitments = self
+            .asserted_commitments(client)
             .await
-            .map_err(Error::Esplora)?
-        else {
-            return Err(Error::Other(format!(
-                "Esplora failed to retrieve a tx {} with id: {}",
-                self.assert_commit_2_transaction.name(),
-                assert_commit_2_txid
-            )));
-        };
+            .map_err(Error::Other)?;
+        let disprove_witness = self.connector_c.generate_disprove_witness(
+            commitments.commit_1_witness,
+            commitments.commit_2_witness,
+            verifying_key,
+        )?;
+        self.finalize_disprove(output_script_pubkey, disprove_witness)
+    }
 
-        let assert_final_txid = self.assert_final_transaction.tx().compute_txid();
-        let assert_final_status = client.get_tx_status(&assert_final_txid).await;
+    // Same as `disprove`, with a witness evaluated earlier, see `evaluate_asserted_proof`
+    pub async fn disprove_with_witness(
+        &mut self,
+        client: &AsyncClient,
+        output_script_pubkey: ScriptBuf,
+        disprove_witness: DisproveWitness,
+    ) -> Result<Transaction, Error> {
+        verify_if_not_mined(client, self.disprove_transaction.tx().compute_txid()).await?;
+        self.verify_assert_final_confirmed(client).await?;
+
+        self.finalize_disprove(output_script_pubkey, disprove_witness)
+    }
 
-        match assert_final_status {
+    async fn verify_assert_final_confirmed(&self, client: &AsyncClient) -> Result<(), Error> {
+        let assert_final_txid = self.assert_final_transaction.tx().compute_txid();
+        match client.get_tx_status(&assert_final_txid).await {
             Ok(status) => match status.confirmed {
-                true => {
-                    // get commit from assert_commit txs
-                    let assert_commit_1_witness =
-                        get_commit_from_assert_commit_tx(&onchain_assert_commit_1_tx);
-                    let assert_commit_2_witness =
-                        get_commit_from_assert_commit_tx(&onchain_assert_commit_2_tx);
-
-                    let (input_script_index, disprove_witness) =
-                        self.connector_c.generate_disprove_witness(
-                            assert_commit_1_witness,
-                            assert_commit_2_witness,
-                            verifying_key,
-                        )?;
-                    self.disprove_transaction.add_input_output(
-                        &self.connector_c,
-                        input_script_index,
-                        disprove_witness,
-                        output_script_pubkey,
-                    );
-                    Ok(self.disprove_transaction.finalize())
-                }
+                true => Ok(()),
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
                     NamedTx::for_tx(&self.assert_final_transaction, status.confirmed),
                 ]))),
@@ -1908,32 +2995,112 @@ impl PegOutGraph {
         }
     }
 
-    pub async fn disprove_chain(
+    fn finalize_disprove(
         &mut self,
-        client: &AsyncClient,
         output_script_pubkey: ScriptBuf,
+        (input_script_index, disprove_witness): DisproveWitness,
     ) -> Result<Transaction, Error> {
-        verify_if_not_mined(client, self.disprove_chain_transaction.tx().compute_txid()).await?;
+        self.disprove_transaction
+            .add_input_output(
+                &self.connector_c,
+                input_script_index,
+                disprove_witness,
+                output_script_pubkey,
+            )
+            .map_err(Error::Validation)?;
+        Ok(self.disprove_transaction.finalize())
+    }
 
-        let kick_off_2_txid = self.kick_off_2_transaction.tx().compute_txid();
-        let kick_off_2_status = client.get_tx_status(&kick_off_2_txid).await;
+    // Commitments of the confirmed assert commit transactions, the reason if they are not on chain
+    pub async fn asserted_commitments(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<AssertedCommitments, String> {
+        let mut assert_commit_witnesses = vec![];
+        for tx in [
+            self.assert_commit_1_transaction.tx(),
+            self.assert_commit_2_transaction.tx(),
+        ] {
+            let txid = tx.compute_txid();
+            match client.get_tx(&txid).await {
+                Ok(Some(onchain_tx)) => {
+                    assert_commit_witnesses.push(get_commit_from_assert_commit_tx(&onchain_tx))
+                }
+                Ok(None) => {
+                    return Err(format!("assert commit transaction {txid} is not on chain"))
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
 
-        match kick_off_2_status {
-            Ok(status) => match status.confirmed {
-                true => {
-                    self.disprove_chain_transaction
-                        .add_output(output_script_pubkey);
+        Ok(AssertedCommitments {
+            commit_2_witness: assert_commit_witnesses.pop().unwrap(),
+            commit_1_witness: assert_commit_witnesses.pop().unwrap(),
+        })
+    }
 
-                    // TODO: This must be a heavier superblock than the one the Operator committed in the KickOff2 tx.
-                    let disprove_sb = find_superblock();
+    // Evaluates the proof committed to in the confirmed assert commit transactions, as the
+    // disprove transaction would, without building the disprove transaction
+    pub async fn verify_asserted_proof(
+        &self,
+        client: &AsyncClient,
+        verifying_key: &ZkProofVerifyingKey,
+    ) -> ProofVerification {
+        match self.asserted_commitments(client).await {
+            Ok(commitments) => {
+                evaluate_asserted_proof(&self.connector_c, commitments, verifying_key).0
+            }
+            Err(reason) => ProofVerification::Inconclusive(reason),
+        }
+    }
 
-                    self.disprove_chain_transaction.sign(
-                        &disprove_sb,
-                        self.start_time_transaction
-                            .start_time_witness
-                            .as_ref()
-                            .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
-                                CommitmentMessageId::StartTime,
+    pub fn connector_c(&self) -> &ConnectorC {
+        &self.connector_c
+    }
+
+    // Segment layout of the chunker connector C was generated with, none for graphs created before
+    // it was recorded
+    pub fn segment_layout_version(&self) -> Option<&str> {
+        self.connector_c.segment_layout_version()
+    }
+
+    pub async fn disprove_chain(
+        &mut self,
+        client: &AsyncClient,
+        output_script_pubkey: ScriptBuf,
+    ) -> Result<Transaction, Error> {
+        // TODO: This must be a heavier superblock than the one the Operator committed in the KickOff2 tx.
+        self.disprove_chain_with_superblock(client, output_script_pubkey, &find_superblock())
+            .await
+    }
+
+    // The superblock has to pass `is_heavier_superblock` against the committed one, see
+    // `committed_superblock`
+    pub async fn disprove_chain_with_superblock(
+        &mut self,
+        client: &AsyncClient,
+        output_script_pubkey: ScriptBuf,
+        disprove_sb: &Header,
+    ) -> Result<Transaction, Error> {
+        verify_if_not_mined(client, self.disprove_chain_transaction.tx().compute_txid()).await?;
+
+        let kick_off_2_txid = self.kick_off_2_transaction.tx().compute_txid();
+        let kick_off_2_status = client.get_tx_status(&kick_off_2_txid).await;
+
+        match kick_off_2_status {
+            Ok(status) => match status.confirmed {
+                true => {
+                    self.disprove_chain_transaction
+                        .add_output(output_script_pubkey)
+                        .map_err(Error::Validation)?;
+
+                    self.disprove_chain_transaction.sign(
+                        disprove_sb,
+                        self.start_time_transaction
+                            .start_time_witness
+                            .as_ref()
+                            .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
+                                CommitmentMessageId::StartTime,
                             )))?,
                         self.kick_off_2_transaction
                             .superblock_hash_witness
@@ -1953,6 +3120,79 @@ impl PegOutGraph {
         }
     }
 
+    // Reads the commitments a disprove chain transaction is checked against from the confirmed
+    // start time and kick-off 2 transactions. The commitment witnesses are kept, so disprove chain
+    // can be built even if the operator never shared them.
+    pub async fn committed_superblock(
+        &mut self,
+        client: &AsyncClient,
+    ) -> Result<CommittedSuperblock, String> {
+        let start_time_parameters =
+            &self.connector_2.commitment_public_keys[&CommitmentMessageId::StartTime].parameters;
+        let superblock_parameters =
+            &self.connector_1.commitment_public_keys[&CommitmentMessageId::Superblock].parameters;
+        let superblock_hash_parameters = &self.connector_1.commitment_public_keys
+            [&CommitmentMessageId::SuperblockHash]
+            .parameters;
+        let not_revealed =
+            |message_id: CommitmentMessageId| format!("{message_id} commitment not found");
+
+        // Both witnesses start with the operator's Schnorr signature
+        let (start_time_height, witness) =
+            confirmed_input_witness(client, self.start_time_transaction.tx()).await?;
+        let (start_time_message, start_time_witness) =
+            revealed_commitment(&witness, 1, start_time_parameters)
+                .ok_or_else(|| not_revealed(CommitmentMessageId::StartTime))?;
+        let (kick_off_2_height, witness) =
+            confirmed_input_witness(client, self.kick_off_2_transaction.tx()).await?;
+        let (superblock_message, _) = revealed_commitment(&witness, 1, superblock_parameters)
+            .ok_or_else(|| not_revealed(CommitmentMessageId::Superblock))?;
+        let (superblock_hash_message, superblock_hash_witness) = revealed_commitment(
+            &witness,
+            1 + 2 * superblock_parameters.total_length() as usize,
+            superblock_hash_parameters,
+        )
+        .ok_or_else(|| not_revealed(CommitmentMessageId::SuperblockHash))?;
+
+        let start_time = u32::from_le_bytes(
+            start_time_message
+                .try_into()
+                .map_err(|_| not_revealed(CommitmentMessageId::StartTime))?,
+        );
+        let superblock: Header = deserialize(&superblock_message)
+            .map_err(|e| format!("committed superblock is not a block header: {e}"))?;
+        let superblock_hash = BlockHash::from_byte_array(
+            superblock_hash_message
+                .try_into()
+                .map_err(|_| not_revealed(CommitmentMessageId::SuperblockHash))?,
+        );
+
+        self.start_time_transaction
+            .start_time_witness
+            .get_or_insert(start_time_witness);
+        self.kick_off_2_transaction
+            .superblock_hash_witness
+            .get_or_insert(superblock_hash_witness);
+
+        Ok(CommittedSuperblock {
+            start_time,
+            superblock,
+            superblock_hash,
+            start_time_height,
+            kick_off_2_height,
+        })
+    }
+
+    // Height from which take 1 or assert initial can spend the connector B output disprove chain
+    // spends
+    pub fn disprove_chain_deadline(&self, kick_off_2_height: u32) -> u32 {
+        kick_off_2_height
+            + self
+                .connector_3
+                .num_blocks_timelock
+                .min(self.connector_b.num_blocks_timelock_1)
+    }
+
     pub async fn take_1(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.take_1_transaction.tx().compute_txid()).await?;
         verify_if_not_mined(client, self.challenge_transaction.tx().compute_txid()).await?;
@@ -2047,6 +3287,29 @@ impl PegOutGraph {
         self.peg_out_chain_event.is_some()
     }
 
+    pub fn peg_in_confirm_txid(&self) -> Txid {
+        self.peg_in_confirm_txid
+    }
+
+    // What the proof the operator asserts has to be about, see `proof_binding`. Known once the
+    // peg-out of the withdrawal is created.
+    pub fn peg_out_facts(&self) -> Result<PegOutFacts, Error> {
+        let (Some(event), Some(peg_out_transaction)) = (
+            self.peg_out_chain_event.as_ref(),
+            self.peg_out_transaction.as_ref(),
+        ) else {
+            return Err(Error::Graph(GraphError::PrecedingTxNotCreated("peg-out")));
+        };
+
+        Ok(PegOutFacts {
+            peg_in_confirm_txid: self.peg_in_confirm_txid,
+            kick_off_1_txid: self.kick_off_1_transaction.tx().compute_txid(),
+            peg_out_txid: peg_out_transaction.tx().compute_txid(),
+            amount: event.amount,
+            withdrawer_evm_address: event.withdrawer_chain_address.clone(),
+        })
+    }
+
     pub fn min_crowdfunding_amount(&self) -> u64 {
         self.challenge_transaction.min_crowdfunding_amount()
     }
@@ -2079,6 +3342,54 @@ impl PegOutGraph {
         }
     }
 
+    // Finds the burn on the destination chain that backs this peg-out and checks it against the
+    // peg-out request the graph was initiated with. The chain adaptors only return finalized
+    // events, so a match is final.
+    pub fn match_peg_out_burnt_event(
+        &self,
+        all_events: &[PegOutBurntEvent],
+    ) -> Result<PegOutBurntEvent, Error> {
+        let Some(peg_out_chain_event) = self.peg_out_chain_event.as_ref() else {
+            return Err(Error::L2(L2Error::PegOutNotInitiated));
+        };
+
+        let events: Vec<&PegOutBurntEvent> = all_events
+            .iter()
+            .filter(|event| {
+                self.peg_in_confirm_txid.eq(&event.source_outpoint.txid)
+                    && event.source_outpoint.vout == peg_out_chain_event.source_outpoint.vout
+            })
+            .collect();
+        let event = match events.len() {
+            0 => {
+                return Err(Error::L2(L2Error::PegOutBurnNotFound(
+                    self.peg_in_confirm_txid,
+                )))
+            }
+            1 => events[0],
+            _ => {
+                return Err(Error::L2(L2Error::PegOutBurnNotUnique(
+                    self.peg_in_confirm_txid,
+                )))
+            }
+        };
+
+        if event.operator_public_key != peg_out_chain_event.operator_public_key {
+            return Err(Error::L2(L2Error::PegOutBurnOperatorMismatch {
+                expected: peg_out_chain_event.operator_public_key,
+                actual: event.operator_public_key,
+            }));
+        }
+        if event.amount != peg_out_chain_event.amount {
+            return Err(Error::L2(L2Error::PegOutBurnAmountMismatch {
+                expected: peg_out_chain_event.amount,
+                actual: event.amount,
+            }));
+        }
+
+        Ok(event.clone())
+    }
+
     async fn get_peg_out_statuses(
         &self,
         client: &AsyncClient,
@@ -2197,83 +3508,15 @@ impl PegOutGraph {
     }
 
     pub async fn validate(&self, client: &AsyncClient) -> Result<(), Error> {
-        let peg_out_graph = self.new_for_validation();
+        self.validate_assert_commit_input_order()?;
+        self.validate_operator_collateral()?;
+        self.validate_presigning_window()?;
+        validate_reward_script(&self.operator_reward_script()).map_err(Error::Validation)?;
 
-        validate_transaction(
-            self.assert_initial_transaction.tx(),
-            peg_out_graph.assert_initial_transaction.tx(),
-            self.assert_initial_transaction.name(),
-        )?;
-        validate_transaction(
-            self.assert_commit_1_transaction.tx(),
-            peg_out_graph.assert_commit_1_transaction.tx(),
-            self.assert_commit_1_transaction.name(),
-        )?;
-        validate_transaction(
-            self.assert_commit_2_transaction.tx(),
-            peg_out_graph.assert_commit_2_transaction.tx(),
-            self.assert_commit_2_transaction.name(),
-        )?;
-        validate_transaction(
-            self.assert_final_transaction.tx(),
-            peg_out_graph.assert_final_transaction.tx(),
-            self.assert_final_transaction.name(),
-        )?;
-        validate_transaction(
-            self.challenge_transaction.tx(),
-            peg_out_graph.challenge_transaction.tx(),
-            self.challenge_transaction.name(),
-        )?;
-        validate_transaction(
-            self.disprove_chain_transaction.tx(),
-            peg_out_graph.disprove_chain_transaction.tx(),
-            self.disprove_chain_transaction.name(),
-        )?;
-        validate_transaction(
-            self.disprove_transaction.tx(),
-            peg_out_graph.disprove_transaction.tx(),
-            self.disprove_transaction.name(),
-        )?;
-        validate_transaction(
-            self.peg_out_confirm_transaction.tx(),
-            peg_out_graph.peg_out_confirm_transaction.tx(),
-            self.peg_out_confirm_transaction.name(),
-        )?;
-        validate_transaction(
-            self.kick_off_1_transaction.tx(),
-            peg_out_graph.kick_off_1_transaction.tx(),
-            self.kick_off_1_transaction.name(),
-        )?;
-        validate_transaction(
-            self.kick_off_2_transaction.tx(),
-            peg_out_graph.kick_off_2_transaction.tx(),
-            self.kick_off_2_transaction.name(),
-        )?;
-        validate_transaction(
-            self.kick_off_timeout_transaction.tx(),
-            peg_out_graph.kick_off_timeout_transaction.tx(),
-            self.kick_off_timeout_transaction.name(),
-        )?;
-        validate_transaction(
-            self.start_time_transaction.tx(),
-            peg_out_graph.start_time_transaction.tx(),
-            self.start_time_transaction.name(),
-        )?;
-        validate_transaction(
-            self.start_time_timeout_transaction.tx(),
-            peg_out_graph.start_time_timeout_transaction.tx(),
-            self.start_time_timeout_transaction.name(),
-        )?;
-        validate_transaction(
-            self.take_1_transaction.tx(),
-            peg_out_graph.take_1_transaction.tx(),
-            self.take_1_transaction.name(),
-        )?;
-        validate_transaction(
-            self.take_2_transaction.tx(),
-            peg_out_graph.take_2_transaction.tx(),
-            self.take_2_transaction.name(),
-        )?;
+        let peg_out_graph = self.new_for_validation();
+        for (tx, derived_tx, tx_name) in self.derived_transaction_pairs(&peg_out_graph) {
+            validate_transaction(tx, derived_tx, tx_name)?;
+        }
 
         let txs_with_commits = vec![
             (
@@ -2312,28 +3555,338 @@ impl PegOutGraph {
             validate_witness(tx, &tx_name, tx_status_res, onchain_tx_res)?;
         }
 
-        verify_public_nonces_for_tx(&self.assert_initial_transaction)?;
-        verify_public_nonces_for_tx(&self.assert_final_transaction)?;
-        verify_public_nonces_for_tx(&self.disprove_chain_transaction)?;
-        verify_public_nonces_for_tx(&self.disprove_transaction)?;
-        verify_public_nonces_for_tx(&self.kick_off_timeout_transaction)?;
-        verify_public_nonces_for_tx(&self.start_time_transaction)?;
-        verify_public_nonces_for_tx(&self.start_time_timeout_transaction)?;
-        verify_public_nonces_for_tx(&self.take_1_transaction)?;
-        verify_public_nonces_for_tx(&self.take_2_transaction)?;
+        for result in self.public_nonce_checks() {
+            result?;
+        }
+
+        Ok(())
+    }
+
+    // Each transaction of the graph next to the one `new_for_validation` derives from its connectors
+    fn derived_transaction_pairs<'a>(
+        &'a self,
+        derived: &'a PegOutGraph,
+    ) -> [(&'a Transaction, &'a Transaction, &'static str); 15] {
+        [
+            (
+                self.assert_initial_transaction.tx(),
+                derived.assert_initial_transaction.tx(),
+                self.assert_initial_transaction.name(),
+            ),
+            (
+                self.assert_commit_1_transaction.tx(),
+                derived.assert_commit_1_transaction.tx(),
+                self.assert_commit_1_transaction.name(),
+            ),
+            (
+                self.assert_commit_2_transaction.tx(),
+                derived.assert_commit_2_transaction.tx(),
+                self.assert_commit_2_transaction.name(),
+            ),
+            (
+                self.assert_final_transaction.tx(),
+                derived.assert_final_transaction.tx(),
+                self.assert_final_transaction.name(),
+            ),
+            (
+                self.challenge_transaction.tx(),
+                derived.challenge_transaction.tx(),
+                self.challenge_transaction.name(),
+            ),
+            (
+                self.disprove_chain_transaction.tx(),
+                derived.disprove_chain_transaction.tx(),
+                self.disprove_chain_transaction.name(),
+            ),
+            (
+                self.disprove_transaction.tx(),
+                derived.disprove_transaction.tx(),
+                self.disprove_transaction.name(),
+            ),
+            (
+                self.peg_out_confirm_transaction.tx(),
+                derived.peg_out_confirm_transaction.tx(),
+                self.peg_out_confirm_transaction.name(),
+            ),
+            (
+                self.kick_off_1_transaction.tx(),
+                derived.kick_off_1_transaction.tx(),
+                self.kick_off_1_transaction.name(),
+            ),
+            (
+                self.kick_off_2_transaction.tx(),
+                derived.kick_off_2_transaction.tx(),
+                self.kick_off_2_transaction.name(),
+            ),
+            (
+                self.kick_off_timeout_transaction.tx(),
+                derived.kick_off_timeout_transaction.tx(),
+                self.kick_off_timeout_transaction.name(),
+            ),
+            (
+                self.start_time_transaction.tx(),
+                derived.start_time_transaction.tx(),
+                self.start_time_transaction.name(),
+            ),
+            (
+                self.start_time_timeout_transaction.tx(),
+                derived.start_time_timeout_transaction.tx(),
+                self.start_time_timeout_transaction.name(),
+            ),
+            (
+                self.take_1_transaction.tx(),
+                derived.take_1_transaction.tx(),
+                self.take_1_transaction.name(),
+            ),
+            (
+                self.take_2_transaction.tx(),
+                derived.take_2_transaction.tx(),
+                self.take_2_transaction.name(),
+            ),
+        ]
+    }
+
+    // Winternitz public keys of every connector committing to a message, by connector name. Some
+    // messages are committed to by more than one connector under the same key.
+    pub fn commitment_public_keys(
+        &self,
+    ) -> Vec<(
+        &'static str,
+        &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    )> {
+        [
+            ("connector 1", &self.connector_1.commitment_public_keys),
+            ("connector 2", &self.connector_2.commitment_public_keys),
+            ("connector 6", &self.connector_6.commitment_public_keys),
+            ("connector b", &self.connector_b.commitment_public_keys),
+        ]
+        .into_iter()
+        .chain(
+            self.connector_e_1
+                .connectors_e
+                .iter()
+                .chain(&self.connector_e_2.connectors_e)
+                .map(|connector| ("connector e", &connector.commitment_public_keys)),
+        )
+        .collect()
+    }
+
+    fn public_nonce_checks(&self) -> [Result<(), Error>; 9] {
+        [
+            verify_public_nonces_for_tx(&self.assert_initial_transaction),
+            verify_public_nonces_for_tx(&self.assert_final_transaction),
+            verify_public_nonces_for_tx(&self.disprove_chain_transaction),
+            verify_public_nonces_for_tx(&self.disprove_transaction),
+            verify_public_nonces_for_tx(&self.kick_off_timeout_transaction),
+            verify_public_nonces_for_tx(&self.start_time_transaction),
+            verify_public_nonces_for_tx(&self.start_time_timeout_transaction),
+            verify_public_nonces_for_tx(&self.take_1_transaction),
+            verify_public_nonces_for_tx(&self.take_2_transaction),
+        ]
+    }
+
+    // What `validate` checks without querying the chain, plus the committee signatures the
+    // pre-signed transactions still miss and the shape of the commitment public keys. Every problem
+    // is reported instead of the first one.
+    pub fn integrity_issues(&self, verifier_pubkeys: &[PublicKey]) -> Vec<GraphIntegrityIssue> {
+        let mut issues: Vec<GraphIntegrityIssue> = [
+            self.check_protocol_params().map_err(Error::Validation),
+            self.check_segment_layout().map_err(Error::Chunker),
+            self.validate_assert_commit_input_order(),
+            self.validate_operator_collateral(),
+            self.validate_presigning_window(),
+            validate_reward_script(&self.operator_reward_script()).map_err(Error::Validation),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .map(GraphIntegrityIssue::Invalid)
+        .collect();
+
+        let malformed_public_keys: Vec<GraphIntegrityIssue> = self
+            .commitment_public_keys()
+            .into_iter()
+            .flat_map(|(connector, public_keys)| {
+                commitment_public_key_issues(connector, public_keys)
+            })
+            .collect();
+
+        // Connectors cannot be derived from malformed keys, the scripts would index past their end
+        if malformed_public_keys.is_empty() {
+            let peg_out_graph = self.new_for_validation();
+            issues.extend(
+                self.derived_transaction_pairs(&peg_out_graph)
+                    .into_iter()
+                    .filter_map(|(tx, derived_tx, tx_name)| {
+                        validate_transaction(tx, derived_tx, tx_name).err()
+                    })
+                    .map(GraphIntegrityIssue::Invalid),
+            );
+        }
+        issues.extend(malformed_public_keys);
+        issues.extend(
+            self.public_nonce_checks()
+                .into_iter()
+                .filter_map(Result::err)
+                .map(GraphIntegrityIssue::Invalid),
+        );
+
+        for transaction in PegOutPresignedTransaction::iter() {
+            let missing = self.missing_signatures(transaction, verifier_pubkeys);
+            if !missing.is_empty() {
+                issues.push(GraphIntegrityIssue::MissingSignatures {
+                    transaction: transaction.to_string(),
+                    missing,
+                });
+            }
+        }
+
+        issues
+    }
+
+    // The assert commit connectors must be in canonical order, and the order recorded in the assert
+    // commit txs must match them. Otherwise parties would compute different sighashes and merge
+    // witnesses into the wrong inputs.
+    // Kick-off 1 must post at least the collateral the graph parameters ask for, and none if they
+    // ask for none. The spending transactions are checked against it by `new_for_validation`.
+    pub fn validate_operator_collateral(&self) -> Result<(), Error> {
+        let required = self.parameters.operator_collateral;
+        let posted = self.operator_collateral().unwrap_or(Amount::ZERO);
+        let valid = match required {
+            Amount::ZERO => posted == Amount::ZERO,
+            _ => posted >= required,
+        };
+        if !valid {
+            return Err(Error::Validation(
+                ValidationError::OperatorCollateralMismatch { required, posted },
+            ));
+        }
+
+        Ok(())
+    }
+
+    // The deadline must follow from the recorded expiry, so it cannot be pushed back without
+    // tampering with the parameters as well
+    pub fn validate_presigning_window(&self) -> Result<(), Error> {
+        if self.presigning_window == PresigningWindow::default() {
+            return Ok(()); // graphs created before the window was recorded
+        }
+
+        let expected = self
+            .parameters
+            .presigning_expiry_days
+            .map(|days| self.presigning_window.created_at + days as u64 * SECONDS_PER_DAY);
+        if self.presigning_window.deadline != expected {
+            return Err(Error::Validation(
+                ValidationError::PresigningDeadlineMismatch {
+                    expected,
+                    found: self.presigning_window.deadline,
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_assert_commit_input_order(&self) -> Result<(), Error> {
+        let txs = [
+            (
+                self.assert_commit_1_transaction.name(),
+                self.connector_e_1.input_order(),
+                self.assert_commit_1_transaction.input_order(),
+            ),
+            (
+                self.assert_commit_2_transaction.name(),
+                self.connector_e_2.input_order(),
+                self.assert_commit_2_transaction.input_order(),
+            ),
+        ];
+
+        for (tx_name, connectors_order, recorded_order) in txs {
+            let mismatch = find_input_order_mismatch(
+                &canonical_input_order(&connectors_order),
+                &connectors_order,
+            )
+            .or_else(|| match recorded_order.is_empty() {
+                true => None, // graphs created before the order was recorded
+                false => find_input_order_mismatch(&connectors_order, recorded_order),
+            });
+            if let Some((input_index, expected, found)) = mismatch {
+                return Err(Error::Validation(
+                    ValidationError::AssertCommitInputOrderMismatch {
+                        tx_name,
+                        input_index,
+                        expected,
+                        found,
+                    },
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Peg-out graphs have no committee of their own, they must be signed by the committee of
+    // their peg-in graph
+    pub fn validate_committee(&self, peg_in_graph: &PegInGraph) -> Result<(), Error> {
+        let committee = peg_in_graph.n_of_n_public_keys();
+        if generate_n_of_n_public_key(committee).0 != self.n_of_n_public_key {
+            return Err(Error::Validation(ValidationError::CommitteeMismatch(
+                self.id().clone(),
+            )));
+        }
+
+        verify_committee_contributions(&self.assert_initial_transaction, committee)?;
+        verify_committee_contributions(&self.assert_final_transaction, committee)?;
+        verify_committee_contributions(&self.disprove_chain_transaction, committee)?;
+        verify_committee_contributions(&self.disprove_transaction, committee)?;
+        verify_committee_contributions(&self.kick_off_timeout_transaction, committee)?;
+        verify_committee_contributions(&self.start_time_transaction, committee)?;
+        verify_committee_contributions(&self.start_time_timeout_transaction, committee)?;
+        verify_committee_contributions(&self.take_1_transaction, committee)?;
+        verify_committee_contributions(&self.take_2_transaction, committee)?;
 
         Ok(())
     }
 
-    pub fn merge(&mut self, source_peg_out_graph: &PegOutGraph) {
+    pub fn merge(&mut self, source_peg_out_graph: &PegOutGraph) -> Result<(), Error> {
+        let mut stale_transactions = vec![];
+        for transaction in PegOutPresignedTransaction::iter() {
+            let round = self.resign_round(transaction);
+            let source_round = source_peg_out_graph.resign_round(transaction);
+            if source_round > round {
+                self.presigned_tx_mut(transaction).reset_musig2_ceremony();
+                self.resign_rounds.insert(transaction, source_round);
+                self.n_of_n_presigned = false;
+            } else if source_round < round {
+                stale_transactions.push(transaction);
+            }
+        }
+
+        // Nonces and signatures of invalidated transactions must not be merged back
+        let cleaned_source_peg_out_graph;
+        let source_peg_out_graph = match stale_transactions.is_empty() {
+            true => source_peg_out_graph,
+            false => {
+                let mut source = source_peg_out_graph.clone();
+                for transaction in stale_transactions {
+                    source.presigned_tx_mut(transaction).reset_musig2_ceremony();
+                }
+                cleaned_source_peg_out_graph = source;
+                &cleaned_source_peg_out_graph
+            }
+        };
+
+        self.peg_out_confirm_transaction
+            .merge(&source_peg_out_graph.peg_out_confirm_transaction);
+
         self.assert_initial_transaction
             .merge(&source_peg_out_graph.assert_initial_transaction);
 
         self.assert_commit_1_transaction
-            .merge(&source_peg_out_graph.assert_commit_1_transaction);
+            .merge(&source_peg_out_graph.assert_commit_1_transaction)?;
 
         self.assert_commit_2_transaction
-            .merge(&source_peg_out_graph.assert_commit_2_transaction);
+            .merge(&source_peg_out_graph.assert_commit_2_transaction)?;
 
         self.assert_final_transaction
             .merge(&source_peg_out_graph.assert_final_transaction);
@@ -2361,18 +3914,46 @@ impl PegOutGraph {
 
         self.take_2_transaction
             .merge(&source_peg_out_graph.take_2_transaction);
+
+        for (verifier_public_key, leaf_indexes) in
+            source_peg_out_graph.merkle_root_spot_check_requests.iter()
+        {
+            self.merkle_root_spot_check_requests
+                .entry(*verifier_public_key)
+                .or_insert_with(|| leaf_indexes.clone());
+        }
+        for (leaf_index, proof) in source_peg_out_graph.merkle_root_spot_check_proofs.iter() {
+            self.merkle_root_spot_check_proofs
+                .entry(*leaf_index)
+                .or_insert_with(|| proof.clone());
+        }
+
+        Ok(())
+    }
+
+    // Collateral output of kick-off 1, returned by take 1 and take 2 and slashed by the other
+    // transactions ending the graph
+    fn collateral_input(kick_off_1_transaction: &KickOff1Transaction) -> Option<Input> {
+        kick_off_1_transaction.collateral_vout().map(|vout| Input {
+            outpoint: OutPoint {
+                txid: kick_off_1_transaction.tx().compute_txid(),
+                vout: vout.to_u32().unwrap(),
+            },
+            amount: kick_off_1_transaction.tx().output[vout].value,
+        })
     }
 
     #[allow(clippy::too_many_arguments)]
     fn create_new_connectors(
         network: Network,
+        timelocks: &GraphTimelocks,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         operator_taproot_public_key: &XOnlyPublicKey,
         operator_public_key: &PublicKey,
-        connector_1_commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
-        connector_2_commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
-        connector_6_commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
-        connector_b_commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        connector_1_commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+        connector_2_commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+        connector_6_commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+        connector_b_commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
         connector_e1_commitment_public_keys: &[BTreeMap<
             CommitmentMessageId,
             WinternitzPublicKey,
@@ -2381,22 +3962,30 @@ impl PegOutGraph {
             CommitmentMessageId,
             WinternitzPublicKey,
         >],
+        claimed_connector_c_merkle_root: Option<TapNodeHash>,
     ) -> PegOutConnectors {
+        let timelocks = timelocks.for_network(network);
+
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
-        let connector_1 = Connector1::new(
+        let mut connector_1 = Connector1::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
             connector_1_commitment_public_keys,
         );
+        connector_1.num_blocks_timelock_leaf_0 = timelocks.kick_off_2;
+        connector_1.num_blocks_timelock_leaf_1 = timelocks.kick_off_timeout;
+        connector_1.num_blocks_timelock_leaf_2 = timelocks.start_time_timeout;
         let connector_2 = Connector2::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
             connector_2_commitment_public_keys,
         );
-        let connector_3 = Connector3::new(network, operator_public_key);
-        let connector_4 = Connector4::new(network, operator_public_key);
+        let mut connector_3 = Connector3::new(network, operator_public_key);
+        connector_3.num_blocks_timelock = timelocks.take_1;
+        let mut connector_4 = Connector4::new(network, operator_public_key);
+        connector_4.num_blocks_timelock = timelocks.take_2;
         let connector_5 = Connector5::new(network, n_of_n_taproot_public_key);
         let connector_6 = Connector6::new(
             network,
@@ -2408,33 +3997,41 @@ impl PegOutGraph {
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
         );
-        let connector_b = ConnectorB::new(
+        let mut connector_b = ConnectorB::new(
             network,
             n_of_n_taproot_public_key,
             connector_b_commitment_public_keys,
         );
+        connector_b.num_blocks_timelock_1 = timelocks.assert_initial;
 
         // connector c pks = connector e1 pks + connector e2 pks
         let commitment_public_keys = &merge_to_connector_c_commits_public_key(
             connector_e1_commitment_public_keys,
             connector_e2_commitment_public_keys,
         );
-        let connector_c =
-            ConnectorC::new(network, operator_taproot_public_key, commitment_public_keys);
+        let connector_c = match claimed_connector_c_merkle_root {
+            Some(_) => ConnectorC::new_for_validation(
+                network,
+                operator_taproot_public_key,
+                commitment_public_keys,
+                claimed_connector_c_merkle_root,
+            ),
+            None => ConnectorC::new(network, operator_taproot_public_key, commitment_public_keys),
+        };
         let connector_d = ConnectorD::new(network, n_of_n_taproot_public_key);
 
-        let assert_commit_connectors_e_1 = AssertCommit1ConnectorsE {
-            connectors_e: connector_e1_commitment_public_keys
+        let assert_commit_connectors_e_1 = AssertCommit1ConnectorsE::new(
+            connector_e1_commitment_public_keys
                 .iter()
                 .map(|x| ConnectorE::new(network, operator_public_key, x))
                 .collect(),
-        };
-        let assert_commit_connectors_e_2 = AssertCommit2ConnectorsE {
-            connectors_e: connector_e2_commitment_public_keys
+        );
+        let assert_commit_connectors_e_2 = AssertCommit2ConnectorsE::new(
+            connector_e2_commitment_public_keys
                 .iter()
                 .map(|x| ConnectorE::new(network, operator_public_key, x))
                 .collect(),
-        };
+        );
 
         let connector_f_1 = ConnectorF1::new(network, operator_public_key);
         let connector_f_2 = ConnectorF2::new(network, operator_public_key);
@@ -2490,6 +4087,963 @@ impl PegOutGraph {
         all_txs.into_iter()
     }
 
+    fn presigned_tx(
+        &self,
+        transaction: PegOutPresignedTransaction,
+    ) -> &dyn PreSignedMusig2Transaction {
+        match transaction {
+            PegOutPresignedTransaction::AssertInitial => &self.assert_initial_transaction,
+            PegOutPresignedTransaction::AssertFinal => &self.assert_final_transaction,
+            PegOutPresignedTransaction::DisproveChain => &self.disprove_chain_transaction,
+            PegOutPresignedTransaction::Disprove => &self.disprove_transaction,
+            PegOutPresignedTransaction::KickOffTimeout => &self.kick_off_timeout_transaction,
+            PegOutPresignedTransaction::StartTimeTimeout => &self.start_time_timeout_transaction,
+            PegOutPresignedTransaction::Take1 => &self.take_1_transaction,
+            PegOutPresignedTransaction::Take2 => &self.take_2_transaction,
+        }
+    }
+
+    fn presigned_tx_mut(
+        &mut self,
+        transaction: PegOutPresignedTransaction,
+    ) -> &mut dyn PreSignedMusig2Transaction {
+        match transaction {
+            PegOutPresignedTransaction::AssertInitial => &mut self.assert_initial_transaction,
+            PegOutPresignedTransaction::AssertFinal => &mut self.assert_final_transaction,
+            PegOutPresignedTransaction::DisproveChain => &mut self.disprove_chain_transaction,
+            PegOutPresignedTransaction::Disprove => &mut self.disprove_transaction,
+            PegOutPresignedTransaction::KickOffTimeout => &mut self.kick_off_timeout_transaction,
+            PegOutPresignedTransaction::StartTimeTimeout => {
+                &mut self.start_time_timeout_transaction
+            }
+            PegOutPresignedTransaction::Take1 => &mut self.take_1_transaction,
+            PegOutPresignedTransaction::Take2 => &mut self.take_2_transaction,
+        }
+    }
+
+    fn pre_sign_transaction(
+        &mut self,
+        transaction: PegOutPresignedTransaction,
+        verifier_context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) -> Result<(), Error> {
+        let txid = self.presigned_tx(transaction).tx().compute_txid();
+        match transaction {
+            PegOutPresignedTransaction::AssertInitial => self.assert_initial_transaction.pre_sign(
+                verifier_context,
+                &self.connector_b,
+                &secret_nonces[&txid],
+            ),
+            PegOutPresignedTransaction::AssertFinal => self.assert_final_transaction.pre_sign(
+                verifier_context,
+                &self.connector_d,
+                &secret_nonces[&txid],
+            ),
+            PegOutPresignedTransaction::DisproveChain => self.disprove_chain_transaction.pre_sign(
+                verifier_context,
+                &self.connector_0,
+                &self.connector_b,
+                &secret_nonces[&txid],
+            ),
+            PegOutPresignedTransaction::Disprove => self.disprove_transaction.pre_sign(
+                verifier_context,
+                &self.connector_0,
+                &self.connector_5,
+                &secret_nonces[&txid],
+            ),
+            PegOutPresignedTransaction::KickOffTimeout => {
+                self.kick_off_timeout_transaction.pre_sign(
+                    verifier_context,
+                    &self.connector_0,
+                    &self.connector_1,
+                    &secret_nonces[&txid],
+                )
+            }
+            PegOutPresignedTransaction::StartTimeTimeout => {
+                self.start_time_timeout_transaction.pre_sign(
+                    verifier_context,
+                    &self.connector_0,
+                    &self.connector_1,
+                    &self.connector_2,
+                    &secret_nonces[&txid],
+                )
+            }
+            PegOutPresignedTransaction::Take1 => self.take_1_transaction.pre_sign(
+                verifier_context,
+                &self.connector_0,
+                &self.connector_b,
+                &secret_nonces[&txid],
+            ),
+            PegOutPresignedTransaction::Take2 => self.take_2_transaction.pre_sign(
+                verifier_context,
+                &self.connector_0,
+                &self.connector_5,
+                &secret_nonces[&txid],
+            ),
+        }
+    }
+
+    pub fn presigned_txid(&self, transaction: PegOutPresignedTransaction) -> Txid {
+        self.presigned_tx(transaction).tx().compute_txid()
+    }
+
+    pub fn resign_round(&self, transaction: PegOutPresignedTransaction) -> u32 {
+        self.resign_rounds
+            .get(&transaction)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // Drops the nonces and signatures of the given transactions and starts a new signing round
+    // for them. The graph is not considered pre-signed until the committee signed them again.
+    // Transactions that no longer match the connectors or the operator reward script of the graph
+    // are rebuilt first. The operator signs its inputs of rebuilt transactions again with
+    // `pre_sign_operator_inputs`.
+    pub fn invalidate_presigned_transactions(
+        &mut self,
+        transactions: &[PegOutPresignedTransaction],
+    ) -> Result<(), Error> {
+        if self.is_peg_out_initiated() {
+            return Err(Error::Graph(GraphError::PegOutAlreadyInitiated));
+        }
+
+        let rebuilt = self.new_for_validation();
+        for transaction in transactions {
+            if self.presigned_txid(*transaction) != rebuilt.presigned_txid(*transaction) {
+                self.replace_presigned_tx(*transaction, &rebuilt);
+            }
+            self.presigned_tx_mut(*transaction).reset_musig2_ceremony();
+            *self.resign_rounds.entry(*transaction).or_default() += 1;
+        }
+        self.n_of_n_presigned = false;
+
+        Ok(())
+    }
+
+    fn replace_presigned_tx(
+        &mut self,
+        transaction: PegOutPresignedTransaction,
+        from: &PegOutGraph,
+    ) {
+        match transaction {
+            PegOutPresignedTransaction::AssertInitial => {
+                self.assert_initial_transaction = from.assert_initial_transaction.clone()
+            }
+            PegOutPresignedTransaction::AssertFinal => {
+                self.assert_final_transaction = from.assert_final_transaction.clone()
+            }
+            PegOutPresignedTransaction::DisproveChain => {
+                self.disprove_chain_transaction = from.disprove_chain_transaction.clone()
+            }
+            PegOutPresignedTransaction::Disprove => {
+                self.disprove_transaction = from.disprove_transaction.clone()
+            }
+            PegOutPresignedTransaction::KickOffTimeout => {
+                self.kick_off_timeout_transaction = from.kick_off_timeout_transaction.clone()
+            }
+            PegOutPresignedTransaction::StartTimeTimeout => {
+                self.start_time_timeout_transaction = from.start_time_timeout_transaction.clone()
+            }
+            PegOutPresignedTransaction::Take1 => {
+                self.take_1_transaction = from.take_1_transaction.clone()
+            }
+            PegOutPresignedTransaction::Take2 => {
+                self.take_2_transaction = from.take_2_transaction.clone()
+            }
+        }
+    }
+
+    pub fn resign_status(&self, verifier_pubkeys: &[PublicKey]) -> Vec<PegOutResignStatus> {
+        PegOutPresignedTransaction::iter()
+            .filter(|transaction| self.resign_round(*transaction) > 0)
+            .map(|transaction| {
+                let (signed, pending): (Vec<PublicKey>, Vec<PublicKey>) = verifier_pubkeys
+                    .iter()
+                    .copied()
+                    .partition(|pubkey| self.presigned_tx(transaction).has_signatures_for(*pubkey));
+                PegOutResignStatus {
+                    transaction,
+                    template_id: self.presigned_tx(transaction).template_id(),
+                    round: self.resign_round(transaction),
+                    signed,
+                    pending,
+                }
+            })
+            .collect()
+    }
+
+    // Whether this graph, as staged by a committee member, may follow `previous`, the same graph
+    // as the committee holds it. Re-sign rounds start one at a time and only before the peg-out,
+    // and nonces or signatures contributed within a round are never replaced.
+    pub fn validate_transition(&self, previous: &PegOutGraph) -> Result<(), Error> {
+        let illegal = |reason: String| {
+            Error::Validation(ValidationError::IllegalTransition(
+                self.id().clone(),
+                reason,
+            ))
+        };
+        for transaction in PegOutPresignedTransaction::iter() {
+            let round = self.resign_round(transaction);
+            let previous_round = previous.resign_round(transaction);
+            if round > previous_round + 1 {
+                return Err(illegal(format!(
+                    "{transaction} skips from re-sign round {previous_round} to {round}"
+                )));
+            }
+            if round > previous_round && previous.is_peg_out_initiated() {
+                return Err(illegal(format!(
+                    "{transaction} re-signed after the peg-out was initiated"
+                )));
+            }
+            if round == previous_round {
+                let conflicts = conflicting_contributions(
+                    previous.presigned_tx(transaction),
+                    self.presigned_tx(transaction),
+                );
+                if !conflicts.is_empty() {
+                    let conflicts = conflicts
+                        .iter()
+                        .map(PublicKey::to_string)
+                        .collect::<Vec<_>>();
+                    return Err(illegal(format!(
+                        "{transaction} replaces the contributions of {}",
+                        conflicts.join(", ")
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Nonces and signatures collected so far for the pre-signed transactions that still miss some
+    pub fn signing_progress(&self, verifier_pubkeys: &[PublicKey]) -> Vec<Musig2SigningProgress> {
+        PegOutPresignedTransaction::iter()
+            .map(|transaction| {
+                Musig2SigningProgress::new(
+                    transaction.to_string(),
+                    self.presigned_tx(transaction),
+                    verifier_pubkeys,
+                )
+            })
+            .filter(|progress| !progress.is_complete())
+            .collect()
+    }
+
+    // Sighash preimages of all pre-signed transactions, for signing them on external devices or
+    // auditing what the committee signs
+    pub fn signing_bundle(&self) -> SigningBundle {
+        SigningBundle {
+            graph_id: self.id().clone(),
+            transactions: PegOutPresignedTransaction::iter()
+                .map(|transaction| {
+                    SigningBundleTransaction::new(
+                        transaction.to_string(),
+                        self.presigned_tx(transaction),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    // Inputs that are only signed, or whose witness is only completed, when the transaction is
+    // broadcast. Their witness depends on on-chain data that does not exist before.
+    fn broadcast_time_inputs(&self, transaction: PegOutPresignedTransaction) -> Vec<usize> {
+        match transaction {
+            PegOutPresignedTransaction::Take2 => {
+                vec![self.take_2_transaction.connector_c_input_index()]
+            }
+            PegOutPresignedTransaction::Disprove => vec![1],
+            _ => vec![],
+        }
+    }
+
+    // Committee signatures and witnesses the transaction still misses. Does not query the chain.
+    pub fn missing_signatures(
+        &self,
+        transaction: PegOutPresignedTransaction,
+        verifier_pubkeys: &[PublicKey],
+    ) -> Vec<MissingPrereq> {
+        let presigned_tx = self.presigned_tx(transaction);
+        let verifier_inputs = presigned_tx.verifier_inputs();
+        let broadcast_time_inputs = self.broadcast_time_inputs(transaction);
+
+        let mut missing = vec![];
+        for (input_index, input) in presigned_tx.tx().input.iter().enumerate() {
+            if broadcast_time_inputs.contains(&input_index) {
+                continue;
+            }
+            if verifier_inputs.contains(&input_index) {
+                let pending: Vec<PublicKey> = verifier_pubkeys
+                    .iter()
+                    .copied()
+                    .filter(|pubkey| {
+                        !presigned_tx
+                            .musig2_signatures()
+                            .get(&input_index)
+                            .is_some_and(|signatures| signatures.contains_key(pubkey))
+                    })
+                    .collect();
+                if !pending.is_empty() {
+                    missing.push(MissingPrereq::Signatures {
+                        input_index,
+                        pending,
+                    });
+                    continue;
+                }
+            }
+            // All partial signatures are there but nobody aggregated them into the witness yet
+            if input.witness.is_empty() {
+                missing.push(MissingPrereq::Witness(input_index));
+            }
+        }
+
+        if transaction == PegOutPresignedTransaction::DisproveChain {
+            if self.start_time_transaction.start_time_witness.is_none() {
+                missing.push(MissingPrereq::CommitmentWitness(
+                    CommitmentMessageId::StartTime,
+                ));
+            }
+            if self
+                .kick_off_2_transaction
+                .superblock_hash_witness
+                .is_none()
+            {
+                missing.push(MissingPrereq::CommitmentWitness(
+                    CommitmentMessageId::SuperblockHash,
+                ));
+            }
+        }
+
+        missing
+    }
+
+    // Checks everything the transaction needs before it can be broadcast: committee signatures,
+    // witnesses, confirmations of the transactions it spends and their relative timelocks. The
+    // timelocks are read from the input sequences, so they are the ones the node enforces.
+    pub async fn ready_to_broadcast(
+        &self,
+        client: &AsyncClient,
+        transaction: PegOutPresignedTransaction,
+        verifier_pubkeys: &[PublicKey],
+    ) -> Result<(), MissingPrereqs> {
+        let tx = self.presigned_tx(transaction).tx();
+        let mut missing = self.missing_signatures(transaction, verifier_pubkeys);
+        let preceding_txs = self.preceding_txs(transaction);
+
+        let txids: Vec<Txid> = preceding_txs.keys().copied().collect();
+        let statuses = get_tx_statuses(client, &txids).await;
+        let blockchain_height = client.get_height().await;
+        if let Err(e) = &blockchain_height {
+            missing.push(MissingPrereq::ChainStatusUnavailable(e.to_string()));
+        }
+
+        for ((txid, num_blocks), status) in preceding_txs.into_iter().zip(statuses) {
+            let name = self.preceding_tx_name(&txid);
+            match status {
+                Ok(status) if !status.confirmed => {
+                    missing.push(MissingPrereq::Confirmation(NamedTx {
+                        txid,
+                        name,
+                        confirmed: false,
+                    }));
+                }
+                Ok(status) => match (status.block_height, &blockchain_height) {
+                    (Some(block_height), Ok(height)) if block_height + num_blocks > *height => {
+                        missing.push(MissingPrereq::Timelock {
+                            preceding_tx: NamedTx {
+                                txid,
+                                name,
+                                confirmed: true,
+                            },
+                            remaining_blocks: block_height + num_blocks - height,
+                        });
+                    }
+                    (None, _) if num_blocks > 0 => {
+                        missing.push(MissingPrereq::ChainStatusUnavailable(format!(
+                            "no block height reported for {name} ({txid})"
+                        )));
+                    }
+                    _ => {}
+                },
+                Err(e) => missing.push(MissingPrereq::ChainStatusUnavailable(e.to_string())),
+            }
+        }
+
+        match missing.is_empty() {
+            true => Ok(()),
+            false => Err(MissingPrereqs {
+                tx_name: transaction.to_string(),
+                txid: tx.compute_txid(),
+                missing,
+            }),
+        }
+    }
+
+    // The transactions spent by a pre-signed transaction, with the longest relative timelock in
+    // blocks on their outputs
+    pub fn preceding_txs(&self, transaction: PegOutPresignedTransaction) -> BTreeMap<Txid, u32> {
+        let mut preceding_txs: BTreeMap<Txid, u32> = BTreeMap::new();
+        for input in &self.presigned_tx(transaction).tx().input {
+            let num_blocks = match input.sequence.to_relative_lock_time() {
+                Some(relative::LockTime::Blocks(height)) => height.value() as u32,
+                _ => 0,
+            };
+            let timelock = preceding_txs.entry(input.previous_output.txid).or_default();
+            *timelock = (*timelock).max(num_blocks);
+        }
+        preceding_txs
+    }
+
+    pub fn preceding_tx_name(&self, txid: &Txid) -> &'static str {
+        if *txid == self.peg_in_confirm_txid {
+            return PEG_IN_CONFIRM_TX_NAME;
+        }
+        [
+            NamedTx::for_tx(&self.kick_off_1_transaction, false),
+            NamedTx::for_tx(&self.kick_off_2_transaction, false),
+            NamedTx::for_tx(&self.start_time_transaction, false),
+            NamedTx::for_tx(&self.challenge_transaction, false),
+            NamedTx::for_tx(&self.assert_initial_transaction, false),
+            NamedTx::for_tx(&self.assert_commit_1_transaction, false),
+            NamedTx::for_tx(&self.assert_commit_2_transaction, false),
+            NamedTx::for_tx(&self.assert_final_transaction, false),
+        ]
+        .into_iter()
+        .find(|named_tx| named_tx.txid == *txid)
+        .map_or("Unknown", |named_tx| named_tx.name)
+    }
+
+    pub fn kick_off_timelocks(&self) -> KickOffTimelocks {
+        KickOffTimelocks {
+            kick_off_2: self.connector_1.num_blocks_timelock_leaf_0,
+            kick_off_timeout: self.connector_1.num_blocks_timelock_leaf_1,
+            start_time_timeout: self.connector_1.num_blocks_timelock_leaf_2,
+        }
+    }
+
+    // Whether spending from connector C can start right away, see `ConnectorC::is_warm`
+    pub fn is_warm(&self) -> bool {
+        self.connector_c.is_warm()
+    }
+
+    // Graphs serialized before connector C stored its merkle root cannot derive its address
+    // until it is warmed up
+    pub fn knows_connector_c_merkle_root(&self) -> bool {
+        self.connector_c.taproot_merkle_root().is_some()
+    }
+
+    pub fn warm_up(&mut self) {
+        self.connector_c.warm_up();
+    }
+
+    pub fn connector_c_taproot_merkle_root(&self) -> Option<TapNodeHash> {
+        self.connector_c.taproot_merkle_root()
+    }
+
+    // See `ConnectorC::export_lock_scripts`
+    pub fn export_lock_scripts(&self, file_path: &Path) -> io::Result<TapNodeHash> {
+        self.connector_c.export_lock_scripts(file_path)
+    }
+
+    // See `ConnectorC::import_lock_scripts`
+    pub fn import_lock_scripts(&self, file_path: &Path) -> Result<(), ChunkerError> {
+        self.connector_c.import_lock_scripts(file_path)
+    }
+
+    // Output descriptors of the connector addresses, for tracking the graph in wallet software.
+    // Connector C is only described by its output key, its script tree is too large.
+    pub fn connector_descriptors(&self) -> Vec<ConnectorDescriptor> {
+        let mut descriptors = vec![
+            generate_taproot_descriptor("connector_0", &self.connector_0),
+            generate_taproot_descriptor("connector_1", &self.connector_1),
+            generate_taproot_descriptor("connector_2", &self.connector_2),
+            generate_p2wsh_descriptor("connector_3", &self.connector_3),
+            generate_p2wsh_descriptor("connector_4", &self.connector_4),
+            generate_taproot_descriptor("connector_5", &self.connector_5),
+            generate_taproot_descriptor("connector_6", &self.connector_6),
+            generate_taproot_descriptor("connector_a", &self.connector_a),
+            generate_taproot_descriptor("connector_b", &self.connector_b),
+            generate_raw_taproot_descriptor(
+                "connector_c",
+                self.connector_c.taproot_output_key(),
+                self.connector_c.generate_taproot_address(),
+            ),
+            generate_taproot_descriptor("connector_d", &self.connector_d),
+        ];
+        descriptors.extend(self.connector_e_1.connectors_e.iter().enumerate().map(
+            |(i, connector)| generate_taproot_descriptor(&format!("connector_e_1_{i}"), connector),
+        ));
+        descriptors.extend(self.connector_e_2.connectors_e.iter().enumerate().map(
+            |(i, connector)| generate_taproot_descriptor(&format!("connector_e_2_{i}"), connector),
+        ));
+        descriptors.push(generate_p2wsh_descriptor(
+            "connector_f_1",
+            &self.connector_f_1,
+        ));
+        descriptors.push(generate_p2wsh_descriptor(
+            "connector_f_2",
+            &self.connector_f_2,
+        ));
+        descriptors
+    }
+
+    // Connectors whose outputs auxiliary transactions may spend. Connector C is left out, its
+    // leaves are the disprove scripts. The E connectors come last, they are the most expensive
+    // to derive addresses of.
+    fn auxiliary_connectors(&self) -> Vec<(String, AuxiliaryConnector<'_>)> {
+        let mut connectors = vec![
+            (
+                String::from("connector_0"),
+                AuxiliaryConnector::Taproot(&self.connector_0),
+            ),
+            (
+                String::from("connector_1"),
+                AuxiliaryConnector::Taproot(&self.connector_1),
+            ),
+            (
+                String::from("connector_2"),
+                AuxiliaryConnector::Taproot(&self.connector_2),
+            ),
+            (
+                String::from("connector_3"),
+                AuxiliaryConnector::P2wsh(&self.connector_3),
+            ),
+            (
+                String::from("connector_4"),
+                AuxiliaryConnector::P2wsh(&self.connector_4),
+            ),
+            (
+                String::from("connector_5"),
+                AuxiliaryConnector::Taproot(&self.connector_5),
+            ),
+            (
+                String::from("connector_6"),
+                AuxiliaryConnector::Taproot(&self.connector_6),
+            ),
+            (
+                String::from("connector_a"),
+                AuxiliaryConnector::Taproot(&self.connector_a),
+            ),
+            (
+                String::from("connector_b"),
+                AuxiliaryConnector::Taproot(&self.connector_b),
+            ),
+            (
+                String::from("connector_d"),
+                AuxiliaryConnector::Taproot(&self.connector_d),
+            ),
+            (
+                String::from("connector_f_1"),
+                AuxiliaryConnector::P2wsh(&self.connector_f_1),
+            ),
+            (
+                String::from("connector_f_2"),
+                AuxiliaryConnector::P2wsh(&self.connector_f_2),
+            ),
+        ];
+        connectors.extend(self.connector_e_1.connectors_e.iter().enumerate().map(
+            |(i, connector)| {
+                (
+                    format!("connector_e_1_{i}"),
+                    AuxiliaryConnector::Taproot(connector),
+                )
+            },
+        ));
+        connectors.extend(self.connector_e_2.connectors_e.iter().enumerate().map(
+            |(i, connector)| {
+                (
+                    format!("connector_e_2_{i}"),
+                    AuxiliaryConnector::Taproot(connector),
+                )
+            },
+        ));
+        connectors
+    }
+
+    // What a custom transaction needs to spend a connector output of the graph through the given
+    // leaf. Refused while a graph transaction can still spend the output: each graph transaction
+    // spending it must have lost another of its inputs to a confirmed transaction.
+    pub async fn auxiliary_input(
+        &self,
+        client: &AsyncClient,
+        outpoint: OutPoint,
+        leaf_index: u32,
+    ) -> Result<AuxiliaryInput, Error> {
+        let not_connector_output = || Error::Graph(GraphError::NotConnectorOutput(outpoint));
+        let template_txs = self.template_txs();
+        let prevout = template_txs
+            .iter()
+            .find(|(_, tx)| tx.compute_txid() == outpoint.txid)
+            .and_then(|(_, tx)| tx.output.get(outpoint.vout as usize))
+            .ok_or_else(not_connector_output)?
+            .clone();
+        let (name, connector) = self
+            .auxiliary_connectors()
+            .into_iter()
+            .find(|(_, connector)| connector.script_pubkey() == prevout.script_pubkey)
+            .ok_or_else(not_connector_output)?;
+        let auxiliary_input = AuxiliaryInput::new(
+            &name,
+            connector,
+            Input {
+                outpoint,
+                amount: prevout.value,
+            },
+            prevout,
+            leaf_index,
+        )?;
+
+        if get_output_spender(client, &outpoint).await?.is_some() {
+            return Err(Error::Graph(GraphError::ConnectorOutputSpent(outpoint)));
+        }
+        let mut active_txs = vec![];
+        for (name, tx) in template_txs {
+            if !tx
+                .input
+                .iter()
+                .any(|input| input.previous_output == outpoint)
+            {
+                continue;
+            }
+            let txid = tx.compute_txid();
+            let mut is_conflicted = false;
+            for input in tx
+                .input
+                .iter()
+                .filter(|input| input.previous_output != outpoint)
+            {
+                if let Some((spender_txid, true)) =
+                    get_output_spender(client, &input.previous_output).await?
+                {
+                    if spender_txid != txid {
+                        is_conflicted = true;
+                        break;
+                    }
+                }
+            }
+            if !is_conflicted {
+                active_txs.push(NamedTx {
+                    txid,
+                    name,
+                    confirmed: false,
+                });
+            }
+        }
+        if !active_txs.is_empty() {
+            return Err(Error::Graph(GraphError::ActiveGraphPath(active_txs)));
+        }
+
+        Ok(auxiliary_input)
+    }
+
+    // Relay policy violations of every transaction template, see `lint_transaction`. The
+    // peg-out transaction is left out, it is not part of the template.
+    pub fn lint_standardness(&self) -> Vec<StandardnessViolation> {
+        [
+            lint_transaction(&self.peg_out_confirm_transaction),
+            lint_transaction(&self.kick_off_1_transaction),
+            lint_transaction(&self.kick_off_2_transaction),
+            lint_transaction(&self.kick_off_timeout_transaction),
+            lint_transaction(&self.start_time_transaction),
+            lint_transaction(&self.start_time_timeout_transaction),
+            lint_transaction(&self.challenge_transaction),
+            lint_transaction(&self.assert_initial_transaction),
+            lint_transaction(&self.assert_commit_1_transaction),
+            lint_transaction(&self.assert_commit_2_transaction),
+            lint_transaction(&self.assert_final_transaction),
+            lint_transaction(&self.disprove_chain_transaction),
+            lint_transaction(&self.disprove_transaction),
+            lint_transaction(&self.take_1_transaction),
+            lint_transaction(&self.take_2_transaction),
+        ]
+        .concat()
+    }
+
+    // Fee rate each pre-signed transaction can be broadcast up to with the fee fixed in the graph,
+    // see `fee_sensitivity`. The challenge transaction is left out, its fee is paid by the
+    // crowdfunding inputs added when it is broadcast.
+    pub fn fee_sensitivity(&self) -> Vec<FeeSensitivity> {
+        let has_collateral = self.operator_collateral().is_some();
+        let collateral_vsize = |vsize: usize| if has_collateral { vsize } else { 0 };
+        let (assert_final_vsize, take_2_vsize) = if self
+            .assert_final_transaction
+            .output_layout()
+            .is_consolidated()
+        {
+            (VSIZE_ASSERT_FINAL_CONSOLIDATED, VSIZE_TAKE_2_CONSOLIDATED)
+        } else {
+            (VSIZE_ASSERT_FINAL, VSIZE_TAKE_2)
+        };
+
+        vec![
+            transaction_fee_sensitivity(&self.peg_out_confirm_transaction, VSIZE_PEG_OUT_CONFIRM),
+            transaction_fee_sensitivity(
+                &self.kick_off_1_transaction,
+                VSIZE_KICK_OFF_1 + collateral_vsize(VSIZE_KICK_OFF_1_COLLATERAL),
+            ),
+            transaction_fee_sensitivity(&self.kick_off_2_transaction, VSIZE_KICK_OFF_2),
+            transaction_fee_sensitivity(&self.kick_off_timeout_transaction, VSIZE_KICK_OFF_TIMEOUT),
+            transaction_fee_sensitivity(&self.start_time_transaction, VSIZE_START_TIME),
+            transaction_fee_sensitivity(
+                &self.start_time_timeout_transaction,
+                VSIZE_START_TIME_TIMEOUT,
+            ),
+            transaction_fee_sensitivity(&self.assert_initial_transaction, VSIZE_ASSERT_INITIAL),
+            transaction_fee_sensitivity(&self.assert_commit_1_transaction, VSIZE_ASSERT_COMMIT1),
+            transaction_fee_sensitivity(&self.assert_commit_2_transaction, VSIZE_ASSERT_COMMIT2),
+            transaction_fee_sensitivity(&self.assert_final_transaction, assert_final_vsize),
+            transaction_fee_sensitivity(&self.disprove_chain_transaction, VSIZE_DISPROVE_CHAIN),
+            transaction_fee_sensitivity(
+                &self.disprove_transaction,
+                VSIZE_DISPROVE + collateral_vsize(VSIZE_COLLATERAL_INPUT),
+            ),
+            transaction_fee_sensitivity(
+                &self.take_1_transaction,
+                VSIZE_TAKE_1 + collateral_vsize(VSIZE_COLLATERAL_INPUT),
+            ),
+            transaction_fee_sensitivity(
+                &self.take_2_transaction,
+                take_2_vsize + collateral_vsize(VSIZE_COLLATERAL_INPUT),
+            ),
+        ]
+    }
+
+    // Executes the connector leaves with representative witnesses, commitments are signed with
+    // the graph's commitment secrets. Connector C is left out: its leaves are the chunker's
+    // disprove scripts, whose witnesses only exist for a faulty assertion.
+    pub fn analyze_stack_usage(
+        &self,
+        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    ) -> Vec<LeafStackUsage> {
+        let start_time = get_start_time_block_number(self.network);
+        let mut analyzer = StackUsageAnalyzer::new(
+            absolute::LockTime::from_height(start_time).expect("Invalid start time block number"),
+        );
+        let signature = placeholder_signature_witness;
+        let commitment = |message_id: CommitmentMessageId, message: &[u8]| {
+            commitment_witness(&commitment_secrets[&message_id], message)
+        };
+
+        analyzer.analyze_connector("connector_0", &self.connector_0);
+
+        let superblock = find_superblock();
+        analyzer.analyze_leaf(
+            "connector_1",
+            &self.connector_1,
+            0,
+            [
+                signature(),
+                commitment(
+                    CommitmentMessageId::Superblock,
+                    &get_superblock_message(&superblock),
+                ),
+                commitment(
+                    CommitmentMessageId::SuperblockHash,
+                    &get_superblock_hash_message(&superblock),
+                ),
+            ]
+            .concat(),
+        );
+        for leaf_index in [1, 2] {
+            analyzer.analyze_leaf("connector_1", &self.connector_1, leaf_index, signature());
+        }
+
+        let start_time_message = start_time.to_le_bytes();
+        analyzer.analyze_leaf(
+            "connector_2",
+            &self.connector_2,
+            0,
+            [
+                signature(),
+                commitment(CommitmentMessageId::StartTime, &start_time_message),
+            ]
+            .concat(),
+        );
+        analyzer.analyze_leaf("connector_2", &self.connector_2, 1, signature());
+
+        analyzer.analyze_connector("connector_5", &self.connector_5);
+
+        let mut connector_6_witness = [
+            signature(),
+            commitment(
+                CommitmentMessageId::PegOutTxIdSourceNetwork,
+                &[0; SOURCE_NETWORK_TXID_LENGTH],
+            ),
+            commitment(
+                CommitmentMessageId::PegOutTxIdDestinationNetwork,
+                &[0; DESTINATION_NETWORK_TXID_LENGTH],
+            ),
+        ]
+        .concat();
+        if self.connector_6.commits_destination_metadata() {
+            connector_6_witness.extend(commitment(
+                CommitmentMessageId::WithdrawalNonce,
+                &[0; WITHDRAWAL_NONCE_MESSAGE_LENGTH],
+            ));
+            connector_6_witness.extend(commitment(
+                CommitmentMessageId::DestinationChainId,
+                &[0; DESTINATION_CHAIN_ID_MESSAGE_LENGTH],
+            ));
+        }
+        analyzer.analyze_leaf("connector_6", &self.connector_6, 0, connector_6_witness);
+
+        analyzer.analyze_connector("connector_a", &self.connector_a);
+
+        // A superblock that passes the time checks and is heavier than the committed one, so the
+        // hashing at the end of the leaf is executed
+        let mut disprove_superblock = superblock;
+        disprove_superblock.time = start_time + 1;
+        let mut disprove_superblock_message = get_superblock_message(&disprove_superblock);
+        disprove_superblock_message.reverse();
+        for leaf_index in [0, 1] {
+            analyzer.analyze_leaf("connector_b", &self.connector_b, leaf_index, signature());
+        }
+        analyzer.analyze_leaf(
+            "connector_b",
+            &self.connector_b,
+            2,
+            [
+                signature(),
+                disprove_superblock_message
+                    .into_iter()
+                    .map(|byte| vec![byte])
+                    .collect(),
+                commitment(CommitmentMessageId::StartTime, &start_time_message),
+                commitment(
+                    CommitmentMessageId::SuperblockHash,
+                    &[u8::MAX; SUPERBLOCK_HASH_MESSAGE_LENGTH],
+                ),
+            ]
+            .concat(),
+        );
+
+        analyzer.analyze_connector("connector_d", &self.connector_d);
+
+        for (name, connectors_e) in [
+            ("connector_e_1", &self.connector_e_1.connectors_e),
+            ("connector_e_2", &self.connector_e_2.connectors_e),
+        ] {
+            for (i, connector_e) in connectors_e.iter().enumerate() {
+                // The leaf verifies the commitments in reverse order
+                let witness = connector_e
+                    .commitment_public_keys
+                    .iter()
+                    .flat_map(|(message_id, public_key)| {
+                        let message_length = public_key.parameters.byte_message_length();
+                        commitment(message_id.clone(), &vec![0; message_length as usize])
+                    })
+                    .collect();
+                analyzer.analyze_leaf(&format!("{name}_{i}"), connector_e, 0, witness);
+            }
+        }
+
+        analyzer.reports()
+    }
+
+    // Only pushes nonces for invalidated transactions the verifier has no nonces for yet, so the
+    // signatures of the other transactions stay valid.
+    pub fn push_verifier_resign_nonces(
+        &mut self,
+        verifier_context: &VerifierContext,
+    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
+        PegOutPresignedTransaction::iter()
+            .filter(|transaction| {
+                self.resign_round(*transaction) > 0
+                    && !self
+                        .presigned_tx(*transaction)
+                        .has_nonces_for(verifier_context.verifier_public_key)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|transaction| {
+                let tx = self.presigned_tx_mut(transaction);
+                (tx.tx().compute_txid(), tx.push_nonces(verifier_context))
+            })
+            .collect()
+    }
+
+    // Signs the invalidated transactions for which all nonces have been collected and the
+    // verifier has not signed yet.
+    pub fn verifier_resign(
+        &mut self,
+        verifier_context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) -> Result<(), Error> {
+        let transactions: Vec<_> = self
+            .pending_signatures(verifier_context, secret_nonces)
+            .into_iter()
+            .filter(|transaction| self.resign_round(*transaction) > 0)
+            .collect();
+        self.verifier_sign_transactions(&transactions, verifier_context, secret_nonces)
+    }
+
+    // Signs every transaction that is ready to be signed, e.g. when a ceremony is resumed after
+    // some transactions were already signed, see `CeremonyStatus`
+    pub fn verifier_sign_pending(
+        &mut self,
+        verifier_context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) -> Result<(), Error> {
+        let transactions = self.pending_signatures(verifier_context, secret_nonces);
+        self.verifier_sign_transactions(&transactions, verifier_context, secret_nonces)
+    }
+
+    // Transactions for which all nonces have been collected and the verifier has not signed yet
+    fn pending_signatures(
+        &self,
+        verifier_context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) -> Vec<PegOutPresignedTransaction> {
+        PegOutPresignedTransaction::iter()
+            .filter(|transaction| {
+                let tx = self.presigned_tx(*transaction);
+                tx.has_all_nonces(&verifier_context.n_of_n_public_keys)
+                    && !tx.has_signatures_for(verifier_context.verifier_public_key)
+                    && secret_nonces.contains_key(&tx.tx().compute_txid())
+            })
+            .collect()
+    }
+
+    fn verifier_sign_transactions(
+        &mut self,
+        transactions: &[PegOutPresignedTransaction],
+        verifier_context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) -> Result<(), Error> {
+        for transaction in transactions.iter().copied() {
+            self.pre_sign_transaction(transaction, verifier_context, secret_nonces)?;
+        }
+
+        if self.has_all_signatures(&verifier_context.n_of_n_public_keys) {
+            self.n_of_n_presigned = true;
+        }
+
+        Ok(())
+    }
+
+    // Where the verifier stands in the signing ceremony of every pre-signed transaction, given the
+    // secret nonces it kept for the graph
+    pub fn ceremony_status(
+        &self,
+        context: &VerifierContext,
+        secret_nonces: Option<&HashMap<Txid, HashMap<usize, SecNonce>>>,
+    ) -> Vec<(PegOutPresignedTransaction, CeremonyStatus)> {
+        PegOutPresignedTransaction::iter()
+            .map(|transaction| {
+                (
+                    transaction,
+                    CeremonyStatus::new(
+                        transaction.to_string(),
+                        self.presigned_tx(transaction),
+                        context,
+                        secret_nonces,
+                    ),
+                )
+            })
+            .collect()
+    }
+
     pub fn has_all_nonces_of(&self, context: &VerifierContext) -> bool {
         self.all_presigned_txs()
             .all(|x| x.has_nonces_for(context.verifier_public_key))
@@ -2508,10 +5062,109 @@ impl PegOutGraph {
     }
 }
 
-pub fn generate_id(peg_in_graph: &PegInGraph, operator_public_key: &PublicKey) -> String {
+pub fn generate_id(peg_in_graph: &PegInGraph, operator_public_key: &PublicKey) -> PegOutGraphId {
+    generate_id_from_peg_in_graph_id(peg_in_graph.peg_in_graph_id(), operator_public_key)
+}
+
+// For an operator that does not hold the peg-in graph, see `OperatorCommitments`
+pub fn generate_id_from_peg_in_graph_id(
+    peg_in_graph_id: &PegInGraphId,
+    operator_public_key: &PublicKey,
+) -> PegOutGraphId {
     let mut hasher = Sha256::new();
 
-    hasher.update(peg_in_graph.id().to_string() + &operator_public_key.to_string());
+    hasher.update(peg_in_graph_id.to_string() + &operator_public_key.to_string());
+
+    PegOutGraphId::from_hash(hasher.finalize().to_hex_string(Upper))
+}
+
+// State of the race for the kick-off 1 outputs, none until kick-off 1 confirmed or if any of the
+// racing transactions could not be looked up
+fn get_kick_off_race(
+    kick_off_1_status: &Result<TxStatus, esplora_client::Error>,
+    start_time_status: &Result<TxStatus, esplora_client::Error>,
+    start_time_timeout_status: &Result<TxStatus, esplora_client::Error>,
+    kick_off_2_status: &Result<TxStatus, esplora_client::Error>,
+    kick_off_timeout_status: &Result<TxStatus, esplora_client::Error>,
+    blockchain_height: &Result<u32, esplora_client::Error>,
+) -> Option<KickOffRace> {
+    let kick_off_1_status = kick_off_1_status.as_ref().ok()?;
+    let kick_off_1_height = kick_off_1_status
+        .block_height
+        .filter(|_| kick_off_1_status.confirmed)?;
+    let mut winner = None;
+    for (path, status) in [
+        (KickOffPath::StartTimeTimeout, start_time_timeout_status),
+        (KickOffPath::KickOff2, kick_off_2_status),
+        (KickOffPath::KickOffTimeout, kick_off_timeout_status),
+    ] {
+        if status.as_ref().ok()?.confirmed {
+            winner = Some(path);
+        }
+    }
+
+    Some(KickOffRace {
+        kick_off_1_height,
+        tip_height: *blockchain_height.as_ref().ok()?,
+        start_time_confirmed: start_time_status.as_ref().ok()?.confirmed,
+        winner,
+    })
+}
+
+// Transaction spending the output and whether it is confirmed
+async fn get_output_spender(
+    client: &AsyncClient,
+    outpoint: &OutPoint,
+) -> Result<Option<(Txid, bool)>, Error> {
+    let output_status = client
+        .get_output_status(&outpoint.txid, outpoint.vout as u64)
+        .await
+        .map_err(Error::Esplora)?;
+
+    Ok(output_status
+        .filter(|output_status| output_status.spent)
+        .and_then(|output_status| {
+            output_status.txid.map(|txid| {
+                (
+                    txid,
+                    output_status.status.is_some_and(|status| status.confirmed),
+                )
+            })
+        }))
+}
+
+// Confirmation height and witness of the first input of a graph transaction as confirmed on chain
+async fn confirmed_input_witness(
+    client: &AsyncClient,
+    tx: &Transaction,
+) -> Result<(u32, Witness), String> {
+    let txid = tx.compute_txid();
+    let height = client
+        .get_tx_status(&txid)
+        .await
+        .map_err(|e| e.to_string())?
+        .block_height
+        .ok_or_else(|| format!("transaction {txid} is not confirmed"))?;
+    match client.get_tx(&txid).await {
+        Ok(Some(onchain_tx)) => Ok((height, onchain_tx.input[0].witness.clone())),
+        Ok(None) => Err(format!("transaction {txid} is not on chain")),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Name and txid of a transaction, the txid commits to everything but the witnesses
+fn template_txid(
+    transaction: &(impl BaseTransaction + PreSignedTransaction),
+) -> (&'static str, Txid) {
+    (transaction.name(), transaction.tx().compute_txid())
+}
+
+fn template_tx(
+    transaction: &(impl BaseTransaction + PreSignedTransaction),
+) -> (&'static str, &Transaction) {
+    (transaction.name(), transaction.tx())
+}
 
-    hasher.finalize().to_hex_string(Upper)
+fn has_witnesses(tx: &Transaction) -> bool {
+    tx.input.iter().all(|input| !input.witness.is_empty())
 }
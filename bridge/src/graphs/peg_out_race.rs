@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use bitcoin::{OutPoint, PublicKey};
+
+use super::base::PegOutGraphId;
+
+// Every operator's peg-out graph of a peg-in can be initiated for the same withdrawal, and each
+// operator fronting it pays the withdrawer from its own funds, while only one of them can take the
+// deposit. The graph whose peg-out confirmed first wins, ties in the same block are broken by the
+// earlier destination chain event, then the graph id, so every client picks the same winner from
+// the same chain state. Until a peg-out confirms there is no winner, fronting is still a race.
+
+// A peg-out graph initiated for a withdrawal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegOutClaim {
+    pub graph_id: PegOutGraphId,
+    pub operator_public_key: PublicKey,
+    pub event_timestamp: u32,
+    pub peg_out_height: Option<u32>, // block height the peg-out confirmed at
+}
+
+impl PegOutClaim {
+    fn rank(&self) -> (bool, u32, u32, &PegOutGraphId) {
+        (
+            self.peg_out_height.is_none(),
+            self.peg_out_height.unwrap_or_default(),
+            self.event_timestamp,
+            &self.graph_id,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegOutRace {
+    // Deposit of the peg-in the withdrawal is paid out of
+    pub source_outpoint: OutPoint,
+    // Winner first, claims without a confirmed peg-out last
+    pub claims: Vec<PegOutClaim>,
+}
+
+impl PegOutRace {
+    pub fn new(source_outpoint: OutPoint, mut claims: Vec<PegOutClaim>) -> Self {
+        claims.sort_by(|a, b| a.rank().cmp(&b.rank()));
+        Self {
+            source_outpoint,
+            claims,
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    pub fn winner(&self) -> Option<&PegOutClaim> {
+        self.claims
+            .first()
+            .filter(|claim| claim.peg_out_height.is_some())
+    }
+
+    // Empty until the race is settled
+    pub fn losers(&self) -> &[PegOutClaim] {
+        match self.is_settled() {
+            true => &self.claims[1..],
+            false => &[],
+        }
+    }
+
+    pub fn is_loser(&self, graph_id: &PegOutGraphId) -> bool {
+        self.losers()
+            .iter()
+            .any(|claim| claim.graph_id == *graph_id)
+    }
+}
+
+// Races of the claims, only withdrawals claimed by more than one graph
+pub fn find_peg_out_races(
+    claims: impl IntoIterator<Item = (OutPoint, PegOutClaim)>,
+) -> Vec<PegOutRace> {
+    let mut claims_by_outpoint: BTreeMap<OutPoint, Vec<PegOutClaim>> = BTreeMap::new();
+    for (source_outpoint, claim) in claims {
+        claims_by_outpoint
+            .entry(source_outpoint)
+            .or_default()
+            .push(claim);
+    }
+
+    claims_by_outpoint
+        .into_iter()
+        .filter(|(_, claims)| claims.len() > 1)
+        .map(|(source_outpoint, claims)| PegOutRace::new(source_outpoint, claims))
+        .collect()
+}
@@ -0,0 +1,319 @@
+use bitcoin::{Amount, Network};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{
+        NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_4_WEEKS,
+        NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY, NUM_BLOCKS_PER_HOUR, NUM_BLOCKS_PER_WEEK,
+    },
+    scripts::DepositorAddressType,
+    transactions::{
+        assert_transactions::assert_final::AssertFinalOutputLayout,
+        base::{relay_fee_at_rate, VSIZE_KICK_OFF_1_COLLATERAL},
+        kick_off_1::KickOff1Commitments,
+    },
+    utils::num_blocks_per_network,
+};
+
+use super::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE, REWARD_MULTIPLIER};
+
+// Share of the disprove and disprove chain outputs that is burnt, the rest goes to the challenger
+pub const DEFAULT_DISPROVE_BURN_PERCENTAGE: u64 = 50;
+
+/// Named presets for the parameters a deployment would otherwise pick one by one for every
+/// graph. The preset is recorded in the graphs created from it.
+#[derive(
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum GraphTemplate {
+    // The parameters the bridge has always used
+    #[default]
+    StandardMainnet,
+    // Short timelocks, single verifier committees and the consolidated assert final layout, for
+    // local testing. Not allowed on mainnet.
+    FastRegtest,
+    // Longer challenge windows, a larger committee, a larger slashable reward and a shorter
+    // pre-signing window. Kick-off 1 also commits to the destination chain id and withdrawal nonce.
+    HighSecurity,
+}
+
+impl GraphTemplate {
+    pub fn parameters(&self) -> GraphParameters {
+        match self {
+            GraphTemplate::StandardMainnet => GraphParameters {
+                template: *self,
+                timelocks: GraphTimelocks::default(),
+                reward_multiplier: REWARD_MULTIPLIER,
+                disprove_burn_percentage: DEFAULT_DISPROVE_BURN_PERCENTAGE,
+                min_committee_size: 1,
+                max_committee_size: None,
+                assert_final_output_layout: AssertFinalOutputLayout::Separate,
+                kick_off_1_commitments: KickOff1Commitments::PegOutTxIds,
+                operator_collateral: Amount::ZERO,
+                presigning_expiry_days: Some(14),
+                fee_rate: MIN_RELAY_FEE_RATE,
+                depositor_address_type: DepositorAddressType::P2wsh,
+            },
+            GraphTemplate::FastRegtest => GraphParameters {
+                template: *self,
+                timelocks: GraphTimelocks {
+                    kick_off_2: NUM_BLOCKS_PER_HOUR,
+                    kick_off_timeout: NUM_BLOCKS_PER_HOUR * 2,
+                    start_time_timeout: NUM_BLOCKS_PER_HOUR,
+                    take_1: NUM_BLOCKS_PER_HOUR,
+                    take_2: NUM_BLOCKS_PER_HOUR,
+                    assert_initial: NUM_BLOCKS_PER_HOUR,
+                },
+                reward_multiplier: REWARD_MULTIPLIER,
+                disprove_burn_percentage: DEFAULT_DISPROVE_BURN_PERCENTAGE,
+                min_committee_size: 1,
+                max_committee_size: None,
+                assert_final_output_layout: AssertFinalOutputLayout::Consolidated,
+                kick_off_1_commitments: KickOff1Commitments::PegOutTxIds,
+                operator_collateral: Amount::ZERO,
+                presigning_expiry_days: Some(1),
+                fee_rate: MIN_RELAY_FEE_RATE,
+                depositor_address_type: DepositorAddressType::P2wsh,
+            },
+            GraphTemplate::HighSecurity => GraphParameters {
+                template: *self,
+                timelocks: GraphTimelocks {
+                    kick_off_2: NUM_BLOCKS_PER_4_WEEKS,
+                    kick_off_timeout: NUM_BLOCKS_PER_4_WEEKS + NUM_BLOCKS_PER_2_WEEKS,
+                    start_time_timeout: NUM_BLOCKS_PER_DAY,
+                    take_1: NUM_BLOCKS_PER_WEEK,
+                    take_2: NUM_BLOCKS_PER_4_WEEKS,
+                    assert_initial: NUM_BLOCKS_PER_WEEK,
+                },
+                reward_multiplier: REWARD_MULTIPLIER * 2,
+                disprove_burn_percentage: 75,
+                min_committee_size: 5,
+                max_committee_size: None,
+                assert_final_output_layout: AssertFinalOutputLayout::Separate,
+                kick_off_1_commitments: KickOff1Commitments::PegOutTxIdsAndDestinationMetadata,
+                operator_collateral: Amount::ZERO,
+                presigning_expiry_days: Some(7),
+                fee_rate: MIN_RELAY_FEE_RATE,
+                depositor_address_type: DepositorAddressType::P2wsh,
+            },
+        }
+    }
+
+    pub fn supports_network(&self, network: Network) -> bool {
+        match self {
+            GraphTemplate::FastRegtest => network != Network::Bitcoin,
+            _ => true,
+        }
+    }
+}
+
+/// Relative timelocks of the peg-out graph, in mainnet blocks. Like the connector defaults, they
+/// are scaled down on test networks, see `num_blocks_per_network`.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct GraphTimelocks {
+    // Kick-off 1 to kick-off 2 (connector 1, leaf 0)
+    pub kick_off_2: u32,
+    // Kick-off 1 to kick-off timeout (connector 1, leaf 1), must be longer than kick_off_2
+    pub kick_off_timeout: u32,
+    // Kick-off 1 to start time timeout (connector 1, leaf 2)
+    pub start_time_timeout: u32,
+    // Kick-off 2 to take 1 (connector 3)
+    pub take_1: u32,
+    // Assert final to take 2 (connector 4)
+    pub take_2: u32,
+    // Kick-off 2 to assert initial (connector B, leaf 1)
+    pub assert_initial: u32,
+}
+
+impl Default for GraphTimelocks {
+    fn default() -> Self {
+        GraphTimelocks {
+            kick_off_2: NUM_BLOCKS_PER_2_WEEKS,
+            kick_off_timeout: NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
+            start_time_timeout: NUM_BLOCKS_PER_6_HOURS,
+            take_1: NUM_BLOCKS_PER_3_DAYS,
+            take_2: NUM_BLOCKS_PER_2_WEEKS,
+            assert_initial: NUM_BLOCKS_PER_3_DAYS,
+        }
+    }
+}
+
+impl GraphTimelocks {
+    // Timelocks in blocks on the given network
+    pub fn for_network(&self, network: Network) -> Self {
+        GraphTimelocks {
+            kick_off_2: num_blocks_per_network(network, self.kick_off_2),
+            kick_off_timeout: num_blocks_per_network(network, self.kick_off_timeout),
+            start_time_timeout: num_blocks_per_network(network, self.start_time_timeout),
+            take_1: num_blocks_per_network(network, self.take_1),
+            take_2: num_blocks_per_network(network, self.take_2),
+            assert_initial: num_blocks_per_network(network, self.assert_initial),
+        }
+    }
+}
+
+/// Parameters of a graph, taken from a `GraphTemplate`. Individual parameters can be overridden
+/// before the graph is created, the template name is kept for reference.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct GraphParameters {
+    pub template: GraphTemplate,
+    pub timelocks: GraphTimelocks,
+    // Fee schedule: share of the deposit, in REWARD_PRECISION units, that the operator funds the
+    // peg-out confirm transaction with and that the timeout and disprove paths pay out from.
+    pub reward_multiplier: u64,
+    pub disprove_burn_percentage: u64,
+    pub min_committee_size: usize,
+    pub max_committee_size: Option<usize>,
+    pub assert_final_output_layout: AssertFinalOutputLayout,
+    // Parameters recorded before the destination metadata commitments existed omit it
+    #[serde(default)]
+    pub kick_off_1_commitments: KickOff1Commitments,
+    // Minimum slashable collateral the operator posts in kick-off 1, none if zero. It is burnt and
+    // paid to the challenger by every slashing transaction, and returned by take 1 or take 2.
+    #[serde(default)]
+    pub operator_collateral: Amount,
+    // Days after creation within which the committee has to pre-sign the graphs, see
+    // `PresigningWindow`. None for no expiry, as for parameters recorded before it existed.
+    #[serde(default)]
+    pub presigning_expiry_days: Option<u32>,
+    // sat/vB paid by the kick-offs, start time, takes and the assert chain, whose output values are
+    // derived from it. The other transactions pay the MIN_RELAY_FEE_* constants. Graphs recorded
+    // before it existed were built at the minimum relay fee rate.
+    #[serde(default = "min_relay_fee_rate")]
+    pub fee_rate: u64,
+    // Depositor address the peg-in deposit spends from, only used by the depositor
+    #[serde(default)]
+    pub depositor_address_type: DepositorAddressType,
+}
+
+pub(crate) fn min_relay_fee_rate() -> u64 {
+    MIN_RELAY_FEE_RATE
+}
+
+impl Default for GraphParameters {
+    fn default() -> Self {
+        GraphTemplate::default().parameters()
+    }
+}
+
+impl From<GraphTemplate> for GraphParameters {
+    fn from(template: GraphTemplate) -> Self {
+        template.parameters()
+    }
+}
+
+impl GraphParameters {
+    pub fn with_assert_final_output_layout(
+        mut self,
+        assert_final_output_layout: AssertFinalOutputLayout,
+    ) -> Self {
+        self.assert_final_output_layout = assert_final_output_layout;
+        self
+    }
+
+    pub fn with_kick_off_1_commitments(
+        mut self,
+        kick_off_1_commitments: KickOff1Commitments,
+    ) -> Self {
+        self.kick_off_1_commitments = kick_off_1_commitments;
+        self
+    }
+
+    pub fn with_timelocks(mut self, timelocks: GraphTimelocks) -> Self {
+        self.timelocks = timelocks;
+        self
+    }
+
+    pub fn with_operator_collateral(mut self, operator_collateral: Amount) -> Self {
+        self.operator_collateral = operator_collateral;
+        self
+    }
+
+    pub fn with_presigning_expiry_days(mut self, presigning_expiry_days: Option<u32>) -> Self {
+        self.presigning_expiry_days = presigning_expiry_days;
+        self
+    }
+
+    pub fn with_fee_rate(mut self, fee_rate: u64) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    pub fn with_depositor_address_type(
+        mut self,
+        depositor_address_type: DepositorAddressType,
+    ) -> Self {
+        self.depositor_address_type = depositor_address_type;
+        self
+    }
+
+    // Smallest operator output that can fund the collateral, including the kick-off 1 relay fee
+    // for spending it
+    pub fn operator_collateral_funding_amount(&self) -> Option<Amount> {
+        (self.operator_collateral > Amount::ZERO).then(|| {
+            self.operator_collateral
+                + Amount::from_sat(relay_fee_at_rate(
+                    VSIZE_KICK_OFF_1_COLLATERAL,
+                    self.fee_rate,
+                ))
+        })
+    }
+
+    pub fn validate(&self, network: Network, committee_size: usize) -> Result<(), String> {
+        if !self.template.supports_network(network) {
+            return Err(format!(
+                "Graph template {} cannot be used on {network}",
+                self.template
+            ));
+        }
+        if committee_size < self.min_committee_size {
+            return Err(format!(
+                "Graph template {} requires at least {} verifiers, got {committee_size}",
+                self.template, self.min_committee_size
+            ));
+        }
+        if let Some(max_committee_size) = self.max_committee_size {
+            if committee_size > max_committee_size {
+                return Err(format!(
+                    "Graph template {} allows at most {max_committee_size} verifiers, got {committee_size}",
+                    self.template
+                ));
+            }
+        }
+        if self.timelocks.kick_off_timeout <= self.timelocks.kick_off_2 {
+            return Err(String::from(
+                "Kick-off timeout timelock must be longer than the kick-off 2 timelock",
+            ));
+        }
+        if self.fee_rate < MIN_RELAY_FEE_RATE {
+            return Err(format!(
+                "Fee rate must be at least the minimum relay fee rate of {MIN_RELAY_FEE_RATE} sat/vB"
+            ));
+        }
+        if self.disprove_burn_percentage > 100 {
+            return Err(String::from("Disprove burn percentage cannot exceed 100"));
+        }
+        if self.operator_collateral > Amount::ZERO
+            && self.operator_collateral < Amount::from_sat(DUST_AMOUNT)
+        {
+            return Err(format!(
+                "Operator collateral must be zero or at least {DUST_AMOUNT} sats"
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -4,11 +4,21 @@ pub mod common;
 pub mod connectors;
 pub mod constants;
 pub mod contexts;
+pub mod conversions;
+pub mod destination;
 pub mod error;
 pub mod graphs;
+pub mod prelude;
 pub mod proof;
+pub mod proof_binding;
+pub mod protocol;
+pub mod rewards;
+pub mod script_policies;
 pub mod scripts;
 pub mod serialization;
 pub mod superblock;
+pub mod test_vectors;
 pub mod transactions;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,31 @@
+// The stable public API of the bridge for library users: `use bridge::prelude::*;` brings in what
+// is needed to run a client, build graphs and handle their errors and events. Items re-exported
+// here only change in a breaking release, the paths of the modules they are defined in may change
+// at any time.
+
+pub use crate::{
+    client::chain::chain::{PegInEvent, PegOutBurntEvent, PegOutEvent},
+    common::ZkProofVerifyingKey,
+    constants::DestinationNetwork,
+    contexts::{
+        base::BaseContext, depositor::DepositorContext, operator::OperatorContext,
+        verifier::VerifierContext, withdrawer::WithdrawerContext,
+    },
+    error::{
+        ChunkerError, ClientError, Error, GraphError, L2Error, TransactionError, ValidationError,
+    },
+    graphs::{
+        base::{BaseGraph, GraphId, PegInGraphId, PegOutGraphId},
+        template::{GraphParameters, GraphTemplate},
+    },
+    transactions::base::{BaseTransaction, Input},
+};
+
+#[cfg(feature = "client")]
+pub use crate::{
+    client::{chain::chain_adaptor::ChainAdaptor, client::BitVMClient, event_log::BridgeEvent},
+    graphs::{
+        peg_in::PegInGraph,
+        peg_out::{PegOutGraph, PegOutPresignedTransaction, WithdrawalStage},
+    },
+};
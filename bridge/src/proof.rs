@@ -1,31 +1,52 @@
 use ark_bn254::{g1::G1Affine, Bn254};
 use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_ec::pairing::Pairing;
-use ark_ff::PrimeField;
+use ark_ff::{Field, PrimeField};
 use ark_groth16::Groth16;
 use ark_relations::lc;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use ark_std::{test_rng, UniformRand};
 
-use bitvm::chunk::api::type_conversion_utils::RawProof;
+use bitvm::chunk::api::{type_conversion_utils::RawProof, PublicInputs};
 use rand::{RngCore, SeedableRng};
 
 // TODO: replace with actual implementation
 pub fn get_proof() -> RawProof {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let a = <Bn254 as Pairing>::ScalarField::rand(&mut rng);
+    let b = <Bn254 as Pairing>::ScalarField::rand(&mut rng);
+
+    prove_dummy_circuit(a, b, &mut rng)
+}
+
+// DO NOT USE IN PRODUCTION! Same as `get_proof`, with the given public inputs, e.g. those bound to
+// a peg-out, see `proof_binding`. Each call sets up a new verifying key.
+pub fn get_proof_with_public_inputs(public: &PublicInputs) -> RawProof {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let a = <Bn254 as Pairing>::ScalarField::rand(&mut rng);
+    let b = public[0] * a.inverse().expect("Random field elements are not zero");
+
+    prove_dummy_circuit(a, b, &mut rng)
+}
+
+fn prove_dummy_circuit(
+    a: <Bn254 as Pairing>::ScalarField,
+    b: <Bn254 as Pairing>::ScalarField,
+    rng: &mut ark_std::rand::rngs::StdRng,
+) -> RawProof {
     type E = Bn254;
     let k = 6;
-    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
     let circuit = DummyCircuit::<<E as Pairing>::ScalarField> {
-        a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
-        b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        a: Some(a),
+        b: Some(b),
         num_variables: 10,
         num_constraints: 1 << k,
     };
-    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+    let (pk, vk) = Groth16::<E>::setup(circuit, rng).unwrap();
 
     let c = circuit.a.unwrap() * circuit.b.unwrap();
 
-    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    let proof = Groth16::<E>::prove(&pk, circuit, rng).unwrap();
 
     RawProof {
         proof,
@@ -0,0 +1,123 @@
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::snark::SNARK;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use bitcoin::{
+    hashes::{sha256, Hash, HashEngine},
+    Amount, Txid,
+};
+use bitvm::chunk::api::{type_conversion_utils::RawProof, PublicInputs};
+use serde::{Deserialize, Serialize};
+
+use crate::{common::ZkProofVerifyingKey, error::ValidationError};
+
+// A valid proof only shows that some withdrawal happened. Its public inputs commit to the peg-out
+// of the graph, so that an operator cannot be reimbursed for one withdrawal by several graphs or
+// with a proof of another peg-out. Proofs generated outside the client are checked against the
+// public inputs derived from the graph before the operator commits to them in the assert commit
+// transactions, since a commitment cannot be taken back.
+
+const PUBLIC_INPUT_TAG: &[u8] = b"bitvm-bridge/proof-public-input";
+
+// What the proof of a peg-out has to be about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegOutFacts {
+    pub peg_in_confirm_txid: Txid,
+    pub kick_off_1_txid: Txid,
+    pub peg_out_txid: Txid,
+    pub amount: Amount,
+    pub withdrawer_evm_address: String, // compared case-insensitively
+}
+
+impl PegOutFacts {
+    pub fn public_inputs(&self) -> PublicInputs {
+        let mut engine = sha256::Hash::engine();
+        engine.input(PUBLIC_INPUT_TAG);
+        engine.input(self.peg_in_confirm_txid.as_byte_array());
+        engine.input(self.kick_off_1_txid.as_byte_array());
+        engine.input(self.peg_out_txid.as_byte_array());
+        engine.input(&self.amount.to_sat().to_le_bytes());
+        engine.input(self.withdrawer_evm_address.to_lowercase().as_bytes());
+
+        [Fr::from_be_bytes_mod_order(
+            sha256::Hash::from_engine(engine).as_byte_array(),
+        )]
+    }
+}
+
+// A proof generated by an external prover, as saved to a json file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExternalProof {
+    pub proof: String,              // hex of the compressed Groth16 proof
+    pub public_inputs: Vec<String>, // hex of the compressed field elements
+}
+
+impl ExternalProof {
+    pub fn new(proof: &RawProof) -> Self {
+        Self {
+            proof: compressed_hex(&proof.proof),
+            public_inputs: proof.public.iter().map(compressed_hex).collect(),
+        }
+    }
+
+    // The proof to commit to, proven against the verifying key the client checks asserted proofs
+    // with. Fails unless it is a valid proof of the given peg-out.
+    pub fn load(
+        &self,
+        facts: &PegOutFacts,
+        verifying_key: &ZkProofVerifyingKey,
+    ) -> Result<RawProof, ValidationError> {
+        let invalid = ValidationError::InvalidExternalProof;
+        let proof = Proof::<Bn254>::deserialize_compressed(
+            &*hex::decode(&self.proof).map_err(|err| invalid(format!("proof: {err}")))?,
+        )
+        .map_err(|err| invalid(format!("proof: {err}")))?;
+        let public = self
+            .public_inputs
+            .iter()
+            .map(|input| {
+                let bytes = hex::decode(input)
+                    .map_err(|err| invalid(format!("public input {input}: {err}")))?;
+                Fr::deserialize_compressed(&*bytes)
+                    .map_err(|err| invalid(format!("public input {input}: {err}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let raw_proof = RawProof {
+            proof,
+            public,
+            vk: verifying_key.clone(),
+        };
+        verify_bound_proof(&raw_proof, facts)?;
+
+        Ok(raw_proof)
+    }
+}
+
+// Checks the public inputs first, a proof about another peg-out can be valid
+pub fn verify_bound_proof(proof: &RawProof, facts: &PegOutFacts) -> Result<(), ValidationError> {
+    let expected = facts.public_inputs();
+    if proof.public != expected {
+        return Err(ValidationError::ProofPublicInputsMismatch {
+            expected: expected.iter().map(compressed_hex).collect(),
+            found: proof.public.iter().map(compressed_hex).collect(),
+        });
+    }
+
+    match Groth16::<Bn254>::verify(&proof.vk, &proof.public, &proof.proof) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ValidationError::InvalidExternalProof(
+            "the proof does not verify against the verifying key".to_string(),
+        )),
+        Err(err) => Err(ValidationError::InvalidExternalProof(err.to_string())),
+    }
+}
+
+fn compressed_hex(value: &impl CanonicalSerialize) -> String {
+    let mut bytes = Vec::new();
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing to a vector does not fail");
+    hex::encode(bytes)
+}
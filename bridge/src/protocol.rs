@@ -0,0 +1,605 @@
+use std::{collections::BTreeMap, fmt};
+
+use bitcoin::{taproot::LeafVersion, Network, Witness};
+use bitvm::signatures::signing_winternitz::WinternitzPublicKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    commitments::CommitmentMessageId,
+    connectors::{base::TaprootConnector, connector_c::ConnectorC, connector_e::ConnectorE},
+    constants::{
+        DESTINATION_CHAIN_ID_MESSAGE_LENGTH, DESTINATION_NETWORK_TXID_LENGTH,
+        NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_4_WEEKS,
+        NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY, NUM_BLOCKS_PER_HOUR, NUM_BLOCKS_PER_WEEK,
+        N_SEQUENCE_FOR_LOCK_TIME, SOURCE_NETWORK_TXID_LENGTH, START_TIME_MESSAGE_LENGTH,
+        WITHDRAWAL_NONCE_MESSAGE_LENGTH,
+    },
+    contexts::base::generate_keys_from_secret,
+    destination::DESTINATION_CHAIN_ID_LENGTH,
+    graphs::{
+        base::{
+            CROWDFUNDING_AMOUNT, DUST_AMOUNT, DUST_RELAY_FEE_RATE, GRAPH_VERSION,
+            MIN_RELAY_FEE_RATE, NUM_REQUIRED_OPERATORS, REWARD_MULTIPLIER, REWARD_PRECISION,
+        },
+        template::{GraphParameters, DEFAULT_DISPROVE_BURN_PERCENTAGE},
+    },
+    superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
+    transactions::{
+        assert_transactions::utils::{
+            groth16_commitment_secrets_to_public_keys, merge_to_connector_c_commits_public_key,
+        },
+        base::{
+            MIN_RELAY_FEE_ASSERT, MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2,
+            MIN_RELAY_FEE_ASSERT_FINAL, MIN_RELAY_FEE_ASSERT_FINAL_CONSOLIDATED,
+            MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_CHALLENGE, MIN_RELAY_FEE_COLLATERAL_INPUT,
+            MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_DISPROVE_CHAIN, MIN_RELAY_FEE_KICK_OFF_1,
+            MIN_RELAY_FEE_KICK_OFF_1_COLLATERAL, MIN_RELAY_FEE_KICK_OFF_2,
+            MIN_RELAY_FEE_KICK_OFF_TIMEOUT, MIN_RELAY_FEE_PEG_IN_CONFIRM,
+            MIN_RELAY_FEE_PEG_IN_DEPOSIT, MIN_RELAY_FEE_PEG_IN_DEPOSIT_CHANGE,
+            MIN_RELAY_FEE_PEG_IN_DEPOSIT_INPUT, MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT,
+            MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME,
+            MIN_RELAY_FEE_START_TIME_TIMEOUT, MIN_RELAY_FEE_TAKE_1, MIN_RELAY_FEE_TAKE_2,
+            MIN_RELAY_FEE_TAKE_2_CONSOLIDATED, VSIZE_ASSERT_COMMIT1, VSIZE_ASSERT_COMMIT2,
+            VSIZE_DISPROVE,
+        },
+        standardness::WITNESS_SCALE_FACTOR,
+    },
+    utils::num_blocks_per_network,
+};
+
+// Constants every member of a committee has to agree on: two clients building the same graph with
+// different values produce different scripts or amounts, and pre-sign transactions the other side
+// does not recognize. Graphs record the version and hash of the parameters they were created under
+// and verifiers only contribute nonces and signatures to graphs created under their own.
+//
+// Changing any registered constant changes `params_hash`. Bump `PARAMS_VERSION` along with it and
+// add an entry to `PARAMS_CHANGELOG`, the protocol tests check the latest entry is current.
+
+pub const PARAMS_VERSION: u32 = 2;
+
+pub struct ParamsChange {
+    pub version: u32,
+    pub params_hash: &'static str,
+    pub description: &'static str,
+}
+
+pub const PARAMS_CHANGELOG: &[ParamsChange] = &[
+    ParamsChange {
+        version: 1,
+        params_hash: "7f9d6fce3fcf3bc020011f2acd7cc5fdcc66c334259a5f4a44ade0a38663f2ed",
+        description: "Parameters as of the introduction of the registry",
+    },
+    ParamsChange {
+        version: 2,
+        params_hash: "d265780e0ef18bd3c1555fafe1eac5d662074908191ecb3d9c0dff7d9819f0ea",
+        description: "Fees of the additional inputs and the change output of peg-in deposits",
+    },
+];
+
+macro_rules! param {
+    ($name:ident) => {
+        (stringify!($name), $name.to_string())
+    };
+}
+
+// Name and value of every consensus-critical constant, in the order they are hashed
+pub fn protocol_params() -> Vec<(&'static str, String)> {
+    vec![
+        // Timelocks, in mainnet blocks, and the fixed timelocks of test networks
+        param!(NUM_BLOCKS_PER_HOUR),
+        param!(NUM_BLOCKS_PER_6_HOURS),
+        param!(NUM_BLOCKS_PER_DAY),
+        param!(NUM_BLOCKS_PER_3_DAYS),
+        param!(NUM_BLOCKS_PER_WEEK),
+        param!(NUM_BLOCKS_PER_2_WEEKS),
+        param!(NUM_BLOCKS_PER_4_WEEKS),
+        (
+            "NUM_BLOCKS_REGTEST",
+            num_blocks_per_network(Network::Regtest, 0).to_string(),
+        ),
+        (
+            "NUM_BLOCKS_TESTNET",
+            num_blocks_per_network(Network::Testnet, 0).to_string(),
+        ),
+        param!(N_SEQUENCE_FOR_LOCK_TIME),
+        // Commitment message lengths
+        param!(START_TIME_MESSAGE_LENGTH),
+        param!(SOURCE_NETWORK_TXID_LENGTH),
+        param!(DESTINATION_NETWORK_TXID_LENGTH),
+        param!(DESTINATION_CHAIN_ID_MESSAGE_LENGTH),
+        param!(WITHDRAWAL_NONCE_MESSAGE_LENGTH),
+        param!(DESTINATION_CHAIN_ID_LENGTH),
+        param!(SUPERBLOCK_MESSAGE_LENGTH),
+        param!(SUPERBLOCK_HASH_MESSAGE_LENGTH),
+        // Fee floors
+        param!(MIN_RELAY_FEE_RATE),
+        param!(MIN_RELAY_FEE_KICK_OFF_1),
+        param!(MIN_RELAY_FEE_KICK_OFF_1_COLLATERAL),
+        param!(MIN_RELAY_FEE_COLLATERAL_INPUT),
+        param!(MIN_RELAY_FEE_START_TIME),
+        param!(MIN_RELAY_FEE_START_TIME_TIMEOUT),
+        param!(MIN_RELAY_FEE_KICK_OFF_2),
+        param!(MIN_RELAY_FEE_KICK_OFF_TIMEOUT),
+        param!(MIN_RELAY_FEE_TAKE_1),
+        param!(MIN_RELAY_FEE_TAKE_2),
+        param!(MIN_RELAY_FEE_TAKE_2_CONSOLIDATED),
+        param!(MIN_RELAY_FEE_PEG_IN_DEPOSIT),
+        param!(MIN_RELAY_FEE_PEG_IN_DEPOSIT_INPUT),
+        param!(MIN_RELAY_FEE_PEG_IN_DEPOSIT_CHANGE),
+        param!(MIN_RELAY_FEE_PEG_IN_CONFIRM),
+        param!(MIN_RELAY_FEE_PEG_IN_REFUND),
+        param!(MIN_RELAY_FEE_PEG_OUT),
+        param!(MIN_RELAY_FEE_PEG_OUT_CONFIRM),
+        param!(MIN_RELAY_FEE_ASSERT),
+        param!(MIN_RELAY_FEE_ASSERT_INITIAL),
+        param!(MIN_RELAY_FEE_ASSERT_COMMIT1),
+        param!(MIN_RELAY_FEE_ASSERT_COMMIT2),
+        param!(MIN_RELAY_FEE_ASSERT_FINAL),
+        param!(MIN_RELAY_FEE_ASSERT_FINAL_CONSOLIDATED),
+        param!(MIN_RELAY_FEE_CHALLENGE),
+        param!(MIN_RELAY_FEE_DISPROVE),
+        param!(MIN_RELAY_FEE_DISPROVE_CHAIN),
+        // Dust
+        param!(DUST_RELAY_FEE_RATE),
+        param!(DUST_AMOUNT),
+        // Amounts and graph layout
+        param!(CROWDFUNDING_AMOUNT),
+        param!(REWARD_PRECISION),
+        param!(REWARD_MULTIPLIER),
+        param!(DEFAULT_DISPROVE_BURN_PERCENTAGE),
+        param!(NUM_REQUIRED_OPERATORS),
+        param!(GRAPH_VERSION),
+    ]
+}
+
+// Lower case hex sha256 of the `name=value` lines of the registry
+pub fn params_hash() -> String {
+    let mut hasher = Sha256::new();
+    for (name, value) in protocol_params() {
+        hasher.update(format!("{name}={value}\n"));
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+// Recorded in graphs, see `BaseGraph::check_protocol_params`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolParamsId {
+    pub version: u32,
+    pub hash: String,
+}
+
+impl ProtocolParamsId {
+    pub fn current() -> Self {
+        Self {
+            version: PARAMS_VERSION,
+            hash: params_hash(),
+        }
+    }
+}
+
+impl fmt::Display for ProtocolParamsId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version {} ({})", self.version, self.hash)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum Broadcaster {
+    #[strum(serialize = "the depositor")]
+    Depositor,
+    #[strum(serialize = "the operator")]
+    Operator,
+    // Fully pre-signed, or completed at broadcast with the broadcaster's own inputs and outputs
+    #[strum(serialize = "anyone")]
+    Anyone,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timelock {
+    pub blocks: u32,
+    pub after: &'static str, // name of the transaction the blocks are counted from
+}
+
+// What a bridge transaction does, for participants looking it up with the `explain` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionDescription {
+    pub name: &'static str, // as returned by `BaseTransaction::name`
+    pub summary: &'static str,
+    pub broadcaster: Broadcaster,
+    // Transactions whose outputs it spends, they have to confirm first
+    pub prerequisites: Vec<&'static str>,
+    pub timelock: Option<Timelock>,
+    pub consequences: String,
+}
+
+impl TransactionDescription {
+    // Accepts the transaction names of the CLI as well, e.g. `kick_off_1` for `KickOff1`
+    pub fn matches(&self, name: &str) -> bool {
+        let normalize = |name: &str| {
+            name.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        };
+        normalize(self.name) == normalize(name)
+    }
+}
+
+impl fmt::Display for TransactionDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.name, self.summary)?;
+        writeln!(f, "  Broadcast by: {}", self.broadcaster)?;
+        match self.prerequisites.is_empty() {
+            true => writeln!(f, "  Requires: nothing on chain")?,
+            false => writeln!(f, "  Requires: {} confirmed", self.prerequisites.join(", "))?,
+        }
+        if let Some(timelock) = &self.timelock {
+            writeln!(
+                f,
+                "  Timelock: {} blocks after {} confirms",
+                timelock.blocks, timelock.after
+            )?;
+        }
+        write!(f, "  Consequences: {}", self.consequences)
+    }
+}
+
+// Every transaction of a peg-in and its peg-out graphs, in the order they are usually broadcast.
+// Timelocks are those of graphs created with `parameters` on `network`.
+pub fn describe(parameters: &GraphParameters, network: Network) -> Vec<TransactionDescription> {
+    let timelocks = parameters.timelocks.for_network(network);
+    let timelock = |blocks, after| Some(Timelock { blocks, after });
+    let collateral = match parameters.operator_collateral.to_sat() {
+        0 => String::new(),
+        _ => format!(
+            " The operator's collateral of {} is slashed as well.",
+            parameters.operator_collateral
+        ),
+    };
+
+    vec![
+        TransactionDescription {
+            name: "PegInDeposit",
+            summary: "Locks the deposit so that the committee can take it over",
+            broadcaster: Broadcaster::Depositor,
+            prerequisites: vec![],
+            timelock: None,
+            consequences: String::from("The deposit is locked until the peg-in confirm moves it to the n-of-n, or the depositor takes it back with the peg-in refund."),
+        },
+        TransactionDescription {
+            name: "PegInConfirm",
+            summary: "Moves the deposit to the n-of-n of the committee",
+            broadcaster: Broadcaster::Anyone,
+            prerequisites: vec!["PegInDeposit"],
+            timelock: None,
+            consequences: String::from("The deposit backs the wrapped tokens minted on the destination chain. Operators can only take it through a peg-out graph pre-signed by the committee."),
+        },
+        TransactionDescription {
+            name: "PegInRefund",
+            summary: "Returns the deposit to the depositor if the committee never confirmed the peg-in",
+            broadcaster: Broadcaster::Depositor,
+            prerequisites: vec!["PegInDeposit"],
+            timelock: timelock(
+                num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+                "PegInDeposit",
+            ),
+            consequences: String::from("The peg-in is cancelled, the peg-in confirm can no longer be broadcast."),
+        },
+        TransactionDescription {
+            name: "PegOut",
+            summary: "Pays a withdrawer from the operator's own funds after a burn on the destination chain",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec![],
+            timelock: None,
+            consequences: String::from("The withdrawer is paid. The operator claims the reimbursement from the deposit with the rest of the peg-out graph."),
+        },
+        TransactionDescription {
+            name: "PegOutConfirm",
+            summary: "Records that the operator paid the withdrawer and funds kick-off 1",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec![],
+            timelock: None,
+            consequences: String::from("Kick-off 1 can be broadcast. It is only broadcast once the burn on the destination chain is final and the peg-out confirmed."),
+        },
+        TransactionDescription {
+            name: "KickOff1",
+            summary: "Starts the operator's claim on the deposit and commits to the peg-out it paid",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["PegOutConfirm"],
+            timelock: None,
+            consequences: format!("The challenge window opens. The operator has to commit to a start time with the start time transaction and broadcast kick-off 2 within {} blocks, or anyone can end its claim with the timeouts.", timelocks.kick_off_timeout),
+        },
+        TransactionDescription {
+            name: "StartTime",
+            summary: "Commits to the time the operator starts counting the superblock period from",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["KickOff1"],
+            timelock: None,
+            consequences: String::from("The start time timeout can no longer be broadcast. Committing a start time that does not match the chain lets anyone disprove the claim."),
+        },
+        TransactionDescription {
+            name: "StartTimeTimeout",
+            summary: "Ends the operator's claim if it did not commit to a start time in time",
+            broadcaster: Broadcaster::Anyone,
+            prerequisites: vec!["KickOff1"],
+            timelock: timelock(timelocks.start_time_timeout, "KickOff1"),
+            consequences: String::from("The operator can no longer be reimbursed by this graph. Part of its funds pays the broadcaster, the rest is burnt."),
+        },
+        TransactionDescription {
+            name: "KickOff2",
+            summary: "Commits to the heaviest superblock the operator saw during the superblock period",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["KickOff1"],
+            timelock: timelock(timelocks.kick_off_2, "KickOff1"),
+            consequences: format!("Take 1 becomes available {} blocks later unless the operator is challenged. A heavier superblock lets anyone broadcast the disprove chain.", timelocks.take_1),
+        },
+        TransactionDescription {
+            name: "KickOffTimeout",
+            summary: "Ends the operator's claim if kick-off 2 was not broadcast in time",
+            broadcaster: Broadcaster::Anyone,
+            prerequisites: vec!["KickOff1"],
+            timelock: timelock(timelocks.kick_off_timeout, "KickOff1"),
+            consequences: String::from("The operator can no longer be reimbursed by this graph. Part of its funds pays the broadcaster, the rest is burnt."),
+        },
+        TransactionDescription {
+            name: "Challenge",
+            summary: "Contests the operator's claim, funded by the challenger",
+            broadcaster: Broadcaster::Anyone,
+            prerequisites: vec!["KickOff1"],
+            timelock: None,
+            consequences: String::from("Take 1 can no longer be broadcast. The operator has to prove its claim with the assert transactions and is paid the crowdfunded amount to cover its cost."),
+        },
+        TransactionDescription {
+            name: "DisproveChain",
+            summary: "Proves that the superblock committed in kick-off 2 is not the heaviest",
+            broadcaster: Broadcaster::Anyone,
+            prerequisites: vec!["KickOff2"],
+            timelock: None,
+            consequences: format!("The operator can no longer be reimbursed by this graph. {}% of its funds is burnt, the rest pays the broadcaster.", parameters.disprove_burn_percentage),
+        },
+        TransactionDescription {
+            name: "Take1",
+            summary: "Reimburses the operator from the deposit when nobody challenged it",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["PegInConfirm", "KickOff1", "KickOff2"],
+            timelock: timelock(timelocks.take_1, "KickOff2"),
+            consequences: String::from("The operator is reimbursed and the graph is settled."),
+        },
+        TransactionDescription {
+            name: "AssertInitial",
+            summary: "Starts the operator's answer to a challenge",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["KickOff2"],
+            timelock: timelock(timelocks.assert_initial, "KickOff2"),
+            consequences: String::from("The operator has to commit to its proof with assert commit 1 and 2, and finish with assert final."),
+        },
+        TransactionDescription {
+            name: "AssertCommit1",
+            summary: "Commits to the first half of the intermediate values of the operator's proof verification",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["AssertInitial"],
+            timelock: None,
+            consequences: String::from("The committed values are public. Values that do not verify let anyone disprove the claim."),
+        },
+        TransactionDescription {
+            name: "AssertCommit2",
+            summary: "Commits to the second half of the intermediate values of the operator's proof verification",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["AssertInitial"],
+            timelock: None,
+            consequences: String::from("The committed values are public. Values that do not verify let anyone disprove the claim."),
+        },
+        TransactionDescription {
+            name: "AssertFinal",
+            summary: "Completes the operator's proof once all intermediate values are committed",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["AssertInitial", "AssertCommit1", "AssertCommit2"],
+            timelock: None,
+            consequences: format!("The disprove window opens. Take 2 becomes available {} blocks later unless the proof is disproven.", timelocks.take_2),
+        },
+        TransactionDescription {
+            name: "Disprove",
+            summary: "Proves that a step of the operator's asserted proof verification fails",
+            broadcaster: Broadcaster::Anyone,
+            prerequisites: vec!["KickOff1", "AssertFinal"],
+            timelock: None,
+            consequences: format!("The operator can no longer be reimbursed by this graph. {}% of its funds is burnt, the rest pays the broadcaster.{collateral}", parameters.disprove_burn_percentage),
+        },
+        TransactionDescription {
+            name: "Take2",
+            summary: "Reimburses the operator from the deposit after its proof stood the disprove window",
+            broadcaster: Broadcaster::Operator,
+            prerequisites: vec!["PegInConfirm", "KickOff1", "AssertFinal"],
+            timelock: timelock(timelocks.take_2, "AssertFinal"),
+            consequences: String::from("The operator is reimbursed and the graph is settled."),
+        },
+    ]
+}
+
+// Sizes of the scripts the chunker and the Winternitz commitments produce. Chunker or script
+// changes that grow them make the assert and disprove txs heavier than the relay fees budgeted for
+// them, see `transactions::base`, so releases are checked against `SCRIPT_BUDGET`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptSizes {
+    pub connector_c_script_bytes: usize, // all lock scripts together
+    // Largest lock script together with its control block
+    pub connector_c_leaf_bytes: usize,
+    // Witnesses of all inputs, with every digit signed by a full preimage
+    pub assert_commit_1_witness_bytes: usize,
+    pub assert_commit_2_witness_bytes: usize,
+}
+
+pub const SCRIPT_BUDGET: ScriptSizes = ScriptSizes {
+    // Generated in memory and cached on disk for every graph a client takes part in
+    connector_c_script_bytes: 512 * 1024 * 1024,
+    // Witness bytes weigh a quarter of a vbyte, so none of these may take more than the vsize the
+    // relay fee of their transaction covers
+    connector_c_leaf_bytes: VSIZE_DISPROVE * WITNESS_SCALE_FACTOR,
+    assert_commit_1_witness_bytes: VSIZE_ASSERT_COMMIT1 * WITNESS_SCALE_FACTOR,
+    assert_commit_2_witness_bytes: VSIZE_ASSERT_COMMIT2 * WITNESS_SCALE_FACTOR,
+};
+
+// Sizes do not depend on the key values, any fixed keys do
+const SCRIPT_SIZES_OPERATOR_SECRET: &str =
+    "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const SCRIPT_SIZES_GRAPH_ID: &str = "script-sizes";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptBudgetEntry {
+    pub name: &'static str,
+    pub bytes: usize,
+    pub budget: usize,
+}
+
+impl ScriptBudgetEntry {
+    pub fn is_exceeded(&self) -> bool {
+        self.bytes > self.budget
+    }
+}
+
+impl fmt::Display for ScriptBudgetEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} bytes ({:.1}%)",
+            self.name,
+            self.bytes,
+            self.budget,
+            self.bytes as f64 * 100.0 / self.budget as f64
+        )?;
+        if self.is_exceeded() {
+            write!(f, ", over budget")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptBudgetReport {
+    pub entries: Vec<ScriptBudgetEntry>,
+}
+
+impl ScriptBudgetReport {
+    pub fn new(sizes: &ScriptSizes, budget: &ScriptSizes) -> Self {
+        let entry = |name, bytes, budget| ScriptBudgetEntry {
+            name,
+            bytes,
+            budget,
+        };
+        Self {
+            entries: vec![
+                entry(
+                    "Connector C lock scripts",
+                    sizes.connector_c_script_bytes,
+                    budget.connector_c_script_bytes,
+                ),
+                entry(
+                    "Largest connector C leaf",
+                    sizes.connector_c_leaf_bytes,
+                    budget.connector_c_leaf_bytes,
+                ),
+                entry(
+                    "Assert commit 1 witnesses",
+                    sizes.assert_commit_1_witness_bytes,
+                    budget.assert_commit_1_witness_bytes,
+                ),
+                entry(
+                    "Assert commit 2 witnesses",
+                    sizes.assert_commit_2_witness_bytes,
+                    budget.assert_commit_2_witness_bytes,
+                ),
+            ],
+        }
+    }
+
+    pub fn exceeded(&self) -> Vec<&ScriptBudgetEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_exceeded())
+            .collect()
+    }
+}
+
+impl fmt::Display for ScriptBudgetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.entries.iter().map(|entry| entry.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+// Measures the scripts of a graph built from fixed keys. Generates the connector C lock scripts,
+// which takes minutes unless they are cached from a previous run.
+pub fn measure_script_sizes() -> ScriptSizes {
+    let network = Network::Regtest;
+    let (operator_keypair, operator_public_key) =
+        generate_keys_from_secret(network, SCRIPT_SIZES_OPERATOR_SECRET);
+    let commitment_secrets = CommitmentMessageId::generate_commitment_secrets_from_seed(
+        SCRIPT_SIZES_OPERATOR_SECRET.as_bytes(),
+        SCRIPT_SIZES_GRAPH_ID,
+    );
+    let (connector_e1_commitment_public_keys, connector_e2_commitment_public_keys) =
+        groth16_commitment_secrets_to_public_keys(&commitment_secrets);
+
+    let connector_c = ConnectorC::new(
+        network,
+        &operator_keypair.x_only_public_key().0,
+        &merge_to_connector_c_commits_public_key(
+            &connector_e1_commitment_public_keys,
+            &connector_e2_commitment_public_keys,
+        ),
+    );
+    let leaf_sizes = connector_c.taproot_leaf_sizes();
+
+    let assert_commit_witness_bytes =
+        |commitment_public_keys: &[BTreeMap<CommitmentMessageId, WinternitzPublicKey>]| -> usize {
+            commitment_public_keys
+                .iter()
+                .map(|public_keys| {
+                    let connector_e = ConnectorE::new(network, &operator_public_key, public_keys);
+                    worst_case_commit_witness_bytes(&connector_e)
+                })
+                .sum()
+        };
+
+    ScriptSizes {
+        connector_c_script_bytes: leaf_sizes.iter().map(|(script, _)| script).sum(),
+        connector_c_leaf_bytes: leaf_sizes
+            .iter()
+            .map(|(script, control_block)| script + control_block)
+            .max()
+            .unwrap_or_default(),
+        assert_commit_1_witness_bytes: assert_commit_witness_bytes(
+            &connector_e1_commitment_public_keys,
+        ),
+        assert_commit_2_witness_bytes: assert_commit_witness_bytes(
+            &connector_e2_commitment_public_keys,
+        ),
+    }
+}
+
+pub fn script_budget_report() -> ScriptBudgetReport {
+    ScriptBudgetReport::new(&measure_script_sizes(), &SCRIPT_BUDGET)
+}
+
+// Every digit of a Winternitz signature is a 20 byte preimage followed by the digit itself, empty
+// for zero. The sizes are measured as if no digit was zero.
+fn worst_case_commit_witness_bytes(connector_e: &ConnectorE) -> usize {
+    let script = connector_e.generate_taproot_leaf_script(0);
+    let control_block = connector_e
+        .generate_taproot_spend_info()
+        .control_block(&(script.clone(), LeafVersion::TapScript))
+        .expect("Unable to create Control block");
+
+    let mut witness = Witness::new();
+    for public_key in connector_e.commitment_public_keys.values() {
+        for _ in &public_key.public_key {
+            witness.push([0u8; 20]);
+            witness.push([0u8; 1]);
+        }
+    }
+    witness.push(script.as_bytes());
+    witness.push(control_block.serialize());
+    witness.size()
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+use bitcoin::{Address, Network, PublicKey, ScriptBuf, TxOut};
+use miniscript::{Descriptor, DescriptorPublicKey};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ValidationError, scripts::generate_pay_to_pubkey_script_address};
+
+// Where the reimbursement of the operator (take 1 and take 2) and the rewards of the challenger
+// (disprove, disprove chain and the timeouts) are paid to. Defaults to the pay to pubkey script of
+// the role key, but any standard script can be configured, e.g. a multisig or custody address.
+
+// Reward destinations per role, set with the `[rewards]` table in bridge.toml. Each is parsed with
+// `parse_reward_destination`, roles without one are paid to their key.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RewardConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifier: Option<String>,
+}
+
+// Reward destination used when none is configured
+pub fn default_reward_script(network: Network, public_key: &PublicKey) -> ScriptBuf {
+    generate_pay_to_pubkey_script_address(network, public_key).script_pubkey()
+}
+
+// Parses an address on the given network, an output descriptor without wildcards or a hex encoded
+// script pubkey
+pub fn parse_reward_destination(
+    network: Network,
+    destination: &str,
+) -> Result<ScriptBuf, ValidationError> {
+    let invalid = |reason: String| {
+        ValidationError::InvalidRewardDestination(format!("{destination}: {reason}"))
+    };
+
+    let script = if let Ok(address) = Address::from_str(destination) {
+        address
+            .require_network(network)
+            .map_err(|e| invalid(e.to_string()))?
+            .script_pubkey()
+    } else if destination.contains('(') {
+        let descriptor = Descriptor::<DescriptorPublicKey>::from_str(destination)
+            .map_err(|e| invalid(e.to_string()))?;
+        if descriptor.has_wildcard() {
+            return Err(invalid(String::from(
+                "descriptors with wildcards describe more than one script",
+            )));
+        }
+        descriptor
+            .at_derivation_index(0)
+            .map_err(|e| invalid(e.to_string()))?
+            .script_pubkey()
+    } else {
+        ScriptBuf::from_hex(destination).map_err(|e| invalid(e.to_string()))?
+    };
+
+    validate_reward_script(&script)?;
+    Ok(script)
+}
+
+// Reward outputs are broadcast by the network only if their script is standard
+pub fn validate_reward_script(script: &ScriptBuf) -> Result<(), ValidationError> {
+    if script.is_p2pk()
+        || script.is_p2pkh()
+        || script.is_p2sh()
+        || script.is_p2wpkh()
+        || script.is_p2wsh()
+        || script.is_p2tr()
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::NonStandardRewardScript(script.clone()))
+    }
+}
+
+pub fn validate_reward_output(output: &TxOut) -> Result<(), ValidationError> {
+    validate_reward_script(&output.script_pubkey)?;
+
+    let min = output.script_pubkey.minimal_non_dust();
+    if output.value < min {
+        return Err(ValidationError::RewardOutputBelowDust {
+            amount: output.value,
+            min,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use std::str::FromStr;
+
+use bitcoin::{Address, Network, PublicKey, ScriptBuf};
+use miniscript::{policy::Concrete, Descriptor, Miniscript, Segwitv0};
+
+// Miniscript counterparts of the hand-written P2WSH scripts in `scripts.rs`, compiled from their
+// spending policies. Their descriptors let wallets and indexers watch the outputs. The scripts
+// that cannot be expressed in miniscript have no counterpart: the burn script uses an
+// uncompressed key and the inscription script carries data in an OP_IF branch.
+
+pub type SegwitMiniscript = Miniscript<PublicKey, Segwitv0>;
+
+pub fn pay_to_pubkey_policy(public_key: &PublicKey) -> Concrete<PublicKey> {
+    Concrete::from_str(&format!("pk({public_key})")).expect("Invalid pay to pubkey policy")
+}
+
+pub fn timelock_policy(public_key: &PublicKey, num_blocks_timelock: u32) -> Concrete<PublicKey> {
+    Concrete::from_str(&format!(
+        "and(pk({public_key}),older({num_blocks_timelock}))"
+    ))
+    .expect("Invalid timelock policy")
+}
+
+pub fn compile_segwit_policy(policy: &Concrete<PublicKey>) -> SegwitMiniscript {
+    policy
+        .compile::<Segwitv0>()
+        .unwrap_or_else(|err| panic!("Could not compile policy {policy}: {err}"))
+}
+
+// Same script as `generate_pay_to_pubkey_script`
+pub fn generate_pay_to_pubkey_miniscript(public_key: &PublicKey) -> SegwitMiniscript {
+    compile_segwit_policy(&pay_to_pubkey_policy(public_key))
+}
+
+// Same spending conditions as `generate_timelock_script`, which drops the CSV argument instead of
+// verifying it. The argument is a positive number, so both succeed and fail for the same
+// witnesses and input sequences. The policy compiler may order the key and timelock checks
+// differently, the encoding below matches the hand-written one in everything but that opcode.
+pub fn generate_timelock_miniscript(
+    public_key: &PublicKey,
+    num_blocks_timelock: u32,
+) -> SegwitMiniscript {
+    SegwitMiniscript::from_str(&format!(
+        "and_v(v:older({num_blocks_timelock}),pk({public_key}))"
+    ))
+    .unwrap_or_else(|err| panic!("Invalid timelock miniscript: {err}"))
+}
+
+pub fn generate_wsh_descriptor(miniscript: SegwitMiniscript) -> Descriptor<PublicKey> {
+    Descriptor::new_wsh(miniscript).expect("Could not create wsh descriptor")
+}
+
+// Describes the outputs of `generate_pay_to_pubkey_script_address`
+pub fn generate_pay_to_pubkey_descriptor(public_key: &PublicKey) -> Descriptor<PublicKey> {
+    generate_wsh_descriptor(generate_pay_to_pubkey_miniscript(public_key))
+}
+
+// Describes outputs locked with `generate_timelock_miniscript`. Outputs of the hand-written
+// `generate_timelock_script_address` have a different script hash.
+pub fn generate_timelock_descriptor(
+    public_key: &PublicKey,
+    num_blocks_timelock: u32,
+) -> Descriptor<PublicKey> {
+    generate_wsh_descriptor(generate_timelock_miniscript(
+        public_key,
+        num_blocks_timelock,
+    ))
+}
+
+pub fn generate_descriptor_address(
+    descriptor: &Descriptor<PublicKey>,
+    network: Network,
+) -> Address {
+    descriptor
+        .address(network)
+        .expect("Descriptor has no address")
+}
+
+pub fn generate_descriptor_script(descriptor: &Descriptor<PublicKey>) -> ScriptBuf {
+    descriptor
+        .explicit_script()
+        .expect("Descriptor has no explicit script")
+}
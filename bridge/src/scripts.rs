@@ -3,6 +3,8 @@ use bitcoin::{
     Address, CompressedPublicKey, Network, PubkeyHash, PublicKey, ScriptBuf, XOnlyPublicKey,
 };
 use bitvm::treepp::script;
+use secp256k1::SECP256K1;
+use serde::{Deserialize, Serialize};
 use std::{str::FromStr, sync::LazyLock};
 
 // TODO replace these public keys
@@ -81,6 +83,37 @@ pub fn generate_pay_to_pubkey_script_address(network: Network, public_key: &Publ
     Address::p2wsh(&generate_pay_to_pubkey_script(public_key), network)
 }
 
+// Address of a taproot output spendable with the key path of `public_key` only, e.g. by a wallet
+// that holds the key
+pub fn generate_p2tr_key_path_address(network: Network, public_key: &XOnlyPublicKey) -> Address {
+    Address::p2tr(SECP256K1, *public_key, None, network)
+}
+
+// Type of the depositor address the peg-in deposit spends from
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum DepositorAddressType {
+    // The depositor public key and OP_CHECKSIG, in a P2WSH output
+    #[default]
+    P2wsh,
+    // The key path of the depositor taproot public key
+    P2tr,
+}
+
+pub fn generate_depositor_address(
+    network: Network,
+    depositor_public_key: &PublicKey,
+    address_type: DepositorAddressType,
+) -> Address {
+    match address_type {
+        DepositorAddressType::P2wsh => {
+            generate_pay_to_pubkey_script_address(network, depositor_public_key)
+        }
+        DepositorAddressType::P2tr => {
+            generate_p2tr_key_path_address(network, &XOnlyPublicKey::from(*depositor_public_key))
+        }
+    }
+}
+
 pub fn generate_pay_to_pubkey_hash_with_inscription_script_address(
     network: Network,
     public_key_hash: &PubkeyHash,
@@ -1,4 +1,10 @@
-use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use bitcoin::{
+    hex::{DisplayHex, FromHex},
+    PublicKey, ScriptBuf, Witness, XOnlyPublicKey,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 pub fn serialize(object: &impl Serialize) -> String {
     serde_json::to_string(object)
@@ -32,3 +38,250 @@ where
         Err(err) => Err(format!("Failed to decode JSON data to object: {}", err)),
     }
 }
+
+// Representations of bitcoin types shared by the graph, transaction and connector types. Fields
+// use these explicitly, so their JSON does not depend on how each type implements serde.
+
+// Types represented by hex strings, e.g. scripts and keys
+pub trait HexEncoding: Sized {
+    type Encoded: Serialize + DeserializeOwned;
+
+    fn encode_hex(&self) -> Self::Encoded;
+    fn decode_hex(encoded: Self::Encoded) -> Result<Self, String>;
+}
+
+impl HexEncoding for ScriptBuf {
+    type Encoded = String;
+
+    fn encode_hex(&self) -> String {
+        self.to_hex_string()
+    }
+
+    fn decode_hex(encoded: String) -> Result<Self, String> {
+        ScriptBuf::from_hex(&encoded).map_err(|e| format!("Invalid script hex {encoded}: {e}"))
+    }
+}
+
+impl HexEncoding for PublicKey {
+    type Encoded = String;
+
+    fn encode_hex(&self) -> String {
+        self.to_string()
+    }
+
+    fn decode_hex(encoded: String) -> Result<Self, String> {
+        PublicKey::from_str(&encoded).map_err(|e| format!("Invalid public key {encoded}: {e}"))
+    }
+}
+
+impl HexEncoding for XOnlyPublicKey {
+    type Encoded = String;
+
+    fn encode_hex(&self) -> String {
+        self.to_string()
+    }
+
+    fn decode_hex(encoded: String) -> Result<Self, String> {
+        XOnlyPublicKey::from_str(&encoded)
+            .map_err(|e| format!("Invalid x-only public key {encoded}: {e}"))
+    }
+}
+
+impl HexEncoding for Vec<u8> {
+    type Encoded = String;
+
+    fn encode_hex(&self) -> String {
+        self.to_lower_hex_string()
+    }
+
+    fn decode_hex(encoded: String) -> Result<Self, String> {
+        Vec::<u8>::from_hex(&encoded).map_err(|e| format!("Invalid hex {encoded}: {e}"))
+    }
+}
+
+// One hex string per witness element
+impl HexEncoding for Witness {
+    type Encoded = Vec<String>;
+
+    fn encode_hex(&self) -> Vec<String> {
+        self.iter()
+            .map(|element| element.to_lower_hex_string())
+            .collect()
+    }
+
+    fn decode_hex(encoded: Vec<String>) -> Result<Self, String> {
+        let elements = encoded
+            .iter()
+            .map(|element| {
+                Vec::<u8>::from_hex(element)
+                    .map_err(|e| format!("Invalid witness element {element}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Witness::from_slice(&elements))
+    }
+}
+
+pub mod hex_encoded {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::HexEncoding;
+
+    pub fn serialize<T: HexEncoding, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.encode_hex().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: HexEncoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::decode_hex(T::Encoded::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+pub mod hex_encoded_vec {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::HexEncoding;
+
+    pub fn serialize<T: HexEncoding, S: Serializer>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(HexEncoding::encode_hex))
+    }
+
+    pub fn deserialize<'de, T: HexEncoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        Vec::<T::Encoded>::deserialize(deserializer)?
+            .into_iter()
+            .map(|encoded| T::decode_hex(encoded).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+pub mod hex_encoded_option {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::HexEncoding;
+
+    pub fn serialize<T: HexEncoding, S: Serializer>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value
+            .as_ref()
+            .map(HexEncoding::encode_hex)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: HexEncoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        Option::<T::Encoded>::deserialize(deserializer)?
+            .map(|encoded| T::decode_hex(encoded).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+// Consensus encoding as a hex string, e.g. for transactions and their prevouts
+pub mod consensus_hex {
+    use bitcoin::consensus::{
+        serde::{Hex, With},
+        Decodable, Encodable,
+    };
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<T: Encodable, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        With::<Hex>::serialize(value, serializer)
+    }
+
+    pub fn deserialize<'de, T: Decodable, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        With::<Hex>::deserialize(deserializer)
+    }
+}
+
+// Maps keyed by public keys, with the keys as hex strings. Serialized in the order of the keys, so
+// every client writes the same bytes for the same map.
+pub mod pubkey_map {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        str::FromStr,
+    };
+
+    use bitcoin::PublicKey;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<V: Serialize, S: Serializer>(
+        map: &HashMap<PublicKey, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let ordered: BTreeMap<String, &V> = map
+            .iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        serializer.collect_map(ordered)
+    }
+
+    pub fn deserialize<'de, V: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<PublicKey, V>, D::Error> {
+        HashMap::<String, V>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, value)| {
+                PublicKey::from_str(&key)
+                    .map(|key| (key, value))
+                    .map_err(|e| D::Error::custom(format!("Invalid public key {key}: {e}")))
+            })
+            .collect()
+    }
+}
+
+// MuSig2 nonces and signatures by input index, then by verifier public key, both in key order
+pub mod input_pubkey_map {
+    use std::collections::{BTreeMap, HashMap};
+
+    use bitcoin::PublicKey;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    struct ByPublicKey<'a, V>(&'a HashMap<PublicKey, V>);
+
+    impl<V: Serialize> Serialize for ByPublicKey<'_, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::pubkey_map::serialize(self.0, serializer)
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(bound(deserialize = "V: Deserialize<'de>"))]
+    struct OwnedByPublicKey<V>(#[serde(with = "super::pubkey_map")] HashMap<PublicKey, V>);
+
+    pub fn serialize<V: Serialize, S: Serializer>(
+        map: &HashMap<usize, HashMap<PublicKey, V>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let ordered: BTreeMap<&usize, ByPublicKey<V>> = map
+            .iter()
+            .map(|(input_index, by_key)| (input_index, ByPublicKey(by_key)))
+            .collect();
+        serializer.collect_map(ordered)
+    }
+
+    pub fn deserialize<'de, V: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<usize, HashMap<PublicKey, V>>, D::Error> {
+        Ok(
+            HashMap::<usize, OwnedByPublicKey<V>>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(input_index, by_key)| (input_index, by_key.0))
+                .collect(),
+        )
+    }
+}
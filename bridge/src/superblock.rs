@@ -41,6 +41,30 @@ pub fn find_superblock() -> Header {
     }
 }
 
+// A superblock disproving the committed one must be mined within this many seconds after the
+// committed start time, enforced by the disprove chain leaf of connector B
+pub const SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS: u32 = 60 * 60 * 24 * 14;
+
+// Whether `candidate` is accepted by the disprove chain leaf of connector B against the committed
+// superblock hash: mined within the measurement period and heavier. Superblock weight is compared
+// on the hash as a number, a smaller hash took more work.
+pub fn is_heavier_superblock(
+    candidate: &Header,
+    committed_hash: &BlockHash,
+    start_time: u32,
+) -> bool {
+    let hash_as_number = |hash: BlockHash| {
+        let mut bytes = hash.to_byte_array();
+        bytes.reverse();
+        bytes
+    };
+
+    candidate.time > start_time
+        && u64::from(candidate.time)
+            < u64::from(start_time) + u64::from(SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS)
+        && hash_as_number(candidate.block_hash()) < hash_as_number(*committed_hash)
+}
+
 pub fn get_superblock_message(sb: &Header) -> Vec<u8> {
     serialize(sb)
 }
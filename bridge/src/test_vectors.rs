@@ -0,0 +1,276 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use bitcoin::{
+    block::Header, consensus::encode::serialize_hex, script::read_scriptint, Network,
+    XOnlyPublicKey,
+};
+use bitcoin_script::script;
+use bitvm::execute_script;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connectors::{base::TaprootConnector, connector_b::ConnectorB},
+    error::Error,
+    serialization::serialize,
+    superblock::{
+        extract_superblock_ts_from_header, find_superblock, get_superblock_hash_message,
+        get_superblock_message,
+    },
+    utils::{sb_hash_from_bytes, sb_hash_from_nibbles, H256},
+};
+
+// x coordinate of the secp256k1 generator point, used as a well known n-of-n key
+const TEST_VECTOR_N_OF_N_TAPROOT_PUBLIC_KEY: &str =
+    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuperblockTimestampVector {
+    // Serialized header. The script expects it pushed byte by byte in reverse, i.e. byte 0 on top.
+    pub header: String,
+    pub expected_timestamp: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuperblockHashVector {
+    pub header: String,
+    // Hash bytes in `get_superblock_hash_message` order, pushed first to last.
+    pub hash_bytes: String,
+    // Big-endian hex of the number left on the stack by `sb_hash_from_bytes`.
+    pub expected_from_bytes: String,
+    // Same hash pushed as nibbles, high nibble first, converted by `sb_hash_from_nibbles`.
+    pub expected_from_nibbles: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuperblockHashComparisonVector {
+    pub committed_header: String,
+    pub disprove_header: String,
+    // Outcome of `SB'.hash < SB.hash` as evaluated in connector B.
+    pub expected_disprove_is_heavier: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConnectorBLeafVector {
+    pub network: Network,
+    pub n_of_n_taproot_public_key: XOnlyPublicKey,
+    pub leaf_index: u32,
+    pub script: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuperblockTestVectors {
+    pub timestamps: Vec<SuperblockTimestampVector>,
+    pub hashes: Vec<SuperblockHashVector>,
+    pub hash_comparisons: Vec<SuperblockHashComparisonVector>,
+    pub connector_b_leaves: Vec<ConnectorBLeafVector>,
+}
+
+impl SuperblockTestVectors {
+    pub fn to_json(&self) -> String {
+        serialize(self)
+    }
+}
+
+/// Generates test vectors for the superblock scripts by executing them, so that the expected
+/// values reflect the script semantics rather than a reimplementation of them.
+pub fn generate_superblock_test_vectors(
+    headers: &[Header],
+    network: Network,
+    n_of_n_taproot_public_key: &XOnlyPublicKey,
+) -> Result<SuperblockTestVectors, Error> {
+    let timestamps = headers
+        .iter()
+        .map(|header| {
+            Ok(SuperblockTimestampVector {
+                header: serialize_hex(header),
+                expected_timestamp: execute_extract_superblock_ts(header)?,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let hashes = headers
+        .iter()
+        .map(|header| {
+            Ok(SuperblockHashVector {
+                header: serialize_hex(header),
+                hash_bytes: hex::encode(get_superblock_hash_message(header)),
+                expected_from_bytes: u32_le_to_hex(&execute_sb_hash_from_bytes(header)?),
+                expected_from_nibbles: u32_le_to_hex(&execute_sb_hash_from_nibbles(header)?),
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let mut hash_comparisons = vec![];
+    for committed_header in headers {
+        for disprove_header in headers {
+            hash_comparisons.push(SuperblockHashComparisonVector {
+                committed_header: serialize_hex(committed_header),
+                disprove_header: serialize_hex(disprove_header),
+                expected_disprove_is_heavier: execute_sb_hash_lessthan(
+                    disprove_header,
+                    committed_header,
+                )?,
+            });
+        }
+    }
+
+    // Leaf 0 does not use commitments, no Winternitz keys are needed
+    let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key, &BTreeMap::new());
+    let leaf_index = 0;
+    let connector_b_leaves = vec![ConnectorBLeafVector {
+        network,
+        n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
+        leaf_index,
+        script: connector_b
+            .generate_taproot_leaf_script(leaf_index)
+            .to_hex_string(),
+    }];
+
+    Ok(SuperblockTestVectors {
+        timestamps,
+        hashes,
+        hash_comparisons,
+        connector_b_leaves,
+    })
+}
+
+/// Test vectors for the current superblock and a few variations of it.
+pub fn default_superblock_test_vectors(network: Network) -> Result<SuperblockTestVectors, Error> {
+    let superblock = find_superblock();
+
+    let mut later_superblock = superblock;
+    later_superblock.time += 60 * 60;
+
+    let mut other_nonce_superblock = superblock;
+    other_nonce_superblock.nonce = other_nonce_superblock.nonce.wrapping_add(1);
+
+    let n_of_n_taproot_public_key =
+        XOnlyPublicKey::from_str(TEST_VECTOR_N_OF_N_TAPROOT_PUBLIC_KEY).unwrap();
+
+    generate_superblock_test_vectors(
+        &[superblock, later_superblock, other_nonce_superblock],
+        network,
+        &n_of_n_taproot_public_key,
+    )
+}
+
+fn execute_extract_superblock_ts(header: &Header) -> Result<u32, Error> {
+    let mut message = get_superblock_message(header);
+    message.reverse();
+
+    let result = execute_script(script! {
+        for byte in message { { byte } }
+        { extract_superblock_ts_from_header() }
+    });
+    let timestamp = read_stack_number(
+        result.final_stack.0.iter_str().collect(),
+        "extract_superblock_ts_from_header",
+    )? as u32;
+    if timestamp != header.time {
+        return Err(Error::Other(format!(
+            "Extracted timestamp {timestamp} does not match the header time {}",
+            header.time
+        )));
+    }
+
+    Ok(timestamp)
+}
+
+fn execute_sb_hash_from_bytes(header: &Header) -> Result<Vec<u32>, Error> {
+    let hash = get_superblock_hash_message(header);
+    let result = execute_script(script! {
+        for byte in hash { { byte } }
+        { sb_hash_from_bytes() }
+    });
+
+    read_stack_hash(
+        result.final_stack.0.iter_str().collect(),
+        "sb_hash_from_bytes",
+    )
+}
+
+fn execute_sb_hash_from_nibbles(header: &Header) -> Result<Vec<u32>, Error> {
+    let hash = get_superblock_hash_message(header);
+    let result = execute_script(script! {
+        for byte in hash {
+            { byte >> 4 }
+            { byte & 0xf }
+        }
+        { sb_hash_from_nibbles() }
+    });
+
+    read_stack_hash(
+        result.final_stack.0.iter_str().collect(),
+        "sb_hash_from_nibbles",
+    )
+}
+
+fn execute_sb_hash_lessthan(
+    disprove_header: &Header,
+    committed_header: &Header,
+) -> Result<bool, Error> {
+    let disprove_hash = execute_sb_hash_from_bytes(disprove_header)?;
+    let committed_hash = execute_sb_hash_from_bytes(committed_header)?;
+    let result = execute_script(script! {
+        { H256::push_u32_le(&disprove_hash) }
+        { H256::push_u32_le(&committed_hash) }
+        { H256::lessthan(1, 0) }
+    });
+
+    Ok(read_stack_number(result.final_stack.0.iter_str().collect(), "H256::lessthan")? != 0)
+}
+
+// Top of the final stack of a script, which leaves a single number
+fn read_stack_number(stack: Vec<Vec<u8>>, script_name: &str) -> Result<i64, Error> {
+    stack
+        .last()
+        .and_then(|item| read_scriptint(item).ok())
+        .ok_or_else(|| Error::Other(format!("{script_name} did not leave a number on the stack")))
+}
+
+// Final stack of a script, which leaves the limbs of a hash
+fn read_stack_hash(stack: Vec<Vec<u8>>, script_name: &str) -> Result<Vec<u32>, Error> {
+    if stack.len() != H256::N_LIMBS as usize
+        || stack.iter().any(|limb| read_scriptint(limb).is_err())
+    {
+        return Err(Error::Other(format!(
+            "{script_name} did not leave a hash on the stack"
+        )));
+    }
+
+    Ok(H256::read_u32_le(stack))
+}
+
+fn u32_le_to_hex(words: &[u32]) -> String {
+    words
+        .iter()
+        .rev()
+        .map(|word| format!("{:08x}", word))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::Network;
+
+    use super::default_superblock_test_vectors;
+    use crate::{serialization::deserialize, superblock::find_superblock};
+
+    #[test]
+    fn test_default_superblock_test_vectors() {
+        let vectors = default_superblock_test_vectors(Network::Regtest).unwrap();
+
+        assert_eq!(
+            vectors.timestamps[0].expected_timestamp,
+            find_superblock().time
+        );
+        for comparison in &vectors.hash_comparisons {
+            if comparison.committed_header == comparison.disprove_header {
+                assert!(!comparison.expected_disprove_is_heavier);
+            }
+        }
+
+        let json = vectors.to_json();
+        assert_eq!(deserialize::<super::SuperblockTestVectors>(&json), vectors);
+    }
+}
@@ -1,6 +1,4 @@
-use bitcoin::{
-    absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-};
+use bitcoin::{absolute, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut};
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +10,7 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, verifier::VerifierContext},
+        error::Error,
         graphs::base::DUST_AMOUNT,
     },
     base::*,
@@ -21,14 +20,18 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct AssertTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -139,7 +142,7 @@ impl AssertTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -147,12 +150,14 @@ impl AssertTransaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_b);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
@@ -171,9 +176,9 @@ impl AssertTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &secret_nonces[&input_index])
     }
 
     pub fn merge(&mut self, assert: &AssertTransaction) {
@@ -1,8 +1,12 @@
-use bitcoin::{absolute, consensus, Amount, ScriptBuf, Transaction, TxOut};
+use bitcoin::{absolute, Amount, ScriptBuf, Transaction, TxOut};
 use bitvm::{chunk::api::type_conversion_utils::RawWitness, execute_raw_script_with_inputs};
 use serde::{Deserialize, Serialize};
 
-use crate::transactions::signing::populate_taproot_input_witness;
+use crate::{
+    commitments::CommitmentMessageId,
+    error::{Error, ValidationError},
+    transactions::signing::populate_taproot_input_witness,
+};
 
 use super::{
     super::{
@@ -10,16 +14,21 @@ use super::{
         base::*,
         pre_signed::*,
     },
-    utils::AssertCommit1ConnectorsE,
+    utils::{find_input_order_mismatch, AssertCommit1ConnectorsE},
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct AssertCommit1Transaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
+    // Commitment message id committed by each input, in input order. Empty for graphs created
+    // before the order was recorded.
+    #[serde(default)]
+    input_order: Vec<CommitmentMessageId>,
 }
 
 impl PreSignedTransaction for AssertCommit1Transaction {
@@ -45,20 +54,26 @@ impl AssertCommit1Transaction {
         connectors_e: &AssertCommit1ConnectorsE,
         connector_f_1: &ConnectorF1,
         tx_inputs: Vec<Input>,
+        fee_rate: u64,
     ) -> Self {
         assert_eq!(
             tx_inputs.len(),
             connectors_e.connectors_num(),
             "inputs and connectors e don't match"
         );
+        assert!(
+            connectors_e.is_canonically_ordered(),
+            "connectors e are not in canonical order"
+        );
 
-        Self::new_for_validation(connectors_e, connector_f_1, tx_inputs)
+        Self::new_for_validation(connectors_e, connector_f_1, tx_inputs, fee_rate)
     }
 
     pub fn new_for_validation(
         connectors_e: &AssertCommit1ConnectorsE,
         connector_f_1: &ConnectorF1,
         tx_inputs: Vec<Input>,
+        fee_rate: u64,
     ) -> Self {
         let mut inputs = vec![];
         let mut prev_outs = vec![];
@@ -77,7 +92,7 @@ impl AssertCommit1Transaction {
             prev_scripts.push(connector_e.generate_taproot_leaf_script(0));
             total_output_amount += input.amount;
         }
-        total_output_amount -= Amount::from_sat(MIN_RELAY_FEE_ASSERT_COMMIT1);
+        total_output_amount -= Amount::from_sat(relay_fee_at_rate(VSIZE_ASSERT_COMMIT1, fee_rate));
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -93,9 +108,14 @@ impl AssertCommit1Transaction {
             },
             prev_outs,
             prev_scripts,
+            input_order: connectors_e.input_order(),
         }
     }
 
+    pub fn input_order(&self) -> &[CommitmentMessageId] {
+        &self.input_order
+    }
+
     pub fn sign(&mut self, connectors_e: &AssertCommit1ConnectorsE, witnesses: Vec<RawWitness>) {
         assert_eq!(witnesses.len(), connectors_e.connectors_num());
         for (input_index, witness) in (0..connectors_e.connectors_num()).zip(witnesses) {
@@ -125,8 +145,25 @@ impl AssertCommit1Transaction {
         }
     }
 
-    pub fn merge(&mut self, assert_commit_1: &AssertCommit1Transaction) {
+    // Witnesses are merged by input index, which is only meaningful if both txs order their
+    // inputs the same way
+    pub fn merge(&mut self, assert_commit_1: &AssertCommit1Transaction) -> Result<(), Error> {
+        if !self.input_order.is_empty() && !assert_commit_1.input_order.is_empty() {
+            if let Some((input_index, expected, found)) =
+                find_input_order_mismatch(&self.input_order, &assert_commit_1.input_order)
+            {
+                return Err(Error::Validation(
+                    ValidationError::AssertCommitInputOrderMismatch {
+                        tx_name: self.name(),
+                        input_index,
+                        expected,
+                        found,
+                    },
+                ));
+            }
+        }
         merge_transactions(&mut self.tx, &assert_commit_1.tx);
+        Ok(())
     }
 }
 
@@ -1,9 +1,13 @@
-use bitcoin::{absolute, consensus, Amount, ScriptBuf, Transaction, TxOut};
+use bitcoin::{absolute, Amount, ScriptBuf, Transaction, TxOut};
 use serde::{Deserialize, Serialize};
 
 use bitvm::{chunk::api::type_conversion_utils::RawWitness, execute_raw_script_with_inputs};
 
-use crate::transactions::signing::populate_taproot_input_witness;
+use crate::{
+    commitments::CommitmentMessageId,
+    error::{Error, ValidationError},
+    transactions::signing::populate_taproot_input_witness,
+};
 
 use super::{
     super::{
@@ -11,16 +15,21 @@ use super::{
         base::*,
         pre_signed::*,
     },
-    utils::AssertCommit2ConnectorsE,
+    utils::{find_input_order_mismatch, AssertCommit2ConnectorsE},
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct AssertCommit2Transaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
+    // Commitment message id committed by each input, in input order. Empty for graphs created
+    // before the order was recorded.
+    #[serde(default)]
+    input_order: Vec<CommitmentMessageId>,
 }
 
 impl PreSignedTransaction for AssertCommit2Transaction {
@@ -46,20 +55,26 @@ impl AssertCommit2Transaction {
         connectors_e: &AssertCommit2ConnectorsE,
         connector_f_2: &ConnectorF2,
         tx_inputs: Vec<Input>,
+        fee_rate: u64,
     ) -> Self {
         assert_eq!(
             tx_inputs.len(),
             connectors_e.connectors_num(),
             "inputs and connectors e don't match"
         );
+        assert!(
+            connectors_e.is_canonically_ordered(),
+            "connectors e are not in canonical order"
+        );
 
-        Self::new_for_validation(connectors_e, connector_f_2, tx_inputs)
+        Self::new_for_validation(connectors_e, connector_f_2, tx_inputs, fee_rate)
     }
 
     pub fn new_for_validation(
         connectors_e: &AssertCommit2ConnectorsE,
         connector_f_2: &ConnectorF2,
         tx_inputs: Vec<Input>,
+        fee_rate: u64,
     ) -> Self {
         let mut inputs = vec![];
         let mut prev_outs = vec![];
@@ -78,7 +93,7 @@ impl AssertCommit2Transaction {
             prev_scripts.push(connector_e.generate_taproot_leaf_script(0));
             total_output_amount += input.amount;
         }
-        total_output_amount -= Amount::from_sat(MIN_RELAY_FEE_ASSERT_COMMIT2);
+        total_output_amount -= Amount::from_sat(relay_fee_at_rate(VSIZE_ASSERT_COMMIT2, fee_rate));
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -94,9 +109,14 @@ impl AssertCommit2Transaction {
             },
             prev_outs,
             prev_scripts,
+            input_order: connectors_e.input_order(),
         }
     }
 
+    pub fn input_order(&self) -> &[CommitmentMessageId] {
+        &self.input_order
+    }
+
     pub fn sign(&mut self, connectors_e: &AssertCommit2ConnectorsE, witnesses: Vec<RawWitness>) {
         assert_eq!(witnesses.len(), connectors_e.connectors_num());
         for (input_index, witness) in (0..connectors_e.connectors_num()).zip(witnesses) {
@@ -126,8 +146,25 @@ impl AssertCommit2Transaction {
         }
     }
 
-    pub fn merge(&mut self, assert_commit_2: &AssertCommit2Transaction) {
+    // Witnesses are merged by input index, which is only meaningful if both txs order their
+    // inputs the same way
+    pub fn merge(&mut self, assert_commit_2: &AssertCommit2Transaction) -> Result<(), Error> {
+        if !self.input_order.is_empty() && !assert_commit_2.input_order.is_empty() {
+            if let Some((input_index, expected, found)) =
+                find_input_order_mismatch(&self.input_order, &assert_commit_2.input_order)
+            {
+                return Err(Error::Validation(
+                    ValidationError::AssertCommitInputOrderMismatch {
+                        tx_name: self.name(),
+                        input_index,
+                        expected,
+                        found,
+                    },
+                ));
+            }
+        }
         merge_transactions(&mut self.tx, &assert_commit_2.tx);
+        Ok(())
     }
 }
 
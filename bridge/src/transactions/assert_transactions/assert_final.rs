@@ -1,6 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut,
+    absolute, Amount, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -15,6 +14,7 @@ use super::{
                 base::*, connector_4::Connector4, connector_5::Connector5, connector_c::ConnectorC,
             },
             contexts::{base::BaseContext, verifier::VerifierContext},
+            error::Error,
             graphs::base::DUST_AMOUNT,
         },
         base::*,
@@ -24,16 +24,59 @@ use super::{
     utils::AssertCommitConnectorsF,
 };
 
+/// Layout of the assert final outputs, decided when the peg-out graph is created.
+///
+/// `Separate` keeps a dedicated connector 4 output for the take 2 timelock. `Consolidated`
+/// folds that dust output into the connector 5 output and relies on the relative timelock
+/// (nSequence) of the pre-signed take 2 input instead, leaving one less UTXO to track.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum AssertFinalOutputLayout {
+    #[default]
+    Separate,
+    Consolidated,
+}
+
+impl AssertFinalOutputLayout {
+    pub fn is_consolidated(&self) -> bool {
+        matches!(self, AssertFinalOutputLayout::Consolidated)
+    }
+
+    // vout of the connector 4 output, if any
+    pub fn connector_4_vout(&self) -> Option<usize> {
+        match self {
+            AssertFinalOutputLayout::Separate => Some(0),
+            AssertFinalOutputLayout::Consolidated => None,
+        }
+    }
+
+    pub fn connector_5_vout(&self) -> usize {
+        match self {
+            AssertFinalOutputLayout::Separate => 1,
+            AssertFinalOutputLayout::Consolidated => 0,
+        }
+    }
+
+    pub fn connector_c_vout(&self) -> usize {
+        self.connector_5_vout() + 1
+    }
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct AssertFinalTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
+    #[serde(default)]
+    output_layout: AssertFinalOutputLayout,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -95,6 +138,8 @@ impl AssertFinalTransaction {
         input_0: Input,
         input_1: Input,
         input_2: Input,
+        output_layout: AssertFinalOutputLayout,
+        fee_rate: u64,
     ) -> Self {
         let mut this = Self::new_for_validation(
             connector_4,
@@ -105,9 +150,11 @@ impl AssertFinalTransaction {
             input_0,
             input_1,
             input_2,
+            output_layout,
+            fee_rate,
         );
 
-        this.sign_commit_inputs(context);
+        this.sign_operator_inputs(context);
 
         this
     }
@@ -122,6 +169,8 @@ impl AssertFinalTransaction {
         input_0: Input,
         input_1: Input,
         input_2: Input,
+        output_layout: AssertFinalOutputLayout,
+        fee_rate: u64,
     ) -> Self {
         let input_0_leaf = 0;
         let _input_0 = connector_d.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -134,33 +183,44 @@ impl AssertFinalTransaction {
             .connector_f_2
             .generate_tx_in(&input_2);
 
-        let total_output_amount = input_1.amount + input_2.amount + input_0.amount
-            - Amount::from_sat(MIN_RELAY_FEE_ASSERT_FINAL);
-
-        // goes to take_2 tx
-        let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
-            script_pubkey: connector_4.generate_address().script_pubkey(),
+        let relay_fee = match output_layout {
+            AssertFinalOutputLayout::Separate => relay_fee_at_rate(VSIZE_ASSERT_FINAL, fee_rate),
+            AssertFinalOutputLayout::Consolidated => {
+                relay_fee_at_rate(VSIZE_ASSERT_FINAL_CONSOLIDATED, fee_rate)
+            }
         };
+        let total_output_amount =
+            input_1.amount + input_2.amount + input_0.amount - Amount::from_sat(relay_fee);
 
-        // goes to take_2 tx or disprove tx
-        let _output_1 = TxOut {
-            value: total_output_amount - Amount::from_sat(DUST_AMOUNT) * 2,
+        let mut outputs = vec![];
+
+        if output_layout.connector_4_vout().is_some() {
+            // goes to take_2 tx
+            outputs.push(TxOut {
+                value: Amount::from_sat(DUST_AMOUNT),
+                script_pubkey: connector_4.generate_address().script_pubkey(),
+            });
+        }
+
+        // goes to take_2 tx or disprove tx, the connector C dust is deducted as well
+        let dust_outputs_amount = Amount::from_sat(DUST_AMOUNT) * (outputs.len() as u64 + 1);
+        outputs.push(TxOut {
+            value: total_output_amount - dust_outputs_amount,
             script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
-        };
+        });
 
         // goes to take_2 tx or disprove tx
-        let _output_2 = TxOut {
+        outputs.push(TxOut {
             value: Amount::from_sat(DUST_AMOUNT),
             script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
-        };
+        });
 
         AssertFinalTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
                 input: vec![_input_0, _input_1, _input_2],
-                output: vec![_output_0, _output_1, _output_2],
+                output: outputs,
             },
             prev_outs: vec![
                 TxOut {
@@ -187,6 +247,7 @@ impl AssertFinalTransaction {
                 assert_commit_connectors_f.connector_f_1.generate_script(),
                 assert_commit_connectors_f.connector_f_2.generate_script(),
             ],
+            output_layout,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
@@ -198,7 +259,7 @@ impl AssertFinalTransaction {
         context: &VerifierContext,
         connector_d: &ConnectorD,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -206,12 +267,19 @@ impl AssertFinalTransaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_d);
         }
+
+        Ok(())
+    }
+
+    // Signatures of the operator made when the graph is created
+    pub fn sign_operator_inputs(&mut self, context: &OperatorContext) {
+        self.sign_commit_inputs(context);
     }
 
     fn sign_commit_inputs(&mut self, context: &OperatorContext) {
@@ -242,9 +310,13 @@ impl AssertFinalTransaction {
         context: &VerifierContext,
         connector_d: &ConnectorD,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_d, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_d, &secret_nonces[&input_index])
+    }
+
+    pub fn output_layout(&self) -> AssertFinalOutputLayout {
+        self.output_layout
     }
 
     pub fn merge(&mut self, assert: &AssertFinalTransaction) {
@@ -1,6 +1,4 @@
-use bitcoin::{
-    absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-};
+use bitcoin::{absolute, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut};
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -10,6 +8,7 @@ use super::{
         super::{
             connectors::{base::*, connector_b::ConnectorB, connector_d::ConnectorD},
             contexts::{base::BaseContext, verifier::VerifierContext},
+            error::Error,
             graphs::base::DUST_AMOUNT,
         },
         base::*,
@@ -21,14 +20,18 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct AssertInitialTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -85,6 +88,7 @@ impl AssertInitialTransaction {
         assert_commit1_connectors_e: &AssertCommit1ConnectorsE,
         assert_commit2_connectors_e: &AssertCommit2ConnectorsE,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
         Self::new_for_validation(
             connector_b,
@@ -92,6 +96,7 @@ impl AssertInitialTransaction {
             assert_commit1_connectors_e,
             assert_commit2_connectors_e,
             input_0,
+            fee_rate,
         )
     }
 
@@ -101,18 +106,20 @@ impl AssertInitialTransaction {
         assert_commit1_connectors_e: &AssertCommit1ConnectorsE,
         assert_commit2_connectors_e: &AssertCommit2ConnectorsE,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
         let input_0_leaf = 1;
         let _input_0 = connector_b.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_ASSERT_INITIAL);
+        let total_output_amount =
+            input_0.amount - Amount::from_sat(relay_fee_at_rate(VSIZE_ASSERT_INITIAL, fee_rate));
 
         let assert_commit1_expense = Amount::from_sat(
-            MIN_RELAY_FEE_ASSERT_COMMIT1
+            relay_fee_at_rate(VSIZE_ASSERT_COMMIT1, fee_rate)
                 + assert_commit1_connectors_e.connectors_num() as u64 * DUST_AMOUNT,
         );
         let assert_commit2_expense = Amount::from_sat(
-            MIN_RELAY_FEE_ASSERT_COMMIT2
+            relay_fee_at_rate(VSIZE_ASSERT_COMMIT2, fee_rate)
                 + assert_commit2_connectors_e.connectors_num() as u64 * DUST_AMOUNT,
         );
         // goes to assert_final
@@ -126,7 +133,7 @@ impl AssertInitialTransaction {
         // simple outputs for assert_x txs
         for i in 0..assert_commit1_connectors_e.connectors_num() {
             let amount = if i == 0 {
-                MIN_RELAY_FEE_ASSERT_COMMIT1 + DUST_AMOUNT
+                relay_fee_at_rate(VSIZE_ASSERT_COMMIT1, fee_rate) + DUST_AMOUNT
             } else {
                 DUST_AMOUNT
             };
@@ -142,7 +149,7 @@ impl AssertInitialTransaction {
         // simple outputs for assert_x txs
         for i in 0..assert_commit2_connectors_e.connectors_num() {
             let amount = if i == 0 {
-                MIN_RELAY_FEE_ASSERT_COMMIT2 + DUST_AMOUNT
+                relay_fee_at_rate(VSIZE_ASSERT_COMMIT2, fee_rate) + DUST_AMOUNT
             } else {
                 DUST_AMOUNT
             };
@@ -178,7 +185,7 @@ impl AssertInitialTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -186,12 +193,14 @@ impl AssertInitialTransaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_b);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
@@ -210,9 +219,9 @@ impl AssertInitialTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &secret_nonces[&input_index])
     }
 
     pub fn merge(&mut self, assert: &AssertInitialTransaction) {
@@ -1,19 +1,32 @@
+use bitcoin::hex::DisplayHex;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
 
 use crate::{
     commitments::CommitmentMessageId,
     connectors::{connector_e::ConnectorE, connector_f_1::ConnectorF1, connector_f_2::ConnectorF2},
+    error::{Error, GraphError},
+    serialization::hex_encoded_vec,
 };
 
 use bitvm::{
     chunk::api::{
-        generate_signatures_for_any_proof,
-        type_conversion_utils::{utils_raw_witnesses_from_signatures, RawProof, RawWitness},
+        api_get_assertions_from_signature, generate_signatures_for_any_proof,
+        type_conversion_utils::{
+            utils_raw_witnesses_from_signatures, utils_signatures_from_raw_witnesses, RawProof,
+            RawWitness,
+        },
     },
     signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
 };
 
+// Intermediate values committed by assert commit 1, assert commit 2 commits the rest
+pub const ASSERT_COMMIT_1_INTERMEDIATE_VALUES: usize = 300;
+
 /// The number of connector e is related to the number of intermediate values.
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct AssertCommit1ConnectorsE {
@@ -21,6 +34,12 @@ pub struct AssertCommit1ConnectorsE {
 }
 
 impl AssertCommit1ConnectorsE {
+    // Sorts the connectors into the canonical input order of the assert commit tx
+    pub fn new(mut connectors_e: Vec<ConnectorE>) -> Self {
+        sort_connectors_e(&mut connectors_e);
+        Self { connectors_e }
+    }
+
     pub fn connectors_num(&self) -> usize {
         self.connectors_e.len()
     }
@@ -37,6 +56,14 @@ impl AssertCommit1ConnectorsE {
             .map(|connector| connector.commitment_public_keys.clone())
             .collect()
     }
+
+    pub fn input_order(&self) -> Vec<CommitmentMessageId> {
+        input_order(&self.connectors_e)
+    }
+
+    pub fn is_canonically_ordered(&self) -> bool {
+        is_canonically_ordered(&self.connectors_e)
+    }
 }
 
 /// The number of connector e is related to the number of intermediate values.
@@ -46,6 +73,12 @@ pub struct AssertCommit2ConnectorsE {
 }
 
 impl AssertCommit2ConnectorsE {
+    // Sorts the connectors into the canonical input order of the assert commit tx
+    pub fn new(mut connectors_e: Vec<ConnectorE>) -> Self {
+        sort_connectors_e(&mut connectors_e);
+        Self { connectors_e }
+    }
+
     pub fn connectors_num(&self) -> usize {
         self.connectors_e.len()
     }
@@ -62,6 +95,85 @@ impl AssertCommit2ConnectorsE {
             .map(|connector| connector.commitment_public_keys.clone())
             .collect()
     }
+
+    pub fn input_order(&self) -> Vec<CommitmentMessageId> {
+        input_order(&self.connectors_e)
+    }
+
+    pub fn is_canonically_ordered(&self) -> bool {
+        is_canonically_ordered(&self.connectors_e)
+    }
+}
+
+// Assert commit txs spend one connector e per input and the parties build them independently.
+// Connectors, inputs and witnesses are zipped by position, so every party must order them the same
+// way: by the commitment message id a connector commits to, with intermediate values in the
+// numeric order of their names. This is also the order the Groth16 witnesses are generated in.
+pub fn canonical_commitment_order(a: &CommitmentMessageId, b: &CommitmentMessageId) -> Ordering {
+    match (a, b) {
+        (
+            CommitmentMessageId::Groth16IntermediateValues((name_a, size_a)),
+            CommitmentMessageId::Groth16IntermediateValues((name_b, size_b)),
+        ) => {
+            let index = |name: &str| u32::from_str_radix(name, 10).ok();
+            (index(name_a), name_a, size_a).cmp(&(index(name_b), name_b, size_b))
+        }
+        _ => a.cmp(b),
+    }
+}
+
+// The commitment message id that orders a connector e, the first one it commits to
+fn order_id(connector_e: &ConnectorE) -> Option<&CommitmentMessageId> {
+    connector_e
+        .commitment_public_keys
+        .keys()
+        .min_by(|a, b| canonical_commitment_order(a, b))
+}
+
+fn sort_connectors_e(connectors_e: &mut [ConnectorE]) {
+    connectors_e.sort_by(|a, b| match (order_id(a), order_id(b)) {
+        (Some(a), Some(b)) => canonical_commitment_order(a, b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    });
+}
+
+fn input_order(connectors_e: &[ConnectorE]) -> Vec<CommitmentMessageId> {
+    connectors_e
+        .iter()
+        .filter_map(|connector| order_id(connector).cloned())
+        .collect()
+}
+
+fn is_canonically_ordered(connectors_e: &[ConnectorE]) -> bool {
+    input_order(connectors_e)
+        .windows(2)
+        .all(|pair| canonical_commitment_order(&pair[0], &pair[1]) != Ordering::Greater)
+}
+
+// The first input whose commitment differs between two orders, if any
+pub fn find_input_order_mismatch(
+    expected: &[CommitmentMessageId],
+    found: &[CommitmentMessageId],
+) -> Option<(
+    usize,
+    Option<CommitmentMessageId>,
+    Option<CommitmentMessageId>,
+)> {
+    (0..expected.len().max(found.len()))
+        .find(|&index| expected.get(index) != found.get(index))
+        .map(|index| {
+            (
+                index,
+                expected.get(index).cloned(),
+                found.get(index).cloned(),
+            )
+        })
+}
+
+pub fn canonical_input_order(order: &[CommitmentMessageId]) -> Vec<CommitmentMessageId> {
+    let mut canonical = order.to_vec();
+    canonical.sort_by(canonical_commitment_order);
+    canonical
 }
 
 pub fn sign_assert_tx_with_groth16_proof(
@@ -89,37 +201,168 @@ pub fn sign_assert_tx_with_groth16_proof(
 
     let raw = utils_raw_witnesses_from_signatures(&sigs);
 
-    let raw1 = raw[0..300].to_vec();
-    let raw2 = raw[300..].to_vec();
+    let raw1 = raw[0..ASSERT_COMMIT_1_INTERMEDIATE_VALUES].to_vec();
+    let raw2 = raw[ASSERT_COMMIT_1_INTERMEDIATE_VALUES..].to_vec();
 
     (raw1, raw2)
 }
 
+// Like `sign_assert_tx_with_groth16_proof`, for an assert sequence that may have been started
+// before, e.g. restarted after a reorg. The values committed by the first attempt are recorded in
+// `committed_values`, later attempts must commit identical ones: committing two values with the
+// same Winternitz key is an equivocation, which is disprovable on its own.
+pub fn sign_assert_tx_with_committed_values(
+    commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    proof: &RawProof,
+    committed_values: &mut Option<CommittedIntermediateValues>,
+) -> Result<(Vec<RawWitness>, Vec<RawWitness>), Error> {
+    check_committed_values(
+        sign_assert_tx_with_groth16_proof(commitment_secrets, proof),
+        committed_values,
+    )
+}
+
+// The check of `sign_assert_tx_with_committed_values`, for witnesses signed ahead of time, e.g. by
+// a background job
+pub fn check_committed_values(
+    (raw1, raw2): (Vec<RawWitness>, Vec<RawWitness>),
+    committed_values: &mut Option<CommittedIntermediateValues>,
+) -> Result<(Vec<RawWitness>, Vec<RawWitness>), Error> {
+    let values = CommittedIntermediateValues::from_witnesses(&raw1, &raw2);
+
+    match committed_values {
+        Some(previous) => {
+            let mismatches = values.diff(previous);
+            if !mismatches.is_empty() {
+                return Err(Error::Graph(GraphError::CommittedValuesMismatch(
+                    mismatches,
+                )));
+            }
+        }
+        None => *committed_values = Some(values),
+    }
+
+    Ok((raw1, raw2))
+}
+
+// Groth16 intermediate values committed by assert commit 1 and 2, in commitment order
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct CommittedIntermediateValues {
+    #[serde(with = "hex_encoded_vec")]
+    values: Vec<Vec<u8>>,
+}
+
+impl CommittedIntermediateValues {
+    // Reads the committed messages back from the Winternitz signatures of both assert commits
+    pub fn from_witnesses(commit_1: &[RawWitness], commit_2: &[RawWitness]) -> Self {
+        let signatures = utils_signatures_from_raw_witnesses(&[commit_1, commit_2].concat());
+        let (public_inputs, field_elements, hashes) = api_get_assertions_from_signature(signatures);
+
+        Self {
+            values: public_inputs
+                .iter()
+                .chain(field_elements.iter())
+                .map(|value| value.to_vec())
+                .chain(hashes.iter().map(|value| value.to_vec()))
+                .collect(),
+        }
+    }
+
+    pub fn values(&self) -> &[Vec<u8>] {
+        &self.values
+    }
+
+    // Every value that differs from `previous`, including values only one of them commits
+    pub fn diff(&self, previous: &CommittedIntermediateValues) -> Vec<CommittedValueMismatch> {
+        (0..self.values.len().max(previous.values.len()))
+            .filter(|&index| self.values.get(index) != previous.values.get(index))
+            .map(|index| CommittedValueMismatch {
+                index,
+                previous: previous.values.get(index).cloned(),
+                current: self.values.get(index).cloned(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct CommittedValueMismatch {
+    pub index: usize, // position in commitment order
+    pub previous: Option<Vec<u8>>,
+    pub current: Option<Vec<u8>>,
+}
+
+impl CommittedValueMismatch {
+    pub fn assert_commit(&self) -> u8 {
+        if self.index < ASSERT_COMMIT_1_INTERMEDIATE_VALUES {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+impl fmt::Display for CommittedValueMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |value: &Option<Vec<u8>>| match value {
+            Some(value) => value.to_lower_hex_string(),
+            None => String::from("nothing"),
+        };
+        write!(
+            f,
+            "intermediate value {} (assert commit {}): committed {}, now {}",
+            self.index,
+            self.assert_commit(),
+            hex(&self.previous),
+            hex(&self.current)
+        )
+    }
+}
+
 pub fn groth16_commitment_secrets_to_public_keys(
     commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
 ) -> (
     Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
     Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
 ) {
-    // hash map to btree map
-    let commitment_secrets: BTreeMap<CommitmentMessageId, WinternitzSecret> =
-        commitment_secrets.clone().into_iter().collect();
+    let commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey> =
+        commitment_secrets
+            .iter()
+            .filter(|(message_id, _)| {
+                matches!(
+                    message_id,
+                    CommitmentMessageId::Groth16IntermediateValues(_)
+                )
+            })
+            .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+            .collect();
+
+    split_groth16_commitment_public_keys(&commitment_public_keys)
+}
 
+// Splits the Groth16 intermediate value public keys between the connectors E of assert commit 1
+// and assert commit 2, ordered by intermediate value index
+pub fn split_groth16_commitment_public_keys(
+    commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+) -> (
+    Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
+    Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
+) {
     // see the unit test: assigner.rs/test_commitment_size
-    let connectors_e_of_transaction = 300;
+    let connectors_e_of_transaction = ASSERT_COMMIT_1_INTERMEDIATE_VALUES;
     let mut connector_e1_commitment_public_keys = vec![];
     let mut connector_e2_commitment_public_keys = vec![];
 
-    let mut secrets_vec = vec![];
-    for (message_id, secret) in commitment_secrets.iter() {
+    let mut public_keys_vec = vec![];
+    for (message_id, public_key) in commitment_public_keys.iter() {
         if let CommitmentMessageId::Groth16IntermediateValues((name, _)) = message_id {
             let index = u32::from_str_radix(name, 10).unwrap();
-            secrets_vec.push((index, (message_id, secret)));
+            public_keys_vec.push((index, (message_id, public_key)));
         }
     }
 
-    secrets_vec.sort_by(|a, b| a.0.cmp(&b.0));
-    for (_, (message_id, secret)) in secrets_vec {
+    public_keys_vec.sort_by(|a, b| a.0.cmp(&b.0));
+    for (_, (message_id, public_key)) in public_keys_vec {
         let pushing_keys =
             if connector_e1_commitment_public_keys.len() < connectors_e_of_transaction {
                 &mut connector_e1_commitment_public_keys
@@ -127,10 +370,7 @@ pub fn groth16_commitment_secrets_to_public_keys(
                 &mut connector_e2_commitment_public_keys
             };
 
-        pushing_keys.push(BTreeMap::from([(
-            message_id.clone(),
-            WinternitzPublicKey::from(secret),
-        )]));
+        pushing_keys.push(BTreeMap::from([(message_id.clone(), public_key.clone())]));
     }
 
     assert!(connector_e1_commitment_public_keys.len() <= connectors_e_of_transaction);
@@ -0,0 +1,219 @@
+use bitcoin::{
+    key::Keypair,
+    secp256k1::Message,
+    sighash::{Prevouts, SighashCache},
+    taproot::{ControlBlock, LeafVersion},
+    EcdsaSighashType, ScriptBuf, TapLeafHash, TapSighashType, Transaction, TxIn, TxOut,
+};
+use secp256k1::SECP256K1;
+
+use crate::{
+    connectors::base::{P2wshConnector, TaprootConnector},
+    error::{Error, GraphError},
+};
+
+use super::{
+    base::Input,
+    signing::{
+        push_taproot_leaf_script_and_cached_control_block_to_witness,
+        push_taproot_leaf_unlock_data_to_witness,
+    },
+};
+
+// Auxiliary transactions spend connector outputs outside of the graph, e.g. donating connector
+// dust to miners or consolidating what is left once a peg-out is done. They are built by the
+// caller, `AuxiliaryInput` supplies what spending a connector output takes. Graphs only hand one
+// out for outputs none of their own transactions can spend anymore, see
+// `PegOutGraph::auxiliary_input`.
+
+pub enum AuxiliaryConnector<'a> {
+    Taproot(&'a dyn TaprootConnector),
+    P2wsh(&'a dyn P2wshConnector),
+}
+
+impl AuxiliaryConnector<'_> {
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        match self {
+            AuxiliaryConnector::Taproot(connector) => {
+                connector.generate_taproot_address().script_pubkey()
+            }
+            AuxiliaryConnector::P2wsh(connector) => connector.generate_address().script_pubkey(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuxiliaryInput {
+    pub connector: String, // e.g. connector_a
+    pub input: Input,
+    pub prevout: TxOut,
+    pub tx_in: TxIn,       // with the sequence the timelock of the script requires
+    pub script: ScriptBuf, // leaf script or P2WSH witness script
+    pub control_block: Option<ControlBlock>, // taproot leaves only
+}
+
+impl AuxiliaryInput {
+    // Spends `input` through the given leaf of a taproot connector. P2WSH connectors have a
+    // single script, leaf 0.
+    pub fn new(
+        name: &str,
+        connector: AuxiliaryConnector,
+        input: Input,
+        prevout: TxOut,
+        leaf_index: u32,
+    ) -> Result<Self, Error> {
+        let invalid_leaf_index = || {
+            Error::Graph(GraphError::InvalidLeafIndex {
+                connector: name.to_string(),
+                leaf_index,
+            })
+        };
+        let (tx_in, script, control_block) = match connector {
+            AuxiliaryConnector::Taproot(connector) => {
+                let spend_info = connector.generate_taproot_spend_info();
+                // Leaves with the same script share an entry, one branch per leaf
+                let num_leaves: usize = spend_info.script_map().values().map(|x| x.len()).sum();
+                if leaf_index as usize >= num_leaves {
+                    return Err(invalid_leaf_index());
+                }
+                let script = connector.generate_taproot_leaf_script(leaf_index);
+                let control_block = spend_info
+                    .control_block(&(script.clone(), LeafVersion::TapScript))
+                    .ok_or_else(invalid_leaf_index)?;
+                (
+                    connector.generate_taproot_leaf_tx_in(leaf_index, &input),
+                    script,
+                    Some(control_block),
+                )
+            }
+            AuxiliaryConnector::P2wsh(connector) => {
+                if leaf_index != 0 {
+                    return Err(invalid_leaf_index());
+                }
+                (
+                    connector.generate_tx_in(&input),
+                    connector.generate_script(),
+                    None,
+                )
+            }
+        };
+
+        Ok(Self {
+            connector: name.to_string(),
+            input,
+            prevout,
+            tx_in,
+            script,
+            control_block,
+        })
+    }
+
+    // Message a signature checked by the script commits to, for signing on external devices.
+    // SIGHASH_DEFAULT for taproot leaves and SIGHASH_ALL for P2WSH scripts. `prevouts` holds the
+    // outputs spent by all inputs of `tx`.
+    pub fn signature_hash(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        prevouts: &[TxOut],
+    ) -> Result<Message, Error> {
+        self.check_input(tx, input_index)?;
+        if prevouts.len() != tx.input.len() || prevouts[input_index] != self.prevout {
+            return Err(Error::Graph(GraphError::AuxiliaryInputMismatch(
+                input_index,
+            )));
+        }
+
+        let mut sighash_cache = SighashCache::new(tx);
+        match self.control_block {
+            Some(_) => sighash_cache
+                .taproot_script_spend_signature_hash(
+                    input_index,
+                    &Prevouts::All(prevouts),
+                    TapLeafHash::from_script(&self.script, LeafVersion::TapScript),
+                    TapSighashType::Default,
+                )
+                .map(Message::from)
+                .map_err(|e| Error::Other(e.to_string())),
+            None => sighash_cache
+                .p2wsh_signature_hash(
+                    input_index,
+                    &self.script,
+                    self.input.amount,
+                    EcdsaSighashType::All,
+                )
+                .map(Message::from)
+                .map_err(|e| Error::Other(e.to_string())),
+        }
+    }
+
+    // Serialized signature of `keypair` over `signature_hash`, to be passed to `finalize`
+    pub fn sign(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        prevouts: &[TxOut],
+        keypair: &Keypair,
+    ) -> Result<Vec<u8>, Error> {
+        let message = self.signature_hash(tx, input_index, prevouts)?;
+        Ok(match self.control_block {
+            Some(_) => bitcoin::taproot::Signature {
+                signature: SECP256K1.sign_schnorr_no_aux_rand(&message, keypair),
+                sighash_type: TapSighashType::Default,
+            }
+            .to_vec(),
+            None => bitcoin::ecdsa::Signature {
+                signature: keypair.secret_key().sign_ecdsa(message),
+                sighash_type: EcdsaSighashType::All,
+            }
+            .to_vec(),
+        })
+    }
+
+    // Completes the witness of the input: `unlock_data` in the order the script consumes it,
+    // followed by the script and, for taproot leaves, the control block
+    pub fn finalize(
+        &self,
+        tx: &mut Transaction,
+        input_index: usize,
+        unlock_data: Vec<Vec<u8>>,
+    ) -> Result<(), Error> {
+        self.check_input(tx, input_index)?;
+
+        tx.input[input_index].witness.clear();
+        match &self.control_block {
+            Some(control_block) => {
+                push_taproot_leaf_unlock_data_to_witness(tx, input_index, unlock_data);
+                push_taproot_leaf_script_and_cached_control_block_to_witness(
+                    tx,
+                    input_index,
+                    control_block,
+                    &self.script,
+                );
+            }
+            None => {
+                for element in unlock_data {
+                    tx.input[input_index].witness.push(element);
+                }
+                tx.input[input_index].witness.push(self.script.as_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    // The input must spend this output with the sequence its script requires
+    fn check_input(&self, tx: &Transaction, input_index: usize) -> Result<(), Error> {
+        match tx.input.get(input_index) {
+            Some(tx_in)
+                if tx_in.previous_output == self.tx_in.previous_output
+                    && tx_in.sequence == self.tx_in.sequence =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::Graph(GraphError::AuxiliaryInputMismatch(
+                input_index,
+            ))),
+        }
+    }
+}
@@ -5,34 +5,75 @@ use crate::{
 };
 use bitcoin::{Amount, OutPoint, PublicKey, Script, Transaction, Txid, XOnlyPublicKey};
 use core::cmp;
+#[cfg(feature = "client")]
 use esplora_client::TxStatus;
 use itertools::Itertools;
 use musig2::{secp256k1::schnorr::Signature, PubNonce};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+// Estimated virtual sizes of the finalized transactions, their relay fees are budgeted for them
+pub const VSIZE_KICK_OFF_1: usize = 6231;
+// Operator collateral input and output added to kick-off 1
+pub const VSIZE_KICK_OFF_1_COLLATERAL: usize = 112;
+// Operator collateral input added to take 1, take 2 and disprove
+pub const VSIZE_COLLATERAL_INPUT: usize = 83;
+pub const VSIZE_START_TIME: usize = 407;
+pub const VSIZE_START_TIME_TIMEOUT: usize = 265;
+pub const VSIZE_KICK_OFF_2: usize = 5461;
+pub const VSIZE_KICK_OFF_TIMEOUT: usize = 182;
+pub const VSIZE_TAKE_1: usize = 380;
+pub const VSIZE_TAKE_2: usize = 347;
+pub const VSIZE_TAKE_2_CONSOLIDATED: usize = 277;
+pub const VSIZE_PEG_IN_DEPOSIT: usize = 122;
+// Each depositor input beyond the first and the change output of a peg-in deposit, see
+// `peg_in_deposit_vsize`
+pub const VSIZE_PEG_IN_DEPOSIT_INPUT: usize = 69;
+pub const VSIZE_PEG_IN_DEPOSIT_CHANGE: usize = 43;
+pub const VSIZE_PEG_IN_CONFIRM: usize = 173;
+pub const VSIZE_PEG_IN_REFUND: usize = 138;
+pub const VSIZE_PEG_OUT: usize = 122;
+pub const VSIZE_PEG_OUT_CONFIRM: usize = 122;
+pub const VSIZE_ASSERT: usize = 232;
+pub const VSIZE_ASSERT_INITIAL: usize = 48953;
+pub const VSIZE_ASSERT_COMMIT1: usize = 739137;
+pub const VSIZE_ASSERT_COMMIT2: usize = 470440;
+pub const VSIZE_ASSERT_FINAL: usize = 352;
+pub const VSIZE_ASSERT_FINAL_CONSOLIDATED: usize = 309;
+pub const VSIZE_CHALLENGE: usize = 317;
+pub const VSIZE_DISPROVE: usize = 238785;
+pub const VSIZE_DISPROVE_CHAIN: usize = 389370;
+
 // TODO: set to larger value to be compatible with future tx modifications
 pub const RELAY_FEE_BUFFER_MULTIPLIER: f32 = 1.0;
-pub const MIN_RELAY_FEE_KICK_OFF_1: u64 = relay_fee(6231);
-pub const MIN_RELAY_FEE_START_TIME: u64 = relay_fee(407);
-pub const MIN_RELAY_FEE_START_TIME_TIMEOUT: u64 = relay_fee(265);
-pub const MIN_RELAY_FEE_KICK_OFF_2: u64 = relay_fee(5461);
-pub const MIN_RELAY_FEE_KICK_OFF_TIMEOUT: u64 = relay_fee(182);
-pub const MIN_RELAY_FEE_TAKE_1: u64 = relay_fee(380);
-pub const MIN_RELAY_FEE_TAKE_2: u64 = relay_fee(347);
-pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT: u64 = relay_fee(122);
-pub const MIN_RELAY_FEE_PEG_IN_CONFIRM: u64 = relay_fee(173);
-pub const MIN_RELAY_FEE_PEG_IN_REFUND: u64 = relay_fee(138);
-pub const MIN_RELAY_FEE_PEG_OUT: u64 = relay_fee(122);
-pub const MIN_RELAY_FEE_PEG_OUT_CONFIRM: u64 = relay_fee(122);
-pub const MIN_RELAY_FEE_ASSERT: u64 = relay_fee(232);
-pub const MIN_RELAY_FEE_ASSERT_INITIAL: u64 = relay_fee(48953);
-pub const MIN_RELAY_FEE_ASSERT_COMMIT1: u64 = relay_fee(739137);
-pub const MIN_RELAY_FEE_ASSERT_COMMIT2: u64 = relay_fee(470440);
-pub const MIN_RELAY_FEE_ASSERT_FINAL: u64 = relay_fee(352);
-pub const MIN_RELAY_FEE_CHALLENGE: u64 = relay_fee(317);
-pub const MIN_RELAY_FEE_DISPROVE: u64 = relay_fee(238785);
-pub const MIN_RELAY_FEE_DISPROVE_CHAIN: u64 = relay_fee(389370);
-
+pub const MIN_RELAY_FEE_KICK_OFF_1: u64 = relay_fee(VSIZE_KICK_OFF_1);
+pub const MIN_RELAY_FEE_KICK_OFF_1_COLLATERAL: u64 = relay_fee(VSIZE_KICK_OFF_1_COLLATERAL);
+pub const MIN_RELAY_FEE_COLLATERAL_INPUT: u64 = relay_fee(VSIZE_COLLATERAL_INPUT);
+pub const MIN_RELAY_FEE_START_TIME: u64 = relay_fee(VSIZE_START_TIME);
+pub const MIN_RELAY_FEE_START_TIME_TIMEOUT: u64 = relay_fee(VSIZE_START_TIME_TIMEOUT);
+pub const MIN_RELAY_FEE_KICK_OFF_2: u64 = relay_fee(VSIZE_KICK_OFF_2);
+pub const MIN_RELAY_FEE_KICK_OFF_TIMEOUT: u64 = relay_fee(VSIZE_KICK_OFF_TIMEOUT);
+pub const MIN_RELAY_FEE_TAKE_1: u64 = relay_fee(VSIZE_TAKE_1);
+pub const MIN_RELAY_FEE_TAKE_2: u64 = relay_fee(VSIZE_TAKE_2);
+pub const MIN_RELAY_FEE_TAKE_2_CONSOLIDATED: u64 = relay_fee(VSIZE_TAKE_2_CONSOLIDATED);
+pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT: u64 = relay_fee(VSIZE_PEG_IN_DEPOSIT);
+pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT_INPUT: u64 = relay_fee(VSIZE_PEG_IN_DEPOSIT_INPUT);
+pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT_CHANGE: u64 = relay_fee(VSIZE_PEG_IN_DEPOSIT_CHANGE);
+pub const MIN_RELAY_FEE_PEG_IN_CONFIRM: u64 = relay_fee(VSIZE_PEG_IN_CONFIRM);
+pub const MIN_RELAY_FEE_PEG_IN_REFUND: u64 = relay_fee(VSIZE_PEG_IN_REFUND);
+pub const MIN_RELAY_FEE_PEG_OUT: u64 = relay_fee(VSIZE_PEG_OUT);
+pub const MIN_RELAY_FEE_PEG_OUT_CONFIRM: u64 = relay_fee(VSIZE_PEG_OUT_CONFIRM);
+pub const MIN_RELAY_FEE_ASSERT: u64 = relay_fee(VSIZE_ASSERT);
+pub const MIN_RELAY_FEE_ASSERT_INITIAL: u64 = relay_fee(VSIZE_ASSERT_INITIAL);
+pub const MIN_RELAY_FEE_ASSERT_COMMIT1: u64 = relay_fee(VSIZE_ASSERT_COMMIT1);
+pub const MIN_RELAY_FEE_ASSERT_COMMIT2: u64 = relay_fee(VSIZE_ASSERT_COMMIT2);
+pub const MIN_RELAY_FEE_ASSERT_FINAL: u64 = relay_fee(VSIZE_ASSERT_FINAL);
+pub const MIN_RELAY_FEE_ASSERT_FINAL_CONSOLIDATED: u64 = relay_fee(VSIZE_ASSERT_FINAL_CONSOLIDATED);
+pub const MIN_RELAY_FEE_CHALLENGE: u64 = relay_fee(VSIZE_CHALLENGE);
+pub const MIN_RELAY_FEE_DISPROVE: u64 = relay_fee(VSIZE_DISPROVE);
+pub const MIN_RELAY_FEE_DISPROVE_CHAIN: u64 = relay_fee(VSIZE_DISPROVE_CHAIN);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Input {
     pub outpoint: OutPoint,
     pub amount: Amount,
@@ -57,7 +98,23 @@ pub trait BaseTransaction {
 }
 
 pub const fn relay_fee(vsize: usize) -> u64 {
-    (vsize as f32 * RELAY_FEE_BUFFER_MULTIPLIER) as u64 * MIN_RELAY_FEE_RATE
+    relay_fee_at_rate(vsize, MIN_RELAY_FEE_RATE)
+}
+
+// Fee of a transaction of the given estimated virtual size at `fee_rate` sat/vB, see
+// `GraphParameters::fee_rate`
+pub const fn relay_fee_at_rate(vsize: usize, fee_rate: u64) -> u64 {
+    (vsize as f32 * RELAY_FEE_BUFFER_MULTIPLIER) as u64 * fee_rate
+}
+
+// Estimated virtual size of a peg-in deposit spending `input_count` depositor outputs
+pub const fn peg_in_deposit_vsize(input_count: usize, has_change: bool) -> usize {
+    let change = if has_change {
+        VSIZE_PEG_IN_DEPOSIT_CHANGE
+    } else {
+        0
+    };
+    VSIZE_PEG_IN_DEPOSIT + input_count.saturating_sub(1) * VSIZE_PEG_IN_DEPOSIT_INPUT + change
 }
 
 pub fn merge_transactions(
@@ -132,6 +189,22 @@ pub fn validate_transaction(
     comparison_transaction: &Transaction,
     tx_name: &'static str,
 ) -> Result<(), Error> {
+    // A truncated transaction, e.g. from a corrupted data file, fails at the first missing index
+    if transaction.input.len() < comparison_transaction.input.len() {
+        return Err(Error::Validation(ValidationError::TxValidationFailed(
+            tx_name,
+            transaction.compute_txid(),
+            transaction.input.len(),
+        )));
+    }
+    if transaction.output.len() < comparison_transaction.output.len() {
+        return Err(Error::Validation(ValidationError::TxValidationFailed(
+            tx_name,
+            transaction.compute_txid(),
+            transaction.output.len(),
+        )));
+    }
+
     for i in 0..comparison_transaction.input.len() {
         if transaction.input[i].previous_output != comparison_transaction.input[i].previous_output
             || transaction.input[i].script_sig != comparison_transaction.input[i].script_sig
@@ -160,6 +233,7 @@ pub fn validate_transaction(
     Ok(())
 }
 
+#[cfg(feature = "client")]
 pub fn validate_witness(
     tx: &Transaction,
     tx_name: &'static str,
@@ -202,7 +276,11 @@ fn verify_public_nonces(
 ) -> Result<(), Error> {
     for (i, nonces) in all_nonces {
         for (pubkey, nonce) in nonces {
-            if !verify_public_nonce(&all_sigs[i][pubkey], nonce, &XOnlyPublicKey::from(*pubkey)) {
+            let is_valid = all_sigs
+                .get(i)
+                .and_then(|sigs| sigs.get(pubkey))
+                .is_some_and(|sig| verify_public_nonce(sig, nonce, &XOnlyPublicKey::from(*pubkey)));
+            if !is_valid {
                 eprintln!(
                     "Failed to verify public nonce for pubkey {pubkey} on tx:input {txid}:{i}."
                 );
@@ -227,18 +305,52 @@ pub fn verify_public_nonces_for_tx(
     )
 }
 
+// Nonces and signatures must come from the committee the graph was created with. Keys of other
+// committees, e.g. a verifier key retired by a rotation, must not be mixed in.
+pub fn verify_committee_contributions(
+    tx: &(impl BaseTransaction + PreSignedMusig2Transaction),
+    committee: &[PublicKey],
+) -> Result<(), Error> {
+    let contributors = tx
+        .musig2_nonces()
+        .values()
+        .flat_map(|nonces| nonces.keys())
+        .chain(
+            tx.musig2_signatures()
+                .values()
+                .flat_map(|signatures| signatures.keys()),
+        );
+    for public_key in contributors {
+        if !committee.contains(public_key) {
+            return Err(Error::Validation(ValidationError::NotCommitteeMember(
+                tx.name(),
+                *public_key,
+                tx.tx().compute_txid(),
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, str::FromStr};
+    use std::collections::HashMap;
+    #[cfg(feature = "client")]
+    use std::str::FromStr;
 
+    #[cfg(feature = "client")]
+    use bitcoin::{
+        absolute, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+    };
     use bitcoin::{
-        absolute,
         key::{
             constants::{SCHNORR_SIGNATURE_SIZE, SECRET_KEY_SIZE},
             Keypair,
         },
-        Amount, OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+        PublicKey, Txid,
     };
+    #[cfg(feature = "client")]
     use esplora_client::TxStatus;
     use musig2::{secp256k1::schnorr::Signature, PubNonce};
 
@@ -248,7 +360,9 @@ mod tests {
         transactions::{pre_signed_musig2::get_nonce_message, signing_musig2::generate_nonce},
     };
 
-    use super::{validate_witness, verify_public_nonces};
+    #[cfg(feature = "client")]
+    use super::validate_witness;
+    use super::verify_public_nonces;
 
     const DUMMY_TXID: &str = "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456";
 
@@ -292,6 +406,7 @@ mod tests {
         (all_nonces, all_sigs)
     }
 
+    #[cfg(feature = "client")]
     fn get_test_tx() -> Transaction {
         Transaction {
             version: bitcoin::transaction::Version(2),
@@ -376,6 +491,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "client")]
     fn test_verify_witness_mismatch() {
         let mut tx = get_test_tx();
         tx.input[0].witness = vec![vec![0u8; 32]].into();
@@ -407,6 +523,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "client")]
     fn test_verify_witness_match() {
         let mut tx = get_test_tx();
         tx.input[0].witness = vec![vec![0u8; 32]].into();
@@ -424,6 +541,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "client")]
     fn test_verify_empty_witness_and_not_empty_onchain_witness() {
         let tx = get_test_tx();
         let mut onchain_tx = tx.clone();
@@ -1,11 +1,13 @@
 use bitcoin::{
-    absolute, consensus, key::Keypair, Amount, Network, PublicKey, ScriptBuf, Sequence,
+    absolute, key::Keypair, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence,
     TapSighashType, Transaction, TxIn, TxOut, Witness,
 };
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
-use crate::{connectors::base::TaprootConnector, graphs::base::DUST_AMOUNT};
+use crate::{
+    connectors::base::TaprootConnector, error::TransactionError, graphs::base::DUST_AMOUNT,
+};
 
 use super::{
     super::{connectors::connector_a::ConnectorA, contexts::operator::OperatorContext, scripts::*},
@@ -16,10 +18,11 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ChallengeTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
     input_amount_crowdfunding: Amount,
 }
@@ -57,7 +60,7 @@ impl ChallengeTransaction {
             input_amount_crowdfunding,
         );
 
-        this.sign_input_0(context, connector_a);
+        this.sign_operator_inputs(context, connector_a);
 
         this
     }
@@ -103,6 +106,11 @@ impl ChallengeTransaction {
         }
     }
 
+    // Signatures of the operator made when the graph is created
+    pub fn sign_operator_inputs(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
+        self.sign_input_0(context, connector_a);
+    }
+
     fn sign_input_0(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
         pre_sign_taproot_input_default(
             self,
@@ -147,13 +155,14 @@ impl ChallengeTransaction {
         }
 
         // add crowdfunding inputs
-        let sighash_type = bitcoin::EcdsaSighashType::AllPlusAnyoneCanPay;
+        let sighash_type = EcdsaSighashType::AllPlusAnyoneCanPay;
         let mut input_index = self.tx.input.len();
         for input in inputs {
+            // signals replaceability, see `bump_fee`
             let _input = TxIn {
                 previous_output: input.outpoint,
                 script_sig: ScriptBuf::new(),
-                sequence: Sequence::MAX,
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
                 witness: Witness::default(),
             };
             self.tx.input.push(_input);
@@ -172,6 +181,74 @@ impl ChallengeTransaction {
         }
     }
 
+    // Replaces the broadcast challenge `replaced_tx` with one paying `fee_rate`. The higher fee is
+    // taken from the refund output, so the operator's SINGLE|ANYONECANPAY signature over output 0
+    // stays valid and only the crowdfunding inputs are signed again. Those all have to be spent
+    // from `keypair`, the signatures of other crowdfunders commit to the refund output.
+    pub fn bump_fee(
+        &mut self,
+        replaced_tx: &Transaction,
+        prev_outs: &[TxOut],
+        keypair: &Keypair,
+        fee_rate: u64,
+    ) -> Result<(), TransactionError> {
+        let replaced_txid = replaced_tx.compute_txid();
+        if replaced_tx.input.first().map(|input| input.previous_output)
+            != Some(self.tx.input[0].previous_output)
+        {
+            return Err(TransactionError::ReplacementNotPermitted(
+                replaced_txid,
+                None,
+            ));
+        }
+
+        let input_amount: Amount = prev_outs.iter().map(|prev_out| prev_out.value).sum();
+        let output_amount: Amount = replaced_tx.output.iter().map(|output| output.value).sum();
+        let replaced_fee = input_amount - output_amount;
+        // BIP125: the replacement pays at least the replaced fee plus the relay fee of its own size
+        let vsize = replaced_tx.vsize();
+        let fee = Amount::from_sat(relay_fee_at_rate(vsize, fee_rate))
+            .max(replaced_fee + Amount::from_sat(relay_fee(vsize)));
+        let fee_increase = fee - replaced_fee;
+
+        let mut tx = replaced_tx.clone();
+        let refund = tx.output.get(1).map_or(Amount::ZERO, |output| output.value);
+        if refund < fee_increase {
+            return Err(TransactionError::ReplacementFeeNotCovered {
+                required: fee_increase,
+                available: refund,
+            });
+        }
+        if (refund - fee_increase).to_sat() >= DUST_AMOUNT {
+            tx.output[1].value = refund - fee_increase;
+        } else {
+            // what is left of the refund goes to the fee as well
+            tx.output.truncate(1);
+        }
+
+        let public_key = PublicKey::from(keypair.public_key());
+        let script = generate_pay_to_pubkey_script(&public_key);
+        let script_pubkey = ScriptBuf::new_p2wsh(&script.wscript_hash());
+        for (input_index, prev_out) in prev_outs.iter().enumerate().skip(1) {
+            if prev_out.script_pubkey != script_pubkey {
+                return Err(TransactionError::ReplacementInputNotOwned(input_index));
+            }
+            tx.input[input_index].sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+            tx.input[input_index].witness = Witness::default();
+            populate_p2wsh_witness(
+                &mut tx,
+                input_index,
+                EcdsaSighashType::AllPlusAnyoneCanPay,
+                &script,
+                prev_out.value,
+                &vec![keypair],
+            );
+        }
+
+        self.tx = tx;
+        Ok(())
+    }
+
     pub fn merge(&mut self, challenge: &ChallengeTransaction) {
         merge_transactions(&mut self.tx, &challenge.tx);
     }
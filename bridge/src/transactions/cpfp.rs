@@ -0,0 +1,101 @@
+use bitcoin::{
+    absolute, key::Keypair, Amount, EcdsaSighashType, PublicKey, ScriptBuf, Sequence, Transaction,
+    TxIn, TxOut, Witness,
+};
+
+use crate::{
+    error::TransactionError,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
+};
+
+use super::{super::scripts::*, base::Input, signing::populate_p2wsh_witness};
+
+// Fee the child has to pay for the package with its unconfirmed parent to reach `fee_rate`. The
+// child pays at least its own relay fee, even if the parent alone already reaches the fee rate.
+pub fn cpfp_fee(
+    parent_fee: Amount,
+    parent_vsize: usize,
+    child_vsize: usize,
+    fee_rate: u64,
+) -> Amount {
+    let package_fee = Amount::from_sat((parent_vsize + child_vsize) as u64 * fee_rate);
+    let child_relay_fee = Amount::from_sat(child_vsize as u64 * MIN_RELAY_FEE_RATE);
+
+    package_fee
+        .checked_sub(parent_fee)
+        .unwrap_or(Amount::ZERO)
+        .max(child_relay_fee)
+}
+
+// Child transaction spending `inputs`, the first of them an output of the unconfirmed parent. All
+// inputs are locked to the pay-to-pubkey script of `keypair`, what is left after the fee goes back
+// to it.
+pub fn create_cpfp_transaction(
+    inputs: &[Input],
+    keypair: &Keypair,
+    parent_fee: Amount,
+    parent_vsize: usize,
+    fee_rate: u64,
+) -> Result<Transaction, TransactionError> {
+    let script = generate_pay_to_pubkey_script(&PublicKey::from(keypair.public_key()));
+    let input_amount: Amount = inputs.iter().map(|input| input.amount).sum();
+
+    // Signed once without a fee to learn the size of the child. Signing again over another output
+    // amount can make a signature a byte longer, a vbyte per input covers that.
+    let unpaid_tx = sign_cpfp_transaction(inputs, keypair, &script, input_amount);
+    let child_vsize = unpaid_tx.vsize() + inputs.len();
+    let fee = cpfp_fee(parent_fee, parent_vsize, child_vsize, fee_rate);
+    let required = fee + Amount::from_sat(DUST_AMOUNT);
+    if input_amount < required {
+        return Err(TransactionError::CpfpFundsInsufficient {
+            required,
+            available: input_amount,
+        });
+    }
+
+    Ok(sign_cpfp_transaction(
+        inputs,
+        keypair,
+        &script,
+        input_amount - fee,
+    ))
+}
+
+fn sign_cpfp_transaction(
+    inputs: &[Input],
+    keypair: &Keypair,
+    script: &ScriptBuf,
+    output_amount: Amount,
+) -> Transaction {
+    let mut tx = Transaction {
+        version: bitcoin::transaction::Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: inputs
+            .iter()
+            .map(|input| TxIn {
+                previous_output: input.outpoint,
+                script_sig: ScriptBuf::new(),
+                // a child paying too little can be replaced by one paying more
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::default(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: output_amount,
+            script_pubkey: ScriptBuf::new_p2wsh(&script.wscript_hash()),
+        }],
+    };
+
+    for (input_index, input) in inputs.iter().enumerate() {
+        populate_p2wsh_witness(
+            &mut tx,
+            input_index,
+            EcdsaSighashType::All,
+            script,
+            input.amount,
+            &vec![keypair],
+        );
+    }
+
+    tx
+}
@@ -1,5 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    absolute, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use bitvm::chunk::api::type_conversion_utils::RawWitness;
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
@@ -8,8 +8,12 @@ use std::collections::HashMap;
 
 use super::{
     super::{
-        connectors::{base::*, connector_5::Connector5, connector_c::ConnectorC},
+        connectors::{
+            base::*, connector_0::Connector0, connector_5::Connector5, connector_c::ConnectorC,
+        },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::{Error, ValidationError},
+        rewards::validate_reward_output,
         scripts::*,
     },
     base::*,
@@ -20,15 +24,19 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct DisproveTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
     reward_output_amount: Amount,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -74,37 +82,71 @@ impl PreSignedMusig2Transaction for DisproveTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> {
-        vec![0]
+        let mut inputs = vec![0];
+        inputs.extend(self.collateral_input_index());
+        inputs
+    }
+    fn verifier_sighash_type(&self, input_index: usize) -> TapSighashType {
+        if Some(input_index) == self.collateral_input_index() {
+            TapSighashType::None
+        } else {
+            TapSighashType::Single
+        }
     }
 }
 
 impl DisproveTransaction {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         context: &OperatorContext,
+        connector_0: &Connector0,
         connector_5: &Connector5,
         connector_c: &ConnectorC,
         input_0: Input,
         input_1: Input,
+        burn_percentage: u64,
+        collateral_input: Option<Input>,
     ) -> Self {
-        Self::new_for_validation(context.network, connector_5, connector_c, input_0, input_1)
+        Self::new_for_validation(
+            context.network,
+            connector_0,
+            connector_5,
+            connector_c,
+            input_0,
+            input_1,
+            burn_percentage,
+            collateral_input,
+        )
     }
 
+    // The operator collateral of kick-off 1 is slashed along with the connector C funds: it is
+    // split between the burn and the challenger reward like them.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_for_validation(
         network: Network,
+        connector_0: &Connector0,
         connector_5: &Connector5,
         connector_c: &ConnectorC,
         input_0: Input,
         input_1: Input,
+        burn_percentage: u64,
+        collateral_input: Option<Input>,
     ) -> Self {
         let input_0_leaf = 1;
         let _input_0 = connector_5.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
         let _input_1 = generate_default_tx_in(&input_1);
 
-        let total_output_amount =
-            input_0.amount + input_1.amount - Amount::from_sat(MIN_RELAY_FEE_DISPROVE);
+        let input_2_leaf = 0;
+        let (collateral_amount, collateral_fee) = match &collateral_input {
+            Some(input_2) => (input_2.amount, MIN_RELAY_FEE_COLLATERAL_INPUT),
+            None => (Amount::ZERO, 0),
+        };
 
-        let output_0_amount = total_output_amount / 2;
+        let total_output_amount = input_0.amount + input_1.amount + collateral_amount
+            - Amount::from_sat(MIN_RELAY_FEE_DISPROVE + collateral_fee);
+
+        let output_0_amount = total_output_amount * burn_percentage / 100;
         let _output_0 = TxOut {
             value: output_0_amount,
             script_pubkey: generate_burn_script_address(network).script_pubkey(),
@@ -116,27 +158,43 @@ impl DisproveTransaction {
             script_pubkey: ScriptBuf::default(),
         };
 
+        let mut inputs = vec![_input_0, _input_1];
+        let mut prev_outs = vec![
+            TxOut {
+                value: input_0.amount,
+                script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
+            },
+            TxOut {
+                value: input_1.amount,
+                script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
+            },
+        ];
+        let mut prev_scripts = vec![
+            connector_5.generate_taproot_leaf_script(input_0_leaf),
+            // `input_1` prev_script is not known at this point
+        ];
+
+        if let Some(input_2) = collateral_input {
+            // Keeps the collateral script at the index of its input
+            prev_scripts.push(ScriptBuf::new());
+
+            inputs.push(connector_0.generate_taproot_leaf_tx_in(input_2_leaf, &input_2));
+            prev_outs.push(TxOut {
+                value: input_2.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_0.generate_taproot_leaf_script(input_2_leaf));
+        }
+
         DisproveTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0, _input_1],
+                input: inputs,
                 output: vec![_output_0, _output_1],
             },
-            prev_outs: vec![
-                TxOut {
-                    value: input_0.amount,
-                    script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_1.amount,
-                    script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
-                },
-            ],
-            prev_scripts: vec![
-                connector_5.generate_taproot_leaf_script(input_0_leaf),
-                // `input_1` prev_script is not known at this point
-            ],
+            prev_outs,
+            prev_scripts,
             reward_output_amount,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
@@ -149,7 +207,7 @@ impl DisproveTransaction {
         context: &VerifierContext,
         connector_5: &Connector5,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -157,12 +215,14 @@ impl DisproveTransaction {
             input_index,
             TapSighashType::Single,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_5);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_5: &Connector5) {
@@ -176,14 +236,61 @@ impl DisproveTransaction {
         );
     }
 
+    fn collateral_input_index(&self) -> Option<usize> {
+        (self.tx.input.len() > 2).then_some(2)
+    }
+
+    // Signed with SIGHASH_NONE, as the reward output is only chosen by the challenger. Input 0
+    // commits to the burn output, which includes the share of the collateral.
+    fn sign_input_2(
+        &mut self,
+        context: &VerifierContext,
+        connector_0: &Connector0,
+        secret_nonce: &SecNonce,
+    ) -> Result<(), Error> {
+        let input_index = 2;
+        pre_sign_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            secret_nonce,
+        )?;
+
+        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
+            self.finalize_input_2(context, connector_0);
+        }
+
+        Ok(())
+    }
+
+    fn finalize_input_2(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+        let input_index = 2;
+        finalize_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            connector_0.generate_taproot_spend_info(),
+        );
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
+        connector_0: &Connector0,
         connector_5: &Connector5,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_5, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_5, &secret_nonces[&input_index])?;
+
+        if let Some(input_index) = self.collateral_input_index() {
+            self.sign_input_2(context, connector_0, &secret_nonces[&input_index])?;
+        }
+
+        Ok(())
     }
 
     pub fn add_input_output(
@@ -192,10 +299,15 @@ impl DisproveTransaction {
         input_script_index: usize,
         input_script_witness: RawWitness,
         output_script_pubkey: ScriptBuf,
-    ) {
-        // Add output
+    ) -> Result<(), ValidationError> {
+        // Add the reward output, which is not signed by the committee
         let output_index = 1;
-        self.tx.output[output_index].script_pubkey = output_script_pubkey;
+        let output = TxOut {
+            value: self.tx.output[output_index].value,
+            script_pubkey: output_script_pubkey,
+        };
+        validate_reward_output(&output)?;
+        self.tx.output[output_index] = output;
 
         let input_index = 1;
 
@@ -213,6 +325,8 @@ impl DisproveTransaction {
             &control_block,
             &script,
         );
+
+        Ok(())
     }
 
     pub fn merge(&mut self, disprove: &DisproveTransaction) {
@@ -1,16 +1,21 @@
 use bitcoin::{
-    absolute, block::Header, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut, Witness,
+    absolute, block::Header, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction,
+    TxOut, Witness,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{connectors::base::TaprootConnector, superblock::get_superblock_message};
+use crate::{
+    connectors::base::TaprootConnector,
+    error::{Error, ValidationError},
+    rewards::validate_reward_output,
+    superblock::get_superblock_message,
+};
 
 use super::{
     super::{
-        connectors::connector_b::ConnectorB,
+        connectors::{connector_0::Connector0, connector_b::ConnectorB},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
         scripts::*,
     },
@@ -22,15 +27,19 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct DisproveChainTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
     reward_output_amount: Amount,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -76,22 +85,61 @@ impl PreSignedMusig2Transaction for DisproveChainTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> {
-        vec![0]
+        let mut inputs = vec![0];
+        inputs.extend(self.collateral_input_index());
+        inputs
+    }
+    fn verifier_sighash_type(&self, input_index: usize) -> TapSighashType {
+        if Some(input_index) == self.collateral_input_index() {
+            TapSighashType::None
+        } else {
+            TapSighashType::Single
+        }
     }
 }
 
 impl DisproveChainTransaction {
-    pub fn new(context: &OperatorContext, connector_b: &ConnectorB, input_0: Input) -> Self {
-        Self::new_for_validation(context.network, connector_b, input_0)
+    pub fn new(
+        context: &OperatorContext,
+        connector_0: &Connector0,
+        connector_b: &ConnectorB,
+        input_0: Input,
+        burn_percentage: u64,
+        collateral_input: Option<Input>,
+    ) -> Self {
+        Self::new_for_validation(
+            context.network,
+            connector_0,
+            connector_b,
+            input_0,
+            burn_percentage,
+            collateral_input,
+        )
     }
 
-    pub fn new_for_validation(network: Network, connector_b: &ConnectorB, input_0: Input) -> Self {
+    // The operator collateral of kick-off 1 is slashed along with the connector B funds: it is
+    // split between the burn and the challenger reward like them.
+    pub fn new_for_validation(
+        network: Network,
+        connector_0: &Connector0,
+        connector_b: &ConnectorB,
+        input_0: Input,
+        burn_percentage: u64,
+        collateral_input: Option<Input>,
+    ) -> Self {
         let input_0_leaf = 2;
         let _input_0 = connector_b.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_DISPROVE_CHAIN);
+        let input_1_leaf = 0;
+        let (collateral_amount, collateral_fee) = match &collateral_input {
+            Some(input_1) => (input_1.amount, MIN_RELAY_FEE_COLLATERAL_INPUT),
+            None => (Amount::ZERO, 0),
+        };
 
-        let burn_amount = total_output_amount / 2;
+        let total_output_amount = input_0.amount + collateral_amount
+            - Amount::from_sat(MIN_RELAY_FEE_DISPROVE_CHAIN + collateral_fee);
+
+        let burn_amount = total_output_amount * burn_percentage / 100;
         let _output_0 = TxOut {
             value: burn_amount,
             script_pubkey: generate_burn_script_address(network).script_pubkey(),
@@ -103,18 +151,31 @@ impl DisproveChainTransaction {
             script_pubkey: ScriptBuf::default(),
         };
 
+        let mut inputs = vec![_input_0];
+        let mut prev_outs = vec![TxOut {
+            value: input_0.amount,
+            script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
+        }];
+        let mut prev_scripts = vec![connector_b.generate_taproot_leaf_script(input_0_leaf)];
+
+        if let Some(input_1) = collateral_input {
+            inputs.push(connector_0.generate_taproot_leaf_tx_in(input_1_leaf, &input_1));
+            prev_outs.push(TxOut {
+                value: input_1.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_0.generate_taproot_leaf_script(input_1_leaf));
+        }
+
         DisproveChainTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0],
+                input: inputs,
                 output: vec![_output_0, _output_1],
             },
-            prev_outs: vec![TxOut {
-                value: input_0.amount,
-                script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
-            }],
-            prev_scripts: vec![connector_b.generate_taproot_leaf_script(input_0_leaf)],
+            prev_outs,
+            prev_scripts,
             reward_output_amount,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
@@ -127,7 +188,7 @@ impl DisproveChainTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -135,12 +196,14 @@ impl DisproveChainTransaction {
             input_index,
             TapSighashType::Single,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_b);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
@@ -154,17 +217,63 @@ impl DisproveChainTransaction {
         );
     }
 
+    fn collateral_input_index(&self) -> Option<usize> {
+        (self.tx.input.len() > 1).then_some(1)
+    }
+
+    // Signed with SIGHASH_NONE, input 0 commits to the burn output, which includes the share of
+    // the collateral
+    fn sign_input_1(
+        &mut self,
+        context: &VerifierContext,
+        connector_0: &Connector0,
+        secret_nonce: &SecNonce,
+    ) -> Result<(), Error> {
+        let input_index = 1;
+        pre_sign_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            secret_nonce,
+        )?;
+
+        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
+            self.finalize_input_1(context, connector_0);
+        }
+
+        Ok(())
+    }
+
+    fn finalize_input_1(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+        let input_index = 1;
+        finalize_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            connector_0.generate_taproot_spend_info(),
+        );
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
+        connector_0: &Connector0,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &secret_nonces[&input_index])?;
+        if let Some(input_index) = self.collateral_input_index() {
+            self.sign_input_1(context, connector_0, &secret_nonces[&input_index])?;
+        }
         // TODO: We probably shouldn't finalize the witness when pre-signing (sign_input_0 calls finalize_input_0,
         // which adds a control block to the witness). Please double-check that the control block should be only added
         // after the tx is signed (see `sign()`) and ready to be broadcast.
+
+        Ok(())
     }
 
     pub fn sign(
@@ -190,9 +299,17 @@ impl DisproveChainTransaction {
         push_taproot_leaf_unlock_data_to_witness(self.tx_mut(), input_index, unlock_data);
     }
 
-    pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
+    // Sets the reward output, which is not signed by the committee
+    pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) -> Result<(), ValidationError> {
         let output_index = 1;
-        self.tx.output[output_index].script_pubkey = output_script_pubkey;
+        let output = TxOut {
+            value: self.tx.output[output_index].value,
+            script_pubkey: output_script_pubkey,
+        };
+        validate_reward_output(&output)?;
+        self.tx.output[output_index] = output;
+
+        Ok(())
     }
 
     pub fn merge(&mut self, disprove: &DisproveChainTransaction) {
@@ -0,0 +1,85 @@
+use std::fmt;
+
+use bitcoin::{Amount, Transaction, TxOut};
+
+use super::{base::BaseTransaction, pre_signed::PreSignedTransaction};
+
+// Pre-signed transactions pay the fee fixed when the graph is created, nobody can add to it once
+// they are signed. They only get into blocks while the mempool minimum fee rate stays at or below
+// what that fee pays for, so committees pick their fee buffers against this report.
+
+// Fee rate, in sat/vB, under which a graph transaction is reported as a risk
+pub const FEE_RATE_WARNING_THRESHOLD: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeSensitivity {
+    pub transaction: &'static str,
+    pub fee: Amount,
+    pub estimated_vsize: usize,
+    // sat/vB, rounded down
+    pub max_fee_rate: u64,
+}
+
+impl FeeSensitivity {
+    pub fn is_below(&self, fee_rate_threshold: u64) -> bool {
+        self.max_fee_rate < fee_rate_threshold
+    }
+}
+
+impl fmt::Display for FeeSensitivity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: pays {} sat for ~{} vB, broadcastable up to {} sat/vB",
+            self.transaction,
+            self.fee.to_sat(),
+            self.estimated_vsize,
+            self.max_fee_rate
+        )
+    }
+}
+
+// `estimated_vsize` is the size of the finalized transaction, see the `VSIZE_*` constants. The
+// template itself has no witness yet.
+pub fn transaction_fee_sensitivity(
+    transaction: &(impl BaseTransaction + PreSignedTransaction),
+    estimated_vsize: usize,
+) -> FeeSensitivity {
+    fee_sensitivity(
+        transaction.name(),
+        transaction.tx(),
+        transaction.prev_outs(),
+        estimated_vsize,
+    )
+}
+
+pub fn fee_sensitivity(
+    name: &'static str,
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    estimated_vsize: usize,
+) -> FeeSensitivity {
+    let input_amount: Amount = prev_outs.iter().map(|prev_out| prev_out.value).sum();
+    let output_amount: Amount = tx.output.iter().map(|output| output.value).sum();
+    let fee = input_amount
+        .checked_sub(output_amount)
+        .unwrap_or(Amount::ZERO);
+
+    FeeSensitivity {
+        transaction: name,
+        fee,
+        estimated_vsize,
+        max_fee_rate: fee.to_sat() / estimated_vsize.max(1) as u64,
+    }
+}
+
+// Transactions of `report` that stop being broadcastable below the threshold
+pub fn fee_sensitivity_warnings(
+    report: &[FeeSensitivity],
+    fee_rate_threshold: u64,
+) -> Vec<&FeeSensitivity> {
+    report
+        .iter()
+        .filter(|sensitivity| sensitivity.is_below(fee_rate_threshold))
+        .collect()
+}
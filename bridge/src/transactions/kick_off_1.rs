@@ -1,17 +1,18 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType, Transaction,
+    TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
 
 use super::{
     super::{
         connectors::{
-            base::*, connector_1::Connector1, connector_2::Connector2, connector_6::Connector6,
-            connector_a::ConnectorA,
+            base::*, connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
+            connector_6::Connector6, connector_a::ConnectorA,
         },
         contexts::operator::OperatorContext,
         graphs::base::DUST_AMOUNT,
+        scripts::*,
     },
     base::*,
     pre_signed::*,
@@ -19,12 +20,37 @@ use super::{
 };
 use bitvm::signatures::signing_winternitz::{generate_winternitz_witness, WinternitzSigningInputs};
 
+/// Messages the operator commits to when broadcasting kick-off 1, decided when the peg-out graph
+/// is created.
+///
+/// `PegOutTxIds` commits to the peg-out txid on both networks. `PegOutTxIdsAndDestinationMetadata`
+/// also commits to the destination chain id and the withdrawal nonce, so a peg-out claimed for one
+/// destination network cannot be replayed by an operator serving several of them.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum KickOff1Commitments {
+    #[default]
+    PegOutTxIds,
+    PegOutTxIdsAndDestinationMetadata,
+}
+
+impl KickOff1Commitments {
+    pub fn includes_destination_metadata(&self) -> bool {
+        matches!(self, KickOff1Commitments::PegOutTxIdsAndDestinationMetadata)
+    }
+}
+
+pub struct DestinationMetadataSigningInputs<'a, 'b> {
+    pub destination_chain_id: WinternitzSigningInputs<'a, 'b>,
+    pub withdrawal_nonce: WinternitzSigningInputs<'a, 'b>,
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct KickOff1Transaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 }
 
@@ -47,32 +73,49 @@ impl PreSignedTransaction for KickOff1Transaction {
 }
 
 impl KickOff1Transaction {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         context: &OperatorContext,
+        connector_0: &Connector0,
         connector_1: &Connector1,
         connector_2: &Connector2,
         connector_6: &Connector6,
         input_0: Input,
+        collateral_input: Option<Input>,
+        fee_rate: u64,
     ) -> Self {
         Self::new_for_validation(
             context.network,
+            &context.operator_public_key,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
+            connector_0,
             connector_1,
             connector_2,
             connector_6,
             input_0,
+            collateral_input,
+            fee_rate,
         )
     }
 
+    // `collateral_input` is an output of the operator that funds the slashable collateral output,
+    // see `GraphParameters::operator_collateral`. The collateral is locked to the n-of-n, which
+    // pre-signs the take transactions returning it and the disprove, disprove chain and timeout
+    // transactions slashing it.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_for_validation(
         network: Network,
+        operator_public_key: &PublicKey,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        connector_0: &Connector0,
         connector_1: &Connector1,
         connector_2: &Connector2,
         connector_6: &Connector6,
         input_0: Input,
+        collateral_input: Option<Input>,
+        fee_rate: u64,
     ) -> Self {
         let connector_a = ConnectorA::new(
             network,
@@ -83,7 +126,8 @@ impl KickOff1Transaction {
         let input_0_leaf = 0;
         let _input_0 = connector_6.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_1);
+        let total_output_amount =
+            input_0.amount - Amount::from_sat(relay_fee_at_rate(VSIZE_KICK_OFF_1, fee_rate));
 
         let _output_0 = TxOut {
             value: Amount::from_sat(DUST_AMOUNT),
@@ -92,7 +136,7 @@ impl KickOff1Transaction {
 
         // fund start time relay fee here since it has no other inputs
         let _output_2 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT + MIN_RELAY_FEE_START_TIME),
+            value: Amount::from_sat(DUST_AMOUNT + relay_fee_at_rate(VSIZE_START_TIME, fee_rate)),
             script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
         };
 
@@ -101,27 +145,52 @@ impl KickOff1Transaction {
             script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
         };
 
+        let mut inputs = vec![_input_0];
+        let mut outputs = vec![_output_0, _output_1, _output_2];
+        let mut prev_outs = vec![TxOut {
+            value: input_0.amount,
+            script_pubkey: connector_6.generate_taproot_address().script_pubkey(), // TODO: Add address of Commit y
+        }];
+        let mut prev_scripts = vec![connector_6.generate_taproot_leaf_script(input_0_leaf)];
+
+        if let Some(collateral_input) = collateral_input {
+            inputs.push(generate_default_tx_in(&collateral_input));
+            outputs.push(TxOut {
+                value: collateral_input.amount
+                    - Amount::from_sat(relay_fee_at_rate(VSIZE_KICK_OFF_1_COLLATERAL, fee_rate)),
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_outs.push(TxOut {
+                value: collateral_input.amount,
+                script_pubkey: generate_pay_to_pubkey_script_address(network, operator_public_key)
+                    .script_pubkey(),
+            });
+            prev_scripts.push(generate_pay_to_pubkey_script(operator_public_key));
+        }
+
         KickOff1Transaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0],
-                output: vec![_output_0, _output_1, _output_2],
+                input: inputs,
+                output: outputs,
             },
-            prev_outs: vec![TxOut {
-                value: input_0.amount,
-                script_pubkey: connector_6.generate_taproot_address().script_pubkey(), // TODO: Add address of Commit y
-            }],
-            prev_scripts: vec![connector_6.generate_taproot_leaf_script(input_0_leaf)],
+            prev_outs,
+            prev_scripts,
         }
     }
 
+    pub fn collateral_vout(&self) -> Option<usize> {
+        (self.tx.output.len() > 3).then_some(3)
+    }
+
     fn sign_input_0(
         &mut self,
         context: &OperatorContext,
         connector_6: &Connector6,
         source_network_txid_inputs: &WinternitzSigningInputs,
         destination_network_txid_inputs: &WinternitzSigningInputs,
+        destination_metadata_inputs: Option<&DestinationMetadataSigningInputs>,
     ) {
         let input_index = 0;
         let script = &self.prev_scripts()[input_index].clone();
@@ -146,6 +215,19 @@ impl KickOff1Transaction {
         // get winternitz signature for destination network txid
         unlock_data.extend(generate_winternitz_witness(destination_network_txid_inputs).to_vec());
 
+        if let Some(destination_metadata_inputs) = destination_metadata_inputs {
+            // get winternitz signature for withdrawal nonce
+            unlock_data.extend(
+                generate_winternitz_witness(&destination_metadata_inputs.withdrawal_nonce).to_vec(),
+            );
+
+            // get winternitz signature for destination chain id
+            unlock_data.extend(
+                generate_winternitz_witness(&destination_metadata_inputs.destination_chain_id)
+                    .to_vec(),
+            );
+        }
+
         populate_taproot_input_witness(
             self.tx_mut(),
             input_index,
@@ -155,6 +237,16 @@ impl KickOff1Transaction {
         );
     }
 
+    fn sign_input_1(&mut self, context: &OperatorContext) {
+        let input_index = 1;
+        pre_sign_p2wsh_input(
+            self,
+            input_index,
+            EcdsaSighashType::All,
+            &vec![&context.operator_keypair],
+        );
+    }
+
     pub fn sign(
         &mut self,
         context: &OperatorContext,
@@ -167,7 +259,33 @@ impl KickOff1Transaction {
             connector_6,
             source_network_txid_inputs,
             destination_network_txid_inputs,
+            None,
+        );
+        if self.collateral_vout().is_some() {
+            self.sign_input_1(context);
+        }
+    }
+
+    // For connector 6 scripts that also commit to the destination chain id and withdrawal nonce,
+    // see `Connector6::commits_destination_metadata`
+    pub fn sign_with_destination_metadata(
+        &mut self,
+        context: &OperatorContext,
+        connector_6: &Connector6,
+        source_network_txid_inputs: &WinternitzSigningInputs,
+        destination_network_txid_inputs: &WinternitzSigningInputs,
+        destination_metadata_inputs: &DestinationMetadataSigningInputs,
+    ) {
+        self.sign_input_0(
+            context,
+            connector_6,
+            source_network_txid_inputs,
+            destination_network_txid_inputs,
+            Some(destination_metadata_inputs),
         );
+        if self.collateral_vout().is_some() {
+            self.sign_input_1(context);
+        }
     }
 }
 
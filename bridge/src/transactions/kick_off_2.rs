@@ -1,7 +1,4 @@
-use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    Witness,
-};
+use bitcoin::{absolute, Amount, ScriptBuf, TapSighashType, Transaction, TxOut, Witness};
 use serde::{Deserialize, Serialize};
 
 use crate::connectors::base::{P2wshConnector, TaprootConnector};
@@ -23,12 +20,14 @@ pub const MIN_RELAY_FEE_AMOUNT: u64 = 105_771;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct KickOff2Transaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 
+    #[serde(default, with = "crate::serialization::hex_encoded_option")]
     pub superblock_hash_witness: Option<Witness>,
 }
 
@@ -52,33 +51,27 @@ impl PreSignedTransaction for KickOff2Transaction {
 
 impl KickOff2Transaction {
     pub fn new(
-        context: &OperatorContext,
         connector_1: &Connector1,
+        connector_3: &Connector3,
         connector_b: &ConnectorB,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
-        Self::new_for_validation(
-            context.network,
-            &context.operator_public_key,
-            connector_1,
-            connector_b,
-            input_0,
-        )
+        Self::new_for_validation(connector_1, connector_3, connector_b, input_0, fee_rate)
     }
 
     pub fn new_for_validation(
-        network: Network,
-        operator_public_key: &PublicKey,
         connector_1: &Connector1,
+        connector_3: &Connector3,
         connector_b: &ConnectorB,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
-        let connector_3 = Connector3::new(network, operator_public_key);
-
         let input_0_leaf = 0;
         let _input_0 = connector_1.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_2);
+        let total_output_amount =
+            input_0.amount - Amount::from_sat(relay_fee_at_rate(VSIZE_KICK_OFF_2, fee_rate));
 
         let _output_0 = TxOut {
             value: Amount::from_sat(DUST_AMOUNT),
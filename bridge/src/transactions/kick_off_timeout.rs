@@ -1,5 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    absolute, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -7,8 +7,10 @@ use std::collections::HashMap;
 
 use super::{
     super::{
-        connectors::{base::*, connector_1::Connector1},
+        connectors::{base::*, connector_0::Connector0, connector_1::Connector1},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::{Error, ValidationError},
+        rewards::validate_reward_output,
         scripts::*,
     },
     base::*,
@@ -18,15 +20,19 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct KickOffTimeoutTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
     reward_output_amount: Amount,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -72,20 +78,55 @@ impl PreSignedMusig2Transaction for KickOffTimeoutTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> {
-        vec![0]
+        let mut inputs = vec![0];
+        inputs.extend(self.collateral_input_index());
+        inputs
+    }
+    fn verifier_sighash_type(&self, input_index: usize) -> TapSighashType {
+        if Some(input_index) == self.collateral_input_index() {
+            TapSighashType::None
+        } else {
+            TapSighashType::Single
+        }
     }
 }
 
 impl KickOffTimeoutTransaction {
-    pub fn new(context: &OperatorContext, connector_1: &Connector1, input_0: Input) -> Self {
-        Self::new_for_validation(context.network, connector_1, input_0)
+    pub fn new(
+        context: &OperatorContext,
+        connector_0: &Connector0,
+        connector_1: &Connector1,
+        input_0: Input,
+        collateral_input: Option<Input>,
+    ) -> Self {
+        Self::new_for_validation(
+            context.network,
+            connector_0,
+            connector_1,
+            input_0,
+            collateral_input,
+        )
     }
 
-    pub fn new_for_validation(network: Network, connector_1: &Connector1, input_0: Input) -> Self {
+    // The operator collateral of kick-off 1 is slashed along with the connector 1 funds
+    pub fn new_for_validation(
+        network: Network,
+        connector_0: &Connector0,
+        connector_1: &Connector1,
+        input_0: Input,
+        collateral_input: Option<Input>,
+    ) -> Self {
         let input_0_leaf = 1;
         let _input_0 = connector_1.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_TIMEOUT);
+        let input_1_leaf = 0;
+        let (collateral_amount, collateral_fee) = match &collateral_input {
+            Some(input_1) => (input_1.amount, MIN_RELAY_FEE_COLLATERAL_INPUT),
+            None => (Amount::ZERO, 0),
+        };
+
+        let total_output_amount = input_0.amount + collateral_amount
+            - Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_TIMEOUT + collateral_fee);
 
         let _output_0 = TxOut {
             value: total_output_amount * 95 / 100,
@@ -98,18 +139,31 @@ impl KickOffTimeoutTransaction {
             script_pubkey: ScriptBuf::default(),
         };
 
+        let mut inputs = vec![_input_0];
+        let mut prev_outs = vec![TxOut {
+            value: input_0.amount,
+            script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
+        }];
+        let mut prev_scripts = vec![connector_1.generate_taproot_leaf_script(input_0_leaf)];
+
+        if let Some(input_1) = collateral_input {
+            inputs.push(connector_0.generate_taproot_leaf_tx_in(input_1_leaf, &input_1));
+            prev_outs.push(TxOut {
+                value: input_1.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_0.generate_taproot_leaf_script(input_1_leaf));
+        }
+
         KickOffTimeoutTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0],
+                input: inputs,
                 output: vec![_output_0, _output_1],
             },
-            prev_outs: vec![TxOut {
-                value: input_0.amount,
-                script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
-            }],
-            prev_scripts: vec![connector_1.generate_taproot_leaf_script(input_0_leaf)],
+            prev_outs,
+            prev_scripts,
             reward_output_amount,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
@@ -122,7 +176,7 @@ impl KickOffTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -130,12 +184,14 @@ impl KickOffTimeoutTransaction {
             input_index,
             TapSighashType::Single,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_1);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_1: &Connector1) {
@@ -149,19 +205,74 @@ impl KickOffTimeoutTransaction {
         );
     }
 
+    fn collateral_input_index(&self) -> Option<usize> {
+        (self.tx.input.len() > 1).then_some(1)
+    }
+
+    // Signed with SIGHASH_NONE, input 0 commits to the burn output, which includes the share of
+    // the collateral
+    fn sign_input_1(
+        &mut self,
+        context: &VerifierContext,
+        connector_0: &Connector0,
+        secret_nonce: &SecNonce,
+    ) -> Result<(), Error> {
+        let input_index = 1;
+        pre_sign_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            secret_nonce,
+        )?;
+
+        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
+            self.finalize_input_1(context, connector_0);
+        }
+
+        Ok(())
+    }
+
+    fn finalize_input_1(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+        let input_index = 1;
+        finalize_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            connector_0.generate_taproot_spend_info(),
+        );
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
+        connector_0: &Connector0,
         connector_1: &Connector1,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_1, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_1, &secret_nonces[&input_index])?;
+
+        if let Some(input_index) = self.collateral_input_index() {
+            self.sign_input_1(context, connector_0, &secret_nonces[&input_index])?;
+        }
+
+        Ok(())
     }
 
-    pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
+    // Sets the reward output, which is not signed by the committee
+    pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) -> Result<(), ValidationError> {
         let output_index = 1;
-        self.tx.output[output_index].script_pubkey = output_script_pubkey;
+        let output = TxOut {
+            value: self.tx.output[output_index].value,
+            script_pubkey: output_script_pubkey,
+        };
+        validate_reward_output(&output)?;
+        self.tx.output[output_index] = output;
+
+        Ok(())
     }
 
     pub fn merge(&mut self, disprove: &KickOffTimeoutTransaction) {
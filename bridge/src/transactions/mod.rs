@@ -1,9 +1,12 @@
 pub mod assert;
 pub mod assert_transactions;
+pub mod auxiliary;
 pub mod base;
 pub mod challenge;
+pub mod cpfp;
 pub mod disprove;
 pub mod disprove_chain;
+pub mod fee_sensitivity;
 pub mod kick_off_1;
 pub mod kick_off_2;
 pub mod kick_off_timeout;
@@ -14,9 +17,14 @@ pub mod peg_out;
 pub mod peg_out_confirm;
 pub mod pre_signed;
 pub mod pre_signed_musig2;
+pub mod script_diagnostics;
 pub mod signing;
+pub mod signing_bundle;
 pub mod signing_musig2;
+pub mod standardness;
 pub mod start_time;
 pub mod start_time_timeout;
 pub mod take_1;
 pub mod take_2;
+pub mod template_id;
+pub mod witness_scrubber;
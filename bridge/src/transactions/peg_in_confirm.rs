@@ -1,6 +1,4 @@
-use bitcoin::{
-    absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-};
+use bitcoin::{absolute, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut};
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -9,6 +7,7 @@ use super::{
     super::{
         connectors::{base::*, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{base::BaseContext, depositor::DepositorContext, verifier::VerifierContext},
+        error::Error,
     },
     base::*,
     pre_signed::*,
@@ -20,16 +19,20 @@ pub const PEG_IN_CONFIRM_TX_NAME: &str = "PegInConfirm";
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PegInConfirmTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 
     n_of_n_public_keys: Vec<PublicKey>,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -85,12 +88,14 @@ impl PegInConfirmTransaction {
         connector_0: &Connector0,
         connector_z: &ConnectorZ,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
         let mut this = Self::new_for_validation(
             connector_0,
             connector_z,
             input_0,
             context.n_of_n_public_keys.clone(),
+            fee_rate,
         );
 
         this.generate_and_push_depositor_signature_input_0(context);
@@ -104,12 +109,14 @@ impl PegInConfirmTransaction {
         input_0: Input,
         n_of_n_public_keys: &[PublicKey],
         depositor_signature: bitcoin::taproot::Signature,
+        fee_rate: u64,
     ) -> Self {
         let mut this = Self::new_for_validation(
             connector_0,
             connector_z,
             input_0,
             n_of_n_public_keys.to_owned(),
+            fee_rate,
         );
 
         this.push_depositor_signature_input(0, depositor_signature);
@@ -122,11 +129,13 @@ impl PegInConfirmTransaction {
         connector_z: &ConnectorZ,
         input_0: Input,
         n_of_n_public_keys: Vec<PublicKey>,
+        fee_rate: u64,
     ) -> Self {
         let input_0_leaf = 1;
         let _input_0 = connector_z.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_PEG_IN_CONFIRM);
+        let total_output_amount =
+            input_0.amount - Amount::from_sat(relay_fee_at_rate(VSIZE_PEG_IN_CONFIRM, fee_rate));
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -181,7 +190,7 @@ impl PegInConfirmTransaction {
         context: &VerifierContext,
         connector_z: &ConnectorZ,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -189,12 +198,14 @@ impl PegInConfirmTransaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_z);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_z: &ConnectorZ) {
@@ -213,9 +224,9 @@ impl PegInConfirmTransaction {
         context: &VerifierContext,
         connector_z: &ConnectorZ,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.push_verifier_signature_input_0(context, connector_z, &secret_nonces[&input_index]);
+        self.push_verifier_signature_input_0(context, connector_z, &secret_nonces[&input_index])
     }
 
     pub fn merge(&mut self, peg_in_confirm: &PegInConfirmTransaction) {
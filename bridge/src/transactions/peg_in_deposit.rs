@@ -1,7 +1,6 @@
 use bitcoin::{
-    absolute,
-    consensus::{self},
-    Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction, TxOut,
+    absolute, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType, Transaction,
+    TxOut,
 };
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +8,8 @@ use super::{
     super::{
         connectors::{base::*, connector_z::ConnectorZ},
         contexts::depositor::DepositorContext,
+        error::{ClientError, Error},
+        graphs::base::DUST_AMOUNT,
         scripts::*,
     },
     base::*,
@@ -16,12 +17,42 @@ use super::{
     signing::populate_p2wsh_witness_with_signatures,
 };
 
+// Vout of the change paid back to the depositor address, if any, after the connector Z output
+const CHANGE_VOUT: usize = 1;
+
+// Depositor outputs a peg-in deposit spends, all from the same depositor address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositFunding {
+    pub inputs: Vec<Input>,
+    // Output of connector Z, at most what the inputs hold after the fee. The rest of the inputs is
+    // paid back to the depositor address. All of the inputs but the fee are deposited if not set.
+    // Funding that cannot pay it is rejected when building the deposit.
+    pub amount: Option<Amount>,
+}
+
+impl DepositFunding {
+    pub fn new(inputs: Vec<Input>, amount: Option<Amount>) -> Self {
+        Self { inputs, amount }
+    }
+
+    pub fn total_amount(&self) -> Amount {
+        self.inputs.iter().map(|input| input.amount).sum()
+    }
+}
+
+impl From<Input> for DepositFunding {
+    fn from(input: Input) -> Self {
+        Self::new(vec![input], None)
+    }
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PegInDepositTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 }
 
@@ -44,73 +75,182 @@ impl PreSignedTransaction for PegInDepositTransaction {
 }
 
 impl PegInDepositTransaction {
-    pub fn new(context: &DepositorContext, connector_z: &ConnectorZ, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        connector_z: &ConnectorZ,
+        funding: impl Into<DepositFunding>,
+        depositor_address_type: DepositorAddressType,
+        fee_rate: u64,
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_public_key,
             connector_z,
-            input_0,
-        );
+            funding,
+            depositor_address_type,
+            fee_rate,
+        )?;
 
-        this.sign_input_0(context);
+        this.sign_inputs(context, depositor_address_type);
 
-        this
+        Ok(this)
     }
 
+    // Signed by an external wallet, which funds the deposit from the P2WSH depositor address
     pub fn new_with_signature(
         network: Network,
         depositor_public_key: &PublicKey,
         connector_z: &ConnectorZ,
         input_0: Input,
         signature: bitcoin::ecdsa::Signature,
-    ) -> Self {
-        let mut this =
-            Self::new_for_validation(network, depositor_public_key, connector_z, input_0);
+        fee_rate: u64,
+    ) -> Result<Self, Error> {
+        let mut this = Self::new_for_validation(
+            network,
+            depositor_public_key,
+            connector_z,
+            input_0,
+            DepositorAddressType::P2wsh,
+            fee_rate,
+        )?;
 
         this.sign_input_0_with_signature(signature);
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
         depositor_public_key: &PublicKey,
         connector_z: &ConnectorZ,
-        input_0: Input,
-    ) -> Self {
-        let _input_0 = generate_default_tx_in(&input_0);
+        funding: impl Into<DepositFunding>,
+        depositor_address_type: DepositorAddressType,
+        fee_rate: u64,
+    ) -> Result<Self, Error> {
+        let funding: DepositFunding = funding.into();
+        let input_count = funding.inputs.len();
+        let total_amount = funding.total_amount();
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_PEG_IN_DEPOSIT);
+        let fee = Amount::from_sat(relay_fee_at_rate(
+            peg_in_deposit_vsize(input_count, false),
+            fee_rate,
+        ));
+        // Depositing everything still has to leave an output above dust
+        let required_amount = funding.amount.unwrap_or(Amount::from_sat(DUST_AMOUNT)) + fee;
+        if funding.inputs.is_empty() || total_amount < required_amount {
+            return Err(Error::Client(ClientError::InsufficientDepositorFunds {
+                required: required_amount,
+                available: total_amount,
+            }));
+        }
+        let deposit_amount = funding.amount.unwrap_or(total_amount - fee);
+        // Change too small to be relayed is left to the fee
+        let change_amount = total_amount
+            .checked_sub(
+                deposit_amount
+                    + Amount::from_sat(relay_fee_at_rate(
+                        peg_in_deposit_vsize(input_count, true),
+                        fee_rate,
+                    )),
+            )
+            .filter(|change_amount| *change_amount >= Amount::from_sat(DUST_AMOUNT));
 
-        let _output_0 = TxOut {
-            value: total_output_amount,
-            script_pubkey: connector_z.generate_taproot_address().script_pubkey(),
+        let depositor_script_pubkey =
+            generate_depositor_address(network, depositor_public_key, depositor_address_type)
+                .script_pubkey();
+        // A key path spend has no script
+        let prev_script = match depositor_address_type {
+            DepositorAddressType::P2wsh => generate_pay_to_pubkey_script(depositor_public_key),
+            DepositorAddressType::P2tr => ScriptBuf::new(),
         };
 
-        PegInDepositTransaction {
+        let mut outputs = vec![TxOut {
+            value: deposit_amount,
+            script_pubkey: connector_z.generate_taproot_address().script_pubkey(),
+        }];
+        if let Some(change_amount) = change_amount {
+            outputs.push(TxOut {
+                value: change_amount,
+                script_pubkey: depositor_script_pubkey.clone(),
+            });
+        }
+
+        Ok(PegInDepositTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0],
-                output: vec![_output_0],
+                input: funding.inputs.iter().map(generate_default_tx_in).collect(),
+                output: outputs,
             },
-            prev_outs: vec![TxOut {
-                value: input_0.amount,
-                script_pubkey: generate_pay_to_pubkey_script_address(network, depositor_public_key)
-                    .script_pubkey(),
-            }],
-            prev_scripts: vec![generate_pay_to_pubkey_script(depositor_public_key)],
-        }
+            prev_outs: funding
+                .inputs
+                .iter()
+                .map(|input| TxOut {
+                    value: input.amount,
+                    script_pubkey: depositor_script_pubkey.clone(),
+                })
+                .collect(),
+            prev_scripts: vec![prev_script; input_count],
+        })
     }
 
-    fn sign_input_0(&mut self, context: &DepositorContext) {
-        let input_index = 0;
-        pre_sign_p2wsh_input(
-            self,
-            input_index,
-            EcdsaSighashType::All,
-            &vec![&context.depositor_keypair],
-        );
+    // Depositor outputs the deposit spends, for rebuilding it
+    pub fn funding(&self) -> DepositFunding {
+        DepositFunding::new(
+            self.tx
+                .input
+                .iter()
+                .zip(&self.prev_outs)
+                .map(|(input, prev_out)| Input {
+                    outpoint: input.previous_output,
+                    amount: prev_out.value,
+                })
+                .collect(),
+            Some(self.tx.output[0].value),
+        )
+    }
+
+    pub fn change_vout(&self) -> Option<usize> {
+        (self.tx.output.len() > CHANGE_VOUT).then_some(CHANGE_VOUT)
+    }
+
+    // What the depositor spent on the deposit: the deposited amount and the fee, without the change
+    pub fn funding_amount(&self) -> Amount {
+        let change_amount = self
+            .change_vout()
+            .map_or(Amount::ZERO, |vout| self.tx.output[vout].value);
+        self.prev_outs
+            .iter()
+            .map(|prev_out| prev_out.value)
+            .sum::<Amount>()
+            - change_amount
+    }
+
+    pub fn estimated_vsize(&self) -> usize {
+        peg_in_deposit_vsize(self.tx.input.len(), self.change_vout().is_some())
+    }
+
+    fn sign_inputs(
+        &mut self,
+        context: &DepositorContext,
+        depositor_address_type: DepositorAddressType,
+    ) {
+        for input_index in 0..self.tx.input.len() {
+            match depositor_address_type {
+                DepositorAddressType::P2wsh => pre_sign_p2wsh_input(
+                    self,
+                    input_index,
+                    EcdsaSighashType::All,
+                    &vec![&context.depositor_keypair],
+                ),
+                DepositorAddressType::P2tr => pre_sign_p2tr_key_spend_input(
+                    self,
+                    input_index,
+                    TapSighashType::All,
+                    &context.depositor_keypair,
+                ),
+            }
+        }
     }
 
     fn sign_input_0_with_signature(&mut self, signature: bitcoin::ecdsa::Signature) {
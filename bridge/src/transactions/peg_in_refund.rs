@@ -1,5 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    absolute, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use serde::{Deserialize, Serialize};
 
@@ -16,10 +16,11 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PegInRefundTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 }
 
@@ -42,12 +43,18 @@ impl PreSignedTransaction for PegInRefundTransaction {
 }
 
 impl PegInRefundTransaction {
-    pub fn new(context: &DepositorContext, connector_z: &ConnectorZ, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        connector_z: &ConnectorZ,
+        input_0: Input,
+        fee_rate: u64,
+    ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_public_key,
             connector_z,
             input_0,
+            fee_rate,
         );
 
         this.sign_input_0(context, connector_z);
@@ -61,9 +68,15 @@ impl PegInRefundTransaction {
         connector_z: &ConnectorZ,
         input_0: Input,
         signature: bitcoin::taproot::Signature,
+        fee_rate: u64,
     ) -> Self {
-        let mut this =
-            Self::new_for_validation(network, depositor_public_key, connector_z, input_0);
+        let mut this = Self::new_for_validation(
+            network,
+            depositor_public_key,
+            connector_z,
+            input_0,
+            fee_rate,
+        );
 
         this.sign_input_0_with_signature(connector_z, signature);
 
@@ -75,11 +88,13 @@ impl PegInRefundTransaction {
         depositor_public_key: &PublicKey,
         connector_z: &ConnectorZ,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
         let input_0_leaf = 0;
         let _input_0 = connector_z.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_PEG_IN_REFUND);
+        let total_output_amount =
+            input_0.amount - Amount::from_sat(relay_fee_at_rate(VSIZE_PEG_IN_REFUND, fee_rate));
 
         let _output_0 = TxOut {
             value: total_output_amount,
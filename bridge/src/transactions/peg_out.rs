@@ -1,10 +1,10 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence,
-    Transaction, TxIn, TxOut, Witness,
+    absolute, key::TweakedPublicKey, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf,
+    Sequence, Transaction, TxIn, TxOut, Witness,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::client::chain::chain::PegOutEvent;
+use crate::client::chain::chain::{PegOutEvent, WithdrawerDestination};
 
 use super::{
     super::{contexts::operator::OperatorContext, scripts::*},
@@ -14,10 +14,11 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PegOutTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 }
 
@@ -68,15 +69,24 @@ impl PegOutTransaction {
 
         let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_PEG_OUT);
 
+        // A taproot destination is paid to directly, spendable by the withdrawer's wallet with a
+        // key path spend. It carries no inscription and is the same size as the P2WSH output.
         let _output_0 = TxOut {
             value: total_output_amount,
-            script_pubkey: generate_pay_to_pubkey_hash_with_inscription_script_address(
-                network,
-                &peg_out_event.withdrawer_public_key_hash,
-                peg_out_event.timestamp,
-                &peg_out_event.withdrawer_chain_address,
-            )
-            .script_pubkey(),
+            script_pubkey: match peg_out_event.withdrawer_destination {
+                WithdrawerDestination::Taproot { output_key } => ScriptBuf::new_p2tr_tweaked(
+                    TweakedPublicKey::dangerous_assume_tweaked(output_key),
+                ),
+                WithdrawerDestination::PublicKeyHash(public_key_hash) => {
+                    generate_pay_to_pubkey_hash_with_inscription_script_address(
+                        network,
+                        &public_key_hash,
+                        peg_out_event.timestamp,
+                        &peg_out_event.withdrawer_chain_address,
+                    )
+                    .script_pubkey()
+                }
+            },
         };
 
         PegOutTransaction {
@@ -1,6 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction,
-    TxOut,
+    absolute, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction, TxOut,
 };
 use serde::{Deserialize, Serialize};
 
@@ -16,10 +15,11 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PegOutConfirmTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 }
 
@@ -50,7 +50,7 @@ impl PegOutConfirmTransaction {
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_operator_inputs(context);
 
         this
     }
@@ -86,6 +86,15 @@ impl PegOutConfirmTransaction {
         }
     }
 
+    // Signatures of the operator made when the graph is created
+    pub fn sign_operator_inputs(&mut self, context: &OperatorContext) {
+        self.sign_input_0(context);
+    }
+
+    pub fn merge(&mut self, peg_out_confirm: &PegOutConfirmTransaction) {
+        merge_transactions(&mut self.tx, &peg_out_confirm.tx);
+    }
+
     fn sign_input_0(&mut self, context: &OperatorContext) {
         let input_index = 0;
         pre_sign_p2wsh_input(
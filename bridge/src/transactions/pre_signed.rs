@@ -6,8 +6,10 @@ use bitcoin::{
 use super::{
     super::contexts::base::BaseContext,
     signing::{
-        populate_p2wpkh_witness, populate_p2wsh_witness, populate_taproot_input_witness_default,
+        populate_p2tr_key_spend_witness, populate_p2wpkh_witness, populate_p2wsh_witness,
+        populate_taproot_input_witness_default,
     },
+    template_id::TemplateId,
 };
 
 pub trait PreSignedTransaction {
@@ -15,6 +17,9 @@ pub trait PreSignedTransaction {
     fn tx_mut(&mut self) -> &mut Transaction;
     fn prev_outs(&self) -> &Vec<TxOut>;
     fn prev_scripts(&self) -> &Vec<ScriptBuf>;
+    fn template_id(&self) -> TemplateId {
+        TemplateId::new(self)
+    }
 }
 
 pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
@@ -57,6 +62,25 @@ pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
     );
 }
 
+// Spends an output locked to the key path of `keypair` only, its prev script is empty
+pub fn pre_sign_p2tr_key_spend_input<T: PreSignedTransaction>(
+    tx: &mut T,
+    input_index: usize,
+    sighash_type: TapSighashType,
+    keypair: &Keypair,
+) {
+    let prev_outs = &tx.prev_outs().clone();
+
+    populate_p2tr_key_spend_witness(
+        tx.tx_mut(),
+        input_index,
+        prev_outs,
+        sighash_type,
+        None,
+        keypair,
+    );
+}
+
 pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
     tx: &mut T,
     input_index: usize,
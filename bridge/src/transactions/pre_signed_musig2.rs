@@ -1,23 +1,28 @@
 use bitcoin::{
     hashes::{sha256, Hash},
     taproot::TaprootSpendInfo,
-    PublicKey, TapSighashType, XOnlyPublicKey,
+    PublicKey, TapSighashType, Txid, XOnlyPublicKey,
 };
 use musig2::{
     secp::MaybeScalar,
     secp256k1::{schnorr::Signature, Message},
     BinaryEncoding, PartialSignature, PubNonce, SecNonce,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use super::{
-    super::contexts::{base::BaseContext, verifier::VerifierContext},
+    super::{
+        contexts::{base::BaseContext, verifier::VerifierContext},
+        error::{Error, TransactionError},
+    },
     pre_signed::PreSignedTransaction,
     signing::push_taproot_leaf_script_and_control_block_to_witness,
+    signing_bundle::SighashPreimage,
     signing_musig2::{
         generate_aggregated_nonce, generate_nonce, generate_taproot_aggregated_signature,
         generate_taproot_partial_signature,
     },
+    template_id::TemplateId,
 };
 
 pub trait PreSignedMusig2Transaction: PreSignedTransaction {
@@ -31,6 +36,25 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
         &mut self,
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>>;
     fn verifier_inputs(&self) -> Vec<usize>;
+    // Must match the sighash type the input is pre-signed with
+    fn verifier_sighash_type(&self, _input_index: usize) -> TapSighashType {
+        TapSighashType::All
+    }
+    // Messages the committee signs for its inputs, see `SighashPreimage`
+    fn sighash_preimages(&self) -> Vec<SighashPreimage> {
+        self.verifier_inputs()
+            .into_iter()
+            .map(|input_index| {
+                SighashPreimage::new(
+                    self.tx(),
+                    self.prev_outs(),
+                    input_index,
+                    &self.prev_scripts()[input_index],
+                    self.verifier_sighash_type(input_index),
+                )
+            })
+            .collect()
+    }
     fn has_nonces_for(&self, verifier_pubkey: PublicKey) -> bool {
         self.has_all_nonces(&[verifier_pubkey])
     }
@@ -42,6 +66,21 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
             })
         })
     }
+    // Whether every committee member signed the input, signatures from keys outside the committee
+    // do not count
+    fn has_all_signatures_for_input(
+        &self,
+        input_index: usize,
+        verifier_pubkeys: &[PublicKey],
+    ) -> bool {
+        self.musig2_signatures()
+            .get(&input_index)
+            .is_some_and(|signatures| {
+                verifier_pubkeys
+                    .iter()
+                    .all(|pubkey| signatures.contains_key(pubkey))
+            })
+    }
     fn has_signatures_for(&self, verifier_pubkey: PublicKey) -> bool {
         self.has_all_signatures(&[verifier_pubkey])
     }
@@ -53,6 +92,13 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
             })
         })
     }
+    // Drops all nonces and signatures, e.g. after the transaction had to be changed. The signing
+    // ceremony has to be repeated with fresh nonces afterwards.
+    fn reset_musig2_ceremony(&mut self) {
+        self.musig2_nonces_mut().clear();
+        self.musig2_nonce_signatures_mut().clear();
+        self.musig2_signatures_mut().clear();
+    }
     fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
         self.verifier_inputs()
             .iter()
@@ -92,6 +138,193 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
     }
 }
 
+// Committee members that have and have not contributed nonces and partial signatures to a
+// pre-signed transaction yet, so the lagging ones can be told apart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Musig2SigningProgress {
+    pub name: String,
+    pub txid: Txid,
+    pub template_id: TemplateId,
+    pub nonces: Vec<PublicKey>,
+    pub pending_nonces: Vec<PublicKey>,
+    pub signatures: Vec<PublicKey>,
+    pub pending_signatures: Vec<PublicKey>,
+}
+
+impl Musig2SigningProgress {
+    pub fn new(
+        name: impl Into<String>,
+        tx: &(impl PreSignedMusig2Transaction + ?Sized),
+        committee: &[PublicKey],
+    ) -> Self {
+        let (nonces, pending_nonces) = committee
+            .iter()
+            .copied()
+            .partition(|pubkey| tx.has_nonces_for(*pubkey));
+        let (signatures, pending_signatures) = committee
+            .iter()
+            .copied()
+            .partition(|pubkey| tx.has_signatures_for(*pubkey));
+
+        Self {
+            name: name.into(),
+            txid: tx.tx().compute_txid(),
+            template_id: tx.template_id(),
+            nonces,
+            pending_nonces,
+            signatures,
+            pending_signatures,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending_nonces.is_empty() && self.pending_signatures.is_empty()
+    }
+}
+
+impl fmt::Display for Musig2SigningProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let committee_size = self.nonces.len() + self.pending_nonces.len();
+        write!(
+            f,
+            "{} ({}): nonces {}/{committee_size}, signatures {}/{committee_size}",
+            self.name,
+            self.txid,
+            self.nonces.len(),
+            self.signatures.len()
+        )?;
+        write!(f, "\n    template: {}", self.template_id)?;
+        for (contribution, pending) in [
+            ("nonces", &self.pending_nonces),
+            ("signatures", &self.pending_signatures),
+        ] {
+            if !pending.is_empty() {
+                let pending = pending.iter().map(PublicKey::to_string).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "\n    waiting for {contribution} from: {}",
+                    pending.join(", ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Where a verifier stands in the signing ceremony of a pre-signed transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CeremonyStep {
+    PushNonces,
+    AwaitNonces, // of other committee members
+    Sign,
+    Signed,
+    // Nonces were pushed but their secret nonces are gone, e.g. the client crashed before saving
+    // them. Nothing was signed with them.
+    NoncesLost,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CeremonyStatus {
+    pub name: String,
+    pub txid: Txid,
+    pub template_id: TemplateId,
+    pub step: CeremonyStep,
+}
+
+impl CeremonyStatus {
+    pub fn new(
+        name: impl Into<String>,
+        tx: &(impl PreSignedMusig2Transaction + ?Sized),
+        context: &VerifierContext,
+        secret_nonces: Option<&HashMap<Txid, HashMap<usize, SecNonce>>>,
+    ) -> Self {
+        let txid = tx.tx().compute_txid();
+        let has_secret_nonces = secret_nonces
+            .and_then(|secret_nonces| secret_nonces.get(&txid))
+            .is_some_and(|secret_nonces| {
+                tx.verifier_inputs()
+                    .iter()
+                    .all(|input_index| secret_nonces.contains_key(input_index))
+            });
+        let step = if tx.has_signatures_for(context.verifier_public_key) {
+            CeremonyStep::Signed
+        } else if !tx.has_nonces_for(context.verifier_public_key) {
+            CeremonyStep::PushNonces
+        } else if !has_secret_nonces {
+            CeremonyStep::NoncesLost
+        } else if !tx.has_all_nonces(&context.n_of_n_public_keys) {
+            CeremonyStep::AwaitNonces
+        } else {
+            CeremonyStep::Sign
+        };
+
+        Self {
+            name: name.into(),
+            txid,
+            template_id: tx.template_id(),
+            step,
+        }
+    }
+}
+
+impl fmt::Display for CeremonyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let step = match self.step {
+            CeremonyStep::PushNonces => "nonces not pushed",
+            CeremonyStep::AwaitNonces => "waiting for the nonces of the committee",
+            CeremonyStep::Sign => "ready to sign",
+            CeremonyStep::Signed => "signed",
+            CeremonyStep::NoncesLost => "secret nonces lost",
+        };
+        write!(
+            f,
+            "{} ({}): {step}\n    template: {}",
+            self.name, self.txid, self.template_id
+        )
+    }
+}
+
+// Committee members whose nonce or partial signature in `source` differs from the one they
+// already contributed to `destination`. Merging `source` would overwrite those contributions.
+pub fn conflicting_contributions(
+    destination: &(impl PreSignedMusig2Transaction + ?Sized),
+    source: &(impl PreSignedMusig2Transaction + ?Sized),
+) -> Vec<PublicKey> {
+    let mut conflicts = conflicting_keys(destination.musig2_nonces(), source.musig2_nonces());
+    conflicts.extend(conflicting_keys(
+        destination.musig2_signatures(),
+        source.musig2_signatures(),
+    ));
+    conflicts.sort();
+    conflicts.dedup();
+
+    conflicts
+}
+
+fn conflicting_keys<T: PartialEq>(
+    destination: &HashMap<usize, HashMap<PublicKey, T>>,
+    source: &HashMap<usize, HashMap<PublicKey, T>>,
+) -> Vec<PublicKey> {
+    source
+        .iter()
+        .filter_map(|(input_index, contributions)| {
+            destination
+                .get(input_index)
+                .map(|known| (known, contributions))
+        })
+        .flat_map(|(known, contributions)| {
+            contributions
+                .iter()
+                .filter(|(pubkey, contribution)| {
+                    known
+                        .get(*pubkey)
+                        .is_some_and(|known| known != *contribution)
+                })
+                .map(|(pubkey, _)| *pubkey)
+        })
+        .collect()
+}
+
 pub fn get_nonce_message(nonce: &PubNonce) -> Message {
     let nonce_hash = sha256::Hash::hash(nonce.to_bytes().as_slice());
     Message::from_digest_slice(nonce_hash.as_ref()).expect("Failed to create nonce message")
@@ -111,18 +344,46 @@ pub fn verify_public_nonce(sig: &Signature, nonce: &PubNonce, pubkey: &XOnlyPubl
     verify_schnorr_signature(sig, &get_nonce_message(nonce), pubkey)
 }
 
+// Nonces or partial signatures of every committee member for an input, in committee order. Entries
+// of keys outside the committee are left out, so they cannot corrupt the aggregate. Fails with the
+// first member that has not contributed.
+fn committee_contributions<T: Clone>(
+    contributions: &HashMap<usize, HashMap<PublicKey, T>>,
+    input_index: usize,
+    committee: &[PublicKey],
+) -> Result<Vec<T>, PublicKey> {
+    committee
+        .iter()
+        .map(|pubkey| {
+            contributions
+                .get(&input_index)
+                .and_then(|input_contributions| input_contributions.get(pubkey))
+                .cloned()
+                .ok_or(*pubkey)
+        })
+        .collect()
+}
+
 pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
     tx: &mut T,
     context: &VerifierContext,
     input_index: usize,
     sighash_type: TapSighashType,
     secret_nonce: &SecNonce,
-) {
+) -> Result<(), Error> {
     // TODO validate nonces first
 
     let prev_outs = &tx.prev_outs().clone();
     let script = &tx.prev_scripts()[input_index].clone();
-    let musig2_nonces = &tx.musig2_nonces()[&input_index].values().cloned().collect();
+    let musig2_nonces =
+        &committee_contributions(tx.musig2_nonces(), input_index, &context.n_of_n_public_keys)
+            .map_err(|missing| {
+                Error::Transaction(TransactionError::NonceMissing(
+                    tx.tx().compute_txid(),
+                    input_index,
+                    missing,
+                ))
+            })?;
 
     let partial_signature = generate_taproot_partial_signature(
         context,
@@ -144,6 +405,8 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         .get_mut(&input_index)
         .unwrap()
         .insert(context.verifier_public_key, partial_signature);
+
+    Ok(())
 }
 
 pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
@@ -153,18 +416,28 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     sighash_type: TapSighashType,
     taproot_spend_info: TaprootSpendInfo,
 ) {
-    // TODO: Verify we have partial signatures from all verifiers.
     // TODO: Verify each signature against the signers public key.
     // See example here: https://github.com/conduition/musig2/blob/c39bfce58098d337a3ec38b54d93def8306d9953/src/signing.rs#L358C1-L366C65
 
+    let committee = context.n_of_n_public_keys();
     let prev_outs = &tx.prev_outs().clone();
     let script = &tx.prev_scripts()[input_index].clone();
-    let musig2_nonces: &Vec<PubNonce> =
-        &tx.musig2_nonces()[&input_index].values().cloned().collect();
-    let musig2_signatures: Vec<MaybeScalar> = tx.musig2_signatures()[&input_index]
-        .values()
-        .map(|&partial_signature| PartialSignature::from(partial_signature))
-        .collect();
+    let musig2_nonces: &Vec<PubNonce> = &committee_contributions(
+        tx.musig2_nonces(),
+        input_index,
+        committee,
+    )
+    .unwrap_or_else(|missing| {
+        panic!("Cannot finalize input {input_index}, verifier {missing} has not pushed a nonce")
+    });
+    let musig2_signatures: Vec<MaybeScalar> =
+        committee_contributions(tx.musig2_signatures(), input_index, committee)
+            .unwrap_or_else(|missing| {
+                panic!("Cannot finalize input {input_index}, verifier {missing} has not pre-signed")
+            })
+            .into_iter()
+            .map(PartialSignature::from)
+            .collect();
     let tx_mut = tx.tx_mut();
 
     // Aggregate signature
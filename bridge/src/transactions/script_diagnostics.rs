@@ -0,0 +1,146 @@
+use std::fmt;
+
+use bitcoin::{OutPoint, Transaction, TxOut, Txid};
+use bitvm::{dry_run_taproot_input, ExecuteInfo};
+#[cfg(feature = "client")]
+use esplora_client::AsyncClient;
+#[cfg(feature = "client")]
+use futures::future::join_all;
+
+// Substring of the reject reasons bitcoind gives for failed input scripts, e.g.
+// `mandatory-script-verify-flag-failed (...)` or `non-mandatory-script-verify-flag (...)`
+const SCRIPT_VERIFY_REJECT_REASON: &str = "script-verify-flag";
+
+#[derive(Debug)]
+pub enum InputScriptResult {
+    // The script path spend was re-executed with the attached witness
+    Executed(ExecuteInfo),
+    // Only the signature is checked, there is no script to execute
+    KeyPathSpend,
+    NotTaproot,
+    PrevoutNotFound,
+}
+
+#[derive(Debug)]
+pub struct InputDiagnostics {
+    pub input_index: usize,
+    pub outpoint: OutPoint,
+    pub result: InputScriptResult,
+}
+
+impl InputDiagnostics {
+    pub fn failed(&self) -> bool {
+        matches!(&self.result, InputScriptResult::Executed(info) if !info.success)
+    }
+}
+
+// Result of re-executing the input scripts of a transaction locally, used to find out why a node
+// rejected it
+#[derive(Debug)]
+pub struct ScriptDiagnostics {
+    pub txid: Txid,
+    pub inputs: Vec<InputDiagnostics>,
+}
+
+impl ScriptDiagnostics {
+    // `prevouts` holds the output spent by each input, None if it could not be looked up
+    pub fn new(tx: &Transaction, prevouts: &[Option<TxOut>]) -> Self {
+        // Signature opcodes need the outputs spent by all inputs to compute the sighash. Inputs
+        // whose prevout is missing are not executed, a placeholder keeps the indices aligned.
+        let all_prevouts: Vec<TxOut> = prevouts
+            .iter()
+            .map(|prevout| prevout.clone().unwrap_or(TxOut::NULL))
+            .collect();
+
+        let inputs = tx
+            .input
+            .iter()
+            .zip(prevouts)
+            .enumerate()
+            .map(|(input_index, (input, prevout))| {
+                let result = match prevout {
+                    None => InputScriptResult::PrevoutNotFound,
+                    Some(prevout) if !prevout.script_pubkey.is_p2tr() => {
+                        InputScriptResult::NotTaproot
+                    }
+                    Some(_) if input.witness.tapscript().is_none() => {
+                        InputScriptResult::KeyPathSpend
+                    }
+                    Some(_) => InputScriptResult::Executed(dry_run_taproot_input(
+                        tx,
+                        input_index,
+                        &all_prevouts,
+                    )),
+                };
+
+                InputDiagnostics {
+                    input_index,
+                    outpoint: input.previous_output,
+                    result,
+                }
+            })
+            .collect();
+
+        Self {
+            txid: tx.compute_txid(),
+            inputs,
+        }
+    }
+
+    pub fn failed_inputs(&self) -> impl Iterator<Item = &InputDiagnostics> {
+        self.inputs.iter().filter(|input| input.failed())
+    }
+}
+
+impl fmt::Display for ScriptDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Script diagnostics for tx {}:", self.txid)?;
+        for input in &self.inputs {
+            write!(f, "Input {} ({}): ", input.input_index, input.outpoint)?;
+            match &input.result {
+                InputScriptResult::Executed(info) => write!(f, "{info}")?,
+                InputScriptResult::KeyPathSpend => {
+                    writeln!(f, "Key path spend, no script to execute.")?
+                }
+                InputScriptResult::NotTaproot => {
+                    writeln!(f, "Not a taproot output, not executed.")?
+                }
+                InputScriptResult::PrevoutNotFound => {
+                    writeln!(f, "Spent output not found, not executed.")?
+                }
+            }
+        }
+        if self.failed_inputs().next().is_none() {
+            writeln!(
+                f,
+                "All executed input scripts succeed locally, the rejection is not caused by them."
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn is_script_verify_rejection(reject_reason: &str) -> bool {
+    reject_reason.contains(SCRIPT_VERIFY_REJECT_REASON)
+}
+
+// Outputs spent by the inputs of the transaction, None where they could not be looked up
+#[cfg(feature = "client")]
+pub async fn get_prevouts(client: &AsyncClient, tx: &Transaction) -> Vec<Option<TxOut>> {
+    join_all(tx.input.iter().map(|input| async move {
+        let outpoint = input.previous_output;
+        client
+            .get_tx(&outpoint.txid)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|prev_tx| prev_tx.output.get(outpoint.vout as usize).cloned())
+    }))
+    .await
+}
+
+// Looks up the outputs spent by the transaction and re-executes its input scripts
+#[cfg(feature = "client")]
+pub async fn diagnose_input_scripts(client: &AsyncClient, tx: &Transaction) -> ScriptDiagnostics {
+    ScriptDiagnostics::new(tx, &get_prevouts(client, tx).await)
+}
@@ -319,7 +319,9 @@ pub fn populate_taproot_input_witness_with_signature(
     populate_taproot_input_witness(tx, input_index, taproot_spend_info, script, unlock_data);
 }
 
-fn generate_p2tr_key_spend_schnorr_signature(
+// Key path signature of a taproot output, whose output key is the internal key of `keypair`
+// tweaked with `merkle_root`. Without a merkle root the output has no script path.
+pub fn generate_p2tr_key_spend_schnorr_signature(
     tx: &mut Transaction,
     input_index: usize,
     prev_outs: &[TxOut],
@@ -373,5 +375,13 @@ pub fn populate_p2tr_key_spend_witness(
         merkle_root,
         keypair,
     );
+    populate_p2tr_key_spend_witness_with_signature(tx, input_index, &signature);
+}
+
+pub fn populate_p2tr_key_spend_witness_with_signature(
+    tx: &mut Transaction,
+    input_index: usize,
+    signature: &bitcoin::taproot::Signature,
+) {
     tx.input[input_index].witness.push(signature.to_vec());
 }
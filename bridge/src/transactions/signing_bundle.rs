@@ -0,0 +1,103 @@
+use bitcoin::{
+    hashes::Hash,
+    hex::{DisplayHex, FromHex},
+    sighash::{Prevouts, SighashCache},
+    taproot::LeafVersion,
+    ScriptBuf, TapLeafHash, TapSighash, TapSighashType, Transaction, TxOut, Txid, Witness,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{pre_signed_musig2::PreSignedMusig2Transaction, template_id::TemplateId};
+
+// Script path spends are signed without OP_CODESEPARATOR, see `taproot_script_spend_signature_hash`
+const NO_CODE_SEPARATOR: u32 = 0xFFFFFFFF;
+
+// Everything the committee authorizes when pre-signing the transactions of a graph, for auditors
+// and hardware signers that recompute the signed messages independently of the bridge
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SigningBundle {
+    pub graph_id: String,
+    pub transactions: Vec<SigningBundleTransaction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SigningBundleTransaction {
+    pub name: String,
+    pub txid: Txid,
+    pub template_id: TemplateId,
+    // Witnesses are stripped, they do not change what is signed
+    #[serde(with = "crate::serialization::consensus_hex")]
+    pub unsigned_tx: Transaction,
+    #[serde(with = "crate::serialization::consensus_hex")]
+    pub prev_outs: Vec<TxOut>,
+    pub sighash_preimages: Vec<SighashPreimage>,
+}
+
+impl SigningBundleTransaction {
+    pub fn new(name: impl Into<String>, tx: &(impl PreSignedMusig2Transaction + ?Sized)) -> Self {
+        let mut unsigned_tx = tx.tx().clone();
+        for input in unsigned_tx.input.iter_mut() {
+            input.witness = Witness::new();
+        }
+
+        Self {
+            name: name.into(),
+            txid: unsigned_tx.compute_txid(),
+            template_id: tx.template_id(),
+            unsigned_tx,
+            prev_outs: tx.prev_outs().clone(),
+            sighash_preimages: tx.sighash_preimages(),
+        }
+    }
+}
+
+// The BIP 341 signature message of a committee input, extended with the leaf hash and code
+// separator position of BIP 342. The committee signs its "TapSighash" tagged hash.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SighashPreimage {
+    pub input_index: usize,
+    pub sighash_type: String,
+    #[serde(with = "crate::serialization::hex_encoded")]
+    pub leaf_script: ScriptBuf,
+    pub leaf_hash: TapLeafHash,
+    pub preimage: String, // hex, including the epoch byte
+    pub sighash: TapSighash,
+}
+
+impl SighashPreimage {
+    pub fn new(
+        tx: &Transaction,
+        prev_outs: &[TxOut],
+        input_index: usize,
+        leaf_script: &ScriptBuf,
+        sighash_type: TapSighashType,
+    ) -> Self {
+        let leaf_hash = TapLeafHash::from_script(leaf_script, LeafVersion::TapScript);
+        let mut preimage = vec![];
+        SighashCache::new(tx)
+            .taproot_encode_signing_data_to(
+                &mut preimage,
+                input_index,
+                &Prevouts::All(prev_outs),
+                None,
+                Some((leaf_hash, NO_CODE_SEPARATOR)),
+                sighash_type,
+            )
+            .expect("Failed to construct sighash preimage");
+
+        Self {
+            input_index,
+            sighash_type: sighash_type.to_string(),
+            leaf_script: leaf_script.clone(),
+            leaf_hash,
+            sighash: TapSighash::hash(&preimage),
+            preimage: preimage.to_lower_hex_string(),
+        }
+    }
+
+    // Whether the sighash is the tagged hash of the preimage, as an external signer computes it
+    pub fn is_consistent(&self) -> bool {
+        Vec::<u8>::from_hex(&self.preimage)
+            .is_ok_and(|preimage| TapSighash::hash(&preimage) == self.sighash)
+    }
+}
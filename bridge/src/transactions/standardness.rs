@@ -0,0 +1,245 @@
+use std::fmt;
+
+use bitcoin::{transaction::Version, Amount, ScriptBuf, Sequence, Transaction, TxOut};
+
+use super::{base::BaseTransaction, pre_signed::PreSignedTransaction};
+
+// Relay policy limits of Bitcoin Core. Transactions breaking them are valid by consensus, but
+// nodes do not relay them, so a graph relying on one can only be settled through a miner.
+
+// MAX_STANDARD_TX_SIGOPS_COST, a fifth of the block limit
+pub const MAX_STANDARD_TX_SIGOPS_COST: usize = 16_000;
+// MAX_OP_RETURN_RELAY, the whole script including the OP_RETURN opcode and the push
+pub const MAX_OP_RETURN_RELAY: usize = 83;
+// MAX_STANDARD_P2WSH_SCRIPT_SIZE
+pub const MAX_STANDARD_P2WSH_SCRIPT_SIZE: usize = 3_600;
+// Version 3 opts into TRUC relay, whose size and package topology limits graph transactions are
+// not designed for
+pub const MAX_STANDARD_TX_VERSION: Version = Version::TWO;
+
+// Legacy and segwit v0 sigops are weighted by this factor in the sigop cost, witness sigops
+// count once. Tapscript sigops are budgeted by the witness size and are not part of the cost.
+pub const WITNESS_SCALE_FACTOR: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandardnessViolationKind {
+    TooManySigops {
+        cost: usize,
+    },
+    NonStandardOutputScript {
+        output_index: usize,
+        script: ScriptBuf,
+    },
+    DustOutput {
+        output_index: usize,
+        amount: Amount,
+        min: Amount,
+    },
+    OversizedOpReturn {
+        output_index: usize,
+        size: usize,
+    },
+    MultipleOpReturns(usize), // usize: number of OP_RETURN outputs
+    OversizedWitnessScript {
+        input_index: usize,
+        size: usize,
+    },
+    NonStandardVersion(Version),
+    // The locktime is set, but every input opts out of it with a final sequence
+    LockTimeNotEnforced,
+    // Relative timelocks (BIP 68) are only enforced from version 2 on
+    RelativeLockTimeNotEnforced {
+        input_index: usize,
+    },
+}
+
+impl fmt::Display for StandardnessViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StandardnessViolationKind::TooManySigops { cost } => write!(
+                f,
+                "sigop cost {cost} exceeds {MAX_STANDARD_TX_SIGOPS_COST}"
+            ),
+            StandardnessViolationKind::NonStandardOutputScript {
+                output_index,
+                script,
+            } => write!(
+                f,
+                "output {output_index} has a non-standard script {}",
+                script.to_hex_string()
+            ),
+            StandardnessViolationKind::DustOutput {
+                output_index,
+                amount,
+                min,
+            } => write!(
+                f,
+                "output {output_index} of {amount} is below the dust limit of {min}"
+            ),
+            StandardnessViolationKind::OversizedOpReturn { output_index, size } => write!(
+                f,
+                "OP_RETURN output {output_index} is {size} bytes, at most {MAX_OP_RETURN_RELAY} are relayed"
+            ),
+            StandardnessViolationKind::MultipleOpReturns(count) => {
+                write!(f, "{count} OP_RETURN outputs, at most one is relayed")
+            }
+            StandardnessViolationKind::OversizedWitnessScript { input_index, size } => write!(
+                f,
+                "input {input_index} spends a {size} byte witness script, at most {MAX_STANDARD_P2WSH_SCRIPT_SIZE} are relayed"
+            ),
+            StandardnessViolationKind::NonStandardVersion(version) => write!(
+                f,
+                "version {} is not standard, expected 1 to {}",
+                version.0, MAX_STANDARD_TX_VERSION.0
+            ),
+            StandardnessViolationKind::LockTimeNotEnforced => write!(
+                f,
+                "locktime is set but every input has a final sequence"
+            ),
+            StandardnessViolationKind::RelativeLockTimeNotEnforced { input_index } => write!(
+                f,
+                "input {input_index} has a relative timelock that version 1 does not enforce"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandardnessViolation {
+    pub transaction: &'static str,
+    pub kind: StandardnessViolationKind,
+}
+
+impl fmt::Display for StandardnessViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.transaction, self.kind)
+    }
+}
+
+// Checks a transaction as it is templated in a graph, before any witness is attached. Every
+// violation is reported rather than the first, so a protocol change is reviewed in one go instead
+// of being rejected input by input at broadcast time.
+pub fn lint_transaction(
+    transaction: &(impl BaseTransaction + PreSignedTransaction),
+) -> Vec<StandardnessViolation> {
+    lint_standardness(
+        transaction.name(),
+        transaction.tx(),
+        transaction.prev_outs(),
+        transaction.prev_scripts(),
+    )
+}
+
+// `prev_scripts` holds the witness script of each input spending a P2WSH output, see
+// `PreSignedTransaction::prev_scripts`
+pub fn lint_standardness(
+    name: &'static str,
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    prev_scripts: &[ScriptBuf],
+) -> Vec<StandardnessViolation> {
+    let mut kinds = Vec::new();
+
+    if tx.version.0 < Version::ONE.0 || tx.version.0 > MAX_STANDARD_TX_VERSION.0 {
+        kinds.push(StandardnessViolationKind::NonStandardVersion(tx.version));
+    }
+    if tx.lock_time.to_consensus_u32() != 0
+        && tx.input.iter().all(|input| input.sequence == Sequence::MAX)
+    {
+        kinds.push(StandardnessViolationKind::LockTimeNotEnforced);
+    }
+    if tx.version.0 < Version::TWO.0 {
+        kinds.extend(
+            tx.input
+                .iter()
+                .enumerate()
+                .filter(|(_, input)| input.sequence.is_relative_lock_time())
+                .map(
+                    |(input_index, _)| StandardnessViolationKind::RelativeLockTimeNotEnforced {
+                        input_index,
+                    },
+                ),
+        );
+    }
+
+    let mut op_returns = 0;
+    for (output_index, output) in tx.output.iter().enumerate() {
+        let script = &output.script_pubkey;
+        if script.is_op_return() {
+            op_returns += 1;
+            if script.len() > MAX_OP_RETURN_RELAY {
+                kinds.push(StandardnessViolationKind::OversizedOpReturn {
+                    output_index,
+                    size: script.len(),
+                });
+            }
+            // Data carrier outputs may carry no value
+            continue;
+        }
+        if !is_standard_output_script(script) {
+            kinds.push(StandardnessViolationKind::NonStandardOutputScript {
+                output_index,
+                script: script.clone(),
+            });
+        }
+        let min = script.minimal_non_dust();
+        if output.value < min {
+            kinds.push(StandardnessViolationKind::DustOutput {
+                output_index,
+                amount: output.value,
+                min,
+            });
+        }
+    }
+    if op_returns > 1 {
+        kinds.push(StandardnessViolationKind::MultipleOpReturns(op_returns));
+    }
+
+    let mut sigop_cost = tx
+        .output
+        .iter()
+        .map(|output| output.script_pubkey.count_sigops_legacy() * WITNESS_SCALE_FACTOR)
+        .sum::<usize>();
+    for (input_index, input) in tx.input.iter().enumerate() {
+        sigop_cost += input.script_sig.count_sigops_legacy() * WITNESS_SCALE_FACTOR;
+
+        let Some(prev_out) = prev_outs.get(input_index) else {
+            continue;
+        };
+        if prev_out.script_pubkey.is_p2wpkh() {
+            sigop_cost += 1;
+        } else if let (true, Some(witness_script)) = (
+            prev_out.script_pubkey.is_p2wsh(),
+            prev_scripts.get(input_index),
+        ) {
+            sigop_cost += witness_script.count_sigops();
+            if witness_script.len() > MAX_STANDARD_P2WSH_SCRIPT_SIZE {
+                kinds.push(StandardnessViolationKind::OversizedWitnessScript {
+                    input_index,
+                    size: witness_script.len(),
+                });
+            }
+        }
+    }
+    if sigop_cost > MAX_STANDARD_TX_SIGOPS_COST {
+        kinds.push(StandardnessViolationKind::TooManySigops { cost: sigop_cost });
+    }
+
+    kinds
+        .into_iter()
+        .map(|kind| StandardnessViolation {
+            transaction: name,
+            kind,
+        })
+        .collect()
+}
+
+// Output types relayed by Bitcoin Core, witness programs of future versions included. Bare
+// multisig is left out, it is no longer relayed by default.
+pub fn is_standard_output_script(script: &ScriptBuf) -> bool {
+    script.is_p2pk()
+        || script.is_p2pkh()
+        || script.is_p2sh()
+        || script.is_witness_program()
+        || script.is_op_return()
+}
@@ -1,6 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    Witness,
+    absolute, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, Witness,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
@@ -22,16 +21,21 @@ use bitvm::signatures::signing_winternitz::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct StartTimeTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 
+    #[serde(default, with = "crate::serialization::hex_encoded_option")]
     pub start_time_witness: Option<Witness>,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -82,12 +86,18 @@ impl PreSignedMusig2Transaction for StartTimeTransaction {
 }
 
 impl StartTimeTransaction {
-    pub fn new(context: &OperatorContext, connector_2: &Connector2, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        connector_2: &Connector2,
+        input_0: Input,
+        fee_rate: u64,
+    ) -> Self {
         Self::new_for_validation(
             context.network,
             &context.operator_public_key,
             connector_2,
             input_0,
+            fee_rate,
         )
     }
 
@@ -96,11 +106,13 @@ impl StartTimeTransaction {
         operator_public_key: &PublicKey,
         connector_2: &Connector2,
         input_0: Input,
+        fee_rate: u64,
     ) -> Self {
         let input_0_leaf = 0;
         let _input_0 = connector_2.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_START_TIME);
+        let total_output_amount =
+            input_0.amount - Amount::from_sat(relay_fee_at_rate(VSIZE_START_TIME, fee_rate));
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -1,5 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    absolute, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -7,8 +7,12 @@ use std::collections::HashMap;
 
 use super::{
     super::{
-        connectors::{base::*, connector_1::Connector1, connector_2::Connector2},
+        connectors::{
+            base::*, connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
+        },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::{Error, ValidationError},
+        rewards::validate_reward_output,
         scripts::*,
     },
     base::*,
@@ -18,15 +22,19 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct StartTimeTimeoutTransaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
     reward_output_amount: Amount,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -72,27 +80,49 @@ impl PreSignedMusig2Transaction for StartTimeTimeoutTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> {
-        vec![0, 1]
+        let mut inputs = vec![0, 1];
+        inputs.extend(self.collateral_input_index());
+        inputs
+    }
+    fn verifier_sighash_type(&self, input_index: usize) -> TapSighashType {
+        match input_index {
+            0 => TapSighashType::Single,
+            _ => TapSighashType::None,
+        }
     }
 }
 
 impl StartTimeTimeoutTransaction {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         context: &OperatorContext,
+        connector_0: &Connector0,
         connector_1: &Connector1,
         connector_2: &Connector2,
         input_0: Input,
         input_1: Input,
+        collateral_input: Option<Input>,
     ) -> Self {
-        Self::new_for_validation(context.network, connector_1, connector_2, input_0, input_1)
+        Self::new_for_validation(
+            context.network,
+            connector_0,
+            connector_1,
+            connector_2,
+            input_0,
+            input_1,
+            collateral_input,
+        )
     }
 
+    // The operator collateral of kick-off 1 is slashed along with the connector 1 and 2 funds
     pub fn new_for_validation(
         network: Network,
+        connector_0: &Connector0,
         connector_1: &Connector1,
         connector_2: &Connector2,
         input_0: Input,
         input_1: Input,
+        collateral_input: Option<Input>,
     ) -> Self {
         let input_0_leaf = 1;
         let _input_0 = connector_2.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -100,8 +130,14 @@ impl StartTimeTimeoutTransaction {
         let input_1_leaf = 2;
         let _input_1 = connector_1.generate_taproot_leaf_tx_in(input_1_leaf, &input_1);
 
-        let total_output_amount =
-            input_0.amount + input_1.amount - Amount::from_sat(MIN_RELAY_FEE_START_TIME_TIMEOUT);
+        let input_2_leaf = 0;
+        let (collateral_amount, collateral_fee) = match &collateral_input {
+            Some(input_2) => (input_2.amount, MIN_RELAY_FEE_COLLATERAL_INPUT),
+            None => (Amount::ZERO, 0),
+        };
+
+        let total_output_amount = input_0.amount + input_1.amount + collateral_amount
+            - Amount::from_sat(MIN_RELAY_FEE_START_TIME_TIMEOUT + collateral_fee);
 
         // Output[0]: value=V*2%*95% to burn
         let _output_0 = TxOut {
@@ -115,27 +151,40 @@ impl StartTimeTimeoutTransaction {
             script_pubkey: ScriptBuf::default(),
         };
 
+        let mut inputs = vec![_input_0, _input_1];
+        let mut prev_outs = vec![
+            TxOut {
+                value: input_0.amount,
+                script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
+            },
+            TxOut {
+                value: input_1.amount,
+                script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
+            },
+        ];
+        let mut prev_scripts = vec![
+            connector_2.generate_taproot_leaf_script(input_0_leaf),
+            connector_1.generate_taproot_leaf_script(input_1_leaf),
+        ];
+
+        if let Some(input_2) = collateral_input {
+            inputs.push(connector_0.generate_taproot_leaf_tx_in(input_2_leaf, &input_2));
+            prev_outs.push(TxOut {
+                value: input_2.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_0.generate_taproot_leaf_script(input_2_leaf));
+        }
+
         StartTimeTimeoutTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0, _input_1],
+                input: inputs,
                 output: vec![_output_0, _output_1],
             },
-            prev_outs: vec![
-                TxOut {
-                    value: input_0.amount,
-                    script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_1.amount,
-                    script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
-                },
-            ],
-            prev_scripts: vec![
-                connector_2.generate_taproot_leaf_script(input_0_leaf),
-                connector_1.generate_taproot_leaf_script(input_1_leaf),
-            ],
+            prev_outs,
+            prev_scripts,
             reward_output_amount,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
@@ -148,7 +197,7 @@ impl StartTimeTimeoutTransaction {
         context: &VerifierContext,
         connector_2: &Connector2,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -156,12 +205,14 @@ impl StartTimeTimeoutTransaction {
             input_index,
             TapSighashType::Single,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_2);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_2: &Connector2) {
@@ -180,7 +231,7 @@ impl StartTimeTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 1;
         pre_sign_musig2_taproot_input(
             self,
@@ -188,12 +239,14 @@ impl StartTimeTimeoutTransaction {
             input_index,
             TapSighashType::None,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_1(context, connector_1);
         }
+
+        Ok(())
     }
 
     fn finalize_input_1(&mut self, context: &dyn BaseContext, connector_1: &Connector1) {
@@ -207,23 +260,78 @@ impl StartTimeTimeoutTransaction {
         );
     }
 
+    fn collateral_input_index(&self) -> Option<usize> {
+        (self.tx.input.len() > 2).then_some(2)
+    }
+
+    // Signed with SIGHASH_NONE, input 0 commits to the burn output, which includes the share of
+    // the collateral
+    fn sign_input_2(
+        &mut self,
+        context: &VerifierContext,
+        connector_0: &Connector0,
+        secret_nonce: &SecNonce,
+    ) -> Result<(), Error> {
+        let input_index = 2;
+        pre_sign_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            secret_nonce,
+        )?;
+
+        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
+            self.finalize_input_2(context, connector_0);
+        }
+
+        Ok(())
+    }
+
+    fn finalize_input_2(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+        let input_index = 2;
+        finalize_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::None,
+            connector_0.generate_taproot_spend_info(),
+        );
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
+        connector_0: &Connector0,
         connector_1: &Connector1,
         connector_2: &Connector2,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_2, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_2, &secret_nonces[&input_index])?;
 
         let input_index = 1;
-        self.sign_input_1(context, connector_1, &secret_nonces[&input_index]);
+        self.sign_input_1(context, connector_1, &secret_nonces[&input_index])?;
+
+        if let Some(input_index) = self.collateral_input_index() {
+            self.sign_input_2(context, connector_0, &secret_nonces[&input_index])?;
+        }
+
+        Ok(())
     }
 
-    pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
+    // Sets the reward output, which is not signed by the committee
+    pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) -> Result<(), ValidationError> {
         let output_index = 1;
-        self.tx.output[output_index].script_pubkey = output_script_pubkey;
+        let output = TxOut {
+            value: self.tx.output[output_index].value,
+            script_pubkey: output_script_pubkey,
+        };
+        validate_reward_output(&output)?;
+        self.tx.output[output_index] = output;
+
+        Ok(())
     }
 
     pub fn merge(&mut self, burn: &StartTimeTimeoutTransaction) {
@@ -1,6 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut,
+    absolute, Amount, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -13,7 +12,7 @@ use super::{
             connector_b::ConnectorB,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        scripts::*,
+        error::Error,
     },
     base::*,
     pre_signed::*,
@@ -22,14 +21,18 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Take1Transaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -75,7 +78,10 @@ impl PreSignedMusig2Transaction for Take1Transaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> {
-        vec![0, 3]
+        match self.collateral_input_index() {
+            Some(index) => vec![0, 3, index],
+            None => vec![0, 3],
+        }
     }
 }
 
@@ -91,10 +97,11 @@ impl Take1Transaction {
         input_1: Input,
         input_2: Input,
         input_3: Input,
+        collateral_input: Option<Input>,
+        fee_rate: u64,
     ) -> Self {
         let mut this = Self::new_for_validation(
-            context.network,
-            &context.operator_public_key,
+            &context.reward_script,
             connector_0,
             connector_3,
             connector_a,
@@ -103,18 +110,18 @@ impl Take1Transaction {
             input_1,
             input_2,
             input_3,
+            collateral_input,
+            fee_rate,
         );
 
-        this.sign_input_1(context, connector_a);
-        this.sign_input_2(context);
+        this.sign_operator_inputs(context, connector_a);
 
         this
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_for_validation(
-        network: Network,
-        operator_public_key: &PublicKey,
+        reward_script: &ScriptBuf,
         connector_0: &Connector0,
         connector_3: &Connector3,
         connector_a: &ConnectorA,
@@ -123,6 +130,8 @@ impl Take1Transaction {
         input_1: Input,
         input_2: Input,
         input_3: Input,
+        collateral_input: Option<Input>,
+        fee_rate: u64,
     ) -> Self {
         let input_0_leaf = 0;
         let _input_0 = connector_0.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -135,46 +144,69 @@ impl Take1Transaction {
         let input_3_leaf = 0;
         let _input_3 = connector_b.generate_taproot_leaf_tx_in(input_3_leaf, &input_3);
 
-        let total_output_amount = input_0.amount + input_1.amount + input_2.amount + input_3.amount
-            - Amount::from_sat(MIN_RELAY_FEE_TAKE_1);
+        // The operator collateral of kick-off 1 is returned along with the peg-in
+        let input_4_leaf = 0;
+        let (collateral_amount, collateral_fee) = match &collateral_input {
+            Some(input_4) => (
+                input_4.amount,
+                relay_fee_at_rate(VSIZE_COLLATERAL_INPUT, fee_rate),
+            ),
+            None => (Amount::ZERO, 0),
+        };
+
+        let total_output_amount =
+            input_0.amount + input_1.amount + input_2.amount + input_3.amount + collateral_amount
+                - Amount::from_sat(relay_fee_at_rate(VSIZE_TAKE_1, fee_rate) + collateral_fee);
 
         let _output_0 = TxOut {
             value: total_output_amount,
-            script_pubkey: generate_pay_to_pubkey_script_address(network, operator_public_key)
-                .script_pubkey(),
+            script_pubkey: reward_script.clone(),
         };
 
+        let mut inputs = vec![_input_0, _input_1, _input_2, _input_3];
+        let mut prev_outs = vec![
+            TxOut {
+                value: input_0.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            },
+            TxOut {
+                value: input_1.amount,
+                script_pubkey: connector_a.generate_taproot_address().script_pubkey(),
+            },
+            TxOut {
+                value: input_2.amount,
+                script_pubkey: connector_3.generate_address().script_pubkey(),
+            },
+            TxOut {
+                value: input_3.amount,
+                script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
+            },
+        ];
+        let mut prev_scripts = vec![
+            connector_0.generate_taproot_leaf_script(input_0_leaf),
+            connector_a.generate_taproot_leaf_script(input_1_leaf),
+            connector_3.generate_script(),
+            connector_b.generate_taproot_leaf_script(input_3_leaf),
+        ];
+
+        if let Some(input_4) = collateral_input {
+            inputs.push(connector_0.generate_taproot_leaf_tx_in(input_4_leaf, &input_4));
+            prev_outs.push(TxOut {
+                value: input_4.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_0.generate_taproot_leaf_script(input_4_leaf));
+        }
+
         Take1Transaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0, _input_1, _input_2, _input_3],
+                input: inputs,
                 output: vec![_output_0],
             },
-            prev_outs: vec![
-                TxOut {
-                    value: input_0.amount,
-                    script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_1.amount,
-                    script_pubkey: connector_a.generate_taproot_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_2.amount,
-                    script_pubkey: connector_3.generate_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_3.amount,
-                    script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
-                },
-            ],
-            prev_scripts: vec![
-                connector_0.generate_taproot_leaf_script(input_0_leaf),
-                connector_a.generate_taproot_leaf_script(input_1_leaf),
-                connector_3.generate_script(),
-                connector_b.generate_taproot_leaf_script(input_3_leaf),
-            ],
+            prev_outs,
+            prev_scripts,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
@@ -186,7 +218,7 @@ impl Take1Transaction {
         context: &VerifierContext,
         connector_0: &Connector0,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -194,12 +226,14 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_0);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
@@ -213,6 +247,12 @@ impl Take1Transaction {
         );
     }
 
+    // Signatures of the operator made when the graph is created
+    pub fn sign_operator_inputs(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
+        self.sign_input_1(context, connector_a);
+        self.sign_input_2(context);
+    }
+
     fn sign_input_1(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
         let input_index = 1;
         pre_sign_taproot_input_default(
@@ -239,7 +279,7 @@ impl Take1Transaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 3;
         pre_sign_musig2_taproot_input(
             self,
@@ -247,12 +287,14 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_3(context, connector_b);
         }
+
+        Ok(())
     }
 
     fn finalize_input_3(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
@@ -266,18 +308,62 @@ impl Take1Transaction {
         );
     }
 
+    fn collateral_input_index(&self) -> Option<usize> {
+        (self.tx.input.len() > 4).then_some(4)
+    }
+
+    fn sign_input_4(
+        &mut self,
+        context: &VerifierContext,
+        connector_0: &Connector0,
+        secret_nonce: &SecNonce,
+    ) -> Result<(), Error> {
+        let input_index = 4;
+        pre_sign_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::All,
+            secret_nonce,
+        )?;
+
+        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
+            self.finalize_input_4(context, connector_0);
+        }
+
+        Ok(())
+    }
+
+    fn finalize_input_4(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+        let input_index = 4;
+        finalize_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::All,
+            connector_0.generate_taproot_spend_info(),
+        );
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_0: &Connector0,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_0, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_0, &secret_nonces[&input_index])?;
 
         let input_index = 3;
-        self.sign_input_3(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_3(context, connector_b, &secret_nonces[&input_index])?;
+
+        if let Some(input_index) = self.collateral_input_index() {
+            self.sign_input_4(context, connector_0, &secret_nonces[&input_index])?;
+        }
+
+        Ok(())
     }
 
     pub fn merge(&mut self, take_1: &Take1Transaction) {
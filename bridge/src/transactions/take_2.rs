@@ -1,5 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
+    absolute, Amount, EcdsaSighashType, PublicKey, ScriptBuf, Sequence, TapSighashType,
     Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
@@ -13,8 +13,9 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        scripts::*,
+        error::Error,
     },
+    assert_transactions::assert_final::AssertFinalOutputLayout,
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
@@ -23,14 +24,20 @@ use super::{
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Take2Transaction {
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     tx: Transaction,
-    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+    #[serde(with = "crate::serialization::consensus_hex")]
     prev_outs: Vec<TxOut>,
+    #[serde(with = "crate::serialization::hex_encoded_vec")]
     prev_scripts: Vec<ScriptBuf>,
+    #[serde(default)]
+    assert_final_output_layout: AssertFinalOutputLayout,
 
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
+    #[serde(with = "crate::serialization::input_pubkey_map")]
     musig2_signatures: HashMap<usize, HashMap<PublicKey, PartialSignature>>,
 }
 
@@ -76,7 +83,9 @@ impl PreSignedMusig2Transaction for Take2Transaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> {
-        vec![0, 2]
+        let mut inputs = vec![0, self.connector_5_input_index()];
+        inputs.extend(self.collateral_input_index());
+        inputs
     }
 }
 
@@ -89,13 +98,14 @@ impl Take2Transaction {
         connector_5: &Connector5,
         connector_c: &ConnectorC,
         input_0: Input,
-        input_1: Input,
+        input_1: Option<Input>,
         input_2: Input,
         input_3: Input,
+        collateral_input: Option<Input>,
+        fee_rate: u64,
     ) -> Self {
         let mut this = Self::new_for_validation(
-            context.network,
-            &context.operator_public_key,
+            &context.reward_script,
             connector_0,
             connector_4,
             connector_5,
@@ -104,88 +114,161 @@ impl Take2Transaction {
             input_1,
             input_2,
             input_3,
+            collateral_input,
+            fee_rate,
         );
 
-        this.sign_input_1(context);
+        this.sign_operator_inputs(context);
 
         this
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_for_validation(
-        network: Network,
-        operator_public_key: &PublicKey,
+        reward_script: &ScriptBuf,
         connector_0: &Connector0,
         connector_4: &Connector4,
         connector_5: &Connector5,
         connector_c: &ConnectorC,
         input_0: Input,
-        input_1: Input,
+        input_1: Option<Input>,
         input_2: Input,
         input_3: Input,
+        collateral_input: Option<Input>,
+        fee_rate: u64,
     ) -> Self {
+        // `input_1` (connector 4) is only present if assert final keeps a separate timelock
+        // output, see `AssertFinalOutputLayout`
+        let assert_final_output_layout = match input_1 {
+            Some(_) => AssertFinalOutputLayout::Separate,
+            None => AssertFinalOutputLayout::Consolidated,
+        };
+
         let input_0_leaf = 1;
         let _input_0 = connector_0.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let _input_1 = connector_4.generate_tx_in(&input_1);
-
         let input_2_leaf = 0;
-        let _input_2 = connector_5.generate_taproot_leaf_tx_in(input_2_leaf, &input_2);
+        let mut _input_2 = connector_5.generate_taproot_leaf_tx_in(input_2_leaf, &input_2);
+        if assert_final_output_layout.is_consolidated() {
+            // Without connector 4 the timelock is enforced by the relative locktime of this
+            // input, which is committed to by the n-of-n signature.
+            _input_2.sequence = Sequence(connector_4.num_blocks_timelock);
+        }
 
         let _input_3 = generate_default_tx_in(&input_3);
 
-        let total_output_amount = input_0.amount + input_1.amount + input_2.amount + input_3.amount
-            - Amount::from_sat(MIN_RELAY_FEE_TAKE_2);
+        let (relay_fee, input_1_amount) = match &input_1 {
+            Some(input_1) => (relay_fee_at_rate(VSIZE_TAKE_2, fee_rate), input_1.amount),
+            None => (
+                relay_fee_at_rate(VSIZE_TAKE_2_CONSOLIDATED, fee_rate),
+                Amount::ZERO,
+            ),
+        };
+        // The operator collateral of kick-off 1 is returned along with the peg-in
+        let input_4_leaf = 1;
+        let (collateral_amount, collateral_fee) = match &collateral_input {
+            Some(input_4) => (
+                input_4.amount,
+                relay_fee_at_rate(VSIZE_COLLATERAL_INPUT, fee_rate),
+            ),
+            None => (Amount::ZERO, 0),
+        };
+
+        let total_output_amount =
+            input_0.amount + input_1_amount + input_2.amount + input_3.amount + collateral_amount
+                - Amount::from_sat(relay_fee + collateral_fee);
 
         let _output_0 = TxOut {
             value: total_output_amount,
-            script_pubkey: generate_pay_to_pubkey_script_address(network, operator_public_key)
-                .script_pubkey(),
+            script_pubkey: reward_script.clone(),
         };
 
+        let mut inputs = vec![_input_0];
+        let mut prev_outs = vec![TxOut {
+            value: input_0.amount,
+            script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+        }];
+        let mut prev_scripts = vec![connector_0.generate_taproot_leaf_script(input_0_leaf)];
+
+        if let Some(input_1) = input_1 {
+            inputs.push(connector_4.generate_tx_in(&input_1));
+            prev_outs.push(TxOut {
+                value: input_1.amount,
+                script_pubkey: connector_4.generate_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_4.generate_script());
+        }
+
+        inputs.push(_input_2);
+        prev_outs.push(TxOut {
+            value: input_2.amount,
+            script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
+        });
+        prev_scripts.push(connector_5.generate_taproot_leaf_script(input_2_leaf));
+
+        inputs.push(_input_3);
+        prev_outs.push(TxOut {
+            value: input_3.amount,
+            script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
+        });
+        // No `input_3` script - key spend path is used
+
+        if let Some(input_4) = collateral_input {
+            // Keeps the collateral script at the index of its input
+            prev_scripts.push(ScriptBuf::new());
+
+            inputs.push(connector_0.generate_taproot_leaf_tx_in(input_4_leaf, &input_4));
+            prev_outs.push(TxOut {
+                value: input_4.amount,
+                script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
+            });
+            prev_scripts.push(connector_0.generate_taproot_leaf_script(input_4_leaf));
+        }
+
         Take2Transaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
-                input: vec![_input_0, _input_1, _input_2, _input_3],
+                input: inputs,
                 output: vec![_output_0],
             },
-            prev_outs: vec![
-                TxOut {
-                    value: input_0.amount,
-                    script_pubkey: connector_0.generate_taproot_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_1.amount,
-                    script_pubkey: connector_4.generate_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_2.amount,
-                    script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
-                },
-                TxOut {
-                    value: input_3.amount,
-                    script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
-                },
-            ],
-            prev_scripts: vec![
-                connector_0.generate_taproot_leaf_script(input_0_leaf),
-                connector_4.generate_script(),
-                connector_5.generate_taproot_leaf_script(input_2_leaf),
-                // No `input_3` script - key spend path is used
-            ],
+            prev_outs,
+            prev_scripts,
+            assert_final_output_layout,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
         }
     }
 
+    fn connector_4_input_index(&self) -> Option<usize> {
+        self.assert_final_output_layout
+            .connector_4_vout()
+            .map(|_| 1)
+    }
+
+    fn connector_5_input_index(&self) -> usize {
+        match self.connector_4_input_index() {
+            Some(index) => index + 1,
+            None => 1,
+        }
+    }
+
+    pub fn connector_c_input_index(&self) -> usize {
+        self.connector_5_input_index() + 1
+    }
+
+    fn collateral_input_index(&self) -> Option<usize> {
+        let index = self.connector_c_input_index() + 1;
+        (self.tx.input.len() > index).then_some(index)
+    }
+
     fn sign_input_0(
         &mut self,
         context: &VerifierContext,
         connector_0: &Connector0,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
@@ -193,12 +276,14 @@ impl Take2Transaction {
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_0(context, connector_0);
         }
+
+        Ok(())
     }
 
     fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
@@ -212,6 +297,13 @@ impl Take2Transaction {
         );
     }
 
+    // Signatures of the operator made when the graph is created
+    pub fn sign_operator_inputs(&mut self, context: &OperatorContext) {
+        if self.connector_4_input_index().is_some() {
+            self.sign_input_1(context);
+        }
+    }
+
     fn sign_input_1(&mut self, context: &OperatorContext) {
         let input_index = 1;
         pre_sign_p2wsh_input(
@@ -227,24 +319,26 @@ impl Take2Transaction {
         context: &VerifierContext,
         connector_5: &Connector5,
         secret_nonce: &SecNonce,
-    ) {
-        let input_index = 2;
+    ) -> Result<(), Error> {
+        let input_index = self.connector_5_input_index();
         pre_sign_musig2_taproot_input(
             self,
             context,
             input_index,
             TapSighashType::All,
             secret_nonce,
-        );
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
-        if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
             self.finalize_input_2(context, connector_5);
         }
+
+        Ok(())
     }
 
     fn finalize_input_2(&mut self, context: &dyn BaseContext, connector_5: &Connector5) {
-        let input_index = 2;
+        let input_index = self.connector_5_input_index();
         finalize_musig2_taproot_input(
             self,
             context,
@@ -255,7 +349,7 @@ impl Take2Transaction {
     }
 
     fn sign_input_3(&mut self, context: &OperatorContext, connector_c: &ConnectorC) {
-        let input_index = 3;
+        let input_index = self.connector_c_input_index();
         let prev_outs = &self.prev_outs().clone();
         let merkle_root = connector_c.taproot_merkle_root();
 
@@ -269,24 +363,68 @@ impl Take2Transaction {
         );
     }
 
+    fn sign_input_4(
+        &mut self,
+        context: &VerifierContext,
+        connector_0: &Connector0,
+        secret_nonce: &SecNonce,
+    ) -> Result<(), Error> {
+        let input_index = self.collateral_input_index().unwrap();
+        pre_sign_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::All,
+            secret_nonce,
+        )?;
+
+        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
+        if self.has_all_signatures_for_input(input_index, &context.n_of_n_public_keys) {
+            self.finalize_input_4(context, connector_0);
+        }
+
+        Ok(())
+    }
+
+    fn finalize_input_4(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+        let input_index = self.collateral_input_index().unwrap();
+        finalize_musig2_taproot_input(
+            self,
+            context,
+            input_index,
+            TapSighashType::All,
+            connector_0.generate_taproot_spend_info(),
+        );
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_0: &Connector0,
         connector_5: &Connector5,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_0, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_0, &secret_nonces[&input_index])?;
 
-        let input_index = 2;
-        self.sign_input_2(context, connector_5, &secret_nonces[&input_index]);
+        let input_index = self.connector_5_input_index();
+        self.sign_input_2(context, connector_5, &secret_nonces[&input_index])?;
+
+        if let Some(input_index) = self.collateral_input_index() {
+            self.sign_input_4(context, connector_0, &secret_nonces[&input_index])?;
+        }
+
+        Ok(())
     }
 
     pub fn sign(&mut self, context: &OperatorContext, connector_c: &ConnectorC) {
         self.sign_input_3(context, connector_c);
     }
 
+    pub fn assert_final_output_layout(&self) -> AssertFinalOutputLayout {
+        self.assert_final_output_layout
+    }
+
     pub fn merge(&mut self, take_2: &Take2Transaction) {
         merge_transactions(&mut self.tx, &take_2.tx);
         merge_musig2_nonces_and_signatures(self, take_2);
@@ -0,0 +1,60 @@
+use std::{fmt, str::FromStr};
+
+use bitcoin::{
+    consensus::Encodable,
+    hashes::{sha256, Hash, HashEngine},
+    Witness,
+};
+use serde::{Deserialize, Serialize};
+
+use super::pre_signed::PreSignedTransaction;
+
+// Committee members refer to a pre-signed transaction by its template id before it is signed,
+// e.g. in status output and signing bundles. It hashes the transaction with every witness
+// replaced by an empty placeholder, as the txid does, together with the outputs and scripts its
+// inputs spend, which the txid leaves out. Clients that compute the same id sign the same message.
+
+const TEMPLATE_ID_TAG: &[u8] = b"bitvm-bridge/template-id";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct TemplateId(sha256::Hash);
+
+impl TemplateId {
+    pub fn new(tx: &(impl PreSignedTransaction + ?Sized)) -> Self {
+        let mut unsigned_tx = tx.tx().clone();
+        for input in unsigned_tx.input.iter_mut() {
+            input.witness = Witness::new();
+        }
+
+        let mut engine = sha256::Hash::engine();
+        engine.input(TEMPLATE_ID_TAG);
+        unsigned_tx
+            .consensus_encode(&mut engine)
+            .expect("Hash engines do not fail");
+        for (prev_out, prev_script) in tx.prev_outs().iter().zip(tx.prev_scripts()) {
+            prev_out
+                .consensus_encode(&mut engine)
+                .expect("Hash engines do not fail");
+            prev_script
+                .consensus_encode(&mut engine)
+                .expect("Hash engines do not fail");
+        }
+
+        Self(sha256::Hash::from_engine(engine))
+    }
+}
+
+impl fmt::Display for TemplateId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for TemplateId {
+    type Err = <sha256::Hash as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        sha256::Hash::from_str(s).map(Self)
+    }
+}
@@ -0,0 +1,115 @@
+use bitcoin::{Transaction, TxOut, Witness};
+use bitvm::dry_run_taproot_input;
+
+// Signatures do not cover the witness, so whoever relays a taproot script path spend can attach
+// an annex or re-encode stack elements the script only checks by value. The tx stays valid and
+// keeps its txid, but its wtxid, weight and fee rate change. Graph transactions are scrubbed to
+// the one canonical witness before they are broadcast, so what confirms can be compared to it.
+
+// Stack elements longer than this are never interpreted as numbers
+const MAX_SCRIPT_NUM_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessFix {
+    AnnexRemoved,
+    MinimalNumber(usize), // usize: index of the re-encoded stack element
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrubbedInput {
+    pub input_index: usize,
+    pub fixes: Vec<WitnessFix>,
+}
+
+// Canonicalizes the witnesses of the taproot script path spends in place, `prevouts` holds the
+// output spent by each input. A fix is only kept if the input script still succeeds with it, and
+// inputs whose script fails as they are are left untouched. Returns the inputs that changed.
+pub fn scrub_witnesses(tx: &mut Transaction, prevouts: &[TxOut]) -> Vec<ScrubbedInput> {
+    let mut scrubbed_inputs = Vec::new();
+    for input_index in 0..tx.input.len() {
+        let is_script_path_spend = prevouts
+            .get(input_index)
+            .is_some_and(|prevout| prevout.script_pubkey.is_p2tr())
+            && tx.input[input_index].witness.tapscript().is_some();
+        if !is_script_path_spend || !dry_run_taproot_input(tx, input_index, prevouts).success {
+            continue;
+        }
+
+        let fixes = scrub_input(tx, input_index, prevouts);
+        if !fixes.is_empty() {
+            scrubbed_inputs.push(ScrubbedInput { input_index, fixes });
+        }
+    }
+
+    scrubbed_inputs
+}
+
+fn scrub_input(tx: &mut Transaction, input_index: usize, prevouts: &[TxOut]) -> Vec<WitnessFix> {
+    let mut fixes = Vec::new();
+    let mut elements = tx.input[input_index].witness.to_vec();
+
+    // Nothing is signed with an annex and it is not relayed, it can only be added by others
+    let mut has_annex = tx.input[input_index].witness.taproot_annex().is_some();
+    if has_annex {
+        let mut candidate = elements.clone();
+        candidate.pop();
+        if try_witness(tx, input_index, prevouts, &candidate) {
+            elements = candidate;
+            has_annex = false;
+            fixes.push(WitnessFix::AnnexRemoved);
+        }
+    }
+
+    // The script, the control block and the annex follow the stack elements
+    let num_stack_elements = elements.len() - if has_annex { 3 } else { 2 };
+    for element_index in 0..num_stack_elements {
+        let Some(minimal) = minimal_number(&elements[element_index]) else {
+            continue;
+        };
+        let mut candidate = elements.clone();
+        candidate[element_index] = minimal;
+        if try_witness(tx, input_index, prevouts, &candidate) {
+            elements = candidate;
+            fixes.push(WitnessFix::MinimalNumber(element_index));
+        }
+    }
+
+    tx.input[input_index].witness = Witness::from_slice(&elements);
+    fixes
+}
+
+fn try_witness(
+    tx: &Transaction,
+    input_index: usize,
+    prevouts: &[TxOut],
+    elements: &[Vec<u8>],
+) -> bool {
+    let mut candidate_tx = tx.clone();
+    candidate_tx.input[input_index].witness = Witness::from_slice(elements);
+    dry_run_taproot_input(&candidate_tx, input_index, prevouts).success
+}
+
+// Minimal encoding of a script number, None if the element is not a number or already minimal.
+// Zero is the empty element and only the most significant byte carries the sign.
+pub fn minimal_number(element: &[u8]) -> Option<Vec<u8>> {
+    let (&last, rest) = element.split_last()?;
+    if element.len() > MAX_SCRIPT_NUM_LENGTH
+        || last & 0x7f != 0
+        || rest.last().is_some_and(|byte| byte & 0x80 != 0)
+    {
+        return None;
+    }
+
+    let negative = last & 0x80 != 0;
+    let mut minimal = rest.to_vec();
+    while minimal.last() == Some(&0) {
+        minimal.pop();
+    }
+    match minimal.last().copied() {
+        Some(byte) if byte & 0x80 != 0 => minimal.push(if negative { 0x80 } else { 0 }),
+        Some(byte) if negative => *minimal.last_mut().unwrap() = byte | 0x80,
+        _ => {}
+    }
+
+    Some(minimal)
+}
@@ -27,7 +27,7 @@ pub fn remove_script_and_control_block_from_witness(mut witness: Vec<Vec<u8>>) -
 const LIMB_SIZE: u32 = 30;
 pub type H256 = BigIntImpl<256, LIMB_SIZE>;
 
-fn split_digit(window: u32, index: u32) -> Script {
+pub fn split_digit(window: u32, index: u32) -> Script {
     script! {
         // {v}
         0                           // {v} {A}
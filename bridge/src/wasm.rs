@@ -0,0 +1,44 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    error::{Error, ValidationError},
+    graphs::peg_in_verification::PegInCommitteeParameters,
+};
+
+// Bindings for a browser app to check the peg-in it is served against the committee parameters
+// before the depositor funds it. Parameters and results are passed as json, see
+// `PegInCommitteeParameters` and `VerifiedPegIn`.
+
+fn js_error(err: ValidationError) -> JsError {
+    JsError::new(&Error::Validation(err).to_string())
+}
+
+fn parse_parameters(parameters_json: &str) -> Result<PegInCommitteeParameters, JsError> {
+    serde_json::from_str(parameters_json)
+        .map_err(|err| JsError::new(&format!("Invalid committee parameters: {err}")))
+}
+
+#[wasm_bindgen(js_name = pegInAddresses)]
+pub fn peg_in_addresses(parameters_json: &str) -> Result<String, JsError> {
+    let addresses = parse_parameters(parameters_json)?
+        .addresses()
+        .map_err(js_error)?;
+
+    Ok(serde_json::to_string(&addresses)?)
+}
+
+#[wasm_bindgen(js_name = verifyPegInAddress)]
+pub fn verify_peg_in_address(parameters_json: &str, address: &str) -> Result<(), JsError> {
+    parse_parameters(parameters_json)?
+        .verify_deposit_address(address)
+        .map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = verifyPegInGraph)]
+pub fn verify_peg_in_graph(parameters_json: &str, graph_json: &str) -> Result<String, JsError> {
+    let verified = parse_parameters(parameters_json)?
+        .verify_peg_in_graph(graph_json)
+        .map_err(js_error)?;
+
+    Ok(serde_json::to_string(&verified)?)
+}
@@ -1,7 +1,7 @@
 use bitcoin::{Amount, OutPoint};
 
 use bridge::{
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     transactions::{
         assert_transactions::{
             assert_commit_1::AssertCommit1Transaction, assert_commit_2::AssertCommit2Transaction,
@@ -56,6 +56,7 @@ async fn test_assert_commits_tx_success() {
                 amount: assert_inital_tx.output[idx + vout_base].value,
             })
             .collect(),
+        MIN_RELAY_FEE_RATE,
     );
 
     vout_base += config.assert_commit_connectors_e_1.connectors_num();
@@ -72,6 +73,7 @@ async fn test_assert_commits_tx_success() {
                 amount: assert_inital_tx.output[idx + vout_base].value,
             })
             .collect(),
+        MIN_RELAY_FEE_RATE,
     );
 
     let (witness_for_commit1, witness_for_commit2) =
@@ -2,10 +2,13 @@ use bitcoin::{consensus::encode::serialize_hex, Address, Amount};
 
 use bridge::{
     connectors::base::{P2wshConnector, TaprootConnector},
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     transactions::{
-        assert_transactions::assert_final::AssertFinalTransaction,
-        base::{BaseTransaction, Input, MIN_RELAY_FEE_ASSERT_FINAL},
+        assert_transactions::assert_final::{AssertFinalOutputLayout, AssertFinalTransaction},
+        base::{
+            BaseTransaction, Input, MIN_RELAY_FEE_ASSERT_FINAL,
+            MIN_RELAY_FEE_ASSERT_FINAL_CONSOLIDATED,
+        },
         pre_signed_musig2::PreSignedMusig2Transaction,
     },
 };
@@ -20,6 +23,15 @@ use crate::bridge::{
 
 #[tokio::test]
 async fn test_assert_final_tx_success() {
+    assert_final_tx_success(AssertFinalOutputLayout::Separate).await;
+}
+
+#[tokio::test]
+async fn test_assert_final_tx_consolidated_success() {
+    assert_final_tx_success(AssertFinalOutputLayout::Consolidated).await;
+}
+
+async fn assert_final_tx_success(output_layout: AssertFinalOutputLayout) {
     let config = setup_test_full().await;
     let faucet = Faucet::new(FaucetType::EsploraRegtest);
 
@@ -79,26 +91,50 @@ async fn test_assert_final_tx_success() {
             outpoint: funding_outpoint2,
             amount: input_value2,
         },
+        output_layout,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = assert_final_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = assert_final_tx.push_nonces(&config.verifier_1_context);
 
-    assert_final_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_d,
-        &secret_nonces_0,
-    );
-    assert_final_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_d,
-        &secret_nonces_1,
-    );
+    assert_final_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_d,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    assert_final_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_d,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let tx = assert_final_tx.finalize();
+    let (expected_output_count, relay_fee) = match output_layout {
+        AssertFinalOutputLayout::Separate => (3, MIN_RELAY_FEE_ASSERT_FINAL),
+        AssertFinalOutputLayout::Consolidated => (2, MIN_RELAY_FEE_ASSERT_FINAL_CONSOLIDATED),
+    };
+    assert_eq!(tx.output.len(), expected_output_count);
+    assert_eq!(
+        tx.output[output_layout.connector_5_vout()].script_pubkey,
+        config
+            .connector_5
+            .generate_taproot_address()
+            .script_pubkey()
+    );
+    assert_eq!(
+        tx.output[output_layout.connector_c_vout()].script_pubkey,
+        config
+            .connector_c
+            .generate_taproot_address()
+            .script_pubkey()
+    );
     check_tx_output_sum(
-        reward_amount + assert_commit1_dust_amount + assert_commit2_dust_amount
-            - MIN_RELAY_FEE_ASSERT_FINAL,
+        reward_amount + assert_commit1_dust_amount + assert_commit2_dust_amount - relay_fee,
         &tx,
     );
     let result = config.client_0.esplora.broadcast(&tx).await;
@@ -0,0 +1,125 @@
+use bridge::{
+    connectors::connector_c::validate_commitment_witnesses,
+    error::{ChunkerError, Error, GraphError},
+    serialization::{deserialize, serialize},
+    transactions::assert_transactions::utils::{
+        sign_assert_tx_with_committed_values, sign_assert_tx_with_groth16_proof,
+        CommittedIntermediateValues, ASSERT_COMMIT_1_INTERMEDIATE_VALUES,
+    },
+};
+
+use crate::bridge::setup::setup_test;
+
+#[tokio::test]
+async fn test_reassert_commits_identical_values() {
+    let config = setup_test().await;
+
+    let mut committed_values = None;
+    let (witness_for_commit1, witness_for_commit2) = sign_assert_tx_with_committed_values(
+        &config.commitment_secrets,
+        &config.valid_proof,
+        &mut committed_values,
+    )
+    .unwrap();
+    let first_attempt = committed_values.clone().unwrap();
+    assert_eq!(
+        first_attempt,
+        CommittedIntermediateValues::from_witnesses(&witness_for_commit1, &witness_for_commit2)
+    );
+    assert_eq!(
+        first_attempt.values().len(),
+        witness_for_commit1.len() + witness_for_commit2.len()
+    );
+
+    // The values survive the private data file
+    let restored: CommittedIntermediateValues = deserialize(&serialize(&first_attempt));
+    assert_eq!(restored, first_attempt);
+
+    // Re-asserting the same proof signs the same witnesses
+    let mut committed_values = Some(restored);
+    let reassert = sign_assert_tx_with_committed_values(
+        &config.commitment_secrets,
+        &config.valid_proof,
+        &mut committed_values,
+    )
+    .unwrap();
+    assert_eq!(reassert, (witness_for_commit1, witness_for_commit2));
+    assert_eq!(committed_values, Some(first_attempt));
+}
+
+#[tokio::test]
+async fn test_reassert_with_other_proof_is_flagged() {
+    let config = setup_test().await;
+
+    let (valid_commit1, valid_commit2) =
+        sign_assert_tx_with_groth16_proof(&config.commitment_secrets, &config.valid_proof);
+    let valid_values = CommittedIntermediateValues::from_witnesses(&valid_commit1, &valid_commit2);
+    assert!(valid_values.diff(&valid_values).is_empty());
+
+    let (invalid_commit1, invalid_commit2) =
+        sign_assert_tx_with_groth16_proof(&config.commitment_secrets, &config.invalid_proof);
+    let invalid_values =
+        CommittedIntermediateValues::from_witnesses(&invalid_commit1, &invalid_commit2);
+    let mismatches = invalid_values.diff(&valid_values);
+    assert!(!mismatches.is_empty());
+    for mismatch in &mismatches {
+        assert_eq!(
+            mismatch.previous.as_ref(),
+            valid_values.values().get(mismatch.index)
+        );
+        assert_eq!(
+            mismatch.current.as_ref(),
+            invalid_values.values().get(mismatch.index)
+        );
+        assert_eq!(
+            mismatch.assert_commit(),
+            if mismatch.index < ASSERT_COMMIT_1_INTERMEDIATE_VALUES {
+                1
+            } else {
+                2
+            }
+        );
+    }
+
+    // Nothing is signed if the new proof would equivocate on a committed value
+    let mut committed_values = Some(valid_values.clone());
+    let result = sign_assert_tx_with_committed_values(
+        &config.commitment_secrets,
+        &config.invalid_proof,
+        &mut committed_values,
+    );
+    match result {
+        Err(Error::Graph(GraphError::CommittedValuesMismatch(reported))) => {
+            assert_eq!(reported, mismatches)
+        }
+        other => panic!("Expected a committed values mismatch, got {other:?}"),
+    }
+    assert_eq!(committed_values, Some(valid_values));
+}
+
+#[tokio::test]
+async fn test_partial_assert_commits_are_not_parsed() {
+    let config = setup_test().await;
+
+    let (commit1, commit2) =
+        sign_assert_tx_with_groth16_proof(&config.commitment_secrets, &config.valid_proof);
+    assert!(validate_commitment_witnesses(&commit1, &commit2).is_ok());
+
+    // Only assert commit 1 confirmed
+    match validate_commitment_witnesses(&commit1, &[]) {
+        Err(ChunkerError::MissingCommitmentWitness { expected, found }) => {
+            assert_eq!(expected, commit1.len() + commit2.len());
+            assert_eq!(found, commit1.len());
+        }
+        other => panic!("Expected a missing commitment witness, got {other:?}"),
+    }
+
+    let mut truncated = commit2.clone();
+    truncated[0][0].pop();
+    match validate_commitment_witnesses(&commit1, &truncated) {
+        Err(ChunkerError::MalformedCommitmentWitness {
+            commitment_index, ..
+        }) => assert_eq!(commitment_index, commit1.len()),
+        other => panic!("Expected a malformed commitment witness, got {other:?}"),
+    }
+}
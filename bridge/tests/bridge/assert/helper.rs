@@ -2,6 +2,7 @@ use bitcoin::{Amount, Network, Transaction, Txid};
 use bridge::{
     connectors::{base::TaprootConnector, connector_b::ConnectorB, connector_d::ConnectorD},
     contexts::verifier::VerifierContext,
+    graphs::base::MIN_RELAY_FEE_RATE,
     transactions::{
         assert_transactions::{
             assert_initial::AssertInitialTransaction,
@@ -73,13 +74,18 @@ pub async fn create_and_mine_assert_initial_tx(
         assert_commit_connectors_e_1,
         assert_commit_connectors_e_2,
         input,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = assert_initial_tx.push_nonces(verifier_0_context);
     let secret_nonces_1 = assert_initial_tx.push_nonces(verifier_1_context);
 
-    assert_initial_tx.pre_sign(verifier_0_context, connector_b, &secret_nonces_0);
-    assert_initial_tx.pre_sign(verifier_1_context, connector_b, &secret_nonces_1);
+    assert_initial_tx
+        .pre_sign(verifier_0_context, connector_b, &secret_nonces_0)
+        .unwrap();
+    assert_initial_tx
+        .pre_sign(verifier_1_context, connector_b, &secret_nonces_1)
+        .unwrap();
 
     let tx = assert_initial_tx.finalize();
     let tx_id = tx.compute_txid();
@@ -0,0 +1,195 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use bitcoin::{Network, PublicKey};
+use bitvm::signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret};
+
+use bridge::{
+    commitments::CommitmentMessageId,
+    connectors::{connector_e::ConnectorE, connector_f_1::ConnectorF1},
+    contexts::base::generate_keys_from_secret,
+    error::{Error, ValidationError},
+    graphs::{
+        base::MIN_RELAY_FEE_RATE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+    transactions::assert_transactions::{
+        assert_commit_1::AssertCommit1Transaction,
+        utils::{canonical_commitment_order, AssertCommit1ConnectorsE},
+    },
+};
+use serde_json::Value;
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+const OPERATOR_SECRET: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+fn intermediate_value(name: &str) -> CommitmentMessageId {
+    CommitmentMessageId::Groth16IntermediateValues((name.to_string(), 4))
+}
+
+fn connectors_e(names: &[&str]) -> Vec<ConnectorE> {
+    let operator_public_key = operator_public_key();
+    names
+        .iter()
+        .map(|name| {
+            let secret = WinternitzSecret::new(4);
+            ConnectorE::new(
+                Network::Regtest,
+                &operator_public_key,
+                &BTreeMap::from([(intermediate_value(name), WinternitzPublicKey::from(&secret))]),
+            )
+        })
+        .collect()
+}
+
+fn operator_public_key() -> PublicKey {
+    generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET).1
+}
+
+#[test]
+fn test_intermediate_values_are_ordered_numerically() {
+    assert_eq!(
+        canonical_commitment_order(&intermediate_value("2"), &intermediate_value("10")),
+        Ordering::Less
+    );
+    assert_eq!(
+        canonical_commitment_order(&intermediate_value("10"), &intermediate_value("10")),
+        Ordering::Equal
+    );
+}
+
+#[test]
+fn test_connectors_e_are_sorted_canonically() {
+    let connectors = connectors_e(&["10", "2", "0"]);
+
+    let unordered = AssertCommit1ConnectorsE {
+        connectors_e: connectors.clone(),
+    };
+    assert!(!unordered.is_canonically_ordered());
+
+    let ordered = AssertCommit1ConnectorsE::new(connectors);
+    assert!(ordered.is_canonically_ordered());
+    assert_eq!(
+        ordered.input_order(),
+        vec![
+            intermediate_value("0"),
+            intermediate_value("2"),
+            intermediate_value("10")
+        ]
+    );
+}
+
+#[test]
+fn test_assert_commit_tx_records_input_order() {
+    let connectors_e = AssertCommit1ConnectorsE::new(connectors_e(&["1", "0"]));
+    let connector_f_1 = ConnectorF1::new(Network::Regtest, &operator_public_key());
+    let inputs = vec![
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+    ];
+
+    let tx =
+        AssertCommit1Transaction::new(&connectors_e, &connector_f_1, inputs, MIN_RELAY_FEE_RATE);
+    assert_eq!(tx.input_order(), connectors_e.input_order());
+}
+
+#[test]
+fn test_assert_commit_tx_rejects_merge_with_other_input_order() {
+    let connectors_e = AssertCommit1ConnectorsE::new(connectors_e(&["0", "1"]));
+    let connector_f_1 = ConnectorF1::new(Network::Regtest, &operator_public_key());
+    let inputs = vec![
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+    ];
+    let mut tx =
+        AssertCommit1Transaction::new(&connectors_e, &connector_f_1, inputs, MIN_RELAY_FEE_RATE);
+    assert!(tx.merge(&tx.clone()).is_ok());
+
+    let mut json: Value = serde_json::from_str(&serialize(&tx)).unwrap();
+    json["input_order"].as_array_mut().unwrap().swap(0, 1);
+    let reordered: AssertCommit1Transaction = deserialize(&json.to_string());
+    assert!(matches!(
+        tx.merge(&reordered),
+        Err(Error::Validation(
+            ValidationError::AssertCommitInputOrderMismatch { input_index: 0, .. }
+        ))
+    ));
+}
+
+#[test]
+#[should_panic(expected = "connectors e are not in canonical order")]
+fn test_assert_commit_tx_rejects_unordered_connectors() {
+    let connectors_e = AssertCommit1ConnectorsE {
+        connectors_e: connectors_e(&["1", "0"]),
+    };
+    let connector_f_1 = ConnectorF1::new(Network::Regtest, &operator_public_key());
+    let inputs = vec![
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+    ];
+
+    AssertCommit1Transaction::new(&connectors_e, &connector_f_1, inputs, MIN_RELAY_FEE_RATE);
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_reports_input_order_mismatch() {
+    let config = setup_test().await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::StandardMainnet),
+    )
+    .unwrap();
+    assert!(peg_out_graph.validate_assert_commit_input_order().is_ok());
+
+    let json: Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+
+    // A party that ordered the inputs differently
+    let mut reordered_tx = json.clone();
+    reordered_tx["assert_commit_1_transaction"]["input_order"]
+        .as_array_mut()
+        .unwrap()
+        .swap(0, 1);
+    let reordered_tx: PegOutGraph = deserialize(&reordered_tx.to_string());
+    assert!(matches!(
+        reordered_tx.validate_assert_commit_input_order(),
+        Err(Error::Validation(
+            ValidationError::AssertCommitInputOrderMismatch { input_index: 0, .. }
+        ))
+    ));
+
+    // Connectors that are not in canonical order
+    let mut reordered_connectors = json.clone();
+    reordered_connectors["connector_e_2"]["connectors_e"]
+        .as_array_mut()
+        .unwrap()
+        .swap(1, 2);
+    let reordered_connectors: PegOutGraph = deserialize(&reordered_connectors.to_string());
+    assert!(matches!(
+        reordered_connectors.validate_assert_commit_input_order(),
+        Err(Error::Validation(
+            ValidationError::AssertCommitInputOrderMismatch { input_index: 1, .. }
+        ))
+    ));
+
+    // Graphs created before the order was recorded are checked against their connectors only
+    let mut legacy = json;
+    legacy["assert_commit_1_transaction"]
+        .as_object_mut()
+        .unwrap()
+        .remove("input_order");
+    let legacy: PegOutGraph = deserialize(&legacy.to_string());
+    assert!(legacy.validate_assert_commit_input_order().is_ok());
+}
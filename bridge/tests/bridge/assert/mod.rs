@@ -2,4 +2,6 @@ pub mod assert;
 pub mod assert_commits;
 pub mod assert_final;
 pub mod assert_initial;
+pub mod committed_values;
 pub mod helper;
+pub mod input_order;
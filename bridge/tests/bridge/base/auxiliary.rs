@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    absolute, script::PushBytesBuf, transaction, Amount, OutPoint, ScriptBuf, Transaction, TxOut,
+    Txid,
+};
+use bridge::{
+    connectors::base::TaprootConnector,
+    error::{Error, GraphError},
+    graphs::{
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    transactions::{
+        auxiliary::{AuxiliaryConnector, AuxiliaryInput},
+        script_diagnostics::{InputScriptResult, ScriptDiagnostics},
+    },
+};
+
+use crate::bridge::{
+    helper::{seeded_keypair, stub_input, stub_input_with_amount},
+    setup::setup_test,
+};
+
+const CONNECTOR_AMOUNT: u64 = 10_000;
+
+// Donates the connector output to miners
+fn donation_tx(auxiliary_input: &AuxiliaryInput) -> Transaction {
+    Transaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![auxiliary_input.tx_in.clone()],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::new_op_return(PushBytesBuf::new()),
+        }],
+    }
+}
+
+fn spends_successfully(tx: &Transaction, prevout: &TxOut) -> bool {
+    let diagnostics = ScriptDiagnostics::new(tx, &[Some(prevout.clone())]);
+    matches!(diagnostics.inputs[0].result, InputScriptResult::Executed(_))
+        && diagnostics.failed_inputs().next().is_none()
+}
+
+#[tokio::test]
+async fn test_auxiliary_input_spends_connector_output() {
+    let config = setup_test().await;
+    let connector = &config.connector_a;
+    let input = stub_input_with_amount(
+        "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+        Amount::from_sat(CONNECTOR_AMOUNT),
+    );
+    let prevout = TxOut {
+        value: input.amount,
+        script_pubkey: connector.generate_taproot_address().script_pubkey(),
+    };
+    let auxiliary_input = AuxiliaryInput::new(
+        "connector_a",
+        AuxiliaryConnector::Taproot(connector),
+        input,
+        prevout.clone(),
+        1,
+    )
+    .unwrap();
+
+    let mut tx = donation_tx(&auxiliary_input);
+    let signature = auxiliary_input
+        .sign(
+            &tx,
+            0,
+            &[prevout.clone()],
+            &config.operator_context.operator_keypair,
+        )
+        .unwrap();
+    auxiliary_input
+        .finalize(&mut tx, 0, vec![signature])
+        .unwrap();
+    assert!(spends_successfully(&tx, &prevout));
+
+    // Signed by someone the leaf does not accept
+    let mut tx = donation_tx(&auxiliary_input);
+    let stranger = seeded_keypair(1);
+    let signature = auxiliary_input
+        .sign(&tx, 0, &[prevout.clone()], &stranger)
+        .unwrap();
+    auxiliary_input
+        .finalize(&mut tx, 0, vec![signature])
+        .unwrap();
+    assert!(!spends_successfully(&tx, &prevout));
+
+    // The hooks refuse inputs that do not spend the connector output
+    assert!(matches!(
+        auxiliary_input.sign(
+            &tx,
+            1,
+            &[prevout.clone()],
+            &config.operator_context.operator_keypair
+        ),
+        Err(Error::Graph(GraphError::AuxiliaryInputMismatch(1)))
+    ));
+    tx.input[0].previous_output.vout = 1;
+    assert!(matches!(
+        auxiliary_input.finalize(&mut tx, 0, vec![]),
+        Err(Error::Graph(GraphError::AuxiliaryInputMismatch(0)))
+    ));
+
+    assert!(matches!(
+        AuxiliaryInput::new(
+            "connector_a",
+            AuxiliaryConnector::Taproot(connector),
+            input,
+            prevout.clone(),
+            2,
+        ),
+        Err(Error::Graph(GraphError::InvalidLeafIndex {
+            leaf_index: 2,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        AuxiliaryInput::new(
+            "connector_3",
+            AuxiliaryConnector::P2wsh(&config.connector_3),
+            input,
+            prevout,
+            1,
+        ),
+        Err(Error::Graph(GraphError::InvalidLeafIndex {
+            leaf_index: 1,
+            ..
+        }))
+    ));
+}
+
+#[tokio::test]
+async fn test_auxiliary_input_requires_graph_connector_output() {
+    let config = setup_test().await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    // Refused before the chain is queried
+    let outpoint = OutPoint {
+        txid: Txid::from_str("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900")
+            .unwrap(),
+        vout: 0,
+    };
+    let result = peg_out_graph
+        .auxiliary_input(&config.client_0.esplora, outpoint, 0)
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::Graph(GraphError::NotConnectorOutput(refused))) if refused == outpoint
+    ));
+}
@@ -0,0 +1,83 @@
+use bridge::{
+    commitments::CommitmentMessageId,
+    error::{MissingPrereq, MissingPrereqs},
+    graphs::{
+        peg_in::PegInGraph,
+        peg_out::{PegOutGraph, PegOutPresignedTransaction},
+        template::{GraphParameters, GraphTemplate},
+    },
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+#[tokio::test]
+async fn test_missing_signatures_of_unsigned_graph() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::StandardMainnet),
+    )
+    .unwrap();
+    let committee = peg_in_graph.n_of_n_public_keys();
+
+    // Inputs 1 and 2 are signed by the operator when the graph is created
+    let missing = peg_out_graph.missing_signatures(PegOutPresignedTransaction::Take1, committee);
+    let signed_inputs: Vec<usize> = missing
+        .iter()
+        .map(|missing| match missing {
+            MissingPrereq::Signatures {
+                input_index,
+                pending,
+            } => {
+                assert_eq!(pending, committee);
+                *input_index
+            }
+            _ => panic!("Unexpected missing prerequisite: {missing}"),
+        })
+        .collect();
+    assert_eq!(signed_inputs, vec![0, 3]);
+
+    // The connector C input of take 2 is only signed at broadcast time
+    let missing = peg_out_graph.missing_signatures(PegOutPresignedTransaction::Take2, committee);
+    assert_eq!(missing.len(), 2);
+    assert!(missing
+        .iter()
+        .all(|missing| matches!(missing, MissingPrereq::Signatures { .. })));
+
+    // Disprove chain also needs the commitments revealed by start time and kick-off 2
+    let missing =
+        peg_out_graph.missing_signatures(PegOutPresignedTransaction::DisproveChain, committee);
+    assert!(matches!(
+        missing[..],
+        [
+            MissingPrereq::Signatures { input_index: 0, .. },
+            MissingPrereq::CommitmentWitness(CommitmentMessageId::StartTime),
+            MissingPrereq::CommitmentWitness(CommitmentMessageId::SuperblockHash),
+        ]
+    ));
+
+    let missing_prereqs = MissingPrereqs {
+        tx_name: PegOutPresignedTransaction::DisproveChain.to_string(),
+        txid: peg_out_graph.presigned_txid(PegOutPresignedTransaction::DisproveChain),
+        missing,
+    };
+    let message = missing_prereqs.to_string();
+    println!("{message}");
+    assert!(message.contains("is not ready to broadcast"));
+    assert!(message.contains(&format!(
+        "input 0: waiting for signatures from {}",
+        committee[0]
+    )));
+    assert!(message.contains("StartTime commitment witness not revealed yet"));
+}
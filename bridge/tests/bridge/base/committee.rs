@@ -0,0 +1,110 @@
+use bitcoin::{
+    hashes::Hash,
+    secp256k1::{Message, Secp256k1},
+    taproot, Network, XOnlyPublicKey,
+};
+
+use bridge::{
+    contexts::{
+        base::{generate_keys_from_secret, BaseContext},
+        depositor::DepositorContext,
+        verifier::VerifierContext,
+    },
+    error::{Error, TransactionError},
+    graphs::{base::BaseGraph, peg_in::PegInGraph, template::GraphTemplate},
+    transactions::pre_signed::PreSignedTransaction,
+};
+
+use crate::bridge::{
+    helper::stub_input,
+    setup::{DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET},
+};
+
+const VERIFIER_SECRETS: [&str; 5] = [
+    "1111111111111111111111111111111111111111111111111111111111111111",
+    "2222222222222222222222222222222222222222222222222222222222222222",
+    "3333333333333333333333333333333333333333333333333333333333333333",
+    "4444444444444444444444444444444444444444444444444444444444444444",
+    "5555555555555555555555555555555555555555555555555555555555555555",
+];
+const OUTSIDER_SECRET: &str = "6666666666666666666666666666666666666666666666666666666666666666";
+
+fn has_committee_signature(peg_in_graph: &PegInGraph, committee_key: &XOnlyPublicKey) -> bool {
+    let preimage = &peg_in_graph.signing_bundle().transactions[0].sighash_preimages[0];
+    let message = Message::from_digest(preimage.sighash.to_byte_array());
+    let secp = Secp256k1::verification_only();
+    peg_in_graph.peg_in_confirm_transaction_ref().tx().input[preimage.input_index]
+        .witness
+        .iter()
+        .any(|element| {
+            taproot::Signature::from_slice(element).is_ok_and(|signature| {
+                secp.verify_schnorr(&signature.signature, &message, committee_key)
+                    .is_ok()
+            })
+        })
+}
+
+#[test]
+fn test_peg_in_pre_signed_by_five_verifiers() {
+    let network = Network::Regtest;
+    let committee: Vec<_> = VERIFIER_SECRETS
+        .iter()
+        .map(|secret| generate_keys_from_secret(network, secret).1)
+        .collect();
+    let verifiers: Vec<VerifierContext> = VERIFIER_SECRETS
+        .iter()
+        .map(|secret| VerifierContext::new(network, secret, &committee))
+        .collect();
+    let depositor_context = DepositorContext::new(network, DEPOSITOR_SECRET, &committee);
+
+    let mut peg_in_graph = PegInGraph::new(
+        &depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        DEPOSITOR_EVM_ADDRESS,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+
+    // A nonce from a key outside the committee must not end up in the aggregated nonce
+    let outsider = VerifierContext::new(network, OUTSIDER_SECRET, &committee);
+    peg_in_graph.push_verifier_nonces(&outsider);
+
+    let secret_nonces: Vec<_> = verifiers
+        .iter()
+        .map(|verifier| peg_in_graph.push_verifier_nonces(verifier))
+        .collect();
+
+    // Signing order does not matter, the input is finalized with the last partial signature
+    let committee_key = *depositor_context.n_of_n_taproot_public_key();
+    for (verifier, secret_nonces) in verifiers.iter().zip(&secret_nonces).rev() {
+        assert!(!has_committee_signature(&peg_in_graph, &committee_key));
+        peg_in_graph.verifier_sign(verifier, secret_nonces).unwrap();
+    }
+    assert!(has_committee_signature(&peg_in_graph, &committee_key));
+}
+
+#[test]
+fn test_pre_signing_requires_nonces_of_all_verifiers() {
+    let network = Network::Regtest;
+    let committee: Vec<_> = VERIFIER_SECRETS
+        .iter()
+        .map(|secret| generate_keys_from_secret(network, secret).1)
+        .collect();
+    let depositor_context = DepositorContext::new(network, DEPOSITOR_SECRET, &committee);
+
+    let mut peg_in_graph = PegInGraph::new(
+        &depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        DEPOSITOR_EVM_ADDRESS,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+
+    let verifier = VerifierContext::new(network, VERIFIER_SECRETS[0], &committee);
+    let secret_nonces = peg_in_graph.push_verifier_nonces(&verifier);
+    assert!(matches!(
+        peg_in_graph.verifier_sign(&verifier, &secret_nonces),
+        Err(Error::Transaction(TransactionError::NonceMissing(_, 0, public_key)))
+            if public_key == committee[1]
+    ));
+}
@@ -0,0 +1,76 @@
+use bitcoin::Amount;
+
+use bridge::{
+    error::TransactionError,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::{
+        base::Input,
+        cpfp::{cpfp_fee, create_cpfp_transaction},
+    },
+};
+
+use crate::bridge::{helper::stub_input_with_amount, setup::setup_test};
+
+const PARENT_VSIZE: usize = 6000;
+
+// Outputs of the same parent transaction
+fn input(vout: u32, amount: u64) -> Input {
+    let mut input = stub_input_with_amount(
+        "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+        Amount::from_sat(amount),
+    );
+    input.outpoint.vout = vout;
+    input
+}
+
+#[test]
+fn test_cpfp_fee() {
+    let parent_fee = Amount::from_sat(PARENT_VSIZE as u64);
+
+    // The package of 6100 vB pays 10 sat/vB
+    assert_eq!(
+        cpfp_fee(parent_fee, PARENT_VSIZE, 100, 10),
+        Amount::from_sat(61_000 - 6_000)
+    );
+    // A parent already paying the fee rate still needs the child to pay its own relay fee
+    assert_eq!(
+        cpfp_fee(parent_fee * 10, PARENT_VSIZE, 100, 10),
+        Amount::from_sat(100 * MIN_RELAY_FEE_RATE)
+    );
+}
+
+#[tokio::test]
+async fn test_cpfp_transaction_pays_for_package() {
+    let config = setup_test().await;
+    let keypair = &config.operator_context.operator_keypair;
+    let parent_fee = Amount::from_sat(PARENT_VSIZE as u64);
+    let fee_rate = MIN_RELAY_FEE_RATE * 20;
+
+    let inputs = vec![input(0, DUST_AMOUNT), input(1, 200_000)];
+    let child_tx =
+        create_cpfp_transaction(&inputs, keypair, parent_fee, PARENT_VSIZE, fee_rate).unwrap();
+
+    assert_eq!(child_tx.input.len(), 2);
+    assert_eq!(child_tx.input[0].previous_output, inputs[0].outpoint);
+    assert_eq!(
+        child_tx.output[0].script_pubkey,
+        generate_pay_to_pubkey_script_address(
+            config.network,
+            &config.operator_context.operator_public_key
+        )
+        .script_pubkey()
+    );
+
+    let child_fee = Amount::from_sat(DUST_AMOUNT + 200_000) - child_tx.output[0].value;
+    let package_vsize = (PARENT_VSIZE + child_tx.vsize()) as u64;
+    assert!((parent_fee + child_fee).to_sat() >= package_vsize * fee_rate);
+
+    // The dust output alone cannot pay for the package
+    let result = create_cpfp_transaction(&inputs[..1], keypair, parent_fee, PARENT_VSIZE, fee_rate);
+    assert!(matches!(
+        result,
+        Err(TransactionError::CpfpFundsInsufficient { available, .. })
+            if available == Amount::from_sat(DUST_AMOUNT)
+    ));
+}
@@ -0,0 +1,116 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    secp256k1::{Secp256k1, SecretKey},
+    taproot::TaprootBuilder,
+    Address, Network, ScriptBuf, XOnlyPublicKey,
+};
+use bridge::{
+    connectors::{
+        base::{P2wshConnector, TaprootConnector},
+        descriptor::{
+            generate_p2wsh_descriptor, generate_taproot_descriptor, generate_tr_descriptor,
+            ConnectorDescriptor, DescriptorCoverage,
+        },
+    },
+    scripts::generate_pay_to_pubkey_taproot_script,
+};
+use miniscript::{descriptor::checksum::verify_checksum, Descriptor, Miniscript, Tap};
+
+use crate::bridge::setup::setup_test;
+
+fn x_only_public_key(secret: u8) -> XOnlyPublicKey {
+    SecretKey::from_slice(&[secret; 32])
+        .unwrap()
+        .x_only_public_key(&Secp256k1::new())
+        .0
+}
+
+fn assert_describes(descriptor: &ConnectorDescriptor, address: &Address) {
+    assert_eq!(&descriptor.address, address);
+    assert!(verify_checksum(&descriptor.descriptor).is_ok());
+    if descriptor.coverage == DescriptorCoverage::Complete {
+        let parsed = Descriptor::<XOnlyPublicKey>::from_str(&descriptor.descriptor)
+            .map(|descriptor| descriptor.script_pubkey())
+            .or_else(|_| {
+                Descriptor::<bitcoin::PublicKey>::from_str(&descriptor.descriptor)
+                    .map(|descriptor| descriptor.script_pubkey())
+            })
+            .unwrap();
+        assert_eq!(parsed, address.script_pubkey());
+    }
+}
+
+#[tokio::test]
+async fn test_connector_descriptors() {
+    let config = setup_test().await;
+
+    // Both leaves are the same pay to pubkey script
+    let descriptor = generate_taproot_descriptor("connector_0", &config.connector_0);
+    assert_eq!(descriptor.coverage, DescriptorCoverage::Complete);
+    assert!(descriptor.descriptor.starts_with("tr("));
+    assert_describes(&descriptor, &config.connector_0.generate_taproot_address());
+
+    // The deposit leaf carries the inscription, which is not miniscript
+    let descriptor = generate_taproot_descriptor("connector_z", &config.connector_z);
+    assert_eq!(descriptor.coverage, DescriptorCoverage::OutputOnly);
+    assert!(descriptor.descriptor.starts_with("rawtr("));
+    assert_describes(&descriptor, &config.connector_z.generate_taproot_address());
+
+    let connector_f_1 = &config.assert_commit_connectors_f.connector_f_1;
+    let descriptor = generate_p2wsh_descriptor("connector_f_1", connector_f_1);
+    assert_eq!(descriptor.coverage, DescriptorCoverage::Complete);
+    assert!(descriptor.descriptor.starts_with("wsh(pk("));
+    assert_describes(&descriptor, &connector_f_1.generate_address());
+
+    // The timelock script drops the CSV argument, miniscript verifies it
+    let descriptor = generate_p2wsh_descriptor("connector_3", &config.connector_3);
+    assert_eq!(descriptor.coverage, DescriptorCoverage::OutputOnly);
+    assert!(descriptor.descriptor.starts_with("addr("));
+    assert_describes(&descriptor, &config.connector_3.generate_address());
+}
+
+#[test]
+fn test_tr_descriptor_recovers_script_tree() {
+    let leaves: Vec<(u8, ScriptBuf)> = vec![
+        (
+            2,
+            generate_pay_to_pubkey_taproot_script(&x_only_public_key(1)),
+        ),
+        (
+            3,
+            generate_pay_to_pubkey_taproot_script(&x_only_public_key(2)),
+        ),
+        (
+            3,
+            Miniscript::<XOnlyPublicKey, Tap>::from_str(&format!(
+                "and_v(v:pk({}),older(144))",
+                x_only_public_key(3)
+            ))
+            .unwrap()
+            .encode(),
+        ),
+        (
+            1,
+            generate_pay_to_pubkey_taproot_script(&x_only_public_key(4)),
+        ),
+    ];
+    let spend_info = leaves
+        .iter()
+        .fold(TaprootBuilder::new(), |builder, (depth, script)| {
+            builder.add_leaf(*depth, script.clone()).unwrap()
+        })
+        .finalize(&Secp256k1::new(), x_only_public_key(5))
+        .unwrap();
+
+    let descriptor = generate_tr_descriptor(&spend_info).unwrap();
+    let address = Address::p2tr_tweaked(spend_info.output_key(), Network::Regtest);
+    assert_eq!(descriptor.script_pubkey(), address.script_pubkey());
+
+    let Descriptor::Tr(tr) = &descriptor else {
+        panic!("Expected a taproot descriptor");
+    };
+    let mut depths: Vec<u8> = tr.iter_scripts().map(|(depth, _)| depth).collect();
+    depths.sort();
+    assert_eq!(depths, vec![1, 2, 3, 3]);
+}
@@ -0,0 +1,65 @@
+use std::process::{Command, Output};
+
+// Dependencies only the client needs, none of them may be pulled in without the `client` feature
+const CLIENT_DEPENDENCIES: [&str; 5] = ["tokio", "esplora-client", "alloy", "aws-sdk-s3", "clap"];
+
+fn cargo(args: &[&str]) -> Output {
+    Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .output()
+        .expect("Failed to run cargo")
+}
+
+fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_no_default_features_leave_out_client_dependencies() {
+    let output = cargo(&[
+        "tree",
+        "-p",
+        "bridge",
+        "--no-default-features",
+        "--edges",
+        "normal",
+        "--prefix",
+        "none",
+        "--format",
+        "{p}",
+    ]);
+    assert_success(&output);
+
+    let tree = String::from_utf8_lossy(&output.stdout);
+    let packages: Vec<&str> = tree
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    for dependency in CLIENT_DEPENDENCIES {
+        assert!(
+            !packages.contains(&dependency),
+            "{dependency} is built without the client feature"
+        );
+    }
+}
+
+#[test]
+fn test_no_default_features_build() {
+    // The target directory of this test run is locked while the tests run
+    let target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../target/no-default-features");
+    let output = cargo(&[
+        "check",
+        "-p",
+        "bridge",
+        "--no-default-features",
+        "--lib",
+        "--target-dir",
+        target_dir,
+    ]);
+    assert_success(&output);
+}
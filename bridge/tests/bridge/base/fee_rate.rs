@@ -0,0 +1,91 @@
+use std::{collections::HashMap, str::FromStr};
+
+use bitcoin::{Amount, Network, OutPoint, Txid};
+
+use bridge::{
+    client::esplora::fee_rate_from_estimates,
+    graphs::{
+        base::{MIN_RELAY_FEE_RATE, PEG_IN_FEE},
+        funding::required_funding,
+        peg_in::PegInGraph,
+        template::GraphTemplate,
+    },
+    transactions::base::{BaseTransaction, Input},
+};
+
+use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
+
+#[test]
+fn test_fee_rate_from_estimates() {
+    let estimates = HashMap::from([(1, 20.5), (3, 12.1), (6, 8.0), (144, 0.4)]);
+
+    assert_eq!(fee_rate_from_estimates(&estimates, 1), Some(21));
+    assert_eq!(fee_rate_from_estimates(&estimates, 5), Some(13));
+    assert_eq!(fee_rate_from_estimates(&estimates, 6), Some(8));
+    assert_eq!(
+        fee_rate_from_estimates(&estimates, 1008),
+        Some(MIN_RELAY_FEE_RATE)
+    );
+
+    let estimates = HashMap::from([(2, 5.0)]);
+    assert_eq!(fee_rate_from_estimates(&estimates, 1), None);
+}
+
+#[tokio::test]
+async fn test_peg_in_graph_pays_fee_rate() {
+    let config = setup_test().await;
+    let fee_rate = MIN_RELAY_FEE_RATE * 10;
+    let amount = Amount::from_sat(INITIAL_AMOUNT);
+
+    let parameters = GraphTemplate::FastRegtest.parameters();
+    let high_fee_parameters = parameters.with_fee_rate(fee_rate);
+    let required = required_funding(amount, &parameters);
+    let high_fee_required = required_funding(amount, &high_fee_parameters);
+    assert_eq!(
+        required.peg_in_deposit,
+        amount + Amount::from_sat(PEG_IN_FEE)
+    );
+    assert!(high_fee_required.peg_in_deposit > required.peg_in_deposit);
+    assert!(high_fee_required.peg_out_confirm > required.peg_out_confirm);
+
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount: high_fee_required.peg_in_deposit,
+    };
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        input,
+        &config.depositor_evm_address,
+        parameters,
+    )
+    .unwrap();
+    let high_fee_peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        input,
+        &config.depositor_evm_address,
+        high_fee_parameters,
+    )
+    .unwrap();
+    assert_eq!(high_fee_peg_in_graph.fee_rate(), fee_rate);
+
+    // Both graphs spend the same deposit input, the higher fee rate leaves less in the outputs
+    let output_value = |graph: &PegInGraph| graph.peg_in_confirm_transaction.tx().output[0].value;
+    assert!(output_value(&high_fee_peg_in_graph) < output_value(&peg_in_graph));
+
+    // The fee rate is recorded, so the graph is rebuilt identically when validated
+    assert!(high_fee_peg_in_graph.validate().is_ok());
+}
+
+#[test]
+fn test_fee_rate_below_minimum_is_rejected() {
+    let parameters = GraphTemplate::StandardMainnet
+        .parameters()
+        .with_fee_rate(MIN_RELAY_FEE_RATE - 1);
+    assert!(parameters.validate(Network::Regtest, 1).is_err());
+}
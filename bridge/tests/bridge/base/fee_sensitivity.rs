@@ -0,0 +1,87 @@
+use bitcoin::{
+    absolute, transaction::Version, Amount, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+
+use bridge::{
+    graphs::{
+        base::MIN_RELAY_FEE_RATE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    transactions::fee_sensitivity::{fee_sensitivity, fee_sensitivity_warnings},
+};
+
+use crate::bridge::{
+    helper::{random_hex, stub_input},
+    setup::setup_test,
+};
+
+fn tx_out(sats: u64) -> TxOut {
+    TxOut {
+        value: Amount::from_sat(sats),
+        script_pubkey: ScriptBuf::new(),
+    }
+}
+
+fn spend(output_sats: u64) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: stub_input(&random_hex(32)).outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        }],
+        output: vec![tx_out(output_sats)],
+    }
+}
+
+#[tokio::test]
+async fn test_graph_transactions_pay_the_min_relay_fee_rate() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input(&random_hex(32)),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input(&random_hex(32)),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::FastRegtest),
+    )
+    .unwrap();
+
+    let report = peg_out_graph.fee_sensitivity();
+    for sensitivity in &report {
+        println!("{sensitivity}");
+    }
+    // Every pre-signed transaction but the crowdfunded challenge
+    assert_eq!(report.len(), 14);
+    assert!(fee_sensitivity_warnings(&report, MIN_RELAY_FEE_RATE).is_empty());
+}
+
+#[test]
+fn test_fee_sensitivity_of_fixed_fee() {
+    let prev_outs = [tx_out(10_000)];
+
+    let sensitivity = fee_sensitivity("test", &spend(9_000), &prev_outs, 300);
+    assert_eq!(sensitivity.fee, Amount::from_sat(1_000));
+    // 3.33 sat/vB is rounded down, the fee does not cover 4 sat/vB
+    assert_eq!(sensitivity.max_fee_rate, 3);
+
+    let report = [sensitivity];
+    assert!(fee_sensitivity_warnings(&report, 3).is_empty());
+    assert_eq!(fee_sensitivity_warnings(&report, 4).len(), 1);
+
+    // Outputs exceeding the inputs are paid for by inputs added at broadcast time
+    let sensitivity = fee_sensitivity("test", &spend(11_000), &prev_outs, 300);
+    assert_eq!(sensitivity.fee, Amount::ZERO);
+    assert_eq!(sensitivity.max_fee_rate, 0);
+}
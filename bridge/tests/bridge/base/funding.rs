@@ -0,0 +1,213 @@
+use bitcoin::Amount;
+
+use bridge::{
+    error::{ClientError, Error},
+    graphs::{
+        base::{MIN_RELAY_FEE_RATE, PEG_IN_FEE, PEG_OUT_FEE, REWARD_PRECISION},
+        funding::{
+            peg_in_deposit_amount, peg_out_confirm_funding, required_funding,
+            select_deposit_funding,
+        },
+        peg_in::PegInGraph,
+        template::GraphTemplate,
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::{
+        base::{
+            BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
+            MIN_RELAY_FEE_PEG_IN_DEPOSIT_CHANGE, MIN_RELAY_FEE_PEG_IN_DEPOSIT_INPUT,
+            MIN_RELAY_FEE_PEG_OUT,
+        },
+        peg_in_deposit::DepositFunding,
+        pre_signed::PreSignedTransaction,
+    },
+};
+
+use crate::bridge::{
+    helper::stub_input_with_amount,
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_required_funding_matches_graph() {
+    let config = setup_test().await;
+    let amount = Amount::from_sat(INITIAL_AMOUNT);
+    let parameters = GraphTemplate::FastRegtest.parameters();
+
+    let required = required_funding(amount, &parameters);
+    assert_eq!(
+        required.peg_in_deposit,
+        amount + Amount::from_sat(PEG_IN_FEE)
+    );
+    assert_eq!(
+        required.peg_out,
+        amount + Amount::from_sat(MIN_RELAY_FEE_PEG_OUT)
+    );
+    assert_eq!(
+        required.kick_off_1_collateral,
+        parameters.operator_collateral_funding_amount()
+    );
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        depositor_utxo(0, required.peg_in_deposit),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+
+    // The operator funds the peg-out confirm from the output of the deposit actually created
+    let deposit_amount = peg_in_graph.peg_in_deposit_transaction.tx().output[0].value;
+    assert_eq!(
+        peg_out_confirm_funding(deposit_amount, &parameters),
+        required.peg_out_confirm
+    );
+    assert_eq!(
+        required.peg_out_confirm,
+        deposit_amount * parameters.reward_multiplier / REWARD_PRECISION
+            + Amount::from_sat(PEG_OUT_FEE)
+    );
+
+    let dag = peg_in_graph.transaction_dag();
+    let deposit_name = peg_in_graph.peg_in_deposit_transaction.name();
+    assert_eq!(dag.len(), 3);
+    assert_eq!(dag[0], (deposit_name, vec![]));
+    assert!(dag[1..]
+        .iter()
+        .all(|(_, spent)| *spent == vec![deposit_name]));
+}
+
+// Outputs of the same transaction, as held by the depositor wallet
+fn depositor_utxo(vout: u32, amount: Amount) -> Input {
+    let mut input = stub_input_with_amount(
+        "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+        amount,
+    );
+    input.outpoint.vout = vout;
+    input
+}
+
+#[test]
+fn test_select_deposit_funding() {
+    let utxo_amount = Amount::from_sat(INITIAL_AMOUNT);
+    let utxos = [
+        depositor_utxo(0, utxo_amount),
+        depositor_utxo(1, utxo_amount),
+        depositor_utxo(2, utxo_amount),
+    ];
+    let peg_in_amount = Amount::from_sat(INITIAL_AMOUNT);
+
+    // One output cannot pay the fees on top of the amount, two can
+    let funding = select_deposit_funding(&utxos, peg_in_amount, MIN_RELAY_FEE_RATE).unwrap();
+    assert_eq!(funding.inputs, utxos[..2]);
+    assert_eq!(
+        funding.amount,
+        Some(peg_in_deposit_amount(peg_in_amount, MIN_RELAY_FEE_RATE))
+    );
+
+    let required = select_deposit_funding(&utxos, utxo_amount * 3, MIN_RELAY_FEE_RATE).unwrap_err();
+    assert_eq!(
+        required,
+        peg_in_deposit_amount(utxo_amount * 3, MIN_RELAY_FEE_RATE)
+            + Amount::from_sat(
+                MIN_RELAY_FEE_PEG_IN_DEPOSIT + 2 * MIN_RELAY_FEE_PEG_IN_DEPOSIT_INPUT
+            )
+    );
+}
+
+#[tokio::test]
+async fn test_peg_in_deposit_spends_several_inputs_with_change() {
+    let config = setup_test().await;
+    let utxo_amount = Amount::from_sat(INITIAL_AMOUNT);
+    let deposit_amount =
+        peg_in_deposit_amount(Amount::from_sat(INITIAL_AMOUNT), MIN_RELAY_FEE_RATE);
+    let funding = DepositFunding::new(
+        vec![
+            depositor_utxo(0, utxo_amount),
+            depositor_utxo(1, utxo_amount),
+        ],
+        Some(deposit_amount),
+    );
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        funding,
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+
+    let deposit = &peg_in_graph.peg_in_deposit_transaction;
+    let fee = Amount::from_sat(
+        MIN_RELAY_FEE_PEG_IN_DEPOSIT
+            + MIN_RELAY_FEE_PEG_IN_DEPOSIT_INPUT
+            + MIN_RELAY_FEE_PEG_IN_DEPOSIT_CHANGE,
+    );
+    assert_eq!(deposit.tx().input.len(), 2);
+    assert_eq!(deposit.tx().output[0].value, deposit_amount);
+    assert_eq!(deposit.change_vout(), Some(1));
+    assert_eq!(
+        deposit.tx().output[1].value,
+        utxo_amount * 2 - deposit_amount - fee
+    );
+    assert_eq!(
+        deposit.tx().output[1].script_pubkey,
+        generate_pay_to_pubkey_script_address(
+            config.depositor_context.network,
+            &config.depositor_context.depositor_public_key,
+        )
+        .script_pubkey()
+    );
+    assert_eq!(deposit.funding_amount(), deposit_amount + fee);
+
+    // Verifiers rebuild the same deposit from the graph
+    assert_eq!(
+        peg_in_graph
+            .new_for_validation()
+            .unwrap()
+            .peg_in_deposit_transaction
+            .tx()
+            .compute_txid(),
+        deposit.tx().compute_txid()
+    );
+}
+
+#[tokio::test]
+async fn test_peg_in_deposit_rejects_insufficient_funding() {
+    let config = setup_test().await;
+    let utxo_amount = Amount::from_sat(INITIAL_AMOUNT);
+    let build = |funding: DepositFunding| {
+        PegInGraph::new(
+            &config.depositor_context,
+            funding,
+            &config.depositor_evm_address,
+            GraphTemplate::FastRegtest,
+        )
+    };
+    let is_insufficient = |result: Result<PegInGraph, Error>| {
+        matches!(
+            result,
+            Err(Error::Client(
+                ClientError::InsufficientDepositorFunds { .. }
+            ))
+        )
+    };
+
+    assert!(is_insufficient(build(DepositFunding::new(vec![], None))));
+    // Nothing is left to deposit after the fee
+    assert!(is_insufficient(build(
+        depositor_utxo(0, Amount::from_sat(MIN_RELAY_FEE_PEG_IN_DEPOSIT)).into()
+    )));
+
+    // More is requested than the inputs hold after the fee
+    let result = build(DepositFunding::new(
+        vec![depositor_utxo(0, utxo_amount)],
+        Some(utxo_amount),
+    ));
+    assert!(matches!(
+        result,
+        Err(Error::Client(ClientError::InsufficientDepositorFunds { required, available }))
+            if required == utxo_amount + Amount::from_sat(MIN_RELAY_FEE_PEG_IN_DEPOSIT)
+                && available == utxo_amount
+    ));
+}
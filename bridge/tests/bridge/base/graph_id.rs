@@ -0,0 +1,41 @@
+use bridge::{
+    error::ValidationError,
+    graphs::base::{GraphId, PegInGraphId, PegOutGraphId, GRAPH_ID_LENGTH},
+};
+
+#[test]
+fn test_graph_id_parsing() {
+    let graph_id = "ab".repeat(GRAPH_ID_LENGTH / 2);
+
+    let peg_in_graph_id: PegInGraphId = graph_id.parse().unwrap();
+    assert_eq!(peg_in_graph_id, graph_id.to_ascii_uppercase());
+    let peg_out_graph_id: PegOutGraphId = graph_id.parse().unwrap();
+    assert_eq!(peg_out_graph_id, graph_id.to_ascii_uppercase());
+
+    for invalid in [
+        "AB".repeat(GRAPH_ID_LENGTH / 2 - 1),
+        "AB".repeat(GRAPH_ID_LENGTH / 2) + "A",
+        "XY".repeat(GRAPH_ID_LENGTH / 2),
+        String::new(),
+    ] {
+        assert!(matches!(
+            invalid.parse::<PegInGraphId>(),
+            Err(ValidationError::InvalidGraphId(id)) if id == invalid
+        ));
+        assert!(invalid.parse::<PegOutGraphId>().is_err());
+    }
+}
+
+#[test]
+fn test_graph_id_serializes_as_plain_id() {
+    let graph_id: GraphId = "0F".repeat(GRAPH_ID_LENGTH / 2);
+    let peg_out_graph_id: PegOutGraphId = graph_id.parse().unwrap();
+
+    let json = serde_json::to_string(&peg_out_graph_id).unwrap();
+    assert_eq!(json, serde_json::to_string(&graph_id).unwrap());
+    assert_eq!(
+        serde_json::from_str::<PegOutGraphId>(&json).unwrap(),
+        peg_out_graph_id
+    );
+    assert_eq!(GraphId::from(peg_out_graph_id), graph_id);
+}
@@ -1 +1,23 @@
+pub mod auxiliary;
+pub mod broadcast_readiness;
+pub mod committee;
+pub mod cpfp;
+pub mod descriptor;
+pub mod feature_matrix;
+pub mod fee_rate;
+pub mod fee_sensitivity;
+pub mod funding;
+pub mod graph_id;
 pub mod merge;
+pub mod operator_commitments;
+pub mod prelude;
+pub mod presigning_expiry;
+pub mod proof_binding;
+pub mod protocol;
+pub mod retryable_errors;
+pub mod script_budget;
+pub mod script_diagnostics;
+pub mod signing_bundle;
+pub mod stack_usage;
+pub mod standardness;
+pub mod witness_scrubber;
@@ -0,0 +1,169 @@
+use bitcoin::Amount;
+
+use bridge::{
+    commitments::CommitmentMessageId,
+    error::{Error, ValidationError},
+    graphs::{
+        base::{BaseGraph, GRAPH_ID_LENGTH},
+        operator_commitments::{OperatorCommitments, SignedOperatorCommitments},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+};
+
+use crate::bridge::{
+    helper::stub_input,
+    setup::{setup_test, SetupConfig},
+};
+
+fn peg_in_graph(config: &SetupConfig) -> PegInGraph {
+    PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap()
+}
+
+fn operator_commitments(config: &SetupConfig, peg_in_graph: &PegInGraph) -> OperatorCommitments {
+    OperatorCommitments::new(
+        &config.operator_context,
+        peg_in_graph.peg_in_graph_id(),
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        None,
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+}
+
+// Everything but the presigning window, which records the creation time
+fn graph_json_without_window(peg_out_graph: &PegOutGraph) -> serde_json::Value {
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(peg_out_graph)).unwrap();
+    json.as_object_mut().unwrap().remove("presigning_window");
+    json
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_from_operator_commitments() {
+    let config = setup_test().await;
+    let peg_in_graph = peg_in_graph(&config);
+
+    let signed_commitments =
+        operator_commitments(&config, &peg_in_graph).sign(&config.operator_context);
+    assert!(signed_commitments.verify_signature());
+    let signed_commitments =
+        deserialize::<SignedOperatorCommitments>(&serialize(&signed_commitments));
+    assert!(signed_commitments.verify_signature());
+
+    let mut peg_out_graph =
+        PegOutGraph::new_from_operator_commitments(&peg_in_graph, &signed_commitments).unwrap();
+    assert!(!peg_out_graph.has_operator_signatures());
+
+    peg_out_graph
+        .pre_sign_operator_inputs(
+            &config.operator_context,
+            &peg_in_graph,
+            &config.commitment_secrets,
+        )
+        .unwrap();
+    assert!(peg_out_graph.has_operator_signatures());
+
+    // Pre-signing completes the graph the operator would have created itself
+    let operator_peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        signed_commitments.commitments.peg_out_confirm_input,
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+    assert_eq!(peg_out_graph.id(), operator_peg_out_graph.id());
+    assert_eq!(
+        graph_json_without_window(&peg_out_graph),
+        graph_json_without_window(&operator_peg_out_graph)
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_operator_commitments_are_rejected() {
+    let config = setup_test().await;
+    let peg_in_graph = peg_in_graph(&config);
+    let is_invalid_commitments = |result: Result<PegOutGraph, Error>| {
+        matches!(
+            result,
+            Err(Error::Validation(
+                ValidationError::InvalidOperatorCommitments(_)
+            ))
+        )
+    };
+
+    // Changed after signing
+    let mut signed_commitments =
+        operator_commitments(&config, &peg_in_graph).sign(&config.operator_context);
+    signed_commitments.commitments.peg_out_confirm_input.amount += Amount::from_sat(1);
+    assert!(!signed_commitments.verify_signature());
+    assert!(is_invalid_commitments(
+        PegOutGraph::new_from_operator_commitments(&peg_in_graph, &signed_commitments)
+    ));
+
+    // Made for another peg-in graph
+    let mut commitments = operator_commitments(&config, &peg_in_graph);
+    commitments.peg_in_graph_id = "0".repeat(GRAPH_ID_LENGTH).parse().unwrap();
+    assert!(is_invalid_commitments(
+        PegOutGraph::new_from_operator_commitments(
+            &peg_in_graph,
+            &commitments.sign(&config.operator_context)
+        )
+    ));
+
+    // Missing a commitment public key
+    let mut commitments = operator_commitments(&config, &peg_in_graph);
+    commitments
+        .commitment_public_keys
+        .remove(&CommitmentMessageId::StartTime);
+    assert!(is_invalid_commitments(
+        PegOutGraph::new_from_operator_commitments(
+            &peg_in_graph,
+            &commitments.sign(&config.operator_context)
+        )
+    ));
+}
+
+#[tokio::test]
+async fn test_operator_does_not_sign_graph_of_other_secrets() {
+    let config = setup_test().await;
+    let peg_in_graph = peg_in_graph(&config);
+
+    let mut commitments = operator_commitments(&config, &peg_in_graph);
+    let other_secrets = config
+        .operator_context
+        .generate_commitment_secrets("another graph");
+    commitments.commitment_public_keys = OperatorCommitments::new(
+        &config.operator_context,
+        peg_in_graph.peg_in_graph_id(),
+        commitments.peg_out_confirm_input,
+        None,
+        &other_secrets,
+        GraphParameters::default(),
+    )
+    .commitment_public_keys;
+    let mut peg_out_graph = PegOutGraph::new_from_operator_commitments(
+        &peg_in_graph,
+        &commitments.sign(&config.operator_context),
+    )
+    .unwrap();
+
+    let result = peg_out_graph.pre_sign_operator_inputs(
+        &config.operator_context,
+        &peg_in_graph,
+        &config.commitment_secrets,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::Validation(ValidationError::OperatorGraphMismatch(_)))
+    ));
+    assert!(!peg_out_graph.has_operator_signatures());
+}
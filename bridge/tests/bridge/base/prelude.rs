@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+use bitcoin::{key::Keypair, secp256k1::Secp256k1, Amount, Network, OutPoint, PublicKey, Txid};
+// Downstream users only need the prelude to build and sign a graph
+use bridge::prelude::*;
+
+use crate::bridge::setup::DEPOSITOR_EVM_ADDRESS;
+
+fn public_key(secret: &str) -> PublicKey {
+    PublicKey::new(
+        Keypair::from_seckey_str(&Secp256k1::new(), secret)
+            .unwrap()
+            .public_key(),
+    )
+}
+
+#[test]
+fn test_prelude_peg_in_graph() {
+    let network = Network::Regtest;
+    let verifier_secrets = ["1".repeat(64), "2".repeat(64)];
+    let n_of_n_public_keys: Vec<PublicKey> = verifier_secrets
+        .iter()
+        .map(|secret| public_key(secret))
+        .collect();
+    let verifiers: Vec<VerifierContext> = verifier_secrets
+        .iter()
+        .map(|secret| VerifierContext::new(network, secret, &n_of_n_public_keys))
+        .collect();
+    let depositor = DepositorContext::new(network, &"3".repeat(64), &n_of_n_public_keys);
+
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(10_000_000),
+    };
+    let mut peg_in_graph = PegInGraph::new(
+        &depositor,
+        input,
+        DEPOSITOR_EVM_ADDRESS,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let graph_id: GraphId = peg_in_graph.id().clone();
+    assert!(graph_id.parse::<PegInGraphId>().is_ok());
+
+    let secret_nonces: Vec<_> = verifiers
+        .iter()
+        .map(|verifier| peg_in_graph.push_verifier_nonces(verifier))
+        .collect();
+    for (verifier, secret_nonces) in verifiers.iter().zip(&secret_nonces) {
+        peg_in_graph.verifier_sign(verifier, secret_nonces).unwrap();
+    }
+    assert!(peg_in_graph.integrity_issues().is_empty());
+}
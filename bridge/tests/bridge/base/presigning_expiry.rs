@@ -0,0 +1,105 @@
+use bridge::{
+    error::{Error, ValidationError},
+    graphs::{
+        base::{current_unix_time, BaseGraph, PresigningWindow, SECONDS_PER_DAY},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+#[tokio::test]
+async fn test_peg_in_graph_abandoned_after_deadline() {
+    let config = setup_test().await;
+
+    let mut peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let committee = peg_in_graph.n_of_n_public_keys().to_vec();
+    let window = *peg_in_graph.presigning_window();
+    let deadline = window.created_at + SECONDS_PER_DAY;
+    assert_eq!(window.deadline, Some(deadline));
+
+    assert!(!peg_in_graph.is_abandoned(&committee, current_unix_time()));
+    assert!(peg_in_graph.is_abandoned(&committee, deadline));
+
+    // Graphs pre-signed in time stay usable after the deadline
+    let secret_nonces_0 = peg_in_graph.push_verifier_nonces(&config.verifier_0_context);
+    let secret_nonces_1 = peg_in_graph.push_verifier_nonces(&config.verifier_1_context);
+    peg_in_graph
+        .verifier_sign(&config.verifier_0_context, &secret_nonces_0)
+        .unwrap();
+    assert!(peg_in_graph.is_abandoned(&committee, deadline));
+    peg_in_graph
+        .verifier_sign(&config.verifier_1_context, &secret_nonces_1)
+        .unwrap();
+    assert!(!peg_in_graph.is_abandoned(&committee, deadline));
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_presigning_window() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let committee = peg_in_graph.n_of_n_public_keys();
+    let create_peg_out_graph = |parameters: GraphParameters| {
+        PegOutGraph::new(
+            &config.operator_context,
+            &peg_in_graph,
+            stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+            &config.commitment_secrets,
+            parameters,
+        )
+        .unwrap()
+    };
+
+    let peg_out_graph = create_peg_out_graph(GraphParameters::default());
+    let window = *peg_out_graph.presigning_window();
+    assert_eq!(
+        window.deadline,
+        Some(window.created_at + 14 * SECONDS_PER_DAY)
+    );
+    assert!(!peg_out_graph.is_abandoned(committee, current_unix_time()));
+    assert!(peg_out_graph.validate_presigning_window().is_ok());
+
+    // Pushing the deadline back without changing the recorded expiry invalidates the graph
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    json["presigning_window"]["deadline"] =
+        serde_json::Value::from(window.created_at + 60 * SECONDS_PER_DAY);
+    let tampered = deserialize::<PegOutGraph>(&json.to_string());
+    assert!(matches!(
+        tampered.validate_presigning_window(),
+        Err(Error::Validation(
+            ValidationError::PresigningDeadlineMismatch { .. }
+        ))
+    ));
+
+    // Graphs serialized before the window was recorded have no deadline
+    json.as_object_mut().unwrap().remove("presigning_window");
+    let legacy = deserialize::<PegOutGraph>(&json.to_string());
+    assert_eq!(legacy.presigning_window(), &PresigningWindow::default());
+    assert!(!legacy.is_abandoned(committee, u64::MAX));
+    assert!(legacy.validate_presigning_window().is_ok());
+
+    // A zero day expiry abandons the graph right away, no expiry never does
+    let expired =
+        create_peg_out_graph(GraphParameters::default().with_presigning_expiry_days(Some(0)));
+    assert!(expired.is_abandoned(committee, current_unix_time()));
+    let unlimited =
+        create_peg_out_graph(GraphParameters::default().with_presigning_expiry_days(None));
+    assert_eq!(unlimited.presigning_window().deadline, None);
+    assert!(!unlimited.is_abandoned(committee, u64::MAX));
+}
@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, Txid};
+use bridge::{
+    error::ValidationError,
+    proof::get_proof_with_public_inputs,
+    proof_binding::{verify_bound_proof, ExternalProof, PegOutFacts},
+    serialization::{serialize, try_deserialize},
+};
+
+fn txid(first_byte: char) -> Txid {
+    Txid::from_str(&format!(
+        "{first_byte}e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"
+    ))
+    .unwrap()
+}
+
+fn peg_out_facts() -> PegOutFacts {
+    PegOutFacts {
+        peg_in_confirm_txid: txid('0'),
+        kick_off_1_txid: txid('1'),
+        peg_out_txid: txid('2'),
+        amount: Amount::from_sat(100_000),
+        withdrawer_evm_address: "0xDDdDddDdDdddDDddDDddDDDDdDdDDdDDdDDDDDDd".to_string(),
+    }
+}
+
+#[test]
+fn test_external_proof_bound_to_peg_out() {
+    let facts = peg_out_facts();
+    let raw_proof = get_proof_with_public_inputs(&facts.public_inputs());
+    let external_proof: ExternalProof =
+        try_deserialize(&serialize(&ExternalProof::new(&raw_proof))).unwrap();
+
+    let loaded = external_proof.load(&facts, &raw_proof.vk).unwrap();
+    assert_eq!(loaded, raw_proof);
+
+    // EVM addresses are bound regardless of their checksum casing
+    let mut lowercase_address = peg_out_facts();
+    lowercase_address.withdrawer_evm_address = facts.withdrawer_evm_address.to_lowercase();
+    assert!(verify_bound_proof(&raw_proof, &lowercase_address).is_ok());
+
+    let mut other_amount = peg_out_facts();
+    other_amount.amount = Amount::from_sat(100_001);
+    let mut other_peg_out = peg_out_facts();
+    other_peg_out.peg_out_txid = txid('3');
+    for facts in [other_amount, other_peg_out] {
+        assert!(matches!(
+            external_proof.load(&facts, &raw_proof.vk),
+            Err(ValidationError::ProofPublicInputsMismatch { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_external_proof_of_other_verifying_key() {
+    let facts = peg_out_facts();
+    let raw_proof = get_proof_with_public_inputs(&facts.public_inputs());
+    let other_setup = get_proof_with_public_inputs(&facts.public_inputs());
+
+    assert!(matches!(
+        ExternalProof::new(&raw_proof).load(&facts, &other_setup.vk),
+        Err(ValidationError::InvalidExternalProof(_))
+    ));
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use bitcoin::Network;
+
+use bridge::{
+    error::ValidationError,
+    graphs::{
+        base::BaseGraph,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    protocol::{
+        describe, params_hash, protocol_params, ProtocolParamsId, PARAMS_CHANGELOG, PARAMS_VERSION,
+    },
+    serialization::{deserialize, serialize},
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+#[test]
+fn test_params_changelog_is_current() {
+    let latest = PARAMS_CHANGELOG.last().unwrap();
+    assert_eq!(latest.version, PARAMS_VERSION);
+    assert_eq!(
+        latest.params_hash,
+        params_hash(),
+        "Protocol parameters changed, bump PARAMS_VERSION and record the new hash {} in PARAMS_CHANGELOG",
+        params_hash()
+    );
+
+    for (previous, next) in PARAMS_CHANGELOG.iter().zip(PARAMS_CHANGELOG.iter().skip(1)) {
+        assert!(previous.version < next.version);
+        assert_ne!(previous.params_hash, next.params_hash);
+    }
+}
+
+#[test]
+fn test_protocol_params_are_unique() {
+    let params = protocol_params();
+    let names: HashSet<&str> = params.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names.len(), params.len());
+}
+
+#[tokio::test]
+async fn test_graphs_created_under_other_params_are_refused() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    assert_eq!(
+        peg_in_graph.protocol_params(),
+        Some(&ProtocolParamsId::current())
+    );
+    assert!(peg_in_graph.check_protocol_params().is_ok());
+
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::FastRegtest),
+    )
+    .unwrap();
+    assert!(peg_out_graph.check_protocol_params().is_ok());
+
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    json["protocol_params"]["hash"] = serde_json::Value::from("00".repeat(32));
+    let other = deserialize::<PegOutGraph>(&json.to_string());
+    assert!(matches!(
+        other.check_protocol_params(),
+        Err(ValidationError::ProtocolParamsMismatch { found: Some(_), .. })
+    ));
+
+    // Graphs serialized before parameters were recorded cannot be told apart from other versions
+    json.as_object_mut().unwrap().remove("protocol_params");
+    let legacy = deserialize::<PegOutGraph>(&json.to_string());
+    assert_eq!(legacy.protocol_params(), None);
+    assert!(matches!(
+        legacy.check_protocol_params(),
+        Err(ValidationError::ProtocolParamsMismatch { found: None, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_describe_covers_graph_transactions() {
+    let config = setup_test().await;
+    let parameters = GraphParameters::from(GraphTemplate::FastRegtest);
+    let descriptions = describe(&parameters, Network::Regtest);
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        parameters,
+    )
+    .unwrap();
+
+    // Every transaction the graphs build is described, along with the transactions it spends
+    let dag = peg_in_graph
+        .transaction_dag()
+        .into_iter()
+        .chain(peg_out_graph.transaction_dag());
+    for (name, spent) in dag {
+        let description = descriptions
+            .iter()
+            .find(|description| description.name == name)
+            .unwrap_or_else(|| panic!("{name} is not described"));
+        for spent_name in spent {
+            assert!(
+                description.prerequisites.contains(&spent_name),
+                "{name} spends {spent_name}, which is not listed as a prerequisite"
+            );
+        }
+    }
+
+    let kick_off_2 = descriptions
+        .iter()
+        .find(|description| description.matches("kick_off_2"))
+        .unwrap();
+    assert_eq!(kick_off_2.name, "KickOff2");
+    assert_eq!(
+        kick_off_2.timelock.as_ref().unwrap().blocks,
+        parameters
+            .timelocks
+            .for_network(Network::Regtest)
+            .kick_off_2
+    );
+}
@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Txid};
+use bridge::{
+    commitments::CommitmentMessageId,
+    error::{ClientError, Error, GraphError, HeaderChainError, L2Error, NamedTx, ValidationError},
+};
+
+fn txid() -> Txid {
+    Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327").unwrap()
+}
+
+#[test]
+fn test_retryable_errors() {
+    let retryable = [
+        Error::Client(ClientError::DataStoreUnavailable("timeout".to_string())),
+        Error::Client(ClientError::RegtestRpcFailed(
+            "getnewaddress: connection refused".to_string(),
+        )),
+        Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![NamedTx {
+            txid: txid(),
+            name: "Kick Off 1",
+            confirmed: false,
+        }])),
+        Error::L2(L2Error::PegOutBurnNotFound(txid())),
+        Error::HeaderChain(HeaderChainError::InsufficientConfirmations {
+            txid: txid(),
+            required: 6,
+            verified: 2,
+        }),
+    ];
+    for err in retryable {
+        assert!(err.is_retryable(), "{err}");
+    }
+
+    let fatal = [
+        Error::Client(ClientError::PegOutGraphNotFound("graph".to_string())),
+        Error::Client(ClientError::OperatorContextNotDefined),
+        Error::Client(ClientError::CommitmentSecretNotFound(
+            "graph".to_string(),
+            CommitmentMessageId::StartTime,
+        )),
+        Error::Client(ClientError::FundingUtxoNotFound(OutPoint {
+            txid: txid(),
+            vout: 0,
+        })),
+        Error::Graph(GraphError::PegOutAlreadyInitiated),
+        Error::L2(L2Error::PegOutBurnAmountMismatch {
+            expected: Amount::from_sat(1),
+            actual: Amount::from_sat(2),
+        }),
+        Error::Validation(ValidationError::InvalidGraphParameters(
+            "template".to_string(),
+        )),
+    ];
+    for err in fatal {
+        assert!(!err.is_retryable(), "{err}");
+    }
+}
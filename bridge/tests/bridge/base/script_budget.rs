@@ -0,0 +1,26 @@
+use bridge::protocol::{script_budget_report, ScriptBudgetReport, ScriptSizes, SCRIPT_BUDGET};
+
+#[test]
+fn test_scripts_within_budget() {
+    let report = script_budget_report();
+    println!("{report}");
+
+    assert_eq!(report.entries.len(), 4);
+    assert!(report.entries.iter().all(|entry| entry.bytes > 0));
+    assert!(report.exceeded().is_empty(), "{report}");
+}
+
+#[test]
+fn test_script_budget_report_flags_exceeded_sizes() {
+    let sizes = ScriptSizes {
+        connector_c_leaf_bytes: SCRIPT_BUDGET.connector_c_leaf_bytes + 1,
+        ..SCRIPT_BUDGET
+    };
+    let report = ScriptBudgetReport::new(&sizes, &SCRIPT_BUDGET);
+
+    // Sizes equal to their budget are within it
+    let exceeded = report.exceeded();
+    assert_eq!(exceeded.len(), 1);
+    assert_eq!(exceeded[0].name, "Largest connector C leaf");
+    assert!(report.to_string().contains("over budget"));
+}
@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    absolute,
+    opcodes::all::OP_EQUAL,
+    script::Builder,
+    secp256k1::Secp256k1,
+    taproot::{LeafVersion, TaprootBuilder},
+    transaction, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+    XOnlyPublicKey,
+};
+
+use bridge::transactions::script_diagnostics::{
+    is_script_verify_rejection, InputScriptResult, ScriptDiagnostics,
+};
+
+use crate::bridge::helper::NUMS_INTERNAL_KEY;
+
+fn input(vout: u32, witness: Witness) -> TxIn {
+    TxIn {
+        previous_output: OutPoint {
+            txid: Txid::from_str(
+                "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness,
+    }
+}
+
+// Spends a leaf that checks the witness pushes 2
+fn spend(pushed: u8) -> (Transaction, Vec<Option<TxOut>>) {
+    let secp = Secp256k1::new();
+    let script = Builder::new()
+        .push_int(2)
+        .push_opcode(OP_EQUAL)
+        .into_script();
+    let spend_info = TaprootBuilder::new()
+        .add_leaf(0, script.clone())
+        .unwrap()
+        .finalize(&secp, XOnlyPublicKey::from_str(NUMS_INTERNAL_KEY).unwrap())
+        .unwrap();
+    let control_block = spend_info
+        .control_block(&(script.clone(), LeafVersion::TapScript))
+        .unwrap();
+    let prevout = TxOut {
+        value: Amount::from_sat(10_000),
+        script_pubkey: ScriptBuf::new_p2tr_tweaked(spend_info.output_key()),
+    };
+
+    let tx = Transaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![
+            input(
+                0,
+                Witness::from_slice(&[vec![pushed], script.to_bytes(), control_block.serialize()]),
+            ),
+            input(1, Witness::from_slice(&[vec![1u8; 64]])),
+            input(2, Witness::new()),
+        ],
+        output: vec![TxOut {
+            value: Amount::from_sat(9_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    };
+
+    (tx, vec![Some(prevout.clone()), Some(prevout), None])
+}
+
+#[test]
+fn test_script_diagnostics_pinpoint_failing_input() {
+    let (tx, prevouts) = spend(3);
+    let diagnostics = ScriptDiagnostics::new(&tx, &prevouts);
+
+    assert_eq!(diagnostics.txid, tx.compute_txid());
+    assert_eq!(
+        diagnostics
+            .failed_inputs()
+            .map(|input| input.input_index)
+            .collect::<Vec<_>>(),
+        vec![0]
+    );
+    assert!(matches!(
+        diagnostics.inputs[1].result,
+        InputScriptResult::KeyPathSpend
+    ));
+    assert!(matches!(
+        diagnostics.inputs[2].result,
+        InputScriptResult::PrevoutNotFound
+    ));
+
+    let report = diagnostics.to_string();
+    assert!(report.contains("Script execution failed!"));
+    assert!(report.contains(&tx.input[0].previous_output.to_string()));
+}
+
+#[test]
+fn test_script_diagnostics_valid_witness() {
+    let (tx, prevouts) = spend(2);
+    let diagnostics = ScriptDiagnostics::new(&tx, &prevouts);
+
+    assert!(diagnostics.failed_inputs().next().is_none());
+    assert!(matches!(
+        &diagnostics.inputs[0].result,
+        InputScriptResult::Executed(info) if info.success
+    ));
+    assert!(diagnostics
+        .to_string()
+        .contains("the rejection is not caused by them"));
+}
+
+#[test]
+fn test_script_verify_rejection_reasons() {
+    assert!(is_script_verify_rejection(
+        "sendrawtransaction RPC error: {\"code\":-26,\"message\":\"non-mandatory-script-verify-flag (Script failed an OP_EQUALVERIFY operation)\"}"
+    ));
+    assert!(is_script_verify_rejection(
+        "mandatory-script-verify-flag-failed (Invalid Schnorr signature)"
+    ));
+    assert!(!is_script_verify_rejection("non-BIP68-final"));
+}
@@ -0,0 +1,246 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    absolute,
+    hashes::Hash,
+    secp256k1::{Message, Secp256k1},
+    sighash::{Prevouts, SighashCache},
+    taproot::{self, LeafVersion},
+    transaction, Amount, ScriptBuf, TapLeafHash, TapSighashType, Transaction, TxIn, TxOut, Witness,
+};
+
+use bridge::{
+    contexts::base::BaseContext,
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        peg_in::PegInGraph,
+        peg_out::{PegOutGraph, PegOutPresignedTransaction},
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+    transactions::{
+        pre_signed::PreSignedTransaction,
+        signing_bundle::{SigningBundle, SigningBundleTransaction},
+        template_id::TemplateId,
+    },
+};
+use strum::IntoEnumIterator;
+
+use crate::bridge::{
+    helper::stub_input,
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+struct TemplateTransaction {
+    tx: Transaction,
+    prev_outs: Vec<TxOut>,
+    prev_scripts: Vec<ScriptBuf>,
+}
+
+impl PreSignedTransaction for TemplateTransaction {
+    fn tx(&self) -> &Transaction {
+        &self.tx
+    }
+
+    fn tx_mut(&mut self) -> &mut Transaction {
+        &mut self.tx
+    }
+
+    fn prev_outs(&self) -> &Vec<TxOut> {
+        &self.prev_outs
+    }
+
+    fn prev_scripts(&self) -> &Vec<ScriptBuf> {
+        &self.prev_scripts
+    }
+}
+
+fn template_transaction() -> TemplateTransaction {
+    TemplateTransaction {
+        tx: Transaction {
+            version: transaction::Version(2),
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: stub_input(
+                    "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                )
+                .outpoint,
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(INITIAL_AMOUNT),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        },
+        prev_outs: vec![TxOut {
+            value: Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE),
+            script_pubkey: ScriptBuf::new(),
+        }],
+        prev_scripts: vec![ScriptBuf::from_bytes(vec![0x51])],
+    }
+}
+
+// Recomputes every sighash from the bundle alone, as an external signer would
+fn assert_recomputable(transaction: &SigningBundleTransaction) {
+    assert!(transaction
+        .unsigned_tx
+        .input
+        .iter()
+        .all(|input| input.witness.is_empty()));
+    assert_eq!(transaction.unsigned_tx.compute_txid(), transaction.txid);
+
+    for preimage in &transaction.sighash_preimages {
+        assert!(preimage.is_consistent());
+        assert_eq!(
+            preimage.leaf_hash,
+            TapLeafHash::from_script(&preimage.leaf_script, LeafVersion::TapScript)
+        );
+        let sighash = SighashCache::new(&transaction.unsigned_tx)
+            .taproot_script_spend_signature_hash(
+                preimage.input_index,
+                &Prevouts::All(&transaction.prev_outs),
+                preimage.leaf_hash,
+                TapSighashType::from_str(&preimage.sighash_type).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(preimage.sighash, sighash);
+    }
+}
+
+#[tokio::test]
+async fn test_peg_in_signing_bundle_matches_committee_signature() {
+    let config = setup_test().await;
+
+    let mut peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let bundle = peg_in_graph.signing_bundle();
+    assert_eq!(&bundle.graph_id, peg_in_graph.id());
+    assert_eq!(bundle.transactions.len(), 1);
+    let transaction = &bundle.transactions[0];
+    assert_eq!(transaction.sighash_preimages.len(), 1);
+    assert_eq!(transaction.sighash_preimages[0].sighash_type, "SIGHASH_ALL");
+    assert_recomputable(transaction);
+
+    // The bundle survives a round trip through its JSON format
+    assert_eq!(deserialize::<SigningBundle>(&serialize(&bundle)), bundle);
+
+    let secret_nonces_0 = peg_in_graph.push_verifier_nonces(&config.verifier_0_context);
+    let secret_nonces_1 = peg_in_graph.push_verifier_nonces(&config.verifier_1_context);
+    peg_in_graph
+        .verifier_sign(&config.verifier_0_context, &secret_nonces_0)
+        .unwrap();
+    peg_in_graph
+        .verifier_sign(&config.verifier_1_context, &secret_nonces_1)
+        .unwrap();
+
+    // Signing does not change the exported messages, and the aggregated committee signature
+    // commits to the exported sighash
+    assert_eq!(peg_in_graph.signing_bundle(), bundle);
+    let preimage = &transaction.sighash_preimages[0];
+    let message = Message::from_digest(preimage.sighash.to_byte_array());
+    let committee_key = config.verifier_0_context.n_of_n_taproot_public_key();
+    let secp = Secp256k1::verification_only();
+    let witness =
+        &peg_in_graph.peg_in_confirm_transaction_ref().tx().input[preimage.input_index].witness;
+    assert!(witness.iter().any(|element| {
+        taproot::Signature::from_slice(element).is_ok_and(|signature| {
+            signature.sighash_type.to_string() == preimage.sighash_type
+                && secp
+                    .verify_schnorr(&signature.signature, &message, committee_key)
+                    .is_ok()
+        })
+    }));
+}
+
+#[tokio::test]
+async fn test_peg_out_signing_bundle() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    let bundle = peg_out_graph.signing_bundle();
+    assert_eq!(&bundle.graph_id, peg_out_graph.id());
+    let names: Vec<String> = bundle
+        .transactions
+        .iter()
+        .map(|transaction| transaction.name.clone())
+        .collect();
+    let expected_names: Vec<String> = PegOutPresignedTransaction::iter()
+        .map(|transaction| transaction.to_string())
+        .collect();
+    assert_eq!(names, expected_names);
+
+    for transaction in &bundle.transactions {
+        assert!(!transaction.sighash_preimages.is_empty());
+        assert_recomputable(transaction);
+    }
+
+    // The sighash types follow how each input is pre-signed
+    let sighash_types = |name: PegOutPresignedTransaction| -> Vec<String> {
+        bundle
+            .transactions
+            .iter()
+            .find(|transaction| transaction.name == name.to_string())
+            .unwrap()
+            .sighash_preimages
+            .iter()
+            .map(|preimage| preimage.sighash_type.clone())
+            .collect()
+    };
+    assert_eq!(
+        sighash_types(PegOutPresignedTransaction::Take1),
+        vec!["SIGHASH_ALL", "SIGHASH_ALL"]
+    );
+    assert_eq!(
+        sighash_types(PegOutPresignedTransaction::StartTimeTimeout),
+        vec!["SIGHASH_SINGLE", "SIGHASH_NONE"]
+    );
+    assert_eq!(
+        sighash_types(PegOutPresignedTransaction::Disprove),
+        vec!["SIGHASH_SINGLE"]
+    );
+}
+
+#[test]
+fn test_template_id() {
+    let template = template_transaction();
+    let template_id = template.template_id();
+    assert_eq!(
+        TemplateId::from_str(&template_id.to_string()).unwrap(),
+        template_id
+    );
+
+    // Witnesses are placeholders, signing does not change the id
+    let mut signed = template_transaction();
+    signed.tx.input[0].witness = Witness::from_slice(&[vec![1u8; 64]]);
+    assert_eq!(signed.template_id(), template_id);
+
+    // The txid leaves out what the inputs spend, the template id does not
+    let mut other_amount = template_transaction();
+    other_amount.prev_outs[0].value = Amount::from_sat(INITIAL_AMOUNT);
+    let mut other_script = template_transaction();
+    other_script.prev_scripts[0] = ScriptBuf::from_bytes(vec![0x52]);
+    for other in [other_amount, other_script] {
+        assert_eq!(other.tx.compute_txid(), template.tx.compute_txid());
+        assert_ne!(other.template_id(), template_id);
+    }
+}
@@ -0,0 +1,78 @@
+use bitcoin::absolute;
+
+use bridge::{
+    connectors::stack_usage::{StackUsageAnalyzer, MAX_STACK_SIZE},
+    graphs::{
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+#[tokio::test]
+async fn test_peg_out_graph_leaves_within_stack_limit() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::HighSecurity,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::HighSecurity),
+    )
+    .unwrap();
+
+    let stack_usage = peg_out_graph.analyze_stack_usage(&config.commitment_secrets);
+    for leaf in &stack_usage {
+        println!("{leaf}");
+        assert!(leaf.completed, "{leaf}");
+        assert!(!leaf.usage.exceeds_limit(), "{leaf}");
+        assert!(leaf.usage.max_total_items >= leaf.usage.max_stack_items);
+    }
+
+    // The commitment leaves keep the Winternitz signatures on the stack
+    let connector_6 = stack_usage
+        .iter()
+        .find(|leaf| leaf.connector == "connector_6")
+        .unwrap();
+    assert!(connector_6.usage.max_stack_items > 100);
+
+    // The superblock disprove leaf keeps the committed superblock hash on the altstack
+    let connector_b = stack_usage
+        .iter()
+        .find(|leaf| leaf.connector == "connector_b" && leaf.leaf_index == 2)
+        .unwrap();
+    assert!(connector_b.usage.max_altstack_items > 0);
+
+    assert!(stack_usage
+        .iter()
+        .any(|leaf| leaf.connector.starts_with("connector_e_2_")));
+}
+
+#[tokio::test]
+async fn test_stack_usage_over_limit() {
+    let config = setup_test().await;
+
+    let mut analyzer = StackUsageAnalyzer::new(absolute::LockTime::ZERO);
+    analyzer.analyze_leaf(
+        "connector_0",
+        &config.connector_0,
+        0,
+        vec![vec![]; MAX_STACK_SIZE + 1],
+    );
+    let reports = analyzer.reports();
+
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].usage.max_stack_items, MAX_STACK_SIZE + 1);
+    assert!(reports[0].usage.exceeds_limit());
+    assert!(reports[0].to_string().contains("exceeds the limit"));
+}
@@ -0,0 +1,183 @@
+use bitcoin::{
+    absolute,
+    opcodes::all::{OP_CHECKMULTISIG, OP_NOP, OP_PUSHDATA1, OP_PUSHNUM_1, OP_RETURN},
+    script::Builder,
+    transaction::Version,
+    Amount, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+
+use bridge::{
+    graphs::{
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    transactions::standardness::{
+        lint_standardness, StandardnessViolationKind, MAX_OP_RETURN_RELAY,
+        MAX_STANDARD_P2WSH_SCRIPT_SIZE,
+    },
+};
+
+use crate::bridge::{
+    helper::{random_hex, stub_input},
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+fn tx_in(sequence: Sequence) -> TxIn {
+    TxIn {
+        previous_output: stub_input(&random_hex(32)).outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence,
+        witness: Witness::default(),
+    }
+}
+
+fn lint(
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    prev_scripts: &[ScriptBuf],
+) -> Vec<StandardnessViolationKind> {
+    lint_standardness("test", tx, prev_outs, prev_scripts)
+        .into_iter()
+        .map(|violation| violation.kind)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_graph_templates_are_standard() {
+    let config = setup_test().await;
+
+    for template in [GraphTemplate::FastRegtest, GraphTemplate::HighSecurity] {
+        let peg_in_graph = PegInGraph::new(
+            &config.depositor_context,
+            stub_input(&random_hex(32)),
+            &config.depositor_evm_address,
+            template,
+        )
+        .unwrap();
+        let peg_out_graph = PegOutGraph::new(
+            &config.operator_context,
+            &peg_in_graph,
+            stub_input(&random_hex(32)),
+            &config.commitment_secrets,
+            GraphParameters::from(template),
+        )
+        .unwrap();
+
+        let violations = [
+            peg_in_graph.lint_standardness(),
+            peg_out_graph.lint_standardness(),
+        ]
+        .concat();
+        for violation in &violations {
+            println!("{template}: {violation}");
+        }
+        assert!(violations.is_empty());
+    }
+}
+
+#[test]
+fn test_lint_reports_all_violations() {
+    let dust_script = Builder::new()
+        .push_opcode(OP_PUSHNUM_1)
+        .into_script()
+        .to_p2wsh();
+    let mut oversized_op_return = vec![OP_RETURN.to_u8(), OP_PUSHDATA1.to_u8(), 81];
+    oversized_op_return.extend([0u8; 81]);
+    let oversized_op_return = ScriptBuf::from_bytes(oversized_op_return);
+    let many_sigops = (0..201)
+        .fold(Builder::new(), |builder, _| {
+            builder.push_opcode(OP_CHECKMULTISIG)
+        })
+        .into_script();
+    let oversized_witness_script =
+        ScriptBuf::from_bytes(vec![OP_NOP.to_u8(); MAX_STANDARD_P2WSH_SCRIPT_SIZE + 1]);
+
+    let tx = Transaction {
+        version: Version::ONE,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![tx_in(Sequence::MAX), tx_in(Sequence::from_height(6))],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(100),
+                script_pubkey: dust_script.clone(),
+            },
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: oversized_op_return,
+            },
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::from_bytes(vec![OP_RETURN.to_u8()]),
+            },
+            TxOut {
+                value: Amount::from_sat(INITIAL_AMOUNT),
+                script_pubkey: many_sigops.clone(),
+            },
+        ],
+    };
+    let prev_outs = vec![
+        TxOut {
+            value: Amount::from_sat(INITIAL_AMOUNT),
+            script_pubkey: oversized_witness_script.to_p2wsh(),
+        },
+        TxOut {
+            value: Amount::from_sat(INITIAL_AMOUNT),
+            script_pubkey: dust_script.clone(),
+        },
+    ];
+    let prev_scripts = vec![oversized_witness_script, ScriptBuf::new()];
+
+    // Every violation is reported, not only the first one
+    let violations = lint(&tx, &prev_outs, &prev_scripts);
+    assert_eq!(
+        violations,
+        vec![
+            StandardnessViolationKind::RelativeLockTimeNotEnforced { input_index: 1 },
+            StandardnessViolationKind::DustOutput {
+                output_index: 0,
+                amount: Amount::from_sat(100),
+                min: dust_script.minimal_non_dust(),
+            },
+            StandardnessViolationKind::OversizedOpReturn {
+                output_index: 1,
+                size: MAX_OP_RETURN_RELAY + 1,
+            },
+            StandardnessViolationKind::NonStandardOutputScript {
+                output_index: 3,
+                script: many_sigops,
+            },
+            StandardnessViolationKind::MultipleOpReturns(2),
+            StandardnessViolationKind::OversizedWitnessScript {
+                input_index: 0,
+                size: MAX_STANDARD_P2WSH_SCRIPT_SIZE + 1,
+            },
+            StandardnessViolationKind::TooManySigops { cost: 201 * 20 * 4 },
+        ]
+    );
+
+    // A locktime only applies if an input has a non-final sequence
+    let tx = Transaction {
+        version: Version(4),
+        lock_time: absolute::LockTime::from_height(100).unwrap(),
+        input: vec![tx_in(Sequence::MAX)],
+        output: vec![TxOut {
+            value: Amount::from_sat(INITIAL_AMOUNT),
+            script_pubkey: dust_script,
+        }],
+    };
+    assert_eq!(
+        lint(&tx, &[], &[]),
+        vec![
+            StandardnessViolationKind::NonStandardVersion(Version(4)),
+            StandardnessViolationKind::LockTimeNotEnforced,
+        ]
+    );
+
+    let tx = Transaction {
+        version: Version::TWO,
+        input: vec![tx_in(Sequence::ENABLE_LOCKTIME_NO_RBF)],
+        ..tx
+    };
+    assert!(lint(&tx, &[], &[]).is_empty());
+}
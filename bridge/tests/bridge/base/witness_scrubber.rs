@@ -0,0 +1,159 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    absolute,
+    opcodes::all::{OP_DROP, OP_EQUALVERIFY, OP_PUSHNUM_1, OP_SIZE},
+    script::Builder,
+    secp256k1::Secp256k1,
+    taproot::{LeafVersion, TaprootBuilder},
+    transaction, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+    XOnlyPublicKey,
+};
+
+use bridge::transactions::witness_scrubber::{
+    minimal_number, scrub_witnesses, ScrubbedInput, WitnessFix,
+};
+
+use crate::bridge::helper::NUMS_INTERNAL_KEY;
+
+const ANNEX: [u8; 2] = [0x50, 0x01];
+
+// Drops the element whatever it is
+fn drop_script() -> ScriptBuf {
+    Builder::new()
+        .push_opcode(OP_DROP)
+        .push_opcode(OP_PUSHNUM_1)
+        .into_script()
+}
+
+// Checks the element is a single byte, so its encoding matters
+fn size_script() -> ScriptBuf {
+    Builder::new()
+        .push_opcode(OP_SIZE)
+        .push_int(1)
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_DROP)
+        .push_opcode(OP_PUSHNUM_1)
+        .into_script()
+}
+
+// Each input spends the leaf it is given with the element and, if set, an annex
+fn spend(spends: &[(ScriptBuf, Vec<u8>, bool)]) -> (Transaction, Vec<TxOut>) {
+    let secp = Secp256k1::new();
+    let spend_info = TaprootBuilder::new()
+        .add_leaf(1, drop_script())
+        .unwrap()
+        .add_leaf(1, size_script())
+        .unwrap()
+        .finalize(&secp, XOnlyPublicKey::from_str(NUMS_INTERNAL_KEY).unwrap())
+        .unwrap();
+    let prevout = TxOut {
+        value: Amount::from_sat(10_000),
+        script_pubkey: ScriptBuf::new_p2tr_tweaked(spend_info.output_key()),
+    };
+
+    let input = spends
+        .iter()
+        .enumerate()
+        .map(|(vout, (script, element, annex))| {
+            let control_block = spend_info
+                .control_block(&(script.clone(), LeafVersion::TapScript))
+                .unwrap();
+            let mut witness = vec![
+                element.clone(),
+                script.to_bytes(),
+                control_block.serialize(),
+            ];
+            if *annex {
+                witness.push(ANNEX.to_vec());
+            }
+            TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_str(
+                        "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                    )
+                    .unwrap(),
+                    vout: vout as u32,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::from_slice(&witness),
+            }
+        })
+        .collect();
+    let tx = Transaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input,
+        output: vec![TxOut {
+            value: Amount::from_sat(9_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    };
+
+    (tx, vec![prevout; spends.len()])
+}
+
+#[test]
+fn test_scrub_witnesses_canonicalizes_malleable_elements() {
+    let (mut tx, prevouts) = spend(&[
+        (drop_script(), vec![0x00], true),
+        (drop_script(), vec![0x05, 0x00], false),
+        (drop_script(), vec![0x05], false),
+    ]);
+    let txid = tx.compute_txid();
+    let wtxid = tx.compute_wtxid();
+
+    let scrubbed_inputs = scrub_witnesses(&mut tx, &prevouts);
+    assert_eq!(
+        scrubbed_inputs,
+        vec![
+            ScrubbedInput {
+                input_index: 0,
+                fixes: vec![WitnessFix::AnnexRemoved, WitnessFix::MinimalNumber(0)],
+            },
+            ScrubbedInput {
+                input_index: 1,
+                fixes: vec![WitnessFix::MinimalNumber(0)],
+            },
+        ]
+    );
+    assert_eq!(tx.input[0].witness.len(), 3);
+    assert!(tx.input[0].witness.taproot_annex().is_none());
+    assert!(tx.input[0].witness[0].is_empty());
+    assert_eq!(&tx.input[1].witness[0], &[0x05]);
+    assert_eq!(tx.compute_txid(), txid);
+    assert_ne!(tx.compute_wtxid(), wtxid);
+
+    // Scrubbing is idempotent
+    assert!(scrub_witnesses(&mut tx, &prevouts).is_empty());
+}
+
+#[test]
+fn test_scrub_witnesses_keeps_what_the_script_checks() {
+    let (mut tx, prevouts) = spend(&[
+        // Re-encoding the element would fail the size check
+        (size_script(), vec![0x00], false),
+        // Fails as it is, nothing is touched
+        (size_script(), vec![0x00, 0x00], true),
+    ]);
+    let original = tx.clone();
+
+    assert!(scrub_witnesses(&mut tx, &prevouts).is_empty());
+    assert_eq!(tx, original);
+}
+
+#[test]
+fn test_minimal_number_encoding() {
+    assert_eq!(minimal_number(&[]), None);
+    assert_eq!(minimal_number(&[0x05]), None);
+    assert_eq!(minimal_number(&[0x85]), None);
+    assert_eq!(minimal_number(&[0x85, 0x00]), None);
+    assert_eq!(minimal_number(&[0x00]), Some(vec![]));
+    assert_eq!(minimal_number(&[0x80]), Some(vec![])); // negative zero
+    assert_eq!(minimal_number(&[0x05, 0x00]), Some(vec![0x05]));
+    assert_eq!(minimal_number(&[0x05, 0x00, 0x80]), Some(vec![0x85]));
+    assert_eq!(minimal_number(&[0x85, 0x00, 0x00]), Some(vec![0x85, 0x00]));
+    // Longer elements are not numbers
+    assert_eq!(minimal_number(&[0x05, 0x00, 0x00, 0x00, 0x00]), None);
+}
@@ -0,0 +1,24 @@
+use std::str::FromStr;
+
+use bridge::graphs::peg_out::{ChallengePolicy, ProofVerification};
+
+#[test]
+fn test_challenge_policy_decision() {
+    let inconclusive = ProofVerification::Inconclusive(String::from("not asserted yet"));
+
+    for policy in [ChallengePolicy::Challenge, ChallengePolicy::Wait] {
+        // Conclusive verifications decide regardless of the policy
+        assert!(policy.should_challenge(&ProofVerification::Invalid));
+        assert!(!policy.should_challenge(&ProofVerification::Valid));
+    }
+    assert!(ChallengePolicy::Challenge.should_challenge(&inconclusive));
+    assert!(!ChallengePolicy::Wait.should_challenge(&inconclusive));
+
+    // Automatic mode does not challenge blindly unless asked to
+    assert_eq!(ChallengePolicy::default(), ChallengePolicy::Wait);
+    assert_eq!(
+        ChallengePolicy::from_str("challenge").unwrap(),
+        ChallengePolicy::Challenge
+    );
+    assert_eq!(ChallengePolicy::Wait.to_string(), "wait");
+}
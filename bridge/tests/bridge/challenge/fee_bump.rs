@@ -0,0 +1,173 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxOut, Txid};
+
+use bridge::{
+    connectors::base::TaprootConnector,
+    error::TransactionError,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
+    transactions::{
+        base::{BaseTransaction, Input, InputWithScript, MIN_RELAY_FEE_CHALLENGE},
+        challenge::ChallengeTransaction,
+        pre_signed::PreSignedTransaction,
+    },
+};
+
+use crate::bridge::setup::{setup_test, SetupConfig, INITIAL_AMOUNT};
+
+fn outpoint(vout: u32) -> OutPoint {
+    OutPoint {
+        txid: Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327")
+            .unwrap(),
+        vout,
+    }
+}
+
+// A challenge crowdfunded by the depositor key, with the outputs its inputs spend
+fn crowdfunded_challenge(
+    config: &SetupConfig,
+    crowdfunding_amount: Amount,
+) -> (ChallengeTransaction, Transaction, Vec<TxOut>) {
+    let crowdfunding_public_key = &config.depositor_context.depositor_public_key;
+    let amount_0 = Amount::from_sat(DUST_AMOUNT + MIN_RELAY_FEE_CHALLENGE);
+    let mut challenge_tx = ChallengeTransaction::new(
+        &config.operator_context,
+        &config.connector_a,
+        Input {
+            outpoint: outpoint(0),
+            amount: amount_0,
+        },
+        Amount::from_sat(INITIAL_AMOUNT),
+    );
+
+    let script = generate_pay_to_pubkey_script(crowdfunding_public_key);
+    let crowdfunding_address =
+        generate_pay_to_pubkey_script_address(config.network, crowdfunding_public_key);
+    challenge_tx.add_inputs_and_output(
+        &vec![InputWithScript {
+            outpoint: outpoint(1),
+            amount: crowdfunding_amount,
+            script: &script,
+        }],
+        &config.depositor_context.depositor_keypair,
+        crowdfunding_address.script_pubkey(),
+    );
+
+    let prev_outs = vec![
+        TxOut {
+            value: amount_0,
+            script_pubkey: config
+                .connector_a
+                .generate_taproot_address()
+                .script_pubkey(),
+        },
+        TxOut {
+            value: crowdfunding_amount,
+            script_pubkey: crowdfunding_address.script_pubkey(),
+        },
+    ];
+    let tx = challenge_tx.finalize();
+
+    (challenge_tx, tx, prev_outs)
+}
+
+fn fee(tx: &Transaction, prev_outs: &[TxOut]) -> Amount {
+    prev_outs
+        .iter()
+        .map(|prev_out| prev_out.value)
+        .sum::<Amount>()
+        - tx.output.iter().map(|output| output.value).sum::<Amount>()
+}
+
+#[tokio::test]
+async fn test_challenge_fee_bump() {
+    let config = setup_test().await;
+    let (mut challenge_tx, replaced_tx, prev_outs) =
+        crowdfunded_challenge(&config, Amount::from_sat(INITIAL_AMOUNT * 2));
+    assert_eq!(
+        replaced_tx.input[1].sequence,
+        Sequence::ENABLE_RBF_NO_LOCKTIME
+    );
+
+    challenge_tx
+        .bump_fee(
+            &replaced_tx,
+            &prev_outs,
+            &config.depositor_context.depositor_keypair,
+            MIN_RELAY_FEE_RATE * 10,
+        )
+        .unwrap();
+    let replacement_tx = challenge_tx.tx();
+
+    assert_ne!(replacement_tx.compute_txid(), replaced_tx.compute_txid());
+    // The operator's signature only covers input 0 and output 0, both are left untouched
+    assert_eq!(replacement_tx.input[0], replaced_tx.input[0]);
+    assert_eq!(replacement_tx.output[0], replaced_tx.output[0]);
+    assert!(replacement_tx.output[1].value < replaced_tx.output[1].value);
+    assert_ne!(
+        replacement_tx.input[1].witness,
+        replaced_tx.input[1].witness
+    );
+
+    // BIP125: the replacement pays for its own relay on top of the replaced fee
+    let replaced_fee = fee(&replaced_tx, &prev_outs);
+    let replacement_fee = fee(replacement_tx, &prev_outs);
+    assert!(
+        replacement_fee.to_sat()
+            >= replaced_fee.to_sat() + MIN_RELAY_FEE_RATE * replaced_tx.vsize() as u64
+    );
+}
+
+#[tokio::test]
+async fn test_challenge_fee_bump_not_covered_by_refund() {
+    let config = setup_test().await;
+    let (mut challenge_tx, replaced_tx, prev_outs) =
+        crowdfunded_challenge(&config, Amount::from_sat(INITIAL_AMOUNT + DUST_AMOUNT));
+
+    let result = challenge_tx.bump_fee(
+        &replaced_tx,
+        &prev_outs,
+        &config.depositor_context.depositor_keypair,
+        MIN_RELAY_FEE_RATE * 100,
+    );
+    assert!(matches!(
+        result,
+        Err(TransactionError::ReplacementFeeNotCovered { available, .. })
+            if available == Amount::from_sat(DUST_AMOUNT)
+    ));
+    assert_eq!(challenge_tx.tx(), &replaced_tx);
+}
+
+#[tokio::test]
+async fn test_challenge_fee_bump_rejects_foreign_inputs() {
+    let config = setup_test().await;
+    let (mut challenge_tx, replaced_tx, prev_outs) =
+        crowdfunded_challenge(&config, Amount::from_sat(INITIAL_AMOUNT * 2));
+
+    // Another crowdfunder signed input 1, its signature would not cover the new refund output
+    let result = challenge_tx.bump_fee(
+        &replaced_tx,
+        &prev_outs,
+        &config.operator_context.operator_keypair,
+        MIN_RELAY_FEE_RATE * 10,
+    );
+    assert!(matches!(
+        result,
+        Err(TransactionError::ReplacementInputNotOwned(1))
+    ));
+
+    // Transactions not spending the graph's connector A output are not challenges
+    let mut other_tx = replaced_tx.clone();
+    other_tx.input[0].previous_output = outpoint(2);
+    let result = challenge_tx.bump_fee(
+        &other_tx,
+        &prev_outs,
+        &config.depositor_context.depositor_keypair,
+        MIN_RELAY_FEE_RATE * 10,
+    );
+    assert!(matches!(
+        result,
+        Err(TransactionError::ReplacementNotPermitted(txid, None)) if txid == other_tx.compute_txid()
+    ));
+}
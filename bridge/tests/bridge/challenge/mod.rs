@@ -1 +1,3 @@
 pub mod challenge;
+pub mod challenge_policy;
+pub mod fee_bump;
@@ -0,0 +1,176 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bitcoin::{BlockHash, OutPoint};
+use bridge::{
+    client::{
+        client::{BitVMClient, BitVMClientPrivateData, BitVMClientPublicData},
+        files::BRIDGE_DATA_DIRECTORY_NAME,
+    },
+    serialization::{serialize, try_deserialize},
+};
+use colored::Colorize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+// Test environment config file and its checkpoint variables:
+// E2E_SAVE_CHECKPOINTS=true saves a checkpoint whenever a scenario reaches one.
+// E2E_RESUME_FROM_CHECKPOINT=<name> resumes scenarios from the named checkpoint.
+const TEST_ENV_FILE: &str = ".env.test";
+const SAVE_CHECKPOINTS: &str = "E2E_SAVE_CHECKPOINTS";
+const RESUME_FROM_CHECKPOINT: &str = "E2E_RESUME_FROM_CHECKPOINT";
+
+const CHECKPOINT_DIRECTORY_NAME: &str = "checkpoints";
+
+// Checkpoints capture the client state and the scenario values needed to continue from them. The
+// regtest chain state is snapshotted separately with `regtest/checkpoint.sh`, since it lives in
+// the esplora container. A checkpoint is only used if the chain still contains the block it was
+// taken at and none of the outputs the rest of the scenario spends have been spent since.
+#[derive(Serialize)]
+struct CheckpointRef<'a, T> {
+    height: u32,
+    block_hash: BlockHash,
+    unspent_outpoints: &'a [OutPoint],
+    clients: Vec<ClientSnapshotRef<'a>>,
+    scenario: &'a T,
+}
+
+#[derive(Serialize)]
+struct ClientSnapshotRef<'a> {
+    public_data: &'a BitVMClientPublicData,
+    private_data: &'a BitVMClientPrivateData,
+}
+
+#[derive(Deserialize)]
+struct Checkpoint<T> {
+    height: u32,
+    block_hash: BlockHash,
+    unspent_outpoints: Vec<OutPoint>,
+    clients: Vec<ClientSnapshot>,
+    scenario: T,
+}
+
+#[derive(Deserialize)]
+struct ClientSnapshot {
+    public_data: BitVMClientPublicData,
+    private_data: BitVMClientPrivateData,
+}
+
+fn env_var(var: &str) -> Option<String> {
+    dotenv::from_filename(TEST_ENV_FILE).ok();
+    dotenv::var(var).ok().filter(|value| !value.is_empty())
+}
+
+fn checkpoint_path(name: &str) -> PathBuf {
+    Path::new(BRIDGE_DATA_DIRECTORY_NAME)
+        .join(CHECKPOINT_DIRECTORY_NAME)
+        .join(format!("{name}.json"))
+}
+
+pub async fn save_checkpoint<T: Serialize>(
+    name: &str,
+    clients: &[&BitVMClient],
+    unspent_outpoints: &[OutPoint],
+    scenario: &T,
+) {
+    if env_var(SAVE_CHECKPOINTS).is_none_or(|value| value != "true") {
+        return;
+    }
+
+    let esplora = &clients[0].esplora;
+    let height = esplora
+        .get_height()
+        .await
+        .expect("Failed to get block height");
+    let block_hash = esplora
+        .get_block_hash(height)
+        .await
+        .expect("Failed to get block hash");
+
+    let checkpoint = CheckpointRef {
+        height,
+        block_hash,
+        unspent_outpoints,
+        clients: clients
+            .iter()
+            .map(|client| ClientSnapshotRef {
+                public_data: client.data(),
+                private_data: client.private_data(),
+            })
+            .collect(),
+        scenario,
+    };
+
+    let path = checkpoint_path(name);
+    fs::create_dir_all(path.parent().unwrap()).expect("Failed to create checkpoint directory");
+    fs::write(&path, serialize(&checkpoint)).expect("Failed to save checkpoint");
+    println!(
+        "{} '{name}' at block {height}. Snapshot the chain with `regtest/checkpoint.sh save {name}` to resume from it after the chain has moved on.",
+        "Saved checkpoint".bold().green()
+    );
+}
+
+/// Restores the clients to the named checkpoint and returns the scenario values saved with it,
+/// if the scenario was asked to resume from it and the chain state still matches.
+pub async fn load_checkpoint<T: DeserializeOwned>(
+    name: &str,
+    clients: &mut [&mut BitVMClient],
+) -> Option<T> {
+    if env_var(RESUME_FROM_CHECKPOINT).is_none_or(|value| value != name) {
+        return None;
+    }
+
+    let path = checkpoint_path(name);
+    let Ok(contents) = fs::read_to_string(&path) else {
+        println!(
+            "{} '{name}' not found at {}, running from scratch",
+            "Checkpoint".bold().red(),
+            path.display()
+        );
+        return None;
+    };
+    let checkpoint: Checkpoint<T> = try_deserialize(&contents)
+        .unwrap_or_else(|err| panic!("Invalid checkpoint '{name}': {err}"));
+    assert_eq!(
+        checkpoint.clients.len(),
+        clients.len(),
+        "Checkpoint '{name}' was saved for a different number of clients"
+    );
+
+    let esplora = clients[0].esplora.clone();
+    if esplora.get_block_hash(checkpoint.height).await.ok() != Some(checkpoint.block_hash) {
+        println!(
+            "{} '{name}' is not on the current chain, restore it with `regtest/checkpoint.sh restore {name}`. Running from scratch",
+            "Checkpoint".bold().red()
+        );
+        return None;
+    }
+    for outpoint in &checkpoint.unspent_outpoints {
+        let spent = esplora
+            .get_output_status(&outpoint.txid, outpoint.vout.into())
+            .await
+            .expect("Failed to get output status")
+            .is_some_and(|status| status.spent);
+        if spent {
+            println!(
+                "{} '{name}' was already used, {outpoint} is spent. Restore it with `regtest/checkpoint.sh restore {name}`. Running from scratch",
+                "Checkpoint".bold().red()
+            );
+            return None;
+        }
+    }
+
+    for (client, snapshot) in clients.iter_mut().zip(checkpoint.clients) {
+        client.restore_private_data(snapshot.private_data).unwrap();
+        client.merge_data(snapshot.public_data).unwrap();
+        client.flush().await;
+    }
+    println!(
+        "{} '{name}' at block {}",
+        "Resuming from checkpoint".bold().green(),
+        checkpoint.height
+    );
+
+    Some(checkpoint.scenario)
+}
@@ -0,0 +1,185 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use bitcoin::{Network, Txid};
+use serde_json::Value;
+
+use bridge::{
+    client::{
+        alerts::{AlertConfig, AlertEvent, AlertNotifier},
+        graph_list::GraphKind,
+    },
+    graphs::{base::GraphStage, peg_out::PegOutPresignedTransaction},
+};
+
+const GRAPH_ID: &str = "alerts_test_graph";
+const TIMESTAMP: u64 = 1_700_000_000;
+
+fn alert_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("bitvm_alerts_{name}.jsonl"));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+// Appends every alert to the file, one JSON object per line
+fn notifier(path: &Path) -> AlertNotifier {
+    AlertNotifier::new(
+        AlertConfig {
+            commands: vec![format!(
+                "cat >> {}; echo >> {}",
+                path.display(),
+                path.display()
+            )],
+            ..AlertConfig::default()
+        },
+        Network::Regtest,
+    )
+}
+
+fn delivered_alerts(path: &Path) -> Vec<Value> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_alert_command_receives_json() {
+    let path = alert_file("json");
+    let mut notifier = notifier(&path);
+    let txid =
+        Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327").unwrap();
+
+    assert!(
+        notifier
+            .notify(
+                AlertEvent::DisproveBroadcast {
+                    graph_id: GRAPH_ID.to_string(),
+                    txid,
+                },
+                TIMESTAMP,
+            )
+            .await
+    );
+
+    let alerts = delivered_alerts(&path);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["kind"], "disprove-broadcast");
+    assert_eq!(alerts[0]["graph_id"], GRAPH_ID);
+    assert_eq!(alerts[0]["txid"], txid.to_string());
+    assert_eq!(alerts[0]["network"], "regtest");
+    assert_eq!(alerts[0]["timestamp"], TIMESTAMP);
+    assert!(alerts[0]["message"].as_str().unwrap().contains(GRAPH_ID));
+}
+
+#[tokio::test]
+async fn test_graph_alerts_are_sent_once() {
+    let path = alert_file("once");
+    let mut notifier = notifier(&path);
+    let take_window = |available_at| AlertEvent::TakeWindowApproaching {
+        graph_id: GRAPH_ID.to_string(),
+        transaction: PegOutPresignedTransaction::Take1,
+        available_at,
+    };
+
+    assert!(notifier.notify(take_window(100), TIMESTAMP).await);
+    assert!(!notifier.notify(take_window(100), TIMESTAMP + 600).await);
+    // A reorg moving the window is reported again
+    assert!(notifier.notify(take_window(101), TIMESTAMP + 1200).await);
+
+    // Every flush failure is passed on, the client only reports the first of consecutive ones
+    let flush_failed = AlertEvent::FlushFailed {
+        error: "connection refused".to_string(),
+    };
+    assert!(notifier.notify(flush_failed.clone(), TIMESTAMP).await);
+    assert!(notifier.notify(flush_failed, TIMESTAMP + 600).await);
+
+    let kinds: Vec<Value> = delivered_alerts(&path)
+        .into_iter()
+        .map(|alert| alert["kind"].clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "take-window-approaching",
+            "take-window-approaching",
+            "flush-failed",
+            "flush-failed"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_graph_stage_changed_alert() {
+    let path = alert_file("stage_changed");
+    let mut notifier = notifier(&path);
+
+    notifier
+        .notify(
+            AlertEvent::GraphStageChanged {
+                graph_id: GRAPH_ID.to_string(),
+                graph_type: GraphKind::PegOut,
+                previous: GraphStage::KickedOff,
+                stage: GraphStage::Challenged,
+            },
+            TIMESTAMP,
+        )
+        .await;
+
+    let alerts = delivered_alerts(&path);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["kind"], "graph-stage-changed");
+    assert_eq!(alerts[0]["graph_id"], GRAPH_ID);
+    assert_eq!(alerts[0]["graph_type"], "peg-out");
+    assert_eq!(alerts[0]["previous"], "kicked-off");
+    assert_eq!(alerts[0]["stage"], "challenged");
+    assert_eq!(
+        alerts[0]["message"],
+        format!("Peg-out graph {GRAPH_ID} moved from kicked-off to challenged")
+    );
+}
+
+#[tokio::test]
+async fn test_failing_hooks_do_not_stop_delivery() {
+    let path = alert_file("failing");
+    let config = AlertConfig {
+        webhooks: vec!["http://127.0.0.1:1/alerts".to_string()],
+        commands: vec![
+            "exit 1".to_string(),
+            format!("cat >> {}; echo >> {}", path.display(), path.display()),
+        ],
+        ..AlertConfig::default()
+    };
+    let mut notifier = AlertNotifier::new(config, Network::Regtest);
+
+    notifier
+        .notify(
+            AlertEvent::ChallengeDetected {
+                graph_id: GRAPH_ID.to_string(),
+            },
+            TIMESTAMP,
+        )
+        .await;
+
+    let alerts = delivered_alerts(&path);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["kind"], "challenge-detected");
+}
+
+#[test]
+fn test_alert_config_defaults() {
+    let config: AlertConfig =
+        toml::from_str("webhooks = [\"https://example.com/alerts\"]").unwrap();
+    assert!(config.has_hooks());
+    assert!(config.commands.is_empty());
+    assert_eq!(
+        config.take_window_blocks,
+        AlertConfig::default().take_window_blocks
+    );
+    assert!(!AlertConfig::default().has_hooks());
+    assert!(!config.graph_transitions);
+}
@@ -0,0 +1,175 @@
+use std::fs;
+
+use bitcoin::{
+    absolute, hashes::Hash, transaction, Amount, BlockHash, OutPoint, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bridge::client::audit::{
+    append_broadcast_audit_record, get_broadcast_audit_file_path, raw_tx_hash,
+    read_broadcast_audit_records, AuditTrailIssue, BroadcastOutcome, WitnessMismatch,
+};
+
+use crate::bridge::helper::{empty_temp_dir, seeded_keypair};
+
+const GRAPH_ID: &str = "audit_test_graph";
+
+fn test_tx(value: u64) -> Transaction {
+    Transaction {
+        version: transaction::Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![],
+        output: vec![TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+fn witness_tx(witness: &[&[u8]]) -> Transaction {
+    let mut tx = test_tx(1000);
+    tx.input.push(TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::from_slice(witness),
+    });
+    tx
+}
+
+#[test]
+fn test_broadcast_audit_records_are_signed_and_chained() {
+    let dir = empty_temp_dir("broadcast_audit_chained");
+    let keypair = seeded_keypair(1);
+
+    let first = append_broadcast_audit_record(
+        &dir,
+        &keypair,
+        GRAPH_ID,
+        &test_tx(1000),
+        BroadcastOutcome::Rejected("missing inputs".to_string()),
+    )
+    .unwrap();
+    let second = append_broadcast_audit_record(
+        &dir,
+        &keypair,
+        GRAPH_ID,
+        &test_tx(1000),
+        BroadcastOutcome::Accepted("Tx broadcasted successfully.".to_string()),
+    )
+    .unwrap();
+
+    assert_eq!(first.previous_record_hash, None);
+    assert_eq!(second.previous_record_hash, Some(first.record_hash()));
+    assert_eq!(first.txid, test_tx(1000).compute_txid());
+    assert_eq!(first.broadcaster, keypair.x_only_public_key().0);
+
+    let (records, issues) = read_broadcast_audit_records(&dir).unwrap();
+    assert_eq!(records, vec![first, second]);
+    assert!(issues.is_empty());
+}
+
+#[test]
+fn test_broadcast_audit_detects_tampering() {
+    let dir = empty_temp_dir("broadcast_audit_tampered");
+    let keypair = seeded_keypair(1);
+    for value in [1000, 2000, 3000] {
+        append_broadcast_audit_record(
+            &dir,
+            &keypair,
+            GRAPH_ID,
+            &test_tx(value),
+            BroadcastOutcome::Accepted("Tx broadcasted successfully.".to_string()),
+        )
+        .unwrap();
+    }
+
+    let path = get_broadcast_audit_file_path(&dir);
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+
+    // Rewriting the outcome of a record invalidates its signature and the next record's link
+    let rewritten = lines[1].replace("Accepted", "Rejected");
+    fs::write(&path, [lines[0], &rewritten, lines[2]].join("\n")).unwrap();
+    let (_, issues) = read_broadcast_audit_records(&dir).unwrap();
+    assert_eq!(
+        issues,
+        vec![
+            AuditTrailIssue::InvalidSignature(1),
+            AuditTrailIssue::BrokenChain(2)
+        ]
+    );
+
+    // Dropping a record breaks the chain of the one after it
+    fs::write(&path, [lines[0], lines[2]].join("\n")).unwrap();
+    let (records, issues) = read_broadcast_audit_records(&dir).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(issues, vec![AuditTrailIssue::BrokenChain(1)]);
+}
+
+#[test]
+fn test_broadcast_audit_read_failure_is_returned() {
+    let dir = empty_temp_dir("broadcast_audit_unreadable");
+    read_broadcast_audit_records(&dir).unwrap();
+
+    // An audit file that exists but cannot be read is reported instead of being treated as empty
+    fs::create_dir_all(get_broadcast_audit_file_path(&dir)).unwrap();
+    assert!(read_broadcast_audit_records(&dir).is_err());
+    assert!(append_broadcast_audit_record(
+        &dir,
+        &seeded_keypair(1),
+        GRAPH_ID,
+        &test_tx(1000),
+        BroadcastOutcome::Accepted("Tx broadcasted successfully.".to_string()),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_witness_mismatch_of_confirmed_tx() {
+    let dir = empty_temp_dir("broadcast_audit_witness_mismatch");
+    let keypair = seeded_keypair(1);
+    let block_hash = BlockHash::all_zeros();
+    let broadcast_tx = witness_tx(&[&[0x01], &[0x51]]);
+    // Relayed with an annex, same txid
+    let confirmed_tx = witness_tx(&[&[0x01], &[0x51], &[0x50]]);
+    assert_eq!(broadcast_tx.compute_txid(), confirmed_tx.compute_txid());
+
+    // Only transactions the client broadcast are checked
+    assert_eq!(WitnessMismatch::find(&[], &confirmed_tx, block_hash), None);
+
+    let accepted = append_broadcast_audit_record(
+        &dir,
+        &keypair,
+        GRAPH_ID,
+        &broadcast_tx,
+        BroadcastOutcome::Accepted("Tx broadcasted successfully.".to_string()),
+    )
+    .unwrap();
+    let rejected = append_broadcast_audit_record(
+        &dir,
+        &keypair,
+        GRAPH_ID,
+        &confirmed_tx,
+        BroadcastOutcome::Rejected("txn-already-in-mempool".to_string()),
+    )
+    .unwrap();
+    let records = [accepted.clone(), rejected];
+    assert_eq!(
+        WitnessMismatch::find(&records, &broadcast_tx, block_hash),
+        None
+    );
+
+    // A rejected broadcast of the confirmed witness does not count
+    let witness_mismatch = WitnessMismatch::find(&records, &confirmed_tx, block_hash).unwrap();
+    assert_eq!(witness_mismatch.graph_id, GRAPH_ID);
+    assert_eq!(witness_mismatch.txid, confirmed_tx.compute_txid());
+    assert_eq!(witness_mismatch.block_hash, block_hash);
+    assert_eq!(
+        witness_mismatch.broadcast_raw_tx_hashes,
+        vec![accepted.raw_tx_hash]
+    );
+    assert_eq!(
+        witness_mismatch.confirmed_raw_tx_hash,
+        raw_tx_hash(&confirmed_tx)
+    );
+}
@@ -0,0 +1,211 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use bitcoin::{absolute, key::Keypair, transaction, Network, Transaction, Txid};
+use bridge::client::{
+    audit::BroadcastOutcome,
+    broadcast_middleware::{
+        ApprovalRule, BroadcastApproval, BroadcastContext, BroadcastMiddleware,
+        BroadcastMiddlewareChain, BroadcastPolicyConfig, BroadcastRequest, RateLimitConfig,
+        RateLimitMiddleware,
+    },
+    data_store::data_store::DataStore,
+};
+use esplora_client::Builder;
+
+use crate::bridge::helper::{seeded_keypair, seeded_public_key};
+
+const GRAPH_ID: &str = "peg_out_graph";
+const NOW: u64 = 1_700_000_000;
+
+fn txid() -> Txid {
+    Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327").unwrap()
+}
+
+fn empty_tx() -> Transaction {
+    Transaction {
+        version: transaction::Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![],
+        output: vec![],
+    }
+}
+
+fn request<'a>(
+    tx: &'a Transaction,
+    transaction: Option<&'static str>,
+    timestamp: u64,
+) -> BroadcastRequest<'a> {
+    BroadcastRequest {
+        graph_id: GRAPH_ID,
+        transaction,
+        tx,
+        network: Network::Bitcoin,
+        timestamp,
+    }
+}
+
+// Blocks every transaction named in `blocked`
+struct DenyMiddleware {
+    blocked: Vec<String>,
+}
+
+#[async_trait]
+impl BroadcastMiddleware for DenyMiddleware {
+    fn name(&self) -> &str {
+        "deny"
+    }
+
+    async fn before_broadcast(
+        &self,
+        request: &BroadcastRequest<'_>,
+        _context: &BroadcastContext<'_>,
+    ) -> Result<(), String> {
+        match request.is_one_of(&self.blocked) {
+            true => Err("denied".to_string()),
+            false => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_broadcast_approval_signature() {
+    let approver = seeded_keypair(1);
+    let approval = BroadcastApproval::new(&approver, GRAPH_ID, txid(), NOW);
+    assert!(approval.verify_signature());
+
+    let mut other_graph = approval.clone();
+    other_graph.graph_id = "other_graph".to_string();
+    assert!(!other_graph.verify_signature());
+
+    let mut other_signer = approval.clone();
+    other_signer.public_key = seeded_public_key(2);
+    assert!(!other_signer.verify_signature());
+}
+
+#[test]
+fn test_count_approvals() {
+    let approvers = [seeded_keypair(1), seeded_keypair(2), seeded_keypair(3)];
+    let rule = ApprovalRule {
+        transactions: vec!["take_1".to_string()],
+        networks: vec![Network::Bitcoin],
+        required: 2,
+        approvers: approvers.iter().map(public_key).collect(),
+    };
+    let approval = |keypair: &Keypair| BroadcastApproval::new(keypair, GRAPH_ID, txid(), NOW);
+
+    // Approving twice counts once
+    let approvals = vec![approval(&approvers[0]), approval(&approvers[0])];
+    assert_eq!(rule.count_approvals(&approvals, GRAPH_ID, &txid()), 1);
+
+    // Approvals of other signers, graphs or transactions do not count
+    let mut other_txid = approval(&approvers[2]);
+    other_txid.txid =
+        Txid::from_str("1e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327").unwrap();
+    let approvals = vec![
+        approval(&approvers[0]),
+        approval(&seeded_keypair(4)),
+        BroadcastApproval::new(&approvers[1], "other_graph", txid(), NOW),
+        other_txid,
+    ];
+    assert_eq!(rule.count_approvals(&approvals, GRAPH_ID, &txid()), 1);
+
+    let approvals = vec![approval(&approvers[0]), approval(&approvers[2])];
+    assert_eq!(rule.count_approvals(&approvals, GRAPH_ID, &txid()), 2);
+
+    let tx = empty_tx();
+    assert!(rule.applies_to(&request(&tx, Some("Take1"), NOW)));
+    assert!(!rule.applies_to(&request(&tx, Some("Take2"), NOW)));
+    assert!(!rule.applies_to(&request(&tx, None, NOW)));
+}
+
+#[test]
+fn test_transaction_names() {
+    let tx = empty_tx();
+    let take_1 = request(&tx, Some("Take1"), NOW);
+    assert!(take_1.is_one_of(&["take_1".to_string()]));
+    assert!(take_1.is_one_of(&["TAKE1".to_string(), "take_2".to_string()]));
+    assert!(!take_1.is_one_of(&["take_2".to_string()]));
+    // No list matches every transaction, even those not of a graph
+    assert!(take_1.is_one_of(&[]));
+    assert!(request(&tx, None, NOW).is_one_of(&[]));
+}
+
+#[tokio::test]
+async fn test_broadcast_middleware_chain() {
+    let esplora = Builder::new("http://localhost:8094/regtest/api")
+        .build_async()
+        .unwrap();
+    let data_store = DataStore::new(None).await;
+    let context = BroadcastContext {
+        esplora: &esplora,
+        data_store: &data_store,
+        approval_file_path: "approvals",
+    };
+
+    let mut chain = BroadcastMiddlewareChain::default();
+    chain.push(Box::new(RateLimitMiddleware::new(RateLimitConfig {
+        max_broadcasts: 2,
+        window: 60,
+    })));
+    chain.push(Box::new(DenyMiddleware {
+        blocked: vec!["take_1".to_string()],
+    }));
+
+    let tx = empty_tx();
+    let (middleware, reason) = chain
+        .before_broadcast(&request(&tx, Some("Take1"), NOW), &context)
+        .await
+        .unwrap_err();
+    assert_eq!((middleware.as_str(), reason.as_str()), ("deny", "denied"));
+
+    // Only accepted broadcasts count towards the rate limit
+    let accepted = BroadcastOutcome::Accepted("Confirmed".to_string());
+    let rejected = BroadcastOutcome::Rejected("Fee too low".to_string());
+    for (timestamp, outcome) in [(NOW, &accepted), (NOW + 1, &rejected), (NOW + 2, &accepted)] {
+        let challenge = request(&tx, Some("Challenge"), timestamp);
+        chain.before_broadcast(&challenge, &context).await.unwrap();
+        chain.after_broadcast(&challenge, outcome).await;
+    }
+    let (middleware, _) = chain
+        .before_broadcast(&request(&tx, Some("Challenge"), NOW + 3), &context)
+        .await
+        .unwrap_err();
+    assert_eq!(middleware, "rate limit");
+    // The first broadcast left the window
+    chain
+        .before_broadcast(&request(&tx, Some("Challenge"), NOW + 60), &context)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_broadcast_policy_config() {
+    let config: BroadcastPolicyConfig = toml::from_str(
+        r#"
+        log_file = "broadcasts.jsonl"
+        simulate = true
+        rate_limit = { max_broadcasts = 20, window = 3600 }
+
+        [[sign_off]]
+        command = "exit 0"
+        transactions = ["take_1"]
+
+        [[approvals]]
+        transactions = ["take_1"]
+        networks = ["bitcoin"]
+        required = 1
+        approvers = ["0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"]
+        "#,
+    )
+    .unwrap();
+    let names: Vec<String> = config
+        .middlewares()
+        .iter()
+        .map(|middleware| middleware.name().to_string())
+        .collect();
+    assert_eq!(
+        names,
+        ["rate limit", "approvals", "sign-off", "simulation", "log"]
+    );
+}
@@ -0,0 +1,127 @@
+use bitcoin::PublicKey;
+use bridge::client::ceremony_schedule::{
+    CeremonyProgressTracker, CeremonySchedule, CeremonyScheduleConfig, CeremonyTurn,
+};
+
+use crate::bridge::helper::seeded_public_key;
+
+const CREATED_AT: u64 = 1_700_000_000;
+const GRAPH_ID: &str = "6C2F1A0B9E8D7C6B5A4938271605F4E3D2C1B0A99887766554433221100FFEEDD";
+
+fn committee() -> Vec<PublicKey> {
+    (1..=4).map(seeded_public_key).collect()
+}
+
+fn schedule(committee: &[PublicKey]) -> CeremonySchedule {
+    CeremonySchedule::new(
+        &GRAPH_ID.to_string(),
+        committee,
+        CREATED_AT,
+        &CeremonyScheduleConfig::default(),
+    )
+}
+
+#[test]
+fn test_ceremony_schedule_is_deterministic() {
+    let committee = committee();
+    let mut reversed = committee.clone();
+    reversed.reverse();
+
+    // Every client derives the same order, whatever order it lists the committee in
+    let ceremony_schedule = schedule(&committee);
+    assert_eq!(ceremony_schedule, schedule(&reversed));
+    let mut members = ceremony_schedule.order.clone();
+    members.sort();
+    let mut expected = committee.clone();
+    expected.sort();
+    assert_eq!(members, expected);
+
+    // Graphs are not all started by the same member
+    let first_members: Vec<PublicKey> = (0..16)
+        .map(|i| {
+            CeremonySchedule::new(
+                &format!("{GRAPH_ID}{i}"),
+                &committee,
+                CREATED_AT,
+                &CeremonyScheduleConfig::default(),
+            )
+            .order[0]
+        })
+        .collect();
+    assert!(first_members
+        .iter()
+        .any(|member| *member != first_members[0]));
+}
+
+#[test]
+fn test_ceremony_turns() {
+    let config = CeremonyScheduleConfig::default();
+    let schedule = schedule(&committee());
+    let order = schedule.order.clone();
+    let (first, last) = (order[0], order[3]);
+
+    // The first member goes right away, the others wait for the members ahead of them
+    assert_eq!(schedule.turn_holder(CREATED_AT), Some(&first));
+    assert_eq!(
+        schedule.turn(&first, &order, CREATED_AT),
+        CeremonyTurn::Ours
+    );
+    assert_eq!(
+        schedule.turn(&last, &order, CREATED_AT),
+        CeremonyTurn::Waiting {
+            ahead: order[..3].to_vec(),
+            turn_at: CREATED_AT + 3 * config.turn_secs,
+        }
+    );
+
+    // Once the members ahead contributed there is no need to wait for the turn
+    assert_eq!(
+        schedule.turn(&last, &order[3..], CREATED_AT),
+        CeremonyTurn::Ours
+    );
+
+    // A member that does not contribute holds up the others for its turn only
+    let pending = vec![first, last];
+    let last_turn = CREATED_AT + 3 * config.turn_secs;
+    assert_eq!(
+        schedule.turn(&last, &pending, last_turn),
+        CeremonyTurn::Ours
+    );
+    assert_eq!(
+        schedule.turn(&last, &pending, last_turn + config.turn_secs),
+        CeremonyTurn::Waiting {
+            ahead: vec![first],
+            turn_at: last_turn + schedule.round_secs(),
+        }
+    );
+
+    // Members outside the committee are not scheduled
+    assert_eq!(
+        schedule.turn(&seeded_public_key(9), &order, CREATED_AT),
+        CeremonyTurn::Ours
+    );
+}
+
+#[test]
+fn test_ceremony_deadlock_detection() {
+    let config = CeremonyScheduleConfig::default();
+    let schedule = schedule(&committee());
+    let graph_id = GRAPH_ID.to_string();
+    let deadlock_secs = config.deadlock_rounds * schedule.round_secs();
+    let mut tracker = CeremonyProgressTracker::default();
+
+    assert_eq!(tracker.observe(&graph_id, 2, CREATED_AT), 0);
+    let stalled_secs = tracker.observe(&graph_id, 2, CREATED_AT + deadlock_secs - 1);
+    assert!(!schedule.is_deadlocked(stalled_secs, &config));
+
+    // A new contribution restarts the count
+    assert_eq!(tracker.observe(&graph_id, 3, CREATED_AT + deadlock_secs), 0);
+    let stalled_secs = tracker.observe(&graph_id, 3, CREATED_AT + 2 * deadlock_secs);
+    assert!(schedule.is_deadlocked(stalled_secs, &config));
+
+    tracker.forget(&graph_id);
+    assert_eq!(
+        tracker.observe(&graph_id, 3, CREATED_AT + 3 * deadlock_secs),
+        0
+    );
+}
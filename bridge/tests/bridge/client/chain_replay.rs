@@ -0,0 +1,215 @@
+use std::{fs, path::PathBuf, str::FromStr};
+
+use bitcoin::{Amount, OutPoint, PublicKey, Txid};
+use bridge::{
+    client::chain::{
+        chain::Chain,
+        mock_adaptor::{MockAdaptor, MockAdaptorConfig},
+        recording_adaptor::{ChainRecording, RecordingAdaptor, ReplayAdaptor},
+    },
+    error::{Error, L2Error},
+    graphs::{
+        base::PEG_IN_FEE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    transactions::base::Input,
+};
+
+use crate::bridge::{
+    helper::empty_temp_dir,
+    setup::{setup_test, SetupConfig, INITIAL_AMOUNT},
+};
+
+const PEG_OUT_FIXTURE: &str = "tests/fixtures/chain/peg_out.json";
+// Outpoint and operator of the peg-out recorded in the fixture
+const RECORDED_SOURCE_OUTPOINT: &str =
+    "3f2c5a1e9b8d7c6f5e4d3c2b1a0918273645546372819aabbccddeeff0011223:0";
+const RECORDED_OPERATOR_PUBLIC_KEY: &str =
+    "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+fn fixture_path(fixture: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(fixture)
+}
+
+// The peg-out fixture pointed at the peg-in confirm outpoint and operator of `peg_out_graph`
+fn peg_out_recording(config: &SetupConfig, peg_out_graph: &PegOutGraph) -> ChainRecording {
+    let mut recording = ChainRecording::read_from_file(&fixture_path(PEG_OUT_FIXTURE)).unwrap();
+    recording.rebind_source_outpoint(
+        OutPoint::from_str(RECORDED_SOURCE_OUTPOINT).unwrap(),
+        OutPoint {
+            txid: peg_out_graph.peg_in_confirm_txid(),
+            vout: 0,
+        },
+    );
+    recording.rebind_operator_public_key(
+        PublicKey::from_str(RECORDED_OPERATOR_PUBLIC_KEY).unwrap(),
+        config.operator_context.operator_public_key,
+    );
+    recording
+}
+
+#[tokio::test]
+async fn test_replay_peg_out_init_event() {
+    let (config, mut peg_out_graph) = setup_and_create_peg_out_graph().await;
+    let chain = Chain::new(Box::new(ReplayAdaptor::new(peg_out_recording(
+        &config,
+        &peg_out_graph,
+    ))));
+
+    let mut events = chain.get_peg_out_init().await.unwrap();
+    assert_eq!(events.len(), 2);
+    let event = peg_out_graph
+        .match_and_set_peg_out_event(&mut events)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.amount, Amount::from_sat(99_000_000));
+    assert_eq!(
+        event.operator_public_key,
+        config.operator_context.operator_public_key
+    );
+    assert_eq!(peg_out_graph.peg_out_chain_event, Some(event));
+    assert_eq!(events.len(), 1); // the peg-out of the other operator is left for other graphs
+}
+
+#[tokio::test]
+async fn test_replay_peg_out_burnt_event_once_final() {
+    let (config, mut peg_out_graph) = setup_and_create_peg_out_graph().await;
+    let chain = Chain::new(Box::new(ReplayAdaptor::new(peg_out_recording(
+        &config,
+        &peg_out_graph,
+    ))));
+    let mut init_events = chain.get_peg_out_init().await.unwrap();
+    peg_out_graph
+        .match_and_set_peg_out_event(&mut init_events)
+        .await
+        .unwrap();
+
+    // The burn was not final yet when the fixture was first polled
+    let burnt_events = chain.get_peg_out_burnt().await.unwrap();
+    assert!(matches!(
+        peg_out_graph.match_peg_out_burnt_event(&burnt_events),
+        Err(Error::L2(L2Error::PegOutBurnNotFound(txid))) if txid == peg_out_graph.peg_in_confirm_txid()
+    ));
+
+    let burnt_events = chain.get_peg_out_burnt().await.unwrap();
+    let burn = peg_out_graph
+        .match_peg_out_burnt_event(&burnt_events)
+        .unwrap();
+    assert_eq!(burn.amount, Amount::from_sat(99_000_000));
+
+    // The last recorded response is repeated once the recording runs out
+    let burnt_events = chain.get_peg_out_burnt().await.unwrap();
+    assert_eq!(
+        peg_out_graph
+            .match_peg_out_burnt_event(&burnt_events)
+            .unwrap(),
+        burn
+    );
+}
+
+#[tokio::test]
+async fn test_replay_rejects_burn_of_other_operator() {
+    let (config, mut peg_out_graph) = setup_and_create_peg_out_graph().await;
+    let mut recording = peg_out_recording(&config, &peg_out_graph);
+    // Only the peg-out request is bound to the operator of the graph, the burn keeps the recorded one
+    for response in recording.peg_out_burnt_events.iter_mut() {
+        for event in response.as_mut().unwrap().iter_mut() {
+            event.operator_public_key = PublicKey::from_str(RECORDED_OPERATOR_PUBLIC_KEY).unwrap();
+        }
+    }
+    let chain = Chain::new(Box::new(ReplayAdaptor::new(recording)));
+
+    let mut init_events = chain.get_peg_out_init().await.unwrap();
+    peg_out_graph
+        .match_and_set_peg_out_event(&mut init_events)
+        .await
+        .unwrap();
+    chain.get_peg_out_burnt().await.unwrap();
+    let burnt_events = chain.get_peg_out_burnt().await.unwrap();
+
+    assert!(matches!(
+        peg_out_graph.match_peg_out_burnt_event(&burnt_events),
+        Err(Error::L2(L2Error::PegOutBurnOperatorMismatch { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn test_replay_without_recorded_responses_fails() {
+    let chain = Chain::new(Box::new(ReplayAdaptor::new(ChainRecording::default())));
+
+    assert!(chain.get_peg_out_init().await.is_err());
+    assert!(chain.get_peg_in_minted().await.is_err());
+}
+
+#[tokio::test]
+async fn test_recording_replays_recorded_responses() {
+    let dir = empty_temp_dir("chain_recording");
+    let path = dir.join("recording.json");
+
+    let recorded = ChainRecording::read_from_file(&fixture_path(PEG_OUT_FIXTURE)).unwrap();
+    let init_events = recorded.peg_out_init_events[0].clone().unwrap();
+    let mock_adaptor = MockAdaptor::new(Some(MockAdaptorConfig {
+        peg_out_init_events: Some(init_events.clone()),
+        peg_out_burnt_events: None,
+        peg_out_minted_events: None,
+    }));
+    let recording_chain = Chain::new(Box::new(RecordingAdaptor::new(mock_adaptor, &path)));
+    recording_chain.get_peg_out_init().await.unwrap();
+    recording_chain.get_peg_out_burnt().await.unwrap();
+
+    let recording = ChainRecording::read_from_file(&path).unwrap();
+    assert_eq!(recording.peg_out_init_events, vec![Ok(init_events.clone())]);
+    assert_eq!(recording.peg_out_burnt_events, vec![Ok(vec![])]);
+    assert!(recording.peg_in_minted_events.is_empty());
+
+    let replay_chain = Chain::new(Box::new(ReplayAdaptor::from_file(&path).unwrap()));
+    assert_eq!(replay_chain.get_peg_out_init().await.unwrap(), init_events);
+    assert!(replay_chain.get_peg_out_burnt().await.unwrap().is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+pub async fn setup_and_create_peg_out_graph() -> (SetupConfig, PegOutGraph) {
+    let config = setup_test().await;
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.depositor_evm_address,
+        GraphTemplate::default(),
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    (config, peg_out_graph)
+}
@@ -0,0 +1,78 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use bitcoin::{Amount, OutPoint, Txid};
+use bridge::{
+    client::clock::{Clock, MockClock},
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE, SECONDS_PER_DAY},
+        template::GraphTemplate,
+    },
+    transactions::{base::Input, pre_signed_musig2::PreSignedMusig2Transaction},
+};
+
+use crate::bridge::{
+    helper::random_hex,
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_mock_clock_sleeps_instantly() {
+    let clock = MockClock::new(1_700_000_000);
+
+    let started = std::time::Instant::now();
+    clock.sleep(Duration::from_secs(SECONDS_PER_DAY)).await;
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(clock.now(), 1_700_000_000 + SECONDS_PER_DAY);
+
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(clock.now(), 1_700_000_000 + SECONDS_PER_DAY + 60);
+}
+
+#[tokio::test]
+async fn test_client_presigning_deadline_follows_clock() {
+    let mut config = setup_test().await;
+
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(&random_hex(32)).unwrap(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE),
+    };
+    let peg_in_graph_id = config
+        .client_0
+        .create_peg_in_graph(
+            input,
+            &config.depositor_evm_address,
+            GraphTemplate::FastRegtest,
+        )
+        .await
+        .unwrap();
+    let created_at = config
+        .client_0
+        .data()
+        .peg_in_graphs
+        .iter()
+        .find(|graph| graph.id() == &peg_in_graph_id)
+        .unwrap()
+        .presigning_window()
+        .created_at;
+
+    // Move the client past the pre-signing deadline without waiting a day for it
+    let clock = Arc::new(MockClock::new(created_at));
+    config.client_0.set_clock(clock.clone());
+    clock.advance(Duration::from_secs(SECONDS_PER_DAY));
+    assert_eq!(config.client_0.now(), created_at + SECONDS_PER_DAY);
+
+    config.client_0.push_verifier_nonces(&peg_in_graph_id);
+    let peg_in_graph = config
+        .client_0
+        .data()
+        .peg_in_graphs
+        .iter()
+        .find(|graph| graph.id() == &peg_in_graph_id)
+        .unwrap();
+    assert!(!peg_in_graph
+        .peg_in_confirm_transaction
+        .has_nonces_for(config.verifier_0_context.verifier_public_key));
+}
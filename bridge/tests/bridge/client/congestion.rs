@@ -0,0 +1,77 @@
+use bridge::client::congestion::{
+    BroadcastDecision, CongestionConfig, CongestionScheduler, DeferrableTx,
+};
+
+const HEIGHT: u32 = 100_000;
+
+fn scheduler() -> CongestionScheduler {
+    CongestionScheduler::new(CongestionConfig {
+        max_fee_rate: 10,
+        max_deferral_blocks: 6,
+        confirmation_target: 6,
+    })
+}
+
+#[test]
+fn test_congestion_defers_until_fees_drop() {
+    let mut scheduler = scheduler();
+    let graph_id = "graph".to_string();
+
+    assert_eq!(
+        scheduler.decide(&graph_id, DeferrableTx::Take1, HEIGHT, Some(25)),
+        BroadcastDecision::Defer {
+            fee_rate: 25,
+            blocks_left: 6
+        }
+    );
+    assert_eq!(
+        scheduler.decide(&graph_id, DeferrableTx::Take1, HEIGHT + 2, Some(20)),
+        BroadcastDecision::Defer {
+            fee_rate: 20,
+            blocks_left: 4
+        }
+    );
+    // The threshold itself is not congested
+    assert_eq!(
+        scheduler.decide(&graph_id, DeferrableTx::Take1, HEIGHT + 3, Some(10)),
+        BroadcastDecision::Broadcast
+    );
+
+    // Fees rising again start a new deadline
+    assert_eq!(
+        scheduler.decide(&graph_id, DeferrableTx::Take1, HEIGHT + 4, Some(30)),
+        BroadcastDecision::Defer {
+            fee_rate: 30,
+            blocks_left: 6
+        }
+    );
+}
+
+#[test]
+fn test_congestion_broadcasts_at_deadline() {
+    let mut scheduler = scheduler();
+    let graph_id = "graph".to_string();
+
+    assert!(matches!(
+        scheduler.decide(&graph_id, DeferrableTx::PegOutConfirm, HEIGHT, Some(50)),
+        BroadcastDecision::Defer { .. }
+    ));
+    // Deadlines are tracked per graph and transaction
+    assert!(matches!(
+        scheduler.decide(&graph_id, DeferrableTx::Take1, HEIGHT + 5, Some(50)),
+        BroadcastDecision::Defer { blocks_left: 6, .. }
+    ));
+    assert_eq!(
+        scheduler.decide(&graph_id, DeferrableTx::PegOutConfirm, HEIGHT + 6, Some(50)),
+        BroadcastDecision::Broadcast
+    );
+}
+
+#[test]
+fn test_congestion_broadcasts_without_estimate() {
+    let mut scheduler = scheduler();
+    assert_eq!(
+        scheduler.decide(&"graph".to_string(), DeferrableTx::Take1, HEIGHT, None),
+        BroadcastDecision::Broadcast
+    );
+}
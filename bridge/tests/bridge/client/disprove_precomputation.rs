@@ -0,0 +1,43 @@
+use bridge::{
+    client::disprove_precomputation::DisprovePrecomputation, graphs::peg_out::ProofVerification,
+};
+
+#[test]
+fn test_disprove_precomputation_keeps_conclusive_evaluations() {
+    let precomputation = DisprovePrecomputation::default();
+    let invalid_graph_id = "invalid_graph".to_string();
+    let valid_graph_id = "valid_graph".to_string();
+    let inconclusive_graph_id = "inconclusive_graph".to_string();
+    let disprove_witness = (7, vec![vec![1, 2, 3]]);
+
+    precomputation.insert(
+        &invalid_graph_id,
+        ProofVerification::Invalid,
+        Some(disprove_witness.clone()),
+    );
+    precomputation.insert(&valid_graph_id, ProofVerification::Valid, None);
+    precomputation.insert(
+        &inconclusive_graph_id,
+        ProofVerification::Inconclusive("assert commit transaction is not on chain".to_string()),
+        None,
+    );
+
+    assert_eq!(
+        precomputation.verification(&invalid_graph_id),
+        Some(ProofVerification::Invalid)
+    );
+    assert_eq!(
+        precomputation.disprove_witness(&invalid_graph_id),
+        Some(disprove_witness)
+    );
+    assert_eq!(
+        precomputation.verification(&valid_graph_id),
+        Some(ProofVerification::Valid)
+    );
+    assert_eq!(precomputation.disprove_witness(&valid_graph_id), None);
+
+    // Inconclusive evaluations are tried again
+    assert!(!precomputation.is_started(&inconclusive_graph_id));
+    assert_eq!(precomputation.verification(&inconclusive_graph_id), None);
+    assert!(!precomputation.is_running(&invalid_graph_id));
+}
@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use bitcoin::Txid;
+use bridge::client::esplora_notifications::{
+    get_esplora_websocket_url, parse_notification, ChainNotification,
+};
+use serde_json::json;
+
+const TXID_1: &str = "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327";
+const TXID_2: &str = "4b6e9c1f9f2b4b3b1b8e6a3f2f6c0ad1b7e5e3c2d1f0a9b8c7d6e5f4a3b2c1d0";
+
+#[test]
+fn test_esplora_websocket_url() {
+    assert_eq!(
+        get_esplora_websocket_url("http://localhost:8094/regtest/api/"),
+        "ws://localhost:8094/regtest/api/v1/ws"
+    );
+    assert_eq!(
+        get_esplora_websocket_url("https://mempool.space/testnet4/api"),
+        "wss://mempool.space/testnet4/api/v1/ws"
+    );
+}
+
+#[test]
+fn test_parse_block_notification() {
+    let message = json!({
+        "block": { "id": "00", "height": 840_000 },
+        "mempoolInfo": { "size": 1 },
+    });
+    assert_eq!(
+        parse_notification(&message.to_string()),
+        Some(ChainNotification::NewBlock(840_000))
+    );
+}
+
+#[test]
+fn test_parse_address_activity_notification() {
+    let message = json!({
+        "multi-address-transactions": {
+            "bcrt1qaddress1": { "mempool": [{ "txid": TXID_1 }], "confirmed": [], "removed": [] },
+            "bcrt1qaddress2": { "mempool": [], "confirmed": [{ "txid": TXID_2 }, { "txid": TXID_1 }], "removed": [] },
+        },
+    });
+    let Some(ChainNotification::AddressActivity(mut txids)) =
+        parse_notification(&message.to_string())
+    else {
+        panic!("Expected address activity");
+    };
+    txids.sort();
+    let mut expected = vec![
+        Txid::from_str(TXID_1).unwrap(),
+        Txid::from_str(TXID_2).unwrap(),
+    ];
+    expected.sort();
+    assert_eq!(txids, expected);
+
+    let message = json!({ "address-transactions": [{ "txid": TXID_1 }] });
+    assert_eq!(
+        parse_notification(&message.to_string()),
+        Some(ChainNotification::AddressActivity(vec![Txid::from_str(
+            TXID_1
+        )
+        .unwrap()]))
+    );
+}
+
+#[test]
+fn test_parse_unrelated_notification() {
+    assert_eq!(
+        parse_notification(&json!({ "pong": true }).to_string()),
+        None
+    );
+    assert_eq!(
+        parse_notification(&json!({ "multi-address-transactions": {} }).to_string()),
+        None
+    );
+    assert_eq!(parse_notification("not json"), None);
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use alloy::{
+    primitives::{Address as EvmAddress, Bytes, Log as PrimitiveLog, B256, U256},
+    rpc::types::Log,
+    sol_types::SolEvent,
+};
+use bitcoin::{Amount, OutPoint};
+use bridge::{
+    client::chain::ethereum_adaptor::{
+        decode_peg_out_burnt_event, decode_peg_out_init_event, IBridge,
+    },
+    error::{Error, L2Error},
+    scripts::generate_p2pkh_address,
+};
+
+use super::chain_replay::setup_and_create_peg_out_graph;
+
+// The bridge contract emits the outpoint txid in display byte order
+fn sol_outpoint(outpoint: &OutPoint) -> IBridge::Outpoint {
+    IBridge::Outpoint {
+        txId: B256::from_str(&outpoint.txid.to_string()).unwrap(),
+        vOut: U256::from(outpoint.vout),
+    }
+}
+
+// Log as returned by `eth_getLogs`, decoded the way `EthereumAdaptor` decodes it
+fn rpc_log<T: SolEvent>(event: &T, log_index: u64) -> Log<T> {
+    Log {
+        inner: PrimitiveLog {
+            address: EvmAddress::ZERO,
+            data: event.encode_log_data(),
+        },
+        block_hash: Some(B256::ZERO),
+        block_number: Some(1),
+        block_timestamp: Some(1_700_000_000),
+        transaction_hash: Some(B256::repeat_byte(log_index as u8)),
+        transaction_index: Some(0),
+        log_index: Some(log_index),
+        removed: false,
+    }
+    .log_decode::<T>()
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_decoded_peg_out_events_match_graph() {
+    let (config, mut peg_out_graph) = setup_and_create_peg_out_graph().await;
+    let source_outpoint = OutPoint {
+        txid: peg_out_graph.peg_in_confirm_txid(),
+        vout: 0,
+    };
+    let amount = U256::from(99_000_000u64);
+    let operator_public_key = Bytes::from(config.operator_context.operator_public_key.to_bytes());
+    let withdrawer = EvmAddress::from_str(&config.withdrawer_evm_address).unwrap();
+
+    let init_event = decode_peg_out_init_event(&rpc_log(
+        &IBridge::PegOutInitiated {
+            withdrawer,
+            destination_address: generate_p2pkh_address(
+                config.network,
+                &config.withdrawer_context.withdrawer_public_key,
+            )
+            .to_string(),
+            source_outpoint: sol_outpoint(&source_outpoint),
+            amount,
+            operator_pubKey: operator_public_key.clone(),
+        },
+        0,
+    ))
+    .unwrap();
+    assert_eq!(init_event.source_outpoint, source_outpoint);
+    let mut init_events = vec![init_event];
+    assert!(peg_out_graph
+        .match_and_set_peg_out_event(&mut init_events)
+        .await
+        .unwrap()
+        .is_some());
+
+    let burnt_event = |vout: u32, log_index: u64| {
+        decode_peg_out_burnt_event(&rpc_log(
+            &IBridge::PegOutBurnt {
+                withdrawer,
+                source_outpoint: sol_outpoint(&OutPoint {
+                    vout,
+                    ..source_outpoint
+                }),
+                amount,
+                operator_pubKey: operator_public_key.clone(),
+            },
+            log_index,
+        ))
+    };
+
+    // A burn of another output of the same transaction does not back this peg-out
+    let other_burn = burnt_event(1, 1);
+    assert_eq!(other_burn.source_outpoint.txid, source_outpoint.txid);
+    assert!(matches!(
+        peg_out_graph.match_peg_out_burnt_event(&[other_burn.clone()]),
+        Err(Error::L2(L2Error::PegOutBurnNotFound(_)))
+    ));
+
+    let burn = burnt_event(0, 2);
+    assert_eq!(burn.source_outpoint, source_outpoint);
+    assert_eq!(
+        peg_out_graph
+            .match_peg_out_burnt_event(&[other_burn, burn.clone()])
+            .unwrap(),
+        burn
+    );
+    assert_eq!(burn.amount, Amount::from_sat(99_000_000));
+}
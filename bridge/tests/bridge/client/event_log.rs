@@ -0,0 +1,106 @@
+use bitcoin::{hashes::Hash, merkle_tree::calculate_root, BlockHash, Network, TxMerkleNode, Txid};
+
+use bridge::{
+    client::event_log::{event_candidates, BridgeEvent, BridgeEventLog},
+    graphs::{
+        base::BaseGraph,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+fn txid(seed: u8) -> Txid {
+    Txid::from_byte_array([seed; 32])
+}
+
+fn merkle_root(txids: &[Txid]) -> TxMerkleNode {
+    TxMerkleNode::from_raw_hash(calculate_root(txids.iter().copied()).unwrap().to_raw_hash())
+}
+
+#[tokio::test]
+async fn test_event_candidates_cover_all_graph_transactions() {
+    let config = setup_test().await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    let candidates = event_candidates(
+        std::slice::from_ref(&peg_in_graph),
+        std::slice::from_ref(&peg_out_graph),
+    );
+    assert_eq!(
+        candidates.len(),
+        peg_in_graph.txids().len() + peg_out_graph.txids().len()
+    );
+
+    let peg_in_confirm = candidates
+        .iter()
+        .find(|candidate| candidate.transaction == "PegInConfirm")
+        .unwrap();
+    assert_eq!(peg_in_confirm.graph_id, *peg_in_graph.id());
+    assert_eq!(peg_in_confirm.peg_in_graph_id, *peg_in_graph.id());
+
+    let take_1 = candidates
+        .iter()
+        .find(|candidate| candidate.transaction == "Take1")
+        .unwrap();
+    assert_eq!(take_1.graph_id, *peg_out_graph.id());
+    assert_eq!(take_1.peg_in_graph_id, *peg_in_graph.id());
+}
+
+#[test]
+fn test_bridge_event_inclusion_proof() {
+    let block_txids = [txid(1), txid(2), txid(3), txid(4), txid(5)];
+    let root = merkle_root(&block_txids);
+
+    // Siblings of the 3rd tx: the 4th tx, the pair of the first two and the subtree of the 5th,
+    // whose odd last node is paired with itself
+    let left_pair = calculate_root([txid(1), txid(2)].into_iter()).unwrap();
+    let right_subtree = calculate_root([txid(5), txid(5), txid(5), txid(5)].into_iter()).unwrap();
+    let mut event = BridgeEvent {
+        graph_id: String::from("peg-out graph"),
+        peg_in_graph_id: String::from("peg-in graph"),
+        transaction: String::from("Take1"),
+        txid: txid(3),
+        block_height: 100,
+        block_hash: BlockHash::all_zeros(),
+        position: 2,
+        merkle_branch: vec![txid(4), left_pair, right_subtree],
+    };
+    assert!(event.verify_inclusion(&root));
+
+    let event_json = serialize(&event);
+    assert_eq!(deserialize::<BridgeEvent>(&event_json), event);
+
+    event.position = 3;
+    assert!(!event.verify_inclusion(&root));
+    event.position = 2;
+    event.txid = txid(4);
+    assert!(!event.verify_inclusion(&root));
+}
+
+#[test]
+fn test_new_event_log_is_empty() {
+    let event_log = BridgeEventLog::new(Network::Regtest);
+    assert_eq!(event_log.network(), Network::Regtest);
+    assert!(event_log.events().is_empty());
+    assert!(event_log.events_from(0).is_empty());
+    assert_eq!(event_log.synced_height(), 0);
+    assert!(event_log.witness_mismatches().is_empty());
+}
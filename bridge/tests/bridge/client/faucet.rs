@@ -0,0 +1,39 @@
+use bitcoin::{Amount, Network};
+use bridge::{
+    client::faucet::FaucetClient,
+    error::{ClientError, Error},
+};
+
+use crate::bridge::setup::setup_test;
+
+const FAUCET_URL: &str = "http://localhost:3000";
+
+#[test]
+fn test_faucet_not_available_on_mainnet() {
+    let result = FaucetClient::new(FAUCET_URL, Network::Bitcoin);
+    assert!(matches!(
+        result,
+        Err(Error::Client(ClientError::FaucetNotAvailable(
+            Network::Bitcoin
+        )))
+    ));
+
+    let faucet = FaucetClient::new(FAUCET_URL, Network::Testnet).unwrap();
+    assert_eq!(faucet.network(), Network::Testnet);
+}
+
+#[tokio::test]
+async fn test_faucet_network_must_match_client() {
+    let config = setup_test().await;
+    let faucet = FaucetClient::new(FAUCET_URL, Network::Signet).unwrap();
+
+    let result = config
+        .client_0
+        .fund_depositor_from_faucet(&faucet, Amount::from_sat(100_000))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::Client(ClientError::FaucetNotAvailable(_)))
+    ));
+}
@@ -1,17 +1,13 @@
 use bitcoin::{Address, Amount, OutPoint};
 use bridge::{
-    client::{
-        chain::{
-            chain::{Chain, PegOutEvent},
-            mock_adaptor::{MockAdaptor, MockAdaptorConfig},
-        },
-        client::BitVMClient,
+    client::chain::{
+        chain::{Chain, PegOutEvent, WithdrawerDestination},
+        mock_adaptor::{MockAdaptor, MockAdaptorConfig},
     },
     commitments::CommitmentMessageId,
     graphs::{
-        base::{max, BaseGraph, DUST_AMOUNT, MIN_RELAY_FEE_ASSERT_SET, PEG_IN_FEE, PEG_OUT_FEE},
-        peg_in::PegInGraph,
-        peg_out::PegOutGraph,
+        base::{max, DUST_AMOUNT, MIN_RELAY_FEE_ASSERT_SET, PEG_IN_FEE, PEG_OUT_FEE},
+        template::{GraphParameters, GraphTemplate},
     },
     scripts::{
         generate_p2pkh_address, generate_pay_to_pubkey_script,
@@ -61,12 +57,17 @@ async fn test_peg_in_fees() {
     };
     let peg_in_graph_id = config
         .client_0
-        .create_peg_in_graph(peg_in_input, &config.depositor_evm_address)
-        .await;
+        .create_peg_in_graph(
+            peg_in_input,
+            &config.depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
 
     let esplora_client = config.client_0.esplora.clone();
 
-    let peg_in_graph = get_peg_in_graph_mut(&mut config.client_0, peg_in_graph_id.clone());
+    let peg_in_graph = config.client_0.peg_in_graph_mut(&peg_in_graph_id).unwrap();
     let peg_in_deposit_tx = peg_in_graph.deposit(&esplora_client).await.unwrap();
     check_tx_output_sum(
         INITIAL_AMOUNT + max(MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_REFUND),
@@ -87,7 +88,7 @@ async fn test_peg_in_fees() {
         .process_peg_in_as_verifier(&peg_in_graph_id)
         .await;
 
-    let peg_in_graph = get_peg_in_graph_mut(&mut config.client_0, peg_in_graph_id.clone());
+    let peg_in_graph = config.client_0.peg_in_graph_mut(&peg_in_graph_id).unwrap();
     wait_for_timelock_expiry(config.network, Some("peg-in deposit connector z")).await;
     let peg_in_confirm_tx = peg_in_graph.confirm(&esplora_client).await.unwrap();
     check_tx_output_sum(
@@ -153,15 +154,17 @@ async fn test_peg_out_fees() {
                 amount: peg_in_amount,
             },
             &config.depositor_evm_address,
+            GraphTemplate::default(),
         )
-        .await;
+        .await
+        .unwrap();
     let peg_out_graph_id = config.client_0.create_peg_out_graph(
         &peg_in_graph_id,
         Input {
             outpoint: peg_out_confirm_outpoint,
             amount: peg_out_confirm_input_amount,
         },
-        config.commitment_secrets.clone(),
+        GraphParameters::default(),
     );
 
     let esplora_client = config.client_0.esplora.clone();
@@ -248,12 +251,15 @@ async fn test_peg_out_fees() {
                 &config.withdrawer_context.withdrawer_public_key,
             )
             .to_string(),
-            withdrawer_public_key_hash: config
-                .withdrawer_context
-                .withdrawer_public_key
-                .pubkey_hash(),
+            withdrawer_destination: WithdrawerDestination::PublicKeyHash(
+                config
+                    .withdrawer_context
+                    .withdrawer_public_key
+                    .pubkey_hash(),
+            ),
             operator_public_key: config.operator_context.operator_public_key,
             tx_hash: [0u8; 32].into(), // 32 bytes 0
+            withdrawal_nonce: 0,
         }]),
         peg_out_burnt_events: None,
         peg_out_minted_events: None,
@@ -264,7 +270,10 @@ async fn test_peg_out_fees() {
     config.client_0.set_chain_service(chain_service);
     config.client_0.sync_l2().await;
 
-    let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
+    let peg_out_graph = config
+        .client_0
+        .peg_out_graph_mut(&peg_out_graph_id)
+        .unwrap();
     let peg_out_tx = peg_out_graph
         .peg_out(
             &esplora_client,
@@ -297,15 +306,19 @@ async fn test_peg_out_fees() {
 
     let private_data = config.client_0.private_data();
     let secrets_map = private_data.commitment_secrets[&config.operator_context.operator_public_key]
-        [&peg_out_graph_id]
-        .clone();
-    let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
+        [peg_out_graph_id.as_str()]
+    .clone();
+    let peg_out_graph = config
+        .client_0
+        .peg_out_graph_mut(&peg_out_graph_id)
+        .unwrap();
     let kick_off_1_tx = peg_out_graph
         .kick_off_1(
             &esplora_client,
             &config.operator_context,
             &secrets_map[&CommitmentMessageId::PegOutTxIdSourceNetwork],
             &secrets_map[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+            None,
         )
         .await
         .unwrap();
@@ -375,7 +388,10 @@ async fn test_peg_out_fees() {
         script: &depositor_pubkey_script,
     }];
 
-    let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
+    let peg_out_graph = config
+        .client_0
+        .peg_out_graph_mut(&peg_out_graph_id)
+        .unwrap();
     let challenge_tx = peg_out_graph
         .challenge(
             &esplora_client,
@@ -413,8 +429,8 @@ async fn test_peg_out_fees() {
         .kick_off_2(
             &esplora_client,
             &config.operator_context,
-            &config.commitment_secrets[&CommitmentMessageId::Superblock],
-            &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
+            &secrets_map[&CommitmentMessageId::Superblock],
+            &secrets_map[&CommitmentMessageId::SuperblockHash],
         )
         .await
         .unwrap();
@@ -479,8 +495,9 @@ async fn test_peg_out_fees() {
     let (assert_commit1_tx, assert_commit2_tx) = peg_out_graph
         .assert_commits(
             &esplora_client,
-            &config.commitment_secrets,
+            &secrets_map,
             &config.invalid_proof,
+            &mut None,
         )
         .await
         .unwrap();
@@ -543,23 +560,3 @@ async fn test_peg_out_fees() {
     // minus 2 dust from kick off 1, 1 dust from kick off 2, 1 dust from assert final
     check_tx_output_sum(reward_amount - DUST_AMOUNT * 4, &disprove_tx);
 }
-
-// TODO: consider making the graph getter in client public after refactor
-fn get_peg_in_graph_mut(client: &mut BitVMClient, id: String) -> &mut PegInGraph {
-    client
-        .data_mut()
-        .peg_in_graphs
-        .iter_mut()
-        .find(|graph| graph.id().eq(&id))
-        .unwrap()
-}
-
-// TODO: consider making the graph getter in client public after refactor
-fn get_peg_out_graph_mut(client: &mut BitVMClient, id: String) -> &mut PegOutGraph {
-    client
-        .data_mut()
-        .peg_out_graphs
-        .iter_mut()
-        .find(|graph| graph.id().eq(&id))
-        .unwrap()
-}
@@ -0,0 +1,88 @@
+use bitcoin::{hashes::Hash, Amount, OutPoint, Txid};
+use bridge::{
+    client::{
+        client::BitVMClientPublicData,
+        graph_history::{replay_graph_history, DataStoreVersion, GraphState},
+    },
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        template::GraphTemplate,
+    },
+    transactions::base::Input,
+};
+
+use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
+
+fn version(timestamp: u64, data: &BitVMClientPublicData) -> DataStoreVersion {
+    DataStoreVersion {
+        file_name: format!("{timestamp}-bridge-client-data.json"),
+        timestamp,
+        data: data.clone(),
+    }
+}
+
+#[tokio::test]
+async fn test_replay_peg_in_signing_history() {
+    let config = setup_test().await;
+    let mut client_0 = config.client_0;
+    let mut client_1 = config.client_1;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_byte_array([1; 32]),
+            vout: 0,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE),
+    };
+    let graph_id = client_0
+        .create_peg_in_graph(
+            input,
+            &config.depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
+    let created = version(1000, client_0.data());
+
+    client_0.push_verifier_nonces(&graph_id);
+    let verifier_0_nonces = version(2000, client_0.data());
+
+    client_1.merge_data(client_0.data().clone()).unwrap();
+    client_1.push_verifier_nonces(&graph_id);
+    let verifier_1_nonces = version(3000, client_1.data());
+    // Written by a client that had nothing new to add
+    let unchanged = version(4000, client_1.data());
+
+    // Versions are replayed in timestamp order whatever order they are listed in
+    let transitions = replay_graph_history(
+        &graph_id,
+        vec![unchanged, verifier_1_nonces, created, verifier_0_nonces],
+    );
+
+    let timestamps: Vec<u64> = transitions.iter().map(|t| t.timestamp).collect();
+    assert_eq!(timestamps, vec![1000, 2000, 3000]);
+    assert_eq!(transitions[0].changes, vec!["Graph created".to_string()]);
+    assert!(transitions[1]
+        .changes
+        .iter()
+        .any(|change| change.ends_with(&format!("nonces from {verifier_0}"))));
+    assert!(transitions[2]
+        .changes
+        .iter()
+        .any(|change| change.ends_with(&format!("nonces from {verifier_1}"))));
+
+    // Signing state between the two nonce pushes
+    let progress = transitions[1].signing_progress();
+    assert_eq!(progress.len(), 1);
+    assert_eq!(progress[0].nonces, vec![verifier_0]);
+    assert_eq!(progress[0].pending_nonces, vec![verifier_1]);
+    assert!(matches!(&transitions[2].state, GraphState::PegIn(graph) if graph.id() == &graph_id));
+}
+
+#[test]
+fn test_replay_unknown_graph_is_empty() {
+    let transitions = replay_graph_history(&"unknown".to_string(), vec![]);
+    assert!(transitions.is_empty());
+}
@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use bitcoin::Amount;
+use bridge::{
+    client::graph_list::{GraphKind, GraphListFilter, GraphRole, GraphSummary},
+    graphs::base::GraphStage,
+};
+use serde_json::json;
+use strum::IntoEnumIterator;
+
+#[test]
+fn test_graph_stage_names() {
+    for stage in GraphStage::iter() {
+        let name = stage.to_string();
+        assert_eq!(GraphStage::from_str(&name).unwrap(), stage);
+        assert_eq!(serde_json::to_value(stage).unwrap(), json!(name));
+    }
+    assert_eq!(
+        GraphStage::from_str("pending-ceremony").unwrap(),
+        GraphStage::PendingCeremony
+    );
+    assert_eq!(GraphStage::KickedOff.to_string(), "kicked-off");
+    assert!(GraphStage::from_str("kicked_off").is_err());
+
+    for role in GraphRole::iter() {
+        assert_eq!(GraphRole::from_str(&role.to_string()).unwrap(), role);
+    }
+    assert!(GraphRole::from_str("challenger").is_err());
+}
+
+#[test]
+fn test_graph_list_filter_stage() {
+    let filter = GraphListFilter {
+        role: Some(GraphRole::Operator),
+        stage: Some(GraphStage::PendingCeremony),
+    };
+    assert!(filter.matches_stage(GraphStage::PendingCeremony));
+    assert!(!filter.matches_stage(GraphStage::Ready));
+
+    let any_stage = GraphListFilter::default();
+    assert!(GraphStage::iter().all(|stage| any_stage.matches_stage(stage)));
+}
+
+#[test]
+fn test_graph_summary_json() {
+    let summary = GraphSummary {
+        graph_id: "AB".repeat(32),
+        kind: GraphKind::PegOut,
+        stage: GraphStage::Asserted,
+        peg_in_graph_id: Some("CD".repeat(32)),
+        amount: Amount::from_sat(100_000),
+    };
+
+    let value = serde_json::to_value(&summary).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "graph_id": "AB".repeat(32),
+            "type": "peg-out",
+            "stage": "asserted",
+            "peg_in_graph_id": "CD".repeat(32),
+            "amount": 100_000,
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<GraphSummary>(value).unwrap(),
+        summary
+    );
+}
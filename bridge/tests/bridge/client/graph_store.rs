@@ -0,0 +1,83 @@
+use std::fs;
+
+use bridge::{
+    client::{client::BitVMClientPublicData, graph_store::GraphStore},
+    graphs::{
+        base::BaseGraph,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+};
+
+use crate::bridge::{
+    helper::{empty_temp_dir, stub_input},
+    setup::setup_test,
+};
+
+const LATEST_FILE_NAME: &str = "1700000000000-bridge-client-data.json";
+
+async fn public_data() -> BitVMClientPublicData {
+    let config = setup_test().await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    BitVMClientPublicData {
+        version: 7,
+        peg_in_graphs: vec![peg_in_graph],
+        peg_out_graphs: vec![peg_out_graph],
+    }
+}
+
+#[tokio::test]
+async fn test_graph_store_only_writes_changed_graphs() {
+    let config = setup_test().await;
+    let dir = empty_temp_dir("graph_store_changed");
+    let mut data = public_data().await;
+
+    let mut graph_store = GraphStore::new(&dir);
+    assert!(graph_store.load().is_none());
+    assert_eq!(graph_store.save(&data, Some(LATEST_FILE_NAME)), 2);
+    assert_eq!(graph_store.save(&data, Some(LATEST_FILE_NAME)), 0);
+
+    // A restarted client picks up the graphs and the data store file it processed last
+    let mut restarted_graph_store = GraphStore::new(&dir);
+    let (loaded_data, state) = restarted_graph_store.load().unwrap();
+    assert!(loaded_data == data);
+    assert_eq!(
+        state.latest_processed_file_name.as_deref(),
+        Some(LATEST_FILE_NAME)
+    );
+    assert_eq!(restarted_graph_store.save(&data, Some(LATEST_FILE_NAME)), 0);
+
+    data.peg_in_graphs[0].push_verifier_nonces(&config.verifier_0_context);
+    assert_eq!(restarted_graph_store.save(&data, Some(LATEST_FILE_NAME)), 1);
+    assert!(GraphStore::new(&dir).load().unwrap().0 == data);
+}
+
+#[tokio::test]
+async fn test_graph_store_with_corrupted_graph_is_discarded() {
+    let dir = empty_temp_dir("graph_store_corrupted");
+    let data = public_data().await;
+
+    let mut graph_store = GraphStore::new(&dir);
+    graph_store.save(&data, Some(LATEST_FILE_NAME));
+    let path = graph_store.peg_out_graph_file_path(data.peg_out_graphs[0].id());
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::write(&path, &contents[..contents.len() / 2]).unwrap();
+
+    assert!(GraphStore::new(&dir).load().is_none());
+}
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, Network, OutPoint, Txid};
+use bridge::{
+    client::heartbeat::{
+        generate_heartbeat_beacon_tx, heartbeat_beacon_payload, CommitteeHeartbeats,
+        HeartbeatRecord, HEARTBEAT_BEACON_FEE, HEARTBEAT_BEACON_TAG,
+        MISSED_HEARTBEATS_BEFORE_OFFLINE,
+    },
+    scripts::generate_p2wpkh_address,
+    transactions::{base::Input, standardness::lint_standardness},
+};
+
+use crate::bridge::helper::{random_hex, seeded_keypair, seeded_public_key};
+
+const INTERVAL: u64 = 60;
+const NOW: u64 = 1_700_000_000;
+
+#[test]
+fn test_committee_liveness_from_heartbeats() {
+    let (online, offline, silent, outsider) = (
+        seeded_keypair(1),
+        seeded_keypair(2),
+        seeded_keypair(3),
+        seeded_keypair(4),
+    );
+    let mut heartbeats = CommitteeHeartbeats::new(&[
+        seeded_public_key(1),
+        seeded_public_key(2),
+        seeded_public_key(3),
+    ]);
+
+    let record = HeartbeatRecord::new(&online, NOW - 10, INTERVAL, None);
+    assert!(record.verify_signature());
+    assert!(heartbeats.insert(record.clone()));
+    // Older heartbeats do not replace newer ones
+    assert!(!heartbeats.insert(HeartbeatRecord::new(&online, NOW - 100, INTERVAL, None)));
+    assert!(heartbeats.insert(HeartbeatRecord::new(
+        &offline,
+        NOW - MISSED_HEARTBEATS_BEFORE_OFFLINE * INTERVAL,
+        INTERVAL,
+        None
+    )));
+
+    // Records of non-members or with a signature of another key are ignored
+    assert!(!heartbeats.insert(HeartbeatRecord::new(&outsider, NOW, INTERVAL, None)));
+    let forged = HeartbeatRecord {
+        public_key: seeded_public_key(3),
+        ..HeartbeatRecord::new(&online, NOW, INTERVAL, None)
+    };
+    assert!(!forged.verify_signature());
+    assert!(!heartbeats.insert(forged));
+
+    let liveness = heartbeats.liveness(NOW);
+    assert_eq!(liveness.len(), 3);
+    assert_eq!(liveness[0].last_seen, Some(NOW - 10));
+    assert_eq!(liveness[0].age, 10);
+    assert!(!liveness[0].is_offline());
+    assert_eq!(
+        liveness[1].missed_heartbeats,
+        MISSED_HEARTBEATS_BEFORE_OFFLINE
+    );
+    assert!(liveness[1].is_offline());
+    assert!(liveness[1].to_string().contains("OFFLINE"));
+    assert_eq!(liveness[2].last_seen, None);
+    assert!(liveness[2].is_offline());
+    assert!(liveness[2].to_string().ends_with("never seen"));
+
+    // The record survives the data store
+    let restored: HeartbeatRecord =
+        serde_json::from_str(&serde_json::to_string(&record).unwrap()).unwrap();
+    assert_eq!(restored, record);
+    assert!(restored.verify_signature());
+}
+
+#[test]
+fn test_heartbeat_beacon_tx() {
+    let keypair = seeded_keypair(1);
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(&random_hex(32)).unwrap(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(10_000),
+    };
+
+    let tx = generate_heartbeat_beacon_tx(Network::Regtest, &keypair, &input, NOW).unwrap();
+    let address = generate_p2wpkh_address(Network::Regtest, &seeded_public_key(1));
+    assert_eq!(tx.output[0].script_pubkey, address.script_pubkey());
+    assert_eq!(tx.output[0].value, input.amount - HEARTBEAT_BEACON_FEE);
+    assert!(tx.output[1].script_pubkey.is_op_return());
+    let payload = heartbeat_beacon_payload(&seeded_public_key(1), NOW);
+    assert!(payload.starts_with(&HEARTBEAT_BEACON_TAG));
+    assert!(tx.output[1].script_pubkey.as_bytes().ends_with(&payload));
+    assert!(lint_standardness("heartbeat beacon", &tx, &[], &[]).is_empty());
+
+    // The record points other members to the beacon
+    let record = HeartbeatRecord::new(&keypair, NOW, INTERVAL, Some(tx.compute_txid()));
+    assert!(record.verify_signature());
+
+    assert!(generate_heartbeat_beacon_tx(Network::Bitcoin, &keypair, &input, NOW).is_err());
+    let dust_input = Input {
+        amount: HEARTBEAT_BEACON_FEE,
+        ..input
+    };
+    assert!(generate_heartbeat_beacon_tx(Network::Regtest, &keypair, &dust_input, NOW).is_err());
+}
@@ -0,0 +1,42 @@
+use bridge::client::jobs::{JobKind, JobOutput, JobQueue, JobState};
+
+#[tokio::test]
+async fn test_jobs_report_their_outputs_once_finished() {
+    let jobs = JobQueue::new(1);
+    let graph_id = "peg_out_graph".to_string();
+
+    let succeeding = jobs.submit(JobKind::GenerateLockScripts, &graph_id, || {
+        Ok(JobOutput::LockScripts)
+    });
+    let panicking = jobs.submit(JobKind::CreatePegOutGraph, &graph_id, || {
+        panic!("Peg out graph already exists")
+    });
+    assert!(succeeding < panicking);
+    assert!(!jobs.status(panicking).unwrap().is_finished());
+
+    let mut finished = vec![];
+    while finished.len() < 2 {
+        jobs.finished().await;
+        finished.extend(jobs.take_finished());
+    }
+    finished.sort_by_key(|(status, _)| status.id);
+    assert!(matches!(finished[0].1, Ok(JobOutput::LockScripts)));
+    assert!(matches!(&finished[1].1, Err(reason) if reason == "Peg out graph already exists"));
+    // Unfinished until the outputs are applied
+    assert_eq!(jobs.status(succeeding).unwrap().state, JobState::Running);
+    assert!(jobs.take_finished().is_empty());
+
+    jobs.complete(succeeding, Ok("lock scripts cached".to_string()));
+    jobs.complete(panicking, Err("Peg out graph already exists".to_string()));
+    let statuses = jobs.list();
+    assert_eq!(
+        statuses.iter().map(|status| status.id).collect::<Vec<_>>(),
+        vec![succeeding, panicking]
+    );
+    assert_eq!(
+        statuses[0].state,
+        JobState::Succeeded("lock scripts cached".to_string())
+    );
+    assert!(statuses.iter().all(|status| status.is_finished()));
+    assert!(jobs.status(panicking + 1).is_none());
+}
@@ -0,0 +1,171 @@
+use std::fs;
+
+use bridge::client::cli::{
+    key_command::{Config, KeysCommand},
+    keystore::{EncryptedSecret, KeyRole, PassphraseProvider},
+};
+
+use crate::bridge::helper::empty_temp_dir;
+
+const OPERATOR_SECRET: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+const VERIFIER_SECRET: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+const NEW_VERIFIER_SECRET: &str =
+    "3333333333333333333333333333333333333333333333333333333333333333";
+
+fn keys_command(name: &str) -> KeysCommand {
+    let dir = empty_temp_dir(&format!("keystore_{name}"));
+    KeysCommand::new(Some(dir.to_string_lossy().to_string()))
+}
+
+fn plaintext_config() -> Config {
+    let mut config = Config::default();
+    config.keys.operator = Some(OPERATOR_SECRET.to_string());
+    config.keys.verifier = Some(VERIFIER_SECRET.to_string());
+    config
+}
+
+#[test]
+fn test_encrypted_secret_requires_passphrase_and_role() {
+    let encrypted = EncryptedSecret::encrypt(KeyRole::Operator, OPERATOR_SECRET, "passphrase")
+        .expect("Failed to encrypt secret");
+    assert!(!encrypted.ciphertext.contains(OPERATOR_SECRET));
+
+    assert_eq!(
+        encrypted.decrypt(KeyRole::Operator, "passphrase").unwrap(),
+        OPERATOR_SECRET
+    );
+    assert!(encrypted.decrypt(KeyRole::Operator, "wrong").is_err());
+    assert!(encrypted.decrypt(KeyRole::Verifier, "passphrase").is_err());
+}
+
+#[test]
+fn test_encrypted_keys_are_not_written_in_plaintext() {
+    let keys_command = keys_command("not_plaintext");
+    let mut config = plaintext_config();
+    assert_eq!(
+        config.plaintext_roles(),
+        vec![KeyRole::Operator, KeyRole::Verifier]
+    );
+
+    config
+        .encrypt_plaintext_secrets(&mut PassphraseProvider::with_master("master"))
+        .unwrap();
+    keys_command.write_config(&config).unwrap();
+
+    let content = fs::read_to_string(&keys_command.config_path).unwrap();
+    assert!(!content.contains(OPERATOR_SECRET));
+    assert!(!content.contains(VERIFIER_SECRET));
+
+    let stored = keys_command.read_config().unwrap();
+    assert!(stored.keys.operator.is_none());
+    assert!(stored.plaintext_roles().is_empty());
+
+    let unlocked = keys_command
+        .read_unlocked_config(&mut PassphraseProvider::with_master("master"))
+        .unwrap();
+    assert_eq!(unlocked.keys.operator.as_deref(), Some(OPERATOR_SECRET));
+    assert_eq!(unlocked.keys.verifier.as_deref(), Some(VERIFIER_SECRET));
+
+    assert!(keys_command
+        .read_unlocked_config(&mut PassphraseProvider::with_master("wrong"))
+        .is_err());
+}
+
+#[test]
+fn test_per_role_passphrases() {
+    let keys_command = keys_command("per_role");
+    let mut config = plaintext_config();
+    let mut passphrases = PassphraseProvider::new()
+        .with_passphrase(KeyRole::Operator, "operator")
+        .with_passphrase(KeyRole::Verifier, "verifier");
+    config.encrypt_plaintext_secrets(&mut passphrases).unwrap();
+    keys_command.write_config(&config).unwrap();
+
+    let stored = keys_command.read_config().unwrap();
+    let operator = stored.keystore.operator.as_ref().unwrap();
+    assert!(operator.decrypt(KeyRole::Operator, "operator").is_ok());
+    assert!(operator.decrypt(KeyRole::Operator, "verifier").is_err());
+
+    let unlocked = keys_command
+        .read_unlocked_config(
+            &mut PassphraseProvider::with_master("master")
+                .with_passphrase(KeyRole::Operator, "operator")
+                .with_passphrase(KeyRole::Verifier, "verifier"),
+        )
+        .unwrap();
+    assert_eq!(unlocked.keys.operator.as_deref(), Some(OPERATOR_SECRET));
+}
+
+#[test]
+fn test_rotated_verifier_key_stays_encrypted() {
+    let keys_command = keys_command("rotation");
+    let mut config = plaintext_config();
+    config
+        .encrypt_plaintext_secrets(&mut PassphraseProvider::with_master("master"))
+        .unwrap();
+    keys_command.write_config(&config).unwrap();
+
+    let mut passphrases = PassphraseProvider::with_master("master");
+    let mut config = keys_command.read_unlocked_config(&mut passphrases).unwrap();
+    config.retire_verifier_secret();
+    config
+        .set_secret(
+            KeyRole::Verifier,
+            NEW_VERIFIER_SECRET,
+            true,
+            &mut passphrases,
+        )
+        .unwrap();
+    keys_command.write_config(&config).unwrap();
+
+    let content = fs::read_to_string(&keys_command.config_path).unwrap();
+    assert!(!content.contains(VERIFIER_SECRET));
+    assert!(!content.contains(NEW_VERIFIER_SECRET));
+
+    let unlocked = keys_command
+        .read_unlocked_config(&mut PassphraseProvider::with_master("master"))
+        .unwrap();
+    assert_eq!(unlocked.keys.verifier.as_deref(), Some(NEW_VERIFIER_SECRET));
+    assert_eq!(unlocked.keys.retired_verifiers, vec![VERIFIER_SECRET]);
+}
+
+#[test]
+fn test_plaintext_config_is_still_supported() {
+    let keys_command = keys_command("plaintext");
+    keys_command.write_config(&plaintext_config()).unwrap();
+
+    let content = fs::read_to_string(&keys_command.config_path).unwrap();
+    assert!(content.contains(OPERATOR_SECRET));
+    assert!(!content.contains("keystore"));
+
+    // No passphrase is needed without encrypted keys
+    let unlocked = keys_command
+        .read_unlocked_config(&mut PassphraseProvider::new())
+        .unwrap();
+    assert_eq!(unlocked.keys.operator.as_deref(), Some(OPERATOR_SECRET));
+    assert_eq!(
+        unlocked.plaintext_roles(),
+        vec![KeyRole::Operator, KeyRole::Verifier]
+    );
+}
+
+#[test]
+fn test_zeroize_secrets() {
+    let mut config = plaintext_config();
+    config.keys.retired_verifiers = vec![NEW_VERIFIER_SECRET.to_string()];
+    config.keys.verifying_key = Some("verifying_key".to_string());
+    config
+        .encrypt_plaintext_secrets(&mut PassphraseProvider::with_master("master"))
+        .unwrap();
+
+    config.keys.zeroize_secrets();
+    assert!(config.keys.operator.is_none());
+    assert!(config.keys.verifier.is_none());
+    assert!(config.keys.retired_verifiers.is_empty());
+    // The verifying key is not secret, and the encrypted keys can still be unlocked
+    assert_eq!(config.keys.verifying_key.as_deref(), Some("verifying_key"));
+    config
+        .unlock(&mut PassphraseProvider::with_master("master"))
+        .unwrap();
+    assert_eq!(config.keys.operator.as_deref(), Some(OPERATOR_SECRET));
+}
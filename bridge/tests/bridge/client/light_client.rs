@@ -0,0 +1,216 @@
+use bitcoin::{
+    block::{Header, Version},
+    blockdata::constants::genesis_block,
+    hashes::Hash,
+    merkle_tree::calculate_root,
+    BlockHash, CompactTarget, Network, Target, TxMerkleNode, Txid,
+};
+use bridge::{
+    client::light_client::{HeaderChain, HeaderCheckpoint},
+    error::HeaderChainError,
+};
+use esplora_client::MerkleProof;
+
+const REGTEST_BITS: u32 = 0x207fffff;
+
+fn mine(prev: &Header, merkle_root: TxMerkleNode, time: u32, bits: u32) -> Header {
+    let mut header = Header {
+        version: Version::TWO,
+        prev_blockhash: prev.block_hash(),
+        merkle_root,
+        time,
+        bits: CompactTarget::from_consensus(bits),
+        nonce: 0,
+    };
+    while header
+        .validate_pow(Target::from_compact(header.bits))
+        .is_err()
+    {
+        header.nonce += 1;
+    }
+    header
+}
+
+// Headers following `prev`, one second apart, distinguished by `seed`
+fn mine_chain(prev: &Header, count: u32, seed: u8) -> Vec<Header> {
+    let mut headers: Vec<Header> = vec![];
+    for i in 0..count {
+        let prev = headers.last().unwrap_or(prev);
+        let merkle_root = TxMerkleNode::from_byte_array([seed; 32]);
+        headers.push(mine(prev, merkle_root, prev.time + 1 + i, REGTEST_BITS));
+    }
+    headers
+}
+
+fn regtest_chain() -> HeaderChain {
+    HeaderChain::new(
+        Network::Regtest,
+        HeaderCheckpoint::genesis(Network::Regtest),
+        genesis_block(Network::Regtest).header,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_header_chain_rejects_wrong_checkpoint_header() {
+    let checkpoint = HeaderCheckpoint {
+        height: 0,
+        hash: BlockHash::all_zeros(),
+    };
+    assert!(matches!(
+        HeaderChain::new(
+            Network::Regtest,
+            checkpoint,
+            genesis_block(Network::Regtest).header
+        ),
+        Err(HeaderChainError::CheckpointMismatch(0))
+    ));
+
+    // Outside regtest, the checkpoint must start a difficulty period
+    let header = genesis_block(Network::Bitcoin).header;
+    let checkpoint = HeaderCheckpoint {
+        height: 1,
+        hash: header.block_hash(),
+    };
+    assert!(matches!(
+        HeaderChain::new(Network::Bitcoin, checkpoint, header),
+        Err(HeaderChainError::CheckpointNotAtRetarget(1))
+    ));
+}
+
+#[test]
+fn test_header_chain_extends_with_valid_headers() {
+    let mut header_chain = regtest_chain();
+    let headers = mine_chain(header_chain.header_at(0).unwrap(), 3, 1);
+    header_chain.extend(headers.clone()).unwrap();
+
+    assert_eq!(header_chain.tip_height(), 3);
+    assert_eq!(header_chain.tip_hash(), headers[2].block_hash());
+    assert_eq!(header_chain.header_at(1), Some(&headers[0]));
+    assert_eq!(header_chain.header_at(4), None);
+}
+
+#[test]
+fn test_header_chain_rejects_invalid_headers() {
+    let mut header_chain = regtest_chain();
+    header_chain
+        .extend(mine_chain(header_chain.header_at(0).unwrap(), 2, 1))
+        .unwrap();
+    let tip = *header_chain.header_at(2).unwrap();
+    let merkle_root = TxMerkleNode::from_byte_array([2; 32]);
+
+    let not_connected = mine(
+        header_chain.header_at(1).unwrap(),
+        merkle_root,
+        tip.time + 1,
+        REGTEST_BITS,
+    );
+    assert!(matches!(
+        header_chain.validate_next(&not_connected),
+        Err(HeaderChainError::HeaderNotConnected(3))
+    ));
+
+    let wrong_bits = mine(&tip, merkle_root, tip.time + 1, 0x2000ffff);
+    assert!(matches!(
+        header_chain.validate_next(&wrong_bits),
+        Err(HeaderChainError::UnexpectedDifficulty { height: 3, .. })
+    ));
+
+    let mut no_work = mine(&tip, merkle_root, tip.time + 1, REGTEST_BITS);
+    while no_work
+        .validate_pow(Target::from_compact(no_work.bits))
+        .is_ok()
+    {
+        no_work.nonce += 1;
+    }
+    assert!(matches!(
+        header_chain.validate_next(&no_work),
+        Err(HeaderChainError::InvalidProofOfWork(3))
+    ));
+
+    // Not after the median time of the previous blocks
+    let too_old = mine(
+        &tip,
+        merkle_root,
+        header_chain.header_at(1).unwrap().time,
+        REGTEST_BITS,
+    );
+    assert!(matches!(
+        header_chain.validate_next(&too_old),
+        Err(HeaderChainError::TimestampTooOld(3))
+    ));
+
+    assert!(header_chain
+        .extend(vec![mine(&tip, merkle_root, tip.time + 1, REGTEST_BITS)])
+        .is_ok());
+}
+
+#[test]
+fn test_header_chain_reorganizes_to_more_work() {
+    let mut header_chain = regtest_chain();
+    let genesis = *header_chain.header_at(0).unwrap();
+    let headers = mine_chain(&genesis, 3, 1);
+    header_chain.extend(headers.clone()).unwrap();
+
+    // A fork with less work is ignored
+    let shorter_fork = mine_chain(&headers[0], 1, 2);
+    assert!(matches!(
+        header_chain.reorganize(1, shorter_fork),
+        Err(HeaderChainError::LessWork(1))
+    ));
+    assert_eq!(header_chain.tip_hash(), headers[2].block_hash());
+
+    let longer_fork = mine_chain(&headers[0], 3, 3);
+    header_chain.reorganize(1, longer_fork.clone()).unwrap();
+    assert_eq!(header_chain.tip_height(), 4);
+    assert_eq!(header_chain.tip_hash(), longer_fork[2].block_hash());
+    assert_eq!(header_chain.header_at(1), Some(&headers[0]));
+}
+
+#[test]
+fn test_header_chain_verifies_merkle_proofs() {
+    let txids: Vec<Txid> = (1..=3u8).map(|i| Txid::from_byte_array([i; 32])).collect();
+    let merkle_root = calculate_root(txids.iter().copied()).unwrap();
+
+    let mut header_chain = regtest_chain();
+    let genesis = *header_chain.header_at(0).unwrap();
+    let block = mine(
+        &genesis,
+        TxMerkleNode::from_raw_hash(merkle_root.to_raw_hash()),
+        genesis.time + 1,
+        REGTEST_BITS,
+    );
+    header_chain.extend(vec![block]).unwrap();
+
+    // The last tx of an odd level is paired with itself
+    let proof = MerkleProof {
+        block_height: 1,
+        merkle: vec![
+            txids[2],
+            calculate_root(txids[..2].iter().copied()).unwrap(),
+        ],
+        pos: 2,
+    };
+    assert!(header_chain.verify_merkle_proof(&txids[2], &proof).is_ok());
+
+    let proof = MerkleProof {
+        block_height: 1,
+        merkle: vec![
+            txids[1],
+            calculate_root([txids[2], txids[2]].into_iter()).unwrap(),
+        ],
+        pos: 0,
+    };
+    assert!(header_chain.verify_merkle_proof(&txids[0], &proof).is_ok());
+    assert!(matches!(
+        header_chain.verify_merkle_proof(&txids[1], &proof),
+        Err(HeaderChainError::MerkleProofMismatch(_))
+    ));
+
+    // Proofs for blocks outside the chain
+    let proof = MerkleProof {
+        block_height: 2,
+        ..proof
+    };
+    assert!(header_chain.verify_merkle_proof(&txids[0], &proof).is_err());
+}
@@ -0,0 +1,105 @@
+use std::mem::size_of;
+
+use bridge::client::memory_cache::{Cache, CacheConfig, CacheWeight};
+
+#[derive(Clone)]
+struct Blob(Vec<u8>);
+
+impl CacheWeight for Blob {
+    fn weight(&self) -> usize {
+        self.0.len()
+    }
+}
+
+fn blob(len: usize) -> Blob {
+    Blob(vec![0; len])
+}
+
+// Weight of an entry with a one character key
+fn entry_weight(len: usize) -> usize {
+    size_of::<String>() + 1 + len
+}
+
+#[test]
+fn test_cache_evicts_least_recently_used_entries_by_size() {
+    let mut cache = Cache::new(entry_weight(100) * 3);
+    cache.put("a".to_string(), blob(100));
+    cache.put("b".to_string(), blob(100));
+    cache.put("c".to_string(), blob(100));
+    assert!(cache.get("a").is_some()); // "b" is now the least recently used entry
+
+    cache.put("d".to_string(), blob(150));
+
+    assert!(cache.contains("a"));
+    assert!(!cache.contains("b"));
+    assert!(!cache.contains("c"));
+    assert!(cache.contains("d"));
+    let stats = cache.stats();
+    assert_eq!(stats.entries, 2);
+    assert_eq!(stats.size_bytes, entry_weight(100) + entry_weight(150));
+    assert_eq!(stats.evictions, 2);
+}
+
+#[test]
+fn test_cache_counts_hits_and_misses() {
+    let mut cache = Cache::new(1024);
+    cache.put("a".to_string(), blob(10));
+
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("b").is_none());
+    assert!(cache.contains("a"));
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.evictions, 0);
+}
+
+#[test]
+fn test_cache_does_not_keep_entries_larger_than_capacity() {
+    let mut cache = Cache::new(entry_weight(100));
+    cache.put("a".to_string(), blob(100));
+    cache.put("b".to_string(), blob(101));
+
+    assert!(cache.contains("a"));
+    assert!(!cache.contains("b"));
+    assert_eq!(cache.stats().evictions, 1);
+}
+
+#[test]
+fn test_cache_replacing_entry_updates_size() {
+    let mut cache = Cache::new(1024);
+    cache.put("a".to_string(), blob(100));
+    cache.put("a".to_string(), blob(10));
+
+    let stats = cache.stats();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.size_bytes, entry_weight(10));
+}
+
+#[test]
+fn test_cache_shrinking_capacity_evicts_entries() {
+    let mut cache = Cache::new(1024);
+    cache.put("a".to_string(), blob(100));
+    cache.put("b".to_string(), blob(100));
+
+    cache.set_capacity(entry_weight(100));
+
+    assert!(!cache.contains("a"));
+    assert!(cache.contains("b"));
+    let stats = cache.stats();
+    assert_eq!(stats.capacity_bytes, entry_weight(100));
+    assert_eq!(stats.evictions, 1);
+}
+
+#[test]
+fn test_cache_config_defaults_missing_capacities() {
+    let config: CacheConfig = toml::from_str("spend_info_capacity_bytes = 4096").unwrap();
+
+    assert_eq!(config.spend_info_capacity_bytes, 4096);
+    assert_eq!(
+        config.lock_scripts_capacity_bytes,
+        CacheConfig::default().lock_scripts_capacity_bytes
+    );
+}
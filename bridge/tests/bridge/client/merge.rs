@@ -3,7 +3,12 @@ use std::str::FromStr;
 use bitcoin::{Amount, OutPoint, Txid};
 use bridge::{
     client::client::{BitVMClient, BitVMClientPublicData},
-    graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    graphs::{
+        base::PEG_OUT_FEE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
     transactions::base::Input,
 };
 
@@ -24,7 +29,7 @@ async fn test_merge_add_new_graph() {
     assert_eq!(data.peg_in_graphs.len(), 1);
     assert_eq!(data.peg_out_graphs.len(), 1);
 
-    client.merge_data(new_data);
+    client.merge_data(new_data).unwrap();
 
     let merged_data = client.data();
 
@@ -64,8 +69,13 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
     };
     let peg_in_graph_id = config
         .client_0
-        .create_peg_in_graph(input, &config.depositor_evm_address)
-        .await;
+        .create_peg_in_graph(
+            input,
+            &config.depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
 
     config.client_0.create_peg_out_graph(
         &peg_in_graph_id,
@@ -73,7 +83,7 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             outpoint: peg_out_outpoint,
             amount,
         },
-        config.commitment_secrets.clone(),
+        GraphParameters::default(),
     );
 
     let new_peg_in_graph = PegInGraph::new(
@@ -83,7 +93,9 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount: Amount::from_sat(INITIAL_AMOUNT),
         },
         &config.depositor_evm_address,
-    );
+        GraphTemplate::default(),
+    )
+    .unwrap();
 
     let new_peg_out_graph = PegOutGraph::new(
         &config.operator_context,
@@ -93,7 +105,9 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount,
         },
         &config.commitment_secrets,
-    );
+        GraphParameters::default(),
+    )
+    .unwrap();
 
     (config.client_0, new_peg_in_graph, new_peg_out_graph)
 }
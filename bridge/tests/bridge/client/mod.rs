@@ -1,7 +1,37 @@
+pub mod alerts;
+pub mod audit;
+pub mod broadcast_middleware;
+pub mod ceremony_schedule;
+pub mod chain_replay;
+pub mod clock;
+pub mod congestion;
+pub mod disprove_precomputation;
+pub mod esplora_notifications;
+pub mod ethereum_adaptor;
+pub mod event_log;
+pub mod faucet;
 pub mod fee;
+pub mod graph_history;
+pub mod graph_list;
+pub mod graph_store;
+pub mod heartbeat;
+pub mod jobs;
+pub mod keystore;
+pub mod light_client;
+pub mod memory_cache;
 pub mod merge;
 pub mod musig2_keys;
 pub mod musig2_peg_in;
 pub mod musig2_peg_out;
+pub mod peg_in_queue;
+pub mod private_data;
+pub mod profiling;
+pub mod read_handle;
+pub mod rehearsal;
+pub mod resume_ceremony;
+pub mod shutdown;
+pub mod staged_flush;
 pub mod sync;
+pub mod track_withdrawal;
 pub mod validate;
+pub mod watchtower;
@@ -1,8 +1,13 @@
 use bitcoin::Amount;
 
 use bridge::{
-    graphs::base::PEG_IN_FEE, scripts::generate_pay_to_pubkey_script_address,
-    transactions::base::Input,
+    client::client::BitVMClient,
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        template::GraphTemplate,
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::{base::Input, pre_signed_musig2::Musig2SigningProgress},
 };
 
 use serial_test::serial;
@@ -40,8 +45,13 @@ async fn test_musig2_peg_in() {
     .await;
 
     let graph_id = depositor_operator_verifier_0_client
-        .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
-        .await;
+        .create_peg_in_graph(
+            Input { outpoint, amount },
+            &config.depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
     println!("Depositor: Created new graph {graph_id}");
 
     println!("Depositor: Mining peg in deposit...");
@@ -60,6 +70,13 @@ async fn test_musig2_peg_in() {
     println!("Verifier 0: Generating nonces...");
     depositor_operator_verifier_0_client.push_verifier_nonces(&graph_id);
 
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let progress = signing_progress(&depositor_operator_verifier_0_client, &graph_id).unwrap();
+    assert_eq!(progress.nonces, vec![verifier_0]);
+    assert_eq!(progress.pending_nonces, vec![verifier_1]);
+    assert!(progress.signatures.is_empty());
+
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
 
@@ -80,6 +97,11 @@ async fn test_musig2_peg_in() {
     println!("Verifier 0: Pre-signing...");
     depositor_operator_verifier_0_client.push_verifier_signature(&graph_id);
 
+    let progress = signing_progress(&depositor_operator_verifier_0_client, &graph_id).unwrap();
+    assert!(progress.pending_nonces.is_empty());
+    assert_eq!(progress.signatures, vec![verifier_0]);
+    assert_eq!(progress.pending_signatures, vec![verifier_1]);
+
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
 
@@ -89,6 +111,7 @@ async fn test_musig2_peg_in() {
 
     println!("Verifier 1: Pre-signing...");
     verifier_1_client.push_verifier_signature(&graph_id);
+    assert!(signing_progress(&verifier_1_client, &graph_id).is_none());
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -108,3 +131,13 @@ async fn test_musig2_peg_in() {
     println!("Operator: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
 }
+
+fn signing_progress(client: &BitVMClient, graph_id: &String) -> Option<Musig2SigningProgress> {
+    client
+        .data()
+        .peg_in_graphs
+        .iter()
+        .find(|peg_in_graph| peg_in_graph.id() == graph_id)
+        .unwrap()
+        .signing_progress()
+}
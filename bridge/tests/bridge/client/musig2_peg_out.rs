@@ -3,7 +3,7 @@ use bitvm::chunk::api::type_conversion_utils::RawProof;
 use bridge::{
     client::{
         chain::{
-            chain::{Chain, PegOutEvent},
+            chain::{Chain, PegOutEvent, WithdrawerDestination},
             mock_adaptor::{MockAdaptor, MockAdaptorConfig},
         },
         client::BitVMClient,
@@ -11,7 +11,11 @@ use bridge::{
     contexts::{
         depositor::DepositorContext, operator::OperatorContext, withdrawer::WithdrawerContext,
     },
-    graphs::base::{PEG_IN_FEE, PEG_OUT_FEE},
+    graphs::{
+        base::{PegInGraphId, PegOutGraphId, PEG_IN_FEE, PEG_OUT_FEE},
+        peg_out::PegOutPresignedTransaction,
+        template::{GraphParameters, GraphTemplate},
+    },
     scripts::{
         generate_p2pkh_address, generate_pay_to_pubkey_script,
         generate_pay_to_pubkey_script_address,
@@ -27,8 +31,8 @@ use serial_test::serial;
 use crate::bridge::{
     faucet::{Faucet, FaucetType},
     helper::{
-        find_peg_in_graph_by_peg_out, generate_stub_outpoint, wait_for_confirmation_with_message,
-        wait_for_timelock_expiry,
+        find_peg_in_graph_by_peg_out, generate_stub_outpoint, get_peg_out_burnt_event,
+        wait_for_confirmation_with_message, wait_for_timelock_expiry,
     },
     setup::{setup_test, INITIAL_AMOUNT},
 };
@@ -76,6 +80,100 @@ async fn test_musig2_peg_out_take_1() {
         .expect("Failed to broadcast take 1");
 }
 
+#[tokio::test]
+#[serial]
+async fn test_musig2_peg_out_resign_take_1() {
+    println!("Testing musig2 re-signing for take 1");
+    let (
+        mut depositor_operator_verifier_0_client,
+        mut verifier_1_client,
+        peg_out_graph_id,
+        depositor_context,
+        withdrawer_evm_address,
+        withdrawer_context,
+        operator_context,
+        _,
+    ) = create_peg_out_graph().await;
+
+    println!("Invalidating take 1...");
+    depositor_operator_verifier_0_client
+        .invalidate_presigned_transactions(&peg_out_graph_id, &[PegOutPresignedTransaction::Take1])
+        .await
+        .expect("Failed to invalidate take 1");
+    let resign_status = depositor_operator_verifier_0_client
+        .resign_status(&peg_out_graph_id)
+        .unwrap();
+    assert_eq!(resign_status.len(), 1);
+    assert_eq!(
+        resign_status[0].transaction,
+        PegOutPresignedTransaction::Take1
+    );
+    assert_eq!(resign_status[0].round, 1);
+    assert!(resign_status[0].signed.is_empty());
+    depositor_operator_verifier_0_client.flush().await;
+
+    println!("Verifier 0 push take 1 nonces");
+    depositor_operator_verifier_0_client
+        .push_verifier_resign_nonces(&peg_out_graph_id)
+        .unwrap();
+    depositor_operator_verifier_0_client.flush().await;
+
+    println!("Verifier 1 push take 1 nonces");
+    verifier_1_client.sync().await;
+    verifier_1_client
+        .push_verifier_resign_nonces(&peg_out_graph_id)
+        .unwrap();
+    verifier_1_client.flush().await;
+
+    println!("Verifier 0 re-sign take 1");
+    depositor_operator_verifier_0_client.sync().await;
+    depositor_operator_verifier_0_client
+        .push_verifier_resign_signature(&peg_out_graph_id)
+        .unwrap();
+    depositor_operator_verifier_0_client.flush().await;
+
+    println!("Verifier 1 re-sign take 1");
+    verifier_1_client.sync().await;
+    verifier_1_client
+        .push_verifier_resign_signature(&peg_out_graph_id)
+        .unwrap();
+    verifier_1_client.flush().await;
+
+    depositor_operator_verifier_0_client.sync().await;
+    let resign_status = depositor_operator_verifier_0_client
+        .resign_status(&peg_out_graph_id)
+        .unwrap();
+    assert!(resign_status[0].pending.is_empty());
+
+    simulate_peg_out_from_l2(
+        &mut depositor_operator_verifier_0_client,
+        &peg_out_graph_id,
+        &operator_context,
+        &withdrawer_evm_address,
+        &withdrawer_context,
+    )
+    .await;
+
+    let with_kick_off_2_tx = true;
+    let with_challenge_tx = false;
+    let with_assert_tx = None;
+    broadcast_transactions_from_peg_out_graph(
+        &mut depositor_operator_verifier_0_client,
+        &peg_out_graph_id,
+        &depositor_context,
+        with_kick_off_2_tx,
+        with_challenge_tx,
+        with_assert_tx,
+    )
+    .await;
+
+    depositor_operator_verifier_0_client.sync().await;
+    depositor_operator_verifier_0_client
+        .broadcast_take_1(&peg_out_graph_id)
+        .await
+        .expect("Failed to broadcast take 1");
+}
+
 #[tokio::test]
 #[serial]
 async fn test_musig2_peg_out_take_2() {
@@ -331,7 +429,7 @@ async fn test_musig2_peg_out_peg_out() {
 
 async fn broadcast_transactions_from_peg_out_graph(
     client: &mut BitVMClient,
-    peg_out_graph_id: &String,
+    peg_out_graph_id: &PegOutGraphId,
     depositor_context: &DepositorContext,
     with_kick_off_2_tx: bool,
     with_challenge_tx: bool,
@@ -444,7 +542,7 @@ async fn broadcast_transactions_from_peg_out_graph(
 async fn create_peg_out_graph() -> (
     BitVMClient,
     BitVMClient,
-    String,
+    PegOutGraphId,
     DepositorContext,
     String,
     WithdrawerContext,
@@ -503,7 +601,7 @@ async fn create_peg_out_graph() -> (
             outpoint: kick_off_outpoint,
             amount: kick_off_input_amount,
         },
-        config.commitment_secrets,
+        GraphParameters::default(),
     );
 
     println!("Verifier 0 push peg-in nonces");
@@ -575,7 +673,7 @@ async fn create_peg_in_graph(
     deposit_funding_address: Address,
     deposit_amount: Amount,
     depositor_evm_address: &String,
-) -> String {
+) -> PegInGraphId {
     let deposit_outpoint =
         generate_stub_outpoint(client_0, &deposit_funding_address, deposit_amount).await;
     let graph_id = client_0
@@ -585,8 +683,10 @@ async fn create_peg_in_graph(
                 amount: deposit_amount,
             },
             depositor_evm_address,
+            GraphTemplate::default(),
         )
-        .await;
+        .await
+        .unwrap();
     println!("Peg in graph created: {}", graph_id);
 
     client_0
@@ -600,7 +700,7 @@ async fn create_peg_in_graph(
 
 async fn simulate_peg_out_from_l2(
     client: &mut BitVMClient,
-    peg_out_graph_id: &String,
+    peg_out_graph_id: &PegOutGraphId,
     operator_context: &OperatorContext,
     withdrawer_evm_address: &String,
     withdrawer_context: &WithdrawerContext,
@@ -614,25 +714,29 @@ async fn simulate_peg_out_from_l2(
     );
     let peg_in_confirm_amount = peg_in_confirm.tx().output[peg_in_confirm_vout].value;
 
+    let peg_out_event = PegOutEvent {
+        source_outpoint: OutPoint {
+            txid: peg_in_confirm.tx().compute_txid(),
+            vout: peg_in_confirm_vout.to_u32().unwrap(),
+        },
+        amount: peg_in_confirm_amount,
+        timestamp: 1722328130u32,
+        withdrawer_chain_address: withdrawer_evm_address.clone(),
+        withdrawer_destination_address: generate_p2pkh_address(
+            withdrawer_context.network,
+            &withdrawer_context.withdrawer_public_key,
+        )
+        .to_string(),
+        withdrawer_destination: WithdrawerDestination::PublicKeyHash(
+            withdrawer_context.withdrawer_public_key.pubkey_hash(),
+        ),
+        operator_public_key: operator_context.operator_public_key,
+        tx_hash: [0u8; 4].into(),
+        withdrawal_nonce: 0,
+    };
     let mock_adaptor_config = MockAdaptorConfig {
-        peg_out_init_events: Some(vec![PegOutEvent {
-            source_outpoint: OutPoint {
-                txid: peg_in_confirm.tx().compute_txid(),
-                vout: peg_in_confirm_vout.to_u32().unwrap(),
-            },
-            amount: peg_in_confirm_amount,
-            timestamp: 1722328130u32,
-            withdrawer_chain_address: withdrawer_evm_address.clone(),
-            withdrawer_destination_address: generate_p2pkh_address(
-                withdrawer_context.network,
-                &withdrawer_context.withdrawer_public_key,
-            )
-            .to_string(),
-            withdrawer_public_key_hash: withdrawer_context.withdrawer_public_key.pubkey_hash(),
-            operator_public_key: operator_context.operator_public_key,
-            tx_hash: [0u8; 4].into(),
-        }]),
-        peg_out_burnt_events: None,
+        peg_out_init_events: Some(vec![peg_out_event.clone()]),
+        peg_out_burnt_events: Some(vec![get_peg_out_burnt_event(&peg_out_event)]),
         peg_out_minted_events: None,
     };
     let mock_adaptor = MockAdaptor::new(Some(mock_adaptor_config));
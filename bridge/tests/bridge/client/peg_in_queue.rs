@@ -0,0 +1,86 @@
+use bitcoin::{Amount, Network};
+
+use bridge::{
+    client::peg_in_queue::{
+        PegInPriorityQueue, PegInPriorityWeights, PegInProcessingConfig, PegInQueueEntry,
+    },
+    constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_DAY},
+};
+
+const HEIGHT: u32 = 100_000;
+
+fn entry(graph_id: &str, btc: f64, deposit_height: Option<u32>) -> PegInQueueEntry {
+    PegInQueueEntry {
+        graph_id: graph_id.to_string(),
+        amount: Amount::from_btc(btc).unwrap(),
+        deposit_height,
+        refund_timelock: NUM_BLOCKS_PER_2_WEEKS,
+    }
+}
+
+fn order(config: &PegInProcessingConfig, entries: Vec<PegInQueueEntry>) -> Vec<String> {
+    PegInPriorityQueue::new(config, Network::Bitcoin, HEIGHT, entries)
+        .map(|entry| entry.graph_id)
+        .collect()
+}
+
+#[test]
+fn test_peg_in_queue_urgent_graphs_first() {
+    let config = PegInProcessingConfig::default();
+    let almost_refundable = HEIGHT - NUM_BLOCKS_PER_2_WEEKS + 10;
+    let entries = vec![
+        entry("large", 10.0, Some(HEIGHT - 100)),
+        entry("urgent", 0.1, Some(almost_refundable)),
+        entry("unconfirmed", 50.0, None),
+    ];
+
+    assert_eq!(
+        order(&config, entries),
+        vec!["urgent", "unconfirmed", "large"]
+    );
+}
+
+#[test]
+fn test_peg_in_queue_weights() {
+    let entries = vec![
+        entry("old", 1.0, Some(HEIGHT - NUM_BLOCKS_PER_DAY)),
+        entry("large", 100.0, Some(HEIGHT - 1)),
+    ];
+
+    let by_age = PegInProcessingConfig {
+        weights: PegInPriorityWeights {
+            confirmation_age: 1.0,
+            amount: 0.0,
+            refund_urgency: 0.0,
+        },
+        ..PegInProcessingConfig::default()
+    };
+    assert_eq!(order(&by_age, entries.clone()), vec!["old", "large"]);
+
+    let by_amount = PegInProcessingConfig {
+        weights: PegInPriorityWeights {
+            confirmation_age: 0.0,
+            amount: 1.0,
+            refund_urgency: 0.0,
+        },
+        ..PegInProcessingConfig::default()
+    };
+    assert_eq!(order(&by_amount, entries), vec!["large", "old"]);
+}
+
+#[test]
+fn test_peg_in_queue_ties_are_deterministic() {
+    let config = PegInProcessingConfig::default();
+    let entries = vec![
+        entry("c", 1.0, Some(HEIGHT - 10)),
+        entry("a", 1.0, Some(HEIGHT - 10)),
+        entry("b", 1.0, Some(HEIGHT - 10)),
+    ];
+
+    let queue = PegInPriorityQueue::new(&config, Network::Bitcoin, HEIGHT, entries);
+    assert_eq!(queue.len(), 3);
+    assert_eq!(
+        queue.map(|entry| entry.graph_id).collect::<Vec<_>>(),
+        vec!["a", "b", "c"]
+    );
+}
@@ -0,0 +1,167 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use bitcoin::{hashes::Hash, Network, Txid};
+use bitvm::signatures::signing_winternitz::WinternitzSecret;
+use bridge::{
+    client::{
+        client::BitVMClientPrivateData,
+        files::{
+            get_private_data_file_path, get_private_data_from_files, get_private_graph_file_path,
+            read_private_graph_file, save_private_graph_file, PrivateDataRole,
+        },
+    },
+    commitments::CommitmentMessageId,
+    contexts::base::generate_keys_from_secret,
+    error::{ClientError, Error},
+    serialization::serialize,
+    transactions::signing_musig2::generate_nonce,
+};
+
+use crate::bridge::helper::empty_temp_dir;
+
+const GRAPH_ID: &str = "private_data_test_graph";
+
+// Operator and verifier keys of the same directory
+fn private_data() -> BitVMClientPrivateData {
+    let (_, operator_public_key) = generate_keys_from_secret(Network::Regtest, &"1".repeat(64));
+    let (_, verifier_public_key) = generate_keys_from_secret(Network::Regtest, &"2".repeat(64));
+
+    let mut private_data = BitVMClientPrivateData::default();
+    private_data.commitment_secrets.insert(
+        operator_public_key,
+        HashMap::from([(
+            GRAPH_ID.to_string(),
+            HashMap::from([(CommitmentMessageId::StartTime, WinternitzSecret::new(4))]),
+        )]),
+    );
+    private_data.secret_nonces.insert(
+        verifier_public_key,
+        HashMap::from([(
+            GRAPH_ID.to_string(),
+            HashMap::from([(
+                Txid::from_byte_array([1; 32]),
+                HashMap::from([(0, generate_nonce())]),
+            )]),
+        )]),
+    );
+    private_data
+}
+
+fn save_all(dir: &Path, private_data: &BitVMClientPrivateData) {
+    for (role, public_key, graph_id) in private_data.graph_keys() {
+        let graph_data = private_data.graph_data(role, &public_key, &graph_id);
+        save_private_graph_file(dir, role, &public_key, &graph_id, &serialize(&graph_data));
+    }
+}
+
+#[test]
+fn test_private_data_is_split_by_role_and_graph() {
+    let dir = empty_temp_dir("private_data_split");
+    let private_data = private_data();
+    save_all(&dir, &private_data);
+
+    let mut graph_keys = private_data.graph_keys();
+    graph_keys.sort_by_key(|(role, _, _)| *role == PrivateDataRole::Verifier);
+    let paths: Vec<PathBuf> = graph_keys
+        .iter()
+        .map(|(role, public_key, graph_id)| {
+            get_private_graph_file_path(&dir, *role, public_key, graph_id)
+        })
+        .collect();
+    assert_eq!(paths.len(), 2);
+    assert_ne!(paths[0].parent(), paths[1].parent());
+    assert!(paths.iter().all(|path| path.exists()));
+
+    assert!(get_private_data_from_files(&dir).unwrap() == private_data);
+}
+
+#[test]
+fn test_private_data_written_concurrently() {
+    let dir = empty_temp_dir("private_data_concurrent");
+    let private_data = private_data();
+
+    // Two threads per role save the same graph file, every write is complete
+    let graph_keys = private_data.graph_keys();
+    thread::scope(|scope| {
+        for (role, public_key, graph_id) in [graph_keys.clone(), graph_keys].concat() {
+            let (dir, private_data) = (&dir, &private_data);
+            scope.spawn(move || {
+                for _ in 0..10 {
+                    let graph_data = private_data.graph_data(role, &public_key, &graph_id);
+                    save_private_graph_file(
+                        dir,
+                        role,
+                        &public_key,
+                        &graph_id,
+                        &serialize(&graph_data),
+                    );
+                }
+            });
+        }
+    });
+
+    assert!(get_private_data_from_files(&dir).unwrap() == private_data);
+}
+
+#[test]
+fn test_partially_written_private_data_is_detected() {
+    let dir = empty_temp_dir("private_data_partial");
+    let private_data = private_data();
+    save_all(&dir, &private_data);
+
+    let (role, public_key, graph_id) = private_data.graph_keys().remove(0);
+    let path = get_private_graph_file_path(&dir, role, &public_key, &graph_id);
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::write(&path, &contents[..contents.len() / 2]).unwrap();
+
+    assert!(matches!(
+        read_private_graph_file(&path),
+        Err(Error::Client(ClientError::PrivateGraphFileCorrupted(corrupted))) if corrupted == graph_id
+    ));
+    assert!(matches!(
+        get_private_data_from_files(&dir),
+        Err(Error::Client(ClientError::PrivateGraphFileCorrupted(_)))
+    ));
+}
+
+#[test]
+fn test_undeserializable_private_data_is_an_error() {
+    let dir = empty_temp_dir("private_data_undeserializable");
+    let private_data = private_data();
+    save_all(&dir, &private_data);
+
+    // Intact according to its checksum, but not private graph data
+    let (role, public_key, graph_id) = private_data.graph_keys().remove(0);
+    save_private_graph_file(&dir, role, &public_key, &graph_id, "not private data");
+    let path = get_private_graph_file_path(&dir, role, &public_key, &graph_id);
+
+    assert!(matches!(
+        get_private_data_from_files(&dir),
+        Err(Error::Client(ClientError::PrivateDataInvalid(invalid, _)))
+            if invalid == path.display().to_string()
+    ));
+}
+
+#[test]
+fn test_private_data_file_is_migrated() {
+    let dir = empty_temp_dir("private_data_migrated");
+    let private_data = private_data();
+    let legacy_path = get_private_data_file_path(&dir);
+    fs::create_dir_all(legacy_path.parent().unwrap()).unwrap();
+    fs::write(&legacy_path, serialize(&private_data)).unwrap();
+
+    assert!(get_private_data_from_files(&dir).unwrap() == private_data);
+    // No plaintext copy is left behind once the graph files hold the secrets
+    assert!(!legacy_path.exists());
+    assert_eq!(
+        fs::read_dir(legacy_path.parent().unwrap()).unwrap().count(),
+        2
+    );
+    // Read from the graph files from now on
+    assert!(get_private_data_from_files(&dir).unwrap() == private_data);
+}
@@ -0,0 +1,46 @@
+use std::{thread::sleep, time::Duration};
+
+use bridge::client::profiling::{
+    enable_startup_profile, is_startup_profile_enabled, profile_phase, startup_profile,
+};
+
+fn indentation_of(report: &str, phase_name: &str) -> usize {
+    let line = report
+        .lines()
+        .find(|line| line.contains(phase_name))
+        .unwrap();
+    line.len() - line.trim_start().len()
+}
+
+#[test]
+fn test_startup_profile_nests_phases() {
+    enable_startup_profile();
+    assert!(is_startup_profile_enabled());
+
+    let result = profile_phase("outer test phase", || {
+        profile_phase("inner test phase", || sleep(Duration::from_millis(10)));
+        42
+    });
+    assert_eq!(result, 42);
+
+    let profile = startup_profile().unwrap();
+    let inner = profile
+        .phases()
+        .iter()
+        .find(|phase| phase.name == "inner test phase")
+        .unwrap();
+    let outer = profile
+        .phases()
+        .iter()
+        .find(|phase| phase.name == "outer test phase")
+        .unwrap();
+    assert!(inner.duration >= Duration::from_millis(10));
+    assert!(outer.duration >= inner.duration);
+    assert!(outer.start <= inner.start);
+
+    let report = profile.to_string();
+    println!("{report}");
+    assert!(
+        indentation_of(&report, "inner test phase") > indentation_of(&report, "outer test phase")
+    );
+}
@@ -0,0 +1,76 @@
+use bitcoin::Amount;
+
+use bridge::{
+    client::sdk::query::ClientCliQuery,
+    graphs::{base::PEG_IN_FEE, template::GraphTemplate},
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::Input,
+};
+
+use crate::bridge::{
+    faucet::{Faucet, FaucetType},
+    helper::generate_stub_outpoint,
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_read_handle_sees_published_data() {
+    let mut config = setup_test().await;
+    config.client_0.sync().await;
+
+    let read_handle = config.client_0.read_handle();
+    assert!(*read_handle.data() == *config.client_0.data());
+    let peg_in_graph_count = read_handle.data().peg_in_graphs.len();
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    let faucet = Faucet::new(FaucetType::EsploraRegtest);
+    faucet.fund_input(&address, amount).await.wait().await;
+    let outpoint = generate_stub_outpoint(&config.client_0, &address, amount).await;
+
+    config
+        .client_0
+        .create_peg_in_graph(
+            Input { outpoint, amount },
+            &config.depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
+
+    // Changes are not visible to readers until they are flushed
+    assert_eq!(read_handle.data().peg_in_graphs.len(), peg_in_graph_count);
+
+    // Readers run in their own tasks while the client keeps mutating its data
+    let depositor_public_key = config.depositor_context.depositor_public_key;
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let read_handle = read_handle.clone();
+            tokio::spawn(async move {
+                read_handle
+                    .get_depositor_status(&depositor_public_key)
+                    .await
+            })
+        })
+        .collect();
+    config.client_0.flush().await;
+    for reader in readers {
+        reader.await.expect("Reader task failed");
+    }
+
+    assert!(*read_handle.data() == *config.client_0.data());
+    assert_eq!(
+        read_handle
+            .get_depositor_status(&depositor_public_key)
+            .await
+            .len(),
+        config
+            .client_0
+            .get_depositor_status(&depositor_public_key)
+            .await
+            .len()
+    );
+}
@@ -0,0 +1,207 @@
+use bitcoin::Amount;
+use bridge::{
+    client::rehearsal::{
+        Actor, ExpectedBalance, RehearsalAction, RehearsalProof, Scenario, StepExpectation,
+    },
+    graphs::base::{PEG_IN_FEE, PEG_OUT_FEE},
+    transactions::base::MIN_RELAY_FEE_PEG_OUT,
+};
+
+const EXAMPLES: [(&str, &str); 3] = [
+    (
+        "take_1",
+        include_str!("../../../../regtest/rehearsals/take_1.toml"),
+    ),
+    (
+        "disprove",
+        include_str!("../../../../regtest/rehearsals/disprove.toml"),
+    ),
+    (
+        "kick_off_timeout",
+        include_str!("../../../../regtest/rehearsals/kick_off_timeout.toml"),
+    ),
+];
+
+const SCENARIO: &str = r#"
+name = "test"
+amount = 100000
+
+[participants]
+depositor = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7"
+operator = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac"
+verifiers = ["ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2"]
+challenger = "5d6c5a2b1f0c09e8f4e7d6b1c2a3f4e5d6c7b8a9f0e1d2c3b4a5968778695a4b"
+"#;
+
+fn scenario_with(steps: &str) -> Result<Scenario, String> {
+    Scenario::from_toml(&format!("{SCENARIO}\n{steps}"))
+}
+
+#[test]
+fn test_example_scenarios_are_valid() {
+    for (name, content) in EXAMPLES {
+        let scenario =
+            Scenario::from_toml(content).unwrap_or_else(|e| panic!("{name} is invalid: {e}"));
+        assert!(!scenario.steps.is_empty(), "{name} has no steps");
+    }
+}
+
+#[test]
+fn test_actors_are_parsed() {
+    assert_eq!("operator".parse::<Actor>(), Ok(Actor::Operator));
+    assert_eq!("verifier_1".parse::<Actor>(), Ok(Actor::Verifier(1)));
+    assert_eq!(Actor::Verifier(1).to_string(), "verifier_1");
+    assert!("verifier_x".parse::<Actor>().is_err());
+    assert!("withdrawer".parse::<Actor>().is_err());
+}
+
+#[test]
+fn test_scenario_defaults() {
+    let scenario = scenario_with("").unwrap();
+    assert_eq!(scenario.proof, RehearsalProof::Valid);
+    assert_eq!(
+        scenario.actors(),
+        vec![
+            Actor::Depositor,
+            Actor::Operator,
+            Actor::Verifier(0),
+            Actor::Challenger
+        ]
+    );
+
+    let scenario = scenario_with(
+        r#"
+[[steps]]
+at = 3
+actor = "operator"
+action = "take_1"
+"#,
+    )
+    .unwrap();
+    assert_eq!(scenario.steps[0].action, RehearsalAction::Take1);
+    assert_eq!(scenario.steps[0].expect, StepExpectation::Accepted);
+}
+
+#[test]
+fn test_scenario_rejects_invalid_steps() {
+    // Only the operator holds the keys of its txs
+    assert!(scenario_with(
+        r#"
+[[steps]]
+at = 0
+actor = "verifier_0"
+action = "kick_off_1"
+"#
+    )
+    .is_err());
+
+    assert!(scenario_with(
+        r#"
+[[steps]]
+at = 0
+actor = "verifier_1"
+action = "disprove"
+"#
+    )
+    .is_err());
+
+    assert!(scenario_with(
+        r#"
+[[steps]]
+at = 0
+actor = "operator"
+action = "challenge"
+"#
+    )
+    .is_err());
+
+    assert!(scenario_with(
+        r#"
+[[steps]]
+at = 4
+actor = "operator"
+action = "kick_off_1"
+
+[[steps]]
+at = 2
+actor = "operator"
+action = "kick_off_2"
+"#
+    )
+    .is_err());
+
+    assert!(scenario_with(
+        r#"
+[[expected_balances]]
+actor = "challenger"
+min_change = 10
+max_change = 0
+"#
+    )
+    .is_err());
+}
+
+#[test]
+fn test_scenario_funding() {
+    let scenario = scenario_with(
+        r#"
+[[steps]]
+at = 0
+actor = "operator"
+action = "peg_out"
+
+[[steps]]
+at = 6
+actor = "challenger"
+action = "challenge"
+
+[[steps]]
+at = 7
+actor = "verifier_0"
+action = "challenge"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        scenario.setup_funding(),
+        vec![
+            (Actor::Depositor, Amount::from_sat(100000 + PEG_IN_FEE)),
+            (Actor::Operator, Amount::from_sat(100000 + PEG_OUT_FEE)),
+            (
+                Actor::Operator,
+                Amount::from_sat(100000 + MIN_RELAY_FEE_PEG_OUT)
+            ),
+        ]
+    );
+    assert_eq!(
+        scenario.challenge_funding(Amount::from_sat(5000)),
+        vec![
+            (Actor::Challenger, Amount::from_sat(5000)),
+            (Actor::Verifier(0), Amount::from_sat(5000)),
+        ]
+    );
+
+    // Without a peg-out in the timeline, the operator only funds its peg-out confirm input
+    assert_eq!(scenario_with("").unwrap().setup_funding().len(), 2);
+}
+
+#[test]
+fn test_expected_balance_bounds() {
+    let expected = ExpectedBalance {
+        actor: Actor::Operator,
+        min_change: Some(-10),
+        max_change: Some(10),
+    };
+    assert!(expected.is_met(0));
+    assert!(expected.is_met(-10));
+    assert!(!expected.is_met(11));
+
+    let at_least = ExpectedBalance {
+        min_change: Some(1),
+        max_change: None,
+        ..expected
+    };
+    assert!(at_least.is_met(i64::MAX));
+    assert!(!at_least.is_met(0));
+}
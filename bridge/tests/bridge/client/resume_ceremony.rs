@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use bitcoin::{hashes::Hash, PublicKey, Txid};
+use bridge::{
+    client::{client::BitVMClientPrivateData, secret_nonces::EncryptedSecretNonces},
+    graphs::{base::BaseGraph, peg_in::PegInGraph, template::GraphTemplate},
+    transactions::{pre_signed_musig2::CeremonyStep, signing_musig2::generate_nonce},
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+const GRAPH_ID: &str = "resume_ceremony_test_graph";
+
+#[tokio::test]
+async fn test_peg_in_ceremony_status() {
+    let config = setup_test().await;
+    let mut peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let (verifier_0, verifier_1) = (&config.verifier_0_context, &config.verifier_1_context);
+    let step =
+        |graph: &PegInGraph, secret_nonces| graph.ceremony_status(verifier_0, secret_nonces).step;
+
+    assert_eq!(step(&peg_in_graph, None), CeremonyStep::PushNonces);
+    let secret_nonces_0 = peg_in_graph.push_verifier_nonces(verifier_0);
+    // Nonces are never pushed twice, the secret nonces of the first push stay valid
+    assert!(peg_in_graph.push_verifier_nonces(verifier_0).is_empty());
+    assert_eq!(
+        step(&peg_in_graph, Some(&secret_nonces_0)),
+        CeremonyStep::AwaitNonces
+    );
+    assert_eq!(step(&peg_in_graph, None), CeremonyStep::NoncesLost);
+
+    let secret_nonces_1 = peg_in_graph.push_verifier_nonces(verifier_1);
+    assert_eq!(
+        step(&peg_in_graph, Some(&secret_nonces_0)),
+        CeremonyStep::Sign
+    );
+    peg_in_graph
+        .verifier_sign(verifier_0, &secret_nonces_0)
+        .unwrap();
+    assert_eq!(
+        step(&peg_in_graph, Some(&secret_nonces_0)),
+        CeremonyStep::Signed
+    );
+    // Signed transactions no longer need their secret nonces
+    assert_eq!(step(&peg_in_graph, None), CeremonyStep::Signed);
+
+    peg_in_graph
+        .verifier_sign(verifier_1, &secret_nonces_1)
+        .unwrap();
+    let status = peg_in_graph.ceremony_status(verifier_1, None);
+    assert_eq!(status.step, CeremonyStep::Signed);
+    assert!(status.to_string().contains(": signed\n"));
+}
+
+#[tokio::test]
+async fn test_encrypted_secret_nonces() {
+    let config = setup_test().await;
+    let keypair = &config.verifier_0_context.verifier_keypair;
+    let secret_nonces = HashMap::from([(
+        Txid::from_byte_array([1; 32]),
+        HashMap::from([(0, generate_nonce()), (1, generate_nonce())]),
+    )]);
+
+    let encrypted = EncryptedSecretNonces::encrypt(keypair, GRAPH_ID, &secret_nonces).unwrap();
+    assert_eq!(encrypted.decrypt(keypair, GRAPH_ID).unwrap(), secret_nonces);
+    // Bound to the graph and the verifier key
+    assert!(encrypted.decrypt(keypair, "other_graph").is_err());
+    assert!(encrypted
+        .decrypt(&config.verifier_1_context.verifier_keypair, GRAPH_ID)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_decrypt_secret_nonces_of_own_key() {
+    let config = setup_test().await;
+    let keypair_0 = &config.verifier_0_context.verifier_keypair;
+    let keypair_1 = &config.verifier_1_context.verifier_keypair;
+    let secret_nonces = HashMap::from([(
+        Txid::from_byte_array([1; 32]),
+        HashMap::from([(0, generate_nonce())]),
+    )]);
+
+    let mut private_data = BitVMClientPrivateData::default();
+    for keypair in [keypair_0, keypair_1] {
+        private_data.encrypted_secret_nonces.insert(
+            PublicKey::new(keypair.public_key()),
+            HashMap::from([(
+                GRAPH_ID.to_string(),
+                EncryptedSecretNonces::encrypt(keypair, GRAPH_ID, &secret_nonces).unwrap(),
+            )]),
+        );
+    }
+
+    assert!(private_data.decrypt_secret_nonces(keypair_0).is_empty());
+    let verifier_0 = PublicKey::new(keypair_0.public_key());
+    let verifier_1 = PublicKey::new(keypair_1.public_key());
+    assert_eq!(
+        private_data.secret_nonces[&verifier_0][GRAPH_ID],
+        secret_nonces
+    );
+    assert!(!private_data
+        .encrypted_secret_nonces
+        .contains_key(&verifier_0));
+    // Nonces of other verifiers sharing the directory stay encrypted
+    assert!(!private_data.secret_nonces.contains_key(&verifier_1));
+    assert!(private_data.encrypted_secret_nonces[&verifier_1].contains_key(GRAPH_ID));
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use bridge::client::shutdown::{Shutdown, ShutdownReason};
+
+#[tokio::test]
+async fn test_shutdown_after_max_runtime() {
+    let max_runtime = Duration::from_millis(200);
+    let mut shutdown = Shutdown::listen(Some(max_runtime)).unwrap();
+    assert_eq!(shutdown.requested(), None);
+
+    let reason = tokio::time::timeout(Duration::from_secs(10), shutdown.wait())
+        .await
+        .expect("Shutdown was not requested after the maximum runtime");
+    assert_eq!(reason, ShutdownReason::MaxRuntime(max_runtime));
+    assert_eq!(shutdown.requested(), Some(reason));
+    assert!(shutdown.uptime() >= max_runtime);
+}
+
+#[tokio::test]
+async fn test_no_shutdown_without_max_runtime() {
+    let mut shutdown = Shutdown::listen(None).unwrap();
+
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), shutdown.wait())
+            .await
+            .is_err()
+    );
+    assert_eq!(shutdown.requested(), None);
+}
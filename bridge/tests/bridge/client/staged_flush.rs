@@ -0,0 +1,161 @@
+use bridge::{
+    client::{
+        client::BitVMClientPublicData,
+        staged_flush::{
+            validate_transition, FlushAcknowledgment, FlushCoordinationConfig, StagedFlush,
+            StagedFlushStatus,
+        },
+    },
+    error::{Error, ValidationError},
+    graphs::{
+        base::BaseGraph,
+        peg_in::PegInGraph,
+        peg_out::{PegOutGraph, PegOutPresignedTransaction},
+        template::{GraphParameters, GraphTemplate},
+    },
+};
+
+use crate::bridge::{
+    helper::{seeded_keypair, seeded_public_key, stub_input},
+    setup::setup_test,
+};
+
+const STAGE: &str = "1700000000000-bridge-client-data.json";
+const NOW: u64 = 1_700_000_000;
+
+fn data(peg_out_graph: &PegOutGraph) -> BitVMClientPublicData {
+    BitVMClientPublicData {
+        version: 1,
+        peg_in_graphs: vec![],
+        peg_out_graphs: vec![peg_out_graph.clone()],
+    }
+}
+
+fn is_illegal_transition(result: Result<(), Error>) -> bool {
+    matches!(
+        result,
+        Err(Error::Validation(ValidationError::IllegalTransition(_, _)))
+    )
+}
+
+#[test]
+fn test_staged_flush_settles_on_acknowledgments() {
+    let (approver, other_approver, rejecter) =
+        (seeded_keypair(1), seeded_keypair(2), seeded_keypair(3));
+    let config = FlushCoordinationConfig {
+        required_acknowledgments: 2,
+        ..FlushCoordinationConfig::default()
+    };
+
+    let approval = FlushAcknowledgment::new(&approver, STAGE, NOW, None);
+    assert!(approval.is_approval());
+    assert!(approval.verify_signature());
+    let restored: FlushAcknowledgment =
+        serde_json::from_str(&serde_json::to_string(&approval).unwrap()).unwrap();
+    assert_eq!(restored, approval);
+    assert!(restored.verify_signature());
+
+    // Acknowledgments changed after signing or signed by another key do not verify
+    let tampered = FlushAcknowledgment {
+        rejection: Some(String::from("forged")),
+        ..approval.clone()
+    };
+    assert!(!tampered.verify_signature());
+    let forged = FlushAcknowledgment {
+        public_key: seeded_public_key(2),
+        ..approval.clone()
+    };
+    assert!(!forged.verify_signature());
+
+    // Not enough approvals yet
+    let mut staged_flush = StagedFlush::new(STAGE.to_string(), NOW);
+    assert!(!staged_flush.record_acknowledgments(&[approval.clone()], &config, NOW));
+    assert!(staged_flush.is_pending());
+    assert_eq!(staged_flush.approvals, vec![seeded_public_key(1)]);
+
+    let other_approval = FlushAcknowledgment::new(&other_approver, STAGE, NOW, None);
+    assert!(staged_flush.record_acknowledgments(
+        &[approval.clone(), other_approval.clone()],
+        &config,
+        NOW
+    ));
+
+    // A single rejection outweighs any number of approvals
+    let rejection =
+        FlushAcknowledgment::new(&rejecter, STAGE, NOW, Some(String::from("bad nonces")));
+    let mut staged_flush = StagedFlush::new(STAGE.to_string(), NOW);
+    assert!(!staged_flush.record_acknowledgments(
+        &[approval.clone(), other_approval, rejection],
+        &config,
+        NOW
+    ));
+    assert_eq!(
+        staged_flush.status,
+        StagedFlushStatus::Rejected(vec![(seeded_public_key(3), String::from("bad nonces"))])
+    );
+    assert!(staged_flush.to_string().contains("REJECTED"));
+
+    // Expired stages are given up and stay that way
+    let mut staged_flush = StagedFlush::new(STAGE.to_string(), NOW);
+    let expired = NOW + config.expiry;
+    assert!(!staged_flush.record_acknowledgments(&[approval], &config, expired));
+    assert_eq!(staged_flush.status, StagedFlushStatus::Unacknowledged);
+    assert!(staged_flush.to_string().contains("UNACKNOWLEDGED"));
+}
+
+#[tokio::test]
+async fn test_illegal_transitions_are_rejected() {
+    let config = setup_test().await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let fresh_peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+    let mut canonical_graph = fresh_peg_out_graph.clone();
+    canonical_graph.push_verifier_nonces(&config.verifier_0_context);
+    let canonical = data(&canonical_graph);
+
+    // Contributions of other members are added
+    let mut staged_graph = canonical_graph.clone();
+    staged_graph.push_verifier_nonces(&config.verifier_1_context);
+    assert!(validate_transition(&canonical, &data(&staged_graph)).is_ok());
+
+    // Graphs the committee does not know yet are not compared
+    let mut unknown_graph_data = data(&staged_graph);
+    unknown_graph_data.peg_in_graphs.push(peg_in_graph.clone());
+    assert!(validate_transition(&canonical, &unknown_graph_data).is_ok());
+
+    // Nonces a member already contributed are not replaced
+    let mut staged_graph = fresh_peg_out_graph.clone();
+    staged_graph.push_verifier_nonces(&config.verifier_0_context);
+    assert!(is_illegal_transition(validate_transition(
+        &canonical,
+        &data(&staged_graph)
+    )));
+
+    // Re-sign rounds start one at a time
+    let mut staged_graph = canonical_graph.clone();
+    staged_graph
+        .invalidate_presigned_transactions(&[PegOutPresignedTransaction::Take1])
+        .unwrap();
+    assert!(validate_transition(&canonical, &data(&staged_graph)).is_ok());
+    staged_graph
+        .invalidate_presigned_transactions(&[PegOutPresignedTransaction::Take1])
+        .unwrap();
+    let result = validate_transition(&canonical, &data(&staged_graph));
+    assert!(matches!(
+        &result,
+        Err(Error::Validation(ValidationError::IllegalTransition(graph_id, _)))
+            if graph_id == staged_graph.id()
+    ));
+}
@@ -1,7 +1,11 @@
 use bitcoin::Amount;
 
 use bridge::{
-    graphs::base::PEG_OUT_FEE, scripts::generate_pay_to_pubkey_script_address,
+    graphs::{
+        base::PEG_OUT_FEE,
+        template::{GraphParameters, GraphTemplate},
+    },
+    scripts::generate_pay_to_pubkey_script_address,
     transactions::base::Input,
 };
 
@@ -32,8 +36,13 @@ async fn test_sync() {
     println!("Creating peg in graph ...");
     let peg_in_graph_id = config
         .client_0
-        .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
-        .await;
+        .create_peg_in_graph(
+            Input { outpoint, amount },
+            &config.depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
 
     println!("Creating peg out graph ...");
     config.client_0.create_peg_out_graph(
@@ -50,7 +59,7 @@ async fn test_sync() {
             .await,
             amount,
         },
-        config.commitment_secrets,
+        GraphParameters::default(),
     );
 
     println!("Save to remote");
@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Txid};
+use bridge::{
+    client::chain::chain::{PegOutEvent, WithdrawerDestination},
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        peg_in::PegInGraph,
+        peg_out::{OperatorReimbursementStage, PegOutGraph, WithdrawalStage},
+        template::{GraphParameters, GraphTemplate},
+    },
+    scripts::generate_p2pkh_address,
+    transactions::base::Input,
+};
+use esplora_client::TxStatus;
+
+use crate::bridge::{
+    helper::random_hex,
+    setup::{setup_test, SetupConfig, INITIAL_AMOUNT},
+};
+
+fn input(amount: u64) -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(&random_hex(32)).unwrap(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(amount),
+    }
+}
+
+fn requested_peg_out_graph(config: &SetupConfig) -> PegOutGraph {
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        input(INITIAL_AMOUNT + PEG_IN_FEE),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let mut peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        input(INITIAL_AMOUNT),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    peg_out_graph.peg_out_chain_event = Some(PegOutEvent {
+        source_outpoint: OutPoint {
+            txid: peg_out_graph.peg_in_confirm_txid(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT),
+        timestamp: 1722328130u32,
+        withdrawer_chain_address: config.withdrawer_evm_address.clone(),
+        withdrawer_destination_address: generate_p2pkh_address(
+            config.withdrawer_context.network,
+            &config.withdrawer_context.withdrawer_public_key,
+        )
+        .to_string(),
+        withdrawer_destination: WithdrawerDestination::PublicKeyHash(
+            config
+                .withdrawer_context
+                .withdrawer_public_key
+                .pubkey_hash(),
+        ),
+        operator_public_key: config.operator_context.operator_public_key,
+        tx_hash: [0u8; 4].into(),
+        withdrawal_nonce: 0,
+    });
+
+    peg_out_graph
+}
+
+fn tx_status(block_height: Option<u32>) -> TxStatus {
+    TxStatus {
+        confirmed: block_height.is_some(),
+        block_height,
+        block_hash: None,
+        block_time: None,
+    }
+}
+
+#[tokio::test]
+async fn test_withdrawal_matches_chain_and_destination_address() {
+    let config = setup_test().await;
+    let mut peg_out_graph = requested_peg_out_graph(&config);
+    let destination_address = peg_out_graph
+        .peg_out_chain_event
+        .as_ref()
+        .unwrap()
+        .withdrawer_destination_address
+        .clone();
+
+    assert!(peg_out_graph.is_withdrawal_of(&config.withdrawer_evm_address));
+    // EVM addresses may be given with or without their checksum casing
+    assert!(peg_out_graph.is_withdrawal_of(&config.withdrawer_evm_address.to_uppercase()));
+    assert!(peg_out_graph.is_withdrawal_of(&destination_address));
+    assert!(!peg_out_graph.is_withdrawal_of(&config.depositor_evm_address));
+
+    peg_out_graph.peg_out_chain_event = None;
+    assert!(!peg_out_graph.is_withdrawal_of(&config.withdrawer_evm_address));
+    assert!(peg_out_graph
+        .interpret_withdrawal_progress(None, OperatorReimbursementStage::NotStarted, 100)
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_withdrawal_progress_follows_peg_out_status() {
+    let config = setup_test().await;
+    let peg_out_graph = requested_peg_out_graph(&config);
+
+    let requested = peg_out_graph
+        .interpret_withdrawal_progress(None, OperatorReimbursementStage::NotStarted, 100)
+        .unwrap();
+    assert_eq!(requested.graph_id, *peg_out_graph.id());
+    assert_eq!(requested.amount, Amount::from_sat(INITIAL_AMOUNT));
+    assert_eq!(requested.stage, WithdrawalStage::PegOutRequested);
+    assert_eq!(requested.confirmations, 0);
+
+    let broadcast = peg_out_graph
+        .interpret_withdrawal_progress(
+            Some(&tx_status(None)),
+            OperatorReimbursementStage::NotStarted,
+            100,
+        )
+        .unwrap();
+    assert_eq!(broadcast.stage, WithdrawalStage::PegOutBroadcast);
+    assert!(broadcast.has_advanced_from(&requested));
+
+    let confirmed = peg_out_graph
+        .interpret_withdrawal_progress(
+            Some(&tx_status(Some(98))),
+            OperatorReimbursementStage::NotStarted,
+            100,
+        )
+        .unwrap();
+    assert_eq!(confirmed.stage, WithdrawalStage::PegOutConfirmed);
+    assert_eq!(confirmed.confirmations, 3);
+
+    // More confirmations are reported, but are not an event on their own
+    let more_confirmations = peg_out_graph
+        .interpret_withdrawal_progress(
+            Some(&tx_status(Some(98))),
+            OperatorReimbursementStage::NotStarted,
+            110,
+        )
+        .unwrap();
+    assert_eq!(more_confirmations.confirmations, 13);
+    assert!(!more_confirmations.has_advanced_from(&confirmed));
+
+    let kicked_off = peg_out_graph
+        .interpret_withdrawal_progress(
+            Some(&tx_status(Some(98))),
+            OperatorReimbursementStage::KickedOff,
+            110,
+        )
+        .unwrap();
+    assert!(kicked_off.has_advanced_from(&more_confirmations));
+}
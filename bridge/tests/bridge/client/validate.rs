@@ -4,7 +4,12 @@ use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
     client::client::{BitVMClient, BitVMClientPublicData},
-    graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    graphs::{
+        base::PEG_OUT_FEE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
     scripts::generate_burn_script,
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
@@ -109,7 +114,9 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_0,
         },
         &config.depositor_evm_address,
-    );
+        GraphTemplate::default(),
+    )
+    .unwrap();
 
     let peg_in_graph_1 = PegInGraph::new(
         &config.depositor_context,
@@ -118,7 +125,9 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_1,
         },
         &config.depositor_evm_address,
-    );
+        GraphTemplate::default(),
+    )
+    .unwrap();
 
     let peg_out_graph = PegOutGraph::new(
         &config.operator_context,
@@ -128,7 +137,9 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_0,
         },
         &config.commitment_secrets,
-    );
+        GraphParameters::default(),
+    )
+    .unwrap();
 
     let data = BitVMClientPublicData {
         version: 1,
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use bitcoin::{hashes::Hash, Amount, BlockHash, OutPoint, Txid};
+
+use bitvm::signatures::signing_winternitz::WinternitzSigningInputs;
+use bridge::{
+    commitments::{revealed_commitment, CommitmentMessageId},
+    graphs::base::MIN_RELAY_FEE_RATE,
+    superblock::{
+        get_superblock_hash_message, get_superblock_message, is_heavier_superblock,
+        SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS,
+    },
+    transactions::{
+        base::{BaseTransaction, Input},
+        kick_off_2::KickOff2Transaction,
+    },
+};
+
+use crate::bridge::{helper::get_superblock_header, setup::setup_test};
+
+#[tokio::test]
+async fn test_revealed_commitments_of_kick_off_2() {
+    let config = setup_test().await;
+    let mut kick_off_2_tx = KickOff2Transaction::new(
+        &config.connector_1,
+        &config.connector_3,
+        &config.connector_b,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount: Amount::from_sat(100_000),
+        },
+        MIN_RELAY_FEE_RATE,
+    );
+
+    let superblock_header = get_superblock_header();
+    kick_off_2_tx.sign(
+        &config.operator_context,
+        &config.connector_1,
+        &WinternitzSigningInputs {
+            message: &get_superblock_message(&superblock_header),
+            signing_key: &config.commitment_secrets[&CommitmentMessageId::Superblock],
+        },
+        &WinternitzSigningInputs {
+            message: &get_superblock_hash_message(&superblock_header),
+            signing_key: &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
+        },
+    );
+    let witness = &kick_off_2_tx.finalize().input[0].witness;
+
+    // The witness starts with the operator's Schnorr signature
+    let superblock_parameters =
+        &config.connector_1.commitment_public_keys[&CommitmentMessageId::Superblock].parameters;
+    let (superblock_message, superblock_witness) =
+        revealed_commitment(witness, 1, superblock_parameters).unwrap();
+    assert_eq!(
+        superblock_message,
+        get_superblock_message(&superblock_header)
+    );
+
+    let superblock_hash_offset = 1 + superblock_witness.len();
+    let superblock_hash_parameters =
+        &config.connector_1.commitment_public_keys[&CommitmentMessageId::SuperblockHash].parameters;
+    let (superblock_hash_message, superblock_hash_witness) =
+        revealed_commitment(witness, superblock_hash_offset, superblock_hash_parameters).unwrap();
+    assert_eq!(
+        superblock_hash_message,
+        get_superblock_hash_message(&superblock_header)
+    );
+    assert_eq!(
+        superblock_hash_witness.len(),
+        2 * superblock_hash_parameters.total_length() as usize
+    );
+
+    // Only the script and the control block follow, too few elements for another commitment
+    assert!(revealed_commitment(
+        witness,
+        superblock_hash_offset + superblock_hash_witness.len(),
+        superblock_hash_parameters
+    )
+    .is_none());
+}
+
+#[test]
+fn test_heavier_superblock() {
+    let superblock = get_superblock_header();
+    let start_time = superblock.time - 1;
+    let lightest_hash = BlockHash::from_byte_array([0xff; 32]);
+
+    assert!(is_heavier_superblock(
+        &superblock,
+        &lightest_hash,
+        start_time
+    ));
+    // Not heavier than itself
+    assert!(!is_heavier_superblock(
+        &superblock,
+        &superblock.block_hash(),
+        start_time
+    ));
+    // Mined at or before the start time
+    assert!(!is_heavier_superblock(
+        &superblock,
+        &lightest_hash,
+        superblock.time
+    ));
+    // Mined after the measurement period
+    assert!(!is_heavier_superblock(
+        &superblock,
+        &lightest_hash,
+        superblock.time - SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS
+    ));
+}
@@ -0,0 +1,169 @@
+use bitcoin_script::script;
+use bitvm::{
+    execute_script,
+    signatures::{
+        signing_winternitz::{
+            generate_winternitz_witness, winternitz_message_checksig, WinternitzPublicKey,
+            WinternitzSecret, WinternitzSigningInputs, LOG_D,
+        },
+        utils::digits_to_number,
+    },
+};
+use bridge::{
+    constants::START_TIME_MESSAGE_LENGTH,
+    conversions::{
+        self, bytes_to_nibbles, hash_to_u32_le, number_to_digits, u32_le_to_hash, H256_BYTE_LENGTH,
+        MAX_SCRIPT_NUMBER,
+    },
+    utils::{sb_hash_from_bytes, sb_hash_from_nibbles, split_digit, H256},
+};
+use proptest::prelude::*;
+
+const START_TIME_DIGIT_COUNT: usize = START_TIME_MESSAGE_LENGTH * 2;
+
+// Numbers close to the bounds and with leading zero digits, in addition to random ones
+fn script_number() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        Just(0u32),
+        Just(1u32),
+        Just(MAX_SCRIPT_NUMBER),
+        Just(MAX_SCRIPT_NUMBER - 1),
+        (0u32..16).prop_map(|bits| 1u32 << bits),
+        0u32..=u16::MAX as u32,
+        0u32..=MAX_SCRIPT_NUMBER,
+    ]
+}
+
+fn hash() -> impl Strategy<Value = [u8; H256_BYTE_LENGTH]> {
+    prop_oneof![
+        Just([0u8; H256_BYTE_LENGTH]),
+        Just([0xffu8; H256_BYTE_LENGTH]),
+        // leading zero bytes, as in block hashes
+        (0usize..H256_BYTE_LENGTH, any::<[u8; H256_BYTE_LENGTH]>()).prop_map(
+            |(leading_zeros, mut hash)| {
+                hash[..leading_zeros].fill(0);
+                hash
+            }
+        ),
+        any::<[u8; H256_BYTE_LENGTH]>(),
+    ]
+}
+
+fn assert_digits_to_number<const DIGIT_COUNT: usize, const DIGIT_BITS: usize>(number: u32) {
+    let digits = number_to_digits(number, DIGIT_COUNT, DIGIT_BITS);
+    assert_eq!(conversions::digits_to_number(&digits, DIGIT_BITS), number);
+
+    let result = execute_script(script! {
+        for digit in digits { { digit } }
+        { digits_to_number::<DIGIT_COUNT, DIGIT_BITS>() }
+        { number }
+        OP_EQUAL
+    });
+    assert!(
+        result.success,
+        "digits_to_number::<{DIGIT_COUNT}, {DIGIT_BITS}> failed for {number}: {result}"
+    );
+}
+
+fn assert_sb_hash_matches(conversion: bitcoin_script::Script, hash: &[u8; H256_BYTE_LENGTH]) {
+    let result = execute_script(script! {
+        { conversion }
+        { H256::push_u32_le(&hash_to_u32_le(hash)) }
+        { H256::equal(1, 0) }
+    });
+    assert!(
+        result.success,
+        "Hash conversion failed for {}: {result}",
+        hex::encode(hash)
+    );
+}
+
+proptest! {
+    #[test]
+    fn test_digits_to_number_nibbles(number in script_number()) {
+        assert_digits_to_number::<START_TIME_DIGIT_COUNT, { LOG_D as usize }>(number);
+    }
+
+    #[test]
+    fn test_digits_to_number_bytes(number in script_number()) {
+        assert_digits_to_number::<4, 8>(number);
+    }
+
+    #[test]
+    fn test_digits_to_number_single_digit(digit in 0u32..16) {
+        assert_digits_to_number::<1, 4>(digit);
+    }
+
+    #[test]
+    fn test_digits_to_number_partial_width(number in 0u32..(1 << 12)) {
+        assert_digits_to_number::<3, 4>(number);
+    }
+
+    #[test]
+    fn test_split_digit(window in 2u32..=8, index in 1u32..8, digit in any::<u32>()) {
+        prop_assume!(index < window);
+        let digit = digit & ((1 << window) - 1);
+        let (high, low) = conversions::split_digit(digit, window, index);
+        prop_assert_eq!((high << (window - index)) | low, digit);
+
+        let result = execute_script(script! {
+            { digit }
+            { split_digit(window, index) }
+            { high }
+            OP_EQUALVERIFY
+            { low }
+            OP_EQUAL
+        });
+        prop_assert!(result.success, "split_digit({}, {}) failed for {}", window, index, digit);
+    }
+
+    #[test]
+    fn test_hash_to_u32_le_round_trip(hash in hash()) {
+        prop_assert_eq!(u32_le_to_hash(&hash_to_u32_le(&hash)), hash);
+    }
+}
+
+proptest! {
+    // Hash conversions and Winternitz signatures are expensive to execute
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn test_sb_hash_from_bytes(hash in hash()) {
+        assert_sb_hash_matches(
+            script! {
+                for byte in hash { { byte } }
+                { sb_hash_from_bytes() }
+            },
+            &hash,
+        );
+    }
+
+    #[test]
+    fn test_sb_hash_from_nibbles(hash in hash()) {
+        assert_sb_hash_matches(
+            script! {
+                for nibble in bytes_to_nibbles(&hash) { { nibble } }
+                { sb_hash_from_nibbles() }
+            },
+            &hash,
+        );
+    }
+
+    #[test]
+    fn test_winternitz_message_to_number(number in script_number()) {
+        let secret = WinternitzSecret::new(START_TIME_MESSAGE_LENGTH);
+        let public_key = WinternitzPublicKey::from(&secret);
+
+        let result = execute_script(script! {
+            { generate_winternitz_witness(&WinternitzSigningInputs {
+                message: &number.to_le_bytes(),
+                signing_key: &secret,
+            }).to_vec() }
+            { winternitz_message_checksig(&public_key) }
+            { digits_to_number::<START_TIME_DIGIT_COUNT, { LOG_D as usize }>() }
+            { number }
+            OP_EQUAL
+        });
+        prop_assert!(result.success, "Winternitz round trip failed for {}", number);
+    }
+}
@@ -0,0 +1,39 @@
+use bridge::client::data_store::encryption::DataStoreEncryption;
+
+const TEST_ENCRYPTION_KEY: &str =
+    "5c1fe6b3e5d4f0f2fd4bd47dcbbd2b5a0d3c6e9b5a8e1f2a3b4c5d6e7f8091a2";
+const OTHER_ENCRYPTION_KEY: &str =
+    "a2918f7e6d5c4b3a2f1e8a5b9e6c3d0a5a2bbdcb7dd44bfdf2f0d4e5b3e6f15c";
+
+#[test]
+fn test_data_store_encryption_round_trip() {
+    let encryption = DataStoreEncryption::from_hex(TEST_ENCRYPTION_KEY).unwrap();
+    let contents = "{\"peg_in_graphs\":[],\"peg_out_graphs\":[]}";
+
+    let encrypted = encryption.encrypt_string(contents).unwrap();
+    assert!(!encrypted.contains("peg_in_graphs"));
+    assert_eq!(encryption.decrypt_string(&encrypted).unwrap(), contents);
+
+    // every object is encrypted with a fresh data key
+    assert_ne!(encryption.encrypt_string(contents).unwrap(), encrypted);
+
+    let bytes = contents.as_bytes().to_vec();
+    let encrypted_bytes = encryption.encrypt(&bytes).unwrap();
+    assert_eq!(encryption.decrypt(&encrypted_bytes).unwrap(), bytes);
+}
+
+#[test]
+fn test_data_store_encryption_rejects_invalid_data() {
+    let encryption = DataStoreEncryption::from_hex(TEST_ENCRYPTION_KEY).unwrap();
+    let other_encryption = DataStoreEncryption::from_hex(OTHER_ENCRYPTION_KEY).unwrap();
+
+    let mut encrypted = encryption.encrypt(b"graph data").unwrap();
+    assert!(other_encryption.decrypt(&encrypted).is_err());
+
+    let last = encrypted.len() - 1;
+    encrypted[last] ^= 1;
+    assert!(encryption.decrypt(&encrypted).is_err());
+
+    assert!(encryption.decrypt(b"graph data").is_err());
+    assert!(DataStoreEncryption::from_hex("00").is_err());
+}
@@ -1,3 +1 @@
-pub mod ftp;
-pub mod ftps;
-pub mod sftp;
+pub mod encryption;
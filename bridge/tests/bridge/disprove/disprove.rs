@@ -1,7 +1,10 @@
 use bitcoin::{key::Keypair, Amount, PrivateKey, PublicKey, TxOut};
+use bitvm::chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256};
 
 use bridge::{
     connectors::base::TaprootConnector,
+    error::{ChunkerError, Error},
+    graphs::template::DEFAULT_DISPROVE_BURN_PERCENTAGE,
     scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_DISPROVE},
@@ -43,6 +46,7 @@ async fn test_disprove_tx_success() {
 
     let mut disprove_tx = DisproveTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_5,
         &config.connector_c,
         Input {
@@ -53,28 +57,38 @@ async fn test_disprove_tx_success() {
             outpoint: outpoint_1,
             amount: amount_1,
         },
+        DEFAULT_DISPROVE_BURN_PERCENTAGE,
+        None,
     );
 
     let secret_nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context);
 
-    disprove_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    disprove_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
     let verifier_reward_script = reward_address.script_pubkey(); // send reward to withdrawer address
-    disprove_tx.add_input_output(&config.connector_c, 1, vec![], verifier_reward_script);
+    disprove_tx
+        .add_input_output(&config.connector_c, 1, vec![], verifier_reward_script)
+        .unwrap();
 
     let tx = disprove_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
@@ -110,6 +124,7 @@ async fn test_disprove_tx_with_verifier_added_to_output_success() {
 
     let mut disprove_tx = DisproveTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_5,
         &config.connector_c,
         Input {
@@ -120,28 +135,38 @@ async fn test_disprove_tx_with_verifier_added_to_output_success() {
             outpoint: outpoint_1,
             amount: amount_1,
         },
+        DEFAULT_DISPROVE_BURN_PERCENTAGE,
+        None,
     );
 
     let secret_nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context);
 
-    disprove_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    disprove_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
     let verifier_reward_script = reward_address.script_pubkey(); // send reward to withdrawer address
-    disprove_tx.add_input_output(&config.connector_c, 1, vec![], verifier_reward_script);
+    disprove_tx
+        .add_input_output(&config.connector_c, 1, vec![], verifier_reward_script)
+        .unwrap();
 
     let mut tx = disprove_tx.finalize();
 
@@ -171,3 +196,46 @@ async fn test_disprove_tx_with_verifier_added_to_output_success() {
     println!("Disprove tx result: {:?}\n", result);
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_disprove_witness_rejects_incomplete_commitments() {
+    let config = setup_test_full().await;
+    let num_commitments = NUM_PUBS + NUM_U256 + NUM_HASH;
+
+    let result = config.connector_c.generate_disprove_witness(
+        vec![],
+        vec![vec![]; num_commitments - 1],
+        &config.invalid_proof.vk,
+    );
+    match result {
+        Err(Error::Chunker(
+            err @ ChunkerError::MissingCommitmentWitness {
+                expected, found, ..
+            },
+        )) => {
+            assert_eq!(expected, num_commitments);
+            assert_eq!(found, num_commitments - 1);
+            assert!(err.is_actionable());
+        }
+        other => panic!("Expected a missing commitment witness error, got {other:?}"),
+    }
+
+    let result = config.connector_c.generate_disprove_witness(
+        vec![vec![vec![0u8; 3]]; NUM_PUBS + NUM_U256],
+        vec![vec![]; NUM_HASH],
+        &config.invalid_proof.vk,
+    );
+    match result {
+        Err(Error::Chunker(
+            err @ ChunkerError::MalformedCommitmentWitness {
+                commitment_index, ..
+            },
+        )) => {
+            assert_eq!(commitment_index, 0);
+            assert!(err.is_actionable());
+        }
+        other => panic!("Expected a malformed commitment witness error, got {other:?}"),
+    }
+
+    assert!(!ChunkerError::ValidProof.is_actionable());
+}
@@ -4,6 +4,7 @@ use bitvm::signatures::signing_winternitz::{generate_winternitz_witness, Wintern
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
+    graphs::template::DEFAULT_DISPROVE_BURN_PERCENTAGE,
     scripts::generate_pay_to_pubkey_script_address,
     superblock::{get_start_time_block_number, get_superblock_hash_message},
     transactions::{
@@ -41,29 +42,40 @@ async fn test_disprove_chain_tx_success() {
 
     let mut disprove_chain_tx = DisproveChainTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_b,
         Input { outpoint, amount },
+        DEFAULT_DISPROVE_BURN_PERCENTAGE,
+        None,
     );
 
     let secret_nonces_0 = disprove_chain_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove_chain_tx.push_nonces(&config.verifier_1_context);
 
-    disprove_chain_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    disprove_chain_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    disprove_chain_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove_chain_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
-    disprove_chain_tx.add_output(reward_address.script_pubkey());
+    disprove_chain_tx
+        .add_output(reward_address.script_pubkey())
+        .unwrap();
 
     // TODO: setup the test headers appropriately for the verification in Disprove Chain to pass
     let committed_sb = get_superblock_header();
@@ -6,7 +6,10 @@ use crate::bridge::{
 use bitcoin::{Address, Amount};
 use bridge::{
     client::chain::chain::Chain,
-    graphs::base::{BaseGraph, PEG_IN_FEE, PEG_OUT_FEE},
+    graphs::{
+        base::{BaseGraph, PegInGraphId, PegOutGraphId, PEG_IN_FEE, PEG_OUT_FEE},
+        template::{GraphParameters, GraphTemplate},
+    },
     transactions::pre_signed::PreSignedTransaction,
 };
 use bridge::{
@@ -108,7 +111,7 @@ async fn test_e2e_1_simulate_peg_out() {
     };
     println!("Broadcasting peg out...");
     operator_client
-        .broadcast_peg_out(peg_out_graph.id(), input)
+        .broadcast_peg_out(peg_out_graph.peg_out_graph_id(), input)
         .await
         .expect("Failed to broadcast peg out");
 
@@ -150,7 +153,7 @@ async fn test_e2e_2_verify_burn_event_and_simulate_peg_out_process() {
 async fn create_graph() -> (
     BitVMClient,
     BitVMClient,
-    String,
+    PegOutGraphId,
     DepositorContext,
     OperatorContext,
 ) {
@@ -214,7 +217,7 @@ async fn create_graph() -> (
             outpoint: kick_off_outpoint,
             amount: kick_off_input_amount,
         },
-        config.commitment_secrets,
+        GraphParameters::default(),
     );
 
     println!("Verifier 0 push peg-out nonces");
@@ -251,7 +254,7 @@ async fn create_peg_in_graph(
     deposit_funding_address: Address,
     deposit_amount: Amount,
     depositor_evm_address: &String,
-) -> String {
+) -> PegInGraphId {
     let deposit_outpoint =
         generate_stub_outpoint(client_0, &deposit_funding_address, deposit_amount).await;
     let graph_id = client_0
@@ -261,8 +264,10 @@ async fn create_peg_in_graph(
                 amount: deposit_amount,
             },
             depositor_evm_address,
+            GraphTemplate::default(),
         )
-        .await;
+        .await
+        .unwrap();
 
     client_0
         .broadcast_peg_in_deposit(&graph_id)
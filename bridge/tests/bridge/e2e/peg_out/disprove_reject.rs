@@ -1,4 +1,7 @@
-use bridge::error::{ChunkerError, Error};
+use bridge::{
+    error::{ChunkerError, Error},
+    graphs::peg_out::ProofVerification,
+};
 
 use colored::Colorize;
 use serial_test::serial;
@@ -27,6 +30,9 @@ async fn test_e2e_disprove_reject() {
     )
     .await;
 
+    let verification = verifier_1.verify_asserted_proof(&peg_out_graph_id).await;
+    assert!(matches!(verification, Ok(ProofVerification::Valid)));
+
     let result = verifier_1
         .broadcast_disprove(&peg_out_graph_id, reward_script)
         .await;
@@ -43,6 +49,10 @@ async fn test_e2e_disprove_reject() {
         .red(),
     );
 
+    if let Err(Error::Chunker(err)) = &result {
+        assert!(!err.is_actionable());
+    }
+
     println!(
         "{}",
         "Successfully rejected disproving correct ZK proof"
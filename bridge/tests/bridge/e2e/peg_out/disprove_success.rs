@@ -1,3 +1,4 @@
+use bridge::graphs::peg_out::ProofVerification;
 use colored::Colorize;
 use serial_test::serial;
 
@@ -25,6 +26,9 @@ async fn test_e2e_disprove_success() {
     )
     .await;
 
+    let verification = verifier_1.verify_asserted_proof(&peg_out_graph_id).await;
+    assert!(matches!(verification, Ok(ProofVerification::Invalid)));
+
     let result = verifier_1
         .broadcast_disprove(&peg_out_graph_id, reward_script)
         .await;
@@ -1,18 +1,33 @@
-use bitcoin::{Address, Amount, ScriptBuf};
+use bitcoin::{Address, Amount, OutPoint, ScriptBuf};
 use bitvm::chunk::api::type_conversion_utils::RawProof;
 use bridge::{
-    client::client::BitVMClient,
-    graphs::base::{BaseGraph, PEG_IN_FEE, PEG_OUT_FEE},
+    client::{
+        chain::{
+            chain::{Chain, PegOutBurntEvent},
+            mock_adaptor::{MockAdaptor, MockAdaptorConfig},
+        },
+        client::BitVMClient,
+    },
+    graphs::{
+        base::{PegInGraphId, PegOutGraphId, PEG_IN_FEE, PEG_OUT_FEE},
+        template::{GraphParameters, GraphTemplate},
+    },
     scripts::generate_pay_to_pubkey_script_address,
-    transactions::base::{Input, MIN_RELAY_FEE_PEG_OUT},
+    transactions::{
+        base::{Input, MIN_RELAY_FEE_PEG_OUT},
+        pre_signed::PreSignedTransaction,
+    },
 };
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 
 use crate::bridge::{
+    checkpoint::{load_checkpoint, save_checkpoint},
     faucet::{Faucet, FaucetType},
     helper::{
-        generate_stub_outpoint, get_default_peg_out_event, print_tx_broadcasted,
-        wait_for_confirmation_with_message, wait_for_timelock_expiry,
+        find_peg_in_graph_by_peg_out, generate_stub_outpoint, get_default_peg_out_event,
+        get_peg_out_burnt_event, print_tx_broadcasted, wait_for_confirmation_with_message,
+        wait_for_timelock_expiry,
     },
     setup::{setup_test, INITIAL_AMOUNT},
 };
@@ -22,11 +37,16 @@ pub async fn create_peg_in_graph(
     verifier_1: &mut BitVMClient,
     deposit_input: Input,
     depositor_evm_address: &String,
-) -> String {
+) -> PegInGraphId {
     println!("{}", "Creating PEG-IN graph...".bold().yellow());
     let graph_id = depositor_verifier_0
-        .create_peg_in_graph(deposit_input, depositor_evm_address)
-        .await;
+        .create_peg_in_graph(
+            deposit_input,
+            depositor_evm_address,
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
 
     let peg_in_deposit_txid = depositor_verifier_0
         .broadcast_peg_in_deposit(&graph_id)
@@ -84,10 +104,26 @@ pub async fn create_peg_in_graph(
     graph_id
 }
 
+const PEG_IN_CONFIRMED_CHECKPOINT: &str = "peg_in_confirmed";
+const PEG_OUT_CEREMONY_CHECKPOINT: &str = "peg_out_ceremony_complete";
+
+#[derive(Serialize, Deserialize)]
+struct PegInConfirmedCheckpoint {
+    peg_in_graph_id: PegInGraphId,
+    peg_out_confirm_outpoint: OutPoint,
+    peg_out_outpoint: OutPoint,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PegOutCeremonyCheckpoint {
+    peg_out_graph_id: PegOutGraphId,
+    peg_out_outpoint: OutPoint,
+}
+
 pub async fn create_peg_out_graph() -> (
     BitVMClient,
     BitVMClient,
-    String,
+    PegOutGraphId,
     ScriptBuf,
     Input,
     RawProof,
@@ -97,76 +133,135 @@ pub async fn create_peg_out_graph() -> (
     let mut verifier_0_operator_depositor = config.client_0;
     let mut verifier_1 = config.client_1;
 
-    // verify funding inputs
-    let mut funding_inputs: Vec<(&Address, Amount)> = Vec::new();
-
-    let deposit_input_amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
-    let deposit_funding_address = generate_pay_to_pubkey_script_address(
-        config.depositor_context.network,
-        &config.depositor_context.depositor_public_key,
+    let reward_address = generate_pay_to_pubkey_script_address(
+        config.verifier_1_context.network,
+        &config.verifier_1_context.verifier_public_key,
     );
-    funding_inputs.push((&deposit_funding_address, deposit_input_amount));
+    let reward_script = reward_address.script_pubkey();
 
     let peg_out_input_amount = Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_OUT);
-    let peg_out_funding_address = generate_pay_to_pubkey_script_address(
-        config.operator_context.network,
-        &config.operator_context.operator_public_key,
-    );
-    funding_inputs.push((&peg_out_funding_address, peg_out_input_amount));
-
     let peg_out_confirm_input_amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
-    let peg_out_confirm_funding_address = generate_pay_to_pubkey_script_address(
-        config.operator_context.network,
-        &config.operator_context.operator_public_key,
-    );
-    funding_inputs.push((
-        &peg_out_confirm_funding_address,
-        peg_out_confirm_input_amount,
-    ));
-
-    let faucet = Faucet::new(FaucetType::EsploraRegtest);
-    faucet
-        .fund_inputs(&verifier_0_operator_depositor, &funding_inputs)
-        .await
-        .wait()
-        .await;
-
-    // create peg-in graph
-    let peg_in_deposit_outpoint = generate_stub_outpoint(
-        &verifier_0_operator_depositor,
-        &deposit_funding_address,
-        deposit_input_amount,
-    )
-    .await;
 
-    let peg_in_graph_id = create_peg_in_graph(
-        &mut verifier_0_operator_depositor,
-        &mut verifier_1,
-        Input {
-            outpoint: peg_in_deposit_outpoint,
-            amount: deposit_input_amount,
-        },
-        &config.depositor_evm_address,
+    if let Some(checkpoint) = load_checkpoint::<PegOutCeremonyCheckpoint>(
+        PEG_OUT_CEREMONY_CHECKPOINT,
+        &mut [&mut verifier_0_operator_depositor, &mut verifier_1],
     )
-    .await;
-
-    // create peg-out graph
-    let peg_out_confirm_outpoint = generate_stub_outpoint(
-        &verifier_0_operator_depositor,
-        &peg_out_confirm_funding_address,
-        peg_out_confirm_input_amount,
+    .await
+    {
+        return (
+            verifier_0_operator_depositor,
+            verifier_1,
+            checkpoint.peg_out_graph_id,
+            reward_script,
+            Input {
+                outpoint: checkpoint.peg_out_outpoint,
+                amount: peg_out_input_amount,
+            },
+            config.valid_proof,
+            config.invalid_proof,
+        );
+    }
+
+    let peg_in_checkpoint = match load_checkpoint::<PegInConfirmedCheckpoint>(
+        PEG_IN_CONFIRMED_CHECKPOINT,
+        &mut [&mut verifier_0_operator_depositor, &mut verifier_1],
     )
-    .await;
+    .await
+    {
+        Some(checkpoint) => checkpoint,
+        None => {
+            // verify funding inputs
+            let mut funding_inputs: Vec<(&Address, Amount)> = Vec::new();
+
+            let deposit_input_amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+            let deposit_funding_address = generate_pay_to_pubkey_script_address(
+                config.depositor_context.network,
+                &config.depositor_context.depositor_public_key,
+            );
+            funding_inputs.push((&deposit_funding_address, deposit_input_amount));
+
+            let peg_out_funding_address = generate_pay_to_pubkey_script_address(
+                config.operator_context.network,
+                &config.operator_context.operator_public_key,
+            );
+            funding_inputs.push((&peg_out_funding_address, peg_out_input_amount));
+
+            let peg_out_confirm_funding_address = generate_pay_to_pubkey_script_address(
+                config.operator_context.network,
+                &config.operator_context.operator_public_key,
+            );
+            funding_inputs.push((
+                &peg_out_confirm_funding_address,
+                peg_out_confirm_input_amount,
+            ));
+
+            let faucet = Faucet::new(FaucetType::EsploraRegtest);
+            faucet
+                .fund_inputs(&verifier_0_operator_depositor, &funding_inputs)
+                .await
+                .wait()
+                .await;
+
+            // create peg-in graph
+            let peg_in_deposit_outpoint = generate_stub_outpoint(
+                &verifier_0_operator_depositor,
+                &deposit_funding_address,
+                deposit_input_amount,
+            )
+            .await;
+
+            let peg_in_graph_id = create_peg_in_graph(
+                &mut verifier_0_operator_depositor,
+                &mut verifier_1,
+                Input {
+                    outpoint: peg_in_deposit_outpoint,
+                    amount: deposit_input_amount,
+                },
+                &config.depositor_evm_address,
+            )
+            .await;
+
+            let checkpoint = PegInConfirmedCheckpoint {
+                peg_in_graph_id,
+                peg_out_confirm_outpoint: generate_stub_outpoint(
+                    &verifier_0_operator_depositor,
+                    &peg_out_confirm_funding_address,
+                    peg_out_confirm_input_amount,
+                )
+                .await,
+                peg_out_outpoint: generate_stub_outpoint(
+                    &verifier_0_operator_depositor,
+                    &peg_out_funding_address,
+                    peg_out_input_amount,
+                )
+                .await,
+            };
+            save_checkpoint(
+                PEG_IN_CONFIRMED_CHECKPOINT,
+                &[&verifier_0_operator_depositor, &verifier_1],
+                &[
+                    checkpoint.peg_out_confirm_outpoint,
+                    checkpoint.peg_out_outpoint,
+                ],
+                &checkpoint,
+            )
+            .await;
+
+            checkpoint
+        }
+    };
+
+    // create peg-out graph, the ceremony uses fresh nonces when resuming from the peg-in checkpoint
     verifier_0_operator_depositor.sync().await;
 
     println!("{}", "Creating PEG-OUT graph...".bold().yellow());
     let peg_out_graph_id = verifier_0_operator_depositor.create_peg_out_graph(
-        &peg_in_graph_id,
+        &peg_in_checkpoint.peg_in_graph_id,
         Input {
-            outpoint: peg_out_confirm_outpoint,
+            outpoint: peg_in_checkpoint.peg_out_confirm_outpoint,
             amount: peg_out_confirm_input_amount,
         },
-        config.commitment_secrets,
+        GraphParameters::default(),
     );
 
     println!("{}", "PEG-OUT ceremony start".bold().yellow());
@@ -197,26 +292,25 @@ pub async fn create_peg_out_graph() -> (
     verifier_1.flush().await;
     println!("{}", "PEG-OUT ceremony finish".bold().yellow());
 
-    let reward_address = generate_pay_to_pubkey_script_address(
-        config.verifier_1_context.network,
-        &config.verifier_1_context.verifier_public_key,
-    );
-    let reward_script = reward_address.script_pubkey();
-
-    let peg_out_outpoint = generate_stub_outpoint(
-        &verifier_0_operator_depositor,
-        &peg_out_funding_address,
-        peg_out_input_amount,
+    let checkpoint = PegOutCeremonyCheckpoint {
+        peg_out_graph_id,
+        peg_out_outpoint: peg_in_checkpoint.peg_out_outpoint,
+    };
+    save_checkpoint(
+        PEG_OUT_CEREMONY_CHECKPOINT,
+        &[&verifier_0_operator_depositor, &verifier_1],
+        &[checkpoint.peg_out_outpoint],
+        &checkpoint,
     )
     .await;
 
     (
         verifier_0_operator_depositor,
         verifier_1,
-        peg_out_graph_id,
+        checkpoint.peg_out_graph_id,
         reward_script,
         Input {
-            outpoint: peg_out_outpoint,
+            outpoint: checkpoint.peg_out_outpoint,
             amount: peg_out_input_amount,
         },
         config.valid_proof,
@@ -227,7 +321,7 @@ pub async fn create_peg_out_graph() -> (
 pub async fn broadcast_txs_for_disprove_scenario(
     operator: &mut BitVMClient,
     verifier_1: &mut BitVMClient,
-    peg_out_graph_id: &String,
+    peg_out_graph_id: &PegOutGraphId,
     peg_out_input: Input,
     proof: &RawProof,
 ) {
@@ -236,14 +330,33 @@ pub async fn broadcast_txs_for_disprove_scenario(
     operator.sync().await;
 
     let peg_out_graph = operator
-        .data_mut()
-        .peg_out_graphs
-        .iter_mut()
-        .find(|x| x.id() == peg_out_graph_id)
+        .peg_out_graph_mut(peg_out_graph_id)
         .expect(&format!("Peg-out graph {peg_out_graph_id} not found"));
 
     // set arbitrary peg_out_chain_event
-    peg_out_graph.peg_out_chain_event = Some(get_default_peg_out_event());
+    let peg_out_event = get_default_peg_out_event();
+    peg_out_graph.peg_out_chain_event = Some(peg_out_event.clone());
+
+    // and the matching burn on the destination chain, checked before peg-out confirm
+    let peg_in_graph = find_peg_in_graph_by_peg_out(operator, peg_out_graph_id)
+        .expect(&format!("Peg-in graph for {peg_out_graph_id} not found"));
+    let peg_out_burnt_event = PegOutBurntEvent {
+        source_outpoint: OutPoint {
+            txid: peg_in_graph
+                .peg_in_confirm_transaction_ref()
+                .tx()
+                .compute_txid(),
+            vout: 0,
+        },
+        ..get_peg_out_burnt_event(&peg_out_event)
+    };
+    operator.set_chain_service(Chain::new(Box::new(MockAdaptor::new(Some(
+        MockAdaptorConfig {
+            peg_out_init_events: None,
+            peg_out_burnt_events: Some(vec![peg_out_burnt_event]),
+            peg_out_minted_events: None,
+        },
+    )))));
 
     let peg_out_txid = operator
         .broadcast_peg_out(peg_out_graph_id, peg_out_input)
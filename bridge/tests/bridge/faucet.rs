@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, process::Command, time::Duration};
 use tokio::time::sleep;
 
+#[cfg(feature = "regtest")]
+use super::helper::regtest_controller;
 use super::helper::wait_for_confirmation_with_message;
 
 const ALPEN_SIGNET_ESPLORA_URL: &str = "https://esploraapi53d3659b.devnet-annapurna.stratabtc.org/";
@@ -63,7 +65,7 @@ impl Faucet {
     pub async fn fund_input(&self, address: &Address, amount: Amount) -> &Self {
         match self.faucet_type {
             FaucetType::Signet => self.fund_input_with_retry(address, amount).await,
-            FaucetType::EsploraRegtest => self.fund_input_by_cli(address, amount),
+            FaucetType::EsploraRegtest => self.fund_input_on_regtest(address, amount).await,
         };
         self
     }
@@ -86,13 +88,28 @@ impl Faucet {
             if utxos.is_none() || utxos.is_some_and(|x| x.len() < expected_count) {
                 match self.faucet_type {
                     FaucetType::Signet => self.fund_input_with_retry(input.0, input.1).await,
-                    FaucetType::EsploraRegtest => self.fund_input_by_cli(input.0, input.1),
+                    FaucetType::EsploraRegtest => {
+                        self.fund_input_on_regtest(input.0, input.1).await
+                    }
                 };
             }
         }
         self
     }
 
+    // Through the bitcoind RPC of the regtest controller if configured, otherwise through the
+    // bitcoin-cli of the esplora container
+    async fn fund_input_on_regtest(&self, address: &Address, amount: Amount) -> Txid {
+        #[cfg(feature = "regtest")]
+        if let Some(controller) = regtest_controller(Network::Regtest) {
+            return controller
+                .fund(address, amount)
+                .await
+                .unwrap_or_else(|e| panic!("Could not fund {address}: {e}"));
+        }
+        self.fund_input_by_cli(address, amount)
+    }
+
     fn fund_input_by_cli(&self, address: &Address, amount: Amount) -> Txid {
         let funding_command = format!("/srv/explorer/bitcoin/bin/bitcoin-cli -conf=/data/.bitcoin.conf -datadir=/data/bitcoin sendtoaddress {} {}", address, amount.to_btc());
         let command = format!(
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, collections::BTreeMap, path::Path, str::FromStr, time::Duration};
+use std::{borrow::Cow, fs, path::PathBuf, str::FromStr, time::Duration};
 
 use ark_bn254::g1::G1Affine;
 use ark_std::{test_rng, UniformRand};
@@ -7,33 +7,50 @@ use bitcoin::hashes::hash160::Hash;
 use bitcoin::{
     block::{Header, Version},
     hex::{Case::Lower, DisplayHex},
+    key::Keypair,
+    secp256k1::Secp256k1,
     Address, Amount, BlockHash, CompactTarget, Network, OutPoint, Transaction, TxMerkleNode,
 };
 use bitcoin::{PubkeyHash, PublicKey, Txid};
 
 use bitvm::chunk::api::type_conversion_utils::RawProof;
-use bitvm::chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256};
-use bitvm::signatures::wots_api;
-use bridge::client::chain::chain::PegOutEvent;
-use bridge::client::data_store::local_file::TEST_DATA_DIRECTORY_NAME;
+#[cfg(feature = "regtest")]
+use bridge::client::regtest::{RegtestController, RegtestRpcConfig};
+use bridge::client::{
+    chain::chain::{PegOutBurntEvent, PegOutEvent, WithdrawerDestination},
+    clock::{BlockProducer, RegtestBlockProducer},
+    esplora::get_esplora_url,
+};
 use bridge::proof::get_proof;
 use bridge::{
     client::client::BitVMClient,
     graphs::{
-        base::{BaseGraph, REWARD_MULTIPLIER, REWARD_PRECISION},
+        base::{PEG_IN_FEE, REWARD_MULTIPLIER, REWARD_PRECISION},
         peg_in::PegInGraph,
         peg_out::PegOutGraph,
     },
-    utils::{num_blocks_per_network, read_disk_cache, write_disk_cache},
+    transactions::base::Input,
+    utils::num_blocks_per_network,
 };
 
 use colored::Colorize;
+use esplora_client::Builder;
 use rand::{RngCore, SeedableRng};
 use tokio::time::sleep;
 
+use crate::bridge::setup::INITIAL_AMOUNT;
+
+// BIP341 NUMS point, no one can spend through the key path
+pub const NUMS_INTERNAL_KEY: &str =
+    "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
 // Test environment config file and its variables
 const TEST_ENV_FILE: &str = ".env.test";
 const REGTEST_BLOCK_TIME: &str = "REGTEST_BLOCK_TIME";
+// Command mining the number of blocks appended to it, e.g. `../regtest/generate-blocks.sh`. If
+// set, tests on regtest mine the blocks they wait for instead of sleeping for the block time. The
+// bitcoind RPC of the regtest controller takes precedence when built with the `regtest` feature.
+const REGTEST_BLOCK_GENERATOR_COMMAND: &str = "REGTEST_BLOCK_GENERATOR_COMMAND";
 
 fn load_u32_env_var_from_file(var: &str, file_name: &str) -> u32 {
     dotenv::from_filename(file_name)
@@ -44,6 +61,43 @@ fn load_u32_env_var_from_file(var: &str, file_name: &str) -> u32 {
         .expect(format!("Could not parse {var} specified in {file_name}").as_str())
 }
 
+// Controller of the regtest bitcoind, if its RPC is configured with `BITCOIND_RPC_URL` and the
+// other `client::regtest` variables in the test environment config file
+#[cfg(feature = "regtest")]
+pub fn regtest_controller(network: Network) -> Option<RegtestController> {
+    if network != Network::Regtest {
+        return None;
+    }
+    dotenv::from_filename(TEST_ENV_FILE).ok();
+    let config = RegtestRpcConfig::from_env()?;
+    let esplora = Builder::new(get_esplora_url(network))
+        .build_async()
+        .expect("Could not build esplora client");
+
+    Some(RegtestController::new(config, esplora).unwrap_or_else(|e| panic!("{e}")))
+}
+
+fn on_demand_block_producer(network: Network) -> Option<Box<dyn BlockProducer>> {
+    if network != Network::Regtest {
+        return None;
+    }
+    #[cfg(feature = "regtest")]
+    if let Some(controller) = regtest_controller(network) {
+        return Some(Box::new(controller));
+    }
+    dotenv::from_filename(TEST_ENV_FILE).ok();
+    let command = dotenv::var(REGTEST_BLOCK_GENERATOR_COMMAND)
+        .ok()
+        .filter(|command| !command.is_empty())?;
+    let esplora = Builder::new(get_esplora_url(network))
+        .build_async()
+        .expect("Could not build esplora client");
+
+    Some(Box::new(
+        RegtestBlockProducer::new(esplora, &command).unwrap_or_else(|e| panic!("{e}")),
+    ))
+}
+
 /// Returns expected block time for the given network in seconds.
 fn network_block_time(network: Network) -> u32 {
     match network {
@@ -85,11 +139,26 @@ async fn wait_with_message(timeout: Duration, message: &str) {
     sleep(timeout).await;
 }
 
+// Mines the blocks if the regtest controller or a block generator command is configured,
+// otherwise waits for them
+async fn wait_for_blocks(network: Network, num_blocks: u32, timeout: Duration, message: &str) {
+    match on_demand_block_producer(network) {
+        Some(block_producer) => {
+            println!("Mining {DURATION_COLOR}{num_blocks} blocks{RESET_COLOR}{message}...");
+            block_producer
+                .produce_blocks(num_blocks)
+                .await
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+        None => wait_with_message(timeout, message).await,
+    }
+}
+
 pub async fn wait_for_confirmation_with_message(network: Network, message: Option<&str>) {
     let timeout = Duration::from_secs(tx_wait_time(network));
     let message = format!(" for {}", message.unwrap_or("tx confirmation"));
 
-    wait_with_message(timeout, message.as_str()).await;
+    wait_for_blocks(network, 1, timeout, message.as_str()).await;
 }
 
 pub async fn wait_for_confirmation(network: Network) {
@@ -100,10 +169,8 @@ pub async fn wait_for_timelock_expiry(network: Network, timelock_name: Option<&s
     // Note that the extra 1 second from tx_wait_time() compounds here. Normally this will not be an issue.
     // You'll just wait a couple seconds longer than the required number of blocks. However, if you need to
     // wait for an exact number of seconds, consider using a simple sleep (or adding a sister helper function).
-    let tx_wait_time = tx_wait_time(network);
-    let timeout = Duration::from_secs(
-        tx_wait_time * num_blocks_per_network(network, 0) as u64 + tx_wait_time,
-    );
+    let num_blocks = num_blocks_per_network(network, 0) + 1;
+    let timeout = Duration::from_secs(tx_wait_time(network) * num_blocks as u64);
     let message = format!(
         " for{} timelock to expire",
         match timelock_name {
@@ -112,7 +179,7 @@ pub async fn wait_for_timelock_expiry(network: Network, timelock_name: Option<&s
         }
     );
 
-    wait_with_message(timeout, message.as_str()).await;
+    wait_for_blocks(network, num_blocks, timeout, message.as_str()).await;
 }
 
 pub async fn generate_stub_outpoint(
@@ -188,24 +255,43 @@ pub async fn verify_funding_inputs(client: &BitVMClient, funding_inputs: &Vec<(&
     }
 }
 
-pub fn find_peg_in_graph(client: &BitVMClient, peg_in_graph_id: &str) -> Option<PegInGraph> {
-    let peg_in_graph = client
-        .data()
-        .peg_in_graphs
-        .iter()
-        .find(|&graph| graph.id().eq(peg_in_graph_id));
+// Peg-in sized input of a transaction that is never looked up, for graphs that are not broadcast
+pub fn stub_input(txid: &str) -> Input {
+    stub_input_with_amount(txid, Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE))
+}
 
-    peg_in_graph.cloned()
+// Directory under the system temp directory, emptied so a test starts from a clean state
+pub fn empty_temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bitvm_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    dir
 }
 
-pub fn find_peg_out_graph(client: &BitVMClient, peg_out_graph_id: &str) -> Option<PegOutGraph> {
-    let peg_out_graph = client
-        .data()
-        .peg_out_graphs
-        .iter()
-        .find(|&graph| graph.id().eq(&peg_out_graph_id));
+// Keys derived from a repeated seed byte, the same on every run
+pub fn seeded_keypair(seed: u8) -> Keypair {
+    Keypair::from_seckey_slice(&Secp256k1::new(), &[seed; 32]).unwrap()
+}
 
-    peg_out_graph.cloned()
+pub fn seeded_public_key(seed: u8) -> PublicKey {
+    PublicKey::new(seeded_keypair(seed).public_key())
+}
+
+pub fn stub_input_with_amount(txid: &str, amount: Amount) -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(txid).unwrap(),
+            vout: 0,
+        },
+        amount,
+    }
+}
+
+pub fn find_peg_in_graph(client: &BitVMClient, peg_in_graph_id: &str) -> Option<PegInGraph> {
+    client.peg_in_graph(peg_in_graph_id).cloned()
+}
+
+pub fn find_peg_out_graph(client: &BitVMClient, peg_out_graph_id: &str) -> Option<PegOutGraph> {
+    client.peg_out_graph(peg_out_graph_id).cloned()
 }
 
 pub fn find_peg_in_graph_by_peg_out(
@@ -240,9 +326,9 @@ pub fn get_default_peg_out_event() -> PegOutEvent {
     PegOutEvent {
         withdrawer_chain_address: "".to_string(),
         withdrawer_destination_address: "".to_string(),
-        withdrawer_public_key_hash: PubkeyHash::from_raw_hash(
+        withdrawer_destination: WithdrawerDestination::PublicKeyHash(PubkeyHash::from_raw_hash(
             Hash::from_str("0e6719ac074b0e3cac76d057643506faa1c266b3").unwrap(),
-        ),
+        )),
         source_outpoint: OutPoint {
             txid: Txid::from_str(
                 "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
@@ -257,6 +343,19 @@ pub fn get_default_peg_out_event() -> PegOutEvent {
         .unwrap(),
         timestamp: 0,
         tx_hash: vec![],
+        withdrawal_nonce: 0,
+    }
+}
+
+// Burn on the destination chain that settles the given peg-out request
+pub fn get_peg_out_burnt_event(peg_out_event: &PegOutEvent) -> PegOutBurntEvent {
+    PegOutBurntEvent {
+        withdrawer_chain_address: peg_out_event.withdrawer_chain_address.clone(),
+        source_outpoint: peg_out_event.source_outpoint,
+        amount: peg_out_event.amount,
+        operator_public_key: peg_out_event.operator_public_key,
+        timestamp: peg_out_event.timestamp,
+        tx_hash: peg_out_event.tx_hash.clone(),
     }
 }
 
@@ -267,47 +366,6 @@ pub fn random_hex<'a>(size: usize) -> Cow<'a, str> {
     Cow::Owned(buffer.to_hex_string(Lower))
 }
 
-const INTERMEDIATE_VARIABLES_FILE_NAME: &str = "intermediates.bin";
-const TEST_CACHE_DIRECTORY_NAME: &str = "test_cache";
-
-pub fn get_intermediate_variables_cached() -> BTreeMap<String, usize> {
-    let intermediate_variables_cache_path = Path::new(TEST_DATA_DIRECTORY_NAME)
-        .join(TEST_CACHE_DIRECTORY_NAME)
-        .join(INTERMEDIATE_VARIABLES_FILE_NAME);
-    let intermediate_variables = if intermediate_variables_cache_path.exists() {
-        read_disk_cache(&intermediate_variables_cache_path)
-            .inspect_err(|e| {
-                eprintln!(
-                    "Failed to read intermediate variables cache after validates its existence: {}",
-                    e
-                );
-            })
-            .ok()
-    } else {
-        None
-    };
-
-    intermediate_variables.unwrap_or_else(|| {
-        println!("Generating new intermediate variables...");
-        let mut intermediate_variables: BTreeMap<String, usize> = BTreeMap::new();
-        for i in 0..NUM_PUBS {
-            intermediate_variables.insert(format!("{}", i), 32);
-        }
-        for i in 0..NUM_U256 {
-            intermediate_variables.insert(format!("{}", i + NUM_PUBS), 32);
-        }
-        for i in 0..NUM_HASH {
-            intermediate_variables.insert(
-                format!("{}", i + NUM_PUBS + NUM_U256),
-                wots_api::HASH_LEN as usize,
-            );
-        }
-
-        write_disk_cache(&intermediate_variables_cache_path, &intermediate_variables).unwrap();
-        intermediate_variables
-    })
-}
-
 pub fn get_valid_proof() -> RawProof {
     get_proof()
 }
@@ -11,10 +11,11 @@ use bridge::{
     client::client::BitVMClient,
     connectors::{base::TaprootConnector, connector_0::Connector0},
     graphs::{
-        base::{BaseGraph, PEG_OUT_FEE},
+        base::{BaseGraph, MIN_RELAY_FEE_RATE, PEG_OUT_FEE},
         peg_in::PegInVerifierStatus,
+        template::GraphTemplate,
     },
-    scripts::generate_pay_to_pubkey_script_address,
+    scripts::{generate_pay_to_pubkey_script_address, DepositorAddressType},
     transactions::{
         base::{
             BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
@@ -39,7 +40,10 @@ async fn test_peg_in_success() {
         &config.depositor_context,
         &config.connector_z,
         deposit_input,
-    );
+        DepositorAddressType::P2wsh,
+        MIN_RELAY_FEE_RATE,
+    )
+    .unwrap();
 
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
@@ -65,21 +69,26 @@ async fn test_peg_in_success() {
         &config.connector_0,
         &config.connector_z,
         confirm_input,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = peg_in_confirm.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = peg_in_confirm.push_nonces(&config.verifier_1_context);
 
-    peg_in_confirm.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_z,
-        &secret_nonces_0,
-    );
-    peg_in_confirm.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_z,
-        &secret_nonces_1,
-    );
+    peg_in_confirm
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let confirm_txid = peg_in_confirm_tx.compute_txid();
@@ -132,7 +141,10 @@ async fn test_peg_in_time_lock_not_surpassed() {
         &config.depositor_context,
         &config.connector_z,
         deposit_input,
-    );
+        DepositorAddressType::P2wsh,
+        MIN_RELAY_FEE_RATE,
+    )
+    .unwrap();
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -151,8 +163,12 @@ async fn test_peg_in_time_lock_not_surpassed() {
         outpoint: refund_funding_outpoint,
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
-    let peg_in_refund =
-        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, refund_input);
+    let peg_in_refund = PegInRefundTransaction::new(
+        &config.depositor_context,
+        &config.connector_z,
+        refund_input,
+        MIN_RELAY_FEE_RATE,
+    );
     let peg_in_refund_tx = peg_in_refund.finalize();
 
     // mine peg-in refund
@@ -182,7 +198,10 @@ async fn test_peg_in_time_lock_surpassed() {
         &config.depositor_context,
         &config.connector_z,
         deposit_input,
-    );
+        DepositorAddressType::P2wsh,
+        MIN_RELAY_FEE_RATE,
+    )
+    .unwrap();
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -201,8 +220,12 @@ async fn test_peg_in_time_lock_surpassed() {
         outpoint: refund_funding_outpoint,
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
-    let peg_in_refund =
-        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, refund_input);
+    let peg_in_refund = PegInRefundTransaction::new(
+        &config.depositor_context,
+        &config.connector_z,
+        refund_input,
+        MIN_RELAY_FEE_RATE,
+    );
     let peg_in_refund_tx = peg_in_refund.finalize();
     let refund_txid = peg_in_refund_tx.compute_txid();
 
@@ -279,8 +302,8 @@ async fn get_pegin_input(config: &SetupConfig, sats: u64) -> Input {
 async fn test_peg_in_graph_automatic_verifier() {
     // helper functions
     let sync = |a: &mut BitVMClient, b: &mut BitVMClient| {
-        a.merge_data(b.data().clone());
-        b.merge_data(a.data().clone());
+        a.merge_data(b.data().clone()).unwrap();
+        b.merge_data(a.data().clone()).unwrap();
     };
     let graph = |client: &BitVMClient| client.data().peg_in_graphs[0].clone();
     let pegouts_of = |client: &BitVMClient| {
@@ -312,8 +335,13 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // create the actual graph & check that status changes to PegInWait
     client_0
-        .create_peg_in_graph(deposit_input, "0000000000000000000000000000000000000000")
-        .await;
+        .create_peg_in_graph(
+            deposit_input,
+            "0000000000000000000000000000000000000000",
+            GraphTemplate::default(),
+        )
+        .await
+        .unwrap();
     assert_eq!(
         graph(client_0)
             .verifier_status(&esplora, &config.verifier_0_context, &[])
@@ -323,7 +351,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // wait peg-in deposit and wait for the tx to be confirmed (which will set status to PegInPendingOurNonces)
     client_0
-        .process_peg_in_as_depositor(&graph(client_0).id())
+        .process_peg_in_as_depositor(graph(client_0).peg_in_graph_id())
         .await;
     loop {
         if !matches!(
@@ -365,7 +393,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // make operator submit a pegout graph & check that status changes to PegInWait
     client_0
-        .process_peg_in_as_operator(&graph(client_0).id())
+        .process_peg_in_as_operator(graph(client_0).peg_in_graph_id())
         .await;
     assert_eq!(
         graph(client_0)
@@ -380,7 +408,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // submit client_0 nonce & check that status changes to PegInAwaitingNonces
     client_0
-        .process_peg_in_as_verifier(&graph(client_0).id())
+        .process_peg_in_as_verifier(graph(client_0).peg_in_graph_id())
         .await;
     sync(client_0, client_1);
     assert_eq!(
@@ -396,7 +424,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // submit client_1 nonce & check that status changes to PegInPendingOurSignature
     client_1
-        .process_peg_in_as_verifier(&graph(client_0).id())
+        .process_peg_in_as_verifier(graph(client_0).peg_in_graph_id())
         .await;
     sync(client_0, client_1);
     assert!(matches!(
@@ -412,7 +440,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // submit client_0 signature & check that status changes to PegInAwaitingSignatures
     client_0
-        .process_peg_in_as_verifier(&graph(client_0).id())
+        .process_peg_in_as_verifier(graph(client_0).peg_in_graph_id())
         .await;
     sync(client_0, client_1);
     assert_eq!(
@@ -428,7 +456,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // submit client_1 signature & check that status changes to PegInPresign
     client_1
-        .process_peg_in_as_verifier(&graph(client_0).id())
+        .process_peg_in_as_verifier(graph(client_0).peg_in_graph_id())
         .await;
     sync(client_0, client_1);
     assert_eq!(
@@ -444,7 +472,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     // submit confirm tx & check that status changes to PegInComplete
     client_0
-        .process_peg_in_as_verifier(&graph(client_0).id())
+        .process_peg_in_as_verifier(graph(client_0).peg_in_graph_id())
         .await;
     loop {
         if graph(client_0)
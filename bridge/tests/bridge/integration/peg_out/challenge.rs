@@ -54,6 +54,7 @@ async fn test_challenge_success() {
         &config.client_0,
         &config.operator_context,
         &kick_off_1_funding_utxo_address,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         &config.connector_6,
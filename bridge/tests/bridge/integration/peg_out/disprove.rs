@@ -1,12 +1,17 @@
 use bitcoin::{Address, Amount, OutPoint};
 use bridge::{
     connectors::{base::TaprootConnector, connector_c::get_commit_from_assert_commit_tx},
-    graphs::base::DUST_AMOUNT,
+    graphs::{
+        base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
+        template::DEFAULT_DISPROVE_BURN_PERCENTAGE,
+    },
     scripts::generate_pay_to_pubkey_script_address,
     transactions::{
         assert_transactions::{
-            assert_commit_1::AssertCommit1Transaction, assert_commit_2::AssertCommit2Transaction,
-            assert_final::AssertFinalTransaction, utils::sign_assert_tx_with_groth16_proof,
+            assert_commit_1::AssertCommit1Transaction,
+            assert_commit_2::AssertCommit2Transaction,
+            assert_final::{AssertFinalOutputLayout, AssertFinalTransaction},
+            utils::sign_assert_tx_with_groth16_proof,
         },
         base::{
             BaseTransaction, Input, MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2,
@@ -67,6 +72,7 @@ async fn test_disprove_success() {
         &config.client_0,
         &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
@@ -115,6 +121,7 @@ async fn test_disprove_success() {
                 amount: assert_initial_tx.output[idx + vout_base].value,
             })
             .collect(),
+        MIN_RELAY_FEE_RATE,
     );
     assert_commit_1.sign(
         &config.assert_commit_connectors_e_1,
@@ -150,6 +157,7 @@ async fn test_disprove_success() {
                 amount: assert_initial_tx.output[idx + vout_base].value,
             })
             .collect(),
+        MIN_RELAY_FEE_RATE,
     );
     assert_commit_2.sign(
         &config.assert_commit_connectors_e_2,
@@ -206,21 +214,27 @@ async fn test_disprove_success() {
         assert_final_input_0,
         assert_final_input_1,
         assert_final_input_2,
+        AssertFinalOutputLayout::Separate,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = assert_final.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = assert_final.push_nonces(&config.verifier_1_context);
 
-    assert_final.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_d,
-        &secret_nonces_0,
-    );
-    assert_final.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_d,
-        &secret_nonces_1,
-    );
+    assert_final
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_d,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    assert_final
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_d,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let assert_final_tx = assert_final.finalize();
     let assert_final_txid = assert_final_tx.compute_txid();
@@ -267,37 +281,48 @@ async fn test_disprove_success() {
 
     let mut disprove = DisproveTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_5,
         &config.connector_c,
         disprove_input_0,
         disprove_input_1,
+        DEFAULT_DISPROVE_BURN_PERCENTAGE,
+        None,
     );
 
     let secret_nonces_0 = disprove.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove.push_nonces(&config.verifier_1_context);
 
-    disprove.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    disprove
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
     let verifier_reward_script = reward_address.script_pubkey(); // send reward to withdrawer address
-    disprove.add_input_output(
-        &config.connector_c,
-        script_index,
-        disprove_witness,
-        verifier_reward_script,
-    );
+    disprove
+        .add_input_output(
+            &config.connector_c,
+            script_index,
+            disprove_witness,
+            verifier_reward_script,
+        )
+        .unwrap();
 
     let disprove_tx = disprove.finalize();
     let disprove_txid = disprove_tx.compute_txid();
@@ -1,7 +1,7 @@
 use bitcoin::{Address, Amount, OutPoint};
 use bridge::{
     connectors::base::TaprootConnector,
-    graphs::base::DUST_AMOUNT,
+    graphs::{base::DUST_AMOUNT, template::DEFAULT_DISPROVE_BURN_PERCENTAGE},
     scripts::generate_pay_to_pubkey_script_address,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_DISPROVE_CHAIN, MIN_RELAY_FEE_KICK_OFF_2},
@@ -43,6 +43,7 @@ async fn test_disprove_chain_success() {
         &config.client_0,
         &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
@@ -62,29 +63,40 @@ async fn test_disprove_chain_success() {
 
     let mut disprove_chain = DisproveChainTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_b,
         disprove_chain_input_0,
+        DEFAULT_DISPROVE_BURN_PERCENTAGE,
+        None,
     );
 
     let secret_nonces_0 = disprove_chain.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove_chain.push_nonces(&config.verifier_1_context);
 
-    disprove_chain.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    disprove_chain.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    disprove_chain
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove_chain
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
-    disprove_chain.add_output(reward_address.script_pubkey());
+    disprove_chain
+        .add_output(reward_address.script_pubkey())
+        .unwrap();
 
     let disprove_chain_tx = disprove_chain.finalize();
     let disprove_chain_txid = disprove_chain_tx.compute_txid();
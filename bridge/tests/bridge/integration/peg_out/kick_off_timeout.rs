@@ -44,6 +44,7 @@ async fn test_kick_off_timeout_success() {
         &config.client_0,
         &config.operator_context,
         &kick_off_1_funding_utxo_address,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         &config.connector_6,
@@ -64,8 +65,10 @@ async fn test_kick_off_timeout_success() {
 
     let mut kick_off_timeout = KickOffTimeoutTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_1,
         kick_off_timeout_input_0,
+        None,
     );
 
     let secret_nonces_0 = kick_off_timeout.push_nonces(&config.verifier_0_context);
@@ -77,28 +80,36 @@ async fn test_kick_off_timeout_success() {
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys, // Verifiers get this via remote storage.
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_0_context,
-        &verifier_0_connector_1,
-        &secret_nonces_0,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &verifier_0_connector_1,
+            &secret_nonces_0,
+        )
+        .unwrap();
     let verifier_1_connector_1 = Connector1::new(
         config.verifier_0_context.network,
         &config.operator_context.operator_taproot_public_key,
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys,
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_1_context,
-        &verifier_1_connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &verifier_1_connector_1,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
-    kick_off_timeout.add_output(reward_address.script_pubkey());
+    kick_off_timeout
+        .add_output(reward_address.script_pubkey())
+        .unwrap();
 
     let kick_off_timeout_tx = kick_off_timeout.finalize();
     let kick_off_timeout_txid = kick_off_timeout_tx.compute_txid();
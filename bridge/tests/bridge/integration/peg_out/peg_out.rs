@@ -1,17 +1,25 @@
-use bitcoin::{Address, Amount};
+use bitcoin::{Address, Amount, Network};
 
 use bridge::{
-    client::chain::chain::PegOutEvent,
-    scripts::{generate_p2pkh_address, generate_pay_to_pubkey_script_address},
+    client::chain::chain::{PegOutEvent, WithdrawerDestination},
+    contexts::base::generate_keys_from_secret,
+    scripts::{
+        generate_p2pkh_address, generate_p2tr_key_path_address, generate_p2wpkh_address,
+        generate_pay_to_pubkey_script_address,
+    },
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_PEG_OUT},
         peg_out::PegOutTransaction,
+        pre_signed::PreSignedTransaction,
     },
 };
 
 use crate::bridge::{
     faucet::{Faucet, FaucetType},
-    helper::{check_tx_output_sum, generate_stub_outpoint, verify_funding_inputs},
+    helper::{
+        check_tx_output_sum, generate_stub_outpoint, get_default_peg_out_event,
+        verify_funding_inputs,
+    },
     setup::{setup_test, INITIAL_AMOUNT},
 };
 
@@ -59,12 +67,15 @@ async fn test_peg_out_success() {
             &config.withdrawer_context.withdrawer_public_key,
         )
         .to_string(),
-        withdrawer_public_key_hash: config
-            .withdrawer_context
-            .withdrawer_public_key
-            .pubkey_hash(),
+        withdrawer_destination: WithdrawerDestination::PublicKeyHash(
+            config
+                .withdrawer_context
+                .withdrawer_public_key
+                .pubkey_hash(),
+        ),
         operator_public_key: config.operator_context.operator_public_key,
         tx_hash: [0u8; 4].into(),
+        withdrawal_nonce: 0,
     };
     let input = Input {
         outpoint: operator_funding_outpoint,
@@ -83,3 +94,46 @@ async fn test_peg_out_success() {
     assert!(peg_out_result.is_ok());
     println!("Peg Out Txid: {:?}", peg_out_txid);
 }
+
+#[test]
+fn test_peg_out_pays_taproot_destination_address() {
+    let network = Network::Regtest;
+    let (withdrawer_keypair, withdrawer_public_key) = generate_keys_from_secret(
+        network,
+        "fffd54f6d8f8ad470cb507fd4b6e9b3ea26b4221a4900cc5ad5916ce67c02f1e",
+    );
+    let taproot_address =
+        generate_p2tr_key_path_address(network, &withdrawer_keypair.x_only_public_key().0);
+    let mut event = get_default_peg_out_event();
+    let input = Input {
+        outpoint: event.source_outpoint,
+        amount: Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_OUT),
+    };
+
+    event.withdrawer_destination = WithdrawerDestination::from_address(&taproot_address).unwrap();
+    let peg_out =
+        PegOutTransaction::new_for_validation(network, &event.operator_public_key, &event, input);
+    assert_eq!(
+        peg_out.tx().output[0].script_pubkey,
+        taproot_address.script_pubkey()
+    );
+
+    // A P2PKH destination keeps the inscription
+    event.withdrawer_destination = WithdrawerDestination::from_address(&generate_p2pkh_address(
+        network,
+        &withdrawer_public_key,
+    ))
+    .unwrap();
+    let peg_out =
+        PegOutTransaction::new_for_validation(network, &event.operator_public_key, &event, input);
+    assert!(peg_out.tx().output[0].script_pubkey.is_p2wsh());
+
+    // Other address types cannot be paid out
+    assert!(
+        WithdrawerDestination::from_address(&generate_p2wpkh_address(
+            network,
+            &withdrawer_public_key
+        ))
+        .is_none()
+    );
+}
@@ -2,7 +2,7 @@ use bitcoin::{Address, Amount, OutPoint};
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     superblock::get_start_time_block_number,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_KICK_OFF_1, MIN_RELAY_FEE_START_TIME},
@@ -42,6 +42,7 @@ async fn test_start_time_success() {
         &config.client_0,
         &config.operator_context,
         &kick_off_1_funding_utxo_address,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         &config.connector_6,
@@ -64,6 +65,7 @@ async fn test_start_time_success() {
         &config.operator_context,
         &config.connector_2,
         start_time_input_0,
+        MIN_RELAY_FEE_RATE,
     );
 
     start_time.sign(
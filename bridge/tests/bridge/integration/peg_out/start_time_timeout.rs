@@ -48,6 +48,7 @@ async fn test_start_time_timeout_success() {
         &config.client_0,
         &config.operator_context,
         &kick_off_1_funding_utxo_address,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         &config.connector_6,
@@ -75,33 +76,43 @@ async fn test_start_time_timeout_success() {
     };
     let mut start_time_timeout = StartTimeTimeoutTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         start_time_timeout_input_0,
         start_time_timeout_input_1,
+        None,
     );
 
     let secret_nonces_0 = start_time_timeout.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = start_time_timeout.push_nonces(&config.verifier_1_context);
 
-    start_time_timeout.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_1,
-    );
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_1,
+            &config.connector_2,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_1,
+            &config.connector_2,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
-    start_time_timeout.add_output(reward_address.script_pubkey());
+    start_time_timeout
+        .add_output(reward_address.script_pubkey())
+        .unwrap();
 
     let start_time_timeout_tx = start_time_timeout.finalize();
     let start_time_timeout_txid = start_time_timeout_tx.compute_txid();
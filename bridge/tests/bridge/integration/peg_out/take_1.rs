@@ -3,7 +3,7 @@ use bitvm::signatures::signing_winternitz::WinternitzSigningInputs;
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
-    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_ASSERT_SET, PEG_OUT_FEE},
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_ASSERT_SET, MIN_RELAY_FEE_RATE, PEG_OUT_FEE},
     scripts::generate_pay_to_pubkey_script_address,
     superblock::{get_superblock_hash_message, get_superblock_message},
     transactions::{
@@ -71,6 +71,7 @@ async fn test_take_1_success() {
         &config.client_0,
         &config.operator_context,
         &kick_off_1_funding_utxo_address,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         &config.connector_6,
@@ -89,10 +90,11 @@ async fn test_take_1_success() {
         amount: kick_off_1_tx.output[vout as usize].value,
     };
     let mut kick_off_2 = KickOff2Transaction::new(
-        &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         kick_off_2_input_0,
+        MIN_RELAY_FEE_RATE,
     );
     let superblock_header = get_superblock_header();
     kick_off_2.sign(
@@ -160,23 +162,29 @@ async fn test_take_1_success() {
         take_1_input_1,
         take_1_input_2,
         take_1_input_3,
+        None,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = take_1.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_1.push_nonces(&config.verifier_1_context);
 
-    take_1.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    take_1.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    take_1
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_1
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let take_1_tx = take_1.finalize();
     let take_1_txid = take_1_tx.compute_txid();
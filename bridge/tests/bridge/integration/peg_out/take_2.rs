@@ -1,7 +1,7 @@
 use bitcoin::{Address, Amount, OutPoint};
 use bridge::{
     connectors::base::TaprootConnector,
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     scripts::generate_pay_to_pubkey_script_address,
     transactions::{
         base::{
@@ -116,26 +116,32 @@ async fn test_take_2_success() {
         &config.connector_5,
         &config.connector_c,
         take_2_input_0,
-        take_2_input_1,
+        Some(take_2_input_1),
         take_2_input_2,
         take_2_input_3,
+        None,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = take_2.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_2.push_nonces(&config.verifier_1_context);
 
-    take_2.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    take_2.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    take_2
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_2
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     take_2.sign(&config.operator_context, &config.connector_c);
 
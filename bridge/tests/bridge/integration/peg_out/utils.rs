@@ -7,10 +7,12 @@ use bridge::{
     commitments::CommitmentMessageId,
     connectors::{
         connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
-        connector_4::Connector4, connector_5::Connector5, connector_6::Connector6,
-        connector_b::ConnectorB, connector_c::ConnectorC, connector_z::ConnectorZ,
+        connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
+        connector_6::Connector6, connector_b::ConnectorB, connector_c::ConnectorC,
+        connector_z::ConnectorZ,
     },
     contexts::{depositor::DepositorContext, operator::OperatorContext, verifier::VerifierContext},
+    graphs::base::MIN_RELAY_FEE_RATE,
     superblock::{get_superblock_hash_message, get_superblock_message},
     transactions::{
         assert::AssertTransaction,
@@ -30,6 +32,7 @@ pub async fn create_and_mine_kick_off_1_tx(
     client: &BitVMClient,
     operator_context: &OperatorContext,
     kick_off_1_funding_utxo_address: &Address,
+    connector_0: &Connector0,
     connector_1: &Connector1,
     connector_2: &Connector2,
     connector_6: &Connector6,
@@ -44,10 +47,13 @@ pub async fn create_and_mine_kick_off_1_tx(
     };
     let mut kick_off_1 = KickOff1Transaction::new(
         operator_context,
+        connector_0,
         connector_1,
         connector_2,
         connector_6,
         kick_off_1_input,
+        None,
+        MIN_RELAY_FEE_RATE,
     );
 
     let ethereum_txid = "8b274fbb76c72f66c467c976c61d5ac212620e036818b5986a33f7b557cb2de8";
@@ -82,6 +88,7 @@ pub async fn create_and_mine_kick_off_2_tx(
     client: &BitVMClient,
     operator_context: &OperatorContext,
     connector_1: &Connector1,
+    connector_3: &Connector3,
     connector_b: &ConnectorB,
     kick_off_2_funding_utxo_address: &Address,
     input_amount: Amount,
@@ -94,10 +101,11 @@ pub async fn create_and_mine_kick_off_2_tx(
         amount: input_amount,
     };
     let mut kick_off_2 = KickOff2Transaction::new(
-        &operator_context,
-        &connector_1,
-        &connector_b,
+        connector_1,
+        connector_3,
+        connector_b,
         kick_off_2_input,
+        MIN_RELAY_FEE_RATE,
     );
     let superblock_header = get_superblock_header();
     kick_off_2.sign(
@@ -153,8 +161,12 @@ pub async fn create_and_mine_assert_tx(
     let secret_nonces_0 = assert.push_nonces(verifier_0_context);
     let secret_nonces_1 = assert.push_nonces(verifier_1_context);
 
-    assert.pre_sign(verifier_0_context, connector_b, &secret_nonces_0);
-    assert.pre_sign(verifier_1_context, connector_b, &secret_nonces_1);
+    assert
+        .pre_sign(verifier_0_context, connector_b, &secret_nonces_0)
+        .unwrap();
+    assert
+        .pre_sign(verifier_1_context, connector_b, &secret_nonces_1)
+        .unwrap();
 
     let assert_tx = assert.finalize();
     let assert_txid = assert_tx.compute_txid();
@@ -184,14 +196,23 @@ pub async fn create_and_mine_peg_in_confirm_tx(
         outpoint: peg_in_confirm_funding_outpoint,
         amount: input_amount,
     };
-    let mut peg_in_confirm =
-        PegInConfirmTransaction::new(depositor_context, connector_0, connector_z, confirm_input);
+    let mut peg_in_confirm = PegInConfirmTransaction::new(
+        depositor_context,
+        connector_0,
+        connector_z,
+        confirm_input,
+        MIN_RELAY_FEE_RATE,
+    );
 
     let secret_nonces_0 = peg_in_confirm.push_nonces(verifier_0_context);
     let secret_nonces_1 = peg_in_confirm.push_nonces(verifier_1_context);
 
-    peg_in_confirm.pre_sign(verifier_0_context, connector_z, &secret_nonces_0);
-    peg_in_confirm.pre_sign(verifier_1_context, connector_z, &secret_nonces_1);
+    peg_in_confirm
+        .pre_sign(verifier_0_context, connector_z, &secret_nonces_0)
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(verifier_1_context, connector_z, &secret_nonces_1)
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let peg_in_confirm_txid = peg_in_confirm_tx.compute_txid();
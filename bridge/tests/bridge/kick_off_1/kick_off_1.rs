@@ -1,16 +1,21 @@
-use bitcoin::Amount;
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
     commitments::CommitmentMessageId,
-    connectors::base::TaprootConnector,
-    graphs::base::DUST_AMOUNT,
+    connectors::{base::TaprootConnector, connector_6::Connector6},
+    constants::DestinationNetwork,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_KICK_OFF_1, MIN_RELAY_FEE_START_TIME},
-        kick_off_1::KickOff1Transaction,
+        kick_off_1::{DestinationMetadataSigningInputs, KickOff1Transaction},
+        pre_signed::PreSignedTransaction,
+        script_diagnostics::ScriptDiagnostics,
     },
 };
 
-use bitvm::signatures::signing_winternitz::WinternitzSigningInputs;
+use bitvm::signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSigningInputs};
 
 use crate::bridge::{
     faucet::{Faucet, FaucetType},
@@ -42,10 +47,13 @@ async fn test_kick_off_1_tx_success() {
 
     let mut kick_off_1_tx = KickOff1Transaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         &config.connector_6,
         input,
+        None,
+        MIN_RELAY_FEE_RATE,
     );
     let ethereum_txid = "8b274fbb76c72f66c467c976c61d5ac212620e036818b5986a33f7b557cb2de8";
     let bitcoin_txid = "8b4cce4a1a9522392c095df6416533d89e1e6ac7bdf8ab3c1685426b321ed182";
@@ -71,3 +79,110 @@ async fn test_kick_off_1_tx_success() {
     println!("Kick Off 1 tx result: {:?}\n", result);
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_kick_off_1_tx_commits_destination_metadata() {
+    let config = setup_test().await;
+
+    let mut commitment_public_keys = config.connector_6.commitment_public_keys.clone();
+    for message_id in [
+        CommitmentMessageId::DestinationChainId,
+        CommitmentMessageId::WithdrawalNonce,
+    ] {
+        let public_key = WinternitzPublicKey::from(&config.commitment_secrets[&message_id]);
+        commitment_public_keys.insert(message_id, public_key);
+    }
+    let connector_6 = Connector6::new(
+        config.operator_context.network,
+        &config.operator_context.operator_taproot_public_key,
+        &commitment_public_keys,
+    );
+    assert!(!config.connector_6.commits_destination_metadata());
+    assert!(connector_6.commits_destination_metadata());
+    assert_ne!(
+        connector_6.generate_taproot_address(),
+        config.connector_6.generate_taproot_address()
+    );
+
+    let subsequent_tx_fee = MIN_RELAY_FEE_START_TIME + DUST_AMOUNT * 2;
+    let input_amount =
+        Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_KICK_OFF_1 + subsequent_tx_fee);
+    // Only executed locally, the input does not need to exist
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount: input_amount,
+    };
+
+    let mut kick_off_1_tx = KickOff1Transaction::new(
+        &config.operator_context,
+        &config.connector_0,
+        &config.connector_1,
+        &config.connector_2,
+        &connector_6,
+        input,
+        None,
+        MIN_RELAY_FEE_RATE,
+    );
+    let bitcoin_txid = [1u8; 32];
+    let ethereum_txid = [2u8; 32];
+    let destination_chain_id = DestinationNetwork::EthereumSepolia.chain_id().to_be_bytes();
+    let withdrawal_nonce = 42u64.to_be_bytes();
+    kick_off_1_tx.sign_with_destination_metadata(
+        &config.operator_context,
+        &connector_6,
+        &WinternitzSigningInputs {
+            message: &bitcoin_txid,
+            signing_key: &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
+        },
+        &WinternitzSigningInputs {
+            message: &ethereum_txid,
+            signing_key: &config.commitment_secrets
+                [&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+        },
+        &DestinationMetadataSigningInputs {
+            destination_chain_id: WinternitzSigningInputs {
+                message: &destination_chain_id,
+                signing_key: &config.commitment_secrets[&CommitmentMessageId::DestinationChainId],
+            },
+            withdrawal_nonce: WinternitzSigningInputs {
+                message: &withdrawal_nonce,
+                signing_key: &config.commitment_secrets[&CommitmentMessageId::WithdrawalNonce],
+            },
+        },
+    );
+
+    // The leaf script checks the metadata commitments along with the peg-out txids
+    let tx = kick_off_1_tx.finalize();
+    let prev_out = kick_off_1_tx.prev_outs()[0].clone();
+    let diagnostics = ScriptDiagnostics::new(&tx, &[Some(prev_out)]);
+    assert!(
+        diagnostics.failed_inputs().next().is_none(),
+        "{diagnostics}"
+    );
+
+    // Without the metadata commitments the witness does not satisfy the extended script
+    let mut mismatched_tx = kick_off_1_tx.clone();
+    mismatched_tx.sign(
+        &config.operator_context,
+        &connector_6,
+        &WinternitzSigningInputs {
+            message: &bitcoin_txid,
+            signing_key: &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
+        },
+        &WinternitzSigningInputs {
+            message: &ethereum_txid,
+            signing_key: &config.commitment_secrets
+                [&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+        },
+    );
+    let tx = mismatched_tx.finalize();
+    let prev_out = mismatched_tx.prev_outs()[0].clone();
+    let diagnostics = ScriptDiagnostics::new(&tx, &[Some(prev_out)]);
+    assert_eq!(diagnostics.failed_inputs().count(), 1);
+}
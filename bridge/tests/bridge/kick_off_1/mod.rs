@@ -1 +1,2 @@
 pub mod kick_off_1;
+pub mod peg_out_race;
@@ -0,0 +1,123 @@
+use std::str::FromStr;
+
+use bitcoin::{Network, OutPoint, Txid};
+
+use bridge::{
+    contexts::base::generate_keys_from_secret,
+    graphs::{
+        base::PegOutGraphId,
+        peg_out_race::{find_peg_out_races, PegOutClaim, PegOutRace},
+    },
+};
+
+const OPERATOR_SECRETS: [&str; 3] = [
+    "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac",
+    "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2",
+    "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f",
+];
+const EVENT_TIMESTAMP: u32 = 1_700_000_000;
+
+fn source_outpoint(vout: u32) -> OutPoint {
+    OutPoint {
+        txid: Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327")
+            .unwrap(),
+        vout,
+    }
+}
+
+fn claim(operator: usize, event_timestamp: u32, peg_out_height: Option<u32>) -> PegOutClaim {
+    let (_, operator_public_key) =
+        generate_keys_from_secret(Network::Regtest, OPERATOR_SECRETS[operator]);
+    PegOutClaim {
+        graph_id: PegOutGraphId::from_str(&format!("{:064X}", operator + 1)).unwrap(),
+        operator_public_key,
+        event_timestamp,
+        peg_out_height,
+    }
+}
+
+fn graph_ids(claims: &[PegOutClaim]) -> Vec<PegOutGraphId> {
+    claims.iter().map(|claim| claim.graph_id.clone()).collect()
+}
+
+#[test]
+fn test_peg_out_race_is_open_until_a_peg_out_confirms() {
+    let race = PegOutRace::new(
+        source_outpoint(0),
+        vec![
+            claim(0, EVENT_TIMESTAMP + 1, None),
+            claim(1, EVENT_TIMESTAMP, None),
+        ],
+    );
+
+    assert!(!race.is_settled());
+    assert!(race.winner().is_none());
+    assert!(race.losers().is_empty());
+    assert!(!race.is_loser(&claim(0, EVENT_TIMESTAMP, None).graph_id));
+}
+
+#[test]
+fn test_first_confirmed_peg_out_wins() {
+    // The later event confirmed first, and a graph without a confirmed peg-out loses too
+    let race = PegOutRace::new(
+        source_outpoint(0),
+        vec![
+            claim(0, EVENT_TIMESTAMP, Some(101)),
+            claim(1, EVENT_TIMESTAMP + 1, Some(100)),
+            claim(2, EVENT_TIMESTAMP - 1, None),
+        ],
+    );
+
+    assert_eq!(
+        race.winner(),
+        Some(&claim(1, EVENT_TIMESTAMP + 1, Some(100)))
+    );
+    assert_eq!(
+        graph_ids(race.losers()),
+        graph_ids(&[claim(0, 0, None), claim(2, 0, None)])
+    );
+    assert!(race.is_loser(&claim(2, 0, None).graph_id));
+    assert!(!race.is_loser(&claim(1, 0, None).graph_id));
+}
+
+#[test]
+fn test_peg_out_race_ties_are_broken_deterministically() {
+    // Same block: the earlier destination chain event wins
+    let race = PegOutRace::new(
+        source_outpoint(0),
+        vec![
+            claim(0, EVENT_TIMESTAMP + 1, Some(100)),
+            claim(1, EVENT_TIMESTAMP, Some(100)),
+        ],
+    );
+    assert_eq!(race.winner().unwrap().graph_id, claim(1, 0, None).graph_id);
+
+    // Same block and event time: the lower graph id wins, whatever order the graphs are in
+    let claims = vec![
+        claim(2, EVENT_TIMESTAMP, Some(100)),
+        claim(1, EVENT_TIMESTAMP, Some(100)),
+    ];
+    let mut reversed = claims.clone();
+    reversed.reverse();
+    let race = PegOutRace::new(source_outpoint(0), claims);
+    assert_eq!(race, PegOutRace::new(source_outpoint(0), reversed));
+    assert_eq!(race.winner().unwrap().graph_id, claim(1, 0, None).graph_id);
+}
+
+#[test]
+fn test_find_peg_out_races() {
+    let races = find_peg_out_races(vec![
+        (source_outpoint(0), claim(0, EVENT_TIMESTAMP, Some(100))),
+        (source_outpoint(1), claim(1, EVENT_TIMESTAMP, Some(100))),
+        (source_outpoint(0), claim(2, EVENT_TIMESTAMP, None)),
+    ]);
+
+    // A withdrawal initiated for one graph only is no race
+    assert_eq!(races.len(), 1);
+    assert_eq!(races[0].source_outpoint, source_outpoint(0));
+    assert_eq!(
+        graph_ids(&races[0].claims),
+        graph_ids(&[claim(0, 0, None), claim(2, 0, None)])
+    );
+    assert!(races[0].is_loser(&claim(2, 0, None).graph_id));
+}
@@ -3,7 +3,7 @@ use bitcoin::Amount;
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     superblock::{get_superblock_hash_message, get_superblock_message},
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_KICK_OFF_2},
@@ -42,13 +42,14 @@ async fn test_kick_off_2_tx_success() {
         generate_stub_outpoint(&config.client_0, &funding_utxo_address0, input_value0).await;
 
     let mut kick_off_2_tx = KickOff2Transaction::new(
-        &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         Input {
             outpoint: funding_outpoint0,
             amount: input_value0,
         },
+        MIN_RELAY_FEE_RATE,
     );
 
     let superblock_header = get_superblock_header();
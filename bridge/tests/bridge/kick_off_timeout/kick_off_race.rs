@@ -0,0 +1,118 @@
+use bridge::{
+    client::clock::{BlockProducer, MockBlockProducer},
+    graphs::{
+        kick_off_race::{KickOffPath, KickOffRace, KickOffTimelocks},
+        template::GraphTimelocks,
+    },
+};
+
+const KICK_OFF_1_HEIGHT: u32 = 800_000;
+
+// Mainnet timelocks, on regtest all of them are the same
+fn timelocks() -> KickOffTimelocks {
+    let timelocks = GraphTimelocks::default();
+    KickOffTimelocks {
+        kick_off_2: timelocks.kick_off_2,
+        kick_off_timeout: timelocks.kick_off_timeout,
+        start_time_timeout: timelocks.start_time_timeout,
+    }
+}
+
+fn race(
+    block_producer: &MockBlockProducer,
+    start_time_confirmed: bool,
+    winner: Option<KickOffPath>,
+) -> KickOffRace {
+    KickOffRace {
+        kick_off_1_height: KICK_OFF_1_HEIGHT,
+        tip_height: block_producer.height(),
+        start_time_confirmed,
+        winner,
+    }
+}
+
+// Mines up to the tip after which a transaction with the given relative timelock on kick-off 1
+// can be mined
+async fn produce_until_timelock(block_producer: &MockBlockProducer, num_blocks: u32) {
+    let target = KICK_OFF_1_HEIGHT + num_blocks - 1;
+    let height = block_producer
+        .produce_blocks(target - block_producer.height())
+        .await
+        .unwrap();
+    assert_eq!(height, target);
+}
+
+#[tokio::test]
+async fn test_start_time_timeout_race_at_boundary_heights() {
+    let timelocks = timelocks();
+    let block_producer = MockBlockProducer::new(KICK_OFF_1_HEIGHT);
+
+    // Only the operator can act one block before the boundary
+    produce_until_timelock(&block_producer, timelocks.start_time_timeout - 1).await;
+    let state = race(&block_producer, false, None);
+    assert!(!state.is_timelock_met(timelocks.start_time_timeout));
+    assert_eq!(
+        state.operator_path(&timelocks),
+        Some(KickOffPath::StartTime)
+    );
+    assert_eq!(state.verifier_path(&timelocks), None);
+
+    // At the boundary start time and its timeout race
+    block_producer.produce_blocks(1).await.unwrap();
+    let state = race(&block_producer, false, None);
+    assert_eq!(
+        state.operator_path(&timelocks),
+        Some(KickOffPath::StartTime)
+    );
+    assert_eq!(
+        state.verifier_path(&timelocks),
+        Some(KickOffPath::StartTimeTimeout)
+    );
+
+    // The confirmed one decides, however late the other side sees it
+    let timed_out = race(&block_producer, false, Some(KickOffPath::StartTimeTimeout));
+    assert_eq!(timed_out.operator_path(&timelocks), None);
+    assert_eq!(timed_out.verifier_path(&timelocks), None);
+    let started = race(&block_producer, true, None);
+    assert_eq!(started.operator_path(&timelocks), None);
+    assert_eq!(started.verifier_path(&timelocks), None);
+}
+
+#[tokio::test]
+async fn test_kick_off_timeout_race_at_boundary_heights() {
+    let timelocks = timelocks();
+    let block_producer = MockBlockProducer::new(KICK_OFF_1_HEIGHT);
+
+    produce_until_timelock(&block_producer, timelocks.kick_off_2 - 1).await;
+    let state = race(&block_producer, true, None);
+    assert_eq!(state.operator_path(&timelocks), None);
+    assert_eq!(state.verifier_path(&timelocks), None);
+
+    // The operator's window opens
+    block_producer.produce_blocks(1).await.unwrap();
+    let state = race(&block_producer, true, None);
+    assert_eq!(state.operator_path(&timelocks), Some(KickOffPath::KickOff2));
+    assert_eq!(state.verifier_path(&timelocks), None);
+
+    // Still only the operator's one block before the timeout
+    produce_until_timelock(&block_producer, timelocks.kick_off_timeout - 1).await;
+    let state = race(&block_producer, true, None);
+    assert_eq!(state.operator_path(&timelocks), Some(KickOffPath::KickOff2));
+    assert_eq!(state.verifier_path(&timelocks), None);
+
+    // From the boundary on both are valid
+    block_producer.produce_blocks(1).await.unwrap();
+    let state = race(&block_producer, true, None);
+    assert_eq!(state.operator_path(&timelocks), Some(KickOffPath::KickOff2));
+    assert_eq!(
+        state.verifier_path(&timelocks),
+        Some(KickOffPath::KickOffTimeout)
+    );
+
+    for winner in [KickOffPath::KickOff2, KickOffPath::KickOffTimeout] {
+        let settled = race(&block_producer, true, Some(winner));
+        assert!(settled.is_settled());
+        assert_eq!(settled.operator_path(&timelocks), None);
+        assert_eq!(settled.verifier_path(&timelocks), None);
+    }
+}
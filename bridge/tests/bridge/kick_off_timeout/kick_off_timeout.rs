@@ -39,32 +39,42 @@ async fn test_kick_off_timeout_tx_success() {
 
     let mut kick_off_timeout_tx = KickOffTimeoutTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_1,
         Input {
             outpoint: outpoint_0,
             amount: input_value0,
         },
+        None,
     );
 
     let secret_nonces_0 = kick_off_timeout_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = kick_off_timeout_tx.push_nonces(&config.verifier_1_context);
 
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &secret_nonces_0,
-    );
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_1,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    kick_off_timeout_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_1,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
-    kick_off_timeout_tx.add_output(reward_address.script_pubkey());
+    kick_off_timeout_tx
+        .add_output(reward_address.script_pubkey())
+        .unwrap();
 
     let tx = kick_off_timeout_tx.finalize();
     check_tx_output_sum(reward_amount, &tx);
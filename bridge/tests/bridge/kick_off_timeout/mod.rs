@@ -1 +1,2 @@
+pub mod kick_off_race;
 pub mod kick_off_timeout;
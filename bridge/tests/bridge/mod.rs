@@ -1,7 +1,9 @@
 pub mod assert;
 pub mod base;
 pub mod challenge;
+pub mod checkpoint;
 pub mod client;
+pub mod conversions;
 pub mod data_store;
 pub mod disprove;
 pub mod disprove_chain;
@@ -13,10 +15,14 @@ pub mod kick_off_1;
 pub mod kick_off_2;
 pub mod kick_off_timeout;
 pub mod peg_in;
+pub mod rewards;
+pub mod script_policies;
 pub mod serialization;
 pub mod setup;
+pub mod snapshot;
 pub mod start_time;
 pub mod start_time_timeout;
 pub mod take_1;
 pub mod take_2;
+pub mod template;
 pub mod validate;
@@ -0,0 +1,193 @@
+use std::str::FromStr;
+
+use bitcoin::hex::FromHex;
+use bridge::{
+    connectors::{base::TaprootConnector, connector_z::ConnectorZ},
+    constants::DestinationNetwork,
+    destination::{
+        destination_commitment, find_destination_chain, DestinationAddress,
+        DestinationAddressFormat,
+    },
+    error::{Error, ValidationError},
+    graphs::{peg_in::PegInGraph, template::GraphTemplate},
+};
+
+use crate::bridge::{
+    helper::{random_hex, stub_input},
+    setup::setup_test,
+};
+
+const STARKNET_CHAIN_ID: u64 = 0x534e5f4d41494e; // SN_MAIN
+
+fn is_invalid(result: Result<DestinationAddress, ValidationError>) -> bool {
+    matches!(result, Err(ValidationError::InvalidDestinationAddress(_)))
+}
+
+#[test]
+fn test_parse_destination_address() {
+    let evm_address = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+    let destination = DestinationAddress::from_str(&format!("1:{evm_address}")).unwrap();
+    assert_eq!(destination.chain_id, 1);
+    assert_eq!(
+        destination.chain().address_format,
+        DestinationAddressFormat::Evm
+    );
+    assert_eq!(destination.to_string(), format!("1:{evm_address}"));
+    assert_eq!(
+        destination.payload(),
+        [
+            1u64.to_be_bytes().to_vec(),
+            Vec::<u8>::from_hex(&evm_address[2..]).unwrap()
+        ]
+        .concat()
+    );
+    assert_eq!(
+        DestinationAddress::from_payload(&destination.payload()).unwrap(),
+        destination
+    );
+
+    // Starknet addresses are felts, leading zeros may be left out
+    let destination = DestinationAddress::from_str(&format!("{STARKNET_CHAIN_ID}:0x49d3")).unwrap();
+    assert_eq!(destination.address.len(), 32);
+    assert_eq!(destination.address[30..], [0x49, 0xd3]);
+    assert_eq!(
+        DestinationAddress::from_payload(&destination.payload()).unwrap(),
+        destination
+    );
+    assert!(is_invalid(DestinationAddress::from_str(&format!(
+        "{STARKNET_CHAIN_ID}:0x{}",
+        "f".repeat(64)
+    ))));
+    assert!(is_invalid(DestinationAddress::new(
+        STARKNET_CHAIN_ID,
+        vec![0u8; 20]
+    )));
+
+    // EVM addresses have exactly 20 bytes
+    assert!(is_invalid(DestinationAddress::from_str("1:0x49d3")));
+    assert!(is_invalid(DestinationAddress::from_str(&format!(
+        "1:{}",
+        &evm_address[2..]
+    ))));
+    assert!(is_invalid(DestinationAddress::from_str(&format!(
+        "7:{evm_address}"
+    ))));
+    assert!(is_invalid(DestinationAddress::from_payload(&[0u8; 4])));
+
+    for network in [
+        DestinationNetwork::Ethereum,
+        DestinationNetwork::EthereumSepolia,
+        DestinationNetwork::Local,
+    ] {
+        assert_eq!(network.destination_chain().chain_id, network.chain_id());
+        assert_eq!(network.destination_chain().name, network.to_string());
+    }
+    assert!(find_destination_chain(STARKNET_CHAIN_ID).is_some());
+}
+
+#[tokio::test]
+async fn test_connector_z_commits_tagged_destination() {
+    let config = setup_test().await;
+    let context = &config.depositor_context;
+
+    // Untagged EVM addresses keep the layout of existing graphs
+    assert_eq!(
+        destination_commitment(&config.depositor_evm_address).unwrap(),
+        config.depositor_evm_address.as_bytes()
+    );
+    let legacy_connector_z = ConnectorZ::new(
+        context.network,
+        &config.depositor_evm_address,
+        &context.depositor_taproot_public_key,
+        &context.n_of_n_taproot_public_key,
+    )
+    .unwrap();
+    assert_eq!(
+        legacy_connector_z.generate_taproot_address(),
+        config.connector_z.generate_taproot_address()
+    );
+
+    let starknet_address = format!("{STARKNET_CHAIN_ID}:0x{}", "07".repeat(31));
+    let payload = DestinationAddress::from_str(&starknet_address)
+        .unwrap()
+        .payload();
+    let connector_z = ConnectorZ::new(
+        context.network,
+        &starknet_address,
+        &context.depositor_taproot_public_key,
+        &context.n_of_n_taproot_public_key,
+    )
+    .unwrap();
+    let leaf_script = connector_z.generate_taproot_leaf_script(1);
+    assert!(leaf_script
+        .as_bytes()
+        .windows(payload.len())
+        .any(|window| window == payload));
+    assert_ne!(
+        connector_z.generate_taproot_address(),
+        legacy_connector_z.generate_taproot_address()
+    );
+
+    let peg_in_graph = PegInGraph::new(
+        context,
+        stub_input(&random_hex(32)),
+        &starknet_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    assert_eq!(peg_in_graph.depositor_evm_address, starknet_address);
+}
+
+#[tokio::test]
+async fn test_invalid_destination_is_a_validation_error() {
+    let config = setup_test().await;
+    let context = &config.depositor_context;
+
+    // Untagged destinations must be 20 byte EVM addresses
+    for destination in [
+        "0x49d3",
+        "70997970c51812dc3a010c7d01b50e0d17dc79c8",
+        "depositor",
+    ] {
+        assert!(matches!(
+            destination_commitment(destination),
+            Err(ValidationError::InvalidDestinationAddress(_))
+        ));
+        assert!(matches!(
+            ConnectorZ::new(
+                context.network,
+                destination,
+                &context.depositor_taproot_public_key,
+                &context.n_of_n_taproot_public_key,
+            ),
+            Err(ValidationError::InvalidDestinationAddress(_))
+        ));
+        assert!(matches!(
+            PegInGraph::new(
+                context,
+                stub_input(&random_hex(32)),
+                destination,
+                GraphTemplate::FastRegtest
+            ),
+            Err(Error::Validation(
+                ValidationError::InvalidDestinationAddress(_)
+            ))
+        ));
+    }
+
+    // A graph received with a destination connector Z cannot commit to fails validation
+    let mut peg_in_graph = PegInGraph::new(
+        context,
+        stub_input(&random_hex(32)),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    peg_in_graph.depositor_evm_address = String::from("0x49d3");
+    assert!(matches!(
+        peg_in_graph.validate(),
+        Err(Error::Validation(
+            ValidationError::InvalidDestinationAddress(_)
+        ))
+    ));
+}
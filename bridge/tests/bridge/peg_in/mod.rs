@@ -1,3 +1,5 @@
+pub mod destination_address;
 pub mod peg_in_confirm;
 pub mod peg_in_deposit;
 pub mod peg_in_refund;
+pub mod served_graph_verification;
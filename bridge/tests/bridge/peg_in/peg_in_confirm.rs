@@ -2,6 +2,7 @@ use bitcoin::{consensus::encode::serialize_hex, Amount};
 
 use bridge::{
     connectors::base::TaprootConnector,
+    graphs::base::MIN_RELAY_FEE_RATE,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_CONFIRM},
         peg_in_confirm::PegInConfirmTransaction,
@@ -38,21 +39,26 @@ async fn test_peg_in_confirm_tx_success() {
         &config.connector_0,
         &config.connector_z,
         Input { outpoint, amount },
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
 
-    peg_in_confirm_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_z,
-        &secret_nonces_0,
-    );
-    peg_in_confirm_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_z,
-        &secret_nonces_1,
-    );
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let tx = peg_in_confirm_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
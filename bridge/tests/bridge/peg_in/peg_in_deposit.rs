@@ -1,7 +1,10 @@
 use bitcoin::Amount;
 
 use bridge::{
-    scripts::generate_pay_to_pubkey_script_address,
+    graphs::base::MIN_RELAY_FEE_RATE,
+    scripts::{
+        generate_p2tr_key_path_address, generate_pay_to_pubkey_script_address, DepositorAddressType,
+    },
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
         peg_in_deposit::PegInDepositTransaction,
@@ -31,7 +34,10 @@ async fn test_peg_in_deposit_tx_success() {
         &config.depositor_context,
         &config.connector_z,
         Input { outpoint, amount },
-    );
+        DepositorAddressType::P2wsh,
+        MIN_RELAY_FEE_RATE,
+    )
+    .unwrap();
 
     println!(
         "Depositor public key: {:?}\n",
@@ -45,3 +51,32 @@ async fn test_peg_in_deposit_tx_success() {
     println!("Peg in deposit tx result: {:?}\n", result);
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_peg_in_deposit_tx_from_taproot_address_success() {
+    let config = setup_test().await;
+    let faucet = Faucet::new(FaucetType::EsploraRegtest);
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT);
+    let address = generate_p2tr_key_path_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_taproot_public_key,
+    );
+    faucet.fund_input(&address, amount).await.wait().await;
+    let outpoint = generate_stub_outpoint(&config.client_0, &address, amount).await;
+
+    let peg_in_deposit_tx = PegInDepositTransaction::new(
+        &config.depositor_context,
+        &config.connector_z,
+        Input { outpoint, amount },
+        DepositorAddressType::P2tr,
+        MIN_RELAY_FEE_RATE,
+    )
+    .unwrap();
+
+    let tx = peg_in_deposit_tx.finalize();
+    check_tx_output_sum(INITIAL_AMOUNT, &tx);
+    let result = config.client_0.esplora.broadcast(&tx).await;
+    println!("Peg in deposit tx result: {:?}\n", result);
+    assert!(result.is_ok());
+}
@@ -2,6 +2,7 @@ use bitcoin::Amount;
 
 use bridge::{
     connectors::base::TaprootConnector,
+    graphs::base::MIN_RELAY_FEE_RATE,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_REFUND},
         peg_in_refund::PegInRefundTransaction,
@@ -36,6 +37,7 @@ async fn test_peg_in_refund_tx_success() {
         &config.depositor_context,
         &config.connector_z,
         Input { outpoint, amount },
+        MIN_RELAY_FEE_RATE,
     );
 
     let tx = peg_in_refund_tx.finalize();
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use bitcoin::Address;
+use bridge::{
+    error::ValidationError,
+    graphs::{
+        base::BaseGraph, peg_in::PegInGraph, peg_in_verification::PegInCommitteeParameters,
+        template::GraphTemplate,
+    },
+    serialization::serialize,
+    transactions::pre_signed::PreSignedTransaction,
+};
+use serde_json::Value;
+
+use crate::bridge::{
+    helper::{random_hex, stub_input},
+    setup::setup_test,
+};
+
+async fn served_peg_in() -> (PegInCommitteeParameters, PegInGraph) {
+    let config = setup_test().await;
+    let context = &config.depositor_context;
+    let parameters = PegInCommitteeParameters {
+        network: context.network,
+        depositor_public_key: context.depositor_public_key,
+        n_of_n_public_keys: context.n_of_n_public_keys.clone(),
+        depositor_evm_address: config.depositor_evm_address.clone(),
+    };
+    let peg_in_graph = PegInGraph::new(
+        context,
+        stub_input(&random_hex(32)),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+
+    (parameters, peg_in_graph)
+}
+
+fn is_graph_mismatch<T>(result: Result<T, ValidationError>, field: &str) -> bool {
+    matches!(result, Err(ValidationError::PegInGraphMismatch(mismatch)) if mismatch == field)
+}
+
+#[tokio::test]
+async fn test_verify_served_peg_in_address() {
+    let (parameters, peg_in_graph) = served_peg_in().await;
+    let addresses = parameters.addresses().unwrap();
+    let script_pubkey = |address: &str| {
+        Address::from_str(address)
+            .unwrap()
+            .assume_checked()
+            .script_pubkey()
+    };
+    assert_eq!(
+        script_pubkey(&addresses.deposit),
+        peg_in_graph.peg_in_deposit_transaction.tx().output[0].script_pubkey
+    );
+    assert_eq!(
+        script_pubkey(&addresses.confirm),
+        peg_in_graph.peg_in_confirm_transaction.tx().output[0].script_pubkey
+    );
+    assert!(parameters
+        .verify_deposit_address(&addresses.deposit)
+        .is_ok());
+
+    // The confirm address is not where the depositor sends funds to
+    assert!(matches!(
+        parameters.verify_deposit_address(&addresses.confirm),
+        Err(ValidationError::PegInAddressMismatch { .. })
+    ));
+    assert!(matches!(
+        parameters.verify_deposit_address("not an address"),
+        Err(ValidationError::PegInAddressMismatch { .. })
+    ));
+
+    // A depositor with the wrong committee derives another address
+    let mut reordered = parameters.clone();
+    reordered.n_of_n_public_keys.reverse();
+    assert!(reordered
+        .verify_deposit_address(&addresses.deposit)
+        .is_err());
+
+    let mut no_committee = parameters.clone();
+    no_committee.n_of_n_public_keys.clear();
+    assert!(matches!(
+        no_committee.addresses(),
+        Err(ValidationError::InvalidGraphParameters(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_verify_served_peg_in_graph() {
+    let (parameters, peg_in_graph) = served_peg_in().await;
+    let graph_json = serialize(&peg_in_graph);
+
+    let verified = parameters.verify_peg_in_graph(&graph_json).unwrap();
+    assert!(verified.graph_id == *peg_in_graph.id());
+    assert_eq!(verified.addresses, parameters.addresses().unwrap());
+    assert_eq!(
+        verified.amount,
+        peg_in_graph.peg_in_deposit_transaction.tx().output[0].value
+    );
+
+    let mut other_destination = parameters.clone();
+    other_destination.depositor_evm_address =
+        String::from("0x0000000000000000000000000000000000000001");
+    assert!(is_graph_mismatch(
+        other_destination.verify_peg_in_graph(&graph_json),
+        "depositor_evm_address"
+    ));
+
+    let mut reordered = parameters.clone();
+    reordered.n_of_n_public_keys.reverse();
+    assert!(is_graph_mismatch(
+        reordered.verify_peg_in_graph(&graph_json),
+        "n_of_n_public_keys"
+    ));
+
+    // A graph whose id does not follow from its deposit
+    let mut tampered: Value = serde_json::from_str(&graph_json).unwrap();
+    tampered["id"] = Value::String("0".repeat(64));
+    assert!(is_graph_mismatch(
+        parameters.verify_peg_in_graph(&tampered.to_string()),
+        "id"
+    ));
+
+    assert!(matches!(
+        parameters.verify_peg_in_graph("{}"),
+        Err(ValidationError::MalformedGraph(_))
+    ));
+}
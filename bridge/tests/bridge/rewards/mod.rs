@@ -0,0 +1 @@
+pub mod reward_destination;
@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    bip32::{Xpriv, Xpub},
+    consensus::encode::deserialize_hex,
+    opcodes::all::OP_RETURN,
+    script::Builder,
+    secp256k1::Secp256k1,
+    Address, Amount, Network, OutPoint, ScriptBuf, Transaction, Txid,
+};
+use bridge::{
+    error::ValidationError,
+    graphs::{
+        base::PEG_IN_FEE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    rewards::{default_reward_script, parse_reward_destination, validate_reward_script},
+    serialization::{deserialize, serialize},
+    transactions::{
+        base::{Input, MIN_RELAY_FEE_KICK_OFF_TIMEOUT},
+        kick_off_timeout::KickOffTimeoutTransaction,
+    },
+};
+use serde_json::Value;
+
+use crate::bridge::{
+    helper::random_hex,
+    setup::{setup_test, SetupConfig, INITIAL_AMOUNT},
+};
+
+fn input(amount: Amount) -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(&random_hex(32)).unwrap(),
+            vout: 0,
+        },
+        amount,
+    }
+}
+
+// 2-of-2 of the operator and verifier keys, as a custody setup would use
+fn multisig_descriptor(config: &SetupConfig) -> String {
+    format!(
+        "wsh(multi(2,{},{}))",
+        config.operator_context.operator_public_key, config.verifier_0_context.verifier_public_key
+    )
+}
+
+fn take_output_script(graph_json: &Value, transaction: &str) -> ScriptBuf {
+    let tx: Transaction = deserialize_hex(graph_json[transaction]["tx"].as_str().unwrap()).unwrap();
+    tx.output[0].script_pubkey.clone()
+}
+
+#[tokio::test]
+async fn test_parse_reward_destination() {
+    let config = setup_test().await;
+    let network = config.operator_context.network;
+
+    let default_script =
+        default_reward_script(network, &config.operator_context.operator_public_key);
+    let address = Address::from_script(&default_script, network).unwrap();
+    assert_eq!(
+        parse_reward_destination(network, &address.to_string()).unwrap(),
+        default_script
+    );
+    assert_eq!(
+        parse_reward_destination(network, &default_script.to_hex_string()).unwrap(),
+        default_script
+    );
+
+    let multisig_script = parse_reward_destination(network, &multisig_descriptor(&config)).unwrap();
+    assert!(multisig_script.is_p2wsh());
+    assert_ne!(multisig_script, default_script);
+
+    // Mainnet addresses are rejected on test networks
+    let mainnet_address = Address::from_script(&default_script, Network::Bitcoin).unwrap();
+    assert!(matches!(
+        parse_reward_destination(network, &mainnet_address.to_string()),
+        Err(ValidationError::InvalidRewardDestination(_))
+    ));
+
+    // A descriptor with a wildcard describes many scripts
+    let xpub = Xpub::from_priv(
+        &Secp256k1::new(),
+        &Xpriv::new_master(network, &[1u8; 32]).unwrap(),
+    );
+    assert!(matches!(
+        parse_reward_destination(network, &format!("wpkh({xpub}/0/*)")),
+        Err(ValidationError::InvalidRewardDestination(_))
+    ));
+    assert!(parse_reward_destination(network, &format!("wpkh({xpub}/0/7)")).is_ok());
+
+    let op_return = Builder::new().push_opcode(OP_RETURN).into_script();
+    assert!(matches!(
+        parse_reward_destination(network, &op_return.to_hex_string()),
+        Err(ValidationError::NonStandardRewardScript(_))
+    ));
+    assert!(matches!(
+        parse_reward_destination(network, "not a destination"),
+        Err(ValidationError::InvalidRewardDestination(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_take_transactions_pay_operator_reward_script() {
+    let config = setup_test().await;
+    let network = config.operator_context.network;
+    let reward_script = parse_reward_destination(network, &multisig_descriptor(&config)).unwrap();
+    let operator_context = config
+        .operator_context
+        .clone()
+        .with_reward_script(reward_script.clone())
+        .unwrap();
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        input(Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE)),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &operator_context,
+        &peg_in_graph,
+        input(Amount::from_sat(INITIAL_AMOUNT)),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+    assert_eq!(peg_out_graph.operator_reward_script(), reward_script);
+
+    let graph_json: Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    assert_eq!(
+        take_output_script(&graph_json, "take_1_transaction"),
+        reward_script
+    );
+    assert_eq!(
+        take_output_script(&graph_json, "take_2_transaction"),
+        reward_script
+    );
+    let validation_json: Value =
+        serde_json::from_str(&serialize(&peg_out_graph.new_for_validation())).unwrap();
+    assert_eq!(
+        validation_json["take_1_transaction"]["tx"],
+        graph_json["take_1_transaction"]["tx"]
+    );
+
+    // Graphs saved before the reward script was configurable pay to the operator key
+    let mut legacy_json = graph_json.clone();
+    legacy_json
+        .as_object_mut()
+        .unwrap()
+        .remove("operator_reward_script");
+    let legacy_graph: PegOutGraph = deserialize(&legacy_json.to_string());
+    assert_eq!(
+        legacy_graph.operator_reward_script(),
+        default_reward_script(network, &config.operator_context.operator_public_key)
+    );
+}
+
+#[tokio::test]
+async fn test_reward_output_is_validated() {
+    let config = setup_test().await;
+    let reward_script = default_reward_script(
+        config.verifier_0_context.network,
+        &config.verifier_0_context.verifier_public_key,
+    );
+    assert!(validate_reward_script(&reward_script).is_ok());
+
+    let mut kick_off_timeout = KickOffTimeoutTransaction::new(
+        &config.operator_context,
+        &config.connector_0,
+        &config.connector_1,
+        input(Amount::from_sat(INITIAL_AMOUNT)),
+        None,
+    );
+    let op_return = Builder::new().push_opcode(OP_RETURN).into_script();
+    assert!(matches!(
+        kick_off_timeout.add_output(op_return),
+        Err(ValidationError::NonStandardRewardScript(_))
+    ));
+    assert!(kick_off_timeout.add_output(reward_script.clone()).is_ok());
+
+    // 5% of 1000 sats does not cover the dust limit of the reward output
+    let mut kick_off_timeout = KickOffTimeoutTransaction::new(
+        &config.operator_context,
+        &config.connector_0,
+        &config.connector_1,
+        input(Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_TIMEOUT + 1000)),
+        None,
+    );
+    assert!(matches!(
+        kick_off_timeout.add_output(reward_script),
+        Err(ValidationError::RewardOutputBelowDust { .. })
+    ));
+}
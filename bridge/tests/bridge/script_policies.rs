@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    opcodes::all::{OP_DROP, OP_VERIFY},
+    script::Instruction,
+    secp256k1::{Secp256k1, SecretKey},
+    Network, PublicKey, Script,
+};
+use bridge::{
+    script_policies::{
+        compile_segwit_policy, generate_descriptor_address, generate_descriptor_script,
+        generate_pay_to_pubkey_descriptor, generate_pay_to_pubkey_miniscript,
+        generate_timelock_descriptor, generate_timelock_miniscript, pay_to_pubkey_policy,
+        timelock_policy,
+    },
+    scripts::{
+        generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address,
+        generate_timelock_script,
+    },
+};
+use miniscript::{policy::Liftable, Descriptor};
+use proptest::prelude::*;
+
+fn public_key() -> impl Strategy<Value = PublicKey> {
+    any::<[u8; 32]>().prop_filter_map("Invalid secret key", |bytes| {
+        SecretKey::from_slice(&bytes)
+            .ok()
+            .map(|secret_key| PublicKey::new(secret_key.public_key(&Secp256k1::new())))
+    })
+}
+
+fn num_blocks_timelock() -> impl Strategy<Value = u32> {
+    prop_oneof![Just(1u32), Just(u16::MAX as u32), 1u32..=u16::MAX as u32]
+}
+
+fn instructions(script: &Script) -> Vec<Instruction<'_>> {
+    script
+        .instructions()
+        .collect::<Result<_, _>>()
+        .expect("Script should decode")
+}
+
+proptest! {
+    #[test]
+    fn test_pay_to_pubkey_miniscript_matches_script(public_key in public_key()) {
+        let miniscript = generate_pay_to_pubkey_miniscript(&public_key);
+        prop_assert_eq!(miniscript.encode(), generate_pay_to_pubkey_script(&public_key));
+
+        let descriptor = generate_pay_to_pubkey_descriptor(&public_key);
+        prop_assert_eq!(
+            generate_descriptor_address(&descriptor, Network::Regtest),
+            generate_pay_to_pubkey_script_address(Network::Regtest, &public_key)
+        );
+    }
+
+    #[test]
+    fn test_timelock_miniscript_matches_script(
+        public_key in public_key(),
+        num_blocks_timelock in num_blocks_timelock()
+    ) {
+        let miniscript_script = generate_timelock_miniscript(&public_key, num_blocks_timelock).encode();
+        let hand_written_script = generate_timelock_script(&public_key, num_blocks_timelock);
+
+        // The only difference is how the CSV argument is removed from the stack
+        let miniscript_instructions: Vec<Instruction> = instructions(&miniscript_script)
+            .into_iter()
+            .map(|instruction| match instruction {
+                Instruction::Op(OP_VERIFY) => Instruction::Op(OP_DROP),
+                instruction => instruction,
+            })
+            .collect();
+        prop_assert_eq!(miniscript_instructions, instructions(&hand_written_script));
+    }
+
+    #[test]
+    fn test_policies_lift_to_same_semantics(
+        public_key in public_key(),
+        num_blocks_timelock in num_blocks_timelock()
+    ) {
+        let policy = pay_to_pubkey_policy(&public_key);
+        prop_assert_eq!(
+            compile_segwit_policy(&policy).lift().unwrap().normalized(),
+            policy.lift().unwrap().normalized()
+        );
+
+        let policy = timelock_policy(&public_key, num_blocks_timelock);
+        let lifted_policy = policy.lift().unwrap().normalized();
+        prop_assert_eq!(
+            compile_segwit_policy(&policy).lift().unwrap().normalized(),
+            lifted_policy.clone()
+        );
+        prop_assert_eq!(
+            generate_timelock_miniscript(&public_key, num_blocks_timelock)
+                .lift()
+                .unwrap()
+                .normalized(),
+            lifted_policy
+        );
+    }
+
+    #[test]
+    fn test_descriptors_round_trip(
+        public_key in public_key(),
+        num_blocks_timelock in num_blocks_timelock()
+    ) {
+        for descriptor in [
+            generate_pay_to_pubkey_descriptor(&public_key),
+            generate_timelock_descriptor(&public_key, num_blocks_timelock),
+        ] {
+            let parsed = Descriptor::<PublicKey>::from_str(&descriptor.to_string()).unwrap();
+            prop_assert_eq!(
+                generate_descriptor_script(&parsed),
+                generate_descriptor_script(&descriptor)
+            );
+            prop_assert_eq!(parsed, descriptor);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fs, io::ErrorKind, path::PathBuf, str::FromStr};
+
+use bitcoin::{
+    hashes::Hash,
+    taproot::{TaprootBuilder, TaprootSpendInfo},
+    ScriptBuf, TapNodeHash, XOnlyPublicKey,
+};
+use bridge::{
+    connectors::{
+        cache_files::{
+            cache_file_format, cache_header, migrate_cache_directory, read_merkle_root_file,
+            write_merkle_root_file, CacheFileFormat, CACHE_HEADER_LENGTH, MERKLE_ROOT_FILE_TAG,
+            MERKLE_ROOT_FILE_VERSION,
+        },
+        lock_scripts_file::{
+            write_lock_scripts_file, LockScriptsFile, LOCK_SCRIPTS_FILE_TAG,
+            LOCK_SCRIPTS_FILE_VERSION,
+        },
+    },
+    utils::write_disk_cache,
+};
+use secp256k1::SECP256K1;
+
+use crate::bridge::helper::empty_temp_dir;
+
+fn cache_directory(name: &str) -> PathBuf {
+    let dir = empty_temp_dir(&format!("cache_files_{name}"));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn lock_scripts() -> Vec<Vec<u8>> {
+    (0..9u8)
+        .map(|leaf| (0..100).map(|byte| byte ^ leaf).collect())
+        .collect()
+}
+
+fn spend_info(lock_scripts: &[Vec<u8>]) -> TaprootSpendInfo {
+    let internal_key = XOnlyPublicKey::from_str(
+        "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+    )
+    .unwrap();
+    TaprootBuilder::with_huffman_tree(
+        lock_scripts
+            .iter()
+            .map(|script| (1, ScriptBuf::from_bytes(script.clone()))),
+    )
+    .unwrap()
+    .finalize(SECP256K1, internal_key)
+    .unwrap()
+}
+
+#[test]
+fn test_cache_header_version() {
+    let header = cache_header(LOCK_SCRIPTS_FILE_TAG, 1);
+    // Files written before the version was a separate field keep being read
+    assert_eq!(&header, b"BVMLKS01");
+    assert_eq!(
+        cache_file_format(LOCK_SCRIPTS_FILE_TAG, &header),
+        CacheFileFormat::Version(1)
+    );
+    assert_eq!(
+        &cache_header(LOCK_SCRIPTS_FILE_TAG, LOCK_SCRIPTS_FILE_VERSION),
+        b"BVMLKS02"
+    );
+    assert_eq!(
+        cache_file_format(
+            LOCK_SCRIPTS_FILE_TAG,
+            &cache_header(LOCK_SCRIPTS_FILE_TAG, 42)
+        ),
+        CacheFileFormat::Version(42)
+    );
+    assert_eq!(
+        cache_file_format(MERKLE_ROOT_FILE_TAG, &header),
+        CacheFileFormat::Legacy
+    );
+    assert_eq!(
+        cache_file_format(LOCK_SCRIPTS_FILE_TAG, b"BVM"),
+        CacheFileFormat::Legacy
+    );
+}
+
+#[test]
+fn test_migrate_removes_stale_cache_files() {
+    let dir = cache_directory("previous");
+    let lock_scripts = lock_scripts();
+    let spend_info = spend_info(&lock_scripts);
+    let merkle_root = spend_info.merkle_root().unwrap();
+    let legacy_lock_scripts_path = dir.join("lock_scripts_legacy.bin");
+    let legacy_merkle_root_path = dir.join("merkle_root_legacy.bin");
+    write_disk_cache(&legacy_lock_scripts_path, &lock_scripts).unwrap();
+    write_disk_cache(&legacy_merkle_root_path, &merkle_root.to_byte_array()).unwrap();
+
+    // Version 1 has the current layout under a previous cache id
+    let version_1_path = dir.join("lock_scripts_version_1.bin");
+    write_lock_scripts_file(&version_1_path, &lock_scripts, &spend_info).unwrap();
+    let mut data = fs::read(&version_1_path).unwrap();
+    data[..CACHE_HEADER_LENGTH].copy_from_slice(&cache_header(LOCK_SCRIPTS_FILE_TAG, 1));
+    fs::write(&version_1_path, data).unwrap();
+
+    let current_lock_scripts_path = dir.join("lock_scripts_current.bin");
+    let current_merkle_root_path = dir.join("merkle_root_current.bin");
+    write_lock_scripts_file(&current_lock_scripts_path, &lock_scripts, &spend_info).unwrap();
+    write_merkle_root_file(&current_merkle_root_path, merkle_root).unwrap();
+
+    // Readers accept the previous formats, e.g. for importing exported lock scripts
+    assert_eq!(
+        read_merkle_root_file(&legacy_merkle_root_path).unwrap(),
+        merkle_root
+    );
+    let file = LockScriptsFile::open(&version_1_path).unwrap();
+    assert_eq!(file.lock_scripts().unwrap(), lock_scripts);
+    assert_eq!(file.merkle_root(), merkle_root);
+    drop(file);
+
+    let report = migrate_cache_directory(&dir).unwrap();
+    assert_eq!(
+        report.removed,
+        vec![
+            legacy_lock_scripts_path.clone(),
+            version_1_path.clone(),
+            legacy_merkle_root_path.clone()
+        ]
+    );
+    assert_eq!(report.up_to_date, 2);
+    assert!(report.failed.is_empty());
+    assert!(!legacy_lock_scripts_path.exists());
+    assert!(!version_1_path.exists());
+    assert!(!legacy_merkle_root_path.exists());
+
+    let file = LockScriptsFile::open(&current_lock_scripts_path).unwrap();
+    assert_eq!(file.lock_scripts().unwrap(), lock_scripts);
+    assert_eq!(
+        read_merkle_root_file(&current_merkle_root_path).unwrap(),
+        merkle_root
+    );
+    assert_eq!(
+        cache_file_format(
+            MERKLE_ROOT_FILE_TAG,
+            &fs::read(&current_merkle_root_path).unwrap()
+        ),
+        CacheFileFormat::Version(MERKLE_ROOT_FILE_VERSION)
+    );
+
+    let report = migrate_cache_directory(&dir).unwrap();
+    assert!(report.removed.is_empty());
+    assert_eq!(report.up_to_date, 2);
+}
+
+#[test]
+fn test_migrate_leaves_newer_cache_formats() {
+    let dir = cache_directory("newer");
+    let merkle_root_path = dir.join("merkle_root_test.bin");
+    let mut data = cache_header(MERKLE_ROOT_FILE_TAG, MERKLE_ROOT_FILE_VERSION + 1).to_vec();
+    data.extend(TapNodeHash::all_zeros().to_byte_array());
+    fs::write(&merkle_root_path, &data).unwrap();
+
+    assert_eq!(
+        read_merkle_root_file(&merkle_root_path).unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+    let report = migrate_cache_directory(&dir).unwrap();
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].1.kind(), ErrorKind::Unsupported);
+    assert_eq!(fs::read(&merkle_root_path).unwrap(), data);
+}
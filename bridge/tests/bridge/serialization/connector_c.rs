@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use bitvm::{
+    chunk::api::{segment_layout_version, NUM_TAPS},
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
+};
+use bridge::{
+    commitments::CommitmentMessageId,
+    connectors::{base::TaprootConnector, connector_c::ConnectorC},
+    error::{ChunkerError, Error},
+    serialization::{deserialize, serialize},
+};
+use secp256k1::SECP256K1;
+
+use crate::bridge::setup::setup_test_full;
+
+#[tokio::test]
+async fn test_connector_c_serialization() {
+    let config = setup_test_full().await;
+
+    let json = serialize(&config.connector_c);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["taproot_merkle_root"].is_string());
+
+    let deserialized = deserialize::<ConnectorC>(&json);
+    assert!(deserialized == config.connector_c);
+    assert_eq!(
+        deserialized.generate_taproot_address(),
+        config.connector_c.generate_taproot_address()
+    );
+
+    // Connectors serialized without the merkle root derive the same address from the cache
+    let mut legacy_value = value.clone();
+    legacy_value
+        .as_object_mut()
+        .unwrap()
+        .remove("taproot_merkle_root");
+    let legacy = deserialize::<ConnectorC>(&legacy_value.to_string());
+    assert_eq!(
+        legacy.taproot_merkle_root(),
+        config.connector_c.taproot_merkle_root()
+    );
+    assert_eq!(
+        legacy.generate_taproot_address(),
+        config.connector_c.generate_taproot_address()
+    );
+}
+
+#[tokio::test]
+async fn test_connector_c_lazy_lock_scripts_match_address() {
+    let config = setup_test_full().await;
+
+    // Lock scripts generated on demand commit to the output key derived from the stored root
+    let output_key = config.connector_c.taproot_output_key();
+    for leaf_index in [0, config.connector_c.taproot_scripts_len() - 1] {
+        let (script, control_block) = config
+            .connector_c
+            .taproot_script_and_control_block(leaf_index);
+        assert!(control_block.verify_taproot_commitment(SECP256K1, output_key.to_inner(), &script));
+    }
+}
+
+#[tokio::test]
+async fn test_connector_c_accessors_do_not_generate_lock_scripts() {
+    let config = setup_test_full().await;
+    assert!(config.connector_c.is_warm());
+
+    // Commitment public keys no lock scripts were ever generated for
+    let secret = WinternitzSecret::new(32);
+    let commitment_public_keys = BTreeMap::from([(
+        CommitmentMessageId::Groth16IntermediateValues(("0".to_string(), 32)),
+        WinternitzPublicKey::from(&secret),
+    )]);
+    let claimed_merkle_root = config.connector_c.claimed_taproot_merkle_root();
+    let cold = ConnectorC::new_for_validation(
+        config.network,
+        &config.operator_context.operator_taproot_public_key,
+        &commitment_public_keys,
+        claimed_merkle_root,
+    );
+
+    assert!(!cold.is_warm());
+    assert_eq!(cold.taproot_merkle_root(), claimed_merkle_root);
+    assert_eq!(cold.taproot_scripts_len(), NUM_TAPS);
+    let _ = cold.generate_taproot_address();
+    assert!(!cold.is_warm());
+
+    // Without a stored or cached merkle root the address is only available after a warm-up
+    let mut legacy_value: serde_json::Value = serde_json::from_str(&serialize(&cold)).unwrap();
+    legacy_value
+        .as_object_mut()
+        .unwrap()
+        .remove("taproot_merkle_root");
+    let legacy = deserialize::<ConnectorC>(&legacy_value.to_string());
+    assert_eq!(legacy.taproot_merkle_root(), None);
+}
+
+#[tokio::test]
+async fn test_connector_c_segment_layout_version() {
+    let config = setup_test_full().await;
+    assert_eq!(
+        config.connector_c.segment_layout_version(),
+        Some(segment_layout_version().as_str())
+    );
+    assert!(config.connector_c.check_segment_layout().is_ok());
+
+    let json = serialize(&config.connector_c);
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    // Connectors serialized before the layout was recorded are assumed to match
+    value
+        .as_object_mut()
+        .unwrap()
+        .remove("segment_layout_version");
+    let legacy = deserialize::<ConnectorC>(&value.to_string());
+    assert_eq!(legacy.segment_layout_version(), None);
+    assert!(legacy.check_segment_layout().is_ok());
+
+    // A connector generated by a chunker with another layout is refused before anything is
+    // evaluated against it
+    value["segment_layout_version"] = serde_json::Value::from("0-00000000");
+    let incompatible = deserialize::<ConnectorC>(&value.to_string());
+    let err = incompatible.check_segment_layout().unwrap_err();
+    assert!(matches!(
+        &err,
+        ChunkerError::SegmentLayoutMismatch { expected, found }
+            if *expected == segment_layout_version() && found == "0-00000000"
+    ));
+    assert!(Error::Chunker(err).to_string().contains("0-00000000"));
+}
@@ -0,0 +1,331 @@
+use std::{collections::BTreeMap, fs, str::FromStr};
+
+use bitcoin::{Network, PublicKey, TapNodeHash, XOnlyPublicKey};
+use bitvm::signatures::{signing_winternitz::WinternitzPublicKey, winternitz::Parameters};
+use bridge::{
+    commitments::CommitmentMessageId,
+    connectors::{
+        connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
+        connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
+        connector_6::Connector6, connector_a::ConnectorA, connector_b::ConnectorB,
+        connector_c::ConnectorC, connector_d::ConnectorD, connector_e::ConnectorE,
+        connector_f_1::ConnectorF1, connector_f_2::ConnectorF2, connector_z::ConnectorZ,
+    },
+    destination::destination_commitment,
+    serialization::{deserialize, serialize},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::bridge::snapshot::{recorded_snapshot, snapshot_path, UPDATE_SNAPSHOTS_VAR};
+
+// Connectors are stored with every graph, so a change to their serialized form breaks loading
+// graphs written by earlier releases. Each connector is serialized under fixed keys and compared
+// against a committed fixture, see `recorded_snapshot`. Fixtures of earlier formats under `legacy`
+// are never rewritten.
+const FIXTURE_DIR: &str = "tests/fixtures/connectors";
+const LEGACY_FIXTURE_DIR: &str = "tests/fixtures/connectors/legacy";
+
+const NETWORK: Network = Network::Regtest;
+const N_OF_N_TAPROOT_PUBLIC_KEY: &str =
+    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const OPERATOR_TAPROOT_PUBLIC_KEY: &str =
+    "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+const OPERATOR_PUBLIC_KEY: &str =
+    "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+const EVM_ADDRESS: &str = "0x0000000000000000000000000000000000000001";
+const DESTINATION: &str = "1:0x0000000000000000000000000000000000000001";
+const SEGMENT_LAYOUT_VERSION: &str = "golden-fixture-layout";
+
+fn n_of_n_taproot_public_key() -> XOnlyPublicKey {
+    XOnlyPublicKey::from_str(N_OF_N_TAPROOT_PUBLIC_KEY).unwrap()
+}
+
+fn operator_taproot_public_key() -> XOnlyPublicKey {
+    XOnlyPublicKey::from_str(OPERATOR_TAPROOT_PUBLIC_KEY).unwrap()
+}
+
+fn operator_public_key() -> PublicKey {
+    PublicKey::from_str(OPERATOR_PUBLIC_KEY).unwrap()
+}
+
+fn commitment_public_keys(
+    ids: impl IntoIterator<Item = CommitmentMessageId>,
+) -> BTreeMap<CommitmentMessageId, WinternitzPublicKey> {
+    let public_key = WinternitzPublicKey {
+        public_key: vec![[1; 20], [2; 20], [3; 20]],
+        parameters: Parameters::new(1, 4),
+    };
+    ids.into_iter().map(|id| (id, public_key.clone())).collect()
+}
+
+fn intermediate_value_id() -> CommitmentMessageId {
+    CommitmentMessageId::Groth16IntermediateValues((String::from("F_10"), 32))
+}
+
+fn read_legacy_fixture(name: &str) -> String {
+    let path = snapshot_path(&format!("{LEGACY_FIXTURE_DIR}/{name}.json"));
+    fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read fixture {}: {e}", path.display()))
+}
+
+// Returns the fixture the serialized connector was compared against
+fn assert_golden_json(name: &str, actual: &Value) -> String {
+    let path = snapshot_path(&format!("{FIXTURE_DIR}/{name}.json"));
+    let fixture = recorded_snapshot(
+        &path,
+        &(serde_json::to_string_pretty(actual).unwrap() + "\n"),
+    );
+    let expected: Value = serde_json::from_str(&fixture).unwrap();
+    assert_eq!(
+        *actual,
+        expected,
+        "{name} serializes differently from {}. Run with {UPDATE_SNAPSHOTS_VAR}=1 if the change is intended and graphs of earlier releases still load.",
+        path.display()
+    );
+
+    fixture
+}
+
+fn assert_golden<T: Serialize + DeserializeOwned + PartialEq>(name: &str, connector: &T) {
+    let actual: Value = serde_json::from_str(&serialize(connector)).unwrap();
+    let fixture = assert_golden_json(name, &actual);
+    assert!(
+        deserialize::<T>(&fixture) == *connector,
+        "{name} fixture does not deserialize to the connector"
+    );
+}
+
+#[test]
+fn test_connector_0_golden() {
+    assert_golden(
+        "connector_0",
+        &Connector0 {
+            network: NETWORK,
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+        },
+    );
+}
+
+#[test]
+fn test_connector_1_golden() {
+    assert_golden(
+        "connector_1",
+        &Connector1 {
+            network: NETWORK,
+            operator_taproot_public_key: operator_taproot_public_key(),
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+            commitment_public_keys: commitment_public_keys([
+                CommitmentMessageId::StartTime,
+                CommitmentMessageId::SuperblockHash,
+            ]),
+            num_blocks_timelock_leaf_0: 6,
+            num_blocks_timelock_leaf_1: 12,
+            num_blocks_timelock_leaf_2: 18,
+        },
+    );
+}
+
+#[test]
+fn test_connector_2_golden() {
+    assert_golden(
+        "connector_2",
+        &Connector2 {
+            network: NETWORK,
+            operator_taproot_public_key: operator_taproot_public_key(),
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+            commitment_public_keys: commitment_public_keys([CommitmentMessageId::StartTime]),
+        },
+    );
+}
+
+#[test]
+fn test_connector_3_golden() {
+    assert_golden(
+        "connector_3",
+        &Connector3 {
+            network: NETWORK,
+            operator_public_key: operator_public_key(),
+            num_blocks_timelock: 6,
+        },
+    );
+}
+
+#[test]
+fn test_connector_4_golden() {
+    assert_golden(
+        "connector_4",
+        &Connector4 {
+            network: NETWORK,
+            operator_public_key: operator_public_key(),
+            num_blocks_timelock: 12,
+        },
+    );
+}
+
+#[test]
+fn test_connector_5_golden() {
+    assert_golden(
+        "connector_5",
+        &Connector5 {
+            network: NETWORK,
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+        },
+    );
+}
+
+#[test]
+fn test_connector_6_golden() {
+    let connector_6 = Connector6 {
+        network: NETWORK,
+        operator_taproot_public_key: operator_taproot_public_key(),
+        commitment_public_keys: commitment_public_keys([
+            CommitmentMessageId::PegOutTxIdSourceNetwork,
+            CommitmentMessageId::PegOutTxIdDestinationNetwork,
+            CommitmentMessageId::DestinationChainId,
+            CommitmentMessageId::WithdrawalNonce,
+        ]),
+    };
+    assert!(connector_6.commits_destination_metadata());
+    assert_golden("connector_6", &connector_6);
+}
+
+#[test]
+fn test_connector_a_golden() {
+    assert_golden(
+        "connector_a",
+        &ConnectorA {
+            network: NETWORK,
+            operator_taproot_public_key: operator_taproot_public_key(),
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+        },
+    );
+}
+
+#[test]
+fn test_connector_b_golden() {
+    assert_golden(
+        "connector_b",
+        &ConnectorB {
+            network: NETWORK,
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+            commitment_public_keys: commitment_public_keys([CommitmentMessageId::Superblock]),
+            num_blocks_timelock_1: 6,
+        },
+    );
+}
+
+#[test]
+fn test_connector_c_golden() {
+    // A claimed merkle root spares generating the lock scripts, see `new_for_validation`
+    let connector_c = ConnectorC::new_for_validation(
+        NETWORK,
+        &operator_taproot_public_key(),
+        &commitment_public_keys([intermediate_value_id()]),
+        Some(TapNodeHash::from_str(&"07".repeat(32)).unwrap()),
+    );
+
+    // The segment layout follows the chunker version rather than the serialized form
+    let mut actual: Value = serde_json::from_str(&serialize(&connector_c)).unwrap();
+    actual["segment_layout_version"] = Value::from(SEGMENT_LAYOUT_VERSION);
+    let fixture = assert_golden_json("connector_c", &actual);
+
+    let loaded = deserialize::<ConnectorC>(&fixture);
+    assert_eq!(
+        loaded.segment_layout_version(),
+        Some(SEGMENT_LAYOUT_VERSION)
+    );
+    assert_eq!(
+        serde_json::from_str::<Value>(&serialize(&loaded)).unwrap(),
+        actual
+    );
+}
+
+#[test]
+fn test_connector_d_golden() {
+    assert_golden(
+        "connector_d",
+        &ConnectorD {
+            network: NETWORK,
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+        },
+    );
+}
+
+#[test]
+fn test_connector_e_golden() {
+    assert_golden(
+        "connector_e",
+        &ConnectorE {
+            network: NETWORK,
+            operator_public_key: operator_public_key(),
+            commitment_public_keys: commitment_public_keys([intermediate_value_id()]),
+        },
+    );
+}
+
+#[test]
+fn test_connector_f_golden() {
+    assert_golden(
+        "connector_f_1",
+        &ConnectorF1 {
+            network: NETWORK,
+            operator_public_key: operator_public_key(),
+        },
+    );
+    assert_golden(
+        "connector_f_2",
+        &ConnectorF2 {
+            network: NETWORK,
+            operator_public_key: operator_public_key(),
+        },
+    );
+}
+
+#[test]
+fn test_connector_z_golden() {
+    assert_golden(
+        "connector_z",
+        &ConnectorZ {
+            network: NETWORK,
+            depositor_taproot_public_key: operator_taproot_public_key(),
+            n_of_n_taproot_public_key: n_of_n_taproot_public_key(),
+            evm_address: String::from(DESTINATION),
+            num_blocks_timelock_0: 6,
+        },
+    );
+}
+
+#[test]
+fn test_legacy_connector_c_fixture() {
+    // Written before the merkle root and the segment layout were recorded
+    let connector_c = deserialize::<ConnectorC>(&read_legacy_fixture("connector_c"));
+    assert!(connector_c.claimed_taproot_merkle_root().is_none());
+    assert!(connector_c.segment_layout_version().is_none());
+    assert!(connector_c.check_segment_layout().is_ok());
+}
+
+#[test]
+fn test_legacy_connector_6_fixture() {
+    // Written before graphs could commit destination chain metadata
+    let connector_6 = deserialize::<Connector6>(&read_legacy_fixture("connector_6"));
+    assert!(!connector_6.commits_destination_metadata());
+    assert!(
+        connector_6.commitment_public_keys
+            == commitment_public_keys([
+                CommitmentMessageId::PegOutTxIdSourceNetwork,
+                CommitmentMessageId::PegOutTxIdDestinationNetwork,
+            ])
+    );
+}
+
+#[test]
+fn test_legacy_connector_z_fixture() {
+    // Written before destinations were tagged with their chain id
+    let connector_z = deserialize::<ConnectorZ>(&read_legacy_fixture("connector_z"));
+    assert_eq!(connector_z.evm_address, EVM_ADDRESS);
+    assert_eq!(
+        destination_commitment(&connector_z.evm_address).unwrap(),
+        EVM_ADDRESS.as_bytes()
+    );
+}
@@ -0,0 +1,85 @@
+use bridge::{
+    graphs::{
+        base::BaseGraph,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+};
+
+use crate::bridge::{
+    helper::stub_input,
+    setup::{setup_test, SetupConfig},
+};
+
+// Every deserialization builds fresh hash maps with their own random iteration order
+const SERIALIZATION_ROUNDS: usize = 8;
+
+fn graphs(config: &SetupConfig) -> (PegInGraph, PegOutGraph) {
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    (peg_in_graph, peg_out_graph)
+}
+
+fn assert_stable_serialization<T>(object: &T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serialize(object);
+    for _ in 0..SERIALIZATION_ROUNDS {
+        assert_eq!(serialize(&deserialize::<T>(&json)), json);
+    }
+}
+
+#[tokio::test]
+async fn test_graph_serialization_is_byte_identical_across_deserializations() {
+    let config = setup_test().await;
+    let (mut peg_in_graph, mut peg_out_graph) = graphs(&config);
+
+    // Nonces of several verifiers fill the per-input maps of every pre-signed transaction
+    for verifier_context in [&config.verifier_0_context, &config.verifier_1_context] {
+        peg_in_graph.push_verifier_nonces(verifier_context);
+        peg_out_graph.push_verifier_nonces(verifier_context);
+    }
+
+    assert_stable_serialization(&peg_in_graph);
+    assert_stable_serialization(&peg_out_graph);
+}
+
+#[tokio::test]
+async fn test_musig2_nonces_are_serialized_in_public_key_order() {
+    let config = setup_test().await;
+    let (_, mut peg_out_graph) = graphs(&config);
+
+    // Pushed out of order, the serialized graph still lists the verifiers by public key
+    let mut verifiers = [
+        config.verifier_0_context.verifier_public_key.to_string(),
+        config.verifier_1_context.verifier_public_key.to_string(),
+    ];
+    peg_out_graph.push_verifier_nonces(&config.verifier_1_context);
+    peg_out_graph.push_verifier_nonces(&config.verifier_0_context);
+    let json = serialize(&peg_out_graph);
+    let nonces = &json[json.find("\"musig2_nonces\"").unwrap()..];
+
+    verifiers.sort();
+    let first_positions = verifiers
+        .iter()
+        .map(|verifier| nonces.find(verifier.as_str()).unwrap())
+        .collect::<Vec<_>>();
+    assert!(first_positions[0] < first_positions[1]);
+}
@@ -0,0 +1,241 @@
+use std::{collections::HashMap, str::FromStr};
+
+use bitcoin::{
+    absolute, key::TapTweak, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf,
+    Sequence, Transaction, TxIn, TxOut, Txid, Witness, XOnlyPublicKey,
+};
+use bridge::{
+    connectors::{
+        connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
+        connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
+        connector_6::Connector6, connector_a::ConnectorA, connector_b::ConnectorB,
+        connector_d::ConnectorD, connector_z::ConnectorZ,
+    },
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+    transactions::{
+        base::Input, pre_signed_musig2::get_nonce_message, signing_musig2::generate_nonce,
+    },
+};
+use musig2::{secp256k1::schnorr::Signature, PubNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::bridge::{
+    helper::{seeded_keypair, seeded_public_key},
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+fn x_only_public_key(seed: u8) -> XOnlyPublicKey {
+    seeded_keypair(seed).x_only_public_key().0
+}
+
+fn stub_txid() -> Txid {
+    Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327").unwrap()
+}
+
+fn stub_transaction() -> Transaction {
+    Transaction {
+        version: Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: stub_txid(),
+                vout: 1,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::from_slice(&[vec![1u8; 64], vec![]]),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(INITIAL_AMOUNT),
+            script_pubkey: ScriptBuf::new_p2tr_tweaked(
+                x_only_public_key(1).dangerous_assume_tweaked(),
+            ),
+        }],
+    }
+}
+
+fn nonces_by_input(num_inputs: usize) -> HashMap<usize, HashMap<PublicKey, PubNonce>> {
+    (0..num_inputs)
+        .map(|input_index| {
+            let nonces = (1..=3)
+                .map(|seed| (seeded_public_key(seed), generate_nonce().public_nonce()))
+                .collect();
+            (input_index, nonces)
+        })
+        .collect()
+}
+
+fn nonce_signatures_by_input(num_inputs: usize) -> HashMap<usize, HashMap<PublicKey, Signature>> {
+    (0..num_inputs)
+        .map(|input_index| {
+            let signatures = (1..=3)
+                .map(|seed| {
+                    let nonce = generate_nonce().public_nonce();
+                    let signature = seeded_keypair(seed).sign_schnorr(get_nonce_message(&nonce));
+                    (seeded_public_key(seed), signature)
+                })
+                .collect();
+            (input_index, signatures)
+        })
+        .collect()
+}
+
+// Round trips a value through a serde helper as a field would. Helpers replacing the serde
+// implementation of a type must keep its JSON, so previously saved graphs still load.
+macro_rules! round_trip_tests {
+    ($($name:ident: $type:ty, $with:literal, $value:expr, legacy_json: $legacy:literal;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                #[derive(Serialize, Deserialize, PartialEq, Debug)]
+                struct Field(#[serde(with = $with)] $type);
+
+                let value: $type = $value;
+                let json = serialize(&Field(value.clone()));
+                assert_eq!(deserialize::<Field>(&json), Field(value.clone()));
+                if $legacy {
+                    assert_eq!(
+                        serde_json::from_str::<Value>(&json).unwrap(),
+                        serde_json::to_value(&value).unwrap()
+                    );
+                }
+            }
+        )*
+    };
+}
+
+round_trip_tests! {
+    test_empty_script: ScriptBuf, "bridge::serialization::hex_encoded",
+        ScriptBuf::new(), legacy_json: true;
+    test_script: ScriptBuf, "bridge::serialization::hex_encoded",
+        ScriptBuf::new_p2tr_tweaked(x_only_public_key(1).dangerous_assume_tweaked()),
+        legacy_json: true;
+    test_scripts: Vec<ScriptBuf>, "bridge::serialization::hex_encoded_vec",
+        vec![ScriptBuf::new(), ScriptBuf::from_bytes(vec![0x6a, 0x01, 0x07])], legacy_json: true;
+    test_no_scripts: Vec<ScriptBuf>, "bridge::serialization::hex_encoded_vec",
+        vec![], legacy_json: true;
+    test_public_key: PublicKey, "bridge::serialization::hex_encoded",
+        seeded_public_key(1), legacy_json: true;
+    test_public_keys: Vec<PublicKey>, "bridge::serialization::hex_encoded_vec",
+        vec![seeded_public_key(1), seeded_public_key(2)], legacy_json: true;
+    test_x_only_public_key: XOnlyPublicKey, "bridge::serialization::hex_encoded",
+        x_only_public_key(1), legacy_json: true;
+    test_witness: Option<Witness>, "bridge::serialization::hex_encoded_option",
+        Some(Witness::from_slice(&[vec![0u8; 73], vec![], vec![1u8]])), legacy_json: true;
+    test_empty_witness: Option<Witness>, "bridge::serialization::hex_encoded_option",
+        Some(Witness::new()), legacy_json: true;
+    test_no_witness: Option<Witness>, "bridge::serialization::hex_encoded_option",
+        None, legacy_json: true;
+    test_transaction: Transaction, "bridge::serialization::consensus_hex",
+        stub_transaction(), legacy_json: false;
+    test_prev_outs: Vec<TxOut>, "bridge::serialization::consensus_hex",
+        stub_transaction().output, legacy_json: false;
+    test_pubkey_map: HashMap<PublicKey, String>, "bridge::serialization::pubkey_map",
+        (1..=3).map(|seed| (seeded_public_key(seed), seed.to_string())).collect(),
+        legacy_json: true;
+    test_nonces_by_input: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+        "bridge::serialization::input_pubkey_map", nonces_by_input(3), legacy_json: true;
+    test_nonce_signatures_by_input: HashMap<usize, HashMap<PublicKey, Signature>>,
+        "bridge::serialization::input_pubkey_map", nonce_signatures_by_input(2),
+        legacy_json: true;
+    test_no_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
+        "bridge::serialization::input_pubkey_map", HashMap::new(), legacy_json: true;
+}
+
+#[test]
+fn test_consensus_hex_is_consensus_encoding() {
+    #[derive(Serialize)]
+    struct Field(#[serde(with = "bridge::serialization::consensus_hex")] Transaction);
+
+    let transaction = stub_transaction();
+    assert_eq!(
+        serialize(&Field(transaction.clone())),
+        format!(
+            "\"{}\"",
+            bitcoin::consensus::encode::serialize_hex(&transaction)
+        )
+    );
+}
+
+macro_rules! connector_round_trip_tests {
+    ($($name:ident: $connector:ident, $type:ty;)*) => {
+        $(
+            #[tokio::test]
+            async fn $name() {
+                let config = setup_test().await;
+
+                let json = serialize(&config.$connector);
+                assert!(deserialize::<$type>(&json) == config.$connector);
+            }
+        )*
+    };
+}
+
+connector_round_trip_tests! {
+    test_connector_0_round_trip: connector_0, Connector0;
+    test_connector_1_round_trip: connector_1, Connector1;
+    test_connector_2_round_trip: connector_2, Connector2;
+    test_connector_3_round_trip: connector_3, Connector3;
+    test_connector_4_round_trip: connector_4, Connector4;
+    test_connector_5_round_trip: connector_5, Connector5;
+    test_connector_6_round_trip: connector_6, Connector6;
+    test_connector_a_round_trip: connector_a, ConnectorA;
+    test_connector_b_round_trip: connector_b, ConnectorB;
+    test_connector_d_round_trip: connector_d, ConnectorD;
+    test_connector_z_round_trip: connector_z, ConnectorZ;
+}
+
+#[tokio::test]
+async fn test_pre_signed_graphs_round_trip() {
+    let config = setup_test().await;
+
+    let input = |txid: &str| Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(txid).unwrap(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE),
+    };
+    let mut peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let mut peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    // Nonces and signatures of both verifiers on every pre-signed input
+    for verifier_context in [&config.verifier_0_context, &config.verifier_1_context] {
+        let secret_nonces = peg_in_graph.push_verifier_nonces(verifier_context);
+        peg_in_graph
+            .verifier_sign(verifier_context, &secret_nonces)
+            .unwrap();
+        peg_out_graph.push_verifier_nonces(verifier_context);
+    }
+
+    let json = serialize(&peg_in_graph);
+    assert!(deserialize::<PegInGraph>(&json) == peg_in_graph);
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value["depositor_public_key"],
+        config.depositor_context.depositor_public_key.to_string()
+    );
+
+    let json = serialize(&peg_out_graph);
+    assert!(deserialize::<PegOutGraph>(&json) == peg_out_graph);
+}
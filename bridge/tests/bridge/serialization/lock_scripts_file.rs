@@ -0,0 +1,126 @@
+use std::{io::ErrorKind, path::PathBuf, str::FromStr, time::Instant};
+
+use bitcoin::{
+    key::TapTweak,
+    taproot::{LeafVersion, TaprootBuilder, TaprootSpendInfo},
+    ScriptBuf, XOnlyPublicKey,
+};
+use bridge::{
+    connectors::lock_scripts_file::{write_lock_scripts_file, LockScriptsFile},
+    utils::{read_disk_cache, write_disk_cache},
+};
+use secp256k1::SECP256K1;
+
+use crate::bridge::helper::empty_temp_dir;
+
+fn lock_scripts_path(name: &str) -> PathBuf {
+    empty_temp_dir(&format!("lock_scripts_{name}")).join("lock_scripts_test.bin")
+}
+
+fn internal_key() -> XOnlyPublicKey {
+    XOnlyPublicKey::from_str("cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115")
+        .unwrap()
+}
+
+fn random_lock_scripts(leaf_count: usize, script_length: usize) -> Vec<Vec<u8>> {
+    (0..leaf_count)
+        .map(|_| (0..script_length).map(|_| rand::random::<u8>()).collect())
+        .collect()
+}
+
+fn spend_info(lock_scripts: &[Vec<u8>]) -> TaprootSpendInfo {
+    TaprootBuilder::with_huffman_tree(
+        lock_scripts
+            .iter()
+            .map(|script| (1, ScriptBuf::from_bytes(script.clone()))),
+    )
+    .unwrap()
+    .finalize(SECP256K1, internal_key())
+    .unwrap()
+}
+
+#[test]
+fn test_lock_scripts_file_lookups_match_taproot_tree() {
+    let file_path = lock_scripts_path("lookups");
+    let lock_scripts = random_lock_scripts(37, 200);
+    let spend_info = spend_info(&lock_scripts);
+    write_lock_scripts_file(&file_path, &lock_scripts, &spend_info).unwrap();
+
+    let file = LockScriptsFile::open(&file_path).unwrap();
+    assert_eq!(file.leaf_count(), lock_scripts.len());
+    assert_eq!(Some(file.merkle_root()), spend_info.merkle_root());
+
+    let (output_key, _) = internal_key().tap_tweak(SECP256K1, spend_info.merkle_root());
+    for (leaf_index, expected_script) in lock_scripts.iter().enumerate() {
+        let (script, control_block) = file
+            .script_and_control_block(leaf_index, internal_key())
+            .unwrap();
+        assert_eq!(script.as_bytes(), expected_script.as_slice());
+        assert_eq!(
+            Some(&control_block),
+            spend_info
+                .control_block(&(script.clone(), LeafVersion::TapScript))
+                .as_ref()
+        );
+        assert!(control_block.verify_taproot_commitment(SECP256K1, output_key.to_inner(), &script));
+    }
+    assert_eq!(file.lock_scripts().unwrap(), lock_scripts);
+
+    assert_eq!(
+        file.script_and_control_block(lock_scripts.len(), internal_key())
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_lock_scripts_file_rejects_previous_layout() {
+    let file_path = lock_scripts_path("previous_layout");
+    write_disk_cache(&file_path, &random_lock_scripts(4, 100)).unwrap();
+
+    assert_eq!(
+        LockScriptsFile::open(&file_path).err().unwrap().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+// Cold lookups of a single leaf, with lock scripts the size of the connector C cache. Run with
+// `cargo test --release -- --ignored --nocapture bench_lock_scripts_file`.
+#[ignore]
+#[test]
+fn bench_lock_scripts_file_cold_lookup() {
+    const LEAF_COUNT: usize = 600;
+    const SCRIPT_LENGTH: usize = 18 * 1024;
+    let lock_scripts = random_lock_scripts(LEAF_COUNT, SCRIPT_LENGTH);
+    let spend_info = spend_info(&lock_scripts);
+    let leaf_index = LEAF_COUNT / 2;
+
+    let previous_path = lock_scripts_path("bench_previous");
+    write_disk_cache(&previous_path, &lock_scripts).unwrap();
+    let start = Instant::now();
+    let lock_scripts_bytes: Vec<Vec<u8>> = read_disk_cache(&previous_path).unwrap();
+    let script = ScriptBuf::from_bytes(lock_scripts_bytes[leaf_index].clone());
+    let control_block = spend_info(&lock_scripts_bytes)
+        .control_block(&(script.clone(), LeafVersion::TapScript))
+        .unwrap();
+    let previous = start.elapsed();
+
+    let indexed_path = lock_scripts_path("bench_indexed");
+    write_lock_scripts_file(&indexed_path, &lock_scripts, &spend_info).unwrap();
+    let start = Instant::now();
+    let file = LockScriptsFile::open(&indexed_path).unwrap();
+    let indexed_lookup = file
+        .script_and_control_block(leaf_index, internal_key())
+        .unwrap();
+    let indexed = start.elapsed();
+
+    let start = Instant::now();
+    assert_eq!(file.lock_scripts().unwrap().len(), LEAF_COUNT);
+    let indexed_full_read = start.elapsed();
+
+    assert_eq!(indexed_lookup, (script, control_block));
+    println!("Cold lookup, previous layout: {previous:?}");
+    println!("Cold lookup, indexed layout:  {indexed:?}");
+    println!("Full read, indexed layout:    {indexed_full_read:?}");
+}
@@ -1,3 +1,9 @@
 pub mod assert_transaction;
+pub mod cache_files;
+pub mod connector_c;
+pub mod connector_golden;
+pub mod deterministic_ordering;
+pub mod hex_encoding;
+pub mod lock_scripts_file;
 pub mod peg_in_graph;
 pub mod peg_out_graph;
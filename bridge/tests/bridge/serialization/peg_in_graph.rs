@@ -1,7 +1,7 @@
 use bitcoin::Amount;
 
 use bridge::{
-    graphs::peg_in::PegInGraph,
+    graphs::{peg_in::PegInGraph, template::GraphTemplate},
     scripts::generate_pay_to_pubkey_script_address,
     serialization::{deserialize, serialize},
     transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
@@ -33,7 +33,9 @@ async fn test_peg_in_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
-    );
+        GraphTemplate::default(),
+    )
+    .unwrap();
 
     let json = serialize(&peg_in_graph);
     assert!(!json.is_empty());
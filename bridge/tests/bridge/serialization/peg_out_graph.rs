@@ -1,7 +1,12 @@
 use bitcoin::{Address, Amount};
 
 use bridge::{
-    graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    graphs::{
+        base::PEG_OUT_FEE,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
     scripts::generate_pay_to_pubkey_script_address,
     serialization::{deserialize, serialize},
     transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM},
@@ -44,7 +49,9 @@ async fn test_peg_out_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
-    );
+        GraphTemplate::default(),
+    )
+    .unwrap();
 
     let kick_off_outpoint =
         generate_stub_outpoint(&config.client_0, &kick_off_address, kick_off_amount).await;
@@ -57,7 +64,9 @@ async fn test_peg_out_graph_serialization() {
             amount: kick_off_amount,
         },
         &config.commitment_secrets,
-    );
+        GraphParameters::default(),
+    )
+    .unwrap();
 
     let json = serialize(&peg_out_graph);
     assert!(!json.is_empty());
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use bitcoin::{Network, PublicKey};
 
-use super::helper::{get_intermediate_variables_cached, get_valid_proof, invalidate_proof};
+use super::helper::{get_valid_proof, invalidate_proof};
 use bridge::{
     client::{
         chain::chain_adaptor::get_chain_adaptor, client::BitVMClient, esplora::get_esplora_url,
@@ -15,16 +15,12 @@ use bridge::{
         connector_c::ConnectorC, connector_d::ConnectorD, connector_e::ConnectorE,
         connector_f_1::ConnectorF1, connector_f_2::ConnectorF2, connector_z::ConnectorZ,
     },
-    constants::{
-        DestinationNetwork, DESTINATION_NETWORK_TXID_LENGTH, SOURCE_NETWORK_TXID_LENGTH,
-        START_TIME_MESSAGE_LENGTH,
-    },
+    constants::DestinationNetwork,
     contexts::{
         base::generate_keys_from_secret, depositor::DepositorContext, operator::OperatorContext,
         verifier::VerifierContext, withdrawer::WithdrawerContext,
     },
     serialization::serialize,
-    superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
     transactions::assert_transactions::utils::{
         groth16_commitment_secrets_to_public_keys, merge_to_connector_c_commits_public_key,
         AssertCommit1ConnectorsE, AssertCommit2ConnectorsE, AssertCommitConnectorsF,
@@ -33,19 +29,19 @@ use bridge::{
 
 use bitvm::{
     chunk::api::type_conversion_utils::RawProof,
-    signatures::{
-        signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
-        winternitz::Parameters,
-    },
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
 };
 
 const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
 const VERIFIER_0_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
 const VERIFIER_1_SECRET: &str = "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
-const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+pub const DEPOSITOR_SECRET: &str =
+    "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
 const WITHDRAWER_SECRET: &str = "fffd54f6d8f8ad470cb507fd4b6e9b3ea26b4221a4900cc5ad5916ce67c02f1e";
 
-const DEPOSITOR_EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"; // l2 local test network account 1
+const TEST_GRAPH_ID: &str = "test_graph_id";
+
+pub const DEPOSITOR_EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"; // l2 local test network account 1
 const WITHDRAWER_EVM_ADDRESS: &str = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"; // l2 local test network account 2
 
 pub const INITIAL_AMOUNT: u64 = 2 << 20; // 2097152
@@ -116,8 +112,8 @@ pub async fn setup_test_full() -> SetupConfigFull {
     let (connector_e1_commitment_public_keys, connector_e2_commitment_public_keys) =
         groth16_commitment_secrets_to_public_keys(&config.commitment_secrets);
 
-    let assert_commit_connectors_e_1 = AssertCommit1ConnectorsE {
-        connectors_e: connector_e1_commitment_public_keys
+    let assert_commit_connectors_e_1 = AssertCommit1ConnectorsE::new(
+        connector_e1_commitment_public_keys
             .iter()
             .map(|x| {
                 ConnectorE::new(
@@ -127,9 +123,9 @@ pub async fn setup_test_full() -> SetupConfigFull {
                 )
             })
             .collect(),
-    };
-    let assert_commit_connectors_e_2 = AssertCommit2ConnectorsE {
-        connectors_e: connector_e2_commitment_public_keys
+    );
+    let assert_commit_connectors_e_2 = AssertCommit2ConnectorsE::new(
+        connector_e2_commitment_public_keys
             .iter()
             .map(|x| {
                 ConnectorE::new(
@@ -139,7 +135,7 @@ pub async fn setup_test_full() -> SetupConfigFull {
                 )
             })
             .collect(),
-    };
+    );
 
     let commitment_public_keys = merge_to_connector_c_commits_public_key(
         &connector_e1_commitment_public_keys,
@@ -189,8 +185,6 @@ pub async fn setup_test() -> SetupConfig {
     let source_network = Network::Regtest;
     let destination_network = DestinationNetwork::Local;
 
-    let commitment_secrets = get_test_commitment_secrets();
-
     let (_, verifier_0_public_key) = generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
     let (_, verifier_1_public_key) = generate_keys_from_secret(source_network, VERIFIER_1_SECRET);
     let mut n_of_n_public_keys: Vec<PublicKey> = Vec::new();
@@ -201,6 +195,8 @@ pub async fn setup_test() -> SetupConfig {
         DepositorContext::new(source_network, DEPOSITOR_SECRET, &n_of_n_public_keys);
     let operator_context =
         OperatorContext::new(source_network, OPERATOR_SECRET, &n_of_n_public_keys);
+    // Fixed graph id to ensure repeatable spending addresses.
+    let commitment_secrets = operator_context.generate_commitment_secrets(TEST_GRAPH_ID);
     let verifier_0_context =
         VerifierContext::new(source_network, VERIFIER_0_SECRET, &n_of_n_public_keys);
     let verifier_1_context =
@@ -249,7 +245,7 @@ pub async fn setup_test() -> SetupConfig {
     let connector_b = ConnectorB::new(
         source_network,
         &operator_context.n_of_n_taproot_public_key,
-        &HashMap::from([
+        &BTreeMap::from([
             (
                 CommitmentMessageId::StartTime,
                 WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
@@ -277,14 +273,15 @@ pub async fn setup_test() -> SetupConfig {
         DEPOSITOR_EVM_ADDRESS,
         &depositor_context.depositor_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
-    );
+    )
+    .unwrap();
     let connector_0 = Connector0::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
     let connector_1 = Connector1::new(
         source_network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
-        &HashMap::from([
+        &BTreeMap::from([
             (
                 CommitmentMessageId::Superblock,
                 WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::Superblock]),
@@ -301,7 +298,7 @@ pub async fn setup_test() -> SetupConfig {
         source_network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
-        &HashMap::from([(
+        &BTreeMap::from([(
             CommitmentMessageId::StartTime,
             WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
         )]),
@@ -312,7 +309,7 @@ pub async fn setup_test() -> SetupConfig {
     let connector_6 = Connector6::new(
         source_network,
         &operator_context.operator_taproot_public_key,
-        &HashMap::from([
+        &BTreeMap::from([
             (
                 CommitmentMessageId::PegOutTxIdSourceNetwork,
                 WinternitzPublicKey::from(
@@ -356,47 +353,3 @@ pub async fn setup_test() -> SetupConfig {
         invalid_proof,
     }
 }
-
-// Use fixed secrets for testing to ensure repeatable spending addresses.
-fn get_test_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecret> {
-    let mut commitment_map = HashMap::from([
-        (
-            CommitmentMessageId::PegOutTxIdSourceNetwork,
-            generate_test_winternitz_secret(0, SOURCE_NETWORK_TXID_LENGTH),
-        ),
-        (
-            CommitmentMessageId::PegOutTxIdDestinationNetwork,
-            generate_test_winternitz_secret(1, DESTINATION_NETWORK_TXID_LENGTH),
-        ),
-        (
-            CommitmentMessageId::StartTime,
-            generate_test_winternitz_secret(2, START_TIME_MESSAGE_LENGTH),
-        ),
-        (
-            CommitmentMessageId::Superblock,
-            generate_test_winternitz_secret(3, SUPERBLOCK_MESSAGE_LENGTH),
-        ),
-        (
-            CommitmentMessageId::SuperblockHash,
-            generate_test_winternitz_secret(4, SUPERBLOCK_HASH_MESSAGE_LENGTH),
-        ),
-    ]);
-
-    let all_variables = get_intermediate_variables_cached();
-    // split variable to different connectors
-    for (v, size) in all_variables {
-        commitment_map.insert(
-            CommitmentMessageId::Groth16IntermediateValues((v, size)),
-            generate_test_winternitz_secret(5, size),
-        );
-    }
-    commitment_map
-}
-
-fn generate_test_winternitz_secret(index: u8, message_size: usize) -> WinternitzSecret {
-    let parameters = Parameters::new((message_size * 2) as u32, 4);
-    WinternitzSecret::from_string(
-        &format!("b138982ce17ac813d505b5b40b665d404e9528{:02x}", index),
-        &parameters,
-    )
-}
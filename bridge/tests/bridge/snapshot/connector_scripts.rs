@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use bitcoin::{
+    hashes::{sha256, Hash},
+    Address, ScriptBuf,
+};
+use bridge::connectors::base::{P2wshConnector, TaprootConnector};
+
+use crate::bridge::{
+    setup::setup_test_full,
+    snapshot::{recorded_snapshot, snapshot_path, UPDATE_SNAPSHOTS_VAR},
+};
+
+// Changing a leaf script changes the connector address and invalidates every graph pre-signed
+// with it
+const SNAPSHOT_FILE: &str = "tests/bridge/snapshot/connector_scripts.snap";
+
+#[derive(Default)]
+struct Snapshot(String);
+
+impl Snapshot {
+    fn address(&mut self, connector: &str, address: &Address) {
+        writeln!(self.0, "{connector} address: {address}").unwrap();
+    }
+
+    fn leaf(&mut self, connector: &str, leaf_index: usize, script: &ScriptBuf) {
+        writeln!(
+            self.0,
+            "{connector} leaf {leaf_index}: {} ({} bytes)",
+            sha256::Hash::hash(script.as_bytes()),
+            script.len()
+        )
+        .unwrap();
+    }
+
+    fn taproot(&mut self, connector: &str, taproot_connector: &impl TaprootConnector) {
+        self.address(connector, &taproot_connector.generate_taproot_address());
+        let leaf_count: usize = taproot_connector
+            .generate_taproot_spend_info()
+            .script_map()
+            .values()
+            .map(|merkle_branches| merkle_branches.len())
+            .sum();
+        for leaf_index in 0..leaf_count {
+            self.leaf(
+                connector,
+                leaf_index,
+                &taproot_connector.generate_taproot_leaf_script(leaf_index as u32),
+            );
+        }
+    }
+
+    fn p2wsh(&mut self, connector: &str, p2wsh_connector: &impl P2wshConnector) {
+        self.address(connector, &p2wsh_connector.generate_address());
+        self.leaf(connector, 0, &p2wsh_connector.generate_script());
+    }
+}
+
+fn assert_snapshot(actual: &str) {
+    let path = snapshot_path(SNAPSHOT_FILE);
+    let expected = recorded_snapshot(&path, actual);
+    let changed: Vec<String> = expected
+        .lines()
+        .zip(actual.lines())
+        .filter(|(expected, actual)| expected != actual)
+        .map(|(expected, actual)| format!("- {expected}\n+ {actual}"))
+        .collect();
+    assert!(
+        changed.is_empty() && expected.lines().count() == actual.lines().count(),
+        "Connector scripts differ from {}:\n{}\nRun with {UPDATE_SNAPSHOTS_VAR}=1 if the change is intended.",
+        path.display(),
+        changed.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn test_connector_scripts_snapshot() {
+    let config = setup_test_full().await;
+    let mut snapshot = Snapshot::default();
+
+    snapshot.taproot("connector_z", &config.connector_z);
+    snapshot.taproot("connector_0", &config.connector_0);
+    snapshot.taproot("connector_1", &config.connector_1);
+    snapshot.taproot("connector_2", &config.connector_2);
+    snapshot.p2wsh("connector_3", &config.connector_3);
+    snapshot.p2wsh("connector_4", &config.connector_4);
+    snapshot.taproot("connector_5", &config.connector_5);
+    snapshot.taproot("connector_6", &config.connector_6);
+    snapshot.taproot("connector_a", &config.connector_a);
+    snapshot.taproot("connector_b", &config.connector_b);
+    snapshot.address(
+        "connector_c",
+        &config.connector_c.generate_taproot_address(),
+    );
+    for leaf_index in 0..config.connector_c.taproot_scripts_len() {
+        let (script, _) = config
+            .connector_c
+            .taproot_script_and_control_block(leaf_index);
+        snapshot.leaf("connector_c", leaf_index, &script);
+    }
+    snapshot.taproot("connector_d", &config.connector_d);
+    for (i, connector_e) in config
+        .assert_commit_connectors_e_1
+        .connectors_e
+        .iter()
+        .enumerate()
+    {
+        snapshot.taproot(&format!("connector_e_1_{i}"), connector_e);
+    }
+    for (i, connector_e) in config
+        .assert_commit_connectors_e_2
+        .connectors_e
+        .iter()
+        .enumerate()
+    {
+        snapshot.taproot(&format!("connector_e_2_{i}"), connector_e);
+    }
+    snapshot.p2wsh(
+        "connector_f_1",
+        &config.assert_commit_connectors_f.connector_f_1,
+    );
+    snapshot.p2wsh(
+        "connector_f_2",
+        &config.assert_commit_connectors_f.connector_f_2,
+    );
+
+    assert_snapshot(&snapshot.0);
+}
@@ -0,0 +1,31 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub mod connector_scripts;
+
+// Snapshots are recorded by running the tests with UPDATE_SNAPSHOTS=1 and committing the written
+// files, so intended changes show up in review
+pub const UPDATE_SNAPSHOTS_VAR: &str = "UPDATE_SNAPSHOTS";
+
+pub fn snapshot_path(file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file)
+}
+
+// Snapshot `actual` is compared against, recorded from `actual` first when updating snapshots. A
+// missing snapshot fails like a changed one, otherwise the test passes trivially wherever the
+// snapshot was never committed.
+pub fn recorded_snapshot(path: &Path, actual: &str) -> String {
+    if std::env::var(UPDATE_SNAPSHOTS_VAR).is_ok_and(|value| value == "1") {
+        fs::write(path, actual).unwrap();
+        println!("Snapshot written to {}", path.display());
+    }
+
+    fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "Failed to read snapshot {}: {err}\nRun with {UPDATE_SNAPSHOTS_VAR}=1 to record it.",
+            path.display()
+        )
+    })
+}
@@ -3,7 +3,7 @@ use bitcoin::Amount;
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     superblock::get_start_time_block_number,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_START_TIME},
@@ -40,6 +40,7 @@ async fn test_start_time_tx_success() {
             outpoint: funding_outpoint0,
             amount: input_value0,
         },
+        MIN_RELAY_FEE_RATE,
     );
 
     start_time_tx.sign(
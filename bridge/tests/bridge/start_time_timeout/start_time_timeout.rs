@@ -51,6 +51,7 @@ async fn test_start_time_timeout_tx_success() {
 
     let mut start_time_timeout_tx = StartTimeTimeoutTransaction::new(
         &config.operator_context,
+        &config.connector_0,
         &config.connector_1,
         &config.connector_2,
         Input {
@@ -61,28 +62,37 @@ async fn test_start_time_timeout_tx_success() {
             outpoint: funding_outpoint1,
             amount: input_value1,
         },
+        None,
     );
 
     let secret_nonces_0 = start_time_timeout_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = start_time_timeout_tx.push_nonces(&config.verifier_1_context);
 
-    start_time_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_1,
-    );
+    start_time_timeout_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_1,
+            &config.connector_2,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    start_time_timeout_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_1,
+            &config.connector_2,
+            &secret_nonces_1,
+        )
+        .unwrap();
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
-    start_time_timeout_tx.add_output(reward_address.script_pubkey());
+    start_time_timeout_tx
+        .add_output(reward_address.script_pubkey())
+        .unwrap();
 
     let tx = start_time_timeout_tx.finalize();
     check_tx_output_sum(reward_amount + DUST_AMOUNT, &tx);
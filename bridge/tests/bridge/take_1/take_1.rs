@@ -2,7 +2,7 @@ use bitcoin::{Address, Amount};
 
 use bridge::{
     connectors::base::{P2wshConnector, TaprootConnector},
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_TAKE_1},
         pre_signed_musig2::PreSignedMusig2Transaction,
@@ -74,23 +74,29 @@ async fn test_take_1_tx_success() {
             outpoint: funding_outpoint3,
             amount: input_value3,
         },
+        None,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = take_1_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_1_tx.push_nonces(&config.verifier_1_context);
 
-    take_1_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    take_1_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    take_1_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_1_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let tx = take_1_tx.finalize();
     check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
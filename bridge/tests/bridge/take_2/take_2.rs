@@ -2,9 +2,10 @@ use bitcoin::{Address, Amount};
 
 use bridge::{
     connectors::base::{P2wshConnector, TaprootConnector},
-    graphs::base::DUST_AMOUNT,
+    graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_RATE},
     transactions::{
-        base::{BaseTransaction, Input, MIN_RELAY_FEE_TAKE_2},
+        assert_transactions::assert_final::AssertFinalOutputLayout,
+        base::{BaseTransaction, Input, MIN_RELAY_FEE_TAKE_2, MIN_RELAY_FEE_TAKE_2_CONSOLIDATED},
         pre_signed_musig2::PreSignedMusig2Transaction,
         take_2::Take2Transaction,
     },
@@ -65,10 +66,10 @@ async fn test_take_2_tx_success() {
             outpoint: funding_outpoint0,
             amount: input_value0,
         },
-        Input {
+        Some(Input {
             outpoint: funding_outpoint1,
             amount: input_value1,
-        },
+        }),
         Input {
             outpoint: funding_outpoint2,
             amount: input_value2,
@@ -77,29 +78,135 @@ async fn test_take_2_tx_success() {
             outpoint: funding_outpoint3,
             amount: input_value3,
         },
+        None,
+        MIN_RELAY_FEE_RATE,
     );
 
     let secret_nonces_0 = take_2_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_2_tx.push_nonces(&config.verifier_1_context);
 
-    take_2_tx.pre_sign(
-        &config.verifier_0_context,
+    take_2_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_2_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
+
+    take_2_tx.sign(&config.operator_context, &config.connector_c);
+
+    let tx = take_2_tx.finalize();
+    check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
+    wait_for_timelock_expiry(config.network, Some("assert connector 4")).await;
+    let result = config.client_0.esplora.broadcast(&tx).await;
+    println!("Txid: {:?}", tx.compute_txid());
+    println!("Take 2 tx result: {:?}\n", result);
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_take_2_tx_consolidated_success() {
+    let config = setup_test_full().await;
+    let faucet = Faucet::new(FaucetType::EsploraRegtest);
+
+    let mut funding_inputs: Vec<(&Address, Amount)> = vec![];
+    let input_value0 = Amount::from_sat(ONE_HUNDRED + MIN_RELAY_FEE_TAKE_2_CONSOLIDATED);
+    let funding_utxo_address0 = config.connector_0.generate_taproot_address();
+    funding_inputs.push((&funding_utxo_address0, input_value0));
+
+    // connector 4 dust is folded into the connector 5 output
+    let reward_amount = get_reward_amount(ONE_HUNDRED);
+    let input_value2 = Amount::from_sat(reward_amount + DUST_AMOUNT);
+    let funding_utxo_address2 = config.connector_5.generate_taproot_address();
+    funding_inputs.push((&funding_utxo_address2, input_value2));
+
+    let input_value3 = Amount::from_sat(DUST_AMOUNT);
+    let funding_utxo_address3 = config.connector_c.generate_taproot_address();
+    funding_inputs.push((&funding_utxo_address3, input_value3));
+    faucet
+        .fund_inputs(&config.client_0, &funding_inputs)
+        .await
+        .wait()
+        .await;
+
+    let funding_outpoint0 =
+        generate_stub_outpoint(&config.client_0, &funding_utxo_address0, input_value0).await;
+    let funding_outpoint2 =
+        generate_stub_outpoint(&config.client_0, &funding_utxo_address2, input_value2).await;
+    let funding_outpoint3 =
+        generate_stub_outpoint(&config.client_0, &funding_utxo_address3, input_value3).await;
+
+    let mut take_2_tx = Take2Transaction::new(
+        &config.operator_context,
         &config.connector_0,
+        &config.connector_4,
         &config.connector_5,
-        &secret_nonces_0,
+        &config.connector_c,
+        Input {
+            outpoint: funding_outpoint0,
+            amount: input_value0,
+        },
+        None,
+        Input {
+            outpoint: funding_outpoint2,
+            amount: input_value2,
+        },
+        Input {
+            outpoint: funding_outpoint3,
+            amount: input_value3,
+        },
+        None,
+        MIN_RELAY_FEE_RATE,
     );
-    take_2_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_1,
+    assert_eq!(
+        take_2_tx.assert_final_output_layout(),
+        AssertFinalOutputLayout::Consolidated
     );
 
+    let secret_nonces_0 = take_2_tx.push_nonces(&config.verifier_0_context);
+    let secret_nonces_1 = take_2_tx.push_nonces(&config.verifier_1_context);
+
+    take_2_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_2_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
+
     take_2_tx.sign(&config.operator_context, &config.connector_c);
 
     let tx = take_2_tx.finalize();
+    assert_eq!(tx.input.len(), 3);
+    assert_eq!(
+        tx.input[1].sequence.to_consensus_u32(),
+        config.connector_4.num_blocks_timelock
+    );
     check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
-    wait_for_timelock_expiry(config.network, Some("assert connector 4")).await;
+
+    // the relative timelock on the connector 5 input must be enforced without connector 4
+    let result = config.client_0.esplora.broadcast(&tx).await;
+    assert!(result.is_err());
+
+    wait_for_timelock_expiry(config.network, Some("assert connector 5")).await;
     let result = config.client_0.esplora.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Take 2 tx result: {:?}\n", result);
@@ -0,0 +1,276 @@
+use std::str::FromStr;
+
+use bitcoin::{consensus::encode::deserialize_hex, Amount, Network, Transaction};
+
+use bridge::{
+    constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_4_WEEKS},
+    error::{Error, ValidationError},
+    graphs::{
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+    transactions::{base::MIN_RELAY_FEE_KICK_OFF_1_COLLATERAL, kick_off_1::KickOff1Commitments},
+};
+use strum::IntoEnumIterator;
+
+use crate::bridge::{
+    helper::{stub_input, stub_input_with_amount},
+    setup::setup_test,
+};
+
+#[test]
+fn test_graph_template_names() {
+    for template in GraphTemplate::iter() {
+        let name = template.to_string();
+        assert_eq!(GraphTemplate::from_str(&name).unwrap(), template);
+        assert_eq!(serialize(&template), format!("\"{name}\""));
+        assert_eq!(template.parameters().template, template);
+    }
+    assert_eq!(GraphTemplate::default().to_string(), "standard-mainnet");
+    assert!(GraphTemplate::from_str("low-security").is_err());
+}
+
+#[test]
+fn test_graph_template_validation() {
+    let standard = GraphTemplate::StandardMainnet.parameters();
+    assert!(standard.validate(Network::Bitcoin, 2).is_ok());
+    assert!(standard.validate(Network::Regtest, 0).is_err());
+
+    let fast_regtest = GraphTemplate::FastRegtest.parameters();
+    assert!(fast_regtest.validate(Network::Regtest, 1).is_ok());
+    assert!(fast_regtest.validate(Network::Bitcoin, 1).is_err());
+
+    let high_security = GraphTemplate::HighSecurity.parameters();
+    assert!(high_security.validate(Network::Bitcoin, 2).is_err());
+    assert!(high_security.validate(Network::Bitcoin, 5).is_ok());
+
+    let mut timelocks = standard.timelocks;
+    timelocks.kick_off_timeout = timelocks.kick_off_2;
+    assert!(standard
+        .with_timelocks(timelocks)
+        .validate(Network::Bitcoin, 2)
+        .is_err());
+}
+
+#[test]
+fn test_graph_template_timelocks() {
+    let standard = GraphTemplate::StandardMainnet.parameters().timelocks;
+    let high_security = GraphTemplate::HighSecurity.parameters().timelocks;
+
+    let mainnet = standard.for_network(Network::Bitcoin);
+    assert_eq!(mainnet, standard);
+    assert_eq!(mainnet.kick_off_2, NUM_BLOCKS_PER_2_WEEKS);
+    assert_eq!(
+        high_security.for_network(Network::Bitcoin).kick_off_2,
+        NUM_BLOCKS_PER_4_WEEKS
+    );
+
+    // Test networks keep their short timelocks whatever the template
+    assert_eq!(
+        standard.for_network(Network::Regtest),
+        high_security.for_network(Network::Regtest)
+    );
+}
+
+#[test]
+fn test_graph_template_kick_off_1_commitments() {
+    assert_eq!(
+        GraphTemplate::StandardMainnet
+            .parameters()
+            .kick_off_1_commitments,
+        KickOff1Commitments::PegOutTxIds
+    );
+    let high_security = GraphTemplate::HighSecurity.parameters();
+    assert!(high_security
+        .kick_off_1_commitments
+        .includes_destination_metadata());
+
+    // Parameters recorded before the destination metadata commitments keep the txid commitments
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&high_security)).unwrap();
+    json.as_object_mut()
+        .unwrap()
+        .remove("kick_off_1_commitments");
+    let legacy = deserialize::<GraphParameters>(&json.to_string());
+    assert_eq!(
+        legacy.kick_off_1_commitments,
+        KickOff1Commitments::PegOutTxIds
+    );
+    assert_eq!(
+        legacy.with_kick_off_1_commitments(KickOff1Commitments::PegOutTxIdsAndDestinationMetadata),
+        high_security
+    );
+}
+
+#[test]
+fn test_graph_template_operator_collateral() {
+    let standard = GraphTemplate::StandardMainnet.parameters();
+    assert_eq!(standard.operator_collateral, Amount::ZERO);
+    assert_eq!(standard.operator_collateral_funding_amount(), None);
+
+    let collateral = Amount::from_sat(100_000);
+    let with_collateral = standard.with_operator_collateral(collateral);
+    assert!(with_collateral.validate(Network::Bitcoin, 2).is_ok());
+    assert_eq!(
+        with_collateral.operator_collateral_funding_amount(),
+        Some(collateral + Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_1_COLLATERAL))
+    );
+
+    // Dust collateral could not be spent by disprove or returned by take 1 and take 2
+    assert!(standard
+        .with_operator_collateral(Amount::from_sat(1))
+        .validate(Network::Bitcoin, 2)
+        .is_err());
+
+    // Parameters recorded before collateral existed require none
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&with_collateral)).unwrap();
+    json.as_object_mut().unwrap().remove("operator_collateral");
+    assert_eq!(deserialize::<GraphParameters>(&json.to_string()), standard);
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_records_template_parameters() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::HighSecurity,
+    )
+    .unwrap();
+    assert_eq!(peg_in_graph.template(), GraphTemplate::HighSecurity);
+
+    let parameters = peg_in_graph.template().parameters();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        parameters,
+    )
+    .unwrap();
+    assert_eq!(peg_out_graph.parameters(), &parameters);
+
+    // The recorded parameters are used to rebuild the graph for validation
+    let esplora = &config.client_0.esplora;
+    assert!(peg_out_graph.validate(esplora).await.is_ok());
+
+    let deserialized = deserialize::<PegOutGraph>(&serialize(&peg_out_graph));
+    assert_eq!(deserialized.parameters(), &parameters);
+    assert!(deserialized.validate(esplora).await.is_ok());
+
+    // Tampering with the recorded parameters invalidates the graph
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    json["parameters"]["disprove_burn_percentage"] = serde_json::Value::from(10);
+    let tampered = deserialize::<PegOutGraph>(&json.to_string());
+    assert!(tampered.validate(esplora).await.is_err());
+
+    // Graphs serialized without parameters fall back to the standard template
+    json.as_object_mut().unwrap().remove("parameters");
+    let legacy = deserialize::<PegOutGraph>(&json.to_string());
+    assert_eq!(legacy.parameters(), &GraphParameters::default());
+}
+
+fn input_count(graph_json: &serde_json::Value, transaction: &str) -> usize {
+    let tx: Transaction = deserialize_hex(graph_json[transaction]["tx"].as_str().unwrap()).unwrap();
+    tx.input.len()
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_with_operator_collateral() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+
+    let collateral = Amount::from_sat(100_000);
+    let parameters = peg_in_graph
+        .template()
+        .parameters()
+        .with_operator_collateral(collateral);
+    let peg_out_graph = PegOutGraph::new_with_collateral(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        Some(stub_input_with_amount(
+            "9ab6ac5f0e2d3f5bdfa1f1b5f0d1eaa8e5c1b0e2f3a4b5c6d7e8f90a1b2c3d4e",
+            parameters.operator_collateral_funding_amount().unwrap(),
+        )),
+        &config.commitment_secrets,
+        parameters,
+    )
+    .unwrap();
+    assert_eq!(peg_out_graph.operator_collateral(), Some(collateral));
+
+    // Disprove slashes the collateral, take 1 and take 2 return it to the operator
+    let json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    assert_eq!(input_count(&json, "kick_off_1_transaction"), 2);
+    assert_eq!(input_count(&json, "take_1_transaction"), 5);
+    assert_eq!(input_count(&json, "take_2_transaction"), 5);
+    assert_eq!(input_count(&json, "disprove_transaction"), 3);
+    // Every other slashing path takes the collateral as well
+    assert_eq!(input_count(&json, "kick_off_timeout_transaction"), 2);
+    assert_eq!(input_count(&json, "start_time_timeout_transaction"), 3);
+    assert_eq!(input_count(&json, "disprove_chain_transaction"), 2);
+
+    let esplora = &config.client_0.esplora;
+    assert!(peg_out_graph.validate(esplora).await.is_ok());
+    let deserialized = deserialize::<PegOutGraph>(&json.to_string());
+    assert_eq!(deserialized.operator_collateral(), Some(collateral));
+    assert!(deserialized.validate(esplora).await.is_ok());
+
+    // Raising the recorded collateral above the posted one invalidates the graph
+    let mut tampered = json.clone();
+    tampered["parameters"]["operator_collateral"] = serde_json::Value::from(200_000);
+    let tampered = deserialize::<PegOutGraph>(&tampered.to_string());
+    assert!(tampered.validate(esplora).await.is_err());
+
+    // Graphs without collateral keep their transactions unchanged
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        peg_in_graph.template().parameters(),
+    )
+    .unwrap();
+    assert_eq!(peg_out_graph.operator_collateral(), None);
+    let json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    assert_eq!(input_count(&json, "kick_off_1_transaction"), 1);
+    assert_eq!(input_count(&json, "disprove_transaction"), 2);
+    assert_eq!(input_count(&json, "kick_off_timeout_transaction"), 1);
+    assert_eq!(input_count(&json, "start_time_timeout_transaction"), 2);
+    assert_eq!(input_count(&json, "disprove_chain_transaction"), 1);
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_without_collateral_input() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::StandardMainnet,
+    )
+    .unwrap();
+    let result = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::default().with_operator_collateral(Amount::from_sat(100_000)),
+    );
+    assert!(matches!(
+        result,
+        Err(Error::Validation(ValidationError::InvalidGraphParameters(reason)))
+            if reason == "Graph parameters require an operator collateral input"
+    ));
+}
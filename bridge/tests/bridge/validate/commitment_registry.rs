@@ -0,0 +1,63 @@
+use bridge::{
+    error::ValidationError,
+    graphs::{
+        base::BaseGraph,
+        commitment_registry::commitment_key_reuses,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
+};
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+#[tokio::test]
+async fn test_commitment_key_reuse_across_graphs() {
+    let config = setup_test().await;
+
+    let peg_out_graphs: Vec<PegOutGraph> = [
+        (
+            "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+        ),
+        (
+            "f23ea1fd87bbd5a0ac5dd2ebb08b7fbe1e67f7acbd0e3e1d0ec56b4ba2ff3cf6",
+            "8f1c3b3a37ffae9b8d5a1ac0fd14cad6b3e1c0b9e0e3b2cf0e4f2e0b1c0d4a11",
+        ),
+    ]
+    .into_iter()
+    .map(|(deposit_txid, peg_out_confirm_txid)| {
+        let peg_in_graph = PegInGraph::new(
+            &config.depositor_context,
+            stub_input(deposit_txid),
+            &config.depositor_evm_address,
+            GraphTemplate::FastRegtest,
+        )
+        .unwrap();
+        // Both graphs are created from the same secrets
+        PegOutGraph::new(
+            &config.operator_context,
+            &peg_in_graph,
+            stub_input(peg_out_confirm_txid),
+            &config.commitment_secrets,
+            GraphParameters::from(GraphTemplate::FastRegtest),
+        )
+        .unwrap()
+    })
+    .collect();
+
+    // Connectors of one graph committing to the same message share its key
+    assert!(commitment_key_reuses(&peg_out_graphs[..1]).is_empty());
+
+    let reuses = commitment_key_reuses(&peg_out_graphs);
+    assert!(!reuses.is_empty());
+    assert!(reuses.iter().all(|reuse| {
+        reuse.graph_id == *peg_out_graphs[1].id()
+            && reuse.earlier_graph_id == *peg_out_graphs[0].id()
+            && reuse.message_id == reuse.earlier_message_id
+    }));
+    assert!(matches!(
+        ValidationError::from(reuses[0].clone()),
+        ValidationError::CommitmentKeyReused { .. }
+    ));
+}
@@ -0,0 +1,149 @@
+use bridge::{
+    client::cli::utils::report_graph_integrity,
+    commitments::CommitmentMessageId,
+    error::{ClientError, Error},
+    graphs::{
+        base::BaseGraph,
+        integrity::GraphIntegrityIssue,
+        peg_in::PegInGraph,
+        peg_out::{PegOutGraph, PegOutPresignedTransaction},
+        template::{GraphParameters, GraphTemplate},
+    },
+    scripts::generate_pay_to_pubkey_script,
+    serialization::{deserialize, serialize},
+};
+use strum::IntoEnumIterator;
+
+use crate::bridge::{helper::stub_input, setup::setup_test};
+
+#[tokio::test]
+async fn test_graph_integrity_issues() {
+    let config = setup_test().await;
+
+    let mut peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::FastRegtest),
+    )
+    .unwrap();
+    let committee = peg_in_graph.n_of_n_public_keys().to_vec();
+
+    // Freshly created graphs are only missing the committee signatures
+    let issues = peg_in_graph.integrity_issues();
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(
+        issues[0],
+        GraphIntegrityIssue::MissingSignatures { .. }
+    ));
+    let issues = peg_out_graph.integrity_issues(&committee);
+    assert_eq!(issues.len(), PegOutPresignedTransaction::iter().count());
+    assert!(issues
+        .iter()
+        .all(|issue| matches!(issue, GraphIntegrityIssue::MissingSignatures { .. })));
+
+    let secret_nonces_0 = peg_in_graph.push_verifier_nonces(&config.verifier_0_context);
+    let secret_nonces_1 = peg_in_graph.push_verifier_nonces(&config.verifier_1_context);
+    peg_in_graph
+        .verifier_sign(&config.verifier_0_context, &secret_nonces_0)
+        .unwrap();
+    peg_in_graph
+        .verifier_sign(&config.verifier_1_context, &secret_nonces_1)
+        .unwrap();
+    assert!(peg_in_graph.integrity_issues().is_empty());
+
+    // A truncated commitment public key is reported instead of panicking while deriving scripts
+    // from it
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    json["connector_2"]["commitment_public_keys"][String::from(CommitmentMessageId::StartTime)]
+        ["public_key"]
+        .as_array_mut()
+        .unwrap()
+        .pop();
+    let corrupted = deserialize::<PegOutGraph>(&json.to_string());
+    let issues = corrupted.integrity_issues(&committee);
+    assert!(issues.iter().any(|issue| matches!(
+        issue,
+        GraphIntegrityIssue::MalformedCommitmentPublicKey {
+            message_id: CommitmentMessageId::StartTime,
+            ..
+        }
+    )));
+}
+
+#[test]
+fn test_report_graph_integrity_fails_on_issues() {
+    let graph_id = "graph";
+    assert!(report_graph_integrity(graph_id, Ok(vec![])).is_ok());
+
+    // Both a graph with issues and a graph that could not be validated fail the command, so the
+    // exit code is non-zero
+    let issues = vec![GraphIntegrityIssue::Invalid(Error::Other(
+        "transaction mismatch".to_string(),
+    ))];
+    let err = report_graph_integrity(graph_id, Ok(issues)).unwrap_err();
+    assert_eq!(err.to_string(), "Graph graph has 1 issue(s)");
+
+    let not_found = Err(Error::Client(ClientError::GraphNotFound(
+        graph_id.to_string(),
+    )));
+    let err = report_graph_integrity(graph_id, not_found).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Failed to validate graph graph: "));
+}
+
+#[tokio::test]
+async fn test_invalidate_rebuilds_outdated_transactions() {
+    let config = setup_test().await;
+
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        stub_input("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327"),
+        &config.depositor_evm_address,
+        GraphTemplate::FastRegtest,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        stub_input("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900"),
+        &config.commitment_secrets,
+        GraphParameters::from(GraphTemplate::FastRegtest),
+    )
+    .unwrap();
+
+    // Pay the operator reward somewhere else, take 1 and take 2 no longer match the graph
+    let reward_script =
+        generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key);
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    json["operator_reward_script"] = reward_script.to_hex_string().into();
+    let mut changed = deserialize::<PegOutGraph>(&json.to_string());
+    let take_1_txid = changed.presigned_txid(PegOutPresignedTransaction::Take1);
+    let take_2_txid = changed.presigned_txid(PegOutPresignedTransaction::Take2);
+
+    changed
+        .invalidate_presigned_transactions(&[PegOutPresignedTransaction::Take1])
+        .unwrap();
+
+    let expected = changed.new_for_validation();
+    let rebuilt_txid = changed.presigned_txid(PegOutPresignedTransaction::Take1);
+    assert_ne!(rebuilt_txid, take_1_txid);
+    assert_eq!(
+        rebuilt_txid,
+        expected.presigned_txid(PegOutPresignedTransaction::Take1)
+    );
+    // Transactions that were not invalidated are left as they are
+    assert_eq!(
+        changed.presigned_txid(PegOutPresignedTransaction::Take2),
+        take_2_txid
+    );
+}
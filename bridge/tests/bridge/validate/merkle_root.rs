@@ -0,0 +1,204 @@
+use std::{fs, str::FromStr};
+
+use bitcoin::{taproot::TaprootBuilder, Amount, OutPoint, ScriptBuf, Txid};
+
+use bitvm::chunk::api::NUM_TAPS;
+use bridge::{
+    connectors::{
+        connector_c::{ConnectorC, TaprootLeafProof},
+        lock_scripts_file::{write_lock_scripts_file, LockScriptsFile},
+    },
+    error::ChunkerError,
+    graphs::{
+        base::PEG_IN_FEE,
+        peg_in::PegInGraph,
+        peg_out::{MerkleRootStatus, MerkleRootVerification, PegOutGraph},
+        template::{GraphParameters, GraphTemplate},
+    },
+    serialization::{deserialize, serialize},
+    transactions::base::Input,
+};
+
+use secp256k1::SECP256K1;
+
+use crate::bridge::{
+    helper::empty_temp_dir,
+    setup::{setup_test, setup_test_full, SetupConfig, INITIAL_AMOUNT},
+};
+
+const TAMPERED_MERKLE_ROOT: &str =
+    "0101010101010101010101010101010101010101010101010101010101010101";
+
+#[tokio::test]
+async fn test_connector_c_leaf_proofs() {
+    let config = setup_test_full().await;
+    let connector_c = &config.connector_c;
+
+    let first = connector_c.taproot_leaf_proof(0);
+    let last = connector_c.taproot_leaf_proof(NUM_TAPS - 1);
+    assert!(connector_c.verify_taproot_leaf_proof(&first).is_ok());
+    assert!(connector_c.verify_taproot_leaf_proof(&last).is_ok());
+
+    // A script that is not at the proven position
+    let swapped = TaprootLeafProof {
+        script: last.script.clone(),
+        ..first.clone()
+    };
+    assert!(connector_c.verify_taproot_leaf_proof(&swapped).is_err());
+
+    let out_of_range = TaprootLeafProof {
+        leaf_index: NUM_TAPS,
+        ..first.clone()
+    };
+    assert!(connector_c
+        .verify_taproot_leaf_proof(&out_of_range)
+        .is_err());
+
+    // Proofs do not verify against a root the leaves are not part of
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(connector_c)).unwrap();
+    json["taproot_merkle_root"] = serde_json::Value::from(TAMPERED_MERKLE_ROOT);
+    let tampered = deserialize::<ConnectorC>(&json.to_string());
+    assert!(tampered.verify_taproot_leaf_proof(&first).is_err());
+}
+
+#[tokio::test]
+async fn test_connector_c_lock_scripts_import() {
+    let config = setup_test_full().await;
+    let connector_c = &config.connector_c;
+    let dir = empty_temp_dir("lock_scripts_import");
+    let exported = dir.join("exported.bin");
+
+    let merkle_root = connector_c.export_lock_scripts(&exported).unwrap();
+    assert_eq!(Some(merkle_root), connector_c.taproot_merkle_root());
+    assert!(connector_c.import_lock_scripts(&exported).is_ok());
+
+    // Lock scripts of another tree are never cached for the connector
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(connector_c)).unwrap();
+    json["taproot_merkle_root"] = serde_json::Value::from(TAMPERED_MERKLE_ROOT);
+    let tampered = deserialize::<ConnectorC>(&json.to_string());
+    assert!(matches!(
+        tampered.import_lock_scripts(&exported),
+        Err(ChunkerError::LockScriptsMerkleRootMismatch { found: Some(found), .. }) if found == merkle_root
+    ));
+
+    let truncated = dir.join("truncated.bin");
+    let lock_scripts = LockScriptsFile::open(&exported)
+        .unwrap()
+        .lock_scripts()
+        .unwrap();
+    let spend_info = TaprootBuilder::with_huffman_tree(
+        lock_scripts[..4]
+            .iter()
+            .map(|script| (1, ScriptBuf::from_bytes(script.clone()))),
+    )
+    .unwrap()
+    .finalize(SECP256K1, connector_c.operator_taproot_public_key)
+    .unwrap();
+    write_lock_scripts_file(&truncated, &lock_scripts[..4], &spend_info).unwrap();
+    assert!(matches!(
+        connector_c.import_lock_scripts(&truncated),
+        Err(ChunkerError::LockScriptCountMismatch { found: 4, .. })
+    ));
+
+    let garbage = dir.join("garbage.bin");
+    fs::write(&garbage, b"not a lock scripts file").unwrap();
+    assert!(matches!(
+        connector_c.import_lock_scripts(&garbage),
+        Err(ChunkerError::InvalidLockScripts(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_merkle_root_spot_check() {
+    let (config, mut peg_out_graph) = setup_and_create_peg_out_graph().await;
+    let verifier_context = &config.verifier_0_context;
+    let spot_check = MerkleRootVerification::SpotCheck { sample_size: 4 };
+
+    // The operator's root is in the local cache, so it is compared directly
+    assert_eq!(
+        peg_out_graph.merkle_root_status(verifier_context, MerkleRootVerification::Regenerate),
+        MerkleRootStatus::Verified
+    );
+    assert_eq!(
+        peg_out_graph.merkle_root_status(verifier_context, spot_check),
+        MerkleRootStatus::Verified
+    );
+
+    peg_out_graph.request_merkle_root_spot_check(verifier_context, 4);
+    assert!(peg_out_graph.has_pending_merkle_root_spot_check_requests());
+
+    // The request reaches the operator through the data store
+    let mut operator_peg_out_graph = deserialize::<PegOutGraph>(&serialize(&peg_out_graph));
+    assert!(operator_peg_out_graph.push_merkle_root_spot_check_proofs(&config.operator_context));
+    assert!(!operator_peg_out_graph.has_pending_merkle_root_spot_check_requests());
+    assert!(!operator_peg_out_graph.push_merkle_root_spot_check_proofs(&config.operator_context));
+
+    peg_out_graph.merge(&operator_peg_out_graph).unwrap();
+    assert!(!peg_out_graph.has_pending_merkle_root_spot_check_requests());
+    assert_eq!(
+        peg_out_graph.merkle_root_status(verifier_context, spot_check),
+        MerkleRootStatus::Verified
+    );
+    assert!(peg_out_graph
+        .validate(&config.client_0.esplora)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_merkle_root_claim_mismatch() {
+    let (config, peg_out_graph) = setup_and_create_peg_out_graph().await;
+
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_out_graph)).unwrap();
+    json["connector_c"]["taproot_merkle_root"] = serde_json::Value::from(TAMPERED_MERKLE_ROOT);
+    let tampered = deserialize::<PegOutGraph>(&json.to_string());
+
+    assert!(matches!(
+        tampered.merkle_root_status(
+            &config.verifier_0_context,
+            MerkleRootVerification::SpotCheck { sample_size: 4 }
+        ),
+        MerkleRootStatus::Invalid(_)
+    ));
+}
+
+async fn setup_and_create_peg_out_graph() -> (SetupConfig, PegOutGraph) {
+    let config = setup_test().await;
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.depositor_evm_address,
+        GraphTemplate::default(),
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.commitment_secrets,
+        GraphParameters::default(),
+    )
+    .unwrap();
+
+    (config, peg_out_graph)
+}
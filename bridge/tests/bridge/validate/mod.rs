@@ -1 +1,4 @@
+pub mod commitment_registry;
+pub mod integrity;
+pub mod merkle_root;
 pub mod validate;
@@ -3,8 +3,16 @@ use std::str::FromStr;
 use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
+    contexts::{
+        base::generate_keys_from_secret, depositor::DepositorContext, verifier::VerifierContext,
+    },
     error::{Error, ValidationError},
-    graphs::{base::PEG_IN_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+        template::{GraphParameters, GraphTemplate},
+    },
     scripts::generate_burn_script,
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
@@ -12,6 +20,9 @@ use esplora_client::AsyncClient;
 
 use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
 
+const ROTATED_VERIFIER_SECRET: &str =
+    "5c1b2ea8a1fc3f07b2cdd1c9a55a0e3d8c3a1d2c8f8d3b0e6f4d2a1b3c5e7f90";
+
 #[tokio::test]
 async fn test_validate_success() {
     let (peg_in_graph, peg_out_graph, _, esplora) = setup_and_create_graphs().await;
@@ -167,6 +178,50 @@ async fn test_validate_invalid_script_pubkey() {
     }
 }
 
+#[tokio::test]
+async fn test_validate_committee() {
+    let (mut peg_in_graph, mut peg_out_graph, peg_in_outpoint, _) = setup_and_create_graphs().await;
+    assert!(peg_out_graph.validate_committee(&peg_in_graph).is_ok());
+
+    // A verifier rotated its key, the graphs created before still belong to the previous committee
+    let network = peg_in_graph.network();
+    let committee = peg_in_graph.n_of_n_public_keys().to_vec();
+    let (_, rotated_public_key) = generate_keys_from_secret(network, ROTATED_VERIFIER_SECRET);
+    let rotated_committee = vec![rotated_public_key, committee[1]];
+
+    let rotated_peg_in_graph = PegInGraph::new(
+        &DepositorContext::new(network, ROTATED_VERIFIER_SECRET, &rotated_committee),
+        Input {
+            outpoint: peg_in_outpoint,
+            amount: Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE),
+        },
+        "0x0000000000000000000000000000000000000000",
+        GraphTemplate::default(),
+    )
+    .unwrap();
+    assert!(matches!(
+        peg_out_graph.validate_committee(&rotated_peg_in_graph),
+        Err(Error::Validation(ValidationError::CommitteeMismatch(_)))
+    ));
+
+    // Signing with the new key must not mix it into a graph of the previous committee
+    let rotated_verifier_context =
+        VerifierContext::new(network, ROTATED_VERIFIER_SECRET, &committee);
+    peg_in_graph.push_verifier_nonces(&rotated_verifier_context);
+    assert!(matches!(
+        peg_in_graph.validate(),
+        Err(Error::Validation(ValidationError::NotCommitteeMember(_, public_key, _)))
+            if public_key == rotated_public_key
+    ));
+
+    peg_out_graph.push_verifier_nonces(&rotated_verifier_context);
+    assert!(matches!(
+        peg_out_graph.validate_committee(&peg_in_graph),
+        Err(Error::Validation(ValidationError::NotCommitteeMember(_, public_key, _)))
+            if public_key == rotated_public_key
+    ));
+}
+
 async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncClient) {
     let config = setup_test().await;
 
@@ -189,7 +244,9 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncC
             amount,
         },
         &config.depositor_evm_address,
-    );
+        GraphTemplate::default(),
+    )
+    .unwrap();
 
     let peg_out_graph = PegOutGraph::new(
         &config.operator_context,
@@ -199,7 +256,9 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncC
             amount,
         },
         &config.commitment_secrets,
-    );
+        GraphParameters::default(),
+    )
+    .unwrap();
 
     (
         peg_in_graph,
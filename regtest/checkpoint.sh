@@ -0,0 +1,51 @@
+#!/bin/bash
+
+data_path=$(<data_path)
+if [ -z "$data_path" ]; then
+  echo "Run install.sh first"
+  exit 1
+fi
+
+command=$1
+name=$2
+chain_data="$data_path/esplora-bitcoin-regtest-data"
+checkpoints="$data_path/checkpoints"
+
+if [ "$command" != "list" ] && [ -z "$name" ]; then
+  echo "Usage: ./checkpoint.sh save|restore|delete <name>"
+  echo "       ./checkpoint.sh list"
+  exit 1
+fi
+
+case "$command" in
+  save)
+    ./stop.sh
+    echo "Saving chain state to checkpoint $name ..."
+    rm -rf "$checkpoints/$name"
+    mkdir -p "$checkpoints"
+    cp -a "$chain_data" "$checkpoints/$name"
+    echo "Checkpoint $name saved, run start.sh to continue"
+    ;;
+  restore)
+    if [ ! -d "$checkpoints/$name" ]; then
+      echo "Checkpoint $name not found"
+      exit 1
+    fi
+    ./stop.sh
+    echo "Restoring chain state from checkpoint $name ..."
+    rm -rf "$chain_data"
+    cp -a "$checkpoints/$name" "$chain_data"
+    echo "Checkpoint $name restored, run start.sh to continue"
+    ;;
+  list)
+    ls -1 "$checkpoints" 2>/dev/null
+    ;;
+  delete)
+    rm -rf "$checkpoints/$name"
+    echo "Checkpoint $name deleted"
+    ;;
+  *)
+    echo "Unknown command: $command"
+    exit 1
+    ;;
+esac
@@ -0,0 +1,20 @@
+#!/bin/bash
+# Mines the given number of blocks right away, e.g. for tests set up with
+# REGTEST_BLOCK_GENERATOR_COMMAND in .env.test
+if [ -z "$1" ]; then
+        echo "Usage: $0 <number of blocks>"
+        exit 1
+fi
+
+pid=`docker ps | grep blockstream/esplora | awk '{print $1}'`
+if [ -z "$pid" ]; then
+        echo "esplora client not running, see start.sh" >&2
+        exit 1
+fi
+
+set -e
+
+cli="/srv/explorer/bitcoin/bin/bitcoin-cli -conf=/data/.bitcoin.conf -datadir=/data/bitcoin"
+docker exec $pid /bin/bash -c "$cli loadwallet default" > /dev/null 2>&1 || true
+address=`docker exec $pid /bin/bash -c "$cli getnewaddress"`
+docker exec $pid /bin/bash -c "$cli generatetoaddress $1 $address" > /dev/null